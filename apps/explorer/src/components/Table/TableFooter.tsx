@@ -7,22 +7,22 @@ import { Link } from '~/ui/Link';
 import {
     Pagination,
     type PaginationResponse,
-    type usePaginationStack,
+    type PaginationStack,
 } from '~/ui/Pagination';
 import { Text } from '~/ui/Text';
 import { numberSuffix } from '~/utils/numberUtil';
 
-interface Props {
+interface Props<Cursor> {
     label: string;
     count?: number;
     disablePagination?: boolean;
-    pagination: ReturnType<typeof usePaginationStack>;
-    data?: PaginationResponse<any>;
+    pagination: PaginationStack<Cursor>;
+    data?: PaginationResponse<Cursor>;
     limit: number;
     onLimitChange(value: number): void;
 }
 
-export function TableFooter({
+export function TableFooter<Cursor>({
     data,
     label,
     pagination,
@@ -30,7 +30,7 @@ export function TableFooter({
     count,
     limit,
     onLimitChange,
-}: Props) {
+}: Props<Cursor>) {
     return (
         <div className="flex items-center justify-between">
             {disablePagination ? (
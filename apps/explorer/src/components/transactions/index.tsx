@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 import { useRpcClient } from '@mysten/core';
+import { type TransactionCursor } from '@mysten/sui.js';
 import { useQuery } from '@tanstack/react-query';
 import { useMemo, useState } from 'react';
 
@@ -32,7 +33,7 @@ export function Transactions({
         rpc.getTotalTransactionNumber()
     );
 
-    const pagination = usePaginationStack();
+    const pagination = usePaginationStack<TransactionCursor>();
 
     const transactionQuery = useQuery(
         ['transactions', { limit, cursor: pagination.cursor }],
@@ -21,7 +21,12 @@ export interface PaginationResponse<Cursor = string> {
     hasNextPage: boolean;
 }
 
-export function usePaginationStack<Cursor = string>() {
+export interface PaginationStack<Cursor = string> {
+    cursor?: Cursor;
+    props(data?: Partial<PaginationResponse<Cursor>>): PaginationProps;
+}
+
+export function usePaginationStack<Cursor = string>(): PaginationStack<Cursor> {
     const [stack, setStack] = useState<Cursor[]>([]);
 
     return {
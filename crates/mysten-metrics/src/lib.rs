@@ -9,7 +9,10 @@ use std::task::{Context, Poll};
 use std::time::Instant;
 
 use once_cell::sync::OnceCell;
-use prometheus::{register_int_gauge_vec_with_registry, IntGaugeVec, Registry};
+use prometheus::{
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_gauge_vec_with_registry, HistogramVec, IntCounterVec, IntGaugeVec, Registry,
+};
 use tap::TapFallible;
 use tracing::warn;
 
@@ -18,6 +21,9 @@ use uuid::Uuid;
 
 mod guards;
 pub mod histogram;
+pub mod metered_channel;
+pub mod overrides;
+pub mod remote_write;
 pub use guards::*;
 
 #[derive(Debug)]
@@ -26,8 +32,16 @@ pub struct Metrics {
     pub futures: IntGaugeVec,
     pub scope_iterations: IntGaugeVec,
     pub scope_duration_ns: IntGaugeVec,
+    pub channel_inflight: IntGaugeVec,
+    pub channel_received: IntCounterVec,
+    pub channel_send_wait: HistogramVec,
+    pub channel_dropped: IntCounterVec,
 }
 
+const CHANNEL_SEND_WAIT_BUCKETS: &[f64] = &[
+    0.000_001, 0.000_01, 0.000_1, 0.001, 0.01, 0.1, 0.5, 1., 5., 10., 60.,
+];
+
 impl Metrics {
     fn new(registry: &Registry) -> Self {
         Self {
@@ -59,6 +73,35 @@ impl Metrics {
                 registry,
             )
             .unwrap(),
+            channel_inflight: register_int_gauge_vec_with_registry!(
+                "channel_inflight",
+                "Number of messages in each monitored channel.",
+                &["name"],
+                registry,
+            )
+            .unwrap(),
+            channel_received: register_int_counter_vec_with_registry!(
+                "channel_received",
+                "Total number of messages received from each monitored channel.",
+                &["name"],
+                registry,
+            )
+            .unwrap(),
+            channel_send_wait: register_histogram_vec_with_registry!(
+                "channel_send_wait",
+                "Time in seconds senders of each monitored channel waited for capacity.",
+                &["name"],
+                CHANNEL_SEND_WAIT_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            channel_dropped: register_int_counter_vec_with_registry!(
+                "channel_dropped",
+                "Total number of messages that could not be sent to each monitored channel, because it was full or closed.",
+                &["name"],
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
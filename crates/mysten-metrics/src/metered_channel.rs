@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(dead_code)]
 
+use crate::get_metrics;
 use async_trait::async_trait;
 use std::future::Future;
 // TODO: complete tests - This kinda sorta facades the whole tokio::mpsc::{Sender, Receiver}: without tests, this will be fragile to maintain.
 use futures::{FutureExt, Stream, TryFutureExt};
-use prometheus::{IntCounter, IntGauge};
+use prometheus::{Histogram, IntCounter, IntGauge};
 use std::task::{Context, Poll};
 use tokio::sync::mpsc::{
     self,
@@ -23,6 +24,10 @@ mod metered_channel_tests;
 pub struct Sender<T> {
     inner: mpsc::Sender<T>,
     gauge: IntGauge,
+    /// Time spent waiting for capacity in `send` and `reserve`.
+    send_wait: Option<Histogram>,
+    /// Messages that could not be sent.
+    dropped: Option<IntCounter>,
 }
 
 impl<T> Clone for Sender<T> {
@@ -30,6 +35,8 @@ impl<T> Clone for Sender<T> {
         Self {
             inner: self.inner.clone(),
             gauge: self.gauge.clone(),
+            send_wait: self.send_wait.clone(),
+            dropped: self.dropped.clone(),
         }
     }
 }
@@ -131,9 +138,14 @@ impl<T> Sender<T> {
     /// Sends a value, waiting until there is capacity.
     /// Increments the gauge in case of a successful `send`.
     pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let _timer = self
+            .send_wait
+            .as_ref()
+            .map(|send_wait| send_wait.start_timer());
         self.inner
             .send(value)
             .inspect_ok(|_| self.gauge.inc())
+            .inspect_err(|_| self.inc_dropped())
             .await
     }
 
@@ -152,6 +164,10 @@ impl<T> Sender<T> {
                 self.gauge.inc();
                 val
             })
+            .map_err(|err| {
+                self.inc_dropped();
+                err
+            })
     }
 
     // TODO: facade [`send_timeout`](tokio::mpsc::Sender::send_timeout) under the tokio feature flag "time"
@@ -168,6 +184,10 @@ impl<T> Sender<T> {
     /// available, it is reserved for the caller.
     /// Increments the gauge in case of a successful `reserve`.
     pub async fn reserve(&self) -> Result<Permit<'_, T>, SendError<()>> {
+        let _timer = self
+            .send_wait
+            .as_ref()
+            .map(|send_wait| send_wait.start_timer());
         self.inner
             .reserve()
             // remove this unsightly hack once https://github.com/rust-lang/rust/issues/91345 is resolved
@@ -207,6 +227,12 @@ impl<T> Sender<T> {
     pub fn gauge(&self) -> &IntGauge {
         &self.gauge
     }
+
+    fn inc_dropped(&self) {
+        if let Some(dropped) = &self.dropped {
+            dropped.inc();
+        }
+    }
 }
 
 ////////////////////////////////
@@ -281,6 +307,8 @@ pub fn channel<T>(size: usize, gauge: &IntGauge) -> (Sender<T>, Receiver<T>) {
         Sender {
             inner: sender,
             gauge: gauge.clone(),
+            send_wait: None,
+            dropped: None,
         },
         Receiver {
             inner: receiver,
@@ -302,6 +330,8 @@ pub fn channel_with_total<T>(
         Sender {
             inner: sender,
             gauge: gauge.clone(),
+            send_wait: None,
+            dropped: None,
         },
         Receiver {
             inner: receiver,
@@ -311,6 +341,34 @@ pub fn channel_with_total<T>(
     )
 }
 
+/// Similar to `channel`, but the occupancy of the channel, the number of received messages, the
+/// time senders wait for capacity and the number of messages that could not be sent are exported
+/// in the `channel_*` metrics, labelled by `name`. Metrics are only exported once
+/// [`init_metrics`](crate::init_metrics) has been called.
+#[track_caller]
+pub fn monitored_channel<T>(name: &str, size: usize) -> (Sender<T>, Receiver<T>) {
+    let Some(metrics) = get_metrics() else {
+        let gauge = IntGauge::new("channel_inflight", "Number of messages in the channel").unwrap();
+        return channel(size, &gauge);
+    };
+    let gauge = metrics.channel_inflight.with_label_values(&[name]);
+    gauge.set(0);
+    let (sender, receiver) = mpsc::channel(size);
+    (
+        Sender {
+            inner: sender,
+            gauge: gauge.clone(),
+            send_wait: Some(metrics.channel_send_wait.with_label_values(&[name])),
+            dropped: Some(metrics.channel_dropped.with_label_values(&[name])),
+        },
+        Receiver {
+            inner: receiver,
+            gauge,
+            total: Some(metrics.channel_received.with_label_values(&[name])),
+        },
+    )
+}
+
 #[async_trait]
 pub trait WithPermit<T> {
     async fn with_permit<F: Future + Send>(&self, f: F) -> Option<(Permit<T>, F::Output)>;
@@ -0,0 +1,150 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Operator overrides of the histogram buckets and of the set of exported metrics.
+//!
+//! The overrides are installed once at startup with `set_metrics_overrides`, and applied by the
+//! metric structs that support them when they are constructed, e.g. `ExecutorMetrics`:
+//!
+//! ```ignore
+//! let overrides = metrics_overrides();
+//! register_histogram_with_registry!(
+//!     "batch_execution_latency",
+//!     "Latency between the creation and the execution of a batch",
+//!     overrides.buckets("batch_execution_latency", LATENCY_SEC_BUCKETS),
+//!     overrides.registry("batch_execution_latency", registry),
+//! )
+//! ```
+//!
+//! Metrics that are not exported are still registered, to a registry that is never gathered, so
+//! the code updating them is unchanged.
+
+use once_cell::sync::OnceCell;
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tap::TapFallible;
+use tracing::warn;
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetricsOverrides {
+    /// Bucket boundaries of histograms, by metric name, replacing their built-in ones.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub histogram_buckets: BTreeMap<String, Vec<f64>>,
+    /// When set, only these metrics are exported, among the ones supporting overrides.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_metrics: Option<BTreeSet<String>>,
+    /// Metrics that are not exported, e.g. because of their high cardinality.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub disabled_metrics: BTreeSet<String>,
+}
+
+impl MetricsOverrides {
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, buckets) in &self.histogram_buckets {
+            if buckets.is_empty() {
+                return Err(format!("histogram buckets of {name} are empty"));
+            }
+            if buckets.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return Err(format!(
+                    "histogram buckets of {name} must be strictly increasing, got {buckets:?}"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        !self.disabled_metrics.contains(name)
+            && self
+                .allowed_metrics
+                .as_ref()
+                .map_or(true, |allowed| allowed.contains(name))
+    }
+
+    /// Bucket boundaries of the histogram `name`.
+    pub fn buckets(&self, name: &str, default: &[f64]) -> Vec<f64> {
+        self.histogram_buckets
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| default.to_vec())
+    }
+
+    /// Registry to register the metric `name` to: `registry` when it is exported, a detached one
+    /// otherwise.
+    pub fn registry(&self, name: &str, registry: &Registry) -> Registry {
+        if self.is_enabled(name) {
+            registry.clone()
+        } else {
+            Registry::new()
+        }
+    }
+}
+
+static METRICS_OVERRIDES: OnceCell<MetricsOverrides> = OnceCell::new();
+
+/// Installs the overrides applied to the metrics constructed from now on.
+pub fn set_metrics_overrides(overrides: MetricsOverrides) -> Result<(), String> {
+    overrides.validate()?;
+    let _ = METRICS_OVERRIDES
+        .set(overrides)
+        .tap_err(|_| warn!("metrics overrides already set"));
+    Ok(())
+}
+
+/// The installed overrides, or no overrides at all if none were installed.
+pub fn metrics_overrides() -> &'static MetricsOverrides {
+    static NO_OVERRIDES: OnceCell<MetricsOverrides> = OnceCell::new();
+    METRICS_OVERRIDES
+        .get()
+        .unwrap_or_else(|| NO_OVERRIDES.get_or_init(MetricsOverrides::default))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::register_int_counter_with_registry;
+
+    #[test]
+    fn test_histogram_buckets() {
+        let overrides = MetricsOverrides {
+            histogram_buckets: [("latency".to_string(), vec![0.1, 1.0])].into(),
+            ..Default::default()
+        };
+        assert!(overrides.validate().is_ok());
+        assert_eq!(overrides.buckets("latency", &[1.0, 2.0]), vec![0.1, 1.0]);
+        assert_eq!(overrides.buckets("other", &[1.0, 2.0]), vec![1.0, 2.0]);
+
+        let invalid = MetricsOverrides {
+            histogram_buckets: [("latency".to_string(), vec![1.0, 0.1])].into(),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_disabled_metrics() {
+        let registry = Registry::new();
+        let overrides = MetricsOverrides {
+            allowed_metrics: Some(["kept".to_string(), "denied".to_string()].into()),
+            disabled_metrics: ["denied".to_string()].into(),
+            ..Default::default()
+        };
+        assert!(overrides.is_enabled("kept"));
+        assert!(!overrides.is_enabled("denied"));
+        assert!(!overrides.is_enabled("not_allowed"));
+
+        for name in ["kept", "denied", "not_allowed"] {
+            register_int_counter_with_registry!(name, "help", overrides.registry(name, &registry))
+                .unwrap()
+                .inc();
+        }
+        let names: Vec<_> = registry
+            .gather()
+            .iter()
+            .map(|family| family.get_name().to_string())
+            .collect();
+        assert_eq!(names, vec!["kept".to_string()]);
+    }
+}
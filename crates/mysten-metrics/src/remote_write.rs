@@ -0,0 +1,373 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encoding of gathered metrics for the Prometheus remote-write protocol, for nodes that push
+//! their metrics instead of being scraped, along with Prometheus style relabeling of the pushed
+//! series.
+//!
+//! Histograms and summaries are flattened into their `_bucket`/`quantile`, `_sum` and `_count`
+//! series, the same way Prometheus does when scraping them.
+
+use prometheus::proto::{Metric, MetricFamily, MetricType};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+pub const CONTENT_TYPE: &str = "application/x-protobuf";
+pub const CONTENT_ENCODING: &str = "snappy";
+pub const VERSION_HEADER: &str = "X-Prometheus-Remote-Write-Version";
+pub const VERSION: &str = "0.1.0";
+
+const METRIC_NAME_LABEL: &str = "__name__";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct WriteRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub timeseries: Vec<TimeSeries>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TimeSeries {
+    /// Sorted by name, including the metric name as `__name__`.
+    #[prost(message, repeated, tag = "1")]
+    pub labels: Vec<Label>,
+    #[prost(message, repeated, tag = "2")]
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct Label {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Sample {
+    #[prost(double, tag = "1")]
+    pub value: f64,
+    /// Milliseconds since the Unix epoch.
+    #[prost(int64, tag = "2")]
+    pub timestamp: i64,
+}
+
+impl Label {
+    fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl WriteRequest {
+    /// Converts gathered metric families into a request, with every sample taken at
+    /// `timestamp_ms`. Series dropped by the relabeling rules are left out.
+    pub fn from_metric_families(
+        families: &[MetricFamily],
+        timestamp_ms: i64,
+        relabeler: &Relabeler,
+    ) -> Self {
+        let mut timeseries = vec![];
+        for family in families {
+            for metric in family.get_metric() {
+                for (name, extra_label, value) in samples(family, metric) {
+                    let mut labels: Vec<_> = metric
+                        .get_label()
+                        .iter()
+                        .map(|label| Label::new(label.get_name(), label.get_value()))
+                        .collect();
+                    labels.push(Label::new(METRIC_NAME_LABEL, name));
+                    labels.extend(extra_label);
+                    if let Some(labels) = relabeler.apply(labels) {
+                        timeseries.push(TimeSeries {
+                            labels,
+                            samples: vec![Sample {
+                                value,
+                                timestamp: timestamp_ms,
+                            }],
+                        });
+                    }
+                }
+            }
+        }
+        Self { timeseries }
+    }
+
+    /// Serializes the request as a snappy compressed protobuf, the body of a remote-write push.
+    pub fn encode_compressed(&self) -> Result<Vec<u8>, snap::Error> {
+        snap::raw::Encoder::new().compress_vec(&prost::Message::encode_to_vec(self))
+    }
+}
+
+/// The series of a metric as (name, extra label, value).
+fn samples(family: &MetricFamily, metric: &Metric) -> Vec<(String, Option<Label>, f64)> {
+    let name = family.get_name();
+    match family.get_field_type() {
+        MetricType::COUNTER => vec![(name.into(), None, metric.get_counter().get_value())],
+        MetricType::GAUGE => vec![(name.into(), None, metric.get_gauge().get_value())],
+        MetricType::UNTYPED => vec![(name.into(), None, metric.get_untyped().get_value())],
+        MetricType::HISTOGRAM => {
+            let histogram = metric.get_histogram();
+            let count = histogram.get_sample_count() as f64;
+            let bucket = |le: String, value: f64| {
+                (format!("{name}_bucket"), Some(Label::new("le", le)), value)
+            };
+            let mut samples: Vec<_> = histogram
+                .get_bucket()
+                .iter()
+                .map(|b| {
+                    bucket(
+                        b.get_upper_bound().to_string(),
+                        b.get_cumulative_count() as f64,
+                    )
+                })
+                .collect();
+            samples.push(bucket("+Inf".into(), count));
+            samples.push((format!("{name}_sum"), None, histogram.get_sample_sum()));
+            samples.push((format!("{name}_count"), None, count));
+            samples
+        }
+        MetricType::SUMMARY => {
+            let summary = metric.get_summary();
+            let mut samples: Vec<_> = summary
+                .get_quantile()
+                .iter()
+                .map(|q| {
+                    let quantile = Label::new("quantile", q.get_quantile().to_string());
+                    (name.into(), Some(quantile), q.get_value())
+                })
+                .collect();
+            samples.push((format!("{name}_sum"), None, summary.get_sample_sum()));
+            samples.push((
+                format!("{name}_count"),
+                None,
+                summary.get_sample_count() as f64,
+            ));
+            samples
+        }
+    }
+}
+
+/// A relabeling rule, with the semantics of Prometheus' `relabel_config`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RelabelConfig {
+    /// Labels whose values are joined with `separator` and matched against `regex`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_labels: Vec<String>,
+    #[serde(default = "default_separator")]
+    pub separator: String,
+    /// Anchored at both ends. Matched against label names by `labeldrop` and `labelkeep`.
+    #[serde(default = "default_regex")]
+    pub regex: String,
+    /// Label set by `replace`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_label: Option<String>,
+    /// Value of the target label of `replace`, which may refer to the groups of `regex`.
+    #[serde(default = "default_replacement")]
+    pub replacement: String,
+    #[serde(default)]
+    pub action: RelabelAction,
+}
+
+fn default_separator() -> String {
+    ";".into()
+}
+
+fn default_regex() -> String {
+    "(.*)".into()
+}
+
+fn default_replacement() -> String {
+    "$1".into()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelabelAction {
+    /// Set the target label to the replacement if the regex matches, removing it if the
+    /// replacement is empty.
+    #[default]
+    Replace,
+    /// Drop the series unless the regex matches.
+    Keep,
+    /// Drop the series if the regex matches.
+    Drop,
+    /// Remove the labels whose name matches the regex.
+    LabelDrop,
+    /// Remove the labels whose name does not match the regex.
+    LabelKeep,
+}
+
+pub struct Relabeler {
+    rules: Vec<(RelabelConfig, Regex)>,
+}
+
+impl Relabeler {
+    pub fn new(configs: &[RelabelConfig]) -> Result<Self, regex::Error> {
+        let rules = configs
+            .iter()
+            .map(|config| {
+                let regex = Regex::new(&format!("^(?:{})$", config.regex))?;
+                Ok((config.clone(), regex))
+            })
+            .collect::<Result<_, regex::Error>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Applies the rules in order to the labels of a series. Returns the labels sorted by name,
+    /// or `None` if the series is dropped.
+    pub fn apply(&self, mut labels: Vec<Label>) -> Option<Vec<Label>> {
+        for (config, regex) in &self.rules {
+            match config.action {
+                RelabelAction::Replace => {
+                    let value = source_value(config, &labels);
+                    let (Some(target), Some(captures)) =
+                        (&config.target_label, regex.captures(&value)) else {
+                        continue;
+                    };
+                    let mut replacement = String::new();
+                    captures.expand(&config.replacement, &mut replacement);
+                    labels.retain(|label| &label.name != target);
+                    if !replacement.is_empty() {
+                        labels.push(Label::new(target, replacement));
+                    }
+                }
+                RelabelAction::Keep => {
+                    if !regex.is_match(&source_value(config, &labels)) {
+                        return None;
+                    }
+                }
+                RelabelAction::Drop => {
+                    if regex.is_match(&source_value(config, &labels)) {
+                        return None;
+                    }
+                }
+                RelabelAction::LabelDrop => labels.retain(|label| !regex.is_match(&label.name)),
+                RelabelAction::LabelKeep => labels.retain(|label| regex.is_match(&label.name)),
+            }
+        }
+        labels.sort_by(|a, b| a.name.cmp(&b.name));
+        Some(labels)
+    }
+}
+
+fn source_value(config: &RelabelConfig, labels: &[Label]) -> String {
+    config
+        .source_labels
+        .iter()
+        .map(|name| {
+            labels
+                .iter()
+                .find(|label| &label.name == name)
+                .map_or("", |label| label.value.as_str())
+        })
+        .collect::<Vec<_>>()
+        .join(&config.separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{Histogram, HistogramOpts, IntCounterVec, Opts, Registry};
+
+    fn rule(action: RelabelAction, source_labels: &[&str], regex: &str) -> RelabelConfig {
+        RelabelConfig {
+            source_labels: source_labels.iter().map(|l| l.to_string()).collect(),
+            separator: default_separator(),
+            regex: regex.into(),
+            target_label: None,
+            replacement: default_replacement(),
+            action,
+        }
+    }
+
+    fn names(request: &WriteRequest) -> Vec<String> {
+        request
+            .timeseries
+            .iter()
+            .map(|series| {
+                series
+                    .labels
+                    .iter()
+                    .map(|label| format!("{}={}", label.name, label.value))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn converts_counters_and_histograms() {
+        let registry = Registry::new();
+        let counter = IntCounterVec::new(Opts::new("requests", "requests"), &["route"]).unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        counter.with_label_values(&["/a"]).inc_by(3);
+        let histogram =
+            Histogram::with_opts(HistogramOpts::new("latency", "latency").buckets(vec![1.0, 2.5]))
+                .unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+        histogram.observe(2.0);
+
+        let request = WriteRequest::from_metric_families(
+            &registry.gather(),
+            42,
+            &Relabeler::new(&[]).unwrap(),
+        );
+
+        assert_eq!(
+            names(&request),
+            vec![
+                "__name__=latency_bucket,le=1",
+                "__name__=latency_bucket,le=2.5",
+                "__name__=latency_bucket,le=+Inf",
+                "__name__=latency_sum",
+                "__name__=latency_count",
+                "__name__=requests,route=/a",
+            ]
+        );
+        let values: Vec<_> = request
+            .timeseries
+            .iter()
+            .map(|series| series.samples[0].value)
+            .collect();
+        assert_eq!(values, vec![0.0, 1.0, 1.0, 2.0, 1.0, 3.0]);
+        assert!(request
+            .timeseries
+            .iter()
+            .all(|series| series.samples[0].timestamp == 42));
+        assert!(!request.encode_compressed().unwrap().is_empty());
+    }
+
+    #[test]
+    fn relabeling() {
+        let labels = vec![
+            Label::new(METRIC_NAME_LABEL, "sui_requests"),
+            Label::new("route", "/a"),
+            Label::new("instance", "validator-1:9184"),
+        ];
+
+        let mut replace = rule(RelabelAction::Replace, &["instance"], "(.*):.*");
+        replace.target_label = Some("host".into());
+        let relabeler =
+            Relabeler::new(&[replace, rule(RelabelAction::LabelDrop, &[], "instance")]).unwrap();
+        assert_eq!(
+            relabeler.apply(labels.clone()).unwrap(),
+            vec![
+                Label::new(METRIC_NAME_LABEL, "sui_requests"),
+                Label::new("host", "validator-1"),
+                Label::new("route", "/a"),
+            ]
+        );
+
+        let keep =
+            Relabeler::new(&[rule(RelabelAction::Keep, &[METRIC_NAME_LABEL], "sui_.*")]).unwrap();
+        assert!(keep.apply(labels.clone()).is_some());
+        let drop = Relabeler::new(&[rule(RelabelAction::Drop, &["route"], "/a|/b")]).unwrap();
+        assert!(drop.apply(labels.clone()).is_none());
+        let label_keep =
+            Relabeler::new(&[rule(RelabelAction::LabelKeep, &[], "__name__|route")]).unwrap();
+        assert_eq!(label_keep.apply(labels).unwrap().len(), 2);
+    }
+}
@@ -1,13 +1,14 @@
 // Copyright (c) 2021, Facebook, Inc. and its affiliates
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use super::{channel, channel_with_total};
+use super::{channel, channel_with_total, monitored_channel};
+use crate::{get_metrics, init_metrics};
 use futures::{
     executor::block_on,
     task::{noop_waker, Context, Poll},
     FutureExt,
 };
-use prometheus::{IntCounter, IntGauge};
+use prometheus::{IntCounter, IntGauge, Registry};
 use tokio::sync::mpsc::error::TrySendError;
 
 #[test]
@@ -201,3 +202,43 @@ fn test_try_send_full() {
     assert_eq!(received_item, item);
     assert_eq!(counter.get(), 0);
 }
+
+#[test]
+fn test_monitored_channel() {
+    init_metrics(&Registry::new());
+    let metrics = get_metrics().unwrap();
+    let inflight = metrics
+        .channel_inflight
+        .with_label_values(&["test_monitored"]);
+    let received = metrics
+        .channel_received
+        .with_label_values(&["test_monitored"]);
+    let dropped = metrics
+        .channel_dropped
+        .with_label_values(&["test_monitored"]);
+    let (tx, mut rx) = monitored_channel("test_monitored", 1);
+
+    block_on(tx.send(42)).unwrap();
+    assert_eq!(inflight.get(), 1);
+    assert_eq!(
+        metrics
+            .channel_send_wait
+            .with_label_values(&["test_monitored"])
+            .get_sample_count(),
+        1
+    );
+
+    // The channel is full.
+    assert!(matches!(tx.try_send(43), Err(TrySendError::Full(_))));
+    assert_eq!(dropped.get(), 1);
+
+    assert_eq!(block_on(rx.recv()), Some(42));
+    assert_eq!(inflight.get(), 0);
+    assert_eq!(received.get(), 1);
+
+    // The channel is closed.
+    drop(rx);
+    assert!(block_on(tx.send(44)).is_err());
+    assert_eq!(dropped.get(), 2);
+    assert_eq!(inflight.get(), 0);
+}
@@ -17,6 +17,7 @@ use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use tracing::{debug, info, instrument, warn};
 
 use crate::programmable_transactions;
+use sui_framework::natives::execution_trace;
 use sui_protocol_config::{
     check_limit_by_meter, LimitThresholdCrossed, ProtocolConfig, ProtocolVersion,
 };
@@ -146,7 +147,12 @@ fn charge_gas_for_object_read<S>(
         .filter(|(id, _)| !is_system_package(**id))
         .map(|(_, obj)| obj.object_size_for_gas_metering())
         .sum();
-    gas_status.charge_storage_read(total_size)
+    let gas_remaining = gas_status.computation_gas_remaining();
+    let result = gas_status.charge_storage_read(total_size);
+    execution_trace::record_object_read_gas(
+        gas_remaining.saturating_sub(gas_status.computation_gas_remaining()),
+    );
+    result
 }
 
 #[instrument(name = "tx_execute", level = "debug", skip_all)]
@@ -215,10 +221,19 @@ fn execute_transaction<
                 ))
             }
         };
+        if execution_result.is_ok() && protocol_config.check_limit_events_size_supported().is_ok() {
+            execution_result =
+                check_events_size(temporary_store, !gas_status.is_unmetered(), protocol_config)
+                    .and(execution_result);
+        }
         execution_result
     });
     if !gas_status.is_unmetered() {
+        let gas_remaining = gas_status.computation_gas_remaining();
         temporary_store.charge_gas(gas_object_ref.0, &mut gas_status, &mut result, gas);
+        execution_trace::record_storage_gas(
+            gas_remaining.saturating_sub(gas_status.computation_gas_remaining()),
+        );
     }
     if !is_system {
         #[cfg(debug_assertions)]
@@ -235,6 +250,39 @@ fn execute_transaction<
     (cost_summary, result)
 }
 
+/// Checks that the events emitted by the transaction are within the limits. As for effects,
+/// system transactions are only rejected over a hard limit, and logged over the soft one.
+fn check_events_size<S>(
+    temporary_store: &TemporaryStore<S>,
+    is_metered: bool,
+    protocol_config: &ProtocolConfig,
+) -> Result<(), ExecutionError> {
+    let events_size = temporary_store.events_size();
+    match check_limit_by_meter!(
+        is_metered,
+        events_size,
+        protocol_config.max_serialized_tx_events_size_bytes(),
+        protocol_config.max_serialized_tx_events_size_bytes_system_tx()
+    ) {
+        LimitThresholdCrossed::None => Ok(()),
+        LimitThresholdCrossed::Soft(_, limit) => {
+            warn!(
+                events_size = events_size,
+                soft_limit = limit,
+                "Transaction events size crossed soft limit",
+            );
+            Ok(())
+        }
+        LimitThresholdCrossed::Hard(_, limit) => Err(ExecutionError::new_with_source(
+            ExecutionErrorKind::EventsTooLarge {
+                current_size: events_size as u64,
+                max_size: limit as u64,
+            },
+            "Transaction events are too large",
+        )),
+    }
+}
+
 fn execution_loop<
     Mode: ExecutionMode,
     S: BackingPackageStore + ParentSync + ChildObjectResolver + ObjectStore + GetModule,
@@ -4,6 +4,7 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     fmt,
+    time::Instant,
 };
 
 use move_binary_format::{
@@ -25,6 +26,7 @@ use move_vm_runtime::{
 };
 use move_vm_types::loaded_data::runtime_types::{StructType, Type};
 use serde::{de::DeserializeSeed, Deserialize};
+use sui_framework::natives::{execution_profile, execution_trace};
 use sui_protocol_config::ProtocolConfig;
 use sui_types::{
     base_types::{
@@ -34,6 +36,7 @@ use sui_types::{
     coin::Coin,
     error::{ExecutionError, ExecutionErrorKind},
     event::Event,
+    execution_trace::MoveCallTrace,
     gas::SuiGasStatus,
     id::UID,
     messages::{
@@ -70,6 +73,7 @@ pub fn execute<E: fmt::Debug, S: StorageView<E>, Mode: ExecutionMode>(
     gas_coin: Option<ObjectID>,
     pt: ProgrammableTransaction,
 ) -> Result<Mode::ExecutionResults, ExecutionError> {
+    let _profiler = execution_profile::profile_transaction();
     let ProgrammableTransaction { inputs, commands } = pt;
     let mut context = ExecutionContext::new(
         protocol_config,
@@ -82,15 +86,26 @@ pub fn execute<E: fmt::Debug, S: StorageView<E>, Mode: ExecutionMode>(
     )?;
     // execute commands
     let mut mode_results = Mode::empty_results();
+    let tracing = execution_trace::is_tracing();
     for (idx, command) in commands.into_iter().enumerate() {
-        execute_command::<_, _, Mode>(&mut context, &mut mode_results, command)
-            .map_err(|e| e.with_command_index(idx))?
+        if tracing {
+            let (kind, call) = command_trace_info(&command);
+            let gas_remaining = context.gas_status.computation_gas_remaining();
+            execution_trace::begin_command(idx, kind, call, gas_remaining);
+        }
+        let result = execute_command::<_, _, Mode>(&mut context, &mut mode_results, command);
+        if tracing {
+            execution_trace::end_command(context.gas_status.computation_gas_remaining());
+        }
+        result.map_err(|e| e.with_command_index(idx))?
     }
     // apply changes
+    let finish_start = Instant::now();
     let ExecutionResults {
         object_changes,
         user_events,
     } = context.finish::<Mode>()?;
+    execution_profile::record_object_runtime(finish_start.elapsed());
     state_view.apply_object_changes(object_changes);
     for (module_id, tag, contents) in user_events {
         state_view.log_event(Event::new(
@@ -104,6 +119,27 @@ pub fn execute<E: fmt::Debug, S: StorageView<E>, Mode: ExecutionMode>(
     Ok(mode_results)
 }
 
+/// The name of the command, and the function it calls if any, as recorded in execution traces
+fn command_trace_info(command: &Command) -> (&'static str, Option<MoveCallTrace>) {
+    match command {
+        Command::MoveCall(call) => (
+            "MoveCall",
+            Some(MoveCallTrace {
+                package: call.package,
+                module: call.module.to_string(),
+                function: call.function.to_string(),
+                type_arguments: call.type_arguments.iter().map(|t| t.to_string()).collect(),
+            }),
+        ),
+        Command::TransferObjects(..) => ("TransferObjects", None),
+        Command::SplitCoins(..) => ("SplitCoins", None),
+        Command::MergeCoins(..) => ("MergeCoins", None),
+        Command::Publish(..) => ("Publish", None),
+        Command::MakeMoveVec(..) => ("MakeMoveVec", None),
+        Command::Upgrade(..) => ("Upgrade", None),
+    }
+}
+
 /// Execute a single command
 fn execute_command<E: fmt::Debug, S: StorageView<E>, Mode: ExecutionMode>(
     context: &mut ExecutionContext<E, S>,
@@ -7,9 +7,13 @@ use clap::*;
 use prometheus::Registry;
 use rand::seq::SliceRandom;
 
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use sui_benchmark::drivers::bench_driver::BenchDriver;
 use sui_benchmark::drivers::driver::Driver;
+use sui_benchmark::drivers::results::BenchmarkResults;
+use sui_benchmark::drivers::throughput_search::ThroughputSearchConfig;
 use sui_benchmark::drivers::BenchmarkCmp;
 use sui_benchmark::drivers::BenchmarkStats;
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
@@ -116,6 +120,7 @@ async fn main() -> Result<()> {
         .unwrap();
     let prev_benchmark_stats_path = opts.compare_with.clone();
     let curr_benchmark_stats_path = opts.benchmark_stats_path.clone();
+    let results_path = opts.results_path.clone();
     let registry_clone = registry.clone();
     let handle = std::thread::spawn(move || {
         client_runtime.block_on(async move {
@@ -131,7 +136,16 @@ async fn main() -> Result<()> {
             // otherwise summarized benchmark results are
             // published in the end
             let show_progress = interval.is_unbounded();
-            let driver = BenchDriver::new(opts.stat_collection_interval, stress_stat_collection);
+            let mut driver =
+                BenchDriver::new(opts.stat_collection_interval, stress_stat_collection);
+            if opts.max_tps_search {
+                driver = driver.with_throughput_search(ThroughputSearchConfig {
+                    latency_slo_p50_ms: opts.latency_slo_p50_ms,
+                    latency_slo_p99_ms: opts.latency_slo_p99_ms,
+                    step_duration: Duration::from_secs(opts.tps_search_step_secs),
+                    resolution_tps: opts.tps_search_resolution,
+                });
+            }
             driver
                 .run(
                     bench_setup.proxies,
@@ -164,6 +178,10 @@ async fn main() -> Result<()> {
                     eprintln!("Benchmark Report:");
                     eprintln!("{}", benchmark_table);
 
+                    if let Some(max_sustainable_tps) = benchmark_stats.max_sustainable_tps {
+                        eprintln!("Max sustainable TPS: {}", max_sustainable_tps);
+                    }
+
                     if stress_stat_collection {
                         eprintln!("Stress Performance Report:");
                         let stress_stats_table = stress_stats.to_table();
@@ -188,6 +206,10 @@ async fn main() -> Result<()> {
                         let serialized = serde_json::to_string(&benchmark_stats)?;
                         std::fs::write(curr_benchmark_stats_path, serialized)?;
                     }
+                    if !results_path.is_empty() {
+                        BenchmarkResults::from(&benchmark_stats).write(Path::new(&results_path))?;
+                        eprintln!("Benchmark results exported to {}", results_path);
+                    }
                 }
                 Err(e) => eprintln!("{e}"),
             },
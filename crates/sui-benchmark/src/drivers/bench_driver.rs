@@ -19,7 +19,7 @@ use prometheus::{register_int_counter_vec_with_registry, CounterVec};
 use prometheus::{GaugeVec, IntCounter};
 use rand::seq::SliceRandom;
 use tokio::sync::mpsc::Sender;
-use tokio::sync::OnceCell;
+use tokio::sync::{watch, OnceCell};
 use tokio_util::sync::CancellationToken;
 
 use crate::drivers::driver::Driver;
@@ -27,18 +27,22 @@ use crate::drivers::HistogramWrapper;
 use crate::system_state_observer::SystemStateObserver;
 use crate::workloads::payload::Payload;
 use crate::workloads::WorkloadInfo;
-use crate::ValidatorProxy;
+use crate::{ExecutionEffects, ValidatorProxy};
 use std::collections::{BTreeMap, VecDeque};
 use std::sync::Arc;
 use std::time::Duration;
+use sui_types::base_types::AuthorityName;
+use sui_types::committee::Committee;
 use sui_types::messages::{TransactionDataAPI, VerifiedTransaction};
 use sysinfo::{CpuExt, System, SystemExt};
 use tokio::sync::Barrier;
 use tokio::{time, time::Instant};
 use tracing::{debug, error, info};
 
+use super::results::LatencyPercentiles;
+use super::throughput_search::{search_max_sustainable_tps, ThroughputSearchConfig};
 use super::Interval;
-use super::{BenchmarkStats, StressStats};
+use super::{BenchmarkStats, IntervalStats, StressStats};
 pub struct BenchMetrics {
     pub benchmark_duration: IntCounter,
     pub num_success: IntCounterVec,
@@ -149,10 +153,17 @@ enum NextOp {
         latency: Duration,
         /// Number of commands in the executed transction
         num_commands: u16,
+        /// Validators that signed the effects certificate
+        signers: Vec<AuthorityName>,
         /// The payload updated with the effects of the transaction
         payload: Box<dyn Payload>,
     },
-    Retry(RetryType),
+    Retry {
+        /// The transaction to resubmit and its payload
+        retry: RetryType,
+        /// Kind of the error the transaction failed with
+        error_kind: String,
+    },
 }
 
 async fn print_and_start_benchmark() -> &'static Instant {
@@ -175,6 +186,9 @@ pub struct BenchDriver {
     pub stress_stat_collection: bool,
     pub start_time: Instant,
     pub token: CancellationToken,
+    pub throughput_search: Option<ThroughputSearchConfig>,
+    // fraction of their target qps the workers currently run at
+    rate_scale: Arc<watch::Sender<f64>>,
 }
 
 impl BenchDriver {
@@ -184,8 +198,16 @@ impl BenchDriver {
             stress_stat_collection,
             start_time: Instant::now(),
             token: CancellationToken::new(),
+            throughput_search: None,
+            rate_scale: Arc::new(watch::channel(1.0).0),
         }
     }
+    /// Instead of running at the target qps, search for the highest rate (up to the target qps)
+    /// at which the latency SLOs hold and stop the benchmark once it is found.
+    pub fn with_throughput_search(mut self, config: ThroughputSearchConfig) -> BenchDriver {
+        self.throughput_search = Some(config);
+        self
+    }
     pub fn terminate(&self) {
         self.token.cancel()
     }
@@ -287,6 +309,14 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
         if num_workers == 0 {
             return Err(anyhow!("No workers to run benchmark!"));
         }
+        let max_tps: u64 = bench_workers.iter().map(|w| w.target_qps).sum();
+        let (search_tx, search_rx) = match self.throughput_search {
+            Some(config) => {
+                let (search_tx, search_rx) = tokio::sync::mpsc::channel(100);
+                (Some(search_tx), Some((config, search_rx)))
+            }
+            None => (None, None),
+        };
         let stat_delay_micros = 1_000_000 * self.stat_collection_interval;
         let metrics = Arc::new(BenchMetrics::new(registry));
         let barrier = Arc::new(Barrier::new(num_workers as usize));
@@ -306,7 +336,7 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
         });
         for (i, worker) in bench_workers.into_iter().enumerate() {
             let cloned_token = self.token.clone();
-            let request_delay_micros = 1_000_000 / worker.target_qps;
+            let mut rate_scale = self.rate_scale.subscribe();
             let mut free_pool = worker.payload;
             let progress_cloned = progress.clone();
             let tx_cloned = tx.clone();
@@ -322,10 +352,14 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 let mut num_no_gas = 0;
                 let mut num_in_flight: u64 = 0;
                 let mut num_submitted = 0;
+                let mut errors: BTreeMap<String, u64> = BTreeMap::new();
+                let mut validator_signatures: BTreeMap<String, u64> = BTreeMap::new();
                 let mut latency_histogram =
                     hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3).unwrap();
-                let mut request_interval =
-                    time::interval(Duration::from_micros(request_delay_micros));
+                let mut request_interval = time::interval(request_delay(
+                    worker.target_qps,
+                    *rate_scale.borrow_and_update(),
+                ));
                 request_interval.set_missed_tick_behavior(time::MissedTickBehavior::Burst);
                 let mut stat_interval = time::interval(Duration::from_micros(stat_delay_micros));
                 let mut futures: FuturesUnordered<BoxFuture<NextOp>> = FuturesUnordered::new();
@@ -337,6 +371,10 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                         _ = cloned_token.cancelled() => {
                             break;
                         }
+                        Ok(()) = rate_scale.changed() => {
+                            request_interval = time::interval(request_delay(worker.target_qps, *rate_scale.borrow()));
+                            request_interval.set_missed_tick_behavior(time::MissedTickBehavior::Burst);
+                        }
                         _ = stat_interval.tick() => {
                             if tx_cloned
                                 .try_send(Stats {
@@ -350,6 +388,10 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                                         num_success_txes,
                                         num_success_cmds,
                                         latency_ms: HistogramWrapper {histogram: latency_histogram.clone()},
+                                        max_sustainable_tps: None,
+                                        errors: std::mem::take(&mut errors),
+                                        validator_signatures: std::mem::take(&mut validator_signatures),
+                                        timeline: vec![],
                                     },
                                 })
                                 .is_err()
@@ -396,17 +438,16 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                                                 metrics_cloned.num_in_flight.with_label_values(&[&b.1.to_string()]).dec();
                                                 // let auth_sign_info = AuthorityStrongQuorumSignInfo::try_from(&cert.auth_sign_info).unwrap();
                                                 // auth_sign_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_tx_cert.with_label_values(&[&name.unwrap().to_string()]).inc());
-                                                if let Some(sig_info) = effects.quorum_sig() {
-                                                    sig_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_effects_cert.with_label_values(&[&name.unwrap().to_string()]).inc())
-                                                }
+                                                let signers = effects_signers(&effects, &committee_cloned);
+                                                signers.iter().for_each(|name| metrics_cloned.validators_in_effects_cert.with_label_values(&[&name.to_string()]).inc());
                                                 let num_commands = b.0.data().transaction_data().kind().num_commands() as u16;
                                                 b.1.make_new_payload(&effects);
-                                                NextOp::Response { latency, num_commands, payload: b.1 }
+                                                NextOp::Response { latency, num_commands, signers, payload: b.1 }
                                             }
                                             Err(err) => {
                                                 error!("{}", err);
                                                 metrics_cloned.num_error.with_label_values(&[&b.1.to_string()]).inc();
-                                                NextOp::Retry(b)
+                                                NextOp::Retry { error_kind: error_kind(&err), retry: b }
                                             }
                                         }
                                     });
@@ -448,15 +489,16 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                                             metrics_cloned.num_in_flight.with_label_values(&[&payload.to_string()]).dec();
                                             // let auth_sign_info = AuthorityStrongQuorumSignInfo::try_from(&cert.auth_sign_info).unwrap();
                                             // auth_sign_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_tx_cert.with_label_values(&[&name.unwrap().to_string()]).inc());
-                                            if let Some(sig_info) = effects.quorum_sig() { sig_info.authorities(&committee_cloned).for_each(|name| metrics_cloned.validators_in_effects_cert.with_label_values(&[&name.unwrap().to_string()]).inc()) }
+                                            let signers = effects_signers(&effects, &committee_cloned);
+                                            signers.iter().for_each(|name| metrics_cloned.validators_in_effects_cert.with_label_values(&[&name.to_string()]).inc());
                                             payload.make_new_payload(&effects);
                                             let num_commands = tx.data().transaction_data().kind().num_commands() as u16;
-                                            NextOp::Response { latency, num_commands, payload }
+                                            NextOp::Response { latency, num_commands, signers, payload }
                                         }
                                         Err(err) => {
                                             error!("Retry due to error: {}", err);
                                             metrics_cloned.num_error.with_label_values(&[&payload.to_string()]).inc();
-                                            NextOp::Retry(Box::new((tx, payload)))
+                                            NextOp::Retry { error_kind: error_kind(&err), retry: Box::new((tx, payload)) }
                                         }
                                     }
                                 });
@@ -465,14 +507,18 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                         }
                         Some(op) = futures.next() => {
                             match op {
-                                NextOp::Retry(b) => {
-                                    retry_queue.push_back(b);
+                                NextOp::Retry { retry, error_kind } => {
+                                    *errors.entry(error_kind).or_default() += 1;
+                                    retry_queue.push_back(retry);
                                     BenchDriver::update_progress(*start_time, run_duration, progress_cloned.clone());
                                     if progress_cloned.is_finished() {
                                         break;
                                     }
                                 }
-                                NextOp::Response { latency, num_commands, payload } => {
+                                NextOp::Response { latency, num_commands, signers, payload } => {
+                                    for name in signers {
+                                        *validator_signatures.entry(name.to_string()).or_default() += 1;
+                                    }
                                     num_success_txes += 1;
                                     num_success_cmds += num_commands as u64;
                                     num_in_flight -= 1;
@@ -502,6 +548,10 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                             latency_ms: HistogramWrapper {
                                 histogram: latency_histogram,
                             },
+                            max_sustainable_tps: None,
+                            errors,
+                            validator_signatures,
+                            timeline: vec![],
                         },
                     })
                     .is_err()
@@ -521,6 +571,10 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 latency_ms: HistogramWrapper {
                     histogram: hdrhistogram::Histogram::<u64>::new_with_max(120_000, 3).unwrap(),
                 },
+                max_sustainable_tps: None,
+                errors: BTreeMap::new(),
+                validator_signatures: BTreeMap::new(),
+                timeline: vec![],
             };
             let mut stat_collection: BTreeMap<usize, Stats> = BTreeMap::new();
             let mut counter = 0;
@@ -571,10 +625,43 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
                 };
                 counter += 1;
                 if counter % num_workers == 0 {
+                    benchmark_stat.timeline.push(IntervalStats {
+                        elapsed_secs: start.elapsed().as_secs_f64(),
+                        tps: total_qps as f64,
+                        cps: total_cps as f64,
+                        num_success_txes,
+                        num_error_txes,
+                        latency_ms: LatencyPercentiles::from_histogram(&latency_histogram),
+                    });
                     stat = format!("TPS = {}, CPS = {}, latency_ms(min/p50/p99/max) = {}/{}/{}/{}, num_success_tx = {}, num_error_tx = {}, num_success_cmds = {}, no_gas = {}, submitted = {}, in_flight = {}", total_qps, total_cps, latency_histogram.min(), latency_histogram.value_at_quantile(0.5), latency_histogram.value_at_quantile(0.99), latency_histogram.max(), num_success_txes, num_error_txes, num_success_cmds, num_no_gas, num_submitted, num_in_flight);
                     if show_progress {
                         eprintln!("{}", stat);
                     }
+                    if let Some(search_tx) = &search_tx {
+                        let duration = stat_collection
+                            .values()
+                            .map(|v| v.bench_stats.duration)
+                            .max()
+                            .unwrap_or_default();
+                        if search_tx
+                            .try_send(BenchmarkStats {
+                                duration,
+                                num_error_txes,
+                                num_success_txes,
+                                num_success_cmds,
+                                latency_ms: HistogramWrapper {
+                                    histogram: latency_histogram,
+                                },
+                                max_sustainable_tps: None,
+                                errors: BTreeMap::new(),
+                                validator_signatures: BTreeMap::new(),
+                                timeline: vec![],
+                            })
+                            .is_err()
+                        {
+                            debug!("Failed to send stats to the throughput search!");
+                        }
+                    }
                 }
             }
             benchmark_stat
@@ -623,6 +710,23 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
             stress_stat
         });
 
+        let search_task = search_rx.map(|(config, samples)| {
+            let rate_scale = self.rate_scale.clone();
+            let token = self.token.clone();
+            let stat_collection_interval = Duration::from_secs(self.stat_collection_interval);
+            tokio::spawn(async move {
+                search_max_sustainable_tps(
+                    config,
+                    max_tps,
+                    stat_collection_interval,
+                    &rate_scale,
+                    samples,
+                    token,
+                )
+                .await
+            })
+        });
+
         let all_tasks = try_join_all(tasks);
         let _res = tokio::select! {
             _ = ctrl_c() => {
@@ -631,12 +735,42 @@ impl Driver<(BenchmarkStats, StressStats)> for BenchDriver {
             }
             res = all_tasks => res.unwrap().into_iter().collect()
         };
-        let benchmark_stat = benchmark_stat_task.await.unwrap();
+        let mut benchmark_stat = benchmark_stat_task.await.unwrap();
+        if let Some(search_task) = search_task {
+            benchmark_stat.max_sustainable_tps = Some(search_task.await.unwrap());
+        }
         let stress_stat = stress_stat_task.await.unwrap();
         Ok((benchmark_stat, stress_stat))
     }
 }
 
+/// The validators that signed the effects certificate of a transaction, if there is one.
+fn effects_signers(effects: &ExecutionEffects, committee: &Committee) -> Vec<AuthorityName> {
+    effects.quorum_sig().map_or_else(Vec::new, |sig_info| {
+        sig_info
+            .authorities(committee)
+            .map(|name| *name.unwrap())
+            .collect()
+    })
+}
+
+/// A short name for the kind of `error`, used to break errors down in the benchmark results.
+fn error_kind(error: &anyhow::Error) -> String {
+    let description = format!("{:?}", error.root_cause());
+    description
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .filter(|kind| !kind.is_empty())
+        .unwrap_or("Other")
+        .to_string()
+}
+
+/// Delay between two requests of a worker running at `rate_scale` times its target qps.
+fn request_delay(target_qps: u64, rate_scale: f64) -> Duration {
+    let qps = (target_qps as f64 * rate_scale).max(1.0);
+    Duration::from_micros((1_000_000.0 / qps) as u64)
+}
+
 fn stress_stats_collector(
     progress: Arc<ProgressBar>,
     metrics: Arc<BenchMetrics>,
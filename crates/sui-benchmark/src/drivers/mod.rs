@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use duration_str::parse;
-use std::{str::FromStr, time::Duration};
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
 
 pub mod bench_driver;
 pub mod driver;
+pub mod results;
+pub mod throughput_search;
 use comfy_table::{Cell, Color, ContentArrangement, Row, Table};
 use hdrhistogram::{serialization::Serializer, Histogram};
+use results::LatencyPercentiles;
 
 #[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub enum Interval {
@@ -105,6 +108,30 @@ pub struct BenchmarkStats {
     /// Total number of commands in transactions that executed successfully
     pub num_success_cmds: u64,
     pub latency_ms: HistogramWrapper,
+    /// Highest TPS at which the latency SLOs held, when the run searched for it
+    #[serde(default)]
+    pub max_sustainable_tps: Option<u64>,
+    /// Number of failed transaction attempts by kind of error
+    #[serde(default)]
+    pub errors: BTreeMap<String, u64>,
+    /// Number of effects certificates signed by each validator
+    #[serde(default)]
+    pub validator_signatures: BTreeMap<String, u64>,
+    /// Aggregated stats of every stat collection interval, only recorded for the whole run
+    #[serde(default)]
+    pub timeline: Vec<IntervalStats>,
+}
+
+/// Throughput and latency of the benchmark over one stat collection interval.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct IntervalStats {
+    /// Time since the start of the benchmark at the end of the interval
+    pub elapsed_secs: f64,
+    pub tps: f64,
+    pub cps: f64,
+    pub num_success_txes: u64,
+    pub num_error_txes: u64,
+    pub latency_ms: LatencyPercentiles,
 }
 
 impl BenchmarkStats {
@@ -117,6 +144,15 @@ impl BenchmarkStats {
             .histogram
             .add(&sample_stat.latency_ms.histogram)
             .unwrap();
+        for (kind, count) in &sample_stat.errors {
+            *self.errors.entry(kind.clone()).or_default() += count;
+        }
+        for (validator, count) in &sample_stat.validator_signatures {
+            *self
+                .validator_signatures
+                .entry(validator.clone())
+                .or_default() += count;
+        }
     }
     pub fn to_table(&self) -> Table {
         let mut table = Table::new();
@@ -0,0 +1,191 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Machine readable results of a benchmark run, for performance CI and dashboards.
+//!
+//! Unlike [`BenchmarkStats`], which is serialized as is to compare runs with each other, the
+//! format of [`BenchmarkResults`] is stable: fields are only ever added, and any other change
+//! bumps [`RESULTS_SCHEMA_VERSION`]. Results are written as JSON, or as CSV with one row per
+//! stat collection interval followed by a row for the whole run.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::Result;
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+
+use super::{BenchmarkStats, IntervalStats};
+
+pub const RESULTS_SCHEMA_VERSION: u32 = 1;
+
+const CSV_HEADER: &str = "scope,elapsed_secs,tps,cps,num_success_txes,num_error_txes,\
+    latency_min_ms,latency_p50_ms,latency_p95_ms,latency_p99_ms,latency_p999_ms,latency_max_ms";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatencyPercentiles {
+    pub min: u64,
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub p999: u64,
+    pub max: u64,
+    pub mean: f64,
+}
+
+impl LatencyPercentiles {
+    pub fn from_histogram(histogram: &Histogram<u64>) -> Self {
+        Self {
+            min: histogram.min(),
+            p50: histogram.value_at_quantile(0.5),
+            p95: histogram.value_at_quantile(0.95),
+            p99: histogram.value_at_quantile(0.99),
+            p999: histogram.value_at_quantile(0.999),
+            max: histogram.max(),
+            mean: histogram.mean(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ValidatorResults {
+    /// Number of effects certificates of the run the validator signed
+    pub effects_certificates_signed: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BenchmarkResults {
+    pub schema_version: u32,
+    pub duration_secs: f64,
+    pub tps: f64,
+    pub cps: f64,
+    pub num_success_txes: u64,
+    pub num_error_txes: u64,
+    pub num_success_cmds: u64,
+    /// Share of the submitted transactions that ended in an error
+    pub error_rate: f64,
+    pub latency_ms: LatencyPercentiles,
+    pub max_sustainable_tps: Option<u64>,
+    /// Number of failed transaction attempts by kind of error
+    pub errors: BTreeMap<String, u64>,
+    /// Per validator stats, keyed by the validator public key
+    pub validators: BTreeMap<String, ValidatorResults>,
+    /// Throughput and latency of every stat collection interval of the run
+    pub timeline: Vec<IntervalStats>,
+}
+
+impl From<&BenchmarkStats> for BenchmarkResults {
+    fn from(stats: &BenchmarkStats) -> Self {
+        let duration_secs = stats.duration.as_secs_f64();
+        let per_sec = |count: u64| {
+            if duration_secs > 0.0 {
+                count as f64 / duration_secs
+            } else {
+                0.0
+            }
+        };
+        let num_txes = stats.num_success_txes + stats.num_error_txes;
+        Self {
+            schema_version: RESULTS_SCHEMA_VERSION,
+            duration_secs,
+            tps: per_sec(stats.num_success_txes),
+            cps: per_sec(stats.num_success_cmds),
+            num_success_txes: stats.num_success_txes,
+            num_error_txes: stats.num_error_txes,
+            num_success_cmds: stats.num_success_cmds,
+            error_rate: if num_txes > 0 {
+                stats.num_error_txes as f64 / num_txes as f64
+            } else {
+                0.0
+            },
+            latency_ms: LatencyPercentiles::from_histogram(&stats.latency_ms.histogram),
+            max_sustainable_tps: stats.max_sustainable_tps,
+            errors: stats.errors.clone(),
+            validators: stats
+                .validator_signatures
+                .iter()
+                .map(|(name, signed)| {
+                    let results = ValidatorResults {
+                        effects_certificates_signed: *signed,
+                    };
+                    (name.clone(), results)
+                })
+                .collect(),
+            timeline: stats.timeline.clone(),
+        }
+    }
+}
+
+impl BenchmarkResults {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        writeln!(csv, "{CSV_HEADER}").unwrap();
+        for interval in &self.timeline {
+            write_csv_row(
+                &mut csv,
+                "interval",
+                interval.elapsed_secs,
+                interval.tps,
+                interval.cps,
+                interval.num_success_txes,
+                interval.num_error_txes,
+                &interval.latency_ms,
+            );
+        }
+        write_csv_row(
+            &mut csv,
+            "total",
+            self.duration_secs,
+            self.tps,
+            self.cps,
+            self.num_success_txes,
+            self.num_error_txes,
+            &self.latency_ms,
+        );
+        csv
+    }
+
+    /// Write the results to `path`, as CSV if it has a `csv` extension and as JSON otherwise.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let contents = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => self.to_csv(),
+            _ => self.to_json()?,
+        };
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_csv_row(
+    csv: &mut String,
+    scope: &str,
+    elapsed_secs: f64,
+    tps: f64,
+    cps: f64,
+    num_success_txes: u64,
+    num_error_txes: u64,
+    latency_ms: &LatencyPercentiles,
+) {
+    write!(
+        csv,
+        "{scope},{elapsed_secs:.3},{tps:.2},{cps:.2},{num_success_txes},{num_error_txes},"
+    )
+    .unwrap();
+    writeln!(
+        csv,
+        "{},{},{},{},{},{}",
+        latency_ms.min,
+        latency_ms.p50,
+        latency_ms.p95,
+        latency_ms.p99,
+        latency_ms.p999,
+        latency_ms.max,
+    )
+    .unwrap();
+}
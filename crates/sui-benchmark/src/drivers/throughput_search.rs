@@ -0,0 +1,150 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+use tokio::sync::{mpsc::Receiver, watch};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use super::BenchmarkStats;
+
+/// Share of the target TPS a step must actually achieve to count as sustained.
+const MIN_ACHIEVED_RATIO: f64 = 0.9;
+/// Share of the transactions that may fail during a step before it counts as unsustained.
+const MAX_ERROR_RATE: f64 = 0.01;
+/// The search starts at this fraction of the maximum TPS and doubles from there.
+const INITIAL_FRACTION: u64 = 16;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThroughputSearchConfig {
+    /// The p50 latency a rate must stay under to be sustainable.
+    pub latency_slo_p50_ms: u64,
+    /// The p99 latency a rate must stay under to be sustainable.
+    pub latency_slo_p99_ms: u64,
+    /// How long each candidate rate is run for. The first stat collection interval of every step
+    /// is discarded to let the in-flight transactions of the previous rate drain.
+    pub step_duration: Duration,
+    /// The search stops once the gap between the highest sustained and lowest failed rates is
+    /// under this many transactions per second.
+    pub resolution_tps: u64,
+}
+
+/// Measurements of one step of the search.
+struct StepResult {
+    achieved_tps: f64,
+    error_rate: f64,
+    latency_p50_ms: u64,
+    latency_p99_ms: u64,
+}
+
+impl StepResult {
+    fn from_samples(samples: &[BenchmarkStats]) -> Option<Self> {
+        let first = samples.first()?;
+        let mut histogram = first.latency_ms.histogram.clone();
+        histogram.reset();
+        let (mut num_success, mut num_error, mut duration) = (0, 0, Duration::ZERO);
+        for sample in samples {
+            num_success += sample.num_success_txes;
+            num_error += sample.num_error_txes;
+            duration += sample.duration;
+            histogram.add(&sample.latency_ms.histogram).ok()?;
+        }
+        if duration.is_zero() {
+            return None;
+        }
+        Some(Self {
+            achieved_tps: num_success as f64 / duration.as_secs_f64(),
+            error_rate: num_error as f64 / (num_success + num_error).max(1) as f64,
+            latency_p50_ms: histogram.value_at_quantile(0.5),
+            latency_p99_ms: histogram.value_at_quantile(0.99),
+        })
+    }
+
+    fn is_sustained(&self, config: &ThroughputSearchConfig, target_tps: u64) -> bool {
+        self.achieved_tps >= target_tps as f64 * MIN_ACHIEVED_RATIO
+            && self.error_rate <= MAX_ERROR_RATE
+            && self.latency_p50_ms <= config.latency_slo_p50_ms
+            && self.latency_p99_ms <= config.latency_slo_p99_ms
+    }
+}
+
+/// Drives the rate of the bench workers to find the highest TPS at which the latency SLOs hold:
+/// the rate doubles from a fraction of `max_tps` until a step violates the SLOs, then the
+/// sustainable rate is binary searched between the last good and the first bad step.
+///
+/// The rate is published to the workers as a fraction of `max_tps` through `rate_scale`, the
+/// aggregated stats of every stat collection interval are read from `samples`. The benchmark is
+/// cancelled through `token` once the search converges, and the highest sustained rate returned.
+pub async fn search_max_sustainable_tps(
+    config: ThroughputSearchConfig,
+    max_tps: u64,
+    stat_collection_interval: Duration,
+    rate_scale: &watch::Sender<f64>,
+    mut samples: Receiver<BenchmarkStats>,
+    token: CancellationToken,
+) -> u64 {
+    let resolution = config.resolution_tps.max(1);
+    let mut sustained = 0;
+    let mut failed: Option<u64> = None;
+    let mut target = std::cmp::max(max_tps / INITIAL_FRACTION, 1);
+
+    loop {
+        rate_scale.send_replace(target as f64 / max_tps as f64);
+        let step_start = Instant::now();
+        let warmed_up = step_start + stat_collection_interval;
+        let step_end = step_start + config.step_duration.max(2 * stat_collection_interval);
+
+        let mut step_samples = vec![];
+        let mut finished = false;
+        loop {
+            tokio::select! {
+                sample = samples.recv() => match sample {
+                    Some(sample) if Instant::now() >= warmed_up => step_samples.push(sample),
+                    Some(_) => (),
+                    None => {
+                        finished = true;
+                        break;
+                    }
+                },
+                _ = tokio::time::sleep_until(step_end) => break,
+            }
+        }
+        // The benchmark ended on its own before the search converged.
+        if finished {
+            break;
+        }
+
+        let Some(result) = StepResult::from_samples(&step_samples) else {
+            info!("No stats collected while running at {target} TPS, stopping the search");
+            break;
+        };
+        let ok = result.is_sustained(&config, target);
+        eprintln!(
+            "TPS search: target = {}, achieved = {:.1}, error% = {:.2}, latency_ms(p50/p99) = {}/{} => {}",
+            target,
+            result.achieved_tps,
+            100.0 * result.error_rate,
+            result.latency_p50_ms,
+            result.latency_p99_ms,
+            if ok { "sustained" } else { "not sustained" },
+        );
+        if ok {
+            sustained = target;
+        } else {
+            failed = Some(target);
+        }
+
+        target = match failed {
+            // Still ramping up
+            None if target >= max_tps => break,
+            None => std::cmp::min(target * 2, max_tps),
+            // Binary searching between the last sustained and first failed rates
+            Some(failed) if failed - sustained <= resolution => break,
+            Some(failed) => sustained + (failed - sustained) / 2,
+        };
+    }
+
+    token.cancel();
+    sustained
+}
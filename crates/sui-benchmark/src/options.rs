@@ -4,6 +4,7 @@
 use clap::*;
 
 use strum_macros::EnumString;
+use sui_types::base_types::ObjectID;
 
 use crate::drivers::Interval;
 
@@ -99,6 +100,10 @@ pub struct Opts {
     /// Path where previous benchmark stats is stored to use for comparison
     #[clap(long, default_value = "", global = true)]
     pub compare_with: String,
+    /// Path where the results of the run are exported with a stable schema, as CSV if the path
+    /// ends with `.csv` and as JSON otherwise. Nothing is exported if empty.
+    #[clap(long, default_value = "", global = true)]
+    pub results_path: String,
     // Stat collection interval seconds
     #[clap(long, default_value = "10", global = true)]
     pub stat_collection_interval: u64,
@@ -108,6 +113,25 @@ pub struct Opts {
     // the end of the benchmark or periodically during a continuous run.
     #[clap(long, action, global = true)]
     pub stress_stat_collection: bool,
+    /// Instead of running at a fixed rate, ramp up the rate and then
+    /// binary search the highest TPS at which the latency SLOs below
+    /// hold, reporting it as the max sustainable TPS. The target qps
+    /// of the workloads is the upper bound of the search.
+    #[clap(long, action, global = true)]
+    pub max_tps_search: bool,
+    /// p50 latency SLO used by the max TPS search
+    #[clap(long, default_value = "1000", global = true)]
+    pub latency_slo_p50_ms: u64,
+    /// p99 latency SLO used by the max TPS search
+    #[clap(long, default_value = "5000", global = true)]
+    pub latency_slo_p99_ms: u64,
+    /// How long each rate is run for during the max TPS search
+    #[clap(long, default_value = "60", global = true)]
+    pub tps_search_step_secs: u64,
+    /// The max TPS search stops once the sustainable rate is known
+    /// within this many TPS
+    #[clap(long, default_value = "10", global = true)]
+    pub tps_search_resolution: u64,
 
     /// Start the stress test at a given protocol version. (Usually unnecessary if stress test is
     /// built at the same commit as the validators.
@@ -148,6 +172,14 @@ pub enum RunSpec {
         // relative weight of adversarial transactions in the benchmark workload
         #[clap(long, default_value = "0")]
         adversarial: u32,
+        // relative weight of shared object contention
+        // transactions in the benchmark workload
+        #[clap(long, default_value = "0")]
+        shared_contention: u32,
+        // relative weight of Move call fuzzing transactions
+        // in the benchmark workload
+        #[clap(long, default_value = "0")]
+        move_fuzz: u32,
 
         // --- workload-specific options --- (TODO: use subcommands or similar)
         // 100 for max hotness i.e all requests target
@@ -161,6 +193,28 @@ pub enum RunSpec {
         // batch size use for batch payment workload
         #[clap(long, default_value = "15")]
         batch_payment_size: u32,
+        // number of shared counters the contention workload
+        // spreads its transactions over
+        #[clap(long, default_value = "10")]
+        shared_contention_counters: u64,
+        // exponent (in hundredths) of the Zipfian distribution
+        // over the contention counters, 0 for uniform access
+        // and 100 for a classic Zipf distribution
+        #[clap(long, default_value = "100")]
+        shared_contention_skew: u32,
+        // percentage of contention transactions that only
+        // read their counter instead of incrementing it
+        #[clap(long, default_value = "0")]
+        shared_contention_read_ratio: u32,
+        // comma separated IDs of published packages to fuzz,
+        // in addition to the basics package the fuzzer publishes
+        #[clap(
+            long,
+            multiple_occurrences = false,
+            multiple_values = true,
+            value_delimiter = ','
+        )]
+        move_fuzz_packages: Vec<ObjectID>,
 
         // --- generic options ---
         // Target qps
@@ -4,7 +4,9 @@
 pub mod adversarial;
 pub mod batch_payment;
 pub mod delegation;
+pub mod move_fuzz;
 pub mod payload;
+pub mod shared_contention;
 pub mod shared_counter;
 pub mod transfer_object;
 pub mod workload;
@@ -0,0 +1,887 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fuzzes the public and entry functions of published packages with random but type-correct
+//! programmable transactions. Arguments are generated from the normalized signatures of the
+//! functions, and object arguments are picked among the live objects the workload has seen in
+//! effects. Move aborts and other execution errors are expected and only counted, invariant
+//! violations are logged together with the calls that triggered them.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use move_binary_format::file_format::{Ability, AbilitySet, Visibility};
+use move_binary_format::file_format_common::VERSION_MAX;
+use move_binary_format::normalized::{Struct, Type};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use rand::distributions::Alphanumeric;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use sui_json_rpc_types::{SuiExecutionStatus, SuiTransactionEffectsAPI};
+use sui_types::base_types::{
+    ObjectID, ObjectRef, SequenceNumber, TX_CONTEXT_MODULE_NAME, TX_CONTEXT_STRUCT_NAME,
+};
+use sui_types::crypto::get_key_pair;
+use sui_types::gas_coin::GasCoin;
+use sui_types::messages::{
+    Argument, ExecutionFailureStatus, ExecutionStatus, ObjectArg, TransactionData,
+    TransactionEffectsAPI, VerifiedTransaction,
+};
+use sui_types::object::Owner;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::utils::to_sender_signed_transaction;
+use sui_types::{
+    MOVE_STDLIB_ADDRESS, MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_ADDRESS, SUI_FRAMEWORK_OBJECT_ID,
+};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info};
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::util::publish_basics_package;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{Workload, WorkloadBuilder, MAX_GAS_FOR_TESTING};
+use crate::workloads::{Gas, GasCoinConfig, WorkloadBuilderInfo, WorkloadParams};
+use crate::{ExecutionEffects, ValidatorProxy};
+
+/// Most Move calls chained into a single fuzzed transaction.
+const MAX_CALLS_PER_TRANSACTION: usize = 3;
+/// How many functions are tried for each call before giving up on finding a callable one.
+const MAX_ATTEMPTS_PER_CALL: usize = 16;
+/// Longest vector or string generated for a pure argument.
+const MAX_VECTOR_LENGTH: usize = 8;
+/// How often the fuzzing statistics are printed while the workload runs.
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A function of a fuzzed package, callable from a programmable transaction.
+#[derive(Debug)]
+struct FuzzTarget {
+    package: ObjectID,
+    module: Identifier,
+    function: Identifier,
+    type_parameters: Vec<AbilitySet>,
+    parameters: Vec<Type>,
+    return_: Vec<Type>,
+}
+
+/// The functions being fuzzed, and the declarations of the structs their signatures use.
+#[derive(Debug, Default)]
+struct Signatures {
+    targets: Vec<FuzzTarget>,
+    structs: HashMap<(AccountAddress, Identifier, Identifier), Struct>,
+    /// Types type parameters are instantiated with: a few primitives, and the non-generic
+    /// structs of the fuzzed packages.
+    type_arguments: Vec<Type>,
+}
+
+impl Signatures {
+    /// Read and normalize the modules of `packages`. The Move standard library and the Sui
+    /// framework are always read for their struct declarations, but only fuzzed if listed.
+    async fn load(proxy: &Arc<dyn ValidatorProxy + Sync + Send>, packages: &[ObjectID]) -> Self {
+        let mut signatures = Self {
+            type_arguments: vec![Type::Bool, Type::U8, Type::U64, Type::U128, Type::Address],
+            ..Default::default()
+        };
+        let mut package_ids = vec![MOVE_STDLIB_OBJECT_ID, SUI_FRAMEWORK_OBJECT_ID];
+        for package_id in packages {
+            if !package_ids.contains(package_id) {
+                package_ids.push(*package_id);
+            }
+        }
+
+        for package_id in package_ids {
+            let fuzzed = packages.contains(&package_id);
+            let object = proxy
+                .get_object(package_id)
+                .await
+                .unwrap_or_else(|e| panic!("Failed to read package {package_id}: {e}"));
+            let modules = object
+                .data
+                .try_as_package()
+                .unwrap_or_else(|| panic!("Object {package_id} is not a package"))
+                .normalize(VERSION_MAX)
+                .expect("Failed to normalize package modules");
+            for module in modules.into_values() {
+                for (name, declaration) in module.structs {
+                    if fuzzed && declaration.type_parameters.is_empty() {
+                        signatures.type_arguments.push(Type::Struct {
+                            address: module.address,
+                            module: module.name.clone(),
+                            name: name.clone(),
+                            type_arguments: vec![],
+                        });
+                    }
+                    signatures
+                        .structs
+                        .insert((module.address, module.name.clone(), name), declaration);
+                }
+                if !fuzzed {
+                    continue;
+                }
+                for (name, function) in module.exposed_functions {
+                    if function.visibility != Visibility::Public && !function.is_entry {
+                        continue;
+                    }
+                    signatures.targets.push(FuzzTarget {
+                        package: package_id,
+                        module: module.name.clone(),
+                        function: name,
+                        type_parameters: function.type_parameters,
+                        parameters: function.parameters,
+                        return_: function.return_,
+                    });
+                }
+            }
+        }
+        signatures
+    }
+
+    /// The abilities of a fully instantiated value type, `None` if it uses a struct that was not
+    /// loaded.
+    fn abilities(&self, ty: &Type) -> Option<AbilitySet> {
+        match ty {
+            Type::Bool
+            | Type::U8
+            | Type::U16
+            | Type::U32
+            | Type::U64
+            | Type::U128
+            | Type::U256
+            | Type::Address => Some(AbilitySet::PRIMITIVES),
+            Type::Signer => Some(AbilitySet::SIGNER),
+            Type::Vector(inner) => Some(self.abilities(inner)?.intersect(AbilitySet::VECTOR)),
+            Type::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => {
+                let declaration = self
+                    .structs
+                    .get(&(*address, module.clone(), name.clone()))?;
+                let mut abilities = declaration.abilities;
+                for (parameter, argument) in declaration.type_parameters.iter().zip(type_arguments)
+                {
+                    if parameter.is_phantom {
+                        continue;
+                    }
+                    let argument = self.abilities(argument)?;
+                    for ability in [Ability::Copy, Ability::Drop, Ability::Store] {
+                        if !argument.has_ability(ability) {
+                            abilities = abilities.remove(ability);
+                        }
+                    }
+                    if !argument.has_store() {
+                        abilities = abilities.remove(Ability::Key);
+                    }
+                }
+                Some(abilities)
+            }
+            Type::TypeParameter(_) | Type::Reference(_) | Type::MutableReference(_) => None,
+        }
+    }
+
+    /// A random type satisfying `constraints`.
+    fn type_argument<R: Rng>(&self, constraints: AbilitySet, rng: &mut R) -> Option<Type> {
+        let candidates: Vec<_> = self
+            .type_arguments
+            .iter()
+            .filter(|ty| {
+                self.abilities(ty)
+                    .map_or(false, |abilities| constraints.is_subset(abilities))
+            })
+            .collect();
+        candidates.choose(rng).map(|ty| (*ty).clone())
+    }
+}
+
+/// Objects fuzzed transactions can take as arguments, shared by all the payloads of the workload.
+/// Objects owned by an address are tracked by the payload of that address.
+#[derive(Debug, Default)]
+struct LiveObjects {
+    /// Type of every object seen in effects, `None` while it is being read.
+    types: HashMap<ObjectID, Option<StructTag>>,
+    /// Initial shared version of the shared objects.
+    shared: BTreeMap<ObjectID, SequenceNumber>,
+    immutable: BTreeMap<ObjectID, ObjectRef>,
+}
+
+impl LiveObjects {
+    fn has_type(&self, id: &ObjectID, tag: &StructTag) -> bool {
+        matches!(self.types.get(id), Some(Some(t)) if t == tag)
+    }
+
+    /// Reads the types of the objects received on `ids` until every payload has been dropped.
+    fn spawn_type_resolver(
+        live_objects: Arc<Mutex<LiveObjects>>,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        mut ids: mpsc::UnboundedReceiver<ObjectID>,
+    ) {
+        tokio::spawn(async move {
+            while let Some(id) = ids.recv().await {
+                match proxy.get_object(id).await {
+                    Ok(object) => {
+                        let tag = object.data.struct_tag();
+                        live_objects.lock().unwrap().types.insert(id, tag);
+                    }
+                    Err(e) => {
+                        // Forget the object so its type is read again next time it is seen
+                        debug!("Failed to read the type of object {id}: {e}");
+                        live_objects.lock().unwrap().types.remove(&id);
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[derive(Debug, Default)]
+struct FuzzStats {
+    transactions: AtomicU64,
+    succeeded: AtomicU64,
+    failed: AtomicU64,
+    invariant_violations: AtomicU64,
+    /// Transactions for which no function could be called with the live objects.
+    without_calls: AtomicU64,
+}
+
+impl FuzzStats {
+    /// Prints the statistics periodically until all the payloads have been dropped.
+    fn spawn_reporter(stats: Weak<FuzzStats>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REPORT_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(stats) = stats.upgrade() else {
+                    break;
+                };
+                eprintln!(
+                    "Move fuzzer: {} transactions, {} succeeded, {} failed, \
+                    {} invariant violations, {} without callable functions",
+                    stats.transactions.load(Ordering::Relaxed),
+                    stats.succeeded.load(Ordering::Relaxed),
+                    stats.failed.load(Ordering::Relaxed),
+                    stats.invariant_violations.load(Ordering::Relaxed),
+                    stats.without_calls.load(Ordering::Relaxed),
+                );
+            }
+        });
+    }
+}
+
+/// An argument chosen for a call, added to the transaction once all the arguments of the call
+/// have been found.
+#[derive(Debug)]
+enum PlannedArgument {
+    Pure(Vec<u8>),
+    Object { object: ObjectArg, by_value: bool },
+    GasCoin,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+    ByValue,
+}
+
+#[derive(Debug)]
+pub struct MoveFuzzTestPayload {
+    signatures: Arc<Signatures>,
+    live_objects: Arc<Mutex<LiveObjects>>,
+    resolver: mpsc::UnboundedSender<ObjectID>,
+    stats: Arc<FuzzStats>,
+    /// Objects owned by the sender, not including the gas coin.
+    owned: BTreeMap<ObjectID, ObjectRef>,
+    /// Calls of the transaction in flight and the owned objects it takes by value.
+    in_flight: Option<(String, Vec<ObjectID>)>,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for MoveFuzzTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "move_fuzz")
+    }
+}
+
+impl Payload for MoveFuzzTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        self.gas.0 = effects.gas_object().0;
+        let (calls, consumed) = self.in_flight.take().unwrap_or_default();
+        self.stats.transactions.fetch_add(1, Ordering::Relaxed);
+        match execution_failure(effects) {
+            None => {
+                self.stats.succeeded.fetch_add(1, Ordering::Relaxed);
+            }
+            Some((error, true)) => {
+                self.stats
+                    .invariant_violations
+                    .fetch_add(1, Ordering::Relaxed);
+                error!("Move fuzzer hit an invariant violation: {error}, calls:\n{calls}");
+            }
+            Some(_) => {
+                self.stats.failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        // Objects taken by value are either deleted, wrapped or given back in the effects
+        for id in consumed {
+            self.owned.remove(&id);
+        }
+        let mut live_objects = self.live_objects.lock().unwrap();
+        for (obj_ref, owner) in effects.created().into_iter().chain(effects.mutated()) {
+            if obj_ref.0 == self.gas.0 .0 {
+                continue;
+            }
+            match owner {
+                Owner::AddressOwner(address) if address == self.gas.1 => {
+                    self.owned.insert(obj_ref.0, obj_ref);
+                }
+                Owner::Shared {
+                    initial_shared_version,
+                } => {
+                    live_objects
+                        .shared
+                        .insert(obj_ref.0, initial_shared_version);
+                }
+                Owner::Immutable => {
+                    live_objects.immutable.insert(obj_ref.0, obj_ref);
+                }
+                Owner::AddressOwner(_) | Owner::ObjectOwner(_) => continue,
+            }
+            if let Entry::Vacant(entry) = live_objects.types.entry(obj_ref.0) {
+                entry.insert(None);
+                self.resolver.send(obj_ref.0).ok();
+            }
+        }
+        for obj_ref in effects.deleted() {
+            self.owned.remove(&obj_ref.0);
+            live_objects.shared.remove(&obj_ref.0);
+        }
+    }
+
+    fn make_transaction(&mut self) -> VerifiedTransaction {
+        let mut rng = rand::thread_rng();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let mut taken = BTreeSet::new();
+        let mut consumed = vec![];
+        let mut calls = String::new();
+        {
+            let live_objects = self.live_objects.lock().unwrap();
+            for _ in 0..rng.gen_range(1..=MAX_CALLS_PER_TRANSACTION) {
+                for _ in 0..MAX_ATTEMPTS_PER_CALL {
+                    if self.add_call(
+                        &mut builder,
+                        &live_objects,
+                        &mut taken,
+                        &mut consumed,
+                        &mut calls,
+                        &mut rng,
+                    ) {
+                        break;
+                    }
+                }
+            }
+        }
+        if calls.is_empty() {
+            // Nothing is callable yet, give the sender a coin to pass to functions instead
+            self.stats.without_calls.fetch_add(1, Ordering::Relaxed);
+            builder.transfer_sui(self.gas.1, Some(1));
+        }
+        self.in_flight = Some((calls, consumed));
+
+        let system_state = self.system_state_observer.state.borrow();
+        let gas_budget = system_state
+            .protocol_config
+            .as_ref()
+            .expect("Protocol config not in system state")
+            .max_tx_gas();
+        let data = TransactionData::new_programmable(
+            self.gas.1,
+            vec![self.gas.0],
+            builder.finish(),
+            gas_budget,
+            system_state.reference_gas_price,
+        );
+        to_sender_signed_transaction(data, self.gas.2.as_ref())
+    }
+}
+
+impl MoveFuzzTestPayload {
+    /// Appends a call to a random function to `builder`. Returns false if the function picked
+    /// cannot be called with the live objects, in which case `builder` is left untouched.
+    fn add_call<R: Rng>(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        live_objects: &LiveObjects,
+        taken: &mut BTreeSet<ObjectID>,
+        consumed: &mut Vec<ObjectID>,
+        calls: &mut String,
+        rng: &mut R,
+    ) -> bool {
+        let Some(target) = self.signatures.targets.choose(rng) else {
+            return false;
+        };
+        let Some(type_arguments) = target
+            .type_parameters
+            .iter()
+            .map(|constraints| self.signatures.type_argument(*constraints, rng))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+        let Some(type_tags) = type_arguments
+            .iter()
+            .map(type_tag)
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+
+        let mut call_taken = taken.clone();
+        let mut arguments = vec![];
+        for parameter in &target.parameters {
+            let parameter = instantiate(parameter, &type_arguments);
+            if is_tx_context(&parameter) {
+                continue;
+            }
+            let Some(argument) = self.argument(&parameter, live_objects, &mut call_taken, rng)
+            else {
+                return false;
+            };
+            arguments.push(argument);
+        }
+
+        // Returned values that cannot be dropped are sent to the sender, functions returning
+        // values that can be neither dropped nor transferred are not called.
+        let mut transferred = vec![];
+        for (index, ty) in target.return_.iter().enumerate() {
+            let Some(abilities) = self
+                .signatures
+                .abilities(&instantiate(ty, &type_arguments))
+            else {
+                return false;
+            };
+            if abilities.has_drop() {
+                continue;
+            }
+            if !abilities.has_key() || !abilities.has_store() {
+                return false;
+            }
+            transferred.push(index as u16);
+        }
+
+        writeln!(
+            calls,
+            "{}::{}::{}<{:?}>({:?})",
+            target.package, target.module, target.function, type_tags, arguments
+        )
+        .unwrap();
+        *taken = call_taken;
+        let arguments = arguments
+            .into_iter()
+            .map(|argument| match argument {
+                PlannedArgument::Pure(bytes) => builder.pure_bytes(bytes, false),
+                PlannedArgument::Object { object, by_value } => {
+                    if by_value {
+                        consumed.push(object.id());
+                    }
+                    builder
+                        .obj(object)
+                        .expect("Objects are only taken once per transaction")
+                }
+                PlannedArgument::GasCoin => Argument::GasCoin,
+            })
+            .collect();
+        let Argument::Result(result) = builder.programmable_move_call(
+            target.package,
+            target.module.clone(),
+            target.function.clone(),
+            type_tags,
+            arguments,
+        ) else {
+            unreachable!("Commands always produce a result");
+        };
+        if !transferred.is_empty() {
+            builder.transfer_args(
+                self.gas.1,
+                transferred
+                    .into_iter()
+                    .map(|index| Argument::NestedResult(result, index))
+                    .collect(),
+            );
+        }
+        true
+    }
+
+    /// A random argument of type `ty`, objects are picked among the live objects that are not
+    /// already `taken` by the transaction.
+    fn argument<R: Rng>(
+        &self,
+        ty: &Type,
+        live_objects: &LiveObjects,
+        taken: &mut BTreeSet<ObjectID>,
+        rng: &mut R,
+    ) -> Option<PlannedArgument> {
+        if let Some(bytes) = pure_value(ty, rng) {
+            return Some(PlannedArgument::Pure(bytes));
+        }
+        let (inner, access) = match ty {
+            Type::Reference(inner) => (&**inner, Access::Read),
+            Type::MutableReference(inner) => (&**inner, Access::Write),
+            Type::Struct { .. } => (ty, Access::ByValue),
+            _ => return None,
+        };
+        let tag = inner.clone().into_struct_tag()?;
+
+        // The gas coin can be borrowed, but not taken by value
+        if GasCoin::is_gas_coin(&tag)
+            && access != Access::ByValue
+            && rng.gen_bool(0.5)
+            && taken.insert(self.gas.0 .0)
+        {
+            return Some(PlannedArgument::GasCoin);
+        }
+
+        let mut candidates = vec![];
+        for (id, obj_ref) in &self.owned {
+            if !taken.contains(id) && live_objects.has_type(id, &tag) {
+                candidates.push(ObjectArg::ImmOrOwnedObject(*obj_ref));
+            }
+        }
+        if access != Access::ByValue {
+            for (id, initial_shared_version) in &live_objects.shared {
+                if !taken.contains(id) && live_objects.has_type(id, &tag) {
+                    candidates.push(ObjectArg::SharedObject {
+                        id: *id,
+                        initial_shared_version: *initial_shared_version,
+                        mutable: access == Access::Write,
+                    });
+                }
+            }
+        }
+        if access == Access::Read {
+            for (id, obj_ref) in &live_objects.immutable {
+                if !taken.contains(id) && live_objects.has_type(id, &tag) {
+                    candidates.push(ObjectArg::ImmOrOwnedObject(*obj_ref));
+                }
+            }
+        }
+        let object = *candidates.choose(rng)?;
+        taken.insert(object.id());
+        Some(PlannedArgument::Object {
+            object,
+            by_value: access == Access::ByValue,
+        })
+    }
+}
+
+/// The execution error of a failed transaction, and whether it is an invariant violation.
+fn execution_failure(effects: &ExecutionEffects) -> Option<(String, bool)> {
+    match effects {
+        ExecutionEffects::CertifiedTransactionEffects(effects, _) => {
+            match effects.data().status() {
+                ExecutionStatus::Success => None,
+                ExecutionStatus::Failure { error, command } => Some((
+                    format!("{error:?} in command {command:?}"),
+                    matches!(
+                        error,
+                        ExecutionFailureStatus::InvariantViolation
+                            | ExecutionFailureStatus::VMInvariantViolation
+                    ),
+                )),
+            }
+        }
+        ExecutionEffects::SuiTransactionEffects(effects) => match effects.status() {
+            SuiExecutionStatus::Success => None,
+            SuiExecutionStatus::Failure { error } => Some((
+                error.clone(),
+                error.starts_with("InvariantViolation")
+                    || error.starts_with("VMInvariantViolation"),
+            )),
+        },
+    }
+}
+
+/// Replaces the type parameters in `ty` with `type_arguments`.
+fn instantiate(ty: &Type, type_arguments: &[Type]) -> Type {
+    match ty {
+        Type::TypeParameter(index) => type_arguments[*index as usize].clone(),
+        Type::Vector(inner) => Type::Vector(Box::new(instantiate(inner, type_arguments))),
+        Type::Reference(inner) => Type::Reference(Box::new(instantiate(inner, type_arguments))),
+        Type::MutableReference(inner) => {
+            Type::MutableReference(Box::new(instantiate(inner, type_arguments)))
+        }
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments: arguments,
+        } => Type::Struct {
+            address: *address,
+            module: module.clone(),
+            name: name.clone(),
+            type_arguments: arguments
+                .iter()
+                .map(|argument| instantiate(argument, type_arguments))
+                .collect(),
+        },
+        _ => ty.clone(),
+    }
+}
+
+fn type_tag(ty: &Type) -> Option<TypeTag> {
+    Some(match ty {
+        Type::Bool => TypeTag::Bool,
+        Type::U8 => TypeTag::U8,
+        Type::U16 => TypeTag::U16,
+        Type::U32 => TypeTag::U32,
+        Type::U64 => TypeTag::U64,
+        Type::U128 => TypeTag::U128,
+        Type::U256 => TypeTag::U256,
+        Type::Address => TypeTag::Address,
+        Type::Vector(inner) => TypeTag::Vector(Box::new(type_tag(inner)?)),
+        Type::Struct { .. } => TypeTag::Struct(Box::new(ty.clone().into_struct_tag()?)),
+        _ => return None,
+    })
+}
+
+/// Whether `ty` is a reference to the `TxContext`, which is passed implicitly.
+fn is_tx_context(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(inner) | Type::MutableReference(inner) => matches!(
+            &**inner,
+            Type::Struct { address, module, name, .. }
+                if *address == SUI_FRAMEWORK_ADDRESS
+                    && module.as_ident_str() == TX_CONTEXT_MODULE_NAME
+                    && name.as_ident_str() == TX_CONTEXT_STRUCT_NAME
+        ),
+        _ => false,
+    }
+}
+
+/// The BCS bytes of a random value of `ty`, if values of `ty` can be passed as pure arguments.
+fn pure_value<R: Rng>(ty: &Type, rng: &mut R) -> Option<Vec<u8>> {
+    Some(match ty {
+        Type::Bool => vec![rng.gen_range(0..2)],
+        Type::U8 => integer(1, rng),
+        Type::U16 => integer(2, rng),
+        Type::U32 => integer(4, rng),
+        Type::U64 => integer(8, rng),
+        Type::U128 => integer(16, rng),
+        Type::U256 => integer(32, rng),
+        Type::Address => rng.gen::<[u8; AccountAddress::LENGTH]>().to_vec(),
+        Type::Vector(inner) => {
+            // Make sure the elements are pure even if the vector ends up empty
+            let first = pure_value(inner, rng)?;
+            let len = rng.gen_range(0..=MAX_VECTOR_LENGTH);
+            let mut bytes = uleb128(len);
+            if len > 0 {
+                bytes.extend(first);
+                for _ in 1..len {
+                    bytes.extend(pure_value(inner, rng)?);
+                }
+            }
+            bytes
+        }
+        Type::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => match (*address, module.as_str(), name.as_str()) {
+            (MOVE_STDLIB_ADDRESS, "string" | "ascii", "String") => {
+                let len = rng.gen_range(0..=MAX_VECTOR_LENGTH);
+                let mut bytes = uleb128(len);
+                bytes.extend((0..len).map(|_| rng.sample(Alphanumeric)));
+                bytes
+            }
+            (MOVE_STDLIB_ADDRESS, "option", "Option") => {
+                let value = pure_value(type_arguments.first()?, rng)?;
+                if rng.gen_bool(0.5) {
+                    vec![0]
+                } else {
+                    let mut bytes = vec![1];
+                    bytes.extend(value);
+                    bytes
+                }
+            }
+            (SUI_FRAMEWORK_ADDRESS, "object", "ID") => {
+                rng.gen::<[u8; AccountAddress::LENGTH]>().to_vec()
+            }
+            _ => return None,
+        },
+        Type::Signer | Type::TypeParameter(_) | Type::Reference(_) | Type::MutableReference(_) => {
+            return None
+        }
+    })
+}
+
+/// The little endian bytes of a random integer `width` bytes wide, biased towards boundaries.
+fn integer<R: Rng>(width: usize, rng: &mut R) -> Vec<u8> {
+    let mut bytes = vec![0; width];
+    match rng.gen_range(0..8) {
+        0 => (),
+        1 => bytes.fill(u8::MAX),
+        2 => bytes[0] = rng.gen(),
+        _ => rng.fill(&mut bytes[..]),
+    }
+    bytes
+}
+
+fn uleb128(mut value: usize) -> Vec<u8> {
+    let mut bytes = vec![];
+    while value >= 0x80 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+    bytes
+}
+
+#[derive(Debug)]
+pub struct MoveFuzzWorkloadBuilder {
+    num_payloads: u64,
+    packages: Vec<ObjectID>,
+}
+
+impl MoveFuzzWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        packages: Vec<ObjectID>,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if max_ops == 0 || num_workers == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                target_qps,
+                num_workers,
+                max_ops,
+            };
+            let workload_builder =
+                Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(MoveFuzzWorkloadBuilder {
+                    num_payloads: max_ops,
+                    packages,
+                }));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for MoveFuzzWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        // Gas coin for publishing the basics package
+        let (address, keypair) = get_key_pair();
+        vec![GasCoinConfig {
+            amount: MAX_GAS_FOR_TESTING,
+            address,
+            keypair: Arc::new(keypair),
+        }]
+    }
+
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        // Gas coins for running workload
+        for _i in 0..self.num_payloads {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+
+    async fn build(
+        &self,
+        mut init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(MoveFuzzWorkload {
+            packages: self.packages.clone(),
+            signatures: Arc::new(Signatures::default()),
+            init_gas: init_gas.pop().unwrap(),
+            payload_gas,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct MoveFuzzWorkload {
+    /// Packages to fuzz, the basics package is published and added to them on init.
+    packages: Vec<ObjectID>,
+    signatures: Arc<Signatures>,
+    pub init_gas: Gas,
+    pub payload_gas: Vec<Gas>,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for MoveFuzzWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        let (gas, sender, keypair) = &self.init_gas;
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        info!("Publishing basics package");
+        let package_ref =
+            publish_basics_package(*gas, proxy.clone(), *sender, keypair, gas_price).await;
+        self.packages.push(package_ref.0);
+
+        let signatures = Signatures::load(&proxy, &self.packages).await;
+        info!(
+            "Fuzzing {} functions of packages {:?}",
+            signatures.targets.len(),
+            self.packages
+        );
+        self.signatures = Arc::new(signatures);
+    }
+
+    async fn make_test_payloads(
+        &self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        let live_objects = Arc::new(Mutex::new(LiveObjects::default()));
+        let (resolver, ids) = mpsc::unbounded_channel();
+        LiveObjects::spawn_type_resolver(live_objects.clone(), proxy, ids);
+        let stats = Arc::new(FuzzStats::default());
+        FuzzStats::spawn_reporter(Arc::downgrade(&stats));
+        self.payload_gas
+            .iter()
+            .map(|gas| {
+                Box::<dyn Payload>::from(Box::new(MoveFuzzTestPayload {
+                    signatures: self.signatures.clone(),
+                    live_objects: live_objects.clone(),
+                    resolver: resolver.clone(),
+                    stats: stats.clone(),
+                    owned: BTreeMap::new(),
+                    in_flight: None,
+                    gas: gas.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,449 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::workload::Workload;
+use crate::workloads::{Gas, WorkloadBuilderInfo, WorkloadParams};
+
+use crate::system_state_observer::SystemStateObserver;
+use crate::workloads::payload::Payload;
+use crate::workloads::workload::{WorkloadBuilder, MAX_GAS_FOR_TESTING};
+use crate::workloads::GasCoinConfig;
+use crate::{ExecutionEffects, ValidatorProxy};
+use async_trait::async_trait;
+use comfy_table::{Cell, ContentArrangement, Row, Table};
+use futures::future::join_all;
+use hdrhistogram::Histogram;
+use rand::Rng;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use sui_types::crypto::get_key_pair;
+use sui_types::{
+    base_types::{ObjectDigest, ObjectID, SequenceNumber},
+    messages::VerifiedTransaction,
+};
+use test_utils::messages::{
+    make_counter_create_transaction, make_counter_increment_transaction,
+    make_counter_read_transaction,
+};
+
+use crate::util::publish_basics_package;
+use tracing::info;
+
+/// How often the per-object contention table is printed while the workload runs.
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+/// Only the hottest objects are reported, the tail of the distribution is rarely interesting.
+const MAX_REPORTED_OBJECTS: usize = 20;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SharedContentionConfig {
+    /// Number of shared counters the workload spreads its transactions over.
+    pub num_counters: u64,
+    /// Exponent of the Zipfian distribution over the counters, in hundredths. 0 gives a uniform
+    /// distribution, larger values concentrate the load on the first few counters.
+    pub skew: u32,
+    /// Percentage of transactions that only read their counter instead of incrementing it.
+    pub read_ratio: u32,
+}
+
+/// Samples counter indices following a Zipfian distribution, where the counter of rank `k`
+/// is picked with a probability proportional to `1 / k^s`.
+#[derive(Debug)]
+struct ZipfSampler {
+    cumulative_weights: Vec<f64>,
+}
+
+impl ZipfSampler {
+    fn new(num_items: usize, exponent: f64) -> Self {
+        let mut total = 0.0;
+        let cumulative_weights = (1..=num_items)
+            .map(|rank| {
+                total += 1.0 / (rank as f64).powf(exponent);
+                total
+            })
+            .collect();
+        Self { cumulative_weights }
+    }
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> usize {
+        let total = *self
+            .cumulative_weights
+            .last()
+            .expect("Sampler has no items");
+        let point = rng.gen_range(0.0..total);
+        self.cumulative_weights
+            .partition_point(|weight| *weight <= point)
+            .min(self.cumulative_weights.len() - 1)
+    }
+}
+
+#[derive(Debug)]
+struct ObjectContentionStats {
+    reads: AtomicU64,
+    writes: AtomicU64,
+    // transactions submitted while a write to the same object was still in flight
+    conflicts: AtomicU64,
+    in_flight_writes: AtomicU64,
+    completed_writes: AtomicU64,
+    latency_ms: Mutex<Histogram<u64>>,
+}
+
+impl ObjectContentionStats {
+    fn new() -> Self {
+        Self {
+            reads: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
+            conflicts: AtomicU64::new(0),
+            in_flight_writes: AtomicU64::new(0),
+            completed_writes: AtomicU64::new(0),
+            latency_ms: Mutex::new(Histogram::new(3).expect("Failed to create histogram")),
+        }
+    }
+}
+
+/// Per-object counters shared by all the payloads of a contention workload.
+#[derive(Debug)]
+struct ContentionStats {
+    objects: Vec<ObjectContentionStats>,
+}
+
+impl ContentionStats {
+    fn new(num_objects: usize) -> Self {
+        Self {
+            objects: (0..num_objects)
+                .map(|_| ObjectContentionStats::new())
+                .collect(),
+        }
+    }
+
+    fn record_submission(&self, index: usize, write: bool) {
+        let stats = &self.objects[index];
+        let conflicting = if write {
+            stats.writes.fetch_add(1, Ordering::Relaxed);
+            stats.in_flight_writes.fetch_add(1, Ordering::Relaxed) > 0
+        } else {
+            stats.reads.fetch_add(1, Ordering::Relaxed);
+            stats.in_flight_writes.load(Ordering::Relaxed) > 0
+        };
+        if conflicting {
+            stats.conflicts.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn record_completion(&self, index: usize, write: bool, success: bool, latency: Duration) {
+        let stats = &self.objects[index];
+        if write {
+            stats.in_flight_writes.fetch_sub(1, Ordering::Relaxed);
+            if success {
+                stats.completed_writes.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        stats
+            .latency_ms
+            .lock()
+            .unwrap()
+            .saturating_record(latency.as_millis() as u64);
+    }
+
+    fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_width(200)
+            .set_header(vec![
+                "object rank",
+                "reads",
+                "writes",
+                "conflict%",
+                "latency (p50)",
+                "latency (p99)",
+            ]);
+        for (rank, stats) in self.objects.iter().take(MAX_REPORTED_OBJECTS).enumerate() {
+            let reads = stats.reads.load(Ordering::Relaxed);
+            let writes = stats.writes.load(Ordering::Relaxed);
+            let conflicts = stats.conflicts.load(Ordering::Relaxed);
+            let latency_ms = stats.latency_ms.lock().unwrap();
+            let mut row = Row::new();
+            row.add_cell(Cell::new(rank + 1));
+            row.add_cell(Cell::new(reads));
+            row.add_cell(Cell::new(writes));
+            row.add_cell(Cell::new(
+                (100 * conflicts) as f32 / (reads + writes).max(1) as f32,
+            ));
+            row.add_cell(Cell::new(latency_ms.value_at_quantile(0.5)));
+            row.add_cell(Cell::new(latency_ms.value_at_quantile(0.99)));
+            table.add_row(row);
+        }
+        table
+    }
+
+    /// Prints the contention table periodically until all the payloads have been dropped.
+    fn spawn_reporter(stats: Weak<ContentionStats>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(REPORT_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(stats) = stats.upgrade() else {
+                    break;
+                };
+                eprintln!("Shared object contention:\n{}", stats.to_table());
+            }
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct SharedContentionTestPayload {
+    package_id: ObjectID,
+    counters: Arc<Vec<(ObjectID, SequenceNumber, ObjectDigest)>>,
+    sampler: Arc<ZipfSampler>,
+    read_ratio: u32,
+    stats: Arc<ContentionStats>,
+    // counter index, whether it was a write and submission time of the transaction in flight
+    in_flight: Option<(usize, bool, Instant)>,
+    gas: Gas,
+    system_state_observer: Arc<SystemStateObserver>,
+}
+
+impl std::fmt::Display for SharedContentionTestPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "shared_contention")
+    }
+}
+
+impl Payload for SharedContentionTestPayload {
+    fn make_new_payload(&mut self, effects: &ExecutionEffects) {
+        self.gas.0 = effects.gas_object().0;
+        if let Some((index, write, submitted)) = self.in_flight.take() {
+            self.stats
+                .record_completion(index, write, effects.is_ok(), submitted.elapsed());
+        }
+    }
+    fn make_transaction(&mut self) -> VerifiedTransaction {
+        let mut rng = rand::thread_rng();
+        let index = self.sampler.sample(&mut rng);
+        let write = rng.gen_range(0..100) >= self.read_ratio;
+        let (counter_id, counter_initial_shared_version, _) = self.counters[index];
+        let gas_price = Some(
+            self.system_state_observer
+                .state
+                .borrow()
+                .reference_gas_price,
+        );
+        self.stats.record_submission(index, write);
+        self.in_flight = Some((index, write, Instant::now()));
+        if write {
+            make_counter_increment_transaction(
+                self.gas.0,
+                self.package_id,
+                counter_id,
+                counter_initial_shared_version,
+                self.gas.1,
+                &self.gas.2,
+                gas_price,
+            )
+        } else {
+            // The expected value is a best guess, a read racing with a write may abort, which
+            // still exercises the scheduling of the immutable shared input.
+            let expected_value = self.stats.objects[index]
+                .completed_writes
+                .load(Ordering::Relaxed);
+            make_counter_read_transaction(
+                self.gas.0,
+                self.package_id,
+                counter_id,
+                counter_initial_shared_version,
+                expected_value,
+                self.gas.1,
+                &self.gas.2,
+                gas_price,
+            )
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SharedContentionWorkloadBuilder {
+    config: SharedContentionConfig,
+    num_payloads: u64,
+}
+
+impl SharedContentionWorkloadBuilder {
+    pub fn from(
+        workload_weight: f32,
+        target_qps: u64,
+        num_workers: u64,
+        in_flight_ratio: u64,
+        config: SharedContentionConfig,
+    ) -> Option<WorkloadBuilderInfo> {
+        let target_qps = (workload_weight * target_qps as f32) as u64;
+        let num_workers = (workload_weight * num_workers as f32).ceil() as u64;
+        let max_ops = target_qps * in_flight_ratio;
+        if config.num_counters == 0 || num_workers == 0 || max_ops == 0 {
+            None
+        } else {
+            let workload_params = WorkloadParams {
+                target_qps,
+                num_workers,
+                max_ops,
+            };
+            let workload_builder = Box::<dyn WorkloadBuilder<dyn Payload>>::from(Box::new(
+                SharedContentionWorkloadBuilder {
+                    config,
+                    num_payloads: max_ops,
+                },
+            ));
+            let builder_info = WorkloadBuilderInfo {
+                workload_params,
+                workload_builder,
+            };
+            Some(builder_info)
+        }
+    }
+}
+
+#[async_trait]
+impl WorkloadBuilder<dyn Payload> for SharedContentionWorkloadBuilder {
+    async fn generate_coin_config_for_init(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+
+        // Gas coin for publishing package
+        let (address, keypair) = get_key_pair();
+        configs.push(GasCoinConfig {
+            amount: MAX_GAS_FOR_TESTING,
+            address,
+            keypair: Arc::new(keypair),
+        });
+
+        // Gas coins for creating counters
+        for _i in 0..self.config.num_counters {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+    async fn generate_coin_config_for_payloads(&self) -> Vec<GasCoinConfig> {
+        let mut configs = vec![];
+        // Gas coins for running workload
+        for _i in 0..self.num_payloads {
+            let (address, keypair) = get_key_pair();
+            configs.push(GasCoinConfig {
+                amount: MAX_GAS_FOR_TESTING,
+                address,
+                keypair: Arc::new(keypair),
+            });
+        }
+        configs
+    }
+    async fn build(
+        &self,
+        init_gas: Vec<Gas>,
+        payload_gas: Vec<Gas>,
+    ) -> Box<dyn Workload<dyn Payload>> {
+        Box::<dyn Workload<dyn Payload>>::from(Box::new(SharedContentionWorkload {
+            basics_package_id: None,
+            counters: vec![],
+            init_gas,
+            payload_gas,
+            config: self.config,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct SharedContentionWorkload {
+    pub basics_package_id: Option<ObjectID>,
+    pub counters: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    pub init_gas: Vec<Gas>,
+    pub payload_gas: Vec<Gas>,
+    pub config: SharedContentionConfig,
+}
+
+#[async_trait]
+impl Workload<dyn Payload> for SharedContentionWorkload {
+    async fn init(
+        &mut self,
+        proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) {
+        if self.basics_package_id.is_some() {
+            return;
+        }
+        let gas_price = system_state_observer.state.borrow().reference_gas_price;
+        let (head, tail) = self
+            .init_gas
+            .split_first()
+            .expect("Not enough gas to initialize shared contention workload");
+
+        // Publish basics package
+        info!("Publishing basics package");
+        self.basics_package_id = Some(
+            publish_basics_package(head.0, proxy.clone(), head.1, &head.2, gas_price)
+                .await
+                .0,
+        );
+        if !self.counters.is_empty() {
+            // We already initialized the workload with some counters
+            return;
+        }
+        let mut futures = vec![];
+        for (gas, sender, keypair) in tail.iter() {
+            let transaction = make_counter_create_transaction(
+                *gas,
+                self.basics_package_id.unwrap(),
+                *sender,
+                keypair,
+                Some(gas_price),
+            );
+            let proxy_ref = proxy.clone();
+            futures.push(async move {
+                if let Ok(effects) = proxy_ref.execute_transaction(transaction.into()).await {
+                    effects.created()[0].0
+                } else {
+                    panic!("Failed to create shared counter!");
+                }
+            });
+        }
+        self.counters = join_all(futures).await;
+    }
+    async fn make_test_payloads(
+        &self,
+        _proxy: Arc<dyn ValidatorProxy + Sync + Send>,
+        system_state_observer: Arc<SystemStateObserver>,
+    ) -> Vec<Box<dyn Payload>> {
+        info!(
+            "Creating shared contention txn payloads over {} counters with skew {} and {}% reads",
+            self.counters.len(),
+            self.config.skew,
+            self.config.read_ratio
+        );
+        let counters = Arc::new(self.counters.clone());
+        let sampler = Arc::new(ZipfSampler::new(
+            counters.len(),
+            self.config.skew as f64 / 100.0,
+        ));
+        let stats = Arc::new(ContentionStats::new(counters.len()));
+        ContentionStats::spawn_reporter(Arc::downgrade(&stats));
+        let read_ratio = std::cmp::min(self.config.read_ratio, 100);
+        self.payload_gas
+            .iter()
+            .map(|g| {
+                Box::<dyn Payload>::from(Box::new(SharedContentionTestPayload {
+                    package_id: self.basics_package_id.unwrap(),
+                    counters: counters.clone(),
+                    sampler: sampler.clone(),
+                    read_ratio,
+                    stats: stats.clone(),
+                    in_flight: None,
+                    gas: g.clone(),
+                    system_state_observer: system_state_observer.clone(),
+                }))
+            })
+            .collect()
+    }
+}
@@ -6,11 +6,16 @@ use crate::options::{Opts, RunSpec};
 use crate::system_state_observer::SystemStateObserver;
 use crate::workloads::batch_payment::BatchPaymentWorkloadBuilder;
 use crate::workloads::delegation::DelegationWorkloadBuilder;
+use crate::workloads::move_fuzz::MoveFuzzWorkloadBuilder;
+use crate::workloads::shared_contention::{
+    SharedContentionConfig, SharedContentionWorkloadBuilder,
+};
 use crate::workloads::shared_counter::SharedCounterWorkloadBuilder;
 use crate::workloads::transfer_object::TransferObjectWorkloadBuilder;
 use crate::workloads::WorkloadInfo;
 use anyhow::Result;
 use std::sync::Arc;
+use sui_types::base_types::ObjectID;
 
 use super::adversarial::AdversarialWorkloadBuilder;
 
@@ -32,8 +37,14 @@ impl WorkloadConfiguration {
                 delegation,
                 batch_payment,
                 adversarial,
+                shared_contention,
+                move_fuzz,
                 batch_payment_size,
                 shared_counter_hotness_factor,
+                shared_contention_counters,
+                shared_contention_skew,
+                shared_contention_read_ratio,
+                ref move_fuzz_packages,
                 ..
             } => {
                 Self::build_workloads(
@@ -44,8 +55,16 @@ impl WorkloadConfiguration {
                     delegation,
                     batch_payment,
                     adversarial,
+                    shared_contention,
+                    move_fuzz,
                     batch_payment_size,
                     shared_counter_hotness_factor,
+                    SharedContentionConfig {
+                        num_counters: shared_contention_counters,
+                        skew: shared_contention_skew,
+                        read_ratio: shared_contention_read_ratio,
+                    },
+                    move_fuzz_packages.clone(),
                     target_qps,
                     in_flight_ratio,
                     bank,
@@ -65,8 +84,12 @@ impl WorkloadConfiguration {
         delegation_weight: u32,
         batch_payment_weight: u32,
         adversarial_weight: u32,
+        shared_contention_weight: u32,
+        move_fuzz_weight: u32,
         batch_payment_size: u32,
         shared_counter_hotness_factor: u32,
+        shared_contention_config: SharedContentionConfig,
+        move_fuzz_packages: Vec<ObjectID>,
         target_qps: u64,
         in_flight_ratio: u64,
         mut bank: BenchmarkBank,
@@ -77,7 +100,9 @@ impl WorkloadConfiguration {
             + transfer_object_weight
             + delegation_weight
             + batch_payment_weight
-            + adversarial_weight;
+            + adversarial_weight
+            + shared_contention_weight
+            + move_fuzz_weight;
         let mut workload_builders = vec![];
         let shared_workload = SharedCounterWorkloadBuilder::from(
             shared_counter_weight as f32 / total_weight as f32,
@@ -117,6 +142,22 @@ impl WorkloadConfiguration {
             in_flight_ratio,
         );
         workload_builders.push(adversarial_workload);
+        let shared_contention_workload = SharedContentionWorkloadBuilder::from(
+            shared_contention_weight as f32 / total_weight as f32,
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            shared_contention_config,
+        );
+        workload_builders.push(shared_contention_workload);
+        let move_fuzz_workload = MoveFuzzWorkloadBuilder::from(
+            move_fuzz_weight as f32 / total_weight as f32,
+            target_qps,
+            num_workers,
+            in_flight_ratio,
+            move_fuzz_packages,
+        );
+        workload_builders.push(move_fuzz_workload);
         let (workload_params, workload_builders): (Vec<_>, Vec<_>) = workload_builders
             .into_iter()
             .flatten()
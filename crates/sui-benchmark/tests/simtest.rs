@@ -10,6 +10,7 @@ mod test {
     use std::time::{Duration, Instant};
     use sui_benchmark::bank::BenchmarkBank;
     use sui_benchmark::system_state_observer::SystemStateObserver;
+    use sui_benchmark::workloads::shared_contention::SharedContentionConfig;
     use sui_benchmark::workloads::workload_configuration::WorkloadConfiguration;
     use sui_benchmark::{
         drivers::{bench_driver::BenchDriver, driver::Driver, Interval},
@@ -20,6 +21,7 @@ mod test {
     use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
     use sui_core::checkpoints::CheckpointStore;
     use sui_macros::{register_fail_point_async, register_fail_points, sim_test};
+    use sui_simulator::scenario::{NetworkScenario, Trigger};
     use sui_simulator::{configs::*, SimConfig};
     use sui_types::messages_checkpoint::VerifiedCheckpoint;
     use test_utils::messages::get_sui_gas_object_with_wallet_context;
@@ -96,6 +98,40 @@ mod test {
         test_simulated_load(test_cluster, 120).await;
     }
 
+    #[sim_test(config = "test_config()")]
+    async fn test_simulated_load_network_faults() {
+        sui_protocol_config::ProtocolConfig::poison_get_for_min_version();
+        let test_cluster = build_test_cluster(4, 0).await;
+        let validators: Vec<_> = test_cluster
+            .swarm
+            .validators()
+            .map(|v| v.sim_node_id().unwrap())
+            .collect();
+        NetworkScenario::new()
+            .isolate(
+                Trigger::at_secs(10),
+                Trigger::at_secs(25),
+                validators[0],
+                &validators[1..],
+            )
+            .packet_loss(
+                Trigger::at_secs(5),
+                Trigger::at_secs(40),
+                validators[1],
+                validators[2],
+                0.3,
+            )
+            .latency(
+                Trigger::at_secs(20),
+                Trigger::at_secs(50),
+                validators[2],
+                validators[3],
+                Duration::from_millis(200),
+            )
+            .run();
+        test_simulated_load(test_cluster, 60).await;
+    }
+
     fn handle_failpoint(
         dead_validator: Arc<Mutex<Option<DeadValidator>>>,
         client_node: sui_simulator::task::NodeId,
@@ -270,6 +306,15 @@ mod test {
 
         let shared_counter_hotness_factor = 50;
 
+        let shared_contention_weight = 1;
+        let shared_contention_config = SharedContentionConfig {
+            num_counters: 5,
+            skew: 100,
+            read_ratio: 20,
+        };
+
+        let move_fuzz_weight = 1;
+
         let workloads = WorkloadConfiguration::build_workloads(
             num_workers,
             num_transfer_accounts,
@@ -278,8 +323,12 @@ mod test {
             delegation_weight,
             batch_payment_weight,
             adversarial_weight,
+            shared_contention_weight,
+            move_fuzz_weight,
             batch_payment_size,
             shared_counter_hotness_factor,
+            shared_contention_config,
+            vec![],
             target_qps,
             in_flight_ratio,
             bank,
@@ -469,12 +469,15 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     db_path,
                     network_address,
                     metrics_address: validator.genesis_info.metrics_address,
+                    metrics_auth: None,
                     // TODO: admin server is hard coded to start on 127.0.0.1 - we should probably
                     // provide the entire socket address here to avoid confusion.
                     admin_interface_port: match self.validator_ip_sel {
                         ValidatorIpSelection::Simulator => 8888,
                         _ => utils::get_available_port("127.0.0.1"),
                     },
+                    admin_interface_auth_token: None,
+                    admin_interface_auth: None,
                     json_rpc_address: utils::available_local_socket_address(),
                     consensus_config: Some(consensus_config),
                     enable_event_processing: false,
@@ -490,6 +493,23 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
                     supported_protocol_versions: Some(supported_protocol_versions),
                     db_checkpoint_config: self.db_checkpoint_config.clone(),
                     indirect_objects_threshold: usize::MAX,
+                    transaction_deny_config: Default::default(),
+                    memory_budget_config: None,
+                    quorum_driver_config: None,
+                    execution_cache_config: None,
+                    system_transaction_trace_config: None,
+                    local_execution_time_limit_config: None,
+                    slow_op_config: None,
+                    read_only_fullnode_config: None,
+                    archive_read_fallback_config: None,
+                    transaction_scan_config: None,
+                    checkpoint_stream_config: None,
+                    rest_server_address: None,
+                    name_service_config: None,
+                    gas_price_oracle_config: None,
+                    db_tuning: Default::default(),
+                    health_check_config: None,
+                    logging: None,
                 }
             })
             .collect();
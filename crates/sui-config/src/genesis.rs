@@ -338,7 +338,7 @@ pub struct GenesisValidatorInfo {
 }
 
 impl GenesisValidatorInfo {
-    fn validate(&self) -> Result<(), anyhow::Error> {
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
         if !self.info.name.is_ascii() {
             bail!("name must be ascii");
         }
@@ -670,6 +670,52 @@ impl Builder {
         self
     }
 
+    /// Adds a signature over the unsigned genesis checkpoint made from another copy of the
+    /// ceremony, once verified.
+    pub fn add_validator_signature_info(
+        mut self,
+        signature: AuthoritySignInfo,
+    ) -> Result<Self, anyhow::Error> {
+        self.verify_validator_signature(&signature)?;
+        self.signatures.insert(signature.authority, signature);
+        Ok(self)
+    }
+
+    /// Verifies that `signature` is a signature over the unsigned genesis checkpoint by one of the
+    /// validators.
+    pub fn verify_validator_signature(
+        &self,
+        signature: &AuthoritySignInfo,
+    ) -> Result<(), anyhow::Error> {
+        let Some(unsigned_genesis) = &self.built_genesis else {
+            bail!("the unsigned genesis checkpoint hasn't been built yet");
+        };
+        if !self.validators.contains_key(&signature.authority) {
+            bail!("signature from unknown validator {}", signature.authority);
+        }
+        let committee = Self::committee(&unsigned_genesis.objects);
+        signature
+            .verify_secure(
+                unsigned_genesis.checkpoint(),
+                Intent::default().with_scope(IntentScope::CheckpointSummary),
+                &committee,
+            )
+            .with_context(|| {
+                format!(
+                    "invalid signature over the genesis checkpoint from {}",
+                    signature.authority
+                )
+            })
+    }
+
+    pub fn validators(&self) -> &BTreeMap<AuthorityPublicKeyBytes, GenesisValidatorInfo> {
+        &self.validators
+    }
+
+    pub fn signatures(&self) -> &BTreeMap<AuthorityPublicKeyBytes, AuthoritySignInfo> {
+        &self.signatures
+    }
+
     pub fn unsigned_genesis_checkpoint(&self) -> Option<UnsignedGenesis> {
         self.built_genesis.clone()
     }
@@ -3,8 +3,11 @@
 
 use crate::genesis;
 use crate::p2p::P2pConfig;
+use crate::transaction_deny_config::TransactionDenyConfig;
 use crate::Config;
 use anyhow::Result;
+use mysten_metrics::overrides::MetricsOverrides;
+use mysten_metrics::remote_write::RelabelConfig;
 use narwhal_config::Parameters as ConsensusParameters;
 use once_cell::sync::OnceCell;
 use rand::rngs::OsRng;
@@ -18,7 +21,7 @@ use std::usize;
 use sui_keys::keypair_file::{read_authority_keypair_from_file, read_keypair_from_file};
 use sui_protocol_config::SupportedProtocolVersions;
 use sui_storage::object_store::ObjectStoreConfig;
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::crypto::AuthorityPublicKeyBytes;
 use sui_types::crypto::KeypairTraits;
 use sui_types::crypto::NetworkKeyPair;
@@ -26,6 +29,7 @@ use sui_types::crypto::NetworkPublicKey;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::{get_key_pair_from_rng, AccountKeyPair, AuthorityKeyPair};
 use sui_types::multiaddr::Multiaddr;
+use typed_store::rocks::DBTableTuning;
 
 // Default max number of concurrent requests served
 pub const DEFAULT_GRPC_CONCURRENCY_LIMIT: usize = 20000000000;
@@ -51,8 +55,19 @@ pub struct NodeConfig {
 
     #[serde(default = "default_metrics_address")]
     pub metrics_address: SocketAddr,
+    /// TLS and authentication of the metrics endpoint. Served over plain HTTP to anyone when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_auth: Option<EndpointAuthConfig>,
     #[serde(default = "default_admin_interface_port")]
     pub admin_interface_port: u16,
+    /// Bearer token required by the admin interface endpoints that change which transactions
+    /// this node accepts. Those endpoints are disabled when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_interface_auth_token: Option<String>,
+    /// TLS and authentication of the whole admin interface. Served over plain HTTP to anyone
+    /// that can reach it when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_interface_auth: Option<EndpointAuthConfig>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub consensus_config: Option<ConsensusConfig>,
@@ -97,6 +112,91 @@ pub struct NodeConfig {
 
     #[serde(default)]
     pub indirect_objects_threshold: usize,
+
+    /// Transactions this node refuses to sign or submit. Overrides applied through the admin
+    /// interface are persisted under `db_path` and take precedence over this value.
+    #[serde(default, skip_serializing_if = "TransactionDenyConfig::is_empty")]
+    pub transaction_deny_config: TransactionDenyConfig,
+
+    /// Limit on the memory used by the node's major caches and queues. Unlimited if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_budget_config: Option<MemoryBudgetConfig>,
+
+    /// Retry and failover policy of the quorum driver submitting transactions on behalf of the
+    /// transaction orchestrator. Uses the defaults if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum_driver_config: Option<QuorumDriverConfig>,
+
+    /// In-memory cache of objects and transaction effects in front of the authority store.
+    /// Every read goes to the database if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_cache_config: Option<ExecutionCacheConfig>,
+
+    /// Logs the arguments and state changes of every system transaction executed, such as
+    /// consensus commit prologues and epoch changes. Not logged if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_transaction_trace_config: Option<SystemTransactionTraceConfig>,
+
+    /// Wall-clock limits on the dry runs and dev-inspect calls executed by a fullnode to answer
+    /// RPC requests. Uses the defaults if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_execution_time_limit_config: Option<LocalExecutionTimeLimitConfig>,
+
+    /// Thresholds above which RPC calls, certificate executions and consensus commits are logged
+    /// as slow, and kept for the admin interface. Uses the defaults if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_op_config: Option<SlowOpConfig>,
+
+    /// Runs the fullnode in read-only mode if set: the transactions of synced checkpoints are not
+    /// executed, their outputs are fetched from a peer that executed them instead. Read-only nodes
+    /// serve read RPC only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_fullnode_config: Option<ReadOnlyFullnodeConfig>,
+
+    /// Archive from which the read APIs serve the transactions and past object versions that this
+    /// node has pruned, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_read_fallback_config: Option<ArchiveReadFallbackConfig>,
+
+    /// If set, queries of the transactions sent by or to an address are answered by scanning the
+    /// most recent checkpoints, which lets fullnodes serve them without maintaining indexes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_scan_config: Option<TransactionScanConfig>,
+
+    /// Serves a gRPC stream of full checkpoint data, for indexers and other clients following the
+    /// chain, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_stream_config: Option<CheckpointStreamConfig>,
+
+    /// Address of the REST server serving the BCS encoding of checkpoints, transactions, effects
+    /// and objects, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rest_server_address: Option<SocketAddr>,
+
+    /// On-chain name service whose names the read API resolves, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_service_config: Option<NameServiceConfig>,
+
+    /// Estimates gas prices from the transactions of the most recent checkpoints, for the read
+    /// API to recommend, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_price_oracle_config: Option<GasPriceOracleConfig>,
+
+    /// RocksDB options of tables of the authority store overriding their defaults, by table name,
+    /// e.g. to give the `objects` table a larger block cache.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub db_tuning: BTreeMap<String, DBTableTuning>,
+
+    /// Serves `/health/live` and `/health/ready` endpoints for load balancers and orchestrators
+    /// to probe, if set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_check_config: Option<HealthCheckConfig>,
+
+    /// Format, levels and sampling of the log output, if set. It takes the place of `RUST_LOG`,
+    /// which still takes precedence when set, and is reloaded from the config file through the
+    /// admin interface.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingConfig>,
 }
 
 fn default_authority_store_pruning_config() -> AuthorityStorePruningConfig {
@@ -196,6 +296,10 @@ impl NodeConfig {
         self.db_path.join("db_checkpoints")
     }
 
+    pub fn transaction_deny_config_override_path(&self) -> PathBuf {
+        self.db_path.join("transaction_deny_config_override.yaml")
+    }
+
     pub fn network_address(&self) -> &Multiaddr {
         &self.network_address
     }
@@ -290,6 +394,45 @@ pub struct AuthorityStorePruningConfig {
     pub max_checkpoints_in_batch: usize,
     pub max_transactions_in_batch: usize,
     pub use_range_deletion: bool,
+    /// Leave old object versions to a compaction filter of the objects table, dropping them as
+    /// they are compacted, instead of deleting them.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub use_compaction_filter: bool,
+    /// How long checkpoint contents are kept. Kept forever when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_contents_retention: Option<RetentionPolicy>,
+    /// How long transaction effects are kept. Kept forever when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effects_retention: Option<RetentionPolicy>,
+    /// How long transaction events are kept. Kept forever when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events_retention: Option<RetentionPolicy>,
+    /// How long transaction data is kept. Kept forever when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transactions_retention: Option<RetentionPolicy>,
+    /// How long the transaction and event indexes of a transaction are kept, on nodes maintaining
+    /// indexes. Kept forever when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indexes_retention: Option<RetentionPolicy>,
+    /// Never prune checkpoint contents, transactions or effects that state sync peers lagging
+    /// behind this node may still need to fetch from it.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub catch_up_safe: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Retention of a kind of history, counted back from the highest executed checkpoint. History
+/// retention is only applied on fullnodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RetentionPolicy {
+    /// Keep the history of the given number of most recent epochs, the current one included.
+    Epochs(u64),
+    /// Keep the history of checkpoints created within the given number of days.
+    Days(u64),
 }
 
 impl Default for AuthorityStorePruningConfig {
@@ -301,6 +444,13 @@ impl Default for AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            use_compaction_filter: false,
+            checkpoint_contents_retention: None,
+            effects_retention: None,
+            events_retention: None,
+            transactions_retention: None,
+            indexes_retention: None,
+            catch_up_safe: false,
         }
     }
 }
@@ -314,6 +464,13 @@ impl AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            use_compaction_filter: false,
+            checkpoint_contents_retention: None,
+            effects_retention: None,
+            events_retention: None,
+            transactions_retention: None,
+            indexes_retention: None,
+            catch_up_safe: false,
         }
     }
     pub fn fullnode_config() -> Self {
@@ -324,10 +481,519 @@ impl AuthorityStorePruningConfig {
             max_checkpoints_in_batch: 200,
             max_transactions_in_batch: 1000,
             use_range_deletion: true,
+            use_compaction_filter: false,
+            checkpoint_contents_retention: None,
+            effects_retention: None,
+            events_retention: None,
+            transactions_retention: None,
+            indexes_retention: None,
+            catch_up_safe: false,
+        }
+    }
+
+    /// Whether any checkpoint history is pruned, in addition to old object versions.
+    pub fn prunes_history(&self) -> bool {
+        self.checkpoint_contents_retention.is_some()
+            || self.effects_retention.is_some()
+            || self.events_retention.is_some()
+            || self.transactions_retention.is_some()
+            || self.indexes_retention.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MemoryBudgetConfig {
+    /// Memory the components accounted for may use in total, in bytes.
+    pub limit_bytes: usize,
+    /// Usage, in percent of the limit, above which components are asked to shrink.
+    #[serde(default = "default_shrink_threshold_pct")]
+    pub shrink_threshold_pct: u8,
+    /// Usage, in percent of the limit, that components are shrunk down to.
+    #[serde(default = "default_shrink_target_pct")]
+    pub shrink_target_pct: u8,
+    #[serde(default = "default_memory_budget_check_interval_ms")]
+    pub check_interval_ms: u64,
+}
+
+fn default_shrink_threshold_pct() -> u8 {
+    90
+}
+
+fn default_shrink_target_pct() -> u8 {
+    75
+}
+
+fn default_memory_budget_check_interval_ms() -> u64 {
+    1000
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HealthCheckConfig {
+    /// Address the health endpoints are served on.
+    pub address: SocketAddr,
+    /// Number of checkpoints the highest executed checkpoint may lag behind the highest synced
+    /// checkpoint for the node to be ready.
+    #[serde(default = "default_max_checkpoint_lag")]
+    pub max_checkpoint_lag: u64,
+    /// Age, in seconds, of the highest executed checkpoint above which the node is not ready,
+    /// e.g. because it is not syncing from its peers anymore.
+    #[serde(default = "default_max_checkpoint_age_secs")]
+    pub max_checkpoint_age_secs: u64,
+    /// Time, in seconds, without progress of the round committed by consensus above which a
+    /// validator is not ready.
+    #[serde(default = "default_max_consensus_stall_secs")]
+    pub max_consensus_stall_secs: u64,
+}
+
+fn default_max_checkpoint_lag() -> u64 {
+    100
+}
+
+fn default_max_checkpoint_age_secs() -> u64 {
+    60
+}
+
+fn default_max_consensus_stall_secs() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SystemTransactionTraceConfig {
+    /// Logs every object written, deleted or wrapped by a system transaction, in addition to
+    /// its arguments and decoded system state changes.
+    #[serde(default = "bool_true")]
+    pub object_changes: bool,
+    /// Also traces the genesis transaction, which writes every genesis object.
+    #[serde(default)]
+    pub genesis: bool,
+}
+
+impl Default for SystemTransactionTraceConfig {
+    fn default() -> Self {
+        Self {
+            object_changes: true,
+            genesis: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExecutionCacheConfig {
+    /// Memory the cached objects may use, in bytes.
+    #[serde(default = "default_object_cache_max_bytes")]
+    pub object_cache_max_bytes: usize,
+    /// Memory the cached transaction effects may use, in bytes.
+    #[serde(default = "default_effects_cache_max_bytes")]
+    pub effects_cache_max_bytes: usize,
+    /// Time after which a cached object is read from the database again.
+    #[serde(default = "default_object_cache_ttl_secs")]
+    pub object_ttl_secs: u64,
+    /// Time after which cached transaction effects are read from the database again.
+    #[serde(default = "default_effects_cache_ttl_secs")]
+    pub effects_ttl_secs: u64,
+    /// Objects and effects written by execution are staged, and added to the cache once this
+    /// many transactions are staged, so that execution takes the cache locks once per batch.
+    #[serde(default = "default_cache_write_back_batch_size")]
+    pub write_back_batch_size: usize,
+}
+
+fn default_object_cache_max_bytes() -> usize {
+    512 << 20
+}
+
+fn default_effects_cache_max_bytes() -> usize {
+    128 << 20
+}
+
+fn default_object_cache_ttl_secs() -> u64 {
+    600
+}
+
+fn default_effects_cache_ttl_secs() -> u64 {
+    600
+}
+
+fn default_cache_write_back_batch_size() -> usize {
+    64
+}
+
+impl Default for ExecutionCacheConfig {
+    fn default() -> Self {
+        Self {
+            object_cache_max_bytes: default_object_cache_max_bytes(),
+            effects_cache_max_bytes: default_effects_cache_max_bytes(),
+            object_ttl_secs: default_object_cache_ttl_secs(),
+            effects_ttl_secs: default_effects_cache_ttl_secs(),
+            write_back_batch_size: default_cache_write_back_batch_size(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LocalExecutionTimeLimitConfig {
+    /// Time a dry run may take, in milliseconds, including the time spent waiting for an
+    /// execution slot.
+    #[serde(default = "default_local_execution_time_limit_ms")]
+    pub dry_run_time_limit_ms: u64,
+    /// Time a dev-inspect call may take, in milliseconds, including the time spent waiting for an
+    /// execution slot.
+    #[serde(default = "default_local_execution_time_limit_ms")]
+    pub dev_inspect_time_limit_ms: u64,
+    /// Executions that may run at once. The Move VM cannot be interrupted, so an execution past
+    /// its time limit keeps its slot until it runs out of gas.
+    #[serde(default = "default_max_concurrent_local_executions")]
+    pub max_concurrent_executions: usize,
+}
+
+fn default_local_execution_time_limit_ms() -> u64 {
+    5_000
+}
+
+fn default_max_concurrent_local_executions() -> usize {
+    64
+}
+
+impl Default for LocalExecutionTimeLimitConfig {
+    fn default() -> Self {
+        Self {
+            dry_run_time_limit_ms: default_local_execution_time_limit_ms(),
+            dev_inspect_time_limit_ms: default_local_execution_time_limit_ms(),
+            max_concurrent_executions: default_max_concurrent_local_executions(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SlowOpConfig {
+    #[serde(default = "default_rpc_slow_op_threshold_ms")]
+    pub rpc_threshold_ms: u64,
+    #[serde(default = "default_execution_slow_op_threshold_ms")]
+    pub execution_threshold_ms: u64,
+    #[serde(default = "default_consensus_commit_slow_op_threshold_ms")]
+    pub consensus_commit_threshold_ms: u64,
+    /// Number of the most recent slow operations kept for the admin interface.
+    #[serde(default = "default_slow_op_capacity")]
+    pub capacity: usize,
+}
+
+fn default_rpc_slow_op_threshold_ms() -> u64 {
+    2_000
+}
+
+fn default_execution_slow_op_threshold_ms() -> u64 {
+    500
+}
+
+fn default_consensus_commit_slow_op_threshold_ms() -> u64 {
+    1_000
+}
+
+fn default_slow_op_capacity() -> usize {
+    1_000
+}
+
+impl Default for SlowOpConfig {
+    fn default() -> Self {
+        Self {
+            rpc_threshold_ms: default_rpc_slow_op_threshold_ms(),
+            execution_threshold_ms: default_execution_slow_op_threshold_ms(),
+            consensus_commit_threshold_ms: default_consensus_commit_slow_op_threshold_ms(),
+            capacity: default_slow_op_capacity(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ArchiveReadFallbackConfig {
+    pub object_store_config: ObjectStoreConfig,
+    /// Number of transactions, and of object versions, read from the archive that are kept in
+    /// memory once verified. Reads are not cached if zero.
+    #[serde(default = "default_archive_read_cache_capacity")]
+    pub cache_capacity: usize,
+}
+
+fn default_archive_read_cache_capacity() -> usize {
+    10_000
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TransactionScanConfig {
+    /// Number of most recent checkpoints scanned by a query.
+    #[serde(default = "default_transaction_scan_max_checkpoints")]
+    pub max_checkpoints: u64,
+    /// Whether to stop maintaining the indexes of the node. Queries relying on them, e.g. of the
+    /// objects owned by an address, are then not supported.
+    #[serde(default)]
+    pub disable_indexes: bool,
+}
+
+fn default_transaction_scan_max_checkpoints() -> u64 {
+    1_000
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NameServiceConfig {
+    /// Package defining the types of the name service.
+    pub package_address: SuiAddress,
+    /// Table mapping domains to their records.
+    pub registry_id: ObjectID,
+    /// Table mapping addresses to their primary domain.
+    pub reverse_registry_id: ObjectID,
+    /// Number of resolutions, and of reverse resolutions, kept in memory. Resolutions are not
+    /// cached if zero.
+    #[serde(default = "default_name_service_cache_capacity")]
+    pub cache_capacity: usize,
+    /// How long a cached resolution is served for before being read again.
+    #[serde(default = "default_name_service_cache_ttl_ms")]
+    pub cache_ttl_ms: u64,
+}
+
+fn default_name_service_cache_capacity() -> usize {
+    10_000
+}
+
+fn default_name_service_cache_ttl_ms() -> u64 {
+    60_000
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GasPriceOracleConfig {
+    /// Number of most recent checkpoints whose transactions the estimates are computed from.
+    #[serde(default = "default_gas_price_oracle_checkpoints")]
+    pub checkpoints: u64,
+    /// How often newly executed checkpoints are added to the estimates.
+    #[serde(default = "default_gas_price_oracle_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+fn default_gas_price_oracle_checkpoints() -> u64 {
+    100
+}
+
+fn default_gas_price_oracle_poll_interval_ms() -> u64 {
+    1_000
+}
+
+impl Default for GasPriceOracleConfig {
+    fn default() -> Self {
+        Self {
+            checkpoints: default_gas_price_oracle_checkpoints(),
+            poll_interval_ms: default_gas_price_oracle_poll_interval_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ReadOnlyFullnodeConfig {
+    /// Peer to fetch transaction outputs from first, typically the executing fullnode feeding the
+    /// read replicas. The other connected peers are asked, in random order, when it fails or
+    /// doesn't have the outputs yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream_peer_id: Option<anemo::PeerId>,
+    /// Timeout of a single request for the outputs of a transaction.
+    #[serde(default = "default_transaction_outputs_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+    /// Delay before requesting outputs again, after a failed request or from a peer that has not
+    /// executed the transaction yet.
+    #[serde(default = "default_transaction_outputs_retry_interval_ms")]
+    pub retry_interval_ms: u64,
+}
+
+fn default_transaction_outputs_request_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_transaction_outputs_retry_interval_ms() -> u64 {
+    500
+}
+
+impl Default for ReadOnlyFullnodeConfig {
+    fn default() -> Self {
+        Self {
+            upstream_peer_id: None,
+            request_timeout_ms: default_transaction_outputs_request_timeout_ms(),
+            retry_interval_ms: default_transaction_outputs_retry_interval_ms(),
         }
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CheckpointStreamConfig {
+    /// Address the checkpoint stream server listens on.
+    pub address: Multiaddr,
+    /// Maximum number of checkpoints read ahead of a client, regardless of what it requests.
+    #[serde(default = "default_checkpoint_stream_max_buffered_checkpoints")]
+    pub max_buffered_checkpoints: u32,
+    /// Delay before checking again for newly executed checkpoints, once a client has caught up.
+    #[serde(default = "default_checkpoint_stream_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+fn default_checkpoint_stream_max_buffered_checkpoints() -> u32 {
+    16
+}
+
+fn default_checkpoint_stream_poll_interval_ms() -> u64 {
+    100
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct QuorumDriverConfig {
+    /// Number of times a transaction is retried after transient failures before giving up.
+    #[serde(default = "default_quorum_driver_max_retry_times")]
+    pub max_retry_times: u8,
+    /// Delay before the first retry, doubled on every subsequent retry.
+    #[serde(default = "default_quorum_driver_retry_initial_delay_ms")]
+    pub retry_initial_delay_ms: u64,
+    /// Timeout of a single request to a validator. A validator that does not answer in time is
+    /// counted as failed, and the driver makes progress with the others.
+    #[serde(default = "default_quorum_driver_validator_request_timeout_ms")]
+    pub validator_request_timeout_ms: u64,
+    /// Time since a transaction was first submitted during which it keeps being retried, even
+    /// once `max_retry_times` is exhausted. Giving up earlier invites the client to sign another
+    /// transaction over the same objects while the first one may still be certified, which
+    /// equivocates and locks the objects until the end of the epoch.
+    #[serde(default)]
+    pub equivocation_protection_window_ms: u64,
+    /// Order in which validators are contacted.
+    #[serde(default)]
+    pub validator_sampling: ValidatorSamplingStrategy,
+}
+
+fn default_quorum_driver_max_retry_times() -> u8 {
+    10
+}
+
+fn default_quorum_driver_retry_initial_delay_ms() -> u64 {
+    200
+}
+
+fn default_quorum_driver_validator_request_timeout_ms() -> u64 {
+    60_000
+}
+
+impl Default for QuorumDriverConfig {
+    fn default() -> Self {
+        Self {
+            max_retry_times: default_quorum_driver_max_retry_times(),
+            retry_initial_delay_ms: default_quorum_driver_retry_initial_delay_ms(),
+            validator_request_timeout_ms: default_quorum_driver_validator_request_timeout_ms(),
+            equivocation_protection_window_ms: 0,
+            validator_sampling: ValidatorSamplingStrategy::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ValidatorSamplingStrategy {
+    /// Validators are contacted in a random order weighted by stake, so that a quorum is likely
+    /// to be reached with the first validators contacted.
+    #[default]
+    StakeWeighted,
+    /// Validators are contacted in a uniformly random order, spreading load evenly regardless
+    /// of stake.
+    Uniform,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EndpointAuthConfig {
+    /// Serves the endpoint over TLS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<EndpointTlsConfig>,
+    /// Bearer token required on every request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EndpointTlsConfig {
+    /// PEM file with the certificate chain of the endpoint.
+    pub certificate_path: PathBuf,
+    /// PEM file with the private key of the endpoint.
+    pub private_key_path: PathBuf,
+    /// PEM file with the CA certificates that client certificates must be signed by. Clients
+    /// must present a certificate when set (mTLS), and are not authenticated by TLS otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ca_certificate_path: Option<PathBuf>,
+    /// How often the files are checked for changes, so that rotated certificates are served
+    /// without a restart.
+    #[serde(default = "default_tls_reload_interval_secs")]
+    pub reload_interval_secs: u64,
+}
+
+fn default_tls_reload_interval_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LoggingConfig {
+    #[serde(default)]
+    pub format: LogFormat,
+    /// Level of the components without a level of their own.
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// Levels by component, i.e. by module path prefix, e.g. `sui_core::authority: debug`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, String>,
+    /// Fraction of the log events of noisy components to keep, after their level is applied.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sampling: Vec<LogSamplingConfig>,
+}
+
+impl LoggingConfig {
+    /// The levels as a filter in the `RUST_LOG` syntax.
+    pub fn filter_directives(&self) -> String {
+        std::iter::once(self.level.clone())
+            .chain(
+                self.components
+                    .iter()
+                    .map(|(component, level)| format!("{component}={level}")),
+            )
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+    /// Human readable lines, with ANSI colors on a terminal.
+    #[default]
+    Text,
+    /// Newline-delimited JSON objects, for log pipelines. The format cannot be reloaded.
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LogSamplingConfig {
+    /// Component the rule applies to, i.e. module path prefix.
+    pub component: String,
+    /// Fraction of the events to keep, between 0 and 1.
+    pub rate: f64,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct MetricsConfig {
@@ -335,6 +1001,45 @@ pub struct MetricsConfig {
     pub push_interval_seconds: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub push_url: Option<String>,
+    /// Pushes the metrics to a Prometheus remote-write endpoint, for nodes that cannot expose
+    /// their metrics address to be scraped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_write: Option<RemoteWriteConfig>,
+    /// Histogram buckets and metrics to export, for the metrics supporting overrides, e.g. the
+    /// consensus executor ones. Applied at startup.
+    #[serde(default, flatten)]
+    pub overrides: MetricsOverrides,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RemoteWriteConfig {
+    pub url: String,
+    #[serde(default = "default_remote_write_interval_secs")]
+    pub push_interval_seconds: u64,
+    /// Client certificate presented to the endpoint (mTLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<RemoteWriteTlsConfig>,
+    /// Applied in order to every pushed series, with the semantics of Prometheus'
+    /// `write_relabel_configs`. The metric name is the `__name__` label.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relabel_configs: Vec<RelabelConfig>,
+}
+
+fn default_remote_write_interval_secs() -> u64 {
+    60
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RemoteWriteTlsConfig {
+    /// PEM file with the client certificate chain.
+    pub certificate_path: PathBuf,
+    /// PEM file with the private key of the client certificate.
+    pub private_key_path: PathBuf,
+    /// PEM file with CA certificates to trust for the endpoint, in addition to the system ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_certificate_path: Option<PathBuf>,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -346,6 +1051,10 @@ pub struct DBCheckpointConfig {
     pub checkpoint_path: Option<PathBuf>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_store_config: Option<ObjectStoreConfig>,
+    /// Object store db checkpoints are restored from when the node starts without a db. The
+    /// latest fully uploaded db checkpoint is downloaded, and state sync catches up from there.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restore_object_store_config: Option<ObjectStoreConfig>,
 }
 
 /// Publicly known information about a validator
@@ -676,6 +1385,38 @@ mod tests {
         let _template: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
 
+    #[test]
+    fn logging_config() {
+        let config: super::LoggingConfig = serde_yaml::from_str(
+            "format: json\n\
+            components:\n  sui_core::authority: debug\n  narwhal_primary: warn\n\
+            sampling:\n  - component: sui_network\n    rate: 0.1\n",
+        )
+        .unwrap();
+        assert_eq!(config.format, super::LogFormat::Json);
+        assert_eq!(
+            config.filter_directives(),
+            "info,narwhal_primary=warn,sui_core::authority=debug"
+        );
+        assert_eq!(config.sampling[0].component, "sui_network");
+    }
+
+    #[test]
+    fn metrics_overrides_config() {
+        let config: super::MetricsConfig = serde_yaml::from_str(
+            "push-url: http://localhost:9184\n\
+            histogram-buckets:\n  batch_execution_latency: [0.1, 1.0, 10.0]\n\
+            disabled-metrics: [primary_peer_rtt_seconds]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.overrides.buckets("batch_execution_latency", &[]),
+            vec![0.1, 1.0, 10.0]
+        );
+        assert!(!config.overrides.is_enabled("primary_peer_rtt_seconds"));
+        assert!(config.overrides.is_enabled("batch_execution_latency"));
+    }
+
     #[test]
     fn load_key_pairs_to_node_config() {
         let protocol_key_pair: AuthorityKeyPair =
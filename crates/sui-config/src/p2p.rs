@@ -4,6 +4,7 @@
 use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
 
 use serde::{Deserialize, Serialize};
+use sui_storage::object_store::ObjectStoreConfig;
 use sui_types::multiaddr::Multiaddr;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -131,6 +132,19 @@ pub struct StateSyncConfig {
     /// If unspecified, this will default to no limit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub get_checkpoint_contents_rate_limit: Option<NonZeroU32>,
+
+    /// Per-peer rate-limit (in requests/sec) for the GetTransactionOutputs RPC.
+    ///
+    /// If unspecified, this will default to no limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get_transaction_outputs_rate_limit: Option<NonZeroU32>,
+
+    /// Object store holding an archive of checkpoints, from which the checkpoints that no peer
+    /// can provide (e.g. because they have been pruned) are synced.
+    ///
+    /// If unspecified, checkpoints are only synced from peers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_reader_config: Option<ObjectStoreConfig>,
 }
 
 impl StateSyncConfig {
@@ -0,0 +1,49 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+/// Transactions that this node refuses to sign (validators) or to submit (fullnodes).
+///
+/// This is a local policy: it is only applied to transactions before they are certified, and never
+/// to the execution of certificates, which must stay identical across the network.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TransactionDenyConfig {
+    /// Transactions taking any of these objects as input are rejected.
+    #[serde(default)]
+    pub object_deny_list: BTreeSet<ObjectID>,
+
+    /// Transactions calling, publishing against, or upgrading any of these packages are rejected.
+    #[serde(default)]
+    pub package_deny_list: BTreeSet<ObjectID>,
+
+    /// Transactions sent or sponsored by any of these addresses are rejected.
+    #[serde(default)]
+    pub address_deny_list: BTreeSet<SuiAddress>,
+}
+
+impl Config for TransactionDenyConfig {}
+
+impl TransactionDenyConfig {
+    pub fn is_empty(&self) -> bool {
+        self.object_deny_list.is_empty()
+            && self.package_deny_list.is_empty()
+            && self.address_deny_list.is_empty()
+    }
+
+    pub fn is_object_denied(&self, object_id: &ObjectID) -> bool {
+        self.object_deny_list.contains(object_id)
+    }
+
+    pub fn is_package_denied(&self, package_id: &ObjectID) -> bool {
+        self.package_deny_list.contains(package_id)
+    }
+
+    pub fn is_address_denied(&self, address: &SuiAddress) -> bool {
+        self.address_deny_list.contains(address)
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reads of transactions and past object versions that this node has pruned, served from an
+//! archive instead.
+//!
+//! Nothing read from the archive is trusted. A transaction is served only if it is included in a
+//! checkpoint certified by the committee of its epoch, and an object version only if it is among
+//! the objects written by the effects of its previous transaction.
+
+use crate::epoch::committee_store::CommitteeStore;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use sui_storage::archive::ArchiveReader;
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages::{TransactionEffects, TransactionEffectsAPI, VerifiedTransaction};
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointTimestamp};
+use sui_types::object::Object;
+use tracing::debug;
+
+/// A transaction read from the archive, and the checkpoint that includes it.
+#[derive(Clone, Debug)]
+pub struct ArchivedTransaction {
+    pub transaction: VerifiedTransaction,
+    pub effects: TransactionEffects,
+    pub checkpoint: CheckpointSequenceNumber,
+    pub timestamp_ms: CheckpointTimestamp,
+}
+
+pub struct ArchiveFallback {
+    reader: ArchiveReader,
+    committee_store: Arc<CommitteeStore>,
+    /// Caches of verified reads, `None` if caching is disabled.
+    transactions: Option<Mutex<LruCache<TransactionDigest, ArchivedTransaction>>>,
+    objects: Option<Mutex<LruCache<(ObjectID, SequenceNumber), Object>>>,
+}
+
+fn invalid(error: String) -> SuiError {
+    SuiError::InvalidArchiveData { error }
+}
+
+fn read_error(e: anyhow::Error) -> SuiError {
+    SuiError::GenericStorageError(format!("Failed to read from archive: {e}"))
+}
+
+impl ArchiveFallback {
+    /// Reads from the archive of `reader`, caching up to `cache_capacity` transactions and as many
+    /// objects once verified.
+    pub fn new(
+        reader: ArchiveReader,
+        committee_store: Arc<CommitteeStore>,
+        cache_capacity: usize,
+    ) -> Self {
+        let cache_capacity = NonZeroUsize::new(cache_capacity);
+        Self {
+            reader,
+            committee_store,
+            transactions: cache_capacity.map(|capacity| Mutex::new(LruCache::new(capacity))),
+            objects: cache_capacity.map(|capacity| Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    /// Returns the transaction and its effects, or `None` if the transaction is not in the
+    /// archive.
+    pub async fn get_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> SuiResult<Option<ArchivedTransaction>> {
+        if let Some(transactions) = &self.transactions {
+            if let Some(transaction) = transactions.lock().get(&digest) {
+                return Ok(Some(transaction.clone()));
+            }
+        }
+
+        let Some(sequence_number) = self
+            .reader
+            .get_transaction_checkpoint(&digest)
+            .await
+            .map_err(read_error)? else {
+            return Ok(None);
+        };
+        let summary = self
+            .reader
+            .get_checkpoint_summary(sequence_number)
+            .await
+            .map_err(read_error)?
+            .ok_or_else(|| invalid(format!("checkpoint {sequence_number} is missing")))?;
+        if *summary.sequence_number() != sequence_number {
+            return Err(invalid(format!(
+                "checkpoint {sequence_number} has sequence number {}",
+                summary.sequence_number()
+            )));
+        }
+        let committee = self
+            .committee_store
+            .get_committee(&summary.epoch())?
+            .ok_or_else(|| {
+                invalid(format!(
+                    "checkpoint {sequence_number} is from unknown epoch {}",
+                    summary.epoch()
+                ))
+            })?;
+        summary.verify_signature(&committee)?;
+
+        let contents = self
+            .reader
+            .get_checkpoint_contents(sequence_number)
+            .await
+            .map_err(read_error)?
+            .ok_or_else(|| {
+                invalid(format!(
+                    "contents of checkpoint {sequence_number} are missing"
+                ))
+            })?;
+        contents
+            .verify_digests(summary.content_digest)
+            .map_err(|e| invalid(e.to_string()))?;
+        let execution_data = contents
+            .iter()
+            .find(|data| *data.transaction.digest() == digest)
+            .ok_or_else(|| {
+                invalid(format!(
+                    "checkpoint {sequence_number} does not include transaction {digest}"
+                ))
+            })?;
+
+        debug!(tx_digest = ?digest, checkpoint = sequence_number, "Read transaction from archive");
+        let transaction = ArchivedTransaction {
+            transaction: VerifiedTransaction::new_unchecked(execution_data.transaction.clone()),
+            effects: execution_data.effects.clone(),
+            checkpoint: sequence_number,
+            timestamp_ms: summary.timestamp_ms,
+        };
+        if let Some(transactions) = &self.transactions {
+            transactions.lock().put(digest, transaction.clone());
+        }
+        Ok(Some(transaction))
+    }
+
+    /// Returns the given version of the object, or `None` if it is not in the archive.
+    pub async fn get_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        if let Some(objects) = &self.objects {
+            if let Some(object) = objects.lock().get(&(object_id, version)) {
+                return Ok(Some(object.clone()));
+            }
+        }
+
+        let Some(object) = self
+            .reader
+            .get_object(&object_id, version)
+            .await
+            .map_err(read_error)? else {
+            return Ok(None);
+        };
+        let object_ref = object.compute_object_reference();
+        if object_ref.0 != object_id || object_ref.1 != version {
+            return Err(invalid(format!(
+                "object {object_id} version {version} is {object_ref:?}"
+            )));
+        }
+        let written = self
+            .get_transaction(object.previous_transaction)
+            .await?
+            .map_or(false, |transaction| {
+                transaction
+                    .effects
+                    .all_changed_objects()
+                    .into_iter()
+                    .any(|(changed, _, _)| *changed == object_ref)
+            });
+        if !written {
+            return Err(invalid(format!(
+                "object {object_ref:?} is not written by transaction {}",
+                object.previous_transaction
+            )));
+        }
+
+        if let Some(objects) = &self.objects {
+            objects.lock().put((object_id, version), object.clone());
+        }
+        Ok(Some(object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_storage::archive::{write_checkpoint, write_object};
+    use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+    use sui_types::base_types::ExecutionData;
+    use sui_types::committee::Committee;
+    use sui_types::crypto::KeypairTraits;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages::TransactionEffectsV1;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointSummary, FullCheckpointContents,
+        SignedCheckpointSummary,
+    };
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_read_from_archive() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let (committee, keys) = Committee::new_simple_test_committee();
+
+        let transaction = VerifiedTransaction::new_genesis_transaction(vec![]);
+        let digest = *transaction.digest();
+        let mut object = Object::immutable_with_id_for_testing(ObjectID::random());
+        object.previous_transaction = digest;
+        let effects = TransactionEffects::V1(TransactionEffectsV1 {
+            transaction_digest: digest,
+            created: vec![(object.compute_object_reference(), object.owner)],
+            ..Default::default()
+        });
+        let contents = FullCheckpointContents::new_with_causally_ordered_transactions(
+            std::iter::once(ExecutionData::new(transaction.into_inner(), effects)),
+        );
+        let summary = CheckpointSummary::new(
+            committee.epoch,
+            1,
+            1,
+            &contents.checkpoint_contents(),
+            None,
+            GasCostSummary::default(),
+            None,
+            1000,
+        );
+        let signatures = keys
+            .iter()
+            .map(|key| {
+                SignedCheckpointSummary::sign(committee.epoch, &summary, key, key.public().into())
+            })
+            .collect();
+        let summary = CertifiedCheckpointSummary::new(summary, signatures, &committee)?;
+        write_checkpoint(config.make()?, &summary, &contents).await?;
+        write_object(config.make()?, &object).await?;
+
+        let fallback = ArchiveFallback::new(
+            ArchiveReader::new(&config)?,
+            Arc::new(CommitteeStore::new_for_testing(&committee)),
+            10,
+        );
+        let transaction = fallback.get_transaction(digest).await?.unwrap();
+        assert_eq!(transaction.checkpoint, 1);
+        assert_eq!(transaction.timestamp_ms, 1000);
+        assert_eq!(
+            fallback.get_object(object.id(), object.version()).await?,
+            Some(object.clone())
+        );
+        assert!(fallback
+            .get_transaction(TransactionDigest::random())
+            .await?
+            .is_none());
+
+        // An object that its previous transaction did not write is rejected
+        let mut other = Object::with_id_owner_for_testing(ObjectID::random(), Default::default());
+        other.previous_transaction = digest;
+        write_object(config.make()?, &other).await?;
+        assert!(matches!(
+            fallback.get_object(other.id(), other.version()).await,
+            Err(SuiError::InvalidArchiveData { .. })
+        ));
+        Ok(())
+    }
+}
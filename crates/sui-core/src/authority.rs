@@ -11,11 +11,12 @@ use arc_swap::{ArcSwap, Guard};
 use chrono::prelude::*;
 use fastcrypto::encoding::Base58;
 use fastcrypto::encoding::Encoding;
+use fastcrypto::hash::MultisetHash;
 use fastcrypto::traits::KeyPair;
 use itertools::Itertools;
 use move_binary_format::compatibility::Compatibility;
 use move_binary_format::CompiledModule;
-use move_core_types::language_storage::ModuleId;
+use move_core_types::language_storage::{ModuleId, StructTag};
 use parking_lot::Mutex;
 use prometheus::{
     register_histogram_with_registry, register_int_counter_vec_with_registry,
@@ -25,6 +26,7 @@ use prometheus::{
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use sui_framework::natives::execution_trace;
 use sui_framework::{MoveStdlib, SuiFramework, SuiSystem, SystemPackage};
 use tap::{TapFallible, TapOptional};
 use tokio::sync::mpsc::unbounded_channel;
@@ -43,15 +45,20 @@ use shared_crypto::intent::{Intent, IntentScope};
 use sui_adapter::execution_engine;
 use sui_adapter::{adapter, execution_mode};
 use sui_config::genesis::Genesis;
-use sui_config::node::{AuthorityStorePruningConfig, DBCheckpointConfig};
+use sui_config::node::{
+    AuthorityStorePruningConfig, DBCheckpointConfig, LocalExecutionTimeLimitConfig, SlowOpConfig,
+    SystemTransactionTraceConfig,
+};
+use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_json_rpc_types::{
-    Checkpoint, DevInspectResults, DryRunTransactionResponse, EventFilter, SuiEvent, SuiMoveValue,
-    SuiObjectDataFilter, SuiTransactionEvents,
+    Checkpoint, DevInspectResults, DryRunTransactionResponse, EventFilter, ObjectChange, SuiEvent,
+    SuiGasBreakdown, SuiMoveValue, SuiObjectDataFilter, SuiObjectOverride, SuiTransactionEvents,
 };
 use sui_macros::{fail_point, fail_point_async, nondeterministic};
 use sui_protocol_config::SupportedProtocolVersions;
 use sui_storage::indexes::{ObjectIndexChanges, MAX_GET_OWNED_OBJECT_SIZE};
 use sui_storage::IndexStore;
+use sui_types::certificate_proof::CertificateProof;
 use sui_types::committee::{EpochId, ProtocolVersion};
 use sui_types::crypto::{
     default_hash, AuthorityKeyPair, AuthoritySignInfo, NetworkKeyPair, Signer,
@@ -61,15 +68,17 @@ use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldName, DynamicFieldT
 use sui_types::error::UserInputError;
 use sui_types::event::{Event, EventID};
 use sui_types::gas::{GasCostSummary, GasPrice, SuiCostTable, SuiGasStatus};
+use sui_types::inclusion_proof::TransactionInclusionProof;
 use sui_types::message_envelope::Message;
 use sui_types::messages_checkpoint::{
     CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
-    CheckpointSummary, CheckpointTimestamp, VerifiedCheckpoint,
+    CheckpointSummary, CheckpointTimestamp, ECMHLiveObjectSetDigest, VerifiedCheckpoint,
 };
 use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
 use sui_types::object::{MoveObject, Owner, PastObjectRead, OBJECT_START_VERSION};
 use sui_types::query::TransactionFilter;
-use sui_types::storage::{ObjectKey, ObjectStore, WriteKind};
+use sui_types::state_proof::ObjectStateProof;
+use sui_types::storage::{DeleteKind, ObjectKey, ObjectStore, WriteKind};
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::sui_system_state::SuiSystemState;
 use sui_types::sui_system_state::SuiSystemStateTrait;
@@ -91,8 +100,12 @@ use crate::authority::authority_per_epoch_store::{AuthorityPerEpochStore, CertTx
 use crate::authority::authority_per_epoch_store_pruner::AuthorityPerEpochStorePruner;
 use crate::authority::authority_store::{ExecutionLockReadGuard, InputKey, ObjectLockStatus};
 use crate::authority::authority_store_pruner::AuthorityStorePruner;
+use crate::authority::checkpoint_history_pruner::PrunedHistory;
 use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+use crate::authority::local_execution_limiter::{LocalExecutionKind, LocalExecutionLimiter};
+use crate::authority::object_overrides::{overridden_objects, ObjectOverrideStore};
+use crate::authority::transaction_outputs::TransactionOutputsSource;
 use crate::checkpoints::CheckpointStore;
 use crate::epoch::committee_store::CommitteeStore;
 use crate::epoch::epoch_metrics::EpochMetrics;
@@ -100,6 +113,7 @@ use crate::event_handler::EventHandler;
 use crate::execution_driver::execution_process;
 use crate::module_cache_metrics::ResolverMetrics;
 use crate::signature_verifier::VerifiedDigestCacheMetrics;
+use crate::slow_ops::{SlowOpKind, SlowOpLog};
 use crate::stake_aggregator::StakeAggregator;
 use crate::{transaction_input_checker, transaction_manager::TransactionManager};
 
@@ -129,7 +143,16 @@ pub mod authority_per_epoch_store_pruner;
 pub mod authority_store_pruner;
 pub mod authority_store_tables;
 pub mod authority_store_types;
+pub mod checkpoint_history_pruner;
 pub mod epoch_start_configuration;
+pub mod execution_cache;
+pub mod local_execution_limiter;
+pub mod object_overrides;
+pub mod shared_object_congestion_tracker;
+pub mod shared_object_dependency_graph;
+pub mod system_transaction_trace;
+pub mod transaction_lifecycle;
+pub mod transaction_outputs;
 
 pub(crate) mod authority_notify_read;
 pub(crate) mod authority_store;
@@ -163,6 +186,8 @@ pub struct AuthorityMetrics {
     total_certs: IntCounter,
     total_cert_attempts: IntCounter,
     total_effects: IntCounter,
+    transaction_outputs_applied: IntCounter,
+    tx_outputs_too_large: IntCounterVec,
     pub shared_obj_tx: IntCounter,
     tx_already_processed: IntCounter,
     num_input_objs: Histogram,
@@ -182,9 +207,13 @@ pub struct AuthorityMetrics {
     pub(crate) transaction_manager_num_pending_certificates: IntGauge,
     pub(crate) transaction_manager_num_executing_certificates: IntGauge,
     pub(crate) transaction_manager_num_ready: IntGauge,
+    pub(crate) transaction_manager_shared_object_critical_path: Histogram,
+    pub(crate) transaction_manager_shared_object_parallelism: Histogram,
 
     pub(crate) execution_driver_executed_transactions: IntCounter,
     pub(crate) execution_driver_dispatch_queue: IntGauge,
+    pub(crate) execution_driver_executing_transactions: IntGauge,
+    pub(crate) execution_driver_parallelism: Histogram,
 
     pub(crate) skipped_consensus_txns: IntCounter,
     pub(crate) skipped_consensus_txns_cache_hit: IntCounter,
@@ -204,6 +233,16 @@ pub struct AuthorityMetrics {
     pub consensus_handler_scores: IntGaugeVec,
     pub consensus_committed_subdags: IntCounterVec,
     pub consensus_committed_certificates: IntCounterVec,
+    pub consensus_handler_deferred_transactions: IntCounter,
+    pub consensus_handler_deferred_transactions_queue: IntGauge,
+    pub consensus_handler_deferred_objects: IntGauge,
+    pub consensus_handler_deferred_transaction_rounds: Histogram,
+    pub consensus_handler_flushed_deferred_transactions: IntCounter,
+    pub consensus_handler_max_shared_object_utilization: IntGauge,
+
+    /// Local execution metrics
+    pub(crate) local_executions_in_flight: IntGauge,
+    pub(crate) local_execution_time_limit_exceeded: IntCounterVec,
 }
 
 // Override default Prom buckets for positive numbers in 0-50k range
@@ -243,6 +282,19 @@ impl AuthorityMetrics {
                 registry,
             )
             .unwrap(),
+            transaction_outputs_applied: register_int_counter_with_registry!(
+                "transaction_outputs_applied",
+                "Number of certificates whose outputs were fetched from a peer instead of executed",
+                registry,
+            )
+            .unwrap(),
+            tx_outputs_too_large: register_int_counter_vec_with_registry!(
+                "tx_outputs_too_large",
+                "Number of certificates that failed because their effects or events were too large",
+                &["output"],
+                registry,
+            )
+            .unwrap(),
 
             shared_obj_tx: register_int_counter_with_registry!(
                 "num_shared_obj_tx",
@@ -356,6 +408,20 @@ impl AuthorityMetrics {
                 registry,
             )
             .unwrap(),
+            transaction_manager_shared_object_critical_path: register_histogram_with_registry!(
+                "transaction_manager_shared_object_critical_path",
+                "Longest chain of dependent transactions on shared objects per enqueued batch",
+                POSITIVE_INT_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            transaction_manager_shared_object_parallelism: register_histogram_with_registry!(
+                "transaction_manager_shared_object_parallelism",
+                "Number of transactions per enqueued batch with shared objects, divided by the length of its longest chain of dependent transactions",
+                POSITIVE_INT_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             execution_driver_executed_transactions: register_int_counter_with_registry!(
                 "execution_driver_executed_transactions",
                 "Cumulative number of transaction executed by execution driver",
@@ -368,6 +434,19 @@ impl AuthorityMetrics {
                 registry,
             )
             .unwrap(),
+            execution_driver_executing_transactions: register_int_gauge_with_registry!(
+                "execution_driver_executing_transactions",
+                "Number of transactions currently executing in execution driver workers",
+                registry,
+            )
+            .unwrap(),
+            execution_driver_parallelism: register_histogram_with_registry!(
+                "execution_driver_parallelism",
+                "Number of transactions executing concurrently when execution driver starts a transaction",
+                POSITIVE_INT_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             skipped_consensus_txns: register_int_counter_with_registry!(
                 "skipped_consensus_txns",
                 "Total number of consensus transactions skipped",
@@ -442,6 +521,56 @@ impl AuthorityMetrics {
                 registry,
             )
                 .unwrap(),
+            consensus_handler_deferred_transactions: register_int_counter_with_registry!(
+                "consensus_handler_deferred_transactions",
+                "Number of newly sequenced transactions deferred to a later commit by shared object congestion control",
+                registry,
+            )
+                .unwrap(),
+            consensus_handler_deferred_transactions_queue: register_int_gauge_with_registry!(
+                "consensus_handler_deferred_transactions_queue",
+                "Number of transactions currently deferred by shared object congestion control",
+                registry,
+            )
+                .unwrap(),
+            consensus_handler_deferred_objects: register_int_gauge_with_registry!(
+                "consensus_handler_deferred_objects",
+                "Number of shared objects mutated by transactions currently deferred by shared object congestion control",
+                registry,
+            )
+                .unwrap(),
+            consensus_handler_deferred_transaction_rounds: register_histogram_with_registry!(
+                "consensus_handler_deferred_transaction_rounds",
+                "Number of consensus rounds deferred transactions waited before being admitted",
+                POSITIVE_INT_BUCKETS.to_vec(),
+                registry,
+            )
+                .unwrap(),
+            consensus_handler_flushed_deferred_transactions: register_int_counter_with_registry!(
+                "consensus_handler_flushed_deferred_transactions",
+                "Number of deferred transactions admitted over the per-object limit because an authority sent EndOfPublish",
+                registry,
+            )
+                .unwrap(),
+            consensus_handler_max_shared_object_utilization: register_int_gauge_with_registry!(
+                "consensus_handler_max_shared_object_utilization",
+                "Utilization of the busiest shared object over recent commits, in percent of the per-commit limit",
+                registry,
+            )
+                .unwrap(),
+            local_executions_in_flight: register_int_gauge_with_registry!(
+                "local_executions_in_flight",
+                "Number of dry runs and dev-inspect executions running, including those past their time limit",
+                registry,
+            )
+                .unwrap(),
+            local_execution_time_limit_exceeded: register_int_counter_vec_with_registry!(
+                "local_execution_time_limit_exceeded",
+                "Number of dry runs and dev-inspect executions aborted for exceeding their time limit",
+                &["kind"],
+                registry,
+            )
+                .unwrap(),
         }
     }
 }
@@ -454,6 +583,32 @@ impl AuthorityMetrics {
 ///
 pub type StableSyncAuthoritySigner = Pin<Arc<dyn Signer<AuthoritySignature> + Send + Sync>>;
 
+/// What would happen if the current epoch ended now, as simulated by
+/// [`AuthorityState::dry_run_epoch_change`].
+#[derive(Debug)]
+pub struct EpochChangeDryRun {
+    pub next_epoch: EpochId,
+    pub current_protocol_version: ProtocolVersion,
+    pub next_protocol_version: ProtocolVersion,
+    /// System packages the next epoch upgrades to, empty if they are not upgraded.
+    pub next_system_packages: Vec<ObjectRef>,
+    /// Whether this node's binary supports the protocol version of the next epoch. If not, the
+    /// node cannot run in the next epoch.
+    pub next_protocol_version_supported: bool,
+    /// Whether this node's binary has the system packages the next epoch upgrades to. If not, the
+    /// node cannot form the change epoch transaction.
+    pub next_system_packages_available: bool,
+    /// Whether the change epoch transaction would fail and put the system in safe mode.
+    pub safe_mode: bool,
+    pub next_committee: Committee,
+}
+
+impl EpochChangeDryRun {
+    pub fn is_in_next_committee(&self, name: &AuthorityName) -> bool {
+        self.next_committee.authority_exists(name)
+    }
+}
+
 pub struct AuthorityState {
     // Fixed size, static, identity of the authority
     /// The name of this authority.
@@ -486,6 +641,21 @@ pub struct AuthorityState {
 
     /// Take db checkpoints af different dbs
     db_checkpoint_config: DBCheckpointConfig,
+
+    /// Transactions this node refuses to sign or submit. Can be swapped at runtime.
+    transaction_deny_config: ArcSwap<TransactionDenyConfig>,
+
+    /// Logs the execution of system transactions, if set.
+    system_transaction_trace_config: Option<SystemTransactionTraceConfig>,
+
+    /// Bounds the wall-clock time of dry runs and dev-inspect calls.
+    local_execution_limiter: LocalExecutionLimiter,
+
+    /// Provides the outputs of certificates in place of executing them, on read-only fullnodes.
+    transaction_outputs_source: Option<Arc<dyn TransactionOutputsSource>>,
+
+    /// Records of the RPC calls, executions and consensus commits slower than their threshold.
+    pub slow_ops: Arc<SlowOpLog>,
 }
 
 /// The authority state encapsulates all state, drives execution, and ensures safety.
@@ -503,6 +673,12 @@ impl AuthorityState {
         !self.is_validator(epoch_store)
     }
 
+    /// Whether this node applies the outputs of checkpointed transactions fetched from a peer,
+    /// instead of executing them.
+    pub fn is_read_only(&self) -> bool {
+        self.transaction_outputs_source.is_some()
+    }
+
     pub fn committee_store(&self) -> &Arc<CommitteeStore> {
         &self.committee_store
     }
@@ -525,6 +701,7 @@ impl AuthorityState {
                 threshold: MAX_EXECUTION_QUEUE_LENGTH,
             });
         }
+        self.check_transaction_deny_list(&transaction.data().intent_message().value)?;
         let (_gas_status, input_objects) = transaction_input_checker::check_transaction_input(
             &self.database,
             epoch_store.as_ref(),
@@ -796,6 +973,11 @@ impl AuthorityState {
         epoch_store: &Arc<AuthorityPerEpochStore>,
     ) -> SuiResult<TransactionEffects> {
         let digest = *certificate.digest();
+        let mut slow_op = self.slow_ops.start(
+            SlowOpKind::Execution,
+            "certificate",
+            Some(digest.to_string()),
+        );
         // The cert could have been processed by a concurrent attempt of the same cert, so check if
         // the effects have already been written.
         if let Some(effects) = self.database.get_executed_effects(&digest)? {
@@ -817,6 +999,7 @@ impl AuthorityState {
                 return Err(err);
             }
         };
+        slow_op.phase("execution_lock");
         // Since we obtain a reference to the epoch store before taking the execution lock, it's
         // possible that reconfiguration has happened and they no longer match.
         if *execution_guard != epoch_store.epoch() {
@@ -843,6 +1026,17 @@ impl AuthorityState {
             }
             Ok(res) => res,
         };
+        slow_op.phase("execute");
+
+        if let Some(trace_config) = &self.system_transaction_trace_config {
+            system_transaction_trace::trace_system_transaction(
+                trace_config,
+                &self.database,
+                certificate,
+                &inner_temporary_store,
+                &effects,
+            );
+        }
 
         fail_point_async!("crash");
 
@@ -855,6 +1049,7 @@ impl AuthorityState {
             epoch_store,
         )
         .await?;
+        slow_op.phase("commit");
         Ok(effects)
     }
 
@@ -959,6 +1154,17 @@ impl AuthorityState {
         let owned_object_refs = input_objects.filter_owned_objects();
         self.check_owned_locks(&owned_object_refs).await?;
 
+        if let Some(source) = &self.transaction_outputs_source {
+            return self
+                .prepare_certificate_from_outputs(
+                    source.as_ref(),
+                    certificate,
+                    input_objects,
+                    epoch_store,
+                )
+                .await;
+        }
+
         let shared_object_refs = input_objects.filter_shared_objects();
         let transaction_dependencies = input_objects.transaction_dependencies();
         let temporary_store = TemporaryStore::new(
@@ -983,10 +1189,78 @@ impl AuthorityState {
                 &epoch_store.epoch_start_config().epoch_data(),
                 epoch_store.protocol_config(),
             );
+        if let ExecutionStatus::Failure { error, .. } = effects.status() {
+            match error {
+                ExecutionFailureStatus::EffectsTooLarge { .. } => self
+                    .metrics
+                    .tx_outputs_too_large
+                    .with_label_values(&["effects"])
+                    .inc(),
+                ExecutionFailureStatus::EventsTooLarge { .. } => self
+                    .metrics
+                    .tx_outputs_too_large
+                    .with_label_values(&["events"])
+                    .inc(),
+                _ => (),
+            }
+        }
 
         Ok((inner_temp_store, effects))
     }
 
+    /// Prepares a certificate on a read-only node, from the outputs of the certificate fetched
+    /// from `source` rather than by executing it. Only certificates included in a checkpoint can
+    /// be prepared this way, as their effects are certified by the checkpoint.
+    async fn prepare_certificate_from_outputs(
+        &self,
+        source: &dyn TransactionOutputsSource,
+        certificate: &VerifiedExecutableTransaction,
+        input_objects: InputObjects,
+        epoch_store: &Arc<AuthorityPerEpochStore>,
+    ) -> SuiResult<(InnerTemporaryStore, TransactionEffects)> {
+        let digest = *certificate.digest();
+        let CertificateProof::Checkpoint(_, sequence_number) = certificate.auth_sig() else {
+            return Err(SuiError::UnsupportedFeatureError {
+                error: format!(
+                    "read-only nodes only apply checkpointed transactions, {digest:?} is not"
+                ),
+            });
+        };
+        let checkpoint = self
+            .checkpoint_store
+            .get_checkpoint_by_sequence_number(*sequence_number)?
+            .ok_or_else(|| {
+                SuiError::GenericStorageError(format!("missing checkpoint {sequence_number}"))
+            })?;
+        let effects_digest = self
+            .checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)?
+            .and_then(|contents| {
+                contents
+                    .iter()
+                    .find(|digests| digests.transaction == digest)
+                    .map(|digests| digests.effects)
+            })
+            .ok_or(SuiError::TransactionNotFound { digest })?;
+        // State sync stores the effects of checkpointed transactions along with them.
+        let effects = self
+            .database
+            .perpetual_tables
+            .effects
+            .get(&effects_digest)?
+            .ok_or(SuiError::TransactionNotFound { digest })?;
+
+        let outputs = source.get_transaction_outputs(effects_digest).await?;
+        let inner_temp_store = transaction_outputs::outputs_to_temporary_store(
+            &effects,
+            outputs,
+            input_objects,
+            epoch_store.protocol_config().move_binary_format_version(),
+        )?;
+        self.metrics.transaction_outputs_applied.inc();
+        Ok((inner_temp_store, effects))
+    }
+
     /// Notifies TransactionManager about an executed certificate.
     pub fn certificate_executed(
         &self,
@@ -997,10 +1271,12 @@ impl AuthorityState {
             .certificate_executed(digest, epoch_store)
     }
 
+    /// If `trace` is set, the response includes a trace of the execution.
     pub async fn dry_exec_transaction(
         &self,
         transaction: TransactionData,
         transaction_digest: TransactionDigest,
+        trace: bool,
     ) -> Result<DryRunTransactionResponse, anyhow::Error> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
         if !self.is_fullnode(&epoch_store) {
@@ -1056,20 +1332,43 @@ impl AuthorityState {
             )
             .expect("We defined natives to not fail here"),
         );
-        let (inner_temp_store, effects, _execution_error) =
-            execution_engine::execute_transaction_to_effects::<execution_mode::Normal, _>(
-                shared_object_refs,
-                temporary_store,
-                kind,
-                signer,
-                &gas_object_refs,
-                transaction_digest,
-                transaction_dependencies,
-                &move_vm,
-                gas_status,
-                &epoch_store.epoch_start_config().epoch_data(),
-                epoch_store.protocol_config(),
-            );
+        let execution_epoch_store = epoch_store.clone();
+        let (inner_temp_store, effects, _execution_error, mut execution_trace) = self
+            .local_execution_limiter
+            .execute(LocalExecutionKind::DryRun, move || {
+                // The trace is always collected, for the gas breakdown.
+                let tracer = execution_trace::trace_transaction();
+                let (inner_temp_store, effects, execution_error) =
+                    execution_engine::execute_transaction_to_effects::<execution_mode::Normal, _>(
+                        shared_object_refs,
+                        temporary_store,
+                        kind,
+                        signer,
+                        &gas_object_refs,
+                        transaction_digest,
+                        transaction_dependencies,
+                        &move_vm,
+                        gas_status,
+                        &execution_epoch_store.epoch_start_config().epoch_data(),
+                        execution_epoch_store.protocol_config(),
+                    );
+                (inner_temp_store, effects, execution_error, tracer.finish())
+            })
+            .await?;
+        execution_trace.record_object_changes(&inner_temp_store);
+        let gas_breakdown = SuiGasBreakdown::new(
+            &execution_trace,
+            &inner_temp_store,
+            transaction.gas_price(),
+            trace,
+            |id| {
+                self.database
+                    .get_object(id)
+                    .ok()
+                    .flatten()
+                    .map(|object| object.storage_rebate)
+            },
+        );
         let tx_digest = *effects.transaction_digest();
 
         let module_cache =
@@ -1083,15 +1382,21 @@ impl AuthorityState {
                 None,
                 &module_cache,
             )?,
+            trace: trace.then_some(execution_trace),
+            gas_breakdown: Some(gas_breakdown),
         })
     }
 
-    /// The object ID for gas can be any object ID, even for an uncreated object
+    /// The object ID for gas can be any object ID, even for an uncreated object.
+    /// If `trace` is set, the results include a trace of the execution.
+    /// The transaction is executed with `object_overrides` in place of the objects on chain.
     pub async fn dev_inspect_transaction(
         &self,
         sender: SuiAddress,
         transaction_kind: TransactionKind,
         gas_price: Option<u64>,
+        trace: bool,
+        object_overrides: Vec<SuiObjectOverride>,
     ) -> Result<DevInspectResults, anyhow::Error> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
         if !self.is_fullnode(&epoch_store) {
@@ -1114,11 +1419,18 @@ impl AuthorityState {
             Owner::AddressOwner(sender),
             TransactionDigest::genesis(),
         );
+        let object_overrides = overridden_objects(
+            self.database.as_ref(),
+            object_overrides,
+            protocol_config,
+            epoch_store.module_cache().as_ref(),
+        )?;
         let (gas_object_ref, input_objects) = transaction_input_checker::check_dev_inspect_input(
             &self.database,
             protocol_config,
             &transaction_kind,
             gas_object,
+            &object_overrides,
         )
         .await?;
         let shared_object_refs = input_objects.filter_shared_objects();
@@ -1137,7 +1449,7 @@ impl AuthorityState {
         let transaction_kind = data.into_kind();
         let transaction_dependencies = input_objects.transaction_dependencies();
         let temporary_store = TemporaryStore::new(
-            self.database.clone(),
+            ObjectOverrideStore::new(self.database.clone(), object_overrides),
             input_objects,
             transaction_digest,
             protocol_config,
@@ -1156,20 +1468,36 @@ impl AuthorityState {
             )
             .expect("We defined natives to not fail here"),
         );
-        let (inner_temp_store, effects, execution_result) =
-            execution_engine::execute_transaction_to_effects::<execution_mode::DevInspect, _>(
-                shared_object_refs,
-                temporary_store,
-                transaction_kind,
-                sender,
-                &[gas_object_ref],
-                transaction_digest,
-                transaction_dependencies,
-                &move_vm,
-                gas_status,
-                &epoch_store.epoch_start_config().epoch_data(),
-                protocol_config,
-            );
+        let execution_epoch_store = epoch_store.clone();
+        let (inner_temp_store, effects, execution_result, trace) =
+            self.local_execution_limiter
+                .execute(LocalExecutionKind::DevInspect, move || {
+                    let tracer = trace.then(execution_trace::trace_transaction);
+                    let (inner_temp_store, effects, execution_result) =
+                        execution_engine::execute_transaction_to_effects::<
+                            execution_mode::DevInspect,
+                            _,
+                        >(
+                            shared_object_refs,
+                            temporary_store,
+                            transaction_kind,
+                            sender,
+                            &[gas_object_ref],
+                            transaction_digest,
+                            transaction_dependencies,
+                            &move_vm,
+                            gas_status,
+                            &execution_epoch_store.epoch_start_config().epoch_data(),
+                            execution_epoch_store.protocol_config(),
+                        );
+                    let trace = tracer.map(|tracer| tracer.finish());
+                    (inner_temp_store, effects, execution_result, trace)
+                })
+                .await?;
+        let trace = trace.map(|mut trace| {
+            trace.record_object_changes(&inner_temp_store);
+            trace
+        });
 
         let module_cache =
             TemporaryModuleResolver::new(&inner_temp_store, epoch_store.module_cache().clone());
@@ -1178,6 +1506,7 @@ impl AuthorityState {
             effects,
             inner_temp_store.events.clone(),
             execution_result,
+            trace,
             &module_cache,
         )
     }
@@ -1454,11 +1783,94 @@ impl AuthorityState {
                 self.metrics
                     .post_processing_total_events_emitted
                     .inc_by(events.data.len() as u64);
+
+                if self.event_handler.has_object_change_subscribers().await {
+                    let object_changes = self.get_object_changes(
+                        certificate.data().intent_message().value.sender(),
+                        effects,
+                    )?;
+                    self.event_handler
+                        .process_object_changes(*tx_digest, object_changes)
+                        .await?;
+                }
             }
         };
         Ok(())
     }
 
+    /// Object changes made by an executed transaction, as streamed to subscribers. The types of
+    /// the objects are read from the versions written, or deleted, by the transaction.
+    fn get_object_changes(
+        &self,
+        sender: SuiAddress,
+        effects: &TransactionEffects,
+    ) -> SuiResult<Vec<ObjectChange>> {
+        let previous_versions: HashMap<_, _> =
+            effects.modified_at_versions().iter().cloned().collect();
+        let mut object_changes = vec![];
+
+        for ((id, version, digest), owner, kind) in effects.all_changed_objects() {
+            let Some(object) = self.database.get_object_by_key(id, *version)? else { continue };
+            if let Some(type_) = object.type_() {
+                let object_type = StructTag::from(type_.clone());
+                match kind {
+                    WriteKind::Mutate => object_changes.push(ObjectChange::Mutated {
+                        sender,
+                        owner: *owner,
+                        object_type,
+                        object_id: *id,
+                        version: *version,
+                        previous_version: previous_versions.get(id).cloned().unwrap_or_default(),
+                        digest: *digest,
+                    }),
+                    WriteKind::Create => object_changes.push(ObjectChange::Created {
+                        sender,
+                        owner: *owner,
+                        object_type,
+                        object_id: *id,
+                        version: *version,
+                        digest: *digest,
+                    }),
+                    WriteKind::Unwrap => (),
+                }
+            } else if let Some(package) = object.data.try_as_package() {
+                if kind == WriteKind::Create {
+                    object_changes.push(ObjectChange::Published {
+                        package_id: package.id(),
+                        version: package.version(),
+                        digest: *digest,
+                        modules: package.serialized_module_map().keys().cloned().collect(),
+                    })
+                }
+            }
+        }
+
+        for ((id, version, _), kind) in effects.all_deleted() {
+            let Some(previous_version) = previous_versions.get(id) else { continue };
+            let object = self.database.get_object_by_key(id, *previous_version)?;
+            let Some(object) = object else { continue };
+            let Some(type_) = object.type_() else { continue };
+            let object_type = StructTag::from(type_.clone());
+            match kind {
+                DeleteKind::Normal => object_changes.push(ObjectChange::Deleted {
+                    sender,
+                    object_type,
+                    object_id: *id,
+                    version: *version,
+                }),
+                DeleteKind::Wrap => object_changes.push(ObjectChange::Wrapped {
+                    sender,
+                    object_type,
+                    object_id: *id,
+                    version: *version,
+                }),
+                DeleteKind::UnwrapThenDelete => (),
+            }
+        }
+
+        Ok(object_changes)
+    }
+
     pub fn unixtime_now_ms() -> u64 {
         let ts_ms = Utc::now().timestamp_millis();
         u64::try_from(ts_ms).expect("Travelling in time machine")
@@ -1593,6 +2005,11 @@ impl AuthorityState {
         pruning_config: AuthorityStorePruningConfig,
         genesis_objects: &[Object],
         db_checkpoint_config: &DBCheckpointConfig,
+        transaction_deny_config: TransactionDenyConfig,
+        system_transaction_trace_config: Option<SystemTransactionTraceConfig>,
+        local_execution_time_limit_config: LocalExecutionTimeLimitConfig,
+        transaction_outputs_source: Option<Arc<dyn TransactionOutputsSource>>,
+        slow_op_config: SlowOpConfig,
     ) -> Arc<Self> {
         Self::check_protocol_version(supported_protocol_versions, epoch_store.protocol_version());
 
@@ -1612,9 +2029,12 @@ impl AuthorityState {
             store.perpetual_tables.clone(),
             checkpoint_store.clone(),
             store.objects_lock_table.clone(),
+            &store.objects_compaction_filter,
             pruning_config,
             epoch_store.epoch_start_state().epoch_duration_ms(),
         );
+        let local_execution_limiter =
+            LocalExecutionLimiter::new(local_execution_time_limit_config, metrics.clone());
         let state = Arc::new(AuthorityState {
             name,
             secret,
@@ -1630,6 +2050,11 @@ impl AuthorityState {
             _objects_pruner,
             _authority_per_epoch_pruner,
             db_checkpoint_config: db_checkpoint_config.clone(),
+            transaction_deny_config: ArcSwap::from_pointee(transaction_deny_config),
+            system_transaction_trace_config,
+            local_execution_limiter,
+            transaction_outputs_source,
+            slow_ops: Arc::new(SlowOpLog::new(slow_op_config)),
         });
 
         // Start a task to execute ready certificates.
@@ -1715,6 +2140,11 @@ impl AuthorityState {
             AuthorityStorePruningConfig::default(),
             genesis.objects(),
             &DBCheckpointConfig::default(),
+            TransactionDenyConfig::default(),
+            None,
+            LocalExecutionTimeLimitConfig::default(),
+            None,
+            SlowOpConfig::default(),
         )
         .await;
 
@@ -1730,6 +2160,24 @@ impl AuthorityState {
         &self.transaction_manager
     }
 
+    pub fn transaction_deny_config(&self) -> Arc<TransactionDenyConfig> {
+        self.transaction_deny_config.load_full()
+    }
+
+    /// Atomically replaces the deny list. Transactions already signed are not affected.
+    pub fn set_transaction_deny_config(&self, transaction_deny_config: TransactionDenyConfig) {
+        self.transaction_deny_config
+            .store(Arc::new(transaction_deny_config));
+    }
+
+    pub fn check_transaction_deny_list(&self, transaction: &TransactionData) -> SuiResult {
+        transaction_input_checker::check_transaction_deny_list(
+            &self.transaction_deny_config.load(),
+            transaction,
+        )?;
+        Ok(())
+    }
+
     /// Adds certificates to the pending certificate store and transaction manager for ordered execution.
     pub fn enqueue_certificates_for_execution(
         &self,
@@ -2313,7 +2761,7 @@ impl AuthorityState {
             .get_transactions(filter, cursor, limit, reverse)
     }
 
-    fn get_checkpoint_store(&self) -> Arc<CheckpointStore> {
+    pub fn get_checkpoint_store(&self) -> Arc<CheckpointStore> {
         self.checkpoint_store.clone()
     }
 
@@ -2325,6 +2773,16 @@ impl AuthorityState {
             .ok_or_else(|| anyhow!("Latest checkpoint sequence number not found"))
     }
 
+    /// Lowest checkpoint whose history of the given kind has not been pruned by this node.
+    pub fn get_lowest_available_history_checkpoint(
+        &self,
+        kind: PrunedHistory,
+    ) -> SuiResult<CheckpointSequenceNumber> {
+        self.database
+            .perpetual_tables
+            .get_lowest_available_history_checkpoint(kind)
+    }
+
     pub fn get_checkpoint_summary_by_sequence_number(
         &self,
         sequence_number: CheckpointSequenceNumber,
@@ -2366,6 +2824,110 @@ impl AuthorityState {
             .ok_or_else(|| anyhow!("Checkpoint contents not found for digest: {:?}", digest))
     }
 
+    /// Builds a proof that `version` of `object_id` is part of the state of the epoch that
+    /// checkpointed the transaction writing it. Once the epoch has ended, the proof is anchored to
+    /// the state root of the epoch, which must match the one computed by the local state
+    /// accumulator.
+    pub fn get_object_state_proof(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<ObjectStateProof, anyhow::Error> {
+        let object = self
+            .database
+            .get_object_by_key(object_id, version)?
+            .ok_or_else(|| anyhow!("Object {object_id} version {version} not found"))?;
+        let digest = object.previous_transaction;
+        let (epoch, sequence_number) = self
+            .database
+            .get_transaction_checkpoint(&digest)?
+            .ok_or_else(|| anyhow!("Transaction {digest:?} is not checkpointed yet"))?;
+        let checkpoint = self
+            .checkpoint_store
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or_else(|| anyhow!("Checkpoint {sequence_number} not found"))?
+            .into_inner();
+        let contents = self.get_checkpoint_contents(checkpoint.content_digest)?;
+        let effects = self
+            .database
+            .get_executed_effects(&digest)?
+            .ok_or_else(|| anyhow!(SuiError::TransactionNotFound { digest }))?;
+        let end_of_epoch_checkpoint = self
+            .checkpoint_store
+            .get_epoch_last_checkpoint(epoch)?
+            .map(|checkpoint| checkpoint.into_inner());
+
+        let proof = ObjectStateProof {
+            object_ref: object.compute_object_reference(),
+            effects,
+            checkpoint,
+            contents,
+            end_of_epoch_checkpoint,
+        };
+        if let (Some(state_root), Some((_, accumulator))) = (
+            proof.state_root(),
+            self.database.get_root_state_accumulator(epoch)?,
+        ) {
+            let local_root = ECMHLiveObjectSetDigest::from(accumulator.digest());
+            if *state_root != local_root {
+                return Err(anyhow!(
+                    "State root of epoch {epoch} is {state_root:?}, but the local state \
+                    accumulator computed {local_root:?}"
+                ));
+            }
+        }
+        Ok(proof)
+    }
+
+    /// Builds a proof that the transaction `digest` was executed and finalized by the
+    /// checkpoint that includes it, to be verified by light clients against the committee of
+    /// its epoch or of the previous epoch.
+    pub fn get_transaction_inclusion_proof(
+        &self,
+        digest: &TransactionDigest,
+    ) -> Result<TransactionInclusionProof, anyhow::Error> {
+        let (epoch, sequence_number) = self
+            .database
+            .get_transaction_checkpoint(digest)?
+            .ok_or_else(|| anyhow!("Transaction {digest:?} is not checkpointed yet"))?;
+        let checkpoint = self
+            .checkpoint_store
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or_else(|| anyhow!("Checkpoint {sequence_number} not found"))?
+            .into_inner();
+        let contents = self.get_checkpoint_contents(checkpoint.content_digest)?;
+        let transaction = self
+            .database
+            .get_transaction(digest)?
+            .ok_or_else(|| anyhow!(SuiError::TransactionNotFound { digest: *digest }))?;
+        let effects = self
+            .database
+            .get_executed_effects(digest)?
+            .ok_or_else(|| anyhow!(SuiError::TransactionNotFound { digest: *digest }))?;
+        let committee = self
+            .committee_store
+            .get_committee(&epoch)?
+            .ok_or_else(|| anyhow!("Committee of epoch {epoch} not found"))?;
+        let previous_epoch_checkpoint = match epoch.checked_sub(1) {
+            Some(previous_epoch) => Some(
+                self.checkpoint_store
+                    .get_epoch_last_checkpoint(previous_epoch)?
+                    .ok_or_else(|| anyhow!("Last checkpoint of epoch {previous_epoch} not found"))?
+                    .into_inner(),
+            ),
+            None => None,
+        };
+
+        Ok(TransactionInclusionProof {
+            transaction: transaction.into_inner().into_data(),
+            effects,
+            checkpoint,
+            contents,
+            committee: committee.members().cloned().collect(),
+            previous_epoch_checkpoint,
+        })
+    }
+
     pub fn get_checkpoint_contents_by_sequence_number(
         &self,
         sequence_number: CheckpointSequenceNumber,
@@ -3073,6 +3635,28 @@ impl AuthorityState {
         Some(res)
     }
 
+    /// Chooses the protocol version and system packages of the next epoch from the capabilities
+    /// received so far in the current epoch.
+    fn choose_next_epoch_protocol_version_and_system_packages(
+        epoch_store: &AuthorityPerEpochStore,
+    ) -> (ProtocolVersion, Vec<ObjectRef>) {
+        let buffer_stake_bps = epoch_store
+            .get_override_protocol_upgrade_buffer_stake()
+            .tap_some(|b| warn!("using overrided buffer stake value of {}", b))
+            .unwrap_or_else(|| {
+                epoch_store
+                    .protocol_config()
+                    .buffer_stake_for_protocol_upgrade_bps()
+            });
+
+        Self::choose_protocol_version_and_system_packages(
+            epoch_store.protocol_version(),
+            epoch_store.committee(),
+            epoch_store.get_capabilities(),
+            buffer_stake_bps,
+        )
+    }
+
     fn choose_protocol_version_and_system_packages(
         current_protocol_version: ProtocolVersion,
         committee: &Committee,
@@ -3175,22 +3759,8 @@ impl AuthorityState {
     ) -> anyhow::Result<(SuiSystemState, TransactionEffects)> {
         let next_epoch = epoch_store.epoch() + 1;
 
-        let buffer_stake_bps = epoch_store
-            .get_override_protocol_upgrade_buffer_stake()
-            .tap_some(|b| warn!("using overrided buffer stake value of {}", b))
-            .unwrap_or_else(|| {
-                epoch_store
-                    .protocol_config()
-                    .buffer_stake_for_protocol_upgrade_bps()
-            });
-
         let (next_epoch_protocol_version, next_epoch_system_packages) =
-            Self::choose_protocol_version_and_system_packages(
-                epoch_store.protocol_version(),
-                epoch_store.committee(),
-                epoch_store.get_capabilities(),
-                buffer_stake_bps,
-            );
+            Self::choose_next_epoch_protocol_version_and_system_packages(epoch_store);
 
         // since system packages are created during the current epoch, they should abide by the
         // rules of the current epoch, including the current epoch's max Move binary format version
@@ -3289,6 +3859,72 @@ impl AuthorityState {
         Ok((system_obj, effects))
     }
 
+    /// Simulates the reconfiguration at the end of the current epoch against the current on-chain
+    /// state, without committing anything: chooses the next protocol version and system packages
+    /// from the capabilities received so far, and executes the change epoch transaction to compute
+    /// the next committee and whether the system would enter safe mode. Gas charges and storage
+    /// rebates of the current epoch are not accounted for, so rewards are not simulated.
+    pub async fn dry_run_epoch_change(
+        &self,
+        epoch_store: &Arc<AuthorityPerEpochStore>,
+        supported_protocol_versions: SupportedProtocolVersions,
+    ) -> anyhow::Result<EpochChangeDryRun> {
+        let next_epoch = epoch_store.epoch() + 1;
+        let current_protocol_version = epoch_store.protocol_version();
+        let (next_protocol_version, next_system_packages) =
+            Self::choose_next_epoch_protocol_version_and_system_packages(epoch_store);
+
+        let next_system_package_bytes = self
+            .get_system_package_bytes(
+                next_system_packages.clone(),
+                epoch_store.protocol_config().move_binary_format_version(),
+            )
+            .await;
+        let next_system_packages_available = next_system_package_bytes.is_some();
+
+        let epoch_start_state = epoch_store.epoch_start_state();
+        let tx = VerifiedTransaction::new_change_epoch(
+            next_epoch,
+            next_protocol_version,
+            0,
+            0,
+            0,
+            epoch_start_state.epoch_start_timestamp_ms() + epoch_start_state.epoch_duration_ms(),
+            // Without the packages, the rest of the reconfiguration can still be simulated.
+            next_system_package_bytes.unwrap_or_default(),
+        );
+        let executable_tx = VerifiedExecutableTransaction::new_system(tx, epoch_store.epoch());
+
+        let execution_guard = self
+            .database
+            .execution_lock_for_executable_transaction(&executable_tx)
+            .await?;
+        let (temporary_store, effects) = self
+            .prepare_certificate(&execution_guard, &executable_tx, epoch_store)
+            .await?;
+        if !effects.status().is_ok() {
+            return Err(anyhow!(
+                "change epoch transaction failed: {:?}",
+                effects.status()
+            ));
+        }
+        let system_state = temporary_store
+            .get_sui_system_state_object()
+            .expect("change epoch tx must write to system object");
+
+        Ok(EpochChangeDryRun {
+            next_epoch,
+            current_protocol_version,
+            next_protocol_version,
+            next_system_packages,
+            next_protocol_version_supported: supported_protocol_versions
+                .is_version_supported(next_protocol_version),
+            next_system_packages_available,
+            safe_mode: system_state.safe_mode(),
+            next_committee: system_state.get_current_epoch_committee().committee,
+        })
+    }
+
     /// This function is called at the very end of the epoch.
     /// This step is required before updating new epoch in the db and calling reopen_epoch_db.
     async fn revert_uncommitted_epoch_transactions(
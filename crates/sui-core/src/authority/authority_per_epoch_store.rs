@@ -33,6 +33,10 @@ use typed_store::rocks::{
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+use crate::authority::shared_object_congestion_tracker::{
+    DeferralQueueSummary, DeferredTransaction,
+};
+use crate::authority::transaction_lifecycle::TransactionLifecycleSpans;
 use crate::authority::{AuthorityStore, ResolverWrapper};
 use crate::checkpoints::{
     CheckpointCommitHeight, CheckpointServiceNotify, EpochStats, PendingCheckpoint,
@@ -44,6 +48,7 @@ use crate::consensus_handler::{
 };
 use crate::epoch::epoch_metrics::EpochMetrics;
 use crate::epoch::reconfiguration::ReconfigState;
+use crate::memory_budget::MemoryConsumer;
 use crate::module_cache_metrics::ResolverMetrics;
 use crate::signature_verifier::*;
 use crate::stake_aggregator::StakeAggregator;
@@ -158,11 +163,18 @@ pub struct AuthorityPerEpochStore {
     /// a metric that doesn't have to be available for each epoch, and it's only used during
     /// the last few seconds of an epoch.
     epoch_close_time: RwLock<Option<Instant>>,
+
+    /// Final consensus reputation scores of the last completed schedule. Not persisted, they are
+    /// only used to estimate the impact of downtime.
+    last_schedule_scores: RwLock<HashMap<AuthorityName, u64>>,
     metrics: Arc<EpochMetrics>,
     epoch_start_configuration: Arc<EpochStartConfiguration>,
 
     /// Execution state that has to restart at each epoch change
     execution_component: ExecutionComponents,
+
+    /// Tracing spans of the transactions in flight on this node.
+    transaction_lifecycle: TransactionLifecycleSpans,
 }
 
 /// AuthorityEpochTables contains tables that contain data that is only valid within an epoch.
@@ -286,6 +298,12 @@ pub struct AuthorityEpochTables {
     /// Contains a single key, which overrides the value of
     /// ProtocolConfig::buffer_stake_for_protocol_upgrade_bps
     override_protocol_upgrade_buffer_stake: DBMap<u64, u64>,
+
+    /// Transactions deferred by shared object congestion control, keyed by the consensus commit
+    /// round that deferred them. Each entry holds the complete queue left after that commit.
+    /// The two latest entries are retained, so that a commit which was only partially processed
+    /// before a crash is replayed against the same queue.
+    deferred_transactions: DBMap<Round, Vec<DeferredTransaction>>,
 }
 
 impl AuthorityEpochTables {
@@ -353,13 +371,8 @@ impl AuthorityPerEpochStore {
         let pending_consensus_transactions = tables.get_all_pending_consensus_transactions();
         let pending_consensus_certificates: HashSet<_> = pending_consensus_transactions
             .iter()
-            .filter_map(|transaction| {
-                if let ConsensusTransactionKind::UserTransaction(certificate) = &transaction.kind {
-                    Some(*certificate.digest())
-                } else {
-                    None
-                }
-            })
+            .flat_map(|transaction| transaction.user_certificates())
+            .map(|certificate| *certificate.digest())
             .collect();
         assert_eq!(
             epoch_start_configuration.epoch_start_state().epoch(),
@@ -395,9 +408,11 @@ impl AuthorityPerEpochStore {
             mutex_table: MutexTable::new(MUTEX_TABLE_SIZE),
             epoch_open_time: current_time,
             epoch_close_time: Default::default(),
+            last_schedule_scores: Default::default(),
             metrics,
             epoch_start_configuration,
             execution_component,
+            transaction_lifecycle: TransactionLifecycleSpans::default(),
         })
     }
 
@@ -446,6 +461,10 @@ impl AuthorityPerEpochStore {
         &self.protocol_config
     }
 
+    pub fn transaction_lifecycle(&self) -> &TransactionLifecycleSpans {
+        &self.transaction_lifecycle
+    }
+
     pub fn epoch(&self) -> EpochId {
         self.committee.epoch
     }
@@ -730,11 +749,10 @@ impl AuthorityPerEpochStore {
         // option is to panic. It is extremely unlikely that more than 2 retries will be needed, as
         // the only two writers are the consensus task and checkpoint execution.
         retry_transaction_forever!({
-            // This code may still be correct without using a transaction snapshot, but I couldn't
-            // convince myself of that.
-            let db_transaction = self.tables.next_shared_object_versions.transaction()?;
+            // The versions read are checked not to have changed when committing.
+            let mut db_transaction = self.tables.next_shared_object_versions.write_transaction();
 
-            let next_versions = db_transaction.multi_get(
+            let next_versions = db_transaction.multi_get_for_update(
                 &self.tables.next_shared_object_versions,
                 objects_to_init.clone(),
             )?;
@@ -786,8 +804,8 @@ impl AuthorityPerEpochStore {
                 "initializing next_shared_object_versions"
             );
             db_transaction
-                .insert_batch(&self.tables.next_shared_object_versions, versions_to_write)?
-                .commit()
+                .insert_batch(&self.tables.next_shared_object_versions, versions_to_write)?;
+            db_transaction.commit().await
         })?;
 
         // this case only occurs when there were uninitialized versions, which is rare, so its much
@@ -865,25 +883,39 @@ impl AuthorityPerEpochStore {
         self.tables
             .pending_consensus_transactions
             .insert(&transaction.key(), transaction)?;
-        if let ConsensusTransactionKind::UserTransaction(cert) = &transaction.kind {
+        if transaction.is_user_certificate() {
             let state = lock.expect("Must pass reconfiguration lock when storing certificate");
             // Caller is responsible for performing graceful check
             assert!(
                 state.should_accept_user_certs(),
                 "Reconfiguration state should allow accepting user transactions"
             );
-            self.pending_consensus_certificates
-                .lock()
-                .insert(*cert.digest());
+            self.pending_consensus_certificates.lock().extend(
+                transaction
+                    .user_certificates()
+                    .iter()
+                    .map(|cert| *cert.digest()),
+            );
         }
         Ok(())
     }
 
     pub fn remove_pending_consensus_transaction(&self, key: &ConsensusTransactionKey) -> SuiResult {
-        self.tables.pending_consensus_transactions.remove(key)?;
-        if let ConsensusTransactionKey::Certificate(cert) = key {
-            self.pending_consensus_certificates.lock().remove(cert);
+        match key {
+            ConsensusTransactionKey::Certificate(cert) => {
+                self.pending_consensus_certificates.lock().remove(cert);
+            }
+            ConsensusTransactionKey::CertificateBundle(_) => {
+                if let Some(bundle) = self.tables.pending_consensus_transactions.get(key)? {
+                    let mut pending = self.pending_consensus_certificates.lock();
+                    for cert in bundle.user_certificates() {
+                        pending.remove(cert.digest());
+                    }
+                }
+            }
+            _ => {}
         }
+        self.tables.pending_consensus_transactions.remove(key)?;
         Ok(())
     }
 
@@ -1440,6 +1472,17 @@ impl AuthorityPerEpochStore {
                 kind: ConsensusTransactionKind::UserTransaction(_certificate),
                 ..
             }) => {}
+            SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                kind: ConsensusTransactionKind::UserTransactionBundle(_),
+                ..
+            }) => {
+                // Bundles are expanded into their certificates by the consensus handler.
+                warn!(
+                    "Unexpected soft bundle from {}",
+                    transaction.certificate.origin()
+                );
+                return Err(());
+            }
             SequencedConsensusTransactionKind::External(ConsensusTransaction {
                 kind: ConsensusTransactionKind::CheckpointSignature(data),
                 ..
@@ -1559,6 +1602,12 @@ impl AuthorityPerEpochStore {
 
                 Ok(Some(certificate))
             }
+            SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                kind: ConsensusTransactionKind::UserTransactionBundle(_),
+                ..
+            }) => {
+                unreachable!("Soft bundles are rejected by verify_consensus_transaction")
+            }
             SequencedConsensusTransactionKind::External(ConsensusTransaction {
                 kind: ConsensusTransactionKind::CheckpointSignature(info),
                 ..
@@ -1668,6 +1717,70 @@ impl AuthorityPerEpochStore {
         self.record_checkpoint_boundary(round)
     }
 
+    /// Returns the deferral queue recorded by the latest commit before `round`, together with the
+    /// round of that commit.
+    pub fn load_deferred_transactions(
+        &self,
+        round: Round,
+    ) -> SuiResult<Option<(Round, Vec<DeferredTransaction>)>> {
+        if round == 0 {
+            return Ok(None);
+        }
+        Ok(self
+            .tables
+            .deferred_transactions
+            .iter()
+            .skip_prior_to(&(round - 1))?
+            .reverse()
+            .next())
+    }
+
+    pub fn set_last_schedule_scores(&self, scores: HashMap<AuthorityName, u64>) {
+        *self.last_schedule_scores.write() = scores;
+    }
+
+    /// Final consensus reputation scores of the last completed schedule, empty until a schedule
+    /// completes in this epoch.
+    pub fn get_last_schedule_scores(&self) -> HashMap<AuthorityName, u64> {
+        self.last_schedule_scores.read().clone()
+    }
+
+    /// Summarizes the deferral queue left by the latest commit.
+    pub fn get_deferral_queue_summary(&self) -> DeferralQueueSummary {
+        let latest = self
+            .tables
+            .deferred_transactions
+            .iter()
+            .skip_to_last()
+            .next();
+        let Some((round, queue)) = latest else {
+            return DeferralQueueSummary::default();
+        };
+        DeferralQueueSummary::new(
+            round,
+            &queue,
+            self.protocol_config()
+                .max_txns_per_shared_object_per_commit(),
+        )
+    }
+
+    /// Records the deferral queue left after the commit at `round`, and drops the entries older
+    /// than `previous_round`, the one the queue was loaded from.
+    pub fn write_deferred_transactions(
+        &self,
+        round: Round,
+        previous_round: Option<Round>,
+        deferred: Vec<DeferredTransaction>,
+    ) -> SuiResult {
+        let mut batch = self.tables.deferred_transactions.batch();
+        if let Some(previous_round) = previous_round {
+            batch = batch.delete_range(&self.tables.deferred_transactions, &0, &previous_round)?;
+        }
+        batch = batch.insert_batch(&self.tables.deferred_transactions, [(round, deferred)])?;
+        batch.write()?;
+        Ok(())
+    }
+
     pub fn get_pending_checkpoints(&self) -> Vec<(CheckpointCommitHeight, PendingCheckpoint)> {
         self.tables.pending_checkpoints.iter().collect()
     }
@@ -1875,6 +1988,17 @@ impl AuthorityPerEpochStore {
     }
 }
 
+/// The in-memory state of the epoch store that can shrink is the caches of verified signatures.
+impl MemoryConsumer for AuthorityPerEpochStore {
+    fn memory_usage(&self) -> usize {
+        self.signature_verifier.cache_memory_usage()
+    }
+
+    fn shrink(&self, bytes: usize) -> usize {
+        self.signature_verifier.shrink_caches(bytes)
+    }
+}
+
 fn transactions_table_default_config() -> DBOptions {
     point_lookup_db_options()
 }
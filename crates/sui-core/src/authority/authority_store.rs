@@ -2,9 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::cmp::Ordering;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::iter;
-use std::ops::Not;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -31,14 +30,16 @@ use sui_types::storage::{
 };
 use sui_types::sui_system_state::get_sui_system_state;
 use sui_types::{base_types::SequenceNumber, fp_bail, fp_ensure, storage::ParentSync};
-use typed_store::rocks::{DBBatch, TypedStoreError};
+use typed_store::rocks::{DBBatch, DBTableTuning, TypedStoreError};
 use typed_store::traits::Map;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use crate::authority::authority_store_pruner::ObjectsCompactionFilter;
 use crate::authority::authority_store_types::{
     get_store_object_pair, ObjectContentDigest, StoreObject, StoreObjectPair, StoreObjectWrapper,
 };
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+use crate::authority::execution_cache::ExecutionCache;
 
 use super::{authority_store_tables::AuthorityPerpetualTables, *};
 use mysten_common::sync::notify_read::NotifyRead;
@@ -71,6 +72,12 @@ pub struct AuthorityStore {
     pub(crate) objects_lock_table: Arc<RwLockTable<ObjectContentDigest>>,
 
     indirect_objects_threshold: usize,
+
+    /// Serves hot objects and effects from memory, if configured.
+    execution_cache: Option<Arc<ExecutionCache>>,
+
+    /// Drops old object versions when the objects table is compacted, if the pruner enables it.
+    pub(crate) objects_compaction_filter: ObjectsCompactionFilter,
 }
 
 pub type ExecutionLockReadGuard<'a> = RwLockReadGuard<'a, EpochId>;
@@ -82,11 +89,21 @@ impl AuthorityStore {
     pub async fn open(
         path: &Path,
         db_options: Option<Options>,
+        db_tuning: &BTreeMap<String, DBTableTuning>,
         genesis: &Genesis,
         committee_store: &Arc<CommitteeStore>,
         indirect_objects_threshold: usize,
+        execution_cache: Option<Arc<ExecutionCache>>,
     ) -> SuiResult<Self> {
-        let perpetual_tables = Arc::new(AuthorityPerpetualTables::open(path, db_options.clone()));
+        let objects_compaction_filter = ObjectsCompactionFilter::default();
+        let perpetual_tables = Arc::new(
+            AuthorityPerpetualTables::open_with_objects_compaction_filter(
+                path,
+                db_options.clone(),
+                objects_compaction_filter.clone(),
+                db_tuning,
+            )?,
+        );
         if perpetual_tables.database_is_empty()? {
             let epoch_start_configuration = EpochStartConfiguration::new_v1(
                 genesis.sui_system_object().into_epoch_start_state(),
@@ -103,8 +120,10 @@ impl AuthorityStore {
         Self::open_inner(
             genesis,
             perpetual_tables,
+            objects_compaction_filter,
             &committee,
             indirect_objects_threshold,
+            execution_cache,
         )
         .await
     }
@@ -119,12 +138,22 @@ impl AuthorityStore {
         // TODO: Since we always start at genesis, the committee should be technically the same
         // as the genesis committee.
         assert_eq!(committee.epoch, 0);
-        let perpetual_tables = Arc::new(AuthorityPerpetualTables::open(path, db_options.clone()));
+        let objects_compaction_filter = ObjectsCompactionFilter::default();
+        let perpetual_tables = Arc::new(
+            AuthorityPerpetualTables::open_with_objects_compaction_filter(
+                path,
+                db_options.clone(),
+                objects_compaction_filter.clone(),
+                &BTreeMap::new(),
+            )?,
+        );
         Self::open_inner(
             genesis,
             perpetual_tables,
+            objects_compaction_filter,
             committee,
             indirect_objects_threshold,
+            None,
         )
         .await
     }
@@ -132,8 +161,10 @@ impl AuthorityStore {
     async fn open_inner(
         genesis: &Genesis,
         perpetual_tables: Arc<AuthorityPerpetualTables>,
+        objects_compaction_filter: ObjectsCompactionFilter,
         committee: &Committee,
         indirect_objects_threshold: usize,
+        execution_cache: Option<Arc<ExecutionCache>>,
     ) -> SuiResult<Self> {
         let epoch = committee.epoch;
 
@@ -146,6 +177,8 @@ impl AuthorityStore {
             execution_lock: RwLock::new(epoch),
             objects_lock_table: Arc::new(RwLockTable::new(NUM_SHARDS)),
             indirect_objects_threshold,
+            execution_cache,
+            objects_compaction_filter,
         };
         // Only initialize an empty database.
         if store
@@ -204,7 +237,17 @@ impl AuthorityStore {
         &self,
         effects_digest: &TransactionEffectsDigest,
     ) -> SuiResult<Option<TransactionEffects>> {
-        Ok(self.perpetual_tables.effects.get(effects_digest)?)
+        let Some(cache) = &self.execution_cache else {
+            return Ok(self.perpetual_tables.effects.get(effects_digest)?);
+        };
+        if let Some(effects) = cache.get_effects(effects_digest) {
+            return Ok(Some(effects));
+        }
+        let effects = self.perpetual_tables.effects.get(effects_digest)?;
+        if let Some(effects) = &effects {
+            cache.insert_effects(effects.clone());
+        }
+        Ok(effects)
     }
 
     /// Returns true if we have an effects structure for this transaction digest
@@ -219,15 +262,7 @@ impl AuthorityStore {
         &self,
         event_digest: &TransactionEventsDigest,
     ) -> Result<Option<TransactionEvents>, TypedStoreError> {
-        let data = self
-            .perpetual_tables
-            .events
-            .iter()
-            .skip_to(&(*event_digest, 0))?
-            .take_while(|((digest, _), _)| digest == event_digest)
-            .map(|(_, e)| e)
-            .collect::<Vec<_>>();
-        Ok(data.is_empty().not().then_some(TransactionEvents { data }))
+        self.perpetual_tables.get_events(event_digest)
     }
 
     pub fn multi_get_events(
@@ -244,16 +279,62 @@ impl AuthorityStore {
         &self,
         effects_digests: impl Iterator<Item = &'a TransactionEffectsDigest>,
     ) -> SuiResult<Vec<Option<TransactionEffects>>> {
-        Ok(self.perpetual_tables.effects.multi_get(effects_digests)?)
+        let Some(cache) = &self.execution_cache else {
+            return Ok(self.perpetual_tables.effects.multi_get(effects_digests)?);
+        };
+        let mut result: Vec<_> = effects_digests
+            .map(|digest| (digest, cache.get_effects(digest)))
+            .collect();
+        let missing: Vec<_> = result
+            .iter()
+            .filter(|(_, effects)| effects.is_none())
+            .map(|(digest, _)| *digest)
+            .collect();
+        let mut fetched = self
+            .perpetual_tables
+            .effects
+            .multi_get(missing.iter().copied())?
+            .into_iter();
+        Ok(result
+            .iter_mut()
+            .map(|(_, effects)| match effects.take() {
+                Some(effects) => Some(effects),
+                None => {
+                    let effects = fetched.next().flatten();
+                    if let Some(effects) = &effects {
+                        cache.insert_effects(effects.clone());
+                    }
+                    effects
+                }
+            })
+            .collect())
+    }
+
+    /// Digest of the effects of an executed transaction.
+    fn get_executed_effects_digest(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> SuiResult<Option<TransactionEffectsDigest>> {
+        let Some(cache) = &self.execution_cache else {
+            return Ok(self.perpetual_tables.executed_effects.get(tx_digest)?);
+        };
+        if let Some(digest) = cache.get_executed_effects_digest(tx_digest) {
+            return Ok(Some(digest));
+        }
+        let generation = cache.generation();
+        let digest = self.perpetual_tables.executed_effects.get(tx_digest)?;
+        if let Some(digest) = digest {
+            cache.insert_executed_effects_digest(*tx_digest, digest, generation);
+        }
+        Ok(digest)
     }
 
     pub fn get_executed_effects(
         &self,
         tx_digest: &TransactionDigest,
     ) -> SuiResult<Option<TransactionEffects>> {
-        let effects_digest = self.perpetual_tables.executed_effects.get(tx_digest)?;
-        match effects_digest {
-            Some(digest) => Ok(self.perpetual_tables.effects.get(&digest)?),
+        match self.get_executed_effects_digest(tx_digest)? {
+            Some(digest) => self.get_effects(&digest),
             None => Ok(None),
         }
     }
@@ -278,12 +359,26 @@ impl AuthorityStore {
     }
 
     pub fn is_tx_already_executed(&self, digest: &TransactionDigest) -> SuiResult<bool> {
+        if let Some(cache) = &self.execution_cache {
+            if cache.get_executed_effects_digest(digest).is_some() {
+                return Ok(true);
+            }
+        }
         Ok(self
             .perpetual_tables
             .executed_effects
             .contains_key(digest)?)
     }
 
+    /// The root state accumulator of `epoch`, and the last checkpoint of the epoch, if the epoch
+    /// was accumulated.
+    pub fn get_root_state_accumulator(
+        &self,
+        epoch: EpochId,
+    ) -> SuiResult<Option<(CheckpointSequenceNumber, Accumulator)>> {
+        Ok(self.perpetual_tables.root_state_hash_by_epoch.get(&epoch)?)
+    }
+
     /// Returns future containing the state hash for the given epoch
     /// once available
     pub async fn notify_read_root_state_hash(
@@ -372,13 +467,25 @@ impl AuthorityStore {
         object_id: &ObjectID,
         version: VersionNumber,
     ) -> Result<Option<Object>, SuiError> {
-        Ok(self
+        let key = ObjectKey(*object_id, version);
+        if let Some(object) = self
+            .execution_cache
+            .as_ref()
+            .and_then(|cache| cache.get_object_by_key(&key))
+        {
+            return Ok(Some(object));
+        }
+        let object = self
             .perpetual_tables
             .objects
-            .get(&ObjectKey(*object_id, version))?
+            .get(&key)?
             .map(|object| self.perpetual_tables.object(object))
             .transpose()?
-            .flatten())
+            .flatten();
+        if let (Some(cache), Some(object)) = (&self.execution_cache, &object) {
+            cache.insert_object(object.clone());
+        }
+        Ok(object)
     }
 
     pub fn get_object_ref_prior_to_key(
@@ -409,15 +516,40 @@ impl AuthorityStore {
         &self,
         object_keys: &[ObjectKey],
     ) -> Result<Vec<Option<Object>>, SuiError> {
-        let wrappers = self.perpetual_tables.objects.multi_get(object_keys)?;
+        let cached: Vec<_> = match &self.execution_cache {
+            Some(cache) => object_keys
+                .iter()
+                .map(|key| cache.get_object_by_key(key))
+                .collect(),
+            None => vec![None; object_keys.len()],
+        };
+        let missing = object_keys
+            .iter()
+            .zip(&cached)
+            .filter(|(_, object)| object.is_none())
+            .map(|(key, _)| key);
+        let mut wrappers = self
+            .perpetual_tables
+            .objects
+            .multi_get(missing)?
+            .into_iter();
         let mut ret = vec![];
 
-        for w in wrappers {
-            ret.push(
-                w.map(|object| self.perpetual_tables.object(object))
-                    .transpose()?
-                    .flatten(),
-            );
+        for object in cached {
+            if object.is_some() {
+                ret.push(object);
+                continue;
+            }
+            let object = wrappers
+                .next()
+                .flatten()
+                .map(|object| self.perpetual_tables.object(object))
+                .transpose()?
+                .flatten();
+            if let (Some(cache), Some(object)) = (&self.execution_cache, &object) {
+                cache.insert_object(object.clone());
+            }
+            ret.push(object);
         }
         Ok(ret)
     }
@@ -629,6 +761,9 @@ impl AuthorityStore {
         }
 
         write_batch.write()?;
+        if let Some(cache) = &self.execution_cache {
+            cache.invalidate_object(&object_ref.0);
+        }
 
         Ok(())
     }
@@ -704,6 +839,10 @@ impl AuthorityStore {
         let _locks = self
             .acquire_read_locks_for_indirect_objects(&inner_temporary_store)
             .await;
+        let cached_writes = self
+            .execution_cache
+            .as_ref()
+            .map(|_| Self::cached_writes(&inner_temporary_store));
         // Extract the new state from the execution
         let mut write_batch = self.perpetual_tables.transactions.batch();
 
@@ -737,6 +876,11 @@ impl AuthorityStore {
         // Commit.
         write_batch.write()?;
 
+        if let (Some(cache), Some((objects, latest_refs))) = (&self.execution_cache, cached_writes)
+        {
+            cache.stage_write(*transaction_digest, effects.clone(), objects, latest_refs);
+        }
+
         // test crashing before notifying
         fail_point_async!("crash");
 
@@ -746,6 +890,35 @@ impl AuthorityStore {
         Ok(())
     }
 
+    /// Objects written by a transaction, and the latest references of the objects it wrote,
+    /// deleted or wrapped.
+    fn cached_writes(inner_temporary_store: &InnerTemporaryStore) -> (Vec<Object>, Vec<ObjectRef>) {
+        let objects = inner_temporary_store
+            .written
+            .values()
+            .map(|(_, object, _)| object.clone())
+            .collect();
+        let latest_refs = inner_temporary_store
+            .written
+            .values()
+            .map(|(object_ref, _, _)| *object_ref)
+            .chain(
+                inner_temporary_store
+                    .deleted
+                    .iter()
+                    .map(|(object_id, (version, kind))| {
+                        let digest = if *kind == DeleteKind::Wrap {
+                            ObjectDigest::OBJECT_DIGEST_WRAPPED
+                        } else {
+                            ObjectDigest::OBJECT_DIGEST_DELETED
+                        };
+                        (*object_id, *version, digest)
+                    }),
+            )
+            .collect();
+        (objects, latest_refs)
+    }
+
     /// Acquires read locks for affected indirect objects
     async fn acquire_read_locks_for_indirect_objects(
         &self,
@@ -1242,6 +1415,9 @@ impl AuthorityStore {
         )?;
 
         write_batch.write()?;
+        if let Some(cache) = &self.execution_cache {
+            cache.invalidate_transaction(&effects);
+        }
 
         Ok(())
     }
@@ -1272,7 +1448,18 @@ impl AuthorityStore {
         &self,
         object_id: ObjectID,
     ) -> Result<Option<ObjectRef>, SuiError> {
-        self.perpetual_tables.get_object_or_tombstone(object_id)
+        let Some(cache) = &self.execution_cache else {
+            return self.perpetual_tables.get_object_or_tombstone(object_id);
+        };
+        if let Some(object_ref) = cache.get_latest_ref(&object_id) {
+            return Ok(Some(object_ref));
+        }
+        let generation = cache.generation();
+        let object_ref = self.perpetual_tables.get_object_or_tombstone(object_id)?;
+        if let Some(object_ref) = object_ref {
+            cache.insert_latest_ref(object_ref, generation);
+        }
+        Ok(object_ref)
     }
 
     pub fn insert_transaction_and_effects(
@@ -1384,7 +1571,23 @@ impl BackingPackageStore for AuthorityStore {
 impl ObjectStore for AuthorityStore {
     /// Read an object and return it, or Ok(None) if the object was not found.
     fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
-        self.perpetual_tables.as_ref().get_object(object_id)
+        let Some(cache) = &self.execution_cache else {
+            return self.perpetual_tables.as_ref().get_object(object_id);
+        };
+        if let Some(object_ref) = cache.get_latest_ref(object_id) {
+            if !object_ref.2.is_alive() {
+                return Ok(None);
+            }
+            return self.get_object_by_key(object_id, object_ref.1);
+        }
+        let generation = cache.generation();
+        let object = self.perpetual_tables.as_ref().get_object(object_id)?;
+        // Tombstones are cached by `get_object_or_tombstone`, which reads the reference.
+        if let Some(object) = &object {
+            cache.insert_latest_ref(object.compute_object_reference(), generation);
+            cache.insert_object(object.clone());
+        }
+        Ok(object)
     }
 }
 
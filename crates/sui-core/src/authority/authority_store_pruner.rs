@@ -1,11 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::authority::authority_store_types::{ObjectContentDigest, StoreData, StoreObject};
+use crate::authority::authority_store_types::{
+    ObjectContentDigest, StoreData, StoreObject, StoreObjectWrapper,
+};
 use crate::checkpoints::CheckpointStore;
 use mysten_metrics::monitored_scope;
+use parking_lot::RwLock;
 use std::cmp::{max, min};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{sync::Arc, time::Duration};
 use sui_config::node::AuthorityStorePruningConfig;
 use sui_storage::mutex_table::RwLockTable;
@@ -26,10 +29,81 @@ pub struct AuthorityStorePruner {
     _objects_pruner_cancel_handle: oneshot::Sender<()>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 enum DeletionMethod {
     RangeDelete,
     PointDelete,
+    /// Old versions are left to the `ObjectsCompactionFilter`, but for those it doesn't drop.
+    CompactionFilter(ObjectsCompactionFilter),
+}
+
+/// Number of objects whose pruned versions are left to the compaction filter, past which the
+/// pruner deletes them itself so that the published versions stay bounded in memory.
+const MAX_OBJECTS_LEFT_TO_COMPACTION: usize = 100_000;
+
+/// The compaction filter of the objects table, dropping the object versions pruned by a pruner
+/// using `DeletionMethod::CompactionFilter`.
+///
+/// The pruner publishes in memory the range of versions it pruned of each object, and a version is
+/// dropped when it is no higher than the highest one published for its object, so that the filter
+/// decides from the compacted entry alone without reading the database. Versions holding an
+/// indirect object, whose reference count must be decremented, and versions superseded by the
+/// deletion or wrapping of the object are still deleted by the pruner.
+///
+/// The pruner range deletes the published versions once more than `MAX_OBJECTS_LEFT_TO_COMPACTION`
+/// objects are pending and when it stops. Versions published before a crash are left until a
+/// higher version of their object is pruned.
+#[derive(Clone, Debug, Default)]
+pub struct ObjectsCompactionFilter {
+    pruned_versions: Arc<RwLock<HashMap<ObjectID, (VersionNumber, VersionNumber)>>>,
+}
+
+impl ObjectsCompactionFilter {
+    /// Whether the version `key` of an object, stored as `object`, is dropped when compacted.
+    pub(crate) fn filter(
+        &self,
+        ObjectKey(object_id, version): ObjectKey,
+        object: StoreObjectWrapper,
+    ) -> bool {
+        Self::may_drop(&object)
+            && self
+                .pruned_versions
+                .read()
+                .get(&object_id)
+                .map_or(false, |(_, highest)| version <= *highest)
+    }
+
+    fn may_drop(object: &StoreObjectWrapper) -> bool {
+        match object.inner() {
+            StoreObject::Value(value) => !matches!(value.data, StoreData::IndirectObject(_)),
+            StoreObject::Deleted | StoreObject::Wrapped => false,
+        }
+    }
+
+    /// Publishes pruned versions, returning all the pending versions for the pruner to delete once
+    /// too many objects are pending.
+    fn publish(
+        &self,
+        keys: impl IntoIterator<Item = ObjectKey>,
+    ) -> Vec<(ObjectID, (VersionNumber, VersionNumber))> {
+        let mut pruned_versions = self.pruned_versions.write();
+        for ObjectKey(object_id, version) in keys {
+            pruned_versions
+                .entry(object_id)
+                .and_modify(|range| *range = (min(range.0, version), max(range.1, version)))
+                .or_insert((version, version));
+        }
+        if pruned_versions.len() > MAX_OBJECTS_LEFT_TO_COMPACTION {
+            pruned_versions.drain().collect()
+        } else {
+            vec![]
+        }
+    }
+
+    /// Takes all the pending versions, for the pruner to delete them when it stops.
+    fn take(&self) -> Vec<(ObjectID, (VersionNumber, VersionNumber))> {
+        self.pruned_versions.write().drain().collect()
+    }
 }
 
 impl AuthorityStorePruner {
@@ -50,21 +124,20 @@ impl AuthorityStorePruner {
                 object_keys_to_prune.push(ObjectKey(*object_id, *seq_number));
             }
         }
-        let mut indirect_objects: HashMap<_, i64> = HashMap::new();
-        for object in perpetual_db
+        let objects = perpetual_db
             .objects
-            .multi_get(object_keys_to_prune.iter())?
-            .into_iter()
-            .flatten()
-        {
-            if let StoreObject::Value(obj) = object.into_inner() {
+            .multi_get(object_keys_to_prune.iter())?;
+        let mut indirect_objects: HashMap<_, i64> = HashMap::new();
+        for object in objects.iter().flatten() {
+            if let StoreObject::Value(obj) = object.inner() {
                 if let StoreData::IndirectObject(indirect_object) = obj.data {
                     *indirect_objects.entry(indirect_object.digest).or_default() -= 1;
                 }
             }
         }
 
-        match deletion_method {
+        let mut keys_left_to_compaction = vec![];
+        match &deletion_method {
             DeletionMethod::RangeDelete => {
                 let mut updates: HashMap<ObjectID, (VersionNumber, VersionNumber)> = HashMap::new();
                 for effects in transaction_effects {
@@ -86,6 +159,25 @@ impl AuthorityStorePruner {
             DeletionMethod::PointDelete => {
                 wb = wb.delete_batch(&perpetual_db.objects, object_keys_to_prune)?;
             }
+            DeletionMethod::CompactionFilter(_) => {
+                let deleted: HashSet<ObjectID> = transaction_effects
+                    .iter()
+                    .flat_map(|effects| effects.deleted().iter().chain(effects.wrapped()))
+                    .map(|(object_id, _, _)| *object_id)
+                    .collect();
+                let (keys_to_delete, keys_to_filter): (Vec<_>, Vec<_>) = object_keys_to_prune
+                    .into_iter()
+                    .zip(objects)
+                    .filter_map(|(key, object)| Some((key, object?)))
+                    .partition(|(key, object)| {
+                        deleted.contains(&key.0) || !ObjectsCompactionFilter::may_drop(object)
+                    });
+                wb = wb.delete_batch(
+                    &perpetual_db.objects,
+                    keys_to_delete.into_iter().map(|(key, _)| key),
+                )?;
+                keys_left_to_compaction = keys_to_filter.into_iter().map(|(key, _)| key).collect();
+            }
         }
         if !indirect_objects.is_empty() {
             let ref_count_update = indirect_objects
@@ -99,6 +191,27 @@ impl AuthorityStorePruner {
             .acquire_locks(indirect_objects.into_keys())
             .await;
         wb.write()?;
+        // Only published once the pruned checkpoint is persisted.
+        if let DeletionMethod::CompactionFilter(filter) = deletion_method {
+            Self::delete_version_ranges(perpetual_db, filter.publish(keys_left_to_compaction))?;
+        }
+        Ok(())
+    }
+
+    fn delete_version_ranges(
+        perpetual_db: &AuthorityPerpetualTables,
+        ranges: Vec<(ObjectID, (VersionNumber, VersionNumber))>,
+    ) -> anyhow::Result<()> {
+        if ranges.is_empty() {
+            return Ok(());
+        }
+        let mut wb = perpetual_db.objects.batch();
+        for (object_id, (min_version, max_version)) in ranges {
+            let start_range = ObjectKey(object_id, min_version);
+            let end_range = ObjectKey(object_id, (max_version.value() + 1).into());
+            wb = wb.delete_range(&perpetual_db.objects, &start_range, &end_range)?;
+        }
+        wb.write()?;
         Ok(())
     }
 
@@ -107,9 +220,12 @@ impl AuthorityStorePruner {
         perpetual_db: &Arc<AuthorityPerpetualTables>,
         checkpoint_store: &Arc<CheckpointStore>,
         objects_lock_table: &Arc<RwLockTable<ObjectContentDigest>>,
+        objects_compaction_filter: &ObjectsCompactionFilter,
         config: AuthorityStorePruningConfig,
     ) -> anyhow::Result<()> {
-        let deletion_method = if config.use_range_deletion {
+        let deletion_method = if config.use_compaction_filter {
+            DeletionMethod::CompactionFilter(objects_compaction_filter.clone())
+        } else if config.use_range_deletion {
             DeletionMethod::RangeDelete
         } else {
             DeletionMethod::PointDelete
@@ -129,8 +245,7 @@ impl AuthorityStorePruner {
         );
         let iter = checkpoint_store
             .certified_checkpoints
-            .iter()
-            .skip_to(&(checkpoint_number + 1))?
+            .range_iter(checkpoint_number + 1..)?
             .map(|(k, ckpt)| (k, ckpt.into_inner()));
 
         #[allow(clippy::explicit_counter_loop)]
@@ -166,7 +281,7 @@ impl AuthorityStorePruner {
                     perpetual_db,
                     objects_lock_table,
                     checkpoint_number,
-                    deletion_method,
+                    deletion_method.clone(),
                 )
                 .await?;
                 batch_effects = vec![];
@@ -196,6 +311,7 @@ impl AuthorityStorePruner {
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
         objects_lock_table: Arc<RwLockTable<ObjectContentDigest>>,
+        objects_compaction_filter: ObjectsCompactionFilter,
     ) -> Sender<()> {
         let (sender, mut recv) = tokio::sync::oneshot::channel();
         debug!(
@@ -216,13 +332,21 @@ impl AuthorityStorePruner {
             loop {
                 tokio::select! {
                     _ = prune_interval.tick(), if config.num_epochs_to_retain != u64::MAX => {
-                        if let Err(err) = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, config).await {
+                        if let Err(err) = Self::prune_objects_for_eligible_epochs(&perpetual_db, &checkpoint_store, &objects_lock_table, &objects_compaction_filter, config).await {
                             error!("Failed to prune objects: {:?}", err);
                         }
                     },
                     _ = &mut recv => break,
                 }
             }
+            if let Err(err) =
+                Self::delete_version_ranges(&perpetual_db, objects_compaction_filter.take())
+            {
+                error!(
+                    "Failed to delete the versions left to compaction: {:?}",
+                    err
+                );
+            }
         });
         sender
     }
@@ -230,6 +354,7 @@ impl AuthorityStorePruner {
         perpetual_db: Arc<AuthorityPerpetualTables>,
         checkpoint_store: Arc<CheckpointStore>,
         objects_lock_table: Arc<RwLockTable<ObjectContentDigest>>,
+        objects_compaction_filter: &ObjectsCompactionFilter,
         pruning_config: AuthorityStorePruningConfig,
         epoch_duration_ms: u64,
     ) -> Self {
@@ -240,6 +365,7 @@ impl AuthorityStorePruner {
                 perpetual_db,
                 checkpoint_store,
                 objects_lock_table,
+                objects_compaction_filter.clone(),
             ),
         }
     }
@@ -251,10 +377,13 @@ mod tests {
     use more_asserts as ma;
     use std::path::Path;
     use std::time::Duration;
-    use std::{collections::HashSet, sync::Arc};
+    use std::{
+        collections::{BTreeMap, HashSet},
+        sync::Arc,
+    };
     use tracing::log::{error, info};
 
-    use crate::authority::authority_store_pruner::DeletionMethod;
+    use crate::authority::authority_store_pruner::{DeletionMethod, ObjectsCompactionFilter};
     use crate::authority::authority_store_tables::AuthorityPerpetualTables;
     use crate::authority::authority_store_types::{
         get_store_object_pair, ObjectContentDigest, StoreData, StoreObject, StoreObjectPair,
@@ -485,6 +614,78 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_compaction_filter_pruning() -> Result<(), anyhow::Error> {
+        let path = tempfile::tempdir()?.into_path();
+        let filter = ObjectsCompactionFilter::default();
+        let perpetual_db = Arc::new(
+            AuthorityPerpetualTables::open_with_objects_compaction_filter(
+                &path,
+                None,
+                filter.clone(),
+                &BTreeMap::new(),
+            )?,
+        );
+        let (pruned_id, retained_id) = (ObjectID::random(), ObjectID::random());
+        for id in [pruned_id, retained_id] {
+            for version in [1, 2, 3] {
+                let obj = get_store_object_pair(Object::immutable_with_id_for_testing(id), 0).0;
+                perpetual_db
+                    .objects
+                    .insert(&ObjectKey(id, SequenceNumber::from(version)), &obj)?;
+            }
+        }
+        let start = ObjectKey(ObjectID::ZERO, SequenceNumber::MIN);
+        let end = ObjectKey(ObjectID::MAX, SequenceNumber::MAX);
+        let versions = |id| {
+            perpetual_db
+                .objects
+                .keys()
+                .filter(|key| key.0 == id)
+                .map(|key| key.1.value())
+                .collect::<Vec<_>>()
+        };
+
+        // Nothing is dropped before the pruner publishes pruned versions
+        perpetual_db.objects.compact_range(&start, &end)?;
+        assert_eq!(perpetual_db.objects.keys().count(), 6);
+
+        // Version 2 of one object is pruned, version 1 below it is superseded as well
+        let mut effects = TransactionEffects::default();
+        *effects.modified_at_versions_mut_for_testing() =
+            vec![(pruned_id, SequenceNumber::from(2))];
+        AuthorityStorePruner::prune_effects(
+            vec![effects],
+            &perpetual_db,
+            &lock_table(),
+            0,
+            DeletionMethod::CompactionFilter(filter.clone()),
+        )
+        .await?;
+        // The pruner left the versions to the compaction filter
+        assert_eq!(versions(pruned_id), vec![1, 2, 3]);
+        perpetual_db.objects.compact_range(&start, &end)?;
+        assert_eq!(versions(pruned_id), vec![3]);
+        assert_eq!(versions(retained_id), vec![1, 2, 3]);
+
+        // The pending versions are deleted by the pruner when it stops
+        let mut effects = TransactionEffects::default();
+        *effects.modified_at_versions_mut_for_testing() =
+            vec![(retained_id, SequenceNumber::from(1))];
+        AuthorityStorePruner::prune_effects(
+            vec![effects],
+            &perpetual_db,
+            &lock_table(),
+            1,
+            DeletionMethod::CompactionFilter(filter.clone()),
+        )
+        .await?;
+        AuthorityStorePruner::delete_version_ranges(&perpetual_db, filter.take())?;
+        perpetual_db.objects.compact_range(&start, &end)?;
+        assert_eq!(versions(retained_id), vec![2, 3]);
+        Ok(())
+    }
+
     #[cfg(not(target_env = "msvc"))]
     #[tokio::test]
     async fn test_db_size_after_compaction() -> Result<(), anyhow::Error> {
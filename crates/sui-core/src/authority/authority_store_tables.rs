@@ -4,22 +4,28 @@
 use super::*;
 use crate::authority::authority_store::LockDetailsWrapper;
 use rocksdb::Options;
+use std::collections::BTreeMap;
+use std::ops::Not;
 use std::path::Path;
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::SequenceNumber;
 use sui_types::digests::TransactionEventsDigest;
 use sui_types::storage::ObjectStore;
 use typed_store::metrics::SamplingInterval;
+use typed_store::rocks::migration::SchemaMigrations;
 use typed_store::rocks::util::{empty_compaction_filter, reference_count_merge_operator};
 use typed_store::rocks::{
-    point_lookup_db_options, DBBatch, DBMap, DBOptions, MetricConf, ReadWriteOptions,
+    point_lookup_db_options, DBBatch, DBMap, DBOptions, DBTableTuning, MetricConf,
+    ReadWriteOptions, TypedStoreError,
 };
 use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
 
+use crate::authority::authority_store_pruner::ObjectsCompactionFilter;
 use crate::authority::authority_store_types::{
     MigratedStoreObjectPair, ObjectContentDigest, StoreData, StoreMoveObjectWrapper, StoreObject,
     StoreObjectValue, StoreObjectWrapper,
 };
+use crate::authority::checkpoint_history_pruner::PrunedHistory;
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
 use typed_store_derive::DBMapUtils;
 
@@ -56,7 +62,8 @@ pub struct AuthorityPerpetualTables {
 
     /// This is a map between the transaction digest and the corresponding transaction that's known to be
     /// executable. This means that it may have been executed locally, or it may have been synced through
-    /// state-sync but hasn't been executed yet.
+    /// state-sync but hasn't been executed yet. Fullnodes configured with a transactions retention
+    /// prune this table, see CheckpointHistoryPruner.
     #[default_options_override_fn = "transactions_table_default_config"]
     pub(crate) transactions: DBMap<TransactionDigest, TrustedTransaction>,
 
@@ -79,7 +86,7 @@ pub struct AuthorityPerpetualTables {
     // Currently this is needed in the validator for returning events during process certificates.
     // We could potentially remove this if we decided not to provide events in the execution path.
     // TODO: Figure out what to do with this table in the long run.
-    // Also we need a pruning policy for this table. We can prune this table along with tx/effects.
+    // Fullnodes configured with an events retention prune this table, see CheckpointHistoryPruner.
     pub(crate) events: DBMap<(TransactionEventsDigest, usize), Event>,
 
     /// When transaction is executed via checkpoint executor, we store association here
@@ -97,6 +104,24 @@ pub struct AuthorityPerpetualTables {
 
     /// A singleton table that stores latest pruned checkpoint. Used to keep objects pruner progress
     pub(crate) pruned_checkpoint: DBMap<(), CheckpointSequenceNumber>,
+
+    /// Highest checkpoint whose history of each kind was pruned. Used to keep checkpoint history
+    /// pruner progress
+    pub(crate) pruned_history: DBMap<PrunedHistory, CheckpointSequenceNumber>,
+}
+
+fn construct_object(
+    indirect_move_objects: &DBMap<ObjectContentDigest, StoreMoveObjectWrapper>,
+    store_object: StoreObjectValue,
+) -> Result<Object, SuiError> {
+    let indirect_object = match store_object.data {
+        StoreData::IndirectObject(ref metadata) => indirect_move_objects
+            .get(&metadata.digest)?
+            .map(|o| o.migrate().into_inner()),
+        _ => None,
+    };
+    let object = MigratedStoreObjectPair(store_object, indirect_object).try_into()?;
+    Ok(object)
 }
 
 impl AuthorityPerpetualTables {
@@ -104,7 +129,17 @@ impl AuthorityPerpetualTables {
         parent_path.join("perpetual")
     }
 
+    /// The migrations of the schema of the tables, run before they are opened by the node. Changes
+    /// which tables written by an earlier release can't be read with, such as dropping a table or
+    /// changing the format of its keys, come with a step here.
+    fn migrations() -> SchemaMigrations {
+        SchemaMigrations::new()
+    }
+
     pub fn open(parent_path: &Path, db_options: Option<Options>) -> Self {
+        Self::migrations()
+            .run(&Self::path(parent_path), db_options.clone(), None)
+            .expect("Failed to migrate the perpetual tables");
         Self::open_tables_read_write(
             Self::path(parent_path),
             MetricConf::with_sampling(SamplingInterval::new(Duration::from_secs(60), 0)),
@@ -113,6 +148,35 @@ impl AuthorityPerpetualTables {
         )
     }
 
+    /// Opens the tables with `objects_compaction_filter` dropping old object versions of the
+    /// objects table, once enabled by the pruner.
+    pub fn open_with_objects_compaction_filter(
+        parent_path: &Path,
+        db_options: Option<Options>,
+        objects_compaction_filter: ObjectsCompactionFilter,
+        db_tuning: &BTreeMap<String, DBTableTuning>,
+    ) -> Result<Self, TypedStoreError> {
+        let mut config = Self::configurator();
+        config
+            .objects
+            .set_compaction_filter("objects compaction filter", move |key, object| {
+                objects_compaction_filter.filter(key, object)
+            });
+        let mut tables_db_options = config.build();
+        tables_db_options.apply_tuning(db_tuning)?;
+        Self::migrations().run(
+            &Self::path(parent_path),
+            db_options.clone(),
+            Some(&tables_db_options),
+        )?;
+        Ok(Self::open_tables_read_write(
+            Self::path(parent_path),
+            MetricConf::with_sampling(SamplingInterval::new(Duration::from_secs(60), 0)),
+            db_options,
+            Some(tables_db_options),
+        ))
+    }
+
     pub fn open_readonly(parent_path: &Path) -> AuthorityPerpetualTablesReadOnly {
         Self::get_read_only_handle(Self::path(parent_path), None, None, MetricConf::default())
     }
@@ -136,15 +200,7 @@ impl AuthorityPerpetualTables {
     }
 
     fn construct_object(&self, store_object: StoreObjectValue) -> Result<Object, SuiError> {
-        let indirect_object = match store_object.data {
-            StoreData::IndirectObject(ref metadata) => self
-                .indirect_move_objects
-                .get(&metadata.digest)?
-                .map(|o| o.migrate().into_inner()),
-            _ => None,
-        };
-        let object = MigratedStoreObjectPair(store_object, indirect_object).try_into()?;
-        Ok(object)
+        construct_object(&self.indirect_move_objects, store_object)
     }
 
     // Constructs `sui_types::object::Object` from `StoreObjectWrapper`.
@@ -226,6 +282,46 @@ impl AuthorityPerpetualTables {
         Ok(wb.insert_batch(&self.pruned_checkpoint, [((), checkpoint_number)])?)
     }
 
+    pub fn get_highest_pruned_history_checkpoint(
+        &self,
+        kind: PrunedHistory,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        Ok(self.pruned_history.get(&kind)?)
+    }
+
+    /// Lowest checkpoint whose history of the given kind has not been pruned.
+    pub fn get_lowest_available_history_checkpoint(
+        &self,
+        kind: PrunedHistory,
+    ) -> SuiResult<CheckpointSequenceNumber> {
+        Ok(self
+            .get_highest_pruned_history_checkpoint(kind)?
+            .map_or(0, |pruned| pruned + 1))
+    }
+
+    pub fn set_highest_pruned_history_checkpoint(
+        &self,
+        wb: DBBatch,
+        kind: PrunedHistory,
+        checkpoint_number: CheckpointSequenceNumber,
+    ) -> SuiResult<DBBatch> {
+        Ok(wb.insert_batch(&self.pruned_history, [(kind, checkpoint_number)])?)
+    }
+
+    pub fn get_events(
+        &self,
+        event_digest: &TransactionEventsDigest,
+    ) -> Result<Option<TransactionEvents>, TypedStoreError> {
+        let data = self
+            .events
+            .iter()
+            .skip_to(&(*event_digest, 0))?
+            .take_while(|((digest, _), _)| digest == event_digest)
+            .map(|(_, e)| e)
+            .collect::<Vec<_>>();
+        Ok(data.is_empty().not().then_some(TransactionEvents { data }))
+    }
+
     pub fn database_is_empty(&self) -> SuiResult<bool> {
         Ok(self
             .objects
@@ -267,6 +363,77 @@ impl ObjectStore for AuthorityPerpetualTables {
     }
 }
 
+/// Historical lookups for offline tools, such as transaction replay, that read the database of a
+/// node that is not running.
+impl AuthorityPerpetualTablesReadOnly {
+    /// Returns the object at exactly `version`, or `None` if that version was never written, was
+    /// pruned, or is a tombstone.
+    pub fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        match self.objects.get(&ObjectKey(*object_id, version))? {
+            Some(store_object) => self.object(store_object),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the reference of the latest entry of `object_id` at or below `version`, which is a
+    /// tombstone if the object was deleted or wrapped, together with the object if it exists.
+    pub fn get_latest_entry_lt_or_eq_version(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<(ObjectRef, Option<Object>)>> {
+        let entry = self
+            .objects
+            .iter()
+            .skip_prior_to(&ObjectKey(*object_id, version))?
+            .next();
+        let Some((object_key, store_object)) = entry else {
+            return Ok(None);
+        };
+        if object_key.0 != *object_id || object_key.1 > version {
+            return Ok(None);
+        }
+        Ok(Some(match store_object.migrate().into_inner() {
+            StoreObject::Value(store_object) => {
+                let object = construct_object(&self.indirect_move_objects, store_object)?;
+                (object.compute_object_reference(), Some(object))
+            }
+            StoreObject::Deleted => (
+                (
+                    object_key.0,
+                    object_key.1,
+                    ObjectDigest::OBJECT_DIGEST_DELETED,
+                ),
+                None,
+            ),
+            StoreObject::Wrapped => (
+                (
+                    object_key.0,
+                    object_key.1,
+                    ObjectDigest::OBJECT_DIGEST_WRAPPED,
+                ),
+                None,
+            ),
+        }))
+    }
+
+    /// Returns the object of an entry of the `objects` table, reading its contents from
+    /// `indirect_move_objects` if stored there, or `None` if the entry is a tombstone.
+    pub fn object(&self, store_object: StoreObjectWrapper) -> SuiResult<Option<Object>> {
+        let StoreObject::Value(store_object) = store_object.migrate().into_inner() else {
+            return Ok(None);
+        };
+        Ok(Some(construct_object(
+            &self.indirect_move_objects,
+            store_object,
+        )?))
+    }
+}
+
 pub struct LiveSetIter<'a> {
     iter:
         <DBMap<ObjectKey, StoreObjectWrapper> as Map<'a, ObjectKey, StoreObjectWrapper>>::Iterator,
@@ -11,6 +11,7 @@ use sui_types::crypto::{default_hash, Signable};
 use sui_types::error::SuiError;
 use sui_types::move_package::MovePackage;
 use sui_types::object::{Data, MoveObject, Object, Owner};
+use typed_store::rocks::migration::VersionedValue;
 
 pub type ObjectContentDigest = ObjectDigest;
 
@@ -36,6 +37,13 @@ pub type ObjectContentDigest = ObjectDigest;
 // - If it is not, use the migration function (as explained above) to migrate it to the next
 //   version.
 // - Repeat until we have arrive at the current version.
+// - Bump `VersionedValue::LATEST_VERSION` for `StoreObjectWrapper`, and register a
+//   `MigrationStep::migrate_values` step of the objects table in
+//   `AuthorityPerpetualTables::migrations`, so that older values are rewritten before the node
+//   opens the table.
+//
+// Other changes to the tables, such as to the format of their keys, are made by a step of
+// `AuthorityPerpetualTables::migrations` as well.
 
 /// Enum wrapper for versioning
 #[derive(Eq, PartialEq, Debug, Clone, Deserialize, Serialize, Hash)]
@@ -75,6 +83,20 @@ impl StoreObjectWrapper {
     }
 }
 
+impl VersionedValue for StoreObjectWrapper {
+    const LATEST_VERSION: u32 = 1;
+
+    fn version(&self) -> u32 {
+        match self {
+            Self::V1(_) => 1,
+        }
+    }
+
+    fn migrate(self) -> Self {
+        StoreObjectWrapper::migrate(self)
+    }
+}
+
 impl From<StoreObject> for StoreObjectWrapper {
     fn from(o: StoreObject) -> Self {
         StoreObjectWrapper::V1(o)
@@ -187,6 +209,16 @@ impl StoreMoveObject {
         assert_eq!(self.ref_count, 1);
         ObjectContentDigest::new(default_hash(self))
     }
+
+    /// Digest of the contents of a stored object, under which it is keyed whatever its current
+    /// reference count.
+    pub fn content_digest(&self) -> ObjectContentDigest {
+        Self {
+            ref_count: 1,
+            ..self.clone()
+        }
+        .digest()
+    }
 }
 
 pub struct StoreObjectPair(pub StoreObjectWrapper, pub Option<StoreMoveObjectWrapper>);
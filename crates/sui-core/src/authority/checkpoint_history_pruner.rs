@@ -0,0 +1,662 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pruning of checkpoint history past its configured retention.
+//!
+//! Checkpoint contents, transactions, transaction effects, events and the transaction and event
+//! indexes each have their own retention policy and their own pruning watermark, which
+//! `sui_getAvailableHistory` reports to clients. Transactions and effects are found through the
+//! contents of their checkpoint, events through the effects of their transaction, and the indexes
+//! of a transaction are removed using its data, effects and events, so a kind of history is never
+//! pruned before the kinds pruned by reading it. Contents, transactions and effects are also
+//! served to state sync peers, and effects read by the objects pruner, so they are not pruned past
+//! the objects pruner watermark, nor, in catch-up-safe mode, past the lowest checkpoint held by a
+//! peer.
+
+use crate::authority::authority_store::AuthorityStore;
+use crate::authority::authority_store_tables::AuthorityPerpetualTables;
+use crate::checkpoints::CheckpointStore;
+use mysten_metrics::monitored_scope;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::{sync::Arc, time::Duration};
+use sui_config::node::{AuthorityStorePruningConfig, RetentionPolicy};
+use sui_network::state_sync;
+use sui_storage::IndexStore;
+use sui_types::base_types::{EpochId, TransactionDigest};
+use sui_types::digests::{
+    CheckpointContentsDigest, TransactionEffectsDigest, TransactionEventsDigest,
+};
+use sui_types::messages::{
+    TransactionDataAPI, TransactionEffects, TransactionEffectsAPI, TransactionEvents,
+    VerifiedTransaction,
+};
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointSummary};
+use tokio::sync::oneshot::{self, Sender};
+use tokio::time::Instant;
+use tracing::log::{debug, error};
+use typed_store::Map;
+
+const MS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+const MAX_TICK_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// A kind of checkpoint history with its own retention.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PrunedHistory {
+    CheckpointContents,
+    Effects,
+    Events,
+    Transactions,
+    Indexes,
+}
+
+impl PrunedHistory {
+    /// All kinds, each after the kinds it is read to prune.
+    const PRUNING_ORDER: [PrunedHistory; 5] = [
+        PrunedHistory::Indexes,
+        PrunedHistory::Events,
+        PrunedHistory::Transactions,
+        PrunedHistory::Effects,
+        PrunedHistory::CheckpointContents,
+    ];
+
+    /// The kinds pruned by reading this kind, which must be out of its way before it is pruned.
+    fn read_to_prune(self) -> &'static [PrunedHistory] {
+        match self {
+            PrunedHistory::Indexes => &[],
+            PrunedHistory::Events | PrunedHistory::Transactions => &[PrunedHistory::Indexes],
+            PrunedHistory::Effects => &[PrunedHistory::Indexes, PrunedHistory::Events],
+            PrunedHistory::CheckpointContents => &[
+                PrunedHistory::Indexes,
+                PrunedHistory::Events,
+                PrunedHistory::Transactions,
+                PrunedHistory::Effects,
+            ],
+        }
+    }
+
+    fn retention(self, config: &AuthorityStorePruningConfig) -> Option<RetentionPolicy> {
+        match self {
+            PrunedHistory::CheckpointContents => config.checkpoint_contents_retention,
+            PrunedHistory::Effects => config.effects_retention,
+            PrunedHistory::Events => config.events_retention,
+            PrunedHistory::Transactions => config.transactions_retention,
+            PrunedHistory::Indexes => config.indexes_retention,
+        }
+    }
+}
+
+pub struct CheckpointHistoryPruner {
+    _cancel_handle: oneshot::Sender<()>,
+}
+
+/// How far history may be pruned, as of the highest executed checkpoint.
+struct PruningLimits {
+    current_epoch: EpochId,
+    current_timestamp_ms: u64,
+    /// Highest checkpoint whose events and indexes may be pruned.
+    events_limit: CheckpointSequenceNumber,
+    /// Highest checkpoint whose contents, transactions and effects may be pruned, if any.
+    synced_history_limit: Option<CheckpointSequenceNumber>,
+}
+
+impl PruningLimits {
+    fn limit(&self, kind: PrunedHistory) -> Option<CheckpointSequenceNumber> {
+        match kind {
+            PrunedHistory::Events | PrunedHistory::Indexes => Some(self.events_limit),
+            PrunedHistory::CheckpointContents
+            | PrunedHistory::Transactions
+            | PrunedHistory::Effects => self.synced_history_limit,
+        }
+    }
+}
+
+#[derive(Default)]
+struct PruningBatch {
+    contents: Vec<CheckpointContentsDigest>,
+    transactions: Vec<TransactionDigest>,
+    effects: Vec<TransactionEffectsDigest>,
+    events: Vec<TransactionEventsDigest>,
+    /// Transactions to unindex, with the effects and events they were indexed with.
+    indexed: Vec<(VerifiedTransaction, TransactionEffects, TransactionEvents)>,
+    watermarks: HashMap<PrunedHistory, CheckpointSequenceNumber>,
+    checkpoints: usize,
+}
+
+impl CheckpointHistoryPruner {
+    fn is_retained(
+        policy: RetentionPolicy,
+        checkpoint: &CheckpointSummary,
+        limits: &PruningLimits,
+    ) -> bool {
+        match policy {
+            RetentionPolicy::Epochs(epochs) => {
+                limits.current_epoch < checkpoint.epoch.saturating_add(epochs)
+            }
+            RetentionPolicy::Days(days) => {
+                limits.current_timestamp_ms
+                    < checkpoint
+                        .timestamp_ms
+                        .saturating_add(days.saturating_mul(MS_PER_DAY))
+            }
+        }
+    }
+
+    /// Decides which kinds of history of `checkpoint` to prune now. `None` means the kind is
+    /// either kept forever or retained from this checkpoint on; since retention and limits only
+    /// move forward with checkpoints, such a kind needs no further look in this pass.
+    fn kinds_to_prune(
+        config: &AuthorityStorePruningConfig,
+        watermarks: &HashMap<PrunedHistory, CheckpointSequenceNumber>,
+        checkpoint: &CheckpointSummary,
+        limits: &PruningLimits,
+    ) -> [(PrunedHistory, Option<bool>); 5] {
+        let sequence_number = checkpoint.sequence_number;
+        let already_pruned =
+            |kind: PrunedHistory| matches!(watermarks.get(&kind), Some(w) if *w >= sequence_number);
+        let decide = |kind, policy: Option<RetentionPolicy>, allowed: bool| match policy {
+            None => None,
+            Some(_) if already_pruned(kind) => Some(false),
+            Some(policy) if allowed && !Self::is_retained(policy, checkpoint, limits) => Some(true),
+            Some(_) => None,
+        };
+
+        let mut decisions = PrunedHistory::PRUNING_ORDER.map(|kind| (kind, None));
+        for i in 0..decisions.len() {
+            let kind = decisions[i].0;
+            // A kind is out of the way of the kinds read to prune it once it is kept forever,
+            // already pruned, or pruned along with them.
+            let cleared = kind.read_to_prune().iter().all(|read| {
+                read.retention(config).is_none()
+                    || decisions[..i]
+                        .iter()
+                        .any(|(kind, decision)| kind == read && decision.is_some())
+            });
+            let within_limit =
+                matches!(limits.limit(kind), Some(limit) if sequence_number <= limit);
+            decisions[i].1 = decide(kind, kind.retention(config), within_limit && cleared);
+        }
+        decisions
+    }
+
+    fn pruning_limits(
+        perpetual_db: &AuthorityPerpetualTables,
+        checkpoint_store: &CheckpointStore,
+        config: &AuthorityStorePruningConfig,
+        lowest_peer_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> anyhow::Result<Option<PruningLimits>> {
+        let Some(highest_executed) = checkpoint_store.get_highest_executed_checkpoint()? else {
+            return Ok(None);
+        };
+        let highest_executed = highest_executed.into_inner().into_data();
+        let mut synced_history_limit = Some(highest_executed.sequence_number);
+        if config.num_epochs_to_retain != u64::MAX {
+            // Objects pruning still reads the contents and effects of checkpoints it has not
+            // processed yet.
+            let objects_pruned = perpetual_db.get_highest_pruned_checkpoint()?;
+            synced_history_limit = synced_history_limit.map(|l| l.min(objects_pruned));
+        }
+        if config.catch_up_safe {
+            synced_history_limit = synced_history_limit
+                .zip(lowest_peer_checkpoint)
+                .map(|(limit, peer)| limit.min(peer));
+        }
+        Ok(Some(PruningLimits {
+            current_epoch: highest_executed.epoch,
+            current_timestamp_ms: highest_executed.timestamp_ms,
+            events_limit: highest_executed.sequence_number,
+            synced_history_limit,
+        }))
+    }
+
+    async fn prune_batch(
+        batch: PruningBatch,
+        perpetual_db: &Arc<AuthorityPerpetualTables>,
+        checkpoint_store: &Arc<CheckpointStore>,
+        index_store: Option<&IndexStore>,
+    ) -> anyhow::Result<()> {
+        let _scope = monitored_scope("CheckpointHistoryPruner");
+        // Indexes are removed first, while the data they were written from is still there.
+        // Unindexing is idempotent, so they are removed again if the node stops before the
+        // watermark is written.
+        if let Some(index_store) = index_store {
+            index_store.prune_transaction_order(
+                batch
+                    .indexed
+                    .iter()
+                    .map(|(transaction, _, _)| transaction.digest()),
+            )?;
+            for (transaction, effects, events) in &batch.indexed {
+                let data = transaction.data().transaction_data();
+                index_store.prune_tx(
+                    data.sender(),
+                    data.input_objects()?.iter().map(|o| o.object_id()),
+                    effects
+                        .all_changed_objects()
+                        .into_iter()
+                        .map(|(obj_ref, owner, _kind)| (*obj_ref, *owner)),
+                    data.move_calls()
+                        .into_iter()
+                        .map(|(package, module, function)| {
+                            (*package, module.to_owned(), function.to_owned())
+                        }),
+                    events,
+                    transaction.digest(),
+                )?;
+            }
+        }
+
+        let mut wb = perpetual_db.effects.batch();
+        wb = wb.delete_batch(&perpetual_db.transactions, batch.transactions)?;
+        wb = wb.delete_batch(&perpetual_db.effects, batch.effects)?;
+        for digest in batch.events {
+            wb = wb.delete_range(
+                &perpetual_db.events,
+                &(digest, usize::MIN),
+                &(digest, usize::MAX),
+            )?;
+        }
+        for (kind, checkpoint_number) in &batch.watermarks {
+            if *kind != PrunedHistory::CheckpointContents {
+                wb = perpetual_db.set_highest_pruned_history_checkpoint(
+                    wb,
+                    *kind,
+                    *checkpoint_number,
+                )?;
+            }
+        }
+        wb.write()?;
+
+        // Contents are deleted last, so that the history found through them is always gone by the
+        // time they are.
+        if let Some(checkpoint_number) = batch.watermarks.get(&PrunedHistory::CheckpointContents) {
+            checkpoint_store.delete_checkpoint_contents(&batch.contents)?;
+            perpetual_db
+                .set_highest_pruned_history_checkpoint(
+                    perpetual_db.pruned_history.batch(),
+                    PrunedHistory::CheckpointContents,
+                    *checkpoint_number,
+                )?
+                .write()?;
+        }
+        Ok(())
+    }
+
+    /// Prunes the history of all checkpoints past their retention.
+    async fn prune_checkpoint_history(
+        perpetual_db: &Arc<AuthorityPerpetualTables>,
+        checkpoint_store: &Arc<CheckpointStore>,
+        index_store: Option<&IndexStore>,
+        config: AuthorityStorePruningConfig,
+        lowest_peer_checkpoint: Option<CheckpointSequenceNumber>,
+    ) -> anyhow::Result<()> {
+        let Some(limits) =
+            Self::pruning_limits(perpetual_db, checkpoint_store, &config, lowest_peer_checkpoint)?
+        else {
+            return Ok(());
+        };
+        let mut watermarks = HashMap::new();
+        let mut start = CheckpointSequenceNumber::MAX;
+        for kind in PrunedHistory::PRUNING_ORDER {
+            if kind.retention(&config).is_none() {
+                continue;
+            }
+            let watermark = perpetual_db.get_highest_pruned_history_checkpoint(kind)?;
+            start = start.min(watermark.map_or(0, |w| w + 1));
+            if let Some(watermark) = watermark {
+                watermarks.insert(kind, watermark);
+            }
+        }
+        debug!(
+            "Starting checkpoint history pruning from checkpoint {}. Current epoch: {}",
+            start, limits.current_epoch
+        );
+
+        let mut batch = PruningBatch::default();
+        let mut transactions_in_batch = 0;
+        let iter = checkpoint_store
+            .certified_checkpoints
+            .range_iter(start..)?
+            .map(|(_, ckpt)| ckpt.into_inner().into_data());
+        for checkpoint in iter {
+            let decisions = Self::kinds_to_prune(&config, &watermarks, &checkpoint, &limits);
+            if decisions.iter().all(|(_, decision)| decision.is_none()) {
+                break;
+            }
+            let prune = |kind: PrunedHistory| decisions.contains(&(kind, Some(true)));
+            let (prune_contents, prune_transactions, prune_effects, prune_events, prune_indexes) = (
+                prune(PrunedHistory::CheckpointContents),
+                prune(PrunedHistory::Transactions),
+                prune(PrunedHistory::Effects),
+                prune(PrunedHistory::Events),
+                prune(PrunedHistory::Indexes),
+            );
+            for (kind, decision) in decisions {
+                if decision == Some(true) {
+                    batch.watermarks.insert(kind, checkpoint.sequence_number);
+                }
+            }
+
+            if prune_transactions || prune_effects || prune_events || prune_indexes {
+                let content = checkpoint_store
+                    .get_checkpoint_contents(&checkpoint.content_digest)?
+                    .ok_or_else(|| anyhow::anyhow!("checkpoint content data is missing"))?;
+                let effects_digests: Vec<_> = content.iter().map(|tx| tx.effects).collect();
+                let transaction_digests: Vec<_> = content.iter().map(|tx| tx.transaction).collect();
+                transactions_in_batch += effects_digests.len();
+                if prune_events || prune_indexes {
+                    let effects = perpetual_db.effects.multi_get(&effects_digests)?;
+                    if effects.iter().any(|effect| effect.is_none()) {
+                        return Err(anyhow::anyhow!("transaction effects data is missing"));
+                    }
+                    if prune_events {
+                        batch.events.extend(
+                            effects
+                                .iter()
+                                .flatten()
+                                .filter_map(|effects| effects.events_digest().copied()),
+                        );
+                    }
+                    if prune_indexes {
+                        let transactions =
+                            perpetual_db.transactions.multi_get(&transaction_digests)?;
+                        // Transactions pruned while indexes were kept forever can't be unindexed
+                        // anymore, and stay in the indexes.
+                        for (transaction, effects) in
+                            transactions.into_iter().zip(effects.into_iter().flatten())
+                        {
+                            let Some(transaction) = transaction else {
+                                continue;
+                            };
+                            let events = match effects.events_digest() {
+                                Some(digest) => {
+                                    perpetual_db.get_events(digest)?.unwrap_or_default()
+                                }
+                                None => TransactionEvents::default(),
+                            };
+                            batch.indexed.push((transaction.into(), effects, events));
+                        }
+                    }
+                }
+                if prune_transactions {
+                    batch.transactions.extend(transaction_digests);
+                }
+                if prune_effects {
+                    batch.effects.extend(effects_digests);
+                }
+            }
+            if prune_contents {
+                batch.contents.push(checkpoint.content_digest);
+            }
+            batch.checkpoints += 1;
+
+            if transactions_in_batch >= config.max_transactions_in_batch
+                || batch.checkpoints >= config.max_checkpoints_in_batch
+            {
+                watermarks.extend(batch.watermarks.clone());
+                Self::prune_batch(
+                    std::mem::take(&mut batch),
+                    perpetual_db,
+                    checkpoint_store,
+                    index_store,
+                )
+                .await?;
+                transactions_in_batch = 0;
+            }
+        }
+        if batch.checkpoints > 0 {
+            watermarks.extend(batch.watermarks.clone());
+            Self::prune_batch(batch, perpetual_db, checkpoint_store, index_store).await?;
+        }
+        debug!(
+            "Finished checkpoint history pruning. Latest pruned checkpoints: {:?}",
+            watermarks
+        );
+        Ok(())
+    }
+
+    fn setup_pruning(
+        config: AuthorityStorePruningConfig,
+        epoch_duration_ms: u64,
+        perpetual_db: Arc<AuthorityPerpetualTables>,
+        checkpoint_store: Arc<CheckpointStore>,
+        index_store: Option<Arc<IndexStore>>,
+        state_sync: state_sync::Handle,
+    ) -> Sender<()> {
+        let (sender, mut recv) = tokio::sync::oneshot::channel();
+        debug!(
+            "Starting checkpoint history pruning service with contents retention {:?}, transactions retention {:?}, effects retention {:?}, events retention {:?}, indexes retention {:?}",
+            config.checkpoint_contents_retention,
+            config.transactions_retention,
+            config.effects_retention,
+            config.events_retention,
+            config.indexes_retention
+        );
+        let tick_duration = Duration::from_millis(epoch_duration_ms / 2)
+            .clamp(Duration::from_secs(1), MAX_TICK_DURATION);
+        let mut prune_interval = tokio::time::interval_at(Instant::now(), tick_duration);
+
+        tokio::task::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = prune_interval.tick() => {
+                        let lowest_peer_checkpoint = state_sync.lowest_peer_checkpoint_sequence_number();
+                        if let Err(err) = Self::prune_checkpoint_history(&perpetual_db, &checkpoint_store, index_store.as_deref(), config, lowest_peer_checkpoint).await {
+                            error!("Failed to prune checkpoint history: {:?}", err);
+                        }
+                    },
+                    _ = &mut recv => break,
+                }
+            }
+        });
+        sender
+    }
+
+    pub fn new(
+        store: &AuthorityStore,
+        checkpoint_store: Arc<CheckpointStore>,
+        index_store: Option<Arc<IndexStore>>,
+        mut pruning_config: AuthorityStorePruningConfig,
+        epoch_duration_ms: u64,
+        state_sync: state_sync::Handle,
+    ) -> Self {
+        if index_store.is_none() {
+            pruning_config.indexes_retention = None;
+        }
+        CheckpointHistoryPruner {
+            _cancel_handle: Self::setup_pruning(
+                pruning_config,
+                epoch_duration_ms,
+                store.perpetual_tables.clone(),
+                checkpoint_store,
+                index_store,
+                state_sync,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::CheckpointContents;
+
+    fn checkpoint(sequence_number: u64, epoch: u64, timestamp_ms: u64) -> CheckpointSummary {
+        CheckpointSummary::new(
+            epoch,
+            sequence_number,
+            0,
+            &CheckpointContents::new_with_causally_ordered_transactions(std::iter::empty()),
+            None,
+            GasCostSummary::default(),
+            None,
+            timestamp_ms,
+        )
+    }
+
+    fn limits(synced_history_limit: Option<u64>) -> PruningLimits {
+        PruningLimits {
+            current_epoch: 5,
+            current_timestamp_ms: 10 * MS_PER_DAY,
+            events_limit: 100,
+            synced_history_limit,
+        }
+    }
+
+    fn decision(
+        config: &AuthorityStorePruningConfig,
+        watermarks: &HashMap<PrunedHistory, u64>,
+        checkpoint: &CheckpointSummary,
+        limits: &PruningLimits,
+        kind: PrunedHistory,
+    ) -> Option<bool> {
+        CheckpointHistoryPruner::kinds_to_prune(config, watermarks, checkpoint, limits)
+            .into_iter()
+            .find_map(|(k, decision)| (k == kind).then_some(decision))
+            .unwrap()
+    }
+
+    fn decisions(
+        config: &AuthorityStorePruningConfig,
+        watermarks: &HashMap<PrunedHistory, u64>,
+        checkpoint: &CheckpointSummary,
+        limits: &PruningLimits,
+    ) -> (Option<bool>, Option<bool>, Option<bool>) {
+        let decision = |kind| decision(config, watermarks, checkpoint, limits, kind);
+        (
+            decision(PrunedHistory::CheckpointContents),
+            decision(PrunedHistory::Effects),
+            decision(PrunedHistory::Events),
+        )
+    }
+
+    #[test]
+    fn test_kinds_to_prune() {
+        let config = AuthorityStorePruningConfig {
+            checkpoint_contents_retention: Some(RetentionPolicy::Epochs(1)),
+            effects_retention: Some(RetentionPolicy::Days(5)),
+            events_retention: Some(RetentionPolicy::Epochs(2)),
+            ..Default::default()
+        };
+        let no_watermarks = HashMap::new();
+
+        // Old enough for every policy.
+        let old = checkpoint(10, 1, MS_PER_DAY);
+        assert_eq!(
+            decisions(&config, &no_watermarks, &old, &limits(Some(100))),
+            (Some(true), Some(true), Some(true))
+        );
+        // Peers still need contents and effects.
+        assert_eq!(
+            decisions(&config, &no_watermarks, &old, &limits(Some(9))),
+            (None, None, Some(true))
+        );
+        assert_eq!(
+            decisions(&config, &no_watermarks, &old, &limits(None)),
+            (None, None, Some(true))
+        );
+        // Already pruned kinds are skipped without holding back the others.
+        let watermarks = HashMap::from([(PrunedHistory::Events, 10)]);
+        assert_eq!(
+            decisions(&config, &watermarks, &old, &limits(Some(100))),
+            (Some(true), Some(true), Some(false))
+        );
+
+        // Events of epoch 4 are retained, so effects and contents found through them are as well,
+        // though old enough on their own.
+        let recent = checkpoint(20, 4, 2 * MS_PER_DAY);
+        assert_eq!(
+            decisions(&config, &no_watermarks, &recent, &limits(Some(100))),
+            (None, None, None)
+        );
+        // Once events are kept forever, effects and contents follow their own retention.
+        let config = AuthorityStorePruningConfig {
+            events_retention: None,
+            ..config
+        };
+        assert_eq!(
+            decisions(&config, &no_watermarks, &recent, &limits(Some(100))),
+            (Some(true), Some(true), None)
+        );
+        // Effects created within the last five days are retained, and so are their contents.
+        let recent = checkpoint(30, 1, 6 * MS_PER_DAY);
+        assert_eq!(
+            decisions(&config, &no_watermarks, &recent, &limits(Some(100))),
+            (None, None, None)
+        );
+    }
+    #[test]
+    fn test_transactions_and_indexes_to_prune() {
+        let config = AuthorityStorePruningConfig {
+            checkpoint_contents_retention: Some(RetentionPolicy::Epochs(1)),
+            effects_retention: Some(RetentionPolicy::Epochs(1)),
+            transactions_retention: Some(RetentionPolicy::Epochs(1)),
+            indexes_retention: Some(RetentionPolicy::Epochs(3)),
+            ..Default::default()
+        };
+        let no_watermarks = HashMap::new();
+
+        // Indexes of epoch 1 are pruned along with the rest.
+        let old = checkpoint(10, 1, MS_PER_DAY);
+        for kind in PrunedHistory::PRUNING_ORDER {
+            let expected = (kind != PrunedHistory::Events).then_some(true);
+            assert_eq!(
+                decision(&config, &no_watermarks, &old, &limits(Some(100)), kind),
+                expected
+            );
+        }
+        // Transactions, like contents and effects, are held back for peers, but not indexes.
+        assert_eq!(
+            decision(
+                &config,
+                &no_watermarks,
+                &old,
+                &limits(None),
+                PrunedHistory::Transactions
+            ),
+            None
+        );
+        assert_eq!(
+            decision(
+                &config,
+                &no_watermarks,
+                &old,
+                &limits(None),
+                PrunedHistory::Indexes
+            ),
+            Some(true)
+        );
+
+        // Indexes of epoch 3 are retained, and so is everything read to prune them.
+        let recent = checkpoint(20, 3, 2 * MS_PER_DAY);
+        for kind in PrunedHistory::PRUNING_ORDER {
+            assert_eq!(
+                decision(&config, &no_watermarks, &recent, &limits(Some(100)), kind),
+                None
+            );
+        }
+        // Unless they are already pruned.
+        let watermarks = HashMap::from([(PrunedHistory::Indexes, 20)]);
+        assert_eq!(
+            decision(
+                &config,
+                &watermarks,
+                &recent,
+                &limits(Some(100)),
+                PrunedHistory::Indexes
+            ),
+            Some(false)
+        );
+        for kind in [
+            PrunedHistory::Transactions,
+            PrunedHistory::Effects,
+            PrunedHistory::CheckpointContents,
+        ] {
+            assert_eq!(
+                decision(&config, &watermarks, &recent, &limits(Some(100)), kind),
+                Some(true)
+            );
+        }
+    }
+}
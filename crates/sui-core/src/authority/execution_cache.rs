@@ -0,0 +1,585 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! In-memory cache of objects and transaction effects in front of the [`AuthorityStore`], so
+//! that hot objects and recently executed transactions are served without reading RocksDB.
+//!
+//! The cache is split into tiers, each with its own memory budget and TTL:
+//! - `objects`: object contents by `(id, version)`, which never change once written.
+//! - `latest`: the latest version of an object, or its tombstone.
+//! - `effects`: transaction effects by effects digest, which never change once written.
+//! - `executed_effects`: effects digest of executed transactions.
+//!
+//! The database is always written first, and remains the source of truth. Objects and effects
+//! written by execution are staged as dirty entries, and written back to the tiers in batches.
+//! The latest versions of written objects are updated right away, since reads of the latest
+//! version must observe them. A read-through insert never replaces a newer latest version, and
+//! is dropped if a revert invalidated the cache since the value was read from the database.
+//!
+//! Only values read from or written to the authority store are cached here. The other caches of
+//! objects keep their own bounds:
+//! - [`ArchiveFallback`] caches objects read from the archive once pruned from the store. Caching
+//!   them here would make pruned versions readable through the store again.
+//! - `ObjectProviderCache` of the JSON-RPC server memoizes the objects read while building a
+//!   single response. It also runs over the indexer's Postgres store, and its reads go through
+//!   this cache when they reach the authority store.
+//! - [`NameService`] caches resolutions derived from registry objects, which expire with the
+//!   registry records rather than with the objects.
+//!
+//! [`AuthorityStore`]: crate::authority::AuthorityStore
+//! [`ArchiveFallback`]: crate::archive_fallback::ArchiveFallback
+//! [`NameService`]: crate::name_service::NameService
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, IntCounter, IntCounterVec, IntGaugeVec, Registry,
+};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_config::node::ExecutionCacheConfig;
+use sui_types::base_types::{ObjectID, ObjectRef, TransactionDigest, TransactionEffectsDigest};
+use sui_types::message_envelope::Message;
+use sui_types::messages::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+
+use crate::memory_budget::{lru_entry_size, MemoryConsumer};
+
+pub struct ExecutionCacheMetrics {
+    hits: IntCounterVec,
+    misses: IntCounterVec,
+    evictions: IntCounterVec,
+    size_bytes: IntGaugeVec,
+    write_back_batches: IntCounter,
+}
+
+impl ExecutionCacheMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            hits: register_int_counter_vec_with_registry!(
+                "execution_cache_hits",
+                "Number of reads served by each tier of the execution cache",
+                &["tier"],
+                registry,
+            )
+            .unwrap(),
+            misses: register_int_counter_vec_with_registry!(
+                "execution_cache_misses",
+                "Number of reads that missed each tier of the execution cache",
+                &["tier"],
+                registry,
+            )
+            .unwrap(),
+            evictions: register_int_counter_vec_with_registry!(
+                "execution_cache_evictions",
+                "Number of entries evicted from each tier of the execution cache, by reason",
+                &["tier", "reason"],
+                registry,
+            )
+            .unwrap(),
+            size_bytes: register_int_gauge_vec_with_registry!(
+                "execution_cache_size_bytes",
+                "Estimated memory used by each tier of the execution cache",
+                &["tier"],
+                registry,
+            )
+            .unwrap(),
+            write_back_batches: register_int_counter_with_registry!(
+                "execution_cache_write_back_batches",
+                "Number of batches of staged writes added to the execution cache",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+struct CacheEntry<V> {
+    value: V,
+    size: usize,
+    inserted_at: Instant,
+}
+
+struct TierInner<K: Hash + Eq, V> {
+    entries: LruCache<K, CacheEntry<V>>,
+    bytes: usize,
+}
+
+/// An LRU cache bounded by the estimated memory of its entries, whose entries expire after a TTL.
+struct CacheTier<K: Hash + Eq, V> {
+    name: &'static str,
+    max_bytes: usize,
+    ttl: Duration,
+    inner: Mutex<TierInner<K, V>>,
+}
+
+impl<K: Hash + Eq, V: Clone> CacheTier<K, V> {
+    fn new(name: &'static str, max_bytes: usize, ttl: Duration) -> Self {
+        Self {
+            name,
+            max_bytes,
+            ttl,
+            inner: Mutex::new(TierInner {
+                entries: LruCache::unbounded(),
+                bytes: 0,
+            }),
+        }
+    }
+
+    fn get(&self, key: &K, metrics: &ExecutionCacheMetrics) -> Option<V> {
+        let mut guard = self.inner.lock();
+        let inner = &mut *guard;
+        match inner.entries.get(key) {
+            Some(entry) if entry.inserted_at.elapsed() <= self.ttl => {
+                metrics.hits.with_label_values(&[self.name]).inc();
+                return Some(entry.value.clone());
+            }
+            Some(_) => {
+                let entry = inner.entries.pop(key).unwrap();
+                inner.bytes -= entry.size;
+                metrics
+                    .evictions
+                    .with_label_values(&[self.name, "ttl"])
+                    .inc();
+            }
+            None => {}
+        }
+        metrics.misses.with_label_values(&[self.name]).inc();
+        None
+    }
+
+    /// Inserts the entry, unless `replace` returns false for the cached value.
+    fn insert_if(
+        &self,
+        key: K,
+        value: V,
+        size: usize,
+        metrics: &ExecutionCacheMetrics,
+        replace: impl FnOnce(Option<&V>) -> bool,
+    ) {
+        let mut guard = self.inner.lock();
+        if !replace(guard.entries.peek(&key).map(|entry| &entry.value)) {
+            return;
+        }
+        Self::insert_locked(&mut guard, key, value, size);
+        self.evict_over_budget(&mut guard, metrics);
+    }
+
+    fn insert_batch(
+        &self,
+        entries: impl IntoIterator<Item = (K, V, usize)>,
+        metrics: &ExecutionCacheMetrics,
+    ) {
+        let mut guard = self.inner.lock();
+        for (key, value, size) in entries {
+            Self::insert_locked(&mut guard, key, value, size);
+        }
+        self.evict_over_budget(&mut guard, metrics);
+    }
+
+    fn insert_locked(inner: &mut TierInner<K, V>, key: K, value: V, size: usize) {
+        let size = size + lru_entry_size::<K, CacheEntry<V>>();
+        let entry = CacheEntry {
+            value,
+            size,
+            inserted_at: Instant::now(),
+        };
+        inner.bytes += size;
+        if let Some((_, replaced)) = inner.entries.push(key, entry) {
+            inner.bytes -= replaced.size;
+        }
+    }
+
+    fn evict_over_budget(&self, inner: &mut TierInner<K, V>, metrics: &ExecutionCacheMetrics) {
+        let mut evicted = 0;
+        while inner.bytes > self.max_bytes {
+            let Some((_, entry)) = inner.entries.pop_lru() else {
+                break;
+            };
+            inner.bytes -= entry.size;
+            evicted += 1;
+        }
+        if evicted > 0 {
+            metrics
+                .evictions
+                .with_label_values(&[self.name, "capacity"])
+                .inc_by(evicted);
+        }
+    }
+
+    fn remove(&self, key: &K) {
+        let mut guard = self.inner.lock();
+        if let Some(entry) = guard.entries.pop(key) {
+            guard.bytes -= entry.size;
+        }
+    }
+
+    /// Evicts the least recently used entries until about `bytes` are freed.
+    fn shrink(&self, bytes: usize, metrics: &ExecutionCacheMetrics) -> usize {
+        let mut guard = self.inner.lock();
+        let mut freed = 0;
+        let mut evicted = 0;
+        while freed < bytes {
+            let Some((_, entry)) = guard.entries.pop_lru() else {
+                break;
+            };
+            guard.bytes -= entry.size;
+            freed += entry.size;
+            evicted += 1;
+        }
+        metrics
+            .evictions
+            .with_label_values(&[self.name, "shrink"])
+            .inc_by(evicted);
+        freed
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.inner.lock().bytes
+    }
+}
+
+/// Outputs of an executed transaction, waiting to be written back to the cache.
+struct StagedWrite {
+    tx_digest: TransactionDigest,
+    effects: TransactionEffects,
+    objects: Vec<Object>,
+}
+
+pub struct ExecutionCache {
+    config: ExecutionCacheConfig,
+    objects: CacheTier<ObjectKey, Object>,
+    latest: CacheTier<ObjectID, ObjectRef>,
+    effects: CacheTier<TransactionEffectsDigest, TransactionEffects>,
+    executed_effects: CacheTier<TransactionDigest, TransactionEffectsDigest>,
+    staged: Mutex<Vec<StagedWrite>>,
+    /// Incremented whenever entries are invalidated, so that values read from the database
+    /// before the invalidation are not cached after it.
+    generation: AtomicU64,
+    metrics: ExecutionCacheMetrics,
+}
+
+impl ExecutionCache {
+    pub fn new(config: ExecutionCacheConfig, registry: &Registry) -> Arc<Self> {
+        let object_ttl = Duration::from_secs(config.object_ttl_secs);
+        let effects_ttl = Duration::from_secs(config.effects_ttl_secs);
+        // The latest versions and executed digests are small, fixed size entries sized against
+        // a fraction of the budget of the tier they index.
+        Arc::new(Self {
+            objects: CacheTier::new("objects", config.object_cache_max_bytes, object_ttl),
+            latest: CacheTier::new("latest", config.object_cache_max_bytes / 8, object_ttl),
+            effects: CacheTier::new("effects", config.effects_cache_max_bytes, effects_ttl),
+            executed_effects: CacheTier::new(
+                "executed_effects",
+                config.effects_cache_max_bytes / 8,
+                effects_ttl,
+            ),
+            staged: Mutex::new(Vec::new()),
+            generation: AtomicU64::new(0),
+            metrics: ExecutionCacheMetrics::new(registry),
+            config,
+        })
+    }
+
+    /// To be read before reading a value from the database, and passed back when caching it.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    pub fn get_object_by_key(&self, key: &ObjectKey) -> Option<Object> {
+        self.objects.get(key, &self.metrics)
+    }
+
+    pub fn insert_object(&self, object: Object) {
+        let key = ObjectKey(object.id(), object.version());
+        let size = object.object_size_for_gas_metering();
+        self.objects
+            .insert_if(key, object, size, &self.metrics, |_| true);
+    }
+
+    /// Reference to the latest version of the object, or to its tombstone if it was deleted or
+    /// wrapped.
+    pub fn get_latest_ref(&self, object_id: &ObjectID) -> Option<ObjectRef> {
+        self.latest.get(object_id, &self.metrics)
+    }
+
+    pub fn insert_latest_ref(&self, object_ref: ObjectRef, generation: u64) {
+        self.latest
+            .insert_if(object_ref.0, object_ref, 0, &self.metrics, |cached| {
+                self.generation() == generation
+                    && cached.map_or(true, |cached| cached.1 < object_ref.1)
+            });
+    }
+
+    pub fn get_effects(&self, digest: &TransactionEffectsDigest) -> Option<TransactionEffects> {
+        self.effects.get(digest, &self.metrics)
+    }
+
+    pub fn insert_effects(&self, effects: TransactionEffects) {
+        let size = effects_size(&effects);
+        self.effects
+            .insert_if(effects.digest(), effects, size, &self.metrics, |_| true);
+    }
+
+    pub fn get_executed_effects_digest(
+        &self,
+        digest: &TransactionDigest,
+    ) -> Option<TransactionEffectsDigest> {
+        self.executed_effects.get(digest, &self.metrics)
+    }
+
+    pub fn insert_executed_effects_digest(
+        &self,
+        digest: TransactionDigest,
+        effects_digest: TransactionEffectsDigest,
+        generation: u64,
+    ) {
+        self.executed_effects
+            .insert_if(digest, effects_digest, 0, &self.metrics, |_| {
+                self.generation() == generation
+            });
+    }
+
+    /// Records the outputs of a transaction that were just committed to the database.
+    /// `latest_refs` are the references of the written objects and the tombstones of the
+    /// deleted and wrapped ones.
+    pub fn stage_write(
+        &self,
+        tx_digest: TransactionDigest,
+        effects: TransactionEffects,
+        objects: Vec<Object>,
+        latest_refs: Vec<ObjectRef>,
+    ) {
+        for object_ref in latest_refs {
+            self.latest
+                .insert_if(object_ref.0, object_ref, 0, &self.metrics, |cached| {
+                    cached.map_or(true, |cached| cached.1 < object_ref.1)
+                });
+        }
+        let batch = {
+            let mut staged = self.staged.lock();
+            staged.push(StagedWrite {
+                tx_digest,
+                effects,
+                objects,
+            });
+            if staged.len() < self.config.write_back_batch_size {
+                return;
+            }
+            std::mem::take(&mut *staged)
+        };
+        self.write_back(batch);
+    }
+
+    /// Writes back all staged writes to the cache.
+    pub fn flush(&self) {
+        let batch = std::mem::take(&mut *self.staged.lock());
+        self.write_back(batch);
+    }
+
+    fn write_back(&self, batch: Vec<StagedWrite>) {
+        if batch.is_empty() {
+            return;
+        }
+        let generation = self.generation();
+        let mut objects = vec![];
+        let mut effects = vec![];
+        for write in batch {
+            let effects_digest = write.effects.digest();
+            self.insert_executed_effects_digest(write.tx_digest, effects_digest, generation);
+            let size = effects_size(&write.effects);
+            effects.push((effects_digest, write.effects, size));
+            objects.extend(write.objects.into_iter().map(|object| {
+                let size = object.object_size_for_gas_metering();
+                (ObjectKey(object.id(), object.version()), object, size)
+            }));
+        }
+        self.objects.insert_batch(objects, &self.metrics);
+        self.effects.insert_batch(effects, &self.metrics);
+        self.metrics.write_back_batches.inc();
+    }
+
+    /// Invalidates the entries of a reverted transaction. Object contents and effects are keyed
+    /// by version and digest, and stay valid.
+    pub fn invalidate_transaction(&self, effects: &TransactionEffects) {
+        self.flush();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.executed_effects.remove(effects.transaction_digest());
+        for object_ref in effects.all_changed_objects().into_iter().map(|(r, _, _)| r) {
+            self.latest.remove(&object_ref.0);
+        }
+        for object_ref in effects.all_deleted().into_iter().map(|(r, _)| r) {
+            self.latest.remove(&object_ref.0);
+        }
+    }
+
+    /// Invalidates the latest version of an object written outside of transaction execution.
+    pub fn invalidate_object(&self, object_id: &ObjectID) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.latest.remove(object_id);
+    }
+
+    fn staged_memory_usage(&self) -> usize {
+        self.staged
+            .lock()
+            .iter()
+            .map(|write| {
+                effects_size(&write.effects)
+                    + write
+                        .objects
+                        .iter()
+                        .map(|object| object.object_size_for_gas_metering())
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+
+    fn tiers(&self) -> [(&'static str, usize); 4] {
+        [
+            (self.objects.name, self.objects.memory_usage()),
+            (self.latest.name, self.latest.memory_usage()),
+            (self.effects.name, self.effects.memory_usage()),
+            (
+                self.executed_effects.name,
+                self.executed_effects.memory_usage(),
+            ),
+        ]
+    }
+}
+
+impl MemoryConsumer for ExecutionCache {
+    fn memory_usage(&self) -> usize {
+        let mut total = self.staged_memory_usage();
+        for (tier, usage) in self.tiers() {
+            self.metrics
+                .size_bytes
+                .with_label_values(&[tier])
+                .set(usage as i64);
+            total += usage;
+        }
+        total
+    }
+
+    /// Shrinks the object contents and effects, which use most of the memory.
+    fn shrink(&self, bytes: usize) -> usize {
+        let freed = self.objects.shrink(bytes, &self.metrics);
+        freed
+            + self
+                .effects
+                .shrink(bytes.saturating_sub(freed), &self.metrics)
+    }
+}
+
+fn effects_size(effects: &TransactionEffects) -> usize {
+    bcs::serialized_size(effects).unwrap_or_else(|_| std::mem::size_of::<TransactionEffects>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::SequenceNumber;
+    use sui_types::digests::ObjectDigest;
+
+    fn cache(config: ExecutionCacheConfig) -> Arc<ExecutionCache> {
+        ExecutionCache::new(config, &Registry::new())
+    }
+
+    #[test]
+    fn test_latest_ref_is_never_replaced_by_older_version() {
+        let cache = cache(ExecutionCacheConfig::default());
+        let id = ObjectID::random();
+        let v1 = (id, SequenceNumber::from(1), ObjectDigest::random());
+        let v2 = (id, SequenceNumber::from(2), ObjectDigest::random());
+
+        // A read of v1 from the database races with the execution writing v2.
+        let generation = cache.generation();
+        cache.stage_write(
+            TransactionDigest::random(),
+            TransactionEffects::default(),
+            vec![],
+            vec![v2],
+        );
+        cache.insert_latest_ref(v1, generation);
+        assert_eq!(cache.get_latest_ref(&id), Some(v2));
+
+        // Reads from before an invalidation are not cached.
+        cache.invalidate_object(&id);
+        cache.insert_latest_ref(v1, generation);
+        assert_eq!(cache.get_latest_ref(&id), None);
+        cache.insert_latest_ref(v1, cache.generation());
+        assert_eq!(cache.get_latest_ref(&id), Some(v1));
+    }
+
+    #[test]
+    fn test_staged_writes_are_written_back_in_batches() {
+        let cache = cache(ExecutionCacheConfig {
+            write_back_batch_size: 2,
+            ..Default::default()
+        });
+        let object = Object::immutable_with_id_for_testing(ObjectID::random());
+        let key = ObjectKey(object.id(), object.version());
+        let tx_digest = TransactionDigest::random();
+
+        cache.stage_write(
+            tx_digest,
+            TransactionEffects::default(),
+            vec![object],
+            vec![],
+        );
+        assert!(cache.get_object_by_key(&key).is_none());
+        assert!(cache.get_executed_effects_digest(&tx_digest).is_none());
+
+        cache.stage_write(
+            TransactionDigest::random(),
+            TransactionEffects::default(),
+            vec![],
+            vec![],
+        );
+        assert!(cache.get_object_by_key(&key).is_some());
+        assert_eq!(
+            cache.get_executed_effects_digest(&tx_digest),
+            Some(TransactionEffects::default().digest())
+        );
+    }
+
+    #[test]
+    fn test_objects_are_evicted_over_budget_and_after_ttl() {
+        let object = Object::immutable_with_id_for_testing(ObjectID::random());
+        let entry_size = object.object_size_for_gas_metering()
+            + lru_entry_size::<ObjectKey, CacheEntry<Object>>();
+        let cache = cache(ExecutionCacheConfig {
+            object_cache_max_bytes: 2 * entry_size,
+            ..Default::default()
+        });
+        let objects: Vec<_> = (0..3)
+            .map(|_| Object::immutable_with_id_for_testing(ObjectID::random()))
+            .collect();
+        for object in &objects {
+            cache.insert_object(object.clone());
+        }
+        let cached = |object: &Object| {
+            cache
+                .get_object_by_key(&ObjectKey(object.id(), object.version()))
+                .is_some()
+        };
+        // The least recently used object was evicted.
+        assert!(!cached(&objects[0]));
+        assert!(cached(&objects[1]));
+        assert!(cached(&objects[2]));
+
+        let cache = self::cache(ExecutionCacheConfig {
+            object_ttl_secs: 0,
+            ..Default::default()
+        });
+        cache.insert_object(object.clone());
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(cache
+            .get_object_by_key(&ObjectKey(object.id(), object.version()))
+            .is_none());
+        assert_eq!(cache.objects.memory_usage(), 0);
+    }
+}
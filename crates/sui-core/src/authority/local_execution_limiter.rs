@@ -0,0 +1,176 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Wall-clock limits on the transactions a fullnode executes locally to answer RPC requests:
+//! dry runs and dev-inspect calls. These are only bounded by their gas budget, which a
+//! computation-heavy transaction can take a long time to exhaust. Executions run on the blocking
+//! thread pool, and the request fails with [`SuiError::LocalExecutionTimeLimitExceeded`] once its
+//! limit is reached, instead of holding on to the RPC worker.
+//!
+//! The Move VM cannot be interrupted, so an execution past its limit runs on in the background
+//! until it completes or runs out of gas. The number of concurrent executions is bounded so that
+//! such executions cannot exhaust the blocking thread pool.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use sui_config::node::LocalExecutionTimeLimitConfig;
+use sui_types::error::{SuiError, SuiResult};
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+use crate::authority::AuthorityMetrics;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalExecutionKind {
+    DryRun,
+    DevInspect,
+}
+
+impl LocalExecutionKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LocalExecutionKind::DryRun => "dry_run",
+            LocalExecutionKind::DevInspect => "dev_inspect",
+        }
+    }
+}
+
+pub struct LocalExecutionLimiter {
+    config: LocalExecutionTimeLimitConfig,
+    permits: Arc<Semaphore>,
+    metrics: Arc<AuthorityMetrics>,
+}
+
+impl LocalExecutionLimiter {
+    pub fn new(config: LocalExecutionTimeLimitConfig, metrics: Arc<AuthorityMetrics>) -> Self {
+        Self {
+            config,
+            permits: Arc::new(Semaphore::new(config.max_concurrent_executions)),
+            metrics,
+        }
+    }
+
+    fn time_limit_ms(&self, kind: LocalExecutionKind) -> u64 {
+        match kind {
+            LocalExecutionKind::DryRun => self.config.dry_run_time_limit_ms,
+            LocalExecutionKind::DevInspect => self.config.dev_inspect_time_limit_ms,
+        }
+    }
+
+    /// Runs `execute` on the blocking thread pool, and returns its result if it completes within
+    /// the time limit of `kind`. The time spent waiting for an execution slot counts against the
+    /// limit.
+    pub async fn execute<T, F>(&self, kind: LocalExecutionKind, execute: F) -> SuiResult<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let limit_ms = self.time_limit_ms(kind);
+        let permits = self.permits.clone();
+        let metrics = self.metrics.clone();
+        let execution = async move {
+            let permit = permits
+                .acquire_owned()
+                .await
+                .expect("Local execution semaphore is never closed");
+            tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                metrics.local_executions_in_flight.inc();
+                let result = execute();
+                metrics.local_executions_in_flight.dec();
+                result
+            })
+            .await
+        };
+
+        match tokio::time::timeout(Duration::from_millis(limit_ms), execution).await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(err)) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            Ok(Err(err)) => Err(SuiError::GenericAuthorityError {
+                error: format!("Local {} execution was cancelled: {err}", kind.as_str()),
+            }),
+            Err(_) => {
+                warn!(
+                    kind = kind.as_str(),
+                    limit_ms, "Local execution exceeded its time limit"
+                );
+                self.metrics
+                    .local_execution_time_limit_exceeded
+                    .with_label_values(&[kind.as_str()])
+                    .inc();
+                Err(SuiError::LocalExecutionTimeLimitExceeded {
+                    kind: kind.as_str().to_string(),
+                    limit_ms,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::Registry;
+    use std::sync::mpsc;
+
+    fn limiter(max_concurrent_executions: usize) -> LocalExecutionLimiter {
+        let config = LocalExecutionTimeLimitConfig {
+            dry_run_time_limit_ms: 200,
+            dev_inspect_time_limit_ms: 1_000,
+            max_concurrent_executions,
+        };
+        LocalExecutionLimiter::new(config, Arc::new(AuthorityMetrics::new(&Registry::new())))
+    }
+
+    #[tokio::test]
+    async fn test_execution_within_limit() {
+        let limiter = limiter(1);
+        let result = limiter
+            .execute(LocalExecutionKind::DryRun, || 42)
+            .await
+            .unwrap();
+        assert_eq!(result, 42);
+    }
+
+    #[tokio::test]
+    async fn test_execution_past_limit() {
+        let limiter = limiter(1);
+        let (release, blocked) = mpsc::channel::<()>();
+        let err = limiter
+            .execute(LocalExecutionKind::DryRun, move || blocked.recv().ok())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SuiError::LocalExecutionTimeLimitExceeded { limit_ms: 200, .. }
+        ));
+        assert_eq!(
+            limiter
+                .metrics
+                .local_execution_time_limit_exceeded
+                .with_label_values(&["dry_run"])
+                .get(),
+            1
+        );
+
+        // The abandoned execution still holds the only slot, so the next one times out waiting.
+        let err = limiter
+            .execute(LocalExecutionKind::DevInspect, || ())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SuiError::LocalExecutionTimeLimitExceeded {
+                limit_ms: 1_000,
+                ..
+            }
+        ));
+
+        release.send(()).unwrap();
+        limiter
+            .execute(LocalExecutionKind::DevInspect, || ())
+            .await
+            .unwrap();
+    }
+}
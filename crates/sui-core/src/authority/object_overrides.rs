@@ -0,0 +1,138 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Object contents and versions supplied by the caller of a dev-inspect execution, in place of
+//! the ones on chain, to simulate the execution against a state the chain is not in.
+//!
+//! Overridden objects replace the inputs of the transaction with the same ID, and are returned by
+//! the store backing the execution in place of the objects it reads, e.g. dynamic fields. Nothing
+//! is written to the authority store.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail};
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::ModuleId;
+use sui_json_rpc_types::SuiObjectOverride;
+use sui_protocol_config::ProtocolConfig;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::object::{MoveObject, Object, ObjectFormatOptions, Owner};
+use sui_types::storage::{BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync};
+
+/// Applies `overrides` to the objects read from `store`, checking that the overridden contents
+/// are valid for the type of the object.
+pub fn overridden_objects(
+    store: &impl ObjectStore,
+    overrides: Vec<SuiObjectOverride>,
+    protocol_config: &ProtocolConfig,
+    resolver: &impl GetModule,
+) -> anyhow::Result<BTreeMap<ObjectID, Object>> {
+    let mut objects = BTreeMap::new();
+    for SuiObjectOverride {
+        object_id,
+        version,
+        bcs_bytes,
+    } in overrides
+    {
+        let mut object = store
+            .get_object(&object_id)?
+            .ok_or_else(|| anyhow!("Overridden object {object_id} not found"))?;
+        let move_object = object
+            .data
+            .try_as_move_mut()
+            .ok_or_else(|| anyhow!("Overridden object {object_id} is a package"))?;
+
+        if let Some(version) = version {
+            if version == SequenceNumber::new() || version >= SequenceNumber::MAX {
+                bail!("Invalid version {version} for overridden object {object_id}");
+            }
+            if version > move_object.version() {
+                move_object.increment_version_to(version);
+            } else if version < move_object.version() {
+                move_object.decrement_version_to(version);
+            }
+        }
+        if let Some(bcs_bytes) = bcs_bytes {
+            let contents = bcs_bytes.to_vec().map_err(|e| anyhow!(e))?;
+            if contents.len() < ObjectID::LENGTH || MoveObject::id_opt(&contents)? != object_id {
+                bail!("Contents of overridden object {object_id} must start with its ID");
+            }
+            move_object.update_contents(contents, protocol_config)?;
+        }
+
+        // The contents must deserialize to the type of the object, for the VM to load them.
+        let layout = move_object.get_layout(ObjectFormatOptions::default(), resolver)?;
+        move_object
+            .to_move_struct(&layout)
+            .map_err(|e| anyhow!("Invalid contents for overridden object {object_id}: {e}"))?;
+
+        if objects.insert(object_id, object).is_some() {
+            bail!("Object {object_id} overridden more than once");
+        }
+    }
+    Ok(objects)
+}
+
+/// A store returning the overridden objects in place of the ones in the store it wraps.
+pub struct ObjectOverrideStore<S> {
+    store: S,
+    overrides: BTreeMap<ObjectID, Object>,
+}
+
+impl<S> ObjectOverrideStore<S> {
+    pub fn new(store: S, overrides: BTreeMap<ObjectID, Object>) -> Self {
+        Self { store, overrides }
+    }
+}
+
+impl<S: ObjectStore> ObjectStore for ObjectOverrideStore<S> {
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        match self.overrides.get(object_id) {
+            Some(object) => Ok(Some(object.clone())),
+            None => self.store.get_object(object_id),
+        }
+    }
+}
+
+impl<S: ChildObjectResolver> ChildObjectResolver for ObjectOverrideStore<S> {
+    fn read_child_object(&self, parent: &ObjectID, child: &ObjectID) -> SuiResult<Option<Object>> {
+        let Some(child_object) = self.overrides.get(child) else {
+            return self.store.read_child_object(parent, child);
+        };
+        if child_object.owner != Owner::ObjectOwner((*parent).into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: *parent,
+                actual_owner: child_object.owner,
+            });
+        }
+        Ok(Some(child_object.clone()))
+    }
+}
+
+impl<S: ParentSync> ParentSync for ObjectOverrideStore<S> {
+    fn get_latest_parent_entry_ref(&self, object_id: ObjectID) -> SuiResult<Option<ObjectRef>> {
+        match self.overrides.get(&object_id) {
+            Some(object) => Ok(Some(object.compute_object_reference())),
+            None => self.store.get_latest_parent_entry_ref(object_id),
+        }
+    }
+}
+
+impl<S: BackingPackageStore> BackingPackageStore for ObjectOverrideStore<S> {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        // Packages cannot be overridden.
+        self.store.get_package_object(package_id)
+    }
+}
+
+impl<S: GetModule<Error = SuiError, Item = CompiledModule>> GetModule for ObjectOverrideStore<S> {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        self.store.get_module_by_id(id)
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Shared object congestion control.
+//!
+//! Transactions that take a mutable reference to the same shared object must execute one after
+//! another, so a single popular object can saturate execution while consensus keeps sequencing
+//! more transactions for it. When the `shared_object_congestion_control` feature flag is set, each
+//! consensus commit admits at most `max_txns_per_shared_object_per_commit` transactions per
+//! mutable shared object. The others are deferred to later commits, where they are considered
+//! ahead of newly sequenced transactions. The queue is admitted in full ahead of every
+//! EndOfPublish message, so that a deferred certificate is never dropped at the end of the epoch.
+//! Deferred transactions are charged the same gas as any other, congestion only delays them.
+//!
+//! Admission decisions depend only on the contents of the commit and on the persisted deferral
+//! queue, so every validator (and a validator replaying a commit after a crash) makes the same
+//! decisions.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use narwhal_types::Round;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::{AuthorityName, ObjectID, TransactionDigest};
+use sui_types::messages::ConsensusTransaction;
+
+/// A transaction in the deferral queue.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeferredTransaction {
+    pub author: AuthorityName,
+    pub transaction: ConsensusTransaction,
+    /// Round of the commit that sequenced the transaction and first deferred it.
+    pub deferred_at_round: Round,
+}
+
+/// Mutable shared objects of a user certificate, or `None` for other consensus messages. For a
+/// soft bundle, an object is listed once per certificate of the bundle that mutates it, so that
+/// the bundle is admitted or deferred as a whole and counts as all of its certificates.
+pub(crate) fn mutable_shared_objects(transaction: &ConsensusTransaction) -> Option<Vec<ObjectID>> {
+    if !transaction.is_user_certificate() {
+        return None;
+    }
+    Some(
+        transaction
+            .user_certificates()
+            .iter()
+            .flat_map(|certificate| certificate.shared_input_objects())
+            .filter(|object| object.mutable)
+            .map(|object| object.id)
+            .collect(),
+    )
+}
+
+/// Counts the transactions admitted for each mutable shared object within a single commit.
+pub struct SharedObjectCongestionTracker {
+    max_txns_per_object: u64,
+    object_txn_counts: HashMap<ObjectID, u64>,
+}
+
+impl SharedObjectCongestionTracker {
+    pub fn new(max_txns_per_object: u64) -> Self {
+        Self {
+            max_txns_per_object,
+            object_txn_counts: HashMap::new(),
+        }
+    }
+
+    /// Admits `transaction` into the current commit, unless one of the shared objects it mutates
+    /// has already reached the per-commit limit. Consensus messages other than user certificates,
+    /// and certificates that do not mutate any shared object, are always admitted.
+    pub fn try_admit(&mut self, transaction: &ConsensusTransaction) -> bool {
+        match mutable_shared_objects(transaction) {
+            Some(objects) => self.try_admit_objects(&objects),
+            None => true,
+        }
+    }
+
+    fn try_admit_objects(&mut self, objects: &[ObjectID]) -> bool {
+        if objects.iter().any(|id| {
+            self.object_txn_counts.get(id).copied().unwrap_or_default() >= self.max_txns_per_object
+        }) {
+            return false;
+        }
+        for id in objects {
+            *self.object_txn_counts.entry(*id).or_default() += 1;
+        }
+        true
+    }
+
+    pub fn into_object_txn_counts(self) -> HashMap<ObjectID, u64> {
+        self.object_txn_counts
+    }
+}
+
+/// Utilization of shared objects over a sliding window of recent commits.
+///
+/// This is local state that is lost on restart, so it is only used for metrics and never for
+/// admission decisions.
+pub struct SharedObjectUtilization {
+    window_size: usize,
+    window: VecDeque<HashMap<ObjectID, u64>>,
+    totals: HashMap<ObjectID, u64>,
+}
+
+impl SharedObjectUtilization {
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            window_size: window_size.max(1),
+            window: VecDeque::new(),
+            totals: HashMap::new(),
+        }
+    }
+
+    pub fn record_commit(&mut self, object_txn_counts: HashMap<ObjectID, u64>) {
+        for (id, count) in &object_txn_counts {
+            *self.totals.entry(*id).or_default() += count;
+        }
+        self.window.push_back(object_txn_counts);
+        while self.window.len() > self.window_size {
+            let expired = self.window.pop_front().unwrap();
+            for (id, count) in expired {
+                let total = self.totals.get_mut(&id).unwrap();
+                *total -= count;
+                if *total == 0 {
+                    self.totals.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Utilization of the busiest shared object over the window, as a percentage of the capacity
+    /// allowed by `max_txns_per_object` in each commit.
+    pub fn max_utilization_percent(&self, max_txns_per_object: u64) -> u64 {
+        let busiest = self.totals.values().copied().max().unwrap_or_default();
+        let capacity = max_txns_per_object.max(1) * self.window.len().max(1) as u64;
+        busiest * 100 / capacity
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeferredTransactionInfo {
+    pub digest: TransactionDigest,
+    pub author: AuthorityName,
+    pub deferred_at_round: Round,
+    /// Mutable shared objects of the transaction, at least one of which is congested.
+    pub objects: Vec<ObjectID>,
+    /// Number of commits until the transaction is admitted, if no authority sends EndOfPublish.
+    /// Deferred transactions are admitted ahead of newly sequenced ones, so this does not depend
+    /// on future traffic.
+    pub expected_commits: Option<u64>,
+}
+
+/// Contents of the deferral queue left by the latest commit.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeferralQueueSummary {
+    /// Round of the latest commit that deferred transactions.
+    pub round: Option<Round>,
+    pub transactions: Vec<DeferredTransactionInfo>,
+    /// Number of deferred transactions mutating each shared object.
+    pub objects: BTreeMap<ObjectID, u64>,
+}
+
+impl DeferralQueueSummary {
+    pub fn new(round: Round, queue: &[DeferredTransaction], max_txns_per_object: u64) -> Self {
+        let objects: Vec<Vec<ObjectID>> = queue
+            .iter()
+            .map(|deferred| mutable_shared_objects(&deferred.transaction).unwrap_or_default())
+            .collect();
+
+        let expected_commits = expected_commits(&objects, max_txns_per_object);
+
+        let mut object_counts = BTreeMap::new();
+        for id in objects.iter().flatten() {
+            *object_counts.entry(*id).or_default() += 1;
+        }
+        // The certificates of a deferred bundle are listed individually, with the objects of the
+        // whole bundle.
+        let transactions = queue
+            .iter()
+            .zip(objects)
+            .zip(expected_commits)
+            .flat_map(|((deferred, objects), expected_commits)| {
+                deferred
+                    .transaction
+                    .user_certificates()
+                    .iter()
+                    .map(move |certificate| DeferredTransactionInfo {
+                        digest: *certificate.digest(),
+                        author: deferred.author,
+                        deferred_at_round: deferred.deferred_at_round,
+                        objects: objects.clone(),
+                        expected_commits,
+                    })
+            })
+            .collect();
+        Self {
+            round: Some(round),
+            transactions,
+            objects: object_counts,
+        }
+    }
+}
+
+/// Replays admission over a queue of transactions mutating `objects`, one commit at a time, and
+/// returns the number of commits until each is admitted.
+fn expected_commits(objects: &[Vec<ObjectID>], max_txns_per_object: u64) -> Vec<Option<u64>> {
+    let mut expected_commits = vec![None; objects.len()];
+    let mut remaining: Vec<usize> = (0..objects.len()).collect();
+    let mut commits = 0;
+    while !remaining.is_empty() {
+        commits += 1;
+        let mut tracker = SharedObjectCongestionTracker::new(max_txns_per_object);
+        let before = remaining.len();
+        remaining.retain(|index| {
+            let admitted = tracker.try_admit_objects(&objects[*index]);
+            if admitted {
+                expected_commits[*index] = Some(commits);
+            }
+            !admitted
+        });
+        // Nothing can ever be admitted with a limit of zero.
+        if remaining.len() == before {
+            break;
+        }
+    }
+    expected_commits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admit_up_to_limit_per_object() {
+        let (a, b) = (ObjectID::random(), ObjectID::random());
+        let mut tracker = SharedObjectCongestionTracker::new(2);
+
+        assert!(tracker.try_admit_objects(&[a]));
+        assert!(tracker.try_admit_objects(&[a, b]));
+        // `a` is full, so a transaction touching both objects is deferred as a whole and does not
+        // consume capacity on `b`.
+        assert!(!tracker.try_admit_objects(&[a, b]));
+        assert!(!tracker.try_admit_objects(&[a]));
+        assert!(tracker.try_admit_objects(&[b]));
+        assert!(!tracker.try_admit_objects(&[b]));
+        // Transactions without mutable shared objects are never deferred.
+        assert!(tracker.try_admit_objects(&[]));
+
+        let counts = tracker.into_object_txn_counts();
+        assert_eq!(counts[&a], 2);
+        assert_eq!(counts[&b], 2);
+    }
+
+    #[test]
+    fn test_expected_commits() {
+        let (a, b) = (ObjectID::random(), ObjectID::random());
+        let queue = vec![vec![a], vec![a, b], vec![a], vec![b], vec![a, b]];
+        assert_eq!(
+            expected_commits(&queue, 2),
+            vec![Some(1), Some(1), Some(2), Some(1), Some(2)]
+        );
+        assert_eq!(expected_commits(&queue, 0), vec![None; 5]);
+    }
+
+    #[test]
+    fn test_utilization_window() {
+        let (a, b) = (ObjectID::random(), ObjectID::random());
+        let mut utilization = SharedObjectUtilization::new(2);
+        assert_eq!(utilization.max_utilization_percent(10), 0);
+
+        utilization.record_commit(HashMap::from([(a, 10), (b, 2)]));
+        assert_eq!(utilization.max_utilization_percent(10), 100);
+
+        utilization.record_commit(HashMap::from([(b, 4)]));
+        assert_eq!(utilization.max_utilization_percent(10), 50);
+
+        // The first commit falls out of the window.
+        utilization.record_commit(HashMap::from([(b, 8)]));
+        assert_eq!(utilization.max_utilization_percent(10), 60);
+        assert!(!utilization.totals.contains_key(&a));
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dependencies between transactions on shared objects.
+//!
+//! Once versions are assigned to the shared inputs of a batch of certificates, the order in which
+//! they must execute is fully determined: a transaction reading or writing version `v` of a shared
+//! object depends on the transaction that mutated the object at the highest version below `v`.
+//! Readers of the same version do not depend on each other, and a writer does not wait for the
+//! readers of the version it replaces, because older object versions stay readable.
+//!
+//! TransactionManager already releases a transaction once all of its input versions exist, so the
+//! graph is not needed for correctness. It is used to rank ready transactions, so that when all
+//! execution workers are busy the ones unblocking the longest chains of dependent transactions run
+//! first, and to measure how much parallelism a batch allows.
+
+use std::collections::{HashMap, VecDeque};
+
+use sui_types::base_types::{ObjectID, SequenceNumber};
+
+/// A shared input of a transaction: the object, the version assigned to the transaction, and
+/// whether the transaction mutates the object.
+pub type SharedInput = (ObjectID, SequenceNumber, bool);
+
+pub struct SharedObjectDependencyGraph {
+    /// For each transaction, the transactions of the batch it depends on.
+    dependencies: Vec<Vec<usize>>,
+    /// For each transaction, the number of transactions on the longest chain starting at it,
+    /// itself included.
+    heights: Vec<u64>,
+}
+
+impl SharedObjectDependencyGraph {
+    /// Builds the graph of a batch of transactions, given the shared inputs of each transaction.
+    /// Dependencies on transactions outside of the batch are ignored.
+    pub fn new(transactions: &[Vec<SharedInput>]) -> Self {
+        // Writers of each object in the batch, by assigned version.
+        let mut writers: HashMap<ObjectID, Vec<(SequenceNumber, usize)>> = HashMap::new();
+        for (index, inputs) in transactions.iter().enumerate() {
+            for (id, version, mutable) in inputs {
+                if *mutable {
+                    writers.entry(*id).or_default().push((*version, index));
+                }
+            }
+        }
+        for versions in writers.values_mut() {
+            versions.sort();
+        }
+
+        let dependencies: Vec<Vec<usize>> = transactions
+            .iter()
+            .enumerate()
+            .map(|(index, inputs)| {
+                let mut dependencies: Vec<usize> = inputs
+                    .iter()
+                    .filter_map(|(id, version, _)| {
+                        let versions = writers.get(id)?;
+                        let position = versions.partition_point(|(v, _)| v < version);
+                        let (_, writer) = versions.get(position.checked_sub(1)?)?;
+                        (*writer != index).then_some(*writer)
+                    })
+                    .collect();
+                dependencies.sort_unstable();
+                dependencies.dedup();
+                dependencies
+            })
+            .collect();
+
+        let heights = Self::compute_heights(&dependencies);
+        Self {
+            dependencies,
+            heights,
+        }
+    }
+
+    /// Computes the heights of all transactions, visiting dependents before their dependencies.
+    /// Dependencies always point to lower versions of an object, so the graph has no cycles.
+    fn compute_heights(dependencies: &[Vec<usize>]) -> Vec<u64> {
+        let mut dependents = vec![0usize; dependencies.len()];
+        for deps in dependencies {
+            for dep in deps {
+                dependents[*dep] += 1;
+            }
+        }
+        let mut heights = vec![1u64; dependencies.len()];
+        let mut queue: VecDeque<usize> = (0..dependencies.len())
+            .filter(|index| dependents[*index] == 0)
+            .collect();
+        while let Some(index) = queue.pop_front() {
+            for dep in &dependencies[index] {
+                heights[*dep] = heights[*dep].max(heights[index] + 1);
+                dependents[*dep] -= 1;
+                if dependents[*dep] == 0 {
+                    queue.push_back(*dep);
+                }
+            }
+        }
+        heights
+    }
+
+    pub fn len(&self) -> usize {
+        self.dependencies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dependencies.is_empty()
+    }
+
+    pub fn dependencies(&self, index: usize) -> &[usize] {
+        &self.dependencies[index]
+    }
+
+    /// Scheduling priority of a transaction: the length of the longest chain of transactions that
+    /// cannot start before it finishes, itself included.
+    pub fn priority(&self, index: usize) -> u64 {
+        self.heights[index]
+    }
+
+    /// Number of transactions on the longest chain of dependent transactions in the batch.
+    pub fn critical_path_len(&self) -> u64 {
+        self.heights.iter().copied().max().unwrap_or_default()
+    }
+
+    /// Average number of transactions of the batch that can execute concurrently, with unlimited
+    /// workers and equal execution times.
+    pub fn parallelism(&self) -> f64 {
+        match self.critical_path_len() {
+            0 => 0.0,
+            critical_path_len => self.len() as f64 / critical_path_len as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependencies_follow_assigned_versions() {
+        let (a, b) = (ObjectID::random(), ObjectID::random());
+        let v = SequenceNumber::from_u64;
+        let graph = SharedObjectDependencyGraph::new(&[
+            // 0 and 1 read `a` at the same version, and 2 writes it.
+            vec![(a, v(1), false)],
+            vec![(a, v(1), false)],
+            vec![(a, v(1), true)],
+            // 3 reads the version written by 2, and writes `b`.
+            vec![(a, v(2), false), (b, v(5), true)],
+            // 4 only touches `b`, after 3.
+            vec![(b, v(6), true)],
+            // 5 is independent.
+            vec![],
+        ]);
+
+        assert_eq!(graph.dependencies(0), &[] as &[usize]);
+        assert_eq!(graph.dependencies(1), &[] as &[usize]);
+        // Writers do not wait for readers of the version they replace.
+        assert_eq!(graph.dependencies(2), &[] as &[usize]);
+        assert_eq!(graph.dependencies(3), &[2]);
+        assert_eq!(graph.dependencies(4), &[3]);
+        assert_eq!(graph.dependencies(5), &[] as &[usize]);
+
+        assert_eq!(
+            (0..graph.len())
+                .map(|i| graph.priority(i))
+                .collect::<Vec<_>>(),
+            vec![1, 1, 3, 2, 1, 1]
+        );
+        assert_eq!(graph.critical_path_len(), 3);
+        assert_eq!(graph.parallelism(), 2.0);
+    }
+}
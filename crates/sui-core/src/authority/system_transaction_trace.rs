@@ -0,0 +1,177 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured logs of the system transactions executed by the node: their decoded arguments,
+//! the system state before and after, and the objects they changed. System transactions are
+//! not submitted by anyone, so these logs are the only record of what they did besides the
+//! effects. All events are logged under the [`TRACE_TARGET`] target.
+
+use move_binary_format::CompiledModule;
+use sui_config::node::SystemTransactionTraceConfig;
+use sui_types::clock::Clock;
+use sui_types::messages::{
+    ChangeEpoch, TransactionEffects, TransactionEffectsAPI, TransactionKind,
+    VerifiedExecutableTransaction,
+};
+use sui_types::object::Object;
+use sui_types::storage::ObjectStore;
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemState, SuiSystemStateTrait};
+use sui_types::temporary_store::InnerTemporaryStore;
+use sui_types::SUI_CLOCK_OBJECT_ID;
+use tracing::info;
+
+pub const TRACE_TARGET: &str = "system_transaction_trace";
+
+/// Logs the execution of `certificate` if it is a system transaction. `store` must not contain
+/// the outputs of the transaction yet, so that the system state before execution can be read.
+pub fn trace_system_transaction<S: ObjectStore>(
+    config: &SystemTransactionTraceConfig,
+    store: &S,
+    certificate: &VerifiedExecutableTransaction,
+    inner_temporary_store: &InnerTemporaryStore,
+    effects: &TransactionEffects,
+) {
+    let tx_digest = certificate.digest();
+    let status = effects.status();
+    match certificate.data().intent_message().value.kind() {
+        TransactionKind::ProgrammableTransaction(_) => return,
+        TransactionKind::ConsensusCommitPrologue(prologue) => {
+            let clock_timestamp_ms = inner_temporary_store
+                .written
+                .get(&SUI_CLOCK_OBJECT_ID)
+                .and_then(|(_, clock, _)| decode_clock(clock))
+                .map(|clock| clock.timestamp_ms);
+            info!(
+                target: TRACE_TARGET,
+                ?tx_digest,
+                ?status,
+                epoch = prologue.epoch,
+                round = prologue.round,
+                commit_timestamp_ms = prologue.commit_timestamp_ms,
+                ?clock_timestamp_ms,
+                "Executed consensus commit prologue"
+            );
+        }
+        TransactionKind::ChangeEpoch(change_epoch) => {
+            info!(
+                target: TRACE_TARGET,
+                ?tx_digest,
+                ?status,
+                next_epoch = change_epoch.epoch,
+                protocol_version = change_epoch.protocol_version.as_u64(),
+                storage_charge = change_epoch.storage_charge,
+                computation_charge = change_epoch.computation_charge,
+                storage_rebate = change_epoch.storage_rebate,
+                epoch_start_timestamp_ms = change_epoch.epoch_start_timestamp_ms,
+                system_packages = ?system_packages(change_epoch),
+                "Executed change epoch"
+            );
+            let before = get_sui_system_state(store);
+            let after = get_sui_system_state(&inner_temporary_store.written);
+            match (before, after) {
+                (Ok(before), Ok(after)) => trace_system_state_change(before, after),
+                (before, after) => info!(
+                    target: TRACE_TARGET,
+                    before_error = ?before.err(),
+                    after_error = ?after.err(),
+                    "Cannot decode the system state changed by the epoch change"
+                ),
+            }
+        }
+        TransactionKind::Genesis(genesis) => {
+            if !config.genesis {
+                return;
+            }
+            info!(
+                target: TRACE_TARGET,
+                ?tx_digest,
+                ?status,
+                objects = genesis.objects.len(),
+                "Executed genesis"
+            );
+        }
+    }
+
+    if config.object_changes {
+        trace_object_changes(inner_temporary_store);
+    }
+}
+
+fn decode_clock(object: &Object) -> Option<Clock> {
+    bcs::from_bytes(object.data.try_as_move()?.contents()).ok()
+}
+
+/// Version, package ID (if it can be decoded), module count and dependencies of each system
+/// package upgraded by the epoch change.
+fn system_packages(change_epoch: &ChangeEpoch) -> Vec<String> {
+    change_epoch
+        .system_packages
+        .iter()
+        .map(|(version, modules, dependencies)| {
+            let package_id = modules
+                .first()
+                .and_then(|module| CompiledModule::deserialize(module).ok())
+                .map(|module| module.self_id().address().to_hex_literal());
+            format!(
+                "{} v{} ({} modules, dependencies {:?})",
+                package_id.as_deref().unwrap_or("<undecodable>"),
+                version,
+                modules.len(),
+                dependencies
+            )
+        })
+        .collect()
+}
+
+fn trace_system_state_change(before: SuiSystemState, after: SuiSystemState) {
+    info!(
+        target: TRACE_TARGET,
+        epoch = %format!("{} -> {}", before.epoch(), after.epoch()),
+        protocol_version = %format!(
+            "{} -> {}",
+            before.protocol_version(),
+            after.protocol_version()
+        ),
+        system_state_version = %format!(
+            "{} -> {}",
+            before.system_state_version(),
+            after.system_state_version()
+        ),
+        reference_gas_price = %format!(
+            "{} -> {}",
+            before.reference_gas_price(),
+            after.reference_gas_price()
+        ),
+        epoch_start_timestamp_ms = %format!(
+            "{} -> {}",
+            before.epoch_start_timestamp_ms(),
+            after.epoch_start_timestamp_ms()
+        ),
+        safe_mode = %format!("{} -> {}", before.safe_mode(), after.safe_mode()),
+        "System state changed"
+    );
+}
+
+fn trace_object_changes(inner_temporary_store: &InnerTemporaryStore) {
+    for (object_ref, object, kind) in inner_temporary_store.written.values() {
+        info!(
+            target: TRACE_TARGET,
+            object_id = %object_ref.0,
+            version = object_ref.1.value(),
+            digest = %object_ref.2,
+            ?kind,
+            object_type = ?object.struct_tag(),
+            owner = %object.owner,
+            "Object written"
+        );
+    }
+    for (object_id, (version, kind)) in &inner_temporary_store.deleted {
+        info!(
+            target: TRACE_TARGET,
+            %object_id,
+            version = version.value(),
+            ?kind,
+            "Object deleted"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Spans following a transaction through its lifecycle on this node, for distributed tracing.
+//!
+//! The lifecycle span of a transaction is opened where the node first sees it: when it is
+//! submitted to the transaction orchestrator or to the validator service, or at the latest when
+//! consensus sequences it. The spans of the later stages (the signing quorum, consensus
+//! submission and execution) are children of the lifecycle span, and consensus sequencing is
+//! recorded as an event within it. The lifecycle span is closed once the transaction is included
+//! in an executed checkpoint, so its duration is the end-to-end latency of the transaction as
+//! observed by this node.
+//!
+//! Spans are only tracked when the `transaction_lifecycle` span is enabled by the subscriber, so
+//! tracing has no cost when it is not exported. At most [`MAX_TRACKED_TRANSACTIONS`] lifecycles
+//! are open at once; the oldest are closed early to make room for new ones.
+
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use sui_types::base_types::TransactionDigest;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::{info, info_span, Id, Span};
+
+pub const MAX_TRACKED_TRANSACTIONS: usize = 100_000;
+
+pub struct TransactionLifecycleSpans {
+    spans: Mutex<LruCache<TransactionDigest, Span>>,
+}
+
+impl Default for TransactionLifecycleSpans {
+    fn default() -> Self {
+        Self::new(NonZeroUsize::new(MAX_TRACKED_TRANSACTIONS).unwrap())
+    }
+}
+
+impl TransactionLifecycleSpans {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            spans: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the lifecycle span of the transaction, opening it as a child of the current span
+    /// if the transaction is not tracked yet.
+    pub fn start(&self, digest: &TransactionDigest) -> Span {
+        let mut spans = self.spans.lock();
+        if let Some(span) = spans.get(digest) {
+            return span.clone();
+        }
+        let span = info_span!("transaction_lifecycle", tx_digest = ?digest);
+        if !span.is_disabled() {
+            spans.put(*digest, span.clone());
+        }
+        span
+    }
+
+    /// The lifecycle span of the transaction, if it is tracked.
+    pub fn get(&self, digest: &TransactionDigest) -> Option<Span> {
+        self.spans.lock().get(digest).cloned()
+    }
+
+    /// Id of the lifecycle span of the transaction, to use as the explicit parent of the span of
+    /// a stage running outside of the lifecycle span.
+    pub fn parent(&self, digest: &TransactionDigest) -> Option<Id> {
+        self.get(digest).and_then(|span| span.id())
+    }
+
+    /// Records that the transaction was sequenced by consensus, opening its lifecycle span if
+    /// this node has not seen the transaction before.
+    pub fn sequenced(&self, digest: &TransactionDigest, round: u64) {
+        self.start(digest)
+            .in_scope(|| info!(round, "Transaction sequenced by consensus"));
+    }
+
+    /// Closes the lifecycle spans of the transactions of an executed checkpoint.
+    pub fn finish(&self, digests: &[TransactionDigest], checkpoint: CheckpointSequenceNumber) {
+        let mut spans = self.spans.lock();
+        for digest in digests {
+            if let Some(span) = spans.pop(digest) {
+                span.in_scope(|| info!(checkpoint, "Transaction included in checkpoint"));
+            }
+        }
+    }
+}
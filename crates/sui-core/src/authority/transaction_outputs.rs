@@ -0,0 +1,163 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read-only nodes do not execute the transactions of the checkpoints they sync. Instead they
+//! fetch the outputs of each transaction from a node that executed it, check them against the
+//! effects certified by the checkpoint, and commit them as if they had executed the transaction.
+
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use sui_network::state_sync::TransactionOutputs;
+use sui_types::digests::TransactionEffectsDigest;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::messages::{InputObjects, TransactionEffects, TransactionEffectsAPI};
+use sui_types::temporary_store::InnerTemporaryStore;
+
+/// Provides the outputs of transactions executed by another node.
+#[async_trait]
+pub trait TransactionOutputsSource: Send + Sync {
+    /// Returns the outputs of the transaction with the given effects. The node providing them may
+    /// not have executed the transaction yet, so implementations should wait for the outputs to
+    /// become available rather than fail.
+    async fn get_transaction_outputs(
+        &self,
+        effects_digest: TransactionEffectsDigest,
+    ) -> SuiResult<TransactionOutputs>;
+}
+
+/// Builds the temporary store that executing the transaction would have produced, from its
+/// certified `effects` and the `outputs` fetched from another node.
+pub fn outputs_to_temporary_store(
+    effects: &TransactionEffects,
+    outputs: TransactionOutputs,
+    input_objects: InputObjects,
+    max_binary_format_version: u32,
+) -> SuiResult<InnerTemporaryStore> {
+    let digest = *effects.transaction_digest();
+    let invalid = |error: String| SuiError::InvalidTransactionOutputs { digest, error };
+
+    match effects.events_digest() {
+        Some(expected) if *expected != outputs.events.digest() => {
+            return Err(invalid(format!(
+                "events digest {:?} does not match the effects, expected {expected:?}",
+                outputs.events.digest()
+            )));
+        }
+        None if !outputs.events.data.is_empty() => {
+            return Err(invalid("the effects have no events".to_string()));
+        }
+        _ => (),
+    }
+
+    let mut objects: BTreeMap<_, _> = outputs
+        .objects
+        .into_iter()
+        .map(|object| (object.id(), object))
+        .collect();
+    let mut written = BTreeMap::new();
+    for (object_ref, _, kind) in effects.all_changed_objects() {
+        let object = objects
+            .remove(&object_ref.0)
+            .ok_or_else(|| invalid(format!("missing object {object_ref:?}")))?;
+        let actual_ref = object.compute_object_reference();
+        if actual_ref != *object_ref {
+            return Err(invalid(format!(
+                "object {actual_ref:?} does not match the effects, expected {object_ref:?}"
+            )));
+        }
+        written.insert(object_ref.0, (*object_ref, object, kind));
+    }
+    if let Some(object_id) = objects.keys().next() {
+        return Err(invalid(format!("unexpected object {object_id}")));
+    }
+
+    let deleted = effects
+        .all_deleted()
+        .into_iter()
+        .map(|((object_id, version, _), kind)| (*object_id, (*version, kind)))
+        .collect();
+
+    Ok(InnerTemporaryStore {
+        mutable_inputs: input_objects.mutable_inputs(),
+        objects: input_objects.into_object_map(),
+        written,
+        deleted,
+        events: outputs.events,
+        max_binary_format_version,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::{ObjectID, TransactionDigest};
+    use sui_types::messages::TransactionEffectsV1;
+    use sui_types::object::Object;
+    use sui_types::storage::WriteKind;
+
+    fn effects_creating(object: &Object) -> TransactionEffects {
+        TransactionEffects::V1(TransactionEffectsV1 {
+            transaction_digest: TransactionDigest::random(),
+            created: vec![(object.compute_object_reference(), object.owner)],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_outputs_matching_effects() {
+        let object = Object::immutable_with_id_for_testing(ObjectID::random());
+        let effects = effects_creating(&object);
+        let outputs = TransactionOutputs {
+            events: Default::default(),
+            objects: vec![object.clone()],
+        };
+
+        let store =
+            outputs_to_temporary_store(&effects, outputs, InputObjects::new(vec![]), 6).unwrap();
+        assert_eq!(
+            store.written.get(&object.id()),
+            Some(&(object.compute_object_reference(), object, WriteKind::Create))
+        );
+        assert!(store.deleted.is_empty());
+    }
+
+    #[test]
+    fn test_outputs_not_matching_effects() {
+        let object = Object::immutable_with_id_for_testing(ObjectID::random());
+        let effects = effects_creating(&object);
+
+        // Missing object
+        let outputs = TransactionOutputs {
+            events: Default::default(),
+            objects: vec![],
+        };
+        assert!(matches!(
+            outputs_to_temporary_store(&effects, outputs, InputObjects::new(vec![]), 6),
+            Err(SuiError::InvalidTransactionOutputs { .. })
+        ));
+
+        // Object with different contents
+        let other = Object::with_id_owner_for_testing(object.id(), Default::default());
+        let outputs = TransactionOutputs {
+            events: Default::default(),
+            objects: vec![other],
+        };
+        assert!(matches!(
+            outputs_to_temporary_store(&effects, outputs, InputObjects::new(vec![]), 6),
+            Err(SuiError::InvalidTransactionOutputs { .. })
+        ));
+
+        // Object not in the effects
+        let outputs = TransactionOutputs {
+            events: Default::default(),
+            objects: vec![
+                object,
+                Object::immutable_with_id_for_testing(ObjectID::random()),
+            ],
+        };
+        assert!(matches!(
+            outputs_to_temporary_store(&effects, outputs, InputObjects::new(vec![]), 6),
+            Err(SuiError::InvalidTransactionOutputs { .. })
+        ));
+    }
+}
@@ -12,6 +12,7 @@ use mysten_metrics::monitored_future;
 use mysten_network::config::Config;
 use std::convert::AsRef;
 use sui_config::genesis::Genesis;
+use sui_config::node::ValidatorSamplingStrategy;
 use sui_config::NetworkConfig;
 use sui_network::{
     default_mysten_network_config, DEFAULT_CONNECT_TIMEOUT_SEC, DEFAULT_REQUEST_TIMEOUT_SEC,
@@ -251,6 +252,13 @@ pub enum AggregatorProcessCertificateError {
     },
 }
 
+struct ProcessCertificateBundleState {
+    // The certificates of a bundle are aggregated independently, but every validator response
+    // covers all of them.
+    certificates: Vec<ProcessCertificateState>,
+    certified: Vec<Option<(VerifiedCertifiedTransactionEffects, TransactionEvents)>>,
+}
+
 struct ProcessTransactionState {
     // The list of signatures gathered at any point
     tx_signatures: StakeAggregator<AuthoritySignInfo, true>,
@@ -337,6 +345,8 @@ pub struct AuthorityAggregator<A> {
     /// Metric base for the purpose of creating new safe clients during reconfiguration.
     pub safe_client_metrics_base: SafeClientMetricsBase,
     pub timeouts: TimeoutConfig,
+    /// Order in which authorities are contacted.
+    pub sampling: ValidatorSamplingStrategy,
     /// Store here for clone during re-config.
     pub committee_store: Arc<CommitteeStore>,
 }
@@ -384,6 +394,7 @@ impl<A> AuthorityAggregator<A> {
             metrics: AuthAggMetrics::new(registry),
             safe_client_metrics_base,
             timeouts,
+            sampling: Default::default(),
             committee_store,
         }
     }
@@ -414,6 +425,7 @@ impl<A> AuthorityAggregator<A> {
             metrics: auth_agg_metrics,
             safe_client_metrics_base,
             timeouts: Default::default(),
+            sampling: Default::default(),
             committee_store,
         }
     }
@@ -479,11 +491,28 @@ impl<A> AuthorityAggregator<A> {
             authority_clients: safe_clients,
             metrics: self.metrics.clone(),
             timeouts: self.timeouts.clone(),
+            sampling: self.sampling,
             safe_client_metrics_base: self.safe_client_metrics_base.clone(),
             committee_store: self.committee_store.clone(),
         })
     }
 
+    /// Returns the authorities in the order they should be contacted.
+    fn sample_authorities(
+        &self,
+        preferences: Option<&BTreeSet<AuthorityName>>,
+        restrict_to: Option<&BTreeSet<AuthorityName>>,
+    ) -> Vec<AuthorityName> {
+        match self.sampling {
+            ValidatorSamplingStrategy::StakeWeighted => {
+                self.committee.shuffle_by_stake(preferences, restrict_to)
+            }
+            ValidatorSamplingStrategy::Uniform => {
+                self.committee.shuffle_uniformly(preferences, restrict_to)
+            }
+        }
+    }
+
     pub fn get_client(&self, name: &AuthorityName) -> Option<&SafeClient<A>> {
         self.authority_clients.get(name)
     }
@@ -628,7 +657,7 @@ where
             Result<V, SuiError>,
         ) -> BoxFuture<'a, ReduceOutput<R, S>>,
     {
-        let authorities_shuffled = self.committee.shuffle_by_stake(authority_preferences, None);
+        let authorities_shuffled = self.sample_authorities(authority_preferences, None);
 
         // First, execute in parallel for each authority FMap.
         let mut responses: futures::stream::FuturesUnordered<_> = authorities_shuffled
@@ -636,12 +665,14 @@ where
             .map(|name| {
                 let client = &self.authority_clients[name];
                 let execute = map_each_authority.clone();
+                let request_timeout = self.timeouts.authority_request_timeout;
                 monitored_future!(async move {
                     (
                         *name,
-                        execute(*name, client)
+                        timeout(request_timeout, execute(*name, client))
                             .instrument(tracing::trace_span!("quorum_map_auth", authority =? name.concise()))
-                            .await,
+                            .await
+                            .unwrap_or(Err(SuiError::TimeoutError)),
                     )
                 })
             })
@@ -701,7 +732,7 @@ where
         let start = tokio::time::Instant::now();
         let mut delay = Duration::from_secs(1);
         loop {
-            let authorities_shuffled = self.committee.shuffle_by_stake(preferences, restrict_to);
+            let authorities_shuffled = self.sample_authorities(preferences, restrict_to);
             let mut authorities_shuffled = authorities_shuffled.iter();
 
             type RequestResult<S> = Result<Result<S, SuiError>, tokio::time::error::Elapsed>;
@@ -1426,26 +1457,11 @@ where
                             } else {
                                 ReduceOutput::Continue(state)
                             }
-                        },
+                        }
                         Err(err) => {
-                            let concise_name = name.concise();
-                            debug!(?tx_digest, name=?concise_name, "Error processing certificate from validator: {:?}", err);
-                            self.metrics
-                                .process_cert_errors
-                                .with_label_values(&[&concise_name.to_string(), err.as_ref()])
-                                .inc();
-                            let (retryable, categorized) = err.is_retryable();
-                            if !categorized {
-                                // TODO: Should minimize possible uncategorized errors here
-                                // use ERROR for now to make them easier to spot.
-                                error!(?tx_digest, "uncategorized tx error: {err}");
-                            }
-                            if !retryable {
-                                state.non_retryable_stake += weight;
-                                state.non_retryable_errors.push((err, vec![name], weight));
-                            } else {
-                                state.retryable_errors.push((err, vec![name], weight));
-                            }
+                            self.record_process_certificate_error(
+                                &tx_digest, &mut state, err, name, weight,
+                            );
                             if state.non_retryable_stake >= validity {
                                 state.retryable = false;
                                 ReduceOutput::Failed(state)
@@ -1460,39 +1476,184 @@ where
             self.timeouts.pre_quorum_timeout,
         )
         .await
+        .map_err(|state| self.process_certificate_error(&tx_digest, state))
+    }
+
+    /// Sends a soft bundle of certificates to the validators, which submit it to consensus so that
+    /// the certificates are sequenced contiguously and in order. Returns the certified effects of
+    /// each certificate, once a quorum of validators agrees on the effects of every one of them.
+    pub async fn process_certificate_bundle(
+        &self,
+        certificates: Vec<CertifiedTransaction>,
+    ) -> Result<
+        Vec<(VerifiedCertifiedTransactionEffects, TransactionEvents)>,
+        AggregatorProcessCertificateError,
+    > {
+        let state = ProcessCertificateBundleState {
+            certificates: certificates
+                .iter()
+                .map(|_| ProcessCertificateState {
+                    effects_map: MultiStakeAggregator::new(Arc::new(self.committee.clone())),
+                    non_retryable_stake: 0,
+                    non_retryable_errors: vec![],
+                    retryable_errors: vec![],
+                    retryable: true,
+                })
+                .collect(),
+            certified: certificates.iter().map(|_| None).collect(),
+        };
+
+        let tx_digests: Vec<_> = certificates.iter().map(|cert| *cert.digest()).collect();
+        let digests_ref = &tx_digests;
+        let certificates_ref = &certificates;
+        let validity = self.committee.validity_threshold();
+        debug!(?tx_digests, "Broadcasting soft bundle to authorities");
+        self.quorum_map_then_reduce_with_timeout(
+            state,
+            |name, client| {
+                Box::pin(async move {
+                    client
+                        .handle_certificate_bundle(certificates_ref.clone())
+                        .instrument(tracing::trace_span!(
+                            "handle_certificate_bundle",
+                            authority =? name.concise()
+                        ))
+                        .await
+                })
+            },
+            |mut state, name, weight, response| {
+                Box::pin(async move {
+                    let pending = state
+                        .certified
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, certified)| certified.is_none())
+                        .map(|(index, _)| index)
+                        .collect::<Vec<_>>();
+                    for index in pending {
+                        let response = response
+                            .as_ref()
+                            .map(|responses| responses[index].clone())
+                            .map_err(Clone::clone);
+                        let tx_digest = &digests_ref[index];
+                        let certificate_state = &mut state.certificates[index];
+                        match self.handle_process_certificate_response(
+                            tx_digest,
+                            certificate_state,
+                            response,
+                            name,
+                        ) {
+                            Ok(Some(effects)) => state.certified[index] = Some(effects),
+                            Ok(None) => {}
+                            Err(err) => self.record_process_certificate_error(
+                                tx_digest,
+                                certificate_state,
+                                err,
+                                name,
+                                weight,
+                            ),
+                        }
+                    }
+                    if state.certified.iter().all(Option::is_some) {
+                        ReduceOutput::Success(state.certified.into_iter().flatten().collect())
+                    } else if state
+                        .certificates
+                        .iter()
+                        .any(|certificate| certificate.non_retryable_stake >= validity)
+                    {
+                        for certificate in &mut state.certificates {
+                            certificate.retryable = false;
+                        }
+                        ReduceOutput::Failed(state)
+                    } else {
+                        ReduceOutput::Continue(state)
+                    }
+                })
+            },
+            // A long timeout before we hear back from a quorum
+            self.timeouts.pre_quorum_timeout,
+        )
+        .await
         .map_err(|state| {
-            debug!(
-                ?tx_digest,
-                num_unique_effects = state.effects_map.unique_key_count(),
-                non_retryable_stake = state.non_retryable_stake,
-                "Received effects responses from validators"
-            );
+            // Report the errors of the first certificate that failed, or that is still pending.
+            let (index, certificate_state) = state
+                .certificates
+                .into_iter()
+                .enumerate()
+                .filter(|(index, _)| state.certified[*index].is_none())
+                .min_by_key(|(_, certificate)| certificate.non_retryable_stake < validity)
+                .expect("A failed bundle has a certificate without certified effects");
+            self.process_certificate_error(&tx_digests[index], certificate_state)
+        })
+    }
 
-            // record errors and tx retryable state
-            for (sui_err, _, _) in state.retryable_errors.iter().chain(state.non_retryable_errors.iter()) {
-                self
-                    .metrics
-                    .total_aggregated_err
-                    .with_label_values(&[
-                        sui_err.as_ref(),
-                        if state.retryable {
-                            "recoverable"
-                        } else {
-                            "non-recoverable"
-                        },
-                    ])
-                    .inc();
+    fn record_process_certificate_error(
+        &self,
+        tx_digest: &TransactionDigest,
+        state: &mut ProcessCertificateState,
+        err: SuiError,
+        name: AuthorityName,
+        weight: StakeUnit,
+    ) {
+        let concise_name = name.concise();
+        debug!(?tx_digest, name=?concise_name, "Error processing certificate from validator: {:?}", err);
+        self.metrics
+            .process_cert_errors
+            .with_label_values(&[&concise_name.to_string(), err.as_ref()])
+            .inc();
+        let (retryable, categorized) = err.is_retryable();
+        if !categorized {
+            // TODO: Should minimize possible uncategorized errors here
+            // use ERROR for now to make them easier to spot.
+            error!(?tx_digest, "uncategorized tx error: {err}");
+        }
+        if !retryable {
+            state.non_retryable_stake += weight;
+            state.non_retryable_errors.push((err, vec![name], weight));
+        } else {
+            state.retryable_errors.push((err, vec![name], weight));
+        }
+    }
+
+    fn process_certificate_error(
+        &self,
+        tx_digest: &TransactionDigest,
+        state: ProcessCertificateState,
+    ) -> AggregatorProcessCertificateError {
+        debug!(
+            ?tx_digest,
+            num_unique_effects = state.effects_map.unique_key_count(),
+            non_retryable_stake = state.non_retryable_stake,
+            "Received effects responses from validators"
+        );
+
+        // record errors and tx retryable state
+        for (sui_err, _, _) in state
+            .retryable_errors
+            .iter()
+            .chain(state.non_retryable_errors.iter())
+        {
+            self.metrics
+                .total_aggregated_err
+                .with_label_values(&[
+                    sui_err.as_ref(),
+                    if state.retryable {
+                        "recoverable"
+                    } else {
+                        "non-recoverable"
+                    },
+                ])
+                .inc();
+        }
+        if state.retryable {
+            AggregatorProcessCertificateError::RetryableExecuteCertificate {
+                retryable_errors: state.retryable_errors,
             }
-            if state.retryable {
-                AggregatorProcessCertificateError::RetryableExecuteCertificate {
-                    retryable_errors: state.retryable_errors,
-                }
-            } else {
-                AggregatorProcessCertificateError::FatalExecuteCertificate {
-                    non_retryable_errors: state.non_retryable_errors,
-                }
+        } else {
+            AggregatorProcessCertificateError::FatalExecuteCertificate {
+                non_retryable_errors: state.non_retryable_errors,
             }
-        })
+        }
     }
 
     fn handle_process_certificate_response(
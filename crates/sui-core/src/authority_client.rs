@@ -31,6 +31,12 @@ pub trait AuthorityAPI {
         certificate: CertifiedTransaction,
     ) -> Result<HandleCertificateResponse, SuiError>;
 
+    /// Execute a soft bundle of certificates, sequenced contiguously and in order.
+    async fn handle_certificate_bundle(
+        &self,
+        request: HandleCertificateBundleRequest,
+    ) -> Result<HandleCertificateBundleResponse, SuiError>;
+
     /// Handle Object information requests for this account.
     async fn handle_object_info_request(
         &self,
@@ -112,6 +118,17 @@ impl AuthorityAPI for NetworkAuthorityClient {
             .map_err(Into::into)
     }
 
+    async fn handle_certificate_bundle(
+        &self,
+        request: HandleCertificateBundleRequest,
+    ) -> Result<HandleCertificateBundleResponse, SuiError> {
+        self.client()
+            .handle_certificate_bundle(request)
+            .await
+            .map(tonic::Response::into_inner)
+            .map_err(Into::into)
+    }
+
     async fn handle_object_info_request(
         &self,
         request: ObjectInfoRequest,
@@ -4,12 +4,13 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::future;
 use mysten_metrics::spawn_monitored_task;
 use prometheus::{
     register_histogram_with_registry, register_int_counter_with_registry, Histogram, IntCounter,
     Registry,
 };
-use std::{io, sync::Arc};
+use std::{collections::HashSet, io, sync::Arc};
 use sui_network::{
     api::{Validator, ValidatorServer},
     tonic,
@@ -29,6 +30,7 @@ use crate::consensus_adapter::{ConnectionMonitorStatusForTests, LazyNarwhalClien
 use crate::{
     authority::{AuthorityState, MAX_PER_OBJECT_EXECUTION_QUEUE_LENGTH},
     consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics},
+    slow_ops::SlowOpKind,
 };
 
 #[cfg(test)]
@@ -135,6 +137,7 @@ pub struct ValidatorServiceMetrics {
     pub handle_transaction_latency: Histogram,
     pub handle_certificate_consensus_latency: Histogram,
     pub handle_certificate_non_consensus_latency: Histogram,
+    pub handle_certificate_bundle_latency: Histogram,
 
     num_rejected_tx_in_epoch_boundary: IntCounter,
     num_rejected_cert_in_epoch_boundary: IntCounter,
@@ -195,6 +198,13 @@ impl ValidatorServiceMetrics {
                 registry,
             )
             .unwrap(),
+            handle_certificate_bundle_latency: register_histogram_with_registry!(
+                "validator_service_handle_certificate_bundle_latency",
+                "Latency of handling a soft bundle of transaction certificates",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             num_rejected_tx_in_epoch_boundary: register_int_counter_with_registry!(
                 "validator_service_num_rejected_tx_in_epoch_boundary",
                 "Number of rejected transaction during epoch transitioning",
@@ -242,6 +252,11 @@ impl ValidatorService {
     ) -> Result<tonic::Response<HandleTransactionResponse>, tonic::Status> {
         let transaction = request.into_inner();
         let epoch_store = state.load_epoch_store_one_call_per_task();
+        let mut slow_op = state.slow_ops.start(
+            SlowOpKind::Rpc,
+            "handle_transaction",
+            Some(transaction.digest().to_string()),
+        );
 
         // Enforce overall transaction size limit.
         let tx_size = bcs::serialized_size(&transaction)
@@ -257,20 +272,24 @@ impl ValidatorService {
         let _metrics_guard = metrics.handle_transaction_latency.start_timer();
         let tx_verif_metrics_guard = metrics.tx_verification_latency.start_timer();
 
-        epoch_store
+        let transaction = epoch_store
             .signature_verifier
-            .verify_tx(transaction.data())
+            .verify_transaction(transaction)
+            .await
             .tap_err(|_| {
                 metrics.signature_errors.inc();
             })?;
-        let transaction = VerifiedTransaction::new_from_verified(transaction);
 
         tx_verif_metrics_guard.stop_and_record();
+        slow_op.phase("verify_tx");
 
         let tx_digest = transaction.digest();
 
         // Enable Trace Propagation across spans/processes using tx_digest
-        let span = error_span!("validator_state_process_tx", ?tx_digest);
+        let span = epoch_store
+            .transaction_lifecycle()
+            .start(tx_digest)
+            .in_scope(|| error_span!("validator_state_process_tx", ?tx_digest));
 
         let info = state
             .handle_transaction(&epoch_store, transaction)
@@ -281,6 +300,7 @@ impl ValidatorService {
                     metrics.num_rejected_tx_in_epoch_boundary.inc();
                 }
             })?;
+        slow_op.phase("sign");
 
         Ok(tonic::Response::new(info))
     }
@@ -295,6 +315,11 @@ impl ValidatorService {
         let epoch_store = state.load_epoch_store_one_call_per_task();
 
         let certificate = request.into_inner();
+        let mut slow_op = state.slow_ops.start(
+            SlowOpKind::Rpc,
+            "handle_certificate",
+            Some(certificate.digest().to_string()),
+        );
 
         let shared_object_tx = certificate.contains_shared_object();
 
@@ -366,6 +391,7 @@ impl ValidatorService {
                     .verify_cert(certificate)
                     .await?
             };
+            slow_op.phase("verify_cert");
 
             let reconfiguration_lock = epoch_store.get_reconfig_state_read_lock_guard();
             if !reconfiguration_lock.should_accept_user_certs() {
@@ -397,12 +423,14 @@ impl ValidatorService {
                 // Instead, check or wait for the existence of certificate effects below.
             }
             drop(reconfiguration_lock);
+            slow_op.phase("submit_to_consensus");
             certificate
         };
 
         // 4) Execute the certificate if it contains only owned object transactions, or wait for
         // the execution results if it contains shared objects.
         let res = state.execute_certificate(&certificate, &epoch_store).await;
+        slow_op.phase("execute");
         match res {
             Ok(effects) => {
                 let events = if let Some(event_digest) = effects.events_digest() {
@@ -418,6 +446,144 @@ impl ValidatorService {
             Err(e) => Err(tonic::Status::from(e)),
         }
     }
+
+    /// Submits the certificates of a soft bundle to consensus in a single consensus transaction,
+    /// so that they are sequenced contiguously and in order, then waits for all of them to be
+    /// executed. Certificates that were sequenced before would break the ordering, so a bundle
+    /// is rejected unless none or all of its certificates have been sequenced already.
+    async fn handle_certificate_bundle(
+        state: Arc<AuthorityState>,
+        consensus_adapter: Arc<ConsensusAdapter>,
+        request: tonic::Request<HandleCertificateBundleRequest>,
+        metrics: Arc<ValidatorServiceMetrics>,
+    ) -> Result<tonic::Response<HandleCertificateBundleResponse>, tonic::Status> {
+        let epoch_store = state.load_epoch_store_one_call_per_task();
+        let certificates = request.into_inner().certificates;
+        let _metrics_guard = metrics.handle_certificate_bundle_latency.start_timer();
+
+        // 1) Check the bundle against the protocol config.
+        let protocol_config = epoch_store.protocol_config();
+        protocol_config
+            .check_soft_bundle_supported()
+            .map_err(|err| SuiError::UnsupportedFeatureError { error: err.0 })?;
+        let max_soft_bundle_size = protocol_config.max_soft_bundle_size();
+        fp_ensure!(
+            !certificates.is_empty() && certificates.len() as u64 <= max_soft_bundle_size,
+            tonic::Status::invalid_argument(format!(
+                "soft bundle size ({}) must be between 1 and {max_soft_bundle_size}",
+                certificates.len()
+            ))
+        );
+        let digests: HashSet<_> = certificates.iter().map(|cert| *cert.digest()).collect();
+        fp_ensure!(
+            digests.len() == certificates.len(),
+            tonic::Status::invalid_argument("soft bundle contains duplicate certificates")
+        );
+        if state.is_fullnode(&epoch_store) {
+            return Err(tonic::Status::unimplemented(
+                "Cannot execute soft bundle on fullnode!",
+            ));
+        }
+        if let Some(certificate) = certificates.iter().find(|cert| cert.is_system_tx()) {
+            return Err(tonic::Status::invalid_argument(format!(
+                "Cannot execute system certificate via RPC interface! {certificate:?}"
+            )));
+        }
+
+        // 2) A bundle that was fully executed before is answered from the stored effects, so that
+        // clients can retry.
+        let mut executed = Vec::with_capacity(certificates.len());
+        for certificate in &certificates {
+            match state.get_signed_effects_and_maybe_resign(certificate.digest(), &epoch_store)? {
+                Some(signed_effects) => executed.push(signed_effects),
+                None => break,
+            }
+        }
+        if executed.len() == certificates.len() {
+            let responses = executed
+                .into_iter()
+                .map(|signed_effects| certificate_response(&state, signed_effects.into_inner()))
+                .collect::<SuiResult<_>>()?;
+            return Ok(tonic::Response::new(HandleCertificateBundleResponse {
+                responses,
+            }));
+        }
+
+        // code block within reconfiguration lock
+        let certificates = {
+            let mut verified = Vec::with_capacity(certificates.len());
+            {
+                let _timer = metrics.cert_verification_latency.start_timer();
+                for certificate in certificates {
+                    verified.push(
+                        epoch_store
+                            .signature_verifier
+                            .verify_cert(certificate)
+                            .await?,
+                    );
+                }
+            }
+
+            let reconfiguration_lock = epoch_store.get_reconfig_state_read_lock_guard();
+            if !reconfiguration_lock.should_accept_user_certs() {
+                metrics.num_rejected_cert_in_epoch_boundary.inc();
+                return Err(SuiError::ValidatorHaltedAtEpochEnd.into());
+            }
+
+            // 3) Submit the bundle to consensus, unless it was sequenced before.
+            let mut sequenced = 0;
+            for certificate in &verified {
+                if epoch_store.is_tx_cert_consensus_message_processed(certificate)? {
+                    sequenced += 1;
+                }
+            }
+            if sequenced == 0 {
+                if !consensus_adapter.check_limits() {
+                    return Err(tonic::Status::resource_exhausted(
+                        "Reached maximum transactions pending in consensus. Consensus is overloaded.".to_string()
+                    ));
+                }
+                let transaction = ConsensusTransaction::new_certificate_bundle_message(
+                    &state.name,
+                    verified.iter().map(|cert| cert.clone().into()).collect(),
+                );
+                consensus_adapter.submit(transaction, Some(&reconfiguration_lock), &epoch_store)?;
+            } else if sequenced < verified.len() {
+                return Err(tonic::Status::invalid_argument(format!(
+                    "{sequenced} of the {} certificates of the soft bundle were already sequenced",
+                    verified.len()
+                )));
+            }
+            drop(reconfiguration_lock);
+            verified
+        };
+
+        // 4) Execute the owned object certificates, and wait for the execution results of the
+        // others.
+        let responses = future::try_join_all(certificates.iter().map(|certificate| async {
+            let effects = state.execute_certificate(certificate, &epoch_store).await?;
+            certificate_response(&state, effects.into_inner())
+        }))
+        .await?;
+        Ok(tonic::Response::new(HandleCertificateBundleResponse {
+            responses,
+        }))
+    }
+}
+
+fn certificate_response(
+    state: &AuthorityState,
+    signed_effects: SignedTransactionEffects,
+) -> SuiResult<HandleCertificateResponse> {
+    let events = if let Some(digest) = signed_effects.events_digest() {
+        state.get_transaction_events(digest)?
+    } else {
+        TransactionEvents::default()
+    };
+    Ok(HandleCertificateResponse {
+        signed_effects,
+        events,
+    })
 }
 
 #[async_trait]
@@ -447,7 +613,12 @@ impl Validator for ValidatorService {
         // processing in the event that the client connection is dropped.
         let metrics = self.metrics.clone();
         spawn_monitored_task!(async move {
-            let span = error_span!("handle_certificate", tx_digest = ?request.get_ref().digest());
+            let tx_digest = *request.get_ref().digest();
+            let span = state
+                .load_epoch_store_one_call_per_task()
+                .transaction_lifecycle()
+                .start(&tx_digest)
+                .in_scope(|| error_span!("handle_certificate", ?tx_digest));
             Self::handle_certificate(state, consensus_adapter, request, metrics)
                 .instrument(span)
                 .await
@@ -456,6 +627,29 @@ impl Validator for ValidatorService {
         .unwrap()
     }
 
+    async fn handle_certificate_bundle(
+        &self,
+        request: tonic::Request<HandleCertificateBundleRequest>,
+    ) -> Result<tonic::Response<HandleCertificateBundleResponse>, tonic::Status> {
+        let state = self.state.clone();
+        let consensus_adapter = self.consensus_adapter.clone();
+
+        // Spawns a task which handles the bundle. The task will unconditionally continue
+        // processing in the event that the client connection is dropped.
+        let metrics = self.metrics.clone();
+        spawn_monitored_task!(async move {
+            let span = error_span!(
+                "handle_certificate_bundle",
+                size = request.get_ref().certificates.len()
+            );
+            Self::handle_certificate_bundle(state, consensus_adapter, request, metrics)
+                .instrument(span)
+                .await
+        })
+        .await
+        .unwrap()
+    }
+
     async fn object_info(
         &self,
         request: tonic::Request<ObjectInfoRequest>,
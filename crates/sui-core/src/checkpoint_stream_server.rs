@@ -0,0 +1,258 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! gRPC service streaming full checkpoint data, from a requested sequence number onwards, to
+//! indexers and other clients following the chain.
+//!
+//! Every stream is fed by a task reading executed checkpoints ahead of the client, into a bounded
+//! channel. The task waits whenever the channel is full, so that a slow client only makes the node
+//! read as many checkpoints ahead as it allowed, and stops once the client goes away. Once caught
+//! up with the node, the task polls for newly executed checkpoints.
+
+use async_trait::async_trait;
+use mysten_metrics::spawn_monitored_task;
+use prometheus::{
+    register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
+    Registry,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::node::CheckpointStreamConfig;
+use sui_network::{api::CheckpointStream, tonic};
+use sui_types::error::SuiError;
+use sui_types::messages::{InputObjectKind, TransactionDataAPI, TransactionEffectsAPI};
+use sui_types::messages_checkpoint::{
+    CheckpointData, CheckpointSequenceNumber, CheckpointStreamRequest, CheckpointTransaction,
+};
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::debug;
+
+use crate::authority::AuthorityState;
+
+pub struct CheckpointStreamMetrics {
+    pub active_streams: IntGauge,
+    pub checkpoints_streamed: IntCounter,
+    pub stream_errors: IntCounter,
+}
+
+impl CheckpointStreamMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            active_streams: register_int_gauge_with_registry!(
+                "active_streams",
+                "Number of clients currently streaming checkpoints",
+                registry,
+            )
+            .unwrap(),
+            checkpoints_streamed: register_int_counter_with_registry!(
+                "checkpoints_streamed",
+                "Number of checkpoints sent to streaming clients",
+                registry,
+            )
+            .unwrap(),
+            stream_errors: register_int_counter_with_registry!(
+                "stream_errors",
+                "Number of streams ended by an error reading checkpoint data",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+pub struct CheckpointStreamService {
+    state: Arc<AuthorityState>,
+    config: CheckpointStreamConfig,
+    metrics: Arc<CheckpointStreamMetrics>,
+}
+
+impl CheckpointStreamService {
+    pub fn new(
+        state: Arc<AuthorityState>,
+        config: CheckpointStreamConfig,
+        prometheus_registry: &Registry,
+    ) -> Self {
+        Self {
+            state,
+            config,
+            metrics: Arc::new(CheckpointStreamMetrics::new(prometheus_registry)),
+        }
+    }
+
+    async fn stream(
+        state: Arc<AuthorityState>,
+        mut sequence_number: CheckpointSequenceNumber,
+        poll_interval: Duration,
+        metrics: Arc<CheckpointStreamMetrics>,
+        tx: mpsc::Sender<Result<CheckpointData, tonic::Status>>,
+    ) {
+        metrics.active_streams.inc();
+        loop {
+            let highest_executed = match state
+                .get_checkpoint_store()
+                .get_highest_executed_checkpoint_seq_number()
+            {
+                Ok(highest_executed) => highest_executed,
+                Err(e) => {
+                    metrics.stream_errors.inc();
+                    let _ = tx.send(Err(SuiError::from(e).into())).await;
+                    break;
+                }
+            };
+            if highest_executed.map_or(true, |highest| highest < sequence_number) {
+                tokio::select! {
+                    _ = tokio::time::sleep(poll_interval) => continue,
+                    _ = tx.closed() => break,
+                }
+            }
+
+            let data = get_checkpoint_data(&state, sequence_number);
+            let failed = data.is_err();
+            if failed {
+                metrics.stream_errors.inc();
+            }
+            if tx.send(data).await.is_err() {
+                debug!(sequence_number, "Checkpoint stream closed by the client");
+                break;
+            }
+            if failed {
+                break;
+            }
+            metrics.checkpoints_streamed.inc();
+            sequence_number += 1;
+        }
+        metrics.active_streams.dec();
+    }
+}
+
+#[async_trait]
+impl CheckpointStream for CheckpointStreamService {
+    type StreamCheckpointsStream = ReceiverStream<Result<CheckpointData, tonic::Status>>;
+
+    async fn stream_checkpoints(
+        &self,
+        request: tonic::Request<CheckpointStreamRequest>,
+    ) -> Result<tonic::Response<Self::StreamCheckpointsStream>, tonic::Status> {
+        let request = request.into_inner();
+        let max_buffered = self.config.max_buffered_checkpoints.max(1);
+        let buffered = request
+            .max_buffered_checkpoints
+            .unwrap_or(max_buffered)
+            .clamp(1, max_buffered);
+
+        let (tx, rx) = mpsc::channel(buffered as usize);
+        spawn_monitored_task!(Self::stream(
+            self.state.clone(),
+            request.start_sequence_number,
+            Duration::from_millis(self.config.poll_interval_ms),
+            self.metrics.clone(),
+            tx,
+        ));
+        Ok(tonic::Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+/// Reads the executed checkpoint `sequence_number` together with its transactions, their effects,
+/// events, and input and output objects. Fails with `NotFound` if any of them is missing, e.g.
+/// because it was pruned.
+pub fn get_checkpoint_data(
+    state: &AuthorityState,
+    sequence_number: CheckpointSequenceNumber,
+) -> Result<CheckpointData, tonic::Status> {
+    let checkpoint_store = state.get_checkpoint_store();
+    let summary = checkpoint_store
+        .get_checkpoint_by_sequence_number(sequence_number)
+        .map_err(SuiError::from)?
+        .ok_or_else(|| not_found(format!("Checkpoint {sequence_number}")))?;
+    let contents = checkpoint_store
+        .get_checkpoint_contents(&summary.content_digest)
+        .map_err(SuiError::from)?
+        .ok_or_else(|| not_found(format!("Contents of checkpoint {sequence_number}")))?;
+
+    let digests: Vec<_> = contents.iter().map(|digests| digests.transaction).collect();
+    let transactions = state.database.multi_get_transactions(&digests)?;
+    let effects = state.database.multi_get_executed_effects(&digests)?;
+
+    let mut checkpoint_transactions = Vec::with_capacity(digests.len());
+    for ((digest, transaction), effects) in digests.iter().zip(transactions).zip(effects) {
+        let transaction =
+            transaction.ok_or_else(|| not_found(format!("Transaction {digest:?}")))?;
+        let effects =
+            effects.ok_or_else(|| not_found(format!("Effects of transaction {digest:?}")))?;
+        let events = match effects.events_digest() {
+            Some(events_digest) => Some(
+                state
+                    .database
+                    .get_events(events_digest)
+                    .map_err(SuiError::from)?
+                    .ok_or_else(|| not_found(format!("Events of transaction {digest:?}")))?,
+            ),
+            None => None,
+        };
+
+        // Objects read by the transaction: those it modified, the shared objects, and the
+        // immutable objects it took as input.
+        let mut input_keys: Vec<_> = effects
+            .modified_at_versions()
+            .iter()
+            .map(|(id, version)| ObjectKey(*id, *version))
+            .chain(
+                effects
+                    .shared_objects()
+                    .iter()
+                    .map(|(id, version, _)| ObjectKey(*id, *version)),
+            )
+            .collect();
+        let input_objects = transaction
+            .data()
+            .transaction_data()
+            .input_objects()
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        input_keys.extend(input_objects.into_iter().filter_map(|kind| match kind {
+            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => Some(ObjectKey(id, version)),
+            _ => None,
+        }));
+        let mut seen = HashSet::new();
+        input_keys.retain(|key| seen.insert(*key));
+
+        let output_keys: Vec<_> = effects
+            .all_changed_objects()
+            .into_iter()
+            .map(|((id, version, _), _, _)| ObjectKey(*id, *version))
+            .collect();
+
+        checkpoint_transactions.push(CheckpointTransaction {
+            transaction: transaction.into_inner(),
+            events,
+            input_objects: get_objects(state, &input_keys)?,
+            output_objects: get_objects(state, &output_keys)?,
+            effects,
+        });
+    }
+
+    Ok(CheckpointData {
+        checkpoint_summary: summary.into_inner(),
+        checkpoint_contents: contents,
+        transactions: checkpoint_transactions,
+    })
+}
+
+fn get_objects(state: &AuthorityState, keys: &[ObjectKey]) -> Result<Vec<Object>, tonic::Status> {
+    state
+        .database
+        .multi_get_object_by_key(keys)?
+        .into_iter()
+        .zip(keys)
+        .map(|(object, ObjectKey(id, version))| {
+            object.ok_or_else(|| not_found(format!("Object {id} at version {version}")))
+        })
+        .collect()
+}
+
+fn not_found(what: String) -> tonic::Status {
+    tonic::Status::not_found(format!("{what} is not available on this node"))
+}
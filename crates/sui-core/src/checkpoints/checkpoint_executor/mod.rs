@@ -482,6 +482,10 @@ async fn execute_checkpoint(
     )
     .await?;
 
+    epoch_store
+        .transaction_lifecycle()
+        .finish(&all_tx_digests, checkpoint_sequence);
+
     // if end of epoch checkpoint, we must finalize the checkpoint after executing
     // the change epoch tx, which is done after all other checkpoint execution
     if !end_of_epoch {
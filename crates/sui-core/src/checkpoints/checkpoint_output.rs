@@ -7,6 +7,7 @@ use crate::consensus_adapter::SubmitToConsensus;
 use crate::epoch::reconfiguration::ReconfigurationInitiator;
 use async_trait::async_trait;
 use std::sync::Arc;
+use sui_macros::fail_point_async;
 use sui_types::base_types::AuthorityName;
 use sui_types::error::SuiResult;
 use sui_types::message_envelope::Message;
@@ -88,6 +89,7 @@ impl<T: SubmitToConsensus + ReconfigurationInitiator> CheckpointOutput
         self.sender
             .submit_to_consensus(&transaction, epoch_store)
             .await?;
+        fail_point_async!("checkpoint-signature-submitted");
         self.metrics
             .last_sent_checkpoint_signature
             .set(checkpoint_seq as i64);
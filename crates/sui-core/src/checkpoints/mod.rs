@@ -51,6 +51,7 @@ use tokio::{
     time::timeout,
 };
 use tracing::{debug, error, info, warn};
+use typed_store::rocks::migration::SchemaMigrations;
 use typed_store::rocks::{DBMap, MetricConf, TypedStoreError};
 use typed_store::traits::{TableSummary, TypedStoreDebug};
 use typed_store::Map;
@@ -96,7 +97,16 @@ pub struct CheckpointStore {
 }
 
 impl CheckpointStore {
+    /// The migrations of the schema of the tables, run before they are opened, see
+    /// `AuthorityPerpetualTables::migrations`.
+    fn migrations() -> SchemaMigrations {
+        SchemaMigrations::new()
+    }
+
     pub fn new(path: &Path) -> Arc<Self> {
+        Self::migrations()
+            .run(path, None, None)
+            .expect("Failed to migrate the checkpoint store");
         Arc::new(Self::open_tables_read_write(
             path.to_path_buf(),
             MetricConf::default(),
@@ -339,6 +349,16 @@ impl CheckpointStore {
         self.checkpoint_content.insert(contents.digest(), &contents)
     }
 
+    pub fn delete_checkpoint_contents(
+        &self,
+        digests: &[CheckpointContentsDigest],
+    ) -> Result<(), TypedStoreError> {
+        self.checkpoint_content
+            .batch()
+            .delete_batch(&self.checkpoint_content, digests)?
+            .write()
+    }
+
     pub fn get_epoch_last_checkpoint(
         &self,
         epoch_id: EpochId,
@@ -1279,6 +1299,7 @@ mod tests {
     use sui_types::object;
     use tempfile::tempdir;
     use tokio::sync::mpsc;
+    use typed_store::rocks::migration::{store_schema_version, INITIAL_STORE_SCHEMA_VERSION};
 
     #[tokio::test]
     pub async fn checkpoint_builder_test() {
@@ -1506,6 +1527,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn checkpoint_store_records_schema_version() {
+        let tempdir = tempdir().unwrap();
+        let store = CheckpointStore::new(tempdir.path());
+        assert_eq!(
+            store_schema_version(&store.certified_checkpoints.rocksdb).unwrap(),
+            INITIAL_STORE_SCHEMA_VERSION
+        );
+    }
+
     fn p(i: u64, t: Vec<u8>) -> PendingCheckpoint {
         PendingCheckpoint {
             roots: t.into_iter().map(d).collect(),
@@ -5,7 +5,7 @@ use arc_swap::{ArcSwap, ArcSwapOption};
 use bytes::Bytes;
 use dashmap::try_result::TryResult;
 use dashmap::DashMap;
-use futures::future::{select, Either};
+use futures::future::{self, select, Either};
 use futures::FutureExt;
 use itertools::Itertools;
 use mysten_network::Multiaddr;
@@ -32,7 +32,7 @@ use sui_types::base_types::TransactionDigest;
 use sui_types::committee::Committee;
 use sui_types::{
     error::{SuiError, SuiResult},
-    messages::ConsensusTransaction,
+    messages::{ConsensusTransaction, ConsensusTransactionKey},
 };
 
 use tap::prelude::*;
@@ -41,14 +41,16 @@ use tokio::task::JoinHandle;
 use tokio::time::{self, sleep, timeout};
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use crate::consensus_validator::consensus_transaction_priority;
 use crate::epoch::reconfiguration::{ReconfigState, ReconfigurationInitiator};
+use crate::memory_budget::{MemoryConsumer, ESTIMATED_CERTIFICATE_SIZE};
 use mysten_metrics::{spawn_monitored_task, GaugeGuard, GaugeGuardFutureExt};
 use sui_simulator::anemo::PeerId;
 use sui_simulator::narwhal_network::connectivity::ConnectionStatus;
 use sui_types::base_types::AuthorityName;
 use sui_types::messages::ConsensusTransactionKind;
 use tokio::time::Duration;
-use tracing::{debug, info, warn};
+use tracing::{debug, debug_span, info, warn, Instrument};
 
 #[cfg(test)]
 #[path = "unit_tests/consensus_tests.rs"]
@@ -232,6 +234,7 @@ impl SubmitToConsensus for LazyNarwhalClient {
         transaction: &ConsensusTransaction,
         _epoch_store: &Arc<AuthorityPerEpochStore>,
     ) -> SuiResult {
+        let priority = consensus_transaction_priority(transaction);
         let transaction =
             bcs::to_bytes(transaction).expect("Serializing consensus transaction cannot fail");
         // The retrieved LocalNarwhalClient can be from the past epoch. Submit would fail after
@@ -247,7 +250,7 @@ impl SubmitToConsensus for LazyNarwhalClient {
         };
         let client = client.as_ref().unwrap().load();
         client
-            .submit_transaction(transaction)
+            .submit_transaction_with_priority(transaction, priority)
             .await
             .map_err(|e| SuiError::FailedToSubmitToConsensus(format!("{:?}", e)))
             .tap_err(|r| {
@@ -370,8 +373,10 @@ impl ConsensusAdapter {
         committee: &Committee,
         transaction: &ConsensusTransaction,
     ) -> (impl Future<Output = ()>, usize, bool) {
-        let (duration, position, mapped_to_low_scoring) = match &transaction.kind {
-            ConsensusTransactionKind::UserTransaction(certificate) => {
+        // A bundle is submitted by the same authorities as its first certificate.
+        let first_certificate = transaction.user_certificates().first();
+        let (duration, position, mapped_to_low_scoring) = match first_certificate {
+            Some(certificate) => {
                 let tx_digest = certificate.digest();
                 let (position, mapped_to_low_scoring) =
                     self.submission_position(committee, tx_digest);
@@ -388,7 +393,7 @@ impl ConsensusAdapter {
                     mapped_to_low_scoring,
                 )
             }
-            _ => (Duration::ZERO, 0, false),
+            None => (Duration::ZERO, 0, false),
         };
         (
             tokio::time::sleep(duration),
@@ -522,8 +527,10 @@ impl ConsensusAdapter {
         let async_stage = self
             .clone()
             .submit_and_wait(transaction, epoch_store.clone());
-        // Number of this tasks is limited by `sequencing_certificate_inflight` limit
-        let join_handle = spawn_monitored_task!(async_stage);
+        // Number of this tasks is limited by `sequencing_certificate_inflight` limit.
+        // The span is a child of the current one, which for certificates is their lifecycle span.
+        let join_handle =
+            spawn_monitored_task!(async_stage.instrument(debug_span!("consensus_submission")));
         join_handle
     }
 
@@ -560,10 +567,20 @@ impl ConsensusAdapter {
             epoch_store.record_epoch_pending_certs_process_time_metric();
         }
 
-        let transaction_key = SequencedConsensusTransactionKey::External(transaction.key());
-        let processed_waiter = epoch_store
-            .consensus_message_processed_notify(transaction_key)
-            .boxed();
+        // Once sequenced, a bundle is processed as its individual certificates.
+        let processed_keys: Vec<_> = match &transaction.kind {
+            ConsensusTransactionKind::UserTransactionBundle(certificates) => certificates
+                .iter()
+                .map(|certificate| ConsensusTransactionKey::Certificate(*certificate.digest()))
+                .collect(),
+            _ => vec![transaction.key()],
+        };
+        let processed_waiter = future::try_join_all(processed_keys.into_iter().map(|key| {
+            epoch_store
+                .consensus_message_processed_notify(SequencedConsensusTransactionKey::External(key))
+        }))
+        .map(|processed| processed.map(|_| ()))
+        .boxed();
 
         let (await_submit, position, mapped_to_low_scoring) =
             self.await_submit_delay(epoch_store.committee(), &transaction);
@@ -658,9 +675,7 @@ impl ConsensusAdapter {
         epoch_store
             .remove_pending_consensus_transaction(&transaction.key())
             .expect("Storage error when removing consensus transaction");
-        let send_end_of_publish = if let ConsensusTransactionKind::UserTransaction(_cert) =
-            &transaction.kind
-        {
+        let send_end_of_publish = if transaction.is_user_certificate() {
             let reconfig_guard = epoch_store.get_reconfig_state_read_lock_guard();
             // If we are in RejectUserCerts state and we just drained the list we need to
             // send EndOfPublish to signal other validators that we are not submitting more certificates to the epoch.
@@ -933,6 +948,13 @@ impl SubmitToConsensus for Arc<ConsensusAdapter> {
     }
 }
 
+/// Transactions submitted to consensus cannot be dropped, so the adapter only reports their usage.
+impl MemoryConsumer for ConsensusAdapter {
+    fn memory_usage(&self) -> usize {
+        self.num_inflight_transactions.load(Ordering::Relaxed) as usize * ESTIMATED_CERTIFICATE_SIZE
+    }
+}
+
 pub fn position_submit_certificate(
     committee: &Committee,
     ourselves: &AuthorityName,
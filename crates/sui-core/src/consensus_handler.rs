@@ -4,23 +4,31 @@
 use crate::authority::authority_per_epoch_store::{
     AuthorityPerEpochStore, ExecutionIndicesWithHash,
 };
+use crate::authority::shared_object_congestion_tracker::{
+    mutable_shared_objects, DeferredTransaction, SharedObjectCongestionTracker,
+    SharedObjectUtilization,
+};
 use crate::authority::AuthorityMetrics;
 use crate::checkpoints::CheckpointService;
+use crate::memory_budget::{lru_memory_usage, shrink_lru, MemoryConsumer};
+use crate::quorum_reachability::QuorumReachability;
 
 use crate::scoring_decision::update_low_scoring_authorities;
+use crate::slow_ops::{SlowOpKind, SlowOpLog};
 use crate::transaction_manager::TransactionManager;
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
+use fastcrypto::hash::Hash as _;
 use fastcrypto::traits::ToFromBytes;
 use lru::LruCache;
-use mysten_metrics::{monitored_scope, spawn_monitored_task};
+use mysten_metrics::{metered_channel, monitored_scope, spawn_monitored_task};
 use narwhal_config::Committee;
 use narwhal_executor::{ExecutionIndices, ExecutionState};
-use narwhal_types::ConsensusOutput;
+use narwhal_types::{Certificate, ConsensusOutput, Round};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
@@ -35,6 +43,10 @@ use sui_types::storage::ParentSync;
 
 use tracing::{debug, error, instrument};
 
+#[cfg(test)]
+#[path = "unit_tests/consensus_handler_tests.rs"]
+mod consensus_handler_tests;
+
 pub struct ConsensusHandler<T> {
     /// A store created for each epoch. ConsensusHandler is recreated each epoch, with the
     /// corresponding store. This store is also used to get the current epoch ID.
@@ -55,10 +67,28 @@ pub struct ConsensusHandler<T> {
     /// Lru cache to quickly discard transactions processed by consensus
     processed_cache: Mutex<LruCache<SequencedConsensusTransactionKey, ()>>,
     transaction_scheduler: AsyncTransactionScheduler,
+    /// Recent per-object load, reported in metrics when shared object congestion control is on
+    shared_object_utilization: Mutex<SharedObjectUtilization>,
+    /// Evidence of the reachability of the authorities, updated with the authors of committed
+    /// certificates
+    quorum_reachability: Arc<QuorumReachability>,
+    /// Records the commits slower than their threshold
+    slow_ops: Arc<SlowOpLog>,
 }
 
 const PROCESSED_CACHE_CAP: usize = 1024 * 1024;
 
+/// Number of commits over which shared object utilization is reported
+const SHARED_OBJECT_UTILIZATION_WINDOW: usize = 100;
+
+/* (serialized, transaction, output_cert, certificate_author) */
+type CommittedTransaction = (
+    Vec<u8>,
+    SequencedConsensusTransactionKind,
+    Arc<Certificate>,
+    AuthorityName,
+);
+
 impl<T> ConsensusHandler<T> {
     pub fn new(
         epoch_store: Arc<AuthorityPerEpochStore>,
@@ -69,6 +99,8 @@ impl<T> ConsensusHandler<T> {
         authority_names_to_peer_ids: Arc<HashMap<AuthorityName, PeerId>>,
         committee: Committee,
         metrics: Arc<AuthorityMetrics>,
+        quorum_reachability: Arc<QuorumReachability>,
+        slow_ops: Arc<SlowOpLog>,
     ) -> Self {
         let last_seen = Mutex::new(Default::default());
         let transaction_scheduler =
@@ -86,8 +118,24 @@ impl<T> ConsensusHandler<T> {
                 NonZeroUsize::new(PROCESSED_CACHE_CAP).unwrap(),
             )),
             transaction_scheduler,
+            shared_object_utilization: Mutex::new(SharedObjectUtilization::new(
+                SHARED_OBJECT_UTILIZATION_WINDOW,
+            )),
+            quorum_reachability,
+            slow_ops,
         }
     }
+
+    fn authority_name(&self, certificate: &Certificate) -> AuthorityName {
+        AuthorityName::from_bytes(
+            self.committee
+                .authority_safe(&certificate.header.author)
+                .protocol_key_bytes()
+                .0
+                .as_ref(),
+        )
+        .unwrap()
+    }
 }
 
 fn update_hash(
@@ -119,37 +167,58 @@ fn update_hash(
     Some(last_seen)
 }
 
+/// Only the cache of processed transactions can shrink: it is a fast path, and transactions
+/// evicted from it are still deduplicated against the epoch store.
+impl<T: Send + Sync> MemoryConsumer for ConsensusHandler<T> {
+    fn memory_usage(&self) -> usize {
+        lru_memory_usage(&self.processed_cache.lock())
+    }
+
+    fn shrink(&self, bytes: usize) -> usize {
+        shrink_lru(&mut self.processed_cache.lock(), bytes)
+    }
+}
+
 #[async_trait]
 impl<T: ParentSync + Send + Sync> ExecutionState for ConsensusHandler<T> {
     /// This function will be called by Narwhal, after Narwhal sequenced this certificate.
     #[instrument(level = "trace", skip_all)]
     async fn handle_consensus_output(&self, consensus_output: ConsensusOutput) {
         let _scope = monitored_scope("HandleConsensusOutput");
+        let mut slow_op = self.slow_ops.start(
+            SlowOpKind::ConsensusCommit,
+            "handle_consensus_output",
+            Some(consensus_output.sub_dag.leader.digest().to_string()),
+        );
         let mut sequenced_transactions = Vec::new();
 
         let mut bytes = 0usize;
         let round = consensus_output.sub_dag.leader_round();
 
-        /* (serialized, transaction, output_cert) */
-        let mut transactions = vec![];
+        let mut transactions: Vec<CommittedTransaction> = vec![];
         // Narwhal enforces some invariants on the header.created_at, so we can use it as a timestamp
         let timestamp = consensus_output.sub_dag.leader.header.created_at;
 
+        let leader_cert = Arc::new(consensus_output.sub_dag.leader.clone());
+        let leader_author = self.authority_name(&leader_cert);
         let prologue_transaction = self.consensus_commit_prologue_transaction(round, timestamp);
         transactions.push((
             vec![],
             SequencedConsensusTransactionKind::System(prologue_transaction),
-            Arc::new(consensus_output.sub_dag.leader.clone()),
+            leader_cert.clone(),
+            leader_author,
         ));
 
         // TODO: spawn a separate task for this as an optimization
-        update_low_scoring_authorities(
+        if let Some(scores) = update_low_scoring_authorities(
             self.low_scoring_authorities.clone(),
             &self.committee,
             consensus_output.sub_dag.reputation_score.clone(),
             self.authority_names_to_peer_ids.clone(),
             &self.metrics,
-        );
+        ) {
+            self.epoch_store.set_last_schedule_scores(scores);
+        }
 
         self.metrics
             .consensus_committed_subdags
@@ -162,6 +231,9 @@ impl<T: ParentSync + Send + Sync> ExecutionState for ConsensusHandler<T> {
                 .with_label_values(&[&author.to_string()])
                 .inc();
             let output_cert = Arc::new(cert);
+            let certificate_author = self.authority_name(&output_cert);
+            self.quorum_reachability
+                .record_consensus_certificate(certificate_author);
             for batch in batches {
                 self.metrics.consensus_handler_processed_batches.inc();
                 for serialized_transaction in batch.transactions {
@@ -185,12 +257,44 @@ impl<T: ParentSync + Send + Sync> ExecutionState for ConsensusHandler<T> {
                         .with_label_values(&[classify(&transaction)])
                         .inc();
                     let transaction = SequencedConsensusTransactionKind::External(transaction);
-                    transactions.push((serialized_transaction, transaction, output_cert.clone()));
+                    transactions.push((
+                        serialized_transaction,
+                        transaction,
+                        output_cert.clone(),
+                        certificate_author,
+                    ));
                 }
             }
         }
 
-        for (seq, (serialized, transaction, output_cert)) in transactions.into_iter().enumerate() {
+        if self
+            .epoch_store
+            .protocol_config()
+            .check_shared_object_congestion_control_supported()
+            .is_ok()
+        {
+            transactions = self.schedule_congested_transactions(round, leader_cert, transactions);
+        }
+
+        // Soft bundles are expanded after congestion control, which admits or defers each bundle
+        // as a whole, so that their certificates get consecutive indices.
+        let expanded_transactions = transactions.into_iter().flat_map(
+            |(serialized, transaction, output_cert, certificate_author)| {
+                self.expand_bundle(transaction)
+                    .into_iter()
+                    .map(move |transaction| {
+                        (
+                            serialized.clone(),
+                            transaction,
+                            output_cert.clone(),
+                            certificate_author,
+                        )
+                    })
+            },
+        );
+        for (seq, (serialized, transaction, output_cert, certificate_author)) in
+            expanded_transactions.enumerate()
+        {
             let index = ExecutionIndices {
                 last_committed_round: round,
                 sub_dag_index: consensus_output.sub_dag.sub_dag_index,
@@ -208,17 +312,18 @@ impl<T: ParentSync + Send + Sync> ExecutionState for ConsensusHandler<T> {
                 }
             };
 
-            let certificate_author = AuthorityName::from_bytes(
-                self.committee
-                    .authority_safe(&output_cert.header.author)
-                    .protocol_key_bytes()
-                    .0
-                    .as_ref(),
-            )
-            .unwrap();
+            if let SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                kind: ConsensusTransactionKind::UserTransaction(certificate),
+                ..
+            }) = &transaction
+            {
+                self.epoch_store
+                    .transaction_lifecycle()
+                    .sequenced(certificate.digest(), round);
+            }
 
             sequenced_transactions.push(SequencedConsensusTransaction {
-                certificate: output_cert.clone(),
+                certificate: output_cert,
                 certificate_author,
                 consensus_index: index_with_hash,
                 transaction,
@@ -228,6 +333,7 @@ impl<T: ParentSync + Send + Sync> ExecutionState for ConsensusHandler<T> {
         self.metrics
             .consensus_handler_processed_bytes
             .inc_by(bytes as u64);
+        slow_op.phase("sequence");
 
         let mut transactions_to_schedule = vec![];
         for sequenced_transaction in sequenced_transactions {
@@ -264,13 +370,17 @@ impl<T: ParentSync + Send + Sync> ExecutionState for ConsensusHandler<T> {
             }
         }
 
+        slow_op.phase("process_transactions");
+
         self.transaction_scheduler
             .schedule(transactions_to_schedule)
             .await;
+        slow_op.phase("schedule");
 
         self.epoch_store
             .handle_commit_boundary(round, timestamp, &self.checkpoint_service)
-            .expect("Unrecoverable error in consensus handler when processing commit boundary")
+            .expect("Unrecoverable error in consensus handler when processing commit boundary");
+        slow_op.phase("commit_boundary");
     }
 
     async fn last_executed_sub_dag_index(&self) -> u64 {
@@ -284,7 +394,7 @@ impl<T: ParentSync + Send + Sync> ExecutionState for ConsensusHandler<T> {
 }
 
 struct AsyncTransactionScheduler {
-    sender: tokio::sync::mpsc::Sender<Vec<VerifiedExecutableTransaction>>,
+    sender: metered_channel::Sender<Vec<VerifiedExecutableTransaction>>,
 }
 
 impl AsyncTransactionScheduler {
@@ -292,7 +402,8 @@ impl AsyncTransactionScheduler {
         transaction_manager: Arc<TransactionManager>,
         epoch_store: Arc<AuthorityPerEpochStore>,
     ) -> Self {
-        let (sender, recv) = tokio::sync::mpsc::channel(16);
+        let (sender, recv) =
+            metered_channel::monitored_channel("consensus_transaction_scheduler", 16);
         spawn_monitored_task!(Self::run(recv, transaction_manager, epoch_store));
         Self { sender }
     }
@@ -302,7 +413,7 @@ impl AsyncTransactionScheduler {
     }
 
     pub async fn run(
-        mut recv: tokio::sync::mpsc::Receiver<Vec<VerifiedExecutableTransaction>>,
+        mut recv: metered_channel::Receiver<Vec<VerifiedExecutableTransaction>>,
         transaction_manager: Arc<TransactionManager>,
         epoch_store: Arc<AuthorityPerEpochStore>,
     ) {
@@ -316,6 +427,166 @@ impl AsyncTransactionScheduler {
 }
 
 impl<T> ConsensusHandler<T> {
+    /// Applies shared object congestion control to the transactions of the commit at `round`,
+    /// which start with the commit prologue. Transactions deferred by earlier commits are admitted
+    /// first, in order, followed by the transactions sequenced in this commit. Transactions that
+    /// would exceed the per-object limit are recorded in the deferral queue for the next commit.
+    ///
+    /// Certificates are not accepted from an authority after its EndOfPublish, so the whole queue
+    /// is admitted ahead of every EndOfPublish, regardless of the limit. Once the epoch stops
+    /// accepting certificates nothing is deferred any more, which leaves the queue empty at
+    /// reconfiguration.
+    ///
+    /// The queue is persisted before any transaction of this commit is processed, so that a replay
+    /// of this commit after a crash makes the same decisions.
+    fn schedule_congested_transactions(
+        &self,
+        round: Round,
+        leader_cert: Arc<Certificate>,
+        transactions: Vec<CommittedTransaction>,
+    ) -> Vec<CommittedTransaction> {
+        let max_txns_per_object = self
+            .epoch_store
+            .protocol_config()
+            .max_txns_per_shared_object_per_commit();
+        let (previous_round, previously_deferred) = self
+            .epoch_store
+            .load_deferred_transactions(round)
+            .expect("Failed to load deferred transactions")
+            .map_or((None, vec![]), |(round, deferred)| (Some(round), deferred));
+
+        let mut tracker = SharedObjectCongestionTracker::new(max_txns_per_object);
+        let accepts_certs = self
+            .epoch_store
+            .get_reconfig_state_read_lock_guard()
+            .should_accept_consensus_certs();
+        let mut scheduled = Vec::with_capacity(previously_deferred.len() + transactions.len());
+        let mut deferred = vec![];
+        let mut deferred_keys = HashSet::new();
+        let mut transactions = transactions.into_iter();
+        // The commit prologue always comes first.
+        scheduled.extend(transactions.next());
+
+        for entry in previously_deferred.iter().cloned() {
+            if tracker.try_admit(&entry.transaction) || !accepts_certs {
+                self.metrics
+                    .consensus_handler_deferred_transaction_rounds
+                    .observe(round.saturating_sub(entry.deferred_at_round) as f64);
+                scheduled.push(self.admit_deferred(entry, &leader_cert));
+            } else if deferred_keys.insert(entry.transaction.key()) {
+                deferred.push(entry);
+            }
+        }
+
+        for (serialized, transaction, output_cert, author) in transactions {
+            match &transaction {
+                SequencedConsensusTransactionKind::External(external)
+                    if accepts_certs && !tracker.try_admit(external) =>
+                {
+                    self.metrics.consensus_handler_deferred_transactions.inc();
+                    if deferred_keys.insert(external.key()) {
+                        deferred.push(DeferredTransaction {
+                            author,
+                            transaction: external.clone(),
+                            deferred_at_round: round,
+                        });
+                    }
+                }
+                SequencedConsensusTransactionKind::External(external)
+                    if external.is_end_of_publish() =>
+                {
+                    self.metrics
+                        .consensus_handler_flushed_deferred_transactions
+                        .inc_by(deferred.len() as u64);
+                    for entry in deferred.drain(..) {
+                        self.metrics
+                            .consensus_handler_deferred_transaction_rounds
+                            .observe(round.saturating_sub(entry.deferred_at_round) as f64);
+                        scheduled.push(self.admit_deferred(entry, &leader_cert));
+                    }
+                    scheduled.push((serialized, transaction, output_cert, author));
+                }
+                _ => scheduled.push((serialized, transaction, output_cert, author)),
+            }
+        }
+
+        self.metrics
+            .consensus_handler_deferred_transactions_queue
+            .set(deferred.len() as i64);
+        let deferred_objects: HashSet<_> = deferred
+            .iter()
+            .filter_map(|entry| mutable_shared_objects(&entry.transaction))
+            .flatten()
+            .collect();
+        self.metrics
+            .consensus_handler_deferred_objects
+            .set(deferred_objects.len() as i64);
+        // Skip the write if nothing was or is deferred, the latest queue on disk is already empty.
+        if !previously_deferred.is_empty() || !deferred.is_empty() {
+            self.epoch_store
+                .write_deferred_transactions(round, previous_round, deferred)
+                .expect("Failed to write deferred transactions");
+        }
+
+        let mut utilization = self.shared_object_utilization.lock();
+        utilization.record_commit(tracker.into_object_txn_counts());
+        self.metrics
+            .consensus_handler_max_shared_object_utilization
+            .set(utilization.max_utilization_percent(max_txns_per_object) as i64);
+        scheduled
+    }
+
+    fn admit_deferred(
+        &self,
+        entry: DeferredTransaction,
+        leader_cert: &Arc<Certificate>,
+    ) -> CommittedTransaction {
+        let serialized = bcs::to_bytes(&entry.transaction)
+            .expect("Serializing a consensus transaction cannot fail");
+        (
+            serialized,
+            SequencedConsensusTransactionKind::External(entry.transaction),
+            leader_cert.clone(),
+            entry.author,
+        )
+    }
+
+    /// Replaces a soft bundle by its certificates, in order. Any other transaction is returned
+    /// as is. Bundles are dropped when soft bundles are disabled or the bundle is over the size
+    /// limit, which the submitting validator has already checked.
+    fn expand_bundle(
+        &self,
+        transaction: SequencedConsensusTransactionKind,
+    ) -> Vec<SequencedConsensusTransactionKind> {
+        let (tracking_id, certificates) = match transaction {
+            SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                tracking_id,
+                kind: ConsensusTransactionKind::UserTransactionBundle(certificates),
+            }) => (tracking_id, certificates),
+            transaction => return vec![transaction],
+        };
+        let protocol_config = self.epoch_store.protocol_config();
+        if protocol_config.check_soft_bundle_supported().is_err()
+            || certificates.len() as u64 > protocol_config.max_soft_bundle_size()
+        {
+            error!(
+                "Ignoring unexpected soft bundle of {} certificates at {:?}",
+                certificates.len(),
+                protocol_config.version
+            );
+            return vec![];
+        }
+        certificates
+            .into_iter()
+            .map(|certificate| {
+                SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                    tracking_id,
+                    kind: ConsensusTransactionKind::UserTransaction(Box::new(certificate)),
+                })
+            })
+            .collect()
+    }
+
     #[allow(dead_code)]
     fn consensus_commit_prologue_transaction(
         &self,
@@ -344,6 +615,7 @@ fn classify(transaction: &ConsensusTransaction) -> &'static str {
                 "owned_certificate"
             }
         }
+        ConsensusTransactionKind::UserTransactionBundle(_) => "certificate_bundle",
         ConsensusTransactionKind::CheckpointSignature(_) => "checkpoint_signature",
         ConsensusTransactionKind::EndOfPublish(_) => "end_of_publish",
         ConsensusTransactionKind::CapabilityNotification(_) => "capability_notification",
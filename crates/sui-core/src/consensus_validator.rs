@@ -9,7 +9,7 @@ use std::sync::Arc;
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::transaction_manager::TransactionManager;
 use async_trait::async_trait;
-use narwhal_worker::TransactionValidator;
+use narwhal_worker::{TransactionPriority, TransactionValidator};
 use sui_types::messages::{ConsensusTransaction, ConsensusTransactionKind};
 use tap::TapFallible;
 use tokio::runtime::Handle;
@@ -46,13 +46,26 @@ fn tx_from_bytes(tx: &[u8]) -> Result<ConsensusTransaction, eyre::Report> {
         .wrap_err("Malformed transaction (failed to deserialize)")
 }
 
+/// The Narwhal lane a consensus transaction is batched in. Transactions the system needs to make
+/// progress (checkpoint signatures, end of publish, capabilities) must not wait behind user
+/// transactions.
+pub(crate) fn consensus_transaction_priority(tx: &ConsensusTransaction) -> TransactionPriority {
+    match tx.kind {
+        ConsensusTransactionKind::UserTransaction(_)
+        | ConsensusTransactionKind::UserTransactionBundle(_) => TransactionPriority::Normal,
+        ConsensusTransactionKind::CheckpointSignature(_)
+        | ConsensusTransactionKind::EndOfPublish(_)
+        | ConsensusTransactionKind::CapabilityNotification(_) => TransactionPriority::System,
+    }
+}
+
 #[async_trait]
 impl TransactionValidator for SuiTxValidator {
     type Error = eyre::Report;
 
-    fn validate(&self, _tx: &[u8]) -> Result<(), Self::Error> {
+    async fn validate(&self, tx: &[u8]) -> Result<TransactionPriority, Self::Error> {
         // We only accept transactions from local sui instance so no need to re-verify it
-        Ok(())
+        Ok(consensus_transaction_priority(&tx_from_bytes(tx)?))
     }
 
     async fn validate_batch(&self, b: &narwhal_types::Batch) -> Result<(), Self::Error> {
@@ -76,6 +89,9 @@ impl TransactionValidator for SuiTxValidator {
                     //     owned_tx_certs.push(VerifiedCertificate::new_unchecked(*certificate));
                     // }
                 }
+                ConsensusTransactionKind::UserTransactionBundle(certificates) => {
+                    cert_batch.extend(certificates)
+                }
                 ConsensusTransactionKind::CheckpointSignature(signature) => {
                     ckpt_batch.push(signature.summary)
                 }
@@ -149,7 +165,7 @@ mod tests {
     };
     use fastcrypto::traits::KeyPair;
     use narwhal_types::Batch;
-    use narwhal_worker::TransactionValidator;
+    use narwhal_worker::{TransactionPriority, TransactionValidator};
     use sui_types::{
         base_types::AuthorityName, messages::ConsensusTransaction, signature::GenericSignature,
     };
@@ -190,8 +206,8 @@ mod tests {
             state.transaction_manager().clone(),
             metrics,
         );
-        let res = validator.validate(&first_transaction_bytes);
-        assert!(res.is_ok(), "{res:?}");
+        let res = validator.validate(&first_transaction_bytes).await;
+        assert!(matches!(res, Ok(TransactionPriority::Normal)), "{res:?}");
 
         let transaction_bytes: Vec<_> = certificates
             .clone()
@@ -9,6 +9,7 @@ use object_store::{DynObjectStore, Error};
 use oneshot::channel;
 use std::collections::BTreeMap;
 use std::collections::Bound::{Included, Unbounded};
+use std::fs;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use std::time::Duration;
@@ -82,12 +83,10 @@ impl DBCheckpointHandler {
         sender
     }
     async fn upload_db_checkpoint_to_object_store(&self) -> Result<()> {
-        let local_checkpoints_by_epoch = self
-            .read_checkpoint_dir(self.input_object_store.clone())
-            .await?;
-        let remote_checkpoints_by_epoch = self
-            .read_checkpoint_dir(self.output_object_store.clone())
-            .await?;
+        let local_checkpoints_by_epoch =
+            read_checkpoint_dir(self.input_object_store.clone()).await?;
+        let remote_checkpoints_by_epoch =
+            read_checkpoint_dir(self.output_object_store.clone()).await?;
 
         let next_epoch = if let Some((last_epoch, path)) =
             remote_checkpoints_by_epoch.iter().next_back()
@@ -154,9 +153,8 @@ impl DBCheckpointHandler {
         Ok(())
     }
     async fn garbage_collect_old_db_checkpoints(&self) -> Result<()> {
-        let local_checkpoints_by_epoch = self
-            .read_checkpoint_dir(self.input_object_store.clone())
-            .await?;
+        let local_checkpoints_by_epoch =
+            read_checkpoint_dir(self.input_object_store.clone()).await?;
         for (epoch, path) in local_checkpoints_by_epoch.iter() {
             let marker_paths: Vec<Path> = self
                 .gc_markers
@@ -188,29 +186,92 @@ impl DBCheckpointHandler {
         }
         Ok(())
     }
-    async fn read_checkpoint_dir(&self, store: Arc<DynObjectStore>) -> Result<BTreeMap<u32, Path>> {
-        let mut checkpoints_by_epoch = BTreeMap::new();
-        let entries = store.list_with_delimiter(None).await?;
-        for entry in entries.common_prefixes {
-            if let Some(filename) = entry.filename() {
-                if !filename.starts_with("epoch_") {
-                    continue;
-                }
-                let epoch = filename
-                    .split_once('_')
-                    .context("Failed to split dir name")
-                    .map(|(_, epoch)| epoch.parse::<u32>())??;
-                checkpoints_by_epoch.insert(epoch, entry);
+}
+
+/// Downloads the latest db checkpoint fully uploaded to the given object store into `db_path`,
+/// which must not exist yet. Returns the epoch of the restored checkpoint, or `None` if the object
+/// store holds no complete db checkpoint.
+pub async fn restore_latest_db_checkpoint(
+    object_store_config: &ObjectStoreConfig,
+    db_path: &std::path::Path,
+) -> Result<Option<u32>> {
+    if db_path.exists() {
+        return Err(anyhow!(
+            "Cannot restore db checkpoint into existing db path: {}",
+            db_path.display()
+        ));
+    }
+    let remote_store = object_store_config.make()?;
+    let remote_checkpoints_by_epoch = read_checkpoint_dir(remote_store.clone()).await?;
+    let mut latest = None;
+    for (epoch, path) in remote_checkpoints_by_epoch.iter().rev() {
+        match remote_store.get(&path.child(SUCCESS_MARKER)).await {
+            Ok(_) => {
+                latest = Some((*epoch, path));
+                break;
+            }
+            Err(Error::NotFound { .. }) => {
+                debug!("Skipping incomplete db checkpoint for epoch: {epoch}");
             }
+            Err(err) => return Err(err.into()),
         }
-        Ok(checkpoints_by_epoch)
     }
+    let Some((epoch, path)) = latest else {
+        return Ok(None);
+    };
+
+    // Download next to the db path first, so that an interrupted download is never mistaken for a
+    // db on the next start.
+    let download_path = db_path.with_extension("restore");
+    if download_path.exists() {
+        fs::remove_dir_all(&download_path)?;
+    }
+    let download_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(download_path.clone()),
+        ..Default::default()
+    };
+    info!(
+        "Restoring db checkpoint for epoch: {epoch} into {}",
+        db_path.display()
+    );
+    copy_recursively(
+        path,
+        remote_store,
+        download_store_config.make()?,
+        NonZeroUsize::new(20).unwrap(),
+    )
+    .await?;
+    let checkpoint_path = download_path.join(path.to_string());
+    fs::remove_file(checkpoint_path.join(SUCCESS_MARKER))?;
+    fs::rename(&checkpoint_path, db_path)?;
+    fs::remove_dir_all(&download_path)?;
+    Ok(Some(epoch))
+}
+
+async fn read_checkpoint_dir(store: Arc<DynObjectStore>) -> Result<BTreeMap<u32, Path>> {
+    let mut checkpoints_by_epoch = BTreeMap::new();
+    let entries = store.list_with_delimiter(None).await?;
+    for entry in entries.common_prefixes {
+        if let Some(filename) = entry.filename() {
+            if !filename.starts_with("epoch_") {
+                continue;
+            }
+            let epoch = filename
+                .split_once('_')
+                .context("Failed to split dir name")
+                .map(|(_, epoch)| epoch.parse::<u32>())??;
+            checkpoints_by_epoch.insert(epoch, entry);
+        }
+    }
+    Ok(checkpoints_by_epoch)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::db_checkpoint_handler::{
-        DBCheckpointHandler, SUCCESS_MARKER, TEST_MARKER, UPLOAD_COMPLETED_MARKER,
+        restore_latest_db_checkpoint, DBCheckpointHandler, SUCCESS_MARKER, TEST_MARKER,
+        UPLOAD_COMPLETED_MARKER,
     };
     use std::fs;
     use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
@@ -378,4 +439,42 @@ mod tests {
         assert!(!local_epoch1_checkpoint.join("data").join("file3").exists());
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_restore_latest_db_checkpoint() -> anyhow::Result<()> {
+        let remote_checkpoint_dir = TempDir::new()?;
+        let remote_checkpoint_dir_path = remote_checkpoint_dir.path();
+        for epoch in 0..3 {
+            let remote_checkpoint = remote_checkpoint_dir_path.join(format!("epoch_{epoch}"));
+            let nested_dir = remote_checkpoint.join("store");
+            fs::create_dir_all(&nested_dir)?;
+            fs::write(nested_dir.join("file1"), format!("epoch {epoch}"))?;
+            // The upload of the last epoch did not complete
+            if epoch < 2 {
+                fs::write(remote_checkpoint.join(SUCCESS_MARKER), b"success")?;
+            }
+        }
+        let remote_store_config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(remote_checkpoint_dir_path.to_path_buf()),
+            ..Default::default()
+        };
+
+        let db_dir = TempDir::new()?;
+        let db_path = db_dir.path().join("db");
+        let restored = restore_latest_db_checkpoint(&remote_store_config, &db_path).await?;
+        assert_eq!(restored, Some(1));
+        assert_eq!(
+            fs::read_to_string(db_path.join("store").join("file1"))?,
+            "epoch 1"
+        );
+        assert!(!db_path.join(SUCCESS_MARKER).exists());
+        assert!(!db_path.with_extension("restore").exists());
+
+        // Never overwrite an existing db
+        assert!(restore_latest_db_checkpoint(&remote_store_config, &db_path)
+            .await
+            .is_err());
+        Ok(())
+    }
 }
@@ -54,6 +54,15 @@ impl CommitteeStore {
         store
     }
 
+    pub fn open_readonly(path: &Path) -> CommitteeStoreTablesReadOnly {
+        CommitteeStoreTables::get_read_only_handle(
+            path.to_path_buf(),
+            None,
+            None,
+            MetricConf::default(),
+        )
+    }
+
     pub fn new_for_testing(genesis_committee: &Committee) -> Self {
         let dir = std::env::temp_dir();
         let path = dir.join(format!("DB_{:?}", nondeterministic!(ObjectID::random())));
@@ -4,8 +4,12 @@
 use tokio_stream::Stream;
 use tracing::{error, instrument, trace};
 
-use sui_json_rpc_types::{EventFilter, SuiTransactionEffects, SuiTransactionEvents};
+use sui_json_rpc_types::{
+    EventFilter, ObjectChange, ObjectChangeFilter, ObjectChangeNotification, SuiTransactionEffects,
+    SuiTransactionEvents,
+};
 use sui_json_rpc_types::{SuiEvent, SuiTransactionEffectsAPI};
+use sui_types::base_types::TransactionDigest;
 use sui_types::error::SuiResult;
 
 use crate::streamer::Streamer;
@@ -18,6 +22,7 @@ pub const EVENT_DISPATCH_BUFFER_SIZE: usize = 1000;
 
 pub struct EventHandler {
     event_streamer: Streamer<SuiEvent, EventFilter>,
+    object_change_streamer: Streamer<ObjectChangeNotification, ObjectChangeFilter>,
 }
 
 impl Default for EventHandler {
@@ -25,6 +30,7 @@ impl Default for EventHandler {
         let streamer = Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE);
         Self {
             event_streamer: streamer,
+            object_change_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE),
         }
     }
 }
@@ -54,4 +60,33 @@ impl EventHandler {
     pub fn subscribe(&self, filter: EventFilter) -> impl Stream<Item = SuiEvent> {
         self.event_streamer.subscribe(filter)
     }
+
+    /// Whether anyone subscribed to object changes, which are not worth computing otherwise.
+    pub async fn has_object_change_subscribers(&self) -> bool {
+        self.object_change_streamer.has_subscribers().await
+    }
+
+    pub async fn process_object_changes(
+        &self,
+        transaction_digest: TransactionDigest,
+        object_changes: Vec<ObjectChange>,
+    ) -> SuiResult {
+        for object_change in object_changes {
+            let notification = ObjectChangeNotification {
+                transaction_digest,
+                object_change,
+            };
+            if let Err(e) = self.object_change_streamer.send(notification).await {
+                error!(error =? e, "Failed to send object change to dispatch");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn subscribe_object_changes(
+        &self,
+        filter: ObjectChangeFilter,
+    ) -> impl Stream<Item = ObjectChangeNotification> {
+        self.object_change_streamer.subscribe(filter)
+    }
 }
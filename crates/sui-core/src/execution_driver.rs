@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
     sync::{Arc, Weak},
     time::Duration,
 };
 
-use mysten_metrics::{monitored_scope, spawn_monitored_task};
-use sui_types::messages::VerifiedExecutableTransaction;
+use mysten_metrics::{monitored_scope, spawn_monitored_task, GaugeGuard};
 use tokio::{
     sync::{mpsc::UnboundedReceiver, oneshot, Semaphore},
     time::sleep,
@@ -15,6 +16,7 @@ use tokio::{
 use tracing::{debug, error, error_span, info, Instrument};
 
 use crate::authority::AuthorityState;
+use crate::transaction_manager::ReadyCertificate;
 
 #[cfg(test)]
 #[path = "unit_tests/execution_driver_tests.rs"]
@@ -25,25 +27,90 @@ mod execution_driver_tests;
 pub const EXECUTION_MAX_ATTEMPTS: u32 = 10;
 const EXECUTION_FAILURE_RETRY_INTERVAL: Duration = Duration::from_secs(1);
 
+/// A ready certificate waiting for an execution worker. Higher priorities come first, then
+/// certificates in the order they became ready.
+struct QueuedCertificate {
+    ready: ReadyCertificate,
+    sequence: Reverse<u64>,
+}
+
+impl QueuedCertificate {
+    fn key(&self) -> (u64, Reverse<u64>) {
+        (self.ready.priority, self.sequence)
+    }
+}
+
+impl PartialEq for QueuedCertificate {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for QueuedCertificate {}
+
+impl PartialOrd for QueuedCertificate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedCertificate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Ready certificates waiting for an execution worker, highest priority first.
+#[derive(Default)]
+pub(crate) struct ReadyQueue {
+    queue: BinaryHeap<QueuedCertificate>,
+    next_sequence: u64,
+}
+
+impl ReadyQueue {
+    pub(crate) fn push(&mut self, ready: ReadyCertificate) {
+        self.queue.push(QueuedCertificate {
+            ready,
+            sequence: Reverse(self.next_sequence),
+        });
+        self.next_sequence += 1;
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<ReadyCertificate> {
+        self.queue.pop().map(|queued| queued.ready)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
 /// When a notification that a new pending transaction is received we activate
 /// processing the transaction in a loop.
+///
+/// Certificates are executed by a pool of workers, one per cpu. When all workers are busy, ready
+/// certificates wait in a queue ordered by their priority, so that transactions which unblock the
+/// longest chains of dependent shared object transactions start first.
 pub async fn execution_process(
     authority_state: Weak<AuthorityState>,
-    mut rx_ready_certificates: UnboundedReceiver<VerifiedExecutableTransaction>,
+    mut rx_ready_certificates: UnboundedReceiver<ReadyCertificate>,
     mut rx_execution_shutdown: oneshot::Receiver<()>,
 ) {
     info!("Starting pending certificates execution process.");
 
     // Rate limit concurrent executions to # of cpus.
-    let limit = Arc::new(Semaphore::new(num_cpus::get()));
+    let workers = num_cpus::get();
+    let limit = Arc::new(Semaphore::new(workers));
+    let mut queue = ReadyQueue::default();
 
     // Loop whenever there is a signal that a new transactions is ready to process.
     loop {
-        let certificate;
+        let (certificate, permit);
         tokio::select! {
             result = rx_ready_certificates.recv() => {
-                if let Some(cert) = result {
-                    certificate = cert;
+                if let Some(ready) = result {
+                    queue.push(ready);
+                    continue;
                 } else {
                     // Should only happen after the AuthorityState has shut down and tx_ready_certificate
                     // has been dropped by TransactionManager.
@@ -51,6 +118,13 @@ pub async fn execution_process(
                     return;
                 };
             }
+            // hold semaphore permit until task completes. unwrap ok because we never close
+            // the semaphore in this context.
+            result = limit.clone().acquire_owned(), if !queue.is_empty() => {
+                permit = result.unwrap();
+                // unwrap ok because the queue is not empty.
+                certificate = queue.pop().unwrap().certificate;
+            }
             _ = &mut rx_execution_shutdown => {
                 info!("Shutdown signal received. Exiting executor ...");
                 return;
@@ -73,15 +147,24 @@ pub async fn execution_process(
         let digest = *certificate.digest();
         debug!(?digest, "Pending certificate execution activated.");
 
-        let limit = limit.clone();
-        // hold semaphore permit until task completes. unwrap ok because we never close
-        // the semaphore in this context.
-        let permit = limit.acquire_owned().await.unwrap();
+        // Achieved parallelism: the number of workers busy, including this certificate.
+        authority
+            .metrics
+            .execution_driver_parallelism
+            .observe((workers - limit.available_permits()) as f64);
+
+        let span = error_span!(
+            parent: epoch_store.transaction_lifecycle().parent(&digest),
+            "execution_driver",
+            tx_digest = ?digest
+        );
 
         // Certificate execution can take significant time, so run it in a separate task.
         spawn_monitored_task!(async move {
             let _scope = monitored_scope("ExecutionDriver");
             let _guard = permit;
+            let _executing =
+                GaugeGuard::acquire(&authority.metrics.execution_driver_executing_transactions);
             if let Ok(true) = authority.is_tx_already_executed(&digest) {
                 return;
             }
@@ -112,6 +195,6 @@ pub async fn execution_process(
                 .execution_driver_executed_transactions
                 .inc();
 
-        }.instrument(error_span!("execution_driver", tx_digest = ?digest)));
+        }.instrument(span));
     }
 }
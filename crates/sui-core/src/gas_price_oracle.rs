@@ -0,0 +1,206 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Estimates of the gas price to pay for a transaction, from the gas prices paid by the
+//! transactions of the most recent checkpoints.
+//!
+//! A task follows the checkpoints executed by the node, and keeps a histogram of the gas prices
+//! paid in a window of the most recent ones, adding the prices of every new checkpoint and
+//! removing those of the checkpoint leaving the window. Estimates are percentiles of the
+//! histogram, never lower than the reference gas price.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use parking_lot::RwLock;
+use sui_config::node::GasPriceOracleConfig;
+use sui_json_rpc_types::SuiGasPriceEstimates;
+use sui_types::messages::TransactionDataAPI;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::{debug, warn};
+
+use crate::authority::AuthorityState;
+
+#[derive(Default)]
+struct GasPriceWindow {
+    /// Gas prices paid by the user transactions of every checkpoint in the window, in order.
+    checkpoints: VecDeque<(CheckpointSequenceNumber, Vec<u64>)>,
+    /// Number of transactions in the window that paid each gas price.
+    histogram: BTreeMap<u64, u64>,
+    transactions: u64,
+}
+
+impl GasPriceWindow {
+    fn push(&mut self, sequence_number: CheckpointSequenceNumber, prices: Vec<u64>, max: usize) {
+        for price in &prices {
+            *self.histogram.entry(*price).or_default() += 1;
+        }
+        self.transactions += prices.len() as u64;
+        self.checkpoints.push_back((sequence_number, prices));
+
+        while self.checkpoints.len() > max {
+            let (_, prices) = self.checkpoints.pop_front().unwrap();
+            self.transactions -= prices.len() as u64;
+            for price in prices {
+                let count = self.histogram.get_mut(&price).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    self.histogram.remove(&price);
+                }
+            }
+        }
+    }
+
+    /// Lowest gas price paid by at least `percentile` percent of the transactions in the window.
+    fn percentile(&self, percentile: u64) -> Option<u64> {
+        if self.transactions == 0 {
+            return None;
+        }
+        let rank = ((self.transactions * percentile + 99) / 100).max(1);
+        let mut seen = 0;
+        for (price, count) in &self.histogram {
+            seen += count;
+            if seen >= rank {
+                return Some(*price);
+            }
+        }
+        self.histogram.keys().next_back().copied()
+    }
+}
+
+pub struct GasPriceOracle {
+    config: GasPriceOracleConfig,
+    window: RwLock<GasPriceWindow>,
+}
+
+impl GasPriceOracle {
+    pub fn new(config: GasPriceOracleConfig) -> Self {
+        Self {
+            config,
+            window: RwLock::new(GasPriceWindow::default()),
+        }
+    }
+
+    /// Follows the checkpoints executed by `state`, starting with the most recent ones that fit
+    /// in the window.
+    pub async fn run(self: Arc<Self>, state: Arc<AuthorityState>) {
+        let poll_interval = Duration::from_millis(self.config.poll_interval_ms);
+        let mut next = None;
+        loop {
+            let highest_executed = match state
+                .get_checkpoint_store()
+                .get_highest_executed_checkpoint_seq_number()
+            {
+                Ok(highest_executed) => highest_executed,
+                Err(e) => {
+                    warn!("Failed to read the highest executed checkpoint: {e}");
+                    None
+                }
+            };
+            if let Some(highest_executed) = highest_executed {
+                let mut sequence_number = next.unwrap_or_else(|| {
+                    (highest_executed + 1).saturating_sub(self.config.checkpoints)
+                });
+                while sequence_number <= highest_executed {
+                    if let Err(e) = self.add_checkpoint(&state, sequence_number) {
+                        // The checkpoint may have been pruned, it only leaves a gap in the window.
+                        warn!(
+                            sequence_number,
+                            "Failed to read gas prices of checkpoint: {e}"
+                        );
+                    }
+                    sequence_number += 1;
+                }
+                next = Some(sequence_number);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    fn add_checkpoint(
+        &self,
+        state: &AuthorityState,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<()> {
+        let checkpoint_store = state.get_checkpoint_store();
+        let summary = checkpoint_store
+            .get_checkpoint_by_sequence_number(sequence_number)?
+            .ok_or_else(|| anyhow!("checkpoint is missing"))?;
+        let contents = checkpoint_store
+            .get_checkpoint_contents(&summary.content_digest)?
+            .ok_or_else(|| anyhow!("checkpoint contents are missing"))?;
+        let digests: Vec<_> = contents.iter().map(|digests| digests.transaction).collect();
+        let prices: Vec<_> = state
+            .database
+            .multi_get_transactions(&digests)?
+            .into_iter()
+            .flatten()
+            .filter_map(|transaction| {
+                let data = transaction.data().transaction_data();
+                (!data.is_system_tx()).then(|| data.gas_price())
+            })
+            .collect();
+
+        debug!(
+            sequence_number,
+            transactions = prices.len(),
+            "Added gas prices of checkpoint"
+        );
+        self.window
+            .write()
+            .push(sequence_number, prices, self.config.checkpoints as usize);
+        Ok(())
+    }
+
+    /// Estimates over the current window, where `reference_gas_price` is the reference gas price
+    /// of the current epoch.
+    pub fn estimates(&self, reference_gas_price: u64) -> SuiGasPriceEstimates {
+        let window = self.window.read();
+        let estimate = |percentile| {
+            window
+                .percentile(percentile)
+                .unwrap_or(reference_gas_price)
+                .max(reference_gas_price)
+                .into()
+        };
+        SuiGasPriceEstimates {
+            reference_gas_price: reference_gas_price.into(),
+            p50: estimate(50),
+            p90: estimate(90),
+            p99: estimate(99),
+            first_checkpoint: window.checkpoints.front().map(|(seq, _)| (*seq).into()),
+            last_checkpoint: window.checkpoints.back().map(|(seq, _)| (*seq).into()),
+            transactions: window.transactions.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_price_window() {
+        let mut window = GasPriceWindow::default();
+        assert_eq!(window.percentile(50), None);
+
+        window.push(0, vec![1000; 10], 2);
+        window.push(1, (1..=10).map(|i| i * 100).collect(), 2);
+        assert_eq!(window.transactions, 20);
+        assert_eq!(window.percentile(50), Some(1000));
+        assert_eq!(window.percentile(25), Some(500));
+        assert_eq!(window.percentile(0), Some(100));
+        assert_eq!(window.percentile(100), Some(1000));
+
+        // The first checkpoint leaves the window.
+        window.push(2, vec![2000, 3000], 2);
+        assert_eq!(window.transactions, 12);
+        assert_eq!(window.checkpoints.front().unwrap().0, 1);
+        assert_eq!(window.percentile(50), Some(600));
+        assert_eq!(window.percentile(90), Some(2000));
+        assert_eq!(window.percentile(99), Some(3000));
+        assert_eq!(window.histogram.get(&1000), Some(&1));
+    }
+}
@@ -4,10 +4,12 @@
 
 extern crate core;
 
+pub mod archive_fallback;
 pub mod authority;
 pub mod authority_aggregator;
 pub mod authority_client;
 pub mod authority_server;
+pub mod checkpoint_stream_server;
 pub mod checkpoints;
 pub mod consensus_adapter;
 pub mod consensus_handler;
@@ -16,13 +18,18 @@ pub mod db_checkpoint_handler;
 pub mod epoch;
 pub mod event_handler;
 mod execution_driver;
+pub mod gas_price_oracle;
 mod math;
+pub mod memory_budget;
 pub mod metrics;
 pub mod module_cache_metrics;
+pub mod name_service;
 pub mod narwhal_manager;
 pub mod quorum_driver;
+pub mod quorum_reachability;
 pub mod safe_client;
 mod scoring_decision;
+pub mod slow_ops;
 mod stake_aggregator;
 pub mod state_accumulator;
 pub mod storage;
@@ -31,6 +38,8 @@ pub mod test_utils;
 pub mod transaction_input_checker;
 mod transaction_manager;
 pub mod transaction_orchestrator;
+pub mod transaction_scan;
+pub mod validator_duty;
 
 #[cfg(test)]
 #[path = "unit_tests/move_package_tests.rs"]
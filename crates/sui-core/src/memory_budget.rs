@@ -0,0 +1,284 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accounting of the memory used by the node's major caches and queues against a global budget.
+//!
+//! Components register with the [`MemoryBudget`] and report an estimate of their memory usage.
+//! The budget periodically collects the estimates into per-component gauges, and when their sum
+//! goes over the shrink threshold, asks the largest components to shrink until usage is back to
+//! the shrink target. Caches can drop entries to shrink; queues holding work that must not be
+//! lost only report their usage.
+
+use lru::LruCache;
+use mysten_metrics::spawn_monitored_task;
+use parking_lot::Mutex;
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_gauge_vec_with_registry,
+    register_int_gauge_with_registry, IntCounterVec, IntGauge, IntGaugeVec, Registry,
+};
+use std::collections::BTreeMap;
+use std::hash::Hash;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use sui_config::node::MemoryBudgetConfig;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+pub trait MemoryConsumer: Send + Sync {
+    /// Estimate of the memory used, in bytes.
+    fn memory_usage(&self) -> usize;
+
+    /// Frees about `bytes` of memory, if possible. Returns the number of bytes freed.
+    fn shrink(&self, _bytes: usize) -> usize {
+        0
+    }
+}
+
+/// Rough estimate of the memory held by a certificate in a queue: the transaction data, the
+/// aggregated signature and the user signatures, plus the queue's own bookkeeping.
+pub(crate) const ESTIMATED_CERTIFICATE_SIZE: usize = 4 * 1024;
+
+/// Estimated memory used by an entry of an [`LruCache`], with its bookkeeping.
+pub(crate) fn lru_entry_size<K, V>() -> usize {
+    std::mem::size_of::<K>() + std::mem::size_of::<V>() + 4 * std::mem::size_of::<usize>()
+}
+
+pub(crate) fn lru_memory_usage<K: Hash + Eq, V>(cache: &LruCache<K, V>) -> usize {
+    cache.len() * lru_entry_size::<K, V>()
+}
+
+/// Evicts the least recently used entries of `cache` until about `bytes` are freed.
+pub(crate) fn shrink_lru<K: Hash + Eq, V>(cache: &mut LruCache<K, V>, bytes: usize) -> usize {
+    let entry_size = lru_entry_size::<K, V>();
+    let mut freed = 0;
+    while freed < bytes && cache.pop_lru().is_some() {
+        freed += entry_size;
+    }
+    freed
+}
+
+pub struct MemoryBudgetMetrics {
+    component_memory_usage: IntGaugeVec,
+    memory_usage: IntGauge,
+    memory_limit: IntGauge,
+    shrink_count: IntCounterVec,
+    shrink_freed_bytes: IntCounterVec,
+}
+
+impl MemoryBudgetMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            component_memory_usage: register_int_gauge_vec_with_registry!(
+                "memory_budget_component_usage_bytes",
+                "Estimated memory used by each component accounted for in the memory budget",
+                &["component"],
+                registry,
+            )
+            .unwrap(),
+            memory_usage: register_int_gauge_with_registry!(
+                "memory_budget_usage_bytes",
+                "Estimated memory used by all components accounted for in the memory budget",
+                registry,
+            )
+            .unwrap(),
+            memory_limit: register_int_gauge_with_registry!(
+                "memory_budget_limit_bytes",
+                "Memory budget of the components accounted for",
+                registry,
+            )
+            .unwrap(),
+            shrink_count: register_int_counter_vec_with_registry!(
+                "memory_budget_shrink_count",
+                "Number of times each component was asked to shrink",
+                &["component"],
+                registry,
+            )
+            .unwrap(),
+            shrink_freed_bytes: register_int_counter_vec_with_registry!(
+                "memory_budget_shrink_freed_bytes",
+                "Estimated memory freed by each component when asked to shrink",
+                &["component"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+pub struct MemoryBudget {
+    config: MemoryBudgetConfig,
+    /// Registered components. A component is dropped from the budget once it is dropped.
+    consumers: Mutex<Vec<(&'static str, Weak<dyn MemoryConsumer>)>>,
+    metrics: MemoryBudgetMetrics,
+}
+
+impl MemoryBudget {
+    pub fn new(config: MemoryBudgetConfig, registry: &Registry) -> Arc<Self> {
+        let metrics = MemoryBudgetMetrics::new(registry);
+        metrics.memory_limit.set(config.limit_bytes as i64);
+        Arc::new(Self {
+            config,
+            consumers: Mutex::new(Vec::new()),
+            metrics,
+        })
+    }
+
+    /// Accounts for the memory used by `consumer` under the name `component`, until the consumer
+    /// is dropped. Several consumers may share a name, e.g. the same cache of successive epochs.
+    pub fn register<C: MemoryConsumer + 'static>(
+        &self,
+        component: &'static str,
+        consumer: &Arc<C>,
+    ) {
+        let consumer: Arc<dyn MemoryConsumer> = consumer.clone();
+        self.consumers
+            .lock()
+            .push((component, Arc::downgrade(&consumer)));
+    }
+
+    /// Updates the usage gauges, and shrinks components if usage is over the shrink threshold.
+    /// Returns the usage after shrinking.
+    pub fn enforce(&self) -> usize {
+        let mut consumers: Vec<_> = {
+            let mut registered = self.consumers.lock();
+            registered.retain(|(_, consumer)| consumer.strong_count() > 0);
+            registered
+                .iter()
+                .filter_map(|(component, consumer)| Some((*component, consumer.upgrade()?)))
+                .collect()
+        };
+        let mut usage: Vec<usize> = consumers
+            .iter()
+            .map(|(_, consumer)| consumer.memory_usage())
+            .collect();
+        let mut total: usize = usage.iter().sum();
+
+        let limit = self.config.limit_bytes;
+        if total > limit / 100 * self.config.shrink_threshold_pct as usize {
+            let target = limit / 100 * self.config.shrink_target_pct as usize;
+            warn!(
+                "Memory usage of {total} bytes is close to the budget of {limit} bytes, shrinking components to {target} bytes"
+            );
+            // Shrink the largest components first.
+            let mut order: Vec<usize> = (0..consumers.len()).collect();
+            order.sort_by_key(|index| std::cmp::Reverse(usage[*index]));
+            for index in order {
+                if total <= target {
+                    break;
+                }
+                let (component, consumer) = &consumers[index];
+                let freed = consumer.shrink(total - target).min(usage[index]);
+                if freed > 0 {
+                    debug!("Component {component} freed {freed} bytes");
+                    usage[index] -= freed;
+                    total -= freed;
+                    self.metrics
+                        .shrink_count
+                        .with_label_values(&[component])
+                        .inc();
+                    self.metrics
+                        .shrink_freed_bytes
+                        .with_label_values(&[component])
+                        .inc_by(freed as u64);
+                }
+            }
+            if total > target {
+                warn!("Memory usage is still at {total} bytes after shrinking all components");
+            }
+        }
+
+        let mut usage_by_component: BTreeMap<&str, usize> = BTreeMap::new();
+        for ((component, _), usage) in consumers.drain(..).zip(usage) {
+            *usage_by_component.entry(component).or_default() += usage;
+        }
+        for (component, usage) in usage_by_component {
+            self.metrics
+                .component_memory_usage
+                .with_label_values(&[component])
+                .set(usage as i64);
+        }
+        self.metrics.memory_usage.set(total as i64);
+        total
+    }
+
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        let mut interval =
+            tokio::time::interval(Duration::from_millis(self.config.check_interval_ms));
+        let budget = Arc::downgrade(&self);
+        spawn_monitored_task!(async move {
+            loop {
+                interval.tick().await;
+                let Some(budget) = budget.upgrade() else {
+                    return;
+                };
+                budget.enforce();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Cache(AtomicUsize);
+
+    impl MemoryConsumer for Cache {
+        fn memory_usage(&self) -> usize {
+            self.0.load(Ordering::Relaxed)
+        }
+
+        fn shrink(&self, bytes: usize) -> usize {
+            let freed = bytes.min(self.memory_usage());
+            self.0.fetch_sub(freed, Ordering::Relaxed);
+            freed
+        }
+    }
+
+    struct Queue(usize);
+
+    impl MemoryConsumer for Queue {
+        fn memory_usage(&self) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_shrink_over_threshold() {
+        let budget = MemoryBudget::new(
+            MemoryBudgetConfig {
+                limit_bytes: 1000,
+                shrink_threshold_pct: 90,
+                shrink_target_pct: 50,
+                check_interval_ms: 1000,
+            },
+            &Registry::new(),
+        );
+        let cache = Arc::new(Cache(AtomicUsize::new(300)));
+        let queue = Arc::new(Queue(400));
+        budget.register("cache", &cache);
+        budget.register("queue", &queue);
+
+        // Under the threshold, nothing is shrunk.
+        assert_eq!(budget.enforce(), 700);
+        assert_eq!(cache.memory_usage(), 300);
+
+        // Over the threshold, the queue cannot shrink, so the cache is shrunk down to the target.
+        cache.0.store(550, Ordering::Relaxed);
+        assert_eq!(budget.enforce(), 500);
+        assert_eq!(cache.memory_usage(), 100);
+        assert_eq!(
+            budget
+                .metrics
+                .component_memory_usage
+                .with_label_values(&["cache"])
+                .get(),
+            100
+        );
+
+        // Dropped components are no longer accounted for.
+        drop(queue);
+        assert_eq!(budget.enforce(), 100);
+    }
+}
@@ -0,0 +1,469 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolution of names registered with the on-chain name service, so that clients do not need to
+//! traverse the dynamic fields of its registries themselves.
+//!
+//! The name service keeps two tables: the registry, mapping each domain to its record, and the
+//! reverse registry, mapping addresses to their primary domain. Entries of both are read directly
+//! from the dynamic fields of the tables, whose IDs are derived from the keys being resolved.
+//! Resolutions are cached for a short while, as they change whenever a record is updated.
+
+use std::fmt::{Display, Formatter};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+use move_core_types::ident_str;
+use move_core_types::identifier::IdentStr;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sui_config::node::NameServiceConfig;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::clock::Clock;
+use sui_types::collection_types::VecMap;
+use sui_types::dynamic_field::{derive_dynamic_field_id, Field};
+use sui_types::error::SuiError;
+use sui_types::id::ID;
+use sui_types::storage::ObjectStore;
+use sui_types::SUI_CLOCK_OBJECT_ID;
+use thiserror::Error;
+
+pub const DOMAIN_MODULE_NAME: &IdentStr = ident_str!("domain");
+pub const DOMAIN_STRUCT_NAME: &IdentStr = ident_str!("Domain");
+
+/// Top level domain of all the names registered with the name service.
+const TOP_LEVEL_DOMAIN: &str = "sui";
+const MAX_LABEL_LENGTH: usize = 63;
+
+#[derive(Debug, Error)]
+pub enum NameServiceError {
+    #[error("Invalid name {name}: {reason}")]
+    InvalidName { name: String, reason: String },
+
+    #[error("Name service is not configured on this node")]
+    NotConfigured,
+
+    #[error("Failed to read the name service registry: {0}")]
+    InvalidRegistry(String),
+
+    #[error(transparent)]
+    SuiError(#[from] SuiError),
+}
+
+/// Rust version of the Move name service `domain::Domain` type. Labels are stored from the top
+/// level domain down, e.g. `["sui", "example"]` for `example.sui`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Domain {
+    labels: Vec<String>,
+}
+
+impl Domain {
+    pub fn type_(package_address: SuiAddress) -> StructTag {
+        StructTag {
+            address: package_address.into(),
+            module: DOMAIN_MODULE_NAME.to_owned(),
+            name: DOMAIN_STRUCT_NAME.to_owned(),
+            type_params: vec![],
+        }
+    }
+}
+
+impl FromStr for Domain {
+    type Err = NameServiceError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| NameServiceError::InvalidName {
+            name: name.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let labels: Vec<String> = name.split('.').rev().map(str::to_string).collect();
+        if labels.len() < 2 {
+            return Err(invalid(
+                "expected at least a label and the top level domain",
+            ));
+        }
+        if labels[0] != TOP_LEVEL_DOMAIN {
+            return Err(invalid("unknown top level domain"));
+        }
+        for label in &labels {
+            if label.is_empty() || label.len() > MAX_LABEL_LENGTH {
+                return Err(invalid("labels must have between 1 and 63 characters"));
+            }
+            if !label
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            {
+                return Err(invalid(
+                    "labels may only contain lowercase letters, digits and hyphens",
+                ));
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return Err(invalid("labels cannot start or end with a hyphen"));
+            }
+        }
+        Ok(Self { labels })
+    }
+}
+
+impl Display for Domain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let labels: Vec<&str> = self.labels.iter().rev().map(String::as_str).collect();
+        write!(f, "{}", labels.join("."))
+    }
+}
+
+/// Rust version of the Move name service `name_record::NameRecord` type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameRecord {
+    /// The registration NFT of the domain.
+    pub nft_id: ID,
+    pub expiration_timestamp_ms: u64,
+    pub target_address: Option<SuiAddress>,
+    pub data: VecMap<String, String>,
+}
+
+impl NameRecord {
+    pub fn is_expired(&self, timestamp_ms: u64) -> bool {
+        self.expiration_timestamp_ms <= timestamp_ms
+    }
+}
+
+/// Cached resolutions, with the time they were read.
+type ResolutionCache<K, V> = Option<Mutex<LruCache<K, (V, Instant)>>>;
+
+pub struct NameService {
+    config: NameServiceConfig,
+    cache_ttl: Duration,
+    /// Caches of resolutions, `None` if caching is disabled.
+    addresses: ResolutionCache<String, Option<SuiAddress>>,
+    names: ResolutionCache<SuiAddress, Option<String>>,
+}
+
+impl NameService {
+    pub fn new(config: NameServiceConfig) -> Self {
+        let cache_capacity = NonZeroUsize::new(config.cache_capacity);
+        Self {
+            cache_ttl: Duration::from_millis(config.cache_ttl_ms),
+            addresses: cache_capacity.map(|capacity| Mutex::new(LruCache::new(capacity))),
+            names: cache_capacity.map(|capacity| Mutex::new(LruCache::new(capacity))),
+            config,
+        }
+    }
+
+    /// Returns the address `name` points to, or `None` if the name is not registered, has expired,
+    /// or does not point to an address.
+    pub fn resolve_address(
+        &self,
+        store: &impl ObjectStore,
+        name: &str,
+    ) -> Result<Option<SuiAddress>, NameServiceError> {
+        let domain: Domain = name.parse()?;
+        let name = domain.to_string();
+        if let Some(address) = self.cached(&self.addresses, &name) {
+            return Ok(address);
+        }
+
+        let address = self.read_address(store, &domain)?;
+        if let Some(addresses) = &self.addresses {
+            addresses.lock().put(name, (address, Instant::now()));
+        }
+        Ok(address)
+    }
+
+    /// Returns the primary name of `address`, or `None` if it has none. Names that no longer point
+    /// to the address are not returned.
+    pub fn resolve_name(
+        &self,
+        store: &impl ObjectStore,
+        address: SuiAddress,
+    ) -> Result<Option<String>, NameServiceError> {
+        if let Some(name) = self.cached(&self.names, &address) {
+            return Ok(name);
+        }
+
+        let domain: Option<Domain> = read_field(
+            store,
+            self.config.reverse_registry_id,
+            &TypeTag::Address,
+            &address,
+        )?;
+        let name = match domain {
+            Some(domain) if self.read_address(store, &domain)? == Some(address) => {
+                Some(domain.to_string())
+            }
+            _ => None,
+        };
+        if let Some(names) = &self.names {
+            names.lock().put(address, (name.clone(), Instant::now()));
+        }
+        Ok(name)
+    }
+
+    fn read_address(
+        &self,
+        store: &impl ObjectStore,
+        domain: &Domain,
+    ) -> Result<Option<SuiAddress>, NameServiceError> {
+        let domain_type = TypeTag::Struct(Box::new(Domain::type_(self.config.package_address)));
+        let record: Option<NameRecord> =
+            read_field(store, self.config.registry_id, &domain_type, domain)?;
+        let Some(record) = record else {
+            return Ok(None);
+        };
+        if record.is_expired(current_timestamp_ms(store)?) {
+            return Ok(None);
+        }
+        Ok(record.target_address)
+    }
+
+    fn cached<K, V>(&self, cache: &ResolutionCache<K, V>, key: &K) -> Option<V>
+    where
+        K: std::hash::Hash + Eq,
+        V: Clone,
+    {
+        let mut cache = cache.as_ref()?.lock();
+        match cache.get(key) {
+            Some((value, read_at)) if read_at.elapsed() < self.cache_ttl => Some(value.clone()),
+            Some(_) => {
+                cache.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+/// Reads the value of the entry of table `table_id` with key `key`, of type `key_type`, or `None`
+/// if the table has no such entry.
+fn read_field<K, V>(
+    store: &impl ObjectStore,
+    table_id: ObjectID,
+    key_type: &TypeTag,
+    key: &K,
+) -> Result<Option<V>, NameServiceError>
+where
+    K: Serialize + DeserializeOwned,
+    V: DeserializeOwned,
+{
+    let key_bytes = bcs::to_bytes(key).map_err(|e| invalid_registry(e.to_string()))?;
+    let id = derive_dynamic_field_id(table_id, key_type, &key_bytes)
+        .map_err(|e| invalid_registry(e.to_string()))?;
+    let Some(object) = store.get_object(&id)? else {
+        return Ok(None);
+    };
+    let move_object = object
+        .data
+        .try_as_move()
+        .ok_or_else(|| invalid_registry(format!("entry {id} is not a Move object")))?;
+    let field: Field<K, V> = bcs::from_bytes(move_object.contents())
+        .map_err(|e| invalid_registry(format!("entry {id} cannot be deserialized: {e}")))?;
+    Ok(Some(field.value))
+}
+
+fn current_timestamp_ms(store: &impl ObjectStore) -> Result<u64, NameServiceError> {
+    let clock = store
+        .get_object(&SUI_CLOCK_OBJECT_ID)?
+        .and_then(|object| object.data.try_as_move().map(|o| o.contents().to_vec()))
+        .ok_or_else(|| invalid_registry("the clock object is missing".to_string()))?;
+    let clock: Clock = bcs::from_bytes(&clock).map_err(|e| invalid_registry(e.to_string()))?;
+    Ok(clock.timestamp_ms)
+}
+
+fn invalid_registry(error: String) -> NameServiceError {
+    NameServiceError::InvalidRegistry(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_protocol_config::ProtocolConfig;
+    use sui_types::base_types::{MoveObjectType, TransactionDigest};
+    use sui_types::id::UID;
+    use sui_types::in_memory_storage::InMemoryStorage;
+    use sui_types::object::{MoveObject, Object, Owner, OBJECT_START_VERSION};
+    use sui_types::SUI_FRAMEWORK_ADDRESS;
+
+    const NOW_MS: u64 = 1_000_000;
+
+    fn config() -> NameServiceConfig {
+        NameServiceConfig {
+            package_address: SuiAddress::random_for_testing_only(),
+            registry_id: ObjectID::random(),
+            reverse_registry_id: ObjectID::random(),
+            cache_capacity: 100,
+            cache_ttl_ms: 60_000,
+        }
+    }
+
+    fn move_object(id: ObjectID, type_: StructTag, contents: Vec<u8>, owner: Owner) -> Object {
+        assert_eq!(&contents[..ObjectID::LENGTH], id.as_ref());
+        let move_object = unsafe {
+            MoveObject::new_from_execution(
+                MoveObjectType::from(type_),
+                false,
+                OBJECT_START_VERSION,
+                contents,
+                &ProtocolConfig::get_for_max_version(),
+            )
+            .unwrap()
+        };
+        Object::new_move(move_object, owner, TransactionDigest::genesis())
+    }
+
+    fn field<K: Serialize, V: Serialize>(
+        table_id: ObjectID,
+        key_type: TypeTag,
+        value_type: TypeTag,
+        key: K,
+        value: V,
+    ) -> Object {
+        let id =
+            derive_dynamic_field_id(table_id, &key_type, &bcs::to_bytes(&key).unwrap()).unwrap();
+        let type_ = StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: ident_str!("dynamic_field").to_owned(),
+            name: ident_str!("Field").to_owned(),
+            type_params: vec![key_type, value_type],
+        };
+        let field = Field {
+            id: UID::new(id),
+            name: key,
+            value,
+        };
+        move_object(
+            id,
+            type_,
+            bcs::to_bytes(&field).unwrap(),
+            Owner::ObjectOwner(table_id.into()),
+        )
+    }
+
+    fn store(config: &NameServiceConfig, records: &[(&str, NameRecord)]) -> InMemoryStorage {
+        let clock = Clock {
+            id: UID::new(SUI_CLOCK_OBJECT_ID),
+            timestamp_ms: NOW_MS,
+        };
+        let mut objects = vec![move_object(
+            SUI_CLOCK_OBJECT_ID,
+            Clock::type_(),
+            bcs::to_bytes(&clock).unwrap(),
+            Owner::Immutable,
+        )];
+        let domain_type = TypeTag::Struct(Box::new(Domain::type_(config.package_address)));
+        let record_type = TypeTag::Struct(Box::new(StructTag {
+            address: config.package_address.into(),
+            module: ident_str!("name_record").to_owned(),
+            name: ident_str!("NameRecord").to_owned(),
+            type_params: vec![],
+        }));
+        for (name, record) in records {
+            let domain: Domain = name.parse().unwrap();
+            objects.push(field(
+                config.registry_id,
+                domain_type.clone(),
+                record_type.clone(),
+                domain.clone(),
+                record.clone(),
+            ));
+            if let Some(address) = record.target_address {
+                objects.push(field(
+                    config.reverse_registry_id,
+                    TypeTag::Address,
+                    domain_type.clone(),
+                    address,
+                    domain,
+                ));
+            }
+        }
+        InMemoryStorage::new(objects)
+    }
+
+    fn record(target_address: Option<SuiAddress>, expiration_timestamp_ms: u64) -> NameRecord {
+        NameRecord {
+            nft_id: ID::new(ObjectID::random()),
+            expiration_timestamp_ms,
+            target_address,
+            data: VecMap { contents: vec![] },
+        }
+    }
+
+    #[test]
+    fn test_domain_parsing() {
+        let domain: Domain = "sub.example.sui".parse().unwrap();
+        assert_eq!(domain.labels, vec!["sui", "example", "sub"]);
+        assert_eq!(domain.to_string(), "sub.example.sui");
+
+        for name in [
+            "sui",
+            "example.move",
+            "example..sui",
+            ".sui",
+            "Example.sui",
+            "-example.sui",
+            "exa_mple.sui",
+        ] {
+            assert!(name.parse::<Domain>().is_err(), "{name}");
+        }
+        assert!(format!("{}.sui", "a".repeat(64)).parse::<Domain>().is_err());
+    }
+
+    #[test]
+    fn test_resolution() {
+        let config = config();
+        let address = SuiAddress::random_for_testing_only();
+        let expired = SuiAddress::random_for_testing_only();
+        let store = store(
+            &config,
+            &[
+                ("example.sui", record(Some(address), NOW_MS + 1)),
+                ("unset.sui", record(None, NOW_MS + 1)),
+                ("expired.sui", record(Some(expired), NOW_MS)),
+            ],
+        );
+        let name_service = NameService::new(config);
+
+        assert_eq!(
+            name_service.resolve_address(&store, "example.sui").unwrap(),
+            Some(address)
+        );
+        assert_eq!(
+            name_service.resolve_name(&store, address).unwrap(),
+            Some("example.sui".to_string())
+        );
+        assert_eq!(
+            name_service.resolve_address(&store, "unset.sui").unwrap(),
+            None
+        );
+        assert_eq!(
+            name_service.resolve_address(&store, "expired.sui").unwrap(),
+            None
+        );
+        assert_eq!(name_service.resolve_name(&store, expired).unwrap(), None);
+        assert_eq!(
+            name_service.resolve_address(&store, "missing.sui").unwrap(),
+            None
+        );
+        assert_eq!(
+            name_service
+                .resolve_name(&store, SuiAddress::random_for_testing_only())
+                .unwrap(),
+            None
+        );
+        assert!(name_service.resolve_address(&store, "example").is_err());
+
+        // Resolutions are served from the cache once read.
+        let empty = InMemoryStorage::new(vec![]);
+        assert_eq!(
+            name_service.resolve_address(&empty, "example.sui").unwrap(),
+            Some(address)
+        );
+        assert_eq!(
+            name_service.resolve_name(&empty, address).unwrap(),
+            Some("example.sui".to_string())
+        );
+    }
+}
@@ -16,6 +16,9 @@ pub struct QuorumDriverMetrics {
     pub(crate) total_ok_responses: IntCounter,
     pub(crate) total_err_responses: IntCounterVec,
     pub(crate) attempt_times_ok_response: Histogram,
+    pub(crate) attempt_times_err_response: Histogram,
+    pub(crate) total_retries: IntCounterVec,
+    pub(crate) total_retries_in_equivocation_protection_window: IntCounter,
 
     // TODO: add histogram of attempt that tx succeeds
     pub(crate) current_requests_in_flight: IntGauge,
@@ -59,6 +62,24 @@ impl QuorumDriverMetrics {
                 "Total attempt times of ok response",
                 registry,
             ),
+            attempt_times_err_response: Histogram::new_in_registry(
+                "quorum_driver_attempt_times_err_response",
+                "Total attempt times of err response",
+                registry,
+            ),
+            total_retries: register_int_counter_vec_with_registry!(
+                "quorum_driver_total_retries",
+                "Total number of retries after transient failures, grouped by the failed action",
+                &["action"],
+                registry,
+            )
+            .unwrap(),
+            total_retries_in_equivocation_protection_window: register_int_counter_with_registry!(
+                "quorum_driver_total_retries_in_equivocation_protection_window",
+                "Total number of retries past the maximum retry times, made because the transaction was still within the equivocation protection window",
+                registry,
+            )
+            .unwrap(),
             current_requests_in_flight: register_int_gauge_with_registry!(
                 "current_requests_in_flight",
                 "Current number of requests being processed in QuorumDriver",
@@ -9,8 +9,10 @@ pub mod reconfig_observer;
 use arc_swap::ArcSwap;
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Formatter};
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
+use sui_config::node::QuorumDriverConfig;
 use sui_types::base_types::{AuthorityName, ObjectRef, TransactionDigest};
 use sui_types::committee::{Committee, EpochId, StakeUnit};
 use sui_types::quorum_driver_types::{
@@ -22,7 +24,7 @@ use tokio::time::{sleep_until, Instant};
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::task::JoinHandle;
 use tracing::Instrument;
-use tracing::{debug, info, warn};
+use tracing::{debug, info, warn, Span};
 
 use crate::authority_aggregator::{
     AggregatorProcessCertificateError, AggregatorProcessTransactionError, AuthorityAggregator,
@@ -44,7 +46,6 @@ mod tests;
 
 const TASK_QUEUE_SIZE: usize = 10000;
 const EFFECTS_QUEUE_SIZE: usize = 10000;
-const TX_MAX_RETRY_TIMES: u8 = 10;
 
 #[derive(Clone)]
 pub struct QuorumDriverTask {
@@ -52,6 +53,11 @@ pub struct QuorumDriverTask {
     pub tx_cert: Option<VerifiedCertificate>,
     pub retry_times: u8,
     pub next_retry_after: Instant,
+    /// When the transaction was first submitted.
+    pub submitted_at: Instant,
+    /// Span of the submission of the transaction, kept across retries so that the aggregator
+    /// spans are in the trace of the transaction.
+    pub span: Span,
 }
 
 impl Debug for QuorumDriverTask {
@@ -61,6 +67,7 @@ impl Debug for QuorumDriverTask {
         write!(writer, "has_tx_cert={} ", self.tx_cert.is_some())?;
         write!(writer, "retry_times={} ", self.retry_times)?;
         write!(writer, "next_retry_after={:?} ", self.next_retry_after)?;
+        write!(writer, "submitted_at={:?} ", self.submitted_at)?;
         write!(f, "{}", writer)
     }
 }
@@ -71,7 +78,7 @@ pub struct QuorumDriver<A> {
     effects_subscribe_sender: tokio::sync::broadcast::Sender<QuorumDriverEffectsQueueResult>,
     notifier: Arc<NotifyRead<TransactionDigest, QuorumDriverResult>>,
     metrics: Arc<QuorumDriverMetrics>,
-    max_retry_times: u8,
+    config: QuorumDriverConfig,
 }
 
 impl<A> QuorumDriver<A> {
@@ -81,7 +88,7 @@ impl<A> QuorumDriver<A> {
         effects_subscribe_sender: tokio::sync::broadcast::Sender<QuorumDriverEffectsQueueResult>,
         notifier: Arc<NotifyRead<TransactionDigest, QuorumDriverResult>>,
         metrics: Arc<QuorumDriverMetrics>,
-        max_retry_times: u8,
+        config: QuorumDriverConfig,
     ) -> Self {
         Self {
             validators,
@@ -89,7 +96,7 @@ impl<A> QuorumDriver<A> {
             effects_subscribe_sender,
             notifier,
             metrics,
-            max_retry_times,
+            config,
         }
     }
 
@@ -120,7 +127,8 @@ impl<A> QuorumDriver<A> {
             })
     }
 
-    /// Enqueue the task again if it hasn't maxed out the total retry attempts.
+    /// Enqueue the task again if it hasn't maxed out the total retry attempts, or if it is still
+    /// within the equivocation protection window.
     /// If it has, notify failure.
     /// Enqueuing happens only after the `next_retry_after`, if not, wait until that instant
     async fn enqueue_again_maybe(
@@ -128,23 +136,35 @@ impl<A> QuorumDriver<A> {
         transaction: VerifiedTransaction,
         tx_cert: Option<VerifiedCertificate>,
         old_retry_times: u8,
+        submitted_at: Instant,
     ) -> SuiResult<()> {
-        if old_retry_times >= self.max_retry_times {
-            // max out the retry times, notify failure
-            info!(tx_digest=?transaction.digest(), "Failed to reach finality after attempting for {} times", old_retry_times+1);
-            self.notify(
-                &transaction,
-                &Err(
-                    QuorumDriverError::FailedWithTransientErrorAfterMaximumAttempts {
-                        total_attempts: old_retry_times + 1,
-                    },
-                ),
-                old_retry_times + 1,
-            );
-            return Ok(());
+        let total_attempts = old_retry_times.saturating_add(1);
+        if old_retry_times >= self.config.max_retry_times {
+            let protection_window =
+                Duration::from_millis(self.config.equivocation_protection_window_ms);
+            if submitted_at.elapsed() >= protection_window {
+                // max out the retry times, notify failure
+                info!(tx_digest=?transaction.digest(), "Failed to reach finality after attempting for {} times", total_attempts);
+                self.notify(
+                    &transaction,
+                    &Err(
+                        QuorumDriverError::FailedWithTransientErrorAfterMaximumAttempts {
+                            total_attempts,
+                        },
+                    ),
+                    total_attempts,
+                );
+                return Ok(());
+            }
+            debug!(tx_digest=?transaction.digest(), "Retrying past the maximum retry times, within the equivocation protection window");
+            self.metrics
+                .total_retries_in_equivocation_protection_window
+                .inc();
         }
+        // The delay stops growing once the maximum retry times are reached.
+        let backoff = u64::pow(2, old_retry_times.min(self.config.max_retry_times).into());
         let next_retry_after =
-            Instant::now() + Duration::from_millis(200 * u64::pow(2, old_retry_times.into()));
+            Instant::now() + Duration::from_millis(self.config.retry_initial_delay_ms * backoff);
         sleep_until(next_retry_after).await;
 
         let tx_cert = match tx_cert {
@@ -158,8 +178,10 @@ impl<A> QuorumDriver<A> {
         self.enqueue_task(QuorumDriverTask {
             transaction,
             tx_cert,
-            retry_times: old_retry_times + 1,
+            retry_times: total_attempts,
             next_retry_after,
+            submitted_at,
+            span: Span::current(),
         })
         .await
     }
@@ -184,6 +206,9 @@ impl<A> QuorumDriver<A> {
                     .total_err_responses
                     .with_label_values(&[err.as_ref()])
                     .inc();
+                self.metrics
+                    .attempt_times_err_response
+                    .report(total_attempts as u64);
                 Err((*tx_digest, err.clone()))
             }
         };
@@ -215,6 +240,8 @@ where
             tx_cert: None,
             retry_times: 0,
             next_retry_after: Instant::now(),
+            submitted_at: Instant::now(),
+            span: Span::current(),
         })
         .await?;
         Ok(ticket)
@@ -238,6 +265,8 @@ where
             tx_cert: None,
             retry_times: 0,
             next_retry_after: Instant::now(),
+            submitted_at: Instant::now(),
+            span: Span::current(),
         })
         .await
     }
@@ -399,22 +428,7 @@ where
                 tracing::debug_span!("aggregator_process_cert", tx_digest = ?certificate.digest()),
             )
             .await
-            .map_err(|agg_err| match agg_err {
-                AggregatorProcessCertificateError::FatalExecuteCertificate {
-                    non_retryable_errors,
-                } => {
-                    debug!(?non_retryable_errors, "Nonretryable certificate");
-                    Some(QuorumDriverError::NonRecoverableTransactionError {
-                        errors: non_retryable_errors,
-                    })
-                }
-                AggregatorProcessCertificateError::RetryableExecuteCertificate {
-                    retryable_errors,
-                } => {
-                    debug!(?retryable_errors, "Retryable certificate");
-                    None
-                }
-            })?;
+            .map_err(Self::process_certificate_error)?;
         let response = QuorumDriverResponse {
             effects_cert: effects,
             events,
@@ -423,12 +437,128 @@ where
         Ok(response)
     }
 
+    pub(crate) async fn process_certificate_bundle(
+        &self,
+        certificates: Vec<VerifiedCertificate>,
+    ) -> Result<Vec<QuorumDriverResponse>, Option<QuorumDriverError>> {
+        let responses = self
+            .validators
+            .load()
+            .process_certificate_bundle(
+                certificates
+                    .into_iter()
+                    .map(VerifiedCertificate::into_inner)
+                    .collect(),
+            )
+            .instrument(tracing::debug_span!("aggregator_process_cert_bundle"))
+            .await
+            .map_err(Self::process_certificate_error)?;
+        Ok(responses
+            .into_iter()
+            .map(|(effects_cert, events)| QuorumDriverResponse {
+                effects_cert,
+                events,
+            })
+            .collect())
+    }
+
+    fn process_certificate_error(
+        agg_err: AggregatorProcessCertificateError,
+    ) -> Option<QuorumDriverError> {
+        match agg_err {
+            AggregatorProcessCertificateError::FatalExecuteCertificate {
+                non_retryable_errors,
+            } => {
+                debug!(?non_retryable_errors, "Nonretryable certificate");
+                Some(QuorumDriverError::NonRecoverableTransactionError {
+                    errors: non_retryable_errors,
+                })
+            }
+            AggregatorProcessCertificateError::RetryableExecuteCertificate { retryable_errors } => {
+                debug!(?retryable_errors, "Retryable certificate");
+                None
+            }
+        }
+    }
+
+    /// Certifies the transactions of a soft bundle one by one, then has the validators submit the
+    /// certificates to consensus together, so that they are sequenced contiguously and in order.
+    /// Bundles do not go through the task queue: transient failures are retried in place, with
+    /// the same limit and backoff as single transactions. A transaction that was executed before
+    /// cannot be ordered with the others, and fails the bundle.
+    pub(crate) async fn execute_transaction_bundle(
+        &self,
+        transactions: Vec<VerifiedTransaction>,
+    ) -> Result<Vec<QuorumDriverResponse>, QuorumDriverError> {
+        let mut certificates = Vec::with_capacity(transactions.len());
+        for transaction in &transactions {
+            let result = self
+                .retry_bundle_step("get tx cert", || {
+                    self.process_transaction(transaction.clone())
+                })
+                .await?;
+            match result {
+                ProcessTransactionResult::Certified(certificate) => certificates.push(certificate),
+                ProcessTransactionResult::Executed(..) => {
+                    return Err(QuorumDriverError::InvalidSoftBundle(format!(
+                        "transaction {:?} was already executed",
+                        transaction.digest()
+                    )));
+                }
+            }
+        }
+        let certificates = &certificates;
+        self.retry_bundle_step("get effects cert", || {
+            self.process_certificate_bundle(certificates.clone())
+        })
+        .await
+    }
+
+    async fn retry_bundle_step<T, F, Fut>(
+        &self,
+        action: &'static str,
+        mut step: F,
+    ) -> Result<T, QuorumDriverError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Option<QuorumDriverError>>>,
+    {
+        let mut retry_times: u8 = 0;
+        loop {
+            match step().await {
+                Ok(result) => return Ok(result),
+                Err(Some(err)) => return Err(err),
+                Err(None) if retry_times >= self.config.max_retry_times => {
+                    return Err(
+                        QuorumDriverError::FailedWithTransientErrorAfterMaximumAttempts {
+                            total_attempts: retry_times.saturating_add(1),
+                        },
+                    );
+                }
+                Err(None) => {
+                    debug!("Failed to {action} for soft bundle - Retrying");
+                    self.metrics
+                        .total_retries
+                        .with_label_values(&[action])
+                        .inc();
+                    let backoff = u64::pow(2, retry_times.into());
+                    tokio::time::sleep(Duration::from_millis(
+                        self.config.retry_initial_delay_ms * backoff,
+                    ))
+                    .await;
+                    retry_times += 1;
+                }
+            }
+        }
+    }
+
     pub async fn update_validators(&self, new_validators: Arc<AuthorityAggregator<A>>) {
         info!(
             "Quorum Driver updating AuthorityAggregator with committee {}",
             new_validators.committee
         );
-        self.validators.store(new_validators);
+        self.validators
+            .store(configure_validators(&new_validators, &self.config));
     }
 
     /// Returns Some(true) if the conflicting transaction is executed successfully
@@ -529,18 +659,18 @@ where
         notifier: Arc<NotifyRead<TransactionDigest, QuorumDriverResult>>,
         reconfig_observer: Arc<dyn ReconfigObserver<A> + Sync + Send>,
         metrics: Arc<QuorumDriverMetrics>,
-        max_retry_times: u8,
+        config: QuorumDriverConfig,
     ) -> Self {
         let (task_tx, task_rx) = mpsc::channel::<QuorumDriverTask>(TASK_QUEUE_SIZE);
         let (subscriber_tx, subscriber_rx) =
             tokio::sync::broadcast::channel::<_>(EFFECTS_QUEUE_SIZE);
         let quorum_driver = Arc::new(QuorumDriver::new(
-            ArcSwap::from(validators),
+            ArcSwap::from(configure_validators(&validators, &config)),
             task_tx,
             subscriber_tx,
             notifier,
             metrics.clone(),
-            max_retry_times,
+            config,
         ));
         let metrics_clone = metrics.clone();
         let processor_handle = {
@@ -588,6 +718,17 @@ where
         self.quorum_driver.submit_transaction(transaction).await
     }
 
+    /// Executes a soft bundle of transactions, which consensus sequences contiguously and in the
+    /// given order. Returns a response for each transaction, in order.
+    pub async fn execute_transaction_bundle(
+        &self,
+        transactions: Vec<VerifiedTransaction>,
+    ) -> Result<Vec<QuorumDriverResponse>, QuorumDriverError> {
+        self.quorum_driver
+            .execute_transaction_bundle(transactions)
+            .await
+    }
+
     /// Create a new `QuorumDriverHandler` based on the same AuthorityAggregator.
     /// Note: the new `QuorumDriverHandler` will have a new `ArcSwap<AuthorityAggregator>`
     /// that is NOT tied to the original one. So if there are multiple QuorumDriver(Handler)
@@ -603,7 +744,7 @@ where
             effects_subscribe_sender,
             notifier: Arc::new(NotifyRead::new()),
             metrics: self.quorum_driver_metrics.clone(),
-            max_retry_times: self.quorum_driver.max_retry_times,
+            config: self.quorum_driver.config.clone(),
         });
         let metrics = self.quorum_driver_metrics.clone();
         let processor_handle = {
@@ -661,6 +802,7 @@ where
             transaction,
             tx_cert,
             retry_times: old_retry_times,
+            submitted_at,
             ..
         } = task;
         let tx_digest = *transaction.digest();
@@ -680,7 +822,11 @@ where
                         effects_cert,
                         events,
                     };
-                    quorum_driver.notify(&transaction, &Ok(response), old_retry_times + 1);
+                    quorum_driver.notify(
+                        &transaction,
+                        &Ok(response),
+                        old_retry_times.saturating_add(1),
+                    );
                     return;
                 }
                 Err(err) => {
@@ -690,6 +836,7 @@ where
                         err,
                         None,
                         old_retry_times,
+                        submitted_at,
                         "get tx cert",
                     );
                     return;
@@ -718,13 +865,18 @@ where
                     err,
                     Some(tx_cert),
                     old_retry_times,
+                    submitted_at,
                     "get effects cert",
                 );
                 return;
             }
         };
 
-        quorum_driver.notify(&transaction, &Ok(response), old_retry_times + 1);
+        quorum_driver.notify(
+            &transaction,
+            &Ok(response),
+            old_retry_times.saturating_add(1),
+        );
     }
 
     fn handle_error(
@@ -733,20 +885,28 @@ where
         err: Option<QuorumDriverError>,
         tx_cert: Option<VerifiedCertificate>,
         old_retry_times: u8,
+        submitted_at: Instant,
         action: &'static str,
     ) {
         let tx_digest = *transaction.digest();
         if let Some(qd_error) = err {
             debug!(?tx_digest, "Failed to {action}: {}", qd_error);
             // non-retryable failure, this task reaches terminal state for now, notify waiter.
-            quorum_driver.notify(&transaction, &Err(qd_error), old_retry_times + 1);
+            quorum_driver.notify(
+                &transaction,
+                &Err(qd_error),
+                old_retry_times.saturating_add(1),
+            );
         } else {
             debug!(?tx_digest, "Failed to {action} - Retrying");
-            spawn_monitored_task!(quorum_driver.enqueue_again_maybe(
-                transaction.clone(),
-                tx_cert,
-                old_retry_times
-            ));
+            quorum_driver
+                .metrics
+                .total_retries
+                .with_label_values(&[action])
+                .inc();
+            spawn_monitored_task!(quorum_driver
+                .enqueue_again_maybe(transaction.clone(), tx_cert, old_retry_times, submitted_at)
+                .in_current_span());
         }
     }
 
@@ -769,7 +929,8 @@ where
             }
             metrics.current_requests_in_flight.dec();
             let qd = quorum_driver.clone();
-            spawn_monitored_task!(QuorumDriverHandler::process_task(qd, task));
+            let span = task.span.clone();
+            spawn_monitored_task!(QuorumDriverHandler::process_task(qd, task).instrument(span));
         }
     }
 }
@@ -779,7 +940,7 @@ pub struct QuorumDriverHandlerBuilder<A> {
     metrics: Arc<QuorumDriverMetrics>,
     notifier: Option<Arc<NotifyRead<TransactionDigest, QuorumDriverResult>>>,
     reconfig_observer: Option<Arc<dyn ReconfigObserver<A> + Sync + Send>>,
-    max_retry_times: u8,
+    config: QuorumDriverConfig,
 }
 
 impl<A> QuorumDriverHandlerBuilder<A>
@@ -792,7 +953,7 @@ where
             metrics,
             notifier: None,
             reconfig_observer: None,
-            max_retry_times: TX_MAX_RETRY_TIMES,
+            config: QuorumDriverConfig::default(),
         }
     }
 
@@ -812,9 +973,14 @@ where
         self
     }
 
+    pub fn with_config(mut self, config: QuorumDriverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Used in tests when smaller number of retries is desired
     pub fn with_max_retry_times(mut self, max_retry_times: u8) -> Self {
-        self.max_retry_times = max_retry_times;
+        self.config.max_retry_times = max_retry_times;
         self
     }
 
@@ -827,7 +993,20 @@ where
             self.reconfig_observer
                 .expect("Reconfig observer is missing"),
             self.metrics,
-            self.max_retry_times,
+            self.config,
         )
     }
 }
+
+/// Applies the per-validator request timeout and the sampling strategy of `config` to
+/// `validators`.
+fn configure_validators<A: Clone>(
+    validators: &AuthorityAggregator<A>,
+    config: &QuorumDriverConfig,
+) -> Arc<AuthorityAggregator<A>> {
+    let mut validators = validators.clone();
+    validators.timeouts.authority_request_timeout =
+        Duration::from_millis(config.validator_request_timeout_ms);
+    validators.sampling = config.validator_sampling;
+    Arc::new(validators)
+}
@@ -8,7 +8,8 @@ use crate::test_utils::make_transfer_sui_transaction;
 use crate::{quorum_driver::QuorumDriverMetrics, test_utils::init_local_authorities};
 use mysten_common::sync::notify_read::{NotifyRead, Registration};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use sui_config::node::QuorumDriverConfig;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{deterministic_random_account_key, get_key_pair, AccountKeyPair};
 use sui_types::messages::{TransactionEffectsAPI, VerifiedTransaction};
@@ -197,6 +198,44 @@ async fn test_quorum_driver_update_validators_and_max_retry_times() {
     handle.await.unwrap();
 }
 
+#[tokio::test]
+async fn test_quorum_driver_equivocation_protection_window() {
+    telemetry_subscribers::init_for_testing();
+    let (mut aggregator, tx) = setup().await;
+    // Client and server epochs mismatch, so every attempt fails with a transient error.
+    aggregator.committee.epoch = 10;
+
+    let metrics = Arc::new(QuorumDriverMetrics::new_for_tests());
+    let quorum_driver_handler =
+        QuorumDriverHandlerBuilder::new(Arc::new(aggregator), metrics.clone())
+            .with_reconfig_observer(Arc::new(DummyReconfigObserver {}))
+            .with_config(QuorumDriverConfig {
+                max_retry_times: 0,
+                retry_initial_delay_ms: 100,
+                equivocation_protection_window_ms: 1000,
+                ..Default::default()
+            })
+            .start();
+
+    let start = Instant::now();
+    let ticket = quorum_driver_handler.submit_transaction(tx).await.unwrap();
+    match tokio::time::timeout(Duration::from_secs(20), ticket).await {
+        Ok(Err(QuorumDriverError::FailedWithTransientErrorAfterMaximumAttempts {
+            total_attempts,
+        })) => assert!(total_attempts > 1),
+        _ => panic!(
+            "The transaction should be retried until the end of the equivocation protection window"
+        ),
+    };
+    assert!(start.elapsed() >= Duration::from_secs(1));
+    assert!(
+        metrics
+            .total_retries_in_equivocation_protection_window
+            .get()
+            > 0
+    );
+}
+
 #[tokio::test]
 async fn test_quorum_driver_object_locked() -> Result<(), anyhow::Error> {
     let gas_objects = generate_test_gas_objects();
@@ -0,0 +1,316 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Detection of network partitions from the point of view of a validator.
+//!
+//! A validator can only take part in certifying transactions and checkpoints while it reaches a
+//! quorum of the committee, weighted by stake. An authority is considered reachable when this
+//! validator is connected to it over the p2p network, whose connections are kept alive by probes,
+//! or when consensus committed one of its certificates recently. The evaluation reports which
+//! authorities are reachable, so that operators can tell a fault local to this validator, where no
+//! other authority is reachable, from a network-wide partition, where only part of the committee
+//! is.
+
+use crate::authority::AuthorityState;
+use crate::consensus_adapter::CheckConnection;
+use mysten_metrics::spawn_monitored_task;
+use parking_lot::Mutex;
+use prometheus::{
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, IntGauge, IntGaugeVec,
+    Registry,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_simulator::narwhal_network::connectivity::ConnectionStatus;
+use sui_types::base_types::AuthorityName;
+use sui_types::committee::{Committee, EpochId, StakeUnit};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Interval between two evaluations of the reachability of the committee.
+const EVALUATION_INTERVAL: Duration = Duration::from_secs(5);
+
+/// An authority without a certificate committed by consensus for this long is not considered
+/// reachable through consensus anymore.
+const CONSENSUS_STALENESS: Duration = Duration::from_secs(30);
+
+pub struct QuorumReachabilityMetrics {
+    can_participate: IntGauge,
+    reachable_stake: IntGauge,
+    authority_reachable: IntGaugeVec,
+}
+
+impl QuorumReachabilityMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            can_participate: register_int_gauge_with_registry!(
+                "quorum_reachability_can_participate",
+                "Whether a quorum of the committee is reachable from this validator",
+                registry,
+            )
+            .unwrap(),
+            reachable_stake: register_int_gauge_with_registry!(
+                "quorum_reachability_reachable_stake",
+                "Stake of the authorities reachable from this validator, including its own",
+                registry,
+            )
+            .unwrap(),
+            authority_reachable: register_int_gauge_vec_with_registry!(
+                "quorum_reachability_authority_reachable",
+                "Whether each authority of the committee is reachable from this validator",
+                &["authority"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum ReachabilityDiagnosis {
+    /// A quorum of the committee is reachable.
+    Healthy,
+    /// No other authority is reachable: the fault is likely local to this validator, e.g. its
+    /// network connectivity or its consensus.
+    Isolated,
+    /// Some authorities are reachable, but not a quorum: the network is likely partitioned, or a
+    /// large part of the committee is down.
+    Partitioned,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AuthorityReachability {
+    pub authority: AuthorityName,
+    pub stake: StakeUnit,
+    /// Whether this validator is connected to the authority, `None` if the connection monitor
+    /// does not know the authority.
+    pub connected: Option<bool>,
+    /// Time since consensus last committed a certificate of the authority, `None` if it did not
+    /// commit one since this validator started.
+    pub last_consensus_certificate_ms: Option<u64>,
+    pub reachable: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct QuorumReachabilityReport {
+    pub epoch: EpochId,
+    /// Whether a quorum of the committee is reachable, so that this validator can take part in
+    /// certifying transactions and checkpoints.
+    pub can_participate: bool,
+    pub diagnosis: ReachabilityDiagnosis,
+    pub reachable_stake: StakeUnit,
+    pub quorum_threshold: StakeUnit,
+    pub total_stake: StakeUnit,
+    pub authorities: Vec<AuthorityReachability>,
+}
+
+impl QuorumReachabilityReport {
+    pub fn unreachable_authorities(&self) -> impl Iterator<Item = &AuthorityReachability> {
+        self.authorities.iter().filter(|a| !a.reachable)
+    }
+}
+
+pub struct QuorumReachability {
+    name: AuthorityName,
+    connection_monitor_status: Arc<dyn CheckConnection>,
+    /// When consensus last committed a certificate of each authority.
+    last_consensus_certificates: Mutex<HashMap<AuthorityName, Instant>>,
+    /// Outcome of the last evaluation, to log changes.
+    could_participate: AtomicBool,
+    metrics: QuorumReachabilityMetrics,
+}
+
+impl QuorumReachability {
+    pub fn new(
+        name: AuthorityName,
+        connection_monitor_status: Arc<dyn CheckConnection>,
+        registry: &Registry,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            connection_monitor_status,
+            last_consensus_certificates: Mutex::new(HashMap::new()),
+            could_participate: AtomicBool::new(true),
+            metrics: QuorumReachabilityMetrics::new(registry),
+        })
+    }
+
+    /// Records that consensus committed a certificate of `authority`.
+    pub fn record_consensus_certificate(&self, authority: AuthorityName) {
+        self.last_consensus_certificates
+            .lock()
+            .insert(authority, Instant::now());
+    }
+
+    /// Evaluates which authorities of `committee` are reachable, and updates the metrics.
+    pub fn evaluate(&self, committee: &Committee) -> QuorumReachabilityReport {
+        let now = Instant::now();
+        let last_consensus_certificates = self.last_consensus_certificates.lock().clone();
+
+        let mut authorities = Vec::with_capacity(committee.num_members());
+        for (authority, stake) in committee.members() {
+            let connected = self
+                .connection_monitor_status
+                .check_connection(&self.name, authority)
+                .map(|status| status == ConnectionStatus::Connected);
+            let last_consensus_certificate = last_consensus_certificates
+                .get(authority)
+                .map(|time| now.saturating_duration_since(*time));
+            let reachable = *authority == self.name
+                || connected == Some(true)
+                || last_consensus_certificate.map_or(false, |age| age < CONSENSUS_STALENESS);
+            authorities.push(AuthorityReachability {
+                authority: *authority,
+                stake: *stake,
+                connected,
+                last_consensus_certificate_ms: last_consensus_certificate
+                    .map(|age| age.as_millis() as u64),
+                reachable,
+            });
+        }
+
+        let reachable_stake = authorities
+            .iter()
+            .filter(|a| a.reachable)
+            .map(|a| a.stake)
+            .sum();
+        let can_participate = reachable_stake >= committee.quorum_threshold();
+        let diagnosis = if can_participate {
+            ReachabilityDiagnosis::Healthy
+        } else if authorities
+            .iter()
+            .any(|a| a.reachable && a.authority != self.name)
+        {
+            ReachabilityDiagnosis::Partitioned
+        } else {
+            ReachabilityDiagnosis::Isolated
+        };
+
+        self.metrics.can_participate.set(can_participate as i64);
+        self.metrics.reachable_stake.set(reachable_stake as i64);
+        for authority in &authorities {
+            self.metrics
+                .authority_reachable
+                .with_label_values(&[&authority.authority.concise().to_string()])
+                .set(authority.reachable as i64);
+        }
+
+        QuorumReachabilityReport {
+            epoch: committee.epoch(),
+            can_participate,
+            diagnosis,
+            reachable_stake,
+            quorum_threshold: committee.quorum_threshold(),
+            total_stake: committee.total_votes,
+            authorities,
+        }
+    }
+
+    /// Periodically evaluates the reachability of the committee of the current epoch of `state`,
+    /// while this node is a member of it, and logs when a quorum is lost or regained.
+    pub fn start(self: Arc<Self>, state: Arc<AuthorityState>) -> JoinHandle<()> {
+        let mut interval = tokio::time::interval(EVALUATION_INTERVAL);
+        let reachability = Arc::downgrade(&self);
+        spawn_monitored_task!(async move {
+            loop {
+                interval.tick().await;
+                let Some(reachability) = reachability.upgrade() else {
+                    return;
+                };
+                let committee = state
+                    .load_epoch_store_one_call_per_task()
+                    .committee()
+                    .clone();
+                if !committee.authority_exists(&reachability.name) {
+                    continue;
+                }
+                reachability.report_changes(&reachability.evaluate(&committee));
+            }
+        })
+    }
+
+    fn report_changes(&self, report: &QuorumReachabilityReport) {
+        let could_participate = self
+            .could_participate
+            .swap(report.can_participate, Ordering::Relaxed);
+        if could_participate && !report.can_participate {
+            let unreachable: Vec<_> = report
+                .unreachable_authorities()
+                .map(|a| a.authority.concise())
+                .collect();
+            warn!(
+                diagnosis = ?report.diagnosis,
+                "Quorum unreachable in epoch {}: reachable stake {} is below the quorum threshold {}, unreachable authorities: {:?}",
+                report.epoch, report.reachable_stake, report.quorum_threshold, unreachable
+            );
+        } else if !could_participate && report.can_participate {
+            info!(
+                "Quorum reachable again in epoch {}, with reachable stake {}",
+                report.epoch, report.reachable_stake
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use sui_simulator::anemo::PeerId;
+
+    /// Reports the authorities of a set as connected, and the others as disconnected.
+    struct Connections(Mutex<HashSet<AuthorityName>>);
+
+    impl CheckConnection for Connections {
+        fn check_connection(
+            &self,
+            _ourself: &AuthorityName,
+            authority: &AuthorityName,
+        ) -> Option<ConnectionStatus> {
+            if self.0.lock().contains(authority) {
+                Some(ConnectionStatus::Connected)
+            } else {
+                Some(ConnectionStatus::Disconnected)
+            }
+        }
+
+        fn update_mapping_for_epoch(&self, _: HashMap<AuthorityName, PeerId>) {}
+    }
+
+    #[test]
+    fn test_quorum_reachability() {
+        let (committee, _) = Committee::new_simple_test_committee_of_size(4);
+        let names: Vec<_> = committee.names().copied().collect();
+        let connections = Arc::new(Connections(Mutex::new(HashSet::new())));
+        let reachability = QuorumReachability::new(names[0], connections.clone(), &Registry::new());
+
+        // Only itself is reachable.
+        let report = reachability.evaluate(&committee);
+        assert!(!report.can_participate);
+        assert_eq!(report.diagnosis, ReachabilityDiagnosis::Isolated);
+        assert_eq!(report.unreachable_authorities().count(), 3);
+
+        // One peer is connected, and consensus committed a certificate of another one.
+        connections.0.lock().insert(names[1]);
+        let report = reachability.evaluate(&committee);
+        assert!(!report.can_participate);
+        assert_eq!(report.diagnosis, ReachabilityDiagnosis::Partitioned);
+
+        reachability.record_consensus_certificate(names[2]);
+        let report = reachability.evaluate(&committee);
+        assert!(report.can_participate);
+        assert_eq!(report.diagnosis, ReachabilityDiagnosis::Healthy);
+        assert_eq!(report.reachable_stake, 3 * committee.weight(&names[3]));
+        assert_eq!(
+            report
+                .unreachable_authorities()
+                .map(|a| a.authority)
+                .collect::<Vec<_>>(),
+            vec![names[3]]
+        );
+        assert_eq!(reachability.metrics.can_participate.get(), 1);
+    }
+}
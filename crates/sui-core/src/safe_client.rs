@@ -340,6 +340,49 @@ where
         Ok(verified)
     }
 
+    /// Execute a soft bundle of certificates.
+    pub async fn handle_certificate_bundle(
+        &self,
+        certificates: Vec<CertifiedTransaction>,
+    ) -> Result<Vec<HandleCertificateResponse>, SuiError> {
+        let digests: Vec<_> = certificates.iter().map(|cert| *cert.digest()).collect();
+        let _timer = self.metrics.handle_certificate_latency.start_timer();
+        let response = self
+            .authority_client
+            .handle_certificate_bundle(HandleCertificateBundleRequest { certificates })
+            .await?;
+
+        let verified = check_error!(
+            self.address,
+            self.verify_certificate_bundle_response(&digests, response),
+            "Client error in handle_certificate_bundle"
+        )?;
+        Ok(verified)
+    }
+
+    fn verify_certificate_bundle_response(
+        &self,
+        digests: &[TransactionDigest],
+        response: HandleCertificateBundleResponse,
+    ) -> SuiResult<Vec<HandleCertificateResponse>> {
+        fp_ensure!(
+            response.responses.len() == digests.len(),
+            SuiError::ByzantineAuthoritySuspicion {
+                authority: self.address,
+                reason: format!(
+                    "Expected {} responses to the soft bundle, got {}",
+                    digests.len(),
+                    response.responses.len()
+                ),
+            }
+        );
+        digests
+            .iter()
+            .zip(response.responses)
+            .map(|(digest, response)| self.verify_certificate_response(digest, response))
+            .collect()
+    }
+
     pub async fn handle_object_info_request(
         &self,
         request: ObjectInfoRequest,
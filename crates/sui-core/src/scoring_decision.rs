@@ -43,15 +43,17 @@ const CUTOFF_VALUE: f64 = 6.0;
 /// this code, we let it detect this and disable itself for safety reasons. If we have a bad network
 /// state then in the interest of making debugging and investigation easier, disabling the scoring
 /// mechanism will likely be helpful.
+///
+/// Returns the final scores of the schedule, when `reputation_scores` are final.
 pub fn update_low_scoring_authorities(
     low_scoring_authorities: Arc<ArcSwap<HashMap<AuthorityName, u64>>>,
     committee: &Committee,
     reputation_scores: ReputationScores,
     authority_names_to_peer_ids: Arc<HashMap<AuthorityName, PeerId>>,
     metrics: &Arc<AuthorityMetrics>,
-) {
+) -> Option<HashMap<AuthorityName, u64>> {
     if !reputation_scores.final_of_schedule {
-        return;
+        return None;
     }
 
     // Convert the narwhal authority ids to the corresponding AuthorityName in SUI so we avoid constantly.
@@ -80,28 +82,15 @@ pub fn update_low_scoring_authorities(
 
     let mut final_low_scoring_map = HashMap::new();
 
-    let mut score_list = vec![];
-    for (score, _stake) in scores_per_authority.values() {
-        score_list.push(*score as f64);
-    }
-
-    let median_value = median(&score_list);
-    let mut deviations = vec![];
-    let mut abs_deviations = vec![];
-    for (i, _) in score_list.clone().iter().enumerate() {
-        deviations.push(score_list[i] - median_value);
-        if score_list[i] != 0.0 {
-            abs_deviations.push((score_list[i] - median_value).abs());
-        }
-    }
-
-    // adjusted median absolute deviation
-    let mad = median(&abs_deviations) / MAD_DIVISOR;
+    let score_list: Vec<u64> = scores_per_authority
+        .values()
+        .map(|(score, _stake)| *score)
+        .collect();
+    let outliers = low_score_outliers(&score_list);
     let mut low_scoring = vec![];
     let mut rest = vec![];
-    for (i, (a, (score, stake))) in scores_per_authority.iter().enumerate() {
-        let temp = deviations[i] / mad;
-        if temp < -CUTOFF_VALUE {
+    for ((a, (score, stake)), outlier) in scores_per_authority.iter().zip(outliers) {
+        if outlier {
             low_scoring.push((a, *score));
         } else {
             rest.push((a, *stake));
@@ -132,10 +121,39 @@ pub fn update_low_scoring_authorities(
         );
 
         low_scoring_authorities.swap(Arc::new(HashMap::new()));
-        return;
+    } else {
+        low_scoring_authorities.swap(Arc::new(final_low_scoring_map));
     }
 
-    low_scoring_authorities.swap(Arc::new(final_low_scoring_map));
+    Some(
+        scores_per_authority
+            .into_iter()
+            .map(|(name, (score, _stake))| (name, score))
+            .collect(),
+    )
+}
+
+/// Returns, for each of `scores`, whether it is a low-value outlier by the adjusted median
+/// absolute deviation described in [`update_low_scoring_authorities`].
+pub fn low_score_outliers(scores: &[u64]) -> Vec<bool> {
+    let score_list: Vec<f64> = scores.iter().map(|score| *score as f64).collect();
+
+    let median_value = median(&score_list);
+    let mut deviations = vec![];
+    let mut abs_deviations = vec![];
+    for score in &score_list {
+        deviations.push(score - median_value);
+        if *score != 0.0 {
+            abs_deviations.push((score - median_value).abs());
+        }
+    }
+
+    // adjusted median absolute deviation
+    let mad = median(&abs_deviations) / MAD_DIVISOR;
+    deviations
+        .into_iter()
+        .map(|deviation| deviation / mad < -CUTOFF_VALUE)
+        .collect()
 }
 
 #[cfg(test)]
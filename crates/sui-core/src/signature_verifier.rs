@@ -2,25 +2,34 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use either::Either;
+use futures::future::join_all;
 use futures::pin_mut;
 use itertools::izip;
 use lru::LruCache;
 use parking_lot::{Mutex, MutexGuard, RwLock};
-use prometheus::{register_int_counter_with_registry, IntCounter, Registry};
+use prometheus::{
+    register_int_counter_vec_with_registry, register_int_counter_with_registry, IntCounter,
+    IntCounterVec, Registry,
+};
 use shared_crypto::intent::Intent;
+use std::collections::HashMap;
+use std::future::Future;
 use std::hash::Hash;
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 use sui_types::{
     committee::Committee,
-    crypto::{AuthoritySignInfoTrait, VerificationObligation},
+    crypto::{AuthoritySignInfoTrait, SignatureScheme, SuiSignature, VerificationObligation},
     digests::CertificateDigest,
     error::{SuiError, SuiResult},
     message_envelope::Message,
-    messages::{CertifiedTransaction, VerifiedCertificate},
+    messages::{CertifiedTransaction, Transaction, VerifiedCertificate, VerifiedTransaction},
     messages_checkpoint::SignedCheckpointSummary,
+    signature::{AuthenticatorTrait, GenericSignature},
 };
 
-use mysten_metrics::monitored_scope;
+use crate::memory_budget::{lru_memory_usage, shrink_lru};
+use mysten_metrics::{monitored_scope, spawn_monitored_task};
 use sui_types::digests::SenderSignedDataDigest;
 use sui_types::messages::SenderSignedData;
 use tap::TapFallible;
@@ -39,18 +48,28 @@ const BATCH_TIMEOUT_MS: Duration = Duration::from_millis(10);
 // not heavily loaded).
 const MAX_BATCH_SIZE: usize = 8;
 
-type Sender = oneshot::Sender<SuiResult<VerifiedCertificate>>;
+// Maximum amount of time a submitted transaction waits for a batch to fill up. Lower than
+// BATCH_TIMEOUT_MS since it adds to the latency of every transaction of a lightly loaded
+// validator, while certificates are mostly verified in the background.
+const TX_BATCH_TIMEOUT_MS: Duration = Duration::from_millis(2);
+
+// Maximum number of submitted transactions verified together. Their signatures are verified in
+// parallel, so larger batches keep more cores busy under load.
+const MAX_TX_BATCH_SIZE: usize = 64;
+
+type CertBuffer = VerificationBuffer<CertifiedTransaction, SuiResult<VerifiedCertificate>>;
+type TxBuffer = VerificationBuffer<Transaction, SuiResult<VerifiedTransaction>>;
 
-struct CertBuffer {
-    certs: Vec<CertifiedTransaction>,
-    senders: Vec<Sender>,
+struct VerificationBuffer<T, R> {
+    items: Vec<T>,
+    senders: Vec<oneshot::Sender<R>>,
     id: u64,
 }
 
-impl CertBuffer {
+impl<T, R> VerificationBuffer<T, R> {
     fn new(capacity: usize) -> Self {
         Self {
-            certs: Vec::with_capacity(capacity),
+            items: Vec::with_capacity(capacity),
             senders: Vec::with_capacity(capacity),
             id: 0,
         }
@@ -59,37 +78,39 @@ impl CertBuffer {
     // Function consumes MutexGuard, therefore releasing the lock after mem swap is done
     fn take_and_replace(mut guard: MutexGuard<'_, Self>) -> Self {
         let this = &mut *guard;
-        let mut new = CertBuffer::new(this.capacity());
+        let mut new = Self::new(this.capacity());
         new.id = this.id + 1;
         std::mem::swap(&mut new, this);
         new
     }
 
     fn capacity(&self) -> usize {
-        debug_assert_eq!(self.certs.capacity(), self.senders.capacity());
-        self.certs.capacity()
+        debug_assert_eq!(self.items.capacity(), self.senders.capacity());
+        self.items.capacity()
     }
 
     fn len(&self) -> usize {
-        debug_assert_eq!(self.certs.len(), self.senders.len());
-        self.certs.len()
+        debug_assert_eq!(self.items.len(), self.senders.len());
+        self.items.len()
     }
 
-    fn push(&mut self, tx: Sender, cert: CertifiedTransaction) {
+    fn push(&mut self, tx: oneshot::Sender<R>, item: T) {
         self.senders.push(tx);
-        self.certs.push(cert);
+        self.items.push(item);
     }
 }
 
 /// Verifies signatures in ways that faster than verifying each signature individually.
 /// - BLS signatures - caching and batch verification.
-/// - User signed data - caching.
+/// - User signed data - caching, and parallel verification of concurrently submitted
+///   transactions.
 pub struct SignatureVerifier {
     committee: Arc<Committee>,
     certificate_cache: VerifiedDigestCache<CertificateDigest>,
     signed_data_cache: VerifiedDigestCache<SenderSignedDataDigest>,
 
     queue: Mutex<CertBuffer>,
+    tx_queue: Mutex<TxBuffer>,
     pub metrics: Arc<VerifiedDigestCacheMetrics>,
 }
 
@@ -110,6 +131,7 @@ impl SignatureVerifier {
                 metrics.signed_data_cache_evictions.clone(),
             ),
             queue: Mutex::new(CertBuffer::new(batch_size)),
+            tx_queue: Mutex::new(TxBuffer::new(MAX_TX_BATCH_SIZE)),
             metrics,
         }
     }
@@ -118,6 +140,17 @@ impl SignatureVerifier {
         Self::new_with_batch_size(committee, MAX_BATCH_SIZE, metrics)
     }
 
+    /// Estimated memory used by the caches of verified signatures.
+    pub fn cache_memory_usage(&self) -> usize {
+        self.certificate_cache.memory_usage() + self.signed_data_cache.memory_usage()
+    }
+
+    /// Evicts verified signatures from the caches until about `bytes` are freed.
+    pub fn shrink_caches(&self, bytes: usize) -> usize {
+        let freed = self.certificate_cache.shrink(bytes);
+        freed + self.signed_data_cache.shrink(bytes.saturating_sub(freed))
+    }
+
     /// Verifies all certs, returns Ok only if all are valid.
     pub fn verify_certs_and_checkpoints(
         &self,
@@ -173,6 +206,29 @@ impl SignatureVerifier {
         &self,
         cert: CertifiedTransaction,
     ) -> SuiResult<VerifiedCertificate> {
+        Self::verify_in_batch(
+            &self.queue,
+            BATCH_TIMEOUT_MS,
+            &self.metrics.cert_batches,
+            cert,
+            |buffer| self.process_queue(buffer),
+        )
+        .await
+    }
+
+    /// Adds `item` to the batch in `queue`, and waits for the result of its verification. The
+    /// batch is verified by `process` once it is full, or once `batch_timeout` elapsed.
+    async fn verify_in_batch<T, R, F, Fut>(
+        queue: &Mutex<VerificationBuffer<T, R>>,
+        batch_timeout: Duration,
+        counters: &BatchCounters,
+        item: T,
+        process: F,
+    ) -> R
+    where
+        F: Fn(VerificationBuffer<T, R>) -> Fut,
+        Fut: Future<Output = ()>,
+    {
         // Cancellation safety: we use parking_lot locks, which cannot be held across awaits.
         // Therefore once the queue has been taken by a thread, it is guaranteed to process the
         // queue and send all results before the future can be cancelled by the caller.
@@ -180,10 +236,10 @@ impl SignatureVerifier {
         pin_mut!(rx);
 
         let prev_id_or_buffer = {
-            let mut queue = self.queue.lock();
-            queue.push(tx, cert);
+            let mut queue = queue.lock();
+            queue.push(tx, item);
             if queue.len() == queue.capacity() {
-                Either::Right(CertBuffer::take_and_replace(queue))
+                Either::Right(VerificationBuffer::take_and_replace(queue))
             } else {
                 Either::Left(queue.id)
             }
@@ -191,34 +247,34 @@ impl SignatureVerifier {
         let prev_id = match prev_id_or_buffer {
             Either::Left(prev_id) => prev_id,
             Either::Right(buffer) => {
-                self.metrics.full_batches.inc();
-                self.process_queue(buffer).await;
-                // unwrap ok - process_queue will have sent the result already
+                counters.full_batches.inc();
+                process(buffer).await;
+                // unwrap ok - process will have sent the result already
                 return rx.try_recv().unwrap();
             }
         };
 
-        if let Ok(res) = timeout(BATCH_TIMEOUT_MS, &mut rx).await {
+        if let Ok(res) = timeout(batch_timeout, &mut rx).await {
             // unwrap ok - tx cannot have been dropped without sending a result.
             return res.unwrap();
         }
-        self.metrics.timeouts.inc();
+        counters.timeouts.inc();
 
         let buffer = {
-            let queue = self.queue.lock();
+            let queue = queue.lock();
             // check if another thread took the queue while we were re-acquiring lock.
             if prev_id == queue.id {
                 debug_assert_ne!(queue.len(), queue.capacity());
-                Some(CertBuffer::take_and_replace(queue))
+                Some(VerificationBuffer::take_and_replace(queue))
             } else {
                 None
             }
         };
 
         if let Some(buffer) = buffer {
-            self.metrics.partial_batches.inc();
-            self.process_queue(buffer).await;
-            // unwrap ok - process_queue will have sent the result already
+            counters.partial_batches.inc();
+            process(buffer).await;
+            // unwrap ok - process will have sent the result already
             return rx.try_recv().unwrap();
         }
 
@@ -243,10 +299,10 @@ impl SignatureVerifier {
     ) {
         let _scope = monitored_scope("BatchCertificateVerifier::process_queue");
 
-        let results = batch_verify_certificates(&committee, &buffer.certs);
+        let results = batch_verify_certificates(&committee, &buffer.items);
         izip!(
             results.into_iter(),
-            buffer.certs.into_iter(),
+            buffer.items.into_iter(),
             buffer.senders.into_iter(),
         )
         .for_each(|(result, cert, tx)| {
@@ -268,6 +324,167 @@ impl SignatureVerifier {
         self.signed_data_cache
             .is_verified(signed_tx.full_message_digest(), || signed_tx.verify(None))
     }
+
+    /// Verifies the user signatures of a submitted transaction asynchronously, in a batch with
+    /// the transactions submitted concurrently.
+    pub async fn verify_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> SuiResult<VerifiedTransaction> {
+        if self
+            .signed_data_cache
+            .is_cached(&transaction.data().full_message_digest())
+        {
+            return Ok(VerifiedTransaction::new_from_verified(transaction));
+        }
+        Self::verify_in_batch(
+            &self.tx_queue,
+            TX_BATCH_TIMEOUT_MS,
+            &self.metrics.tx_batches,
+            transaction,
+            |buffer| self.process_tx_queue(buffer),
+        )
+        .await
+    }
+
+    async fn process_tx_queue(&self, buffer: TxBuffer) {
+        // The batch is verified, and its results sent, by a separate task so that they are sent
+        // even if this future is cancelled.
+        let verified = spawn_monitored_task!(Self::verify_tx_batch(self.metrics.clone(), buffer))
+            .await
+            .expect("Transaction verification task should not fail");
+        self.signed_data_cache.cache_digests(verified);
+    }
+
+    /// Verifies the signatures of the transactions of `buffer` on the blocking threads, split in
+    /// as many chunks as there are cores, and returns the digests of the valid transactions.
+    async fn verify_tx_batch(
+        metrics: Arc<VerifiedDigestCacheMetrics>,
+        buffer: TxBuffer,
+    ) -> Vec<SenderSignedDataDigest> {
+        let VerificationBuffer {
+            items: txs,
+            senders,
+            ..
+        } = buffer;
+        let digests: Vec<_> = txs
+            .iter()
+            .map(|tx| tx.data().full_message_digest())
+            .collect();
+
+        // Clients often submit the same transaction concurrently, e.g. when retrying, its
+        // signatures are only verified once.
+        let mut first_index = HashMap::new();
+        let mut unique = vec![];
+        for (i, digest) in digests.iter().enumerate() {
+            first_index.entry(*digest).or_insert_with(|| {
+                unique.push(i);
+                i
+            });
+        }
+
+        let parallelism = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let chunk_size = (unique.len() + parallelism - 1) / parallelism;
+        let txs = Arc::new(txs);
+        let tasks = unique.chunks(chunk_size.max(1)).map(|chunk| {
+            let chunk = chunk.to_vec();
+            let txs = txs.clone();
+            let metrics = metrics.clone();
+            Handle::current().spawn_blocking(move || {
+                let _scope = monitored_scope("BatchTransactionVerifier::verify_tx_batch");
+                chunk
+                    .into_iter()
+                    .map(|i| (i, verify_user_signatures(txs[i].data(), &metrics)))
+                    .collect::<Vec<_>>()
+            })
+        });
+        let mut results = HashMap::new();
+        for chunk_results in join_all(tasks).await {
+            results.extend(chunk_results.expect("Spawn blocking should not fail"));
+        }
+
+        let txs = match Arc::try_unwrap(txs) {
+            Ok(txs) => txs,
+            Err(_) => unreachable!("all verification tasks are done"),
+        };
+        let mut verified = vec![];
+        for (i, (tx, digest, sender)) in izip!(txs, digests, senders).enumerate() {
+            let result = results[&first_index[&digest]].clone();
+            if result.is_ok() {
+                metrics.total_verified_txs.inc();
+                if first_index[&digest] == i {
+                    verified.push(digest);
+                }
+            } else {
+                metrics.total_failed_txs.inc();
+            }
+            sender
+                .send(result.map(|()| VerifiedTransaction::new_from_verified(tx)))
+                .ok();
+        }
+        verified
+    }
+}
+
+fn verify_user_signatures(
+    signed_tx: &SenderSignedData,
+    metrics: &VerifiedDigestCacheMetrics,
+) -> SuiResult {
+    for (signer, signature) in signed_tx.signatures_to_verify()? {
+        let result = signature.verify_secure_generic(signed_tx.intent_message(), signer);
+        metrics
+            .user_signatures
+            .with_label_values(&[
+                signature_scheme(signature),
+                if result.is_ok() { "verified" } else { "failed" },
+            ])
+            .inc();
+        result?;
+    }
+    Ok(())
+}
+
+fn signature_scheme(signature: &GenericSignature) -> &'static str {
+    match signature {
+        GenericSignature::MultiSig(_) => "multisig",
+        GenericSignature::Signature(signature) => match signature.scheme() {
+            SignatureScheme::ED25519 => "ed25519",
+            SignatureScheme::Secp256k1 => "secp256k1",
+            SignatureScheme::Secp256r1 => "secp256r1",
+            _ => "other",
+        },
+    }
+}
+
+struct BatchCounters {
+    timeouts: IntCounter,
+    full_batches: IntCounter,
+    partial_batches: IntCounter,
+}
+
+impl BatchCounters {
+    fn new(prefix: &str, registry: &Registry) -> Self {
+        Self {
+            timeouts: register_int_counter_with_registry!(
+                format!("{prefix}_timeouts"),
+                "Number of times batch verifier times out and verifies a partial batch",
+                registry
+            )
+            .unwrap(),
+            full_batches: register_int_counter_with_registry!(
+                format!("{prefix}_full_batches"),
+                "Number of times batch verifier verifies a full batch",
+                registry
+            )
+            .unwrap(),
+            partial_batches: register_int_counter_with_registry!(
+                format!("{prefix}_partial_batches"),
+                "Number of times batch verifier verifies a partial batch",
+                registry
+            )
+            .unwrap(),
+        }
+    }
 }
 
 pub struct VerifiedDigestCacheMetrics {
@@ -275,11 +492,13 @@ pub struct VerifiedDigestCacheMetrics {
     pub certificate_signatures_cache_evictions: IntCounter,
     pub signed_data_cache_hits: IntCounter,
     pub signed_data_cache_evictions: IntCounter,
-    timeouts: IntCounter,
-    full_batches: IntCounter,
-    partial_batches: IntCounter,
+    cert_batches: BatchCounters,
     total_verified_certs: IntCounter,
     total_failed_certs: IntCounter,
+    tx_batches: BatchCounters,
+    total_verified_txs: IntCounter,
+    total_failed_txs: IntCounter,
+    user_signatures: IntCounterVec,
 }
 
 impl VerifiedDigestCacheMetrics {
@@ -309,33 +528,36 @@ impl VerifiedDigestCacheMetrics {
                 registry
             )
                 .unwrap(),
-            timeouts: register_int_counter_with_registry!(
-                "async_batch_verifier_timeouts",
-                "Number of times batch verifier times out and verifies a partial batch",
+            cert_batches: BatchCounters::new("async_batch_verifier", registry),
+            total_verified_certs: register_int_counter_with_registry!(
+                "async_batch_verifier_total_verified_certs",
+                "Total number of certs batch verifier has verified",
                 registry
             )
             .unwrap(),
-            full_batches: register_int_counter_with_registry!(
-                "async_batch_verifier_full_batches",
-                "Number of times batch verifier verifies a full batch",
+            total_failed_certs: register_int_counter_with_registry!(
+                "async_batch_verifier_total_failed_certs",
+                "Total number of certs batch verifier has rejected",
                 registry
             )
             .unwrap(),
-            partial_batches: register_int_counter_with_registry!(
-                "async_batch_verifier_partial_batches",
-                "Number of times batch verifier verifies a partial batch",
+            tx_batches: BatchCounters::new("tx_batch_verifier", registry),
+            total_verified_txs: register_int_counter_with_registry!(
+                "tx_batch_verifier_total_verified_txs",
+                "Total number of submitted transactions batch verifier has verified",
                 registry
             )
             .unwrap(),
-            total_verified_certs: register_int_counter_with_registry!(
-                "async_batch_verifier_total_verified_certs",
-                "Total number of certs batch verifier has verified",
+            total_failed_txs: register_int_counter_with_registry!(
+                "tx_batch_verifier_total_failed_txs",
+                "Total number of submitted transactions batch verifier has rejected",
                 registry
             )
             .unwrap(),
-            total_failed_certs: register_int_counter_with_registry!(
-                "async_batch_verifier_total_failed_certs",
-                "Total number of certs batch verifier has rejected",
+            user_signatures: register_int_counter_vec_with_registry!(
+                "tx_batch_verifier_user_signatures",
+                "Number of user signatures of submitted transactions verified, by signature scheme and result",
+                &["scheme", "result"],
                 registry
             )
             .unwrap(),
@@ -458,6 +680,14 @@ impl<D: Hash + Eq + Copy> VerifiedDigestCache<D> {
         });
     }
 
+    fn memory_usage(&self) -> usize {
+        lru_memory_usage(&self.inner.read())
+    }
+
+    fn shrink(&self, bytes: usize) -> usize {
+        shrink_lru(&mut self.inner.write(), bytes)
+    }
+
     pub fn is_verified<F>(&self, digest: D, verify_callback: F) -> SuiResult
     where
         F: FnOnce() -> SuiResult,
@@ -0,0 +1,212 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Records of the operations slower than their configured threshold: validator RPC calls,
+//! certificate executions and consensus commits. Each slow operation is logged with the
+//! `slow_ops` target and kept in a ring buffer, which the admin interface serves, so that tail
+//! latency incidents can be investigated after the fact.
+//!
+//! An operation is timed by the `SlowOpGuard` returned by `SlowOpLog::start`, from its creation to
+//! its drop, so that operations failing early are recorded too. The guard also splits the duration
+//! of the operation into the phases marked with `SlowOpGuard::phase`.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sui_config::node::SlowOpConfig;
+use tracing::warn;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlowOpKind {
+    Rpc,
+    Execution,
+    ConsensusCommit,
+}
+
+impl SlowOpKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "rpc" => Some(SlowOpKind::Rpc),
+            "execution" => Some(SlowOpKind::Execution),
+            "consensus_commit" => Some(SlowOpKind::ConsensusCommit),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SlowOp {
+    pub kind: SlowOpKind,
+    /// Name of the operation, e.g. the RPC method.
+    pub name: &'static str,
+    /// Digest of the transaction, or of the leader certificate of a consensus commit.
+    pub digest: Option<String>,
+    /// Unix timestamp of the end of the operation.
+    pub timestamp_ms: u64,
+    pub duration_ms: u64,
+    /// Duration of each phase of the operation that completed, in order.
+    pub breakdown: Vec<(&'static str, u64)>,
+}
+
+pub struct SlowOpLog {
+    config: SlowOpConfig,
+    entries: Mutex<VecDeque<SlowOp>>,
+}
+
+impl SlowOpLog {
+    pub fn new(config: SlowOpConfig) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(config.capacity)),
+            config,
+        }
+    }
+
+    fn threshold(&self, kind: SlowOpKind) -> Duration {
+        Duration::from_millis(match kind {
+            SlowOpKind::Rpc => self.config.rpc_threshold_ms,
+            SlowOpKind::Execution => self.config.execution_threshold_ms,
+            SlowOpKind::ConsensusCommit => self.config.consensus_commit_threshold_ms,
+        })
+    }
+
+    /// Starts timing an operation, which is recorded when the returned guard is dropped if it
+    /// took longer than the threshold of `kind`.
+    pub fn start(
+        self: &Arc<Self>,
+        kind: SlowOpKind,
+        name: &'static str,
+        digest: Option<String>,
+    ) -> SlowOpGuard {
+        let now = Instant::now();
+        SlowOpGuard {
+            log: self.clone(),
+            kind,
+            name,
+            digest,
+            start: now,
+            phase_start: now,
+            breakdown: vec![],
+        }
+    }
+
+    fn record(&self, op: SlowOp) {
+        warn!(
+            target: "slow_ops",
+            kind = ?op.kind,
+            name = op.name,
+            digest = ?op.digest,
+            duration_ms = op.duration_ms,
+            breakdown = ?op.breakdown,
+            "Slow operation"
+        );
+        if self.config.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock();
+        if entries.len() >= self.config.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(op);
+    }
+
+    /// The slow operations recorded, of `kind` if set, from the oldest to the most recent.
+    pub fn entries(&self, kind: Option<SlowOpKind>) -> Vec<SlowOp> {
+        self.entries
+            .lock()
+            .iter()
+            .filter(|op| kind.map_or(true, |kind| op.kind == kind))
+            .cloned()
+            .collect()
+    }
+}
+
+pub struct SlowOpGuard {
+    log: Arc<SlowOpLog>,
+    kind: SlowOpKind,
+    name: &'static str,
+    digest: Option<String>,
+    start: Instant,
+    phase_start: Instant,
+    breakdown: Vec<(&'static str, Duration)>,
+}
+
+impl SlowOpGuard {
+    /// Marks the end of the phase `name`, which started at the end of the previous phase.
+    pub fn phase(&mut self, name: &'static str) {
+        let now = Instant::now();
+        self.breakdown
+            .push((name, now.duration_since(self.phase_start)));
+        self.phase_start = now;
+    }
+}
+
+impl Drop for SlowOpGuard {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed();
+        if duration < self.log.threshold(self.kind) {
+            return;
+        }
+        self.log.record(SlowOp {
+            kind: self.kind,
+            name: self.name,
+            digest: self.digest.take(),
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            duration_ms: duration.as_millis() as u64,
+            breakdown: self
+                .breakdown
+                .iter()
+                .map(|(phase, duration)| (*phase, duration.as_millis() as u64))
+                .collect(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slow_op_log(capacity: usize) -> Arc<SlowOpLog> {
+        Arc::new(SlowOpLog::new(SlowOpConfig {
+            rpc_threshold_ms: 0,
+            execution_threshold_ms: 60_000,
+            consensus_commit_threshold_ms: 0,
+            capacity,
+        }))
+    }
+
+    #[test]
+    fn test_slow_ops_over_threshold() {
+        let log = slow_op_log(10);
+        {
+            let mut op = log.start(SlowOpKind::Rpc, "handle_certificate", Some("tx".into()));
+            op.phase("verify_cert");
+            op.phase("execute");
+        }
+        // Below the execution threshold.
+        drop(log.start(SlowOpKind::Execution, "certificate", None));
+
+        let entries = log.entries(None);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "handle_certificate");
+        assert_eq!(entries[0].digest.as_deref(), Some("tx"));
+        let phases: Vec<_> = entries[0].breakdown.iter().map(|(p, _)| *p).collect();
+        assert_eq!(phases, vec!["verify_cert", "execute"]);
+        assert!(log.entries(Some(SlowOpKind::Execution)).is_empty());
+    }
+
+    #[test]
+    fn test_slow_ops_ring_buffer() {
+        let log = slow_op_log(2);
+        for name in ["first", "second", "third"] {
+            drop(log.start(SlowOpKind::ConsensusCommit, name, None));
+        }
+        let names: Vec<_> = log.entries(None).iter().map(|op| op.name).collect();
+        assert_eq!(names, vec!["second", "third"]);
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::sync::Arc;
 
-use sui_types::base_types::TransactionDigest;
+use sui_types::base_types::{ObjectID, TransactionDigest, VersionNumber};
 use sui_types::committee::Committee;
 use sui_types::committee::EpochId;
 use sui_types::digests::{TransactionEffectsDigest, TransactionEventsDigest};
@@ -16,8 +16,10 @@ use sui_types::messages_checkpoint::EndOfEpochData;
 use sui_types::messages_checkpoint::FullCheckpointContents;
 use sui_types::messages_checkpoint::VerifiedCheckpoint;
 use sui_types::messages_checkpoint::VerifiedCheckpointContents;
+use sui_types::object::Object;
 use sui_types::storage::ReadStore;
 use sui_types::storage::WriteStore;
+use typed_store::rocks::TypedStoreError;
 use typed_store::Map;
 
 use crate::authority::AuthorityStore;
@@ -116,6 +118,16 @@ impl ReadStore for RocksDbStore {
     ) -> Result<Option<TransactionEvents>, Self::Error> {
         self.authority_store.get_events(digest)
     }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, Self::Error> {
+        self.authority_store
+            .get_object_by_key(object_id, version)
+            .map_err(|e| TypedStoreError::RocksDBError(e.to_string()))
+    }
 }
 
 impl WriteStore for RocksDbStore {
@@ -77,6 +77,10 @@ where
         ReceiverStream::new(rx)
     }
 
+    pub async fn has_subscribers(&self) -> bool {
+        !self.subscribers.read().await.is_empty()
+    }
+
     pub async fn send(&self, data: T) -> Result<(), SuiError> {
         self.streamer_queue
             .send(data)
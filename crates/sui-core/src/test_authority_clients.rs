@@ -18,9 +18,9 @@ use sui_types::{
     crypto::AuthorityKeyPair,
     error::SuiError,
     messages::{
-        CertifiedTransaction, HandleTransactionResponse, ObjectInfoRequest, ObjectInfoResponse,
-        SystemStateRequest, Transaction, TransactionEffectsAPI, TransactionInfoRequest,
-        TransactionInfoResponse,
+        CertifiedTransaction, HandleCertificateBundleRequest, HandleCertificateBundleResponse,
+        HandleTransactionResponse, ObjectInfoRequest, ObjectInfoResponse, SystemStateRequest,
+        Transaction, TransactionEffectsAPI, TransactionInfoRequest, TransactionInfoResponse,
     },
     messages_checkpoint::{CheckpointRequest, CheckpointResponse},
 };
@@ -78,6 +78,18 @@ impl AuthorityAPI for LocalAuthorityClient {
             .unwrap()
     }
 
+    /// Consensus is not involved locally, so the certificates are simply executed in order.
+    async fn handle_certificate_bundle(
+        &self,
+        request: HandleCertificateBundleRequest,
+    ) -> Result<HandleCertificateBundleResponse, SuiError> {
+        let mut responses = Vec::with_capacity(request.certificates.len());
+        for certificate in request.certificates {
+            responses.push(AuthorityAPI::handle_certificate(self, certificate).await?);
+        }
+        Ok(HandleCertificateBundleResponse { responses })
+    }
+
     async fn handle_object_info_request(
         &self,
         request: ObjectInfoRequest,
@@ -209,6 +221,13 @@ impl AuthorityAPI for MockAuthorityApi {
         unimplemented!()
     }
 
+    async fn handle_certificate_bundle(
+        &self,
+        _request: HandleCertificateBundleRequest,
+    ) -> Result<HandleCertificateBundleResponse, SuiError> {
+        unimplemented!()
+    }
+
     /// Handle Object information requests for this account.
     async fn handle_object_info_request(
         &self,
@@ -274,6 +293,13 @@ impl AuthorityAPI for HandleTransactionTestAuthorityClient {
         unimplemented!()
     }
 
+    async fn handle_certificate_bundle(
+        &self,
+        _request: HandleCertificateBundleRequest,
+    ) -> Result<HandleCertificateBundleResponse, SuiError> {
+        unimplemented!()
+    }
+
     async fn handle_object_info_request(
         &self,
         _request: ObjectInfoRequest,
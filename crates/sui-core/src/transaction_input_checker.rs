@@ -3,8 +3,9 @@
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::authority::AuthorityStore;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use sui_adapter::adapter::run_metered_move_bytecode_verifier;
+use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_protocol_config::ProtocolConfig;
 use sui_types::base_types::ObjectRef;
 use sui_types::error::{UserInputError, UserInputResult};
@@ -13,7 +14,7 @@ use sui_types::messages::{
     TransactionKind, VerifiedExecutableTransaction, VersionedProtocolMessage,
 };
 use sui_types::{
-    base_types::{SequenceNumber, SuiAddress},
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
     error::SuiResult,
     fp_ensure,
     gas::{self, SuiGasStatus},
@@ -84,6 +85,42 @@ pub async fn check_transaction_input_with_given_gas(
     Ok((gas_status, input_objects))
 }
 
+/// Rejects transactions matching this node's deny list. This is local policy, so it must only be
+/// applied to transactions before they are signed or submitted, and never to certificates.
+pub fn check_transaction_deny_list(
+    deny_config: &TransactionDenyConfig,
+    transaction: &TransactionData,
+) -> UserInputResult {
+    if deny_config.is_empty() {
+        return Ok(());
+    }
+    for address in [transaction.sender(), transaction.gas_owner()] {
+        fp_ensure!(
+            !deny_config.is_address_denied(&address),
+            UserInputError::TransactionDenied {
+                error: format!("address {address} is denied"),
+            }
+        );
+    }
+    for input in transaction.input_objects()? {
+        match input {
+            InputObjectKind::MovePackage(package_id) => fp_ensure!(
+                !deny_config.is_package_denied(&package_id),
+                UserInputError::TransactionDenied {
+                    error: format!("package {package_id} is denied"),
+                }
+            ),
+            _ => fp_ensure!(
+                !deny_config.is_object_denied(&input.object_id()),
+                UserInputError::TransactionDenied {
+                    error: format!("object {} is denied", input.object_id()),
+                }
+            ),
+        }
+    }
+    Ok(())
+}
+
 /// WARNING! This should only be used for the dev-inspect transaction. This transaction type
 /// bypasses many of the normal object checks
 pub(crate) async fn check_dev_inspect_input(
@@ -91,6 +128,7 @@ pub(crate) async fn check_dev_inspect_input(
     config: &ProtocolConfig,
     kind: &TransactionKind,
     gas_object: Object,
+    overrides: &BTreeMap<ObjectID, Object>,
 ) -> Result<(ObjectRef, InputObjects), anyhow::Error> {
     let gas_object_ref = gas_object.compute_object_reference();
     kind.validity_check(config)?;
@@ -103,7 +141,34 @@ pub(crate) async fn check_dev_inspect_input(
         }
     }
     let mut input_objects = kind.input_objects()?;
-    let mut objects = store.check_input_objects(&input_objects, config)?;
+    // Overridden inputs are not read from the store, owned ones must be referred to with their
+    // overridden version.
+    let stored_input_objects: Vec<_> = input_objects
+        .iter()
+        .filter(|kind| !overrides.contains_key(&kind.object_id()))
+        .cloned()
+        .collect();
+    let mut stored_objects = store
+        .check_input_objects(&stored_input_objects, config)?
+        .into_iter();
+    let mut objects = Vec::with_capacity(input_objects.len());
+    for kind in &input_objects {
+        let Some(object) = overrides.get(&kind.object_id()) else {
+            objects.push(stored_objects.next().expect("One object is read per input"));
+            continue;
+        };
+        if let InputObjectKind::ImmOrOwnedMoveObject(object_ref) = kind {
+            fp_ensure!(
+                object_ref.1 == object.version(),
+                UserInputError::ObjectVersionUnavailableForConsumption {
+                    provided_obj_ref: *object_ref,
+                    current_version: object.version(),
+                }
+                .into()
+            );
+        }
+        objects.push(object.clone());
+    }
     let mut used_objects: HashSet<SuiAddress> = HashSet::new();
     for object in &objects {
         if !object.is_immutable() {
@@ -118,7 +183,19 @@ pub(crate) async fn check_dev_inspect_input(
     }
     input_objects.push(InputObjectKind::ImmOrOwnedMoveObject(gas_object_ref));
     objects.push(gas_object);
+    let input_ids: HashSet<_> = input_objects.iter().map(|kind| kind.object_id()).collect();
     let input_objects = InputObjects::new(input_objects.into_iter().zip(objects).collect());
+
+    // Objects read during execution, e.g. dynamic fields, are written at the version of the
+    // transaction, which must be higher than theirs.
+    let lamport_timestamp = input_objects.lamport_timestamp();
+    for (id, object) in overrides {
+        if object.version() >= lamport_timestamp && !input_ids.contains(id) {
+            anyhow::bail!(
+                "Overridden object {id} is not an input, its version must be lower than the versions of the inputs"
+            );
+        }
+    }
     Ok((gas_object_ref, input_objects))
 }
 
@@ -19,8 +19,10 @@ use tracing::{debug, error, warn};
 
 use crate::authority::{
     authority_per_epoch_store::AuthorityPerEpochStore, authority_store::InputKey,
+    shared_object_dependency_graph::SharedObjectDependencyGraph,
 };
 use crate::authority::{AuthorityMetrics, AuthorityStore};
+use crate::memory_budget::{MemoryConsumer, ESTIMATED_CERTIFICATE_SIZE};
 
 /// TransactionManager is responsible for managing object dependencies of pending transactions,
 /// and publishing a stream of certified transactions (certificates) ready to execute.
@@ -31,7 +33,7 @@ use crate::authority::{AuthorityMetrics, AuthorityStore};
 /// storage, committed objects are notified back to TransactionManager.
 pub struct TransactionManager {
     authority_store: Arc<AuthorityStore>,
-    tx_ready_certificates: UnboundedSender<VerifiedExecutableTransaction>,
+    tx_ready_certificates: UnboundedSender<ReadyCertificate>,
     metrics: Arc<AuthorityMetrics>,
     inner: RwLock<Inner>,
 }
@@ -39,6 +41,16 @@ pub struct TransactionManager {
 struct PendingCertificate {
     certificate: VerifiedExecutableTransaction,
     missing: BTreeSet<InputKey>,
+    priority: u64,
+}
+
+/// A certificate with all of its input objects available, sent to the execution driver.
+pub struct ReadyCertificate {
+    pub certificate: VerifiedExecutableTransaction,
+    /// Length of the longest chain of transactions on shared objects, enqueued together with this
+    /// certificate, that cannot start before it finishes. Ready certificates with a higher
+    /// priority are executed first when all execution workers are busy.
+    pub priority: u64,
 }
 
 #[derive(Default)]
@@ -82,7 +94,7 @@ impl TransactionManager {
     pub(crate) fn new(
         authority_store: Arc<AuthorityStore>,
         epoch_store: &AuthorityPerEpochStore,
-        tx_ready_certificates: UnboundedSender<VerifiedExecutableTransaction>,
+        tx_ready_certificates: UnboundedSender<ReadyCertificate>,
         metrics: Arc<AuthorityMetrics>,
     ) -> TransactionManager {
         let transaction_manager = TransactionManager {
@@ -121,6 +133,7 @@ impl TransactionManager {
     ) -> SuiResult<()> {
         // First, determine missing input objects without lock.
         let mut pending = Vec::new();
+        let mut shared_inputs = Vec::new();
         for cert in certs {
             let digest = *cert.digest();
             // skip already executed txes
@@ -142,6 +155,18 @@ impl TransactionManager {
             if input_object_kinds.len() != input_object_keys.len() {
                 error!("Duplicated input objects: {:?}", input_object_kinds);
             }
+            let input_versions: HashMap<_, _> = input_object_keys
+                .iter()
+                .filter_map(|InputKey(id, version)| version.map(|version| (*id, version)))
+                .collect();
+            shared_inputs.push(
+                cert.shared_input_objects()
+                    .filter_map(|object| {
+                        let version = input_versions.get(&object.id)?;
+                        Some((object.id, *version, object.mutable))
+                    })
+                    .collect::<Vec<_>>(),
+            );
             pending.push(PendingCertificate {
                 certificate: cert,
                 missing: input_object_keys
@@ -153,9 +178,24 @@ impl TransactionManager {
                             .expect("Checking object existence cannot fail!")
                     })
                     .collect(),
+                priority: 0,
             });
         }
 
+        // Rank the certificates by the chains of shared object transactions they unblock.
+        let graph = SharedObjectDependencyGraph::new(&shared_inputs);
+        for (index, pending_cert) in pending.iter_mut().enumerate() {
+            pending_cert.priority = graph.priority(index);
+        }
+        if shared_inputs.iter().any(|inputs| !inputs.is_empty()) {
+            self.metrics
+                .transaction_manager_shared_object_critical_path
+                .observe(graph.critical_path_len() as f64);
+            self.metrics
+                .transaction_manager_shared_object_parallelism
+                .observe(graph.parallelism());
+        }
+
         // After this point, the function cannot return early and must run to the end. Otherwise,
         // it can lead to data inconsistencies and potentially some transactions will never get
         // executed.
@@ -219,7 +259,7 @@ impl TransactionManager {
                 // Record as an executing certificate.
                 assert!(inner.executing_certificates.insert(digest));
                 // Send to execution driver for execution.
-                self.certificate_ready(pending_cert.certificate);
+                self.certificate_ready(pending_cert.certificate, pending_cert.priority);
                 continue;
             }
 
@@ -333,7 +373,7 @@ impl TransactionManager {
                     let pending_cert = inner.pending_certificates.remove(&digest).unwrap();
                     assert!(inner.executing_certificates.insert(digest));
                     ready_digests.push(digest);
-                    self.certificate_ready(pending_cert.certificate);
+                    self.certificate_ready(pending_cert.certificate, pending_cert.priority);
                 } else {
                     debug!(tx_digest = ?digest, missing = ?pending_cert.missing, "Certificate waiting on missing inputs");
                 }
@@ -373,9 +413,12 @@ impl TransactionManager {
     }
 
     /// Sends the ready certificate for execution.
-    fn certificate_ready(&self, certificate: VerifiedExecutableTransaction) {
+    fn certificate_ready(&self, certificate: VerifiedExecutableTransaction, priority: u64) {
         self.metrics.transaction_manager_num_ready.inc();
-        let _ = self.tx_ready_certificates.send(certificate);
+        let _ = self.tx_ready_certificates.send(ReadyCertificate {
+            certificate,
+            priority,
+        });
         self.metrics.execution_driver_dispatch_queue.inc();
     }
 
@@ -414,3 +457,10 @@ impl TransactionManager {
         *inner = Inner::new(new_epoch);
     }
 }
+
+/// Certificates cannot be dropped from the transaction manager, so it only reports its usage.
+impl MemoryConsumer for TransactionManager {
+    fn memory_usage(&self) -> usize {
+        self.execution_queue_len() * ESTIMATED_CERTIFICATE_SIZE
+    }
+}
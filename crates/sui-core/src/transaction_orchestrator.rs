@@ -23,12 +23,13 @@ use prometheus::{
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use sui_config::node::QuorumDriverConfig;
 use sui_storage::write_path_pending_tx_log::WritePathPendingTransactionLog;
 use sui_types::base_types::TransactionDigest;
 use sui_types::error::{SuiError, SuiResult};
 use sui_types::messages::{
     ExecuteTransactionRequest, ExecuteTransactionRequestType, ExecuteTransactionResponse,
-    FinalizedEffects, QuorumDriverResponse, TransactionEffectsAPI,
+    FinalizedEffects, QuorumDriverResponse, Transaction, TransactionEffectsAPI,
     VerifiedCertifiedTransactionEffects, VerifiedExecutableTransaction,
 };
 use sui_types::quorum_driver_types::{
@@ -64,6 +65,7 @@ impl TransactiondOrchestrator<NetworkAuthorityClient> {
         reconfig_channel: Receiver<SuiSystemState>,
         parent_path: &Path,
         prometheus_registry: &Registry,
+        quorum_driver_config: QuorumDriverConfig,
     ) -> anyhow::Result<Self> {
         let safe_client_metrics_base = SafeClientMetricsBase::new(prometheus_registry);
         let auth_agg_metrics = AuthAggMetrics::new(prometheus_registry);
@@ -87,6 +89,7 @@ impl TransactiondOrchestrator<NetworkAuthorityClient> {
             parent_path,
             prometheus_registry,
             observer,
+            quorum_driver_config,
         )
         .await)
     }
@@ -103,6 +106,7 @@ where
         parent_path: &Path,
         prometheus_registry: &Registry,
         reconfig_observer: OnsiteReconfigObserver,
+        quorum_driver_config: QuorumDriverConfig,
     ) -> Self {
         let notifier = Arc::new(NotifyRead::new());
         let quorum_driver_handler = Arc::new(
@@ -112,6 +116,7 @@ where
             )
             .with_notifier(notifier.clone())
             .with_reconfig_observer(Arc::new(reconfig_observer))
+            .with_config(quorum_driver_config)
             .start(),
         );
 
@@ -183,16 +188,29 @@ where
             .transaction
             .verify()
             .map_err(QuorumDriverError::InvalidUserSignature)?;
+        self.validator_state
+            .check_transaction_deny_list(&transaction.data().intent_message().value)
+            .map_err(QuorumDriverError::TransactionDenied)?;
         let (_in_flight_metrics_guards, good_response_metrics) = self.update_metrics(&transaction);
         let tx_digest = *transaction.digest();
         debug!(?tx_digest, "TO Received transaction execution request.");
 
         let _timer_guards = self.get_timer_guards(&transaction);
 
-        let ticket = self.submit(transaction.clone()).await.map_err(|e| {
-            warn!(?tx_digest, "QuorumDriverInternalError: {e:?}");
-            QuorumDriverError::QuorumDriverInternalError(e)
-        })?;
+        // The quorum driver tasks of the transaction are children of its lifecycle span.
+        let lifecycle = self
+            .validator_state
+            .load_epoch_store_one_call_per_task()
+            .transaction_lifecycle()
+            .start(&tx_digest);
+        let ticket = self
+            .submit(transaction.clone())
+            .instrument(lifecycle)
+            .await
+            .map_err(|e| {
+                warn!(?tx_digest, "QuorumDriverInternalError: {e:?}");
+                QuorumDriverError::QuorumDriverInternalError(e)
+            })?;
 
         let wait_for_local_execution = matches!(
             request.request_type,
@@ -246,6 +264,84 @@ where
         }
     }
 
+    /// Executes a soft bundle: independently signed transactions that consensus sequences
+    /// contiguously and in the given order. The bundle is checked against the soft bundle limits
+    /// of the current protocol version before any transaction is certified.
+    #[instrument(name = "tx_orchestrator_execute_transaction_bundle", level = "debug", skip_all,
+    fields(size = transactions.len()), err)]
+    pub async fn execute_transaction_bundle(
+        &self,
+        transactions: Vec<Transaction>,
+        request_type: ExecuteTransactionRequestType,
+    ) -> Result<Vec<ExecuteTransactionResponse>, QuorumDriverError> {
+        let epoch_store = self.validator_state.load_epoch_store_one_call_per_task();
+        let protocol_config = epoch_store.protocol_config();
+        protocol_config
+            .check_soft_bundle_supported()
+            .map_err(|err| QuorumDriverError::InvalidSoftBundle(err.0))?;
+        let max_soft_bundle_size = protocol_config.max_soft_bundle_size();
+        if transactions.is_empty() || transactions.len() as u64 > max_soft_bundle_size {
+            return Err(QuorumDriverError::InvalidSoftBundle(format!(
+                "size ({}) must be between 1 and {max_soft_bundle_size}",
+                transactions.len()
+            )));
+        }
+
+        let mut verified = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            let transaction = transaction
+                .verify()
+                .map_err(QuorumDriverError::InvalidUserSignature)?;
+            self.validator_state
+                .check_transaction_deny_list(&transaction.data().intent_message().value)
+                .map_err(QuorumDriverError::TransactionDenied)?;
+            verified.push(transaction);
+        }
+
+        let responses = timeout(
+            WAIT_FOR_FINALITY_TIMEOUT,
+            self.quorum_driver_handler
+                .execute_transaction_bundle(verified.clone()),
+        )
+        .await
+        .map_err(|_| {
+            debug!("Timeout waiting for soft bundle finality.");
+            QuorumDriverError::TimeoutBeforeFinality
+        })??;
+
+        let wait_for_local_execution = matches!(
+            request_type,
+            ExecuteTransactionRequestType::WaitForLocalExecution
+        );
+        let mut results = Vec::with_capacity(responses.len());
+        for (transaction, response) in verified.into_iter().zip(responses) {
+            let QuorumDriverResponse {
+                effects_cert,
+                events,
+            } = response;
+            // Transactions are executed locally in bundle order, as later ones may depend on the
+            // outputs of earlier ones.
+            let executed_locally = wait_for_local_execution
+                && Self::execute_finalized_tx_locally_with_timeout(
+                    &self.validator_state,
+                    &VerifiedExecutableTransaction::new_from_quorum_execution(
+                        transaction,
+                        effects_cert.executed_epoch(),
+                    ),
+                    &effects_cert,
+                    &self.metrics,
+                )
+                .await
+                .is_ok();
+            results.push(ExecuteTransactionResponse::EffectsCert(Box::new((
+                FinalizedEffects::new_from_effects_cert(effects_cert.into()),
+                events,
+                executed_locally,
+            ))));
+        }
+        Ok(results)
+    }
+
     /// Submits the transaction for execution queue, returns a Future to be awaited
     async fn submit(
         &self,
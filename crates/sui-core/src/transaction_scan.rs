@@ -0,0 +1,500 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Queries of transactions answered by walking the contents of the executed checkpoints instead of
+//! reading the indexes of the node.
+//!
+//! [`query_checkpointed_transactions`] pages through the whole available history in checkpoint
+//! order, which is the same on every node, unlike the order in which a node indexed the
+//! transactions it executed.
+//!
+//! [`scan_transactions`] serves the transactions sent by or to an address on nodes which do not
+//! maintain indexes. A scan only covers a bounded range of checkpoints, which ends at the highest
+//! executed checkpoint and starts no earlier than the lowest checkpoint whose contents and effects
+//! have not been pruned. Matching transactions outside of that range are not returned.
+
+use crate::authority::checkpoint_history_pruner::PrunedHistory;
+use crate::authority::AuthorityState;
+use anyhow::anyhow;
+use std::ops::RangeInclusive;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::messages::{TransactionDataAPI, TransactionEffects, TransactionEffectsAPI};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::query::TransactionFilter;
+
+/// Position of a transaction in the checkpointed history: the checkpoint that includes it and its
+/// index in the contents of the checkpoint.
+pub type TransactionPosition = (CheckpointSequenceNumber, u64);
+
+/// A page of the transactions matching a query, in checkpoint order.
+#[derive(Clone, Debug)]
+pub struct TransactionPage {
+    pub digests: Vec<TransactionDigest>,
+    /// Position to resume the query from. It is past the last transaction of the page when the
+    /// walk stopped on transactions that did not match.
+    pub next: Option<TransactionPosition>,
+    pub has_next_page: bool,
+}
+
+/// Returns up to `limit` transactions matching `filter`, or all transactions if unset, from the
+/// position right after `after` if set. At most `max_walked` transactions are read for a page, so
+/// a page may hold fewer than `limit` transactions and still have a next page.
+pub fn query_checkpointed_transactions(
+    state: &AuthorityState,
+    filter: Option<&TransactionFilter>,
+    after: Option<TransactionPosition>,
+    limit: usize,
+    descending: bool,
+    max_walked: usize,
+) -> anyhow::Result<TransactionPage> {
+    // Walk 1 extra matching transaction to know if there is a next page
+    let mut walk = walk_transactions(state, filter, after, limit + 1, descending, max_walked)?;
+    let has_next_page = walk.transactions.len() > limit || !walk.complete;
+    if walk.transactions.len() > limit {
+        walk.transactions.truncate(limit);
+        walk.last_walked = walk.transactions.last().map(|(position, _)| *position);
+    }
+    Ok(TransactionPage {
+        digests: walk
+            .transactions
+            .into_iter()
+            .map(|(_, digest)| digest)
+            .collect(),
+        next: walk.last_walked.or(after),
+        has_next_page,
+    })
+}
+
+struct TransactionWalk {
+    /// Positions and digests of the matching transactions, in walk order.
+    transactions: Vec<(TransactionPosition, TransactionDigest)>,
+    /// Position of the last transaction walked, matching or not.
+    last_walked: Option<TransactionPosition>,
+    /// Whether the walk reached the end of the available history.
+    complete: bool,
+}
+
+fn walk_transactions(
+    state: &AuthorityState,
+    filter: Option<&TransactionFilter>,
+    after: Option<TransactionPosition>,
+    limit: usize,
+    descending: bool,
+    max_walked: usize,
+) -> anyhow::Result<TransactionWalk> {
+    let mut walk = TransactionWalk {
+        transactions: vec![],
+        last_walked: None,
+        complete: true,
+    };
+    let Some(last) = state
+        .get_checkpoint_store()
+        .get_highest_executed_checkpoint_seq_number()? else {
+        return Ok(walk);
+    };
+    let mut first = 0;
+    for kind in [
+        PrunedHistory::CheckpointContents,
+        PrunedHistory::Transactions,
+        PrunedHistory::Effects,
+    ] {
+        first = first.max(state.get_lowest_available_history_checkpoint(kind)?);
+    }
+    let sequence_numbers: Box<dyn Iterator<Item = CheckpointSequenceNumber>> =
+        match (after, descending) {
+            (None, false) => Box::new(first..=last),
+            (None, true) => Box::new((first..=last).rev()),
+            (Some((checkpoint, _)), false) => Box::new(checkpoint.max(first)..=last),
+            (Some((checkpoint, _)), true) => Box::new((first..=checkpoint.min(last)).rev()),
+        };
+
+    let mut walked = 0;
+    for sequence_number in sequence_numbers {
+        if walked == max_walked {
+            walk.complete = false;
+            break;
+        }
+        let contents = state.get_checkpoint_contents_by_sequence_number(sequence_number)?;
+        let mut positions: Vec<_> = contents
+            .iter()
+            .enumerate()
+            .map(|(index, digests)| ((sequence_number, index as u64), digests.transaction))
+            .collect();
+        if descending {
+            positions.reverse();
+        }
+        positions.retain(|(position, _)| match after {
+            Some(after) if descending => *position < after,
+            Some(after) => *position > after,
+            None => true,
+        });
+        if positions.len() > max_walked - walked {
+            positions.truncate(max_walked - walked);
+            walk.complete = false;
+        }
+
+        let digests: Vec<_> = positions.iter().map(|(_, digest)| *digest).collect();
+        let matching = match filter {
+            Some(filter) => matching_transactions(state, filter, &digests)?,
+            None => vec![true; digests.len()],
+        };
+        for ((position, digest), matches) in positions.into_iter().zip(matching) {
+            walked += 1;
+            walk.last_walked = Some(position);
+            if matches {
+                walk.transactions.push((position, digest));
+                if walk.transactions.len() == limit {
+                    walk.complete = false;
+                    return Ok(walk);
+                }
+            }
+        }
+    }
+    Ok(walk)
+}
+
+/// The transactions matching a scan, and the checkpoints it covered.
+#[derive(Clone, Debug)]
+pub struct TransactionScan {
+    /// Digests of the matching transactions, in scan order.
+    pub digests: Vec<TransactionDigest>,
+    pub checkpoints: RangeInclusive<CheckpointSequenceNumber>,
+}
+
+/// Returns up to `limit` transactions matching `filter` in the `max_checkpoints` most recent
+/// checkpoints, starting after `cursor` if set. Only the `FromAddress` and `ToAddress` filters are
+/// supported.
+pub fn scan_transactions(
+    state: &AuthorityState,
+    filter: &TransactionFilter,
+    cursor: Option<TransactionDigest>,
+    limit: usize,
+    descending: bool,
+    max_checkpoints: u64,
+) -> anyhow::Result<TransactionScan> {
+    if !matches!(
+        filter,
+        TransactionFilter::FromAddress(_) | TransactionFilter::ToAddress(_)
+    ) {
+        return Err(anyhow!(
+            "Only transactions from or to an address can be scanned, got filter {filter:?}"
+        ));
+    }
+    let checkpoints = scanned_checkpoints(state, max_checkpoints)?;
+
+    let mut sequence_numbers: Box<dyn Iterator<Item = CheckpointSequenceNumber>> = if descending {
+        Box::new(checkpoints.clone().rev())
+    } else {
+        Box::new(checkpoints.clone())
+    };
+    // The scan resumes from the checkpoint of the cursor, right after the cursor.
+    let mut skip_through = None;
+    if let Some(cursor) = cursor {
+        let checkpoint = state
+            .get_transaction_checkpoint_sequence(&cursor)?
+            .map(|(_, checkpoint)| checkpoint)
+            .filter(|checkpoint| checkpoints.contains(checkpoint))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Cursor {cursor:?} is not in the scanned checkpoints {}..={}",
+                    checkpoints.start(),
+                    checkpoints.end()
+                )
+            })?;
+        sequence_numbers = if descending {
+            Box::new((*checkpoints.start()..=checkpoint).rev())
+        } else {
+            Box::new(checkpoint..=*checkpoints.end())
+        };
+        skip_through = Some(cursor);
+    }
+
+    let mut digests = vec![];
+    for sequence_number in sequence_numbers {
+        let contents = state.get_checkpoint_contents_by_sequence_number(sequence_number)?;
+        let mut transactions: Vec<_> = contents.iter().map(|digests| digests.transaction).collect();
+        if descending {
+            transactions.reverse();
+        }
+        if let Some(cursor) = skip_through.take() {
+            if let Some(position) = transactions.iter().position(|digest| *digest == cursor) {
+                transactions.drain(..=position);
+            }
+        }
+
+        let matching = matching_transactions(state, filter, &transactions)?;
+        digests.extend(
+            transactions
+                .into_iter()
+                .zip(matching)
+                .filter_map(|(digest, matches)| matches.then_some(digest)),
+        );
+        if digests.len() >= limit {
+            digests.truncate(limit);
+            break;
+        }
+    }
+
+    Ok(TransactionScan {
+        digests,
+        checkpoints,
+    })
+}
+
+/// The `max_checkpoints` most recent checkpoints whose contents and effects are available.
+fn scanned_checkpoints(
+    state: &AuthorityState,
+    max_checkpoints: u64,
+) -> anyhow::Result<RangeInclusive<CheckpointSequenceNumber>> {
+    let last = state.get_latest_checkpoint_sequence_number()?;
+    let mut first = last.saturating_sub(max_checkpoints.saturating_sub(1));
+    for kind in [PrunedHistory::CheckpointContents, PrunedHistory::Effects] {
+        if let Some(pruned) = state
+            .database
+            .perpetual_tables
+            .get_highest_pruned_history_checkpoint(kind)?
+        {
+            first = first.max(pruned + 1);
+        }
+    }
+    Ok(first.min(last)..=last)
+}
+
+/// Whether each of the transactions matches `filter`, the same way as the indexes of the node.
+fn matching_transactions(
+    state: &AuthorityState,
+    filter: &TransactionFilter,
+    digests: &[TransactionDigest],
+) -> anyhow::Result<Vec<bool>> {
+    Ok(match filter {
+        TransactionFilter::FromAddress(_)
+        | TransactionFilter::InputObject(_)
+        | TransactionFilter::MoveFunction { .. } => state
+            .database
+            .multi_get_transactions(digests)?
+            .into_iter()
+            .map(|transaction| {
+                transaction.map_or(false, |transaction| {
+                    let data = &transaction.data().intent_message().value;
+                    match filter {
+                        TransactionFilter::FromAddress(address) => data.sender() == *address,
+                        TransactionFilter::InputObject(object_id) => {
+                            data.input_objects().map_or(false, |inputs| {
+                                inputs.iter().any(|input| input.object_id() == *object_id)
+                            })
+                        }
+                        TransactionFilter::MoveFunction {
+                            package,
+                            module,
+                            function,
+                        } => data.move_calls().into_iter().any(|(p, m, f)| {
+                            p == package
+                                && module
+                                    .as_ref()
+                                    .map_or(true, |module| m.as_str() == module.as_str())
+                                && function
+                                    .as_ref()
+                                    .map_or(true, |function| f.as_str() == function.as_str())
+                        }),
+                        _ => unreachable!(),
+                    }
+                })
+            })
+            .collect(),
+        TransactionFilter::ChangedObject(_) | TransactionFilter::ToAddress(_) => state
+            .database
+            .multi_get_executed_effects(digests)?
+            .into_iter()
+            .map(|effects| {
+                effects.map_or(false, |effects| match filter {
+                    TransactionFilter::ChangedObject(object_id) => effects
+                        .all_changed_objects()
+                        .into_iter()
+                        .any(|(object_ref, _, _)| object_ref.0 == *object_id),
+                    TransactionFilter::ToAddress(address) => is_recipient(&effects, *address),
+                    _ => unreachable!(),
+                })
+            })
+            .collect(),
+    })
+}
+
+/// Whether the transaction with the given effects sent objects to `address`, i.e. left any object
+/// it changed owned by `address`. This matches the `ToAddress` index.
+fn is_recipient(effects: &TransactionEffects, address: SuiAddress) -> bool {
+    effects
+        .all_changed_objects()
+        .into_iter()
+        .any(|(_, owner, _)| owner.get_owner_address().ok() == Some(address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authority::authority_tests::{
+        init_state_with_committee, send_and_confirm_transaction,
+    };
+    use crate::test_utils::make_transfer_sui_transaction;
+    use sui_types::base_types::{dbg_addr, random_object_ref, ExecutionDigests, ObjectID};
+    use sui_types::crypto::{get_key_pair, AccountKeyPair, AuthorityKeyPair, KeypairTraits};
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages::TransactionEffectsV1;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary, SignedCheckpointSummary,
+        VerifiedCheckpoint,
+    };
+    use sui_types::object::{Object, Owner};
+
+    /// Certifies and executes a checkpoint with the given transactions, in the given order.
+    fn execute_checkpoint(
+        state: &AuthorityState,
+        key: &AuthorityKeyPair,
+        previous: Option<&VerifiedCheckpoint>,
+        transactions: &[ExecutionDigests],
+    ) -> VerifiedCheckpoint {
+        let committee = state.clone_committee_for_testing();
+        let contents = CheckpointContents::new_with_causally_ordered_transactions(
+            transactions.iter().copied(),
+        );
+        let summary = CheckpointSummary::new(
+            committee.epoch,
+            previous.map_or(0, |previous| previous.sequence_number + 1),
+            0,
+            &contents,
+            previous.map(|previous| *previous.digest()),
+            GasCostSummary::default(),
+            None,
+            0,
+        );
+        let sign_info =
+            SignedCheckpointSummary::sign(committee.epoch, &summary, key, key.public().into());
+        let checkpoint = VerifiedCheckpoint::new_unchecked(
+            CertifiedCheckpointSummary::new(summary, vec![sign_info], &committee).unwrap(),
+        );
+
+        let store = state.get_checkpoint_store();
+        store.insert_checkpoint_contents(contents).unwrap();
+        store
+            .insert_verified_checkpoint(checkpoint.clone())
+            .unwrap();
+        store
+            .update_highest_executed_checkpoint(&checkpoint)
+            .unwrap();
+        checkpoint
+    }
+
+    /// All the pages of a query, following the cursors until there is no next page.
+    fn query_pages(
+        state: &AuthorityState,
+        filter: Option<&TransactionFilter>,
+        limit: usize,
+        descending: bool,
+        max_walked: usize,
+    ) -> Vec<Vec<TransactionDigest>> {
+        let mut pages = vec![];
+        let mut after = None;
+        loop {
+            let page = query_checkpointed_transactions(
+                state, filter, after, limit, descending, max_walked,
+            )
+            .unwrap();
+            pages.push(page.digests);
+            if !page.has_next_page {
+                return pages;
+            }
+            assert!(pages.len() < 10, "query does not make progress");
+            after = page.next;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_transactions_in_checkpoint_order() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let network_config = sui_config::builder::ConfigBuilder::new(&dir).build();
+        let key = network_config.validator_configs[0]
+            .protocol_key_pair()
+            .copy();
+        let state = init_state_with_committee(&network_config.genesis, &key).await;
+
+        let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+        let (first_recipient, second_recipient) = (dbg_addr(1), dbg_addr(2));
+        let mut executed = vec![];
+        for recipient in [
+            first_recipient,
+            second_recipient,
+            first_recipient,
+            second_recipient,
+        ] {
+            let gas = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+            let gas_ref = gas.compute_object_reference();
+            state.insert_genesis_object(gas).await;
+            let transaction = make_transfer_sui_transaction(
+                gas_ref,
+                recipient,
+                Some(1),
+                sender,
+                &sender_key,
+                None,
+            );
+            let (certificate, effects) = send_and_confirm_transaction(&state, transaction)
+                .await
+                .unwrap();
+            executed.push(ExecutionDigests::new(
+                *certificate.digest(),
+                *effects.digest(),
+            ));
+        }
+
+        // The transactions are checkpointed in another order than they were executed locally.
+        let [t0, t1, t2, t3] = [0, 1, 2, 3].map(|i| executed[i].transaction);
+        let checkpoint = execute_checkpoint(&state, &key, None, &[executed[3], executed[1]]);
+        execute_checkpoint(&state, &key, Some(&checkpoint), &[executed[2], executed[0]]);
+
+        assert_eq!(
+            query_pages(&state, None, 1, false, 100),
+            vec![vec![t3], vec![t1], vec![t2], vec![t0]]
+        );
+        assert_eq!(
+            query_pages(&state, None, 3, true, 100),
+            vec![vec![t0, t2, t1], vec![t3]]
+        );
+
+        // Pages are filled after filtering, and the last one is known to be the last.
+        let filter = TransactionFilter::ToAddress(first_recipient);
+        assert_eq!(
+            query_pages(&state, Some(&filter), 1, false, 100),
+            vec![vec![t2], vec![t0]]
+        );
+        let filter = TransactionFilter::FromAddress(sender);
+        assert_eq!(
+            query_pages(&state, Some(&filter), 2, true, 100),
+            vec![vec![t0, t2], vec![t1, t3]]
+        );
+        let filter = TransactionFilter::ChangedObject(ObjectID::random());
+        assert_eq!(
+            query_pages(&state, Some(&filter), 10, false, 100),
+            vec![vec![]]
+        );
+
+        // A page stops after walking `max_walked` transactions, and the next one resumes after
+        // them even if none of them matched.
+        let filter = TransactionFilter::ToAddress(first_recipient);
+        assert_eq!(
+            query_pages(&state, Some(&filter), 10, false, 1),
+            vec![vec![], vec![], vec![t2], vec![t0]]
+        );
+    }
+
+    #[test]
+    fn test_is_recipient() {
+        let recipient = dbg_addr(1);
+        let effects = TransactionEffects::V1(TransactionEffectsV1 {
+            created: vec![(random_object_ref(), Owner::AddressOwner(recipient))],
+            mutated: vec![(random_object_ref(), Owner::Immutable)],
+            ..Default::default()
+        });
+
+        assert!(is_recipient(&effects, recipient));
+        assert!(!is_recipient(&effects, dbg_addr(2)));
+    }
+}
@@ -10,6 +10,7 @@ use std::task::{Context, Poll};
 use std::{convert::TryInto, env};
 
 use bcs;
+use fastcrypto::encoding::Base64;
 use fastcrypto::hash::MultisetHash;
 use futures::{stream::FuturesUnordered, StreamExt};
 use move_binary_format::access::ModuleAccess;
@@ -41,7 +42,8 @@ use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
 use sui_types::dynamic_field::DynamicFieldType;
 use sui_types::epoch_data::EpochData;
 use sui_types::error::UserInputError;
-use sui_types::gas_coin::GasCoin;
+use sui_types::execution_trace::ObjectChangeKind;
+use sui_types::gas_coin::{GasCoin, GAS};
 use sui_types::object::Data;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemState;
@@ -56,7 +58,7 @@ use sui_types::{
     messages::TransactionExpiration,
     messages::VerifiedTransaction,
     object::{Owner, GAS_VALUE_FOR_TESTING, OBJECT_START_VERSION},
-    SUI_SYSTEM_STATE_OBJECT_ID,
+    SUI_FRAMEWORK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID,
 };
 use sui_types::{SUI_CLOCK_OBJECT_ID, SUI_CLOCK_OBJECT_SHARED_VERSION};
 
@@ -72,6 +74,8 @@ use crate::{
 };
 
 use super::*;
+use typed_store::rocks::migration::{set_store_schema_version, store_schema_version};
+use typed_store::rocks::TypedStoreError;
 
 pub enum TestCallArg {
     Pure(Vec<u8>),
@@ -227,6 +231,7 @@ async fn test_dry_run_transaction() {
         .dry_exec_transaction(
             transaction.data().intent_message().value.clone(),
             transaction_digest,
+            false,
         )
         .await
         .unwrap();
@@ -258,7 +263,7 @@ async fn test_dry_run_transaction() {
         txn_data.gas_price(),
     );
     let response = fullnode
-        .dry_exec_transaction(txn_data, transaction_digest)
+        .dry_exec_transaction(txn_data, transaction_digest, false)
         .await
         .unwrap();
     let gas_usage_no_gas = response.effects.gas_cost_summary();
@@ -291,12 +296,104 @@ async fn test_dry_run_no_gas_big_transfer() {
         .dry_exec_transaction(
             signed.data().intent_message().value.clone(),
             *signed.digest(),
+            false,
         )
         .await
         .unwrap();
     assert_eq!(*dry_run_res.effects.status(), SuiExecutionStatus::Success);
 }
 
+#[tokio::test]
+async fn test_dry_run_trace() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let gas_object_id = ObjectID::random();
+    let (_, fullnode, _) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![(sender, gas_object_id)]).await;
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(recipient, Some(1_000));
+    let data = TransactionData::new_programmable_with_dummy_gas_price(
+        sender,
+        vec![],
+        builder.finish(),
+        SuiCostTable::new_for_testing().max_gas_budget,
+    );
+    let signed = to_sender_signed_transaction(data, &sender_key);
+
+    let response = fullnode
+        .dry_exec_transaction(
+            signed.data().intent_message().value.clone(),
+            *signed.digest(),
+            true,
+        )
+        .await
+        .unwrap();
+    assert_eq!(*response.effects.status(), SuiExecutionStatus::Success);
+    let trace = response.trace.unwrap();
+    let kinds: Vec<_> = trace.commands.iter().map(|c| c.kind.as_str()).collect();
+    assert_eq!(kinds, vec!["SplitCoins", "TransferObjects"]);
+    assert!(trace.commands.iter().all(|c| c.call.is_none()));
+    assert!(trace.object_read_gas > 0);
+    assert!(trace.storage_gas > 0);
+    // The new coin, and the gas coin it was split from
+    let created = response.effects.created()[0].reference.object_id;
+    assert!(trace
+        .object_changes
+        .iter()
+        .any(|change| change.object_id == created && change.kind == ObjectChangeKind::Created));
+    assert!(trace
+        .object_changes
+        .iter()
+        .any(|change| change.kind == ObjectChangeKind::Mutated));
+}
+
+#[tokio::test]
+async fn test_dry_run_gas_breakdown() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let gas_object_id = ObjectID::random();
+    let (_, fullnode, _) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![(sender, gas_object_id)]).await;
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.transfer_sui(recipient, Some(1_000));
+    let data = TransactionData::new_programmable_with_dummy_gas_price(
+        sender,
+        vec![],
+        builder.finish(),
+        SuiCostTable::new_for_testing().max_gas_budget,
+    );
+    let signed = to_sender_signed_transaction(data, &sender_key);
+
+    // The breakdown is returned even when the execution is not traced, without functions.
+    let response = fullnode
+        .dry_exec_transaction(
+            signed.data().intent_message().value.clone(),
+            *signed.digest(),
+            false,
+        )
+        .await
+        .unwrap();
+    assert!(response.trace.is_none());
+    let breakdown = response.gas_breakdown.unwrap();
+    let kinds: Vec<_> = breakdown.commands.iter().map(|c| c.kind.as_str()).collect();
+    assert_eq!(kinds, vec!["SplitCoins", "TransferObjects"]);
+    assert!(breakdown.commands.iter().all(|c| c.functions.is_empty()));
+    assert!(breakdown.object_read_cost > 0);
+
+    // Storage costs and rebates add up to the ones charged.
+    let gas_summary = response.effects.gas_cost_summary();
+    let storage_cost: u64 = breakdown.objects.iter().map(|o| o.storage_cost).sum();
+    let storage_rebate: u64 = breakdown.objects.iter().map(|o| o.storage_rebate).sum();
+    assert_eq!(storage_cost, gas_summary.storage_cost);
+    assert_eq!(storage_rebate, gas_summary.storage_rebate);
+    assert!(breakdown
+        .objects
+        .iter()
+        .any(|o| o.object_id == gas_object_id && o.kind == ObjectChangeKind::Mutated));
+}
+
 #[tokio::test]
 async fn test_dev_inspect_object_by_bytes() {
     let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -577,7 +674,7 @@ async fn test_dev_inspect_dynamic_field() {
     };
     let kind = TransactionKind::programmable(pt);
     let DevInspectResults { error, .. } = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), false, vec![])
         .await
         .unwrap();
     // produces an error
@@ -819,7 +916,7 @@ async fn test_dev_inspect_gas_coin_argument() {
     };
     let kind = TransactionKind::programmable(pt);
     let results = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), false, vec![])
         .await
         .unwrap()
         .results
@@ -881,12 +978,96 @@ async fn test_dev_inspect_uses_unbound_object() {
     let kind = TransactionKind::programmable(pt);
 
     let result = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), false, vec![])
         .await;
     let Err(err) = result else { panic!() };
     assert!(err.to_string().contains("ObjectNotFound"));
 }
 
+#[tokio::test]
+async fn test_dev_inspect_object_overrides() {
+    let (sender, _sender_key): (_, AccountKeyPair) = get_key_pair();
+    let coin_id = ObjectID::random();
+    let (_validator, fullnode, _object_basics) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![(sender, coin_id)]).await;
+    let coin = fullnode.get_object(&coin_id).await.unwrap().unwrap();
+    let digest = coin.compute_object_reference().2;
+    let version = SequenceNumber::from_u64(coin.version().value() + 1);
+
+    let coin_value = |version: SequenceNumber| {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let coin = builder
+            .obj(ObjectArg::ImmOrOwnedObject((coin_id, version, digest)))
+            .unwrap();
+        builder.programmable_move_call(
+            SUI_FRAMEWORK_OBJECT_ID,
+            Identifier::new("coin").unwrap(),
+            Identifier::new("value").unwrap(),
+            vec![GAS::type_tag()],
+            vec![coin],
+        );
+        TransactionKind::programmable(builder.finish())
+    };
+    let overrides = vec![SuiObjectOverride {
+        object_id: coin_id,
+        version: Some(version),
+        bcs_bytes: Some(Base64::from_bytes(
+            &bcs::to_bytes(&GasCoin::new(coin_id, 42)).unwrap(),
+        )),
+    }];
+
+    // The coin has the overridden value, at the overridden version.
+    let results = fullnode
+        .dev_inspect_transaction(
+            sender,
+            coin_value(version),
+            Some(1),
+            false,
+            overrides.clone(),
+        )
+        .await
+        .unwrap()
+        .results
+        .unwrap();
+    let (value, _) = &results[0].return_values[0];
+    assert_eq!(bcs::from_bytes::<u64>(value).unwrap(), 42);
+
+    // The transaction must refer to the overridden version.
+    let result = fullnode
+        .dev_inspect_transaction(
+            sender,
+            coin_value(coin.version()),
+            Some(1),
+            false,
+            overrides,
+        )
+        .await;
+    let Err(err) = result else { panic!() };
+    assert!(err
+        .to_string()
+        .contains("ObjectVersionUnavailableForConsumption"));
+
+    // Contents must be valid for the type of the object.
+    let invalid_overrides = vec![SuiObjectOverride {
+        object_id: coin_id,
+        version: None,
+        bcs_bytes: Some(Base64::from_bytes(coin_id.as_ref())),
+    }];
+    let result = fullnode
+        .dev_inspect_transaction(
+            sender,
+            coin_value(coin.version()),
+            Some(1),
+            false,
+            invalid_overrides,
+        )
+        .await;
+    assert!(result.is_err());
+
+    // Nothing is written.
+    assert_eq!(fullnode.get_object(&coin_id).await.unwrap().unwrap(), coin);
+}
+
 #[tokio::test]
 async fn test_dev_inspect_on_validator() {
     let (sender, _sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -911,6 +1092,63 @@ async fn test_dev_inspect_on_validator() {
     assert!(result.is_err())
 }
 
+#[tokio::test]
+async fn test_dev_inspect_trace() {
+    let (sender, _sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let (_, fullnode, object_basics) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![(sender, gas_object_id)]).await;
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let value = builder.pure(16_u64).unwrap();
+    let recipient = builder.pure(sender).unwrap();
+    builder.programmable_move_call(
+        object_basics.0,
+        Identifier::new("object_basics").unwrap(),
+        Identifier::new("create").unwrap(),
+        vec![],
+        vec![value, recipient],
+    );
+    let kind = TransactionKind::programmable(builder.finish());
+
+    let DevInspectResults { trace, .. } = fullnode
+        .dev_inspect_transaction(sender, kind.clone(), Some(1), false, vec![])
+        .await
+        .unwrap();
+    assert!(trace.is_none());
+
+    let DevInspectResults { effects, trace, .. } = fullnode
+        .dev_inspect_transaction(sender, kind, Some(1), true, vec![])
+        .await
+        .unwrap();
+    let trace = trace.unwrap();
+    assert_eq!(trace.commands.len(), 1);
+    let command = &trace.commands[0];
+    assert_eq!(command.kind, "MoveCall");
+    let call = command.call.as_ref().unwrap();
+    assert_eq!(call.package, object_basics.0);
+    assert_eq!(call.module, "object_basics");
+    assert_eq!(call.function, "create");
+    assert!(command.gas_used > 0);
+    assert!(command
+        .native_calls
+        .iter()
+        .any(|native| native.function.ends_with("::transfer::transfer_impl")));
+
+    let created = effects.created()[0].reference.object_id;
+    let change = trace
+        .object_changes
+        .iter()
+        .find(|change| change.object_id == created)
+        .unwrap();
+    assert_eq!(change.kind, ObjectChangeKind::Created);
+    assert!(change
+        .object_type
+        .as_ref()
+        .unwrap()
+        .ends_with("::object_basics::Object"));
+}
+
 #[tokio::test]
 async fn test_dry_run_on_validator() {
     let (validator, _fullnode, transaction, _gas_object_id, _shared_object_id) =
@@ -920,6 +1158,7 @@ async fn test_dry_run_on_validator() {
         .dry_exec_transaction(
             transaction.data().intent_message().value.clone(),
             transaction_digest,
+            false,
         )
         .await;
     assert!(response.is_err());
@@ -1119,6 +1358,65 @@ async fn test_handle_transfer_transaction_unknown_sender() {
         .is_none());
 }
 
+#[tokio::test]
+async fn test_handle_transaction_denied() {
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let epoch_store = authority_state.load_epoch_store_one_call_per_task();
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let transfer_transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+    );
+
+    authority_state.set_transaction_deny_config(TransactionDenyConfig {
+        object_deny_list: [object_id].into_iter().collect(),
+        ..Default::default()
+    });
+    let err = authority_state
+        .handle_transaction(&epoch_store, transfer_transaction.clone())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SuiError::UserInputError {
+            error: UserInputError::TransactionDenied { .. }
+        }
+    ));
+
+    authority_state.set_transaction_deny_config(TransactionDenyConfig {
+        address_deny_list: [sender].into_iter().collect(),
+        ..Default::default()
+    });
+    assert!(authority_state
+        .handle_transaction(&epoch_store, transfer_transaction.clone())
+        .await
+        .is_err());
+
+    // Lifting the deny list takes effect immediately.
+    authority_state.set_transaction_deny_config(TransactionDenyConfig::default());
+    authority_state
+        .handle_transaction(&epoch_store, transfer_transaction)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_upgrade_module_is_feature_gated() {
     let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -2704,6 +3002,62 @@ async fn test_move_call_delete() {
     assert_eq!((effects.deleted().len(), effects.mutated().len()), (1, 1));
 }
 
+#[tokio::test]
+async fn test_move_call_events_too_large() {
+    let _dont_remove = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+        config.set_limit_events_size_for_testing(true);
+        config.set_max_serialized_tx_events_size_bytes_for_testing(1);
+        config.set_max_serialized_tx_events_size_bytes_system_tx_for_testing(1);
+        config
+    });
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let (authority_state, pkg_ref) =
+        init_state_with_ids_and_object_basics(vec![(sender, gas_object_id)]).await;
+
+    let mut object_ids = vec![];
+    for _ in 0..2 {
+        let effects = create_move_object(
+            &pkg_ref.0,
+            &authority_state,
+            &gas_object_id,
+            &sender,
+            &sender_key,
+        )
+        .await
+        .unwrap();
+        assert!(effects.status().is_ok());
+        object_ids.push(effects.created()[0].0 .0);
+    }
+
+    // Updating an object emits an event, which is over the limit.
+    let effects = call_move(
+        &authority_state,
+        &gas_object_id,
+        &sender,
+        &sender_key,
+        &pkg_ref.0,
+        "object_basics",
+        "update",
+        vec![],
+        vec![
+            TestCallArg::Object(object_ids[0]),
+            TestCallArg::Object(object_ids[1]),
+        ],
+    )
+    .await
+    .unwrap();
+    let ExecutionStatus::Failure { error, command: _ } = effects.status() else {
+        panic!("expected transaction to fail")
+    };
+    assert!(matches!(
+        error,
+        ExecutionFailureStatus::EventsTooLarge { max_size: 1, .. }
+    ));
+    // The events are dropped with the other outputs of the transaction.
+    assert!(effects.events_digest().is_none());
+}
+
 #[tokio::test]
 async fn test_get_latest_parent_entry_genesis() {
     let authority_state = init_state().await;
@@ -2896,6 +3250,11 @@ async fn test_authority_persist() {
             AuthorityStorePruningConfig::default(),
             &[], // no genesis objects
             &DBCheckpointConfig::default(),
+            TransactionDenyConfig::default(),
+            None,
+            LocalExecutionTimeLimitConfig::default(),
+            None,
+            SlowOpConfig::default(),
         )
         .await
     }
@@ -2949,6 +3308,161 @@ async fn test_authority_persist() {
     assert_eq!(obj2.owner, recipient);
 }
 
+#[tokio::test]
+async fn test_authority_store_rejects_newer_schema() {
+    let seed = [1u8; 32];
+    let (genesis, _) = init_state_parameters_from_rng(&mut StdRng::from_seed(seed));
+    let committee = genesis.committee().unwrap();
+
+    let dir = env::temp_dir();
+    let path = dir.join(format!("DB_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+
+    let store =
+        AuthorityStore::open_with_committee_for_testing(&path, None, &committee, &genesis, 0)
+            .await
+            .unwrap();
+    let db = &store.perpetual_tables.objects.rocksdb;
+    let version = store_schema_version(db).unwrap();
+
+    // Pretend the store was written by a release with a newer schema. Its version is read before
+    // the store is opened, so this doesn't depend on the store being closed yet.
+    set_store_schema_version(db, version + 1).unwrap();
+    drop(store);
+
+    let Err(err) =
+        AuthorityStore::open_with_committee_for_testing(&path, None, &committee, &genesis, 0).await
+    else {
+        panic!("a store with a newer schema was opened");
+    };
+    assert!(
+        matches!(
+            &err,
+            SuiError::StorageError(TypedStoreError::SchemaVersionError(_))
+        ),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_get_object_state_proof() {
+    use crate::test_utils::make_transfer_sui_transaction;
+    use sui_types::accumulator::Accumulator;
+    use sui_types::base_types::ExecutionDigests;
+    use sui_types::committee::ProtocolVersion;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary, ECMHLiveObjectSetDigest,
+        EndOfEpochData, SignedCheckpointSummary, VerifiedCheckpoint,
+    };
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let network_config = sui_config::builder::ConfigBuilder::new(&dir).build();
+    let key = network_config.validator_configs[0]
+        .protocol_key_pair()
+        .copy();
+    let state = init_state_with_committee(&network_config.genesis, &key).await;
+    let committee = state.clone_committee_for_testing();
+    let store = state.get_checkpoint_store();
+    let insert_checkpoint = |summary: CheckpointSummary, contents: CheckpointContents| {
+        let sign_info =
+            SignedCheckpointSummary::sign(committee.epoch, &summary, &key, key.public().into());
+        let checkpoint = VerifiedCheckpoint::new_unchecked(
+            CertifiedCheckpointSummary::new(summary, vec![sign_info], &committee).unwrap(),
+        );
+        store.insert_checkpoint_contents(contents).unwrap();
+        store.insert_verified_checkpoint(checkpoint).unwrap();
+    };
+
+    let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
+    let gas = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let gas_ref = gas.compute_object_reference();
+    state.insert_genesis_object(gas).await;
+    let transaction =
+        make_transfer_sui_transaction(gas_ref, dbg_addr(1), Some(1), sender, &sender_key, None);
+    let (certificate, effects) = send_and_confirm_transaction(&state, transaction)
+        .await
+        .unwrap();
+    let digest = *certificate.digest();
+    let object_ref = effects.data().gas_object().0;
+
+    // Nothing can be proven before the transaction is checkpointed.
+    state
+        .get_object_state_proof(&object_ref.0, object_ref.1)
+        .unwrap_err();
+
+    let contents = CheckpointContents::new_with_causally_ordered_transactions(
+        [ExecutionDigests::new(digest, *effects.digest())].into_iter(),
+    );
+    let summary = CheckpointSummary::new(
+        committee.epoch,
+        0,
+        1,
+        &contents,
+        None,
+        GasCostSummary::default(),
+        None,
+        0,
+    );
+    insert_checkpoint(summary, contents);
+    state
+        .database
+        .insert_finalized_transactions(&[digest], committee.epoch, 0)
+        .unwrap();
+
+    let proof = state
+        .get_object_state_proof(&object_ref.0, object_ref.1)
+        .unwrap();
+    assert_eq!(proof.object_ref, object_ref);
+    assert!(proof.end_of_epoch_checkpoint.is_none());
+    proof.verify(&committee).unwrap();
+    // Only the version written by the checkpointed transaction is proven.
+    state
+        .get_object_state_proof(&gas_ref.0, gas_ref.1)
+        .unwrap_err();
+
+    // Once the epoch ended, the proof carries the state root, which must be the local one.
+    let mut accumulator = Accumulator::default();
+    accumulator.insert(object_ref.2);
+    let state_root = ECMHLiveObjectSetDigest::from(accumulator.digest());
+    let contents = CheckpointContents::new_with_causally_ordered_transactions(
+        [ExecutionDigests::random()].into_iter(),
+    );
+    let mut summary = CheckpointSummary::new(
+        committee.epoch,
+        1,
+        2,
+        &contents,
+        None,
+        GasCostSummary::default(),
+        None,
+        0,
+    );
+    summary.end_of_epoch_data = Some(EndOfEpochData {
+        next_epoch_committee: committee.voting_rights.clone(),
+        next_epoch_protocol_version: ProtocolVersion::MIN,
+        epoch_commitments: vec![state_root.clone().into()],
+    });
+    insert_checkpoint(summary, contents);
+    let root_state_hash_by_epoch = &state.database.perpetual_tables.root_state_hash_by_epoch;
+    root_state_hash_by_epoch
+        .insert(&committee.epoch, &(1, accumulator))
+        .unwrap();
+
+    let proof = state
+        .get_object_state_proof(&object_ref.0, object_ref.1)
+        .unwrap();
+    assert_eq!(proof.state_root(), Some(&state_root));
+    proof.verify(&committee).unwrap();
+
+    root_state_hash_by_epoch
+        .insert(&committee.epoch, &(1, Accumulator::default()))
+        .unwrap();
+    state
+        .get_object_state_proof(&object_ref.0, object_ref.1)
+        .unwrap_err();
+}
+
 #[tokio::test]
 async fn test_idempotent_reversed_confirmation() {
     // In this test we exercise the case where an authority first receive the certificate,
@@ -4189,7 +4703,7 @@ pub fn init_transfer_transaction(
 }
 
 #[cfg(test)]
-fn init_certified_transfer_transaction(
+pub(crate) fn init_certified_transfer_transaction(
     sender: SuiAddress,
     secret: &AccountKeyPair,
     recipient: SuiAddress,
@@ -4569,7 +5083,7 @@ pub async fn call_dev_inspect(
     ));
     let kind = TransactionKind::programmable(builder.finish());
     authority
-        .dev_inspect_transaction(*sender, kind, Some(1))
+        .dev_inspect_transaction(*sender, kind, Some(1), false, vec![])
         .await
 }
 
@@ -5129,7 +5643,7 @@ async fn test_for_inc_201_dev_inspect() {
     builder.command(Command::Publish(modules, system_package_ids()));
     let kind = TransactionKind::programmable(builder.finish());
     let DevInspectResults { events, .. } = fullnode
-        .dev_inspect_transaction(sender, kind, Some(1))
+        .dev_inspect_transaction(sender, kind, Some(1), false, vec![])
         .await
         .unwrap();
 
@@ -5169,6 +5683,7 @@ async fn test_for_inc_201_dry_run() {
         .dry_exec_transaction(
             signed.data().intent_message().value.clone(),
             *signed.digest(),
+            false,
         )
         .await
         .unwrap();
@@ -5457,3 +5972,35 @@ async fn test_publish_not_a_package_dependency() {
         failure,
     )
 }
+
+#[tokio::test]
+async fn test_dry_run_epoch_change() {
+    let state = init_state().await;
+    let epoch_store = state.load_epoch_store_one_call_per_task();
+
+    let dry_run = state
+        .dry_run_epoch_change(&epoch_store, SupportedProtocolVersions::SYSTEM_DEFAULT)
+        .await
+        .unwrap();
+
+    // No capabilities were received, so the protocol version is not upgraded.
+    assert_eq!(dry_run.next_epoch, epoch_store.epoch() + 1);
+    assert_eq!(
+        dry_run.next_protocol_version,
+        epoch_store.protocol_version()
+    );
+    assert!(dry_run.next_system_packages.is_empty());
+    assert!(dry_run.next_protocol_version_supported);
+    assert!(dry_run.next_system_packages_available);
+    assert!(!dry_run.safe_mode);
+    assert!(dry_run.is_in_next_committee(&state.name));
+
+    // Nothing is committed.
+    assert_eq!(
+        state
+            .get_sui_system_state_object_for_testing()
+            .unwrap()
+            .epoch(),
+        epoch_store.epoch()
+    );
+}
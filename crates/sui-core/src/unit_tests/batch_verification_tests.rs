@@ -7,12 +7,14 @@ use fastcrypto::traits::KeyPair;
 use futures::future::join_all;
 use prometheus::Registry;
 use rand::{thread_rng, Rng};
+use shared_crypto::intent::Intent;
 use std::sync::Arc;
 use sui_macros::sim_test;
+use sui_types::base_types::random_object_ref;
 use sui_types::committee::Committee;
 use sui_types::crypto::{get_key_pair, AccountKeyPair, AuthorityKeyPair};
 use sui_types::gas::GasCostSummary;
-use sui_types::messages::CertifiedTransaction;
+use sui_types::messages::{CertifiedTransaction, Transaction, TransactionData};
 use sui_types::messages_checkpoint::{
     CheckpointContents, CheckpointSummary, SignedCheckpointSummary,
 };
@@ -143,3 +145,51 @@ async fn test_async_verifier() {
 
     join_all(tasks).await;
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_async_transaction_verifier() {
+    let (committee, _) = Committee::new_simple_test_committee();
+    let registry = Registry::new();
+    let metrics = VerifiedDigestCacheMetrics::new(&registry);
+    let verifier = Arc::new(SignatureVerifier::new(Arc::new(committee), metrics));
+
+    let tasks: Vec<_> = (0..32)
+        .map(|_| {
+            let verifier = verifier.clone();
+            tokio::task::spawn(async move {
+                let (receiver, _): (_, AccountKeyPair) = get_key_pair();
+                let (sender, sender_sec): (_, AccountKeyPair) = get_key_pair();
+                let (_, other_sec): (_, AccountKeyPair) = get_key_pair();
+                for _ in 0..50 {
+                    if thread_rng().gen_range(0..10) == 0 {
+                        // Signed by a key that is not the sender's.
+                        let tx = Transaction::from_data_and_signer(
+                            TransactionData::new_transfer_with_dummy_gas_price(
+                                receiver,
+                                random_object_ref(),
+                                sender,
+                                random_object_ref(),
+                                10000,
+                            ),
+                            Intent::default(),
+                            vec![&other_sec],
+                        );
+                        verifier.verify_transaction(tx).await.unwrap_err();
+                    } else {
+                        let tx = make_dummy_tx(receiver, sender, &sender_sec).into_inner();
+                        // The same transaction submitted concurrently is verified once.
+                        let (first, second) = futures::join!(
+                            verifier.verify_transaction(tx.clone()),
+                            verifier.verify_transaction(tx)
+                        );
+                        assert_eq!(first.unwrap().digest(), second.unwrap().digest());
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for result in join_all(tasks).await {
+        result.unwrap();
+    }
+}
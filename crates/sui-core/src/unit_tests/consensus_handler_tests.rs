@@ -0,0 +1,246 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::authority_store::AuthorityStore;
+use crate::authority::{authority_tests::init_state_with_objects, AuthorityState};
+use crate::checkpoints::{CheckpointMetrics, CheckpointStore, LogCheckpointOutput};
+use crate::consensus_adapter::consensus_tests::{test_certificates, test_gas_objects};
+use crate::consensus_adapter::ConnectionMonitorStatusForTests;
+use crate::state_accumulator::StateAccumulator;
+use prometheus::Registry;
+use sui_protocol_config::{OverrideGuard, ProtocolConfig};
+use sui_types::object::Object;
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
+
+/// An authority holding the shared object and gas objects of `test_certificates`, admitting a
+/// single transaction per shared object and commit.
+async fn congested_state() -> Arc<AuthorityState> {
+    let mut objects = test_gas_objects();
+    objects.push(Object::shared_for_testing());
+    init_state_with_objects(objects).await
+}
+
+fn new_handler(state: &Arc<AuthorityState>) -> ConsensusHandler<Arc<AuthorityStore>> {
+    let epoch_store = state.epoch_store_for_testing().clone();
+    let checkpoint_store = CheckpointStore::new(&tempfile::tempdir().unwrap().into_path());
+    let (checkpoint_service, _exit) = CheckpointService::spawn(
+        state.clone(),
+        checkpoint_store,
+        epoch_store.clone(),
+        Box::new(state.db()),
+        Arc::new(StateAccumulator::new(state.db())),
+        LogCheckpointOutput::boxed(),
+        LogCheckpointOutput::boxed_certified(),
+        CheckpointMetrics::new_for_tests(),
+        3,
+        100_000,
+    );
+    let committee = epoch_store.epoch_start_state().get_narwhal_committee();
+    ConsensusHandler::new(
+        epoch_store,
+        checkpoint_service,
+        state.transaction_manager().clone(),
+        state.db(),
+        Arc::new(ArcSwap::from_pointee(HashMap::new())),
+        Arc::new(HashMap::new()),
+        committee,
+        state.metrics.clone(),
+        QuorumReachability::new(
+            state.name,
+            Arc::new(ConnectionMonitorStatusForTests {}),
+            &Registry::new(),
+        ),
+        state.slow_ops.clone(),
+    )
+}
+
+/// Runs congestion control over a commit at `round` holding `transactions`, and returns the keys
+/// of the transactions it schedules, the commit prologue first.
+fn schedule(
+    handler: &ConsensusHandler<Arc<AuthorityStore>>,
+    round: Round,
+    transactions: &[ConsensusTransaction],
+) -> Vec<SequencedConsensusTransactionKey> {
+    let leader_cert = Arc::new(Certificate::default());
+    let prologue = handler.consensus_commit_prologue_transaction(round, 0);
+    let mut committed: Vec<CommittedTransaction> = vec![(
+        vec![],
+        SequencedConsensusTransactionKind::System(prologue),
+        leader_cert.clone(),
+        AuthorityName::ZERO,
+    )];
+    committed.extend(transactions.iter().map(|transaction| {
+        (
+            bcs::to_bytes(transaction).unwrap(),
+            SequencedConsensusTransactionKind::External(transaction.clone()),
+            leader_cert.clone(),
+            AuthorityName::ZERO,
+        )
+    }));
+    handler
+        .schedule_congested_transactions(round, leader_cert, committed)
+        .into_iter()
+        .map(|(_, transaction, _, _)| transaction.key())
+        .collect()
+}
+
+fn prologue_key(
+    handler: &ConsensusHandler<Arc<AuthorityStore>>,
+    round: Round,
+) -> SequencedConsensusTransactionKey {
+    SequencedConsensusTransactionKind::System(
+        handler.consensus_commit_prologue_transaction(round, 0),
+    )
+    .key()
+}
+
+fn keys(transactions: &[&ConsensusTransaction]) -> Vec<SequencedConsensusTransactionKey> {
+    transactions
+        .iter()
+        .map(|transaction| SequencedConsensusTransactionKey::External(transaction.key()))
+        .collect()
+}
+
+fn deferred_keys(
+    epoch_store: &AuthorityPerEpochStore,
+    round: Round,
+) -> Vec<ConsensusTransactionKey> {
+    epoch_store
+        .load_deferred_transactions(round)
+        .unwrap()
+        .map_or(vec![], |(_, deferred)| {
+            deferred
+                .iter()
+                .map(|entry| entry.transaction.key())
+                .collect()
+        })
+}
+
+fn congestion_config_guard() -> OverrideGuard {
+    ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+        config.set_shared_object_congestion_control_for_testing(true);
+        config.set_max_txns_per_shared_object_per_commit_for_testing(1);
+        config
+    })
+}
+
+async fn certificate_messages(state: &AuthorityState) -> Vec<ConsensusTransaction> {
+    test_certificates(state)
+        .await
+        .into_iter()
+        .map(|certificate| ConsensusTransaction::new_certificate_message(&state.name, certificate))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_defer_and_admit_in_later_commits() {
+    let _guard = congestion_config_guard();
+    let state = congested_state().await;
+    let epoch_store = state.epoch_store_for_testing();
+    let handler = new_handler(&state);
+    let certificates = certificate_messages(&state).await;
+    let [c0, c1, c2, c3] = &certificates[..] else {
+        panic!("expected 4 certificates");
+    };
+
+    // Only the first transaction on the shared object is admitted, the others are deferred in
+    // their sequencing order.
+    let mut expected = vec![prologue_key(&handler, 1)];
+    expected.extend(keys(&[c0]));
+    assert_eq!(schedule(&handler, 1, &certificates), expected);
+    assert_eq!(
+        deferred_keys(&epoch_store, 2),
+        vec![c1.key(), c2.key(), c3.key()]
+    );
+
+    // Deferred transactions are admitted ahead of those sequenced in later commits.
+    for (round, admitted) in [(2, c1), (3, c2), (4, c3)] {
+        let mut expected = vec![prologue_key(&handler, round)];
+        expected.extend(keys(&[admitted]));
+        assert_eq!(schedule(&handler, round, &[]), expected);
+    }
+    assert!(deferred_keys(&epoch_store, 5).is_empty());
+
+    // Messages other than certificates are never deferred.
+    let end_of_publish = ConsensusTransaction::new_end_of_publish(state.name);
+    let mut expected = vec![prologue_key(&handler, 5)];
+    expected.extend(keys(&[&end_of_publish]));
+    assert_eq!(schedule(&handler, 5, &[end_of_publish]), expected);
+}
+
+#[tokio::test]
+async fn test_deferred_transactions_survive_restart() {
+    let _guard = congestion_config_guard();
+    let state = congested_state().await;
+    let epoch_store = state.epoch_store_for_testing();
+    let certificates = certificate_messages(&state).await;
+    let [c0, c1, ..] = &certificates[..] else {
+        panic!("expected 4 certificates");
+    };
+
+    let handler = new_handler(&state);
+    let first_run = schedule(&handler, 1, &certificates);
+    drop(handler);
+
+    // A restarted handler only has the deferral queue persisted in the epoch store. Replaying the
+    // same commit makes the same decisions, and the next commit admits from the persisted queue.
+    let handler = new_handler(&state);
+    assert_eq!(schedule(&handler, 1, &certificates), first_run);
+    let mut expected = vec![prologue_key(&handler, 1)];
+    expected.extend(keys(&[c0]));
+    assert_eq!(first_run, expected);
+
+    let mut expected = vec![prologue_key(&handler, 2)];
+    expected.extend(keys(&[c1]));
+    assert_eq!(schedule(&handler, 2, &[]), expected);
+    assert_eq!(deferred_keys(&epoch_store, 3).len(), 2);
+}
+
+#[tokio::test]
+async fn test_end_of_publish_flushes_deferred_transactions() {
+    let _guard = congestion_config_guard();
+    let state = congested_state().await;
+    let epoch_store = state.epoch_store_for_testing();
+    let handler = new_handler(&state);
+    let certificates = certificate_messages(&state).await;
+    let [_, c1, c2, c3] = &certificates[..] else {
+        panic!("expected 4 certificates");
+    };
+
+    schedule(&handler, 1, &certificates[..2]);
+    assert_eq!(deferred_keys(&epoch_store, 2), vec![c1.key()]);
+
+    // The queue is admitted in full ahead of the EndOfPublish, over the limit, together with the
+    // transactions deferred by this very commit.
+    let end_of_publish = ConsensusTransaction::new_end_of_publish(state.name);
+    let transactions = vec![c2.clone(), c3.clone(), end_of_publish.clone()];
+    let mut expected = vec![prologue_key(&handler, 2)];
+    expected.extend(keys(&[c1, c2, c3, &end_of_publish]));
+    assert_eq!(schedule(&handler, 2, &transactions), expected);
+    assert!(deferred_keys(&epoch_store, 3).is_empty());
+}
+
+#[tokio::test]
+async fn test_deterministic_scheduling() {
+    let _guard = congestion_config_guard();
+    let state = congested_state().await;
+    let other_state = congested_state().await;
+    let certificates = certificate_messages(&state).await;
+    let [c0, c1, c2, c3] = &certificates[..] else {
+        panic!("expected 4 certificates");
+    };
+    let sequenced = vec![c3.clone(), c1.clone(), c0.clone(), c2.clone()];
+
+    // Two validators fed the same commits schedule the same transactions, in the order they were
+    // sequenced in.
+    let handler = new_handler(&state);
+    let other_handler = new_handler(&other_state);
+    let commits = [(1, sequenced), (2, vec![]), (3, vec![]), (4, vec![])];
+    for ((round, transactions), admitted) in commits.into_iter().zip([c3, c1, c0, c2]) {
+        let mut expected = vec![prologue_key(&handler, round)];
+        expected.extend(keys(&[admitted]));
+        assert_eq!(schedule(&handler, round, &transactions), expected);
+        assert_eq!(schedule(&other_handler, round, &transactions), expected);
+    }
+}
@@ -1,30 +1,36 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::authority::authority_tests::{send_consensus, send_consensus_no_execution};
+use super::ReadyQueue;
+use crate::authority::authority_tests::{
+    init_certified_transfer_transaction, init_state_with_objects, send_consensus,
+    send_consensus_no_execution,
+};
 use crate::authority::{AuthorityState, EffectsNotifyRead, MAX_PER_OBJECT_EXECUTION_QUEUE_LENGTH};
 use crate::authority_aggregator::authority_aggregator_tests::{
     create_object_move_transaction, do_cert, do_transaction, extract_cert, get_latest_ref,
     transfer_object_move_transaction,
 };
+use crate::consensus_adapter::consensus_tests::{test_certificates, test_gas_objects};
 use crate::safe_client::SafeClient;
 use crate::test_authority_clients::LocalAuthorityClient;
 use crate::test_utils::init_local_authorities;
+use crate::transaction_manager::TransactionManager;
 
 use std::collections::BTreeSet;
 use std::time::Duration;
 
 use itertools::Itertools;
-use sui_types::base_types::TransactionDigest;
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use sui_types::committee::Committee;
-use sui_types::crypto::{get_key_pair, AccountKeyPair};
+use sui_types::crypto::{deterministic_random_account_key, get_key_pair, AccountKeyPair};
 use sui_types::error::SuiResult;
 use sui_types::messages::{
     TransactionEffects, TransactionEffectsAPI, VerifiedCertificate, VerifiedTransaction,
 };
 use sui_types::object::{Object, Owner};
 use test_utils::messages::{make_counter_create_transaction, make_counter_increment_transaction};
-use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 use tokio::time::{sleep, timeout};
 
 #[allow(dead_code)]
@@ -560,3 +566,67 @@ async fn test_per_object_overload() {
         message,
     );
 }
+
+#[tokio::test]
+#[allow(clippy::disallowed_methods)] // allow unbounded_channel()
+async fn test_ready_certificates_priority() {
+    // The shared object transactions of `test_certificates` form a single chain, while transferring
+    // an owned object depends on nothing.
+    let (sender, keypair) = deterministic_random_account_key();
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let gas_object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let mut objects = test_gas_objects();
+    objects.extend([
+        Object::shared_for_testing(),
+        object.clone(),
+        gas_object.clone(),
+    ]);
+    let state = init_state_with_objects(objects).await;
+    let epoch_store = state.epoch_store_for_testing();
+
+    let transfer = init_certified_transfer_transaction(
+        sender,
+        &keypair,
+        SuiAddress::random_for_testing_only(),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+        &state,
+    );
+    let mut certificates = vec![transfer.clone()];
+    for certificate in test_certificates(&state).await {
+        let certificate = certificate.verify(epoch_store.committee()).unwrap();
+        send_consensus_no_execution(&state, &certificate).await;
+        certificates.push(certificate);
+    }
+
+    let (tx_ready_certificates, mut rx_ready_certificates) = unbounded_channel();
+    let transaction_manager = TransactionManager::new(
+        state.db(),
+        &epoch_store,
+        tx_ready_certificates,
+        state.metrics.clone(),
+    );
+    transaction_manager
+        .enqueue_certificates(certificates.clone(), &epoch_store)
+        .unwrap();
+
+    // The transfer and the head of the shared object chain are ready, in the order they were
+    // enqueued. The head of the chain unblocks the 3 other shared object transactions.
+    let mut queue = ReadyQueue::default();
+    let mut ready = vec![];
+    while let Ok(certificate) = rx_ready_certificates.try_recv() {
+        ready.push((*certificate.certificate.digest(), certificate.priority));
+        queue.push(certificate);
+    }
+    assert_eq!(
+        ready,
+        vec![(*transfer.digest(), 1), (*certificates[1].digest(), 4)]
+    );
+
+    // With all workers busy, the execution driver starts the head of the chain first.
+    let order: Vec<_> = std::iter::from_fn(|| queue.pop())
+        .map(|certificate| *certificate.certificate.digest())
+        .collect();
+    assert_eq!(order, vec![*certificates[1].digest(), *transfer.digest()]);
+    assert!(queue.is_empty());
+}
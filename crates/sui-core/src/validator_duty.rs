@@ -0,0 +1,411 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Estimates of the consensus duties of a validator over a future time window, to help operators
+//! schedule maintenance.
+//!
+//! Consensus rounds are not tied to wall-clock time, so the rounds of the window are extrapolated
+//! from the rate at which rounds were committed since the start of the epoch. Leaders are elected
+//! deterministically per round, so once the rounds are estimated the rounds this validator leads
+//! are exact. The reputation score of a validator counts its votes for the committed leaders of a
+//! schedule, so a validator offline for a fraction of a schedule loses about that fraction of its
+//! score. Validators whose score is a low outlier are treated as low scoring by their peers, who
+//! may report them under the tallying rule.
+
+use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use crate::scoring_decision::low_score_outliers;
+use narwhal_config::Committee;
+use narwhal_node::primary_node::PrimaryNode;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sui_types::base_types::AuthorityName;
+use sui_types::committee::EpochId;
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
+
+/// Progress of consensus in the current epoch.
+#[derive(Clone, Debug)]
+pub struct ConsensusProgress {
+    pub epoch: EpochId,
+    pub epoch_start_timestamp_ms: u64,
+    pub epoch_duration_ms: u64,
+    pub now_ms: u64,
+    pub last_committed_round: u64,
+    pub last_sub_dag_index: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MaintenanceWindowReport {
+    pub epoch: EpochId,
+    pub window_start_ms: u64,
+    pub window_end_ms: u64,
+    /// Expected end of the epoch. Duties past it depend on the next committee and are not
+    /// estimated.
+    pub epoch_end_ms: u64,
+    pub rounds_per_second: f64,
+    pub first_round: u64,
+    pub last_round: u64,
+    /// Leader rounds in the window, and how many of them this validator leads.
+    pub leader_rounds: u64,
+    pub own_leader_rounds: u64,
+    /// Leader rounds this validator is expected to lead over time, given its stake.
+    pub expected_own_leader_rounds: f64,
+    pub schedules: Vec<ScheduleImpact>,
+    pub warnings: Vec<String>,
+}
+
+/// Impact of the window on the reputation score of one consensus schedule.
+#[derive(Clone, Debug, Serialize)]
+pub struct ScheduleImpact {
+    pub schedule: u64,
+    /// Fraction of the commits of the schedule that fall in the window.
+    pub offline_fraction: f64,
+    /// Score of the validator in the last completed schedule, reduced by `offline_fraction`.
+    pub projected_score: Option<u64>,
+    /// Whether the projected score is a low outlier among the scores of the last completed
+    /// schedule.
+    pub low_scoring: Option<bool>,
+}
+
+/// Estimates the duties of `name` between `window_start_ms` and `window_end_ms`, from the progress
+/// of consensus in the epoch of `epoch_store`.
+pub fn estimate_maintenance_window_in_epoch(
+    epoch_store: &AuthorityPerEpochStore,
+    name: AuthorityName,
+    window_start_ms: u64,
+    window_end_ms: u64,
+) -> anyhow::Result<MaintenanceWindowReport> {
+    let epoch_start_state = epoch_store.epoch_start_state();
+    let index = epoch_store.get_last_consensus_index()?.index;
+    let progress = ConsensusProgress {
+        epoch: epoch_store.epoch(),
+        epoch_start_timestamp_ms: epoch_start_state.epoch_start_timestamp_ms(),
+        epoch_duration_ms: epoch_start_state.epoch_duration_ms(),
+        now_ms: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+        last_committed_round: index.last_committed_round,
+        last_sub_dag_index: index.sub_dag_index,
+    };
+    estimate_maintenance_window(
+        &epoch_start_state.get_narwhal_committee(),
+        name,
+        &progress,
+        &epoch_store.get_last_schedule_scores(),
+        PrimaryNode::CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS,
+        window_start_ms,
+        window_end_ms,
+    )
+}
+
+/// Estimates the duties of `name` between `window_start_ms` and `window_end_ms`.
+/// `last_schedule_scores` are the final reputation scores of the last completed schedule, if any.
+pub fn estimate_maintenance_window(
+    committee: &Committee,
+    name: AuthorityName,
+    progress: &ConsensusProgress,
+    last_schedule_scores: &HashMap<AuthorityName, u64>,
+    sub_dags_per_schedule: u64,
+    window_start_ms: u64,
+    window_end_ms: u64,
+) -> anyhow::Result<MaintenanceWindowReport> {
+    if window_end_ms <= window_start_ms {
+        anyhow::bail!("The window must end after it starts");
+    }
+    let authority = committee
+        .authorities()
+        .find(|authority| AuthorityName::from(authority.protocol_key()) == name)
+        .ok_or_else(|| anyhow::anyhow!("{} is not in the committee", name.concise()))?;
+    let elapsed_ms = progress
+        .now_ms
+        .saturating_sub(progress.epoch_start_timestamp_ms);
+    if elapsed_ms == 0 || progress.last_committed_round == 0 {
+        anyhow::bail!("Consensus has not committed any round in this epoch yet");
+    }
+
+    let mut warnings = vec![];
+    let epoch_end_ms = progress.epoch_start_timestamp_ms + progress.epoch_duration_ms;
+    let start_ms = window_start_ms.max(progress.now_ms);
+    let end_ms = window_end_ms.min(epoch_end_ms);
+    if window_end_ms > epoch_end_ms {
+        warnings.push(
+            "the window extends past the end of the epoch, duties in the next epoch are not \
+            estimated"
+                .to_string(),
+        );
+    }
+    if window_start_ms < progress.now_ms {
+        warnings
+            .push("the window has already started, only its remainder is estimated".to_string());
+    }
+
+    let rounds_per_ms = progress.last_committed_round as f64 / elapsed_ms as f64;
+    let round_at = |timestamp_ms: u64| {
+        progress.last_committed_round
+            + (timestamp_ms.saturating_sub(progress.now_ms) as f64 * rounds_per_ms).round() as u64
+    };
+    let first_round = round_at(start_ms);
+    let last_round = round_at(end_ms.max(start_ms));
+
+    // Leaders are only elected in even rounds.
+    let mut leader_rounds = 0;
+    let mut own_leader_rounds = 0;
+    for round in (first_round..=last_round).filter(|round| round % 2 == 0) {
+        leader_rounds += 1;
+        if committee.leader(round).id() == authority.id() {
+            own_leader_rounds += 1;
+        }
+    }
+    let total_stake: u64 = committee.authorities().map(|a| a.stake()).sum();
+    let stake_share = authority.stake() as f64 / total_stake as f64;
+    let expected_own_leader_rounds = leader_rounds as f64 * stake_share;
+    if own_leader_rounds > 0 && own_leader_rounds as f64 > 1.5 * expected_own_leader_rounds {
+        warnings.push(format!(
+            "this validator leads {own_leader_rounds} rounds in the window, more than the \
+            {expected_own_leader_rounds:.1} expected from its stake"
+        ));
+    }
+
+    let schedules = schedule_impacts(
+        name,
+        progress,
+        last_schedule_scores,
+        sub_dags_per_schedule,
+        first_round,
+        last_round,
+    );
+    if schedules
+        .iter()
+        .any(|impact| impact.low_scoring == Some(true))
+    {
+        warnings.push(
+            "this validator would likely be low scoring for at least one schedule, and may be \
+            reported by its peers"
+                .to_string(),
+        );
+    }
+
+    Ok(MaintenanceWindowReport {
+        epoch: progress.epoch,
+        window_start_ms: start_ms,
+        window_end_ms: end_ms.max(start_ms),
+        epoch_end_ms,
+        rounds_per_second: rounds_per_ms * 1000.0,
+        first_round,
+        last_round,
+        leader_rounds,
+        own_leader_rounds,
+        expected_own_leader_rounds,
+        schedules,
+        warnings,
+    })
+}
+
+fn schedule_impacts(
+    name: AuthorityName,
+    progress: &ConsensusProgress,
+    last_schedule_scores: &HashMap<AuthorityName, u64>,
+    sub_dags_per_schedule: u64,
+    first_round: u64,
+    last_round: u64,
+) -> Vec<ScheduleImpact> {
+    if first_round >= last_round {
+        return vec![];
+    }
+    // Not every leader round commits a sub dag, so convert rounds to sub dags at the observed
+    // commit rate.
+    let sub_dags_per_round =
+        (progress.last_sub_dag_index + 1) as f64 / progress.last_committed_round as f64;
+    let sub_dag_at = |round: u64| {
+        progress.last_sub_dag_index
+            + ((round - progress.last_committed_round) as f64 * sub_dags_per_round).round() as u64
+    };
+    let (first_sub_dag, last_sub_dag) = (sub_dag_at(first_round), sub_dag_at(last_round));
+
+    let others: Vec<u64> = last_schedule_scores
+        .iter()
+        .filter(|(other, _)| **other != name)
+        .map(|(_, score)| *score)
+        .collect();
+    let own_score = last_schedule_scores.get(&name).copied();
+
+    (first_sub_dag / sub_dags_per_schedule..=last_sub_dag / sub_dags_per_schedule)
+        .map(|schedule| {
+            let schedule_start = schedule * sub_dags_per_schedule;
+            let schedule_end = schedule_start + sub_dags_per_schedule;
+            let offline = last_sub_dag.min(schedule_end) - first_sub_dag.max(schedule_start);
+            let offline_fraction = offline as f64 / sub_dags_per_schedule as f64;
+            let projected_score =
+                own_score.map(|score| (score as f64 * (1.0 - offline_fraction)).round() as u64);
+            let low_scoring = projected_score.filter(|_| !others.is_empty()).map(|score| {
+                let mut scores = others.clone();
+                scores.push(score);
+                *low_score_outliers(&scores).last().unwrap()
+            });
+            ScheduleImpact {
+                schedule,
+                offline_fraction,
+                projected_score,
+                low_scoring,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::traits::{InsecureDefault, KeyPair as _};
+    use mysten_network::Multiaddr;
+    use narwhal_config::CommitteeBuilder;
+    use narwhal_crypto::KeyPair;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use sui_types::crypto::NetworkPublicKey;
+
+    fn generate_committee(committee_size: usize) -> (Committee, Vec<AuthorityName>) {
+        let mut committee_builder = CommitteeBuilder::new(0);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..committee_size {
+            let pair = KeyPair::generate(&mut rng);
+            committee_builder = committee_builder.add_authority(
+                pair.public().clone(),
+                1,
+                Multiaddr::empty(),
+                NetworkPublicKey::insecure_default(),
+            );
+        }
+        let committee = committee_builder.build();
+        let names = committee
+            .authorities()
+            .map(|authority| authority.protocol_key().into())
+            .collect();
+        (committee, names)
+    }
+
+    /// 10 rounds per second, with a sub dag committed every other round.
+    fn progress() -> ConsensusProgress {
+        ConsensusProgress {
+            epoch: 0,
+            epoch_start_timestamp_ms: 0,
+            epoch_duration_ms: 86_400_000,
+            now_ms: 1_000_000,
+            last_committed_round: 10_000,
+            last_sub_dag_index: 4_999,
+        }
+    }
+
+    #[test]
+    fn test_leader_rounds() {
+        let (committee, names) = generate_committee(4);
+
+        let mut own_leader_rounds = 0;
+        for name in &names {
+            let report = estimate_maintenance_window(
+                &committee,
+                *name,
+                &progress(),
+                &HashMap::new(),
+                300,
+                2_000_000,
+                2_060_000,
+            )
+            .unwrap();
+            assert_eq!(report.first_round, 20_000);
+            assert_eq!(report.last_round, 20_600);
+            assert_eq!(report.leader_rounds, 301);
+            assert_eq!(report.expected_own_leader_rounds, 75.25);
+            own_leader_rounds += report.own_leader_rounds;
+
+            // The window spans the end of one schedule and the start of the next
+            let fractions: Vec<_> = report
+                .schedules
+                .iter()
+                .map(|impact| (impact.schedule, impact.offline_fraction))
+                .collect();
+            assert_eq!(fractions, vec![(33, 201.0 / 300.0), (34, 99.0 / 300.0)]);
+            assert!(report
+                .schedules
+                .iter()
+                .all(|impact| impact.projected_score.is_none() && impact.low_scoring.is_none()));
+        }
+        // Every leader round has exactly one leader
+        assert_eq!(own_leader_rounds, 301);
+    }
+
+    #[test]
+    fn test_schedule_impact() {
+        let (committee, names) = generate_committee(4);
+        let scores = HashMap::from([
+            (names[0], 300),
+            (names[1], 290),
+            (names[2], 300),
+            (names[3], 310),
+        ]);
+
+        // A short window barely changes the score
+        let report = estimate_maintenance_window(
+            &committee,
+            names[0],
+            &progress(),
+            &scores,
+            300,
+            2_000_000,
+            2_001_000,
+        )
+        .unwrap();
+        assert_eq!(report.schedules.len(), 1);
+        assert_eq!(report.schedules[0].projected_score, Some(295));
+        assert_eq!(report.schedules[0].low_scoring, Some(false));
+
+        // Missing two thirds of a schedule makes the validator a low outlier
+        let report = estimate_maintenance_window(
+            &committee,
+            names[0],
+            &progress(),
+            &scores,
+            300,
+            2_000_000,
+            2_060_000,
+        )
+        .unwrap();
+        assert_eq!(report.schedules[0].projected_score, Some(99));
+        assert_eq!(report.schedules[0].low_scoring, Some(true));
+        assert!(report
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("low scoring")));
+    }
+
+    #[test]
+    fn test_window_bounds() {
+        let (committee, names) = generate_committee(4);
+
+        assert!(estimate_maintenance_window(
+            &committee,
+            names[0],
+            &progress(),
+            &HashMap::new(),
+            300,
+            2_000_000,
+            2_000_000,
+        )
+        .is_err());
+
+        // Only the part of the window before the end of the epoch is estimated
+        let report = estimate_maintenance_window(
+            &committee,
+            names[0],
+            &progress(),
+            &HashMap::new(),
+            300,
+            86_000_000,
+            90_000_000,
+        )
+        .unwrap();
+        assert_eq!(report.window_end_ms, 86_400_000);
+        assert_eq!(report.last_round, 864_000);
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
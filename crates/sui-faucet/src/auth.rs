@@ -0,0 +1,462 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Authentication of the clients requesting gas, and enforcement of a daily limit of requests
+//! per client identity.
+//!
+//! Clients are identified by the first of the configured `Verifier`s that accepts the
+//! credentials of their request, and requests without valid credentials are rejected. When no
+//! verifier is configured, clients are identified by their IP address.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use axum::{
+    body::{Body, Bytes},
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, HeaderName, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use fastcrypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::traits::{ToFromBytes, VerifyingKey};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::{FaucetError, FaucetResponse, QuotaStore};
+
+/// Header carrying the captcha token solved by the client.
+pub const CAPTCHA_TOKEN_HEADER: &str = "x-captcha-token";
+/// Headers of the requests signed by a Discord bot on behalf of a Discord user.
+pub const DISCORD_SIGNATURE_HEADER: &str = "x-signature-ed25519";
+pub const DISCORD_TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+pub const DISCORD_USER_HEADER: &str = "x-discord-user";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// What verifiers can inspect of a request.
+pub struct AuthRequest<'a> {
+    pub headers: &'a HeaderMap,
+    pub body: &'a [u8],
+    pub client_ip: Option<IpAddr>,
+}
+
+#[async_trait]
+pub trait Verifier: Send + Sync {
+    /// Identity of the client making `request`, prefixed with the kind of verifier that
+    /// established it, e.g. `discord:<user id>`. Returns `Ok(None)` if the request carries no
+    /// credentials for this verifier, and `Err(FaucetError::Unauthorized(_))` if its
+    /// credentials are invalid.
+    async fn verify(&self, request: &AuthRequest<'_>) -> Result<Option<String>, FaucetError>;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum CaptchaProvider {
+    Turnstile,
+    HCaptcha,
+}
+
+impl CaptchaProvider {
+    fn name(&self) -> &'static str {
+        match self {
+            CaptchaProvider::Turnstile => "turnstile",
+            CaptchaProvider::HCaptcha => "hcaptcha",
+        }
+    }
+
+    fn verify_url(&self) -> &'static str {
+        match self {
+            CaptchaProvider::Turnstile => {
+                "https://challenges.cloudflare.com/turnstile/v0/siteverify"
+            }
+            CaptchaProvider::HCaptcha => "https://api.hcaptcha.com/siteverify",
+        }
+    }
+}
+
+/// Accepts requests with a captcha token that the captcha provider confirms was solved. A
+/// captcha only proves that the client is a human, so clients are identified by their IP.
+pub struct CaptchaVerifier {
+    provider: CaptchaProvider,
+    secret: String,
+    client: reqwest::Client,
+}
+
+impl CaptchaVerifier {
+    pub fn new(provider: CaptchaProvider, secret: String) -> Self {
+        Self {
+            provider,
+            secret,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CaptchaResponse {
+    success: bool,
+}
+
+#[async_trait]
+impl Verifier for CaptchaVerifier {
+    async fn verify(&self, request: &AuthRequest<'_>) -> Result<Option<String>, FaucetError> {
+        let Some(token) = header_str(request.headers, CAPTCHA_TOKEN_HEADER) else {
+            return Ok(None);
+        };
+        let Some(client_ip) = request.client_ip else {
+            return Err(FaucetError::Unauthorized("client IP is unknown".into()));
+        };
+        let client_ip = client_ip.to_string();
+        let response: CaptchaResponse = self
+            .client
+            .post(self.provider.verify_url())
+            .form(&[
+                ("secret", self.secret.as_str()),
+                ("response", token),
+                ("remoteip", client_ip.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(FaucetError::internal)?
+            .json()
+            .await
+            .map_err(FaucetError::internal)?;
+        if !response.success {
+            return Err(FaucetError::Unauthorized("invalid captcha token".into()));
+        }
+        Ok(Some(format!("{}:{client_ip}", self.provider.name())))
+    }
+}
+
+/// Accepts requests with an OAuth bearer token, which is checked against the user info endpoint
+/// of the identity provider. Clients are identified by their `sub` claim.
+pub struct OAuthVerifier {
+    userinfo_url: String,
+    client: reqwest::Client,
+}
+
+impl OAuthVerifier {
+    pub fn new(userinfo_url: String) -> Self {
+        Self {
+            userinfo_url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    sub: String,
+}
+
+#[async_trait]
+impl Verifier for OAuthVerifier {
+    async fn verify(&self, request: &AuthRequest<'_>) -> Result<Option<String>, FaucetError> {
+        let token = header_str(request.headers, header::AUTHORIZATION.as_str())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let Some(token) = token else {
+            return Ok(None);
+        };
+        let response = self
+            .client
+            .get(&self.userinfo_url)
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(FaucetError::internal)?;
+        if !response.status().is_success() {
+            return Err(FaucetError::Unauthorized("invalid bearer token".into()));
+        }
+        let user: UserInfo = response.json().await.map_err(FaucetError::internal)?;
+        Ok(Some(format!("oauth:{}", user.sub)))
+    }
+}
+
+/// Accepts requests relayed by a Discord bot on behalf of a Discord user. The bot signs
+/// `<timestamp>:<user id>:<body of the request>` with its ed25519 key, and sends the hex encoded
+/// signature, the timestamp (in seconds since the Unix epoch) and the user id in the
+/// `DISCORD_*_HEADER` headers.
+pub struct DiscordVerifier {
+    public_key: Ed25519PublicKey,
+    max_age: Duration,
+}
+
+impl DiscordVerifier {
+    pub fn new(public_key: Ed25519PublicKey, max_age: Duration) -> Self {
+        Self {
+            public_key,
+            max_age,
+        }
+    }
+
+    /// Parses the hex encoded public key of the bot.
+    pub fn from_hex(public_key: &str, max_age: Duration) -> Result<Self, FaucetError> {
+        let public_key = Hex::decode(public_key)
+            .ok()
+            .and_then(|bytes| Ed25519PublicKey::from_bytes(&bytes).ok())
+            .ok_or_else(|| FaucetError::internal("invalid Discord public key"))?;
+        Ok(Self::new(public_key, max_age))
+    }
+
+    pub fn signed_message(timestamp: &str, user: &str, body: &[u8]) -> Vec<u8> {
+        let mut message = format!("{timestamp}:{user}:").into_bytes();
+        message.extend_from_slice(body);
+        message
+    }
+}
+
+#[async_trait]
+impl Verifier for DiscordVerifier {
+    async fn verify(&self, request: &AuthRequest<'_>) -> Result<Option<String>, FaucetError> {
+        let Some(signature) = header_str(request.headers, DISCORD_SIGNATURE_HEADER) else {
+            return Ok(None);
+        };
+        let unauthorized = |reason: &str| FaucetError::Unauthorized(reason.to_string());
+        let timestamp = header_str(request.headers, DISCORD_TIMESTAMP_HEADER)
+            .ok_or_else(|| unauthorized("missing signature timestamp"))?;
+        let user = header_str(request.headers, DISCORD_USER_HEADER)
+            .ok_or_else(|| unauthorized("missing Discord user"))?;
+
+        let signed_at = timestamp
+            .parse::<u64>()
+            .map_err(|_| unauthorized("invalid signature timestamp"))?;
+        let now = unix_time().as_secs();
+        if now.abs_diff(signed_at) > self.max_age.as_secs() {
+            return Err(unauthorized("expired signature"));
+        }
+
+        let signature = Hex::decode(signature)
+            .ok()
+            .and_then(|bytes| Ed25519Signature::from_bytes(&bytes).ok())
+            .ok_or_else(|| unauthorized("invalid signature"))?;
+        let message = Self::signed_message(timestamp, user, request.body);
+        self.public_key
+            .verify(&message, &signature)
+            .map_err(|_| unauthorized("invalid signature"))?;
+        Ok(Some(format!("discord:{user}")))
+    }
+}
+
+/// The verifiers, and the daily limit of requests per identity if any.
+pub struct FaucetAuth {
+    verifiers: Vec<Box<dyn Verifier>>,
+    quota: Option<(Mutex<QuotaStore>, u64)>,
+    client_ip_header: Option<HeaderName>,
+}
+
+impl FaucetAuth {
+    /// `client_ip_header` is the header with the IP of the client, set by the load balancer in
+    /// front of the faucet, if any. Otherwise the IP of the peer of the connection is used.
+    pub fn new(
+        verifiers: Vec<Box<dyn Verifier>>,
+        quota: Option<(QuotaStore, u64)>,
+        client_ip_header: Option<HeaderName>,
+    ) -> Self {
+        Self {
+            verifiers,
+            quota: quota.map(|(store, limit)| (Mutex::new(store), limit)),
+            client_ip_header,
+        }
+    }
+
+    fn client_ip<B>(&self, request: &Request<B>) -> Option<IpAddr> {
+        if let Some(name) = &self.client_ip_header {
+            // Proxies append the addresses they forward for, the first one is the client.
+            return header_str(request.headers(), name.as_str())
+                .and_then(|value| value.split(',').next())
+                .and_then(|ip| ip.trim().parse().ok());
+        }
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip())
+    }
+
+    /// Identity of the client making `request`.
+    pub async fn identify(&self, request: &AuthRequest<'_>) -> Result<String, FaucetError> {
+        if self.verifiers.is_empty() {
+            return Ok(match request.client_ip {
+                Some(ip) => format!("ip:{ip}"),
+                None => "ip:unknown".to_string(),
+            });
+        }
+        for verifier in &self.verifiers {
+            if let Some(identity) = verifier.verify(request).await? {
+                return Ok(identity);
+            }
+        }
+        Err(FaucetError::Unauthorized("missing credentials".into()))
+    }
+
+    /// Counts a request of `identity` against its daily limit.
+    pub fn charge(&self, identity: &str) -> Result<(), FaucetError> {
+        let Some((store, limit)) = &self.quota else {
+            return Ok(());
+        };
+        let day = unix_time().as_secs() / SECONDS_PER_DAY;
+        let acquired = store
+            .lock()
+            .unwrap()
+            .try_acquire(identity, day, *limit)
+            .map_err(FaucetError::internal)?;
+        if !acquired {
+            return Err(FaucetError::QuotaExceeded(*limit));
+        }
+        Ok(())
+    }
+}
+
+/// Middleware authenticating the requests for gas, and counting them against the daily limit of
+/// their client.
+pub async fn authenticate(
+    State(auth): State<Arc<FaucetAuth>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let client_ip = auth.client_ip(&request);
+    let (parts, body) = request.into_parts();
+    // Signatures cover the body, so it is read here and handed over to the handler afterwards.
+    let body: Bytes = match hyper::body::to_bytes(body).await {
+        Ok(body) => body,
+        Err(e) => {
+            let error = FaucetError::Unauthorized(format!("cannot read request: {e}"));
+            return reject(StatusCode::BAD_REQUEST, error);
+        }
+    };
+
+    let auth_request = AuthRequest {
+        headers: &parts.headers,
+        body: &body,
+        client_ip,
+    };
+    let identity = match auth.identify(&auth_request).await {
+        Ok(identity) => identity,
+        Err(e @ FaucetError::Unauthorized(_)) => {
+            warn!(?client_ip, "Rejected gas request: {e}");
+            return reject(StatusCode::UNAUTHORIZED, e);
+        }
+        Err(e) => return reject(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+    match auth.charge(&identity) {
+        Ok(()) => {}
+        Err(e @ FaucetError::QuotaExceeded(_)) => {
+            info!(%identity, "Gas request over the daily limit");
+            return reject(StatusCode::TOO_MANY_REQUESTS, e);
+        }
+        Err(e) => return reject(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+fn reject(status: StatusCode, error: FaucetError) -> Response {
+    (status, Json(FaucetResponse::from(error))).into_response()
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn unix_time() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+    use fastcrypto::ed25519::Ed25519KeyPair;
+    use fastcrypto::traits::{KeyPair, Signer};
+    use sui_types::crypto::get_key_pair;
+
+    use super::*;
+
+    fn discord_request(key: &Ed25519KeyPair, timestamp: u64, user: &str, body: &[u8]) -> HeaderMap {
+        let timestamp = timestamp.to_string();
+        let signature: Ed25519Signature =
+            key.sign(&DiscordVerifier::signed_message(&timestamp, user, body));
+        let mut headers = HeaderMap::new();
+        let mut insert = |name: &'static str, value: String| {
+            headers.insert(name, HeaderValue::from_str(&value).unwrap());
+        };
+        insert(DISCORD_SIGNATURE_HEADER, Hex::encode(signature.as_ref()));
+        insert(DISCORD_TIMESTAMP_HEADER, timestamp);
+        insert(DISCORD_USER_HEADER, user.to_string());
+        headers
+    }
+
+    #[tokio::test]
+    async fn discord_verifier() {
+        let (_, key): (_, Ed25519KeyPair) = get_key_pair();
+        let verifier = DiscordVerifier::new(key.public().clone(), Duration::from_secs(60));
+        let body = br#"{"FixedAmountRequest":{"recipient":"0x1"}}"#;
+        let now = unix_time().as_secs();
+
+        let headers = discord_request(&key, now, "1234", body);
+        let request = AuthRequest {
+            headers: &headers,
+            body,
+            client_ip: None,
+        };
+        assert_eq!(
+            verifier.verify(&request).await,
+            Ok(Some("discord:1234".to_string()))
+        );
+
+        // The signature covers the body.
+        let request = AuthRequest {
+            headers: &headers,
+            body: b"{}",
+            client_ip: None,
+        };
+        assert!(matches!(
+            verifier.verify(&request).await,
+            Err(FaucetError::Unauthorized(_))
+        ));
+
+        // Old signatures cannot be replayed.
+        let headers = discord_request(&key, now - 3600, "1234", body);
+        let request = AuthRequest {
+            headers: &headers,
+            body,
+            client_ip: None,
+        };
+        assert!(matches!(
+            verifier.verify(&request).await,
+            Err(FaucetError::Unauthorized(_))
+        ));
+
+        // Requests without a signature are left to the other verifiers.
+        let headers = HeaderMap::new();
+        let request = AuthRequest {
+            headers: &headers,
+            body,
+            client_ip: None,
+        };
+        assert_eq!(verifier.verify(&request).await, Ok(None));
+    }
+
+    #[tokio::test]
+    async fn daily_limit_per_identity() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = QuotaStore::open(&tmp.path().join("quotas"));
+        let auth = FaucetAuth::new(vec![], Some((store, 1)), None);
+
+        let headers = HeaderMap::new();
+        let request = AuthRequest {
+            headers: &headers,
+            body: b"",
+            client_ip: Some("10.0.0.1".parse().unwrap()),
+        };
+        let identity = auth.identify(&request).await.unwrap();
+        assert_eq!(identity, "ip:10.0.0.1");
+        assert_eq!(auth.charge(&identity), Ok(()));
+        assert_eq!(auth.charge(&identity), Err(FaucetError::QuotaExceeded(1)));
+        assert_eq!(auth.charge("ip:10.0.0.2"), Ok(()));
+    }
+}
@@ -3,7 +3,7 @@
 
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum FaucetError {
     #[error("Faucet cannot read objects from fullnode: {0}")]
     FullnodeReadingError(String),
@@ -25,6 +25,15 @@ pub enum FaucetError {
     #[error("Timed out waiting for a coin from the gas coin pool")]
     NoGasCoinAvailable,
 
+    #[error("Too many requests are waiting to be served, please try again later")]
+    QueueFull,
+
+    #[error("Request is not authenticated: {0}")]
+    Unauthorized(String),
+
+    #[error("Daily limit of {0} requests reached, please try again tomorrow")]
+    QuotaExceeded(u64),
+
     #[error("Wallet Error: `{0}`")]
     Wallet(String),
 
@@ -0,0 +1,362 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use mysten_metrics::spawn_monitored_task;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{Faucet, FaucetError, FaucetReceipt, SimpleFaucet};
+
+#[derive(Clone, Debug)]
+pub struct BatchFaucetConfig {
+    /// Maximum number of requests served by one transaction.
+    pub max_batch_size: usize,
+    /// How long the first request of a batch waits for more requests to join it.
+    pub batch_window: Duration,
+    /// Number of finished requests whose status is kept for lookups, which is also the maximum
+    /// number of queued requests.
+    pub max_statuses: usize,
+}
+
+impl Default for BatchFaucetConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 20,
+            batch_window: Duration::from_millis(500),
+            max_statuses: 10_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchRequest {
+    pub id: Uuid,
+    pub recipient: SuiAddress,
+    pub amounts: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchSendStatus {
+    Enqueued,
+    InProgress,
+    Succeeded(FaucetReceipt),
+    Failed(String),
+}
+
+struct QueuedRequest {
+    request: BatchRequest,
+    reply: Option<oneshot::Sender<Result<FaucetReceipt, FaucetError>>>,
+}
+
+/// Queue in front of a `SimpleFaucet`, serving up to `max_batch_size` of the requests it receives
+/// with a single transaction, so that each gas coin of the faucet serves many requests at once.
+///
+/// Requests are served in the order they are received, except that two requests for the same
+/// recipient are never part of the same batch: the later one is deferred to the next batch. The
+/// status of every request can be looked up by its id while it is queued, and after it finished
+/// until `max_statuses` more recent requests finished.
+pub struct BatchFaucet {
+    sender: mpsc::Sender<QueuedRequest>,
+    statuses: Arc<Mutex<RequestStatuses>>,
+}
+
+impl BatchFaucet {
+    pub fn new(faucet: Arc<SimpleFaucet>, config: BatchFaucetConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.max_statuses.max(1));
+        let statuses = Arc::new(Mutex::new(RequestStatuses::new(config.max_statuses)));
+        spawn_monitored_task!(Self::run(faucet, config, receiver, statuses.clone()));
+        Self { sender, statuses }
+    }
+
+    /// Queues `request`, to be served by one of the next batches. Queuing a request whose id is
+    /// already known has no effect, and returns its current status instead, so that clients can
+    /// safely retry.
+    pub fn enqueue(&self, request: BatchRequest) -> Result<BatchSendStatus, FaucetError> {
+        Ok(self
+            .push(QueuedRequest {
+                request,
+                reply: None,
+            })?
+            .unwrap_or(BatchSendStatus::Enqueued))
+    }
+
+    /// Status of the request `id`, or `None` if it is unknown or was forgotten.
+    pub fn status(&self, id: &Uuid) -> Option<BatchSendStatus> {
+        self.statuses.lock().unwrap().get(id)
+    }
+
+    /// Queues `queued`, unless a request with the same id is already known, in which case its
+    /// status is returned.
+    fn push(&self, queued: QueuedRequest) -> Result<Option<BatchSendStatus>, FaucetError> {
+        let id = queued.request.id;
+        let mut statuses = self.statuses.lock().unwrap();
+        if let Some(status) = statuses.get(&id) {
+            return Ok(Some(status));
+        }
+        self.sender
+            .try_send(queued)
+            .map_err(|_| FaucetError::QueueFull)?;
+        statuses.set(id, BatchSendStatus::Enqueued);
+        Ok(None)
+    }
+
+    async fn run(
+        faucet: Arc<SimpleFaucet>,
+        config: BatchFaucetConfig,
+        mut receiver: mpsc::Receiver<QueuedRequest>,
+        statuses: Arc<Mutex<RequestStatuses>>,
+    ) {
+        // Requests left out of the previous batches because their recipient was already in them.
+        let mut deferred: VecDeque<QueuedRequest> = VecDeque::new();
+        loop {
+            let first = match deferred.pop_front() {
+                Some(queued) => queued,
+                None => match receiver.recv().await {
+                    Some(queued) => queued,
+                    None => break,
+                },
+            };
+
+            let mut recipients = HashSet::from([first.request.recipient]);
+            let mut batch = vec![first];
+            let mut next_deferred = VecDeque::new();
+            let window = tokio::time::sleep(config.batch_window);
+            tokio::pin!(window);
+            while batch.len() < config.max_batch_size {
+                let queued = match deferred.pop_front() {
+                    Some(queued) => queued,
+                    None => tokio::select! {
+                        Some(queued) = receiver.recv() => queued,
+                        _ = &mut window => break,
+                    },
+                };
+                if recipients.insert(queued.request.recipient) {
+                    batch.push(queued);
+                } else {
+                    next_deferred.push_back(queued);
+                }
+            }
+            next_deferred.extend(deferred.drain(..));
+            deferred = next_deferred;
+
+            let batch_id = Uuid::new_v4();
+            {
+                let mut statuses = statuses.lock().unwrap();
+                for queued in &batch {
+                    statuses.set(queued.request.id, BatchSendStatus::InProgress);
+                }
+            }
+            faucet.metrics().batch_size.observe(batch.len() as f64);
+
+            // Batches are served concurrently, each with its own gas coin.
+            let faucet = faucet.clone();
+            let statuses = statuses.clone();
+            spawn_monitored_task!(Self::serve(faucet, batch_id, batch, statuses));
+        }
+    }
+
+    async fn serve(
+        faucet: Arc<SimpleFaucet>,
+        batch_id: Uuid,
+        batch: Vec<QueuedRequest>,
+        statuses: Arc<Mutex<RequestStatuses>>,
+    ) {
+        let requests: Vec<_> = batch.iter().map(|queued| queued.request.clone()).collect();
+        let ids: Vec<_> = requests.iter().map(|request| request.id).collect();
+        info!(?batch_id, ?ids, "Serving batch of faucet requests");
+
+        let results = match faucet.batch_send(batch_id, &requests).await {
+            Ok(receipts) => receipts.into_iter().map(Ok).collect(),
+            Err(e) => {
+                warn!(?batch_id, "Failed to serve batch of faucet requests: {e:?}");
+                vec![Err(e); batch.len()]
+            }
+        };
+
+        let mut statuses = statuses.lock().unwrap();
+        for (queued, result) in batch.into_iter().zip(results) {
+            let status = match &result {
+                Ok(receipt) => BatchSendStatus::Succeeded(receipt.clone()),
+                Err(e) => BatchSendStatus::Failed(e.to_string()),
+            };
+            statuses.finish(queued.request.id, status);
+            if let Some(reply) = queued.reply {
+                // The client may have gone away, its request is still served.
+                let _ = reply.send(result);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Faucet for BatchFaucet {
+    async fn send(
+        &self,
+        id: Uuid,
+        recipient: SuiAddress,
+        amounts: &[u64],
+    ) -> Result<FaucetReceipt, FaucetError> {
+        let (reply, receiver) = oneshot::channel();
+        let queued = QueuedRequest {
+            request: BatchRequest {
+                id,
+                recipient,
+                amounts: amounts.to_vec(),
+            },
+            reply: Some(reply),
+        };
+        if self.push(queued)?.is_some() {
+            return Err(FaucetError::internal(format!(
+                "request {id} has already been received"
+            )));
+        }
+        receiver
+            .await
+            .map_err(|_| FaucetError::internal("batch faucet stopped"))?
+    }
+}
+
+/// Statuses of the queued and in progress requests, and of the most recently finished ones.
+struct RequestStatuses {
+    statuses: HashMap<Uuid, BatchSendStatus>,
+    /// Finished requests, from the oldest to the most recent.
+    finished: VecDeque<Uuid>,
+    max_finished: usize,
+}
+
+impl RequestStatuses {
+    fn new(max_finished: usize) -> Self {
+        Self {
+            statuses: HashMap::new(),
+            finished: VecDeque::new(),
+            max_finished,
+        }
+    }
+
+    fn get(&self, id: &Uuid) -> Option<BatchSendStatus> {
+        self.statuses.get(id).cloned()
+    }
+
+    fn set(&mut self, id: Uuid, status: BatchSendStatus) {
+        self.statuses.insert(id, status);
+    }
+
+    fn finish(&mut self, id: Uuid, status: BatchSendStatus) {
+        self.statuses.insert(id, status);
+        self.finished.push_back(id);
+        while self.finished.len() > self.max_finished {
+            if let Some(oldest) = self.finished.pop_front() {
+                self.statuses.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prometheus::Registry;
+    use test_utils::network::TestClusterBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_request_statuses_forget_oldest() {
+        let mut statuses = RequestStatuses::new(2);
+        let ids: Vec<_> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for id in &ids {
+            statuses.set(*id, BatchSendStatus::Enqueued);
+        }
+        for id in &ids {
+            statuses.finish(*id, BatchSendStatus::Failed("error".into()));
+        }
+        assert!(statuses.get(&ids[0]).is_none());
+        assert!(matches!(
+            statuses.get(&ids[2]),
+            Some(BatchSendStatus::Failed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batch_faucet() {
+        telemetry_subscribers::init_for_testing();
+        let test_cluster = TestClusterBuilder::new().build().await.unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let prom_registry = Registry::new();
+        let faucet = SimpleFaucet::new(
+            test_cluster.wallet,
+            &prom_registry,
+            &tmp.path().join("faucet.wal"),
+        )
+        .await
+        .unwrap();
+        let batch_faucet = BatchFaucet::new(
+            Arc::new(faucet),
+            BatchFaucetConfig {
+                max_batch_size: 10,
+                batch_window: Duration::from_secs(1),
+                ..Default::default()
+            },
+        );
+
+        // The last request is for the same recipient as the first one, so it is served by a
+        // different transaction.
+        let mut recipients: Vec<_> = (0..4)
+            .map(|_| SuiAddress::random_for_testing_only())
+            .collect();
+        recipients.push(recipients[0]);
+        let receipts = futures::future::join_all(
+            recipients
+                .iter()
+                .map(|recipient| batch_faucet.send(Uuid::new_v4(), *recipient, &[1, 2])),
+        )
+        .await
+        .into_iter()
+        .map(|receipt| receipt.unwrap())
+        .collect::<Vec<_>>();
+
+        let digests: HashSet<_> = receipts[..4]
+            .iter()
+            .flat_map(|receipt| receipt.sent.iter().map(|coin| coin.transfer_tx_digest))
+            .collect();
+        assert_eq!(digests.len(), 1);
+        assert!(!digests.contains(&receipts[4].sent[0].transfer_tx_digest));
+        for receipt in &receipts {
+            let mut amounts: Vec<_> = receipt.sent.iter().map(|coin| coin.amount).collect();
+            amounts.sort_unstable();
+            assert_eq!(amounts, vec![1, 2]);
+        }
+
+        // Requests can be looked up after they are served, and queuing them again does not send
+        // more coins.
+        let request = BatchRequest {
+            id: Uuid::new_v4(),
+            recipient: SuiAddress::random_for_testing_only(),
+            amounts: vec![1],
+        };
+        batch_faucet.enqueue(request.clone()).unwrap();
+        let receipt = loop {
+            match batch_faucet.status(&request.id) {
+                Some(BatchSendStatus::Succeeded(receipt)) => break receipt,
+                Some(BatchSendStatus::Failed(e)) => panic!("request failed: {e}"),
+                _ => tokio::time::sleep(Duration::from_millis(100)).await,
+            }
+        };
+        let Ok(BatchSendStatus::Succeeded(again)) = batch_faucet.enqueue(request) else {
+            panic!("expected the receipt of the served request");
+        };
+        assert_eq!(
+            receipt.sent[0].transfer_tx_digest,
+            again.sent[0].transfer_tx_digest
+        );
+    }
+}
@@ -6,8 +6,10 @@ use serde::{Deserialize, Serialize};
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use uuid::Uuid;
 
+mod batch_faucet;
 mod simple_faucet;
 mod write_ahead_log;
+pub use self::batch_faucet::{BatchFaucet, BatchFaucetConfig, BatchRequest, BatchSendStatus};
 pub use self::simple_faucet::SimpleFaucet;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -7,8 +7,7 @@ use async_trait::async_trait;
 use prometheus::Registry;
 use tap::tap::TapFallible;
 
-#[cfg(test)]
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::path::Path;
 
@@ -33,7 +32,7 @@ use tokio::time::{timeout, Duration};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use crate::{CoinInfo, Faucet, FaucetError, FaucetReceipt};
+use crate::{BatchRequest, CoinInfo, Faucet, FaucetError, FaucetReceipt};
 
 use super::write_ahead_log::WriteAheadLog;
 
@@ -129,6 +128,71 @@ impl SimpleFaucet {
         Ok(faucet)
     }
 
+    pub(crate) fn metrics(&self) -> &FaucetMetrics {
+        &self.metrics
+    }
+
+    /// Serves all `requests` with a single PaySui transaction, splitting the coins they ask for
+    /// off the same gas coin. The recipients of `requests` must be distinct, so that the coins
+    /// created by the transaction can be attributed to their request.
+    pub async fn batch_send(
+        &self,
+        batch_id: Uuid,
+        requests: &[BatchRequest],
+    ) -> Result<Vec<FaucetReceipt>, FaucetError> {
+        let recipients: HashSet<_> = requests.iter().map(|request| request.recipient).collect();
+        if recipients.len() != requests.len() {
+            return Err(FaucetError::internal(format!(
+                "batch {batch_id} has several requests for the same recipient"
+            )));
+        }
+        info!(
+            ?batch_id,
+            requests = requests.len(),
+            "Getting faucet requests"
+        );
+
+        let (digest, mut coin_ids) = self.transfer_gases(requests, batch_id).await?;
+
+        info!(?batch_id, ?digest, "PaySui txn succeeded");
+        let receipts = requests.iter().map(|request| {
+            let coin_ids = coin_ids.remove(&request.recipient).unwrap_or_default();
+            self.receipt(request, digest, coin_ids)
+        });
+        Ok(futures::future::join_all(receipts).await)
+    }
+
+    async fn receipt(
+        &self,
+        request: &BatchRequest,
+        digest: TransactionDigest,
+        coin_ids: Vec<ObjectID>,
+    ) -> FaucetReceipt {
+        let mut sent = Vec::with_capacity(coin_ids.len());
+        let coin_results =
+            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin(*coin_id))).await;
+        for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
+            let amount = if let Ok(Some((_, coin))) = res {
+                coin.value()
+            } else {
+                info!(
+                    recipient = ?request.recipient,
+                    ?coin_id,
+                    uuid = ?request.id,
+                    "Could not find coin after successful transaction, error: {:?}",
+                    &res,
+                );
+                0
+            };
+            sent.push(CoinInfo {
+                transfer_tx_digest: digest,
+                amount,
+                id: coin_id,
+            });
+        }
+        FaucetReceipt { sent }
+    }
+
     /// Take the consumer lock and pull a Coin ID from the queue, without checking whether it is
     /// valid or not.
     async fn pop_gas_coin(&self, uuid: Uuid) -> Option<ObjectID> {
@@ -291,28 +355,49 @@ impl SimpleFaucet {
         }
     }
 
+    /// Transfers the coins of all `requests` in one transaction, returning the coins created for
+    /// each recipient.
     async fn transfer_gases(
         &self,
-        amounts: &[u64],
-        recipient: SuiAddress,
+        requests: &[BatchRequest],
         uuid: Uuid,
-    ) -> Result<(TransactionDigest, Vec<ObjectID>), FaucetError> {
+    ) -> Result<(TransactionDigest, HashMap<SuiAddress, Vec<ObjectID>>), FaucetError> {
+        let Some(recipient) = requests.first().map(|request| request.recipient) else {
+            return Err(FaucetError::internal(format!("batch {uuid} is empty")));
+        };
+        let (recipients, amounts): (Vec<_>, Vec<_>) = requests
+            .iter()
+            .flat_map(|request| {
+                request
+                    .amounts
+                    .iter()
+                    .map(move |amount| (request.recipient, *amount))
+            })
+            .unzip();
         let number_of_coins = amounts.len();
         let total_amount: u64 = amounts.iter().sum();
-        let gas_cost = self.get_gas_cost().await?;
+        // Every request adds its own split and transfer to the transaction.
+        let gas_cost = self.get_gas_cost().await? * requests.len() as u64;
 
         let gas_coin_response = self.prepare_gas_coin(total_amount + gas_cost, uuid).await;
         match gas_coin_response {
             GasCoinResponse::ValidGasCoin(coin_id) => {
                 let tx_data = self
-                    .build_pay_sui_txn(coin_id, self.active_address, recipient, amounts, gas_cost)
+                    .build_pay_sui_txn(
+                        coin_id,
+                        self.active_address,
+                        recipients.clone(),
+                        amounts,
+                        gas_cost,
+                    )
                     .await
                     .map_err(FaucetError::internal)?;
 
                 {
                     // Register the intention to send this transaction before we send it, so that if
                     // faucet fails or we give up before we get a definite response, we have a
-                    // chance to retry later.
+                    // chance to retry later. Batches are logged under the recipient of their first
+                    // request.
                     let mut wal = self.wal.lock().await;
                     wal.reserve(uuid, coin_id, recipient, tx_data.clone())
                         .map_err(FaucetError::internal)?;
@@ -321,7 +406,7 @@ impl SimpleFaucet {
                     .sign_and_execute_txn(uuid, recipient, coin_id, tx_data)
                     .await?;
 
-                self.check_and_map_transfer_gas_result(response, number_of_coins, recipient)
+                self.check_and_map_transfer_gas_result(response, number_of_coins, &recipients)
                     .await
             }
 
@@ -450,16 +535,14 @@ impl SimpleFaucet {
         &self,
         coin_id: ObjectID,
         signer: SuiAddress,
-        recipient: SuiAddress,
-        amounts: &[u64],
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
         budget: u64,
     ) -> Result<TransactionData, anyhow::Error> {
-        let recipients: Vec<SuiAddress> =
-            std::iter::repeat(recipient).take(amounts.len()).collect();
         let client = self.wallet.get_client().await?;
         client
             .transaction_builder()
-            .pay_sui(signer, vec![coin_id], recipients, amounts.to_vec(), budget)
+            .pay_sui(signer, vec![coin_id], recipients, amounts, budget)
             .await
             .map_err(|e| {
                 anyhow::anyhow!(
@@ -474,8 +557,8 @@ impl SimpleFaucet {
         &self,
         res: SuiTransactionResponse,
         number_of_coins: usize,
-        recipient: SuiAddress,
-    ) -> Result<(TransactionDigest, Vec<ObjectID>), FaucetError> {
+        recipients: &[SuiAddress],
+    ) -> Result<(TransactionDigest, HashMap<SuiAddress, Vec<ObjectID>>), FaucetError> {
         let created = res
             .effects
             .ok_or_else(|| {
@@ -492,13 +575,17 @@ impl SimpleFaucet {
                 number_of_coins, created
             );
         }
-        assert!(created
-            .iter()
-            .all(|created_coin_owner_ref| created_coin_owner_ref.owner == recipient));
-        let coin_ids: Vec<ObjectID> = created
-            .iter()
-            .map(|created_coin_owner_ref| created_coin_owner_ref.reference.object_id)
-            .collect();
+        let mut coin_ids: HashMap<SuiAddress, Vec<ObjectID>> = HashMap::new();
+        for created_coin_owner_ref in &created {
+            let recipient = recipients
+                .iter()
+                .find(|recipient| created_coin_owner_ref.owner == **recipient)
+                .expect("PaySui Transaction should only create coins owned by its recipients");
+            coin_ids
+                .entry(*recipient)
+                .or_default()
+                .push(created_coin_owner_ref.reference.object_id);
+        }
         Ok((res.digest, coin_ids))
     }
 
@@ -536,34 +623,13 @@ impl Faucet for SimpleFaucet {
         recipient: SuiAddress,
         amounts: &[u64],
     ) -> Result<FaucetReceipt, FaucetError> {
-        info!(?recipient, uuid = ?id, "Getting faucet requests");
-
-        let (digest, coin_ids) = self.transfer_gases(amounts, recipient, id).await?;
-
-        info!(uuid = ?id, ?recipient, ?digest, "PaySui txn succeeded");
-        let mut sent = Vec::with_capacity(coin_ids.len());
-        let coin_results =
-            futures::future::join_all(coin_ids.iter().map(|coin_id| self.get_coin(*coin_id))).await;
-        for (coin_id, res) in coin_ids.into_iter().zip(coin_results) {
-            let amount = if let Ok(Some((_, coin))) = res {
-                coin.value()
-            } else {
-                info!(
-                    ?recipient,
-                    ?coin_id,
-                    uuid = ?id,
-                    "Could not find coin after successful transaction, error: {:?}",
-                    &res,
-                );
-                0
-            };
-            sent.push(CoinInfo {
-                transfer_tx_digest: digest,
-                amount,
-                id: coin_id,
-            });
-        }
-        Ok(FaucetReceipt { sent })
+        let request = BatchRequest {
+            id,
+            recipient,
+            amounts: amounts.to_vec(),
+        };
+        let mut receipts = self.batch_send(id, &[request]).await?;
+        Ok(receipts.pop().expect("a receipt for every request"))
     }
 }
 
@@ -1,16 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod auth;
 mod errors;
 mod faucet;
 mod metrics;
+mod quota;
 mod requests;
 mod responses;
 
 pub mod metrics_layer;
 pub use metrics_layer::*;
 
+pub use auth::*;
 pub use errors::FaucetError;
 pub use faucet::*;
+pub use quota::QuotaStore;
 pub use requests::*;
 pub use responses::*;
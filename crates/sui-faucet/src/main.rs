@@ -3,14 +3,15 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
-    http::StatusCode,
+    extract::Path,
+    http::{HeaderName, StatusCode},
+    middleware,
     response::IntoResponse,
     routing::{get, post},
     BoxError, Extension, Json, Router,
 };
 use clap::Parser;
 use http::Method;
-use mysten_metrics::spawn_monitored_task;
 use std::{
     borrow::Cow,
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -20,13 +21,19 @@ use std::{
 use std::{env, path::PathBuf};
 use sui::client_commands::WalletContext;
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
-use sui_faucet::{Faucet, FaucetRequest, FaucetResponse, RequestMetricsLayer, SimpleFaucet};
+use sui_faucet::{
+    authenticate, BatchFaucet, BatchFaucetConfig, BatchFaucetResponse, BatchRequest,
+    BatchStatusFaucetResponse, CaptchaProvider, CaptchaVerifier, DiscordVerifier, Faucet,
+    FaucetAuth, FaucetError, FaucetRequest, FaucetResponse, OAuthVerifier, QuotaStore,
+    RequestMetricsLayer, SimpleFaucet, Verifier,
+};
 use tower::{limit::RateLimitLayer, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
 use uuid::Uuid;
 
 const CONCURRENCY_LIMIT: usize = 30;
+const DISCORD_SIGNATURE_MAX_AGE: Duration = Duration::from_secs(5 * 60);
 
 #[derive(Parser)]
 #[clap(
@@ -56,11 +63,43 @@ struct FaucetConfig {
     #[clap(long, default_value_t = 60)]
     wallet_client_timeout_secs: u64,
 
+    /// Maximum number of requests served by a single transaction.
+    #[clap(long, default_value_t = 20)]
+    max_batch_size: usize,
+
+    /// How long a request waits for more requests to be batched with.
+    #[clap(long, default_value_t = 500)]
+    batch_window_ms: u64,
+
+    /// Number of served requests whose status can still be looked up.
+    #[clap(long, default_value_t = 10_000)]
+    max_request_statuses: usize,
+
     #[clap(long)]
     write_ahead_log: PathBuf,
+
+    /// Hex encoded ed25519 public key of the Discord bot allowed to request gas on behalf of
+    /// Discord users.
+    #[clap(long)]
+    discord_public_key: Option<String>,
+
+    /// User info endpoint of the OAuth identity provider that bearer tokens are checked against.
+    #[clap(long)]
+    oauth_userinfo_url: Option<String>,
+
+    /// Header with the IP of the client, set by the load balancer in front of the faucet.
+    #[clap(long)]
+    client_ip_header: Option<String>,
+
+    /// Maximum number of gas requests per client identity and day, counted in `quota-store`.
+    #[clap(long)]
+    daily_request_limit: Option<u64>,
+
+    #[clap(long)]
+    quota_store: Option<PathBuf>,
 }
 
-struct AppState<F = SimpleFaucet> {
+struct AppState<F = BatchFaucet> {
     faucet: F,
     config: FaucetConfig,
     // TODO: add counter
@@ -88,10 +127,14 @@ async fn main() -> Result<(), anyhow::Error> {
         request_buffer_size,
         max_request_per_second,
         wallet_client_timeout_secs,
+        max_batch_size,
+        batch_window_ms,
+        max_request_statuses,
         ref write_ahead_log,
         ..
     } = config;
 
+    let auth = Arc::new(create_auth(&config)?);
     let context = create_wallet_context(wallet_client_timeout_secs).await?;
 
     let prom_binding = PROM_PORT_ADDR.parse().unwrap();
@@ -99,10 +142,18 @@ async fn main() -> Result<(), anyhow::Error> {
     let registry_service = sui_node::metrics::start_prometheus_server(prom_binding);
     let prometheus_registry = registry_service.default_registry();
 
+    let simple_faucet = SimpleFaucet::new(context, &prometheus_registry, write_ahead_log)
+        .await
+        .unwrap();
     let app_state = Arc::new(AppState {
-        faucet: SimpleFaucet::new(context, &prometheus_registry, write_ahead_log)
-            .await
-            .unwrap(),
+        faucet: BatchFaucet::new(
+            Arc::new(simple_faucet),
+            BatchFaucetConfig {
+                max_batch_size,
+                batch_window: Duration::from_millis(batch_window_ms),
+                max_statuses: max_request_statuses,
+            },
+        ),
         config,
     });
 
@@ -112,9 +163,15 @@ async fn main() -> Result<(), anyhow::Error> {
         .allow_headers(Any)
         .allow_origin(Any);
 
+    let gas_routes = Router::new()
+        .route("/gas", post(request_gas))
+        .route("/v1/gas", post(batch_request_gas))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate));
+
     let app = Router::new()
         .route("/", get(health))
-        .route("/gas", post(request_gas))
+        .merge(gas_routes)
+        .route("/v1/status/:task_id", get(request_status))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -134,7 +191,7 @@ async fn main() -> Result<(), anyhow::Error> {
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -153,21 +210,17 @@ async fn request_gas(
     let id = Uuid::new_v4();
     info!(uuid = ?id, "Got new gas request.");
     let result = match payload {
+        // The request is served by a batch, in its own task, so a connection drop will not
+        // interrupt it and impact the recycling of coins.
         FaucetRequest::FixedAmountRequest(requests) => {
-            // We spawn a tokio task for this such that connection drop will not interrupt
-            // it and impact the reclycing of coins
-            spawn_monitored_task!(async move {
-                state
-                    .faucet
-                    .send(
-                        id,
-                        requests.recipient,
-                        &vec![state.config.amount; state.config.num_coins],
-                    )
-                    .await
-            })
-            .await
-            .unwrap()
+            state
+                .faucet
+                .send(
+                    id,
+                    requests.recipient,
+                    &vec![state.config.amount; state.config.num_coins],
+                )
+                .await
         }
     };
     match result {
@@ -185,6 +238,102 @@ async fn request_gas(
     }
 }
 
+/// handler for requests queued to be served by a batch, answered with the id of the request
+async fn batch_request_gas(
+    Extension(state): Extension<Arc<AppState>>,
+    Json(payload): Json<FaucetRequest>,
+) -> impl IntoResponse {
+    let id = Uuid::new_v4();
+    info!(uuid = ?id, "Got new batch gas request.");
+    let FaucetRequest::FixedAmountRequest(request) = payload;
+    let result = state.faucet.enqueue(BatchRequest {
+        id,
+        recipient: request.recipient,
+        amounts: vec![state.config.amount; state.config.num_coins],
+    });
+    match result {
+        Ok(_) => (StatusCode::ACCEPTED, Json(BatchFaucetResponse::from(id))),
+        Err(v) => {
+            warn!(uuid = ?id, "Failed to queue gas request: {:?}", v);
+            let status = match v {
+                FaucetError::QueueFull => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(BatchFaucetResponse::from(v)))
+        }
+    }
+}
+
+/// handler for looking up the status, and receipt once served, of a batch gas request
+async fn request_status(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(task_id): Path<String>,
+) -> impl IntoResponse {
+    let Ok(id) = Uuid::parse_str(&task_id) else {
+        let error = FaucetError::Internal(format!("invalid task id {task_id}"));
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(BatchStatusFaucetResponse::from(error)),
+        );
+    };
+    match state.faucet.status(&id) {
+        Some(status) => (
+            StatusCode::OK,
+            Json(BatchStatusFaucetResponse::from(status)),
+        ),
+        None => {
+            let error = FaucetError::Internal(format!("unknown task {id}"));
+            (
+                StatusCode::NOT_FOUND,
+                Json(BatchStatusFaucetResponse::from(error)),
+            )
+        }
+    }
+}
+
+/// Verifiers are enabled by their flags, except for the captcha ones, whose secret keys are read
+/// from the `TURNSTILE_SECRET_KEY` and `HCAPTCHA_SECRET_KEY` environment variables.
+fn create_auth(config: &FaucetConfig) -> Result<FaucetAuth, anyhow::Error> {
+    let mut verifiers: Vec<Box<dyn Verifier>> = vec![];
+    if let Ok(secret) = env::var("TURNSTILE_SECRET_KEY") {
+        verifiers.push(Box::new(CaptchaVerifier::new(
+            CaptchaProvider::Turnstile,
+            secret,
+        )));
+    }
+    if let Ok(secret) = env::var("HCAPTCHA_SECRET_KEY") {
+        verifiers.push(Box::new(CaptchaVerifier::new(
+            CaptchaProvider::HCaptcha,
+            secret,
+        )));
+    }
+    if let Some(url) = &config.oauth_userinfo_url {
+        verifiers.push(Box::new(OAuthVerifier::new(url.clone())));
+    }
+    if let Some(public_key) = &config.discord_public_key {
+        verifiers.push(Box::new(DiscordVerifier::from_hex(
+            public_key,
+            DISCORD_SIGNATURE_MAX_AGE,
+        )?));
+    }
+    info!(
+        "Gas requests are authenticated by {} verifiers",
+        verifiers.len()
+    );
+
+    let quota = match (config.daily_request_limit, &config.quota_store) {
+        (Some(limit), Some(path)) => Some((QuotaStore::open(path), limit)),
+        (Some(_), None) => anyhow::bail!("--daily-request-limit requires --quota-store"),
+        (None, _) => None,
+    };
+    let client_ip_header = config
+        .client_ip_header
+        .as_deref()
+        .map(HeaderName::try_from)
+        .transpose()?;
+    Ok(FaucetAuth::new(verifiers, quota, client_ip_header))
+}
+
 async fn create_wallet_context(timeout_secs: u64) -> Result<WalletContext, anyhow::Error> {
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
     info!("Initialize wallet from config path: {:?}", wallet_conf);
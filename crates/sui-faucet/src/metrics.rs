@@ -27,8 +27,11 @@ pub struct FaucetMetrics {
     pub(crate) current_executions_in_flight: IntGauge,
     pub(crate) total_available_coins: IntGauge,
     pub(crate) total_discarded_coins: IntGauge,
+    pub(crate) batch_size: Histogram,
 }
 
+const BATCH_SIZE_BUCKETS: &[f64] = &[1., 2., 5., 10., 20., 50., 100.];
+
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1., 2.5, 5., 10., 20., 30., 60., 90.,
 ];
@@ -105,6 +108,13 @@ impl FaucetMetrics {
                 registry,
             )
             .unwrap(),
+            batch_size: register_histogram_with_registry!(
+                "batch_size",
+                "Number of requests served by a single transaction",
+                BATCH_SIZE_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
         }
     }
 }
@@ -0,0 +1,91 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use typed_store::rocks::{DBMap, TypedStoreError};
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::Map;
+use typed_store_derive::DBMapUtils;
+
+/// Persistent count of the requests served to each identity on the current day, so that the
+/// daily limits survive restarts of the faucet.
+#[derive(DBMapUtils)]
+pub struct QuotaStore {
+    quotas: DBMap<String, Quota>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Quota {
+    /// Days since the Unix epoch.
+    pub day: u64,
+    pub requests: u64,
+}
+
+impl QuotaStore {
+    pub fn open(path: &Path) -> Self {
+        Self::open_tables_read_write(
+            path.to_path_buf(),
+            typed_store::rocks::MetricConf::default(),
+            None,
+            None,
+        )
+    }
+
+    /// Counts a request of `identity` on `day`. Returns `Ok(false)`, without counting the
+    /// request, if `identity` already made `limit` requests that day.
+    pub fn try_acquire(
+        &mut self,
+        identity: &str,
+        day: u64,
+        limit: u64,
+    ) -> Result<bool, TypedStoreError> {
+        let requests = match self.quotas.get(&identity.to_string())? {
+            Some(quota) if quota.day == day => quota.requests,
+            _ => 0,
+        };
+        if requests >= limit {
+            return Ok(false);
+        }
+        self.quotas.insert(
+            &identity.to_string(),
+            &Quota {
+                day,
+                requests: requests + 1,
+            },
+        )?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn quota_is_per_identity_and_day() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut store = QuotaStore::open(&tmp.path().join("quotas"));
+
+        assert!(store.try_acquire("discord:1", 10, 2).unwrap());
+        assert!(store.try_acquire("discord:1", 10, 2).unwrap());
+        assert!(!store.try_acquire("discord:1", 10, 2).unwrap());
+        assert!(store.try_acquire("discord:2", 10, 2).unwrap());
+
+        // Quotas are reset every day.
+        assert!(store.try_acquire("discord:1", 11, 2).unwrap());
+    }
+
+    #[tokio::test]
+    async fn quota_survives_reopening() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("quotas");
+        {
+            let mut store = QuotaStore::open(&path);
+            assert!(store.try_acquire("oauth:user", 10, 1).unwrap());
+        }
+        let mut store = QuotaStore::open(&path);
+        assert!(!store.try_acquire("oauth:user", 10, 1).unwrap());
+    }
+}
@@ -3,6 +3,7 @@
 
 use crate::*;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -11,6 +12,21 @@ pub struct FaucetResponse {
     pub error: Option<String>,
 }
 
+/// Response to a request queued with the batch faucet, with the id to look up its status with.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFaucetResponse {
+    pub task: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchStatusFaucetResponse {
+    pub status: Option<BatchSendStatus>,
+    pub error: Option<String>,
+}
+
 impl From<FaucetError> for FaucetResponse {
     fn from(e: FaucetError) -> Self {
         Self {
@@ -28,3 +44,39 @@ impl From<FaucetReceipt> for FaucetResponse {
         }
     }
 }
+
+impl From<FaucetError> for BatchFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            task: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl From<Uuid> for BatchFaucetResponse {
+    fn from(v: Uuid) -> Self {
+        Self {
+            task: Some(v.to_string()),
+            error: None,
+        }
+    }
+}
+
+impl From<FaucetError> for BatchStatusFaucetResponse {
+    fn from(e: FaucetError) -> Self {
+        Self {
+            status: None,
+            error: Some(e.to_string()),
+        }
+    }
+}
+
+impl From<BatchSendStatus> for BatchStatusFaucetResponse {
+    fn from(v: BatchSendStatus) -> Self {
+        Self {
+            status: Some(v),
+            error: None,
+        }
+    }
+}
@@ -0,0 +1,285 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attribution of transaction execution time between Move bytecode interpretation, native
+//! functions, and object runtime operations.
+//!
+//! Profiling is disabled by default. Once enabled, every native function call made while executing
+//! a transaction is timed, and the times of all transactions are accumulated in a process-wide
+//! profile. Natives of the object runtime (dynamic fields, transfers, events, object ids) are
+//! attributed to object runtime operations rather than to natives, along with finishing the object
+//! runtime at the end of a transaction. The rest of the execution time is attributed to the
+//! interpreter.
+//!
+//! The profile is served by the admin server of the node at `/execution-profile`, rather than by
+//! the JSON-RPC API: profiling slows down the execution of every transaction of the node, and must
+//! not be switched on by its RPC clients.
+
+use move_vm_runtime::native_functions::NativeFunction;
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PROFILE: Lazy<Mutex<ExecutionProfile>> = Lazy::new(Default::default);
+
+thread_local! {
+    /// Profile of the transaction being executed on this thread, if it is profiled.
+    static CURRENT: RefCell<Option<ExecutionProfile>> = RefCell::new(None);
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NativeFunctionProfile {
+    pub calls: u64,
+    pub time: Duration,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionProfile {
+    pub transactions: u64,
+    pub total: Duration,
+    /// Time spent in native functions, object runtime natives excluded.
+    pub natives: Duration,
+    pub object_runtime: Duration,
+    /// Calls and time of each native function, keyed by `address::module::function`.
+    pub native_functions: BTreeMap<String, NativeFunctionProfile>,
+}
+
+impl ExecutionProfile {
+    /// Time spent interpreting Move bytecode.
+    pub fn move_interpreter(&self) -> Duration {
+        self.total
+            .saturating_sub(self.natives)
+            .saturating_sub(self.object_runtime)
+    }
+
+    fn merge(&mut self, other: ExecutionProfile) {
+        self.transactions += other.transactions;
+        self.total += other.total;
+        self.natives += other.natives;
+        self.object_runtime += other.object_runtime;
+        for (name, profile) in other.native_functions {
+            let entry = self.native_functions.entry(name).or_default();
+            entry.calls += profile.calls;
+            entry.time += profile.time;
+        }
+    }
+}
+
+/// Enables or disables profiling. Either way, the accumulated profile is reset.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    reset();
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn reset() {
+    *PROFILE.lock().unwrap() = ExecutionProfile::default();
+}
+
+/// The profile accumulated since profiling was last enabled or reset.
+pub fn snapshot() -> ExecutionProfile {
+    PROFILE.lock().unwrap().clone()
+}
+
+/// Profiles the transaction executing on the current thread until dropped.
+#[must_use]
+pub struct TransactionProfiler {
+    start: Instant,
+}
+
+/// Starts profiling a transaction on the current thread. Returns `None` if profiling is disabled,
+/// or if a transaction is already being profiled on this thread.
+pub fn profile_transaction() -> Option<TransactionProfiler> {
+    if !is_enabled() {
+        return None;
+    }
+    CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        if current.is_some() {
+            return None;
+        }
+        *current = Some(ExecutionProfile::default());
+        Some(TransactionProfiler {
+            start: Instant::now(),
+        })
+    })
+}
+
+impl Drop for TransactionProfiler {
+    fn drop(&mut self) {
+        let Some(mut profile) = CURRENT.with(|current| current.borrow_mut().take()) else {
+            return;
+        };
+        profile.transactions = 1;
+        profile.total = self.start.elapsed();
+        // Profiling may have been disabled and the profile reset while the transaction executed.
+        if is_enabled() {
+            PROFILE.lock().unwrap().merge(profile);
+        }
+    }
+}
+
+/// Attributes time spent on object runtime operations outside of native functions to the
+/// transaction being profiled on the current thread, if any.
+pub fn record_object_runtime(time: Duration) {
+    CURRENT.with(|current| {
+        if let Some(profile) = current.borrow_mut().as_mut() {
+            profile.object_runtime += time;
+        }
+    })
+}
+
+/// Wraps `native` so that its calls are timed while a transaction is profiled.
+pub(crate) fn profiled(
+    name: String,
+    object_runtime: bool,
+    native: NativeFunction,
+) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        if !CURRENT.with(|current| current.borrow().is_some()) {
+            return native(context, ty_args, args);
+        }
+        let start = Instant::now();
+        let result = native(context, ty_args, args);
+        record_native(&name, object_runtime, start.elapsed());
+        result
+    })
+}
+
+/// Attributes a call of the native function `name` to the transaction being profiled on the
+/// current thread, if any.
+fn record_native(name: &str, object_runtime: bool, time: Duration) {
+    CURRENT.with(|current| {
+        if let Some(profile) = current.borrow_mut().as_mut() {
+            if object_runtime {
+                profile.object_runtime += time;
+            } else {
+                profile.natives += time;
+            }
+            let entry = profile
+                .native_functions
+                .entry(name.to_string())
+                .or_default();
+            entry.calls += 1;
+            entry.time += time;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The profile is process-wide, so tests changing it must not run concurrently.
+    static PROFILE_LOCK: Mutex<()> = Mutex::new(());
+
+    fn lock() -> std::sync::MutexGuard<'static, ()> {
+        PROFILE_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_time_attribution() {
+        let _lock = lock();
+        set_enabled(true);
+
+        let profiler = profile_transaction().unwrap();
+        record_native("0x2::hash::blake2b256", false, ms(5));
+        record_native("0x2::hash::blake2b256", false, ms(1));
+        record_native("0x2::dynamic_field::add_child_object", true, ms(2));
+        record_object_runtime(ms(3));
+        // Anything not spent in natives or the object runtime was spent interpreting.
+        std::thread::sleep(ms(20));
+        drop(profiler);
+
+        let profile = snapshot();
+        assert_eq!(profile.transactions, 1);
+        assert_eq!(profile.natives, ms(6));
+        assert_eq!(profile.object_runtime, ms(5));
+        assert!(profile.total >= ms(20));
+        assert_eq!(profile.move_interpreter(), profile.total - ms(11));
+        let hash = profile.native_functions["0x2::hash::blake2b256"];
+        assert_eq!((hash.calls, hash.time), (2, ms(6)));
+        let add_child = profile.native_functions["0x2::dynamic_field::add_child_object"];
+        assert_eq!((add_child.calls, add_child.time), (1, ms(2)));
+
+        // Transactions accumulate into the same profile.
+        let profiler = profile_transaction().unwrap();
+        record_native("0x2::hash::blake2b256", false, ms(4));
+        drop(profiler);
+        let profile = snapshot();
+        assert_eq!(profile.transactions, 2);
+        assert_eq!(profile.natives, ms(10));
+        assert_eq!(profile.native_functions["0x2::hash::blake2b256"].calls, 3);
+
+        set_enabled(false);
+    }
+
+    #[test]
+    fn test_enable_disable_reset() {
+        let _lock = lock();
+        set_enabled(false);
+        assert!(!is_enabled());
+        assert!(profile_transaction().is_none());
+        // Nothing is recorded outside of a profiled transaction.
+        record_native("0x2::hash::blake2b256", false, ms(1));
+        record_object_runtime(ms(1));
+        assert_eq!(snapshot().transactions, 0);
+
+        set_enabled(true);
+        drop(profile_transaction().unwrap());
+        assert_eq!(snapshot().transactions, 1);
+        reset();
+        assert_eq!(snapshot().transactions, 0);
+        assert!(snapshot().native_functions.is_empty());
+
+        // Enabling again resets the profile.
+        drop(profile_transaction().unwrap());
+        set_enabled(true);
+        assert_eq!(snapshot().transactions, 0);
+
+        // A transaction still executing when profiling is disabled is not recorded.
+        let profiler = profile_transaction().unwrap();
+        record_native("0x2::hash::blake2b256", false, ms(1));
+        set_enabled(false);
+        drop(profiler);
+        assert_eq!(snapshot().transactions, 0);
+        assert!(profile_transaction().is_none());
+    }
+
+    #[test]
+    fn test_nested_profiling() {
+        let _lock = lock();
+        set_enabled(true);
+
+        let outer = profile_transaction().unwrap();
+        // A transaction executed while another one is profiled on the same thread, e.g. a dev
+        // inspect run from a native, is part of the outer transaction.
+        let inner = profile_transaction();
+        assert!(inner.is_none());
+        record_native("0x2::hash::blake2b256", false, ms(1));
+        drop(inner);
+        record_native("0x2::hash::blake2b256", false, ms(1));
+        drop(outer);
+
+        let profile = snapshot();
+        assert_eq!(profile.transactions, 1);
+        assert_eq!(profile.natives, ms(2));
+
+        // Once the outer transaction finished, the next one is profiled again.
+        drop(profile_transaction().unwrap());
+        assert_eq!(snapshot().transactions, 2);
+
+        set_enabled(false);
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collection of the [`ExecutionTrace`] of a transaction executed on the current thread.
+//!
+//! Unlike the execution profile, tracing is requested per transaction: the caller starts a trace
+//! before executing the transaction and finishes it on the same thread once executed. The adapter
+//! records the commands and gas charges, and every native function call made while a command
+//! executes is recorded under it.
+
+use move_core_types::gas_algebra::InternalGas;
+use move_vm_runtime::native_functions::NativeFunction;
+use std::cell::RefCell;
+use std::sync::Arc;
+use sui_types::execution_trace::{CommandTrace, ExecutionTrace, MoveCallTrace, NativeCallTrace};
+use sui_types::gas::GasUnits;
+
+thread_local! {
+    /// Trace of the transaction being executed on this thread, if it is traced.
+    static CURRENT: RefCell<Option<TraceState>> = RefCell::new(None);
+}
+
+struct TraceState {
+    trace: ExecutionTrace,
+    /// Computation gas remaining when the current command started.
+    command_start_gas: u64,
+}
+
+/// Traces the transaction executing on the current thread until finished or dropped.
+#[must_use]
+pub struct TransactionTracer {
+    _private: (),
+}
+
+/// Starts tracing a transaction on the current thread, replacing any unfinished trace.
+pub fn trace_transaction() -> TransactionTracer {
+    CURRENT.with(|current| {
+        *current.borrow_mut() = Some(TraceState {
+            trace: ExecutionTrace::default(),
+            command_start_gas: 0,
+        })
+    });
+    TransactionTracer { _private: () }
+}
+
+impl TransactionTracer {
+    pub fn finish(self) -> ExecutionTrace {
+        CURRENT
+            .with(|current| current.borrow_mut().take())
+            .map(|state| state.trace)
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for TransactionTracer {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.borrow_mut().take());
+    }
+}
+
+fn with_trace(f: impl FnOnce(&mut TraceState)) {
+    CURRENT.with(|current| {
+        if let Some(state) = current.borrow_mut().as_mut() {
+            f(state)
+        }
+    })
+}
+
+pub fn is_tracing() -> bool {
+    CURRENT.with(|current| current.borrow().is_some())
+}
+
+pub fn record_object_read_gas(gas: u64) {
+    with_trace(|state| state.trace.object_read_gas += gas)
+}
+
+pub fn record_storage_gas(gas: u64) {
+    with_trace(|state| state.trace.storage_gas += gas)
+}
+
+/// Starts recording a command, given the computation gas remaining before it executes.
+pub fn begin_command(index: usize, kind: &str, call: Option<MoveCallTrace>, gas_remaining: u64) {
+    with_trace(|state| {
+        state.command_start_gas = gas_remaining;
+        state.trace.commands.push(CommandTrace {
+            index,
+            kind: kind.to_owned(),
+            call,
+            gas_used: 0,
+            native_calls: vec![],
+        })
+    })
+}
+
+/// Finishes recording the current command, given the computation gas remaining after it executed,
+/// whether it succeeded or not.
+pub fn end_command(gas_remaining: u64) {
+    with_trace(|state| {
+        if let Some(command) = state.trace.commands.last_mut() {
+            command.gas_used = state.command_start_gas.saturating_sub(gas_remaining);
+        }
+    })
+}
+
+/// Wraps `native` so that its calls are recorded under the current command while a transaction is
+/// traced.
+pub(crate) fn traced(name: String, native: NativeFunction) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        let result = native(context, ty_args, args);
+        if let Ok(native_result) = &result {
+            with_trace(|state| {
+                if let Some(command) = state.trace.commands.last_mut() {
+                    let gas: GasUnits = InternalGas::to_unit_round_down(native_result.cost);
+                    command.native_calls.push(NativeCallTrace {
+                        function: name.clone(),
+                        gas: gas.into(),
+                    });
+                }
+            });
+        }
+        result
+    })
+}
@@ -5,6 +5,8 @@ mod address;
 mod crypto;
 mod dynamic_field;
 mod event;
+pub mod execution_profile;
+pub mod execution_trace;
 mod object;
 pub mod object_runtime;
 mod test_scenario;
@@ -471,6 +473,15 @@ impl NativesCostTable {
     }
 }
 
+/// Modules of the Sui framework whose natives operate on the object runtime.
+const OBJECT_RUNTIME_MODULES: &[&str] = &[
+    "dynamic_field",
+    "event",
+    "object",
+    "test_scenario",
+    "transfer",
+];
+
 pub fn all_natives() -> NativeFunctionTable {
     let sui_framework_natives: &[(&str, &str, NativeFunction)] = &[
         ("address", "from_bytes", make_native!(address::from_bytes)),
@@ -699,6 +710,19 @@ pub fn all_natives() -> NativeFunctionTable {
             // TODO: tune gas params
             NurseryGasParameters::zeros(),
         ))
+        .map(|(address, module_name, func_name, func)| {
+            let name = format!(
+                "0x{}::{}::{}",
+                address.short_str_lossless(),
+                module_name,
+                func_name
+            );
+            let object_runtime = address == SUI_FRAMEWORK_ADDRESS
+                && OBJECT_RUNTIME_MODULES.contains(&module_name.as_str());
+            let func = execution_trace::traced(name.clone(), func);
+            let func = execution_profile::profiled(name, object_runtime, func);
+            (address, module_name, func_name, func)
+        })
         .collect()
 }
 
@@ -0,0 +1,84 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! GraphQL service over the tables of the indexer, as an alternative to its JSON-RPC API.
+//!
+//! Transactions, objects, events and checkpoints are queried from the root and reference each
+//! other, so that related data is fetched in one request, e.g. the objects changed by a
+//! transaction together with their Display metadata. Lists are paginated with cursors, following
+//! the GraphQL connection specification.
+
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{EmptyMutation, EmptySubscription, Schema, SchemaBuilder};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::{Extension, Router};
+use std::net::SocketAddr;
+use sui_indexer::store::PgIndexerStore;
+use tracing::info;
+
+use crate::query::Query;
+
+mod query;
+pub mod types;
+
+pub type SuiGraphQLSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+fn schema_builder() -> SchemaBuilder<Query, EmptyMutation, EmptySubscription> {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+}
+
+/// The schema of the service, resolving queries against `store`.
+pub fn build_schema(store: PgIndexerStore) -> SuiGraphQLSchema {
+    schema_builder().data(store).finish()
+}
+
+async fn graphql_handler(
+    Extension(schema): Extension<SuiGraphQLSchema>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    schema.execute(request.into_inner()).await.into()
+}
+
+async fn graphiql() -> impl IntoResponse {
+    Html(GraphiQLSource::build().endpoint("/").finish())
+}
+
+/// Serves the GraphQL API on `address`, with a GraphiQL IDE on GET requests.
+pub async fn start_graphql_server(
+    store: PgIndexerStore,
+    address: SocketAddr,
+) -> Result<(), anyhow::Error> {
+    let app = Router::new()
+        .route("/", get(graphiql).post(graphql_handler))
+        .layer(Extension(build_schema(store)));
+    info!("Starting GraphQL server on {address}");
+    axum::Server::bind(&address)
+        .serve(app.into_make_service())
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema() {
+        let sdl = schema_builder().finish().sdl();
+        for definition in [
+            "type Transaction",
+            "type Object",
+            "type Event",
+            "type Checkpoint",
+            "type TransactionConnection",
+            "type EventConnection",
+        ] {
+            assert!(
+                sdl.contains(definition),
+                "{definition} missing from:\n{sdl}"
+            );
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use std::net::SocketAddr;
+use tracing::info;
+
+use sui_graphql::start_graphql_server;
+use sui_indexer::new_pg_connection_pool;
+use sui_indexer::store::PgIndexerStore;
+
+#[derive(Parser, Clone, Debug)]
+#[clap(
+    name = "Sui GraphQL",
+    about = "GraphQL service over the data of the Sui indexer",
+    rename_all = "kebab-case"
+)]
+struct GraphQLConfig {
+    /// URL of the Postgres database written by the indexer.
+    #[clap(long)]
+    db_url: String,
+    #[clap(long, default_value = "0.0.0.0:9100")]
+    address: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    let config = GraphQLConfig::parse();
+    info!("GraphQL config: {:#?}", config);
+    let pg_connection_pool = new_pg_connection_pool(&config.db_url).await?;
+    start_graphql_server(PgIndexerStore::new(pg_connection_pool), config.address).await
+}
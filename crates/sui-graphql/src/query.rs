@@ -0,0 +1,171 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::{Context, Object as GraphQLObject, Result};
+use sui_indexer::store::IndexerStore;
+use sui_json_rpc_types::{CheckpointId, EventFilter};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::digests::{CheckpointDigest, TransactionDigest};
+use sui_types::event::EventID;
+use sui_types::parse_sui_struct_tag;
+
+use crate::types::{
+    page_size, store, transactions_by_digests, Checkpoint, Event, Object, Transaction,
+};
+
+pub struct Query;
+
+#[GraphQLObject]
+impl Query {
+    async fn transaction(&self, ctx: &Context<'_>, digest: String) -> Result<Transaction> {
+        let digest: TransactionDigest = digest.parse()?;
+        Ok(Transaction(
+            store(ctx).get_transaction_by_digest(&digest.base58_encode())?,
+        ))
+    }
+
+    /// Transactions in execution order, or sent by `sender` or sent objects by `recipient` if
+    /// set, starting after the transaction with digest `after` if set.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+        sender: Option<String>,
+        recipient: Option<String>,
+        descending: Option<bool>,
+    ) -> Result<Connection<String, Transaction>> {
+        let store = store(ctx);
+        let limit = page_size(first)?;
+        let descending = descending.unwrap_or_default();
+        let after = after
+            .map(|digest| digest.parse::<TransactionDigest>())
+            .transpose()?
+            .map(|digest| digest.base58_encode());
+
+        let mut digests = match (sender, recipient) {
+            (None, None) => {
+                let start = store.get_transaction_sequence_by_digest(after.clone(), descending)?;
+                store.get_all_transaction_digest_page(start, limit + 1, descending)?
+            }
+            (Some(sender), None) => {
+                let sender: SuiAddress = sender.parse()?;
+                let start = store.get_transaction_sequence_by_digest(after.clone(), descending)?;
+                store.get_transaction_digest_page_by_sender_address(
+                    sender.to_string(),
+                    start,
+                    limit + 1,
+                    descending,
+                )?
+            }
+            (None, Some(recipient)) => {
+                let recipient: SuiAddress = recipient.parse()?;
+                let start = store.get_recipient_sequence_by_digest(after.clone(), descending)?;
+                store.get_transaction_digest_page_by_recipient_address(
+                    recipient.to_string(),
+                    start,
+                    limit + 1,
+                    descending,
+                )?
+            }
+            (Some(_), Some(_)) => {
+                return Err("Transactions can be filtered by sender or recipient, not both".into())
+            }
+        };
+
+        let has_next_page = digests.len() > limit;
+        digests.truncate(limit);
+        let mut connection = Connection::new(after.is_some(), has_next_page);
+        for transaction in transactions_by_digests(store, &digests)? {
+            connection.edges.push(Edge::new(
+                transaction.0.transaction_digest.clone(),
+                transaction,
+            ));
+        }
+        Ok(connection)
+    }
+
+    /// The given version of the object, or its latest version if `version` is not set.
+    async fn object(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        version: Option<u64>,
+    ) -> Result<Option<Object>> {
+        let object_id: ObjectID = id.parse()?;
+        Object::read(store(ctx), object_id, version.map(SequenceNumber::from_u64))
+    }
+
+    /// The checkpoint with the given sequence number or digest, or the latest checkpoint if
+    /// neither is set.
+    async fn checkpoint(
+        &self,
+        ctx: &Context<'_>,
+        sequence_number: Option<u64>,
+        digest: Option<String>,
+    ) -> Result<Checkpoint> {
+        let store = store(ctx);
+        let id = match (sequence_number, digest) {
+            (Some(sequence_number), None) => CheckpointId::from(sequence_number),
+            (None, Some(digest)) => CheckpointId::Digest(digest.parse::<CheckpointDigest>()?),
+            (None, None) => {
+                CheckpointId::from(store.get_latest_checkpoint_sequence_number()? as u64)
+            }
+            (Some(_), Some(_)) => {
+                return Err("Set the sequence number or the digest of the checkpoint".into())
+            }
+        };
+        Ok(Checkpoint(store.get_checkpoint(id)?))
+    }
+
+    /// Events in emission order, starting after the event with id `after` if set. At most one of
+    /// `sender`, `transaction` and `type` filters the events.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+        sender: Option<String>,
+        transaction: Option<String>,
+        #[graphql(name = "type")] type_: Option<String>,
+        descending: Option<bool>,
+    ) -> Result<Connection<String, Event>> {
+        let limit = page_size(first)?;
+        let after = after.map(EventID::try_from).transpose()?;
+
+        let mut filters = vec![];
+        if let Some(sender) = sender {
+            filters.push(EventFilter::Sender(sender.parse()?));
+        }
+        if let Some(transaction) = transaction {
+            filters.push(EventFilter::Transaction(transaction.parse()?));
+        }
+        if let Some(type_) = type_ {
+            filters.push(EventFilter::MoveEventType(parse_sui_struct_tag(&type_)?));
+        }
+        let filter = match filters.len() {
+            0 => EventFilter::All(vec![]),
+            1 => filters.remove(0),
+            _ => {
+                return Err(
+                    "Events can only be filtered by one of sender, transaction or type".into(),
+                )
+            }
+        };
+
+        let page = store(ctx).get_events(
+            filter,
+            after.clone(),
+            Some(limit),
+            descending.unwrap_or_default(),
+        )?;
+        let mut connection = Connection::new(after.is_some(), page.has_next_page);
+        for event in page.data {
+            connection
+                .edges
+                .push(Edge::new(event.id.clone().into(), Event(event)));
+        }
+        Ok(connection)
+    }
+}
@@ -0,0 +1,410 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::connection::{Connection, Edge};
+use async_graphql::{Context, Enum, Json, Object as GraphQLObject, Result, SimpleObject};
+use move_core_types::language_storage::StructTag;
+use move_core_types::value::MoveStructLayout;
+use serde_json::Value;
+use sui_indexer::models::checkpoints::Checkpoint as IndexedCheckpoint;
+use sui_indexer::models::transactions::Transaction as IndexedTransaction;
+use sui_indexer::store::{IndexerStore, PgIndexerStore};
+use sui_json_rpc::api::QUERY_MAX_RESULT_LIMIT;
+use sui_json_rpc::read_api::get_rendered_fields;
+use sui_json_rpc_types::{
+    CheckpointId, EventFilter, SuiEvent, SuiMoveStruct, SuiTransactionEffects,
+    SuiTransactionEffectsAPI,
+};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::TransactionDigest;
+use sui_types::display::DisplayVersionUpdatedEvent;
+use sui_types::object::{Object as SuiObject, ObjectRead};
+use sui_types::storage::{DeleteKind, WriteKind};
+
+/// Number of items in a page when the query does not set `first`.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+pub(crate) fn store<'a>(ctx: &Context<'a>) -> &'a PgIndexerStore {
+    ctx.data_unchecked::<PgIndexerStore>()
+}
+
+pub(crate) fn page_size(first: Option<usize>) -> Result<usize> {
+    match first {
+        None => Ok(DEFAULT_PAGE_SIZE),
+        Some(0) => Err("Page size cannot be smaller than 1".into()),
+        Some(first) if first > QUERY_MAX_RESULT_LIMIT => {
+            Err(format!("Page size {first} exceeds max page size {QUERY_MAX_RESULT_LIMIT}").into())
+        }
+        Some(first) => Ok(first),
+    }
+}
+
+/// The transactions with the given digests, in the same order.
+pub(crate) fn transactions_by_digests(
+    store: &PgIndexerStore,
+    digests: &[String],
+) -> Result<Vec<Transaction>> {
+    let mut transactions = store.multi_get_transactions_by_digests(digests)?;
+    digests
+        .iter()
+        .map(|digest| {
+            let position = transactions
+                .iter()
+                .position(|transaction| transaction.transaction_digest == *digest)
+                .ok_or_else(|| format!("Transaction {digest} not found"))?;
+            Ok(Transaction(transactions.swap_remove(position)))
+        })
+        .collect()
+}
+
+pub struct Transaction(pub(crate) IndexedTransaction);
+
+#[GraphQLObject]
+impl Transaction {
+    async fn digest(&self) -> &str {
+        &self.0.transaction_digest
+    }
+
+    async fn sender(&self) -> &str {
+        &self.0.sender
+    }
+
+    async fn kind(&self) -> &str {
+        &self.0.transaction_kind
+    }
+
+    async fn timestamp_ms(&self) -> i64 {
+        self.0.timestamp_ms
+    }
+
+    async fn command_count(&self) -> i64 {
+        self.0.command_count
+    }
+
+    /// Functions called by the transaction, as `package::module::function`.
+    async fn move_calls(&self) -> Vec<&str> {
+        self.0
+            .move_calls
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    async fn gas_budget(&self) -> i64 {
+        self.0.gas_budget
+    }
+
+    async fn gas_price(&self) -> i64 {
+        self.0.gas_price
+    }
+
+    async fn total_gas_cost(&self) -> i64 {
+        self.0.total_gas_cost
+    }
+
+    async fn computation_cost(&self) -> i64 {
+        self.0.computation_cost
+    }
+
+    async fn storage_cost(&self) -> i64 {
+        self.0.storage_cost
+    }
+
+    async fn storage_rebate(&self) -> i64 {
+        self.0.storage_rebate
+    }
+
+    async fn checkpoint(&self, ctx: &Context<'_>) -> Result<Checkpoint> {
+        let sequence_number = self.0.checkpoint_sequence_number as u64;
+        Ok(Checkpoint(
+            store(ctx).get_checkpoint(CheckpointId::from(sequence_number))?,
+        ))
+    }
+
+    /// Objects written, deleted or wrapped by the transaction.
+    async fn changed_objects(&self) -> Result<Vec<ObjectChange>> {
+        let effects: SuiTransactionEffects =
+            serde_json::from_str(&self.0.transaction_effects_content)?;
+        let written = effects
+            .all_changed_objects()
+            .into_iter()
+            .map(|(object_ref, kind)| ObjectChange {
+                object_id: object_ref.reference.object_id,
+                version: object_ref.reference.version,
+                kind: match kind {
+                    WriteKind::Create => ObjectChangeKind::Created,
+                    WriteKind::Mutate => ObjectChangeKind::Mutated,
+                    WriteKind::Unwrap => ObjectChangeKind::Unwrapped,
+                },
+            });
+        let deleted = effects
+            .all_deleted_objects()
+            .into_iter()
+            .map(|(object_ref, kind)| ObjectChange {
+                object_id: object_ref.object_id,
+                version: object_ref.version,
+                kind: match kind {
+                    DeleteKind::Normal => ObjectChangeKind::Deleted,
+                    DeleteKind::UnwrapThenDelete => ObjectChangeKind::UnwrappedThenDeleted,
+                    DeleteKind::Wrap => ObjectChangeKind::Wrapped,
+                },
+            });
+        Ok(written.chain(deleted).collect())
+    }
+
+    async fn events(&self, ctx: &Context<'_>) -> Result<Vec<Event>> {
+        let digest: TransactionDigest = self.0.transaction_digest.parse()?;
+        let page = store(ctx).get_events(EventFilter::Transaction(digest), None, None, false)?;
+        Ok(page.data.into_iter().map(Event).collect())
+    }
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ObjectChangeKind {
+    Created,
+    Mutated,
+    Unwrapped,
+    Deleted,
+    UnwrappedThenDeleted,
+    Wrapped,
+}
+
+pub struct ObjectChange {
+    object_id: ObjectID,
+    version: SequenceNumber,
+    kind: ObjectChangeKind,
+}
+
+#[GraphQLObject]
+impl ObjectChange {
+    async fn object_id(&self) -> String {
+        self.object_id.to_string()
+    }
+
+    /// Version of the object after the transaction.
+    async fn version(&self) -> u64 {
+        self.version.value()
+    }
+
+    async fn kind(&self) -> ObjectChangeKind {
+        self.kind
+    }
+
+    /// The object as written by the transaction, unset if it was deleted or wrapped.
+    async fn object(&self, ctx: &Context<'_>) -> Result<Option<Object>> {
+        match self.kind {
+            ObjectChangeKind::Created | ObjectChangeKind::Mutated | ObjectChangeKind::Unwrapped => {
+                Object::read(store(ctx), self.object_id, Some(self.version))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+pub struct Object {
+    object: SuiObject,
+    layout: Option<MoveStructLayout>,
+}
+
+impl Object {
+    /// The given version of the object, or its latest version if `version` is not set.
+    pub(crate) fn read(
+        store: &PgIndexerStore,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<Option<Self>> {
+        Ok(match store.get_object(object_id, version)? {
+            ObjectRead::Exists(_, object, layout) => Some(Self { object, layout }),
+            ObjectRead::NotExists(_) | ObjectRead::Deleted(_) => None,
+        })
+    }
+}
+
+#[GraphQLObject]
+impl Object {
+    async fn object_id(&self) -> String {
+        self.object.id().to_string()
+    }
+
+    async fn version(&self) -> u64 {
+        self.object.version().value()
+    }
+
+    async fn digest(&self) -> String {
+        self.object.digest().to_string()
+    }
+
+    async fn owner(&self) -> String {
+        self.object.owner.to_string()
+    }
+
+    /// Move type of the object, unset for packages.
+    #[graphql(name = "type")]
+    async fn type_(&self) -> Option<String> {
+        self.object
+            .type_()
+            .map(|object_type| StructTag::from(object_type.clone()).to_string())
+    }
+
+    /// Fields of the Move object, as JSON.
+    async fn contents(&self) -> Result<Option<Json<Value>>> {
+        let move_object = self.object.data.try_as_move();
+        let (Some(move_object), Some(layout)) = (move_object, &self.layout) else {
+            return Ok(None);
+        };
+        let move_struct = SuiMoveStruct::from(move_object.to_move_struct(layout)?);
+        Ok(Some(Json(move_struct.to_json_value())))
+    }
+
+    async fn previous_transaction(&self, ctx: &Context<'_>) -> Result<Transaction> {
+        Ok(Transaction(store(ctx).get_transaction_by_digest(
+            &self.object.previous_transaction.base58_encode(),
+        )?))
+    }
+
+    /// Fields of the object rendered with the latest Display of its type, empty if the type has
+    /// no Display.
+    async fn display(&self, ctx: &Context<'_>) -> Result<Vec<DisplayEntry>> {
+        let (Some(object_type), Some(layout)) = (self.object.type_(), &self.layout) else {
+            return Ok(vec![]);
+        };
+        let object_type = StructTag::from(object_type.clone());
+        let page = store(ctx).get_events(
+            EventFilter::MoveEventType(DisplayVersionUpdatedEvent::type_(&object_type)),
+            None,
+            Some(1),
+            true,
+        )?;
+        let Some(event) = page.data.into_iter().next() else {
+            return Ok(vec![]);
+        };
+        let display: DisplayVersionUpdatedEvent = bcs::from_bytes(&event.bcs)?;
+        let move_struct = self
+            .object
+            .data
+            .try_as_move()
+            .ok_or("Object is not a Move object")?
+            .to_move_struct(layout)?;
+        Ok(get_rendered_fields(display.fields, &move_struct)?
+            .into_iter()
+            .map(|(key, value)| DisplayEntry { key, value })
+            .collect())
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct DisplayEntry {
+    key: String,
+    value: String,
+}
+
+pub struct Event(pub(crate) SuiEvent);
+
+#[GraphQLObject]
+impl Event {
+    /// The cursor of the event, `transaction digest:sequence number`.
+    async fn id(&self) -> String {
+        self.0.id.clone().into()
+    }
+
+    async fn sequence_number(&self) -> u64 {
+        self.0.id.event_seq
+    }
+
+    async fn sender(&self) -> String {
+        self.0.sender.to_string()
+    }
+
+    async fn package_id(&self) -> String {
+        self.0.package_id.to_string()
+    }
+
+    async fn module(&self) -> String {
+        self.0.transaction_module.to_string()
+    }
+
+    #[graphql(name = "type")]
+    async fn type_(&self) -> String {
+        self.0.type_.to_string()
+    }
+
+    async fn json(&self) -> Json<&Value> {
+        Json(&self.0.parsed_json)
+    }
+
+    async fn timestamp_ms(&self) -> Option<u64> {
+        self.0.timestamp_ms
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>) -> Result<Transaction> {
+        Ok(Transaction(store(ctx).get_transaction_by_digest(
+            &self.0.id.tx_digest.base58_encode(),
+        )?))
+    }
+}
+
+pub struct Checkpoint(pub(crate) IndexedCheckpoint);
+
+#[GraphQLObject]
+impl Checkpoint {
+    async fn sequence_number(&self) -> i64 {
+        self.0.sequence_number
+    }
+
+    async fn digest(&self) -> &str {
+        &self.0.checkpoint_digest
+    }
+
+    async fn previous_digest(&self) -> Option<&str> {
+        self.0.previous_checkpoint_digest.as_deref()
+    }
+
+    async fn epoch(&self) -> i64 {
+        self.0.epoch
+    }
+
+    async fn timestamp_ms(&self) -> i64 {
+        self.0.timestamp_ms
+    }
+
+    async fn total_gas_cost(&self) -> i64 {
+        self.0.total_gas_cost
+    }
+
+    /// Number of transactions since genesis, including this checkpoint.
+    async fn total_transactions(&self) -> i64 {
+        self.0.total_transactions_from_genesis
+    }
+
+    /// Transactions of the checkpoint, in execution order, starting after the transaction with
+    /// digest `after` if set.
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> Result<Connection<String, Transaction>> {
+        let limit = page_size(first)?;
+        let digests: Vec<String> = self.0.transactions.iter().flatten().cloned().collect();
+        let start = match &after {
+            Some(after) => {
+                digests
+                    .iter()
+                    .position(|digest| digest == after)
+                    .ok_or_else(|| format!("Transaction {after} is not in the checkpoint"))?
+                    + 1
+            }
+            None => 0,
+        };
+        let end = digests.len().min(start + limit);
+        let mut connection = Connection::new(start > 0, end < digests.len());
+        for transaction in transactions_by_digests(store(ctx), &digests[start..end])? {
+            connection.edges.push(Edge::new(
+                transaction.0.transaction_digest.clone(),
+                transaction,
+            ));
+        }
+        Ok(connection)
+    }
+}
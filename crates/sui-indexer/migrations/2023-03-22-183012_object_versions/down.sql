@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS object_versions ON objects;
+DROP FUNCTION IF EXISTS object_versions_func();
+DROP TABLE IF EXISTS object_versions;
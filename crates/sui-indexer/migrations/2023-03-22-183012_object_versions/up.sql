@@ -0,0 +1,54 @@
+-- One row per version of each object, to list the versions of an object within a range of
+-- checkpoints without scanning every partition of objects_history.
+CREATE TABLE object_versions
+(
+    object_id            address       NOT NULL,
+    version              BIGINT        NOT NULL,
+    epoch                BIGINT        NOT NULL,
+    checkpoint           BIGINT        NOT NULL,
+    object_digest        base58digest  NOT NULL,
+    object_status        object_status NOT NULL,
+    previous_transaction base58digest  NOT NULL,
+    CONSTRAINT object_versions_pk PRIMARY KEY (object_id, version)
+);
+CREATE INDEX object_versions_id_checkpoint_index ON object_versions (object_id, checkpoint);
+
+INSERT INTO object_versions
+SELECT object_id, version, epoch, checkpoint, object_digest, object_status, previous_transaction
+FROM objects_history
+ON CONFLICT DO NOTHING;
+
+CREATE OR REPLACE FUNCTION object_versions_func() RETURNS TRIGGER AS
+$body$
+BEGIN
+    IF (TG_OP = 'INSERT' OR TG_OP = 'UPDATE') THEN
+        INSERT INTO object_versions
+        VALUES (NEW.object_id, NEW.version, NEW.epoch, NEW.checkpoint, NEW.object_digest,
+                NEW.object_status, NEW.previous_transaction)
+        ON CONFLICT DO NOTHING;
+        RETURN NEW;
+    ELSIF (TG_OP = 'DELETE') THEN
+        -- object deleted from the main table, its history is archived as well
+        DELETE FROM object_versions WHERE object_id = old.object_id;
+        RETURN OLD;
+    ELSE
+        RAISE WARNING '[OBJECT_VERSIONS_FUNC] - Other action occurred: %, at %',TG_OP,NOW();
+        RETURN NULL;
+    END IF;
+
+EXCEPTION
+    WHEN data_exception THEN
+        RAISE WARNING '[OBJECT_VERSIONS_FUNC] - UDF ERROR [DATA EXCEPTION] - SQLSTATE: %, SQLERRM: %',SQLSTATE,SQLERRM;
+        RETURN NULL;
+    WHEN OTHERS THEN
+        RAISE WARNING '[OBJECT_VERSIONS_FUNC] - UDF ERROR [OTHER] - SQLSTATE: %, SQLERRM: %',SQLSTATE,SQLERRM;
+        RETURN NULL;
+END;
+$body$
+    LANGUAGE plpgsql;
+
+CREATE TRIGGER object_versions
+    AFTER INSERT OR UPDATE OR DELETE
+    ON objects
+    FOR EACH ROW
+EXECUTE PROCEDURE object_versions_func();
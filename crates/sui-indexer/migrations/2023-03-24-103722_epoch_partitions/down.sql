@@ -0,0 +1,32 @@
+DROP INDEX IF EXISTS object_versions_checkpoint_index;
+
+ALTER TABLE transactions RENAME TO transactions_partitioned;
+ALTER SEQUENCE transactions_id_seq OWNED BY NONE;
+CREATE TABLE transactions (LIKE transactions_partitioned INCLUDING DEFAULTS);
+INSERT INTO transactions SELECT * FROM transactions_partitioned;
+DROP TABLE transactions_partitioned;
+ALTER TABLE transactions ADD PRIMARY KEY (id);
+ALTER TABLE transactions ADD UNIQUE (transaction_digest);
+ALTER SEQUENCE transactions_id_seq OWNED BY transactions.id;
+
+CREATE INDEX transactions_transaction_digest ON transactions (transaction_digest);
+CREATE INDEX transactions_timestamp_ms ON transactions (timestamp_ms);
+CREATE INDEX transactions_sender ON transactions (sender);
+CREATE INDEX transactions_gas_object_id ON transactions (gas_object_id);
+CREATE INDEX transactions_checkpoint_sequence_number ON transactions (checkpoint_sequence_number);
+
+ALTER TABLE events RENAME TO events_partitioned;
+ALTER SEQUENCE events_id_seq OWNED BY NONE;
+CREATE TABLE events (LIKE events_partitioned INCLUDING DEFAULTS);
+INSERT INTO events SELECT * FROM events_partitioned;
+DROP TABLE events_partitioned;
+ALTER TABLE events DROP COLUMN checkpoint;
+ALTER TABLE events ADD PRIMARY KEY (id);
+ALTER SEQUENCE events_id_seq OWNED BY events.id;
+
+CREATE INDEX events_transaction_digest ON events (transaction_digest);
+CREATE INDEX events_sender ON events (sender);
+CREATE INDEX events_package ON events (package);
+CREATE INDEX events_module ON events (module);
+CREATE INDEX events_event_type ON events (event_type);
+CREATE INDEX events_event_time_ms ON events (event_time_ms);
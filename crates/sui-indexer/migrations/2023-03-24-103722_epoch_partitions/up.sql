@@ -0,0 +1,110 @@
+-- Partitions transactions and events by checkpoint, with one partition per epoch as for
+-- objects_history, so that the data of old epochs can be dropped along with their partitions.
+-- The rows are copied with their ids, which are used as cursors, and the sequences are kept.
+
+ALTER TABLE transactions RENAME TO transactions_unpartitioned;
+ALTER SEQUENCE transactions_id_seq OWNED BY NONE;
+CREATE TABLE transactions (
+    id                          BIGINT       NOT NULL DEFAULT nextval('transactions_id_seq'),
+    transaction_digest          base58digest NOT NULL,
+    sender                      VARCHAR(255) NOT NULL,
+    recipients                  TEXT[]       NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    timestamp_ms                BIGINT       NOT NULL,
+    transaction_kind            TEXT         NOT NULL,
+    command_count               BIGINT       NOT NULL,
+    -- object related
+    created                     TEXT[]       NOT NULL,
+    mutated                     TEXT[]       NOT NULL,
+    deleted                     TEXT[]       NOT NULL,
+    unwrapped                   TEXT[]       NOT NULL,
+    wrapped                     TEXT[]       NOT NULL,
+    -- each move call is <package>::<module>::<function>
+    move_calls                  TEXT[]       NOT NULL,
+    -- gas object related
+    gas_object_id               address      NOT NULL,
+    gas_object_sequence         BIGINT       NOT NULL,
+    gas_object_digest           address      NOT NULL,
+    -- gas budget & cost related
+    gas_budget                  BIGINT       NOT NULL,
+    total_gas_cost              BIGINT       NOT NULL,
+    computation_cost            BIGINT       NOT NULL,
+    storage_cost                BIGINT       NOT NULL,
+    storage_rebate              BIGINT       NOT NULL,
+    -- gas price from transaction data,
+    -- not the reference gas price
+    gas_price                   BIGINT       NOT NULL,
+    -- BCS serialized SenderSignedData
+    raw_transaction             bytea        NOT NULL,
+    transaction_content         TEXT         NOT NULL,
+    transaction_effects_content TEXT         NOT NULL,
+    confirmed_local_execution   BOOLEAN,
+    -- unique constraints of a partitioned table must include the partition key
+    CONSTRAINT transactions_pk PRIMARY KEY (id, checkpoint_sequence_number),
+    UNIQUE (transaction_digest, checkpoint_sequence_number)
+) PARTITION BY RANGE (checkpoint_sequence_number);
+CREATE TABLE transactions_partition_0 PARTITION OF transactions FOR VALUES FROM (0) TO (MAXVALUE);
+
+INSERT INTO transactions SELECT * FROM transactions_unpartitioned;
+DROP TABLE transactions_unpartitioned;
+ALTER SEQUENCE transactions_id_seq OWNED BY transactions.id;
+
+CREATE INDEX transactions_transaction_digest ON transactions (transaction_digest);
+CREATE INDEX transactions_timestamp_ms ON transactions (timestamp_ms);
+CREATE INDEX transactions_sender ON transactions (sender);
+CREATE INDEX transactions_gas_object_id ON transactions (gas_object_id);
+CREATE INDEX transactions_checkpoint_sequence_number ON transactions (checkpoint_sequence_number);
+
+ALTER TABLE events RENAME TO events_unpartitioned;
+ALTER SEQUENCE events_id_seq OWNED BY NONE;
+CREATE TABLE events
+(
+    id                 BIGINT       NOT NULL DEFAULT nextval('events_id_seq'),
+    transaction_digest base58digest NOT NULL,
+    event_sequence     BIGINT       NOT NULL,
+    sender             address      NOT NULL,
+    package            address      NOT NULL,
+    module             TEXT         NOT NULL,
+    -- type_ in SuiEvent::MoveEvent
+    event_type         TEXT         NOT NULL,
+    event_time_ms      BIGINT,
+    parsed_json        jsonb        NOT NULL,
+    event_bcs          BYTEA        NOT NULL,
+    -- checkpoint of the transaction that emitted the event
+    checkpoint         BIGINT       NOT NULL,
+    CONSTRAINT events_pk PRIMARY KEY (id, checkpoint)
+) PARTITION BY RANGE (checkpoint);
+CREATE TABLE events_partition_0 PARTITION OF events FOR VALUES FROM (0) TO (MAXVALUE);
+
+-- Every event must be copied with the checkpoint of its transaction.
+DO
+$$
+    DECLARE
+        orphans BIGINT;
+    BEGIN
+        SELECT COUNT(*)
+        INTO orphans
+        FROM events_unpartitioned e
+                 LEFT JOIN transactions t ON e.transaction_digest = t.transaction_digest
+        WHERE t.transaction_digest IS NULL;
+        IF orphans > 0 THEN
+            RAISE EXCEPTION '% events have no transaction, their checkpoint is unknown', orphans;
+        END IF;
+    END
+$$;
+INSERT INTO events
+SELECT e.*, t.checkpoint_sequence_number
+FROM events_unpartitioned e
+         LEFT JOIN transactions t ON e.transaction_digest = t.transaction_digest;
+DROP TABLE events_unpartitioned;
+ALTER SEQUENCE events_id_seq OWNED BY events.id;
+
+CREATE INDEX events_transaction_digest ON events (transaction_digest);
+CREATE INDEX events_sender ON events (sender);
+CREATE INDEX events_package ON events (package);
+CREATE INDEX events_module ON events (module);
+CREATE INDEX events_event_type ON events (event_type);
+CREATE INDEX events_event_time_ms ON events (event_time_ms);
+
+-- Versions are deleted by checkpoint with the partitions of objects_history.
+CREATE INDEX object_versions_checkpoint_index ON object_versions (checkpoint);
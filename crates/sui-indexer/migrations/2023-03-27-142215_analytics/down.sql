@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS package_metrics;
+DROP TABLE IF EXISTS daily_active_addresses;
+DROP TABLE IF EXISTS daily_metrics;
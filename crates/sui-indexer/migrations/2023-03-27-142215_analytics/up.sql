@@ -0,0 +1,29 @@
+-- Rollups updated as checkpoints are ingested, in the same transaction as the checkpoint.
+-- Days are counted from the unix epoch, in UTC.
+CREATE TABLE daily_metrics
+(
+    day                    BIGINT PRIMARY KEY,
+    transactions           BIGINT NOT NULL,
+    active_addresses       BIGINT NOT NULL,
+    total_gas_cost         BIGINT NOT NULL,
+    total_computation_cost BIGINT NOT NULL,
+    total_storage_cost     BIGINT NOT NULL,
+    total_storage_rebate   BIGINT NOT NULL
+);
+
+-- Senders of the transactions of each day, to count every address once a day.
+CREATE TABLE daily_active_addresses
+(
+    day     BIGINT  NOT NULL,
+    address address NOT NULL,
+    CONSTRAINT daily_active_addresses_pk PRIMARY KEY (day, address)
+);
+
+CREATE TABLE package_metrics
+(
+    package_id      address PRIMARY KEY,
+    transactions    BIGINT NOT NULL,
+    move_calls      BIGINT NOT NULL,
+    last_checkpoint BIGINT NOT NULL
+);
+CREATE INDEX package_metrics_transactions ON package_metrics (transactions);
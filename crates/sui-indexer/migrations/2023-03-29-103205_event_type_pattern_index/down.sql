@@ -0,0 +1 @@
+DROP INDEX IF EXISTS events_event_type_pattern;
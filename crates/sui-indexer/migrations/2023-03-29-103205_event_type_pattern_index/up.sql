@@ -0,0 +1,3 @@
+-- Serves the event type patterns of event queries, translated to LIKE patterns anchored at the
+-- start of the type, which the default index on event_type cannot serve.
+CREATE INDEX events_event_type_pattern ON events (event_type text_pattern_ops);
@@ -11,11 +11,14 @@ use sui_json_rpc::api::{
 };
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_types::{
-    BigInt, CheckpointId, EpochInfo, EpochPage, ObjectsPage, Page, SuiObjectDataFilter,
-    SuiObjectResponse, SuiObjectResponseQuery,
+    CheckpointId, CheckpointMetricsPage, DailyMetricsPage, EpochInfo, EpochPage, ObjectHistoryPage,
+    ObjectsPage, PackageMetrics, Page, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiObjectVersion, SuiPastObjectResponse,
 };
 use sui_open_rpc::Module;
-use sui_types::base_types::{EpochId, ObjectID};
+use sui_types::base_types::{EpochId, ObjectID, SequenceNumber};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::PastObjectRead;
 
 use crate::errors::IndexerError;
 use crate::store::IndexerStore;
@@ -39,19 +42,16 @@ impl<S: IndexerStore> ExtendedApi<S> {
         let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_OBJECTS)?;
 
         let at_checkpoint = match at_checkpoint {
-            Some(CheckpointId::SequenceNumber(seq)) => seq,
-            Some(CheckpointId::Digest(digest)) => {
-                <BigInt>::from(self.state.get_checkpoint_sequence_number(digest)?)
-            }
-            None => <BigInt>::from(self.state.get_latest_checkpoint_sequence_number()? as u64),
+            Some(id) => self.checkpoint_sequence_number(id)?,
+            None => self.state.get_latest_checkpoint_sequence_number()? as u64,
         };
 
         let SuiObjectResponseQuery { filter, options } = query;
         let filter = filter.unwrap_or_else(|| SuiObjectDataFilter::MatchAll(vec![]));
 
-        let objects_from_db =
-            self.state
-                .query_objects(filter, <u64>::from(at_checkpoint), cursor, limit + 1)?;
+        let objects_from_db = self
+            .state
+            .query_objects(filter, at_checkpoint, cursor, limit + 1)?;
 
         let mut data = objects_from_db
             .into_iter()
@@ -76,6 +76,96 @@ impl<S: IndexerStore> ExtendedApi<S> {
             has_next_page,
         })
     }
+
+    fn get_object_history_internal(
+        &self,
+        object_id: ObjectID,
+        start_checkpoint: Option<CheckpointId>,
+        end_checkpoint: Option<CheckpointId>,
+        options: Option<SuiObjectDataOptions>,
+        cursor: Option<SequenceNumber>,
+        limit: Option<usize>,
+    ) -> Result<ObjectHistoryPage, IndexerError> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_OBJECTS)?;
+        let start_checkpoint = match start_checkpoint {
+            Some(id) => self.checkpoint_sequence_number(id)?,
+            None => 0,
+        };
+        let end_checkpoint = match end_checkpoint {
+            Some(id) => self.checkpoint_sequence_number(id)?,
+            None => self.state.get_latest_checkpoint_sequence_number()? as u64,
+        };
+        if start_checkpoint > end_checkpoint {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Start checkpoint {start_checkpoint} is after end checkpoint {end_checkpoint}"
+            )));
+        }
+
+        let options = options.unwrap_or_default();
+        let mut data = self
+            .state
+            .get_object_history(
+                object_id,
+                start_checkpoint,
+                end_checkpoint,
+                cursor,
+                limit + 1,
+            )?
+            .into_iter()
+            .map(|(checkpoint, object_read)| {
+                let object = match object_read {
+                    PastObjectRead::VersionFound(object_ref, o, layout) => {
+                        SuiPastObjectResponse::VersionFound(
+                            (object_ref, o, layout, options.clone()).try_into()?,
+                        )
+                    }
+                    PastObjectRead::ObjectDeleted(oref) => {
+                        SuiPastObjectResponse::ObjectDeleted(oref.into())
+                    }
+                    PastObjectRead::ObjectNotExists(id) => {
+                        SuiPastObjectResponse::ObjectNotExists(id)
+                    }
+                    PastObjectRead::VersionNotFound(id, version) => {
+                        SuiPastObjectResponse::VersionNotFound(id, version)
+                    }
+                    PastObjectRead::VersionTooHigh {
+                        object_id,
+                        asked_version,
+                        latest_version,
+                    } => SuiPastObjectResponse::VersionTooHigh {
+                        object_id,
+                        asked_version,
+                        latest_version,
+                    },
+                };
+                Ok(SuiObjectVersion { checkpoint, object })
+            })
+            .collect::<Result<Vec<_>, IndexerError>>()?;
+
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+        let next_cursor = data.last().map(|version| match &version.object {
+            SuiPastObjectResponse::VersionFound(o) => o.version,
+            SuiPastObjectResponse::ObjectDeleted(oref) => oref.version,
+            _ => unreachable!("the history of an object only has found or deleted versions"),
+        });
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    fn checkpoint_sequence_number(
+        &self,
+        id: CheckpointId,
+    ) -> Result<CheckpointSequenceNumber, IndexerError> {
+        Ok(match id {
+            CheckpointId::SequenceNumber(seq) => seq.into(),
+            CheckpointId::Digest(digest) => self.state.get_checkpoint_sequence_number(digest)?,
+        })
+    }
 }
 
 #[async_trait]
@@ -114,6 +204,25 @@ impl<S: IndexerStore + Sync + Send + 'static> ExtendedApiServer for ExtendedApi<
         Ok(self.query_objects_internal(query, cursor, limit, at_checkpoint)?)
     }
 
+    async fn get_object_history(
+        &self,
+        object_id: ObjectID,
+        start_checkpoint: Option<CheckpointId>,
+        end_checkpoint: Option<CheckpointId>,
+        options: Option<SuiObjectDataOptions>,
+        cursor: Option<SequenceNumber>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectHistoryPage> {
+        Ok(self.get_object_history_internal(
+            object_id,
+            start_checkpoint,
+            end_checkpoint,
+            options,
+            cursor,
+            limit,
+        )?)
+    }
+
     async fn get_total_packages(&self) -> RpcResult<u64> {
         Ok(self.state.get_total_packages()?)
     }
@@ -125,6 +234,47 @@ impl<S: IndexerStore + Sync + Send + 'static> ExtendedApiServer for ExtendedApi<
     async fn get_total_objects(&self) -> RpcResult<u64> {
         Ok(self.state.get_total_objects()?)
     }
+
+    async fn get_daily_metrics(
+        &self,
+        cursor: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<DailyMetricsPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)?;
+        let mut data = self.state.get_daily_metrics(cursor, limit + 1)?;
+
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+        let next_cursor = data.last().map(|metrics| metrics.day);
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    async fn get_top_packages(&self, limit: Option<usize>) -> RpcResult<Vec<PackageMetrics>> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)?;
+        Ok(self.state.get_top_packages(limit)?)
+    }
+
+    async fn get_checkpoint_metrics(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+    ) -> RpcResult<CheckpointMetricsPage> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)?;
+        let mut data = self.state.get_checkpoint_metrics(cursor, limit + 1)?;
+
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+        let next_cursor = data.last().map(|metrics| metrics.sequence_number);
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
 }
 
 impl<S> SuiRpcModule for ExtendedApi<S>
@@ -7,8 +7,8 @@ use jsonrpsee::http_client::HttpClient;
 use jsonrpsee::RpcModule;
 use sui_json_rpc::api::{GovernanceReadApiClient, GovernanceReadApiServer};
 use sui_json_rpc::SuiRpcModule;
-use sui_json_rpc_types::SuiCommittee;
 use sui_json_rpc_types::{BigInt, DelegatedStake};
+use sui_json_rpc_types::{SuiCommittee, SuiGasPriceEstimates};
 use sui_open_rpc::Module;
 use sui_types::base_types::{EpochId, ObjectID, SuiAddress};
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
@@ -48,6 +48,10 @@ impl GovernanceReadApiServer for GovernanceReadApi {
     async fn get_reference_gas_price(&self) -> RpcResult<BigInt> {
         self.fullnode.get_reference_gas_price().await
     }
+
+    async fn get_gas_price_estimates(&self) -> RpcResult<SuiGasPriceEstimates> {
+        self.fullnode.get_gas_price_estimates().await
+    }
 }
 
 impl SuiRpcModule for GovernanceReadApi {
@@ -13,12 +13,13 @@ use std::collections::BTreeMap;
 use sui_json_rpc::api::{validate_limit, ReadApiClient, ReadApiServer, QUERY_MAX_RESULT_LIMIT};
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_types::{
-    BigInt, Checkpoint, CheckpointId, CheckpointPage, DynamicFieldPage, MoveFunctionArgType,
-    ObjectsPage, Page, SuiCheckpointSequenceNumber, SuiGetPastObjectRequest,
+    AvailableHistory, BigInt, Checkpoint, CheckpointId, CheckpointPage, DynamicFieldPage,
+    MoveFunctionArgType, ObjectsPage, Page, SuiCheckpointSequenceNumber, SuiGetPastObjectRequest,
     SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct,
-    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiPastObjectResponse,
-    SuiTransactionResponse, SuiTransactionResponseOptions, SuiTransactionResponseQuery,
-    TransactionsPage,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiObjectStateProof,
+    SuiPastObjectResponse, SuiTransactionInclusionProof, SuiTransactionResponse,
+    SuiTransactionResponseOptions, SuiTransactionResponseQuery, TransactionCursor,
+    TransactionScanPage, TransactionsPage,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TxSequenceNumber};
@@ -109,13 +110,15 @@ impl<S: IndexerStore> ReadApi<S> {
     fn query_transactions_internal(
         &self,
         query: SuiTransactionResponseQuery,
-        cursor: Option<TransactionDigest>,
+        cursor: Option<TransactionCursor>,
         limit: Option<usize>,
         descending_order: Option<bool>,
     ) -> Result<TransactionsPage, IndexerError> {
         let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT)?;
         let is_descending = descending_order.unwrap_or_default();
-        let cursor_str = cursor.map(|digest| digest.to_string());
+        let cursor_str = cursor
+            .map(|cursor| self.get_transaction_at(cursor))
+            .transpose()?;
 
         let opts = query.options.unwrap_or_default();
         if !opts.only_digest() {
@@ -216,7 +219,11 @@ impl<S: IndexerStore> ReadApi<S> {
 
         let has_next_page = txn_digests.len() > limit;
         txn_digests.truncate(limit);
-        let next_cursor = txn_digests.last().cloned().map_or(cursor, Some);
+        // Only the position of the last transaction of the page is needed for the next cursor.
+        let next_cursor = match txn_digests.len().checked_sub(1) {
+            Some(last) => Some(self.get_transaction_cursor(&digests_from_db[last])?),
+            None => cursor,
+        };
 
         Ok(Page {
             data: txn_digests
@@ -228,6 +235,45 @@ impl<S: IndexerStore> ReadApi<S> {
         })
     }
 
+    /// The digest of the transaction at the position of `cursor`.
+    fn get_transaction_at(&self, cursor: TransactionCursor) -> Result<String, IndexerError> {
+        let checkpoint = self
+            .state
+            .get_checkpoint(CheckpointId::from(cursor.checkpoint))?;
+        checkpoint
+            .transactions
+            .into_iter()
+            .flatten()
+            .nth(cursor.index as usize)
+            .ok_or_else(|| {
+                IndexerError::InvalidArgumentError(format!(
+                    "Checkpoint {} has no transaction at index {}",
+                    cursor.checkpoint, cursor.index
+                ))
+            })
+    }
+
+    /// The position of the transaction in its checkpoint.
+    fn get_transaction_cursor(&self, digest: &str) -> Result<TransactionCursor, IndexerError> {
+        let checkpoint = self
+            .state
+            .get_transaction_by_digest(digest)?
+            .checkpoint_sequence_number as u64;
+        let index = self
+            .state
+            .get_checkpoint(CheckpointId::from(checkpoint))?
+            .transactions
+            .into_iter()
+            .flatten()
+            .position(|tx_digest| tx_digest == digest)
+            .ok_or_else(|| {
+                IndexerError::PostgresReadError(format!(
+                    "Checkpoint {checkpoint} does not include transaction {digest}"
+                ))
+            })?;
+        Ok(TransactionCursor::new(checkpoint, index as u64))
+    }
+
     fn get_object_with_options_internal(
         &self,
         object_id: ObjectID,
@@ -330,7 +376,7 @@ where
     async fn query_transactions(
         &self,
         query: SuiTransactionResponseQuery,
-        cursor: Option<TransactionDigest>,
+        cursor: Option<TransactionCursor>,
         limit: Option<usize>,
         descending_order: Option<bool>,
     ) -> RpcResult<TransactionsPage> {
@@ -346,6 +392,18 @@ where
         Ok(self.query_transactions_internal(query, cursor, limit, descending_order)?)
     }
 
+    async fn scan_transactions(
+        &self,
+        query: SuiTransactionResponseQuery,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<TransactionScanPage> {
+        self.fullnode
+            .scan_transactions(query, cursor, limit, descending_order)
+            .await
+    }
+
     async fn get_transactions_in_range_deprecated(
         &self,
         start: TxSequenceNumber,
@@ -481,6 +539,10 @@ where
             .into())
     }
 
+    async fn get_available_history(&self) -> RpcResult<AvailableHistory> {
+        self.fullnode.get_available_history().await
+    }
+
     async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
         if !self
             .migrated_methods
@@ -502,6 +564,31 @@ where
             .get_checkpoints(cursor, limit, descending_order)
             .await;
     }
+
+    async fn get_object_state_proof(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> RpcResult<SuiObjectStateProof> {
+        self.fullnode
+            .get_object_state_proof(object_id, version)
+            .await
+    }
+
+    async fn get_transaction_inclusion_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiTransactionInclusionProof> {
+        self.fullnode.get_transaction_inclusion_proof(digest).await
+    }
+
+    async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
+        self.fullnode.resolve_name_service_address(name).await
+    }
+
+    async fn resolve_name_service_name(&self, address: SuiAddress) -> RpcResult<Option<String>> {
+        self.fullnode.resolve_name_service_name(address).await
+    }
 }
 
 impl<S> SuiRpcModule for ReadApi<S>
@@ -9,8 +9,8 @@ use jsonrpsee::RpcModule;
 use sui_json_rpc::api::{WriteApiClient, WriteApiServer};
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_types::{
-    BigInt, DevInspectResults, DryRunTransactionResponse, SuiTransactionResponse,
-    SuiTransactionResponseOptions,
+    BigInt, DevInspectResults, DryRunTransactionResponse, SuiObjectOverride,
+    SuiTransactionResponse, SuiTransactionResponseOptions,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{EpochId, SuiAddress};
@@ -48,14 +48,29 @@ impl WriteApiServer for WriteApi {
         tx_bytes: Base64,
         gas_price: Option<BigInt>,
         epoch: Option<EpochId>,
+        show_trace: Option<bool>,
+        object_overrides: Option<Vec<SuiObjectOverride>>,
     ) -> RpcResult<DevInspectResults> {
         self.fullnode
-            .dev_inspect_transaction(sender_address, tx_bytes, gas_price, epoch)
+            .dev_inspect_transaction(
+                sender_address,
+                tx_bytes,
+                gas_price,
+                epoch,
+                show_trace,
+                object_overrides,
+            )
             .await
     }
 
-    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<DryRunTransactionResponse> {
-        self.fullnode.dry_run_transaction(tx_bytes).await
+    async fn dry_run_transaction(
+        &self,
+        tx_bytes: Base64,
+        show_trace: Option<bool>,
+    ) -> RpcResult<DryRunTransactionResponse> {
+        self.fullnode
+            .dry_run_transaction(tx_bytes, show_trace)
+            .await
     }
 }
 
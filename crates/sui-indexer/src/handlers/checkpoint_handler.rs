@@ -28,6 +28,7 @@ use crate::errors::IndexerError;
 use crate::metrics::IndexerCheckpointHandlerMetrics;
 use crate::models::checkpoints::Checkpoint;
 use crate::models::epoch::{DBEpochInfo, SystemEpochInfoEvent};
+use crate::models::events::Event;
 use crate::models::objects::{DeletedObject, Object, ObjectStatus};
 use crate::models::packages::Package;
 use crate::store::{
@@ -277,9 +278,15 @@ where
             .collect::<Result<Vec<_>, _>>()?;
 
         // Index events
+        let checkpoint_seq = <u64>::from(checkpoint.sequence_number);
         let events = transactions
             .iter()
-            .flat_map(|tx| tx.events.data.iter().map(move |event| event.clone().into()))
+            .flat_map(|tx| {
+                tx.events
+                    .data
+                    .iter()
+                    .map(move |event| Event::new(event.clone(), checkpoint_seq))
+            })
             .collect::<Vec<_>>();
 
         // Index objects
@@ -3,6 +3,7 @@
 #![recursion_limit = "256"]
 
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -25,6 +26,8 @@ use apis::{
 use errors::IndexerError;
 use handlers::checkpoint_handler::CheckpointHandler;
 use mysten_metrics::spawn_monitored_task;
+use processors::export_processor::{ExportConfig, ExportProcessor};
+use processors::retention_processor::RetentionProcessor;
 use store::IndexerStore;
 use sui_core::event_handler::EventHandler;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle, CLIENT_SDK_TYPE_HEADER};
@@ -78,6 +81,13 @@ pub struct IndexerConfig {
     pub migrated_methods: Vec<String>,
     #[clap(long)]
     pub reset_db: bool,
+    /// YAML file configuring the per-epoch export jobs, exports are disabled if not set.
+    #[clap(long)]
+    pub export_config: Option<PathBuf>,
+    /// Number of epochs of transactions, events and objects history to keep, older epoch
+    /// partitions are dropped. Everything is kept if not set.
+    #[clap(long)]
+    pub retention_epochs: Option<u64>,
 }
 
 impl IndexerConfig {
@@ -112,6 +122,8 @@ impl Default for IndexerConfig {
             rpc_server_port: 9000,
             migrated_methods: vec![],
             reset_db: false,
+            export_config: None,
+            retention_epochs: None,
         }
     }
 }
@@ -130,6 +142,16 @@ impl Indexer {
             .expect("Json rpc server should not run into errors upon start.");
         // let JSON RPC server run forever.
         spawn_monitored_task!(handle.stopped());
+        if let Some(path) = &config.export_config {
+            let export_processor =
+                ExportProcessor::new(store.clone(), ExportConfig::load(path)?, registry)?;
+            spawn_monitored_task!(export_processor.start());
+        }
+        if let Some(retention_epochs) = config.retention_epochs {
+            let retention_processor =
+                RetentionProcessor::new(store.clone(), retention_epochs, registry);
+            spawn_monitored_task!(retention_processor.start());
+        }
         info!(
             "Sui indexer of version {:?} started...",
             env!("CARGO_PKG_VERSION")
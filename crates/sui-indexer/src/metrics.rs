@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry, Histogram, IntCounter,
-    Registry,
+    register_histogram_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_with_registry, Histogram, IntCounter,
+    IntCounterVec, IntGauge, Registry,
 };
 
 /// Prometheus metrics for sui-indexer.
@@ -384,3 +385,78 @@ impl IndexerPackageProcessorMetrics {
         }
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct IndexerExportProcessorMetrics {
+    pub total_export_jobs_succeeded: IntCounterVec,
+    pub total_export_job_errors: IntCounterVec,
+    pub total_export_rows_written: IntCounterVec,
+    pub last_exported_epoch: IntGauge,
+    pub export_job_latency: Histogram,
+}
+
+impl IndexerExportProcessorMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            total_export_jobs_succeeded: register_int_counter_vec_with_registry!(
+                "total_export_jobs_succeeded",
+                "Total number of epoch export jobs completed, by job",
+                &["job"],
+                registry,
+            )
+            .unwrap(),
+            total_export_job_errors: register_int_counter_vec_with_registry!(
+                "total_export_job_errors",
+                "Total number of failed epoch export job attempts, by job",
+                &["job"],
+                registry,
+            )
+            .unwrap(),
+            total_export_rows_written: register_int_counter_vec_with_registry!(
+                "total_export_rows_written",
+                "Total number of rows written to export files, by job",
+                &["job"],
+                registry,
+            )
+            .unwrap(),
+            last_exported_epoch: register_int_gauge_with_registry!(
+                "last_exported_epoch",
+                "Last epoch for which all export jobs completed",
+                registry,
+            )
+            .unwrap(),
+            export_job_latency: register_histogram_with_registry!(
+                "export_job_latency",
+                "Time spent exporting the data of an epoch for one job",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexerRetentionProcessorMetrics {
+    pub total_partitions_dropped: IntCounter,
+    pub retention_horizon_epoch: IntGauge,
+}
+
+impl IndexerRetentionProcessorMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            total_partitions_dropped: register_int_counter_with_registry!(
+                "total_partitions_dropped",
+                "Total number of epoch partitions dropped by the retention processor",
+                registry,
+            )
+            .unwrap(),
+            retention_horizon_epoch: register_int_gauge_with_registry!(
+                "retention_horizon_epoch",
+                "First epoch whose partitioned data is retained",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rollups of the network activity, updated incrementally with the increments computed from
+//! each ingested checkpoint.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use diesel::prelude::*;
+
+use sui_json_rpc_types::{DailyMetrics, PackageMetrics};
+use sui_types::base_types::ObjectID;
+
+use crate::errors::IndexerError;
+use crate::models::checkpoints::Checkpoint;
+use crate::models::transaction_index::MoveCall;
+use crate::models::transactions::Transaction;
+use crate::schema::{daily_active_addresses, daily_metrics, package_metrics};
+
+pub const MS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+#[derive(Queryable, Insertable, Debug, Clone, Default, PartialEq, Eq)]
+#[diesel(table_name = daily_metrics)]
+pub struct DBDailyMetrics {
+    pub day: i64,
+    pub transactions: i64,
+    pub active_addresses: i64,
+    pub total_gas_cost: i64,
+    pub total_computation_cost: i64,
+    pub total_storage_cost: i64,
+    pub total_storage_rebate: i64,
+}
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[diesel(table_name = daily_active_addresses)]
+pub struct DailyActiveAddress {
+    pub day: i64,
+    pub address: String,
+}
+
+#[derive(Queryable, Insertable, Debug, Clone, PartialEq, Eq)]
+#[diesel(table_name = package_metrics)]
+pub struct DBPackageMetrics {
+    pub package_id: String,
+    pub transactions: i64,
+    pub move_calls: i64,
+    pub last_checkpoint: i64,
+}
+
+/// The increments of the rollups for one checkpoint. `daily_metrics.active_addresses` is left
+/// at 0, as only the addresses not yet active that day are counted, once committed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckpointRollup {
+    pub daily_metrics: DBDailyMetrics,
+    pub active_addresses: Vec<DailyActiveAddress>,
+    pub packages: Vec<DBPackageMetrics>,
+}
+
+impl CheckpointRollup {
+    pub fn new(
+        checkpoint: &Checkpoint,
+        transactions: &[Transaction],
+        move_calls: &[MoveCall],
+    ) -> Self {
+        // All transactions of a checkpoint share its timestamp.
+        let day = checkpoint.timestamp_ms / MS_PER_DAY;
+        let mut daily_metrics = DBDailyMetrics {
+            day,
+            transactions: transactions.len() as i64,
+            ..Default::default()
+        };
+        let mut active_addresses = BTreeSet::new();
+        for transaction in transactions {
+            daily_metrics.total_gas_cost += transaction.total_gas_cost;
+            daily_metrics.total_computation_cost += transaction.computation_cost;
+            daily_metrics.total_storage_cost += transaction.storage_cost;
+            daily_metrics.total_storage_rebate += transaction.storage_rebate;
+            active_addresses.insert(DailyActiveAddress {
+                day,
+                address: transaction.sender.clone(),
+            });
+        }
+
+        let mut packages: BTreeMap<&str, (BTreeSet<&str>, i64)> = BTreeMap::new();
+        for call in move_calls {
+            let (calling_transactions, calls) =
+                packages.entry(call.move_package.as_str()).or_default();
+            calling_transactions.insert(call.transaction_digest.as_str());
+            *calls += 1;
+        }
+        let packages = packages
+            .into_iter()
+            .map(
+                |(package_id, (calling_transactions, calls))| DBPackageMetrics {
+                    package_id: package_id.to_string(),
+                    transactions: calling_transactions.len() as i64,
+                    move_calls: calls,
+                    last_checkpoint: checkpoint.sequence_number,
+                },
+            )
+            .collect();
+
+        Self {
+            daily_metrics,
+            active_addresses: active_addresses.into_iter().collect(),
+            packages,
+        }
+    }
+}
+
+impl From<DBDailyMetrics> for DailyMetrics {
+    fn from(metrics: DBDailyMetrics) -> Self {
+        Self {
+            day: metrics.day as u64,
+            start_timestamp_ms: (metrics.day * MS_PER_DAY) as u64,
+            transactions: metrics.transactions as u64,
+            active_addresses: metrics.active_addresses as u64,
+            total_gas_cost: metrics.total_gas_cost,
+            total_computation_cost: metrics.total_computation_cost as u64,
+            total_storage_cost: metrics.total_storage_cost as u64,
+            total_storage_rebate: metrics.total_storage_rebate as u64,
+        }
+    }
+}
+
+impl TryFrom<DBPackageMetrics> for PackageMetrics {
+    type Error = IndexerError;
+
+    fn try_from(metrics: DBPackageMetrics) -> Result<Self, Self::Error> {
+        let package_id = metrics.package_id.parse::<ObjectID>().map_err(|e| {
+            IndexerError::SerdeError(format!(
+                "Failed to parse package ID {} with error: {:?}",
+                metrics.package_id, e
+            ))
+        })?;
+        Ok(Self {
+            package_id,
+            transactions: metrics.transactions as u64,
+            move_calls: metrics.move_calls as u64,
+            last_checkpoint: metrics.last_checkpoint as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(digest: &str, sender: &str, gas: i64) -> Transaction {
+        Transaction {
+            id: None,
+            transaction_digest: digest.to_string(),
+            sender: sender.to_string(),
+            recipients: vec![],
+            checkpoint_sequence_number: 7,
+            timestamp_ms: 0,
+            transaction_kind: String::new(),
+            command_count: 1,
+            created: vec![],
+            mutated: vec![],
+            deleted: vec![],
+            unwrapped: vec![],
+            wrapped: vec![],
+            move_calls: vec![],
+            gas_object_id: String::new(),
+            gas_object_sequence: 0,
+            gas_object_digest: String::new(),
+            gas_budget: 0,
+            total_gas_cost: gas,
+            computation_cost: gas,
+            storage_cost: 0,
+            storage_rebate: 0,
+            gas_price: 1,
+            raw_transaction: vec![],
+            transaction_content: String::new(),
+            transaction_effects_content: String::new(),
+            confirmed_local_execution: None,
+        }
+    }
+
+    fn move_call(digest: &str, package: &str) -> MoveCall {
+        MoveCall {
+            transaction_digest: digest.to_string(),
+            move_package: package.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_rollup() {
+        let checkpoint = Checkpoint {
+            sequence_number: 7,
+            timestamp_ms: 3 * MS_PER_DAY + 10,
+            ..Default::default()
+        };
+        let transactions = vec![
+            transaction("tx1", "0xa", 10),
+            transaction("tx2", "0xb", 20),
+            transaction("tx3", "0xa", 30),
+        ];
+        let move_calls = vec![
+            move_call("tx1", "0x2"),
+            move_call("tx1", "0x2"),
+            move_call("tx2", "0x2"),
+            move_call("tx3", "0x3"),
+        ];
+
+        let rollup = CheckpointRollup::new(&checkpoint, &transactions, &move_calls);
+        assert_eq!(
+            rollup.daily_metrics,
+            DBDailyMetrics {
+                day: 3,
+                transactions: 3,
+                active_addresses: 0,
+                total_gas_cost: 60,
+                total_computation_cost: 60,
+                total_storage_cost: 0,
+                total_storage_rebate: 0,
+            }
+        );
+        let active: Vec<_> = rollup
+            .active_addresses
+            .iter()
+            .map(|a| (a.day, a.address.as_str()))
+            .collect();
+        assert_eq!(active, vec![(3, "0xa"), (3, "0xb")]);
+        assert_eq!(
+            rollup.packages,
+            vec![
+                DBPackageMetrics {
+                    package_id: "0x2".to_string(),
+                    transactions: 2,
+                    move_calls: 3,
+                    last_checkpoint: 7,
+                },
+                DBPackageMetrics {
+                    package_id: "0x3".to_string(),
+                    transactions: 1,
+                    move_calls: 1,
+                    last_checkpoint: 7,
+                },
+            ]
+        );
+    }
+}
@@ -11,6 +11,7 @@ use std::str::FromStr;
 use sui_json_rpc_types::SuiEvent;
 use sui_types::base_types::TransactionDigest;
 use sui_types::event::EventID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 #[derive(Queryable, Insertable, Debug, Clone)]
 #[diesel(table_name = events)]
@@ -26,10 +27,11 @@ pub struct Event {
     pub event_time_ms: Option<i64>,
     pub parsed_json: Value,
     pub event_bcs: Vec<u8>,
+    pub checkpoint: i64,
 }
 
-impl From<SuiEvent> for Event {
-    fn from(se: SuiEvent) -> Self {
+impl Event {
+    pub fn new(se: SuiEvent, checkpoint: CheckpointSequenceNumber) -> Self {
         Self {
             id: None,
             transaction_digest: se.id.tx_digest.base58_encode(),
@@ -41,6 +43,7 @@ impl From<SuiEvent> for Event {
             event_time_ms: se.timestamp_ms.map(|t| t as i64),
             parsed_json: se.parsed_json,
             event_bcs: se.bcs,
+            checkpoint: checkpoint as i64,
         }
     }
 }
@@ -19,7 +19,7 @@ use sui_types::base_types::{EpochId, ObjectID, ObjectRef, ObjectType, SequenceNu
 use sui_types::digests::TransactionDigest;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::move_package::MovePackage;
-use sui_types::object::{Data, MoveObject, ObjectFormatOptions, ObjectRead, Owner};
+use sui_types::object::{Data, MoveObject, ObjectFormatOptions, ObjectRead, Owner, PastObjectRead};
 
 const OBJECT: &str = "object";
 
@@ -178,6 +178,25 @@ impl Object {
         })
     }
 
+    /// Reads a version of the object from its history. An object wrapped at this version does
+    /// not have contents of its own, it is read as deleted.
+    pub fn try_into_past_object_read(
+        self,
+        module_cache: &impl GetModule,
+    ) -> Result<PastObjectRead, IndexerError> {
+        Ok(match self.object_status {
+            ObjectStatus::Deleted | ObjectStatus::UnwrappedThenDeleted | ObjectStatus::Wrapped => {
+                PastObjectRead::ObjectDeleted(self.get_object_ref()?)
+            }
+            _ => {
+                let oref = self.get_object_ref()?;
+                let object: sui_types::object::Object = self.try_into()?;
+                let layout = object.get_layout(ObjectFormatOptions::default(), module_cache)?;
+                PastObjectRead::VersionFound(oref, object, layout)
+            }
+        })
+    }
+
     pub fn get_object_ref(&self) -> Result<ObjectRef, IndexerError> {
         let object_id = self.object_id.parse()?;
         let digest = self.object_digest.parse().map_err(|e| {
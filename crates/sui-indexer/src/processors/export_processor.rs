@@ -0,0 +1,541 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exports the events and transactions of every finished epoch to object storage as Parquet
+//! files. An epoch is only exported once all of its checkpoints have been ingested, so exports
+//! never observe a partially indexed epoch. Each job writes `part-NNNNN.parquet` files under
+//! `epoch_<N>/<job>/` followed by a `_SUCCESS` marker; jobs with a marker are skipped, and a job
+//! that is retried overwrites the same part files.
+
+use std::fs;
+use std::path::Path as FsPath;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use arrow::array::{ArrayRef, BinaryArray, Int64Array, StringArray};
+use arrow::record_batch::RecordBatch;
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use parquet::arrow::ArrowWriter;
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use sui_json_rpc_types::EpochInfo;
+use sui_storage::object_store::util::put;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::committee::EpochId;
+
+use crate::errors::IndexerError;
+use crate::metrics::IndexerExportProcessorMetrics;
+use crate::models::events::Event;
+use crate::models::transactions::Transaction;
+use crate::store::IndexerStore;
+
+const SUCCESS_MARKER: &str = "_SUCCESS";
+const EPOCH_BATCH_SIZE: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportJob {
+    Events,
+    Transactions,
+}
+
+impl ExportJob {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportJob::Events => "events",
+            ExportJob::Transactions => "transactions",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ExportConfig {
+    /// Where the exported files are written.
+    pub object_store: ObjectStoreConfig,
+    /// Prefix prepended to all exported paths.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Jobs to run for every finished epoch.
+    #[serde(default = "default_jobs")]
+    pub jobs: Vec<ExportJob>,
+    /// First epoch to export. Earlier epochs are never exported.
+    #[serde(default)]
+    pub start_epoch: EpochId,
+    /// Maximum number of rows per exported file.
+    #[serde(default = "default_rows_per_file")]
+    pub rows_per_file: usize,
+    /// How often to check whether a new epoch can be exported.
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Attempts of a job before giving up on it until the next poll.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry of a failed job, doubled on each further retry.
+    #[serde(default = "default_retry_initial_delay_ms")]
+    pub retry_initial_delay_ms: u64,
+}
+
+fn default_jobs() -> Vec<ExportJob> {
+    vec![ExportJob::Events, ExportJob::Transactions]
+}
+
+fn default_rows_per_file() -> usize {
+    100_000
+}
+
+fn default_poll_interval_secs() -> u64 {
+    60
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_retry_initial_delay_ms() -> u64 {
+    1_000
+}
+
+impl ExportConfig {
+    pub fn load(path: &FsPath) -> Result<Self, IndexerError> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Cannot read export config {}", path.display()))?;
+        serde_yaml::from_str(&content).map_err(|e| {
+            IndexerError::SerdeError(format!(
+                "Failed to parse export config {} with error: {:?}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/// Completion marker written once all files of a job have been uploaded.
+#[derive(Debug, Serialize)]
+struct ExportMarker {
+    epoch: EpochId,
+    job: &'static str,
+    first_checkpoint: i64,
+    last_checkpoint: i64,
+    rows: usize,
+    files: Vec<String>,
+}
+
+pub struct ExportProcessor<S> {
+    pub store: S,
+    pub config: ExportConfig,
+    object_store: Arc<DynObjectStore>,
+    pub export_processor_metrics: IndexerExportProcessorMetrics,
+}
+
+impl<S> ExportProcessor<S>
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
+    pub fn new(
+        store: S,
+        config: ExportConfig,
+        prometheus_registry: &Registry,
+    ) -> Result<ExportProcessor<S>, IndexerError> {
+        let object_store = config.object_store.make()?;
+        let export_processor_metrics = IndexerExportProcessorMetrics::new(prometheus_registry);
+        Ok(Self {
+            store,
+            config,
+            object_store,
+            export_processor_metrics,
+        })
+    }
+
+    pub async fn start(&self) -> Result<(), IndexerError> {
+        info!("Indexer export processor started...");
+        let mut next_epoch = self.config.start_epoch;
+        loop {
+            match self.export_finished_epochs(next_epoch).await {
+                Ok(epoch) => next_epoch = epoch,
+                Err(e) => warn!("Indexer export processor failed with error: {:?}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(self.config.poll_interval_secs)).await;
+        }
+    }
+
+    /// Exports finished epochs starting at `next_epoch`, and returns the first epoch that still
+    /// has to be exported.
+    async fn export_finished_epochs(
+        &self,
+        mut next_epoch: EpochId,
+    ) -> Result<EpochId, IndexerError> {
+        loop {
+            let epochs = self
+                .store
+                .get_epochs(next_epoch.checked_sub(1), EPOCH_BATCH_SIZE)?;
+            let latest_checkpoint = self.store.get_latest_checkpoint_sequence_number()?;
+            let batch_size = epochs.len();
+            for epoch in epochs {
+                let Some(end_of_epoch) = &epoch.end_of_epoch_info else {
+                    return Ok(next_epoch);
+                };
+                if end_of_epoch.last_checkpoint_id as i64 > latest_checkpoint {
+                    return Ok(next_epoch);
+                }
+                if !self.export_epoch(&epoch).await {
+                    return Ok(next_epoch);
+                }
+                self.export_processor_metrics
+                    .last_exported_epoch
+                    .set(epoch.epoch as i64);
+                next_epoch = epoch.epoch + 1;
+            }
+            if batch_size < EPOCH_BATCH_SIZE {
+                return Ok(next_epoch);
+            }
+        }
+    }
+
+    /// Runs every configured job for a finished epoch, returns whether they all completed.
+    async fn export_epoch(&self, epoch: &EpochInfo) -> bool {
+        let mut completed = true;
+        for job in &self.config.jobs {
+            if let Err(e) = self.run_job_with_retries(*job, epoch).await {
+                error!(
+                    "Export job {} of epoch {} failed after {} attempts with error: {:?}",
+                    job.name(),
+                    epoch.epoch,
+                    self.config.max_attempts,
+                    e
+                );
+                completed = false;
+            }
+        }
+        completed
+    }
+
+    async fn run_job_with_retries(
+        &self,
+        job: ExportJob,
+        epoch: &EpochInfo,
+    ) -> Result<(), IndexerError> {
+        let mut delay = Duration::from_millis(self.config.retry_initial_delay_ms);
+        let mut attempt = 1;
+        loop {
+            match self.run_job(job, epoch).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < self.config.max_attempts => {
+                    self.export_processor_metrics
+                        .total_export_job_errors
+                        .with_label_values(&[job.name()])
+                        .inc();
+                    warn!(
+                        "Export job {} of epoch {} failed with error: {:?}, retrying in {:?}...",
+                        job.name(),
+                        epoch.epoch,
+                        e,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.export_processor_metrics
+                        .total_export_job_errors
+                        .with_label_values(&[job.name()])
+                        .inc();
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    async fn run_job(&self, job: ExportJob, epoch: &EpochInfo) -> Result<(), IndexerError> {
+        let dir = self.job_dir(job, epoch.epoch);
+        let marker_path = dir.child(SUCCESS_MARKER);
+        if self.object_store.head(&marker_path).await.is_ok() {
+            return Ok(());
+        }
+        let _timer = self
+            .export_processor_metrics
+            .export_job_latency
+            .start_timer();
+        // The caller only schedules finished epochs.
+        let first_checkpoint = epoch.first_checkpoint_id as i64;
+        let last_checkpoint = epoch
+            .end_of_epoch_info
+            .as_ref()
+            .map(|info| info.last_checkpoint_id as i64)
+            .unwrap_or(first_checkpoint);
+
+        let mut files = vec![];
+        let mut rows = 0;
+        let mut last_processed_id = -1;
+        loop {
+            let (batch, batch_rows, batch_last_id) = match job {
+                ExportJob::Events => {
+                    let events = self.store.read_events_in_checkpoint_range(
+                        first_checkpoint,
+                        last_checkpoint,
+                        last_processed_id,
+                        self.config.rows_per_file,
+                    )?;
+                    let Some(last_id) = events.last().and_then(|e| e.id) else {
+                        break;
+                    };
+                    (events_to_parquet(&events)?, events.len(), last_id)
+                }
+                ExportJob::Transactions => {
+                    let transactions = self.store.read_transactions_in_checkpoint_range(
+                        first_checkpoint,
+                        last_checkpoint,
+                        last_processed_id,
+                        self.config.rows_per_file,
+                    )?;
+                    let Some(last_id) = transactions.last().and_then(|t| t.id) else {
+                        break;
+                    };
+                    (
+                        transactions_to_parquet(&transactions)?,
+                        transactions.len(),
+                        last_id,
+                    )
+                }
+            };
+            let file_name = format!("part-{:05}.parquet", files.len());
+            put(
+                &dir.child(file_name.as_str()),
+                batch,
+                self.object_store.clone(),
+            )
+            .await
+            .map_err(anyhow::Error::from)?;
+            files.push(file_name);
+            rows += batch_rows;
+            last_processed_id = batch_last_id;
+            if batch_rows < self.config.rows_per_file {
+                break;
+            }
+        }
+
+        let marker = ExportMarker {
+            epoch: epoch.epoch,
+            job: job.name(),
+            first_checkpoint,
+            last_checkpoint,
+            rows,
+            files,
+        };
+        let marker = serde_json::to_vec(&marker).map_err(|e| {
+            IndexerError::SerdeError(format!("Failed to serialize export marker: {:?}", e))
+        })?;
+        put(&marker_path, Bytes::from(marker), self.object_store.clone())
+            .await
+            .map_err(anyhow::Error::from)?;
+        self.export_processor_metrics
+            .total_export_jobs_succeeded
+            .with_label_values(&[job.name()])
+            .inc();
+        self.export_processor_metrics
+            .total_export_rows_written
+            .with_label_values(&[job.name()])
+            .inc_by(rows as u64);
+        info!(
+            "Exported {} {} of epoch {} to {}",
+            rows,
+            job.name(),
+            epoch.epoch,
+            dir
+        );
+        Ok(())
+    }
+
+    fn job_dir(&self, job: ExportJob, epoch: EpochId) -> Path {
+        let epoch_dir = format!("epoch_{epoch}");
+        let dir = match &self.config.prefix {
+            Some(prefix) => Path::from(prefix.as_str()).child(epoch_dir.as_str()),
+            None => Path::from(epoch_dir.as_str()),
+        };
+        dir.child(job.name())
+    }
+}
+
+fn transactions_to_parquet(transactions: &[Transaction]) -> anyhow::Result<Bytes> {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "id",
+            Arc::new(Int64Array::from_iter(transactions.iter().map(|t| t.id))) as ArrayRef,
+        ),
+        (
+            "transaction_digest",
+            Arc::new(StringArray::from_iter_values(
+                transactions.iter().map(|t| &t.transaction_digest),
+            )),
+        ),
+        (
+            "sender",
+            Arc::new(StringArray::from_iter_values(
+                transactions.iter().map(|t| &t.sender),
+            )),
+        ),
+        (
+            "checkpoint_sequence_number",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.checkpoint_sequence_number),
+            )),
+        ),
+        (
+            "timestamp_ms",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.timestamp_ms),
+            )),
+        ),
+        (
+            "transaction_kind",
+            Arc::new(StringArray::from_iter_values(
+                transactions.iter().map(|t| &t.transaction_kind),
+            )),
+        ),
+        (
+            "command_count",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.command_count),
+            )),
+        ),
+        (
+            "gas_object_id",
+            Arc::new(StringArray::from_iter_values(
+                transactions.iter().map(|t| &t.gas_object_id),
+            )),
+        ),
+        (
+            "gas_budget",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.gas_budget),
+            )),
+        ),
+        (
+            "gas_price",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.gas_price),
+            )),
+        ),
+        (
+            "total_gas_cost",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.total_gas_cost),
+            )),
+        ),
+        (
+            "computation_cost",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.computation_cost),
+            )),
+        ),
+        (
+            "storage_cost",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.storage_cost),
+            )),
+        ),
+        (
+            "storage_rebate",
+            Arc::new(Int64Array::from_iter_values(
+                transactions.iter().map(|t| t.storage_rebate),
+            )),
+        ),
+        (
+            "raw_transaction",
+            Arc::new(BinaryArray::from_iter_values(
+                transactions.iter().map(|t| &t.raw_transaction),
+            )),
+        ),
+        (
+            "transaction_effects_content",
+            Arc::new(StringArray::from_iter_values(
+                transactions.iter().map(|t| &t.transaction_effects_content),
+            )),
+        ),
+    ])?;
+    write_parquet(batch)
+}
+
+fn events_to_parquet(events: &[Event]) -> anyhow::Result<Bytes> {
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "id",
+            Arc::new(Int64Array::from_iter(events.iter().map(|e| e.id))) as ArrayRef,
+        ),
+        (
+            "transaction_digest",
+            Arc::new(StringArray::from_iter_values(
+                events.iter().map(|e| &e.transaction_digest),
+            )),
+        ),
+        (
+            "event_sequence",
+            Arc::new(Int64Array::from_iter_values(
+                events.iter().map(|e| e.event_sequence),
+            )),
+        ),
+        (
+            "sender",
+            Arc::new(StringArray::from_iter_values(
+                events.iter().map(|e| &e.sender),
+            )),
+        ),
+        (
+            "package",
+            Arc::new(StringArray::from_iter_values(
+                events.iter().map(|e| &e.package),
+            )),
+        ),
+        (
+            "module",
+            Arc::new(StringArray::from_iter_values(
+                events.iter().map(|e| &e.module),
+            )),
+        ),
+        (
+            "event_type",
+            Arc::new(StringArray::from_iter_values(
+                events.iter().map(|e| &e.event_type),
+            )),
+        ),
+        (
+            "event_time_ms",
+            Arc::new(Int64Array::from_iter(
+                events.iter().map(|e| e.event_time_ms),
+            )),
+        ),
+        (
+            "parsed_json",
+            Arc::new(StringArray::from_iter_values(
+                events.iter().map(|e| e.parsed_json.to_string()),
+            )),
+        ),
+        (
+            "event_bcs",
+            Arc::new(BinaryArray::from_iter_values(
+                events.iter().map(|e| &e.event_bcs),
+            )),
+        ),
+        (
+            "checkpoint",
+            Arc::new(Int64Array::from_iter_values(
+                events.iter().map(|e| e.checkpoint),
+            )),
+        ),
+    ])?;
+    write_parquet(batch)
+}
+
+fn write_parquet(batch: RecordBatch) -> anyhow::Result<Bytes> {
+    let mut buffer = vec![];
+    let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(Bytes::from(buffer))
+}
@@ -1,5 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod export_processor;
 pub mod object_processor;
 pub mod processor_orchestrator;
+pub mod retention_processor;
@@ -0,0 +1,97 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Drops the epoch partitions of the partitioned tables (transactions, events and objects
+//! history) once they fall out of the configured retention horizon, keeping the data of the
+//! current epoch and of the `retention_epochs - 1` epochs before it. The horizon should be
+//! larger than the lag of the export processor, so epochs are exported before being dropped.
+
+use std::time::Duration;
+
+use prometheus::Registry;
+use tracing::{info, warn};
+
+use sui_types::committee::EpochId;
+
+use crate::errors::IndexerError;
+use crate::metrics::IndexerRetentionProcessorMetrics;
+use crate::store::IndexerStore;
+
+const RETENTION_POLL_INTERVAL: Duration = Duration::from_secs(600);
+
+pub struct RetentionProcessor<S> {
+    pub store: S,
+    pub retention_epochs: u64,
+    pub retention_processor_metrics: IndexerRetentionProcessorMetrics,
+}
+
+impl<S> RetentionProcessor<S>
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
+    pub fn new(
+        store: S,
+        retention_epochs: u64,
+        prometheus_registry: &Registry,
+    ) -> RetentionProcessor<S> {
+        let retention_processor_metrics =
+            IndexerRetentionProcessorMetrics::new(prometheus_registry);
+        Self {
+            store,
+            retention_epochs,
+            retention_processor_metrics,
+        }
+    }
+
+    pub async fn start(&self) -> Result<(), IndexerError> {
+        info!(
+            "Indexer retention processor started, keeping {} epochs...",
+            self.retention_epochs
+        );
+        loop {
+            if let Err(e) = self.drop_expired_partitions() {
+                warn!("Indexer retention processor failed with error: {:?}", e);
+            }
+            tokio::time::sleep(RETENTION_POLL_INTERVAL).await;
+        }
+    }
+
+    fn drop_expired_partitions(&self) -> Result<(), IndexerError> {
+        let current_epoch = self.store.get_current_epoch()?.epoch;
+        let Some(horizon) = retention_horizon(current_epoch, self.retention_epochs) else {
+            return Ok(());
+        };
+        let dropped = self.store.drop_partitions_before_epoch(horizon)?;
+        self.retention_processor_metrics
+            .retention_horizon_epoch
+            .set(horizon as i64);
+        self.retention_processor_metrics
+            .total_partitions_dropped
+            .inc_by(dropped as u64);
+        Ok(())
+    }
+}
+
+/// First epoch to keep when retaining `retention_epochs` epochs up to `current_epoch`, if any
+/// epoch is out of the horizon.
+fn retention_horizon(current_epoch: EpochId, retention_epochs: u64) -> Option<EpochId> {
+    (current_epoch + 1)
+        .checked_sub(retention_epochs.max(1))
+        .filter(|horizon| *horizon > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::retention_horizon;
+
+    #[test]
+    fn test_retention_horizon() {
+        assert_eq!(retention_horizon(0, 1), None);
+        assert_eq!(retention_horizon(5, 10), None);
+        assert_eq!(retention_horizon(5, 6), None);
+        assert_eq!(retention_horizon(5, 5), Some(1));
+        assert_eq!(retention_horizon(5, 1), Some(5));
+        // At least the current epoch is always kept.
+        assert_eq!(retention_horizon(5, 0), Some(5));
+    }
+}
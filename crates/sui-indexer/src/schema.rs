@@ -56,6 +56,25 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    daily_active_addresses (day, address) {
+        day -> Int8,
+        address -> Varchar,
+    }
+}
+
+diesel::table! {
+    daily_metrics (day) {
+        day -> Int8,
+        transactions -> Int8,
+        active_addresses -> Int8,
+        total_gas_cost -> Int8,
+        total_computation_cost -> Int8,
+        total_storage_cost -> Int8,
+        total_storage_rebate -> Int8,
+    }
+}
+
 diesel::table! {
     epochs (epoch) {
         epoch -> Int8,
@@ -79,7 +98,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    events (id) {
+    events (id, checkpoint) {
         id -> Int8,
         transaction_digest -> Varchar,
         event_sequence -> Int8,
@@ -90,6 +109,7 @@ diesel::table! {
         event_time_ms -> Nullable<Int8>,
         parsed_json -> Jsonb,
         event_bcs -> Bytea,
+        checkpoint -> Int8,
     }
 }
 
@@ -117,6 +137,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ObjectStatus;
+
+    object_versions (object_id, version) {
+        object_id -> Varchar,
+        version -> Int8,
+        epoch -> Int8,
+        checkpoint -> Int8,
+        object_digest -> Varchar,
+        object_status -> ObjectStatus,
+        previous_transaction -> Varchar,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::OwnerType;
@@ -167,6 +202,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    package_metrics (package_id) {
+        package_id -> Varchar,
+        transactions -> Int8,
+        move_calls -> Int8,
+        last_checkpoint -> Int8,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::BcsBytes;
@@ -217,7 +261,7 @@ diesel::table! {
 }
 
 diesel::table! {
-    transactions (id) {
+    transactions (id, checkpoint_sequence_number) {
         id -> Int8,
         transaction_digest -> Varchar,
         sender -> Varchar,
@@ -297,12 +341,16 @@ diesel::allow_tables_to_appear_in_same_query!(
     addresses,
     at_risk_validators,
     checkpoints,
+    daily_active_addresses,
+    daily_metrics,
     epochs,
     events,
     input_objects,
     move_calls,
+    object_versions,
     objects,
     objects_history,
+    package_metrics,
     packages,
     recipients,
     system_states,
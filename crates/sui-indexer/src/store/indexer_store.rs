@@ -4,15 +4,16 @@
 use async_trait::async_trait;
 
 use sui_json_rpc_types::{
-    Checkpoint as RpcCheckpoint, CheckpointId, EpochInfo, EventFilter, EventPage, SuiObjectData,
-    SuiObjectDataFilter, SuiTransactionResponseOptions,
+    Checkpoint as RpcCheckpoint, CheckpointId, CheckpointMetrics, DailyMetrics, EpochInfo,
+    EventFilter, EventPage, PackageMetrics, SuiObjectData, SuiObjectDataFilter,
+    SuiTransactionResponseOptions,
 };
 use sui_types::base_types::{EpochId, ObjectID, SequenceNumber};
 use sui_types::digests::CheckpointDigest;
 use sui_types::error::SuiError;
 use sui_types::event::EventID;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
-use sui_types::object::ObjectRead;
+use sui_types::object::{ObjectRead, PastObjectRead};
 use sui_types::storage::ObjectStore;
 
 use crate::errors::IndexerError;
@@ -61,6 +62,17 @@ pub trait IndexerStore {
         limit: usize,
     ) -> Result<Vec<ObjectRead>, IndexerError>;
 
+    /// The versions of an object created in checkpoints `[start_checkpoint, end_checkpoint]`,
+    /// after version `cursor` if set, in ascending order, with the checkpoint of each version.
+    fn get_object_history(
+        &self,
+        object_id: ObjectID,
+        start_checkpoint: CheckpointSequenceNumber,
+        end_checkpoint: CheckpointSequenceNumber,
+        cursor: Option<SequenceNumber>,
+        limit: usize,
+    ) -> Result<Vec<(CheckpointSequenceNumber, PastObjectRead)>, IndexerError>;
+
     fn get_total_transaction_number_from_checkpoints(&self) -> Result<i64, IndexerError>;
 
     // TODO: combine all get_transaction* methods
@@ -156,12 +168,50 @@ pub trait IndexerStore {
         limit: usize,
     ) -> Result<Vec<Transaction>, IndexerError>;
 
+    /// Transactions of checkpoints `first_checkpoint..=last_checkpoint` with id greater than
+    /// `last_processed_id`, ordered by id.
+    fn read_transactions_in_checkpoint_range(
+        &self,
+        first_checkpoint: i64,
+        last_checkpoint: i64,
+        last_processed_id: i64,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, IndexerError>;
+
+    /// Events emitted by transactions of checkpoints `first_checkpoint..=last_checkpoint` with id
+    /// greater than `last_processed_id`, ordered by id.
+    fn read_events_in_checkpoint_range(
+        &self,
+        first_checkpoint: i64,
+        last_checkpoint: i64,
+        last_processed_id: i64,
+        limit: usize,
+    ) -> Result<Vec<Event>, IndexerError>;
+
     fn get_total_addresses(&self) -> Result<u64, IndexerError>;
     fn get_total_objects(&self) -> Result<u64, IndexerError>;
     fn get_total_packages(&self) -> Result<u64, IndexerError>;
 
+    /// The metrics of the days after `cursor` if set, in ascending order.
+    fn get_daily_metrics(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<DailyMetrics>, IndexerError>;
+    /// The packages called by the most transactions, in descending order of transactions.
+    fn get_top_packages(&self, limit: usize) -> Result<Vec<PackageMetrics>, IndexerError>;
+    /// The metrics of the checkpoints after `cursor` if set, in ascending order.
+    fn get_checkpoint_metrics(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: usize,
+    ) -> Result<Vec<CheckpointMetrics>, IndexerError>;
+
     fn persist_checkpoint(&self, data: &TemporaryCheckpointStore) -> Result<usize, IndexerError>;
     fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<(), IndexerError>;
+    /// Drops the partitioned data of epochs before `epoch`, returning the number of partitions
+    /// dropped.
+    fn drop_partitions_before_epoch(&self, epoch: EpochId) -> Result<usize, IndexerError>;
 
     fn get_epochs(
         &self,
@@ -5,21 +5,25 @@ use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use anyhow::anyhow;
 use async_trait::async_trait;
 use diesel::dsl::{count, max};
+use diesel::pg::Pg;
 use diesel::query_builder::AsQuery;
-use diesel::sql_types::{BigInt, VarChar};
+use diesel::sql_types::{BigInt, Bool, VarChar};
 use diesel::upsert::excluded;
-use diesel::{ExpressionMethods, PgArrayExpressionMethods};
-use diesel::{OptionalExtension, QueryableByName};
+use diesel::{
+    BoolExpressionMethods, BoxableExpression, ExpressionMethods, IntoSql, PgArrayExpressionMethods,
+    TextExpressionMethods,
+};
+use diesel::{OptionalExtension, PgConnection, QueryResult, QueryableByName};
 use diesel::{QueryDsl, RunQueryDsl};
 use move_bytecode_utils::module_cache::SyncModuleCache;
 use tracing::info;
 
 use sui_json_rpc::{ObjectProvider, ObjectProviderCache};
 use sui_json_rpc_types::{
-    CheckpointId, EpochInfo, EventFilter, EventPage, SuiEvent, SuiObjectDataFilter,
+    CheckpointId, CheckpointMetrics, DailyMetrics, EpochInfo, EventFilter, EventPage,
+    PackageMetrics, SuiEvent, SuiObjectDataFilter,
 };
 use sui_json_rpc_types::{
     SuiTransaction, SuiTransactionEffects, SuiTransactionEffectsAPI, SuiTransactionEvents,
@@ -31,9 +35,10 @@ use sui_types::digests::CheckpointDigest;
 use sui_types::digests::TransactionDigest;
 use sui_types::event::EventID;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
-use sui_types::object::ObjectRead;
+use sui_types::object::{ObjectRead, PastObjectRead};
 
 use crate::errors::{Context, IndexerError};
+use crate::models::analytics::{CheckpointRollup, DBDailyMetrics, DBPackageMetrics};
 use crate::models::checkpoints::Checkpoint;
 use crate::models::epoch::DBEpochInfo;
 use crate::models::events::Event;
@@ -41,16 +46,17 @@ use crate::models::objects::Object;
 use crate::models::system_state::DBValidatorSummary;
 use crate::models::transactions::Transaction;
 use crate::schema::{
-    addresses, checkpoints, checkpoints::dsl as checkpoints_dsl, epochs, epochs::dsl as epochs_dsl,
-    events, input_objects, input_objects::dsl as input_objects_dsl, move_calls,
-    move_calls::dsl as move_calls_dsl, objects, objects::dsl as objects_dsl, objects_history,
+    addresses, checkpoints, checkpoints::dsl as checkpoints_dsl, daily_active_addresses,
+    daily_metrics, epochs, epochs::dsl as epochs_dsl, events, input_objects,
+    input_objects::dsl as input_objects_dsl, move_calls, move_calls::dsl as move_calls_dsl,
+    object_versions, objects, objects::dsl as objects_dsl, objects_history, package_metrics,
     packages, recipients, recipients::dsl as recipients_dsl, system_states, transactions,
     transactions::dsl as transactions_dsl, validators,
 };
 use crate::store::diesel_marco::{read_only, transactional};
 use crate::store::indexer_store::TemporaryCheckpointStore;
 use crate::store::module_resolver::IndexerModuleResolver;
-use crate::store::query::DBFilter;
+use crate::store::query::{event_type_like_patterns, DBFilter};
 use crate::store::{IndexerStore, TemporaryEpochStore};
 use crate::types::SuiTransactionFullResponse;
 use crate::utils::{get_balance_changes_from_effect, get_object_changes};
@@ -60,15 +66,15 @@ const MAX_EVENT_PAGE_SIZE: usize = 1000;
 const PG_COMMIT_CHUNK_SIZE: usize = 1000;
 
 const GET_PARTITION_SQL: &str = r#"
-SELECT parent.relname                           AS table_name,
-       MAX(SUBSTRING(child.relname FROM '\d$')) AS last_partition
+SELECT parent.relname                                        AS table_name,
+       CAST(SUBSTRING(child.relname FROM '\d+$') AS BIGINT) AS partition
 FROM pg_inherits
          JOIN pg_class parent ON pg_inherits.inhparent = parent.oid
          JOIN pg_class child ON pg_inherits.inhrelid = child.oid
          JOIN pg_namespace nmsp_parent ON nmsp_parent.oid = parent.relnamespace
          JOIN pg_namespace nmsp_child ON nmsp_child.oid = child.relnamespace
 WHERE parent.relkind = 'p'
-GROUP BY table_name;
+ORDER BY table_name, partition;
 "#;
 
 #[derive(QueryableByName, Debug, Clone)]
@@ -245,6 +251,14 @@ impl IndexerStore for PgIndexerStore {
                 boxed_query =
                     boxed_query.filter(events::dsl::event_type.eq(struct_name.to_string()));
             }
+            EventFilter::MoveEventTypePattern(pattern) => {
+                let mut condition: Box<dyn BoxableExpression<events::table, Pg, SqlType = Bool>> =
+                    Box::new(false.into_sql::<Bool>());
+                for like in event_type_like_patterns(&pattern) {
+                    condition = Box::new(condition.or(events::dsl::event_type.like(like)));
+                }
+                boxed_query = boxed_query.filter(condition);
+            }
             EventFilter::Sender(sender) => {
                 boxed_query = boxed_query.filter(events::dsl::sender.eq(sender.to_string()));
             }
@@ -489,6 +503,69 @@ impl IndexerStore for PgIndexerStore {
         }
     }
 
+    fn get_object_history(
+        &self,
+        object_id: ObjectID,
+        start_checkpoint: CheckpointSequenceNumber,
+        end_checkpoint: CheckpointSequenceNumber,
+        cursor: Option<SequenceNumber>,
+        limit: usize,
+    ) -> Result<Vec<(CheckpointSequenceNumber, PastObjectRead)>, IndexerError> {
+        let (start, end) = (start_checkpoint as i64, end_checkpoint as i64);
+        let objects = read_only!(&self.cp, |conn| {
+            let mut query = object_versions::table
+                .select(object_versions::version)
+                .filter(object_versions::object_id.eq(object_id.to_string()))
+                .filter(object_versions::checkpoint.between(start, end))
+                .into_boxed();
+            if let Some(cursor) = cursor {
+                query = query.filter(object_versions::version.gt(cursor.value() as i64));
+            }
+            let versions = query
+                .order(object_versions::version.asc())
+                .limit(limit as i64)
+                .load::<i64>(conn)?;
+
+            // The checkpoint range prunes the partitions of objects_history to scan.
+            objects_history::dsl::objects_history
+                .select((
+                    objects_history::epoch,
+                    objects_history::checkpoint,
+                    objects_history::object_id,
+                    objects_history::version,
+                    objects_history::object_digest,
+                    objects_history::owner_type,
+                    objects_history::owner_address,
+                    objects_history::initial_shared_version,
+                    objects_history::previous_transaction,
+                    objects_history::object_type,
+                    objects_history::object_status,
+                    objects_history::has_public_transfer,
+                    objects_history::storage_rebate,
+                    objects_history::bcs,
+                ))
+                .filter(objects_history::checkpoint.between(start, end))
+                .filter(objects_history::object_id.eq(object_id.to_string()))
+                .filter(objects_history::version.eq_any(versions))
+                .order(objects_history::version.asc())
+                .load::<Object>(conn)
+        })
+        .context(&format!(
+            "Failed reading history of object {object_id} in checkpoints {start_checkpoint} to {end_checkpoint}"
+        ))?;
+
+        objects
+            .into_iter()
+            .map(|object| {
+                let checkpoint = object.checkpoint as CheckpointSequenceNumber;
+                Ok((
+                    checkpoint,
+                    object.try_into_past_object_read(&self.module_cache)?,
+                ))
+            })
+            .collect()
+    }
+
     fn query_objects(
         &self,
         filter: SuiObjectDataFilter,
@@ -851,6 +928,51 @@ impl IndexerStore for PgIndexerStore {
         ))
     }
 
+    fn read_transactions_in_checkpoint_range(
+        &self,
+        first_checkpoint: i64,
+        last_checkpoint: i64,
+        last_processed_id: i64,
+        limit: usize,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        read_only!(&self.cp, |conn| {
+            transactions_dsl::transactions
+                .filter(transactions_dsl::checkpoint_sequence_number.ge(first_checkpoint))
+                .filter(transactions_dsl::checkpoint_sequence_number.le(last_checkpoint))
+                .filter(transactions_dsl::id.gt(last_processed_id))
+                .order_by(transactions_dsl::id.asc())
+                .limit(limit as i64)
+                .load::<Transaction>(conn)
+        })
+        .context(&format!(
+            "Failed reading transactions of checkpoints {first_checkpoint} to {last_checkpoint} with last_processed_id {last_processed_id}"
+        ))
+    }
+
+    fn read_events_in_checkpoint_range(
+        &self,
+        first_checkpoint: i64,
+        last_checkpoint: i64,
+        last_processed_id: i64,
+        limit: usize,
+    ) -> Result<Vec<Event>, IndexerError> {
+        read_only!(&self.cp, |conn| {
+            let digests = transactions_dsl::transactions
+                .select(transactions_dsl::transaction_digest)
+                .filter(transactions_dsl::checkpoint_sequence_number.ge(first_checkpoint))
+                .filter(transactions_dsl::checkpoint_sequence_number.le(last_checkpoint));
+            events::table
+                .filter(events::dsl::transaction_digest.eq_any(digests))
+                .filter(events::dsl::id.gt(last_processed_id))
+                .order_by(events::dsl::id.asc())
+                .limit(limit as i64)
+                .load::<Event>(conn)
+        })
+        .context(&format!(
+            "Failed reading events of checkpoints {first_checkpoint} to {last_checkpoint} with last_processed_id {last_processed_id}"
+        ))
+    }
+
     fn get_total_addresses(&self) -> Result<u64, IndexerError> {
         let total_addresses = read_only!(&self.cp, |conn| {
             addresses::table
@@ -878,6 +1000,70 @@ impl IndexerStore for PgIndexerStore {
         Ok(total_packages as u64)
     }
 
+    fn get_daily_metrics(
+        &self,
+        cursor: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<DailyMetrics>, IndexerError> {
+        let day = cursor.map(|day| day as i64).unwrap_or(-1);
+        let metrics: Vec<DBDailyMetrics> = read_only!(&self.cp, |conn| {
+            daily_metrics::table
+                .filter(daily_metrics::day.gt(day))
+                .order(daily_metrics::day.asc())
+                .limit(limit as i64)
+                .load(conn)
+        })
+        .context("Failed reading daily metrics from PostgresDB")?;
+        Ok(metrics.into_iter().map(DailyMetrics::from).collect())
+    }
+
+    fn get_top_packages(&self, limit: usize) -> Result<Vec<PackageMetrics>, IndexerError> {
+        let metrics: Vec<DBPackageMetrics> = read_only!(&self.cp, |conn| {
+            package_metrics::table
+                .order((
+                    package_metrics::transactions.desc(),
+                    package_metrics::package_id.asc(),
+                ))
+                .limit(limit as i64)
+                .load(conn)
+        })
+        .context("Failed reading package metrics from PostgresDB")?;
+        metrics.into_iter().map(PackageMetrics::try_from).collect()
+    }
+
+    fn get_checkpoint_metrics(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: usize,
+    ) -> Result<Vec<CheckpointMetrics>, IndexerError> {
+        let sequence_number = cursor.map(|seq| seq as i64).unwrap_or(-1);
+        let metrics: Vec<(i64, i64, i64, f32)> = read_only!(&self.cp, |conn| {
+            checkpoints::table
+                .select((
+                    checkpoints::sequence_number,
+                    checkpoints::timestamp_ms,
+                    checkpoints::total_transactions,
+                    checkpoints::checkpoint_tps,
+                ))
+                .filter(checkpoints::sequence_number.gt(sequence_number))
+                .order(checkpoints::sequence_number.asc())
+                .limit(limit as i64)
+                .load(conn)
+        })
+        .context("Failed reading checkpoint metrics from PostgresDB")?;
+        Ok(metrics
+            .into_iter()
+            .map(
+                |(sequence_number, timestamp_ms, transactions, tps)| CheckpointMetrics {
+                    sequence_number: sequence_number as u64,
+                    timestamp_ms: timestamp_ms as u64,
+                    transactions: transactions as u64,
+                    tps: tps as f64,
+                },
+            )
+            .collect())
+    }
+
     fn persist_checkpoint(&self, data: &TemporaryCheckpointStore) -> Result<usize, IndexerError> {
         let TemporaryCheckpointStore {
             checkpoint,
@@ -1024,6 +1210,63 @@ impl IndexerStore for PgIndexerStore {
                     .context("Failed writing recipients to PostgresDB")?;
             }
 
+            // Update analytics rollups
+            let rollup = CheckpointRollup::new(checkpoint, transactions, move_calls);
+            let mut new_active_addresses = 0;
+            for active_addresses_chunk in rollup.active_addresses.chunks(PG_COMMIT_CHUNK_SIZE) {
+                new_active_addresses += diesel::insert_into(daily_active_addresses::table)
+                    .values(active_addresses_chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing daily active addresses to PostgresDB")?;
+            }
+            let daily = DBDailyMetrics {
+                active_addresses: new_active_addresses as i64,
+                ..rollup.daily_metrics
+            };
+            diesel::insert_into(daily_metrics::table)
+                .values(&daily)
+                .on_conflict(daily_metrics::day)
+                .do_update()
+                .set((
+                    daily_metrics::transactions
+                        .eq(daily_metrics::transactions + excluded(daily_metrics::transactions)),
+                    daily_metrics::active_addresses.eq(daily_metrics::active_addresses
+                        + excluded(daily_metrics::active_addresses)),
+                    daily_metrics::total_gas_cost.eq(daily_metrics::total_gas_cost
+                        + excluded(daily_metrics::total_gas_cost)),
+                    daily_metrics::total_computation_cost.eq(daily_metrics::total_computation_cost
+                        + excluded(daily_metrics::total_computation_cost)),
+                    daily_metrics::total_storage_cost.eq(daily_metrics::total_storage_cost
+                        + excluded(daily_metrics::total_storage_cost)),
+                    daily_metrics::total_storage_rebate.eq(daily_metrics::total_storage_rebate
+                        + excluded(daily_metrics::total_storage_rebate)),
+                ))
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed writing daily metrics to PostgresDB")?;
+            for packages_chunk in rollup.packages.chunks(PG_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(package_metrics::table)
+                    .values(packages_chunk)
+                    .on_conflict(package_metrics::package_id)
+                    .do_update()
+                    .set(
+                        (
+                            package_metrics::transactions.eq(package_metrics::transactions
+                                + excluded(package_metrics::transactions)),
+                            package_metrics::move_calls
+                                .eq(package_metrics::move_calls
+                                    + excluded(package_metrics::move_calls)),
+                            package_metrics::last_checkpoint
+                                .eq(excluded(package_metrics::last_checkpoint)),
+                        ),
+                    )
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing package metrics to PostgresDB")?;
+            }
+
             // update epoch transaction count
             let sql = "UPDATE epochs e1
 SET epoch_total_transactions = e2.epoch_total_transactions + $1
@@ -1047,14 +1290,7 @@ WHERE e1.epoch = e2.epoch
     }
 
     fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<(), IndexerError> {
-        let last_epoch_cp_id = if data.last_epoch.is_none() {
-            0
-        } else {
-            self.get_current_epoch()?.first_checkpoint_id as i64
-        };
-
-        self.partition_manager
-            .advance_epoch(&data.new_epoch, last_epoch_cp_id)?;
+        self.partition_manager.advance_epoch(&data.new_epoch)?;
         let mut pg_pool_conn = get_pg_pool_connection(&self.cp)?;
         pg_pool_conn
             .build_transaction()
@@ -1114,6 +1350,10 @@ WHERE e1.epoch = e2.epoch
         &self.module_cache
     }
 
+    fn drop_partitions_before_epoch(&self, epoch: EpochId) -> Result<usize, IndexerError> {
+        self.partition_manager.drop_partitions_before(epoch)
+    }
+
     fn get_epochs(
         &self,
         cursor: Option<EpochId>,
@@ -1219,30 +1459,32 @@ impl PartitionManager {
         Ok(manager)
     }
 
-    fn advance_epoch(
-        &self,
-        new_epoch: &DBEpochInfo,
-        last_epoch_start_cp: i64,
-    ) -> Result<(), IndexerError> {
-        let next_epoch_id = new_epoch.epoch;
-        let last_epoch_id = new_epoch.epoch - 1;
+    /// Splits the last partition of each partitioned table at the first checkpoint of
+    /// `new_epoch`, creating the partition of the new epoch.
+    fn advance_epoch(&self, new_epoch: &DBEpochInfo) -> Result<(), IndexerError> {
+        let next_epoch_id = new_epoch.epoch as u64;
         let next_epoch_start_cp = new_epoch.first_checkpoint_id;
 
         let tables = self.get_table_partitions()?;
         let table_updated = transactional!(&self.cp, |conn| {
             let mut updated_table = vec![];
-            for (table, last_partition) in &tables {
-                if last_partition < &(next_epoch_id as u64) {
-                    let detach_partition = format!(
-                        "ALTER TABLE {table} DETACH PARTITION {table}_partition_{last_epoch_id};"
-                    );
-                    let attach_partition_with_new_range = format!("ALTER TABLE {table} ATTACH PARTITION {table}_partition_{last_epoch_id} FOR VALUES FROM ('{last_epoch_start_cp}') TO ('{next_epoch_start_cp}');");
-                    let new_partition = format!("CREATE TABLE {table}_partition_{next_epoch_id} PARTITION OF {table} FOR VALUES FROM ({next_epoch_start_cp}) TO (MAXVALUE);");
-                    diesel::sql_query(detach_partition).execute(conn)?;
-                    diesel::sql_query(attach_partition_with_new_range).execute(conn)?;
-                    diesel::sql_query(new_partition).execute(conn)?;
-                    updated_table.push(table);
+            for (table, partitions) in &tables {
+                let Some(&last_partition) = partitions.last() else {
+                    continue;
+                };
+                if last_partition >= next_epoch_id {
+                    continue;
                 }
+                let last_partition_start_cp = Self::epoch_start_checkpoint(conn, last_partition)?;
+                let detach_partition = format!(
+                    "ALTER TABLE {table} DETACH PARTITION {table}_partition_{last_partition};"
+                );
+                let attach_partition_with_new_range = format!("ALTER TABLE {table} ATTACH PARTITION {table}_partition_{last_partition} FOR VALUES FROM ('{last_partition_start_cp}') TO ('{next_epoch_start_cp}');");
+                let new_partition = format!("CREATE TABLE {table}_partition_{next_epoch_id} PARTITION OF {table} FOR VALUES FROM ({next_epoch_start_cp}) TO (MAXVALUE);");
+                diesel::sql_query(detach_partition).execute(conn)?;
+                diesel::sql_query(attach_partition_with_new_range).execute(conn)?;
+                diesel::sql_query(new_partition).execute(conn)?;
+                updated_table.push(table);
             }
             Ok::<_, diesel::result::Error>(updated_table)
         })?;
@@ -1250,26 +1492,75 @@ impl PartitionManager {
         Ok(())
     }
 
-    fn get_table_partitions(&self) -> Result<BTreeMap<String, u64>, IndexerError> {
+    /// Detaches and drops the partitions only holding data of epochs before `epoch`. The last
+    /// partition of a table is never dropped. The versions of `object_versions` are deleted
+    /// along with the partitions of `objects_history` holding their contents. Returns the number
+    /// of partitions dropped.
+    fn drop_partitions_before(&self, epoch: u64) -> Result<usize, IndexerError> {
+        let tables = self.get_table_partitions()?;
+        let mut dropped = vec![];
+        for (table, partitions) in &tables {
+            // A partition holds the epochs up to the start of the next partition.
+            for window in partitions.windows(2) {
+                let (partition, next_partition) = (window[0], window[1]);
+                if next_partition > epoch {
+                    break;
+                }
+                transactional!(&self.cp, |conn| {
+                    if table == "objects_history" {
+                        let next_partition_start_cp =
+                            Self::epoch_start_checkpoint(conn, next_partition)?;
+                        diesel::delete(
+                            object_versions::table
+                                .filter(object_versions::checkpoint.lt(next_partition_start_cp)),
+                        )
+                        .execute(conn)?;
+                    }
+                    diesel::sql_query(format!(
+                        "ALTER TABLE {table} DETACH PARTITION {table}_partition_{partition};"
+                    ))
+                    .execute(conn)?;
+                    diesel::sql_query(format!("DROP TABLE {table}_partition_{partition};"))
+                        .execute(conn)
+                })?;
+                dropped.push(format!("{table}_partition_{partition}"));
+            }
+        }
+        if !dropped.is_empty() {
+            info!("Dropped partitions before epoch {epoch}: {dropped:?}");
+        }
+        Ok(dropped.len())
+    }
+
+    fn epoch_start_checkpoint(conn: &mut PgConnection, epoch: u64) -> QueryResult<i64> {
+        if epoch == 0 {
+            return Ok(0);
+        }
+        epochs::table
+            .select(epochs::first_checkpoint_id)
+            .filter(epochs::epoch.eq(epoch as i64))
+            .first::<i64>(conn)
+    }
+
+    fn get_table_partitions(&self) -> Result<BTreeMap<String, Vec<u64>>, IndexerError> {
         #[derive(QueryableByName, Debug, Clone)]
         struct PartitionedTable {
             #[diesel(sql_type = VarChar)]
             table_name: String,
-            #[diesel(sql_type = VarChar)]
-            last_partition: String,
+            #[diesel(sql_type = BigInt)]
+            partition: i64,
         }
 
-        Ok(
-            read_only!(&self.cp, |conn| diesel::sql_query(GET_PARTITION_SQL)
-                .load(conn))?
-            .into_iter()
-            .map(|table: PartitionedTable| {
-                u64::from_str(&table.last_partition)
-                    .map(|last_partition| (table.table_name, last_partition))
-                    .map_err(|e| anyhow!(e))
-            })
-            .collect::<Result<_, _>>()?,
-        )
+        let mut tables: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+        for table in read_only!(&self.cp, |conn| diesel::sql_query(GET_PARTITION_SQL)
+            .load::<PartitionedTable>(conn))?
+        {
+            tables
+                .entry(table.table_name)
+                .or_default()
+                .push(table.partition as u64);
+        }
+        Ok(tables)
     }
 }
 
@@ -1,7 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use sui_json_rpc_types::SuiObjectDataFilter;
+use move_core_types::language_storage::StructTag;
+use sui_json_rpc_types::{MoveTypePattern, SuiObjectDataFilter};
 use sui_types::base_types::ObjectID;
 
 pub trait DBFilter<C> {
@@ -134,6 +135,56 @@ fn to_outer_clauses(filter: &SuiObjectDataFilter) -> Option<String> {
     }
 }
 
+/// The `LIKE` patterns selecting the events whose `event_type` matches `pattern`, any of which may
+/// match, so that the matching events are found through the `text_pattern_ops` index on
+/// `event_type`. Every wildcard type argument becomes a `%`, which stands for a single type
+/// argument as the number of type arguments of a struct is fixed.
+pub fn event_type_like_patterns(pattern: &MoveTypePattern) -> Vec<String> {
+    match pattern {
+        MoveTypePattern::Package(package) => {
+            vec![format!("{}::%", escape_like(&package.to_hex_literal()))]
+        }
+        MoveTypePattern::Module { package, module } => vec![format!(
+            "{}::{}::%",
+            escape_like(&package.to_hex_literal()),
+            escape_like(module.as_str())
+        )],
+        MoveTypePattern::Struct {
+            package,
+            module,
+            name,
+            type_params,
+        } => {
+            let struct_tag = StructTag {
+                address: (*package).into(),
+                module: module.clone(),
+                name: name.clone(),
+                type_params: vec![],
+            };
+            let like = escape_like(&struct_tag.to_string());
+            if type_params.is_empty() {
+                // The struct itself if it is not generic, or any of its instantiations.
+                let instantiations = format!("{like}<%");
+                return vec![like, instantiations];
+            }
+            let type_params = type_params
+                .iter()
+                .map(|type_param| match type_param {
+                    Some(tag) => escape_like(&tag.to_string()),
+                    None => "%".to_string(),
+                })
+                .collect::<Vec<_>>();
+            vec![format!("{like}<{}>", type_params.join(", "))]
+        }
+    }
+}
+
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
@@ -141,10 +192,10 @@ mod test {
     use move_core_types::ident_str;
     use move_core_types::parser::parse_struct_tag;
 
-    use sui_json_rpc_types::SuiObjectDataFilter;
+    use sui_json_rpc_types::{MoveTypePattern, SuiObjectDataFilter};
     use sui_types::base_types::{ObjectID, SuiAddress};
 
-    use crate::store::query::DBFilter;
+    use crate::store::query::{event_type_like_patterns, DBFilter};
 
     #[test]
     fn test_address_filter() {
@@ -235,4 +286,27 @@ WHERE t1.object_status NOT IN ('deleted', 'wrapped', 'unwrapped_then_deleted')
 LIMIT 100;";
         assert_eq!(expected_sql, filter.to_sql(None, 100, vec!["*"]));
     }
+
+    #[test]
+    fn test_event_type_like_pattern() {
+        let like =
+            |pattern: &str| event_type_like_patterns(&MoveTypePattern::from_str(pattern).unwrap());
+        assert_eq!(like("0xdee9::*"), vec!["0xdee9::%"]);
+        assert_eq!(like("0xdee9::clob_v2::*"), vec!["0xdee9::clob\\_v2::%"]);
+        assert_eq!(
+            like("0xdee9::clob_v2::OrderPlaced"),
+            vec![
+                "0xdee9::clob\\_v2::OrderPlaced",
+                "0xdee9::clob\\_v2::OrderPlaced<%"
+            ]
+        );
+        assert_eq!(
+            like("0xdee9::clob_v2::Pool<0x2::sui::SUI, *>"),
+            vec!["0xdee9::clob\\_v2::Pool<0x2::sui::SUI, %>"]
+        );
+        assert_eq!(
+            like("0x2::coin::CoinMetadata<*>"),
+            vec!["0x2::coin::CoinMetadata<%>"]
+        );
+    }
 }
@@ -44,6 +44,7 @@ impl TryFrom<SuiTransactionResponse> for SuiTransactionFullResponse {
             balance_changes,
             timestamp_ms,
             confirmed_local_execution,
+            served_from_archive: _,
             checkpoint,
             errors,
         } = response;
@@ -132,6 +133,7 @@ impl From<SuiTransactionFullResponse> for SuiTransactionResponse {
             balance_changes,
             timestamp_ms: Some(timestamp_ms),
             confirmed_local_execution,
+            served_from_archive: None,
             checkpoint: Some(checkpoint),
             errors: vec![],
         }
@@ -166,6 +168,7 @@ impl From<SuiTransactionFullResponseWithOptions> for SuiTransactionResponse {
                 .unwrap_or_default(),
             timestamp_ms: Some(response.timestamp_ms),
             confirmed_local_execution: response.confirmed_local_execution,
+            served_from_archive: None,
             checkpoint: Some(response.checkpoint),
             errors: vec![],
         }
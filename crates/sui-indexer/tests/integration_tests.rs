@@ -31,8 +31,8 @@ pub mod pg_integration_test {
     use sui_json_rpc_types::{
         BigInt, CheckpointId, EventFilter, SuiMoveObject, SuiObjectData, SuiObjectDataFilter,
         SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiParsedMoveObject,
-        SuiTransactionResponse, SuiTransactionResponseOptions, SuiTransactionResponseQuery,
-        TransactionBytes,
+        SuiPastObjectResponse, SuiTransactionResponse, SuiTransactionResponseOptions,
+        SuiTransactionResponseQuery, TransactionBytes,
     };
     use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
     use sui_types::base_types::{ObjectID, SuiAddress};
@@ -40,7 +40,7 @@ pub mod pg_integration_test {
     use sui_types::error::SuiObjectResponseError;
     use sui_types::gas_coin::GasCoin;
     use sui_types::messages::ExecuteTransactionRequestType;
-    use sui_types::object::ObjectFormatOptions;
+    use sui_types::object::{ObjectFormatOptions, Owner};
     use sui_types::query::TransactionFilter;
     use sui_types::utils::to_sender_signed_transaction;
     use sui_types::SUI_FRAMEWORK_ADDRESS;
@@ -350,8 +350,10 @@ pub mod pg_integration_test {
             tx_response.digest
         );
         assert_eq!(
-            Some(tx_input_query_response.data.last().unwrap().digest),
-            tx_input_query_response.next_cursor,
+            tx_read_response.checkpoint,
+            tx_input_query_response
+                .next_cursor
+                .map(|cursor| cursor.checkpoint),
         );
 
         // query tx with move call
@@ -575,6 +577,7 @@ pub mod pg_integration_test {
             show_content: true,
             show_bcs: true,
             show_storage_rebate: true,
+            fields: None,
         };
         let resp = indexer_rpc_client
             .get_object_with_options(source_object_id, Some(show_all_content.clone()))
@@ -918,6 +921,114 @@ pub mod pg_integration_test {
         Ok(())
     }
 
+    #[tokio::test]
+    #[timeout(60000)]
+    async fn test_get_object_history() -> Result<(), anyhow::Error> {
+        let (mut test_cluster, indexer_rpc_client, store, _handle) = start_test_cluster(None).await;
+        // Allow indexer to sync genesis
+        wait_until_next_checkpoint(&store).await;
+        let (tx_response, _, recipient, gas_objects) =
+            execute_simple_transfer(&mut test_cluster, &indexer_rpc_client).await?;
+        wait_until_transaction_synced(&store, tx_response.digest.base58_encode().as_str()).await;
+        let transferred = *gas_objects.first().unwrap();
+
+        // the coin is created by genesis, then transferred
+        let first_page = indexer_rpc_client
+            .get_object_history(
+                transferred,
+                None,
+                None,
+                Some(SuiObjectDataOptions::new().with_owner()),
+                None,
+                Some(1),
+            )
+            .await?;
+        assert!(first_page.has_next_page);
+        assert_eq!(first_page.data.len(), 1);
+        assert_eq!(first_page.data[0].checkpoint, 0);
+
+        let second_page = indexer_rpc_client
+            .get_object_history(
+                transferred,
+                None,
+                None,
+                Some(SuiObjectDataOptions::new().with_owner()),
+                first_page.next_cursor,
+                Some(1),
+            )
+            .await?;
+        assert!(!second_page.has_next_page);
+        assert_eq!(second_page.data.len(), 1);
+        match &second_page.data[0].object {
+            SuiPastObjectResponse::VersionFound(object) => {
+                assert_eq!(object.owner, Some(Owner::AddressOwner(recipient)))
+            }
+            object => panic!("Expected the transferred coin, got {object:?}"),
+        }
+
+        // the range ends before the transfer
+        let genesis_history = indexer_rpc_client
+            .get_object_history(
+                transferred,
+                None,
+                Some(CheckpointId::SequenceNumber(BigInt::from(0u64))),
+                Some(SuiObjectDataOptions::new().with_owner()),
+                None,
+                None,
+            )
+            .await?;
+        assert_eq!(genesis_history.data, first_page.data);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[timeout(60000)]
+    async fn test_get_analytics() -> Result<(), anyhow::Error> {
+        let (mut test_cluster, indexer_rpc_client, store, _handle) = start_test_cluster(None).await;
+        // Allow indexer to sync genesis
+        wait_until_next_checkpoint(&store).await;
+        let (tx_response, _, _, _) =
+            execute_simple_transfer(&mut test_cluster, &indexer_rpc_client).await?;
+        wait_until_transaction_synced(&store, tx_response.digest.base58_encode().as_str()).await;
+        let tx_checkpoint = indexer_rpc_client
+            .get_transaction_with_options(tx_response.digest, None)
+            .await?
+            .checkpoint
+            .unwrap();
+
+        // the test cluster runs within a single day, or two around midnight
+        let daily_metrics = indexer_rpc_client.get_daily_metrics(None, None).await?;
+        assert!(!daily_metrics.has_next_page);
+        assert!(!daily_metrics.data.is_empty() && daily_metrics.data.len() <= 2);
+        let daily_transactions: u64 = daily_metrics.data.iter().map(|m| m.transactions).sum();
+        // At least 1 transaction + 1 genesis
+        assert!(daily_transactions >= 2);
+        assert!(daily_metrics.data.iter().all(|m| m.active_addresses >= 1));
+        assert_eq!(
+            store.get_daily_metrics(None, 10)?,
+            indexer_rpc_client.get_daily_metrics(None, None).await?.data
+        );
+
+        let first_page = indexer_rpc_client
+            .get_checkpoint_metrics(None, Some(1))
+            .await?;
+        assert!(first_page.has_next_page);
+        assert_eq!(first_page.data[0].sequence_number, 0);
+        assert_eq!(first_page.next_cursor, Some(0));
+        let tx_checkpoint_metrics = indexer_rpc_client
+            .get_checkpoint_metrics(Some(tx_checkpoint - 1), Some(1))
+            .await?;
+        assert_eq!(tx_checkpoint_metrics.data[0].sequence_number, tx_checkpoint);
+        assert!(tx_checkpoint_metrics.data[0].transactions >= 1);
+
+        let top_packages = indexer_rpc_client.get_top_packages(Some(2)).await?;
+        assert!(top_packages.len() <= 2);
+        assert!(top_packages
+            .windows(2)
+            .all(|w| w[0].transactions >= w[1].transactions));
+        Ok(())
+    }
+
     #[tokio::test]
     #[timeout(60000)]
     async fn pg_parameter_limit_test() {
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 
 pub use balance_changes::*;
 pub use object_changes::*;
+pub use rpc_capabilities::*;
 pub use sui_checkpoint::*;
 pub use sui_coin::*;
 pub use sui_event::*;
@@ -23,6 +24,7 @@ mod rpc_types_tests;
 
 mod balance_changes;
 mod object_changes;
+mod rpc_capabilities;
 mod sui_checkpoint;
 mod sui_coin;
 mod sui_event;
@@ -7,9 +7,13 @@ use serde_with::DisplayFromStr;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::base_types::{
+    ObjectDigest, ObjectID, SequenceNumber, SuiAddress, TransactionDigest,
+};
 use sui_types::object::Owner;
 
+use crate::Filter;
+
 /// ObjectChange are derived from the object mutations in the TransactionEffect to provide richer object information.
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
@@ -81,3 +85,99 @@ pub enum ObjectChange {
         digest: ObjectDigest,
     },
 }
+
+impl ObjectChange {
+    pub fn object_id(&self) -> ObjectID {
+        match self {
+            Self::Published { package_id, .. } => *package_id,
+            Self::Transferred { object_id, .. }
+            | Self::Mutated { object_id, .. }
+            | Self::Deleted { object_id, .. }
+            | Self::Wrapped { object_id, .. }
+            | Self::Created { object_id, .. } => *object_id,
+        }
+    }
+
+    /// The type of the changed object, `None` for published packages.
+    pub fn object_type(&self) -> Option<&StructTag> {
+        match self {
+            Self::Published { .. } => None,
+            Self::Transferred { object_type, .. }
+            | Self::Mutated { object_type, .. }
+            | Self::Deleted { object_type, .. }
+            | Self::Wrapped { object_type, .. }
+            | Self::Created { object_type, .. } => Some(object_type),
+        }
+    }
+
+    /// The owner of the object after the change, `None` if the object no longer exists or is a
+    /// package.
+    pub fn owner(&self) -> Option<&Owner> {
+        match self {
+            Self::Transferred { recipient, .. } => Some(recipient),
+            Self::Mutated { owner, .. } | Self::Created { owner, .. } => Some(owner),
+            Self::Published { .. } | Self::Deleted { .. } | Self::Wrapped { .. } => None,
+        }
+    }
+}
+
+/// An object change made by a transaction, as streamed to subscribers of object changes.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectChangeNotification {
+    pub transaction_digest: TransactionDigest,
+    pub object_change: ObjectChange,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum ObjectChangeFilter {
+    /// Changes of objects owned by the address after the change. Deleted and wrapped objects match
+    /// if the address sent the transaction, as only the owner of an object can delete or wrap it.
+    Owner(SuiAddress),
+    /// Changes of objects of the given type. A type without type parameters matches all of its
+    /// instantiations, e.g. `0x2::coin::Coin` matches coins of any type.
+    ObjectType(
+        #[schemars(with = "String")]
+        #[serde_as(as = "DisplayFromStr")]
+        StructTag,
+    ),
+    /// Changes of objects whose type is defined in the package, and the publication of the package.
+    Package(ObjectID),
+
+    All(Vec<ObjectChangeFilter>),
+    Any(Vec<ObjectChangeFilter>),
+}
+
+impl Filter<ObjectChangeNotification> for ObjectChangeFilter {
+    fn matches(&self, item: &ObjectChangeNotification) -> bool {
+        let change = &item.object_change;
+        match self {
+            Self::Owner(address) => match change {
+                ObjectChange::Deleted { sender, .. } | ObjectChange::Wrapped { sender, .. } => {
+                    sender == address
+                }
+                _ => change.owner().map_or(false, |owner| {
+                    owner.get_owner_address().ok() == Some(*address)
+                }),
+            },
+            Self::ObjectType(filter_type) => change.object_type().map_or(false, |object_type| {
+                if filter_type.type_params.is_empty() {
+                    object_type.address == filter_type.address
+                        && object_type.module == filter_type.module
+                        && object_type.name == filter_type.name
+                } else {
+                    object_type == filter_type
+                }
+            }),
+            Self::Package(package) => match change {
+                ObjectChange::Published { package_id, .. } => package_id == package,
+                _ => change.object_type().map_or(false, |object_type| {
+                    ObjectID::from(object_type.address) == *package
+                }),
+            },
+            Self::All(filters) => filters.iter().all(|filter| filter.matches(item)),
+            Self::Any(filters) => filters.iter().any(|filter| filter.matches(item)),
+        }
+    }
+}
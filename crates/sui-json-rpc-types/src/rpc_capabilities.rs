@@ -0,0 +1,50 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// What a server supports, returned by the `rpc.capabilities` method so that a client can detect
+/// that it is stale, or that the server is, before relying on the behavior of a method.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCapabilities {
+    /// The version of the RPC API served.
+    pub api_version: String,
+    /// The oldest client API version served without breaking changes, if the server requires
+    /// one.
+    pub min_client_api_version: Option<String>,
+    /// The highest protocol version understood by the server.
+    pub max_protocol_version: u64,
+    /// The methods supported by the server, in alphabetical order.
+    pub methods: Vec<String>,
+    pub deprecated_methods: Vec<DeprecatedMethod>,
+    /// Warnings for the client API version given in the request.
+    pub client_warnings: Vec<ClientWarning>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeprecatedMethod {
+    pub method: String,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientWarning {
+    pub kind: ClientWarningKind,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+pub enum ClientWarningKind {
+    /// The client targets an API version older than the oldest one served without breaking
+    /// changes.
+    StaleClient,
+    /// The client targets an API version newer than the one served, its newer methods are not
+    /// available.
+    StaleServer,
+    /// The client API version could not be parsed.
+    InvalidClientApiVersion,
+}
@@ -1,20 +1,25 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::encoding::Base64;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use sui_types::base_types::TransactionDigest;
 use sui_types::committee::EpochId;
 use sui_types::digests::CheckpointDigest;
 use sui_types::gas::GasCostSummary;
+use sui_types::inclusion_proof::TransactionInclusionProof;
 use sui_types::message_envelope::Message;
 use sui_types::messages_checkpoint::{
     CheckpointCommitment, CheckpointContents, CheckpointSequenceNumber, CheckpointSummary,
-    CheckpointTimestamp, EndOfEpochData,
+    CheckpointTimestamp, ECMHLiveObjectSetDigest, EndOfEpochData,
 };
+use sui_types::state_proof::ObjectStateProof;
 
 use crate::BigInt;
 use crate::Page;
+use crate::SuiObjectRef;
 
 pub type SuiCheckpointSequenceNumber = BigInt;
 pub type CheckpointPage = Page<Checkpoint, SuiCheckpointSequenceNumber>;
@@ -51,6 +56,23 @@ pub struct Checkpoint {
     pub checkpoint_commitments: Vec<CheckpointCommitment>,
 }
 
+/// The lowest checkpoint from which each kind of history is available on a node, the history of
+/// earlier checkpoints having been pruned.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableHistory {
+    /// Lowest checkpoint whose contents are available
+    pub checkpoint_contents: SuiCheckpointSequenceNumber,
+    /// Lowest checkpoint whose transactions are available
+    pub transactions: SuiCheckpointSequenceNumber,
+    /// Lowest checkpoint whose transaction effects are available
+    pub effects: SuiCheckpointSequenceNumber,
+    /// Lowest checkpoint whose events are available
+    pub events: SuiCheckpointSequenceNumber,
+    /// Lowest checkpoint whose transactions and events are found by queries
+    pub indexes: SuiCheckpointSequenceNumber,
+}
+
 impl From<(CheckpointSummary, CheckpointContents)> for Checkpoint {
     fn from((summary, contents): (CheckpointSummary, CheckpointContents)) -> Self {
         let digest = summary.digest();
@@ -101,3 +123,62 @@ impl From<CheckpointDigest> for CheckpointId {
         Self::Digest(digest)
     }
 }
+
+/// Proof that an object version is part of the state of an epoch, anchored to the certified
+/// checkpoint of the transaction that wrote it.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename = "ObjectStateProof", rename_all = "camelCase")]
+pub struct SuiObjectStateProof {
+    pub object_ref: SuiObjectRef,
+    pub epoch: EpochId,
+    /// Checkpoint that includes the transaction that wrote the object version
+    pub checkpoint: SuiCheckpointSequenceNumber,
+    /// State root of the epoch, present only once the epoch has ended
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_root: Option<ECMHLiveObjectSetDigest>,
+    /// BCS encoded proof, to be verified against the committee of the epoch
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub bcs: Vec<u8>,
+}
+
+impl From<ObjectStateProof> for SuiObjectStateProof {
+    fn from(proof: ObjectStateProof) -> Self {
+        Self {
+            object_ref: proof.object_ref.into(),
+            epoch: proof.epoch(),
+            checkpoint: proof.checkpoint.sequence_number.into(),
+            state_root: proof.state_root().cloned(),
+            bcs: bcs::to_bytes(&proof).expect("Serializing a proof cannot fail"),
+        }
+    }
+}
+
+/// Proof that a transaction was executed and finalized, anchored to the certified checkpoint that
+/// includes it.
+#[serde_as]
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename = "TransactionInclusionProof", rename_all = "camelCase")]
+pub struct SuiTransactionInclusionProof {
+    pub digest: TransactionDigest,
+    pub epoch: EpochId,
+    /// Checkpoint that includes the transaction
+    pub checkpoint: SuiCheckpointSequenceNumber,
+    /// BCS encoded proof, to be verified against the committee of the epoch, or of the previous
+    /// epoch
+    #[serde_as(as = "Base64")]
+    #[schemars(with = "Base64")]
+    pub bcs: Vec<u8>,
+}
+
+impl From<TransactionInclusionProof> for SuiTransactionInclusionProof {
+    fn from(proof: TransactionInclusionProof) -> Self {
+        Self {
+            digest: proof.transaction_digest(),
+            epoch: proof.epoch(),
+            checkpoint: proof.checkpoint.sequence_number.into(),
+            bcs: bcs::to_bytes(&proof).expect("Serializing a proof cannot fail"),
+        }
+    }
+}
@@ -1,10 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
 use fastcrypto::encoding::Base58;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::StructTag;
+use move_core_types::language_storage::{StructTag, TypeTag};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -14,6 +18,7 @@ use serde_with::DisplayFromStr;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use sui_types::error::SuiResult;
 use sui_types::event::{Event, EventEnvelope, EventID};
+use sui_types::parse_sui_type_tag;
 
 use crate::{type_and_fields_from_move_struct, Page};
 
@@ -134,6 +139,16 @@ pub enum EventFilter {
         #[serde_as(as = "DisplayFromStr")]
         StructTag,
     ),
+    /// Return events whose move event type matches the pattern: every type of a package
+    /// (`0xdee9::*`), every type of a module (`0xdee9::clob_v2::*`), or the instantiations of a
+    /// struct whose type arguments may be `*` to match any type
+    /// (`0xdee9::clob_v2::Pool<0x2::sui::SUI, *>`). A struct without type arguments matches all
+    /// of its instantiations.
+    MoveEventTypePattern(
+        #[schemars(with = "String")]
+        #[serde_as(as = "DisplayFromStr")]
+        MoveTypePattern,
+    ),
     MoveEventField {
         path: String,
         value: Value,
@@ -157,6 +172,7 @@ impl EventFilter {
     fn try_matches(&self, item: &SuiEvent) -> SuiResult<bool> {
         Ok(match self {
             EventFilter::MoveEventType(event_type) => &item.type_ == event_type,
+            EventFilter::MoveEventTypePattern(pattern) => pattern.matches(&item.type_),
             EventFilter::MoveEventField { path, value } => {
                 matches!(item.parsed_json.pointer(path), Some(v) if v == value)
             }
@@ -196,6 +212,158 @@ impl EventFilter {
     }
 }
 
+/// A pattern matching Move struct types, by package, by module, or by struct with wildcard type
+/// arguments.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum MoveTypePattern {
+    /// `<package>::*`
+    Package(ObjectID),
+    /// `<package>::<module>::*`
+    Module {
+        package: ObjectID,
+        module: Identifier,
+    },
+    /// `<package>::<module>::<name><T1, *, ...>`
+    Struct {
+        package: ObjectID,
+        module: Identifier,
+        name: Identifier,
+        /// The type arguments of the struct, `None` matching any type. All instantiations of the
+        /// struct match if empty.
+        type_params: Vec<Option<TypeTag>>,
+    },
+}
+
+impl MoveTypePattern {
+    pub fn matches(&self, type_: &StructTag) -> bool {
+        let package = ObjectID::from(type_.address);
+        match self {
+            MoveTypePattern::Package(p) => *p == package,
+            MoveTypePattern::Module { package: p, module } => {
+                *p == package && *module == type_.module
+            }
+            MoveTypePattern::Struct {
+                package: p,
+                module,
+                name,
+                type_params,
+            } => {
+                *p == package
+                    && *module == type_.module
+                    && *name == type_.name
+                    && (type_params.is_empty()
+                        || type_params.len() == type_.type_params.len()
+                            && type_params.iter().zip(&type_.type_params).all(
+                                |(pattern, type_param)| {
+                                    pattern.as_ref().map_or(true, |tag| tag == type_param)
+                                },
+                            ))
+            }
+        }
+    }
+}
+
+impl FromStr for MoveTypePattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (path, type_params) = match s.find('<') {
+            Some(start) => {
+                let type_params = s[start + 1..]
+                    .strip_suffix('>')
+                    .ok_or_else(|| anyhow!("Unterminated type arguments in {s}"))?;
+                (&s[..start], Some(type_params))
+            }
+            None => (s, None),
+        };
+        let parts: Vec<_> = path.split("::").map(str::trim).collect();
+        Ok(match (parts.as_slice(), type_params) {
+            ([package, "*"], None) => {
+                MoveTypePattern::Package(ObjectID::from_hex_literal(package)?)
+            }
+            ([package, module, "*"], None) => MoveTypePattern::Module {
+                package: ObjectID::from_hex_literal(package)?,
+                module: Identifier::new(*module)?,
+            },
+            ([package, module, name], type_params) => MoveTypePattern::Struct {
+                package: ObjectID::from_hex_literal(package)?,
+                module: Identifier::new(*module)?,
+                name: Identifier::new(*name)?,
+                type_params: split_type_params(type_params.unwrap_or_default())?
+                    .into_iter()
+                    .map(|type_param| match type_param {
+                        "*" => Ok(None),
+                        _ => parse_sui_type_tag(type_param).map(Some),
+                    })
+                    .collect::<Result<_, _>>()?,
+            },
+            _ => bail!(
+                "Invalid Move type pattern {s}, expected <package>::*, <package>::<module>::* or \
+                 <package>::<module>::<name> with optional type arguments"
+            ),
+        })
+    }
+}
+
+/// Splits type arguments on the commas which are not nested in the arguments of another type.
+fn split_type_params(type_params: &str) -> anyhow::Result<Vec<&str>> {
+    if type_params.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    let mut split = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in type_params.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| anyhow!("Unbalanced type arguments {type_params}"))?
+            }
+            ',' if depth == 0 => {
+                split.push(type_params[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        bail!("Unbalanced type arguments {type_params}");
+    }
+    split.push(type_params[start..].trim());
+    Ok(split)
+}
+
+impl Display for MoveTypePattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveTypePattern::Package(package) => write!(f, "{package}::*"),
+            MoveTypePattern::Module { package, module } => write!(f, "{package}::{module}::*"),
+            MoveTypePattern::Struct {
+                package,
+                module,
+                name,
+                type_params,
+            } => {
+                write!(f, "{package}::{module}::{name}")?;
+                if !type_params.is_empty() {
+                    let type_params = type_params
+                        .iter()
+                        .map(|type_param| match type_param {
+                            Some(tag) => tag.to_string(),
+                            None => "*".to_string(),
+                        })
+                        .collect::<Vec<_>>();
+                    write!(f, "<{}>", type_params.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
 impl Filter<SuiEvent> for EventFilter {
     fn matches(&self, item: &SuiEvent) -> bool {
         self.try_matches(item).unwrap_or_default()
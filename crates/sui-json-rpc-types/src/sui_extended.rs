@@ -5,13 +5,15 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
-use sui_types::base_types::EpochId;
+use sui_types::base_types::{EpochId, ObjectID};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::sui_system_state::sui_system_state_summary::SuiValidatorSummary;
 
 use crate::Page;
 
 pub type EpochPage = Page<EpochInfo, EpochId>;
+pub type DailyMetricsPage = Page<DailyMetrics, u64>;
+pub type CheckpointMetricsPage = Page<CheckpointMetrics, CheckpointSequenceNumber>;
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -46,3 +48,40 @@ pub struct EndOfEpochInfo {
     pub total_stake_rewards_distributed: u64,
     pub leftover_storage_fund_inflow: u64,
 }
+
+/// Activity of the network within a day, in UTC.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyMetrics {
+    /// number of days since the unix epoch
+    pub day: u64,
+    pub start_timestamp_ms: u64,
+    pub transactions: u64,
+    /// number of distinct transaction senders
+    pub active_addresses: u64,
+    pub total_gas_cost: i64,
+    pub total_computation_cost: u64,
+    pub total_storage_cost: u64,
+    pub total_storage_rebate: u64,
+}
+
+/// Usage of a package since genesis.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageMetrics {
+    pub package_id: ObjectID,
+    /// number of transactions calling the package
+    pub transactions: u64,
+    pub move_calls: u64,
+    pub last_checkpoint: CheckpointSequenceNumber,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointMetrics {
+    pub sequence_number: CheckpointSequenceNumber,
+    pub timestamp_ms: u64,
+    pub transactions: u64,
+    /// transactions per second since the previous checkpoint
+    pub tps: f64,
+}
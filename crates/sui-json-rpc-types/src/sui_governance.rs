@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use sui_types::base_types::{AuthorityName, EpochId, ObjectID, SuiAddress};
 use sui_types::committee::{Committee, StakeUnit};
 
+use crate::{BigInt, SuiCheckpointSequenceNumber};
+
 /// RPC representation of the [Committee] type.
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename = "CommitteeInfo")]
@@ -55,3 +57,24 @@ pub struct Stake {
     #[serde(flatten)]
     pub status: StakeStatus,
 }
+
+/// Gas prices to pay for a transaction, estimated from the gas prices paid by the transactions of
+/// the most recent checkpoints. Estimates are never lower than the reference gas price.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema, PartialEq, Eq)]
+#[serde(rename = "GasPriceEstimates", rename_all = "camelCase")]
+pub struct SuiGasPriceEstimates {
+    /// Reference gas price of the current epoch.
+    pub reference_gas_price: BigInt,
+    /// Gas price paid by at least half of the recent transactions.
+    pub p50: BigInt,
+    /// Gas price paid by at least 90% of the recent transactions.
+    pub p90: BigInt,
+    /// Gas price paid by at least 99% of the recent transactions.
+    pub p99: BigInt,
+    /// First checkpoint of the estimates, if any.
+    pub first_checkpoint: Option<SuiCheckpointSequenceNumber>,
+    /// Last checkpoint of the estimates, if any.
+    pub last_checkpoint: Option<SuiCheckpointSequenceNumber>,
+    /// Number of transactions the estimates are computed from.
+    pub transactions: BigInt,
+}
@@ -7,7 +7,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use serde_with::{serde_as, DisplayFromStr};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::fmt::{Display, Formatter, Write};
 use sui_types::base_types::{ObjectID, SuiAddress};
@@ -416,6 +416,16 @@ impl SuiMoveStruct {
             }
         }
     }
+
+    /// Drops all named fields but the given ones. Structs with positional fields are unchanged.
+    pub fn retain_fields(&mut self, names: &BTreeSet<String>) {
+        match self {
+            SuiMoveStruct::Runtime(_) => {}
+            SuiMoveStruct::WithTypes { fields, .. } | SuiMoveStruct::WithFields(fields) => {
+                fields.retain(|name, _| names.contains(name))
+            }
+        }
+    }
 }
 
 impl Display for SuiMoveStruct {
@@ -15,7 +15,7 @@ use serde::Serialize;
 use serde_json::Value;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 use std::fmt::Write;
 use std::fmt::{Display, Formatter};
@@ -26,6 +26,7 @@ use sui_types::base_types::{
 };
 use sui_types::error::{SuiObjectResponseError, UserInputError, UserInputResult};
 use sui_types::gas_coin::GasCoin;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::move_package::{MovePackage, TypeOrigin, UpgradeInfo};
 use sui_types::object::{Data, MoveObject, Object, ObjectFormatOptions, ObjectRead, Owner};
 
@@ -136,6 +137,10 @@ pub struct SuiObjectData {
     /// Move object content or package content in BCS, default to be None unless SuiObjectDataOptions.showBcs is set to true
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bcs: Option<SuiRawData>,
+    /// Whether the node serving the object had pruned this version and read it from its archive.
+    /// Only set for past versions of objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_from_archive: Option<bool>,
 }
 
 impl SuiObjectData {
@@ -268,6 +273,10 @@ pub struct SuiObjectDataOptions {
     pub show_bcs: bool,
     /// Whether to show the storage rebate of the object. Default to be False
     pub show_storage_rebate: bool,
+    /// If set, only these fields of the object data are returned and the `show*` options are
+    /// ignored. `objectId`, `version` and `digest` are always returned. Fields of a Move object's
+    /// content can be selected individually as `content.<field name>`. Default to None
+    pub fields: Option<Vec<String>>,
 }
 
 impl SuiObjectDataOptions {
@@ -275,6 +284,62 @@ impl SuiObjectDataOptions {
         Self::default()
     }
 
+    /// Only return the given fields of the object data, see `fields`.
+    pub fn with_fields(fields: &[&str]) -> Self {
+        Self {
+            fields: Some(fields.iter().map(|field| field.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the `show*` options from `fields`, if set. Fails on unknown fields.
+    pub fn resolve_fields(self) -> Result<Self, anyhow::Error> {
+        let Some(fields) = &self.fields else {
+            return Ok(self);
+        };
+        let mut options = Self {
+            fields: self.fields.clone(),
+            ..Default::default()
+        };
+        for field in fields {
+            let name = match field.split_once('.') {
+                Some(("content", subfield)) if !subfield.is_empty() => "content",
+                Some(_) => {
+                    return Err(anyhow!(
+                        "Invalid object data field: {field}, only content fields can be selected \
+                         individually"
+                    ))
+                }
+                None => field.as_str(),
+            };
+            match name {
+                "objectId" | "version" | "digest" => {}
+                "type" => options.show_type = true,
+                "owner" => options.show_owner = true,
+                "previousTransaction" => options.show_previous_transaction = true,
+                "display" => options.show_display = true,
+                "content" => options.show_content = true,
+                "bcs" => options.show_bcs = true,
+                "storageRebate" => options.show_storage_rebate = true,
+                _ => return Err(anyhow!("Unknown object data field: {field}")),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The Move object content fields to return, if only some of them were selected with `fields`.
+    pub fn content_fields(&self) -> Option<BTreeSet<&str>> {
+        let fields = self.fields.as_ref()?;
+        if fields.iter().any(|field| field == "content") {
+            return None;
+        }
+        let content_fields: BTreeSet<_> = fields
+            .iter()
+            .filter_map(|field| field.strip_prefix("content."))
+            .collect();
+        (!content_fields.is_empty()).then_some(content_fields)
+    }
+
     /// return BCS data and all other metadata such as storage rebate
     pub fn bcs_lossless() -> Self {
         Self {
@@ -285,6 +350,7 @@ impl SuiObjectDataOptions {
             show_display: false,
             show_content: false,
             show_storage_rebate: true,
+            fields: None,
         }
     }
 
@@ -298,6 +364,7 @@ impl SuiObjectDataOptions {
             show_display: false,
             show_content: true,
             show_storage_rebate: true,
+            fields: None,
         }
     }
 
@@ -386,6 +453,7 @@ impl TryFrom<(ObjectInfo, SuiObjectDataOptions)> for SuiObjectResponse {
             display: None,
             content: None,
             bcs: None,
+            served_from_archive: None,
         }))
     }
 }
@@ -408,6 +476,9 @@ impl
             SuiObjectDataOptions,
         ),
     ) -> Result<Self, Self::Error> {
+        let content_fields: Option<BTreeSet<String>> = options
+            .content_fields()
+            .map(|fields| fields.into_iter().map(str::to_string).collect());
         let SuiObjectDataOptions {
             show_type,
             show_owner,
@@ -451,7 +522,13 @@ impl
                 }
                 Data::Package(p) => SuiParsedData::try_from_package(p)?,
             };
-            Some(data)
+            Some(match (data, content_fields) {
+                (SuiParsedData::MoveObject(mut object), Some(content_fields)) => {
+                    object.fields.retain_fields(&content_fields);
+                    SuiParsedData::MoveObject(object)
+                }
+                (data, _) => data,
+            })
         } else {
             None
         };
@@ -475,6 +552,7 @@ impl
             content,
             bcs,
             display: None,
+            served_from_archive: None,
         })
     }
 }
@@ -963,6 +1041,18 @@ pub struct SuiMovePackage {
 
 pub type ObjectsPage = Page<SuiObjectResponse, ObjectID>;
 
+pub type ObjectHistoryPage = Page<SuiObjectVersion, SequenceNumber>;
+
+/// A version of an object in its history, with the checkpoint in which the object got this
+/// version.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Eq, PartialEq)]
+#[serde(rename = "ObjectVersion", rename_all = "camelCase")]
+pub struct SuiObjectVersion {
+    pub checkpoint: CheckpointSequenceNumber,
+    /// The object at this version, or its reference if it got deleted or wrapped at this version.
+    pub object: SuiPastObjectResponse,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Eq, PartialEq)]
 #[serde(rename = "GetPastObjectRequest", rename_all = "camelCase")]
 pub struct SuiGetPastObjectRequest {
@@ -1,8 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
 use std::fmt::{self, Display, Formatter, Write};
 
+use anyhow::anyhow;
 use fastcrypto::encoding::Base64;
 use move_binary_format::access::ModuleAccess;
 use move_binary_format::binary_views::BinaryIndexedView;
@@ -22,6 +24,7 @@ use sui_types::base_types::{
 };
 use sui_types::digests::{ObjectDigest, TransactionEventsDigest};
 use sui_types::error::{ExecutionError, SuiError};
+use sui_types::execution_trace::{CommandTrace, ExecutionTrace, ObjectChangeKind};
 use sui_types::gas::GasCostSummary;
 use sui_types::messages::{
     Argument, CallArg, Command, ExecuteTransactionRequestType, ExecutionStatus, GenesisObject,
@@ -36,6 +39,7 @@ use sui_types::parse_sui_type_tag;
 use sui_types::query::TransactionFilter;
 use sui_types::signature::GenericSignature;
 use sui_types::storage::{DeleteKind, WriteKind};
+use sui_types::temporary_store::InnerTemporaryStore;
 
 use crate::balance_changes::BalanceChange;
 use crate::object_changes::ObjectChange;
@@ -92,7 +96,41 @@ impl SuiTransactionResponseQuery {
     }
 }
 
-pub type TransactionsPage = Page<SuiTransactionResponse, TransactionDigest>;
+pub type TransactionsPage = Page<SuiTransactionResponse, TransactionCursor>;
+
+/// Position of a transaction in the checkpointed history: the checkpoint that includes it and its
+/// index in the contents of the checkpoint. Unlike the sequence numbers of the indexes of a node,
+/// the position of a transaction is the same on every fullnode and indexer, and does not change
+/// when they restart, so that a query can be resumed from any of them.
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, JsonSchema, Serialize, Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionCursor {
+    pub checkpoint: CheckpointSequenceNumber,
+    pub index: u64,
+}
+
+impl TransactionCursor {
+    pub fn new(checkpoint: CheckpointSequenceNumber, index: u64) -> Self {
+        Self { checkpoint, index }
+    }
+}
+
+/// A page of the transactions found by scanning a range of checkpoints. Transactions matching the
+/// query outside of `[firstCheckpoint, lastCheckpoint]` are not returned.
+#[derive(Clone, Debug, JsonSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionScanPage {
+    pub data: Vec<SuiTransactionResponse>,
+    pub next_cursor: Option<TransactionDigest>,
+    pub has_next_page: bool,
+    /// The first checkpoint scanned, inclusive.
+    pub first_checkpoint: CheckpointSequenceNumber,
+    /// The last checkpoint scanned, inclusive. This is the highest checkpoint executed by the node
+    /// when the page was read.
+    pub last_checkpoint: CheckpointSequenceNumber,
+}
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Default)]
 #[serde(
@@ -113,6 +151,9 @@ pub struct SuiTransactionResponseOptions {
     pub show_object_changes: bool,
     /// Whether to show balance_changes. Default to be False
     pub show_balance_changes: bool,
+    /// If set, only these fields of the transaction response are returned and the `show*` options
+    /// are ignored. The digest is always returned. Default to None
+    pub fields: Option<Vec<String>>,
 }
 
 impl SuiTransactionResponseOptions {
@@ -128,9 +169,58 @@ impl SuiTransactionResponseOptions {
             show_events: true,
             show_object_changes: true,
             show_balance_changes: true,
+            fields: None,
         }
     }
 
+    /// Only return the given fields of the transaction response, see `fields`.
+    pub fn with_fields(fields: &[&str]) -> Self {
+        Self {
+            fields: Some(fields.iter().map(|field| field.to_string()).collect()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the `show*` options from `fields`, if set. Fails on unknown fields.
+    pub fn resolve_fields(self) -> Result<Self, anyhow::Error> {
+        let Some(fields) = &self.fields else {
+            return Ok(self);
+        };
+        let mut options = Self {
+            fields: self.fields.clone(),
+            ..Default::default()
+        };
+        for field in fields {
+            match field.as_str() {
+                "digest" | "timestampMs" | "checkpoint" | "confirmedLocalExecution" | "errors" => {}
+                "transaction" => options.show_input = true,
+                "rawTransaction" => options.show_raw_input = true,
+                "effects" => options.show_effects = true,
+                "events" => options.show_events = true,
+                "objectChanges" => options.show_object_changes = true,
+                "balanceChanges" => options.show_balance_changes = true,
+                _ => return Err(anyhow!("Unknown transaction response field: {field}")),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Whether to look up the checkpoint of the transaction.
+    pub fn show_checkpoint(&self) -> bool {
+        self.shows_field("checkpoint")
+    }
+
+    /// Whether to look up the timestamp of the transaction's checkpoint.
+    pub fn show_timestamp(&self) -> bool {
+        self.shows_field("timestampMs")
+    }
+
+    fn shows_field(&self, name: &str) -> bool {
+        self.fields
+            .as_ref()
+            .map_or(true, |fields| fields.iter().any(|field| field == name))
+    }
+
     pub fn with_input(mut self) -> Self {
         self.show_input = true;
         self
@@ -188,7 +278,10 @@ impl SuiTransactionResponseOptions {
     }
 
     pub fn only_digest(&self) -> bool {
-        self == &Self::default()
+        match &self.fields {
+            Some(fields) => fields.iter().all(|field| field == "digest"),
+            None => self == &Self::default(),
+        }
     }
 }
 
@@ -218,6 +311,10 @@ pub struct SuiTransactionResponse {
     pub timestamp_ms: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub confirmed_local_execution: Option<bool>,
+    /// Whether the node serving the transaction had pruned it and read it from its archive.
+    /// This is only returned in the read api.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_from_archive: Option<bool>,
     /// The checkpoint number when this transaction was included and hence finalized.
     /// This is only returned in the read api, not in the transaction execution api.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -637,6 +734,164 @@ impl Display for SuiTransactionEffects {
 pub struct DryRunTransactionResponse {
     pub effects: SuiTransactionEffects,
     pub events: SuiTransactionEvents,
+    /// Trace of the execution, if requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<ExecutionTrace>,
+    /// Where the gas of the transaction goes
+    #[serde(
+        rename = "gasBreakdown",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas_breakdown: Option<SuiGasBreakdown>,
+}
+
+/// Breakdown of the gas of a transaction, in MIST. Computation costs are reported before they are
+/// rounded up to the gas buckets charged, so they can add up to less than the computation cost of
+/// the effects.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "GasBreakdown", rename_all = "camelCase")]
+pub struct SuiGasBreakdown {
+    /// Computation cost of reading the input objects.
+    pub object_read_cost: u64,
+    pub commands: Vec<SuiCommandGas>,
+    /// Storage cost and rebate of the objects written or deleted, the gas coin included.
+    pub objects: Vec<SuiObjectGas>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "CommandGas", rename_all = "camelCase")]
+pub struct SuiCommandGas {
+    pub index: usize,
+    /// Name of the command, e.g. `MoveCall` or `SplitCoins`.
+    pub kind: String,
+    pub computation_cost: u64,
+    /// Computation cost of the function called by a `MoveCall` command, and of each native
+    /// function it called, only reported when the execution is traced.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<SuiFunctionGas>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "FunctionGas", rename_all = "camelCase")]
+pub struct SuiFunctionGas {
+    /// `package::module::function` of the function.
+    pub function: String,
+    /// Number of calls of the function by the command.
+    pub calls: u64,
+    pub computation_cost: u64,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ObjectGas", rename_all = "camelCase")]
+pub struct SuiObjectGas {
+    pub object_id: ObjectID,
+    pub kind: ObjectChangeKind,
+    /// Cost of storing the object as written by the transaction, 0 for deleted objects.
+    pub storage_cost: u64,
+    /// Rebate of the storage cost paid for the object before the transaction, before the part
+    /// kept by the storage fund is deducted.
+    pub storage_rebate: u64,
+}
+
+impl SuiGasBreakdown {
+    /// Builds the breakdown of an execution traced by `trace`, whose outputs are in `store`.
+    /// `input_storage_rebate` returns the storage rebate of the objects before the transaction,
+    /// for the objects read from storage rather than given as inputs, i.e. dynamic fields.
+    /// Functions are only reported with `with_functions` set.
+    pub fn new(
+        trace: &ExecutionTrace,
+        store: &InnerTemporaryStore,
+        gas_price: u64,
+        with_functions: bool,
+        input_storage_rebate: impl Fn(&ObjectID) -> Option<u64>,
+    ) -> Self {
+        let commands = trace
+            .commands
+            .iter()
+            .map(|command| SuiCommandGas {
+                index: command.index,
+                kind: command.kind.clone(),
+                computation_cost: command.gas_used * gas_price,
+                functions: if with_functions {
+                    Self::functions(command, gas_price)
+                } else {
+                    vec![]
+                },
+            })
+            .collect();
+
+        let old_storage_rebate = |id: &ObjectID| {
+            store
+                .objects
+                .get(id)
+                .map(|object| object.storage_rebate)
+                .or_else(|| input_storage_rebate(id))
+                .unwrap_or_default()
+        };
+        let objects = trace
+            .object_changes
+            .iter()
+            .map(|change| {
+                let (storage_cost, storage_rebate) = match change.kind {
+                    ObjectChangeKind::Created | ObjectChangeKind::Unwrapped => {
+                        (store.written[&change.object_id].1.storage_rebate, 0)
+                    }
+                    ObjectChangeKind::Mutated => (
+                        store.written[&change.object_id].1.storage_rebate,
+                        old_storage_rebate(&change.object_id),
+                    ),
+                    ObjectChangeKind::Deleted | ObjectChangeKind::Wrapped => {
+                        (0, old_storage_rebate(&change.object_id))
+                    }
+                    // Its storage is paid for through the object that wrapped it.
+                    ObjectChangeKind::UnwrappedThenDeleted => (0, 0),
+                };
+                SuiObjectGas {
+                    object_id: change.object_id,
+                    kind: change.kind,
+                    storage_cost,
+                    storage_rebate,
+                }
+            })
+            .collect();
+
+        Self {
+            object_read_cost: trace.object_read_gas * gas_price,
+            commands,
+            objects,
+        }
+    }
+
+    /// The function called by `command`, with the gas of its native calls deducted, followed by
+    /// the native functions it called.
+    fn functions(command: &CommandTrace, gas_price: u64) -> Vec<SuiFunctionGas> {
+        let mut natives = BTreeMap::<&str, (u64, u64)>::new();
+        for native in &command.native_calls {
+            let (calls, gas) = natives.entry(&native.function).or_default();
+            *calls += 1;
+            *gas += native.gas;
+        }
+        let native_gas: u64 = natives.values().map(|(_, gas)| gas).sum();
+
+        let called = command.call.as_ref().map(|call| SuiFunctionGas {
+            function: format!("{}::{}::{}", call.package, call.module, call.function),
+            calls: 1,
+            computation_cost: command.gas_used.saturating_sub(native_gas) * gas_price,
+        });
+        called
+            .into_iter()
+            .chain(
+                natives
+                    .into_iter()
+                    .map(|(function, (calls, gas))| SuiFunctionGas {
+                        function: function.to_string(),
+                        calls,
+                        computation_cost: gas * gas_price,
+                    }),
+            )
+            .collect()
+    }
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -681,6 +936,24 @@ pub struct DevInspectResults {
     /// Execution error from executing the transaction commands
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Trace of the execution, if requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<ExecutionTrace>,
+}
+
+/// Contents and version given to an object by a dev-inspect execution, in place of the ones it has
+/// on chain.
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ObjectOverride", rename_all = "camelCase")]
+pub struct SuiObjectOverride {
+    pub object_id: ObjectID,
+    /// Version of the object, its version on chain if not set. The transaction must refer to an
+    /// overridden owned object with this version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<SequenceNumber>,
+    /// BCS encoded contents of the Move object, its ID included, its contents on chain if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bcs_bytes: Option<Base64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -705,6 +978,7 @@ impl DevInspectResults {
         effects: TransactionEffects,
         events: TransactionEvents,
         return_values: Result<Vec<ExecutionResult>, ExecutionError>,
+        trace: Option<ExecutionTrace>,
         resolver: &impl GetModule,
     ) -> Result<Self, anyhow::Error> {
         let tx_digest = *effects.transaction_digest();
@@ -739,6 +1013,7 @@ impl DevInspectResults {
             events: SuiTransactionEvents::try_from(events, tx_digest, None, resolver)?,
             results,
             error,
+            trace,
         })
     }
 }
@@ -10,12 +10,16 @@ use move_core_types::language_storage::{StructTag, TypeTag};
 use move_core_types::value::{MoveStruct, MoveValue};
 
 use sui_types::base_types::SequenceNumber;
-use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::base_types::{ObjectDigest, ObjectID, SuiAddress, TransactionDigest};
 use sui_types::gas_coin::GasCoin;
-use sui_types::object::MoveObject;
+use sui_types::object::{MoveObject, Object, Owner};
 use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
 
-use crate::{SuiMoveStruct, SuiMoveValue};
+use crate::{
+    Filter, MoveTypePattern, ObjectChange, ObjectChangeFilter, ObjectChangeNotification,
+    SuiMoveStruct, SuiMoveValue, SuiObjectData, SuiObjectDataOptions, SuiParsedData,
+    SuiTransactionResponseOptions,
+};
 
 #[test]
 fn test_move_value_to_sui_coin() {
@@ -143,3 +147,156 @@ fn test_serde() {
         )
     }
 }
+
+#[test]
+fn test_object_data_fields() {
+    let options = SuiObjectDataOptions::with_fields(&["owner", "version", "content.balance"])
+        .resolve_fields()
+        .unwrap();
+    assert!(options.show_owner && options.show_content);
+    assert!(!options.show_type && !options.show_bcs && !options.show_display);
+
+    let object = Object::with_id_owner_gas_for_testing(
+        ObjectID::random(),
+        SuiAddress::random_for_testing_only(),
+        100,
+    );
+    let data: SuiObjectData = (
+        object.compute_object_reference(),
+        object,
+        Some(GasCoin::layout()),
+        options,
+    )
+        .try_into()
+        .unwrap();
+    assert!(data.owner.is_some() && data.type_.is_none());
+    let Some(SuiParsedData::MoveObject(content)) = data.content else {
+        panic!("Expected Move object content");
+    };
+    let SuiMoveStruct::WithFields(fields) = content.fields else {
+        panic!("Expected named fields");
+    };
+    assert_eq!(fields.keys().collect::<Vec<_>>(), vec!["balance"]);
+
+    // Selecting the whole content keeps all of its fields.
+    let options = SuiObjectDataOptions::with_fields(&["content", "content.balance"]);
+    assert_eq!(options.content_fields(), None);
+
+    assert!(SuiObjectDataOptions::with_fields(&["ownr"])
+        .resolve_fields()
+        .is_err());
+    assert!(SuiObjectDataOptions::with_fields(&["owner.address"])
+        .resolve_fields()
+        .is_err());
+}
+
+#[test]
+fn test_transaction_response_fields() {
+    let options = SuiTransactionResponseOptions::with_fields(&["effects", "checkpoint"])
+        .resolve_fields()
+        .unwrap();
+    assert!(options.show_effects && !options.show_input && !options.show_events);
+    assert!(options.show_checkpoint() && !options.show_timestamp());
+    assert!(!options.only_digest());
+
+    assert!(SuiTransactionResponseOptions::with_fields(&["digest"]).only_digest());
+    assert!(SuiTransactionResponseOptions::default().show_timestamp());
+    assert!(SuiTransactionResponseOptions::with_fields(&["gas"])
+        .resolve_fields()
+        .is_err());
+}
+
+#[test]
+fn test_object_change_filter() {
+    let sender = SuiAddress::random_for_testing_only();
+    let owner = SuiAddress::random_for_testing_only();
+    let coin = ObjectChangeNotification {
+        transaction_digest: TransactionDigest::random(),
+        object_change: ObjectChange::Created {
+            sender,
+            owner: Owner::AddressOwner(owner),
+            object_type: GasCoin::type_(),
+            object_id: ObjectID::random(),
+            version: SequenceNumber::from_u64(1),
+            digest: ObjectDigest::random(),
+        },
+    };
+    let deleted = ObjectChangeNotification {
+        transaction_digest: TransactionDigest::random(),
+        object_change: ObjectChange::Deleted {
+            sender,
+            object_type: GasCoin::type_(),
+            object_id: ObjectID::random(),
+            version: SequenceNumber::from_u64(2),
+        },
+    };
+
+    assert!(ObjectChangeFilter::Owner(owner).matches(&coin));
+    assert!(!ObjectChangeFilter::Owner(sender).matches(&coin));
+    assert!(ObjectChangeFilter::Owner(sender).matches(&deleted));
+
+    let any_coin = StructTag::from_str("0x2::coin::Coin").unwrap();
+    let sui_coin = GasCoin::type_();
+    let other_coin = StructTag::from_str("0x2::coin::Coin<0x2::example::EXAMPLE>").unwrap();
+    assert!(ObjectChangeFilter::ObjectType(any_coin).matches(&coin));
+    assert!(ObjectChangeFilter::ObjectType(sui_coin).matches(&coin));
+    assert!(!ObjectChangeFilter::ObjectType(other_coin).matches(&coin));
+
+    assert!(ObjectChangeFilter::Package(ObjectID::from(SUI_FRAMEWORK_ADDRESS)).matches(&coin));
+    assert!(!ObjectChangeFilter::Package(ObjectID::random()).matches(&coin));
+
+    let filter = ObjectChangeFilter::All(vec![
+        ObjectChangeFilter::Owner(owner),
+        ObjectChangeFilter::ObjectType(GasCoin::type_()),
+    ]);
+    assert!(filter.matches(&coin));
+    assert!(!filter.matches(&deleted));
+}
+
+#[test]
+fn test_move_type_pattern() {
+    let pool = |base: &str, quote: &str| {
+        StructTag::from_str(&format!("0xdee9::clob_v2::Pool<{base}, {quote}>")).unwrap()
+    };
+    let sui_usdc = pool("0x2::sui::SUI", "0x5d4b::coin::COIN");
+    let usdc_sui = pool("0x5d4b::coin::COIN", "0x2::sui::SUI");
+    let matches = |pattern: &str, type_: &StructTag| {
+        MoveTypePattern::from_str(pattern).unwrap().matches(type_)
+    };
+
+    assert!(matches("0xdee9::*", &sui_usdc));
+    assert!(!matches("0x2::*", &sui_usdc));
+    assert!(matches("0xdee9::clob_v2::*", &sui_usdc));
+    assert!(!matches("0xdee9::clob::*", &sui_usdc));
+    assert!(matches("0xdee9::clob_v2::Pool", &sui_usdc));
+    assert!(matches(
+        "0xdee9::clob_v2::Pool<0x2::sui::SUI, *>",
+        &sui_usdc
+    ));
+    assert!(!matches(
+        "0xdee9::clob_v2::Pool<0x2::sui::SUI, *>",
+        &usdc_sui
+    ));
+    assert!(matches(
+        "0xdee9::clob_v2::Pool<*, 0x2::sui::SUI>",
+        &usdc_sui
+    ));
+    assert!(!matches("0xdee9::clob_v2::Pool<*>", &sui_usdc));
+
+    let pattern = MoveTypePattern::from_str("0xdee9::clob_v2::Pool<0x2::sui::SUI, *>").unwrap();
+    assert_eq!(
+        MoveTypePattern::from_str(&pattern.to_string()).unwrap(),
+        pattern
+    );
+
+    for invalid in [
+        "0xdee9",
+        "0xdee9::clob_v2",
+        "0xdee9::*::Pool",
+        "0xdee9::clob_v2::*<*>",
+        "0xdee9::clob_v2::Pool<*",
+        "0xdee9::clob_v2::Pool<0x2::sui::SUI>>",
+    ] {
+        assert!(MoveTypePattern::from_str(invalid).is_err(), "{invalid}");
+    }
+}
@@ -4,9 +4,13 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 
-use sui_json_rpc_types::{CheckpointId, EpochInfo, EpochPage, ObjectsPage, SuiObjectResponseQuery};
+use sui_json_rpc_types::{
+    CheckpointId, CheckpointMetricsPage, DailyMetricsPage, EpochInfo, EpochPage, ObjectHistoryPage,
+    ObjectsPage, PackageMetrics, SuiObjectDataOptions, SuiObjectResponseQuery,
+};
 use sui_open_rpc_macros::open_rpc;
-use sui_types::base_types::{EpochId, ObjectID};
+use sui_types::base_types::{EpochId, ObjectID, SequenceNumber};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 #[open_rpc(namespace = "suix", tag = "Extended API")]
 #[rpc(server, client, namespace = "suix")]
@@ -39,6 +43,24 @@ pub trait ExtendedApi {
         at_checkpoint: Option<CheckpointId>,
     ) -> RpcResult<ObjectsPage>;
 
+    /// Return the versions of an object created within a range of checkpoints, in ascending order, with their contents. Note that this is an enhanced full node only api.
+    #[method(name = "getObjectHistory")]
+    async fn get_object_history(
+        &self,
+        /// the ID of the queried object
+        object_id: ObjectID,
+        /// The first checkpoint of the range, inclusive. Default to the genesis checkpoint if not specified.
+        start_checkpoint: Option<CheckpointId>,
+        /// The last checkpoint of the range, inclusive. Default to the latest checkpoint if not specified.
+        end_checkpoint: Option<CheckpointId>,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+        /// An optional paging cursor. If provided, the query will start from the version after the specified cursor. Default to start from the first version in the range if not specified.
+        cursor: Option<SequenceNumber>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT_OBJECTS] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectHistoryPage>;
+
     /// Return total address count
     #[method(name = "getTotalAddresses")]
     async fn get_total_addresses(&self) -> RpcResult<u64>;
@@ -50,4 +72,32 @@ pub trait ExtendedApi {
     /// Return total package count
     #[method(name = "getTotalPackages")]
     async fn get_total_packages(&self) -> RpcResult<u64>;
+
+    /// Return the number of transactions, active addresses and gas spent per day, in ascending order of days. Note that this is an enhanced full node only api.
+    #[method(name = "getDailyMetrics")]
+    async fn get_daily_metrics(
+        &self,
+        /// An optional paging cursor, the number of a day since the unix epoch. If provided, the query will start from the day after it. Default to start from the first indexed day if not specified.
+        cursor: Option<u64>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT_CHECKPOINTS] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<DailyMetricsPage>;
+
+    /// Return the packages called by the most transactions, in descending order of transactions. Note that this is an enhanced full node only api.
+    #[method(name = "getTopPackages")]
+    async fn get_top_packages(
+        &self,
+        /// Max number of packages returned, default to [QUERY_MAX_RESULT_LIMIT_CHECKPOINTS] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<PackageMetrics>>;
+
+    /// Return the number of transactions and the TPS of checkpoints, in ascending order. Note that this is an enhanced full node only api.
+    #[method(name = "getCheckpointMetrics")]
+    async fn get_checkpoint_metrics(
+        &self,
+        /// An optional paging cursor. If provided, the query will start from the checkpoint after it. Default to start from the genesis checkpoint if not specified.
+        cursor: Option<CheckpointSequenceNumber>,
+        /// Max number of items returned per page, default to [QUERY_MAX_RESULT_LIMIT_CHECKPOINTS] if not specified.
+        limit: Option<usize>,
+    ) -> RpcResult<CheckpointMetricsPage>;
 }
@@ -4,7 +4,7 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 
-use sui_json_rpc_types::{BigInt, DelegatedStake, SuiCommittee};
+use sui_json_rpc_types::{BigInt, DelegatedStake, SuiCommittee, SuiGasPriceEstimates};
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SuiAddress};
 
@@ -41,4 +41,8 @@ pub trait GovernanceReadApi {
     /// Return the reference gas price for the network
     #[method(name = "getReferenceGasPrice")]
     async fn get_reference_gas_price(&self) -> RpcResult<BigInt>;
+
+    /// Return gas prices to pay for a transaction, estimated from the gas prices paid by the transactions of the most recent checkpoints, along with the reference gas price
+    #[method(name = "getGasPriceEstimates")]
+    async fn get_gas_price_estimates(&self) -> RpcResult<SuiGasPriceEstimates>;
 }
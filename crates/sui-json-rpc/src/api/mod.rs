@@ -5,6 +5,7 @@ mod coin;
 mod event;
 mod extended;
 mod governance;
+mod object_change;
 mod read;
 mod transaction_builder;
 mod write;
@@ -30,6 +31,10 @@ pub use governance::GovernanceReadApiClient;
 pub use governance::GovernanceReadApiOpenRpc;
 pub use governance::GovernanceReadApiServer;
 
+pub use object_change::ObjectChangeApiClient;
+pub use object_change::ObjectChangeApiOpenRpc;
+pub use object_change::ObjectChangeApiServer;
+
 pub use read::ReadApiClient;
 pub use read::ReadApiOpenRpc;
 pub use read::ReadApiServer;
@@ -48,6 +53,10 @@ pub const QUERY_MAX_RESULT_LIMIT_CHECKPOINTS: usize = 100;
 
 pub const QUERY_MAX_RESULT_LIMIT_OBJECTS: usize = 256;
 
+/// Maximum number of transactions a fullnode reads from the checkpoints for a page of
+/// `queryTransactions`, matching the query or not.
+pub const QUERY_MAX_WALKED_TRANSACTIONS: usize = 10_000;
+
 pub fn cap_page_limit(limit: Option<usize>) -> usize {
     let limit = limit.unwrap_or_default();
     if limit > QUERY_MAX_RESULT_LIMIT || limit == 0 {
@@ -0,0 +1,20 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpsee_proc_macros::rpc;
+
+use sui_json_rpc_types::{ObjectChangeFilter, ObjectChangeNotification};
+use sui_open_rpc_macros::open_rpc;
+
+#[open_rpc(namespace = "suix", tag = "Object Change API")]
+#[rpc(server, client, namespace = "suix")]
+pub trait ObjectChangeApi {
+    /// Subscribe to a stream of the objects created, mutated, transferred, wrapped and deleted by
+    /// executed transactions
+    #[subscription(name = "subscribeObjectChanges", item = ObjectChangeNotification)]
+    fn subscribe_object_changes(
+        &self,
+        /// the filter criteria of the object change stream, by owner address, object type or package.
+        filter: ObjectChangeFilter,
+    );
+}
@@ -5,11 +5,13 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 use std::collections::BTreeMap;
 use sui_json_rpc_types::{
-    BigInt, Checkpoint, CheckpointId, CheckpointPage, DynamicFieldPage, MoveFunctionArgType,
-    ObjectsPage, SuiCheckpointSequenceNumber, SuiGetPastObjectRequest, SuiMoveNormalizedFunction,
-    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectDataOptions, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiPastObjectResponse, SuiTransactionResponse,
-    SuiTransactionResponseOptions, SuiTransactionResponseQuery, TransactionsPage,
+    AvailableHistory, BigInt, Checkpoint, CheckpointId, CheckpointPage, DynamicFieldPage,
+    MoveFunctionArgType, ObjectsPage, SuiCheckpointSequenceNumber, SuiGetPastObjectRequest,
+    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiObjectStateProof,
+    SuiPastObjectResponse, SuiTransactionInclusionProof, SuiTransactionResponse,
+    SuiTransactionResponseOptions, SuiTransactionResponseQuery, TransactionCursor,
+    TransactionScanPage, TransactionsPage,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{
@@ -146,20 +148,40 @@ pub trait ReadApi {
         function_name: String,
     ) -> RpcResult<SuiMoveNormalizedFunction>;
 
-    /// Return list of transactions for a specified query criteria.
+    /// Return list of transactions for a specified query criteria. Only transactions included in
+    /// a checkpoint are returned, and pages are cursored by the position of the transactions in
+    /// the checkpoints, so that a query can be resumed from any fullnode or indexer. A page may
+    /// hold fewer transactions than the limit and still have a next page, when the node stopped
+    /// reading the checkpoints before filling it.
     #[method(name = "queryTransactions")]
     async fn query_transactions(
         &self,
         /// the transaction query criteria.
         query: SuiTransactionResponseQuery,
         /// An optional paging cursor. If provided, the query will start from the next item after the specified cursor. Default to start from the first item if not specified.
-        cursor: Option<TransactionDigest>,
+        cursor: Option<TransactionCursor>,
         /// Maximum item returned per page, default to QUERY_MAX_RESULT_LIMIT if not specified.
         limit: Option<usize>,
         /// query result ordering, default to false (ascending order), oldest record first.
         descending_order: Option<bool>,
     ) -> RpcResult<TransactionsPage>;
 
+    /// Return list of transactions sent by or to an address, found by scanning the most recent
+    /// checkpoints instead of reading indexes, so that it is served by nodes that maintain no
+    /// indexes. Only the checkpoints reported in the response are scanned.
+    #[method(name = "scanTransactions")]
+    async fn scan_transactions(
+        &self,
+        /// the transaction query criteria, which must filter by sender or recipient address.
+        query: SuiTransactionResponseQuery,
+        /// An optional paging cursor. If provided, the query will start from the next item after the specified cursor. The cursor must be in the scanned checkpoints.
+        cursor: Option<TransactionDigest>,
+        /// Maximum item returned per page, default to QUERY_MAX_RESULT_LIMIT if not specified.
+        limit: Option<usize>,
+        /// query result ordering, default to false (ascending order), oldest record first.
+        descending_order: Option<bool>,
+    ) -> RpcResult<TransactionScanPage>;
+
     /// Returns an ordered list of transaction responses
     /// The method will throw an error if the input contains any duplicate or
     /// the input size exceeds QUERY_MAX_RESULT_LIMIT
@@ -205,6 +227,10 @@ pub trait ReadApi {
     async fn get_latest_checkpoint_sequence_number(&self)
         -> RpcResult<SuiCheckpointSequenceNumber>;
 
+    /// Return the lowest checkpoint from which each kind of history is available, the history of earlier checkpoints having been pruned by the node
+    #[method(name = "getAvailableHistory")]
+    async fn get_available_history(&self) -> RpcResult<AvailableHistory>;
+
     /// Return a checkpoint
     #[method(name = "getCheckpoint")]
     async fn get_checkpoint(
@@ -224,4 +250,42 @@ pub trait ReadApi {
         /// query result ordering, default to false (ascending order), oldest record first.
         descending_order: bool,
     ) -> RpcResult<CheckpointPage>;
+
+    /// Return a proof that an object version is part of the state of the epoch that checkpointed
+    /// the transaction writing it. Once the epoch has ended, the proof also carries the end of
+    /// epoch checkpoint and its state root.
+    #[method(name = "getObjectStateProof")]
+    async fn get_object_state_proof(
+        &self,
+        /// the ID of the object
+        object_id: ObjectID,
+        /// the version of the object
+        version: SequenceNumber,
+    ) -> RpcResult<SuiObjectStateProof>;
+
+    /// Return a proof that a transaction was executed and finalized by the checkpoint that
+    /// includes it, which light clients can verify against the committee of the epoch, or of the
+    /// previous epoch, without trusting the fullnode.
+    #[method(name = "getTransactionInclusionProof")]
+    async fn get_transaction_inclusion_proof(
+        &self,
+        /// the digest of the transaction
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiTransactionInclusionProof>;
+
+    /// Return the address a name registered with the name service points to, or null if the name is not registered, has expired, or does not point to an address.
+    #[method(name = "resolveNameServiceAddress")]
+    async fn resolve_name_service_address(
+        &self,
+        /// the name to resolve, e.g. `example.sui`
+        name: String,
+    ) -> RpcResult<Option<SuiAddress>>;
+
+    /// Return the primary name of an address in the name service, or null if the address has none.
+    #[method(name = "resolveNameServiceName")]
+    async fn resolve_name_service_name(
+        &self,
+        /// the address whose primary name is returned
+        address: SuiAddress,
+    ) -> RpcResult<Option<String>>;
 }
@@ -5,8 +5,8 @@ use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 use sui_json_rpc_types::{
-    BigInt, DevInspectResults, DryRunTransactionResponse, SuiTransactionResponse,
-    SuiTransactionResponseOptions,
+    BigInt, DevInspectResults, DryRunTransactionResponse, SuiObjectOverride,
+    SuiTransactionResponse, SuiTransactionResponseOptions,
 };
 
 use sui_open_rpc_macros::open_rpc;
@@ -52,10 +52,19 @@ pub trait WriteApi {
         gas_price: Option<BigInt>,
         /// The epoch to perform the call. Will be set from the system state object if not provided
         epoch: Option<EpochId>,
+        /// Whether to return a trace of the Move calls, gas charges and object changes of the execution. Default to false
+        show_trace: Option<bool>,
+        /// Contents and versions to give objects during the execution, in place of the ones they have on chain. Nothing is written to the chain
+        object_overrides: Option<Vec<SuiObjectOverride>>,
     ) -> RpcResult<DevInspectResults>;
 
     /// Return transaction execution effects including the gas cost summary,
     /// while the effects are not committed to the chain.
     #[method(name = "dryRunTransaction")]
-    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<DryRunTransactionResponse>;
+    async fn dry_run_transaction(
+        &self,
+        tx_bytes: Base64,
+        /// Whether to return a trace of the Move calls, gas charges and object changes of the execution. Default to false
+        show_trace: Option<bool>,
+    ) -> RpcResult<DryRunTransactionResponse>;
 }
@@ -0,0 +1,665 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Authentication of requests by API key, with per-key quotas for tiered access to the server.
+//!
+//! Each key belongs to a tier limiting the weight of the requests it may send per second, the
+//! weight of a request being the sum of the weights of its calls. Subscriptions are served over
+//! WebSocket connections, which are counted against the subscription quota of the tier when they
+//! are opened. Requests without a known key use the anonymous tier if configured, sharing a
+//! single quota, and are rejected otherwise. Keys are looked up by comparing their digests in
+//! constant time, so that response times don't reveal how much of a guessed key is right.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use fastcrypto::hash::{HashFunction, Sha256};
+use hyper::{Body, Method, Request, Response};
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::core::__reexports::serde_json::value::RawValue;
+use jsonrpsee::core::error::GenericTransportError;
+use jsonrpsee::core::http_helpers::read_body;
+use jsonrpsee::types::Request as RpcRequest;
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+use tower::{Layer, Service};
+
+use crate::error::Error as RpcError;
+use crate::metrics::ApiKeyMetrics;
+use crate::routing_layer::{is_json, response};
+use crate::{API_KEY_HEADER, MAX_REQUEST_SIZE};
+
+const ANONYMOUS: &str = "anonymous";
+const UNKNOWN: &str = "unknown";
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyConfig {
+    /// Quotas of each tier, by name of the tier.
+    pub tiers: HashMap<String, QuotaTier>,
+    /// The API keys, by value of the key.
+    #[serde(default)]
+    pub keys: HashMap<String, ApiKey>,
+    /// Tier of the requests without a known API key, which are rejected if not set.
+    #[serde(default)]
+    pub anonymous_tier: Option<String>,
+    /// Weight of the calls to each method, calls to other methods weigh 1.
+    #[serde(default)]
+    pub method_weights: HashMap<String, u64>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    /// Name of the key in logs and metrics, so that the key itself is never exposed.
+    pub name: String,
+    pub tier: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaTier {
+    /// Weight of the requests allowed per second, on average.
+    pub requests_per_second: u64,
+    /// Weight of the requests allowed at once after the key has been idle, default to
+    /// `requests_per_second`.
+    #[serde(default)]
+    pub request_burst: Option<u64>,
+    /// Subscription connections allowed per minute, on average.
+    pub subscriptions_per_minute: u64,
+}
+
+impl ApiKeyConfig {
+    pub fn load(path: &Path) -> Result<Self, RpcError> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            RpcError::UnexpectedError(format!(
+                "Cannot read API key config {}: {e}",
+                path.display()
+            ))
+        })?;
+        let config: Self = serde_json::from_str(&content).map_err(|e| {
+            RpcError::UnexpectedError(format!(
+                "Cannot parse API key config {}: {e}",
+                path.display()
+            ))
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), RpcError> {
+        let tiers = self
+            .keys
+            .values()
+            .map(|key| &key.tier)
+            .chain(&self.anonymous_tier);
+        for tier in tiers {
+            if !self.tiers.contains_key(tier) {
+                return Err(RpcError::UnexpectedError(format!(
+                    "Unknown tier {tier} in API key config"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a request was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rejection {
+    MissingKey,
+    UnknownKey,
+    RequestQuotaExceeded,
+    /// The request weighs more than the burst of the tier, so it can never be allowed.
+    RequestTooHeavy {
+        weight: u64,
+        burst: u64,
+    },
+    SubscriptionQuotaExceeded,
+}
+
+impl Rejection {
+    fn label(&self) -> &'static str {
+        match self {
+            Rejection::MissingKey => "missing_key",
+            Rejection::UnknownKey => "unknown_key",
+            Rejection::RequestQuotaExceeded => "request_quota_exceeded",
+            Rejection::RequestTooHeavy { .. } => "request_too_heavy",
+            Rejection::SubscriptionQuotaExceeded => "subscription_quota_exceeded",
+        }
+    }
+
+    fn into_response(self) -> Response<Body> {
+        match self {
+            Rejection::MissingKey => {
+                response::unauthorized(format!("Missing API key in header {API_KEY_HEADER}"))
+            }
+            Rejection::UnknownKey => response::unauthorized("Unknown API key".to_string()),
+            Rejection::RequestQuotaExceeded => {
+                response::too_many_requests("Request quota of the API key exceeded".to_string())
+            }
+            Rejection::RequestTooHeavy { weight, burst } => response::too_heavy(format!(
+                "Request weight {weight} exceeds the request burst {burst} of the API key tier"
+            )),
+            Rejection::SubscriptionQuotaExceeded => response::too_many_requests(
+                "Subscription quota of the API key exceeded".to_string(),
+            ),
+        }
+    }
+}
+
+/// Allows up to `capacity` units at once, refilled at `rate` units per second.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: u64, rate: f64, now: Instant) -> Self {
+        Self {
+            capacity: capacity as f64,
+            rate,
+            tokens: capacity as f64,
+            last_refill: now,
+        }
+    }
+
+    fn capacity(&self) -> u64 {
+        self.capacity as u64
+    }
+
+    fn try_take(&mut self, amount: u64, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+        if self.tokens < amount as f64 {
+            return false;
+        }
+        self.tokens -= amount as f64;
+        true
+    }
+}
+
+#[derive(Debug)]
+struct KeyQuota {
+    requests: TokenBucket,
+    subscriptions: TokenBucket,
+}
+
+impl KeyQuota {
+    fn new(tier: &QuotaTier, now: Instant) -> Self {
+        Self {
+            requests: TokenBucket::new(
+                tier.request_burst
+                    .unwrap_or(tier.requests_per_second)
+                    .max(tier.requests_per_second),
+                tier.requests_per_second as f64,
+                now,
+            ),
+            subscriptions: TokenBucket::new(
+                tier.subscriptions_per_minute,
+                tier.subscriptions_per_minute as f64 / 60.0,
+                now,
+            ),
+        }
+    }
+}
+
+/// The remaining quotas of the keys that sent requests, by name of the key.
+pub(crate) struct Quotas {
+    config: ApiKeyConfig,
+    /// The SHA-256 digests of the API keys, with the keys they belong to.
+    key_digests: Vec<([u8; 32], ApiKey)>,
+    quotas: Mutex<HashMap<String, KeyQuota>>,
+    metrics: ApiKeyMetrics,
+}
+
+impl Quotas {
+    pub(crate) fn new(config: ApiKeyConfig, metrics: ApiKeyMetrics) -> Self {
+        let key_digests = config
+            .keys
+            .iter()
+            .map(|(api_key, key)| (key_digest(api_key), key.clone()))
+            .collect();
+        Self {
+            config,
+            key_digests,
+            quotas: Mutex::new(HashMap::new()),
+            metrics,
+        }
+    }
+
+    fn method_weight(&self, method: &str) -> u64 {
+        self.config.method_weights.get(method).copied().unwrap_or(1)
+    }
+
+    /// The key of `api_key`. Its digest is compared with the digests of all the keys, in constant
+    /// time.
+    fn find_key(&self, api_key: &str) -> Option<&ApiKey> {
+        let digest = key_digest(api_key);
+        self.key_digests
+            .iter()
+            .fold(None, |found, (key_digest, key)| {
+                if bool::from(key_digest.ct_eq(&digest)) {
+                    Some(key)
+                } else {
+                    found
+                }
+            })
+    }
+
+    /// The name and the tier of `api_key`.
+    fn resolve(&self, api_key: Option<&str>) -> Result<(&str, &QuotaTier), Rejection> {
+        let (name, tier) = match api_key.map(|key| self.find_key(key)) {
+            Some(Some(key)) => (key.name.as_str(), &key.tier),
+            unknown => match &self.config.anonymous_tier {
+                Some(tier) => (ANONYMOUS, tier),
+                None if unknown.is_some() => return Err(Rejection::UnknownKey),
+                None => return Err(Rejection::MissingKey),
+            },
+        };
+        // Tiers are validated when the config is loaded.
+        Ok((name, &self.config.tiers[tier]))
+    }
+
+    /// Charges a request calling `methods` to the quota of `api_key`.
+    pub(crate) fn check_request(
+        &self,
+        api_key: Option<&str>,
+        methods: &[String],
+        now: Instant,
+    ) -> Result<(), Rejection> {
+        let weight = methods
+            .iter()
+            .map(|method| self.method_weight(method))
+            .sum();
+        let name = self.charge(api_key, now, |quota| {
+            let burst = quota.requests.capacity();
+            if weight > burst {
+                Err(Rejection::RequestTooHeavy { weight, burst })
+            } else if quota.requests.try_take(weight, now) {
+                Ok(())
+            } else {
+                Err(Rejection::RequestQuotaExceeded)
+            }
+        })?;
+        self.metrics
+            .requests_by_key
+            .with_label_values(&[name])
+            .inc();
+        self.metrics
+            .request_weight_by_key
+            .with_label_values(&[name])
+            .inc_by(weight);
+        Ok(())
+    }
+
+    /// Charges a subscription connection to the quota of `api_key`.
+    pub(crate) fn check_subscription(
+        &self,
+        api_key: Option<&str>,
+        now: Instant,
+    ) -> Result<(), Rejection> {
+        let name = self.charge(api_key, now, |quota| {
+            if quota.subscriptions.try_take(1, now) {
+                Ok(())
+            } else {
+                Err(Rejection::SubscriptionQuotaExceeded)
+            }
+        })?;
+        self.metrics
+            .subscriptions_by_key
+            .with_label_values(&[name])
+            .inc();
+        Ok(())
+    }
+
+    /// Runs `take` on the quota of `api_key`, rejecting the request with its rejection if it
+    /// fails. Returns the name of the key.
+    fn charge(
+        &self,
+        api_key: Option<&str>,
+        now: Instant,
+        take: impl FnOnce(&mut KeyQuota) -> Result<(), Rejection>,
+    ) -> Result<&str, Rejection> {
+        let (name, tier) = self.resolve(api_key).map_err(|rejection| {
+            self.metrics
+                .rejected_by_key
+                .with_label_values(&[UNKNOWN, rejection.label()])
+                .inc();
+            rejection
+        })?;
+        let mut quotas = self.quotas.lock().unwrap();
+        let quota = quotas
+            .entry(name.to_string())
+            .or_insert_with(|| KeyQuota::new(tier, now));
+        if let Err(rejection) = take(quota) {
+            self.metrics
+                .rejected_by_key
+                .with_label_values(&[name, rejection.label()])
+                .inc();
+            return Err(rejection);
+        }
+        Ok(name)
+    }
+}
+
+fn key_digest(api_key: &str) -> [u8; 32] {
+    Sha256::digest(api_key.as_bytes()).digest
+}
+
+#[derive(Clone)]
+pub struct ApiKeyLayer {
+    quotas: Option<Arc<Quotas>>,
+}
+
+impl ApiKeyLayer {
+    /// Requests are not authenticated if `quotas` is not set.
+    pub(crate) fn new(quotas: Option<Arc<Quotas>>) -> Self {
+        Self { quotas }
+    }
+}
+
+impl<S> Layer<S> for ApiKeyLayer {
+    type Service = ApiKeyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyService {
+            inner,
+            quotas: self.quotas.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ApiKeyService<S> {
+    inner: S,
+    quotas: Option<Arc<Quotas>>,
+}
+
+impl<S> Service<Request<Body>> for ApiKeyService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Response: 'static,
+    S::Error: Into<Box<dyn Error + Send + Sync>> + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn Error + Send + Sync + 'static>;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    #[inline]
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let clone = self.inner.clone();
+        let quotas = self.quotas.clone();
+        // take the service that was ready
+        // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let res_fut = async move {
+            let Some(quotas) = quotas else {
+                return inner.call(req).await.map_err(|err| err.into());
+            };
+            let api_key = req
+                .headers()
+                .get(API_KEY_HEADER)
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string());
+
+            let req = if is_websocket(&req) {
+                if let Err(rejection) =
+                    quotas.check_subscription(api_key.as_deref(), Instant::now())
+                {
+                    return Ok(rejection.into_response());
+                }
+                req
+            } else if req.method() == Method::POST && is_json(&req) {
+                let (parts, body) = req.into_parts();
+                let (body, _) = match read_body(&parts.headers, body, MAX_REQUEST_SIZE).await {
+                    Ok(r) => r,
+                    Err(GenericTransportError::TooLarge) => {
+                        return Ok(response::too_large(MAX_REQUEST_SIZE))
+                    }
+                    Err(GenericTransportError::Malformed) => return Ok(response::malformed()),
+                    Err(GenericTransportError::Inner(e)) => {
+                        tracing::error!("Internal error reading request body: {}", e);
+                        return Ok(response::internal_error());
+                    }
+                };
+                let methods = method_names(&body);
+                if let Err(rejection) =
+                    quotas.check_request(api_key.as_deref(), &methods, Instant::now())
+                {
+                    return Ok(rejection.into_response());
+                }
+                Request::from_parts(parts, Body::from(body))
+            } else {
+                req
+            };
+            inner.call(req).await.map_err(|err| err.into())
+        };
+        Box::pin(res_fut)
+    }
+}
+
+fn is_websocket(request: &Request<Body>) -> bool {
+    request
+        .headers()
+        .get(hyper::header::UPGRADE)
+        .and_then(|val| val.to_str().ok())
+        .map_or(false, |upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+}
+
+// the methods called by a single or a batch request, a call that cannot be parsed is counted
+// with an empty method name, so it weighs 1.
+fn method_names(body: &[u8]) -> Vec<String> {
+    let method = |request: &[u8]| {
+        serde_json::from_slice::<RpcRequest>(request)
+            .map(|request| request.method.to_string())
+            .unwrap_or_default()
+    };
+    match serde_json::from_slice::<Vec<&RawValue>>(body) {
+        Ok(requests) => requests
+            .iter()
+            .map(|request| method(request.get().as_bytes()))
+            .collect(),
+        Err(_) => vec![method(body)],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use prometheus::Registry;
+
+    use super::*;
+
+    fn quotas(anonymous_tier: Option<&str>) -> Quotas {
+        let config: ApiKeyConfig = serde_json::from_str(
+            r#"{
+                "tiers": {
+                    "free": { "requestsPerSecond": 2, "subscriptionsPerMinute": 1 },
+                    "pro": { "requestsPerSecond": 10, "requestBurst": 20, "subscriptionsPerMinute": 60 }
+                },
+                "keys": {
+                    "free-key": { "name": "alice", "tier": "free" },
+                    "pro-key": { "name": "bob", "tier": "pro" }
+                },
+                "methodWeights": { "sui_multiGetObjects": 5 }
+            }"#,
+        )
+        .unwrap();
+        let config = ApiKeyConfig {
+            anonymous_tier: anonymous_tier.map(String::from),
+            ..config
+        };
+        config.validate().unwrap();
+        Quotas::new(config, ApiKeyMetrics::new(&Registry::new()))
+    }
+
+    fn calls(methods: &[&str]) -> Vec<String> {
+        methods.iter().map(|method| method.to_string()).collect()
+    }
+
+    #[test]
+    fn test_request_quota() {
+        let quotas = quotas(None);
+        let now = Instant::now();
+        let get_object = calls(&["sui_getObject"]);
+
+        assert_eq!(
+            quotas.check_request(Some("free-key"), &get_object, now),
+            Ok(())
+        );
+        assert_eq!(
+            quotas.check_request(Some("free-key"), &get_object, now),
+            Ok(())
+        );
+        assert_eq!(
+            quotas.check_request(Some("free-key"), &get_object, now),
+            Err(Rejection::RequestQuotaExceeded)
+        );
+        // other keys have their own quota
+        assert_eq!(
+            quotas.check_request(Some("pro-key"), &get_object, now),
+            Ok(())
+        );
+
+        // the quota is refilled over time
+        let later = now + Duration::from_millis(500);
+        assert_eq!(
+            quotas.check_request(Some("free-key"), &get_object, later),
+            Ok(())
+        );
+        assert_eq!(
+            quotas.check_request(Some("free-key"), &get_object, later),
+            Err(Rejection::RequestQuotaExceeded)
+        );
+    }
+
+    #[test]
+    fn test_method_weights() {
+        let quotas = quotas(None);
+        let now = Instant::now();
+        // a weight above the burst of the tier is never allowed, even with a full quota
+        let later = now + Duration::from_secs(60);
+        for now in [now, later] {
+            assert_eq!(
+                quotas.check_request(Some("free-key"), &calls(&["sui_multiGetObjects"]), now),
+                Err(Rejection::RequestTooHeavy {
+                    weight: 5,
+                    burst: 2
+                })
+            );
+        }
+        // and it does not use the quota
+        for _ in 0..2 {
+            assert_eq!(
+                quotas.check_request(Some("free-key"), &calls(&["sui_getObject"]), later),
+                Ok(())
+            );
+        }
+        // the calls of a batch are summed up
+        let batch = calls(&[
+            "sui_multiGetObjects",
+            "sui_multiGetObjects",
+            "sui_getObject",
+        ]);
+        assert_eq!(quotas.check_request(Some("pro-key"), &batch, now), Ok(()));
+        assert_eq!(
+            quotas.check_request(Some("pro-key"), &batch, now),
+            Err(Rejection::RequestQuotaExceeded)
+        );
+    }
+
+    #[test]
+    fn test_subscription_quota() {
+        let quotas = quotas(None);
+        let now = Instant::now();
+        assert_eq!(quotas.check_subscription(Some("free-key"), now), Ok(()));
+        assert_eq!(
+            quotas.check_subscription(Some("free-key"), now),
+            Err(Rejection::SubscriptionQuotaExceeded)
+        );
+        // subscriptions do not use the request quota
+        assert_eq!(
+            quotas.check_request(Some("free-key"), &calls(&["sui_getObject"]), now),
+            Ok(())
+        );
+        let later = now + Duration::from_secs(60);
+        assert_eq!(quotas.check_subscription(Some("free-key"), later), Ok(()));
+    }
+
+    #[test]
+    fn test_anonymous_requests() {
+        let get_object = calls(&["sui_getObject"]);
+        let now = Instant::now();
+
+        let quotas_without_anonymous = quotas(None);
+        assert_eq!(
+            quotas_without_anonymous.check_request(None, &get_object, now),
+            Err(Rejection::MissingKey)
+        );
+        assert_eq!(
+            quotas_without_anonymous.check_request(Some("other-key"), &get_object, now),
+            Err(Rejection::UnknownKey)
+        );
+
+        // anonymous requests share the quota of the anonymous tier
+        let quotas = quotas(Some("free"));
+        assert_eq!(quotas.check_request(None, &get_object, now), Ok(()));
+        assert_eq!(
+            quotas.check_request(Some("other-key"), &get_object, now),
+            Ok(())
+        );
+        assert_eq!(
+            quotas.check_request(None, &get_object, now),
+            Err(Rejection::RequestQuotaExceeded)
+        );
+        assert_eq!(
+            quotas.check_request(Some("free-key"), &get_object, now),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_unknown_tier() {
+        let config: ApiKeyConfig = serde_json::from_str(
+            r#"{ "tiers": {}, "keys": { "key": { "name": "alice", "tier": "free" } } }"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_method_names() {
+        assert_eq!(
+            method_names(br#"{"jsonrpc":"2.0","id":1,"method":"sui_getObject","params":[]}"#),
+            vec!["sui_getObject"]
+        );
+        assert_eq!(
+            method_names(
+                br#"[{"jsonrpc":"2.0","id":1,"method":"sui_getObject"},{"jsonrpc":"2.0","id":2,"method":"sui_getEvents"}]"#
+            ),
+            vec!["sui_getObject", "sui_getEvents"]
+        );
+        assert_eq!(method_names(b"not json"), vec![""]);
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::cmp::Ordering;
+
+use sui_json_rpc_types::{ClientWarning, ClientWarningKind, DeprecatedMethod, RpcCapabilities};
+use sui_open_rpc::{compare_versions, Project};
+use sui_protocol_config::ProtocolVersion;
+
+/// The capabilities of the server documented by `rpc_doc`, without client warnings.
+pub(crate) fn rpc_capabilities(
+    rpc_doc: &Project,
+    min_client_api_version: Option<String>,
+) -> RpcCapabilities {
+    RpcCapabilities {
+        api_version: rpc_doc.version().to_string(),
+        min_client_api_version,
+        max_protocol_version: ProtocolVersion::MAX.as_u64(),
+        methods: rpc_doc.method_names().map(String::from).collect(),
+        deprecated_methods: rpc_doc
+            .deprecated_methods()
+            .map(|(method, description)| DeprecatedMethod {
+                method: method.to_string(),
+                description: description.map(String::from),
+            })
+            .collect(),
+        client_warnings: vec![],
+    }
+}
+
+/// Warnings for a client targeting `client_api_version`.
+pub(crate) fn client_warnings(
+    capabilities: &RpcCapabilities,
+    client_api_version: &str,
+) -> Vec<ClientWarning> {
+    let Some(ordering) = compare_versions(client_api_version, &capabilities.api_version) else {
+        return vec![ClientWarning {
+            kind: ClientWarningKind::InvalidClientApiVersion,
+            message: format!("Invalid client API version {client_api_version}"),
+        }];
+    };
+
+    let mut warnings = vec![];
+    if ordering == Ordering::Greater {
+        warnings.push(ClientWarning {
+            kind: ClientWarningKind::StaleServer,
+            message: format!(
+                "Client targets API version {client_api_version}, newer than the version {} served, methods added since are not available",
+                capabilities.api_version
+            ),
+        });
+    }
+    if let Some(min_client_api_version) = &capabilities.min_client_api_version {
+        if compare_versions(client_api_version, min_client_api_version) == Some(Ordering::Less) {
+            warnings.push(ClientWarning {
+                kind: ClientWarningKind::StaleClient,
+                message: format!(
+                    "Client targets API version {client_api_version}, older than the version {min_client_api_version} required by the server, methods may not behave as the client expects"
+                ),
+            });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning_kinds(
+        min_client_api_version: Option<&str>,
+        version: &str,
+    ) -> Vec<ClientWarningKind> {
+        let capabilities = rpc_capabilities(
+            &crate::sui_rpc_doc("0.28.0"),
+            min_client_api_version.map(String::from),
+        );
+        client_warnings(&capabilities, version)
+            .into_iter()
+            .map(|w| w.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_client_warnings() {
+        let min_version = Some("0.27.0");
+        assert!(warning_kinds(min_version, "0.28.0").is_empty());
+        assert!(warning_kinds(min_version, "0.27.1").is_empty());
+        assert_eq!(
+            warning_kinds(min_version, "0.26.0"),
+            vec![ClientWarningKind::StaleClient]
+        );
+        assert_eq!(
+            warning_kinds(min_version, "0.29.0"),
+            vec![ClientWarningKind::StaleServer]
+        );
+
+        // Without a minimum version, old clients are served as they used to be.
+        assert!(warning_kinds(None, "0.1.0").is_empty());
+    }
+}
@@ -5,6 +5,7 @@ use fastcrypto::error::FastCryptoError;
 use hyper::header::InvalidHeaderValue;
 use jsonrpsee::core::Error as RpcError;
 use jsonrpsee::types::error::CallError;
+use sui_core::name_service::NameServiceError;
 use sui_types::error::{SuiError, SuiObjectResponseError, UserInputError};
 use sui_types::quorum_driver_types::QuorumDriverError;
 use thiserror::Error;
@@ -47,6 +48,9 @@ pub enum Error {
 
     #[error(transparent)]
     SuiObjectResponseError(#[from] SuiObjectResponseError),
+
+    #[error(transparent)]
+    NameServiceError(#[from] NameServiceError),
 }
 
 impl From<Error> for RpcError {
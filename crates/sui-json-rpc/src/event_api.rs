@@ -14,14 +14,14 @@ use tracing::{debug, warn};
 
 use mysten_metrics::spawn_monitored_task;
 use sui_core::authority::AuthorityState;
-use sui_json_rpc_types::{EventFilter, EventPage, SuiEvent};
+use sui_json_rpc_types::{EventFilter, EventPage, ObjectChangeFilter, SuiEvent};
 use sui_open_rpc::Module;
 use sui_types::digests::TransactionDigest;
 use sui_types::event::EventID;
 use sui_types::messages::TransactionEffectsAPI;
 
 use crate::api::cap_page_limit;
-use crate::api::EventReadApiServer;
+use crate::api::{EventReadApiServer, ObjectChangeApiServer};
 use crate::error::Error;
 use crate::SuiRpcModule;
 
@@ -130,3 +130,37 @@ impl SuiRpcModule for EventReadApi {
         crate::api::EventReadApiOpenRpc::module_doc()
     }
 }
+
+pub struct ObjectChangeApi {
+    state: Arc<AuthorityState>,
+}
+
+impl ObjectChangeApi {
+    pub fn new(state: Arc<AuthorityState>) -> Self {
+        Self { state }
+    }
+}
+
+impl ObjectChangeApiServer for ObjectChangeApi {
+    fn subscribe_object_changes(
+        &self,
+        sink: SubscriptionSink,
+        filter: ObjectChangeFilter,
+    ) -> SubscriptionResult {
+        spawn_subscription(
+            sink,
+            self.state.event_handler.subscribe_object_changes(filter),
+        );
+        Ok(())
+    }
+}
+
+impl SuiRpcModule for ObjectChangeApi {
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        crate::api::ObjectChangeApiOpenRpc::module_doc()
+    }
+}
@@ -10,13 +10,14 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 
 use sui_core::authority::AuthorityState;
-use sui_json_rpc_types::{BigInt, SuiCommittee};
+use sui_core::gas_price_oracle::GasPriceOracle;
+use sui_json_rpc_types::{BigInt, SuiCommittee, SuiGasPriceEstimates};
 use sui_json_rpc_types::{DelegatedStake, Stake, StakeStatus};
 use sui_open_rpc::Module;
 use sui_types::base_types::{MoveObjectType, ObjectID, SuiAddress};
 use sui_types::committee::EpochId;
 use sui_types::dynamic_field::get_dynamic_field_from_store;
-use sui_types::error::SuiError;
+use sui_types::error::{SuiError, UserInputError};
 use sui_types::governance::StakedSui;
 use sui_types::id::ID;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
@@ -32,11 +33,21 @@ use crate::SuiRpcModule;
 
 pub struct GovernanceReadApi {
     state: Arc<AuthorityState>,
+    /// Estimates gas prices from recent checkpoints, which is not supported if `None`.
+    gas_price_oracle: Option<Arc<GasPriceOracle>>,
 }
 
 impl GovernanceReadApi {
     pub fn new(state: Arc<AuthorityState>) -> Self {
-        Self { state }
+        Self {
+            state,
+            gas_price_oracle: None,
+        }
+    }
+
+    pub fn with_gas_price_oracle(mut self, gas_price_oracle: Arc<GasPriceOracle>) -> Self {
+        self.gas_price_oracle = Some(gas_price_oracle);
+        self
     }
 
     async fn get_staked_sui(&self, owner: SuiAddress) -> Result<Vec<StakedSui>, Error> {
@@ -223,6 +234,17 @@ impl GovernanceReadApiServer for GovernanceReadApi {
         let epoch_store = self.state.load_epoch_store_one_call_per_task();
         Ok(epoch_store.reference_gas_price().into())
     }
+
+    async fn get_gas_price_estimates(&self) -> RpcResult<SuiGasPriceEstimates> {
+        let Some(gas_price_oracle) = &self.gas_price_oracle else {
+            return Err(Error::from(UserInputError::Unsupported(
+                "gas price estimates are not enabled on this node".to_string(),
+            ))
+            .into());
+        };
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        Ok(gas_price_oracle.estimates(epoch_store.reference_gas_price()))
+    }
 }
 
 impl SuiRpcModule for GovernanceReadApi {
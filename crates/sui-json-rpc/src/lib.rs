@@ -3,7 +3,9 @@
 
 use std::env;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use hyper::header::HeaderName;
 use hyper::header::HeaderValue;
@@ -20,12 +22,16 @@ pub use balance_changes::*;
 pub use object_changes::*;
 use sui_open_rpc::{Module, Project};
 
+use crate::api_key_layer::{ApiKeyConfig, ApiKeyLayer, Quotas};
+use crate::capabilities::{client_warnings, rpc_capabilities};
 use crate::error::Error;
-use crate::metrics::MetricsLogger;
+use crate::metrics::{ApiKeyMetrics, BatchMetrics, MetricsLogger};
 use crate::routing_layer::RoutingLayer;
 
 pub mod api;
+mod api_key_layer;
 mod balance_changes;
+mod capabilities;
 pub mod coin_api;
 pub mod error;
 pub mod event_api;
@@ -44,8 +50,13 @@ pub const CLIENT_SDK_VERSION_HEADER: &str = "client-sdk-version";
 /// API version.
 pub const CLIENT_TARGET_API_VERSION_HEADER: &str = "client-target-api-version";
 pub const APP_NAME_HEADER: &str = "app-name";
+/// The API key authenticating the request, when the server is configured with API keys.
+pub const API_KEY_HEADER: &str = "api-key";
 
 pub const MAX_REQUEST_SIZE: u32 = 2 << 30;
+/// Default maximum number of requests in a batch, overridden by the `RPC_MAX_BATCH_SIZE`
+/// environment variable.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 50;
 
 #[cfg(test)]
 #[path = "unit_tests/rpc_server_tests.rs"]
@@ -112,10 +123,23 @@ impl JsonRpcServerBuilder {
                 HeaderName::from_static(CLIENT_SDK_VERSION_HEADER),
                 HeaderName::from_static(CLIENT_TARGET_API_VERSION_HEADER),
                 HeaderName::from_static(APP_NAME_HEADER),
+                HeaderName::from_static(API_KEY_HEADER),
             ]);
 
         let routing = self.rpc_doc.method_routing.clone();
 
+        let min_client_api_version = env::var("RPC_MIN_CLIENT_API_VERSION").ok();
+        let capabilities = rpc_capabilities(&self.rpc_doc, min_client_api_version);
+        self.module
+            .register_method("rpc.capabilities", move |params, _| {
+                let client_api_version: Option<String> = params.sequence().optional_next()?;
+                let mut capabilities = capabilities.clone();
+                if let Some(client_api_version) = client_api_version {
+                    capabilities.client_warnings =
+                        client_warnings(&capabilities, &client_api_version);
+                }
+                Ok(capabilities)
+            })?;
         self.module
             .register_method("rpc.discover", move |_, _| Ok(self.rpc_doc.clone()))?;
         let methods_names = self.module.method_names().collect::<Vec<_>>();
@@ -129,6 +153,15 @@ impl JsonRpcServerBuilder {
             })
             .unwrap_or(u32::MAX);
 
+        let max_batch_size = env::var("RPC_MAX_BATCH_SIZE")
+            .ok()
+            .and_then(|o| {
+                usize::from_str(&o)
+                    .tap_err(|e| warn!("Cannot parse RPC_MAX_BATCH_SIZE to usize: {e}"))
+                    .ok()
+            })
+            .unwrap_or(DEFAULT_MAX_BATCH_SIZE);
+
         let metrics_logger = MetricsLogger::new(&self.registry, &methods_names);
 
         let disable_routing = env::var("DISABLE_BACKWARD_COMPATIBILITY")
@@ -144,14 +177,38 @@ impl JsonRpcServerBuilder {
             }
         );
         // We need to use the routing layer to block access to the old methods when routing is disabled.
-        let routing_layer = RoutingLayer::new(routing, disable_routing);
+        let routing_layer = RoutingLayer::new(
+            routing,
+            disable_routing,
+            max_batch_size,
+            BatchMetrics::new(&self.registry),
+        );
+
+        // Requests are authenticated by API key if the server is configured with a JSON file of
+        // API keys and quotas, see [ApiKeyConfig].
+        let quotas = match env::var("RPC_API_KEYS_CONFIG") {
+            Ok(path) => {
+                let config = ApiKeyConfig::load(Path::new(&path))?;
+                info!(
+                    "API keys enabled, with {} keys and anonymous tier {:?}.",
+                    config.keys.len(),
+                    config.anonymous_tier
+                );
+                Some(Arc::new(Quotas::new(
+                    config,
+                    ApiKeyMetrics::new(&self.registry),
+                )))
+            }
+            Err(_) => None,
+        };
 
         let middleware = tower::ServiceBuilder::new()
             .layer(cors)
+            .layer(ApiKeyLayer::new(quotas))
             .layer(routing_layer);
 
         let server = ServerBuilder::default()
-            .batch_requests_supported(false)
+            .batch_requests_supported(true)
             .max_response_body_size(MAX_REQUEST_SIZE)
             .max_connections(max_connection)
             .set_host_filtering(AllowHosts::Any)
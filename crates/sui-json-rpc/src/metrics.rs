@@ -9,8 +9,10 @@ use crate::{CLIENT_SDK_TYPE_HEADER, CLIENT_TARGET_API_VERSION_HEADER};
 use jsonrpsee::server::logger::{HttpRequest, Logger, MethodKind, TransportProtocol};
 use jsonrpsee::types::Params;
 use prometheus::{
-    register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_gauge_vec_with_registry, HistogramVec, IntCounterVec, IntGaugeVec,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGaugeVec,
 };
 use tokio::time::Instant;
 
@@ -39,6 +41,85 @@ pub struct Metrics {
     rpc_response_size: HistogramVec,
 }
 
+/// Metrics of batch requests. The calls of a batch are also counted one by one by the
+/// [MetricsLogger], like single requests.
+#[derive(Debug, Clone)]
+pub struct BatchMetrics {
+    /// Number of calls in a batch request
+    pub batch_size: Histogram,
+    /// Batch requests rejected for exceeding the maximum batch size
+    pub rejected_batches: IntCounter,
+}
+
+impl BatchMetrics {
+    pub fn new(registry: &prometheus::Registry) -> Self {
+        Self {
+            batch_size: register_histogram_with_registry!(
+                "rpc_batch_size",
+                "Number of calls in a batch request",
+                prometheus::exponential_buckets(1.0, 2.0, 10).unwrap(),
+                registry,
+            )
+            .unwrap(),
+            rejected_batches: register_int_counter_with_registry!(
+                "rpc_rejected_batches",
+                "Number of batch requests rejected for exceeding the maximum batch size",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+/// Usage of the API keys, by name of the key. Requests without a known key are counted under
+/// the name of the anonymous tier, or rejected under `unknown`.
+#[derive(Debug, Clone)]
+pub struct ApiKeyMetrics {
+    /// Requests accepted by key
+    pub requests_by_key: IntCounterVec,
+    /// Total weight of the calls of the requests accepted by key
+    pub request_weight_by_key: IntCounterVec,
+    /// Subscription connections accepted by key
+    pub subscriptions_by_key: IntCounterVec,
+    /// Requests and subscription connections rejected by key and reason
+    pub rejected_by_key: IntCounterVec,
+}
+
+impl ApiKeyMetrics {
+    pub fn new(registry: &prometheus::Registry) -> Self {
+        Self {
+            requests_by_key: register_int_counter_vec_with_registry!(
+                "rpc_requests_by_api_key",
+                "Number of requests accepted by API key",
+                &["key"],
+                registry,
+            )
+            .unwrap(),
+            request_weight_by_key: register_int_counter_vec_with_registry!(
+                "rpc_request_weight_by_api_key",
+                "Total weight of the calls of the requests accepted by API key",
+                &["key"],
+                registry,
+            )
+            .unwrap(),
+            subscriptions_by_key: register_int_counter_vec_with_registry!(
+                "rpc_subscriptions_by_api_key",
+                "Number of subscription connections accepted by API key",
+                &["key"],
+                registry,
+            )
+            .unwrap(),
+            rejected_by_key: register_int_counter_vec_with_registry!(
+                "rpc_rejected_by_api_key",
+                "Number of requests and subscription connections rejected by API key and reason",
+                &["key", "reason"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct MetricsLogger {
     metrics: Metrics,
@@ -24,15 +24,20 @@ use tap::TapFallible;
 use tracing::debug;
 
 use shared_crypto::intent::{AppId, Intent, IntentMessage, IntentScope, IntentVersion};
+use sui_core::archive_fallback::ArchiveFallback;
+use sui_core::authority::checkpoint_history_pruner::PrunedHistory;
 use sui_core::authority::AuthorityState;
+use sui_core::name_service::{NameService, NameServiceError};
+use sui_core::transaction_scan::{query_checkpointed_transactions, scan_transactions};
 use sui_json_rpc_types::{
-    BalanceChange, BigInt, Checkpoint, CheckpointId, CheckpointPage, DynamicFieldPage, EventFilter,
-    MoveFunctionArgType, ObjectChange, ObjectValueKind, ObjectsPage, Page,
-    SuiCheckpointSequenceNumber, SuiGetPastObjectRequest, SuiMoveNormalizedFunction,
-    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiMoveStruct, SuiMoveValue,
-    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiPastObjectResponse,
-    SuiTransaction, SuiTransactionEvents, SuiTransactionResponse, SuiTransactionResponseOptions,
-    SuiTransactionResponseQuery, TransactionsPage,
+    AvailableHistory, BalanceChange, BigInt, Checkpoint, CheckpointId, CheckpointPage,
+    DynamicFieldPage, EventFilter, MoveFunctionArgType, ObjectChange, ObjectValueKind, ObjectsPage,
+    Page, SuiCheckpointSequenceNumber, SuiGetPastObjectRequest, SuiMoveNormalizedFunction,
+    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiMoveStruct, SuiMoveValue, SuiObjectData,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiObjectStateProof,
+    SuiPastObjectResponse, SuiTransaction, SuiTransactionEvents, SuiTransactionInclusionProof,
+    SuiTransactionResponse, SuiTransactionResponseOptions, SuiTransactionResponseQuery,
+    TransactionCursor, TransactionScanPage, TransactionsPage,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{
@@ -51,11 +56,12 @@ use sui_types::messages::{
 };
 use sui_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointTimestamp};
 use sui_types::move_package::normalize_modules;
-use sui_types::object::{Data, Object, ObjectRead, PastObjectRead};
+use sui_types::object::{Data, Object, ObjectFormatOptions, ObjectRead, PastObjectRead};
 
 use crate::api::{cap_page_limit, validate_limit, ReadApiServer};
 use crate::api::{
     QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS, QUERY_MAX_RESULT_LIMIT_OBJECTS,
+    QUERY_MAX_WALKED_TRANSACTIONS,
 };
 use crate::error::Error;
 use crate::{
@@ -68,6 +74,13 @@ const MAX_DISPLAY_NESTED_LEVEL: usize = 10;
 // Fullnodes.
 pub struct ReadApi {
     pub state: Arc<AuthorityState>,
+    /// Serves the transactions and past object versions that this node has pruned.
+    archive: Option<Arc<ArchiveFallback>>,
+    /// Number of most recent checkpoints scanned by `scan_transactions`, which is not supported if
+    /// `None`.
+    scanned_checkpoints: Option<u64>,
+    /// Resolves names registered with the name service, which is not supported if `None`.
+    name_service: Option<Arc<NameService>>,
 }
 
 // Internal data structure to make it easy to work with data returned from
@@ -97,7 +110,67 @@ impl IntermediateTransactionResponse {
 
 impl ReadApi {
     pub fn new(state: Arc<AuthorityState>) -> Self {
-        Self { state }
+        Self {
+            state,
+            archive: None,
+            scanned_checkpoints: None,
+            name_service: None,
+        }
+    }
+
+    pub fn with_archive(mut self, archive: Arc<ArchiveFallback>) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    pub fn with_transaction_scan(mut self, max_checkpoints: u64) -> Self {
+        self.scanned_checkpoints = Some(max_checkpoints);
+        self
+    }
+
+    pub fn with_name_service(mut self, name_service: Arc<NameService>) -> Self {
+        self.name_service = Some(name_service);
+        self
+    }
+
+    fn name_service(&self) -> Result<&NameService, Error> {
+        Ok(self
+            .name_service
+            .as_deref()
+            .ok_or(NameServiceError::NotConfigured)?)
+    }
+
+    /// Reads a transaction that this node has pruned from the archive, or returns `None` if the
+    /// archive does not have it either. Events, balance changes and object changes
+    /// are not available for archived transactions.
+    async fn get_archived_transaction(
+        &self,
+        archive: &ArchiveFallback,
+        digest: TransactionDigest,
+        opts: &SuiTransactionResponseOptions,
+    ) -> Result<Option<SuiTransactionResponse>, Error> {
+        let Some(archived) = archive.get_transaction(digest).await? else {
+            return Ok(None);
+        };
+        let mut temp_response = IntermediateTransactionResponse::new(digest);
+        temp_response.transaction = Some(archived.transaction);
+        temp_response.effects = Some(archived.effects);
+        temp_response.checkpoint_seq = Some(archived.checkpoint.into());
+        if opts.show_timestamp() {
+            temp_response.timestamp = Some(archived.timestamp_ms);
+        }
+        if opts.show_events || opts.show_balance_changes || opts.show_object_changes {
+            temp_response.errors.push(
+                "Events, balance changes and object changes are not available for transactions \
+                served from the archive"
+                    .to_string(),
+            );
+        }
+
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let mut response = convert_to_response(temp_response, opts, epoch_store.module_cache());
+        response.served_from_archive = Some(true);
+        Ok(Some(response))
     }
 
     fn get_checkpoint_internal(&self, id: CheckpointId) -> Result<Checkpoint, Error> {
@@ -137,7 +210,7 @@ impl ReadApiServer for ReadApi {
         }
         let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_OBJECTS)?;
         let SuiObjectResponseQuery { filter, options } = query.unwrap_or_default();
-        let options = options.unwrap_or_default();
+        let options = options.unwrap_or_default().resolve_fields()?;
 
         let mut objects = self
             .state
@@ -202,7 +275,7 @@ impl ReadApiServer for ReadApi {
             debug!(?object_id, "Failed to get object: {:?}", e);
             anyhow!("{e}")
         })?;
-        let options = options.unwrap_or_default();
+        let options = options.unwrap_or_default().resolve_fields()?;
 
         match object_read {
             ObjectRead::NotExists(id) => Ok(SuiObjectResponse::new_with_error(
@@ -273,7 +346,7 @@ impl ReadApiServer for ReadApi {
             .get_past_object_read(&object_id, version)
             .await
             .map_err(|e| anyhow!("{e}"))?;
-        let options = options.unwrap_or_default();
+        let options = options.unwrap_or_default().resolve_fields()?;
         match past_read {
             PastObjectRead::ObjectNotExists(id) => Ok(SuiPastObjectResponse::ObjectNotExists(id)),
             PastObjectRead::VersionFound(object_ref, o, layout) => {
@@ -290,7 +363,35 @@ impl ReadApiServer for ReadApi {
                 Ok(SuiPastObjectResponse::ObjectDeleted(oref.into()))
             }
             PastObjectRead::VersionNotFound(id, seq_num) => {
-                Ok(SuiPastObjectResponse::VersionNotFound(id, seq_num))
+                let archived = match &self.archive {
+                    Some(archive) => archive.get_object(id, seq_num).await.map_err(Error::from)?,
+                    None => None,
+                };
+                let Some(o) = archived else {
+                    return Ok(SuiPastObjectResponse::VersionNotFound(id, seq_num));
+                };
+                let epoch_store = self.state.load_epoch_store_one_call_per_task();
+                let layout = o
+                    .get_layout(
+                        ObjectFormatOptions::default(),
+                        epoch_store.module_cache().as_ref(),
+                    )
+                    .map_err(Error::from)?;
+                let display_fields = if options.show_display {
+                    get_display_fields(self, &o, &layout).await?
+                } else {
+                    None
+                };
+                let mut data: SuiObjectData = (
+                    o.compute_object_reference(),
+                    o,
+                    layout,
+                    options,
+                    display_fields,
+                )
+                    .try_into()?;
+                data.served_from_archive = Some(true);
+                Ok(SuiPastObjectResponse::VersionFound(data))
             }
             PastObjectRead::VersionTooHigh {
                 object_id,
@@ -380,7 +481,17 @@ impl ReadApiServer for ReadApi {
         digest: TransactionDigest,
         opts: Option<SuiTransactionResponseOptions>,
     ) -> RpcResult<SuiTransactionResponse> {
-        let opts = opts.unwrap_or_default();
+        let opts = opts.unwrap_or_default().resolve_fields()?;
+        if let Some(archive) = &self.archive {
+            if !self.state.is_tx_already_executed(&digest)? {
+                if let Some(response) = self
+                    .get_archived_transaction(archive, digest, &opts)
+                    .await?
+                {
+                    return Ok(response);
+                }
+            }
+        }
         let mut temp_response = IntermediateTransactionResponse::new(digest);
 
         // the input is needed for object_changes to retrieve the sender address.
@@ -399,15 +510,17 @@ impl ReadApiServer for ReadApi {
                 )?);
         }
 
-        if let Some((_, seq)) = self
-            .state
-            .get_transaction_checkpoint_sequence(&digest)
-            .map_err(|e| anyhow!("{e}"))?
-        {
-            temp_response.checkpoint_seq = Some(seq.into());
+        if opts.show_checkpoint() || opts.show_timestamp() {
+            if let Some((_, seq)) = self
+                .state
+                .get_transaction_checkpoint_sequence(&digest)
+                .map_err(|e| anyhow!("{e}"))?
+            {
+                temp_response.checkpoint_seq = Some(seq.into());
+            }
         }
 
-        if temp_response.checkpoint_seq.is_some() {
+        if opts.show_timestamp() && temp_response.checkpoint_seq.is_some() {
             let checkpoint = self
                 .state
                 // safe to unwrap because we have checked `is_some` above
@@ -484,7 +597,7 @@ impl ReadApiServer for ReadApi {
             .into());
         }
 
-        let opts = opts.unwrap_or_default();
+        let opts = opts.unwrap_or_default().resolve_fields()?;
 
         // use LinkedHashMap to dedup and can iterate in insertion order.
         let mut temp_response: LinkedHashMap<&TransactionDigest, IntermediateTransactionResponse> =
@@ -529,55 +642,59 @@ impl ReadApiServer for ReadApi {
             }
         }
 
-        let checkpoint_seq_list = self
-                .state
-                .multi_get_transaction_checkpoint(&digests)
-                .await
-                .tap_err(
-                    |err| debug!(digests=?digests, "Failed to multi get checkpoint sequence number: {:?}", err))?;
-        for ((_digest, cache_entry), seq) in temp_response
-            .iter_mut()
-            .zip(checkpoint_seq_list.into_iter())
-        {
-            cache_entry.checkpoint_seq = seq.map(|(_, seq)| seq.into());
-        }
-
-        let unique_checkpoint_numbers = temp_response
-            .values()
-            .filter_map(|cache_entry| cache_entry.checkpoint_seq.map(<u64>::from))
-            // It's likely that many transactions have the same checkpoint, so we don't
-            // need to over-fetch
-            .unique()
-            .collect::<Vec<CheckpointSequenceNumber>>();
-
-        // fetch timestamp from the DB
-        let timestamps = self
-            .state
-            .multi_get_checkpoint_by_sequence_number(&unique_checkpoint_numbers)
-            .map_err(|e| anyhow!("{e}"))?
-            .into_iter()
-            .map(|c| c.map(|checkpoint| checkpoint.timestamp_ms));
+        if opts.show_checkpoint() || opts.show_timestamp() {
+            let checkpoint_seq_list = self
+                    .state
+                    .multi_get_transaction_checkpoint(&digests)
+                    .await
+                    .tap_err(
+                        |err| debug!(digests=?digests, "Failed to multi get checkpoint sequence number: {:?}", err))?;
+            for ((_digest, cache_entry), seq) in temp_response
+                .iter_mut()
+                .zip(checkpoint_seq_list.into_iter())
+            {
+                cache_entry.checkpoint_seq = seq.map(|(_, seq)| seq.into());
+            }
 
-        // construct a hashmap of checkpoint -> timestamp for fast lookup
-        let checkpoint_to_timestamp = unique_checkpoint_numbers
-            .into_iter()
-            .zip(timestamps)
-            .collect::<HashMap<_, _>>();
-
-        // fill cache with the timestamp
-        for (_, cache_entry) in temp_response.iter_mut() {
-            if cache_entry.checkpoint_seq.is_some() {
-                // safe to unwrap because is_some is checked
-                cache_entry.timestamp = *checkpoint_to_timestamp
-                    .get(
-                        cache_entry
-                            .checkpoint_seq
-                            .map(<u64>::from)
-                            .as_ref()
-                            .unwrap(),
-                    )
-                    // Safe to unwrap because checkpoint_seq is guaranteed to exist in checkpoint_to_timestamp
-                    .unwrap();
+            if opts.show_timestamp() {
+                let unique_checkpoint_numbers = temp_response
+                    .values()
+                    .filter_map(|cache_entry| cache_entry.checkpoint_seq.map(<u64>::from))
+                    // It's likely that many transactions have the same checkpoint, so we don't
+                    // need to over-fetch
+                    .unique()
+                    .collect::<Vec<CheckpointSequenceNumber>>();
+
+                // fetch timestamp from the DB
+                let timestamps = self
+                    .state
+                    .multi_get_checkpoint_by_sequence_number(&unique_checkpoint_numbers)
+                    .map_err(|e| anyhow!("{e}"))?
+                    .into_iter()
+                    .map(|c| c.map(|checkpoint| checkpoint.timestamp_ms));
+
+                // construct a hashmap of checkpoint -> timestamp for fast lookup
+                let checkpoint_to_timestamp = unique_checkpoint_numbers
+                    .into_iter()
+                    .zip(timestamps)
+                    .collect::<HashMap<_, _>>();
+
+                // fill cache with the timestamp
+                for (_, cache_entry) in temp_response.iter_mut() {
+                    if cache_entry.checkpoint_seq.is_some() {
+                        // safe to unwrap because is_some is checked
+                        cache_entry.timestamp = *checkpoint_to_timestamp
+                            .get(
+                                cache_entry
+                                    .checkpoint_seq
+                                    .map(<u64>::from)
+                                    .as_ref()
+                                    .unwrap(),
+                            )
+                            // Safe to unwrap because checkpoint_seq is guaranteed to exist in checkpoint_to_timestamp
+                            .unwrap();
+                    }
+                }
             }
         }
 
@@ -815,38 +932,99 @@ impl ReadApiServer for ReadApi {
         &self,
         query: SuiTransactionResponseQuery,
         // If `Some`, the query will start from the next item after the specified cursor
-        cursor: Option<TransactionDigest>,
+        cursor: Option<TransactionCursor>,
         limit: Option<usize>,
         descending_order: Option<bool>,
     ) -> RpcResult<TransactionsPage> {
         let limit = cap_page_limit(limit);
         let descending = descending_order.unwrap_or_default();
-        let opts = query.options.unwrap_or_default();
+        let opts = query.options.unwrap_or_default().resolve_fields()?;
+        let page = query_checkpointed_transactions(
+            &self.state,
+            query.filter.as_ref(),
+            cursor.map(|cursor| (cursor.checkpoint, cursor.index)),
+            limit,
+            descending,
+            QUERY_MAX_WALKED_TRANSACTIONS,
+        )?;
+        let digests = page.digests;
+        let next_cursor = page
+            .next
+            .map(|(checkpoint, index)| TransactionCursor::new(checkpoint, index));
+        let has_next_page = page.has_next_page;
+
+        let data: Vec<SuiTransactionResponse> = if opts.only_digest() {
+            digests
+                .into_iter()
+                .map(SuiTransactionResponse::new)
+                .collect()
+        } else {
+            self.multi_get_transactions_with_options(digests, Some(opts))
+                .await?
+        };
+
+        Ok(Page {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    async fn scan_transactions(
+        &self,
+        query: SuiTransactionResponseQuery,
+        // If `Some`, the query will start from the next item after the specified cursor
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<TransactionScanPage> {
+        let Some(max_checkpoints) = self.scanned_checkpoints else {
+            return Err(anyhow!(UserInputError::Unsupported(
+                "transaction scans are not enabled on this node".to_string()
+            ))
+            .into());
+        };
+        let Some(filter) = query.filter else {
+            return Err(anyhow!(UserInputError::Unsupported(
+                "transaction scans require a sender or recipient address filter".to_string()
+            ))
+            .into());
+        };
+        let limit = cap_page_limit(limit);
+        let descending = descending_order.unwrap_or_default();
+        let opts = query.options.unwrap_or_default().resolve_fields()?;
 
         // Retrieve 1 extra item for next cursor
-        let mut digests =
-            self.state
-                .get_transactions(query.filter, cursor, Some(limit + 1), descending)?;
+        let mut scan = scan_transactions(
+            &self.state,
+            &filter,
+            cursor,
+            limit + 1,
+            descending,
+            max_checkpoints,
+        )?;
 
         // extract next cursor
-        let has_next_page = digests.len() > limit;
-        digests.truncate(limit);
-        let next_cursor = digests.last().cloned().map_or(cursor, Some);
+        let has_next_page = scan.digests.len() > limit;
+        scan.digests.truncate(limit);
+        let next_cursor = scan.digests.last().cloned().map_or(cursor, Some);
 
         let data: Vec<SuiTransactionResponse> = if opts.only_digest() {
-            digests
+            scan.digests
                 .into_iter()
                 .map(SuiTransactionResponse::new)
                 .collect()
         } else {
-            self.multi_get_transactions_with_options(digests, Some(opts))
+            self.multi_get_transactions_with_options(scan.digests, Some(opts))
                 .await?
         };
 
-        Ok(Page {
+        Ok(TransactionScanPage {
             data,
             next_cursor,
             has_next_page,
+            first_checkpoint: *scan.checkpoints.start(),
+            last_checkpoint: *scan.checkpoints.end(),
         })
     }
 
@@ -862,6 +1040,22 @@ impl ReadApiServer for ReadApi {
             .into())
     }
 
+    async fn get_available_history(&self) -> RpcResult<AvailableHistory> {
+        let lowest = |kind| -> Result<SuiCheckpointSequenceNumber, Error> {
+            Ok(self
+                .state
+                .get_lowest_available_history_checkpoint(kind)?
+                .into())
+        };
+        Ok(AvailableHistory {
+            checkpoint_contents: lowest(PrunedHistory::CheckpointContents)?,
+            transactions: lowest(PrunedHistory::Transactions)?,
+            effects: lowest(PrunedHistory::Effects)?,
+            events: lowest(PrunedHistory::Events)?,
+            indexes: lowest(PrunedHistory::Indexes)?,
+        })
+    }
+
     async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
         Ok(self.get_checkpoint_internal(id)?)
     }
@@ -896,6 +1090,37 @@ impl ReadApiServer for ReadApi {
             has_next_page,
         })
     }
+    async fn get_object_state_proof(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> RpcResult<SuiObjectStateProof> {
+        Ok(self
+            .state
+            .get_object_state_proof(&object_id, version)?
+            .into())
+    }
+
+    async fn get_transaction_inclusion_proof(
+        &self,
+        digest: TransactionDigest,
+    ) -> RpcResult<SuiTransactionInclusionProof> {
+        Ok(self.state.get_transaction_inclusion_proof(&digest)?.into())
+    }
+
+    async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
+        let name_service = self.name_service()?;
+        Ok(name_service
+            .resolve_address(self.state.database.as_ref(), &name)
+            .map_err(Error::from)?)
+    }
+
+    async fn resolve_name_service_name(&self, address: SuiAddress) -> RpcResult<Option<String>> {
+        let name_service = self.name_service()?;
+        Ok(name_service
+            .resolve_name(self.state.database.as_ref(), address)
+            .map_err(Error::from)?)
+    }
 }
 
 impl SuiRpcModule for ReadApi {
@@ -1193,7 +1418,9 @@ fn convert_to_response(
         }
     }
 
-    response.checkpoint = cache.checkpoint_seq.map(<u64>::from);
+    if opts.show_checkpoint() {
+        response.checkpoint = cache.checkpoint_seq.map(<u64>::from);
+    }
     response.timestamp_ms = cache.timestamp;
 
     if opts.show_events {
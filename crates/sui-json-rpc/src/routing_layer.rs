@@ -1,9 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::metrics::BatchMetrics;
 use crate::{CLIENT_TARGET_API_VERSION_HEADER, MAX_REQUEST_SIZE};
 use hyper::{http, Body, Method, Request, Response};
 use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::core::__reexports::serde_json::value::RawValue;
 use jsonrpsee::core::error::GenericTransportError;
 use jsonrpsee::core::http_helpers::read_body;
 use jsonrpsee::types::Request as RpcRequest;
@@ -19,13 +21,22 @@ use tower::{Layer, Service};
 pub struct RoutingLayer {
     routes: HashMap<String, MethodRouting>,
     disable_routing: bool,
+    max_batch_size: usize,
+    batch_metrics: BatchMetrics,
 }
 
 impl RoutingLayer {
-    pub fn new(routes: HashMap<String, MethodRouting>, disable_routing: bool) -> Self {
+    pub fn new(
+        routes: HashMap<String, MethodRouting>,
+        disable_routing: bool,
+        max_batch_size: usize,
+        batch_metrics: BatchMetrics,
+    ) -> Self {
         Self {
             routes,
             disable_routing,
+            max_batch_size,
+            batch_metrics,
         }
     }
 }
@@ -34,7 +45,13 @@ impl<S> Layer<S> for RoutingLayer {
     type Service = RpcRoutingService<S>;
 
     fn layer(&self, inner: S) -> Self::Service {
-        RpcRoutingService::new(inner, self.routes.clone(), self.disable_routing)
+        RpcRoutingService::new(
+            inner,
+            self.routes.clone(),
+            self.disable_routing,
+            self.max_batch_size,
+            self.batch_metrics.clone(),
+        )
     }
 }
 
@@ -44,16 +61,26 @@ pub struct RpcRoutingService<S> {
     routes: HashMap<String, MethodRouting>,
     route_to_methods: HashSet<String>,
     disable_routing: bool,
+    max_batch_size: usize,
+    batch_metrics: BatchMetrics,
 }
 
 impl<S> RpcRoutingService<S> {
-    pub fn new(inner: S, routes: HashMap<String, MethodRouting>, disable_routing: bool) -> Self {
+    pub fn new(
+        inner: S,
+        routes: HashMap<String, MethodRouting>,
+        disable_routing: bool,
+        max_batch_size: usize,
+        batch_metrics: BatchMetrics,
+    ) -> Self {
         let route_to_methods = routes.values().map(|v| v.route_to.clone()).collect();
         Self {
             inner,
             routes,
             route_to_methods,
             disable_routing,
+            max_batch_size,
+            batch_metrics,
         }
     }
 }
@@ -80,6 +107,8 @@ where
         let routes = self.routes.clone();
         let route_to_methods = self.route_to_methods.clone();
         let disable_routing = self.disable_routing;
+        let max_batch_size = self.max_batch_size;
+        let batch_metrics = self.batch_metrics.clone();
         // take the service that was ready
         // https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
         let mut inner = std::mem::replace(&mut self.inner, clone);
@@ -115,13 +144,20 @@ where
                         disable_routing,
                     )
                 } else {
-                    process_batched_requests(
+                    match process_batched_requests(
                         &body,
                         &version,
                         &routes,
                         &route_to_methods,
                         disable_routing,
-                    )
+                        max_batch_size,
+                        &batch_metrics,
+                    ) {
+                        Ok(body) => body,
+                        Err(batch_size) => {
+                            return Ok(response::batch_too_large(batch_size, max_batch_size))
+                        }
+                    }
                 };
                 Request::from_parts(parts, Body::from(body))
             } else {
@@ -133,27 +169,42 @@ where
     }
 }
 
+// try to process each rpc request of the batch, returning the size of the batch as error if it
+// has more than `max_batch_size` requests. The server replies to each request of the batch on its
+// own, so that one failing request does not fail the others.
 fn process_batched_requests(
     body: &[u8],
     version: &Option<String>,
     routes: &HashMap<String, MethodRouting>,
     route_to_methods: &HashSet<String>,
     disable_routing: bool,
-) -> Vec<u8> {
-    let Ok(requests) = serde_json::from_slice::<Vec<&[u8]>>(body) else{
-        return body.to_vec();
+    max_batch_size: usize,
+    batch_metrics: &BatchMetrics,
+) -> Result<Vec<u8>, usize> {
+    let Ok(requests) = serde_json::from_slice::<Vec<&RawValue>>(body) else {
+        return Ok(body.to_vec());
     };
-    let mut processed_reqs = Vec::new();
-    for request in requests {
-        let req =
-            process_single_request(request, version, routes, route_to_methods, disable_routing);
-        processed_reqs.push(req);
+    batch_metrics.batch_size.observe(requests.len() as f64);
+    if requests.len() > max_batch_size {
+        batch_metrics.rejected_batches.inc();
+        return Err(requests.len());
     }
-    if let Ok(request) = serde_json::to_vec(&processed_reqs) {
-        request
-    } else {
-        body.to_vec()
+    let mut processed_reqs = Vec::with_capacity(body.len());
+    processed_reqs.push(b'[');
+    for (i, request) in requests.iter().enumerate() {
+        if i > 0 {
+            processed_reqs.push(b',');
+        }
+        processed_reqs.extend(process_single_request(
+            request.get().as_bytes(),
+            version,
+            routes,
+            route_to_methods,
+            disable_routing,
+        ));
     }
+    processed_reqs.push(b']');
+    Ok(processed_reqs)
 }
 
 // try to process the rpc request, return the original values if fail to parse the request.
@@ -200,11 +251,14 @@ fn process_single_request(
 }
 
 // error responses borrowed from jsonrpsee
-mod response {
+pub(crate) mod response {
     use jsonrpsee::core::__reexports::serde_json;
-    use jsonrpsee::types::error::{reject_too_big_request, ErrorCode};
+    use jsonrpsee::types::error::{reject_too_big_request, ErrorCode, ErrorObject};
     use jsonrpsee::types::{ErrorResponse, Id};
     const JSON: &str = "application/json; charset=utf-8";
+    /// Server error codes of requests rejected by the [crate::api_key_layer::ApiKeyLayer].
+    const UNAUTHORIZED_CODE: i32 = -32001;
+    const TOO_MANY_REQUESTS_CODE: i32 = -32029;
 
     pub(crate) fn too_large(limit: u32) -> hyper::Response<hyper::Body> {
         let error = serde_json::to_string(&ErrorResponse::borrowed(
@@ -215,6 +269,46 @@ mod response {
         from_template(hyper::StatusCode::PAYLOAD_TOO_LARGE, error, JSON)
     }
 
+    pub(crate) fn batch_too_large(batch_size: usize, limit: usize) -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::owned(
+                ErrorCode::InvalidRequest.code(),
+                format!("Batch of {batch_size} requests exceeds the limit of {limit} requests"),
+                None::<()>,
+            ),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+        from_template(hyper::StatusCode::PAYLOAD_TOO_LARGE, error, JSON)
+    }
+
+    pub(crate) fn unauthorized(message: String) -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::owned(UNAUTHORIZED_CODE, message, None::<()>),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+        from_template(hyper::StatusCode::UNAUTHORIZED, error, JSON)
+    }
+
+    pub(crate) fn too_many_requests(message: String) -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::owned(TOO_MANY_REQUESTS_CODE, message, None::<()>),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+        from_template(hyper::StatusCode::TOO_MANY_REQUESTS, error, JSON)
+    }
+
+    pub(crate) fn too_heavy(message: String) -> hyper::Response<hyper::Body> {
+        let error = serde_json::to_string(&ErrorResponse::borrowed(
+            ErrorObject::owned(ErrorCode::InvalidRequest.code(), message, None::<()>),
+            Id::Null,
+        ))
+        .expect("built from known-good data; qed");
+        from_template(hyper::StatusCode::PAYLOAD_TOO_LARGE, error, JSON)
+    }
+
     pub(crate) fn internal_error() -> hyper::Response<hyper::Body> {
         let error = serde_json::to_string(&ErrorResponse::borrowed(
             ErrorCode::InternalError.into(),
@@ -16,8 +16,8 @@ use sui_core::authority::AuthorityState;
 use sui_core::authority_client::NetworkAuthorityClient;
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_json_rpc_types::{
-    BigInt, DevInspectResults, DryRunTransactionResponse, SuiTransaction, SuiTransactionEvents,
-    SuiTransactionResponse, SuiTransactionResponseOptions,
+    BigInt, DevInspectResults, DryRunTransactionResponse, SuiObjectOverride, SuiTransaction,
+    SuiTransactionEvents, SuiTransactionResponse, SuiTransactionResponseOptions,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{EpochId, SuiAddress};
@@ -144,6 +144,7 @@ impl TransactionExecutionApi {
                     balance_changes,
                     timestamp_ms: None,
                     confirmed_local_execution: Some(is_executed_locally),
+                    served_from_archive: None,
                     checkpoint: None,
                     errors: vec![],
                 })
@@ -172,20 +173,32 @@ impl WriteApiServer for TransactionExecutionApi {
         tx_bytes: Base64,
         gas_price: Option<BigInt>,
         _epoch: Option<EpochId>,
+        show_trace: Option<bool>,
+        object_overrides: Option<Vec<SuiObjectOverride>>,
     ) -> RpcResult<DevInspectResults> {
         let tx_kind: TransactionKind =
             bcs::from_bytes(&tx_bytes.to_vec().map_err(|e| anyhow!(e))?).map_err(|e| anyhow!(e))?;
         Ok(self
             .state
-            .dev_inspect_transaction(sender_address, tx_kind, gas_price.map(<u64>::from))
+            .dev_inspect_transaction(
+                sender_address,
+                tx_kind,
+                gas_price.map(<u64>::from),
+                show_trace.unwrap_or_default(),
+                object_overrides.unwrap_or_default(),
+            )
             .await?)
     }
 
-    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<DryRunTransactionResponse> {
+    async fn dry_run_transaction(
+        &self,
+        tx_bytes: Base64,
+        show_trace: Option<bool>,
+    ) -> RpcResult<DryRunTransactionResponse> {
         let (txn_data, txn_digest) = get_transaction_data_and_digest(tx_bytes)?;
         Ok(self
             .state
-            .dry_exec_transaction(txn_data, txn_digest)
+            .dry_exec_transaction(txn_data, txn_digest, show_trace.unwrap_or_default())
             .await?)
     }
 }
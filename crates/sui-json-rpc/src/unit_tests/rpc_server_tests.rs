@@ -116,7 +116,7 @@ async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
     let tx = to_sender_signed_transaction(transaction_bytes.to_data()?, keystore.get_key(address)?);
     let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
     let tx_bytes1 = tx_bytes.clone();
-    let dryrun_response = http_client.dry_run_transaction(tx_bytes).await?;
+    let dryrun_response = http_client.dry_run_transaction(tx_bytes, None).await?;
 
     let tx_response: SuiTransactionResponse = http_client
         .execute_transaction(
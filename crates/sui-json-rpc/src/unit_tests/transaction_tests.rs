@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::time::Duration;
 use sui_config::SUI_KEYSTORE_FILENAME;
 use sui_json_rpc_types::SuiTransactionResponseQuery;
 use sui_json_rpc_types::{
@@ -213,6 +214,24 @@ async fn test_get_fullnode_transaction() -> Result<(), anyhow::Error> {
         }
     }
 
+    // Only checkpointed transactions are queried
+    tokio::time::timeout(Duration::from_secs(60), async {
+        for response in &tx_responses {
+            while client
+                .read_api()
+                .get_transaction_with_options(response.digest, SuiTransactionResponseOptions::new())
+                .await?
+                .checkpoint
+                .is_none()
+            {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+        Ok::<_, anyhow::Error>(())
+    })
+    .await
+    .expect("transactions were not checkpointed in time")?;
+
     // test get_recent_transactions with smaller range
     let tx = client
         .read_api()
@@ -255,10 +274,23 @@ async fn test_get_fullnode_transaction() -> Result<(), anyhow::Error> {
         .await
         .unwrap();
     assert_eq!(10, latest.data.len());
-    assert_eq!(Some(all_txs_rev[9].digest), latest.next_cursor);
     assert_eq!(all_txs_rev[0..10], latest.data);
     assert!(latest.has_next_page);
 
+    // test the cursor resumes after the last transaction of the page
+    let previous = client
+        .read_api()
+        .query_transactions(
+            SuiTransactionResponseQuery::default(),
+            latest.next_cursor,
+            Some(10),
+            true,
+        )
+        .await
+        .unwrap();
+    assert_eq!(all_txs_rev[10..20], previous.data);
+    assert!(previous.next_cursor < latest.next_cursor);
+
     // test get from address txs in ascending order
     let address_txs_asc = client
         .read_api()
@@ -4,17 +4,23 @@
 use async_trait::async_trait;
 use hyper::header::HeaderValue;
 use hyper::HeaderMap;
+use jsonrpsee::core::__reexports::serde_json;
+use jsonrpsee::core::__reexports::serde_json::json;
 use jsonrpsee::core::client::ClientT;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::{JsonRawValue, RpcResult};
 use jsonrpsee::http_client::HttpClientBuilder;
 use jsonrpsee::rpc_params;
+use jsonrpsee::types::{ErrorResponse, Id, Request, Response};
 use jsonrpsee::RpcModule;
 use jsonrpsee_proc_macros::rpc;
 use prometheus::Registry;
+use reqwest::Client;
 use std::env;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use sui_config::utils::get_available_port;
 use sui_json_rpc::{JsonRpcServerBuilder, SuiRpcModule, CLIENT_TARGET_API_VERSION_HEADER};
+use sui_json_rpc_types::{ClientWarningKind, RpcCapabilities};
 use sui_open_rpc::Module;
 use sui_open_rpc_macros::open_rpc;
 
@@ -100,6 +106,51 @@ async fn test_rpc_backward_compatibility() {
     handle.stop().unwrap()
 }
 
+#[tokio::test]
+async fn test_rpc_capabilities() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", port);
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    // Without a client version, there is nothing to warn about
+    let capabilities: RpcCapabilities = client
+        .request("rpc.capabilities", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!("1.5", capabilities.api_version);
+    assert!(capabilities.methods.contains(&"test_foo".to_string()));
+    assert!(capabilities.client_warnings.is_empty());
+
+    let capabilities: RpcCapabilities = client
+        .request("rpc.capabilities", rpc_params!["1.5"])
+        .await
+        .unwrap();
+    assert!(capabilities.client_warnings.is_empty());
+
+    // The client targets a version newer than the server's
+    let capabilities: RpcCapabilities = client
+        .request("rpc.capabilities", rpc_params!["1.6"])
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![ClientWarningKind::StaleServer],
+        capabilities
+            .client_warnings
+            .iter()
+            .map(|w| w.kind)
+            .collect::<Vec<_>>()
+    );
+
+    handle.stop().unwrap()
+}
+
 #[tokio::test]
 async fn test_disable_routing() {
     env::set_var("DISABLE_BACKWARD_COMPATIBILITY", "true");
@@ -141,72 +192,104 @@ async fn test_disable_routing() {
     handle.stop().unwrap()
 }
 
-// TODO(chris): clean up this after March 27th, 2023
-// #[tokio::test]
-// async fn test_rpc_backward_compatibility_batched_request() {
-//     let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
-//     builder.register_module(TestApiModule).unwrap();
-
-//     let port = get_available_port("0.0.0.0");
-//     let handle = builder
-//         .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
-//         .await
-//         .unwrap();
-//     let url = format!("http://0.0.0.0:{}", port);
-
-//     // Test with un-versioned client
-//     let client = HttpClientBuilder::default().build(&url).unwrap();
-
-//     let mut builder = BatchRequestBuilder::default();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-
-//     let response = client.batch_request::<String>(builder).await.unwrap();
-//     assert_eq!(3, response.num_successful_calls());
-
-//     // try to access old method directly should fail
-//     let mut builder = BatchRequestBuilder::default();
-//     builder.insert("test_foo_1_5", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-//     builder.insert("test_foo", rpc_params!(true)).unwrap();
-
-//     let response = client.batch_request::<String>(builder).await.unwrap();
-//     assert_eq!(2, response.num_successful_calls());
-
-//     // One malformed request shouldn't fail the whole batch
-//     let client = Client::new();
-//     let response = client
-//         .post(format!("http://127.0.0.1:{}/", port))
-//         .json(&vec![
-//             json!(&Request {
-//                 jsonrpc: Default::default(),
-//                 id: Id::Number(1),
-//                 method: "test_foo".into(),
-//                 params: Some(&JsonRawValue::from_string("[true]".into()).unwrap()),
-//             }),
-//             json!("Bad json input"),
-//         ])
-//         .send()
-//         .await
-//         .unwrap();
-
-//     let responses = response.text().await.unwrap();
-//     let responses: Vec<&JsonRawValue> = serde_json::from_str(&responses).unwrap();
-
-//     // Should have 2 results
-//     assert_eq!(2, responses.len());
-
-//     // First response should success
-//     let response = serde_json::from_str::<Response<String>>(responses[0].get());
-//     assert!(matches!(response, Ok(result) if result.result == "Some string"));
-
-//     // Second response should fail
-//     let response = serde_json::from_str::<ErrorResponse>(responses[1].get());
-//     assert!(matches!(response, Ok(result) if result.error_object().message() == "Invalid request"));
-
-//     handle.stop().unwrap()
-// }
+#[tokio::test]
+async fn test_rpc_backward_compatibility_batched_request() {
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", port);
+
+    // Test with un-versioned client
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    let mut builder = BatchRequestBuilder::default();
+    builder.insert("test_foo", rpc_params!(true)).unwrap();
+    builder.insert("test_foo", rpc_params!(true)).unwrap();
+    builder.insert("test_foo", rpc_params!(true)).unwrap();
+
+    let response = client.batch_request::<String>(builder).await.unwrap();
+    assert_eq!(3, response.num_successful_calls());
+
+    // try to access old method directly should fail
+    let mut builder = BatchRequestBuilder::default();
+    builder.insert("test_foo_1_5", rpc_params!(true)).unwrap();
+    builder.insert("test_foo", rpc_params!(true)).unwrap();
+    builder.insert("test_foo", rpc_params!(true)).unwrap();
+
+    let response = client.batch_request::<String>(builder).await.unwrap();
+    assert_eq!(2, response.num_successful_calls());
+
+    // One malformed request shouldn't fail the whole batch
+    let client = Client::new();
+    let response = client
+        .post(format!("http://127.0.0.1:{}/", port))
+        .json(&vec![
+            json!(&Request {
+                jsonrpc: Default::default(),
+                id: Id::Number(1),
+                method: "test_foo".into(),
+                params: Some(&JsonRawValue::from_string("[true]".into()).unwrap()),
+            }),
+            json!("Bad json input"),
+        ])
+        .send()
+        .await
+        .unwrap();
+
+    let responses = response.text().await.unwrap();
+    let responses: Vec<&JsonRawValue> = serde_json::from_str(&responses).unwrap();
+
+    // Should have 2 results
+    assert_eq!(2, responses.len());
+
+    // First response should success
+    let response = serde_json::from_str::<Response<String>>(responses[0].get());
+    assert!(matches!(response, Ok(result) if result.result == "Some string"));
+
+    // Second response should fail
+    let response = serde_json::from_str::<ErrorResponse>(responses[1].get());
+    assert!(matches!(response, Ok(result) if result.error_object().message() == "Invalid request"));
+
+    handle.stop().unwrap()
+}
+
+#[tokio::test]
+async fn test_batch_request_limit() {
+    env::set_var("RPC_MAX_BATCH_SIZE", "3");
+
+    let mut builder = JsonRpcServerBuilder::new("1.5", &Registry::new());
+    builder.register_module(TestApiModule).unwrap();
+
+    let port = get_available_port("0.0.0.0");
+    let handle = builder
+        .start(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port)))
+        .await
+        .unwrap();
+    let url = format!("http://0.0.0.0:{}", port);
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+
+    let mut builder = BatchRequestBuilder::default();
+    for _ in 0..3 {
+        builder.insert("test_foo", rpc_params!(true)).unwrap();
+    }
+    let response = client.batch_request::<String>(builder).await.unwrap();
+    assert_eq!(3, response.num_successful_calls());
+
+    // Batches larger than the limit are rejected as a whole
+    let mut builder = BatchRequestBuilder::default();
+    for _ in 0..4 {
+        builder.insert("test_foo", rpc_params!(true)).unwrap();
+    }
+    let response = client.batch_request::<String>(builder).await;
+    assert!(response.is_err());
+
+    handle.stop().unwrap()
+}
 
 #[open_rpc(namespace = "test")]
 #[rpc(server, client, namespace = "test")]
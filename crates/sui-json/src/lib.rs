@@ -774,14 +774,18 @@ fn resolve_call_arg(
 fn resolve_call_args(
     view: &BinaryIndexedView,
     type_args: &[TypeTag],
-    json_args: &[SuiJsonValue],
+    json_args: &[Option<SuiJsonValue>],
     parameter_types: &[SignatureToken],
-) -> Result<Vec<ResolvedCallArg>, anyhow::Error> {
+) -> Result<Vec<Option<ResolvedCallArg>>, anyhow::Error> {
     json_args
         .iter()
         .zip(parameter_types)
         .enumerate()
-        .map(|(idx, (arg, param))| resolve_call_arg(view, type_args, idx, arg, param))
+        .map(|(idx, (arg, param))| {
+            arg.as_ref()
+                .map(|arg| resolve_call_arg(view, type_args, idx, arg, param))
+                .transpose()
+        })
         .collect()
 }
 
@@ -795,6 +799,31 @@ pub fn resolve_move_function_args(
     combined_args_json: Vec<SuiJsonValue>,
     allow_arbitrary_function_call: bool,
 ) -> Result<Vec<(ResolvedCallArg, SignatureToken)>, anyhow::Error> {
+    let call_args = resolve_partial_move_function_args(
+        package,
+        module_ident,
+        function,
+        type_args,
+        combined_args_json.into_iter().map(Some).collect(),
+        allow_arbitrary_function_call,
+    )?;
+    Ok(call_args
+        .into_iter()
+        .map(|(arg, expected_type)| (arg.expect("every argument is a JSON value"), expected_type))
+        .collect())
+}
+
+/// Like `resolve_move_function_args`, for calls where some of the arguments are not JSON values,
+/// e.g. the results of earlier commands of a programmable transaction. Those arguments are `None`,
+/// and are only checked for arity; their expected types are still returned.
+pub fn resolve_partial_move_function_args(
+    package: &MovePackage,
+    module_ident: Identifier,
+    function: Identifier,
+    type_args: &[TypeTag],
+    combined_args_json: Vec<Option<SuiJsonValue>>,
+    allow_arbitrary_function_call: bool,
+) -> Result<Vec<(Option<ResolvedCallArg>, SignatureToken)>, anyhow::Error> {
     // Extract the expected function signature
     let module = package.deserialize_module(&module_ident, VERSION_MAX)?;
     let function_str = function.as_ident_str();
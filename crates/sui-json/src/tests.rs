@@ -5,6 +5,7 @@ use std::path::Path;
 use std::str::FromStr;
 
 use fastcrypto::encoding::{Encoding, Hex};
+use move_binary_format::file_format::SignatureToken;
 use move_core_types::language_storage::StructTag;
 use move_core_types::u256::U256;
 use move_core_types::value::{MoveFieldLayout, MoveStructLayout};
@@ -25,7 +26,7 @@ use sui_types::object::Object;
 use sui_types::MOVE_STDLIB_ADDRESS;
 
 use super::{check_valid_homogeneous, HEX_PREFIX};
-use super::{resolve_move_function_args, SuiJsonValue};
+use super::{resolve_move_function_args, resolve_partial_move_function_args, SuiJsonValue};
 
 // Negative test cases
 #[test]
@@ -689,6 +690,64 @@ fn test_basic_args_linter_top_level() {
     }
 }
 
+#[test]
+fn test_partial_move_function_args() {
+    let path =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../sui_programmability/examples/basics");
+    let compiled_modules = BuildConfig::new_for_testing()
+        .build(path)
+        .unwrap()
+        .into_modules();
+    let example_package = Object::new_package_for_testing(
+        compiled_modules,
+        TransactionDigest::genesis(),
+        &make_system_packages(),
+    )
+    .unwrap();
+    let example_package = example_package.data.try_as_package().unwrap();
+
+    let module = Identifier::new("object_basics").unwrap();
+    let function = Identifier::new("transfer").unwrap();
+
+    /*
+    Function signature:
+            public fun transfer(o: Object, recipient: vector<u8>, _ctx: &mut TxContext)
+    */
+    let address = SuiAddress::random_for_testing_only();
+    let addr = SuiJsonValue::new(json!(format!("0x{:02x}", address))).unwrap();
+
+    // The object is the result of an earlier command, only the recipient is resolved.
+    let args = resolve_partial_move_function_args(
+        example_package,
+        module.clone(),
+        function.clone(),
+        &[],
+        vec![None, Some(addr)],
+        /* allow_arbitrary_function_call */ false,
+    )
+    .unwrap();
+
+    assert_eq!(args[0].0, None);
+    assert!(matches!(args[0].1, SignatureToken::Struct(_)));
+    assert_eq!(
+        args[1].0,
+        Some(ResolvedCallArg::Pure(
+            bcs::to_bytes(&AccountAddress::from(address)).unwrap()
+        ))
+    );
+
+    // Arguments that are not resolved still count towards the arity of the function.
+    assert!(resolve_partial_move_function_args(
+        example_package,
+        module,
+        function,
+        &[],
+        vec![None],
+        /* allow_arbitrary_function_call */ false,
+    )
+    .is_err());
+}
+
 #[test]
 fn test_convert_address_from_bcs() {
     let bcs_bytes = [
@@ -14,20 +14,23 @@ use std::fs;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
     enum_dispatch, get_key_pair_from_rng, EncodeDecodeBase64, PublicKey, Signature,
-    SignatureScheme, SuiKeyPair,
+    SignatureScheme, SuiKeyPair, SuiSignature,
 };
 
-use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
+use crate::key_derive::{derive_key_pair_from_path, generate_new_key, validate_path};
+use crate::ledger::LedgerDevice;
 
 #[derive(Serialize, Deserialize)]
 #[enum_dispatch(AccountKeystore)]
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Ledger(LedgerKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -91,6 +94,11 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Ledger(ledger) => {
+                writeln!(writer, "Keystore Type : Ledger")?;
+                write!(writer, "Keystore Path : {:?}", ledger.path)?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -283,3 +291,204 @@ impl InMemKeystore {
         Self { keys }
     }
 }
+
+/// Keystore of accounts whose keys are held by a Ledger device. Only the derivation paths and
+/// public keys of the accounts are stored, every signature is made by the device.
+#[derive(Default)]
+pub struct LedgerKeystore {
+    accounts: BTreeMap<SuiAddress, LedgerAccount>,
+    path: Option<PathBuf>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerAccount {
+    derivation_path: String,
+    public_key: PublicKey,
+}
+
+impl LedgerAccount {
+    fn scheme(&self) -> SignatureScheme {
+        match self.public_key {
+            PublicKey::Ed25519(_) => SignatureScheme::ED25519,
+            PublicKey::Secp256k1(_) => SignatureScheme::Secp256k1,
+            PublicKey::Secp256r1(_) => SignatureScheme::Secp256r1,
+        }
+    }
+}
+
+impl Serialize for LedgerKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(
+            self.path
+                .as_ref()
+                .unwrap_or(&PathBuf::default())
+                .to_str()
+                .unwrap_or(""),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for LedgerKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        LedgerKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for LedgerKeystore {
+    fn sign_hashed(
+        &self,
+        _address: &SuiAddress,
+        _msg: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        Err(signature::Error::from_source(
+            "Ledger devices only sign intent messages they can show, not hashed messages",
+        ))
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let account = self.accounts.get(address).ok_or_else(|| {
+            signature::Error::from_source(format!("Cannot find key for address: [{address}]"))
+        })?;
+        let path = DerivationPath::from_str(&account.derivation_path)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        let intent_msg = IntentMessage::new(intent, msg);
+        let bytes =
+            bcs::to_bytes(&intent_msg).map_err(|e| signature::Error::from_source(e.to_string()))?;
+
+        let signature = LedgerDevice::connect()
+            .and_then(|device| device.sign(account.scheme(), &path, &bytes))
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        // Make sure the device signed with the key of the account.
+        signature
+            .verify_secure(&intent_msg, *address)
+            .map_err(|e| {
+                signature::Error::from_source(format!(
+                    "Invalid signature from the Ledger device: {e}"
+                ))
+            })?;
+        Ok(signature)
+    }
+
+    fn add_key(&mut self, _keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        Err(anyhow!(
+            "Private keys cannot be added to a Ledger keystore, its keys are derived by the device"
+        ))
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.accounts
+            .values()
+            .map(|account| account.public_key.clone())
+            .collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        Err(anyhow!(
+            "The private key of address [{address}] never leaves the Ledger device"
+        ))
+    }
+
+    /// Adds the account of the key derived by the device at `derivation_path`, there is no
+    /// recovery phrase to return as it is the one of the device.
+    fn generate_and_add_new_key(
+        &mut self,
+        key_scheme: SignatureScheme,
+        derivation_path: Option<DerivationPath>,
+    ) -> Result<(SuiAddress, String, SignatureScheme), anyhow::Error> {
+        let address = self.add_account(key_scheme, derivation_path)?;
+        Ok((address, String::new(), key_scheme))
+    }
+
+    fn import_from_mnemonic(
+        &mut self,
+        _phrase: &str,
+        _key_scheme: SignatureScheme,
+        _derivation_path: Option<DerivationPath>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        Err(anyhow!(
+            "Ledger keystores only hold keys derived by the device from its own recovery phrase"
+        ))
+    }
+}
+
+impl LedgerKeystore {
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let accounts = if path.exists() {
+            let reader = BufReader::new(
+                File::open(path)
+                    .map_err(|e| anyhow!("Can't open LedgerKeystore from {:?}: {e}", path))?,
+            );
+            let accounts: Vec<LedgerAccount> = serde_json::from_reader(reader)
+                .map_err(|e| anyhow!("Can't deserialize LedgerKeystore from {:?}: {e}", path))?;
+            accounts
+                .into_iter()
+                .map(|account| ((&account.public_key).into(), account))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            accounts,
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    /// Adds the account of the key derived by the connected device at `derivation_path`, or at
+    /// the default path of `key_scheme`.
+    pub fn add_account(
+        &mut self,
+        key_scheme: SignatureScheme,
+        derivation_path: Option<DerivationPath>,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        let path = validate_path(&key_scheme, derivation_path)?;
+        let public_key = LedgerDevice::connect()?.get_public_key(key_scheme, &path)?;
+        let address = (&public_key).into();
+        self.accounts.insert(
+            address,
+            LedgerAccount {
+                derivation_path: path.to_string(),
+                public_key,
+            },
+        );
+        self.save()?;
+        Ok(address)
+    }
+
+    /// Derivation path of the key of `address` on the device.
+    pub fn derivation_path(&self, address: &SuiAddress) -> Option<&str> {
+        self.accounts
+            .get(address)
+            .map(|account| account.derivation_path.as_str())
+    }
+
+    pub fn set_path(&mut self, path: &Path) {
+        self.path = Some(path.to_path_buf());
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        if let Some(path) = &self.path {
+            let store =
+                serde_json::to_string_pretty(&self.accounts.values().collect::<Vec<_>>()).unwrap();
+            fs::write(path, store)?
+        }
+        Ok(())
+    }
+}
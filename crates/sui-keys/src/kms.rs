@@ -0,0 +1,217 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing with asymmetric keys of AWS KMS.
+//!
+//! The key must be an `ECC_SECG_P256K1` or `ECC_NIST_P256` key with the `SIGN_VERIFY` usage. KMS
+//! is called over its JSON API, with requests signed by AWS Signature Version 4 ([aws_sigv4]) from
+//! credentials read from the usual `AWS_*` environment variables.
+
+use std::env;
+use std::time::SystemTime;
+
+use anyhow::{anyhow, bail, ensure};
+use async_trait::async_trait;
+use aws_sigv4::http_request::{sign, SignableRequest, SigningParams, SigningSettings};
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use sui_types::crypto::{PublicKey, Signature, SignatureScheme};
+
+use crate::signer::{ec_public_key, ecdsa_der_signature, Signer};
+
+const SERVICE: &str = "kms";
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// Credentials of the AWS account of the key.
+#[derive(Clone, Debug)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+/// Where and as whom to call KMS.
+#[derive(Clone, Debug)]
+pub struct AwsKmsConfig {
+    pub region: String,
+    pub credentials: AwsCredentials,
+    /// The KMS endpoint, `https://kms.<region>.amazonaws.com` unless set.
+    pub endpoint: Option<String>,
+}
+
+impl AwsKmsConfig {
+    /// The configuration from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, the optional
+    /// `AWS_SESSION_TOKEN`, `AWS_REGION` or `AWS_DEFAULT_REGION`, and the optional
+    /// `AWS_ENDPOINT_URL`.
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+        let required = |name: &str| var(name).ok_or_else(|| anyhow!("{name} is not set"));
+        Ok(Self {
+            region: var("AWS_REGION")
+                .or_else(|| var("AWS_DEFAULT_REGION"))
+                .ok_or_else(|| anyhow!("Neither AWS_REGION nor AWS_DEFAULT_REGION is set"))?,
+            credentials: AwsCredentials {
+                access_key_id: required("AWS_ACCESS_KEY_ID")?,
+                secret_access_key: required("AWS_SECRET_ACCESS_KEY")?,
+                session_token: var("AWS_SESSION_TOKEN"),
+            },
+            endpoint: var("AWS_ENDPOINT_URL"),
+        })
+    }
+}
+
+/// A [Signer] with a key of AWS KMS, whose private key never leaves KMS.
+pub struct AwsKmsSigner {
+    client: reqwest::Client,
+    config: AwsKmsConfig,
+    endpoint: reqwest::Url,
+    key_id: String,
+    public_key: PublicKey,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetPublicKeyResponse {
+    public_key: String,
+    key_spec: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignResponse {
+    signature: String,
+}
+
+impl AwsKmsSigner {
+    /// Connects to the KMS key `key_id`, an ID, ARN or alias of the key, and reads its public key.
+    pub async fn connect(
+        key_id: impl Into<String>,
+        config: AwsKmsConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let key_id = key_id.into();
+        let endpoint = config
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://{SERVICE}.{}.amazonaws.com", config.region));
+        let endpoint = reqwest::Url::parse(&endpoint)
+            .map_err(|e| anyhow!("Invalid KMS endpoint {endpoint}: {e}"))?;
+        let client = reqwest::Client::new();
+
+        let response: GetPublicKeyResponse = call(
+            &client,
+            &config,
+            &endpoint,
+            "GetPublicKey",
+            json!({ "KeyId": key_id }),
+        )
+        .await?;
+        let scheme = match response.key_spec.as_str() {
+            "ECC_SECG_P256K1" => SignatureScheme::Secp256k1,
+            "ECC_NIST_P256" => SignatureScheme::Secp256r1,
+            spec => bail!(
+                "KMS key {key_id} is a {spec} key, only ECC_SECG_P256K1 and ECC_NIST_P256 keys \
+                 can sign Sui transactions"
+            ),
+        };
+        let public_key = ec_public_key(scheme, &decode_base64(&response.public_key)?)?;
+        Ok(Self {
+            client,
+            config,
+            endpoint,
+            key_id,
+            public_key,
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for AwsKmsSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    async fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature, anyhow::Error> {
+        // KMS hashes the message with SHA-256 before signing, as Sui does on verification.
+        let response: SignResponse = call(
+            &self.client,
+            &self.config,
+            &self.endpoint,
+            "Sign",
+            json!({
+                "KeyId": self.key_id,
+                "Message": Base64::encode(digest),
+                "MessageType": "RAW",
+                "SigningAlgorithm": "ECDSA_SHA_256",
+            }),
+        )
+        .await?;
+        ecdsa_der_signature(&self.public_key, &decode_base64(&response.signature)?)
+    }
+}
+
+/// Calls the KMS action `action` with `request`.
+async fn call<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    config: &AwsKmsConfig,
+    endpoint: &reqwest::Url,
+    action: &str,
+    request: serde_json::Value,
+) -> Result<T, anyhow::Error> {
+    let request = signed_request(
+        config,
+        endpoint.as_str(),
+        action,
+        serde_json::to_vec(&request)?,
+        SystemTime::now(),
+    )?;
+    let response = client
+        .execute(reqwest::Request::try_from(request)?)
+        .await
+        .map_err(|e| anyhow!("Can't reach KMS at {endpoint}: {e}"))?;
+    let status = response.status();
+    let body = response.bytes().await?;
+    ensure!(
+        status.is_success(),
+        "KMS {action} failed ({status}): {}",
+        String::from_utf8_lossy(&body)
+    );
+    serde_json::from_slice(&body).map_err(|e| anyhow!("Invalid KMS {action} response: {e}"))
+}
+
+/// The request of the KMS action `action` with the JSON `payload` to `endpoint`, signed with AWS
+/// Signature Version 4 at `time`.
+pub fn signed_request(
+    config: &AwsKmsConfig,
+    endpoint: &str,
+    action: &str,
+    payload: Vec<u8>,
+    time: SystemTime,
+) -> Result<http::Request<Vec<u8>>, anyhow::Error> {
+    let mut request = http::Request::post(endpoint)
+        .header("content-type", CONTENT_TYPE)
+        .header("x-amz-target", format!("TrentService.{action}"))
+        .body(payload)
+        .map_err(|e| anyhow!("Invalid KMS endpoint {endpoint}: {e}"))?;
+    let mut params = SigningParams::builder()
+        .access_key(&config.credentials.access_key_id)
+        .secret_key(&config.credentials.secret_access_key)
+        .region(&config.region)
+        .service_name(SERVICE)
+        .time(time)
+        .settings(SigningSettings::default());
+    params.set_security_token(config.credentials.session_token.as_deref());
+    let params = params
+        .build()
+        .map_err(|e| anyhow!("Can't sign the KMS request: {e}"))?;
+    let (instructions, _) = sign(SignableRequest::from(&request), &params)
+        .map_err(|e| anyhow!("Can't sign the KMS request: {e}"))?
+        .into_parts();
+    instructions.apply_to_request(&mut request);
+    Ok(request)
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, anyhow::Error> {
+    Base64::decode(value).map_err(|e| anyhow!("Invalid base64 from KMS {value}: {e}"))
+}
@@ -0,0 +1,303 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing with keys held by a Ledger hardware wallet running the Sui app.
+//!
+//! Commands are APDUs exchanged with the device over USB HID, split into 64 byte reports. The
+//! device derives its keys from the BIP32 path of every command, so that private keys never leave
+//! it: it returns public keys, and signs the intent messages it is sent once the user approved
+//! them on the device.
+//!
+//! Devices are only reached through the hidraw interface of Linux, connecting to a device fails
+//! on other platforms.
+
+#[cfg(target_os = "linux")]
+use std::fs::{self, File, OpenOptions};
+#[cfg(target_os = "linux")]
+use std::io::{Read, Write};
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, ensure};
+use bip32::DerivationPath;
+use fastcrypto::traits::ToFromBytes;
+use sui_types::crypto::{PublicKey, Signature, SignatureScheme};
+
+/// Vendor ID of the USB devices of Ledger.
+pub const LEDGER_VENDOR_ID: u16 = 0x2c97;
+
+const HID_PACKET_SIZE: usize = 64;
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+
+const CLA: u8 = 0x00;
+const INS_GET_PUBLIC_KEY: u8 = 0x02;
+const INS_SIGN: u8 = 0x03;
+const P1_FIRST: u8 = 0x00;
+const P1_MORE: u8 = 0x01;
+const P2_LAST: u8 = 0x00;
+const P2_MORE: u8 = 0x80;
+const MAX_APDU_DATA: usize = 255;
+
+const SW_OK: u16 = 0x9000;
+const SW_USER_REJECTED: u16 = 0x6985;
+const SW_APP_NOT_OPEN: u16 = 0x6e00;
+const SW_LOCKED: u16 = 0x5515;
+
+/// Sends a command APDU to the device and returns its response, including the trailing status
+/// word.
+pub trait LedgerTransport {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error>;
+}
+
+/// Transport over the hidraw interface of a Ledger device connected by USB.
+pub struct HidTransport {
+    #[cfg(target_os = "linux")]
+    device: File,
+}
+
+impl HidTransport {
+    /// Opens the first Ledger device found among the hidraw devices of the system.
+    #[cfg(target_os = "linux")]
+    pub fn open() -> Result<Self, anyhow::Error> {
+        let path = find_hidraw_device()?;
+        let device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .map_err(|e| anyhow!("Can't open Ledger device {path:?}: {e}"))?;
+        Ok(Self { device })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open() -> Result<Self, anyhow::Error> {
+        bail!(
+            "Ledger devices are only supported on Linux, not on {}",
+            std::env::consts::OS
+        )
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl LedgerTransport for HidTransport {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let mut device = &self.device;
+        for packet in hid_packets(apdu) {
+            // hidraw expects reports to be prefixed with their ID, which is always 0.
+            let mut report = [0u8; HID_PACKET_SIZE + 1];
+            report[1..].copy_from_slice(&packet);
+            device.write_all(&report)?;
+        }
+
+        let mut reader = HidResponseReader::default();
+        loop {
+            let mut packet = [0u8; HID_PACKET_SIZE];
+            let read = device.read(&mut packet)?;
+            ensure!(read > 0, "Ledger device disconnected");
+            if let Some(response) = reader.push(&packet[..read])? {
+                return Ok(response);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl LedgerTransport for HidTransport {
+    fn exchange(&self, _apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        bail!("Ledger devices are only supported on Linux")
+    }
+}
+
+/// Finds the hidraw device of the first Ledger device, preferring its first interface which is
+/// the one accepting APDUs.
+#[cfg(target_os = "linux")]
+fn find_hidraw_device() -> Result<PathBuf, anyhow::Error> {
+    let mut candidates = vec![];
+    let entries =
+        fs::read_dir("/sys/class/hidraw").map_err(|e| anyhow!("Can't list HID devices: {e}"))?;
+    for entry in entries.flatten() {
+        let Ok(uevent) = fs::read_to_string(entry.path().join("device/uevent")) else {
+            continue;
+        };
+        let is_ledger = uevent.lines().any(|line| {
+            line.strip_prefix("HID_ID=")
+                .and_then(|id| id.split(':').nth(1))
+                .and_then(|vendor| u32::from_str_radix(vendor, 16).ok())
+                == Some(LEDGER_VENDOR_ID as u32)
+        });
+        if is_ledger {
+            let first_interface = uevent
+                .lines()
+                .any(|line| line.starts_with("HID_PHYS=") && line.ends_with("input0"));
+            candidates.push((
+                !first_interface,
+                PathBuf::from("/dev").join(entry.file_name()),
+            ));
+        }
+    }
+    candidates.sort();
+    candidates
+        .into_iter()
+        .next()
+        .map(|(_, path)| path)
+        .ok_or_else(|| anyhow!("No Ledger device found, is it connected and unlocked?"))
+}
+
+/// Splits a command APDU into HID reports, the first of which carries its length.
+pub fn hid_packets(apdu: &[u8]) -> Vec<[u8; HID_PACKET_SIZE]> {
+    let mut data = (apdu.len() as u16).to_be_bytes().to_vec();
+    data.extend_from_slice(apdu);
+
+    let chunk_size = HID_PACKET_SIZE - 5;
+    data.chunks(chunk_size)
+        .enumerate()
+        .map(|(sequence, chunk)| {
+            let mut packet = [0u8; HID_PACKET_SIZE];
+            packet[0..2].copy_from_slice(&HID_CHANNEL.to_be_bytes());
+            packet[2] = HID_TAG_APDU;
+            packet[3..5].copy_from_slice(&(sequence as u16).to_be_bytes());
+            packet[5..5 + chunk.len()].copy_from_slice(chunk);
+            packet
+        })
+        .collect()
+}
+
+/// Reassembles a response APDU from the HID reports of the device.
+#[derive(Default)]
+pub struct HidResponseReader {
+    length: Option<usize>,
+    sequence: u16,
+    data: Vec<u8>,
+}
+
+impl HidResponseReader {
+    /// Adds the next report, returning the response once all its reports were read.
+    pub fn push(&mut self, packet: &[u8]) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        ensure!(packet.len() >= 5, "Ledger response report is too short");
+        ensure!(
+            u16::from_be_bytes([packet[0], packet[1]]) == HID_CHANNEL && packet[2] == HID_TAG_APDU,
+            "Unexpected Ledger response report header"
+        );
+        let sequence = u16::from_be_bytes([packet[3], packet[4]]);
+        ensure!(
+            sequence == self.sequence,
+            "Ledger response report out of sequence: expected {}, got {sequence}",
+            self.sequence
+        );
+        self.sequence += 1;
+
+        let mut payload = &packet[5..];
+        if self.length.is_none() {
+            ensure!(payload.len() >= 2, "Ledger response report is too short");
+            self.length = Some(u16::from_be_bytes([payload[0], payload[1]]) as usize);
+            payload = &payload[2..];
+        }
+        let length = self.length.unwrap_or_default();
+        let remaining = length - self.data.len();
+        self.data
+            .extend_from_slice(&payload[..remaining.min(payload.len())]);
+        Ok((self.data.len() == length).then(|| std::mem::take(&mut self.data)))
+    }
+}
+
+/// Sui app of a Ledger device.
+pub struct LedgerDevice<T = HidTransport> {
+    transport: T,
+}
+
+impl LedgerDevice {
+    /// Connects to the first Ledger device connected by USB.
+    pub fn connect() -> Result<Self, anyhow::Error> {
+        Ok(Self::new(HidTransport::open()?))
+    }
+}
+
+impl<T: LedgerTransport> LedgerDevice<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    /// The public key derived by the device at `path`.
+    pub fn get_public_key(
+        &self,
+        scheme: SignatureScheme,
+        path: &DerivationPath,
+    ) -> Result<PublicKey, anyhow::Error> {
+        let data = key_path_data(scheme, path)?;
+        let response = self.send(INS_GET_PUBLIC_KEY, P1_FIRST, P2_LAST, &data)?;
+        let (length, key) = response
+            .split_first()
+            .ok_or_else(|| anyhow!("Empty public key response from the Ledger device"))?;
+        ensure!(
+            key.len() >= *length as usize,
+            "Truncated public key response from the Ledger device"
+        );
+        PublicKey::try_from_bytes(scheme, &key[..*length as usize])
+            .map_err(|e| anyhow!("Invalid public key from the Ledger device: {e}"))
+    }
+
+    /// Signs the BCS bytes of an intent message with the key derived at `path`, once the user
+    /// approved it on the device.
+    pub fn sign(
+        &self,
+        scheme: SignatureScheme,
+        path: &DerivationPath,
+        intent_msg: &[u8],
+    ) -> Result<Signature, anyhow::Error> {
+        let public_key = self.get_public_key(scheme, path)?;
+
+        let mut data = key_path_data(scheme, path)?;
+        data.extend_from_slice(&(intent_msg.len() as u32).to_be_bytes());
+        data.extend_from_slice(intent_msg);
+
+        let chunks: Vec<_> = data.chunks(MAX_APDU_DATA).collect();
+        let mut response = vec![];
+        for (i, chunk) in chunks.iter().enumerate() {
+            let p1 = if i == 0 { P1_FIRST } else { P1_MORE };
+            let p2 = if i + 1 == chunks.len() {
+                P2_LAST
+            } else {
+                P2_MORE
+            };
+            response = self.send(INS_SIGN, p1, p2, chunk)?;
+        }
+
+        let signature = [&[scheme.flag()], &response[..], public_key.as_ref()].concat();
+        Signature::from_bytes(&signature)
+            .map_err(|e| anyhow!("Invalid signature from the Ledger device: {e}"))
+    }
+
+    fn send(&self, ins: u8, p1: u8, p2: u8, data: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let mut apdu = vec![CLA, ins, p1, p2, data.len() as u8];
+        apdu.extend_from_slice(data);
+        let mut response = self.transport.exchange(&apdu)?;
+        ensure!(
+            response.len() >= 2,
+            "Ledger response is missing its status word"
+        );
+        let status = response.split_off(response.len() - 2);
+        match u16::from_be_bytes([status[0], status[1]]) {
+            SW_OK => Ok(response),
+            SW_USER_REJECTED => bail!("Request rejected on the Ledger device"),
+            SW_APP_NOT_OPEN => bail!("The Sui app is not open on the Ledger device"),
+            SW_LOCKED => bail!("The Ledger device is locked"),
+            status => bail!("Ledger device returned error status {status:#06x}"),
+        }
+    }
+}
+
+/// Scheme flag and BIP32 path of the key of a command.
+fn key_path_data(scheme: SignatureScheme, path: &DerivationPath) -> Result<Vec<u8>, anyhow::Error> {
+    if !matches!(
+        scheme,
+        SignatureScheme::ED25519 | SignatureScheme::Secp256k1
+    ) {
+        bail!("Ledger devices only hold ed25519 and secp256k1 keys, not {scheme:?}");
+    }
+    let components: Vec<_> = path.iter().collect();
+    let mut data = vec![scheme.flag(), components.len() as u8];
+    for component in components {
+        data.extend_from_slice(&u32::from(component).to_be_bytes());
+    }
+    Ok(data)
+}
@@ -4,3 +4,7 @@
 pub mod key_derive;
 pub mod keypair_file;
 pub mod keystore;
+pub mod kms;
+pub mod ledger;
+pub mod pkcs11;
+pub mod signer;
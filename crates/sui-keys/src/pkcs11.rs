@@ -0,0 +1,152 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing with keys of an HSM through its PKCS#11 module.
+//!
+//! The module is the shared library of the HSM vendor, loaded at runtime with [cryptoki]. The key
+//! is an EC key over secp256k1 or secp256r1, found by the label of its private and public key
+//! objects on the token. The HSM signs with `CKM_ECDSA` the SHA-256 hash of the digests it is
+//! sent, the hash Sui verifies ECDSA signatures with.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, ensure};
+use async_trait::async_trait;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::error::{Error, RvError};
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use fastcrypto::hash::{HashFunction, Sha256};
+use sui_types::crypto::{PublicKey, Signature};
+
+use crate::signer::{ec_params_scheme, ec_point_public_key, ecdsa_signature, Signer};
+
+/// The HSM key to sign with.
+#[derive(Clone, Debug)]
+pub struct Pkcs11Config {
+    /// Path of the PKCS#11 module of the HSM.
+    pub module: PathBuf,
+    /// Index of the slot of the token among the slots with a token, the first one unless set.
+    pub slot_index: Option<usize>,
+    /// The user PIN of the token.
+    pub pin: String,
+    /// The label of the private and public key objects of the key.
+    pub key_label: String,
+}
+
+/// A [Signer] with a key of an HSM, whose private key never leaves the HSM.
+pub struct Pkcs11Signer {
+    // A session can't be used from several threads at once.
+    session: Arc<Mutex<Session>>,
+    private_key: ObjectHandle,
+    public_key: PublicKey,
+}
+
+impl Pkcs11Signer {
+    /// Loads the PKCS#11 module, logs in the token and finds the key of `config`.
+    pub fn open(config: &Pkcs11Config) -> Result<Self, anyhow::Error> {
+        let mut module = Pkcs11::new(&config.module)
+            .map_err(|e| anyhow!("Can't load PKCS#11 module {:?}: {e}", config.module))?;
+        match module.initialize(CInitializeArgs::OsThreads) {
+            Ok(()) | Err(Error::Pkcs11(RvError::CryptokiAlreadyInitialized)) => {}
+            Err(e) => return Err(pkcs11_error("C_Initialize", e)),
+        }
+
+        let slots = module
+            .get_slots_with_token()
+            .map_err(|e| pkcs11_error("C_GetSlotList", e))?;
+        let slot_index = config.slot_index.unwrap_or(0);
+        let slot = *slots.get(slot_index).ok_or_else(|| {
+            anyhow!(
+                "No token in slot {slot_index}, the PKCS#11 module has {} slots with a token",
+                slots.len()
+            )
+        })?;
+        // The session keeps the module loaded.
+        let session = module
+            .open_ro_session(slot)
+            .map_err(|e| pkcs11_error("C_OpenSession", e))?;
+        match session.login(UserType::User, Some(&config.pin)) {
+            Ok(()) | Err(Error::Pkcs11(RvError::UserAlreadyLoggedIn)) => {}
+            Err(e) => return Err(pkcs11_error("C_Login", e)),
+        }
+
+        let find = |class: ObjectClass| -> Result<ObjectHandle, anyhow::Error> {
+            let objects = session
+                .find_objects(&[
+                    Attribute::Class(class),
+                    Attribute::Label(config.key_label.as_bytes().to_vec()),
+                ])
+                .map_err(|e| pkcs11_error("C_FindObjects", e))?;
+            ensure!(
+                !objects.is_empty(),
+                "No key labeled {} on the token",
+                config.key_label
+            );
+            Ok(objects[0])
+        };
+
+        let public = find(ObjectClass::PUBLIC_KEY)?;
+        let (mut ec_params, mut ec_point) = (None, None);
+        for attribute in session
+            .get_attributes(public, &[AttributeType::EcParams, AttributeType::EcPoint])
+            .map_err(|e| pkcs11_error("C_GetAttributeValue", e))?
+        {
+            match attribute {
+                Attribute::EcParams(value) => ec_params = Some(value),
+                Attribute::EcPoint(value) => ec_point = Some(value),
+                _ => {}
+            }
+        }
+        let (ec_params, ec_point) = ec_params.zip(ec_point).ok_or_else(|| {
+            anyhow!(
+                "The key labeled {} on the token is not an EC key",
+                config.key_label
+            )
+        })?;
+        let scheme = ec_params_scheme(&ec_params)?;
+        let public_key = ec_point_public_key(scheme, &ec_point)?;
+        let private_key = find(ObjectClass::PRIVATE_KEY)?;
+        Ok(Self {
+            session: Arc::new(Mutex::new(session)),
+            private_key,
+            public_key,
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for Pkcs11Signer {
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    async fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature, anyhow::Error> {
+        let session = self.session.clone();
+        let private_key = self.private_key;
+        let hash = Sha256::digest(digest).digest;
+        // PKCS#11 calls block until the HSM responds.
+        let signature = tokio::task::spawn_blocking(move || {
+            session
+                .lock()
+                .map_err(|_| anyhow!("A previous PKCS#11 signing panicked"))?
+                .sign(&Mechanism::Ecdsa, private_key, &hash)
+                .map_err(|e| pkcs11_error("C_Sign", e))
+        })
+        .await??;
+        // CKM_ECDSA signatures are `r || s`, not DER encoded.
+        let signature: [u8; 64] = signature.as_slice().try_into().map_err(|_| {
+            anyhow!(
+                "Unexpected {} bytes ECDSA signature from the HSM",
+                signature.len()
+            )
+        })?;
+        ecdsa_signature(&self.public_key, &signature)
+    }
+}
+
+fn pkcs11_error(function: &str, error: Error) -> anyhow::Error {
+    anyhow!("PKCS#11 {function} failed: {error}")
+}
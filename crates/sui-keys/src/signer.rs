@@ -0,0 +1,157 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing with keys held outside of the keystore.
+//!
+//! A [Signer] signs the digests of intent messages with a key it may never expose, such as a key
+//! of a cloud KMS ([crate::kms]) or of an HSM ([crate::pkcs11]). Those sign with ECDSA over
+//! secp256k1 or secp256r1: the helpers of this module turn their signatures into Sui signatures.
+
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use fastcrypto::hash::HashFunction;
+use fastcrypto::traits::ToFromBytes;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::pkcs8::der::asn1::OctetStringRef;
+use k256::pkcs8::der::Decode;
+use k256::pkcs8::{AssociatedOid, DecodePublicKey, ObjectIdentifier};
+use serde::Serialize;
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    DefaultHash, PublicKey, Signature, SignatureScheme, SuiKeyPair, SuiSignature,
+};
+
+/// A key signing Sui transactions and messages.
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// The public key of the key.
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs `digest`, the Blake2b256 digest of the BCS bytes of an intent message.
+    async fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature, anyhow::Error>;
+
+    /// The address of the key.
+    fn address(&self) -> SuiAddress {
+        (&self.public_key()).into()
+    }
+}
+
+#[async_trait]
+impl Signer for SuiKeyPair {
+    fn public_key(&self) -> PublicKey {
+        self.public()
+    }
+
+    async fn sign_digest(&self, digest: &[u8; 32]) -> Result<Signature, anyhow::Error> {
+        Ok(Signature::new_hashed(digest, self))
+    }
+}
+
+/// Signs `value` with the intent `intent`, like [crate::keystore::AccountKeystore::sign_secure].
+/// The signature is verified before it is returned, so that a misconfigured external key fails
+/// here rather than on execution.
+pub async fn sign_secure<T>(
+    signer: &dyn Signer,
+    value: &T,
+    intent: Intent,
+) -> Result<Signature, anyhow::Error>
+where
+    T: Serialize,
+{
+    let intent_msg = IntentMessage::new(intent, value);
+    let mut hasher = DefaultHash::default();
+    hasher.update(bcs::to_bytes(&intent_msg)?);
+    let signature = signer.sign_digest(&hasher.finalize().digest).await?;
+    signature
+        .verify_secure(&intent_msg, signer.address())
+        .map_err(|e| anyhow!("The signer returned an invalid signature: {e}"))?;
+    Ok(signature)
+}
+
+/// The Sui signature of `public_key` from the `r || s` bytes of an ECDSA signature. Sui only
+/// accepts signatures with `s` in the lower half of the curve order, which KMS and HSMs don't
+/// ensure, so `s` is normalized.
+pub fn ecdsa_signature(
+    public_key: &PublicKey,
+    signature: &[u8; 64],
+) -> Result<Signature, anyhow::Error> {
+    let scheme = public_key.scheme();
+    let signature = match scheme {
+        SignatureScheme::Secp256k1 => k256::ecdsa::Signature::try_from(&signature[..])
+            .map(|signature| signature.normalize_s().unwrap_or(signature).to_vec())
+            .map_err(|e| anyhow!("Invalid ECDSA signature: {e}"))?,
+        SignatureScheme::Secp256r1 => p256::ecdsa::Signature::try_from(&signature[..])
+            .map(|signature| signature.normalize_s().unwrap_or(signature).to_vec())
+            .map_err(|e| anyhow!("Invalid ECDSA signature: {e}"))?,
+        _ => bail!("ECDSA signatures are only supported for secp256k1 and secp256r1 keys"),
+    };
+    let bytes = [&[scheme.flag()], &signature[..], public_key.as_ref()].concat();
+    Signature::from_bytes(&bytes).map_err(|e| anyhow!("Invalid ECDSA signature: {e}"))
+}
+
+/// The Sui signature of `public_key` from a DER encoded ECDSA signature, as returned by KMS, see
+/// [ecdsa_signature].
+pub fn ecdsa_der_signature(public_key: &PublicKey, der: &[u8]) -> Result<Signature, anyhow::Error> {
+    let signature = match public_key.scheme() {
+        SignatureScheme::Secp256k1 => k256::ecdsa::Signature::from_der(der)
+            .map(|signature| signature.to_vec())
+            .map_err(|e| anyhow!("Invalid DER signature: {e}"))?,
+        SignatureScheme::Secp256r1 => p256::ecdsa::Signature::from_der(der)
+            .map(|signature| signature.to_vec())
+            .map_err(|e| anyhow!("Invalid DER signature: {e}"))?,
+        _ => bail!("ECDSA signatures are only supported for secp256k1 and secp256r1 keys"),
+    };
+    let signature: [u8; 64] = signature
+        .try_into()
+        .map_err(|_| anyhow!("Invalid DER signature: not over a 256 bit curve"))?;
+    ecdsa_signature(public_key, &signature)
+}
+
+/// The public key of `scheme` from a DER SubjectPublicKeyInfo, as returned by KMS.
+pub fn ec_public_key(scheme: SignatureScheme, der: &[u8]) -> Result<PublicKey, anyhow::Error> {
+    let compressed = match scheme {
+        SignatureScheme::Secp256k1 => k256::PublicKey::from_public_key_der(der)
+            .map(|key| key.to_encoded_point(true).as_bytes().to_vec()),
+        SignatureScheme::Secp256r1 => p256::PublicKey::from_public_key_der(der)
+            .map(|key| key.to_encoded_point(true).as_bytes().to_vec()),
+        _ => bail!("Only secp256k1 and secp256r1 keys can sign Sui transactions"),
+    }
+    .map_err(|e| anyhow!("Invalid {scheme:?} public key: {e}"))?;
+    PublicKey::try_from_bytes(scheme, &compressed)
+        .map_err(|e| anyhow!("Invalid {scheme:?} public key: {e}"))
+}
+
+/// The public key of `scheme` from the `CKA_EC_POINT` of a PKCS#11 key, a SEC1 encoded point
+/// wrapped in a DER octet string, which some modules omit.
+pub fn ec_point_public_key(
+    scheme: SignatureScheme,
+    ec_point: &[u8],
+) -> Result<PublicKey, anyhow::Error> {
+    let point = OctetStringRef::from_der(ec_point)
+        .map(|point| point.as_bytes())
+        .unwrap_or(ec_point);
+    let compressed = match scheme {
+        SignatureScheme::Secp256k1 => k256::PublicKey::from_sec1_bytes(point)
+            .map(|key| key.to_encoded_point(true).as_bytes().to_vec()),
+        SignatureScheme::Secp256r1 => p256::PublicKey::from_sec1_bytes(point)
+            .map(|key| key.to_encoded_point(true).as_bytes().to_vec()),
+        _ => bail!("Only secp256k1 and secp256r1 keys can sign Sui transactions"),
+    }
+    .map_err(|e| anyhow!("Invalid {scheme:?} public key: {e}"))?;
+    PublicKey::try_from_bytes(scheme, &compressed)
+        .map_err(|e| anyhow!("Invalid {scheme:?} public key: {e}"))
+}
+
+/// The signature scheme of the named curve of DER encoded EC parameters.
+pub fn ec_params_scheme(params: &[u8]) -> Result<SignatureScheme, anyhow::Error> {
+    let oid = ObjectIdentifier::from_der(params)
+        .map_err(|e| anyhow!("Invalid EC parameters, expected a named curve: {e}"))?;
+    if oid == k256::Secp256k1::OID {
+        Ok(SignatureScheme::Secp256k1)
+    } else if oid == p256::NistP256::OID {
+        Ok(SignatureScheme::Secp256r1)
+    } else {
+        bail!("Only secp256k1 and secp256r1 keys can sign Sui transactions")
+    }
+}
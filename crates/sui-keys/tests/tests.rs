@@ -1,12 +1,28 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use std::cell::RefCell;
 use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
+use bip32::DerivationPath;
 use fastcrypto::hash::HashFunction;
+use fastcrypto::secp256k1::Secp256k1KeyPair;
+use fastcrypto::secp256r1::Secp256r1KeyPair;
+use shared_crypto::intent::{Intent, IntentMessage};
+use signature::Signer;
 use tempfile::TempDir;
 
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore, LedgerKeystore};
+use sui_keys::kms::{signed_request, AwsCredentials, AwsKmsConfig};
+use sui_keys::ledger::{hid_packets, HidResponseReader, LedgerDevice, LedgerTransport};
+use sui_keys::signer::{
+    ec_params_scheme, ec_point_public_key, ec_public_key, ecdsa_der_signature, ecdsa_signature,
+    sign_secure, Signer as _,
+};
+use sui_types::crypto::{
+    get_key_pair, AccountKeyPair, DefaultHash, Signature, SignatureScheme, SuiKeyPair,
+    SuiSignature, SuiSignatureInner,
+};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -69,3 +85,259 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+/// Ledger device answering with the key pair of a single account.
+struct MockLedger {
+    keypair: SuiKeyPair,
+    signing: RefCell<Vec<u8>>,
+}
+
+impl LedgerTransport for MockLedger {
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let (ins, p2, data) = (apdu[1], apdu[3], &apdu[5..]);
+        assert_eq!(apdu[4] as usize, data.len());
+        let mut response = match ins {
+            0x02 => {
+                let public_key = self.keypair.public();
+                [&[public_key.as_ref().len() as u8], public_key.as_ref()].concat()
+            }
+            0x03 => {
+                self.signing.borrow_mut().extend_from_slice(data);
+                if p2 != 0 {
+                    return Ok(vec![0x90, 0x00]);
+                }
+                let data = self.signing.take();
+                let path_length = 2 + 4 * data[1] as usize;
+                let msg = &data[path_length + 4..];
+                let mut hasher = DefaultHash::default();
+                hasher.update(msg);
+                let signature: Signature = self.keypair.sign(&hasher.finalize().digest);
+                signature.signature_bytes().to_vec()
+            }
+            _ => return Ok(vec![0x6d, 0x00]),
+        };
+        response.extend_from_slice(&[0x90, 0x00]);
+        Ok(response)
+    }
+}
+
+#[test]
+fn ledger_hid_framing_test() -> Result<(), anyhow::Error> {
+    let apdu: Vec<u8> = (0..200).map(|i| i as u8).collect();
+    let packets = hid_packets(&apdu);
+    assert_eq!(packets.len(), 4);
+    assert_eq!(&packets[0][..7], &[0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 200]);
+    assert_eq!(&packets[3][..5], &[0x01, 0x01, 0x05, 0x00, 0x03]);
+
+    let mut reader = HidResponseReader::default();
+    let mut response = None;
+    for packet in &packets {
+        assert!(response.is_none());
+        response = reader.push(packet)?;
+    }
+    assert_eq!(response, Some(apdu));
+
+    // Reports must be read in sequence.
+    let mut reader = HidResponseReader::default();
+    assert!(reader.push(&packets[1]).is_err());
+    Ok(())
+}
+
+#[test]
+fn ledger_device_sign_test() -> Result<(), anyhow::Error> {
+    let (address, keypair): (_, AccountKeyPair) = get_key_pair();
+    let keypair = SuiKeyPair::Ed25519(keypair);
+    let public_key = keypair.public();
+    let device = LedgerDevice::new(MockLedger {
+        keypair,
+        signing: RefCell::new(vec![]),
+    });
+    let path = DerivationPath::from_str("m/44'/784'/0'/0'/0'")?;
+
+    assert_eq!(
+        device.get_public_key(SignatureScheme::ED25519, &path)?,
+        public_key
+    );
+
+    // Long enough to be sent to the device in several commands.
+    let msg = vec![7u8; 600];
+    let intent_msg = IntentMessage::new(Intent::default(), msg);
+    let signature = device.sign(
+        SignatureScheme::ED25519,
+        &path,
+        &bcs::to_bytes(&intent_msg)?,
+    )?;
+    signature.verify_secure(&intent_msg, address)?;
+
+    assert!(device
+        .get_public_key(SignatureScheme::Secp256r1, &path)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn ledger_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("ledger.keystore");
+    let mut keystore = Keystore::from(LedgerKeystore::new(&keystore_path)?);
+    assert!(keystore.keys().is_empty());
+    assert!(keystore.to_string().contains("Keystore Type : Ledger"));
+
+    // Private keys never enter or leave a Ledger keystore.
+    let (address, keypair): (_, AccountKeyPair) = get_key_pair();
+    assert!(keystore.add_key(SuiKeyPair::Ed25519(keypair)).is_err());
+    assert!(keystore.get_key(&address).is_err());
+    assert!(keystore.sign_hashed(&address, &[0; 32]).is_err());
+    assert!(keystore
+        .sign_secure(&address, &"msg", Intent::default())
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn kms_signed_request_test() -> Result<(), anyhow::Error> {
+    let config = AwsKmsConfig {
+        region: "us-east-1".to_string(),
+        credentials: AwsCredentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        },
+        endpoint: None,
+    };
+    // 2015-08-30T12:36:00Z
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1440938160);
+    let request = signed_request(
+        &config,
+        "https://kms.us-east-1.amazonaws.com/",
+        "GetPublicKey",
+        br#"{"KeyId":"alias/sui"}"#.to_vec(),
+        time,
+    )?;
+    assert_eq!(request.headers()["x-amz-date"], "20150830T123600Z");
+    assert_eq!(
+        request.headers()["authorization"],
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/kms/aws4_request, \
+         SignedHeaders=content-type;host;x-amz-date;x-amz-target, \
+         Signature=16566210f9ce00aec6230f43f79478558feb6f3c53897650020e413e552aeeb9"
+    );
+    assert!(!request.headers().contains_key("x-amz-security-token"));
+    Ok(())
+}
+
+/// DER encoding of the ECDSA signature `r || s`.
+fn der_signature(signature: &[u8]) -> Vec<u8> {
+    let integer = |bytes: &[u8]| {
+        let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(31);
+        let mut value = bytes[start..].to_vec();
+        if value[0] & 0x80 != 0 {
+            value.insert(0, 0);
+        }
+        [&[0x02, value.len() as u8], &value[..]].concat()
+    };
+    let body = [integer(&signature[..32]), integer(&signature[32..])].concat();
+    [&[0x30, body.len() as u8], &body[..]].concat()
+}
+
+/// `order - s` for the big endian `s`.
+fn negate(order: &[u8; 32], s: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; 32];
+    let mut borrow = 0;
+    for ((out, a), b) in result.iter_mut().zip(order).zip(s).rev() {
+        let digit = *a as i16 - *b as i16 - borrow;
+        borrow = (digit < 0) as i16;
+        *out = (digit + 256 * borrow) as u8;
+    }
+    result
+}
+
+#[test]
+fn ecdsa_signature_test() -> Result<(), anyhow::Error> {
+    let secp256k1_order = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36,
+        0x41, 0x41,
+    ];
+    let (_, keypair): (_, Secp256k1KeyPair) = get_key_pair();
+    let keypair = SuiKeyPair::Secp256k1(keypair);
+    let signature = Signature::new_hashed(&[7; 32], &keypair);
+    let compact: [u8; 64] = signature.signature_bytes().try_into()?;
+
+    // Signatures of KMS are DER encoded.
+    assert_eq!(
+        ecdsa_der_signature(&keypair.public(), &der_signature(&compact))?,
+        signature
+    );
+    assert!(ecdsa_der_signature(&keypair.public(), &compact).is_err());
+    assert!(ecdsa_der_signature(&keypair.public(), &der_signature(&compact)[..10]).is_err());
+
+    // Either s or its negation is returned, Sui only accepts the lower one.
+    assert_eq!(ecdsa_signature(&keypair.public(), &compact)?, signature);
+    let high_s: [u8; 64] = [
+        &compact[..32],
+        &negate(&secp256k1_order, &compact[32..])[..],
+    ]
+    .concat()
+    .try_into()
+    .unwrap();
+    assert_eq!(ecdsa_signature(&keypair.public(), &high_s)?, signature);
+
+    let (_, keypair): (_, AccountKeyPair) = get_key_pair();
+    assert!(ecdsa_signature(&SuiKeyPair::Ed25519(keypair).public(), &compact).is_err());
+    Ok(())
+}
+
+#[test]
+fn ec_public_key_test() -> Result<(), anyhow::Error> {
+    // The generator points of secp256k1 and secp256r1, in a DER SubjectPublicKeyInfo as returned
+    // by KMS.
+    let secp256k1_x = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    let secp256k1_spki = hex::decode(format!(
+        "3056301006072a8648ce3d020106052b8104000a034200\
+         04{secp256k1_x}483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"
+    ))?;
+    let secp256r1_x = "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+    let secp256r1_spki = hex::decode(format!(
+        "3059301306072a8648ce3d020106082a8648ce3d030107034200\
+         04{secp256r1_x}4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"
+    ))?;
+
+    let public_key = ec_public_key(SignatureScheme::Secp256k1, &secp256k1_spki)?;
+    assert_eq!(public_key.scheme(), SignatureScheme::Secp256k1);
+    assert_eq!(hex::encode(public_key.as_ref()), format!("02{secp256k1_x}"));
+    let public_key = ec_public_key(SignatureScheme::Secp256r1, &secp256r1_spki)?;
+    assert_eq!(hex::encode(public_key.as_ref()), format!("03{secp256r1_x}"));
+    assert!(ec_public_key(SignatureScheme::Secp256k1, &[0x04; 10]).is_err());
+    assert!(ec_public_key(SignatureScheme::Secp256k1, &secp256r1_spki).is_err());
+
+    // The EC points of PKCS#11 keys, with or without their DER octet string.
+    let point = &secp256r1_spki[secp256r1_spki.len() - 65..];
+    let public_key = ec_point_public_key(SignatureScheme::Secp256r1, point)?;
+    assert_eq!(hex::encode(public_key.as_ref()), format!("03{secp256r1_x}"));
+    let octet_string = [&[0x04, 65][..], point].concat();
+    let public_key = ec_point_public_key(SignatureScheme::Secp256r1, &octet_string)?;
+    assert_eq!(hex::encode(public_key.as_ref()), format!("03{secp256r1_x}"));
+
+    assert_eq!(
+        ec_params_scheme(&hex::decode("06052b8104000a")?)?,
+        SignatureScheme::Secp256k1
+    );
+    assert_eq!(
+        ec_params_scheme(&hex::decode("06082a8648ce3d030107")?)?,
+        SignatureScheme::Secp256r1
+    );
+    assert!(ec_params_scheme(&hex::decode("06052b81040022")?).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn signer_sign_secure_test() -> Result<(), anyhow::Error> {
+    let (_, keypair): (_, Secp256r1KeyPair) = get_key_pair();
+    let keypair = SuiKeyPair::Secp256r1(keypair);
+    let signature = sign_secure(&keypair, &"msg", Intent::default()).await?;
+    signature.verify_secure(
+        &IntentMessage::new(Intent::default(), &"msg"),
+        keypair.address(),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,497 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coverage reports of the Move unit tests of a package, in the lcov format read by CI tooling
+//! and as HTML pages.
+//!
+//! The coverage map recorded by the test runner counts the executions of every bytecode
+//! instruction. Instructions are mapped back to the source lines they were compiled from, and a
+//! line counts as many executions as the most executed instruction starting on it. HTML pages
+//! also list the instructions of every function with their execution counts.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use move_binary_format::{access::ModuleAccess, file_format::FunctionDefinitionIndex};
+use move_compiler::compiled_unit::{CompiledUnitEnum, NamedCompiledModule};
+use move_coverage::coverage_map::{CoverageMap, ExecCoverageMap};
+use move_package::BuildConfig;
+use sui_framework_build::compiled_package::BuildConfig as SuiBuildConfig;
+
+/// Coverage map written to the root of the package by the Move unit test runner.
+const COVERAGE_MAP_FILE: &str = ".coverage_map.mvcov";
+const LCOV_FILE: &str = "lcov.info";
+const HTML_INDEX_FILE: &str = "index.html";
+
+pub struct InstructionCoverage {
+    pub pc: u16,
+    pub instruction: String,
+    /// Source line the instruction was compiled from, starting at 1.
+    pub line: usize,
+    pub count: u64,
+}
+
+pub struct FunctionCoverage {
+    pub name: String,
+    /// Line of the definition of the function.
+    pub line: usize,
+    pub instructions: Vec<InstructionCoverage>,
+}
+
+impl FunctionCoverage {
+    /// Number of calls of the function, the executions of its first instruction.
+    pub fn calls(&self) -> u64 {
+        self.instructions.first().map_or(0, |i| i.count)
+    }
+
+    pub fn covered(&self) -> usize {
+        self.instructions.iter().filter(|i| i.count > 0).count()
+    }
+}
+
+pub struct ModuleCoverage {
+    /// `<address>::<module>` of the module.
+    pub name: String,
+    pub source_path: PathBuf,
+    pub source: String,
+    /// Functions with bytecode, natives have none.
+    pub functions: Vec<FunctionCoverage>,
+}
+
+impl ModuleCoverage {
+    fn new(
+        unit: &NamedCompiledModule,
+        source_path: &Path,
+        coverage: &ExecCoverageMap,
+    ) -> anyhow::Result<Self> {
+        let NamedCompiledModule {
+            module, source_map, ..
+        } = unit;
+        let source = fs::read_to_string(source_path)
+            .map_err(|e| anyhow!("Can't read source file {}: {e}", source_path.display()))?;
+        let line_starts: Vec<_> = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let line_of = |offset: u32| line_starts.partition_point(|start| *start <= offset as usize);
+
+        let self_id = module.self_id();
+        let module_map = coverage
+            .module_maps
+            .get(&(*self_id.address(), self_id.name().to_owned()));
+
+        let mut functions = vec![];
+        for (index, definition) in module.function_defs().iter().enumerate() {
+            let Some(code) = &definition.code else {
+                continue;
+            };
+            let index = FunctionDefinitionIndex::new(index as u16);
+            let name = module.identifier_at(module.function_handle_at(definition.function).name);
+            let function_map = module_map.and_then(|map| map.function_maps.get(name));
+            let function_source_map = source_map.get_function_source_map(index)?;
+
+            let mut instructions = vec![];
+            for (pc, instruction) in code.code.iter().enumerate() {
+                let location = source_map.get_code_location(index, pc as u16)?;
+                instructions.push(InstructionCoverage {
+                    pc: pc as u16,
+                    instruction: format!("{instruction:?}"),
+                    line: line_of(location.start()),
+                    count: function_map
+                        .and_then(|map| map.get(&(pc as u64)))
+                        .copied()
+                        .unwrap_or(0),
+                });
+            }
+            functions.push(FunctionCoverage {
+                name: name.to_string(),
+                line: line_of(function_source_map.definition_location.start()),
+                instructions,
+            });
+        }
+
+        Ok(Self {
+            name: format!("{}::{}", self_id.address().to_hex_literal(), self_id.name()),
+            source_path: source_path.to_path_buf(),
+            source,
+            functions,
+        })
+    }
+
+    pub fn total(&self) -> usize {
+        self.functions.iter().map(|f| f.instructions.len()).sum()
+    }
+
+    pub fn covered(&self) -> usize {
+        self.functions.iter().map(FunctionCoverage::covered).sum()
+    }
+
+    /// Executions of every line with instructions, as the executions of the most executed of
+    /// them, and whether some of them never ran.
+    pub fn lines(&self) -> BTreeMap<usize, (u64, bool)> {
+        let mut lines = BTreeMap::<usize, (u64, bool)>::new();
+        for instruction in self.functions.iter().flat_map(|f| &f.instructions) {
+            let (count, partial) = lines.entry(instruction.line).or_default();
+            *count = (*count).max(instruction.count);
+            *partial |= instruction.count == 0;
+        }
+        lines
+    }
+}
+
+/// Reads the coverage map of the last test run of the package at `path`, and writes its lcov
+/// and HTML reports to `output_dir`.
+pub fn write_coverage_reports(
+    path: &Path,
+    build_config: BuildConfig,
+    output_dir: &Path,
+) -> anyhow::Result<()> {
+    let coverage_map = CoverageMap::from_binary_file(path.join(COVERAGE_MAP_FILE))?;
+    let coverage = coverage_map.to_unified_exec_map();
+    let package = SuiBuildConfig {
+        config: BuildConfig {
+            test_mode: false,
+            ..build_config
+        },
+        run_bytecode_verifier: false,
+        print_diags_to_stderr: false,
+    }
+    .build(path.to_path_buf())?;
+
+    let mut modules = vec![];
+    for unit in package.package.root_modules() {
+        if let CompiledUnitEnum::Module(module) = &unit.unit {
+            modules.push(ModuleCoverage::new(module, &unit.source_path, &coverage)?);
+        }
+    }
+
+    fs::create_dir_all(output_dir)?;
+    fs::write(output_dir.join(LCOV_FILE), lcov_report(&modules))?;
+    fs::write(output_dir.join(HTML_INDEX_FILE), html_index(&modules))?;
+    for module in &modules {
+        fs::write(output_dir.join(html_file_name(module)), html_module(module))?;
+    }
+    Ok(())
+}
+
+/// The lcov tracefile of `modules`, with function and line records for every module.
+pub fn lcov_report(modules: &[ModuleCoverage]) -> String {
+    let mut report = String::new();
+    for module in modules {
+        writeln!(report, "TN:").unwrap();
+        writeln!(report, "SF:{}", module.source_path.display()).unwrap();
+        for function in &module.functions {
+            writeln!(report, "FN:{},{}", function.line, function.name).unwrap();
+        }
+        for function in &module.functions {
+            writeln!(report, "FNDA:{},{}", function.calls(), function.name).unwrap();
+        }
+        writeln!(report, "FNF:{}", module.functions.len()).unwrap();
+        writeln!(
+            report,
+            "FNH:{}",
+            module.functions.iter().filter(|f| f.calls() > 0).count()
+        )
+        .unwrap();
+
+        let lines = module.lines();
+        for (line, (count, _)) in &lines {
+            writeln!(report, "DA:{line},{count}").unwrap();
+        }
+        writeln!(report, "LF:{}", lines.len()).unwrap();
+        writeln!(
+            report,
+            "LH:{}",
+            lines.values().filter(|(count, _)| *count > 0).count()
+        )
+        .unwrap();
+        writeln!(report, "end_of_record").unwrap();
+    }
+    report
+}
+
+/// Summary page of the coverage of `modules`, linking to the page of every module.
+pub fn html_index(modules: &[ModuleCoverage]) -> String {
+    let mut page = html_header("Move coverage");
+    page.push_str("<h1>Move coverage</h1>\n<table>\n");
+    page.push_str("<tr><th>Module</th><th>Instructions</th><th>Coverage</th></tr>\n");
+    for module in modules {
+        writeln!(
+            page,
+            "<tr><td><a href=\"{}\">{}</a></td><td>{}/{}</td>{}</tr>",
+            html_file_name(module),
+            escape_html(&module.name),
+            module.covered(),
+            module.total(),
+            percentage_cell(module.covered(), module.total()),
+        )
+        .unwrap();
+    }
+    let covered = modules.iter().map(ModuleCoverage::covered).sum();
+    let total = modules.iter().map(ModuleCoverage::total).sum();
+    writeln!(
+        page,
+        "<tr><th>Total</th><th>{covered}/{total}</th>{}</tr>",
+        percentage_cell(covered, total)
+    )
+    .unwrap();
+    page.push_str("</table>\n</body>\n</html>\n");
+    page
+}
+
+/// Page of a module, with the coverage of its functions, its annotated source and the coverage
+/// of the instructions of every function.
+pub fn html_module(module: &ModuleCoverage) -> String {
+    let mut page = html_header(&module.name);
+    writeln!(
+        page,
+        "<h1>{}</h1>\n<p>{}</p>\n<table>",
+        escape_html(&module.name),
+        escape_html(&module.source_path.display().to_string())
+    )
+    .unwrap();
+    page.push_str(
+        "<tr><th>Function</th><th>Calls</th><th>Instructions</th><th>Coverage</th></tr>\n",
+    );
+    for function in &module.functions {
+        writeln!(
+            page,
+            "<tr><td><a href=\"#fn-{0}\">{0}</a></td><td>{1}</td><td>{2}/{3}</td>{4}</tr>",
+            escape_html(&function.name),
+            function.calls(),
+            function.covered(),
+            function.instructions.len(),
+            percentage_cell(function.covered(), function.instructions.len()),
+        )
+        .unwrap();
+    }
+    page.push_str("</table>\n<h2>Source</h2>\n<pre>\n");
+
+    let lines = module.lines();
+    for (i, line) in module.source.lines().enumerate() {
+        let number = i + 1;
+        let (class, count) = match lines.get(&number) {
+            None => ("", String::new()),
+            Some((0, _)) => (" class=\"uncovered\"", "0".to_string()),
+            Some((count, true)) => (" class=\"partial\"", count.to_string()),
+            Some((count, false)) => (" class=\"covered\"", count.to_string()),
+        };
+        writeln!(
+            page,
+            "<span{class} id=\"L{number}\">{number:>5} {count:>7} | {}</span>",
+            escape_html(line)
+        )
+        .unwrap();
+    }
+    page.push_str("</pre>\n<h2>Bytecode</h2>\n");
+
+    for function in &module.functions {
+        writeln!(
+            page,
+            "<h3 id=\"fn-{0}\">{0}</h3>\n<table>\n\
+             <tr><th>Offset</th><th>Instruction</th><th>Line</th><th>Executions</th></tr>",
+            escape_html(&function.name)
+        )
+        .unwrap();
+        for instruction in &function.instructions {
+            let class = if instruction.count > 0 {
+                "covered"
+            } else {
+                "uncovered"
+            };
+            writeln!(
+                page,
+                "<tr class=\"{class}\"><td>{}</td><td><code>{}</code></td>\
+                 <td><a href=\"#L{2}\">{2}</a></td><td>{3}</td></tr>",
+                instruction.pc,
+                escape_html(&instruction.instruction),
+                instruction.line,
+                instruction.count,
+            )
+            .unwrap();
+        }
+        page.push_str("</table>\n");
+    }
+    page.push_str("</body>\n</html>\n");
+    page
+}
+
+fn html_file_name(module: &ModuleCoverage) -> String {
+    format!("{}.html", module.name.replace("::", "-"))
+}
+
+fn html_header(title: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n\
+         body {{ font-family: sans-serif; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         td, th {{ border: 1px solid #ccc; padding: 2px 8px; text-align: left; }}\n\
+         pre span {{ display: block; }}\n\
+         .covered {{ background-color: #dfd; }}\n\
+         .partial {{ background-color: #ffd; }}\n\
+         .uncovered {{ background-color: #fdd; }}\n\
+         </style>\n</head>\n<body>\n",
+        escape_html(title)
+    )
+}
+
+fn percentage_cell(covered: usize, total: usize) -> String {
+    if total == 0 {
+        return "<td>-</td>".to_string();
+    }
+    format!("<td>{:.2}%</td>", covered as f64 * 100.0 / total as f64)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A module where `f` was called twice, always taking its `else` branch, and `g` never.
+    fn module() -> ModuleCoverage {
+        let instruction = |pc, instruction: &str, line, count| InstructionCoverage {
+            pc,
+            instruction: instruction.to_string(),
+            line,
+            count,
+        };
+        ModuleCoverage {
+            name: "0x2::m".to_string(),
+            source_path: PathBuf::from("sources/m.move"),
+            source: "module 0x2::m {
+    fun f(x: u64): bool {
+        if (x < 10) { true }
+        else { false }
+    }
+    fun g() {}
+}
+"
+            .to_string(),
+            functions: vec![
+                FunctionCoverage {
+                    name: "f".to_string(),
+                    line: 2,
+                    instructions: vec![
+                        instruction(0, "CopyLoc(0)", 3, 2),
+                        instruction(1, "LdU64(10)", 3, 2),
+                        instruction(2, "Lt", 3, 2),
+                        instruction(3, "BrFalse(6)", 3, 2),
+                        instruction(4, "LdTrue", 3, 0),
+                        instruction(5, "Ret", 3, 0),
+                        instruction(6, "LdFalse", 4, 2),
+                        instruction(7, "Ret", 4, 2),
+                    ],
+                },
+                FunctionCoverage {
+                    name: "g".to_string(),
+                    line: 6,
+                    instructions: vec![instruction(0, "Ret", 6, 0)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_lcov_report() {
+        assert_eq!(
+            lcov_report(&[module()]),
+            "TN:
+SF:sources/m.move
+FN:2,f
+FN:6,g
+FNDA:2,f
+FNDA:0,g
+FNF:2
+FNH:1
+DA:3,2
+DA:4,2
+DA:6,0
+LF:3
+LH:2
+end_of_record
+"
+        );
+    }
+
+    #[test]
+    fn test_html_index() {
+        let page = html_index(&[module()]);
+        assert_eq!(
+            page.strip_prefix(&html_header("Move coverage")).unwrap(),
+            r#"<h1>Move coverage</h1>
+<table>
+<tr><th>Module</th><th>Instructions</th><th>Coverage</th></tr>
+<tr><td><a href="0x2-m.html">0x2::m</a></td><td>6/9</td><td>66.67%</td></tr>
+<tr><th>Total</th><th>6/9</th><td>66.67%</td></tr>
+</table>
+</body>
+</html>
+"#
+        );
+        assert!(html_index(&[]).contains("<tr><th>Total</th><th>0/0</th><td>-</td></tr>"));
+    }
+
+    #[test]
+    fn test_html_module() {
+        let page = html_module(&module());
+        assert_eq!(
+            page.strip_prefix(&html_header("0x2::m")).unwrap(),
+            r##"<h1>0x2::m</h1>
+<p>sources/m.move</p>
+<table>
+<tr><th>Function</th><th>Calls</th><th>Instructions</th><th>Coverage</th></tr>
+<tr><td><a href="#fn-f">f</a></td><td>2</td><td>6/8</td><td>75.00%</td></tr>
+<tr><td><a href="#fn-g">g</a></td><td>0</td><td>0/1</td><td>0.00%</td></tr>
+</table>
+<h2>Source</h2>
+<pre>
+<span id="L1">    1         | module 0x2::m {</span>
+<span id="L2">    2         |     fun f(x: u64): bool {</span>
+<span class="partial" id="L3">    3       2 |         if (x &lt; 10) { true }</span>
+<span class="covered" id="L4">    4       2 |         else { false }</span>
+<span id="L5">    5         |     }</span>
+<span class="uncovered" id="L6">    6       0 |     fun g() {}</span>
+<span id="L7">    7         | }</span>
+</pre>
+<h2>Bytecode</h2>
+<h3 id="fn-f">f</h3>
+<table>
+<tr><th>Offset</th><th>Instruction</th><th>Line</th><th>Executions</th></tr>
+<tr class="covered"><td>0</td><td><code>CopyLoc(0)</code></td><td><a href="#L3">3</a></td><td>2</td></tr>
+<tr class="covered"><td>1</td><td><code>LdU64(10)</code></td><td><a href="#L3">3</a></td><td>2</td></tr>
+<tr class="covered"><td>2</td><td><code>Lt</code></td><td><a href="#L3">3</a></td><td>2</td></tr>
+<tr class="covered"><td>3</td><td><code>BrFalse(6)</code></td><td><a href="#L3">3</a></td><td>2</td></tr>
+<tr class="uncovered"><td>4</td><td><code>LdTrue</code></td><td><a href="#L3">3</a></td><td>0</td></tr>
+<tr class="uncovered"><td>5</td><td><code>Ret</code></td><td><a href="#L3">3</a></td><td>0</td></tr>
+<tr class="covered"><td>6</td><td><code>LdFalse</code></td><td><a href="#L4">4</a></td><td>2</td></tr>
+<tr class="covered"><td>7</td><td><code>Ret</code></td><td><a href="#L4">4</a></td><td>2</td></tr>
+</table>
+<h3 id="fn-g">g</h3>
+<table>
+<tr><th>Offset</th><th>Instruction</th><th>Line</th><th>Executions</th></tr>
+<tr class="uncovered"><td>0</td><td><code>Ret</code></td><td><a href="#L6">6</a></td><td>0</td></tr>
+</table>
+</body>
+</html>
+"##
+        );
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">&</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        assert!(html_header("<script>").contains("<title>&lt;script&gt;</title>"));
+    }
+}
@@ -15,6 +15,8 @@ pub mod build;
 mod cost_calib;
 #[cfg(feature = "coverage")]
 pub mod coverage;
+#[cfg(feature = "unit_test")]
+pub mod coverage_report;
 #[cfg(feature = "disassemble")]
 pub mod disassemble;
 pub mod new;
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::build;
+use super::{build, coverage_report};
 use clap::Parser;
 use move_cli::base::{
     self,
@@ -30,6 +30,10 @@ const MAX_UNIT_TEST_INSTRUCTIONS: u64 = 1_000_000;
 pub struct Test {
     #[clap(flatten)]
     pub test: test::Test,
+    /// Directory to write the lcov and HTML reports of the coverage of the tests to, when they
+    /// run with `--coverage`. Defaults to the `coverage` directory of the package
+    #[clap(long = "coverage-report-dir")]
+    pub coverage_report_dir: Option<PathBuf>,
 }
 
 impl Test {
@@ -57,12 +61,21 @@ impl Test {
             generate_struct_layouts,
             dump_package_digest,
         )?;
-        run_move_unit_tests(
+        let result = run_move_unit_tests(
             &rerooted_path,
-            build_config,
+            build_config.clone(),
             Some(unit_test_config),
             self.test.compute_coverage,
-        )
+        )?;
+        if self.test.compute_coverage {
+            let output_dir = self
+                .coverage_report_dir
+                .clone()
+                .unwrap_or_else(|| rerooted_path.join("coverage"));
+            coverage_report::write_coverage_reports(&rerooted_path, build_config, &output_dir)?;
+            println!("Coverage reports written to {}", output_dir.display());
+        }
+        Ok(result)
     }
 }
 
@@ -40,6 +40,15 @@ fn main() -> Result<()> {
                 .codec_path(codec_path)
                 .build(),
         )
+        .method(
+            Method::builder()
+                .name("handle_certificate_bundle")
+                .route_name("CertificateBundle")
+                .input_type("sui_types::messages::HandleCertificateBundleRequest")
+                .output_type("sui_types::messages::HandleCertificateBundleResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
         .method(
             Method::builder()
                 .name("object_info")
@@ -78,9 +87,25 @@ fn main() -> Result<()> {
         )
         .build();
 
+    let checkpoint_stream_service = Service::builder()
+        .name("CheckpointStream")
+        .package("sui.checkpoints")
+        .comment("Streams full checkpoint data to clients following the chain")
+        .method(
+            Method::builder()
+                .name("stream_checkpoints")
+                .route_name("StreamCheckpoints")
+                .input_type("sui_types::messages_checkpoint::CheckpointStreamRequest")
+                .output_type("sui_types::messages_checkpoint::CheckpointData")
+                .codec_path(codec_path)
+                .server_streaming()
+                .build(),
+        )
+        .build();
+
     Builder::new()
         .out_dir(&out_dir)
-        .compile(&[validator_service]);
+        .compile(&[validator_service, checkpoint_stream_service]);
 
     build_anemo_services(&out_dir);
 
@@ -137,6 +162,15 @@ fn build_anemo_services(out_dir: &Path) {
                 .codec_path(codec_path)
                 .build(),
         )
+        .method(
+            anemo_build::manual::Method::builder()
+                .name("get_transaction_outputs")
+                .route_name("GetTransactionOutputs")
+                .request_type("sui_types::digests::TransactionEffectsDigest")
+                .response_type("Option<crate::state_sync::TransactionOutputs>")
+                .codec_path(codec_path)
+                .build(),
+        )
         .build();
 
     anemo_build::manual::Builder::new()
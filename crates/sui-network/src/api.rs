@@ -1,10 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod checkpoints {
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/sui.checkpoints.CheckpointStream.rs"
+    ));
+}
+
 mod validator {
     include!(concat!(env!("OUT_DIR"), "/sui.validator.Validator.rs"));
 }
 
+pub use checkpoints::{
+    checkpoint_stream_client::CheckpointStreamClient,
+    checkpoint_stream_server::{CheckpointStream, CheckpointStreamServer},
+};
+
 pub use validator::{
     validator_client::ValidatorClient,
     validator_server::{Validator, ValidatorServer},
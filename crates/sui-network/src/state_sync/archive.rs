@@ -0,0 +1,42 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use sui_storage::archive::ArchiveReader;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary as Checkpoint, CheckpointSequenceNumber, FullCheckpointContents,
+};
+
+/// A source of checkpoints to fall back on when no peer can provide them, e.g. because all of our
+/// peers have pruned them.
+///
+/// Checkpoints read from an archive are verified exactly like the ones received from peers.
+#[async_trait]
+pub trait CheckpointArchive: Send + Sync {
+    async fn get_checkpoint_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<Checkpoint>>;
+
+    async fn get_checkpoint_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<FullCheckpointContents>>;
+}
+
+#[async_trait]
+impl CheckpointArchive for ArchiveReader {
+    async fn get_checkpoint_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<Checkpoint>> {
+        ArchiveReader::get_checkpoint_summary(self, sequence_number).await
+    }
+
+    async fn get_checkpoint_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<FullCheckpointContents>> {
+        ArchiveReader::get_checkpoint_contents(self, sequence_number).await
+    }
+}
@@ -3,6 +3,7 @@
 
 use anemo::codegen::InboundRequestLayer;
 use anemo_tower::rate_limit;
+use narwhal_network::peer_quality::PeerQuality;
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -16,14 +17,16 @@ use tokio::{
 };
 
 use super::{
-    metrics::Metrics, server::Server, Handle, PeerHeights, StateSync, StateSyncEventLoop,
-    StateSyncMessage, StateSyncServer,
+    metrics::Metrics, server::Server, CheckpointArchive, Handle, PeerHeights, StateSync,
+    StateSyncEventLoop, StateSyncMessage, StateSyncServer,
 };
 use sui_types::storage::WriteStore;
 
 pub struct Builder<S> {
     store: Option<S>,
     config: Option<StateSyncConfig>,
+    archive: Option<Arc<dyn CheckpointArchive>>,
+    peer_quality: Option<Arc<PeerQuality>>,
     metrics: Option<Metrics>,
 }
 
@@ -33,6 +36,8 @@ impl Builder<()> {
         Self {
             store: None,
             config: None,
+            archive: None,
+            peer_quality: None,
             metrics: None,
         }
     }
@@ -43,6 +48,8 @@ impl<S> Builder<S> {
         Builder {
             store: Some(store),
             config: self.config,
+            archive: self.archive,
+            peer_quality: self.peer_quality,
             metrics: self.metrics,
         }
     }
@@ -52,6 +59,18 @@ impl<S> Builder<S> {
         self
     }
 
+    /// Sync the checkpoints that no peer can provide from `archive`.
+    pub fn archive(mut self, archive: Arc<dyn CheckpointArchive>) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// Try the peers with the best quality score first when syncing checkpoints.
+    pub fn peer_quality(mut self, peer_quality: Arc<PeerQuality>) -> Self {
+        self.peer_quality = Some(peer_quality);
+        self
+    }
+
     pub fn with_metrics(mut self, registry: &prometheus::Registry) -> Self {
         self.metrics = Some(Metrics::enabled(registry));
         self
@@ -93,6 +112,14 @@ where
                 )),
             );
         }
+        if let Some(limit) = state_sync_config.get_transaction_outputs_rate_limit {
+            state_sync_server = state_sync_server.add_layer_for_get_transaction_outputs(
+                InboundRequestLayer::new(rate_limit::RateLimitLayer::new(
+                    governor::Quota::per_second(limit),
+                    rate_limit::WaitMode::Block,
+                )),
+            );
+        }
 
         (builder, state_sync_server)
     }
@@ -101,6 +128,8 @@ where
         let Builder {
             store,
             config,
+            archive,
+            peer_quality,
             metrics,
         } = self;
         let store = store.unwrap();
@@ -111,10 +140,6 @@ where
         let (checkpoint_event_sender, _receiver) =
             broadcast::channel(config.synced_checkpoint_broadcast_channel_capacity());
         let weak_sender = sender.downgrade();
-        let handle = Handle {
-            sender,
-            checkpoint_event_sender: checkpoint_event_sender.clone(),
-        };
         let peer_heights = PeerHeights {
             peers: HashMap::new(),
             unprocessed_checkpoints: HashMap::new(),
@@ -122,6 +147,11 @@ where
         }
         .pipe(RwLock::new)
         .pipe(Arc::new);
+        let handle = Handle {
+            sender,
+            checkpoint_event_sender: checkpoint_event_sender.clone(),
+            peer_heights: peer_heights.clone(),
+        };
 
         let server = Server {
             store: store.clone(),
@@ -137,6 +167,8 @@ where
                 store,
                 peer_heights,
                 checkpoint_event_sender,
+                archive,
+                peer_quality,
                 metrics,
             },
             server,
@@ -151,6 +183,8 @@ pub struct UnstartedStateSync<S> {
     pub(super) store: S,
     pub(super) peer_heights: Arc<RwLock<PeerHeights>>,
     pub(super) checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    pub(super) archive: Option<Arc<dyn CheckpointArchive>>,
+    pub(super) peer_quality: Option<Arc<PeerQuality>>,
     pub(super) metrics: Metrics,
 }
 
@@ -167,6 +201,8 @@ where
             store,
             peer_heights,
             checkpoint_event_sender,
+            archive,
+            peer_quality,
             metrics,
         } = self;
 
@@ -182,6 +218,8 @@ where
                 peer_heights,
                 checkpoint_event_sender,
                 network,
+                archive,
+                peer_quality,
                 metrics,
             },
             handle,
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use mysten_metrics::histogram::Histogram;
-use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
+use prometheus::{
+    register_int_counter_with_registry, register_int_gauge_with_registry, IntCounter, IntGauge,
+    Registry,
+};
 use std::sync::Arc;
 use std::time::Duration;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
@@ -53,6 +56,18 @@ impl Metrics {
                 .report(age.as_millis() as u64);
         }
     }
+
+    pub fn inc_checkpoint_summaries_from_archive(&self) {
+        if let Some(inner) = &self.0 {
+            inner.checkpoint_summaries_from_archive.inc();
+        }
+    }
+
+    pub fn inc_checkpoint_contents_from_archive(&self) {
+        if let Some(inner) = &self.0 {
+            inner.checkpoint_contents_from_archive.inc();
+        }
+    }
 }
 
 struct Inner {
@@ -60,6 +75,8 @@ struct Inner {
     highest_verified_checkpoint: IntGauge,
     highest_synced_checkpoint: IntGauge,
     checkpoint_summary_age_ms: Histogram,
+    checkpoint_summaries_from_archive: IntCounter,
+    checkpoint_contents_from_archive: IntCounter,
 }
 
 impl Inner {
@@ -91,6 +108,20 @@ impl Inner {
                 "Age of checkpoints summaries when they arrive and are verified.",
                 registry,
             ),
+
+            checkpoint_summaries_from_archive: register_int_counter_with_registry!(
+                "checkpoint_summaries_from_archive",
+                "Number of checkpoint summaries read from the archive because no peer had them",
+                registry
+            )
+            .unwrap(),
+
+            checkpoint_contents_from_archive: register_int_counter_with_registry!(
+                "checkpoint_contents_from_archive",
+                "Number of checkpoint contents read from the archive because no peer had them",
+                registry
+            )
+            .unwrap(),
         }
         .pipe(Arc::new)
     }
@@ -46,10 +46,19 @@
 //! indicating that a new checkpoint has been fully downloaded. Notifications on this broadcast
 //! channel will always be made in order. StateSync will also send out a notification to its peers
 //! of the newly synchronized checkpoint so that it can help other peers synchronize.
+//!
+//! Peers may have pruned the oldest checkpoints, in which case a node syncing from far behind
+//! cannot get them from any peer. If a [CheckpointArchive] is configured, StateSync falls back to
+//! reading the checkpoints and their contents that no peer could provide from the archive. They
+//! are verified in the same way as checkpoints received from peers.
+//!
+//! The peers that can provide a checkpoint are tried in a random order, or from the best to the
+//! worst quality score when a [PeerQuality] is configured.
 
 use anemo::{types::PeerEvent, PeerId, Request, Response, Result};
 use anyhow::anyhow;
 use futures::{FutureExt, StreamExt};
+use narwhal_network::peer_quality::PeerQuality;
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -57,7 +66,7 @@ use std::{
 };
 use sui_config::p2p::StateSyncConfig;
 use sui_types::{
-    digests::{CheckpointContentsDigest, CheckpointDigest},
+    digests::CheckpointDigest,
     messages_checkpoint::{
         CertifiedCheckpointSummary as Checkpoint, CheckpointSequenceNumber, FullCheckpointContents,
         VerifiedCheckpoint, VerifiedCheckpointContents,
@@ -75,6 +84,7 @@ use tracing::{debug, info, trace, warn};
 mod generated {
     include!(concat!(env!("OUT_DIR"), "/sui.StateSync.rs"));
 }
+mod archive;
 mod builder;
 mod metrics;
 mod server;
@@ -82,12 +92,13 @@ pub mod test_utils;
 #[cfg(test)]
 mod tests;
 
+pub use archive::CheckpointArchive;
 pub use builder::{Builder, UnstartedStateSync};
 pub use generated::{
     state_sync_client::StateSyncClient,
     state_sync_server::{StateSync, StateSyncServer},
 };
-pub use server::GetCheckpointSummaryRequest;
+pub use server::{GetCheckpointSummaryRequest, TransactionOutputs};
 
 use self::metrics::Metrics;
 
@@ -99,6 +110,7 @@ use self::metrics::Metrics;
 pub struct Handle {
     sender: mpsc::Sender<StateSyncMessage>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    peer_heights: Arc<RwLock<PeerHeights>>,
 }
 
 impl Handle {
@@ -121,8 +133,19 @@ impl Handle {
     pub fn subscribe_to_synced_checkpoints(&self) -> broadcast::Receiver<VerifiedCheckpoint> {
         self.checkpoint_event_sender.subscribe()
     }
+
+    /// The lowest checkpoint known to be held by a peer on the same chain as us. Peers resume
+    /// syncing from the checkpoint following it, so history past this point may still be fetched
+    /// from us. Returns `None` if no such peer is known.
+    pub fn lowest_peer_checkpoint_sequence_number(&self) -> Option<CheckpointSequenceNumber> {
+        self.peer_heights
+            .read()
+            .unwrap()
+            .lowest_known_checkpoint_sequence_number()
+    }
 }
 
+#[derive(Debug)]
 struct PeerHeights {
     /// Table used to track the highest checkpoint for each of our peers.
     peers: HashMap<PeerId, PeerStateSyncInfo>,
@@ -154,6 +177,12 @@ impl PeerHeights {
             .max()
     }
 
+    pub fn lowest_known_checkpoint_sequence_number(&self) -> Option<CheckpointSequenceNumber> {
+        self.peers_on_same_chain()
+            .map(|(_, info)| info.height)
+            .min()
+    }
+
     pub fn peers_on_same_chain(&self) -> impl Iterator<Item = (&PeerId, &PeerStateSyncInfo)> {
         self.peers
             .iter()
@@ -265,6 +294,8 @@ struct StateSyncEventLoop<S> {
     peer_heights: Arc<RwLock<PeerHeights>>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
     network: anemo::Network,
+    archive: Option<Arc<dyn CheckpointArchive>>,
+    peer_quality: Option<Arc<PeerQuality>>,
     metrics: Metrics,
 }
 
@@ -519,6 +550,8 @@ where
                 self.network.clone(),
                 self.store.clone(),
                 self.peer_heights.clone(),
+                self.archive.clone(),
+                self.peer_quality.clone(),
                 self.metrics.clone(),
                 self.config.checkpoint_header_download_concurrency(),
                 self.config.timeout(),
@@ -553,13 +586,14 @@ where
 
         if highest_verified_checkpoint.sequence_number()
             > highest_synced_checkpoint.sequence_number()
-            // skip if we aren't connected to any peers that can help
-            && self
-                .peer_heights
-                .read()
-                .unwrap()
-                .highest_known_checkpoint_sequence_number()
-                > Some(*highest_synced_checkpoint.sequence_number())
+            // skip if we aren't connected to any peers that can help, unless the archive can
+            && (self.archive.is_some()
+                || self
+                    .peer_heights
+                    .read()
+                    .unwrap()
+                    .highest_known_checkpoint_sequence_number()
+                    > Some(*highest_synced_checkpoint.sequence_number()))
         {
             let task = sync_checkpoint_contents(
                 self.network.clone(),
@@ -567,6 +601,8 @@ where
                 self.peer_heights.clone(),
                 self.weak_sender.clone(),
                 self.checkpoint_event_sender.clone(),
+                self.archive.clone(),
+                self.peer_quality.clone(),
                 self.metrics.clone(),
                 self.config.checkpoint_content_download_concurrency(),
                 self.config.checkpoint_content_timeout(),
@@ -759,6 +795,8 @@ async fn sync_to_checkpoint<S>(
     network: anemo::Network,
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
+    archive: Option<Arc<dyn CheckpointArchive>>,
+    peer_quality: Option<Arc<PeerQuality>>,
     metrics: Metrics,
     checkpoint_header_download_concurrency: usize,
     timeout: Duration,
@@ -805,7 +843,12 @@ where
                 .map(StateSyncClient::new)
                 .collect::<Vec<_>>();
             rand::seq::SliceRandom::shuffle(peers.as_mut_slice(), &mut rng);
+            if let Some(peer_quality) = &peer_quality {
+                peer_quality.sort_by_score(&mut peers, |peer| peer.inner().peer_id());
+            }
             let peer_heights = peer_heights.clone();
+            let archive = archive.clone();
+            let metrics = metrics.clone();
             async move {
                 if let Some(checkpoint) = peer_heights
                     .read()
@@ -842,6 +885,21 @@ where
                     }
                 }
 
+                // None of our peers could help, e.g. they have all pruned the checkpoint
+                if let Some(archive) = archive {
+                    if let Some(checkpoint) = archive
+                        .get_checkpoint_summary(next)
+                        .await
+                        .tap_err(|e| warn!("unable to read checkpoint {next} from archive: {e}"))
+                        .ok()
+                        .flatten()
+                        .filter(|checkpoint| *checkpoint.sequence_number() == next)
+                    {
+                        metrics.inc_checkpoint_summaries_from_archive();
+                        return (Some(checkpoint), next, None);
+                    }
+                }
+
                 (None, next, None)
             }
         })
@@ -854,7 +912,7 @@ where
         // Verify the checkpoint
         let checkpoint = {
             let checkpoint = maybe_checkpoint
-                .ok_or_else(|| anyhow::anyhow!("no peers were able to help sync"))?;
+                .ok_or_else(|| anyhow::anyhow!("no peers or archive were able to help sync"))?;
             match verify_checkpoint(&current, &store, checkpoint) {
                 Ok(verified_checkpoint) => verified_checkpoint,
                 Err(checkpoint) => {
@@ -964,6 +1022,8 @@ async fn sync_checkpoint_contents<S>(
     peer_heights: Arc<RwLock<PeerHeights>>,
     sender: mpsc::WeakSender<StateSyncMessage>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    archive: Option<Arc<dyn CheckpointArchive>>,
+    peer_quality: Option<Arc<PeerQuality>>,
     metrics: Metrics,
     checkpoint_content_download_concurrency: usize,
     timeout: Duration,
@@ -992,6 +1052,9 @@ async fn sync_checkpoint_contents<S>(
                 network.clone(),
                 &store,
                 peer_heights.clone(),
+                archive.clone(),
+                peer_quality.as_deref(),
+                &metrics,
                 timeout,
                 checkpoint,
             )
@@ -1035,6 +1098,9 @@ async fn sync_one_checkpoint_contents<S>(
     network: anemo::Network,
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
+    archive: Option<Arc<dyn CheckpointArchive>>,
+    peer_quality: Option<&PeerQuality>,
+    metrics: &Metrics,
     timeout: Duration,
     checkpoint: VerifiedCheckpoint,
 ) -> Result<(VerifiedCheckpoint, u64)>
@@ -1055,8 +1121,11 @@ where
         .map(StateSyncClient::new)
         .collect::<Vec<_>>();
     rand::seq::SliceRandom::shuffle(peers.as_mut_slice(), &mut rng);
+    if let Some(peer_quality) = peer_quality {
+        peer_quality.sort_by_score(&mut peers, |peer| peer.inner().peer_id());
+    }
 
-    let Some(contents) = get_full_checkpoint_contents(&mut peers, &store, checkpoint.content_digest, timeout).await else {
+    let Some(contents) = get_full_checkpoint_contents(&mut peers, &store, archive.as_deref(), metrics, &checkpoint, timeout).await else {
         return Err(anyhow!("unable to sync checkpoint contents for checkpoint {}", checkpoint.sequence_number()));
     };
 
@@ -1068,13 +1137,16 @@ where
 async fn get_full_checkpoint_contents<S>(
     peers: &mut [StateSyncClient<anemo::Peer>],
     store: S,
-    digest: CheckpointContentsDigest,
+    archive: Option<&dyn CheckpointArchive>,
+    metrics: &Metrics,
+    checkpoint: &VerifiedCheckpoint,
     timeout: Duration,
 ) -> Option<FullCheckpointContents>
 where
     S: WriteStore,
     <S as ReadStore>::Error: std::error::Error,
 {
+    let digest = checkpoint.content_digest;
     if let Some(contents) = store
         .get_full_checkpoint_contents(&digest)
         .expect("store operation should not fail")
@@ -1104,5 +1176,24 @@ where
         }
     }
 
-    None
+    // None of our peers could help, e.g. they have all pruned the checkpoint
+    let sequence_number = *checkpoint.sequence_number();
+    let contents = archive?
+        .get_checkpoint_contents(sequence_number)
+        .await
+        .tap_err(|e| {
+            warn!("unable to read contents of checkpoint {sequence_number} from archive: {e}")
+        })
+        .ok()
+        .flatten()?;
+    if contents.verify_digests(digest).is_err() {
+        warn!("contents of checkpoint {sequence_number} in archive do not match its digest");
+        return None;
+    }
+    metrics.inc_checkpoint_contents_from_archive();
+    let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
+    store
+        .insert_checkpoint_contents(verified_contents)
+        .expect("store operation should not fail");
+    Some(contents)
 }
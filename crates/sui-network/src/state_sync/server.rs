@@ -6,11 +6,13 @@ use anemo::{rpc::Status, Request, Response, Result};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 use sui_types::{
-    digests::{CheckpointContentsDigest, CheckpointDigest},
+    digests::{CheckpointContentsDigest, CheckpointDigest, TransactionEffectsDigest},
+    messages::{TransactionEffectsAPI, TransactionEvents},
     messages_checkpoint::{
         CertifiedCheckpointSummary as Checkpoint, CheckpointSequenceNumber, FullCheckpointContents,
         VerifiedCheckpoint,
     },
+    object::Object,
     storage::ReadStore,
     storage::WriteStore,
 };
@@ -23,6 +25,14 @@ pub enum GetCheckpointSummaryRequest {
     BySequenceNumber(CheckpointSequenceNumber),
 }
 
+/// The outputs of an executed transaction, which a node can apply in place of executing it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionOutputs {
+    pub events: TransactionEvents,
+    /// The created, mutated and unwrapped objects, at their versions after the transaction.
+    pub objects: Vec<Object>,
+}
+
 pub(super) struct Server<S> {
     pub(super) store: S,
     pub(super) peer_heights: Arc<RwLock<PeerHeights>>,
@@ -103,4 +113,44 @@ where
             .map_err(|e| Status::internal(e.to_string()))?;
         Ok(Response::new(contents))
     }
+
+    async fn get_transaction_outputs(
+        &self,
+        request: Request<TransactionEffectsDigest>,
+    ) -> Result<Response<Option<TransactionOutputs>>, Status> {
+        let Some(effects) = self
+            .store
+            .get_transaction_effects(request.inner())
+            .map_err(|e| Status::internal(e.to_string()))?
+        else {
+            return Ok(Response::new(None));
+        };
+
+        let events = match effects.events_digest() {
+            Some(digest) => match self
+                .store
+                .get_transaction_events(digest)
+                .map_err(|e| Status::internal(e.to_string()))?
+            {
+                Some(events) => events,
+                None => return Ok(Response::new(None)),
+            },
+            None => TransactionEvents::default(),
+        };
+
+        // Objects are only available once this node has executed the transaction.
+        let mut objects = Vec::new();
+        for ((object_id, version, _), _, _) in effects.all_changed_objects() {
+            match self
+                .store
+                .get_object_by_key(object_id, *version)
+                .map_err(|e| Status::internal(e.to_string()))?
+            {
+                Some(object) => objects.push(object),
+                None => return Ok(Response::new(None)),
+            }
+        }
+
+        Ok(Response::new(Some(TransactionOutputs { events, objects })))
+    }
 }
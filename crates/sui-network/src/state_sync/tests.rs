@@ -4,15 +4,23 @@
 use crate::{
     state_sync::{
         test_utils::{empty_contents, CommitteeFixture},
-        Builder, GetCheckpointSummaryRequest, PeerStateSyncInfo, StateSync, StateSyncMessage,
-        UnstartedStateSync,
+        Builder, CheckpointArchive, GetCheckpointSummaryRequest, PeerStateSyncInfo, StateSync,
+        StateSyncMessage, UnstartedStateSync,
     },
     utils::build_network,
 };
 use anemo::{PeerId, Request};
-use std::{collections::HashMap, time::Duration};
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use sui_types::{
-    messages_checkpoint::CheckpointDigest,
+    base_types::{ExecutionData, ObjectID},
+    message_envelope::Message,
+    messages::{TransactionEffects, TransactionEffectsV1, VerifiedTransaction},
+    messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointDigest, CheckpointSequenceNumber,
+        FullCheckpointContents, VerifiedCheckpoint, VerifiedCheckpointContents,
+    },
+    object::Object,
     storage::{ReadStore, SharedInMemoryStore, WriteStore},
 };
 use tokio::time::timeout;
@@ -169,6 +177,55 @@ async fn server_get_checkpoint() {
     }
 }
 
+#[tokio::test]
+async fn server_get_transaction_outputs() {
+    let (builder, server) = Builder::new()
+        .store(SharedInMemoryStore::default())
+        .build_internal();
+
+    let object = Object::immutable_with_id_for_testing(ObjectID::random());
+    let transaction = VerifiedTransaction::new_genesis_transaction(vec![]);
+    let effects = TransactionEffects::V1(TransactionEffectsV1 {
+        transaction_digest: *transaction.digest(),
+        created: vec![(object.compute_object_reference(), object.owner)],
+        ..Default::default()
+    });
+    let effects_digest = effects.digest();
+
+    // Unknown effects have no outputs
+    let response = server
+        .get_transaction_outputs(Request::new(effects_digest))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.is_none());
+
+    // Nor do effects whose objects the server doesn't have, e.g. not yet executed
+    builder.store.inner_mut().insert_checkpoint_contents(
+        VerifiedCheckpointContents::new_unchecked(
+            FullCheckpointContents::new_with_causally_ordered_transactions(std::iter::once(
+                ExecutionData::new(transaction.into_inner(), effects),
+            )),
+        ),
+    );
+    let response = server
+        .get_transaction_outputs(Request::new(effects_digest))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.is_none());
+
+    builder.store.inner_mut().insert_object(object.clone());
+    let outputs = server
+        .get_transaction_outputs(Request::new(effects_digest))
+        .await
+        .unwrap()
+        .into_inner()
+        .unwrap();
+    assert!(outputs.events.data.is_empty());
+    assert_eq!(outputs.objects, vec![object]);
+}
+
 #[tokio::test]
 async fn isolated_sync_job() {
     let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
@@ -380,3 +437,92 @@ async fn sync_with_checkpoints_being_inserted() {
         &sequence_number_to_digest
     );
 }
+
+struct TestArchive(HashMap<CheckpointSequenceNumber, VerifiedCheckpoint>);
+
+#[async_trait]
+impl CheckpointArchive for TestArchive {
+    async fn get_checkpoint_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<CertifiedCheckpointSummary>> {
+        Ok(self
+            .0
+            .get(&sequence_number)
+            .map(|checkpoint| checkpoint.clone().into_inner()))
+    }
+
+    async fn get_checkpoint_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<FullCheckpointContents>> {
+        Ok(self
+            .0
+            .contains_key(&sequence_number)
+            .then(|| empty_contents().into_inner()))
+    }
+}
+
+#[tokio::test]
+async fn sync_from_archive() {
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (ordered_checkpoints, _, _) = committee.make_checkpoints(10, None);
+    let genesis = ordered_checkpoints.first().cloned().unwrap();
+    let last = ordered_checkpoints.last().cloned().unwrap();
+    // Checkpoints following our genesis, but not signed by our committee
+    let other_committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (forged_checkpoints, _, _) = other_committee.make_checkpoints(9, Some(genesis.clone()));
+
+    for (checkpoints, synced) in [(&ordered_checkpoints, true), (&forged_checkpoints, false)] {
+        let archive = checkpoints
+            .iter()
+            .map(|checkpoint| (*checkpoint.sequence_number(), checkpoint.clone()))
+            .collect();
+        let (builder, _server) = Builder::new()
+            .store(SharedInMemoryStore::default())
+            .archive(Arc::new(TestArchive(archive)))
+            .build();
+        let network = build_network(|router| router);
+        let (mut event_loop, _handle) = builder.build(network);
+        event_loop.store.inner_mut().insert_genesis_state(
+            genesis.clone(),
+            empty_contents(),
+            committee.committee().to_owned(),
+        );
+
+        // A peer that has pruned every checkpoint it knows of
+        event_loop.peer_heights.write().unwrap().peers.insert(
+            PeerId([9; 32]),
+            PeerStateSyncInfo {
+                genesis_checkpoint_digest: *genesis.digest(),
+                on_same_chain_as_us: true,
+                height: *last.sequence_number(),
+            },
+        );
+        event_loop
+            .peer_heights
+            .write()
+            .unwrap()
+            .insert_checkpoint(last.clone().into_inner());
+
+        event_loop.maybe_start_checkpoint_summary_sync_task();
+        event_loop.tasks.join_next().await.unwrap().unwrap();
+        let highest_verified = event_loop.store.get_highest_verified_checkpoint().unwrap();
+        if !synced {
+            assert_eq!(highest_verified.digest(), genesis.digest());
+            continue;
+        }
+        assert_eq!(highest_verified.digest(), last.digest());
+
+        event_loop.maybe_start_checkpoint_contents_sync_task();
+        event_loop.tasks.join_next().await.unwrap().unwrap();
+        assert_eq!(
+            event_loop
+                .store
+                .get_highest_synced_checkpoint()
+                .unwrap()
+                .digest(),
+            last.digest()
+        );
+    }
+}
@@ -1,23 +1,55 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::endpoint_auth;
+use crate::logging;
 use crate::SuiNode;
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     routing::{get, post},
     Router,
 };
-use mysten_metrics::spawn_monitored_task;
 use serde::Deserialize;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+use sui_config::transaction_deny_config::TransactionDenyConfig;
+use sui_core::slow_ops::SlowOpKind;
+use sui_core::validator_duty::estimate_maintenance_window_in_epoch;
+use sui_framework::natives::execution_profile;
 use sui_types::error::SuiError;
-use telemetry_subscribers::FilterHandle;
+use telemetry_subscribers::TracingHandle;
 use tracing::info;
 
 // Example commands:
 //
+// Set the log filter, e.g. to log the debug events of a module:
+//
+//   $ curl -X POST -d 'info,sui_core::authority=debug' 'http://127.0.0.1:1337/logging'
+//
+// Reload the levels and sampling rules of the `logging` section of the node config file, after
+// editing it:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/logging/reload'
+//
+// Set the filter of the spans and events exported over OTLP and recorded by chrome trace
+// captures, independently from the log filter:
+//
+//   $ curl -X POST -d 'info,sui_core=debug,narwhal_primary=debug' 'http://127.0.0.1:1337/tracing'
+//
+// Record the spans and events passing the trace filter for 10 seconds, in the Chrome trace event
+// format (load the output into chrome://tracing or https://ui.perfetto.dev):
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/chrome-trace?duration_secs=10' -o trace.json
+//
+// Start the tokio-console server and record the data it needs, or stop recording. Task data is
+// only available if the node was built with RUSTFLAGS="--cfg tokio_unstable":
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/tokio-console?enabled=true'
+//
 // Set buffer stake for current epoch 2 to 1500 basis points:
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/set-override-buffer-stake?buffer_bps=1500&epoch=2'
@@ -34,30 +66,146 @@ use tracing::info;
 // View current all capabilities from all authorities that have been received by this node:
 //
 //   $ curl 'http://127.0.0.1:1337/capabilities'
+//
+// View the transaction deny config currently applied by this node:
+//
+//   $ curl 'http://127.0.0.1:1337/transaction-deny-config'
+//
+// Replace the transaction deny config, without a restart. The override is persisted and survives
+// restarts until it is cleared. Requires `admin-interface-auth-token` to be set in the node config:
+//
+//   $ curl -X POST -H 'Authorization: Bearer <token>' \
+//       -d '{"address-deny-list": ["0x..."], "package-deny-list": [], "object-deny-list": []}' \
+//       'http://127.0.0.1:1337/transaction-deny-config'
+//
+// Clear the override and revert to the transaction deny config of the node config:
+//
+//   $ curl -X POST -H 'Authorization: Bearer <token>' \
+//       'http://127.0.0.1:1337/clear-transaction-deny-config'
+//
+// Start attributing Move execution time to the interpreter, native functions and object runtime
+// operations (this resets the accumulated profile), or stop it:
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/execution-profile?enabled=true'
+//
+// View the execution time profile accumulated since profiling started:
+//
+//   $ curl 'http://127.0.0.1:1337/execution-profile'
+//
+// Simulate the reconfiguration at the end of the current epoch, to check that this node supports
+// the next protocol version and has its system packages, and whether it is in the next committee:
+//
+//   $ curl 'http://127.0.0.1:1337/epoch-change-dry-run'
+//
+// View the transactions deferred by shared object congestion control on this validator, the
+// objects they are waiting for, and the number of commits until they are expected to be admitted:
+//
+//   $ curl 'http://127.0.0.1:1337/deferred-transactions'
+//
+// Estimate the consensus rounds this validator is expected to lead during a maintenance window
+// of one hour starting at the given unix time in milliseconds, and the impact of being offline
+// during the window on its reputation score:
+//
+//   $ curl 'http://127.0.0.1:1337/maintenance-window?start_ms=1700000000000&duration_ms=3600000'
+//
+// Check whether a quorum of the committee is reachable from this validator, with the connection
+// status and the age of the last certificate committed by consensus of each authority. The
+// diagnosis tells a fault local to this validator (`Isolated`) from a network partition
+// (`Partitioned`):
+//
+//   $ curl 'http://127.0.0.1:1337/quorum-reachability'
+//
+// View the most recent RPC calls, certificate executions and consensus commits that took longer
+// than their `slow-op-config` threshold, with the time spent in each of their phases. Filter by
+// kind with `kind=rpc`, `kind=execution` or `kind=consensus_commit`:
+//
+//   $ curl 'http://127.0.0.1:1337/slow-ops?kind=execution'
+//
+// With `admin-interface-auth` in the node config, the interface can be served over TLS, require
+// client certificates, and require a bearer token on every request (in addition to
+// `admin-interface-auth-token` on the endpoints that need it):
+//
+//   $ curl --cacert ca.pem --cert client.pem --key client.key -H 'Authorization: Bearer <token>' \
+//       'https://127.0.0.1:1337/capabilities'
 
 const LOGGING_ROUTE: &str = "/logging";
+const RELOAD_LOGGING_ROUTE: &str = "/logging/reload";
+const TRACING_ROUTE: &str = "/tracing";
+const CHROME_TRACE_ROUTE: &str = "/chrome-trace";
+const TOKIO_CONSOLE_ROUTE: &str = "/tokio-console";
 const SET_BUFFER_STAKE_ROUTE: &str = "/set-override-buffer-stake";
 const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
+const TRANSACTION_DENY_CONFIG: &str = "/transaction-deny-config";
+const CLEAR_TRANSACTION_DENY_CONFIG: &str = "/clear-transaction-deny-config";
+const EXECUTION_PROFILE: &str = "/execution-profile";
+const EPOCH_CHANGE_DRY_RUN: &str = "/epoch-change-dry-run";
+const DEFERRED_TRANSACTIONS: &str = "/deferred-transactions";
+const MAINTENANCE_WINDOW: &str = "/maintenance-window";
+const QUORUM_REACHABILITY: &str = "/quorum-reachability";
+const SLOW_OPS: &str = "/slow-ops";
+
+const MAX_CHROME_TRACE_DURATION: Duration = Duration::from_secs(60);
 
 struct AppState {
     node: Arc<SuiNode>,
-    filter_handle: FilterHandle,
+    tracing_handle: TracingHandle,
+    config_path: PathBuf,
+    auth_token: Option<String>,
 }
 
-pub fn start_admin_server(node: Arc<SuiNode>, port: u16, filter_handle: FilterHandle) {
-    let filter = filter_handle.get().unwrap();
+impl AppState {
+    /// Checks the bearer token of a request to an endpoint that changes what the node accepts.
+    /// Such endpoints are disabled unless a token is configured.
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+        let Some(expected) = &self.auth_token else {
+            return Err((
+                StatusCode::FORBIDDEN,
+                "admin-interface-auth-token is not configured\n".to_string(),
+            ));
+        };
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        // Compared in constant time, so that the response time doesn't reveal the token.
+        let authorized = provided
+            .map(|provided| bool::from(provided.as_bytes().ct_eq(expected.as_bytes())))
+            .unwrap_or(false);
+        if !authorized {
+            return Err((StatusCode::UNAUTHORIZED, "invalid auth token\n".to_string()));
+        }
+        Ok(())
+    }
+}
+
+pub fn start_admin_server(
+    node: Arc<SuiNode>,
+    port: u16,
+    tracing_handle: TracingHandle,
+    config_path: PathBuf,
+) {
+    let filter = tracing_handle.get_log().unwrap();
 
+    let auth_token = node.config.admin_interface_auth_token.clone();
+    let auth_config = node.config.admin_interface_auth.clone();
     let app_state = AppState {
         node,
-        filter_handle,
+        tracing_handle,
+        config_path,
+        auth_token,
     };
 
     let app = Router::new()
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(LOGGING_ROUTE, post(set_filter))
+        .route(RELOAD_LOGGING_ROUTE, post(reload_logging_config))
+        .route(TRACING_ROUTE, get(get_trace_filter))
+        .route(TRACING_ROUTE, post(set_trace_filter))
+        .route(CHROME_TRACE_ROUTE, post(chrome_trace))
+        .route(TOKIO_CONSOLE_ROUTE, post(set_tokio_console))
         .route(
             SET_BUFFER_STAKE_ROUTE,
             post(set_override_protocol_upgrade_buffer_stake),
@@ -67,6 +215,19 @@ pub fn start_admin_server(node: Arc<SuiNode>, port: u16, filter_handle: FilterHa
             post(clear_override_protocol_upgrade_buffer_stake),
         )
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
+        .route(TRANSACTION_DENY_CONFIG, get(get_transaction_deny_config))
+        .route(TRANSACTION_DENY_CONFIG, post(set_transaction_deny_config))
+        .route(
+            CLEAR_TRANSACTION_DENY_CONFIG,
+            post(clear_transaction_deny_config),
+        )
+        .route(EXECUTION_PROFILE, get(get_execution_profile))
+        .route(EXECUTION_PROFILE, post(set_execution_profiling))
+        .route(EPOCH_CHANGE_DRY_RUN, get(epoch_change_dry_run))
+        .route(DEFERRED_TRANSACTIONS, get(deferred_transactions))
+        .route(MAINTENANCE_WINDOW, get(maintenance_window))
+        .route(QUORUM_REACHABILITY, get(quorum_reachability))
+        .route(SLOW_OPS, get(slow_ops))
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -76,16 +237,11 @@ pub fn start_admin_server(node: Arc<SuiNode>, port: u16, filter_handle: FilterHa
         "starting admin server"
     );
 
-    spawn_monitored_task!(async move {
-        axum::Server::bind(&socket_address)
-            .serve(app.into_make_service())
-            .await
-            .unwrap();
-    });
+    endpoint_auth::spawn_server(socket_address, app, auth_config.as_ref());
 }
 
 async fn get_filter(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
-    match state.filter_handle.get() {
+    match state.tracing_handle.get_log() {
         Ok(filter) => (StatusCode::OK, filter),
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
     }
@@ -95,7 +251,7 @@ async fn set_filter(
     State(state): State<Arc<AppState>>,
     new_filter: String,
 ) -> (StatusCode, String) {
-    match state.filter_handle.update(&new_filter) {
+    match state.tracing_handle.update_log(&new_filter) {
         Ok(()) => {
             info!(filter =% new_filter, "Log filter updated");
             (StatusCode::OK, "".into())
@@ -104,6 +260,79 @@ async fn set_filter(
     }
 }
 
+async fn reload_logging_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match logging::reload_logging_config(&state.config_path, &state.tracing_handle) {
+        Ok(()) => (StatusCode::OK, "logging config reloaded\n".to_string()),
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+    }
+}
+
+async fn get_trace_filter(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match state.tracing_handle.get_trace() {
+        Ok(filter) => (StatusCode::OK, filter),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn set_trace_filter(
+    State(state): State<Arc<AppState>>,
+    new_filter: String,
+) -> (StatusCode, String) {
+    match state.tracing_handle.update_trace(&new_filter) {
+        Ok(()) => {
+            info!(filter =% new_filter, "Trace filter updated");
+            (StatusCode::OK, "".into())
+        }
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct ChromeTrace {
+    duration_secs: u64,
+}
+
+async fn chrome_trace(
+    State(state): State<Arc<AppState>>,
+    chrome_trace: Query<ChromeTrace>,
+) -> (StatusCode, String) {
+    let Query(ChromeTrace { duration_secs }) = chrome_trace;
+    let duration = Duration::from_secs(duration_secs);
+    if duration > MAX_CHROME_TRACE_DURATION {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "duration_secs must be at most {}\n",
+                MAX_CHROME_TRACE_DURATION.as_secs()
+            ),
+        );
+    }
+
+    // The capture stops when it is dropped, if the request is cancelled before it finishes.
+    let capture = match state.tracing_handle.start_chrome_trace() {
+        Ok(capture) => capture,
+        Err(err) => return (StatusCode::CONFLICT, err.to_string()),
+    };
+    info!(?duration, "Chrome trace capture started");
+    tokio::time::sleep(duration).await;
+    (StatusCode::OK, capture.finish())
+}
+
+async fn set_tokio_console(
+    State(state): State<Arc<AppState>>,
+    enabled: Query<Enabled>,
+) -> (StatusCode, String) {
+    let Query(Enabled { enabled }) = enabled;
+    match state.tracing_handle.set_tokio_console(enabled) {
+        Ok(()) => {
+            let status = if enabled { "enabled" } else { "disabled" };
+            info!("tokio-console {}", status);
+            (StatusCode::OK, format!("tokio-console {}\n", status))
+        }
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
 async fn capabilities(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
     let epoch_store = state.node.state().load_epoch_store_one_call_per_task();
     let capabilities = epoch_store.get_capabilities();
@@ -188,3 +417,238 @@ async fn force_close_epoch(
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
     }
 }
+
+async fn deferred_transactions(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let epoch_store = state.node.state().load_epoch_store_one_call_per_task();
+    match serde_json::to_string_pretty(&epoch_store.get_deferral_queue_summary()) {
+        Ok(summary) => (StatusCode::OK, summary),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct MaintenanceWindow {
+    start_ms: u64,
+    duration_ms: u64,
+}
+
+async fn maintenance_window(
+    State(state): State<Arc<AppState>>,
+    window: Query<MaintenanceWindow>,
+) -> (StatusCode, String) {
+    let Query(MaintenanceWindow {
+        start_ms,
+        duration_ms,
+    }) = window;
+    let epoch_store = state.node.state().load_epoch_store_one_call_per_task();
+    let report = match estimate_maintenance_window_in_epoch(
+        &epoch_store,
+        state.node.state().name,
+        start_ms,
+        start_ms.saturating_add(duration_ms),
+    ) {
+        Ok(report) => report,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+    match serde_json::to_string_pretty(&report) {
+        Ok(report) => (StatusCode::OK, report),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn quorum_reachability(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match serde_json::to_string_pretty(&state.node.quorum_reachability()) {
+        Ok(report) => (StatusCode::OK, report),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct SlowOpsFilter {
+    kind: Option<String>,
+}
+
+async fn slow_ops(
+    State(state): State<Arc<AppState>>,
+    filter: Query<SlowOpsFilter>,
+) -> (StatusCode, String) {
+    let kind = match filter.0.kind.as_deref().map(SlowOpKind::parse) {
+        None => None,
+        Some(Some(kind)) => Some(kind),
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                "kind must be one of rpc, execution, consensus_commit\n".to_string(),
+            )
+        }
+    };
+    match serde_json::to_string_pretty(&state.node.state().slow_ops.entries(kind)) {
+        Ok(entries) => (StatusCode::OK, entries),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn get_transaction_deny_config(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    match serde_json::to_string_pretty(&*state.node.transaction_deny_config()) {
+        Ok(config) => (StatusCode::OK, config),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn set_transaction_deny_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: String,
+) -> (StatusCode, String) {
+    if let Err(err) = state.authorize(&headers) {
+        return err;
+    }
+    let config: TransactionDenyConfig = match serde_json::from_str(&body) {
+        Ok(config) => config,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()),
+    };
+
+    match state.node.set_transaction_deny_config_override(config) {
+        Ok(()) => (
+            StatusCode::OK,
+            "transaction deny config updated\n".to_string(),
+        ),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn clear_transaction_deny_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> (StatusCode, String) {
+    if let Err(err) = state.authorize(&headers) {
+        return err;
+    }
+
+    match state.node.clear_transaction_deny_config_override() {
+        Ok(()) => (
+            StatusCode::OK,
+            "transaction deny config override cleared\n".to_string(),
+        ),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+async fn get_execution_profile() -> (StatusCode, String) {
+    if !execution_profile::is_enabled() {
+        return (
+            StatusCode::OK,
+            "execution profiling is disabled\n".to_string(),
+        );
+    }
+    let profile = execution_profile::snapshot();
+    let share = |time: Duration| {
+        if profile.total.is_zero() {
+            0.0
+        } else {
+            100.0 * time.as_secs_f64() / profile.total.as_secs_f64()
+        }
+    };
+
+    let mut output = format!("transactions: {}\n", profile.transactions);
+    output.push_str(&format!("total: {:?}\n", profile.total));
+    for (name, time) in [
+        ("move interpreter", profile.move_interpreter()),
+        ("natives", profile.natives),
+        ("object runtime", profile.object_runtime),
+    ] {
+        output.push_str(&format!("{}: {:?} ({:.1}%)\n", name, time, share(time)));
+    }
+
+    let mut natives: Vec<_> = profile.native_functions.iter().collect();
+    natives.sort_by(|(_, a), (_, b)| b.time.cmp(&a.time));
+    output.push_str("native functions:\n");
+    for (name, native) in natives {
+        output.push_str(&format!(
+            "  {}: {} calls, {:?} ({:.1}%)\n",
+            name,
+            native.calls,
+            native.time,
+            share(native.time)
+        ));
+    }
+
+    (StatusCode::OK, output)
+}
+
+#[derive(Deserialize)]
+struct Enabled {
+    enabled: bool,
+}
+
+async fn set_execution_profiling(enabled: Query<Enabled>) -> (StatusCode, String) {
+    let Query(Enabled { enabled }) = enabled;
+    execution_profile::set_enabled(enabled);
+    let status = if enabled { "enabled" } else { "disabled" };
+    (StatusCode::OK, format!("execution profiling {}\n", status))
+}
+
+async fn epoch_change_dry_run(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let dry_run = match state.node.dry_run_epoch_change().await {
+        Ok(dry_run) => dry_run,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    };
+    let name = state.node.state().name;
+
+    let mut output = format!("next epoch: {}\n", dry_run.next_epoch);
+    output.push_str(&format!(
+        "protocol version: {:?} -> {:?}\n",
+        dry_run.current_protocol_version, dry_run.next_protocol_version
+    ));
+    output.push_str(&format!(
+        "system packages: {:?}\n",
+        dry_run.next_system_packages
+    ));
+    output.push_str(&format!("safe mode: {}\n", dry_run.safe_mode));
+    output.push_str(&format!(
+        "next committee: {} validators, total stake {}\n",
+        dry_run.next_committee.num_members(),
+        dry_run.next_committee.total_votes
+    ));
+    for (authority, stake) in &dry_run.next_committee.voting_rights {
+        output.push_str(&format!("  {}: {}\n", authority.concise(), stake));
+    }
+
+    let mut warnings = vec![];
+    if !dry_run.next_protocol_version_supported {
+        warnings.push(format!(
+            "this binary does not support protocol version {:?}, upgrade it before the epoch ends",
+            dry_run.next_protocol_version
+        ));
+    }
+    if !dry_run.next_system_packages_available {
+        warnings.push(
+            "this binary does not have the system packages of the next epoch, upgrade it before \
+            the epoch ends"
+                .to_string(),
+        );
+    }
+    if dry_run.safe_mode {
+        warnings.push("the change epoch transaction would fail and enter safe mode".to_string());
+    }
+    let in_current_committee = state
+        .node
+        .state()
+        .load_epoch_store_one_call_per_task()
+        .committee()
+        .authority_exists(&name);
+    match (in_current_committee, dry_run.is_in_next_committee(&name)) {
+        (true, false) => {
+            warnings.push("this node leaves the committee in the next epoch".to_string())
+        }
+        (false, true) => {
+            warnings.push("this node joins the committee in the next epoch".to_string())
+        }
+        _ => (),
+    }
+    for warning in warnings {
+        output.push_str(&format!("warning: {}\n", warning));
+    }
+
+    (StatusCode::OK, output)
+}
@@ -0,0 +1,179 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! TLS, client certificate and bearer token authentication for the HTTP endpoints of the node
+//! that are not meant to be public: the admin interface and the metrics endpoint.
+
+use anyhow::{anyhow, Context};
+use axum::{
+    extract::State,
+    http::{header, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use mysten_metrics::spawn_monitored_task;
+use std::fs;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use subtle::ConstantTimeEq;
+use sui_config::node::{EndpointAuthConfig, EndpointTlsConfig};
+use sui_tls::rustls;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Spawns a server for `app` on `address`, over TLS and requiring a bearer token as configured.
+/// Panics if the TLS certificates cannot be loaded, rather than serving the endpoint unprotected.
+pub fn spawn_server(
+    address: SocketAddr,
+    app: Router,
+    config: Option<&EndpointAuthConfig>,
+) -> JoinHandle<()> {
+    let config = config.cloned().unwrap_or_default();
+    let app = match config.auth_token {
+        Some(token) => app.layer(middleware::from_fn_with_state(
+            Arc::new(token),
+            require_auth_token,
+        )),
+        None => app,
+    };
+
+    let Some(tls_config) = config.tls else {
+        return spawn_monitored_task!(async move {
+            axum::Server::bind(&address)
+                .serve(app.into_make_service())
+                .await
+                .unwrap();
+        });
+    };
+    let server_config = load_server_config(&tls_config)
+        .unwrap_or_else(|err| panic!("Failed to load TLS certificates of {address}: {err:?}"));
+    let rustls_config = RustlsConfig::from_config(Arc::new(server_config));
+    spawn_monitored_task!(reload_on_change(tls_config, rustls_config.clone()));
+    spawn_monitored_task!(async move {
+        axum_server::bind(address)
+            .acceptor(RustlsAcceptor::new(rustls_config))
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+    })
+}
+
+async fn require_auth_token<B>(
+    State(token): State<Arc<String>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    // Compared in constant time, so that the response time doesn't reveal the token.
+    let authorized = provided
+        .map(|provided| bool::from(provided.as_bytes().ct_eq(token.as_bytes())))
+        .unwrap_or(false);
+    if !authorized {
+        return (StatusCode::UNAUTHORIZED, "invalid auth token\n").into_response();
+    }
+    next.run(request).await
+}
+
+fn load_server_config(config: &EndpointTlsConfig) -> anyhow::Result<rustls::ServerConfig> {
+    let certificates = load_certificates(&config.certificate_path)?;
+    let private_key = load_private_key(&config.private_key_path)?;
+    let builder = rustls::ServerConfig::builder().with_safe_defaults();
+    let builder = match &config.client_ca_certificate_path {
+        Some(path) => {
+            let mut roots = rustls::RootCertStore::empty();
+            for certificate in load_certificates(path)? {
+                roots.add(&certificate).map_err(|err| {
+                    anyhow!("Invalid CA certificate in {}: {err:?}", path.display())
+                })?;
+            }
+            builder
+                .with_client_cert_verifier(rustls::server::AllowAnyAuthenticatedClient::new(roots))
+        }
+        None => builder.with_no_client_auth(),
+    };
+    Ok(builder.with_single_cert(certificates, private_key)?)
+}
+
+fn load_certificates(path: &Path) -> anyhow::Result<Vec<rustls::Certificate>> {
+    let file = fs::File::open(path).with_context(|| format!("Cannot open {}", path.display()))?;
+    let certificates: Vec<_> = rustls_pemfile::certs(&mut BufReader::new(file))
+        .with_context(|| format!("Cannot parse certificates in {}", path.display()))?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+    if certificates.is_empty() {
+        return Err(anyhow!("No certificates found in {}", path.display()));
+    }
+    Ok(certificates)
+}
+
+fn load_private_key(path: &Path) -> anyhow::Result<rustls::PrivateKey> {
+    let file = fs::File::open(path).with_context(|| format!("Cannot open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    while let Some(item) = rustls_pemfile::read_one(&mut reader)
+        .with_context(|| format!("Cannot parse private key in {}", path.display()))?
+    {
+        match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(rustls::PrivateKey(key)),
+            _ => {}
+        }
+    }
+    Err(anyhow!(
+        "No private key found in {} (encrypted keys are not supported)",
+        path.display()
+    ))
+}
+
+/// Reloads the certificates whenever one of their files changes. A rotation that leaves the files
+/// in an invalid state keeps the current certificates until the files are valid again.
+async fn reload_on_change(config: EndpointTlsConfig, rustls_config: RustlsConfig) {
+    let mut interval = tokio::time::interval(Duration::from_secs(config.reload_interval_secs));
+    let mut loaded = modification_times(&config);
+    loop {
+        interval.tick().await;
+        let current = modification_times(&config);
+        if current == loaded {
+            continue;
+        }
+        match load_server_config(&config) {
+            Ok(server_config) => {
+                rustls_config.reload_from_config(Arc::new(server_config));
+                info!(
+                    "Reloaded TLS certificate {}",
+                    config.certificate_path.display()
+                );
+                loaded = current;
+            }
+            Err(err) => {
+                warn!("Failed to reload TLS certificates, keeping the current ones: {err:?}")
+            }
+        }
+    }
+}
+
+fn modification_times(config: &EndpointTlsConfig) -> Vec<Option<SystemTime>> {
+    [
+        Some(&config.certificate_path),
+        Some(&config.private_key_path),
+        config.client_ca_certificate_path.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    })
+    .collect()
+}
@@ -0,0 +1,316 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Health endpoints for load balancers and orchestrators such as Kubernetes.
+//!
+//! `/health/live` answers as long as the node is running, and is meant for liveness probes that
+//! restart the process. `/health/ready` tells whether the node is keeping up with the network, and
+//! answers `503 Service Unavailable` when one of its checks fails, so that a lagging fullnode is
+//! taken out of rotation until it catches up. The checks are:
+//!
+//!  * `checkpoint_lag`: checkpoints synced from peers but not executed yet.
+//!  * `checkpoint_age`: seconds since the highest executed checkpoint was created, which grows
+//!    when the node stops syncing altogether.
+//!  * `consensus_stall`: seconds since the round committed by consensus last advanced, on
+//!    validators only.
+//!  * `db_write_stopped`: column families whose writes are stopped by RocksDB, e.g. because
+//!    compactions cannot keep up.
+//!
+//! Example commands:
+//!
+//!   $ curl 'http://127.0.0.1:9185/health/live'
+//!   $ curl 'http://127.0.0.1:9185/health/ready'
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use mysten_metrics::spawn_monitored_task;
+use prometheus::core::Collector;
+use prometheus::IntGaugeVec;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use sui_config::node::HealthCheckConfig;
+use sui_core::authority::AuthorityState;
+use sui_core::checkpoints::CheckpointStore;
+use sui_types::committee::EpochId;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use typed_store::metrics::DBMetrics;
+
+const LIVE_ROUTE: &str = "/health/live";
+const READY_ROUTE: &str = "/health/ready";
+
+#[derive(Debug, Serialize)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub healthy: bool,
+    /// Measured value, absent when it could not be measured, which fails the check.
+    pub value: Option<u64>,
+    pub threshold: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+impl HealthCheck {
+    fn at_most(name: &'static str, value: Option<u64>, threshold: u64) -> Self {
+        Self {
+            name,
+            healthy: value.map_or(false, |value| value <= threshold),
+            value,
+            threshold,
+            details: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LivenessReport {
+    pub live: bool,
+    pub epoch: EpochId,
+    pub uptime_secs: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub epoch: EpochId,
+    pub highest_executed_checkpoint: Option<CheckpointSequenceNumber>,
+    pub highest_synced_checkpoint: Option<CheckpointSequenceNumber>,
+    pub checks: Vec<HealthCheck>,
+}
+
+/// Tracks when the round committed by consensus last changed. Progress is only observed when the
+/// node is probed, so a stall is measured from the first probe that saw the current round.
+#[derive(Default)]
+struct RoundProgress {
+    last: Option<(u64, Instant)>,
+}
+
+impl RoundProgress {
+    /// Records the current round, and returns the time since it last changed.
+    fn observe(&mut self, round: u64, now: Instant) -> Duration {
+        match self.last {
+            Some((last_round, since)) if last_round == round => now.duration_since(since),
+            _ => {
+                self.last = Some((round, now));
+                Duration::ZERO
+            }
+        }
+    }
+}
+
+pub struct HealthChecker {
+    state: Arc<AuthorityState>,
+    checkpoint_store: Arc<CheckpointStore>,
+    config: HealthCheckConfig,
+    start_time: Instant,
+    consensus_progress: Mutex<RoundProgress>,
+}
+
+impl HealthChecker {
+    pub fn new(
+        state: Arc<AuthorityState>,
+        checkpoint_store: Arc<CheckpointStore>,
+        config: HealthCheckConfig,
+    ) -> Self {
+        Self {
+            state,
+            checkpoint_store,
+            config,
+            start_time: Instant::now(),
+            consensus_progress: Mutex::new(RoundProgress::default()),
+        }
+    }
+
+    pub fn liveness(&self) -> LivenessReport {
+        LivenessReport {
+            live: true,
+            epoch: self.state.load_epoch_store_one_call_per_task().epoch(),
+            uptime_secs: self.start_time.elapsed().as_secs(),
+        }
+    }
+
+    pub fn readiness(&self) -> ReadinessReport {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        let highest_executed = self
+            .checkpoint_store
+            .get_highest_executed_checkpoint_seq_number()
+            .unwrap_or_else(|err| {
+                warn!("Failed to read the highest executed checkpoint: {err}");
+                None
+            });
+        let highest_synced = self
+            .checkpoint_store
+            .get_highest_synced_checkpoint_seq_number()
+            .unwrap_or_else(|err| {
+                warn!("Failed to read the highest synced checkpoint: {err}");
+                None
+            });
+
+        let mut checks = vec![];
+
+        // A node that has not synced any checkpoint has nothing to execute either.
+        let lag = match (highest_synced, highest_executed) {
+            (Some(synced), Some(executed)) => Some(synced.saturating_sub(executed)),
+            (Some(synced), None) => Some(synced + 1),
+            (None, _) => Some(0),
+        };
+        checks.push(HealthCheck::at_most(
+            "checkpoint_lag",
+            lag,
+            self.config.max_checkpoint_lag,
+        ));
+
+        let age = highest_executed
+            .and_then(|seq| {
+                self.checkpoint_store
+                    .get_checkpoint_by_sequence_number(seq)
+                    .ok()
+                    .flatten()
+            })
+            .map(|checkpoint| {
+                SystemTime::now()
+                    .duration_since(checkpoint.timestamp())
+                    .unwrap_or_default()
+                    .as_secs()
+            });
+        checks.push(HealthCheck::at_most(
+            "checkpoint_age",
+            age,
+            self.config.max_checkpoint_age_secs,
+        ));
+
+        if self.state.is_validator(&epoch_store) {
+            let stall = epoch_store
+                .get_last_consensus_index()
+                .map(|index| {
+                    self.consensus_progress
+                        .lock()
+                        .unwrap()
+                        .observe(index.index.last_committed_round, Instant::now())
+                        .as_secs()
+                })
+                .ok();
+            checks.push(HealthCheck::at_most(
+                "consensus_stall",
+                stall,
+                self.config.max_consensus_stall_secs,
+            ));
+        }
+
+        let stopped =
+            stopped_column_families(&DBMetrics::get().cf_metrics.rocksdb_is_write_stopped);
+        let mut write_stopped =
+            HealthCheck::at_most("db_write_stopped", Some(stopped.len() as u64), 0);
+        write_stopped.details = stopped;
+        checks.push(write_stopped);
+
+        ReadinessReport {
+            ready: checks.iter().all(|check| check.healthy),
+            epoch: epoch_store.epoch(),
+            highest_executed_checkpoint: highest_executed,
+            highest_synced_checkpoint: highest_synced,
+            checks,
+        }
+    }
+}
+
+/// Column families whose writes are stopped, according to the last report of the DB metrics.
+fn stopped_column_families(is_write_stopped: &IntGaugeVec) -> Vec<String> {
+    is_write_stopped
+        .collect()
+        .iter()
+        .flat_map(|family| family.get_metric())
+        .filter(|metric| metric.get_gauge().get_value() == 1.0)
+        .filter_map(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .find(|label| label.get_name() == "cf_name")
+                .map(|label| label.get_value().to_string())
+        })
+        .collect()
+}
+
+pub fn start_health_server(checker: HealthChecker, address: SocketAddr) -> JoinHandle<()> {
+    let app = Router::new()
+        .route(LIVE_ROUTE, get(live))
+        .route(READY_ROUTE, get(ready))
+        .with_state(Arc::new(checker));
+
+    info!(address =% address, "starting health server");
+    spawn_monitored_task!(async move {
+        axum::Server::bind(&address)
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+    })
+}
+
+async fn live(State(checker): State<Arc<HealthChecker>>) -> Json<LivenessReport> {
+    Json(checker.liveness())
+}
+
+async fn ready(State(checker): State<Arc<HealthChecker>>) -> (StatusCode, Json<ReadinessReport>) {
+    let report = checker.readiness();
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{register_int_gauge_vec_with_registry, Registry};
+
+    #[test]
+    fn test_health_check_threshold() {
+        assert!(HealthCheck::at_most("check", Some(10), 10).healthy);
+        assert!(!HealthCheck::at_most("check", Some(11), 10).healthy);
+        assert!(!HealthCheck::at_most("check", None, 10).healthy);
+    }
+
+    #[test]
+    fn test_round_progress() {
+        let mut progress = RoundProgress::default();
+        let start = Instant::now();
+        assert_eq!(progress.observe(5, start), Duration::ZERO);
+        assert_eq!(
+            progress.observe(5, start + Duration::from_secs(10)),
+            Duration::from_secs(10)
+        );
+        // Any change of round is progress, including the reset at the start of an epoch.
+        assert_eq!(
+            progress.observe(0, start + Duration::from_secs(20)),
+            Duration::ZERO
+        );
+        assert_eq!(
+            progress.observe(0, start + Duration::from_secs(25)),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn test_stopped_column_families() {
+        let registry = Registry::new();
+        let is_write_stopped = register_int_gauge_vec_with_registry!(
+            "rocksdb_is_write_stopped",
+            "Whether writes are stopped",
+            &["cf_name"],
+            registry,
+        )
+        .unwrap();
+        assert!(stopped_column_families(&is_write_stopped).is_empty());
+
+        is_write_stopped.with_label_values(&["objects"]).set(1);
+        is_write_stopped.with_label_values(&["transactions"]).set(0);
+        assert_eq!(
+            stopped_column_families(&is_write_stopped),
+            vec!["objects".to_string()]
+        );
+    }
+}
@@ -34,12 +34,21 @@ use mysten_metrics::{spawn_monitored_task, RegistryService};
 use mysten_network::server::ServerBuilder;
 use narwhal_network::metrics::MetricsMakeCallbackHandler;
 use narwhal_network::metrics::{NetworkConnectionMetrics, NetworkMetrics};
-use sui_config::node::DBCheckpointConfig;
-use sui_config::{ConsensusConfig, NodeConfig};
+use narwhal_network::peer_quality::{
+    Direction, PeerQuality, PeerQualityMakeCallbackHandler, PeerQualityMetrics,
+};
+use sui_config::node::{CheckpointStreamConfig, DBCheckpointConfig};
+use sui_config::transaction_deny_config::TransactionDenyConfig;
+use sui_config::{Config, ConsensusConfig, NodeConfig};
+use sui_core::archive_fallback::ArchiveFallback;
 use sui_core::authority::authority_per_epoch_store::AuthorityPerEpochStore;
+use sui_core::authority::checkpoint_history_pruner::CheckpointHistoryPruner;
 use sui_core::authority::epoch_start_configuration::EpochStartConfiguration;
+use sui_core::authority::execution_cache::ExecutionCache;
+use sui_core::authority::transaction_outputs::TransactionOutputsSource;
 use sui_core::authority_aggregator::AuthorityAggregator;
 use sui_core::authority_server::ValidatorService;
+use sui_core::checkpoint_stream_server::CheckpointStreamService;
 use sui_core::checkpoints::checkpoint_executor;
 use sui_core::checkpoints::{
     CheckpointMetrics, CheckpointService, CheckpointStore, SendCheckpointToStateSync,
@@ -50,34 +59,39 @@ use sui_core::consensus_adapter::{
 };
 use sui_core::consensus_handler::ConsensusHandler;
 use sui_core::consensus_validator::{SuiTxValidator, SuiTxValidatorMetrics};
-use sui_core::db_checkpoint_handler::DBCheckpointHandler;
+use sui_core::db_checkpoint_handler::{restore_latest_db_checkpoint, DBCheckpointHandler};
 use sui_core::epoch::committee_store::CommitteeStore;
 use sui_core::epoch::data_removal::EpochDataRemover;
 use sui_core::epoch::epoch_metrics::EpochMetrics;
 use sui_core::epoch::reconfiguration::ReconfigurationInitiator;
+use sui_core::gas_price_oracle::GasPriceOracle;
+use sui_core::memory_budget::MemoryBudget;
 use sui_core::module_cache_metrics::ResolverMetrics;
+use sui_core::name_service::NameService;
 use sui_core::narwhal_manager::{NarwhalConfiguration, NarwhalManager, NarwhalManagerMetrics};
+use sui_core::quorum_reachability::{QuorumReachability, QuorumReachabilityReport};
 use sui_core::signature_verifier::VerifiedDigestCacheMetrics;
 use sui_core::state_accumulator::StateAccumulator;
 use sui_core::storage::RocksDbStore;
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_core::{
-    authority::{AuthorityState, AuthorityStore},
+    authority::{AuthorityState, AuthorityStore, EpochChangeDryRun},
     authority_client::NetworkAuthorityClient,
 };
 use sui_json_rpc::coin_api::CoinReadApi;
-use sui_json_rpc::event_api::EventReadApi;
+use sui_json_rpc::event_api::{EventReadApi, ObjectChangeApi};
 use sui_json_rpc::governance_api::GovernanceReadApi;
 use sui_json_rpc::read_api::ReadApi;
 use sui_json_rpc::transaction_builder_api::TransactionBuilderApi;
 use sui_json_rpc::transaction_execution_api::TransactionExecutionApi;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
 use sui_macros::fail_point_async;
-use sui_network::api::ValidatorServer;
+use sui_network::api::{CheckpointStreamServer, ValidatorServer};
 use sui_network::discovery;
 use sui_network::discovery::TrustedPeerChangeEvent;
 use sui_network::state_sync;
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
+use sui_storage::archive::ArchiveReader;
 use sui_storage::IndexStore;
 use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
 use sui_types::committee::Committee;
@@ -91,10 +105,16 @@ use sui_types::sui_system_state::SuiSystemStateTrait;
 use typed_store::DBMetrics;
 
 use crate::metrics::GrpcMetrics;
+use crate::read_only::P2pTransactionOutputsSource;
 
 pub mod admin;
+mod endpoint_auth;
 mod handle;
+mod health;
+pub mod logging;
 pub mod metrics;
+mod read_only;
+mod rest;
 
 pub struct ValidatorComponents {
     validator_server_handle: JoinHandle<Result<()>>,
@@ -122,6 +142,7 @@ pub struct SuiNode {
     checkpoint_store: Arc<CheckpointStore>,
     accumulator: Arc<StateAccumulator>,
     connection_monitor_status: Arc<ConnectionMonitorStatus>,
+    quorum_reachability: Arc<QuorumReachability>,
 
     /// Broadcast channel to send the starting system state for the next epoch.
     end_of_epoch_channel: broadcast::Sender<SuiSystemState>,
@@ -130,6 +151,11 @@ pub struct SuiNode {
     trusted_peer_change_tx: watch::Sender<TrustedPeerChangeEvent>,
 
     _db_checkpoint_handle: Option<Sender<()>>,
+    _checkpoint_stream_handle: Option<JoinHandle<Result<()>>>,
+    _rest_server_handle: Option<JoinHandle<()>>,
+    _health_server_handle: Option<JoinHandle<()>>,
+    _checkpoint_history_pruner: Option<CheckpointHistoryPruner>,
+    memory_budget: Option<Arc<MemoryBudget>>,
 
     #[cfg(msim)]
     sim_node: sui_simulator::runtime::NodeHandle,
@@ -160,6 +186,9 @@ impl SuiNode {
         // TODO: maybe have a config enum that takes care of this for us.
         let is_validator = config.consensus_config().is_some();
         let is_full_node = !is_validator;
+        if is_validator && config.read_only_fullnode_config.is_some() {
+            return Err(anyhow!("Validators cannot run in read-only mode"));
+        }
         let prometheus_registry = registry_service.default_registry();
 
         info!(node =? config.protocol_public_key(),
@@ -170,6 +199,17 @@ impl SuiNode {
         DBMetrics::init(&prometheus_registry);
         mysten_metrics::init_metrics(&prometheus_registry);
 
+        // A node started without a db bootstraps from the latest db checkpoint uploaded by another
+        // node, and state sync catches up from there.
+        if let Some(restore_config) = &config.db_checkpoint_config.restore_object_store_config {
+            if !config.db_path().exists() {
+                match restore_latest_db_checkpoint(restore_config, &config.db_path()).await? {
+                    Some(epoch) => info!("Restored db from the db checkpoint of epoch {epoch}"),
+                    None => warn!("No db checkpoint to restore from, starting from genesis"),
+                }
+            }
+        }
+
         let genesis = config.genesis()?;
 
         let secret = Arc::pin(config.protocol_key_pair().copy());
@@ -179,13 +219,18 @@ impl SuiNode {
             &genesis_committee,
             None,
         ));
+        let execution_cache = config
+            .execution_cache_config
+            .map(|cache_config| ExecutionCache::new(cache_config, &prometheus_registry));
         let store = Arc::new(
             AuthorityStore::open(
                 &config.db_path().join("store"),
                 None,
+                &config.db_tuning,
                 genesis,
                 &committee_store,
                 config.indirect_objects_threshold,
+                execution_cache.clone(),
             )
             .await?,
         );
@@ -237,7 +282,11 @@ impl SuiNode {
             checkpoint_store.clone(),
         );
 
-        let index_store = if is_validator {
+        let indexes_disabled = config
+            .transaction_scan_config
+            .as_ref()
+            .map_or(false, |scan_config| scan_config.disable_indexes);
+        let index_store = if is_validator || indexes_disabled {
             None
         } else {
             Some(Arc::new(IndexStore::new(config.db_path().join("indexes"))))
@@ -247,10 +296,15 @@ impl SuiNode {
         // TODO only configure validators as seed/preferred peers for validators and not for
         // fullnodes once we've had a chance to re-work fullnode configuration generation.
         let (trusted_peer_change_tx, trusted_peer_change_rx) = watch::channel(Default::default());
+        let peer_quality = Arc::new(PeerQuality::new(PeerQualityMetrics::new(
+            "sui",
+            &prometheus_registry,
+        )));
         let (p2p_network, discovery_handle, state_sync_handle) = Self::create_p2p_network(
             &config,
             state_sync_store,
             trusted_peer_change_rx,
+            peer_quality.clone(),
             &prometheus_registry,
         )?;
         // We must explicitly send this instead of relying on the initial value to trigger
@@ -283,6 +337,11 @@ impl SuiNode {
             None => None,
         };
 
+        let transaction_outputs_source = config.read_only_fullnode_config.clone().map(|c| {
+            info!(upstream_peer_id = ?c.upstream_peer_id, "Starting read-only fullnode");
+            Arc::new(P2pTransactionOutputsSource::new(p2p_network.clone(), c))
+                as Arc<dyn TransactionOutputsSource>
+        });
         let state = AuthorityState::new(
             config.protocol_public_key(),
             secret,
@@ -296,8 +355,34 @@ impl SuiNode {
             config.authority_store_pruning_config,
             genesis.objects(),
             &db_checkpoint_config,
+            Self::load_transaction_deny_config(config)?,
+            config.system_transaction_trace_config,
+            config.local_execution_time_limit_config.unwrap_or_default(),
+            transaction_outputs_source,
+            config.slow_op_config.unwrap_or_default(),
         )
         .await;
+        let checkpoint_history_pruner =
+            (is_full_node && config.authority_store_pruning_config.prunes_history()).then(|| {
+                CheckpointHistoryPruner::new(
+                    &store,
+                    checkpoint_store.clone(),
+                    index_store.clone(),
+                    config.authority_store_pruning_config,
+                    epoch_store.epoch_start_state().epoch_duration_ms(),
+                    state_sync_handle.clone(),
+                )
+            });
+        let memory_budget = config.memory_budget_config.map(|budget_config| {
+            let memory_budget = MemoryBudget::new(budget_config, &prometheus_registry);
+            memory_budget.register("transaction_manager", state.transaction_manager());
+            memory_budget.register("epoch_store", &epoch_store);
+            if let Some(execution_cache) = &execution_cache {
+                memory_budget.register("execution_cache", execution_cache);
+            }
+            memory_budget.clone().start();
+            memory_budget
+        });
         // ensure genesis txn was executed
         if epoch_store.epoch() == 0 {
             let txn = &genesis.transaction();
@@ -318,13 +403,15 @@ impl SuiNode {
         let (end_of_epoch_channel, end_of_epoch_receiver) =
             broadcast::channel(config.end_of_epoch_broadcast_channel_capacity);
 
-        let transaction_orchestrator = if is_full_node {
+        // Read-only fullnodes do not execute transactions, and so do not submit them either.
+        let transaction_orchestrator = if is_full_node && !state.is_read_only() {
             Some(Arc::new(
                 TransactiondOrchestrator::new_with_network_clients(
                     state.clone(),
                     end_of_epoch_receiver,
                     &config.db_path(),
                     &prometheus_registry,
+                    config.quorum_driver_config.clone().unwrap_or_default(),
                 )
                 .await?,
             ))
@@ -340,6 +427,33 @@ impl SuiNode {
         )
         .await?;
 
+        let checkpoint_stream_handle = match &config.checkpoint_stream_config {
+            Some(stream_config) => Some(
+                Self::start_grpc_checkpoint_stream_service(
+                    stream_config,
+                    state.clone(),
+                    &registry_service,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        let rest_server_handle = config
+            .rest_server_address
+            .map(|address| rest::start_rest_server(state.clone(), address));
+
+        let health_server_handle = config.health_check_config.map(|health_check_config| {
+            health::start_health_server(
+                health::HealthChecker::new(
+                    state.clone(),
+                    checkpoint_store.clone(),
+                    health_check_config,
+                ),
+                health_check_config.address,
+            )
+        });
+
         let accumulator = Arc::new(StateAccumulator::new(store));
 
         let authority_names_to_peer_ids = epoch_store
@@ -356,6 +470,7 @@ impl SuiNode {
                 p2p_network.downgrade(),
                 network_connection_metrics,
                 HashMap::new(),
+                Some(peer_quality),
             );
 
         let connection_monitor_status = ConnectionMonitorStatus {
@@ -365,6 +480,13 @@ impl SuiNode {
 
         let connection_monitor_status = Arc::new(connection_monitor_status);
 
+        let quorum_reachability = QuorumReachability::new(
+            state.name,
+            connection_monitor_status.clone(),
+            &registry_service.default_registry(),
+        );
+        quorum_reachability.clone().start(state.clone());
+
         let validator_components = if state.is_validator(&epoch_store) {
             let components = Self::construct_validator_components(
                 &config,
@@ -375,7 +497,9 @@ impl SuiNode {
                 state_sync_handle.clone(),
                 accumulator.clone(),
                 connection_monitor_status.clone(),
+                quorum_reachability.clone(),
                 &registry_service,
+                memory_budget.clone(),
             )
             .await?;
             // This is only needed during cold start.
@@ -400,9 +524,15 @@ impl SuiNode {
             accumulator,
             end_of_epoch_channel,
             connection_monitor_status,
+            quorum_reachability,
             trusted_peer_change_tx,
 
             _db_checkpoint_handle: db_checkpoint_handle,
+            _checkpoint_stream_handle: checkpoint_stream_handle,
+            _rest_server_handle: rest_server_handle,
+            _health_server_handle: health_server_handle,
+            _checkpoint_history_pruner: checkpoint_history_pruner,
+            memory_budget,
             #[cfg(msim)]
             sim_node: sui_simulator::runtime::NodeHandle::current(),
         };
@@ -445,6 +575,17 @@ impl SuiNode {
             .clear_override_protocol_upgrade_buffer_stake(epoch)
     }
 
+    /// Simulates the end of the current epoch, to check that this node is ready for the next one.
+    pub async fn dry_run_epoch_change(&self) -> anyhow::Result<EpochChangeDryRun> {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        self.state
+            .dry_run_epoch_change(
+                &epoch_store,
+                self.config.supported_protocol_versions.unwrap(),
+            )
+            .await
+    }
+
     pub fn set_override_protocol_upgrade_buffer_stake(
         &self,
         epoch: EpochId,
@@ -454,6 +595,62 @@ impl SuiNode {
             .set_override_protocol_upgrade_buffer_stake(epoch, buffer_stake_bps)
     }
 
+    /// Evaluates whether a quorum of the committee of the current epoch is reachable from this
+    /// node.
+    pub fn quorum_reachability(&self) -> QuorumReachabilityReport {
+        let epoch_store = self.state.load_epoch_store_one_call_per_task();
+        self.quorum_reachability.evaluate(epoch_store.committee())
+    }
+
+    pub fn transaction_deny_config(&self) -> Arc<TransactionDenyConfig> {
+        self.state.transaction_deny_config()
+    }
+
+    /// Replaces the transaction deny config of the running node. The override is persisted next
+    /// to the database, and takes precedence over the node config across restarts until cleared.
+    pub fn set_transaction_deny_config_override(
+        &self,
+        transaction_deny_config: TransactionDenyConfig,
+    ) -> Result<()> {
+        let path = self.config.transaction_deny_config_override_path();
+        // Write to a temporary file first so that a crash never leaves a truncated override.
+        let tmp_path = path.with_extension("yaml.tmp");
+        transaction_deny_config.save(&tmp_path)?;
+        std::fs::rename(&tmp_path, &path)?;
+        info!(
+            ?transaction_deny_config,
+            "Transaction deny config overridden"
+        );
+        self.state
+            .set_transaction_deny_config(transaction_deny_config);
+        Ok(())
+    }
+
+    /// Removes the persisted override and reverts to the deny config from the node config.
+    pub fn clear_transaction_deny_config_override(&self) -> Result<()> {
+        let path = self.config.transaction_deny_config_override_path();
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        info!("Transaction deny config override cleared");
+        self.state
+            .set_transaction_deny_config(self.config.transaction_deny_config.clone());
+        Ok(())
+    }
+
+    fn load_transaction_deny_config(config: &NodeConfig) -> Result<TransactionDenyConfig> {
+        let path = config.transaction_deny_config_override_path();
+        if path.exists() {
+            info!(
+                "Loading transaction deny config override from {}",
+                path.display()
+            );
+            TransactionDenyConfig::load(&path)
+        } else {
+            Ok(config.transaction_deny_config.clone())
+        }
+    }
+
     // Testing-only API to start epoch close process.
     // For production code, please use the non-testing version.
     pub async fn close_epoch_for_testing(&self) -> SuiResult {
@@ -474,11 +671,19 @@ impl SuiNode {
         config: &NodeConfig,
         state_sync_store: RocksDbStore,
         trusted_peer_change_rx: watch::Receiver<TrustedPeerChangeEvent>,
+        peer_quality: Arc<PeerQuality>,
         prometheus_registry: &Registry,
     ) -> Result<(Network, discovery::Handle, state_sync::Handle)> {
-        let (state_sync, state_sync_server) = state_sync::Builder::new()
-            .config(config.p2p_config.state_sync.clone().unwrap_or_default())
+        let state_sync_config = config.p2p_config.state_sync.clone().unwrap_or_default();
+        let mut state_sync_builder = state_sync::Builder::new();
+        if let Some(archive_reader_config) = &state_sync_config.archive_reader_config {
+            state_sync_builder =
+                state_sync_builder.archive(Arc::new(ArchiveReader::new(archive_reader_config)?));
+        }
+        let (state_sync, state_sync_server) = state_sync_builder
+            .config(state_sync_config)
             .store(state_sync_store)
+            .peer_quality(peer_quality.clone())
             .with_metrics(prometheus_registry)
             .build();
 
@@ -506,6 +711,10 @@ impl SuiNode {
                     Arc::new(inbound_network_metrics),
                     config.p2p_config.excessive_message_size(),
                 )))
+                .layer(CallbackLayer::new(PeerQualityMakeCallbackHandler::new(
+                    peer_quality.clone(),
+                    Direction::Inbound,
+                )))
                 .service(routes);
 
             let outbound_layer = ServiceBuilder::new()
@@ -518,6 +727,10 @@ impl SuiNode {
                     Arc::new(outbound_network_metrics),
                     config.p2p_config.excessive_message_size(),
                 )))
+                .layer(CallbackLayer::new(PeerQualityMakeCallbackHandler::new(
+                    peer_quality,
+                    Direction::Outbound,
+                )))
                 .into_inner();
 
             let mut anemo_config = config.p2p_config.anemo_config.clone().unwrap_or_default();
@@ -551,7 +764,9 @@ impl SuiNode {
         state_sync_handle: state_sync::Handle,
         accumulator: Arc<StateAccumulator>,
         connection_monitor_status: Arc<ConnectionMonitorStatus>,
+        quorum_reachability: Arc<QuorumReachability>,
         registry_service: &RegistryService,
+        memory_budget: Option<Arc<MemoryBudget>>,
     ) -> Result<ValidatorComponents> {
         let consensus_config = config
             .consensus_config()
@@ -564,6 +779,9 @@ impl SuiNode {
             connection_monitor_status.clone(),
             &registry_service.default_registry(),
         ));
+        if let Some(memory_budget) = &memory_budget {
+            memory_budget.register("consensus_adapter", &consensus_adapter);
+        }
         let narwhal_manager =
             Self::construct_narwhal_manager(config, consensus_config, registry_service)?;
 
@@ -596,9 +814,11 @@ impl SuiNode {
             narwhal_epoch_data_remover,
             accumulator,
             connection_monitor_status,
+            quorum_reachability,
             validator_server_handle,
             checkpoint_metrics,
             sui_tx_validator_metrics,
+            memory_budget,
         )
         .await
     }
@@ -614,9 +834,11 @@ impl SuiNode {
         narwhal_epoch_data_remover: EpochDataRemover,
         accumulator: Arc<StateAccumulator>,
         connection_monitor_status: Arc<ConnectionMonitorStatus>,
+        quorum_reachability: Arc<QuorumReachability>,
         validator_server_handle: JoinHandle<Result<()>>,
         checkpoint_metrics: Arc<CheckpointMetrics>,
         sui_tx_validator_metrics: Arc<SuiTxValidatorMetrics>,
+        memory_budget: Option<Arc<MemoryBudget>>,
     ) -> Result<ValidatorComponents> {
         let (checkpoint_service, checkpoint_service_exit) = Self::start_checkpoint_service(
             config,
@@ -651,7 +873,12 @@ impl SuiNode {
                 .clone(),
             committee.clone(),
             state.metrics.clone(),
+            quorum_reachability,
+            state.slow_ops.clone(),
         ));
+        if let Some(memory_budget) = &memory_budget {
+            memory_budget.register("consensus_handler", &consensus_handler);
+        }
 
         let transactions_addr = &config
             .consensus_config
@@ -801,6 +1028,32 @@ impl SuiNode {
         Ok(grpc_server)
     }
 
+    async fn start_grpc_checkpoint_stream_service(
+        config: &CheckpointStreamConfig,
+        state: Arc<AuthorityState>,
+        registry_service: &RegistryService,
+    ) -> Result<tokio::task::JoinHandle<Result<()>>> {
+        // A registry of its own, for the gRPC metrics not to clash with the validator server's.
+        let registry = Registry::new_custom(Some("checkpoint_stream".to_string()), None)?;
+        registry_service.add(registry.clone());
+        let checkpoint_stream_service =
+            CheckpointStreamService::new(state, config.clone(), &registry);
+
+        let server = ServerBuilder::from_config(
+            &mysten_network::config::Config::new(),
+            GrpcMetrics::new(&registry),
+        )
+        .add_service(CheckpointStreamServer::new(checkpoint_stream_service))
+        .bind(&config.address)
+        .await
+        .map_err(|err| anyhow!(err.to_string()))?;
+        info!(
+            "Streaming checkpoints to clients on {}",
+            server.local_addr()
+        );
+        Ok(spawn_monitored_task!(server.serve().map_err(Into::into)))
+    }
+
     pub fn state(&self) -> Arc<AuthorityState> {
         self.state.clone()
     }
@@ -989,9 +1242,11 @@ impl SuiNode {
                             narwhal_epoch_data_remover,
                             self.accumulator.clone(),
                             self.connection_monitor_status.clone(),
+                            self.quorum_reachability.clone(),
                             validator_server_handle,
                             checkpoint_metrics,
                             sui_tx_validator_metrics,
+                            self.memory_budget.clone(),
                         )
                         .await?,
                     )
@@ -1021,7 +1276,9 @@ impl SuiNode {
                             self.state_sync.clone(),
                             self.accumulator.clone(),
                             self.connection_monitor_status.clone(),
+                            self.quorum_reachability.clone(),
                             &self.registry_service,
+                            self.memory_budget.clone(),
                         )
                         .await?,
                     )
@@ -1097,6 +1354,9 @@ impl SuiNode {
             .expect("Reconfigure authority state cannot fail");
         info!(next_epoch, "Validator State has been reconfigured");
         assert_eq!(next_epoch, new_epoch_store.epoch());
+        if let Some(memory_budget) = &self.memory_budget {
+            memory_budget.register("epoch_store", &new_epoch_store);
+        }
         new_epoch_store
     }
 }
@@ -1132,10 +1392,31 @@ pub async fn build_server(
 
     let mut server = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), prometheus_registry);
 
-    server.register_module(ReadApi::new(state.clone()))?;
+    let mut read_api = ReadApi::new(state.clone());
+    if let Some(archive_config) = &config.archive_read_fallback_config {
+        read_api = read_api.with_archive(Arc::new(ArchiveFallback::new(
+            ArchiveReader::new(&archive_config.object_store_config)?,
+            state.committee_store().clone(),
+            archive_config.cache_capacity,
+        )));
+    }
+    if let Some(scan_config) = &config.transaction_scan_config {
+        read_api = read_api.with_transaction_scan(scan_config.max_checkpoints);
+    }
+    if let Some(name_service_config) = &config.name_service_config {
+        read_api =
+            read_api.with_name_service(Arc::new(NameService::new(name_service_config.clone())));
+    }
+    server.register_module(read_api)?;
     server.register_module(CoinReadApi::new(state.clone()))?;
     server.register_module(TransactionBuilderApi::new(state.clone()))?;
-    server.register_module(GovernanceReadApi::new(state.clone()))?;
+    let mut governance_api = GovernanceReadApi::new(state.clone());
+    if let Some(gas_price_oracle_config) = &config.gas_price_oracle_config {
+        let gas_price_oracle = Arc::new(GasPriceOracle::new(gas_price_oracle_config.clone()));
+        spawn_monitored_task!(gas_price_oracle.clone().run(state.clone()));
+        governance_api = governance_api.with_gas_price_oracle(gas_price_oracle);
+    }
+    server.register_module(governance_api)?;
 
     if let Some(transaction_orchestrator) = transaction_orchestrator {
         server.register_module(TransactionExecutionApi::new(
@@ -1145,6 +1426,7 @@ pub async fn build_server(
     }
 
     server.register_module(EventReadApi::new(state.clone()))?;
+    server.register_module(ObjectChangeApi::new(state.clone()))?;
 
     let rpc_server_handle = server.start(config.json_rpc_address).await?;
 
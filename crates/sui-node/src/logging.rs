@@ -0,0 +1,57 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Applies the `logging` section of the node config to the telemetry subscriber, at startup and
+//! when it is reloaded through the admin interface.
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use sui_config::node::{LogFormat, LoggingConfig};
+use sui_config::{Config, NodeConfig};
+use telemetry_subscribers::{LogSamplingRule, TelemetryConfig, TracingHandle};
+use tracing::info;
+
+fn sampling_rules(config: &LoggingConfig) -> Vec<LogSamplingRule> {
+    config
+        .sampling
+        .iter()
+        .map(|sampling| LogSamplingRule {
+            target: sampling.component.clone(),
+            rate: sampling.rate,
+        })
+        .collect()
+}
+
+/// Sets up the log output of the telemetry config from the logging config.
+pub fn configure_telemetry(
+    mut telemetry_config: TelemetryConfig,
+    config: &LoggingConfig,
+) -> TelemetryConfig {
+    if config.format == LogFormat::Json {
+        telemetry_config = telemetry_config.with_json();
+    }
+    telemetry_config
+        .with_log_level(&config.filter_directives())
+        .with_log_sampling(sampling_rules(config))
+}
+
+/// Reads the logging config from the node config file again, and applies its levels and sampling
+/// rules. The log format only changes on restart.
+pub fn reload_logging_config(config_path: &Path, tracing_handle: &TracingHandle) -> Result<()> {
+    let config = NodeConfig::load(config_path)?;
+    let Some(logging) = config.logging else {
+        return Err(anyhow!(
+            "{} has no logging config",
+            config_path.display()
+        ));
+    };
+    let directives = logging.filter_directives();
+    tracing_handle
+        .update_log(&directives)
+        .map_err(|e| anyhow!("invalid log levels: {e}"))?;
+    tracing_handle
+        .update_log_sampling(sampling_rules(&logging))
+        .map_err(|e| anyhow!("invalid log sampling: {e}"))?;
+    info!(filter =% directives, sampling = ?logging.sampling, "Logging config reloaded");
+    Ok(())
+}
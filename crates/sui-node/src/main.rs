@@ -6,7 +6,7 @@ use clap::Parser;
 use std::path::PathBuf;
 use std::time::Duration;
 use sui_config::{Config, NodeConfig};
-use sui_node::metrics;
+use sui_node::{logging, metrics};
 use sui_protocol_config::SupportedProtocolVersions;
 use sui_telemetry::send_telemetry_event;
 use sui_types::multiaddr::Multiaddr;
@@ -57,17 +57,29 @@ async fn main() -> Result<()> {
     );
     config.supported_protocol_versions = Some(SupportedProtocolVersions::SYSTEM_DEFAULT);
 
-    let registry_service = metrics::start_prometheus_server(config.metrics_address);
+    // Applied before any metric supporting overrides is constructed.
+    if let Some(metrics_config) = &config.metrics {
+        mysten_metrics::overrides::set_metrics_overrides(metrics_config.overrides.clone())
+            .map_err(|e| anyhow::anyhow!("invalid metrics overrides: {e}"))?;
+    }
+
+    let registry_service = metrics::start_prometheus_server_with_auth(
+        config.metrics_address,
+        config.metrics_auth.as_ref(),
+    );
     let prometheus_registry = registry_service.default_registry();
     prometheus_registry
         .register(mysten_metrics::uptime_metric(VERSION))
         .unwrap();
 
     // Initialize logging
-    let (_guard, filter_handle) = telemetry_subscribers::TelemetryConfig::new()
+    let mut telemetry_config = telemetry_subscribers::TelemetryConfig::new()
         .with_env()
-        .with_prom_registry(&prometheus_registry)
-        .init();
+        .with_prom_registry(&prometheus_registry);
+    if let Some(logging_config) = &config.logging {
+        telemetry_config = logging::configure_telemetry(telemetry_config, logging_config);
+    }
+    let (_guard, tracing_handle) = telemetry_config.init();
 
     info!("Sui Node version: {VERSION}");
     info!(
@@ -81,6 +93,7 @@ async fn main() -> Result<()> {
     );
 
     metrics::start_metrics_push_task(&config, registry_service.clone());
+    metrics::start_metrics_remote_write_task(&config, registry_service.clone())?;
 
     if let Some(listen_address) = args.listen_address {
         config.network_address = listen_address;
@@ -95,7 +108,12 @@ async fn main() -> Result<()> {
     });
 
     let node = sui_node::SuiNode::start(&config, registry_service).await?;
-    sui_node::admin::start_admin_server(node.clone(), config.admin_interface_port, filter_handle);
+    sui_node::admin::start_admin_server(
+        node.clone(),
+        config.admin_interface_port,
+        tracing_handle,
+        args.config_path,
+    );
 
     // TODO: Do we want to provide a way for the node to gracefully shutdown?
     loop {
@@ -15,8 +15,11 @@ use prometheus::{
 
 use std::net::SocketAddr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sui_config::node::EndpointAuthConfig;
 use sui_network::tonic::Code;
 
+use crate::endpoint_auth;
+use mysten_metrics::remote_write::{self, Relabeler, WriteRequest};
 use mysten_metrics::RegistryService;
 use tracing::warn;
 
@@ -26,6 +29,15 @@ const METRICS_ROUTE: &str = "/metrics";
 // and endpoint that prometheus agent can use to poll for the metrics.
 // A RegistryService is returned that can be used to get access in prometheus Registries.
 pub fn start_prometheus_server(addr: SocketAddr) -> RegistryService {
+    start_prometheus_server_with_auth(addr, None)
+}
+
+/// Like `start_prometheus_server`, serving the endpoint over TLS and requiring a bearer token as
+/// configured.
+pub fn start_prometheus_server_with_auth(
+    addr: SocketAddr,
+    auth_config: Option<&EndpointAuthConfig>,
+) -> RegistryService {
     let registry = Registry::new();
 
     let registry_service = RegistryService::new(registry);
@@ -41,12 +53,7 @@ pub fn start_prometheus_server(addr: SocketAddr) -> RegistryService {
         .route(METRICS_ROUTE, get(metrics))
         .layer(Extension(registry_service.clone()));
 
-    tokio::spawn(async move {
-        axum::Server::bind(&addr)
-            .serve(app.into_make_service())
-            .await
-            .unwrap();
-    });
+    endpoint_auth::spawn_server(addr, app, auth_config);
 
     registry_service
 }
@@ -107,6 +114,7 @@ pub fn start_metrics_push_task(config: &sui_config::NodeConfig, registry: Regist
         Some(MetricsConfig {
             push_interval_seconds,
             push_url: Some(url),
+            ..
         }) => {
             let interval = push_interval_seconds
                 .map(Duration::from_secs)
@@ -183,6 +191,102 @@ pub fn start_metrics_push_task(config: &sui_config::NodeConfig, registry: Regist
     });
 }
 
+fn remote_write_client(
+    tls: Option<&sui_config::node::RemoteWriteTlsConfig>,
+) -> Result<reqwest::Client, anyhow::Error> {
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(30));
+    if let Some(tls) = tls {
+        let mut pem = std::fs::read(&tls.certificate_path)?;
+        pem.push(b'\n');
+        pem.extend(std::fs::read(&tls.private_key_path)?);
+        builder = builder.identity(reqwest::Identity::from_pem(&pem)?);
+        if let Some(ca_certificate_path) = &tls.ca_certificate_path {
+            let ca_certificate = std::fs::read(ca_certificate_path)?;
+            builder =
+                builder.add_root_certificate(reqwest::Certificate::from_pem(&ca_certificate)?);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Starts a task to periodically push metrics to a Prometheus remote-write endpoint, if one is
+/// configured.
+pub fn start_metrics_remote_write_task(
+    config: &sui_config::NodeConfig,
+    registry: RegistryService,
+) -> Result<(), anyhow::Error> {
+    let Some(config) = config.metrics.as_ref().and_then(|m| m.remote_write.as_ref()) else {
+        return Ok(());
+    };
+    let url = reqwest::Url::parse(&config.url)?;
+    let client = remote_write_client(config.tls.as_ref())?;
+    let relabeler = Relabeler::new(&config.relabel_configs)?;
+    let interval = Duration::from_secs(config.push_interval_seconds);
+
+    async fn remote_write(
+        client: &reqwest::Client,
+        url: &reqwest::Url,
+        registry: &RegistryService,
+        relabeler: &Relabeler,
+    ) -> Result<(), anyhow::Error> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let request = WriteRequest::from_metric_families(&registry.gather_all(), now, relabeler);
+
+        let response = client
+            .post(url.to_owned())
+            .header(header::CONTENT_TYPE, remote_write::CONTENT_TYPE)
+            .header(header::CONTENT_ENCODING, remote_write::CONTENT_ENCODING)
+            .header(remote_write::VERSION_HEADER, remote_write::VERSION)
+            .body(request.encode_compressed()?)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = match response.text().await {
+                Ok(body) => body,
+                Err(error) => format!("couldn't decode response body; {error}"),
+            };
+            return Err(anyhow::anyhow!(
+                "metrics remote write failed: [{}]:{}",
+                status,
+                body
+            ));
+        }
+
+        tracing::debug!(
+            "successfully wrote {} series to {url}",
+            request.timeseries.len()
+        );
+
+        Ok(())
+    }
+
+    tokio::spawn(async move {
+        tracing::info!(
+            remote_write_url =% url,
+            interval =? interval,
+            "Started Metrics Remote Write Service"
+        );
+
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(error) = remote_write(&client, &url, &registry, &relabeler).await {
+                tracing::warn!("unable to remote write metrics: {error}");
+            }
+        }
+    });
+
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct GrpcMetrics {
     inflight_grpc: IntGaugeVec,
@@ -233,9 +337,10 @@ impl MetricsCallbackProvider for GrpcMetrics {
 
 #[cfg(test)]
 mod tests {
-    use crate::metrics::start_prometheus_server;
+    use crate::metrics::{start_prometheus_server, start_prometheus_server_with_auth};
     use prometheus::{IntCounter, Registry};
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use sui_config::node::EndpointAuthConfig;
 
     #[tokio::test]
     pub async fn test_metrics_endpoint_with_multiple_registries_add_remove() {
@@ -298,6 +403,31 @@ sui_counter_2 1"
         ));
     }
 
+    #[tokio::test]
+    pub async fn test_metrics_endpoint_requires_auth_token() {
+        let port: u16 = 8082;
+        let socket = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port);
+        let auth_config = EndpointAuthConfig {
+            tls: None,
+            auth_token: Some("secret".to_string()),
+        };
+
+        let _registry_service = start_prometheus_server_with_auth(socket, Some(&auth_config));
+
+        tokio::task::yield_now().await;
+
+        let client = reqwest::Client::new();
+        let url = format!("http://127.0.0.1:{}/metrics", port);
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let response = client.get(&url).bearer_auth("wrong").send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let response = client.get(&url).bearer_auth("secret").send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+
     async fn get_metrics(port: u16) -> String {
         let client = reqwest::Client::new();
         let response = client
@@ -0,0 +1,90 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use anemo::{Network, Request};
+use async_trait::async_trait;
+use rand::seq::SliceRandom;
+use sui_config::node::ReadOnlyFullnodeConfig;
+use sui_core::authority::transaction_outputs::TransactionOutputsSource;
+use sui_network::state_sync::{StateSyncClient, TransactionOutputs};
+use sui_types::digests::TransactionEffectsDigest;
+use sui_types::error::SuiResult;
+use tracing::{debug, warn};
+
+/// Fetches the outputs of transactions from the state sync service of a peer. The configured
+/// upstream peer is asked first, then the other connected peers in random order, so that a
+/// single failing or lagging peer doesn't stall the node.
+pub struct P2pTransactionOutputsSource {
+    network: Network,
+    config: ReadOnlyFullnodeConfig,
+}
+
+impl P2pTransactionOutputsSource {
+    pub fn new(network: Network, config: ReadOnlyFullnodeConfig) -> Self {
+        Self { network, config }
+    }
+
+    /// Connected peers in the order they are asked for outputs.
+    fn peers(&self) -> Vec<anemo::Peer> {
+        let upstream_peer_id = self.config.upstream_peer_id;
+        let mut peers: Vec<_> = self
+            .network
+            .peers()
+            .into_iter()
+            .filter(|peer_id| Some(*peer_id) != upstream_peer_id)
+            .filter_map(|peer_id| self.network.peer(peer_id))
+            .collect();
+        peers.shuffle(&mut rand::thread_rng());
+        if let Some(upstream) = upstream_peer_id.and_then(|peer_id| self.network.peer(peer_id)) {
+            peers.insert(0, upstream);
+        }
+        peers
+    }
+}
+
+#[async_trait]
+impl TransactionOutputsSource for P2pTransactionOutputsSource {
+    /// Asks each peer in turn, and retries until one returns the outputs. The checkpoint executor
+    /// cannot make progress without them, and every peer may be behind this node.
+    async fn get_transaction_outputs(
+        &self,
+        effects_digest: TransactionEffectsDigest,
+    ) -> SuiResult<TransactionOutputs> {
+        let timeout = Duration::from_millis(self.config.request_timeout_ms);
+        let retry_interval = Duration::from_millis(self.config.retry_interval_ms);
+        loop {
+            let peers = self.peers();
+            if peers.is_empty() {
+                warn!(
+                    upstream_peer_id = ?self.config.upstream_peer_id,
+                    "No peer to fetch transaction outputs from"
+                );
+            }
+            for peer in peers {
+                let peer_id = peer.peer_id();
+                let request = Request::new(effects_digest).with_timeout(timeout);
+                match StateSyncClient::new(peer)
+                    .get_transaction_outputs(request)
+                    .await
+                {
+                    Ok(response) => match response.into_inner() {
+                        Some(outputs) => return Ok(outputs),
+                        None => debug!(
+                            ?peer_id,
+                            ?effects_digest,
+                            "Peer does not have the transaction outputs yet"
+                        ),
+                    },
+                    Err(e) => warn!(
+                        ?peer_id,
+                        ?effects_digest,
+                        "Failed to fetch transaction outputs: {e}"
+                    ),
+                }
+            }
+            tokio::time::sleep(retry_interval).await;
+        }
+    }
+}
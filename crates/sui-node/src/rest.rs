@@ -0,0 +1,297 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! REST endpoints serving the BCS encoding of checkpoints, transactions, effects and objects, for
+//! consumers reading large volumes of data that do not need the JSON representations of the
+//! JSON-RPC API.
+//!
+//! Responses carry the digest of the data they encode as their ETag, and answer requests whose
+//! `If-None-Match` matches with `304 Not Modified`. Data addressed by digest, sequence number or
+//! version never changes and may be cached indefinitely; the latest version of an object must be
+//! revalidated.
+//!
+//! Example commands:
+//!
+//!   $ curl 'http://127.0.0.1:9002/checkpoints/1000'
+//!   $ curl 'http://127.0.0.1:9002/checkpoints/<digest>/contents'
+//!   $ curl 'http://127.0.0.1:9002/transactions/<digest>'
+//!   $ curl 'http://127.0.0.1:9002/transactions/<digest>/effects'
+//!   $ curl 'http://127.0.0.1:9002/objects/0x5'
+//!   $ curl 'http://127.0.0.1:9002/objects/0x5/versions/42'
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use mysten_metrics::spawn_monitored_task;
+use serde::Serialize;
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use sui_core::authority::AuthorityState;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::{CheckpointDigest, TransactionDigest};
+use sui_types::error::SuiError;
+use sui_types::message_envelope::Message;
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint};
+use sui_types::storage::ObjectStore;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+const CHECKPOINT: &str = "/checkpoints/:checkpoint";
+const CHECKPOINT_CONTENTS: &str = "/checkpoints/:checkpoint/contents";
+const TRANSACTION: &str = "/transactions/:digest";
+const TRANSACTION_EFFECTS: &str = "/transactions/:digest/effects";
+const OBJECT: &str = "/objects/:object_id";
+const OBJECT_VERSION: &str = "/objects/:object_id/versions/:version";
+
+const BCS_CONTENT_TYPE: &str = "application/bcs";
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+const REVALIDATE_CACHE_CONTROL: &str = "no-cache";
+
+type RestResult = Result<Response, (StatusCode, String)>;
+
+pub fn start_rest_server(state: Arc<AuthorityState>, address: SocketAddr) -> JoinHandle<()> {
+    let app = Router::new()
+        .route(CHECKPOINT, get(checkpoint))
+        .route(CHECKPOINT_CONTENTS, get(checkpoint_contents))
+        .route(TRANSACTION, get(transaction))
+        .route(TRANSACTION_EFFECTS, get(transaction_effects))
+        .route(OBJECT, get(object))
+        .route(OBJECT_VERSION, get(object_version))
+        .with_state(state);
+
+    info!(address =% address, "starting REST server");
+    spawn_monitored_task!(async move {
+        axum::Server::bind(&address)
+            .serve(app.into_make_service())
+            .await
+            .unwrap();
+    })
+}
+
+async fn checkpoint(
+    State(state): State<Arc<AuthorityState>>,
+    Path(checkpoint): Path<String>,
+    headers: HeaderMap,
+) -> RestResult {
+    let checkpoint = get_checkpoint(&state, &checkpoint)?;
+    Ok(bcs_response(
+        &headers,
+        checkpoint.digest(),
+        true,
+        checkpoint.inner(),
+    ))
+}
+
+async fn checkpoint_contents(
+    State(state): State<Arc<AuthorityState>>,
+    Path(checkpoint): Path<String>,
+    headers: HeaderMap,
+) -> RestResult {
+    let checkpoint = get_checkpoint(&state, &checkpoint)?;
+    let contents = state
+        .get_checkpoint_store()
+        .get_checkpoint_contents(&checkpoint.content_digest)
+        .map_err(|e| internal_error(SuiError::from(e)))?
+        .ok_or_else(|| not_found(format!("Contents of checkpoint {}", checkpoint.digest())))?;
+    Ok(bcs_response(
+        &headers,
+        &checkpoint.content_digest,
+        true,
+        &contents,
+    ))
+}
+
+async fn transaction(
+    State(state): State<Arc<AuthorityState>>,
+    Path(digest): Path<String>,
+    headers: HeaderMap,
+) -> RestResult {
+    let digest: TransactionDigest = parse(&digest, "transaction digest")?;
+    let transaction = state
+        .database
+        .get_transaction(&digest)
+        .map_err(|e| internal_error(SuiError::from(e)))?
+        .ok_or_else(|| not_found(format!("Transaction {digest}")))?;
+    Ok(bcs_response(&headers, &digest, true, transaction.inner()))
+}
+
+async fn transaction_effects(
+    State(state): State<Arc<AuthorityState>>,
+    Path(digest): Path<String>,
+    headers: HeaderMap,
+) -> RestResult {
+    let digest: TransactionDigest = parse(&digest, "transaction digest")?;
+    let effects = state
+        .database
+        .get_executed_effects(&digest)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found(format!("Effects of transaction {digest}")))?;
+    Ok(bcs_response(&headers, &effects.digest(), true, &effects))
+}
+
+async fn object(
+    State(state): State<Arc<AuthorityState>>,
+    Path(object_id): Path<String>,
+    headers: HeaderMap,
+) -> RestResult {
+    let object_id: ObjectID = parse(&object_id, "object ID")?;
+    let object = state
+        .database
+        .get_object(&object_id)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found(format!("Object {object_id}")))?;
+    // The latest version of the object changes as transactions are executed.
+    Ok(bcs_response(&headers, &object.digest(), false, &object))
+}
+
+async fn object_version(
+    State(state): State<Arc<AuthorityState>>,
+    Path((object_id, version)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> RestResult {
+    let object_id: ObjectID = parse(&object_id, "object ID")?;
+    let version = SequenceNumber::from_u64(parse(&version, "object version")?);
+    let object = state
+        .database
+        .get_object_by_key(&object_id, version)
+        .map_err(internal_error)?
+        .ok_or_else(|| not_found(format!("Object {object_id} at version {version}")))?;
+    Ok(bcs_response(&headers, &object.digest(), true, &object))
+}
+
+/// Reads a checkpoint by sequence number, or by digest.
+fn get_checkpoint(
+    state: &AuthorityState,
+    checkpoint: &str,
+) -> Result<VerifiedCheckpoint, (StatusCode, String)> {
+    let checkpoint_store = state.get_checkpoint_store();
+    let result = match checkpoint.parse::<CheckpointSequenceNumber>() {
+        Ok(sequence_number) => checkpoint_store.get_checkpoint_by_sequence_number(sequence_number),
+        Err(_) => {
+            let digest: CheckpointDigest =
+                parse(checkpoint, "checkpoint sequence number or digest")?;
+            checkpoint_store.get_checkpoint_by_digest(&digest)
+        }
+    };
+    result
+        .map_err(|e| internal_error(SuiError::from(e)))?
+        .ok_or_else(|| not_found(format!("Checkpoint {checkpoint}")))
+}
+
+/// The BCS encoding of `value`, or `304 Not Modified` if the request already has the version
+/// identified by `etag`. Immutable responses may be cached indefinitely.
+fn bcs_response<T: Serialize>(
+    headers: &HeaderMap,
+    etag: &impl Display,
+    immutable: bool,
+    value: &T,
+) -> Response {
+    let etag = format!("\"{etag}\"");
+    let cache_control = if immutable {
+        IMMUTABLE_CACHE_CONTROL
+    } else {
+        REVALIDATE_CACHE_CONTROL
+    };
+    let etag_header = HeaderValue::from_str(&etag).expect("digests are valid header values");
+    let cache_headers = [
+        (header::ETAG, etag_header),
+        (
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        ),
+    ];
+
+    let not_modified = headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|candidate| {
+            let candidate = candidate.trim();
+            candidate == "*" || candidate.trim_start_matches("W/") == etag
+        });
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    match bcs::to_bytes(value) {
+        Ok(bytes) => (
+            StatusCode::OK,
+            cache_headers,
+            [(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(BCS_CONTENT_TYPE),
+            )],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => internal_error(e).into_response(),
+    }
+}
+
+fn parse<T: FromStr>(value: &str, what: &str) -> Result<T, (StatusCode, String)> {
+    value.parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid {what}: {value}\n"),
+        )
+    })
+}
+
+fn not_found(what: String) -> (StatusCode, String) {
+    (
+        StatusCode::NOT_FOUND,
+        format!("{what} not found on this node\n"),
+    )
+}
+
+fn internal_error(e: impl Display) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{e}\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bcs_response_etag() {
+        let value = vec![1u64, 2, 3];
+        let digest = TransactionDigest::random();
+        let etag = format!("\"{digest}\"");
+
+        let response = bcs_response(&HeaderMap::new(), &digest, true, &value);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            IMMUTABLE_CACHE_CONTROL
+        );
+        assert_eq!(response.headers()[header::CONTENT_TYPE], BCS_CONTENT_TYPE);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"{}\", {etag}", TransactionDigest::random())).unwrap(),
+        );
+        let response = bcs_response(&headers, &digest, false, &value);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            REVALIDATE_CACHE_CONTROL
+        );
+
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"{}\"", TransactionDigest::random())).unwrap(),
+        );
+        let response = bcs_response(&headers, &digest, true, &value);
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
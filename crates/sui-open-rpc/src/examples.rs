@@ -26,8 +26,8 @@ use sui_json_rpc_types::{
     SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectRef, SuiObjectResponse,
     SuiObjectResponseQuery, SuiParsedData, SuiPastObjectResponse, SuiTransaction,
     SuiTransactionData, SuiTransactionEffects, SuiTransactionEffectsV1, SuiTransactionResponse,
-    SuiTransactionResponseOptions, SuiTransactionResponseQuery, TransactionBytes, TransactionsPage,
-    TransferObjectParams,
+    SuiTransactionResponseOptions, SuiTransactionResponseQuery, TransactionBytes,
+    TransactionCursor, TransactionsPage, TransferObjectParams,
 };
 use sui_open_rpc::ExamplePairing;
 use sui_types::base_types::{
@@ -227,6 +227,7 @@ impl RpcExampleProvider {
             type_: Some(ObjectType::Struct(MoveObjectType::GasCoin)),
             bcs: None,
             display: None,
+            served_from_archive: None,
         });
 
         Examples::new(
@@ -266,6 +267,7 @@ impl RpcExampleProvider {
             type_: Some(ObjectType::Struct(MoveObjectType::GasCoin)),
             bcs: None,
             display: None,
+            served_from_archive: None,
         });
 
         Examples::new(
@@ -320,6 +322,7 @@ impl RpcExampleProvider {
                 display: None,
                 content: None,
                 bcs: None,
+                served_from_archive: None,
             })
             .collect::<Vec<_>>();
 
@@ -388,7 +391,7 @@ impl RpcExampleProvider {
         let mut data = self.get_transaction_digests(5..9);
         let has_next_page = data.len() > (9 - 5);
         data.truncate(9 - 5);
-        let next_cursor = data.last().cloned();
+        let next_cursor = Some(TransactionCursor::new(1200, 3));
         let data = data.into_iter().map(SuiTransactionResponse::new).collect();
 
         let result = TransactionsPage {
@@ -410,7 +413,7 @@ impl RpcExampleProvider {
                             options: None,
                         }),
                     ),
-                    ("cursor", json!(TransactionDigest::new(self.rng.gen()))),
+                    ("cursor", json!(TransactionCursor::new(1200, 0))),
                     ("limit", json!(100)),
                     ("descending_order", json!(false)),
                 ],
@@ -522,6 +525,7 @@ impl RpcExampleProvider {
             }),
             raw_transaction,
             confirmed_local_execution: None,
+            served_from_archive: None,
             checkpoint: None,
             errors: vec![],
         };
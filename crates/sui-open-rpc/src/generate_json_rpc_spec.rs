@@ -12,7 +12,7 @@ use sui_core::SUI_CORE_VERSION;
 use crate::examples::RpcExampleProvider;
 use sui_json_rpc::api::ExtendedApiOpenRpc;
 use sui_json_rpc::coin_api::CoinReadApi;
-use sui_json_rpc::event_api::EventReadApi;
+use sui_json_rpc::event_api::{EventReadApi, ObjectChangeApi};
 use sui_json_rpc::governance_api::GovernanceReadApi;
 use sui_json_rpc::read_api::ReadApi;
 use sui_json_rpc::sui_rpc_doc;
@@ -49,6 +49,7 @@ async fn main() {
     open_rpc.add_module(ReadApi::rpc_doc_module());
     open_rpc.add_module(CoinReadApi::rpc_doc_module());
     open_rpc.add_module(EventReadApi::rpc_doc_module());
+    open_rpc.add_module(ObjectChangeApi::rpc_doc_module());
     open_rpc.add_module(TransactionExecutionApi::rpc_doc_module());
     open_rpc.add_module(TransactionBuilderApi::rpc_doc_module());
     open_rpc.add_module(GovernanceReadApi::rpc_doc_module());
@@ -3,6 +3,7 @@
 
 extern crate core;
 
+use std::cmp::Ordering;
 use std::collections::btree_map::Entry::Occupied;
 use std::collections::{BTreeMap, HashMap};
 
@@ -65,6 +66,24 @@ impl Project {
         }
     }
 
+    /// The version of the API.
+    pub fn version(&self) -> &str {
+        &self.info.version
+    }
+
+    /// The names of the methods of the API, in alphabetical order.
+    pub fn method_names(&self) -> impl Iterator<Item = &str> {
+        self.methods.iter().map(|m| m.name.as_str())
+    }
+
+    /// The deprecated methods of the API, with their description.
+    pub fn deprecated_methods(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.methods
+            .iter()
+            .filter(|m| m.deprecated)
+            .map(|m| (m.name.as_str(), m.description.as_deref()))
+    }
+
     pub fn add_module(&mut self, module: Module) {
         self.methods.extend(module.methods);
 
@@ -183,6 +202,11 @@ impl MethodRouting {
     }
 }
 
+/// Compares two API versions, `None` if either of them is not a valid version.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    Some(Versioning::new(a)?.cmp(&Versioning::new(b)?))
+}
+
 #[test]
 fn test_version_matching() {
     let routing = MethodRouting::eq("1.5", "test");
@@ -200,6 +224,17 @@ fn test_version_matching() {
     assert!(!routing.matches("1.5.1"));
 }
 
+#[test]
+fn test_compare_versions() {
+    assert_eq!(compare_versions("0.27.0", "0.28.0"), Some(Ordering::Less));
+    assert_eq!(compare_versions("0.28.0", "0.28.0"), Some(Ordering::Equal));
+    assert_eq!(compare_versions("1.0.0", "0.28.1"), Some(Ordering::Greater));
+    assert_eq!(
+        compare_versions("0.28.10", "0.28.9"),
+        Some(Ordering::Greater)
+    );
+}
+
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct ExamplePairing {
     name: String,
@@ -113,7 +113,14 @@ pub struct Error(pub String);
 struct FeatureFlags {
     // Add feature flags here, e.g.:
     // new_protocol_feature: bool,
+    // and to `ProtocolConfig::set_feature_flag_for_testing`.
     package_upgrades: bool,
+    // If true, transactions touching heavily used shared objects are deferred to later commits.
+    shared_object_congestion_control: bool,
+    // If true, validators accept soft bundles of certificates to be sequenced contiguously.
+    soft_bundle: bool,
+    // If true, the total size of the events emitted by a transaction is limited.
+    limit_events_size: bool,
 }
 
 /// Constants that change the behavior of the protocol.
@@ -155,6 +162,14 @@ pub struct ProtocolConfig {
     /// Maximum size of serialized transaction effects for system transactions.
     max_serialized_tx_effects_size_bytes_system_tx: Option<u64>,
 
+    /// Maximum total size of the serialized events emitted by a transaction.
+    /// Only used when the `limit_events_size` feature flag is set.
+    max_serialized_tx_events_size_bytes: Option<u64>,
+
+    /// Maximum total size of the serialized events emitted by a system transaction.
+    /// Only used when the `limit_events_size` feature flag is set.
+    max_serialized_tx_events_size_bytes_system_tx: Option<u64>,
+
     /// Maximum number of gas payment objects for a transaction.
     max_gas_payment_objects: Option<u32>,
 
@@ -358,6 +373,15 @@ pub struct ProtocolConfig {
     /// 3f+1 must vote), while 0bps would indicate that 2f+1 is sufficient.
     buffer_stake_for_protocol_upgrade_bps: Option<u64>,
 
+    /// Max number of transactions that may take a mutable reference to the same shared object in
+    /// a single consensus commit. Transactions over the limit are deferred to later commits.
+    /// Only used when the `shared_object_congestion_control` feature flag is set.
+    max_txns_per_shared_object_per_commit: Option<u64>,
+
+    /// Max number of certificates in a soft bundle.
+    /// Only used when the `soft_bundle` feature flag is set.
+    max_soft_bundle_size: Option<u64>,
+
     // === Native Function Costs ===
 
     // `address` module
@@ -541,6 +565,39 @@ impl ProtocolConfig {
             )))
         }
     }
+
+    pub fn check_shared_object_congestion_control_supported(&self) -> Result<(), Error> {
+        if self.feature_flags.shared_object_congestion_control {
+            Ok(())
+        } else {
+            Err(Error(format!(
+                "shared object congestion control is not supported at {:?}",
+                self.version
+            )))
+        }
+    }
+
+    pub fn check_soft_bundle_supported(&self) -> Result<(), Error> {
+        if self.feature_flags.soft_bundle {
+            Ok(())
+        } else {
+            Err(Error(format!(
+                "soft bundles are not supported at {:?}",
+                self.version
+            )))
+        }
+    }
+
+    pub fn check_limit_events_size_supported(&self) -> Result<(), Error> {
+        if self.feature_flags.limit_events_size {
+            Ok(())
+        } else {
+            Err(Error(format!(
+                "limiting the size of events is not supported at {:?}",
+                self.version
+            )))
+        }
+    }
 }
 
 // getters
@@ -559,6 +616,14 @@ impl ProtocolConfig {
         self.max_serialized_tx_effects_size_bytes_system_tx
             .expect(CONSTANT_ERR_MSG)
     }
+    pub fn max_serialized_tx_events_size_bytes(&self) -> u64 {
+        self.max_serialized_tx_events_size_bytes
+            .expect(CONSTANT_ERR_MSG)
+    }
+    pub fn max_serialized_tx_events_size_bytes_system_tx(&self) -> u64 {
+        self.max_serialized_tx_events_size_bytes_system_tx
+            .expect(CONSTANT_ERR_MSG)
+    }
     pub fn max_gas_payment_objects(&self) -> u32 {
         self.max_gas_payment_objects.expect(CONSTANT_ERR_MSG)
     }
@@ -744,6 +809,13 @@ impl ProtocolConfig {
         self.buffer_stake_for_protocol_upgrade_bps
             .expect(CONSTANT_ERR_MSG)
     }
+    pub fn max_txns_per_shared_object_per_commit(&self) -> u64 {
+        self.max_txns_per_shared_object_per_commit
+            .expect(CONSTANT_ERR_MSG)
+    }
+    pub fn max_soft_bundle_size(&self) -> u64 {
+        self.max_soft_bundle_size.expect(CONSTANT_ERR_MSG)
+    }
 
     pub fn address_from_bytes_cost_base(&self) -> u64 {
         self.address_from_bytes_cost_base.expect(CONSTANT_ERR_MSG)
@@ -1227,6 +1299,10 @@ impl ProtocolConfig {
                 max_input_objects: Some(2048),
                 max_serialized_tx_effects_size_bytes: Some(512 * 1024),
                 max_serialized_tx_effects_size_bytes_system_tx: Some(512 * 1024 * 16),
+                // Events are not limited until a protocol version enables the
+                // `limit_events_size` feature flag.
+                max_serialized_tx_events_size_bytes: None,
+                max_serialized_tx_events_size_bytes_system_tx: None,
                 max_gas_payment_objects: Some(256),
                 max_modules_in_publish: Some(128),
                 max_arguments: Some(512),
@@ -1296,6 +1372,14 @@ impl ProtocolConfig {
                 // MUSTFIX: This number should be increased to at least 2000 (20%) for mainnet.
                 buffer_stake_for_protocol_upgrade_bps: Some(0),
 
+                // Congestion control is disabled until a protocol version enables the
+                // `shared_object_congestion_control` feature flag.
+                max_txns_per_shared_object_per_commit: None,
+
+                // Soft bundles are disabled until a protocol version enables the `soft_bundle`
+                // feature flag.
+                max_soft_bundle_size: None,
+
                 /// === Native Function Costs ===
                 // `address` module
                 // Cost params for the Move native function `address::from_bytes(bytes: vector<u8>)`
@@ -1501,6 +1585,42 @@ impl ProtocolConfig {
     pub fn set_package_upgrades_for_testing(&mut self, val: bool) {
         self.feature_flags.package_upgrades = val
     }
+    pub fn set_shared_object_congestion_control_for_testing(&mut self, val: bool) {
+        self.feature_flags.shared_object_congestion_control = val
+    }
+    pub fn set_max_txns_per_shared_object_per_commit_for_testing(&mut self, m: u64) {
+        self.max_txns_per_shared_object_per_commit = Some(m)
+    }
+    pub fn set_soft_bundle_for_testing(&mut self, val: bool) {
+        self.feature_flags.soft_bundle = val
+    }
+    pub fn set_max_soft_bundle_size_for_testing(&mut self, m: u64) {
+        self.max_soft_bundle_size = Some(m)
+    }
+    pub fn set_limit_events_size_for_testing(&mut self, val: bool) {
+        self.feature_flags.limit_events_size = val
+    }
+    pub fn set_max_serialized_tx_events_size_bytes_for_testing(&mut self, m: u64) {
+        self.max_serialized_tx_events_size_bytes = Some(m)
+    }
+    pub fn set_max_serialized_tx_events_size_bytes_system_tx_for_testing(&mut self, m: u64) {
+        self.max_serialized_tx_events_size_bytes_system_tx = Some(m)
+    }
+
+    /// Set a feature flag by the name of its field in `FeatureFlags`, so that test harnesses can
+    /// toggle flags without a dedicated setter for each of them.
+    pub fn set_feature_flag_for_testing(&mut self, name: &str, val: bool) {
+        let flag = match name {
+            "package_upgrades" => &mut self.feature_flags.package_upgrades,
+            "shared_object_congestion_control" => {
+                &mut self.feature_flags.shared_object_congestion_control
+            }
+            "soft_bundle" => &mut self.feature_flags.soft_bundle,
+            "limit_events_size" => &mut self.feature_flags.limit_events_size,
+            _ => panic!("unknown feature flag {name}"),
+        };
+        *flag = val;
+    }
 }
 
 type OverrideFn = dyn Fn(ProtocolVersion, ProtocolConfig) -> ProtocolConfig + Send;
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::error::{Error, SuiRpcResult};
+use crate::subscription::{event_subscription, transaction_subscription, SubscriptionConfig};
 use crate::{RpcClient, WAIT_FOR_TX_TIMEOUT_SEC};
 use fastcrypto::encoding::Base64;
 use futures::stream;
@@ -13,13 +14,15 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use sui_json_rpc::api::GovernanceReadApiClient;
 use sui_json_rpc_types::{
-    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake, DryRunTransactionResponse,
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, SuiCoinMetadata, SuiCommittee, SuiEvent,
-    SuiGetPastObjectRequest, SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiPastObjectResponse, SuiTransactionEffectsAPI,
-    SuiTransactionResponse, SuiTransactionResponseOptions, SuiTransactionResponseQuery,
-    TransactionsPage,
+    AvailableHistory, Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake,
+    DryRunTransactionResponse, DynamicFieldPage, EventFilter, EventPage, ObjectChangeFilter,
+    ObjectChangeNotification, ObjectsPage, SuiCoinMetadata, SuiCommittee, SuiEvent,
+    SuiGetPastObjectRequest, SuiMoveNormalizedFunction, SuiMoveNormalizedModule,
+    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiPastObjectResponse,
+    SuiTransactionEffectsAPI, SuiTransactionResponse, SuiTransactionResponseOptions,
+    SuiTransactionResponseQuery, TransactionCursor, TransactionsPage,
 };
+use sui_transaction_builder::coin_selection::CoinSelector;
 use sui_types::balance::Supply;
 use sui_types::base_types::{
     ObjectID, SequenceNumber, SuiAddress, TransactionDigest, TxSequenceNumber,
@@ -31,7 +34,9 @@ use sui_types::messages::{ExecuteTransactionRequestType, TransactionData, Verifi
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 use futures::StreamExt;
-use sui_json_rpc::api::{CoinReadApiClient, EventReadApiClient, ReadApiClient, WriteApiClient};
+use sui_json_rpc::api::{
+    CoinReadApiClient, EventReadApiClient, ObjectChangeApiClient, ReadApiClient, WriteApiClient,
+};
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 
 #[derive(Debug)]
@@ -168,7 +173,7 @@ impl ReadApi {
     pub async fn query_transactions(
         &self,
         query: SuiTransactionResponseQuery,
-        cursor: Option<TransactionDigest>,
+        cursor: Option<TransactionCursor>,
         limit: Option<usize>,
         descending_order: bool,
     ) -> SuiRpcResult<TransactionsPage> {
@@ -196,10 +201,27 @@ impl ReadApi {
             .into())
     }
 
+    /// Return the lowest checkpoint from which each kind of history is available on the node
+    pub async fn get_available_history(&self) -> SuiRpcResult<AvailableHistory> {
+        Ok(self.api.http.get_available_history().await?)
+    }
+
+    /// Subscribes to the transactions matching `query` executed after the transaction `cursor`,
+    /// or after the latest one if `None`. Transactions are queried again after a failure, so that
+    /// the stream delivers every transaction exactly once, see [crate::subscription].
+    pub fn subscribe_transactions_resilient(
+        &self,
+        query: SuiTransactionResponseQuery,
+        cursor: Option<TransactionCursor>,
+        config: SubscriptionConfig,
+    ) -> impl Stream<Item = SuiTransactionResponse> {
+        transaction_subscription(self.api.clone(), query, cursor, config)
+    }
+
     pub fn get_transactions_stream(
         &self,
         query: SuiTransactionResponseQuery,
-        cursor: Option<TransactionDigest>,
+        cursor: Option<TransactionCursor>,
         descending_order: bool,
     ) -> impl Stream<Item = SuiTransactionResponse> + '_ {
         stream::unfold(
@@ -234,6 +256,19 @@ impl ReadApi {
             .await?)
     }
 
+    pub async fn get_normalized_move_function(
+        &self,
+        package: ObjectID,
+        module: String,
+        function: String,
+    ) -> SuiRpcResult<SuiMoveNormalizedFunction> {
+        Ok(self
+            .api
+            .http
+            .get_normalized_move_function(package, module, function)
+            .await?)
+    }
+
     // TODO(devx): we can probably cache this given an epoch
     pub async fn get_reference_gas_price(&self) -> SuiRpcResult<u64> {
         Ok(self.api.http.get_reference_gas_price().await?.into())
@@ -246,7 +281,19 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&tx)?))
+            .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&tx)?), None)
+            .await?)
+    }
+
+    /// Dry runs the transaction, and returns a trace of its execution along with its effects.
+    pub async fn dry_run_transaction_with_trace(
+        &self,
+        tx: TransactionData,
+    ) -> SuiRpcResult<DryRunTransactionResponse> {
+        Ok(self
+            .api
+            .http
+            .dry_run_transaction(Base64::from_bytes(&bcs::to_bytes(&tx)?), Some(true))
             .await?)
     }
 }
@@ -341,6 +388,38 @@ impl CoinReadApi {
         Ok(coins)
     }
 
+    /// Like [Self::select_coins], but selects at most `max_coins` of all the matching coins with
+    /// `coin_selector`, rather than the first ones returned by the node.
+    pub async fn select_coins_with(
+        &self,
+        coin_selector: &dyn CoinSelector,
+        address: SuiAddress,
+        coin_type: Option<String>,
+        amount: u128,
+        locked_until_epoch: Option<EpochId>,
+        exclude: Vec<ObjectID>,
+        max_coins: usize,
+    ) -> SuiRpcResult<Vec<Coin>> {
+        let coins = self
+            .get_coins_stream(address, coin_type)
+            .filter(|coin: &Coin| {
+                future::ready(
+                    locked_until_epoch == coin.locked_until_epoch
+                        && !exclude.contains(&coin.coin_object_id),
+                )
+            })
+            .collect::<Vec<_>>()
+            .await;
+        let balances: Vec<_> = coins.iter().map(|coin| coin.balance).collect();
+        let selected = coin_selector
+            .select(&balances, amount, max_coins)
+            .ok_or(Error::InsufficientFund { address, amount })?;
+        Ok(selected
+            .into_iter()
+            .map(|index| coins[index].clone())
+            .collect())
+    }
+
     pub async fn get_balance(
         &self,
         owner: SuiAddress,
@@ -387,6 +466,35 @@ impl EventApi {
         }
     }
 
+    pub async fn subscribe_object_changes(
+        &self,
+        filter: ObjectChangeFilter,
+    ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<ObjectChangeNotification>>> {
+        match &self.api.ws {
+            Some(c) => {
+                let subscription: Subscription<ObjectChangeNotification> =
+                    c.subscribe_object_changes(filter).await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(Error::Subscription(
+                "Subscription only supported by WebSocket client.".to_string(),
+            )),
+        }
+    }
+
+    /// Subscribes to the events matching `filter` emitted after the event `cursor`, or after the
+    /// latest one if `None`. Dropped subscriptions are reconnected and the events missed in the
+    /// meantime backfilled, so that the stream delivers every event exactly once, see
+    /// [crate::subscription].
+    pub fn subscribe_event_resilient(
+        &self,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+        config: SubscriptionConfig,
+    ) -> SuiRpcResult<impl Stream<Item = SuiEvent>> {
+        event_subscription(self.api.clone(), filter, cursor, config)
+    }
+
     pub async fn get_events(&self, digest: TransactionDigest) -> SuiRpcResult<Vec<SuiEvent>> {
         Ok(self.api.http.get_events(digest).await?)
     }
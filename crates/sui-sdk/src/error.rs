@@ -5,6 +5,8 @@ use sui_types::base_types::{SuiAddress, TransactionDigest};
 use sui_types::error::UserInputError;
 use thiserror::Error;
 
+use crate::move_call::MoveCallError;
+
 pub type SuiRpcResult<T = ()> = Result<T, Error>;
 
 #[derive(Error, Debug)]
@@ -15,6 +17,8 @@ pub enum Error {
     PcsSerialisationError(#[from] bcs::Error),
     #[error(transparent)]
     UserInputError(#[from] UserInputError),
+    #[error(transparent)]
+    MoveCallError(#[from] MoveCallError),
     #[error("Subscription error : {0}")]
     Subscription(String),
     #[error("Encountered error when confirming tx status for {0:?}, err: {1:?}")]
@@ -28,6 +32,14 @@ pub enum Error {
         client_version: String,
         server_version: String,
     },
+    #[error(transparent)]
+    RestRequestError(#[from] reqwest::Error),
+    #[error("REST request {url} failed with status {status}: {message}")]
+    RestError {
+        url: String,
+        status: u16,
+        message: String,
+    },
     #[error("Insufficient fund for address [{address}], requested amount: {amount}")]
     InsufficientFund { address: SuiAddress, amount: u128 },
 }
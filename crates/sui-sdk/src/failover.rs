@@ -0,0 +1,253 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Requests spread over several full nodes, failing over the unreachable ones.
+//!
+//! A client has a primary endpoint, the URL it is built with, failover endpoints taking over when
+//! the primary is unreachable, and read replicas. Reads go to the read replicas in turn, then to
+//! the primary and failover endpoints; a read failing on the network is sent to the next endpoint
+//! right away. Transactions are executed by the primary, or by the first failover endpoint if the
+//! primary is unhealthy, and are never sent to a second endpoint.
+//!
+//! Endpoints are checked periodically: an endpoint is unhealthy when it doesn't respond, and a
+//! read replica also when it lags behind the most advanced endpoint by more checkpoints than
+//! allowed. Unhealthy endpoints are only tried once all the healthy ones failed. A read replica
+//! may not have executed a transaction the primary just executed yet.
+
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use jsonrpsee::core::client::{BatchResponse, ClientT, Subscription, SubscriptionClientT};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::core::Error;
+use jsonrpsee::http_client::HttpClient;
+use serde::de::DeserializeOwned;
+use sui_json_rpc::api::ReadApiClient;
+use tracing::{info, warn};
+
+use crate::middleware::{is_transient, RawParams, NON_IDEMPOTENT_METHODS};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Role {
+    Primary,
+    Failover,
+    ReadReplica,
+}
+
+/// The endpoints of a client, the primary first.
+pub(crate) struct Endpoints {
+    endpoints: Vec<Endpoint>,
+    /// The read replica reads start with, rotating.
+    next_replica: AtomicUsize,
+    /// Maximum number of checkpoints a healthy read replica lags behind.
+    max_replica_lag: u64,
+}
+
+struct Endpoint {
+    url: String,
+    client: HttpClient,
+    role: Role,
+    healthy: AtomicBool,
+}
+
+impl Endpoints {
+    pub(crate) fn new(endpoints: Vec<(String, HttpClient, Role)>, max_replica_lag: u64) -> Self {
+        debug_assert_eq!(
+            endpoints.first().map(|(_, _, role)| *role),
+            Some(Role::Primary)
+        );
+        Self {
+            endpoints: endpoints
+                .into_iter()
+                .map(|(url, client, role)| Endpoint {
+                    url,
+                    client,
+                    role,
+                    healthy: AtomicBool::new(true),
+                })
+                .collect(),
+            next_replica: AtomicUsize::new(0),
+            max_replica_lag,
+        }
+    }
+
+    /// Checks the health of the endpoints every `interval`, until they are dropped.
+    pub(crate) fn spawn_health_checks(self: &Arc<Self>, interval: Duration) {
+        if self.endpoints.len() < 2 {
+            return;
+        }
+        let endpoints: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let Some(endpoints) = endpoints.upgrade() else {
+                    return;
+                };
+                endpoints.check_health().await;
+            }
+        });
+    }
+
+    async fn check_health(&self) {
+        let checkpoints = join_all(
+            self.endpoints
+                .iter()
+                .map(|endpoint| endpoint.client.get_latest_checkpoint_sequence_number()),
+        )
+        .await;
+        let latest = checkpoints
+            .iter()
+            .flatten()
+            .map(|checkpoint| u64::from(*checkpoint))
+            .max();
+        for (endpoint, checkpoint) in self.endpoints.iter().zip(checkpoints) {
+            let healthy = match (checkpoint, latest) {
+                (Ok(checkpoint), Some(latest)) => {
+                    endpoint.role != Role::ReadReplica
+                        || latest - u64::from(checkpoint) <= self.max_replica_lag
+                }
+                _ => false,
+            };
+            endpoint.set_healthy(healthy);
+        }
+    }
+
+    /// The endpoints to send a request to in turn, the healthy ones first. Only the first one for
+    /// requests not to send twice.
+    fn candidates(&self, read: bool) -> Vec<&Endpoint> {
+        let mut candidates = vec![];
+        if read {
+            let replicas: Vec<_> = self
+                .endpoints
+                .iter()
+                .filter(|endpoint| endpoint.role == Role::ReadReplica)
+                .collect();
+            if !replicas.is_empty() {
+                let start = self.next_replica.fetch_add(1, Ordering::Relaxed) % replicas.len();
+                candidates.extend(replicas[start..].iter().chain(&replicas[..start]));
+            }
+        }
+        candidates.extend(
+            self.endpoints
+                .iter()
+                .filter(|endpoint| endpoint.role != Role::ReadReplica),
+        );
+        // Stable: the order is kept among healthy and among unhealthy endpoints.
+        candidates.sort_by_key(|endpoint| !endpoint.healthy.load(Ordering::Relaxed));
+        if !read {
+            candidates.truncate(1);
+        }
+        candidates
+    }
+
+    fn is_read(method: &str) -> bool {
+        !NON_IDEMPOTENT_METHODS.contains(&method)
+    }
+}
+
+impl Endpoint {
+    fn set_healthy(&self, healthy: bool) {
+        if self.healthy.swap(healthy, Ordering::Relaxed) != healthy {
+            if healthy {
+                info!("{:?} endpoint {} is healthy again", self.role, self.url);
+            } else {
+                warn!("{:?} endpoint {} is unhealthy", self.role, self.url);
+            }
+        }
+    }
+}
+
+impl Debug for Endpoints {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(
+                self.endpoints
+                    .iter()
+                    .map(|endpoint| (endpoint.role, &endpoint.url)),
+            )
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ClientT for Endpoints {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), Error>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let endpoint = self.candidates(false)[0];
+        endpoint.client.notification(method, params).await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let candidates = self.candidates(Self::is_read(method));
+        let (last, others) = candidates.split_last().expect("the primary is a candidate");
+        if others.is_empty() {
+            return last.client.request(method, params).await;
+        }
+        // Serialized once, to be sent to every candidate.
+        let params = RawParams(params.to_rpc_params().map_err(Error::ParseError)?);
+        for endpoint in others {
+            match endpoint.client.request(method, params.clone()).await {
+                Err(e) if is_transient(&e) => {
+                    warn!("{method} failed on {}, failing over: {e}", endpoint.url);
+                    endpoint.set_healthy(false);
+                }
+                result => return result,
+            }
+        }
+        last.client.request(method, params).await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, Error>
+    where
+        R: DeserializeOwned + Debug + 'a,
+    {
+        // A batch may hold requests not to send twice.
+        let endpoint = self.candidates(false)[0];
+        endpoint.client.batch_request(batch).await
+    }
+}
+
+#[async_trait]
+impl SubscriptionClientT for Endpoints {
+    async fn subscribe<'a, Notif, Params>(
+        &self,
+        subscribe_method: &'a str,
+        params: Params,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, Error>
+    where
+        Params: ToRpcParams + Send,
+        Notif: DeserializeOwned,
+    {
+        let endpoint = self.candidates(false)[0];
+        endpoint
+            .client
+            .subscribe(subscribe_method, params, unsubscribe_method)
+            .await
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, Error>
+    where
+        Notif: DeserializeOwned,
+    {
+        let endpoint = self.candidates(false)[0];
+        endpoint.client.subscribe_to_method(method).await
+    }
+}
@@ -8,7 +8,7 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use jsonrpsee::core::client::ClientT;
-use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
+use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClientBuilder};
 use jsonrpsee::rpc_params;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 
@@ -16,20 +16,34 @@ use crate::error::{Error, SuiRpcResult};
 use serde_json::Value;
 use sui_adapter::execution_mode::Normal;
 pub use sui_json as json;
+pub use sui_keys as keys;
 
 use crate::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriver, ReadApi};
+use crate::failover::{Endpoints, Role};
+use crate::middleware::{Middleware, MiddlewareClient};
 use sui_json_rpc::{
     CLIENT_SDK_TYPE_HEADER, CLIENT_SDK_VERSION_HEADER, CLIENT_TARGET_API_VERSION_HEADER,
 };
 pub use sui_json_rpc_types as rpc_types;
 use sui_json_rpc_types::{
-    CheckpointId, ObjectsPage, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
+    CheckpointId, ClientWarning, ObjectsPage, RpcCapabilities, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery,
 };
+pub use sui_transaction_builder::coin_selection;
+use sui_transaction_builder::coin_selection::CoinSelector;
+pub use sui_transaction_builder::MoveCallArg;
 use sui_transaction_builder::{DataReader, TransactionBuilder};
 pub use sui_types as types;
 use sui_types::base_types::{ObjectID, SuiAddress};
 pub mod apis;
 pub mod error;
+mod failover;
+#[cfg(feature = "local-execution")]
+pub mod local_execution;
+pub mod middleware;
+pub mod move_call;
+pub mod rest;
+pub mod subscription;
 pub const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
 const WAIT_FOR_TX_TIMEOUT_SEC: u64 = 60;
 
@@ -37,6 +51,11 @@ pub struct SuiClientBuilder {
     request_timeout: Duration,
     max_concurrent_requests: usize,
     ws_url: Option<String>,
+    coin_selector: Option<Arc<dyn CoinSelector>>,
+    middleware: Middleware,
+    endpoints: Vec<(String, Role)>,
+    health_check_interval: Duration,
+    max_replica_lag: u64,
 }
 
 impl Default for SuiClientBuilder {
@@ -45,6 +64,11 @@ impl Default for SuiClientBuilder {
             request_timeout: Duration::from_secs(60),
             max_concurrent_requests: 256,
             ws_url: None,
+            coin_selector: None,
+            middleware: Middleware::default(),
+            endpoints: vec![],
+            health_check_interval: Duration::from_secs(10),
+            max_replica_lag: 5,
         }
     }
 }
@@ -65,6 +89,49 @@ impl SuiClientBuilder {
         self
     }
 
+    /// The strategy the transaction builder selects gas coins with, [coin_selection::LargestFirst]
+    /// unless set.
+    pub fn coin_selector(mut self, coin_selector: Arc<dyn CoinSelector>) -> Self {
+        self.coin_selector = Some(coin_selector);
+        self
+    }
+
+    /// The timeouts, retries and circuit breaker of the JSON-RPC requests of the client.
+    pub fn middleware(mut self, middleware: Middleware) -> Self {
+        self.middleware = middleware;
+        self
+    }
+
+    /// A full node taking over when the one the client is built with is unreachable, the first
+    /// added first.
+    pub fn failover_url(mut self, url: impl AsRef<str>) -> Self {
+        self.endpoints
+            .push((url.as_ref().to_string(), Role::Failover));
+        self
+    }
+
+    /// A full node to send reads to, in turn with the other read replicas, instead of the one the
+    /// client is built with. Transactions are still executed by the latter.
+    pub fn read_replica_url(mut self, url: impl AsRef<str>) -> Self {
+        self.endpoints
+            .push((url.as_ref().to_string(), Role::ReadReplica));
+        self
+    }
+
+    /// How often the health of the endpoints is checked when there are several, every 10 seconds
+    /// unless set.
+    pub fn health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// How many checkpoints a read replica may lag behind the other endpoints before reads avoid
+    /// it, 5 unless set.
+    pub fn max_replica_lag(mut self, checkpoints: u64) -> Self {
+        self.max_replica_lag = checkpoints;
+        self
+    }
+
     pub async fn build(self, http: impl AsRef<str>) -> SuiRpcResult<SuiClient> {
         let client_version = env!("CARGO_PKG_VERSION");
         let mut headers = HeaderMap::new();
@@ -79,35 +146,50 @@ impl SuiClientBuilder {
         );
         headers.insert(CLIENT_SDK_TYPE_HEADER, HeaderValue::from_static("rust"));
 
-        let ws = if let Some(url) = self.ws_url {
-            Some(
-                WsClientBuilder::default()
-                    .max_request_body_size(2 << 30)
-                    .max_concurrent_requests(self.max_concurrent_requests)
-                    .set_headers(headers.clone())
-                    .request_timeout(self.request_timeout)
-                    .build(url)
-                    .await?,
-            )
+        let ws_config = self.ws_url.map(|url| WsConfig {
+            url,
+            headers: headers.clone(),
+            max_concurrent_requests: self.max_concurrent_requests,
+            request_timeout: self.request_timeout,
+        });
+        let ws = if let Some(ws_config) = &ws_config {
+            Some(ws_config.connect().await?)
         } else {
             None
         };
 
-        let http = HttpClientBuilder::default()
-            .max_request_body_size(2 << 30)
-            .max_concurrent_requests(self.max_concurrent_requests)
-            .set_headers(headers.clone())
-            .request_timeout(self.request_timeout)
-            .build(http)?;
+        let urls =
+            std::iter::once((http.as_ref().to_string(), Role::Primary)).chain(self.endpoints);
+        let mut endpoints = vec![];
+        for (url, role) in urls {
+            let http = HttpClientBuilder::default()
+                .max_request_body_size(2 << 30)
+                .max_concurrent_requests(self.max_concurrent_requests)
+                .set_headers(headers.clone())
+                .request_timeout(self.request_timeout)
+                .build(&url)?;
+            endpoints.push((url, http, role));
+        }
+        let http = Arc::new(Endpoints::new(endpoints, self.max_replica_lag));
 
+        // Served by the first reachable endpoint.
         let info = Self::get_server_info(&http, &ws).await?;
+        http.spawn_health_checks(self.health_check_interval);
 
-        let rpc = RpcClient { http, ws, info };
+        let rpc = RpcClient {
+            http: MiddlewareClient::new(http, self.middleware),
+            ws,
+            ws_config,
+            info,
+        };
         let api = Arc::new(rpc);
         let read_api = Arc::new(ReadApi::new(api.clone()));
         let quorum_driver = QuorumDriver::new(api.clone());
         let event_api = EventApi::new(api.clone());
-        let transaction_builder = TransactionBuilder::new(read_api.clone());
+        let mut transaction_builder = TransactionBuilder::new(read_api.clone());
+        if let Some(coin_selector) = self.coin_selector {
+            transaction_builder = transaction_builder.with_coin_selector(coin_selector);
+        }
         let coin_read_api = CoinReadApi::new(api.clone());
         let governance_api = GovernanceApi::new(api.clone());
 
@@ -122,10 +204,7 @@ impl SuiClientBuilder {
         })
     }
 
-    async fn get_server_info(
-        http: &HttpClient,
-        ws: &Option<WsClient>,
-    ) -> Result<ServerInfo, Error> {
+    async fn get_server_info(http: &Endpoints, ws: &Option<WsClient>) -> Result<ServerInfo, Error> {
         let rpc_spec: Value = http.request("rpc.discover", rpc_params![]).await?;
         let version = rpc_spec
             .pointer("/info/version")
@@ -134,6 +213,11 @@ impl SuiClientBuilder {
                 Error::DataError("Fail parsing server version from rpc.discover endpoint.".into())
             })?;
         let rpc_methods = Self::parse_methods(&rpc_spec)?;
+        // Servers older than the capability negotiation do not serve `rpc.capabilities`.
+        let capabilities = http
+            .request("rpc.capabilities", rpc_params![env!("CARGO_PKG_VERSION")])
+            .await
+            .ok();
 
         let subscriptions = if let Some(ws) = ws {
             let rpc_spec: Value = ws.request("rpc.discover", rpc_params![]).await?;
@@ -145,6 +229,7 @@ impl SuiClientBuilder {
             rpc_methods,
             subscriptions,
             version: version.to_string(),
+            capabilities,
         })
     }
 
@@ -179,11 +264,32 @@ pub struct SuiClient {
 }
 
 pub(crate) struct RpcClient {
-    http: HttpClient,
+    http: MiddlewareClient,
     ws: Option<WsClient>,
+    ws_config: Option<WsConfig>,
     info: ServerInfo,
 }
 
+/// How to open a websocket connection to the node, again when a subscription needs to reconnect.
+pub(crate) struct WsConfig {
+    url: String,
+    headers: HeaderMap,
+    max_concurrent_requests: usize,
+    request_timeout: Duration,
+}
+
+impl WsConfig {
+    pub(crate) async fn connect(&self) -> SuiRpcResult<WsClient> {
+        Ok(WsClientBuilder::default()
+            .max_request_body_size(2 << 30)
+            .max_concurrent_requests(self.max_concurrent_requests)
+            .set_headers(self.headers.clone())
+            .request_timeout(self.request_timeout)
+            .build(&self.url)
+            .await?)
+    }
+}
+
 impl Debug for RpcClient {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -198,6 +304,7 @@ struct ServerInfo {
     rpc_methods: Vec<String>,
     subscriptions: Vec<String>,
     version: String,
+    capabilities: Option<RpcCapabilities>,
 }
 
 impl SuiClient {
@@ -213,6 +320,19 @@ impl SuiClient {
         &self.api.info.version
     }
 
+    /// The capabilities of the server, `None` if it does not support capability negotiation.
+    pub fn capabilities(&self) -> Option<&RpcCapabilities> {
+        self.api.info.capabilities.as_ref()
+    }
+
+    /// Warnings of the server for the API version of this client, e.g. when the server requires
+    /// a newer client.
+    pub fn client_warnings(&self) -> &[ClientWarning] {
+        self.capabilities()
+            .map(|c| c.client_warnings.as_slice())
+            .unwrap_or_default()
+    }
+
     pub fn check_api_version(&self) -> SuiRpcResult<()> {
         let server_version = self.api_version();
         let client_version = env!("CARGO_PKG_VERSION");
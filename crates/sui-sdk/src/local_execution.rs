@@ -0,0 +1,341 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Predicts the effects of transactions by executing them locally, against objects supplied by
+//! the client rather than the state of a fullnode. The protocol config and epoch can be fetched
+//! from a fullnode once, or supplied directly, so that transaction construction logic can be unit
+//! tested offline and deterministically.
+//!
+//! Transactions are executed by the same execution layer as validators, but the objects they
+//! read are only those known to the [`LocalExecutor`], so the predicted effects match the actual
+//! ones only if those objects are up to date.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use move_vm_runtime::move_vm::MoveVM;
+use sui_adapter::{adapter, execution_engine, execution_mode};
+use sui_json_rpc_types::SuiObjectDataOptions;
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_types::base_types::{ObjectID, TransactionDigest};
+use sui_types::crypto::default_hash;
+use sui_types::epoch_data::EpochData;
+use sui_types::error::{ExecutionError, UserInputError, UserInputResult};
+use sui_types::gas::{self, SuiCostTable, SuiGasStatus};
+use sui_types::in_memory_storage::InMemoryStorage;
+use sui_types::messages::{
+    InputObjectKind, InputObjects, TransactionData, TransactionDataAPI, TransactionEffects,
+    TransactionEffectsAPI, TransactionEvents,
+};
+use sui_types::object::{Object, Owner};
+use sui_types::temporary_store::{InnerTemporaryStore, TemporaryStore};
+
+use crate::error::{Error, SuiRpcResult};
+use crate::SuiClient;
+
+/// The outcome of a locally executed transaction.
+#[derive(Debug)]
+pub struct LocalExecutionResult {
+    pub effects: TransactionEffects,
+    pub events: TransactionEvents,
+    /// Objects created, mutated or unwrapped by the transaction, at their new versions.
+    pub written: Vec<Object>,
+    /// Why execution failed, if it did. Gas is charged regardless, as reflected by `effects`.
+    pub execution_error: Option<ExecutionError>,
+}
+
+/// Executes transactions against a local set of objects, with a fixed protocol config and epoch.
+pub struct LocalExecutor {
+    protocol_config: ProtocolConfig,
+    epoch_data: EpochData,
+    reference_gas_price: u64,
+    move_vm: Arc<MoveVM>,
+    store: InMemoryStorage,
+}
+
+impl LocalExecutor {
+    /// Creates an executor without any object, not even the system packages, see
+    /// [`LocalExecutor::with_system_packages`].
+    pub fn new(
+        protocol_config: ProtocolConfig,
+        epoch: u64,
+        epoch_start_timestamp_ms: u64,
+        reference_gas_price: u64,
+    ) -> Self {
+        let move_vm = Arc::new(
+            adapter::new_move_vm(sui_framework::natives::all_natives(), &protocol_config)
+                .expect("We defined natives to not fail here"),
+        );
+        Self {
+            protocol_config,
+            epoch_data: EpochData::new(epoch, epoch_start_timestamp_ms, Default::default()),
+            reference_gas_price,
+            move_vm,
+            store: InMemoryStorage::default(),
+        }
+    }
+
+    /// Creates an executor with the protocol config, epoch and reference gas price currently in
+    /// effect on the network of `client`. No object is fetched.
+    pub async fn from_fullnode(client: &SuiClient) -> SuiRpcResult<Self> {
+        let system_state = client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await?;
+        let protocol_version = ProtocolVersion::new(system_state.protocol_version);
+        if protocol_version > ProtocolVersion::MAX {
+            return Err(Error::DataError(format!(
+                "Protocol version {} of the network is not supported by this SDK, which supports \
+                 up to version {}",
+                system_state.protocol_version,
+                ProtocolVersion::MAX.as_u64()
+            )));
+        }
+        Ok(Self::new(
+            ProtocolConfig::get_for_version(protocol_version),
+            system_state.epoch,
+            system_state.epoch_start_timestamp_ms,
+            system_state.reference_gas_price,
+        ))
+    }
+
+    /// Adds the system packages built into this SDK, such as the Sui framework.
+    pub fn with_system_packages(mut self) -> Self {
+        for package in sui_framework::make_system_objects() {
+            self.store.insert_object(package);
+        }
+        self
+    }
+
+    pub fn protocol_config(&self) -> &ProtocolConfig {
+        &self.protocol_config
+    }
+
+    /// Adds `object`, replacing any object with the same ID.
+    pub fn insert_object(&mut self, object: Object) {
+        self.store.insert_object(object);
+    }
+
+    pub fn get_object(&self, object_id: &ObjectID) -> Option<&Object> {
+        self.store.get_object(object_id)
+    }
+
+    /// Fetches the current version of `object_ids` from the fullnode of `client`, replacing the
+    /// local objects with the same IDs.
+    pub async fn fetch_objects(
+        &mut self,
+        client: &SuiClient,
+        object_ids: Vec<ObjectID>,
+    ) -> SuiRpcResult<()> {
+        let responses = client
+            .read_api()
+            .multi_get_object_with_options(object_ids, SuiObjectDataOptions::bcs_lossless())
+            .await?;
+        for response in responses {
+            let data = response
+                .into_object()
+                .map_err(|err| Error::DataError(err.to_string()))?;
+            let object: Object = data
+                .try_into()
+                .map_err(|err: anyhow::Error| Error::DataError(err.to_string()))?;
+            self.store.insert_object(object);
+        }
+        Ok(())
+    }
+
+    /// Predicts the outcome of `transaction`, leaving the local objects unchanged.
+    pub fn simulate(
+        &mut self,
+        transaction: &TransactionData,
+    ) -> SuiRpcResult<LocalExecutionResult> {
+        let (result, _) = self.execute_impl(transaction)?;
+        Ok(result)
+    }
+
+    /// Executes `transaction` and applies its effects to the local objects, so that transactions
+    /// depending on each other can be executed in sequence.
+    pub fn execute(&mut self, transaction: &TransactionData) -> SuiRpcResult<LocalExecutionResult> {
+        let (result, inner_temporary_store) = self.execute_impl(transaction)?;
+        self.store
+            .finish(inner_temporary_store.written, inner_temporary_store.deleted);
+        Ok(result)
+    }
+
+    fn execute_impl(
+        &mut self,
+        transaction: &TransactionData,
+    ) -> SuiRpcResult<(LocalExecutionResult, InnerTemporaryStore)> {
+        transaction.validity_check(&self.protocol_config)?;
+        let objects = self.check_input_objects(transaction)?;
+        let gas_status = self.check_gas(transaction, &objects)?;
+        let input_objects = InputObjects::new(objects);
+
+        let transaction_digest = TransactionDigest::new(default_hash(transaction));
+        let shared_object_refs = input_objects.filter_shared_objects();
+        let transaction_dependencies = input_objects.transaction_dependencies();
+        let temporary_store = TemporaryStore::new(
+            &mut self.store,
+            input_objects,
+            transaction_digest,
+            &self.protocol_config,
+        );
+        let (kind, signer, gas) = transaction.execution_parts();
+        let (inner_temporary_store, effects, execution_result) =
+            execution_engine::execute_transaction_to_effects::<execution_mode::Normal, _>(
+                shared_object_refs,
+                temporary_store,
+                kind,
+                signer,
+                &gas,
+                transaction_digest,
+                transaction_dependencies,
+                &self.move_vm,
+                gas_status,
+                &self.epoch_data,
+                &self.protocol_config,
+            );
+        debug_assert_eq!(effects.transaction_digest(), &transaction_digest);
+
+        let result = LocalExecutionResult {
+            events: inner_temporary_store.events.clone(),
+            written: inner_temporary_store
+                .written
+                .values()
+                .map(|(_, object, _)| object.clone())
+                .collect(),
+            execution_error: execution_result.err(),
+            effects,
+        };
+        Ok((result, inner_temporary_store))
+    }
+
+    /// Loads the input objects of `transaction`, which must be the local objects at the versions
+    /// referenced, owned by the sender or, for gas, the gas owner.
+    fn check_input_objects(
+        &self,
+        transaction: &TransactionData,
+    ) -> UserInputResult<Vec<(InputObjectKind, Object)>> {
+        let gas_object_ids: HashSet<_> = transaction.gas().iter().map(|(id, _, _)| *id).collect();
+        let mut mutable_objects = HashSet::new();
+        let mut objects = vec![];
+        for kind in transaction.input_objects()? {
+            let object_id = kind.object_id();
+            let object = match kind {
+                InputObjectKind::MovePackage(package_id) => {
+                    let package = self
+                        .store
+                        .get_object(&package_id)
+                        .filter(|object| object.is_package())
+                        .ok_or(UserInputError::DependentPackageNotFound { package_id })?;
+                    objects.push((kind, package.clone()));
+                    continue;
+                }
+                InputObjectKind::ImmOrOwnedMoveObject((_, version, digest)) => {
+                    let object = self.store.get_object(&object_id).ok_or(
+                        UserInputError::ObjectNotFound {
+                            object_id,
+                            version: Some(version),
+                        },
+                    )?;
+                    if object.version() != version {
+                        return Err(UserInputError::ObjectVersionUnavailableForConsumption {
+                            provided_obj_ref: (object_id, version, digest),
+                            current_version: object.version(),
+                        });
+                    }
+                    if object.digest() != digest {
+                        return Err(UserInputError::InvalidObjectDigest {
+                            object_id,
+                            expected_digest: object.digest(),
+                        });
+                    }
+                    let owner = if gas_object_ids.contains(&object_id) {
+                        transaction.gas_owner()
+                    } else {
+                        transaction.sender()
+                    };
+                    match object.owner {
+                        Owner::Immutable => (),
+                        Owner::AddressOwner(actual_owner) if actual_owner == owner => (),
+                        Owner::AddressOwner(actual_owner) => {
+                            return Err(UserInputError::IncorrectUserSignature {
+                                error: format!(
+                                    "Object {object_id} is owned by {actual_owner}, not {owner}"
+                                ),
+                            })
+                        }
+                        Owner::ObjectOwner(parent) => {
+                            return Err(UserInputError::InvalidChildObjectArgument {
+                                child_id: object_id,
+                                parent_id: parent.into(),
+                            })
+                        }
+                        Owner::Shared { .. } => return Err(UserInputError::NotSharedObjectError),
+                    }
+                    object
+                }
+                InputObjectKind::SharedMoveObject { .. } => {
+                    let object = self.store.get_object(&object_id).ok_or(
+                        UserInputError::ObjectNotFound {
+                            object_id,
+                            version: None,
+                        },
+                    )?;
+                    if !object.is_shared() {
+                        return Err(UserInputError::NotSharedObjectError);
+                    }
+                    object
+                }
+            };
+            if !object.is_immutable() && !mutable_objects.insert(object_id) {
+                return Err(UserInputError::MutableObjectUsedMoreThanOnce { object_id });
+            }
+            objects.push((kind, object.clone()));
+        }
+        Ok(objects)
+    }
+
+    fn check_gas(
+        &self,
+        transaction: &TransactionData,
+        objects: &[(InputObjectKind, Object)],
+    ) -> UserInputResult<SuiGasStatus<'static>> {
+        let gas_price = transaction.gas_price();
+        if gas_price < self.reference_gas_price {
+            return Err(UserInputError::GasPriceUnderRGP {
+                gas_price,
+                reference_gas_price: self.reference_gas_price,
+            });
+        }
+        let mut gas_objects = transaction.gas().iter().map(|(object_id, version, _)| {
+            objects
+                .iter()
+                .find(|(_, object)| object.id() == *object_id)
+                .map(|(_, object)| object.clone())
+                .ok_or(UserInputError::ObjectNotFound {
+                    object_id: *object_id,
+                    version: Some(*version),
+                })
+        });
+        let gas_object = gas_objects
+            .next()
+            .ok_or(UserInputError::MissingGasPayment)??;
+        let more_gas_objects = gas_objects.collect::<UserInputResult<Vec<_>>>()?;
+
+        let cost_table = SuiCostTable::new(&self.protocol_config);
+        let storage_gas_price = self.protocol_config.storage_gas_price();
+        gas::check_gas_balance(
+            &gas_object,
+            transaction.gas_budget(),
+            std::cmp::max(gas_price, storage_gas_price),
+            more_gas_objects,
+            &cost_table,
+        )?;
+        gas::start_gas_metering(
+            transaction.gas_budget(),
+            gas_price,
+            storage_gas_price,
+            cost_table,
+        )
+    }
+}
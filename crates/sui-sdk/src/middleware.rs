@@ -0,0 +1,408 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Timeouts, retries and a circuit breaker around the JSON-RPC requests of a [crate::SuiClient].
+//!
+//! Every request of the client goes through the [Middleware] set with
+//! [crate::SuiClientBuilder::middleware]:
+//! - A method can have a timeout of its own, shorter than the request timeout of the client.
+//! - Requests failing on the network or timing out are retried with a jittered exponential
+//!   backoff, except for requests which are not idempotent, such as executing a transaction.
+//! - After consecutive failures, the circuit breaker fails requests without sending them until
+//!   the endpoint had time to recover, then lets a single request through to probe it.
+//!
+//! [RequestObserver]s are told of every attempt, retry and rejection, e.g. to export metrics.
+//! Errors returned by the node, such as invalid params, neither count as failures nor are
+//! retried: the endpoint is healthy.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use jsonrpsee::core::client::{BatchResponse, ClientT, Subscription, SubscriptionClientT};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::core::Error;
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+
+use crate::failover::Endpoints;
+
+/// Methods which must not be sent twice, never retried.
+pub(crate) const NON_IDEMPOTENT_METHODS: &[&str] = &["sui_executeTransaction"];
+/// Name of batch requests, for timeouts and observers.
+const BATCH_METHOD: &str = "batch";
+
+/// The timeouts, retries, circuit breaker and observers of the requests of a client.
+#[derive(Clone, Default)]
+pub struct Middleware {
+    method_timeouts: HashMap<String, Duration>,
+    retry_policy: Option<RetryPolicy>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
+    observers: Vec<Arc<dyn RequestObserver>>,
+}
+
+impl Middleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fails the requests to `method`, e.g. `sui_getObject`, not answered within `timeout`. The
+    /// request timeout of the client applies to all requests, this one can only be shorter.
+    pub fn method_timeout(mut self, method: impl Into<String>, timeout: Duration) -> Self {
+        self.method_timeouts.insert(method.into(), timeout);
+        self
+    }
+
+    /// Retries the idempotent requests failing on the network or timing out.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Fails requests fast while the endpoint is failing.
+    pub fn circuit_breaker(mut self, circuit_breaker: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Tells `observer` of the requests, in addition to the observers already added.
+    pub fn observer(mut self, observer: Arc<dyn RequestObserver>) -> Self {
+        self.observers.push(observer);
+        self
+    }
+}
+
+impl Debug for Middleware {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Middleware")
+            .field("method_timeouts", &self.method_timeouts)
+            .field("retry_policy", &self.retry_policy)
+            .field("circuit_breaker", &self.circuit_breaker)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
+
+/// Retries of a failed request, with exponentially growing delays.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every retry.
+    pub initial_delay: Duration,
+    /// Maximum delay between two attempts.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before the retry `retry`, counted from 1: between half and all of the backoff,
+    /// so that clients failing together don't retry together.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+/// When the circuit breaker opens, and for how long.
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerConfig {
+    /// Number of consecutive failed requests opening the circuit.
+    pub failure_threshold: u32,
+    /// Time requests fail without being sent once the circuit is open, before a request probes
+    /// the endpoint.
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent.
+    Closed,
+    /// Requests fail without being sent.
+    Open,
+    /// A single request is sent to probe the endpoint, the others fail without being sent.
+    HalfOpen,
+}
+
+/// Hooks called on the requests of a client, e.g. to export metrics. Requests are named by their
+/// method, or `batch` for batch requests.
+pub trait RequestObserver: Send + Sync {
+    /// The attempt `attempt`, counted from 1, of a request to `method` completed after `elapsed`,
+    /// with `error` if it failed.
+    fn on_attempt(&self, _method: &str, _attempt: u32, _elapsed: Duration, _error: Option<&Error>) {
+    }
+
+    /// A failed request to `method` is retried after `delay`, for the attempt `attempt`.
+    fn on_retry(&self, _method: &str, _attempt: u32, _delay: Duration) {}
+
+    /// A request to `method` failed without being sent, the circuit being open.
+    fn on_rejected(&self, _method: &str) {}
+
+    /// The circuit breaker changed to `state`.
+    fn on_circuit_state(&self, _state: CircuitState) {}
+}
+
+/// The HTTP clients of the endpoints, with the middleware applied to their requests.
+pub(crate) struct MiddlewareClient {
+    client: Arc<Endpoints>,
+    middleware: Middleware,
+    breaker: Option<Mutex<Breaker>>,
+}
+
+impl MiddlewareClient {
+    pub(crate) fn new(client: Arc<Endpoints>, middleware: Middleware) -> Self {
+        let breaker = middleware.circuit_breaker.clone().map(|config| {
+            Mutex::new(Breaker {
+                config,
+                state: BreakerState::Closed { failures: 0 },
+            })
+        });
+        Self {
+            client,
+            middleware,
+            breaker,
+        }
+    }
+
+    /// Sends a request with `send`, again on transient failures up to `max_retries` times.
+    async fn call<T, F, Fut>(&self, method: &str, max_retries: u32, mut send: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let timeout = self.middleware.method_timeouts.get(method).copied();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            if !self.admit() {
+                self.observe(|observer| observer.on_rejected(method));
+                return Err(Error::Transport(anyhow::anyhow!(
+                    "The circuit breaker is open after consecutive failures of the endpoint, \
+                     {method} was not sent"
+                )));
+            }
+            // The result is scoped to this block, so that `T` doesn't have to be `Send`.
+            let error = {
+                let started = Instant::now();
+                let result = match timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, send())
+                        .await
+                        .unwrap_or(Err(Error::RequestTimeout)),
+                    None => send().await,
+                };
+                let error = result.as_ref().err();
+                self.record(error.map_or(false, is_transient));
+                self.observe(|observer| {
+                    observer.on_attempt(method, attempt, started.elapsed(), error)
+                });
+                match result {
+                    Ok(value) => return Ok(value),
+                    Err(error) => error,
+                }
+            };
+            let retry_policy = match &self.middleware.retry_policy {
+                Some(retry_policy) if attempt <= max_retries && is_transient(&error) => {
+                    retry_policy
+                }
+                _ => return Err(error),
+            };
+            let delay = retry_policy.delay(attempt);
+            self.observe(|observer| observer.on_retry(method, attempt + 1, delay));
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn max_retries(&self, method: &str) -> u32 {
+        match &self.middleware.retry_policy {
+            Some(retry_policy) if !NON_IDEMPOTENT_METHODS.contains(&method) => {
+                retry_policy.max_retries
+            }
+            _ => 0,
+        }
+    }
+
+    /// Whether a request can be sent.
+    fn admit(&self) -> bool {
+        let Some(breaker) = &self.breaker else {
+            return true;
+        };
+        let mut breaker = breaker.lock().unwrap();
+        match breaker.state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { until } if Instant::now() >= until => {
+                breaker.state = BreakerState::HalfOpen;
+                self.observe(|observer| observer.on_circuit_state(CircuitState::HalfOpen));
+                true
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen => false,
+        }
+    }
+
+    /// Records the outcome of a request sent.
+    fn record(&self, failed: bool) {
+        let Some(breaker) = &self.breaker else {
+            return;
+        };
+        let mut breaker = breaker.lock().unwrap();
+        let open = BreakerState::Open {
+            until: Instant::now() + breaker.config.open_duration,
+        };
+        let (state, changed) = match (breaker.state, failed) {
+            (BreakerState::Closed { failures }, true)
+                if failures + 1 >= breaker.config.failure_threshold =>
+            {
+                (open, Some(CircuitState::Open))
+            }
+            (BreakerState::Closed { failures }, true) => (
+                BreakerState::Closed {
+                    failures: failures + 1,
+                },
+                None,
+            ),
+            (BreakerState::Closed { .. }, false) => (BreakerState::Closed { failures: 0 }, None),
+            (BreakerState::HalfOpen, true) => (open, Some(CircuitState::Open)),
+            (BreakerState::HalfOpen, false) => (
+                BreakerState::Closed { failures: 0 },
+                Some(CircuitState::Closed),
+            ),
+            // A request sent before the circuit opened.
+            (state @ BreakerState::Open { .. }, _) => (state, None),
+        };
+        breaker.state = state;
+        if let Some(changed) = changed {
+            self.observe(|observer| observer.on_circuit_state(changed));
+        }
+    }
+
+    fn observe(&self, f: impl Fn(&dyn RequestObserver)) {
+        for observer in &self.middleware.observers {
+            f(observer.as_ref());
+        }
+    }
+}
+
+impl Debug for MiddlewareClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} with {:?}", self.client, self.middleware)
+    }
+}
+
+#[async_trait]
+impl ClientT for MiddlewareClient {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), Error>
+    where
+        Params: ToRpcParams + Send,
+    {
+        self.client.notification(method, params).await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        // Serialized once, to be sent again on retries.
+        let params = RawParams(params.to_rpc_params().map_err(Error::ParseError)?);
+        self.call(method, self.max_retries(method), || {
+            self.client.request::<R, _>(method, params.clone())
+        })
+        .await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, Error>
+    where
+        R: DeserializeOwned + Debug + 'a,
+    {
+        // A batch may hold non idempotent requests, it is never retried.
+        let mut batch = Some(batch);
+        self.call(BATCH_METHOD, 0, || {
+            let batch = batch.take().expect("batch requests are sent once");
+            self.client.batch_request(batch)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SubscriptionClientT for MiddlewareClient {
+    async fn subscribe<'a, Notif, Params>(
+        &self,
+        subscribe_method: &'a str,
+        params: Params,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, Error>
+    where
+        Params: ToRpcParams + Send,
+        Notif: DeserializeOwned,
+    {
+        self.client
+            .subscribe(subscribe_method, params, unsubscribe_method)
+            .await
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, Error>
+    where
+        Notif: DeserializeOwned,
+    {
+        self.client.subscribe_to_method(method).await
+    }
+}
+
+/// Params already serialized.
+#[derive(Clone)]
+pub(crate) struct RawParams(pub(crate) Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+struct Breaker {
+    config: CircuitBreakerConfig,
+    state: BreakerState,
+}
+
+#[derive(Clone, Copy)]
+enum BreakerState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen,
+}
+
+/// Whether a request failed on the network or timed out, rather than being answered.
+pub(crate) fn is_transient(error: &Error) -> bool {
+    matches!(error, Error::Transport(_) | Error::RequestTimeout)
+}
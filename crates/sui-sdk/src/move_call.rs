@@ -0,0 +1,517 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Move calls built from typed arguments, checked against the normalized signature of the called
+//! function before the transaction is submitted.
+//!
+//! The visibility of the function, the number of its type arguments and arguments, whether every
+//! argument is a pure value or an object, and the types of pure values and objects are checked,
+//! with the type parameters of the function instantiated by the type arguments of the call.
+//! Mistakes are reported with the argument at fault, instead of as a `CommandArgumentError` once
+//! the transaction executed. Results of earlier commands of the transaction are only checked when
+//! it executes.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use serde::Serialize;
+use sui_json_rpc_types::{
+    SuiMoveAbility, SuiMoveNormalizedFunction, SuiMoveNormalizedType, SuiMoveVisibility,
+    SuiObjectData, SuiObjectDataOptions,
+};
+use sui_types::base_types::{ObjectID, ObjectType, TX_CONTEXT_MODULE_NAME, TX_CONTEXT_STRUCT_NAME};
+use sui_types::id::ID;
+use sui_types::messages::{Argument, CallArg, Command, ObjectArg};
+use sui_types::object::Owner;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::{MoveTypeTagTrait, MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
+use thiserror::Error;
+
+use crate::error::{Error, SuiRpcResult};
+use crate::SuiClient;
+
+/// An argument of a Move call.
+#[derive(Clone, Debug)]
+pub enum TypedArg {
+    /// A pure value, in BCS, with its Move type.
+    Pure {
+        type_: TypeTag,
+        bytes: Vec<u8>,
+    },
+    Object(ObjectID),
+    /// Objects passed as a vector.
+    Objects(Vec<ObjectID>),
+    /// The result of an earlier command of the transaction.
+    Result(Argument),
+}
+
+impl TypedArg {
+    pub fn pure<T: MoveTypeTagTrait + Serialize>(value: &T) -> SuiRpcResult<Self> {
+        Ok(Self::Pure {
+            type_: T::get_type_tag(),
+            bytes: bcs::to_bytes(value)?,
+        })
+    }
+}
+
+/// An argument of a Move call, checked against the signature of the function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResolvedArg {
+    Input(CallArg),
+    ObjVec(Vec<ObjectArg>),
+    Result(Argument),
+}
+
+/// How a function takes an object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+    Value,
+    Ref,
+    MutRef,
+}
+
+impl Display for Access {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Access::Value => write!(f, "by value"),
+            Access::Ref => write!(f, "by immutable reference"),
+            Access::MutRef => write!(f, "by mutable reference"),
+        }
+    }
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum MoveCallError {
+    #[error("{function} is neither public nor an entry function, transactions can't call it")]
+    NotCallable { function: String },
+    #[error("{function} takes {expected} type arguments, got {actual}")]
+    TypeArgumentCount {
+        function: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Type argument {index} of {function} must have the key ability, {type_arg} does not")]
+    TypeArgumentAbilities {
+        function: String,
+        index: usize,
+        type_arg: TypeTag,
+    },
+    #[error("{function} takes {expected} arguments, got {actual}")]
+    ArgumentCount {
+        function: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Argument {index} of {function} can't be passed by transactions")]
+    UnsupportedParameter { function: String, index: usize },
+    #[error("Argument {index} of {function} expects {expected}, got {actual}")]
+    ArgumentKind {
+        function: String,
+        index: usize,
+        expected: String,
+        actual: &'static str,
+    },
+    #[error("Argument {index} of {function} expects a value of type {expected}, got {actual}")]
+    PureType {
+        function: String,
+        index: usize,
+        expected: TypeTag,
+        actual: TypeTag,
+    },
+    #[error(
+        "Argument {index} of {function} expects an object of type {expected}, \
+         object {object} is of type {actual}"
+    )]
+    ObjectType {
+        function: String,
+        index: usize,
+        object: ObjectID,
+        expected: TypeTag,
+        actual: String,
+    },
+    #[error("Argument {index} of {function} takes object {object} {access}, but it is immutable")]
+    ImmutableObject {
+        function: String,
+        index: usize,
+        object: ObjectID,
+        access: Access,
+    },
+    #[error("Object {object} was not found with its type and owner")]
+    MissingObject { object: ObjectID },
+}
+
+/// Builds a Move call from typed arguments, which are checked against the signature of the
+/// function fetched from the fullnode.
+#[derive(Clone, Debug)]
+pub struct MoveCallBuilder {
+    package: ObjectID,
+    module: String,
+    function: String,
+    type_args: Vec<TypeTag>,
+    args: Vec<TypedArg>,
+}
+
+impl MoveCallBuilder {
+    pub fn new(package: ObjectID, module: impl Into<String>, function: impl Into<String>) -> Self {
+        Self {
+            package,
+            module: module.into(),
+            function: function.into(),
+            type_args: vec![],
+            args: vec![],
+        }
+    }
+
+    pub fn type_arg(mut self, type_arg: TypeTag) -> Self {
+        self.type_args.push(type_arg);
+        self
+    }
+
+    pub fn arg(mut self, arg: TypedArg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    pub fn pure<T: MoveTypeTagTrait + Serialize>(self, value: &T) -> SuiRpcResult<Self> {
+        Ok(self.arg(TypedArg::pure(value)?))
+    }
+
+    pub fn object(self, object: ObjectID) -> Self {
+        self.arg(TypedArg::Object(object))
+    }
+
+    pub fn objects(self, objects: Vec<ObjectID>) -> Self {
+        self.arg(TypedArg::Objects(objects))
+    }
+
+    pub fn result(self, argument: Argument) -> Self {
+        self.arg(TypedArg::Result(argument))
+    }
+
+    /// Checks the call against the signature of the function and the objects passed to it, and
+    /// adds it to `builder`, returning its result.
+    pub async fn build(
+        self,
+        client: &SuiClient,
+        builder: &mut ProgrammableTransactionBuilder,
+    ) -> SuiRpcResult<Argument> {
+        let module = Identifier::new(self.module.as_str())
+            .map_err(|e| Error::DataError(format!("Invalid module name {}: {e}", self.module)))?;
+        let function = Identifier::new(self.function.as_str()).map_err(|e| {
+            Error::DataError(format!("Invalid function name {}: {e}", self.function))
+        })?;
+        let signature = client
+            .read_api()
+            .get_normalized_move_function(self.package, self.module.clone(), self.function.clone())
+            .await?;
+
+        let object_ids: Vec<_> = self
+            .args
+            .iter()
+            .flat_map(|arg| match arg {
+                TypedArg::Object(id) => vec![*id],
+                TypedArg::Objects(ids) => ids.clone(),
+                TypedArg::Pure { .. } | TypedArg::Result(_) => vec![],
+            })
+            .collect();
+        let mut objects = BTreeMap::new();
+        if !object_ids.is_empty() {
+            let responses = client
+                .read_api()
+                .multi_get_object_with_options(
+                    object_ids,
+                    SuiObjectDataOptions::new().with_type().with_owner(),
+                )
+                .await?;
+            for response in responses {
+                let data = response
+                    .into_object()
+                    .map_err(|err| Error::DataError(err.to_string()))?;
+                objects.insert(data.object_id, data);
+            }
+        }
+
+        let args = self
+            .resolve(&signature, &objects)?
+            .into_iter()
+            .map(|arg| match arg {
+                ResolvedArg::Input(call_arg) => builder.input(call_arg),
+                ResolvedArg::ObjVec(objects) => builder.make_obj_vec(objects),
+                ResolvedArg::Result(argument) => Ok(argument),
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|err| Error::DataError(err.to_string()))?;
+        Ok(builder.command(Command::move_call(
+            self.package,
+            module,
+            function,
+            self.type_args,
+            args,
+        )))
+    }
+
+    /// Checks the call against `signature`, the signature of the function, and `objects`, the
+    /// objects passed to it with their types and owners, and resolves its arguments.
+    pub fn resolve(
+        &self,
+        signature: &SuiMoveNormalizedFunction,
+        objects: &BTreeMap<ObjectID, SuiObjectData>,
+    ) -> Result<Vec<ResolvedArg>, MoveCallError> {
+        let function = format!("{}::{}::{}", self.package, self.module, self.function);
+        if !signature.is_entry && !matches!(signature.visibility, SuiMoveVisibility::Public) {
+            return Err(MoveCallError::NotCallable { function });
+        }
+
+        if signature.type_parameters.len() != self.type_args.len() {
+            return Err(MoveCallError::TypeArgumentCount {
+                function,
+                expected: signature.type_parameters.len(),
+                actual: self.type_args.len(),
+            });
+        }
+        for (index, (constraints, type_arg)) in signature
+            .type_parameters
+            .iter()
+            .zip(&self.type_args)
+            .enumerate()
+        {
+            let needs_key = constraints
+                .abilities
+                .iter()
+                .any(|ability| matches!(ability, SuiMoveAbility::Key));
+            if needs_key && !matches!(type_arg, TypeTag::Struct(_)) {
+                return Err(MoveCallError::TypeArgumentAbilities {
+                    function,
+                    index,
+                    type_arg: type_arg.clone(),
+                });
+            }
+        }
+
+        // The transaction context is passed by the runtime, not by the transaction.
+        let parameters = match signature.parameters.split_last() {
+            Some((last, parameters)) if is_tx_context(last) => parameters,
+            _ => &signature.parameters[..],
+        };
+        if parameters.len() != self.args.len() {
+            return Err(MoveCallError::ArgumentCount {
+                function,
+                expected: parameters.len(),
+                actual: self.args.len(),
+            });
+        }
+
+        parameters
+            .iter()
+            .zip(&self.args)
+            .enumerate()
+            .map(|(index, (parameter, arg))| {
+                self.resolve_arg(&function, index, parameter, arg, objects)
+            })
+            .collect()
+    }
+
+    fn resolve_arg(
+        &self,
+        function: &str,
+        index: usize,
+        parameter: &SuiMoveNormalizedType,
+        arg: &TypedArg,
+        objects: &BTreeMap<ObjectID, SuiObjectData>,
+    ) -> Result<ResolvedArg, MoveCallError> {
+        let (type_, access) = match parameter {
+            SuiMoveNormalizedType::Reference(type_) => (type_.as_ref(), Access::Ref),
+            SuiMoveNormalizedType::MutableReference(type_) => (type_.as_ref(), Access::MutRef),
+            type_ => (type_, Access::Value),
+        };
+        let expected = instantiate(type_, &self.type_args).ok_or_else(|| {
+            MoveCallError::UnsupportedParameter {
+                function: function.to_string(),
+                index,
+            }
+        })?;
+        let kind_error = |actual| MoveCallError::ArgumentKind {
+            function: function.to_string(),
+            index,
+            expected: describe(&expected),
+            actual,
+        };
+
+        match arg {
+            TypedArg::Result(argument) => Ok(ResolvedArg::Result(*argument)),
+            TypedArg::Pure { type_, bytes } => {
+                if !is_pure(&expected) {
+                    return Err(kind_error("a pure value"));
+                }
+                if !pure_type_matches(type_, &expected) {
+                    return Err(MoveCallError::PureType {
+                        function: function.to_string(),
+                        index,
+                        expected,
+                        actual: type_.clone(),
+                    });
+                }
+                Ok(ResolvedArg::Input(CallArg::Pure(bytes.clone())))
+            }
+            TypedArg::Object(object) => {
+                if is_pure(&expected) || matches!(expected, TypeTag::Vector(_)) {
+                    return Err(kind_error("an object"));
+                }
+                let object_arg =
+                    resolve_object(function, index, access, &expected, *object, objects)?;
+                Ok(ResolvedArg::Input(CallArg::Object(object_arg)))
+            }
+            TypedArg::Objects(ids) => {
+                let TypeTag::Vector(element) = &expected else {
+                    return Err(kind_error("a vector of objects"));
+                };
+                if is_pure(element) {
+                    return Err(kind_error("a vector of objects"));
+                }
+                ids.iter()
+                    .map(|id| resolve_object(function, index, Access::Value, element, *id, objects))
+                    .collect::<Result<_, _>>()
+                    .map(ResolvedArg::ObjVec)
+            }
+        }
+    }
+}
+
+fn resolve_object(
+    function: &str,
+    index: usize,
+    access: Access,
+    expected: &TypeTag,
+    object: ObjectID,
+    objects: &BTreeMap<ObjectID, SuiObjectData>,
+) -> Result<ObjectArg, MoveCallError> {
+    let missing = MoveCallError::MissingObject { object };
+    let data = objects.get(&object).ok_or_else(|| missing.clone())?;
+    let actual = match &data.type_ {
+        Some(ObjectType::Struct(type_)) => Some(TypeTag::from(type_.clone())),
+        Some(ObjectType::Package) => None,
+        None => return Err(missing),
+    };
+    if actual.as_ref() != Some(expected) {
+        return Err(MoveCallError::ObjectType {
+            function: function.to_string(),
+            index,
+            object,
+            expected: expected.clone(),
+            actual: actual.map_or_else(|| "package".to_string(), |type_| type_.to_string()),
+        });
+    }
+
+    match data.owner {
+        Some(Owner::Shared {
+            initial_shared_version,
+        }) => Ok(ObjectArg::SharedObject {
+            id: object,
+            initial_shared_version,
+            mutable: access != Access::Ref,
+        }),
+        Some(Owner::Immutable) if access != Access::Ref => Err(MoveCallError::ImmutableObject {
+            function: function.to_string(),
+            index,
+            object,
+            access,
+        }),
+        Some(_) => Ok(ObjectArg::ImmOrOwnedObject(data.object_ref())),
+        None => Err(missing),
+    }
+}
+
+/// The type of a parameter, with the type parameters of the function replaced by `type_args`.
+fn instantiate(type_: &SuiMoveNormalizedType, type_args: &[TypeTag]) -> Option<TypeTag> {
+    Some(match type_ {
+        SuiMoveNormalizedType::Bool => TypeTag::Bool,
+        SuiMoveNormalizedType::U8 => TypeTag::U8,
+        SuiMoveNormalizedType::U16 => TypeTag::U16,
+        SuiMoveNormalizedType::U32 => TypeTag::U32,
+        SuiMoveNormalizedType::U64 => TypeTag::U64,
+        SuiMoveNormalizedType::U128 => TypeTag::U128,
+        SuiMoveNormalizedType::U256 => TypeTag::U256,
+        SuiMoveNormalizedType::Address => TypeTag::Address,
+        SuiMoveNormalizedType::Vector(element) => {
+            TypeTag::Vector(Box::new(instantiate(element, type_args)?))
+        }
+        SuiMoveNormalizedType::TypeParameter(index) => type_args.get(*index as usize)?.clone(),
+        SuiMoveNormalizedType::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => TypeTag::Struct(Box::new(StructTag {
+            address: AccountAddress::from_hex_literal(address).ok()?,
+            module: Identifier::new(module.as_str()).ok()?,
+            name: Identifier::new(name.as_str()).ok()?,
+            type_params: type_arguments
+                .iter()
+                .map(|type_| instantiate(type_, type_args))
+                .collect::<Option<_>>()?,
+        })),
+        SuiMoveNormalizedType::Signer
+        | SuiMoveNormalizedType::Reference(_)
+        | SuiMoveNormalizedType::MutableReference(_) => return None,
+    })
+}
+
+fn is_tx_context(type_: &SuiMoveNormalizedType) -> bool {
+    match type_ {
+        SuiMoveNormalizedType::Reference(type_)
+        | SuiMoveNormalizedType::MutableReference(type_) => matches!(
+            type_.as_ref(),
+            SuiMoveNormalizedType::Struct { address, module, name, .. }
+                if AccountAddress::from_hex_literal(address).ok() == Some(SUI_FRAMEWORK_ADDRESS)
+                    && module == TX_CONTEXT_MODULE_NAME.as_str()
+                    && name == TX_CONTEXT_STRUCT_NAME.as_str()
+        ),
+        _ => false,
+    }
+}
+
+/// Whether values of `type_` are passed as pure values rather than objects.
+fn is_pure(type_: &TypeTag) -> bool {
+    match type_ {
+        TypeTag::Signer => false,
+        TypeTag::Vector(element) => is_pure(element),
+        TypeTag::Struct(struct_tag) => {
+            let (address, module, name) = (
+                struct_tag.address,
+                struct_tag.module.as_str(),
+                struct_tag.name.as_str(),
+            );
+            if address == MOVE_STDLIB_ADDRESS && module == "option" && name == "Option" {
+                struct_tag.type_params.iter().all(is_pure)
+            } else {
+                (address == MOVE_STDLIB_ADDRESS
+                    && matches!((module, name), ("string", "String") | ("ascii", "String")))
+                    || **struct_tag == ID::type_()
+            }
+        }
+        _ => true,
+    }
+}
+
+/// Whether a pure value of type `actual` can be passed for `expected`, IDs being encoded as
+/// addresses.
+fn pure_type_matches(actual: &TypeTag, expected: &TypeTag) -> bool {
+    match (actual, expected) {
+        (TypeTag::Vector(actual), TypeTag::Vector(expected)) => pure_type_matches(actual, expected),
+        (TypeTag::Address, expected) => {
+            *expected == TypeTag::Address || *expected == ID::get_type_tag()
+        }
+        (actual, expected) => actual == expected,
+    }
+}
+
+fn describe(expected: &TypeTag) -> String {
+    match expected {
+        type_ if is_pure(type_) => format!("a pure value of type {type_}"),
+        TypeTag::Vector(element) => format!("a vector of objects of type {element}"),
+        type_ => format!("an object of type {type_}"),
+    }
+}
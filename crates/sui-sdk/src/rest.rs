@@ -0,0 +1,171 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A read client of the REST endpoints of a node, serving the BCS encoding of checkpoints,
+//! transactions, effects and objects.
+//!
+//! Responses are deserialized straight into the `sui-types` structs, without the JSON
+//! representations of the JSON-RPC API, for integrators reading large volumes of data. The node
+//! must be configured with a `rest-server-address`. Data read by digest, sequence number or
+//! version is checked to be the data requested.
+
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::{CheckpointDigest, TransactionDigest};
+use sui_types::messages::{Transaction, TransactionEffects, TransactionEffectsAPI};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+};
+use sui_types::object::Object;
+
+use crate::error::{Error, SuiRpcResult};
+
+/// Reads the BCS encoded data of a node, `None` if the node doesn't have it.
+#[derive(Clone, Debug)]
+pub struct RestClient {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl RestClient {
+    /// A client of the REST server at `url`, e.g. `http://127.0.0.1:9002`.
+    pub fn new(url: impl AsRef<str>) -> SuiRpcResult<Self> {
+        Self::new_with_client(reqwest::Client::new(), url)
+    }
+
+    /// A client of the REST server at `url` sending requests with `client`, configured with
+    /// timeouts, proxies or connection limits.
+    pub fn new_with_client(client: reqwest::Client, url: impl AsRef<str>) -> SuiRpcResult<Self> {
+        let url = url.as_ref();
+        let mut url = Url::parse(url)
+            .map_err(|e| Error::DataError(format!("Invalid REST server URL {url}: {e}")))?;
+        // Paths are joined to the URL, under its last segment only if it ends with a slash.
+        if !url.path().ends_with('/') {
+            url.set_path(&format!("{}/", url.path()));
+        }
+        Ok(Self { client, url })
+    }
+
+    pub async fn get_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiRpcResult<Option<CertifiedCheckpointSummary>> {
+        let checkpoint: Option<CertifiedCheckpointSummary> =
+            self.get(&format!("checkpoints/{sequence_number}")).await?;
+        if let Some(checkpoint) = &checkpoint {
+            check(
+                checkpoint.sequence_number == sequence_number,
+                "checkpoint",
+                sequence_number,
+            )?;
+        }
+        Ok(checkpoint)
+    }
+
+    pub async fn get_checkpoint_by_digest(
+        &self,
+        digest: CheckpointDigest,
+    ) -> SuiRpcResult<Option<CertifiedCheckpointSummary>> {
+        let checkpoint: Option<CertifiedCheckpointSummary> =
+            self.get(&format!("checkpoints/{digest}")).await?;
+        if let Some(checkpoint) = &checkpoint {
+            check(checkpoint.digest() == &digest, "checkpoint", digest)?;
+        }
+        Ok(checkpoint)
+    }
+
+    /// The contents of the checkpoint `sequence_number`. Check them against the content digest of
+    /// the checkpoint to trust them as much as the checkpoint.
+    pub async fn get_checkpoint_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiRpcResult<Option<CheckpointContents>> {
+        self.get(&format!("checkpoints/{sequence_number}/contents"))
+            .await
+    }
+
+    pub async fn get_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> SuiRpcResult<Option<Transaction>> {
+        let transaction: Option<Transaction> = self.get(&format!("transactions/{digest}")).await?;
+        if let Some(transaction) = &transaction {
+            check(transaction.digest() == &digest, "transaction", digest)?;
+        }
+        Ok(transaction)
+    }
+
+    pub async fn get_transaction_effects(
+        &self,
+        digest: TransactionDigest,
+    ) -> SuiRpcResult<Option<TransactionEffects>> {
+        let effects: Option<TransactionEffects> =
+            self.get(&format!("transactions/{digest}/effects")).await?;
+        if let Some(effects) = &effects {
+            check(
+                effects.transaction_digest() == &digest,
+                "effects of transaction",
+                digest,
+            )?;
+        }
+        Ok(effects)
+    }
+
+    /// The latest version of the object `object_id` known to the node.
+    pub async fn get_object(&self, object_id: ObjectID) -> SuiRpcResult<Option<Object>> {
+        let object: Option<Object> = self.get(&format!("objects/{object_id}")).await?;
+        if let Some(object) = &object {
+            check(object.id() == object_id, "object", object_id)?;
+        }
+        Ok(object)
+    }
+
+    pub async fn get_object_with_version(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> SuiRpcResult<Option<Object>> {
+        let object: Option<Object> = self
+            .get(&format!("objects/{object_id}/versions/{}", version.value()))
+            .await?;
+        if let Some(object) = &object {
+            check(
+                object.id() == object_id && object.version() == version,
+                "object",
+                format!("{object_id} at version {version}"),
+            )?;
+        }
+        Ok(object)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> SuiRpcResult<Option<T>> {
+        let url = self
+            .url
+            .join(path)
+            .map_err(|e| Error::DataError(format!("Invalid REST path {path}: {e}")))?;
+        let response = self.client.get(url.clone()).send().await?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let body = response.bytes().await?;
+        if !status.is_success() {
+            return Err(Error::RestError {
+                url: url.to_string(),
+                status: status.as_u16(),
+                message: String::from_utf8_lossy(&body).trim().to_string(),
+            });
+        }
+        Ok(Some(bcs::from_bytes(&body)?))
+    }
+}
+
+fn check(matches: bool, what: &str, requested: impl std::fmt::Display) -> SuiRpcResult<()> {
+    if !matches {
+        return Err(Error::DataError(format!(
+            "The REST server returned another {what} than {requested}"
+        )));
+    }
+    Ok(())
+}
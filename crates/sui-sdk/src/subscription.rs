@@ -0,0 +1,341 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Subscriptions surviving dropped connections, delivering every item exactly once.
+//!
+//! An event subscription dropped by the node or the network is resubscribed on a new websocket
+//! connection, and the events emitted in the meantime are backfilled with `queryEvents` from the
+//! cursor of the last event delivered. The node has no transaction subscription: transactions are
+//! followed with `queryTransactions` from the last transaction delivered instead. Items returned
+//! both by a backfill and by the subscription are only delivered once.
+
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{stream, Stream, StreamExt};
+use jsonrpsee::core::client::Subscription;
+use jsonrpsee::ws_client::WsClient;
+use sui_json_rpc::api::{EventReadApiClient, ReadApiClient};
+use sui_json_rpc_types::{
+    EventFilter, SuiEvent, SuiTransactionResponse, SuiTransactionResponseQuery,
+};
+use sui_types::base_types::TransactionDigest;
+use sui_types::event::EventID;
+use tracing::warn;
+
+use crate::error::{Error, SuiRpcResult};
+use crate::RpcClient;
+
+/// Number of recently delivered items remembered, to drop their duplicates.
+const RECENT_ITEMS: usize = 10_000;
+
+/// Retries and backfills of a resilient subscription.
+#[derive(Clone, Debug)]
+pub struct SubscriptionConfig {
+    /// Delay before retrying after a failure, doubled on every consecutive failure.
+    pub retry_delay: Duration,
+    /// Maximum delay between retries.
+    pub max_retry_delay: Duration,
+    /// Number of items queried per page when backfilling.
+    pub page_size: usize,
+    /// Interval between queries for new transactions once all were delivered.
+    pub poll_interval: Duration,
+}
+
+impl Default for SubscriptionConfig {
+    fn default() -> Self {
+        Self {
+            retry_delay: Duration::from_millis(500),
+            max_retry_delay: Duration::from_secs(30),
+            page_size: 100,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// The events matching `filter` emitted after the event `cursor`, or after the latest one if
+/// `None`, streamed until the stream is dropped.
+pub(crate) fn event_subscription(
+    api: Arc<RpcClient>,
+    filter: EventFilter,
+    cursor: Option<EventID>,
+    config: SubscriptionConfig,
+) -> SuiRpcResult<impl Stream<Item = SuiEvent>> {
+    if api.ws_config.is_none() {
+        return Err(Error::Subscription(
+            "Subscription only supported by WebSocket client.".to_string(),
+        ));
+    }
+    let subscription = EventSubscription {
+        api,
+        filter,
+        started: cursor.is_some(),
+        cursor,
+        live: None,
+        backfill: VecDeque::new(),
+        recent: RecentItems::default(),
+        backoff: Backoff::new(&config),
+        config,
+    };
+    Ok(stream::unfold(
+        subscription,
+        |mut subscription| async move {
+            let event = subscription.next().await;
+            Some((event, subscription))
+        },
+    ))
+}
+
+/// The transactions matching `query` executed after the transaction `cursor`, or after the
+/// latest one if `None`, streamed until the stream is dropped.
+pub(crate) fn transaction_subscription(
+    api: Arc<RpcClient>,
+    query: SuiTransactionResponseQuery,
+    cursor: Option<TransactionDigest>,
+    config: SubscriptionConfig,
+) -> impl Stream<Item = SuiTransactionResponse> {
+    let subscription = TransactionSubscription {
+        api,
+        query,
+        started: cursor.is_some(),
+        cursor,
+        pending: VecDeque::new(),
+        recent: RecentItems::default(),
+        backoff: Backoff::new(&config),
+        config,
+    };
+    stream::unfold(subscription, |mut subscription| async move {
+        let transaction = subscription.next().await;
+        Some((transaction, subscription))
+    })
+}
+
+struct EventSubscription {
+    api: Arc<RpcClient>,
+    filter: EventFilter,
+    config: SubscriptionConfig,
+    /// Whether the cursor was set, by the caller or to the latest event on the first connection.
+    started: bool,
+    /// The last event delivered.
+    cursor: Option<EventID>,
+    /// The connection and its subscription, the connection must outlive the subscription.
+    live: Option<(WsClient, Subscription<SuiEvent>)>,
+    backfill: VecDeque<SuiEvent>,
+    recent: RecentItems<EventID>,
+    backoff: Backoff,
+}
+
+impl EventSubscription {
+    async fn next(&mut self) -> SuiEvent {
+        loop {
+            if let Some(event) = self.backfill.pop_front() {
+                if let Some(event) = self.deliver(event) {
+                    return event;
+                }
+                continue;
+            }
+            let item = match &mut self.live {
+                Some((_, subscription)) => subscription.next().await,
+                None => {
+                    match self.connect().await {
+                        Ok(()) => self.backoff.reset(),
+                        Err(e) => {
+                            warn!("Can't resume the event subscription: {e}");
+                            self.backoff.wait().await;
+                        }
+                    }
+                    continue;
+                }
+            };
+            match item {
+                Some(Ok(event)) => {
+                    if let Some(event) = self.deliver(event) {
+                        return event;
+                    }
+                }
+                Some(Err(e)) => {
+                    warn!("Event subscription failed, reconnecting: {e}");
+                    self.live = None;
+                }
+                None => {
+                    warn!("Event subscription dropped, reconnecting");
+                    self.live = None;
+                }
+            }
+        }
+    }
+
+    /// Subscribes on a new connection, then queries the events the subscription may have missed,
+    /// emitted since the last event delivered.
+    async fn connect(&mut self) -> SuiRpcResult<()> {
+        if !self.started {
+            let latest = self
+                .api
+                .http
+                .query_events(self.filter.clone(), None, Some(1), Some(true))
+                .await?;
+            self.cursor = latest.data.into_iter().next().map(|event| event.id);
+            self.started = true;
+        }
+
+        let ws_config = self
+            .api
+            .ws_config
+            .as_ref()
+            .expect("checked on subscription");
+        let ws = ws_config.connect().await?;
+        let subscription = ws.subscribe_event(self.filter.clone()).await?;
+
+        let mut cursor = self.cursor.clone();
+        loop {
+            let page = self
+                .api
+                .http
+                .query_events(
+                    self.filter.clone(),
+                    cursor,
+                    Some(self.config.page_size),
+                    Some(false),
+                )
+                .await?;
+            self.backfill.extend(page.data);
+            match page.next_cursor {
+                Some(next_cursor) if page.has_next_page => cursor = Some(next_cursor),
+                _ => break,
+            }
+        }
+        self.live = Some((ws, subscription));
+        Ok(())
+    }
+
+    fn deliver(&mut self, event: SuiEvent) -> Option<SuiEvent> {
+        if !self.recent.insert(event.id.clone()) {
+            return None;
+        }
+        self.cursor = Some(event.id.clone());
+        Some(event)
+    }
+}
+
+struct TransactionSubscription {
+    api: Arc<RpcClient>,
+    query: SuiTransactionResponseQuery,
+    config: SubscriptionConfig,
+    /// Whether the cursor was set, by the caller or to the latest transaction on the first query.
+    started: bool,
+    /// The last transaction delivered.
+    cursor: Option<TransactionDigest>,
+    pending: VecDeque<SuiTransactionResponse>,
+    recent: RecentItems<TransactionDigest>,
+    backoff: Backoff,
+}
+
+impl TransactionSubscription {
+    async fn next(&mut self) -> SuiTransactionResponse {
+        loop {
+            if let Some(transaction) = self.pending.pop_front() {
+                if self.recent.insert(transaction.digest) {
+                    self.cursor = Some(transaction.digest);
+                    return transaction;
+                }
+                continue;
+            }
+            match self.query().await {
+                Ok(true) => self.backoff.reset(),
+                Ok(false) => {
+                    self.backoff.reset();
+                    tokio::time::sleep(self.config.poll_interval).await;
+                }
+                Err(e) => {
+                    warn!("Can't query the transactions of the subscription: {e}");
+                    self.backoff.wait().await;
+                }
+            }
+        }
+    }
+
+    /// Queries the transactions executed since the last transaction delivered, returns whether
+    /// there were any.
+    async fn query(&mut self) -> SuiRpcResult<bool> {
+        if !self.started {
+            let latest = self
+                .api
+                .http
+                .query_transactions(self.query.clone(), None, Some(1), Some(true))
+                .await?;
+            self.cursor = latest.data.first().map(|transaction| transaction.digest);
+            self.started = true;
+        }
+        let page = self
+            .api
+            .http
+            .query_transactions(
+                self.query.clone(),
+                self.cursor,
+                Some(self.config.page_size),
+                Some(false),
+            )
+            .await?;
+        let found = !page.data.is_empty();
+        self.pending.extend(page.data);
+        Ok(found)
+    }
+}
+
+/// The items recently delivered.
+struct RecentItems<T> {
+    order: VecDeque<T>,
+    items: HashSet<T>,
+}
+
+impl<T> Default for RecentItems<T> {
+    fn default() -> Self {
+        Self {
+            order: VecDeque::new(),
+            items: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> RecentItems<T> {
+    /// Remembers `item`, returns whether it was not delivered recently.
+    fn insert(&mut self, item: T) -> bool {
+        if !self.items.insert(item.clone()) {
+            return false;
+        }
+        self.order.push_back(item);
+        if self.order.len() > RECENT_ITEMS {
+            let oldest = self.order.pop_front().unwrap();
+            self.items.remove(&oldest);
+        }
+        true
+    }
+}
+
+/// Exponential delay between consecutive failures.
+struct Backoff {
+    initial: Duration,
+    max: Duration,
+    delay: Duration,
+}
+
+impl Backoff {
+    fn new(config: &SubscriptionConfig) -> Self {
+        Self {
+            initial: config.retry_delay,
+            max: config.max_retry_delay,
+            delay: config.retry_delay,
+        }
+    }
+
+    async fn wait(&mut self) {
+        tokio::time::sleep(self.delay).await;
+        self.delay = (self.delay * 2).min(self.max);
+    }
+
+    fn reset(&mut self) {
+        self.delay = self.initial;
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_sdk::coin_selection::{BranchAndBound, CoinSelector, ConsolidateDust, LargestFirst};
+
+fn total(balances: &[u64], selected: &[usize]) -> u128 {
+    selected.iter().map(|index| balances[*index] as u128).sum()
+}
+
+#[test]
+fn test_largest_first() {
+    let balances = [5, 50, 1, 20, 10];
+    assert_eq!(LargestFirst.select(&balances, 60, 10), Some(vec![1, 3]));
+    assert_eq!(LargestFirst.select(&balances, 50, 10), Some(vec![1]));
+    assert_eq!(LargestFirst.select(&balances, 0, 10), Some(vec![]));
+    assert_eq!(LargestFirst.select(&balances, 87, 10), None);
+    // Enough balance, but in too many coins.
+    assert_eq!(LargestFirst.select(&balances, 81, 3), None);
+}
+
+#[test]
+fn test_largest_first_many_small_coins() {
+    // A single coin can't pay, which the naive selection used to fail on.
+    let balances = vec![1_000; 500];
+    let selected = LargestFirst.select(&balances, 200_000, 256).unwrap();
+    assert_eq!(selected.len(), 200);
+    assert_eq!(LargestFirst.select(&balances, 300_000, 256), None);
+}
+
+#[test]
+fn test_branch_and_bound_exact_match() {
+    let balances = [50, 30, 21, 12, 9];
+    let selected = BranchAndBound::default().select(&balances, 42, 10).unwrap();
+    assert_eq!(total(&balances, &selected), 42);
+
+    let selected = BranchAndBound::default().select(&balances, 80, 10).unwrap();
+    assert_eq!(total(&balances, &selected), 80);
+    assert_eq!(selected, vec![0, 1]);
+}
+
+#[test]
+fn test_branch_and_bound_tolerance() {
+    let balances = [50, 30, 22];
+    let exact = BranchAndBound::default();
+    // No exact match: falls back to the largest coins.
+    assert_eq!(exact.select(&balances, 51, 10), Some(vec![0, 1]));
+
+    let tolerant = BranchAndBound {
+        tolerance: 2,
+        ..Default::default()
+    };
+    let selected = tolerant.select(&balances, 51, 10).unwrap();
+    assert_eq!(total(&balances, &selected), 52);
+}
+
+#[test]
+fn test_branch_and_bound_limits() {
+    let balances = [40, 30, 20, 10];
+    // 60 is 40 + 20 or 30 + 20 + 10, only the former fits in 2 coins.
+    let selected = BranchAndBound::default().select(&balances, 60, 2).unwrap();
+    assert_eq!(selected, vec![0, 2]);
+    assert_eq!(BranchAndBound::default().select(&balances, 101, 10), None);
+
+    // Out of tries, the largest coins are selected.
+    let bounded = BranchAndBound {
+        tolerance: 0,
+        max_tries: 0,
+    };
+    assert_eq!(bounded.select(&balances, 60, 10), Some(vec![0, 1]));
+}
+
+#[test]
+fn test_consolidate_dust() {
+    let balances = [3, 100, 1, 2, 40, 5];
+    let selector = ConsolidateDust {
+        dust_threshold: 5,
+        max_dust_coins: 2,
+    };
+    assert_eq!(selector.select(&balances, 120, 10), Some(vec![1, 4, 2, 3]));
+    // The dust only fills the room left under the maximum number of coins.
+    assert_eq!(selector.select(&balances, 120, 3), Some(vec![1, 4, 2]));
+    assert_eq!(selector.select(&balances, 200, 10), None);
+}
@@ -0,0 +1,70 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use sui_config::utils::available_local_socket_address;
+use sui_config::SUI_KEYSTORE_FILENAME;
+use sui_json_rpc_types::SuiTransactionResponseOptions;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_sdk::SuiClientBuilder;
+use sui_types::messages::ExecuteTransactionRequestType;
+use sui_types::utils::to_sender_signed_transaction;
+use test_utils::network::TestClusterBuilder;
+
+/// The URL of a port nothing listens on.
+fn unreachable_url() -> String {
+    format!("http://{}", available_local_socket_address())
+}
+
+#[tokio::test]
+async fn test_failover_when_primary_is_unreachable() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let client = SuiClientBuilder::default()
+        .failover_url(test_cluster.rpc_url())
+        .health_check_interval(Duration::from_millis(100))
+        .build(unreachable_url())
+        .await?;
+
+    client.read_api().get_total_transaction_number().await?;
+    // Once the health checks ran, the failover endpoint is tried first.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    client.read_api().get_reference_gas_price().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reads_and_execution_with_unreachable_read_replica() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let client = SuiClientBuilder::default()
+        .read_replica_url(unreachable_url())
+        .build(test_cluster.rpc_url())
+        .await?;
+    let address = test_cluster.accounts[0];
+    let keystore_path = test_cluster.swarm.dir().join(SUI_KEYSTORE_FILENAME);
+    let keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+
+    // Reads fail over to the primary.
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await?;
+    let coin = coins.data.first().unwrap();
+
+    // Transactions are executed by the primary.
+    let data = client
+        .transaction_builder()
+        .transfer_sui(address, coin.coin_object_id, 10_000, address, Some(1))
+        .await?;
+    let tx = to_sender_signed_transaction(data, keystore.get_key(&address)?);
+    let response = client
+        .quorum_driver()
+        .execute_transaction(
+            tx,
+            SuiTransactionResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+        )
+        .await?;
+    assert!(response.effects.is_some());
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+#![cfg(feature = "local-execution")]
+
+use sui_protocol_config::ProtocolConfig;
+use sui_sdk::error::Error;
+use sui_sdk::local_execution::LocalExecutor;
+use sui_types::base_types::{dbg_addr, ObjectID, ObjectRef, SuiAddress};
+use sui_types::error::UserInputError;
+use sui_types::gas_coin::GasCoin;
+use sui_types::message_envelope::Message;
+use sui_types::messages::{TransactionData, TransactionEffectsAPI};
+use sui_types::object::{Object, Owner};
+
+const GAS_PRICE: u64 = 1;
+const GAS_BUDGET: u64 = 50_000_000;
+
+fn executor_with_gas(owner: SuiAddress) -> (LocalExecutor, ObjectRef) {
+    let mut executor = LocalExecutor::new(ProtocolConfig::get_for_max_version(), 0, 0, GAS_PRICE)
+        .with_system_packages();
+    let gas = Object::with_id_owner_for_testing(ObjectID::random(), owner);
+    let gas_ref = gas.compute_object_reference();
+    executor.insert_object(gas);
+    (executor, gas_ref)
+}
+
+#[test]
+fn test_simulate_is_deterministic() {
+    let (sender, recipient) = (dbg_addr(1), dbg_addr(2));
+    let (mut executor, gas_ref) = executor_with_gas(sender);
+    let transaction = TransactionData::new_transfer_sui(
+        recipient,
+        sender,
+        Some(1000),
+        gas_ref,
+        GAS_BUDGET,
+        GAS_PRICE,
+    );
+
+    let first = executor.simulate(&transaction).unwrap();
+    let second = executor.simulate(&transaction).unwrap();
+    assert!(first.execution_error.is_none());
+    assert!(first.effects.status().is_ok());
+    assert_eq!(first.effects.created().len(), 1);
+    assert_eq!(first.effects.digest(), second.effects.digest());
+
+    // Simulating leaves the gas coin untouched.
+    let gas = executor.get_object(&gas_ref.0).unwrap();
+    assert_eq!(gas.compute_object_reference(), gas_ref);
+}
+
+#[test]
+fn test_execute_applies_effects() {
+    let (sender, recipient) = (dbg_addr(1), dbg_addr(2));
+    let (mut executor, gas_ref) = executor_with_gas(sender);
+    let transaction = TransactionData::new_transfer_sui(
+        recipient,
+        sender,
+        Some(1000),
+        gas_ref,
+        GAS_BUDGET,
+        GAS_PRICE,
+    );
+
+    let result = executor.execute(&transaction).unwrap();
+    let ((coin_id, _, _), owner) = result.effects.created()[0];
+    assert_eq!(owner, Owner::AddressOwner(recipient));
+    let coin = GasCoin::try_from(executor.get_object(&coin_id).unwrap()).unwrap();
+    assert_eq!(coin.value(), 1000);
+
+    // The gas coin was mutated, so the transaction cannot be executed again.
+    let gas = executor.get_object(&gas_ref.0).unwrap();
+    assert!(gas.version() > gas_ref.1);
+    let err = executor.simulate(&transaction).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UserInputError(UserInputError::ObjectVersionUnavailableForConsumption { .. })
+    ));
+}
+
+#[test]
+fn test_rejects_objects_of_other_owners() {
+    let (sender, other) = (dbg_addr(1), dbg_addr(2));
+    let (mut executor, gas_ref) = executor_with_gas(other);
+    let transaction = TransactionData::new_transfer_sui(
+        other,
+        sender,
+        Some(1000),
+        gas_ref,
+        GAS_BUDGET,
+        GAS_PRICE,
+    );
+
+    let err = executor.simulate(&transaction).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UserInputError(UserInputError::IncorrectUserSignature { .. })
+    ));
+}
@@ -0,0 +1,180 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use jsonrpsee::core::Error as RpcError;
+use sui_sdk::error::Error;
+use sui_sdk::middleware::{
+    CircuitBreakerConfig, CircuitState, Middleware, RequestObserver, RetryPolicy,
+};
+use sui_sdk::SuiClientBuilder;
+use test_utils::network::TestClusterBuilder;
+use tokio::io::copy_bidirectional;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+#[derive(Default)]
+struct Observer {
+    attempts: AtomicU32,
+    failures: AtomicU32,
+    retries: AtomicU32,
+    rejections: AtomicU32,
+    states: Mutex<Vec<CircuitState>>,
+}
+
+impl RequestObserver for Observer {
+    fn on_attempt(
+        &self,
+        _method: &str,
+        _attempt: u32,
+        _elapsed: Duration,
+        error: Option<&RpcError>,
+    ) {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        if error.is_some() {
+            self.failures.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn on_retry(&self, _method: &str, _attempt: u32, _delay: Duration) {
+        self.retries.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_rejected(&self, _method: &str) {
+        self.rejections.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_circuit_state(&self, state: CircuitState) {
+        self.states.lock().unwrap().push(state);
+    }
+}
+
+/// A TCP proxy to the node which can be taken down, dropping its connections.
+struct Proxy {
+    address: SocketAddr,
+    up: Arc<AtomicBool>,
+    connections: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl Proxy {
+    async fn start(target: SocketAddr) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = Self {
+            address: listener.local_addr().unwrap(),
+            up: Arc::new(AtomicBool::new(true)),
+            connections: Default::default(),
+        };
+        let up = proxy.up.clone();
+        let connections = proxy.connections.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut inbound, _) = listener.accept().await.unwrap();
+                if !up.load(Ordering::SeqCst) {
+                    continue;
+                }
+                connections.lock().unwrap().push(tokio::spawn(async move {
+                    let mut outbound = TcpStream::connect(target).await.unwrap();
+                    let _ = copy_bidirectional(&mut inbound, &mut outbound).await;
+                }));
+            }
+        });
+        proxy
+    }
+
+    fn set_up(&self, up: bool) {
+        self.up.store(up, Ordering::SeqCst);
+        if !up {
+            for connection in self.connections.lock().unwrap().drain(..) {
+                connection.abort();
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_retries_and_circuit_breaker() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let target = test_cluster
+        .rpc_url()
+        .trim_start_matches("http://")
+        .parse()?;
+    let proxy = Proxy::start(target).await;
+
+    let observer = Arc::new(Observer::default());
+    let open_duration = Duration::from_millis(500);
+    let middleware = Middleware::new()
+        .retry_policy(RetryPolicy {
+            max_retries: 2,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(50),
+        })
+        .circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 3,
+            open_duration,
+        })
+        .observer(observer.clone());
+    let client = SuiClientBuilder::default()
+        .middleware(middleware)
+        .build(format!("http://{}", proxy.address))
+        .await?;
+
+    client.read_api().get_total_transaction_number().await?;
+    assert_eq!(observer.attempts.load(Ordering::SeqCst), 1);
+
+    // Failing on the network, the request is retried twice, which opens the circuit.
+    proxy.set_up(false);
+    let result = client.read_api().get_total_transaction_number().await;
+    assert!(matches!(
+        result,
+        Err(Error::RpcError(RpcError::Transport(_)))
+    ));
+    assert_eq!(observer.attempts.load(Ordering::SeqCst), 4);
+    assert_eq!(observer.failures.load(Ordering::SeqCst), 3);
+    assert_eq!(observer.retries.load(Ordering::SeqCst), 2);
+    assert_eq!(*observer.states.lock().unwrap(), vec![CircuitState::Open]);
+
+    // The open circuit fails requests without sending them.
+    let result = client.read_api().get_total_transaction_number().await;
+    assert!(result.is_err());
+    assert_eq!(observer.rejections.load(Ordering::SeqCst), 1);
+    assert_eq!(observer.attempts.load(Ordering::SeqCst), 4);
+
+    // Once open for long enough, a request probes the endpoint, closing the circuit.
+    proxy.set_up(true);
+    tokio::time::sleep(open_duration).await;
+    client.read_api().get_total_transaction_number().await?;
+    assert_eq!(
+        *observer.states.lock().unwrap(),
+        vec![
+            CircuitState::Open,
+            CircuitState::HalfOpen,
+            CircuitState::Closed
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_method_timeout() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let middleware = Middleware::new()
+        .method_timeout("sui_getTotalTransactionNumber", Duration::ZERO)
+        .retry_policy(RetryPolicy::default());
+    let client = SuiClientBuilder::default()
+        .middleware(middleware)
+        .build(test_cluster.rpc_url())
+        .await?;
+
+    let result = client.read_api().get_total_transaction_number().await;
+    assert!(matches!(
+        result,
+        Err(Error::RpcError(RpcError::RequestTimeout))
+    ));
+    // Other methods are not affected.
+    client.read_api().get_reference_gas_price().await?;
+    Ok(())
+}
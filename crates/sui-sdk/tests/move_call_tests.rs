@@ -0,0 +1,275 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use sui_json_rpc_types::{
+    SuiMoveAbility, SuiMoveAbilitySet, SuiMoveNormalizedFunction, SuiMoveNormalizedType,
+    SuiMoveVisibility, SuiObjectData,
+};
+use sui_sdk::move_call::{Access, MoveCallBuilder, MoveCallError, ResolvedArg, TypedArg};
+use sui_types::base_types::{dbg_addr, random_object_ref, ObjectID, ObjectType};
+use sui_types::coin::Coin;
+use sui_types::gas_coin::GAS;
+use sui_types::messages::{Argument, CallArg, ObjectArg};
+use sui_types::object::Owner;
+use sui_types::{TypeTag, SUI_FRAMEWORK_OBJECT_ID};
+
+fn framework_struct(
+    module: &str,
+    name: &str,
+    type_arguments: Vec<SuiMoveNormalizedType>,
+) -> SuiMoveNormalizedType {
+    SuiMoveNormalizedType::Struct {
+        address: "0x2".to_string(),
+        module: module.to_string(),
+        name: name.to_string(),
+        type_arguments,
+    }
+}
+
+/// The signature of `0x2::coin::split<T>(&mut Coin<T>, u64, &mut TxContext): Coin<T>`.
+fn split_signature() -> SuiMoveNormalizedFunction {
+    let coin = framework_struct(
+        "coin",
+        "Coin",
+        vec![SuiMoveNormalizedType::TypeParameter(0)],
+    );
+    SuiMoveNormalizedFunction {
+        visibility: SuiMoveVisibility::Public,
+        is_entry: false,
+        type_parameters: vec![SuiMoveAbilitySet { abilities: vec![] }],
+        parameters: vec![
+            SuiMoveNormalizedType::MutableReference(Box::new(coin.clone())),
+            SuiMoveNormalizedType::U64,
+            SuiMoveNormalizedType::MutableReference(Box::new(framework_struct(
+                "tx_context",
+                "TxContext",
+                vec![],
+            ))),
+        ],
+        return_: vec![coin],
+    }
+}
+
+fn sui_coin() -> TypeTag {
+    TypeTag::Struct(Box::new(Coin::type_(GAS::type_tag())))
+}
+
+fn split(type_arg: TypeTag) -> MoveCallBuilder {
+    MoveCallBuilder::new(SUI_FRAMEWORK_OBJECT_ID, "coin", "split").type_arg(type_arg)
+}
+
+fn object(type_: TypeTag, owner: Owner) -> SuiObjectData {
+    let (object_id, version, digest) = random_object_ref();
+    let TypeTag::Struct(struct_tag) = type_ else {
+        panic!("objects are structs");
+    };
+    SuiObjectData {
+        object_id,
+        version,
+        digest,
+        type_: Some(ObjectType::Struct((*struct_tag).into())),
+        owner: Some(owner),
+        previous_transaction: None,
+        storage_rebate: None,
+        display: None,
+        content: None,
+        bcs: None,
+        served_from_archive: None,
+    }
+}
+
+fn objects_by_id(objects: &[&SuiObjectData]) -> BTreeMap<ObjectID, SuiObjectData> {
+    objects
+        .iter()
+        .map(|object| (object.object_id, (*object).clone()))
+        .collect()
+}
+
+#[test]
+fn test_resolve_move_call() {
+    let coin = object(sui_coin(), Owner::AddressOwner(dbg_addr(1)));
+    let resolved = split(GAS::type_tag())
+        .object(coin.object_id)
+        .pure(&1000u64)
+        .unwrap()
+        .resolve(&split_signature(), &objects_by_id(&[&coin]))
+        .unwrap();
+    assert_eq!(
+        resolved,
+        vec![
+            ResolvedArg::Input(CallArg::Object(ObjectArg::ImmOrOwnedObject(
+                coin.object_ref()
+            ))),
+            ResolvedArg::Input(CallArg::Pure(bcs::to_bytes(&1000u64).unwrap())),
+        ]
+    );
+
+    // Results of earlier commands are only checked on execution.
+    let resolved = split(GAS::type_tag())
+        .result(Argument::Result(0))
+        .result(Argument::Input(0))
+        .resolve(&split_signature(), &BTreeMap::new())
+        .unwrap();
+    assert_eq!(
+        resolved,
+        vec![
+            ResolvedArg::Result(Argument::Result(0)),
+            ResolvedArg::Result(Argument::Input(0))
+        ]
+    );
+}
+
+#[test]
+fn test_move_call_signature_errors() {
+    let coin = object(sui_coin(), Owner::AddressOwner(dbg_addr(1)));
+    let objects = objects_by_id(&[&coin]);
+
+    let error = MoveCallBuilder::new(SUI_FRAMEWORK_OBJECT_ID, "coin", "split")
+        .object(coin.object_id)
+        .pure(&1000u64)
+        .unwrap()
+        .resolve(&split_signature(), &objects)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MoveCallError::TypeArgumentCount {
+            expected: 1,
+            actual: 0,
+            ..
+        }
+    ));
+
+    let error = split(GAS::type_tag())
+        .object(coin.object_id)
+        .resolve(&split_signature(), &objects)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MoveCallError::ArgumentCount {
+            expected: 2,
+            actual: 1,
+            ..
+        }
+    ));
+
+    let mut signature = split_signature();
+    signature.visibility = SuiMoveVisibility::Friend;
+    let error = split(GAS::type_tag())
+        .object(coin.object_id)
+        .pure(&1000u64)
+        .unwrap()
+        .resolve(&signature, &objects)
+        .unwrap_err();
+    assert!(matches!(error, MoveCallError::NotCallable { .. }));
+
+    let mut signature = split_signature();
+    signature.type_parameters[0]
+        .abilities
+        .push(SuiMoveAbility::Key);
+    let error = split(TypeTag::U64)
+        .object(coin.object_id)
+        .pure(&1000u64)
+        .unwrap()
+        .resolve(&signature, &objects)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MoveCallError::TypeArgumentAbilities { index: 0, .. }
+    ));
+}
+
+#[test]
+fn test_move_call_argument_errors() {
+    let coin = object(sui_coin(), Owner::AddressOwner(dbg_addr(1)));
+    let objects = objects_by_id(&[&coin]);
+
+    // A pure value for an object.
+    let error = split(GAS::type_tag())
+        .pure(&dbg_addr(1))
+        .unwrap()
+        .pure(&1000u64)
+        .unwrap()
+        .resolve(&split_signature(), &objects)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MoveCallError::ArgumentKind {
+            index: 0,
+            actual: "a pure value",
+            ..
+        }
+    ));
+    assert!(error.to_string().starts_with(&format!(
+        "Argument 0 of {SUI_FRAMEWORK_OBJECT_ID}::coin::split expects an object of type"
+    )));
+
+    // An object for a pure value.
+    let error = split(GAS::type_tag())
+        .object(coin.object_id)
+        .object(coin.object_id)
+        .resolve(&split_signature(), &objects)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MoveCallError::ArgumentKind {
+            index: 1,
+            actual: "an object",
+            ..
+        }
+    ));
+
+    // A pure value of another type.
+    let error = split(GAS::type_tag())
+        .object(coin.object_id)
+        .pure(&1000u32)
+        .unwrap()
+        .resolve(&split_signature(), &objects)
+        .unwrap_err();
+    assert_eq!(
+        error,
+        MoveCallError::PureType {
+            function: format!("{SUI_FRAMEWORK_OBJECT_ID}::coin::split"),
+            index: 1,
+            expected: TypeTag::U64,
+            actual: TypeTag::U32,
+        }
+    );
+
+    // A coin of another type than the type argument.
+    let error = split(TypeTag::Struct(Box::new(Coin::type_(TypeTag::U8))))
+        .object(coin.object_id)
+        .pure(&1000u64)
+        .unwrap()
+        .resolve(&split_signature(), &objects)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MoveCallError::ObjectType { index: 0, object, .. } if object == coin.object_id
+    ));
+
+    // Immutable objects can't be borrowed mutably.
+    let frozen = object(sui_coin(), Owner::Immutable);
+    let error = split(GAS::type_tag())
+        .object(frozen.object_id)
+        .pure(&1000u64)
+        .unwrap()
+        .resolve(&split_signature(), &objects_by_id(&[&frozen]))
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        MoveCallError::ImmutableObject {
+            access: Access::MutRef,
+            ..
+        }
+    ));
+
+    let error = split(GAS::type_tag())
+        .arg(TypedArg::Object(ObjectID::random()))
+        .pure(&1000u64)
+        .unwrap()
+        .resolve(&split_signature(), &objects)
+        .unwrap_err();
+    assert!(matches!(error, MoveCallError::MissingObject { .. }));
+}
@@ -0,0 +1,54 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_config::utils::available_local_socket_address;
+use sui_sdk::rest::RestClient;
+use sui_types::base_types::ObjectID;
+use sui_types::message_envelope::Message;
+use sui_types::messages::TransactionEffectsAPI;
+use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
+use test_utils::network::{start_fullnode_from_config, TestClusterBuilder};
+
+#[tokio::test]
+async fn test_rest_client() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await?;
+    let mut config = test_cluster.fullnode_config_builder().build()?;
+    let rest_address = available_local_socket_address();
+    config.rest_server_address = Some(rest_address);
+    let _fullnode = start_fullnode_from_config(config).await?;
+    let client = RestClient::new(format!("http://{rest_address}"))?;
+
+    let genesis = client.get_checkpoint(0).await?.unwrap();
+    assert_eq!(genesis.sequence_number, 0);
+    let by_digest = client
+        .get_checkpoint_by_digest(*genesis.digest())
+        .await?
+        .unwrap();
+    assert_eq!(by_digest.digest(), genesis.digest());
+
+    let contents = client.get_checkpoint_contents(0).await?.unwrap();
+    assert_eq!(contents.digest(), &genesis.content_digest);
+    let digests = contents.iter().next().unwrap();
+    let transaction = client.get_transaction(digests.transaction).await?.unwrap();
+    assert_eq!(transaction.digest(), &digests.transaction);
+    let effects = client
+        .get_transaction_effects(digests.transaction)
+        .await?
+        .unwrap();
+    assert_eq!(effects.digest(), digests.effects);
+    assert_eq!(effects.transaction_digest(), &digests.transaction);
+
+    let system_state = client
+        .get_object(SUI_SYSTEM_STATE_OBJECT_ID)
+        .await?
+        .unwrap();
+    let at_version = client
+        .get_object_with_version(SUI_SYSTEM_STATE_OBJECT_ID, system_state.version())
+        .await?
+        .unwrap();
+    assert_eq!(at_version.digest(), system_state.digest());
+
+    assert!(client.get_object(ObjectID::random()).await?.is_none());
+    assert!(client.get_checkpoint(u64::MAX).await?.is_none());
+    Ok(())
+}
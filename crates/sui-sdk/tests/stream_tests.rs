@@ -3,12 +3,30 @@
 
 use futures::StreamExt;
 use std::future;
+use std::time::Duration;
 use sui::client_commands::SuiClientCommands;
 use sui_config::genesis_config::DEFAULT_GAS_AMOUNT;
 use sui_config::genesis_config::DEFAULT_NUMBER_OF_OBJECT_PER_ACCOUNT;
 use sui_json_rpc_types::{EventFilter, SuiTransactionResponseQuery};
+use sui_sdk::subscription::SubscriptionConfig;
 use sui_sdk::{SuiClientBuilder, SUI_COIN_TYPE};
-use test_utils::network::TestClusterBuilder;
+use test_utils::network::{TestCluster, TestClusterBuilder};
+use tokio::time::timeout;
+
+const STREAM_TIMEOUT: Duration = Duration::from_secs(30);
+
+async fn create_example_nft(test_cluster: &mut TestCluster) -> Result<(), anyhow::Error> {
+    SuiClientCommands::CreateExampleNFT {
+        name: None,
+        description: None,
+        url: None,
+        gas: None,
+        gas_budget: None,
+    }
+    .execute(&mut test_cluster.wallet)
+    .await?;
+    Ok(())
+}
 
 #[tokio::test]
 async fn test_transactions_stream() -> Result<(), anyhow::Error> {
@@ -136,3 +154,96 @@ async fn test_coins_stream() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_resilient_transactions_subscription() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let client = SuiClientBuilder::default()
+        .build(test_cluster.rpc_url())
+        .await?;
+    let config = SubscriptionConfig {
+        poll_interval: Duration::from_millis(100),
+        ..Default::default()
+    };
+    let mut transactions = Box::pin(client.read_api().subscribe_transactions_resilient(
+        SuiTransactionResponseQuery::default(),
+        None,
+        config.clone(),
+    ));
+    // The subscription starts after the latest transaction once polled.
+    assert!(timeout(Duration::from_secs(1), transactions.next())
+        .await
+        .is_err());
+
+    create_example_nft(&mut test_cluster).await?;
+    create_example_nft(&mut test_cluster).await?;
+    let streamed: Vec<_> = timeout(STREAM_TIMEOUT, transactions.as_mut().take(2).collect()).await?;
+    let mut executed: Vec<_> = client
+        .read_api()
+        .get_transactions_stream(SuiTransactionResponseQuery::default(), None, true)
+        .take(2)
+        .collect()
+        .await;
+    executed.reverse();
+    let digests = |transactions: &[sui_json_rpc_types::SuiTransactionResponse]| {
+        transactions.iter().map(|tx| tx.digest).collect::<Vec<_>>()
+    };
+    assert_eq!(digests(&streamed), digests(&executed));
+
+    // Resuming from a cursor delivers the transactions executed after it, once.
+    let resumed: Vec<_> = timeout(
+        STREAM_TIMEOUT,
+        client
+            .read_api()
+            .subscribe_transactions_resilient(
+                SuiTransactionResponseQuery::default(),
+                Some(executed[0].digest),
+                config,
+            )
+            .take(1)
+            .collect(),
+    )
+    .await?;
+    assert_eq!(digests(&resumed), digests(&executed[1..]));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resilient_event_subscription() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new()
+        .enable_fullnode_events()
+        .build()
+        .await?;
+    let client = test_cluster.sui_client().clone();
+
+    create_example_nft(&mut test_cluster).await?;
+    let cursor = client
+        .event_api()
+        .query_events(EventFilter::All(vec![]), None, Some(1), true)
+        .await?
+        .data[0]
+        .id
+        .clone();
+
+    // Events emitted before the subscription are backfilled from the cursor.
+    create_example_nft(&mut test_cluster).await?;
+    create_example_nft(&mut test_cluster).await?;
+    let mut events = Box::pin(client.event_api().subscribe_event_resilient(
+        EventFilter::All(vec![]),
+        Some(cursor.clone()),
+        SubscriptionConfig::default(),
+    )?);
+    let backfilled: Vec<_> = timeout(STREAM_TIMEOUT, events.as_mut().take(2).collect()).await?;
+    let expected = client
+        .event_api()
+        .query_events(EventFilter::All(vec![]), Some(cursor), None, false)
+        .await?
+        .data;
+    assert_eq!(backfilled, expected);
+
+    // Later events are streamed by the subscription, without repeating the backfilled ones.
+    create_example_nft(&mut test_cluster).await?;
+    let live = timeout(STREAM_TIMEOUT, events.next()).await?.unwrap();
+    assert!(!backfilled.contains(&live));
+    Ok(())
+}
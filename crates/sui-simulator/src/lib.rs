@@ -18,6 +18,9 @@ pub use telemetry_subscribers;
 pub use tempfile;
 pub use tower;
 
+#[cfg(msim)]
+pub mod scenario;
+
 #[cfg(msim)]
 pub mod configs {
     use msim::*;
@@ -0,0 +1,255 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scripted network faults for simulation tests.
+//!
+//! A [`NetworkScenario`] is a list of faults, each active between a start and an end
+//! [`Trigger`]. Faults are applied by clogging links of the simulated network, so the same
+//! scenario run with the same seed produces the same message schedule:
+//!
+//! ```ignore
+//! NetworkScenario::new()
+//!     // cut the first validator off from the others between 10s and 30s
+//!     .isolate(Trigger::at_secs(10), Trigger::at_secs(30), v0, &[v1, v2, v3])
+//!     // then drop half of what it sends to v1 until round 120
+//!     .packet_loss(Trigger::at_secs(30), Trigger::when(move || round() >= 120), v0, v1, 0.5)
+//!     .run();
+//! ```
+
+use msim::net::NetSim;
+use msim::rand::{thread_rng, Rng};
+use msim::task::{JoinHandle, NodeId};
+use msim::time::{sleep, Instant};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+/// How often triggers are evaluated and probabilistic faults are re-sampled.
+const TICK: Duration = Duration::from_millis(10);
+
+/// When a fault starts or stops applying.
+#[derive(Clone)]
+pub enum Trigger {
+    /// A fixed offset from the moment the scenario starts running.
+    At(Duration),
+    /// The first tick at which the predicate holds, e.g. once consensus reached a given round.
+    When(Arc<dyn Fn() -> bool + Send + Sync>),
+}
+
+impl Trigger {
+    pub fn at_secs(secs: u64) -> Self {
+        Self::At(Duration::from_secs(secs))
+    }
+
+    pub fn when(predicate: impl Fn() -> bool + Send + Sync + 'static) -> Self {
+        Self::When(Arc::new(predicate))
+    }
+
+    fn fired(&self, elapsed: Duration) -> bool {
+        match self {
+            Self::At(offset) => elapsed >= *offset,
+            Self::When(predicate) => predicate(),
+        }
+    }
+}
+
+impl fmt::Debug for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::At(offset) => write!(f, "At({:?})", offset),
+            Self::When(_) => write!(f, "When(..)"),
+        }
+    }
+}
+
+/// A fault on the links between simulated nodes. Links are directed, `(from, to)` only affects
+/// the packets sent by `from` to `to`.
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// Drop every packet on the links.
+    Partition { links: Vec<(NodeId, NodeId)> },
+    /// Drop each packet on the links with the given probability.
+    PacketLoss {
+        links: Vec<(NodeId, NodeId)>,
+        rate: f64,
+    },
+    /// Hold back the packets on the links by `delay`. The simulator only models latency for the
+    /// whole network, so this is approximated by repeatedly clogging the links for `delay` and
+    /// reopening them for a tick, relying on the transport to resend what was dropped.
+    Latency {
+        links: Vec<(NodeId, NodeId)>,
+        delay: Duration,
+    },
+}
+
+impl Fault {
+    fn links(&self) -> &[(NodeId, NodeId)] {
+        match self {
+            Self::Partition { links }
+            | Self::PacketLoss { links, .. }
+            | Self::Latency { links, .. } => links,
+        }
+    }
+
+    /// Whether the links should be clogged during the current tick, `active_for` being how long
+    /// the fault has been applying.
+    fn clogged<R: Rng>(&self, active_for: Duration, rng: &mut R) -> bool {
+        match self {
+            Self::Partition { .. } => true,
+            Self::PacketLoss { rate, .. } => rng.gen_bool(rate.clamp(0.0, 1.0)),
+            Self::Latency { delay, .. } => {
+                let period = delay.as_millis() + TICK.as_millis();
+                active_for.as_millis() % period < delay.as_millis()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ScenarioStep {
+    start: Trigger,
+    end: Trigger,
+    fault: Fault,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StepState {
+    Pending,
+    Active(Instant),
+    Done,
+}
+
+/// A timeline of network faults, see the module documentation.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkScenario {
+    steps: Vec<ScenarioStep>,
+}
+
+impl NetworkScenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `fault` from `start` until `end`. The end trigger is only evaluated once the fault
+    /// has started.
+    pub fn with_fault(mut self, start: Trigger, end: Trigger, fault: Fault) -> Self {
+        self.steps.push(ScenarioStep { start, end, fault });
+        self
+    }
+
+    /// Cut all traffic between the two groups of nodes, in both directions.
+    pub fn partition(
+        self,
+        start: Trigger,
+        end: Trigger,
+        side_a: &[NodeId],
+        side_b: &[NodeId],
+    ) -> Self {
+        let links = side_a
+            .iter()
+            .flat_map(|a| side_b.iter().flat_map(move |b| [(*a, *b), (*b, *a)]))
+            .collect();
+        self.with_fault(start, end, Fault::Partition { links })
+    }
+
+    /// Cut all traffic between `node` and `others`, in both directions.
+    pub fn isolate(self, start: Trigger, end: Trigger, node: NodeId, others: &[NodeId]) -> Self {
+        self.partition(start, end, &[node], others)
+    }
+
+    /// Drop the given fraction of the packets sent by `from` to `to`. The reverse direction is
+    /// not affected.
+    pub fn packet_loss(
+        self,
+        start: Trigger,
+        end: Trigger,
+        from: NodeId,
+        to: NodeId,
+        rate: f64,
+    ) -> Self {
+        let links = vec![(from, to)];
+        self.with_fault(start, end, Fault::PacketLoss { links, rate })
+    }
+
+    /// Delay the packets exchanged between `a` and `b`, in both directions.
+    pub fn latency(
+        self,
+        start: Trigger,
+        end: Trigger,
+        a: NodeId,
+        b: NodeId,
+        delay: Duration,
+    ) -> Self {
+        let links = vec![(a, b), (b, a)];
+        self.with_fault(start, end, Fault::Latency { links, delay })
+    }
+
+    /// Run the scenario on the current simulated node until every fault has ended. All the links
+    /// touched by the scenario are reopened when it completes.
+    pub fn run(self) -> JoinHandle<()> {
+        msim::task::spawn(async move {
+            let net = msim::plugin::simulator::<NetSim>();
+            let mut rng = thread_rng();
+            let started = Instant::now();
+            let mut states = vec![StepState::Pending; self.steps.len()];
+            let mut clogged_links = HashMap::<(NodeId, NodeId), bool>::new();
+
+            while states.iter().any(|state| *state != StepState::Done) {
+                let now = Instant::now();
+                let elapsed = now - started;
+                let mut wanted = HashMap::<(NodeId, NodeId), bool>::new();
+
+                for (index, (step, state)) in self.steps.iter().zip(states.iter_mut()).enumerate() {
+                    if *state == StepState::Pending && step.start.fired(elapsed) {
+                        info!("network scenario: starting step {index}: {:?}", step.fault);
+                        *state = StepState::Active(now);
+                    }
+                    if let StepState::Active(since) = *state {
+                        if step.end.fired(elapsed) {
+                            info!("network scenario: ending step {index}");
+                            *state = StepState::Done;
+                            continue;
+                        }
+                        let clogged = step.fault.clogged(now - since, &mut rng);
+                        for link in step.fault.links() {
+                            *wanted.entry(*link).or_default() |= clogged;
+                        }
+                    }
+                }
+
+                for (link, clogged) in clogged_links.iter_mut() {
+                    let want = wanted.remove(link).unwrap_or(false);
+                    if want != *clogged {
+                        set_link(&net, *link, want);
+                        *clogged = want;
+                    }
+                }
+                for (link, want) in wanted {
+                    if want {
+                        set_link(&net, link, true);
+                    }
+                    clogged_links.insert(link, want);
+                }
+
+                sleep(TICK).await;
+            }
+
+            for (link, clogged) in clogged_links {
+                if clogged {
+                    set_link(&net, link, false);
+                }
+            }
+            info!("network scenario: completed");
+        })
+    }
+}
+
+fn set_link(net: &NetSim, (from, to): (NodeId, NodeId), clogged: bool) {
+    if clogged {
+        net.clog_link(from, to);
+    } else {
+        net.unclog_link(from, to);
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, ensure, Context};
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+
+/// 1 SUI, the smallest stake accepted by the system.
+pub const MIN_STAKE_AMOUNT: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StakeAutomationConfig {
+    /// Fullnode serving the JSON-RPC API.
+    pub rpc_url: String,
+    #[serde(default)]
+    pub mode: ExecutionMode,
+    /// Time between two cycles.
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Gas budget of each transaction, in MIST.
+    #[serde(default = "default_gas_budget")]
+    pub gas_budget: u64,
+    /// Addresses whose stakes are managed.
+    pub accounts: Vec<AccountConfig>,
+    /// Validators stakes may be delegated to. Stakes with any other validator are withdrawn.
+    pub validator_allowlist: Vec<SuiAddress>,
+    #[serde(default)]
+    pub policy: RebalancePolicy,
+    /// Active stakes with at least this much estimated reward, in MIST, are withdrawn to be
+    /// staked again with their rewards on the next cycle.
+    #[serde(default = "default_min_reward_to_restake")]
+    pub min_reward_to_restake: u64,
+    /// Deviation from the target allocation of a validator, in basis points of the total stake
+    /// of the account, above which stakes are moved to other validators.
+    #[serde(default = "default_rebalance_threshold_bps")]
+    pub rebalance_threshold_bps: u64,
+    /// Keystore holding the keys of the accounts, used by the default signer in execute mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore_path: Option<PathBuf>,
+    /// File the report of every cycle is appended to, as a JSON line. Reports are only logged
+    /// if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AccountConfig {
+    pub address: SuiAddress,
+    /// SUI kept liquid on the address and never staked, in MIST, on top of the gas budget.
+    #[serde(default)]
+    pub reserve: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExecutionMode {
+    /// Only plans the actions of each cycle and reports them.
+    #[default]
+    Report,
+    /// Builds the transactions of each cycle and dry runs them on the fullnode, without signing.
+    DryRun,
+    /// Signs and executes the transactions of each cycle.
+    Execute,
+}
+
+/// How the stake of each account is split across the allowlisted validators.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RebalancePolicy {
+    /// The same amount with every allowlisted validator.
+    #[default]
+    EqualWeight,
+    /// Amounts proportional to the weight of each validator. Allowlisted validators without a
+    /// weight receive no new stake.
+    Weighted(BTreeMap<SuiAddress, u64>),
+}
+
+fn default_poll_interval_secs() -> u64 {
+    3600
+}
+
+fn default_gas_budget() -> u64 {
+    50_000_000
+}
+
+fn default_min_reward_to_restake() -> u64 {
+    MIN_STAKE_AMOUNT
+}
+
+fn default_rebalance_threshold_bps() -> u64 {
+    500
+}
+
+impl StakeAutomationConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let config = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read {}", path.display()))?;
+        let config: Self = serde_yaml::from_str(&config)
+            .with_context(|| format!("Cannot parse {}", path.display()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(!self.accounts.is_empty(), "No account to manage");
+        ensure!(
+            !self.validator_allowlist.is_empty(),
+            "The validator allowlist is empty"
+        );
+        ensure!(
+            self.rebalance_threshold_bps <= 10_000,
+            "The rebalance threshold cannot exceed 10000 basis points"
+        );
+        if let RebalancePolicy::Weighted(weights) = &self.policy {
+            if let Some(validator) = weights
+                .keys()
+                .find(|validator| !self.validator_allowlist.contains(validator))
+            {
+                return Err(anyhow!(
+                    "Validator {validator} has a weight but is not allowlisted"
+                ));
+            }
+            ensure!(
+                weights.values().any(|weight| *weight > 0),
+                "Every validator weight is zero"
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Automates the stakes of a set of addresses: rewards are compounded by withdrawing and staking
+//! again the stakes that earned enough, stakes are moved away from validators that are not
+//! allowlisted, and the stake of each address is kept split across the allowlisted validators
+//! according to a policy. Every cycle can be reported only, dry run or executed.
+
+pub mod config;
+pub mod planner;
+pub mod report;
+pub mod signer;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use futures::StreamExt;
+use shared_crypto::intent::Intent;
+use sui_json_rpc_types::{
+    StakeStatus, SuiExecutionStatus, SuiObjectDataOptions, SuiTransactionEffectsAPI,
+    SuiTransactionResponseOptions,
+};
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
+use sui_types::messages::{Argument, Command, ObjectArg, Transaction, TransactionData};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
+use sui_types::{
+    SUI_SYSTEM_PACKAGE_ID, SUI_SYSTEM_STATE_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
+};
+use tracing::error;
+
+use crate::config::{AccountConfig, ExecutionMode, StakeAutomationConfig};
+use crate::planner::{plan_actions, AccountState, StakeAction};
+use crate::report::{AccountReport, CycleReport, Outcome};
+use crate::signer::TransactionSigner;
+
+/// Largest number of coins used to pay for gas and new stakes in one transaction. Smaller coins
+/// are left for later cycles.
+const MAX_GAS_COINS: usize = 32;
+
+pub struct StakeAutomation {
+    config: StakeAutomationConfig,
+    client: SuiClient,
+    signer: Option<Arc<dyn TransactionSigner>>,
+}
+
+/// The coins and stakes of an account, as read at the start of a cycle.
+struct AccountSnapshot {
+    state: AccountState,
+    gas_coins: Vec<ObjectRef>,
+}
+
+impl StakeAutomation {
+    /// `signer` is only used, and required, in execute mode.
+    pub fn new(
+        config: StakeAutomationConfig,
+        client: SuiClient,
+        signer: Option<Arc<dyn TransactionSigner>>,
+    ) -> anyhow::Result<Self> {
+        config.validate()?;
+        if config.mode == ExecutionMode::Execute && signer.is_none() {
+            return Err(anyhow!("Execute mode requires a signer"));
+        }
+        Ok(Self {
+            config,
+            client,
+            signer,
+        })
+    }
+
+    /// Runs a cycle every poll interval, forever. A failed cycle is retried on the next one.
+    pub async fn run(&self) {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.poll_interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.run_cycle().await {
+                error!("Stake automation cycle failed: {err:?}");
+            }
+        }
+    }
+
+    /// Plans, and depending on the mode dry runs or executes, the actions of every account, then
+    /// writes the report of the cycle.
+    pub async fn run_cycle(&self) -> anyhow::Result<CycleReport> {
+        let system_state = self
+            .client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await?;
+        let active_validators: BTreeSet<SuiAddress> = system_state
+            .active_validators
+            .iter()
+            .map(|validator| validator.sui_address)
+            .collect();
+
+        let mut accounts = vec![];
+        for account in &self.config.accounts {
+            accounts.push(self.process_account(account, &active_validators).await);
+        }
+        let report = CycleReport {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            epoch: system_state.epoch,
+            mode: self.config.mode,
+            accounts,
+        };
+        report.write(self.config.report_path.as_deref())?;
+        Ok(report)
+    }
+
+    async fn process_account(
+        &self,
+        account: &AccountConfig,
+        active_validators: &BTreeSet<SuiAddress>,
+    ) -> AccountReport {
+        let mut report = AccountReport {
+            address: account.address,
+            liquid_balance: 0,
+            staked_principal: 0,
+            estimated_rewards: 0,
+            actions: vec![],
+            outcome: Outcome::NoAction,
+        };
+        let snapshot = match self.read_account(account.address).await {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                report.outcome = Outcome::Failed {
+                    error: format!("Cannot read the account: {err}"),
+                };
+                return report;
+            }
+        };
+        report.liquid_balance = snapshot.state.liquid_balance;
+        for stake in snapshot.state.stakes.iter().flat_map(|d| &d.stakes) {
+            report.staked_principal += stake.principal;
+            if let StakeStatus::Active { estimated_reward } = stake.status {
+                report.estimated_rewards += estimated_reward;
+            }
+        }
+
+        report.actions = plan_actions(&self.config, active_validators, account, &snapshot.state);
+        if report.actions.is_empty() {
+            return report;
+        }
+        report.outcome = match self.submit(&snapshot, &report.actions).await {
+            Ok(outcome) => outcome,
+            Err(err) => Outcome::Failed {
+                error: err.to_string(),
+            },
+        };
+        report
+    }
+
+    async fn read_account(&self, address: SuiAddress) -> anyhow::Result<AccountSnapshot> {
+        let mut coins: Vec<_> = self
+            .client
+            .coin_read_api()
+            .get_coins_stream(address, None)
+            .filter(|coin| futures::future::ready(coin.locked_until_epoch.is_none()))
+            .collect()
+            .await;
+        coins.sort_by_key(|coin| std::cmp::Reverse(coin.balance));
+        coins.truncate(MAX_GAS_COINS);
+        let stakes = self.client.governance_api().get_stakes(address).await?;
+        Ok(AccountSnapshot {
+            state: AccountState {
+                address,
+                liquid_balance: coins.iter().map(|coin| coin.balance).sum(),
+                stakes,
+            },
+            gas_coins: coins.iter().map(|coin| coin.object_ref()).collect(),
+        })
+    }
+
+    async fn submit(
+        &self,
+        snapshot: &AccountSnapshot,
+        actions: &[StakeAction],
+    ) -> anyhow::Result<Outcome> {
+        if self.config.mode == ExecutionMode::Report {
+            return Ok(Outcome::Planned);
+        }
+        let transaction = self.build_transaction(snapshot, actions).await?;
+        let address = snapshot.state.address;
+
+        if self.config.mode == ExecutionMode::DryRun {
+            let response = self
+                .client
+                .read_api()
+                .dry_run_transaction(transaction)
+                .await?;
+            return Ok(Outcome::DryRun {
+                error: status_error(response.effects.status()),
+                gas_cost_summary: response.effects.gas_cost_summary().clone(),
+            });
+        }
+
+        let signer = self
+            .signer
+            .as_ref()
+            .expect("Execute mode requires a signer");
+        let signature = signer.sign(address, &transaction).await?;
+        let transaction =
+            Transaction::from_data(transaction, Intent::sui_transaction(), vec![signature])
+                .verify()?;
+        let response = self
+            .client
+            .quorum_driver()
+            .execute_transaction(
+                transaction,
+                SuiTransactionResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await?;
+        Ok(Outcome::Executed {
+            digest: response.digest,
+            error: response
+                .effects
+                .as_ref()
+                .and_then(|effects| status_error(effects.status())),
+        })
+    }
+
+    /// Builds a single transaction with every action of the account. New stakes are split from
+    /// the gas coin, which the gas payment coins are merged into.
+    async fn build_transaction(
+        &self,
+        snapshot: &AccountSnapshot,
+        actions: &[StakeAction],
+    ) -> anyhow::Result<TransactionData> {
+        if snapshot.gas_coins.is_empty() {
+            return Err(anyhow!("No SUI coin to pay for gas"));
+        }
+        let staked_sui_ids: Vec<ObjectID> = actions
+            .iter()
+            .filter_map(|action| match action {
+                StakeAction::Withdraw { staked_sui_id, .. } => Some(*staked_sui_id),
+                StakeAction::Stake { .. } => None,
+            })
+            .collect();
+        let mut staked_sui_refs = BTreeMap::new();
+        if !staked_sui_ids.is_empty() {
+            let responses = self
+                .client
+                .read_api()
+                .multi_get_object_with_options(staked_sui_ids, SuiObjectDataOptions::new())
+                .await?;
+            for response in responses {
+                let object = response.into_object()?;
+                staked_sui_refs.insert(object.object_id, object.object_ref());
+            }
+        }
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let system_state = builder.obj(ObjectArg::SharedObject {
+            id: SUI_SYSTEM_STATE_OBJECT_ID,
+            initial_shared_version: SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
+            mutable: true,
+        })?;
+        for action in actions {
+            match action {
+                StakeAction::Withdraw { staked_sui_id, .. } => {
+                    let staked_sui = staked_sui_refs
+                        .get(staked_sui_id)
+                        .ok_or_else(|| anyhow!("Stake {staked_sui_id} not found"))?;
+                    let staked_sui = builder.obj(ObjectArg::ImmOrOwnedObject(*staked_sui))?;
+                    builder.programmable_move_call(
+                        SUI_SYSTEM_PACKAGE_ID,
+                        SUI_SYSTEM_MODULE_NAME.to_owned(),
+                        WITHDRAW_STAKE_FUN_NAME.to_owned(),
+                        vec![],
+                        vec![system_state, staked_sui],
+                    );
+                }
+                StakeAction::Stake { validator, amount } => {
+                    let amount = builder.pure(*amount)?;
+                    let validator = builder.pure(*validator)?;
+                    let coin =
+                        builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+                    builder.programmable_move_call(
+                        SUI_SYSTEM_PACKAGE_ID,
+                        SUI_SYSTEM_MODULE_NAME.to_owned(),
+                        ADD_STAKE_FUN_NAME.to_owned(),
+                        vec![],
+                        vec![system_state, coin, validator],
+                    );
+                }
+            }
+        }
+
+        let gas_price = self.client.read_api().get_reference_gas_price().await?;
+        Ok(TransactionData::new_programmable(
+            snapshot.state.address,
+            snapshot.gas_coins.clone(),
+            builder.finish(),
+            self.config.gas_budget,
+            gas_price,
+        ))
+    }
+}
+
+fn status_error(status: &SuiExecutionStatus) -> Option<String> {
+    match status {
+        SuiExecutionStatus::Success => None,
+        SuiExecutionStatus::Failure { error } => Some(error.clone()),
+    }
+}
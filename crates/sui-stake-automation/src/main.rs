@@ -0,0 +1,90 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use clap::Parser;
+use sui_sdk::SuiClientBuilder;
+use sui_stake_automation::config::{ExecutionMode, StakeAutomationConfig};
+use sui_stake_automation::signer::{KeystoreSigner, TransactionSigner};
+use sui_stake_automation::StakeAutomation;
+use tracing::info;
+
+#[derive(Parser)]
+#[clap(
+    name = "Sui Stake Automation",
+    about = "Compounds and rebalances the stakes of a set of addresses",
+    rename_all = "kebab-case"
+)]
+struct Args {
+    #[clap(long)]
+    config: PathBuf,
+
+    /// Overrides the mode of the config file.
+    #[clap(long, arg_enum)]
+    mode: Option<Mode>,
+
+    /// Runs a single cycle and exits.
+    #[clap(long)]
+    once: bool,
+}
+
+#[derive(Clone, Copy, clap::ArgEnum)]
+enum Mode {
+    Report,
+    DryRun,
+    Execute,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    let args = Args::parse();
+    let mut config = StakeAutomationConfig::load(&args.config)?;
+    if let Some(mode) = args.mode {
+        config.mode = match mode {
+            Mode::Report => ExecutionMode::Report,
+            Mode::DryRun => ExecutionMode::DryRun,
+            Mode::Execute => ExecutionMode::Execute,
+        };
+    }
+
+    let signer: Option<Arc<dyn TransactionSigner>> = match config.mode {
+        ExecutionMode::Execute => {
+            let path = config
+                .keystore_path
+                .as_ref()
+                .ok_or_else(|| anyhow!("Execute mode requires a keystore path"))?;
+            let signer = KeystoreSigner::new(path)?;
+            let addresses = signer.addresses();
+            if let Some(account) = config
+                .accounts
+                .iter()
+                .find(|account| !addresses.contains(&account.address))
+            {
+                return Err(anyhow!("The keystore has no key for {}", account.address));
+            }
+            Some(Arc::new(signer))
+        }
+        ExecutionMode::Report | ExecutionMode::DryRun => None,
+    };
+
+    let client = SuiClientBuilder::default().build(&config.rpc_url).await?;
+    info!(
+        mode = ?config.mode,
+        accounts = config.accounts.len(),
+        "Starting stake automation"
+    );
+    let automation = StakeAutomation::new(config, client, signer)?;
+    if args.once {
+        automation.run_cycle().await?;
+    } else {
+        automation.run().await;
+    }
+    Ok(())
+}
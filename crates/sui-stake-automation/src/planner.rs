@@ -0,0 +1,391 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decides what to do with the stakes of an account in a cycle. Withdrawals and new stakes are
+//! planned independently: SUI withdrawn in a cycle is only staked again on the next one, once it
+//! shows up in the balance of the account.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use sui_json_rpc_types::{DelegatedStake, StakeStatus};
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+use crate::config::{AccountConfig, RebalancePolicy, StakeAutomationConfig, MIN_STAKE_AMOUNT};
+
+/// What an account holds at the start of a cycle.
+#[derive(Debug, Clone)]
+pub struct AccountState {
+    pub address: SuiAddress,
+    /// Balance of the SUI coins that can pay for gas and new stakes.
+    pub liquid_balance: u64,
+    pub stakes: Vec<DelegatedStake>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "action")]
+pub enum StakeAction {
+    Withdraw {
+        validator: SuiAddress,
+        staked_sui_id: ObjectID,
+        principal: u64,
+        estimated_reward: u64,
+        reason: WithdrawReason,
+    },
+    Stake {
+        validator: SuiAddress,
+        amount: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WithdrawReason {
+    /// The validator is not allowlisted.
+    NotAllowlisted,
+    /// The rewards of the stake are worth staking again.
+    CompoundRewards,
+    /// The validator holds more than its share of the stake of the account.
+    Rebalance,
+}
+
+struct ActiveStake {
+    staked_sui_id: ObjectID,
+    principal: u64,
+    estimated_reward: u64,
+}
+
+/// Plans the withdrawals and new stakes of `account`, given the validators currently active.
+pub fn plan_actions(
+    config: &StakeAutomationConfig,
+    active_validators: &BTreeSet<SuiAddress>,
+    account: &AccountConfig,
+    state: &AccountState,
+) -> Vec<StakeAction> {
+    let weights = target_weights(config, active_validators);
+    let total_weight: u64 = weights.values().sum();
+    let mut actions = vec![];
+    if total_weight == 0 {
+        return actions;
+    }
+
+    // Stake currently delegated to each validator with a target weight, and not withdrawn.
+    let mut allocation: BTreeMap<SuiAddress, u64> =
+        weights.keys().map(|validator| (*validator, 0)).collect();
+    let mut kept_stakes: BTreeMap<SuiAddress, Vec<ActiveStake>> = BTreeMap::new();
+    let mut withdrawn = 0u64;
+    for delegated in &state.stakes {
+        let validator = delegated.validator_address;
+        for stake in &delegated.stakes {
+            let estimated_reward = match stake.status {
+                StakeStatus::Active { estimated_reward } => estimated_reward,
+                // Pending stakes cannot be moved, but count towards the allocation.
+                StakeStatus::Pending => {
+                    if let Some(allocated) = allocation.get_mut(&validator) {
+                        *allocated += stake.principal;
+                    }
+                    continue;
+                }
+            };
+            let reason = if !config.validator_allowlist.contains(&validator) {
+                Some(WithdrawReason::NotAllowlisted)
+            } else if !weights.contains_key(&validator) {
+                Some(WithdrawReason::Rebalance)
+            } else if estimated_reward >= config.min_reward_to_restake {
+                Some(WithdrawReason::CompoundRewards)
+            } else {
+                None
+            };
+            match reason {
+                Some(reason) => {
+                    withdrawn += stake.principal + estimated_reward;
+                    actions.push(StakeAction::Withdraw {
+                        validator,
+                        staked_sui_id: stake.staked_sui_id,
+                        principal: stake.principal,
+                        estimated_reward,
+                        reason,
+                    });
+                }
+                None => {
+                    *allocation.entry(validator).or_default() += stake.principal;
+                    kept_stakes.entry(validator).or_default().push(ActiveStake {
+                        staked_sui_id: stake.staked_sui_id,
+                        principal: stake.principal,
+                        estimated_reward,
+                    });
+                }
+            }
+        }
+    }
+
+    let stakeable = state
+        .liquid_balance
+        .saturating_sub(account.reserve.saturating_add(config.gas_budget));
+    let total = allocation.values().sum::<u64>() + withdrawn + stakeable;
+    let targets: BTreeMap<SuiAddress, u64> = weights
+        .iter()
+        .map(|(validator, weight)| (*validator, share(total, *weight, total_weight)))
+        .collect();
+    let tolerance = share(total, config.rebalance_threshold_bps, 10_000);
+
+    // Withdraw from the validators above their target, as long as it gets them closer to it.
+    for (validator, stakes) in &mut kept_stakes {
+        let target = targets[validator];
+        let allocated = allocation.get_mut(validator).unwrap();
+        if *allocated <= target.saturating_add(tolerance) {
+            continue;
+        }
+        stakes.sort_by_key(|stake| stake.principal);
+        for stake in stakes.iter() {
+            let remaining = *allocated - stake.principal;
+            if remaining.abs_diff(target) >= allocated.abs_diff(target) {
+                continue;
+            }
+            *allocated = remaining;
+            actions.push(StakeAction::Withdraw {
+                validator: *validator,
+                staked_sui_id: stake.staked_sui_id,
+                principal: stake.principal,
+                estimated_reward: stake.estimated_reward,
+                reason: WithdrawReason::Rebalance,
+            });
+            if *allocated <= target.saturating_add(tolerance) {
+                break;
+            }
+        }
+    }
+
+    // Stake the liquid balance with the validators below their target, in proportion to how
+    // far below they are. Anything left once every target is met is split by weight.
+    let deficits: BTreeMap<SuiAddress, u64> = targets
+        .iter()
+        .map(|(validator, target)| (*validator, target.saturating_sub(allocation[validator])))
+        .collect();
+    let total_deficit: u64 = deficits.values().sum();
+    for (validator, deficit) in &deficits {
+        let amount = if stakeable >= total_deficit {
+            deficit + share(stakeable - total_deficit, weights[validator], total_weight)
+        } else {
+            share(stakeable, *deficit, total_deficit)
+        };
+        if amount >= MIN_STAKE_AMOUNT {
+            actions.push(StakeAction::Stake {
+                validator: *validator,
+                amount,
+            });
+        }
+    }
+    actions
+}
+
+/// Weight of each validator that may receive new stake: allowlisted, active and, for weighted
+/// policies, with a non-zero weight.
+fn target_weights(
+    config: &StakeAutomationConfig,
+    active_validators: &BTreeSet<SuiAddress>,
+) -> BTreeMap<SuiAddress, u64> {
+    config
+        .validator_allowlist
+        .iter()
+        .filter(|validator| active_validators.contains(validator))
+        .filter_map(|validator| {
+            let weight = match &config.policy {
+                RebalancePolicy::EqualWeight => 1,
+                RebalancePolicy::Weighted(weights) => weights.get(validator).copied()?,
+            };
+            (weight > 0).then_some((*validator, weight))
+        })
+        .collect()
+}
+
+/// `amount * numerator / denominator`, rounded down.
+fn share(amount: u64, numerator: u64, denominator: u64) -> u64 {
+    if denominator == 0 {
+        return 0;
+    }
+    (amount as u128 * numerator as u128 / denominator as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ExecutionMode;
+    use sui_json_rpc_types::Stake;
+    use sui_types::base_types::dbg_addr;
+
+    const SUI: u64 = MIN_STAKE_AMOUNT;
+
+    fn config(allowlist: Vec<SuiAddress>, policy: RebalancePolicy) -> StakeAutomationConfig {
+        StakeAutomationConfig {
+            rpc_url: "http://127.0.0.1:9000".to_string(),
+            mode: ExecutionMode::Report,
+            poll_interval_secs: 60,
+            gas_budget: SUI,
+            accounts: vec![],
+            validator_allowlist: allowlist,
+            policy,
+            min_reward_to_restake: 10 * SUI,
+            rebalance_threshold_bps: 500,
+            keystore_path: None,
+            report_path: None,
+        }
+    }
+
+    fn account() -> AccountConfig {
+        AccountConfig {
+            address: dbg_addr(1),
+            reserve: 0,
+        }
+    }
+
+    fn delegated(validator: SuiAddress, stakes: Vec<(u64, StakeStatus)>) -> DelegatedStake {
+        DelegatedStake {
+            validator_address: validator,
+            staking_pool: ObjectID::random(),
+            stakes: stakes
+                .into_iter()
+                .map(|(principal, status)| Stake {
+                    staked_sui_id: ObjectID::random(),
+                    stake_request_epoch: 0,
+                    stake_active_epoch: 1,
+                    principal,
+                    status,
+                })
+                .collect(),
+        }
+    }
+
+    fn active(estimated_reward: u64) -> StakeStatus {
+        StakeStatus::Active { estimated_reward }
+    }
+
+    fn stakes(actions: &[StakeAction]) -> BTreeMap<SuiAddress, u64> {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                StakeAction::Stake { validator, amount } => Some((*validator, *amount)),
+                StakeAction::Withdraw { .. } => None,
+            })
+            .collect()
+    }
+
+    fn withdrawals(actions: &[StakeAction]) -> Vec<(SuiAddress, u64, WithdrawReason)> {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                StakeAction::Withdraw {
+                    validator,
+                    principal,
+                    reason,
+                    ..
+                } => Some((*validator, *principal, *reason)),
+                StakeAction::Stake { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stakes_liquid_balance_equally() {
+        let (a, b) = (dbg_addr(10), dbg_addr(11));
+        let config = config(vec![a, b], RebalancePolicy::EqualWeight);
+        let state = AccountState {
+            address: dbg_addr(1),
+            liquid_balance: 101 * SUI,
+            stakes: vec![],
+        };
+        let actions = plan_actions(&config, &[a, b].into(), &account(), &state);
+        assert_eq!(stakes(&actions), [(a, 50 * SUI), (b, 50 * SUI)].into());
+    }
+
+    #[test]
+    fn test_withdraws_from_validators_not_allowlisted() {
+        let (a, other) = (dbg_addr(10), dbg_addr(11));
+        let config = config(vec![a], RebalancePolicy::EqualWeight);
+        let state = AccountState {
+            address: dbg_addr(1),
+            liquid_balance: 0,
+            stakes: vec![delegated(other, vec![(20 * SUI, active(0))])],
+        };
+        let actions = plan_actions(&config, &[a, other].into(), &account(), &state);
+        assert_eq!(
+            withdrawals(&actions),
+            vec![(other, 20 * SUI, WithdrawReason::NotAllowlisted)]
+        );
+        assert!(stakes(&actions).is_empty());
+    }
+
+    #[test]
+    fn test_compounds_rewards() {
+        let a = dbg_addr(10);
+        let config = config(vec![a], RebalancePolicy::EqualWeight);
+        let state = AccountState {
+            address: dbg_addr(1),
+            liquid_balance: 0,
+            stakes: vec![delegated(
+                a,
+                vec![(100 * SUI, active(10 * SUI)), (100 * SUI, active(SUI))],
+            )],
+        };
+        let actions = plan_actions(&config, &[a].into(), &account(), &state);
+        assert_eq!(
+            withdrawals(&actions),
+            vec![(a, 100 * SUI, WithdrawReason::CompoundRewards)]
+        );
+    }
+
+    #[test]
+    fn test_rebalances_by_weight() {
+        let (a, b) = (dbg_addr(10), dbg_addr(11));
+        let config = config(
+            vec![a, b],
+            RebalancePolicy::Weighted([(a, 1), (b, 3)].into()),
+        );
+        let state = AccountState {
+            address: dbg_addr(1),
+            liquid_balance: 0,
+            stakes: vec![
+                delegated(a, vec![(50 * SUI, active(0)), (50 * SUI, active(0))]),
+                delegated(b, vec![(100 * SUI, active(0))]),
+            ],
+        };
+        // The target of a is 50 SUI out of 200, so one of its stakes is moved.
+        let actions = plan_actions(&config, &[a, b].into(), &account(), &state);
+        assert_eq!(
+            withdrawals(&actions),
+            vec![(a, 50 * SUI, WithdrawReason::Rebalance)]
+        );
+    }
+
+    #[test]
+    fn test_keeps_reserve_and_skips_small_stakes() {
+        let (a, b) = (dbg_addr(10), dbg_addr(11));
+        let config = config(vec![a, b], RebalancePolicy::EqualWeight);
+        let account = AccountConfig {
+            address: dbg_addr(1),
+            reserve: 9 * SUI,
+        };
+        let state = AccountState {
+            address: dbg_addr(1),
+            liquid_balance: 11 * SUI,
+            stakes: vec![],
+        };
+        // Only 1 SUI can be staked once the reserve and gas budget are set aside, which is too
+        // little to split across two validators.
+        let actions = plan_actions(&config, &[a, b].into(), &account, &state);
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn test_ignores_inactive_validators() {
+        let (a, b) = (dbg_addr(10), dbg_addr(11));
+        let config = config(vec![a, b], RebalancePolicy::EqualWeight);
+        let state = AccountState {
+            address: dbg_addr(1),
+            liquid_balance: 11 * SUI,
+            stakes: vec![],
+        };
+        let actions = plan_actions(&config, &[a].into(), &account(), &state);
+        assert_eq!(stakes(&actions), [(a, 10 * SUI)].into());
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+use sui_json_rpc_types::SuiGasCostSummary;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+use tracing::{info, warn};
+
+use crate::config::ExecutionMode;
+use crate::planner::StakeAction;
+
+/// What a cycle did for every account.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CycleReport {
+    pub timestamp_ms: u64,
+    pub epoch: u64,
+    pub mode: ExecutionMode,
+    pub accounts: Vec<AccountReport>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AccountReport {
+    pub address: SuiAddress,
+    pub liquid_balance: u64,
+    pub staked_principal: u64,
+    pub estimated_rewards: u64,
+    pub actions: Vec<StakeAction>,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "outcome")]
+pub enum Outcome {
+    /// Nothing to do for the account.
+    NoAction,
+    /// The actions were planned but no transaction was built, in report mode.
+    Planned,
+    /// The transaction was dry run, in dry-run mode.
+    DryRun {
+        error: Option<String>,
+        gas_cost_summary: SuiGasCostSummary,
+    },
+    /// The transaction was submitted, in execute mode.
+    Executed {
+        digest: TransactionDigest,
+        error: Option<String>,
+    },
+    /// The state of the account could not be read, or its transaction could not be built, signed
+    /// or submitted.
+    Failed { error: String },
+}
+
+impl CycleReport {
+    /// Logs a summary of the report, and appends it to `path` as a JSON line if set.
+    pub fn write(&self, path: Option<&Path>) -> anyhow::Result<()> {
+        for account in &self.accounts {
+            match &account.outcome {
+                Outcome::Failed { error } => warn!(
+                    address = %account.address,
+                    actions = account.actions.len(),
+                    "Stake automation failed: {error}"
+                ),
+                outcome => info!(
+                    address = %account.address,
+                    liquid_balance = account.liquid_balance,
+                    staked_principal = account.staked_principal,
+                    estimated_rewards = account.estimated_rewards,
+                    actions = account.actions.len(),
+                    ?outcome,
+                    "Stake automation cycle"
+                ),
+            }
+        }
+
+        let Some(path) = path else {
+            return Ok(());
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Cannot open report file {}", path.display()))?;
+        let mut line = serde_json::to_vec(self)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        Ok(())
+    }
+}
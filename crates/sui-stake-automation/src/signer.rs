@@ -0,0 +1,48 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use shared_crypto::intent::Intent;
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::Signature;
+use sui_types::messages::TransactionData;
+
+/// Signs the transactions of the managed accounts. Implementations may hold the keys locally, or
+/// forward the transactions to a custody service or hardware security module.
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    async fn sign(&self, address: SuiAddress, data: &TransactionData) -> anyhow::Result<Signature>;
+}
+
+/// Signs with the keys of a Sui keystore file.
+pub struct KeystoreSigner {
+    keystore: Keystore,
+}
+
+impl KeystoreSigner {
+    pub fn new(path: &PathBuf) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Err(anyhow!("Keystore {} does not exist", path.display()));
+        }
+        Ok(Self {
+            keystore: Keystore::from(FileBasedKeystore::new(path)?),
+        })
+    }
+
+    pub fn addresses(&self) -> Vec<SuiAddress> {
+        self.keystore.addresses()
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for KeystoreSigner {
+    async fn sign(&self, address: SuiAddress, data: &TransactionData) -> anyhow::Result<Signature> {
+        Ok(self
+            .keystore
+            .sign_secure(&address, data, Intent::sui_transaction())?)
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Archive of checkpoints in an object store, from which nodes can sync the history that their
+//! peers have pruned.
+//!
+//! The summary of checkpoint `n` is stored at `checkpoints/n.sum` and its full contents at
+//! `checkpoints/n.chk`. The sequence number of the checkpoint including transaction `d` is stored
+//! at `transactions/d`, and version `v` of object `o` at `objects/o/v`. All of them are BCS
+//! encoded. The archive is not trusted: readers must verify the summaries against the committee of
+//! their epoch, the contents against the verified summaries, and the objects against the effects
+//! of the transactions that wrote them.
+
+use crate::object_store::util::put;
+use crate::object_store::ObjectStoreConfig;
+use anyhow::Context;
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, FullCheckpointContents,
+};
+use sui_types::object::Object;
+
+const SUMMARY_FILE_SUFFIX: &str = "sum";
+const CONTENTS_FILE_SUFFIX: &str = "chk";
+
+fn checkpoint_path(sequence_number: CheckpointSequenceNumber, suffix: &str) -> Path {
+    Path::from(format!("checkpoints/{sequence_number}.{suffix}"))
+}
+
+fn transaction_path(digest: &TransactionDigest) -> Path {
+    Path::from(format!("transactions/{}", digest.base58_encode()))
+}
+
+fn object_path(object_id: &ObjectID, version: SequenceNumber) -> Path {
+    Path::from(format!("objects/{object_id}/{}", version.value()))
+}
+
+#[derive(Clone)]
+pub struct ArchiveReader {
+    store: Arc<DynObjectStore>,
+}
+
+impl ArchiveReader {
+    pub fn new(config: &ObjectStoreConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            store: config.make()?,
+        })
+    }
+
+    /// Returns the summary of the checkpoint, or `None` if it is not in the archive.
+    pub async fn get_checkpoint_summary(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<CertifiedCheckpointSummary>> {
+        self.get(&checkpoint_path(sequence_number, SUMMARY_FILE_SUFFIX))
+            .await
+    }
+
+    /// Returns the full contents of the checkpoint, or `None` if they are not in the archive.
+    pub async fn get_checkpoint_contents(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> anyhow::Result<Option<FullCheckpointContents>> {
+        self.get(&checkpoint_path(sequence_number, CONTENTS_FILE_SUFFIX))
+            .await
+    }
+
+    /// Returns the sequence number of the checkpoint that includes the transaction, or `None` if
+    /// the transaction is not in the archive.
+    pub async fn get_transaction_checkpoint(
+        &self,
+        digest: &TransactionDigest,
+    ) -> anyhow::Result<Option<CheckpointSequenceNumber>> {
+        self.get(&transaction_path(digest)).await
+    }
+
+    /// Returns the given version of the object, or `None` if it is not in the archive.
+    pub async fn get_object(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> anyhow::Result<Option<Object>> {
+        self.get(&object_path(object_id, version)).await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, location: &Path) -> anyhow::Result<Option<T>> {
+        let bytes = match self.store.get(location).await {
+            Ok(result) => result.bytes().await?,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        bcs::from_bytes(&bytes)
+            .map(Some)
+            .with_context(|| format!("Failed to decode {location}"))
+    }
+}
+
+/// Writes a checkpoint to the archive in `store`.
+pub async fn write_checkpoint(
+    store: Arc<DynObjectStore>,
+    summary: &CertifiedCheckpointSummary,
+    contents: &FullCheckpointContents,
+) -> anyhow::Result<()> {
+    let sequence_number = *summary.sequence_number();
+    put(
+        &checkpoint_path(sequence_number, CONTENTS_FILE_SUFFIX),
+        Bytes::from(bcs::to_bytes(contents)?),
+        store.clone(),
+    )
+    .await?;
+    for digests in contents.checkpoint_contents().iter() {
+        put(
+            &transaction_path(&digests.transaction),
+            Bytes::from(bcs::to_bytes(&sequence_number)?),
+            store.clone(),
+        )
+        .await?;
+    }
+    // The summary is written last, so that readers finding it can also find the contents.
+    put(
+        &checkpoint_path(sequence_number, SUMMARY_FILE_SUFFIX),
+        Bytes::from(bcs::to_bytes(summary)?),
+        store,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Writes a version of an object to the archive in `store`.
+pub async fn write_object(store: Arc<DynObjectStore>, object: &Object) -> anyhow::Result<()> {
+    put(
+        &object_path(&object.id(), object.version()),
+        Bytes::from(bcs::to_bytes(object)?),
+        store,
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object_store::ObjectStoreType;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_missing_checkpoint() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let reader = ArchiveReader::new(&ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        })?;
+
+        assert!(reader.get_checkpoint_summary(1).await?.is_none());
+        assert!(reader.get_checkpoint_contents(1).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_contents() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let contents =
+            FullCheckpointContents::new_with_causally_ordered_transactions(std::iter::empty());
+        put(
+            &checkpoint_path(7, CONTENTS_FILE_SUFFIX),
+            Bytes::from(bcs::to_bytes(&contents)?),
+            config.make()?,
+        )
+        .await?;
+
+        let reader = ArchiveReader::new(&config)?;
+        assert_eq!(reader.get_checkpoint_contents(7).await?, Some(contents));
+        assert!(reader.get_checkpoint_summary(7).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_object() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let object = Object::immutable_with_id_for_testing(ObjectID::random());
+        write_object(config.make()?, &object).await?;
+
+        let reader = ArchiveReader::new(&config)?;
+        assert_eq!(
+            reader.get_object(&object.id(), object.version()).await?,
+            Some(object.clone())
+        );
+        assert!(reader
+            .get_object(&object.id(), SequenceNumber::from_u64(2))
+            .await?
+            .is_none());
+        Ok(())
+    }
+}
@@ -314,6 +314,144 @@ impl IndexStore {
         Ok(sequence)
     }
 
+    /// Removes pruned transactions from the transaction order, before they are unindexed with
+    /// `prune_tx`. Transactions are ordered as they are indexed, so the transactions of pruned
+    /// checkpoints mostly form a single run of the order, which is removed with a range delete.
+    /// Transactions indexed before their checkpoint, e.g. executed through the RPC, may be
+    /// interleaved with transactions that are not pruned, which split the order into several runs.
+    pub fn prune_transaction_order<'a>(
+        &self,
+        digests: impl IntoIterator<Item = &'a TransactionDigest>,
+    ) -> SuiResult {
+        let mut pruned: Vec<TxSequenceNumber> = self
+            .tables
+            .transactions_seq
+            .multi_get(digests)?
+            .into_iter()
+            .flatten()
+            .collect();
+        pruned.sort_unstable();
+        let (Some(first), Some(last)) = (pruned.first(), pruned.last()) else {
+            return Ok(());
+        };
+
+        let mut runs = vec![];
+        let mut run: Option<(TxSequenceNumber, TxSequenceNumber)> = None;
+        for (sequence, _) in self.tables.transaction_order.range_iter(*first..=*last)? {
+            if pruned.binary_search(&sequence).is_ok() {
+                run = Some(run.map_or((sequence, sequence), |(start, _)| (start, sequence)));
+            } else {
+                runs.extend(run.take());
+            }
+        }
+        runs.extend(run);
+
+        let mut batch = self.tables.transaction_order.batch();
+        for (start, end) in runs {
+            batch = batch.delete_range(&self.tables.transaction_order, &start, &(end + 1))?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
+    /// Removes the indexes `index_tx` wrote for a transaction, from the same data, so that the
+    /// transaction is no longer found through them. The owner and dynamic field indexes, which
+    /// index live objects rather than transactions, are left as they are. Does nothing if the
+    /// transaction is not indexed. The transaction order is pruned by `prune_transaction_order`.
+    pub fn prune_tx(
+        &self,
+        sender: SuiAddress,
+        active_inputs: impl Iterator<Item = ObjectID>,
+        mutated_objects: impl Iterator<Item = (ObjectRef, Owner)> + Clone,
+        move_functions: impl Iterator<Item = (ObjectID, Identifier, Identifier)>,
+        events: &TransactionEvents,
+        digest: &TransactionDigest,
+    ) -> SuiResult {
+        let Some(sequence) = self.tables.transactions_seq.get(digest)? else {
+            return Ok(());
+        };
+        let timestamp_ms = self.tables.timestamps.get(digest)?;
+
+        let batch = self.tables.transactions_from_addr.batch();
+        let batch = batch.delete_batch(&self.tables.transactions_seq, std::iter::once(*digest))?;
+        let batch = batch.delete_batch(
+            &self.tables.transactions_from_addr,
+            std::iter::once((sender, sequence)),
+        )?;
+        let batch = batch.delete_batch(
+            &self.tables.transactions_by_input_object_id,
+            active_inputs.map(|id| (id, sequence)),
+        )?;
+        let batch = batch.delete_batch(
+            &self.tables.transactions_by_mutated_object_id,
+            mutated_objects
+                .clone()
+                .map(|(obj_ref, _)| (obj_ref.0, sequence)),
+        )?;
+        let batch = batch.delete_batch(
+            &self.tables.transactions_by_move_function,
+            move_functions.map(|(obj_id, module, function)| {
+                (obj_id, module.to_string(), function.to_string(), sequence)
+            }),
+        )?;
+        let batch = batch.delete_batch(
+            &self.tables.transactions_to_addr,
+            mutated_objects.filter_map(|(_, owner)| {
+                owner.get_owner_address().ok().map(|addr| (addr, sequence))
+            }),
+        )?;
+        let batch = batch.delete_batch(&self.tables.timestamps, std::iter::once(*digest))?;
+
+        // events. The ids of the events of a transaction all start with its sequence number, so its
+        // events keyed by id, or by its timestamp then id, are a single range.
+        let events_end = (sequence + 1, usize::MIN);
+        let batch = if events.data.is_empty() {
+            batch
+        } else {
+            batch.delete_range(
+                &self.tables.event_order,
+                &(sequence, usize::MIN),
+                &events_end,
+            )?
+        };
+        let batch = batch.delete_batch(
+            &self.tables.event_by_move_module,
+            events.data.iter().enumerate().map(|(i, e)| {
+                (
+                    ModuleId::new(e.package_id.into(), e.transaction_module.clone()),
+                    (sequence, i),
+                )
+            }),
+        )?;
+        let batch = batch.delete_batch(
+            &self.tables.event_by_sender,
+            events
+                .data
+                .iter()
+                .enumerate()
+                .map(|(i, e)| (e.sender, (sequence, i))),
+        )?;
+        let batch = batch.delete_batch(
+            &self.tables.event_by_move_event,
+            events
+                .data
+                .iter()
+                .enumerate()
+                .map(|(i, e)| (e.type_.clone(), (sequence, i))),
+        )?;
+        let batch = match timestamp_ms {
+            Some(timestamp_ms) if !events.data.is_empty() => batch.delete_range(
+                &self.tables.event_by_time,
+                &(timestamp_ms, (sequence, usize::MIN)),
+                &(timestamp_ms, events_end),
+            )?,
+            _ => batch,
+        };
+
+        batch.write()?;
+        Ok(())
+    }
+
     pub fn next_sequence_number(&self) -> TxSequenceNumber {
         self.next_sequence_number.load(Ordering::SeqCst) + 1
     }
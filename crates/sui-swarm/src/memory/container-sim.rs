@@ -90,6 +90,12 @@ impl Container {
         Some(SuiNodeHandle::new(self.node_watch.borrow().upgrade()?))
     }
 
+    /// The id of the simulated node running the Node. A new id is assigned every time the Node is
+    /// restarted.
+    pub fn sim_node_id(&self) -> Option<sui_simulator::task::NodeId> {
+        self.handle.as_ref().map(|handle| handle.node_id)
+    }
+
     /// Check to see that the Node is still alive by checking if the receiving side of the
     /// `cancel_sender` has been dropped.
     ///
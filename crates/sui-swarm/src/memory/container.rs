@@ -77,7 +77,10 @@ impl Container {
             let runtime = builder.enable_all().build().unwrap();
 
             runtime.block_on(async move {
-                let registry_service = metrics::start_prometheus_server(config.metrics_address);
+                let registry_service = metrics::start_prometheus_server_with_auth(
+                    config.metrics_address,
+                    config.metrics_auth.as_ref(),
+                );
                 info!(
                     "Started Prometheus HTTP endpoint. To query metrics use\n\tcurl -s http://{}/metrics",
                     config.metrics_address
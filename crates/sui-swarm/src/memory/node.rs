@@ -85,6 +85,17 @@ impl Node {
             .and_then(|c| c.get_node_handle())
     }
 
+    /// The id of the simulated node this Node currently runs on, used to script network faults
+    /// against it. Changes when the Node is restarted.
+    #[cfg(msim)]
+    pub fn sim_node_id(&self) -> Option<sui_simulator::task::NodeId> {
+        self.container
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|c| c.sim_node_id())
+    }
+
     /// Perform a health check on this Node by:
     /// * Checking that the node is running
     /// * Calling the Node's gRPC Health service if it's a validator.
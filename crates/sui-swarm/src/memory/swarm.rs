@@ -2,12 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::Node;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use futures::future::try_join_all;
 use rand::rngs::OsRng;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
+use std::time::Duration;
 use std::{
     mem, ops,
     path::{Path, PathBuf},
@@ -18,8 +19,10 @@ use sui_config::builder::{
 use sui_config::genesis_config::{GenesisConfig, ValidatorConfigInfo};
 use sui_config::node::DBCheckpointConfig;
 use sui_config::NetworkConfig;
+use sui_node::SuiNodeHandle;
 use sui_protocol_config::{ProtocolVersion, SupportedProtocolVersions};
 use sui_types::base_types::AuthorityName;
+use sui_types::committee::EpochId;
 use sui_types::object::Object;
 use tempfile::TempDir;
 
@@ -326,6 +329,37 @@ impl Swarm {
     pub fn fullnodes(&self) -> impl Iterator<Item = &Node> {
         self.fullnodes.values()
     }
+
+    /// Has every Validator vote to close the current epoch without waiting for the end of its
+    /// duration, and waits until they all reached the next epoch, which is returned.
+    pub async fn advance_epoch(&self) -> Result<EpochId> {
+        let handles = self
+            .validators()
+            .map(|node| {
+                node.get_node_handle()
+                    .ok_or_else(|| anyhow!("Validator {} is not running", node.name().concise()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let current_epoch = |handle: &SuiNodeHandle| {
+            handle.with(|node| node.state().load_epoch_store_one_call_per_task().epoch())
+        };
+        let epoch = handles.iter().map(current_epoch).min().unwrap_or_default();
+
+        for handle in &handles {
+            handle
+                .with_async(|node| async move {
+                    let epoch_store = node.state().load_epoch_store_one_call_per_task();
+                    node.close_epoch(&epoch_store).await
+                })
+                .await?;
+        }
+
+        let mut interval = tokio::time::interval(Duration::from_millis(100));
+        while handles.iter().any(|handle| current_epoch(handle) <= epoch) {
+            interval.tick().await;
+        }
+        Ok(epoch + 1)
+    }
 }
 
 #[derive(Debug)]
@@ -3,13 +3,24 @@
 
 use crate::{
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
-    get_object, get_transaction, make_clients, restore_from_db_checkpoint, ConciseObjectOutput,
-    GroupedObjectOutput, VerboseObjectOutput,
+    db_verify::verify_db,
+    epoch_report::{self, load_end_of_epoch_reports},
+    gas_market_sim::{
+        load_epoch_history, simulate, GasMarketSimConfig, RgpUpdateRule, TipMechanism,
+    },
+    get_object, get_transaction,
+    inclusion_proof::transaction_inclusion_proof_from_db,
+    make_clients,
+    package_dump::{dump_packages_from_archive, dump_packages_from_db},
+    protocol_config::{execute_protocol_config_command, ProtocolConfigCommand},
+    replay::replay_transaction,
+    restore_from_db_checkpoint, ConciseObjectOutput, GroupedObjectOutput, VerboseObjectOutput,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::path::PathBuf;
 use sui_config::genesis::Genesis;
 use sui_core::authority_client::AuthorityAPI;
+use sui_protocol_config::ProtocolVersion;
 
 use sui_types::{base_types::*, object::Owner};
 
@@ -94,6 +105,10 @@ pub enum ToolCommand {
         /// Path of the DB to read
         #[clap(long = "db-path")]
         db_path: String,
+        /// Directory for the RocksDB secondary instance through which the DB is read, which can
+        /// be in use by a running node. Defaults to a new temporary directory
+        #[clap(long = "secondary-path")]
+        secondary_path: Option<PathBuf>,
         #[clap(subcommand)]
         cmd: Option<DbToolCommand>,
     },
@@ -139,6 +154,121 @@ pub enum ToolCommand {
         #[clap(long = "db-checkpoint-path")]
         db_checkpoint_path: PathBuf,
     },
+
+    /// Replay historical epochs from a node db under alternative gas pricing rules and report
+    /// fee revenue and inclusion latency distributions.
+    #[clap(name = "simulate-gas-market")]
+    SimulateGasMarket {
+        /// Path of the node db, i.e. the `db-path` of the node config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        #[clap(long = "start-epoch", default_value = "0")]
+        start_epoch: EpochId,
+        #[clap(long = "end-epoch", default_value_t = EpochId::MAX)]
+        end_epoch: EpochId,
+        #[clap(value_enum, long = "rgp-rule", default_value = "historical")]
+        rgp_rule: RgpUpdateRule,
+        #[clap(value_enum, long = "tip-mechanism", default_value = "first-price")]
+        tip_mechanism: TipMechanism,
+        /// Computation units that fit into a single checkpoint
+        #[clap(long = "checkpoint-capacity", default_value = "10000000")]
+        checkpoint_capacity: u64,
+        /// Target utilization used by the `target-utilization` rule
+        #[clap(long = "target-utilization", default_value = "0.5")]
+        target_utilization: f64,
+        /// Maximum relative change of the RGP per epoch used by the `target-utilization` rule
+        #[clap(long = "max-rgp-change", default_value = "0.125")]
+        max_rgp_change: f64,
+        /// RGP of the first simulated epoch, defaults to the historical one
+        #[clap(long = "initial-rgp")]
+        initial_rgp: Option<u64>,
+    },
+
+    /// Decode the end-of-epoch checkpoints of a node db (advance_epoch arguments, safe mode,
+    /// storage fund flows and validator rewards) into a readable report, and optionally CSV.
+    #[clap(name = "analyze-end-of-epoch")]
+    AnalyzeEndOfEpoch {
+        /// Path of the node db, i.e. the `db-path` of the node config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        #[clap(long = "start-epoch", default_value = "0")]
+        start_epoch: EpochId,
+        #[clap(long = "end-epoch", default_value_t = EpochId::MAX)]
+        end_epoch: EpochId,
+        /// Directory to write `epochs.csv` and `validator_rewards.csv` to
+        #[clap(long = "csv-dir")]
+        csv_dir: Option<PathBuf>,
+    },
+
+    /// Re-execute a historical transaction against the state it originally executed on, read
+    /// from a fullnode or archival node db, and diff the resulting effects against the on-chain
+    /// effects.
+    #[clap(name = "replay")]
+    Replay {
+        /// Path of the node db, i.e. the `db-path` of the node config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        #[clap(long, help = "The digest of the transaction to replay")]
+        digest: TransactionDigest,
+        /// Replay under this protocol version instead of the one the transaction executed with,
+        /// e.g. to audit the effect of gas model changes
+        #[clap(long = "protocol-version")]
+        protocol_version: Option<u64>,
+    },
+
+    /// Recompute the digests of the objects, transactions, effects, events and checkpoints in a
+    /// node db, and report the entries that don't match their key or the other tables.
+    #[clap(name = "db-verify")]
+    DbVerify {
+        /// Path of the node db, i.e. the `db-path` of the node config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+    },
+
+    /// Export every Move package published up to a checkpoint, as bytecode, disassembled bytecode
+    /// and metadata (version, upgrade lineage, dependencies), for source verification.
+    #[clap(name = "dump-packages")]
+    DumpPackages {
+        /// Path of the node db, i.e. the `db-path` of the node config
+        #[clap(
+            long = "db-path",
+            conflicts_with = "archive_path",
+            required_unless_present = "archive_path"
+        )]
+        db_path: Option<PathBuf>,
+        /// Local directory of a checkpoint archive, to read the packages from instead of a node db
+        #[clap(long = "archive-path", requires = "checkpoint")]
+        archive_path: Option<PathBuf>,
+        /// Only export the packages published up to this checkpoint. Defaults to all the
+        /// checkpointed packages of the node db
+        #[clap(long = "checkpoint")]
+        checkpoint: Option<CheckpointSequenceNumber>,
+        /// Directory to write the packages to
+        #[clap(long = "output-dir")]
+        output_dir: PathBuf,
+    },
+
+    /// Build a proof, from the db of a node, that a transaction was executed and finalized by a
+    /// checkpoint, which light clients can verify without trusting the node.
+    #[clap(name = "transaction-inclusion-proof")]
+    TransactionInclusionProof {
+        /// Path of the node db, i.e. the `db-path` of the node config
+        #[clap(long = "db-path")]
+        db_path: PathBuf,
+        #[clap(long, help = "The digest of the transaction")]
+        digest: TransactionDigest,
+        /// File to write the BCS encoded proof to
+        #[clap(long = "output")]
+        output: PathBuf,
+    },
+
+    /// Show, diff and validate the protocol configs built into this binary, to see which limits
+    /// and feature flags change with a protocol upgrade.
+    #[clap(name = "protocol-config")]
+    ProtocolConfig {
+        #[clap(subcommand)]
+        cmd: ProtocolConfigCommand,
+    },
 }
 
 trait OptionDebug<T> {
@@ -227,10 +357,14 @@ impl ToolCommand {
             ToolCommand::FetchTransaction { genesis, digest } => {
                 print!("{}", get_transaction(digest, genesis).await?);
             }
-            ToolCommand::DbTool { db_path, cmd } => {
+            ToolCommand::DbTool {
+                db_path,
+                secondary_path,
+                cmd,
+            } => {
                 let path = PathBuf::from(db_path);
                 match cmd {
-                    Some(c) => execute_db_tool_command(path, c)?,
+                    Some(c) => execute_db_tool_command(path, secondary_path, c)?,
                     None => print_db_all_tables(path)?,
                 }
             }
@@ -290,6 +424,107 @@ impl ToolCommand {
                 let config = sui_config::NodeConfig::load(config_path)?;
                 restore_from_db_checkpoint(&config, &db_checkpoint_path).await?;
             }
+            ToolCommand::SimulateGasMarket {
+                db_path,
+                start_epoch,
+                end_epoch,
+                rgp_rule,
+                tip_mechanism,
+                checkpoint_capacity,
+                target_utilization,
+                max_rgp_change,
+                initial_rgp,
+            } => {
+                let history = load_epoch_history(&db_path, start_epoch, end_epoch)?;
+                let config = GasMarketSimConfig {
+                    rgp_rule,
+                    tip_mechanism,
+                    checkpoint_capacity,
+                    target_utilization,
+                    max_rgp_change,
+                    initial_rgp,
+                };
+                println!("{}", simulate(&history, &config));
+            }
+            ToolCommand::AnalyzeEndOfEpoch {
+                db_path,
+                start_epoch,
+                end_epoch,
+                csv_dir,
+            } => {
+                let reports = load_end_of_epoch_reports(&db_path, start_epoch, end_epoch)?;
+                for report in &reports {
+                    println!("{report}");
+                }
+                if let Some(csv_dir) = csv_dir {
+                    std::fs::create_dir_all(&csv_dir)?;
+                    epoch_report::write_csv(
+                        &reports,
+                        std::fs::File::create(csv_dir.join("epochs.csv"))?,
+                        std::fs::File::create(csv_dir.join("validator_rewards.csv"))?,
+                    )?;
+                }
+            }
+            ToolCommand::Replay {
+                db_path,
+                digest,
+                protocol_version,
+            } => {
+                let result = replay_transaction(
+                    &db_path,
+                    digest,
+                    protocol_version.map(ProtocolVersion::new),
+                )?;
+                println!("{result}");
+                // Effects are expected to change under another protocol version, but must match
+                // otherwise.
+                if !result.effects_match() && !result.protocol_version_overridden {
+                    return Err(anyhow!("Replay of transaction {digest} diverged"));
+                }
+            }
+            ToolCommand::DbVerify { db_path } => {
+                let report = verify_db(&db_path)?;
+                print!("{report}");
+                if report.corrupt() > 0 {
+                    return Err(anyhow!(
+                        "Found {} corrupt entries in {}",
+                        report.corrupt(),
+                        db_path.display()
+                    ));
+                }
+            }
+            ToolCommand::DumpPackages {
+                db_path,
+                archive_path,
+                checkpoint,
+                output_dir,
+            } => {
+                let index = match (db_path, archive_path, checkpoint) {
+                    (_, Some(archive_path), Some(checkpoint)) => {
+                        dump_packages_from_archive(&archive_path, checkpoint, &output_dir).await?
+                    }
+                    (Some(db_path), _, _) => {
+                        dump_packages_from_db(&db_path, checkpoint, &output_dir)?
+                    }
+                    _ => return Err(anyhow!("Either --db-path or --archive-path is required")),
+                };
+                print!("{index}");
+            }
+            ToolCommand::TransactionInclusionProof {
+                db_path,
+                digest,
+                output,
+            } => {
+                let proof = transaction_inclusion_proof_from_db(&db_path, digest)?;
+                std::fs::write(&output, bcs::to_bytes(&proof)?)?;
+                println!(
+                    "Wrote the proof of transaction {digest} in checkpoint {} of epoch {} to {}",
+                    proof.checkpoint.sequence_number,
+                    proof.epoch(),
+                    output.display()
+                );
+            }
+            ToolCommand::ProtocolConfig { cmd } => execute_protocol_config_command(cmd)?,
         };
         Ok(())
     }
@@ -3,6 +3,12 @@
 
 use anyhow::anyhow;
 use clap::{Parser, ValueEnum};
+use narwhal_config::{AuthorityIdentifier, WorkerId};
+use narwhal_storage::{NodeStorage, PayloadToken, ProposerKey};
+use narwhal_types::{
+    Batch, BatchDigest, Certificate, CertificateDigest, CommittedSubDagShell, Header, HeaderDigest,
+    Round, SequenceNumber, VoteInfo,
+};
 use rocksdb::MultiThreaded;
 use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
@@ -10,18 +16,25 @@ use strum_macros::EnumString;
 use sui_core::authority::authority_per_epoch_store::AuthorityEpochTables;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::authority_store_types::{StoreData, StoreObject};
+use sui_core::checkpoints::CheckpointStore;
 use sui_core::epoch::committee_store::CommitteeStoreTables;
 use sui_storage::IndexStoreTables;
 use sui_types::base_types::{EpochId, ObjectID};
-use typed_store::rocks::{default_db_options, MetricConf};
+use typed_store::rocks::{
+    default_db_options, int_properties, open_cf_secondary, DBMap, MetricConf, ReadWriteOptions,
+};
 use typed_store::traits::{Map, TableSummary};
 
+/// The stores of a node. All of them are opened as RocksDB secondary instances, which can read
+/// the stores while the node is running.
 #[derive(EnumString, Clone, Parser, Debug, ValueEnum)]
 pub enum StoreName {
     Validator,
     Index,
     Epoch,
-    // TODO: Add the new checkpoint v2 tables.
+    Checkpoint,
+    /// The Narwhal store of an epoch
+    Consensus,
 }
 impl std::fmt::Display for StoreName {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -46,10 +59,131 @@ pub fn list_tables(path: PathBuf) -> anyhow::Result<Vec<String>> {
         })
 }
 
+/// Reads the integer RocksDB properties of the tables of the DB at `db_path`, or of
+/// `table_name` only, by table.
+pub fn table_properties(
+    db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
+    table_name: Option<&str>,
+) -> anyhow::Result<BTreeMap<String, BTreeMap<String, i64>>> {
+    let tables = match table_name {
+        Some(table_name) => vec![table_name.to_owned()],
+        None => list_tables(db_path.clone())?,
+    };
+    let rocksdb = open_cf_secondary(&db_path, secondary_path, MetricConf::default())?;
+    tables
+        .into_iter()
+        .map(|table| {
+            let properties = int_properties(&rocksdb, &table)?;
+            Ok((table, properties))
+        })
+        .collect()
+}
+
+/// Evaluates `$body` with `$table` bound to the table `$table_name` of a consensus store.
+/// Narwhal opens its tables one by one rather than through a derived read-only handle, which
+/// would dispatch on the table name.
+macro_rules! with_consensus_table {
+    ($rocksdb:expr, $table_name:expr, |$table:ident| $body:expr) => {
+        match $table_name {
+            NodeStorage::LAST_PROPOSED_CF => {
+                let $table = consensus_table::<ProposerKey, Header>($rocksdb, $table_name)?;
+                $body
+            }
+            NodeStorage::VOTES_CF => {
+                let $table =
+                    consensus_table::<AuthorityIdentifier, VoteInfo>($rocksdb, $table_name)?;
+                $body
+            }
+            NodeStorage::HEADERS_CF => {
+                let $table = consensus_table::<HeaderDigest, Header>($rocksdb, $table_name)?;
+                $body
+            }
+            NodeStorage::CERTIFICATES_CF => {
+                let $table =
+                    consensus_table::<CertificateDigest, Certificate>($rocksdb, $table_name)?;
+                $body
+            }
+            NodeStorage::CERTIFICATE_DIGEST_BY_ROUND_CF => {
+                let $table = consensus_table::<(Round, AuthorityIdentifier), CertificateDigest>(
+                    $rocksdb,
+                    $table_name,
+                )?;
+                $body
+            }
+            NodeStorage::CERTIFICATE_DIGEST_BY_ORIGIN_CF => {
+                let $table = consensus_table::<(AuthorityIdentifier, Round), CertificateDigest>(
+                    $rocksdb,
+                    $table_name,
+                )?;
+                $body
+            }
+            NodeStorage::PAYLOAD_CF => {
+                let $table = consensus_table::<(BatchDigest, WorkerId), PayloadToken>(
+                    $rocksdb,
+                    $table_name,
+                )?;
+                $body
+            }
+            NodeStorage::BATCHES_CF => {
+                let $table = consensus_table::<BatchDigest, Batch>($rocksdb, $table_name)?;
+                $body
+            }
+            NodeStorage::LAST_COMMITTED_CF => {
+                let $table = consensus_table::<AuthorityIdentifier, Round>($rocksdb, $table_name)?;
+                $body
+            }
+            NodeStorage::SUB_DAG_INDEX_CF => {
+                let $table =
+                    consensus_table::<SequenceNumber, CommittedSubDagShell>($rocksdb, $table_name)?;
+                $body
+            }
+            _ => eyre::bail!("No such table name: {}", $table_name),
+        }
+    };
+}
+
+fn consensus_table<K, V>(
+    rocksdb: &std::sync::Arc<typed_store::rocks::RocksDB>,
+    table_name: &str,
+) -> eyre::Result<DBMap<K, V>> {
+    Ok(DBMap::reopen(
+        rocksdb,
+        Some(table_name),
+        &ReadWriteOptions::default(),
+    )?)
+}
+
+fn consensus_table_summary(
+    db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
+    table_name: &str,
+) -> eyre::Result<TableSummary> {
+    let rocksdb = open_cf_secondary(&db_path, secondary_path, MetricConf::default())?;
+    with_consensus_table!(&rocksdb, table_name, |table| table.table_summary())
+}
+
+fn dump_consensus_table(
+    db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
+    table_name: &str,
+    page_size: u16,
+    page_number: usize,
+) -> eyre::Result<BTreeMap<String, String>> {
+    let rocksdb = open_cf_secondary(&db_path, secondary_path, MetricConf::default())?;
+    with_consensus_table!(&rocksdb, table_name, |table| Ok(table
+        .iter()
+        .skip(page_number * (page_size as usize))
+        .take(page_size as usize)
+        .map(|(k, v)| (format!("{:?}", k), format!("{:?}", v)))
+        .collect()))
+}
+
 pub fn table_summary(
     store_name: StoreName,
     epoch: Option<EpochId>,
     db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
     table_name: &str,
 ) -> anyhow::Result<TableSummary> {
     match store_name {
@@ -57,25 +191,59 @@ pub fn table_summary(
             let epoch_tables = AuthorityEpochTables::describe_tables();
             if epoch_tables.contains_key(table_name) {
                 let epoch = epoch.ok_or_else(|| anyhow!("--epoch is required"))?;
-                AuthorityEpochTables::open_readonly(epoch, &db_path).table_summary(table_name)
-            } else {
-                AuthorityPerpetualTables::open_readonly(&db_path).table_summary(table_name)
-            }
-        }
-        StoreName::Index => {
-            IndexStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+                AuthorityEpochTables::get_read_only_handle(
+                    AuthorityEpochTables::path(epoch, &db_path),
+                    secondary_path,
+                    None,
+                    MetricConf::default(),
+                )
                 .table_summary(table_name)
-        }
-        StoreName::Epoch => {
-            CommitteeStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
+            } else {
+                AuthorityPerpetualTables::get_read_only_handle(
+                    AuthorityPerpetualTables::path(&db_path),
+                    secondary_path,
+                    None,
+                    MetricConf::default(),
+                )
                 .table_summary(table_name)
+            }
         }
+        StoreName::Index => IndexStoreTables::get_read_only_handle(
+            db_path,
+            secondary_path,
+            None,
+            MetricConf::default(),
+        )
+        .table_summary(table_name),
+        StoreName::Epoch => CommitteeStoreTables::get_read_only_handle(
+            db_path,
+            secondary_path,
+            None,
+            MetricConf::default(),
+        )
+        .table_summary(table_name),
+        StoreName::Checkpoint => CheckpointStore::get_read_only_handle(
+            db_path,
+            secondary_path,
+            None,
+            MetricConf::default(),
+        )
+        .table_summary(table_name),
+        StoreName::Consensus => consensus_table_summary(db_path, secondary_path, table_name),
     }
     .map_err(|err| anyhow!(err.to_string()))
 }
 
-pub fn duplicate_objects_summary(db_path: PathBuf) -> (usize, usize, usize, usize) {
-    let perpetual_tables = AuthorityPerpetualTables::open_readonly(&db_path);
+pub fn duplicate_objects_summary(
+    db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
+) -> (usize, usize, usize, usize) {
+    let perpetual_tables = AuthorityPerpetualTables::get_read_only_handle(
+        AuthorityPerpetualTables::path(&db_path),
+        secondary_path,
+        None,
+        MetricConf::default(),
+    );
     let iter = perpetual_tables.objects.iter();
     let mut total_count = 0;
     let mut duplicate_count = 0;
@@ -110,6 +278,7 @@ pub fn dump_table(
     store_name: StoreName,
     epoch: Option<EpochId>,
     db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
     table_name: &str,
     page_size: u16,
     page_number: usize,
@@ -119,29 +288,46 @@ pub fn dump_table(
             let epoch_tables = AuthorityEpochTables::describe_tables();
             if epoch_tables.contains_key(table_name) {
                 let epoch = epoch.ok_or_else(|| anyhow!("--epoch is required"))?;
-                AuthorityEpochTables::open_readonly(epoch, &db_path).dump(
-                    table_name,
-                    page_size,
-                    page_number,
+                AuthorityEpochTables::get_read_only_handle(
+                    AuthorityEpochTables::path(epoch, &db_path),
+                    secondary_path,
+                    None,
+                    MetricConf::default(),
                 )
+                .dump(table_name, page_size, page_number)
             } else {
-                AuthorityPerpetualTables::open_readonly(&db_path).dump(
-                    table_name,
-                    page_size,
-                    page_number,
+                AuthorityPerpetualTables::get_read_only_handle(
+                    AuthorityPerpetualTables::path(&db_path),
+                    secondary_path,
+                    None,
+                    MetricConf::default(),
                 )
+                .dump(table_name, page_size, page_number)
             }
         }
-        StoreName::Index => {
-            IndexStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default()).dump(
-                table_name,
-                page_size,
-                page_number,
-            )
-        }
-        StoreName::Epoch => {
-            CommitteeStoreTables::get_read_only_handle(db_path, None, None, MetricConf::default())
-                .dump(table_name, page_size, page_number)
+        StoreName::Index => IndexStoreTables::get_read_only_handle(
+            db_path,
+            secondary_path,
+            None,
+            MetricConf::default(),
+        )
+        .dump(table_name, page_size, page_number),
+        StoreName::Epoch => CommitteeStoreTables::get_read_only_handle(
+            db_path,
+            secondary_path,
+            None,
+            MetricConf::default(),
+        )
+        .dump(table_name, page_size, page_number),
+        StoreName::Checkpoint => CheckpointStore::get_read_only_handle(
+            db_path,
+            secondary_path,
+            None,
+            MetricConf::default(),
+        )
+        .dump(table_name, page_size, page_number),
+        StoreName::Consensus => {
+            dump_consensus_table(db_path, secondary_path, table_name, page_size, page_number)
         }
     }
     .map_err(|err| anyhow!(err.to_string()))
@@ -149,10 +335,52 @@ pub fn dump_table(
 
 #[cfg(test)]
 mod test {
+    use narwhal_storage::NodeStorage;
     use sui_core::authority::authority_per_epoch_store::AuthorityEpochTables;
     use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+    use sui_core::checkpoints::CheckpointStore;
+
+    use crate::db_tool::db_dump::{
+        dump_table, list_tables, table_properties, table_summary, StoreName,
+    };
 
-    use crate::db_tool::db_dump::{dump_table, list_tables, StoreName};
+    #[tokio::test]
+    async fn db_dump_while_open() -> Result<(), anyhow::Error> {
+        let checkpoints_path = tempfile::tempdir()?.into_path();
+        let consensus_path = tempfile::tempdir()?.into_path();
+
+        // Keep the stores open, as a running node does.
+        let _checkpoint_store = CheckpointStore::new(&checkpoints_path);
+        let _consensus_store = NodeStorage::reopen(&consensus_path);
+
+        for (store_name, path) in [
+            (StoreName::Checkpoint, checkpoints_path),
+            (StoreName::Consensus, consensus_path),
+        ] {
+            let tables = list_tables(path.clone())?;
+            assert!(!tables.is_empty());
+            for t in &tables {
+                dump_table(store_name.clone(), None, path.clone(), None, t, 10, 0)?;
+                table_summary(store_name.clone(), None, path.clone(), None, t)?;
+            }
+            assert!(dump_table(
+                store_name.clone(),
+                None,
+                path.clone(),
+                None,
+                "missing",
+                10,
+                0
+            )
+            .is_err());
+            assert_eq!(
+                table_properties(path, None, None)?.len(),
+                tables.len(),
+                "{store_name}"
+            );
+        }
+        Ok(())
+    }
 
     #[tokio::test]
     async fn db_dump_population() -> Result<(), anyhow::Error> {
@@ -179,6 +407,7 @@ mod test {
                 StoreName::Validator,
                 Some(0),
                 primary_path.clone(),
+                None,
                 &t,
                 0,
                 0,
@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use self::db_dump::{dump_table, duplicate_objects_summary, list_tables, table_summary, StoreName};
+use self::db_dump::{
+    dump_table, duplicate_objects_summary, list_tables, table_properties, table_summary, StoreName,
+};
 use clap::Parser;
 use std::path::PathBuf;
 use sui_types::base_types::EpochId;
@@ -14,6 +16,7 @@ pub enum DbToolCommand {
     ListTables,
     Dump(Dump),
     TableSummary(Dump),
+    TableProperties(TableProperties),
     DuplicatesSummary,
 }
 
@@ -41,21 +44,43 @@ pub struct Dump {
     epoch: Option<EpochId>,
 }
 
-pub fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> anyhow::Result<()> {
+/// Print the RocksDB properties of the tables of the DB, such as the estimated number of keys,
+/// the size of the SST files and the pending compactions
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct TableProperties {
+    /// Only print the properties of this table
+    #[clap(long = "table-name")]
+    table_name: Option<String>,
+}
+
+pub fn execute_db_tool_command(
+    db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
+    cmd: DbToolCommand,
+) -> anyhow::Result<()> {
     match cmd {
         DbToolCommand::ListTables => print_db_all_tables(db_path),
         DbToolCommand::Dump(d) => print_all_entries(
             d.store_name,
             d.epoch,
             db_path,
+            secondary_path,
             &d.table_name,
             d.page_size,
             d.page_number,
         ),
-        DbToolCommand::TableSummary(d) => {
-            print_db_table_summary(d.store_name, d.epoch, db_path, &d.table_name)
+        DbToolCommand::TableSummary(d) => print_db_table_summary(
+            d.store_name,
+            d.epoch,
+            db_path,
+            secondary_path,
+            &d.table_name,
+        ),
+        DbToolCommand::TableProperties(p) => {
+            print_db_table_properties(db_path, secondary_path, p.table_name.as_deref())
         }
-        DbToolCommand::DuplicatesSummary => print_db_duplicates_summary(db_path),
+        DbToolCommand::DuplicatesSummary => print_db_duplicates_summary(db_path, secondary_path),
     }
 }
 
@@ -64,9 +89,26 @@ pub fn print_db_all_tables(db_path: PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub fn print_db_duplicates_summary(db_path: PathBuf) -> anyhow::Result<()> {
+pub fn print_db_table_properties(
+    db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
+    table_name: Option<&str>,
+) -> anyhow::Result<()> {
+    for (table, properties) in table_properties(db_path, secondary_path, table_name)? {
+        println!("{}:", table);
+        for (property, value) in properties {
+            println!("  {} = {}", property, value);
+        }
+    }
+    Ok(())
+}
+
+pub fn print_db_duplicates_summary(
+    db_path: PathBuf,
+    secondary_path: Option<PathBuf>,
+) -> anyhow::Result<()> {
     let (total_count, duplicate_count, total_bytes, duplicated_bytes) =
-        duplicate_objects_summary(db_path);
+        duplicate_objects_summary(db_path, secondary_path);
     println!(
         "Total objects = {}, duplicated objects = {}, total bytes = {}, duplicated bytes = {}",
         total_count, duplicate_count, total_bytes, duplicated_bytes
@@ -78,9 +120,10 @@ pub fn print_db_table_summary(
     store: StoreName,
     epoch: Option<EpochId>,
     path: PathBuf,
+    secondary_path: Option<PathBuf>,
     table_name: &str,
 ) -> anyhow::Result<()> {
-    let summary = table_summary(store, epoch, path, table_name)?;
+    let summary = table_summary(store, epoch, path, secondary_path, table_name)?;
     let quantiles = vec![25, 50, 75, 90, 99];
     println!(
         "Total num keys = {}, total key bytes = {}, total value bytes = {}",
@@ -109,11 +152,20 @@ pub fn print_all_entries(
     store: StoreName,
     epoch: Option<EpochId>,
     path: PathBuf,
+    secondary_path: Option<PathBuf>,
     table_name: &str,
     page_size: u16,
     page_number: usize,
 ) -> anyhow::Result<()> {
-    for (k, v) in dump_table(store, epoch, path, table_name, page_size, page_number)? {
+    for (k, v) in dump_table(
+        store,
+        epoch,
+        path,
+        secondary_path,
+        table_name,
+        page_size,
+        page_number,
+    )? {
         println!("{:>100?}: {:?}", k, v);
     }
     Ok(())
@@ -0,0 +1,568 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Integrity verification of the database of a node.
+//!
+//! Most values of the authority stores are either keyed by their digest or referenced by digest
+//! from another table. The verification walks the tables, recomputes these digests and reports
+//! every entry that doesn't match, with its table and key so that it can be located:
+//! - `objects` entries reconstruct into the object of their key, with the digest recorded by the
+//!   effects that wrote them and by the locks on owned objects,
+//! - `indirect_move_objects`, `transactions`, `effects` and `events` hash to their key,
+//! - executed effects belong to their transaction,
+//! - certified checkpoints are stored under their sequence number and digest and chain to the
+//!   previous checkpoint, and checkpoint contents hash to their key and list the effects the
+//!   node executed for their transactions.
+//!
+//! History pruned by the node is not reported missing. Each table is walked on its own thread.
+
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use sui_core::authority::authority_store_tables::{
+    AuthorityPerpetualTables, AuthorityPerpetualTablesReadOnly,
+};
+use sui_core::authority::checkpoint_history_pruner::PrunedHistory;
+use sui_core::checkpoints::{CheckpointStore, CheckpointStoreReadOnly};
+use sui_types::digests::{CheckpointDigest, TransactionEventsDigest};
+use sui_types::error::SuiResult;
+use sui_types::event::Event;
+use sui_types::message_envelope::Message;
+use sui_types::messages::{TransactionEffectsAPI, TransactionEvents};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::storage::ObjectKey;
+use typed_store::rocks::DBMap;
+use typed_store::traits::Map;
+
+/// Number of corruptions listed per table, beyond which they are only counted.
+const MAX_LISTED_CORRUPTIONS: usize = 1000;
+
+/// An entry failing verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corruption {
+    pub table: &'static str,
+    pub key: String,
+    pub detail: String,
+}
+
+impl Display for Corruption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]: {}", self.table, self.key, self.detail)
+    }
+}
+
+#[derive(Debug)]
+pub struct TableReport {
+    pub table: &'static str,
+    pub checked: u64,
+    pub corrupt: u64,
+    /// The first `MAX_LISTED_CORRUPTIONS` corruptions found in the table.
+    pub corruptions: Vec<Corruption>,
+}
+
+impl TableReport {
+    fn new(table: &'static str) -> Self {
+        Self {
+            table,
+            checked: 0,
+            corrupt: 0,
+            corruptions: vec![],
+        }
+    }
+
+    fn corrupt(&mut self, key: impl Debug, detail: impl Display) {
+        self.push(format!("{key:?}"), detail.to_string());
+    }
+
+    fn push(&mut self, key: String, detail: String) {
+        self.corrupt += 1;
+        if self.corruptions.len() < MAX_LISTED_CORRUPTIONS {
+            self.corruptions.push(Corruption {
+                table: self.table,
+                key,
+                detail,
+            });
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct VerificationReport {
+    pub tables: Vec<TableReport>,
+}
+
+impl VerificationReport {
+    pub fn corrupt(&self) -> u64 {
+        self.tables.iter().map(|table| table.corrupt).sum()
+    }
+
+    pub fn corruptions(&self) -> impl Iterator<Item = &Corruption> {
+        self.tables.iter().flat_map(|table| &table.corruptions)
+    }
+}
+
+impl Display for VerificationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for table in &self.tables {
+            writeln!(
+                f,
+                "{}: {} entries checked, {} corrupt",
+                table.table, table.checked, table.corrupt
+            )?;
+            for corruption in &table.corruptions {
+                writeln!(f, "  {corruption}")?;
+            }
+            if table.corrupt > table.corruptions.len() as u64 {
+                writeln!(
+                    f,
+                    "  ... and {} more",
+                    table.corrupt - table.corruptions.len() as u64
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Verifies the stores of the node db at `db_path`, i.e. the `db-path` of the node config.
+pub fn verify_db(db_path: &Path) -> anyhow::Result<VerificationReport> {
+    let tables = AuthorityPerpetualTables::open_readonly(&db_path.join("store"));
+    let checkpoint_store = CheckpointStore::open_readonly(&db_path.join("checkpoints"));
+    let pruned_effects = tables.pruned_history.get(&PrunedHistory::Effects)?;
+    let pruned_contents = tables
+        .pruned_history
+        .get(&PrunedHistory::CheckpointContents)?;
+
+    let reports = std::thread::scope(|s| {
+        let tables = &tables;
+        let checkpoint_store = &checkpoint_store;
+        let handles = vec![
+            s.spawn(move || verify_objects(tables)),
+            s.spawn(move || verify_indirect_move_objects(tables)),
+            s.spawn(move || verify_owned_object_locks(tables)),
+            s.spawn(move || verify_transactions(tables)),
+            s.spawn(move || verify_effects(tables)),
+            s.spawn(move || verify_executed_effects(tables, pruned_effects)),
+            s.spawn(move || verify_events(tables)),
+            s.spawn(move || verify_certified_checkpoints(checkpoint_store, pruned_contents)),
+            s.spawn(move || verify_checkpoint_by_digest(checkpoint_store)),
+            s.spawn(move || verify_checkpoint_contents(tables, checkpoint_store)),
+        ];
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("verification thread panicked"))
+            .collect()
+    });
+    Ok(VerificationReport { tables: reports })
+}
+
+/// Runs `check` on every entry of `table`. Entries that can't be read, or whose check fails to
+/// read another table, are reported corrupt.
+fn verify_table<K, V>(
+    name: &'static str,
+    table: &DBMap<K, V>,
+    mut check: impl FnMut(&mut TableReport, &K, V) -> SuiResult,
+) -> TableReport
+where
+    K: DeserializeOwned + Debug,
+    V: DeserializeOwned,
+{
+    let mut report = TableReport::new(name);
+    for entry in table.checked_iter() {
+        report.checked += 1;
+        match entry {
+            Ok((key, value)) => {
+                if let Err(e) = check(&mut report, &key, value) {
+                    report.corrupt(&key, format!("failed to verify: {e}"));
+                }
+            }
+            Err(e) => report.push("?".to_string(), format!("failed to read: {e}")),
+        }
+    }
+    report
+}
+
+fn verify_objects(tables: &AuthorityPerpetualTablesReadOnly) -> TableReport {
+    verify_table("objects", &tables.objects, |report, key, store_object| {
+        if let Some(object) = tables.object(store_object)? {
+            if (object.id(), object.version()) != (key.0, key.1) {
+                report.corrupt(
+                    key,
+                    format!(
+                        "stores object {} at version {}",
+                        object.id(),
+                        object.version()
+                    ),
+                );
+            }
+        }
+        Ok(())
+    })
+}
+
+fn verify_indirect_move_objects(tables: &AuthorityPerpetualTablesReadOnly) -> TableReport {
+    verify_table(
+        "indirect_move_objects",
+        &tables.indirect_move_objects,
+        |report, digest, object| {
+            let actual = object.migrate().into_inner().content_digest();
+            if actual != *digest {
+                report.corrupt(digest, format!("contents hash to {actual:?}"));
+            }
+            Ok(())
+        },
+    )
+}
+
+fn verify_owned_object_locks(tables: &AuthorityPerpetualTablesReadOnly) -> TableReport {
+    verify_table(
+        "owned_object_transaction_locks",
+        &tables.owned_object_transaction_locks,
+        |report, object_ref, _| {
+            match tables.get_object_by_key(&object_ref.0, object_ref.1)? {
+                Some(object) if object.digest() != object_ref.2 => {
+                    report.corrupt(object_ref, format!("object has digest {}", object.digest()))
+                }
+                Some(_) => (),
+                None => report.corrupt(object_ref, "locks a missing object"),
+            }
+            Ok(())
+        },
+    )
+}
+
+fn verify_transactions(tables: &AuthorityPerpetualTablesReadOnly) -> TableReport {
+    verify_table(
+        "transactions",
+        &tables.transactions,
+        |report, digest, transaction| {
+            let actual = transaction.inner().digest();
+            if actual != digest {
+                report.corrupt(digest, format!("transaction hashes to {actual}"));
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Besides the digest of the effects, checks the digest of the objects they wrote that were not
+/// pruned since.
+fn verify_effects(tables: &AuthorityPerpetualTablesReadOnly) -> TableReport {
+    verify_table("effects", &tables.effects, |report, digest, effects| {
+        let actual = effects.digest();
+        if actual != *digest {
+            report.corrupt(digest, format!("effects hash to {actual}"));
+        }
+        for (object_ref, _, _) in effects.all_changed_objects() {
+            if !object_ref.2.is_alive() {
+                continue;
+            }
+            let Some(object) = tables.get_object_by_key(&object_ref.0, object_ref.1)? else {
+                continue;
+            };
+            if object.digest() != object_ref.2 {
+                report.corrupt(
+                    digest,
+                    format!(
+                        "wrote {object_ref:?}, but {:?} has digest {}",
+                        ObjectKey(object_ref.0, object_ref.1),
+                        object.digest()
+                    ),
+                );
+            }
+        }
+        Ok(())
+    })
+}
+
+fn verify_executed_effects(
+    tables: &AuthorityPerpetualTablesReadOnly,
+    pruned_effects: Option<CheckpointSequenceNumber>,
+) -> TableReport {
+    verify_table(
+        "executed_effects",
+        &tables.executed_effects,
+        |report, transaction_digest, effects_digest| {
+            match tables.effects.get(&effects_digest)? {
+                Some(effects) if effects.transaction_digest() != transaction_digest => report
+                    .corrupt(
+                        transaction_digest,
+                        format!(
+                            "effects {effects_digest} are of transaction {}",
+                            effects.transaction_digest()
+                        ),
+                    ),
+                Some(_) => (),
+                None => {
+                    let checkpoint = tables
+                        .executed_transactions_to_checkpoint
+                        .get(transaction_digest)?
+                        .map(|(_, checkpoint)| checkpoint);
+                    let pruned = matches!(
+                        (checkpoint, pruned_effects),
+                        (Some(checkpoint), Some(pruned)) if checkpoint <= pruned
+                    );
+                    if !pruned {
+                        report.corrupt(
+                            transaction_digest,
+                            format!("effects {effects_digest} are missing"),
+                        );
+                    }
+                }
+            }
+            Ok(())
+        },
+    )
+}
+
+/// The events of a transaction are stored under their digest with consecutive indexes.
+fn verify_events(tables: &AuthorityPerpetualTablesReadOnly) -> TableReport {
+    fn verify_group(report: &mut TableReport, digest: TransactionEventsDigest, data: Vec<Event>) {
+        let actual = TransactionEvents { data }.digest();
+        if actual != digest {
+            report.corrupt(digest, format!("events hash to {actual:?}"));
+        }
+    }
+
+    let mut group: Option<(TransactionEventsDigest, Vec<Event>)> = None;
+    let mut report = verify_table(
+        "events",
+        &tables.events,
+        |report, (digest, index), event| {
+            match &mut group {
+                Some((group_digest, data)) if group_digest == digest => {
+                    if *index != data.len() {
+                        report.corrupt(
+                            (digest, index),
+                            format!("expected event index {}", data.len()),
+                        );
+                    }
+                    data.push(event);
+                }
+                _ => {
+                    if let Some((group_digest, data)) = group.take() {
+                        verify_group(report, group_digest, data);
+                    }
+                    if *index != 0 {
+                        report.corrupt((digest, index), "expected event index 0");
+                    }
+                    group = Some((*digest, vec![event]));
+                }
+            }
+            Ok(())
+        },
+    );
+    if let Some((digest, data)) = group {
+        verify_group(&mut report, digest, data);
+    }
+    report
+}
+
+fn verify_certified_checkpoints(
+    checkpoint_store: &CheckpointStoreReadOnly,
+    pruned_contents: Option<CheckpointSequenceNumber>,
+) -> TableReport {
+    let mut previous: Option<(CheckpointSequenceNumber, CheckpointDigest)> = None;
+    verify_table(
+        "certified_checkpoints",
+        &checkpoint_store.certified_checkpoints,
+        |report, sequence_number, checkpoint| {
+            let summary = checkpoint.inner().data();
+            let digest = *checkpoint.inner().digest();
+            if summary.sequence_number != *sequence_number {
+                report.corrupt(
+                    sequence_number,
+                    format!("stores checkpoint {}", summary.sequence_number),
+                );
+            }
+            if let Some((previous_number, previous_digest)) = previous {
+                if previous_number + 1 == *sequence_number
+                    && summary.previous_digest != Some(previous_digest)
+                {
+                    report.corrupt(
+                        sequence_number,
+                        format!(
+                            "previous digest {:?} is not the digest {previous_digest} of \
+                             checkpoint {previous_number}",
+                            summary.previous_digest
+                        ),
+                    );
+                }
+            }
+            previous = Some((*sequence_number, digest));
+
+            if !checkpoint_store
+                .checkpoint_by_digest
+                .contains_key(&digest)?
+            {
+                report.corrupt(
+                    sequence_number,
+                    format!("checkpoint {digest} is missing from checkpoint_by_digest"),
+                );
+            }
+            let pruned = pruned_contents.map_or(false, |pruned| *sequence_number <= pruned);
+            if !pruned
+                && !checkpoint_store
+                    .checkpoint_content
+                    .contains_key(&summary.content_digest)?
+            {
+                report.corrupt(
+                    sequence_number,
+                    format!("contents {} are missing", summary.content_digest),
+                );
+            }
+            Ok(())
+        },
+    )
+}
+
+fn verify_checkpoint_by_digest(checkpoint_store: &CheckpointStoreReadOnly) -> TableReport {
+    verify_table(
+        "checkpoint_by_digest",
+        &checkpoint_store.checkpoint_by_digest,
+        |report, digest, checkpoint| {
+            let actual = checkpoint.inner().digest();
+            if actual != digest {
+                report.corrupt(digest, format!("checkpoint hashes to {actual}"));
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Contents of checkpoints not executed yet list transactions without executed effects, which
+/// are not reported.
+fn verify_checkpoint_contents(
+    tables: &AuthorityPerpetualTablesReadOnly,
+    checkpoint_store: &CheckpointStoreReadOnly,
+) -> TableReport {
+    verify_table(
+        "checkpoint_content",
+        &checkpoint_store.checkpoint_content,
+        |report, digest, contents| {
+            let actual = contents.digest();
+            if actual != digest {
+                report.corrupt(digest, format!("contents hash to {actual}"));
+            }
+            for digests in contents.iter() {
+                match tables.executed_effects.get(&digests.transaction)? {
+                    Some(effects) if effects != digests.effects => report.corrupt(
+                        digest,
+                        format!(
+                            "lists effects {} of transaction {}, which executed with effects \
+                             {effects}",
+                            digests.effects, digests.transaction
+                        ),
+                    ),
+                    _ => (),
+                }
+            }
+            Ok(())
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::ident_str;
+    use move_core_types::language_storage::StructTag;
+    use std::time::Duration;
+    use sui_types::base_types::SuiAddress;
+    use sui_types::digests::TransactionEffectsDigest;
+    use sui_types::messages::TransactionEffects;
+    use sui_types::SUI_FRAMEWORK_ADDRESS;
+    use typed_store::rocks::{MetricConf, ReadWriteOptions};
+
+    fn event(contents: Vec<u8>) -> Event {
+        let module = ident_str!("test");
+        Event::new(
+            &SUI_FRAMEWORK_ADDRESS,
+            module,
+            SuiAddress::default(),
+            StructTag {
+                address: SUI_FRAMEWORK_ADDRESS,
+                module: module.to_owned(),
+                name: ident_str!("TestEvent").to_owned(),
+                type_params: vec![],
+            },
+            contents,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_verify_db() -> anyhow::Result<()> {
+        let db_path = tempfile::tempdir()?;
+        let store_path = db_path.path().join("store");
+        drop(AuthorityPerpetualTables::open(&store_path, None));
+        drop(CheckpointStore::new(&db_path.path().join("checkpoints")));
+        // Let the metrics tasks of the tables release the db.
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        // Write to the tables directly, as the node would never write corrupt entries.
+        let cf_names = AuthorityPerpetualTables::describe_tables();
+        let cfs: Vec<&str> = cf_names.keys().map(|x| x.as_str()).collect();
+        let rocksdb = typed_store::rocks::open_cf(
+            AuthorityPerpetualTables::path(&store_path),
+            None,
+            MetricConf::default(),
+            &cfs,
+        )?;
+        let effects_table = DBMap::<TransactionEffectsDigest, TransactionEffects>::reopen(
+            &rocksdb,
+            Some("effects"),
+            &ReadWriteOptions::default(),
+        )?;
+        let events_table = DBMap::<(TransactionEventsDigest, usize), Event>::reopen(
+            &rocksdb,
+            Some("events"),
+            &ReadWriteOptions::default(),
+        )?;
+
+        let effects = TransactionEffects::default();
+        let misplaced_effects = TransactionEffects::default();
+        let wrong_effects_digest = TransactionEffectsDigest::random();
+        effects_table.insert(&effects.digest(), &effects)?;
+        effects_table.insert(&wrong_effects_digest, &misplaced_effects)?;
+
+        let events = TransactionEvents {
+            data: vec![event(vec![1]), event(vec![2])],
+        };
+        let tampered_events = TransactionEvents {
+            data: vec![event(vec![3]), event(vec![4])],
+        };
+        let tampered_digest = tampered_events.digest();
+        for (digest, data) in [
+            (events.digest(), events.data),
+            (tampered_digest, vec![event(vec![3]), event(vec![5])]),
+        ] {
+            for (index, event) in data.into_iter().enumerate() {
+                events_table.insert(&(digest, index), &event)?;
+            }
+        }
+        drop((effects_table, events_table, rocksdb));
+
+        let report = verify_db(db_path.path())?;
+        let table = |name| {
+            report
+                .tables
+                .iter()
+                .find(|table| table.table == name)
+                .unwrap()
+        };
+        assert_eq!(table("effects").checked, 2);
+        assert_eq!(table("events").checked, 4);
+        assert_eq!(report.corrupt(), 2);
+        let corruptions: Vec<_> = report.corruptions().collect();
+        assert_eq!(corruptions[0].table, "effects");
+        assert_eq!(corruptions[0].key, format!("{wrong_effects_digest:?}"));
+        assert_eq!(
+            corruptions[0].detail,
+            format!("effects hash to {}", misplaced_effects.digest())
+        );
+        assert_eq!(corruptions[1].table, "events");
+        assert_eq!(corruptions[1].key, format!("{tampered_digest:?}"));
+        Ok(())
+    }
+}
@@ -0,0 +1,539 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decoding of end-of-epoch checkpoints.
+//!
+//! The last checkpoint of every epoch contains the `ChangeEpoch` system transaction, whose
+//! arguments and emitted events record the gas fees collected during the epoch, the storage fund
+//! flows and the rewards paid to every validator. This module reads them from a node's database
+//! and turns them into a human-readable report and CSV files.
+
+use anyhow::anyhow;
+use move_core_types::ident_str;
+use move_core_types::identifier::IdentStr;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_types::base_types::{EpochId, SequenceNumber, SuiAddress};
+use sui_types::event::Event;
+use sui_types::messages::{TransactionDataAPI, TransactionEffectsAPI, TransactionKind};
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, CheckpointTimestamp};
+use sui_types::sui_system_state::PoolTokenExchangeRate;
+use sui_types::SUI_SYSTEM_ADDRESS;
+use typed_store::traits::Map;
+
+const SUI_SYSTEM_STATE_INNER_MODULE: &IdentStr = ident_str!("sui_system_state_inner");
+const SYSTEM_EPOCH_INFO_EVENT: &IdentStr = ident_str!("SystemEpochInfoEvent");
+const VALIDATOR_SET_MODULE: &IdentStr = ident_str!("validator_set");
+const VALIDATOR_EPOCH_INFO_EVENT: &IdentStr = ident_str!("ValidatorEpochInfoEvent");
+
+/// Rust mirror of `sui_system::sui_system_state_inner::SystemEpochInfoEvent`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SystemEpochInfo {
+    pub epoch: u64,
+    pub protocol_version: u64,
+    pub reference_gas_price: u64,
+    pub total_stake: u64,
+    pub storage_fund_reinvestment: u64,
+    pub storage_charge: u64,
+    pub storage_rebate: u64,
+    pub storage_fund_balance: u64,
+    pub stake_subsidy_amount: u64,
+    pub total_gas_fees: u64,
+    pub total_stake_rewards_distributed: u64,
+    pub leftover_storage_fund_inflow: u64,
+}
+
+impl SystemEpochInfo {
+    /// Net change of the storage fund over the epoch: storage charges, reinvested rewards and
+    /// leftover inflows come in, storage rebates go out.
+    pub fn storage_fund_net_inflow(&self) -> i64 {
+        self.storage_charge as i64
+            + self.storage_fund_reinvestment as i64
+            + self.leftover_storage_fund_inflow as i64
+            - self.storage_rebate as i64
+    }
+}
+
+/// Rust mirror of `sui_system::validator_set::ValidatorEpochInfoEvent`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ValidatorEpochInfo {
+    pub epoch: u64,
+    pub validator_address: SuiAddress,
+    pub reference_gas_survey_quote: u64,
+    pub stake: u64,
+    pub commission_rate: u64,
+    pub pool_staking_reward: u64,
+    pub storage_fund_staking_reward: u64,
+    pub pool_token_exchange_rate: PoolTokenExchangeRate,
+    pub tallying_rule_reporters: Vec<SuiAddress>,
+    pub tallying_rule_global_score: u64,
+}
+
+impl ValidatorEpochInfo {
+    pub fn total_reward(&self) -> u64 {
+        self.pool_staking_reward + self.storage_fund_staking_reward
+    }
+}
+
+/// The arguments of the `ChangeEpoch` transaction, without the system package bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdvanceEpochArgs {
+    pub next_epoch: EpochId,
+    pub protocol_version: u64,
+    pub storage_charge: u64,
+    pub computation_charge: u64,
+    pub storage_rebate: u64,
+    pub epoch_start_timestamp_ms: u64,
+    /// Versions of the system packages written before the new epoch starts.
+    pub system_packages: Vec<SequenceNumber>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EndOfEpochReport {
+    pub epoch: EpochId,
+    pub checkpoint: CheckpointSequenceNumber,
+    pub timestamp_ms: CheckpointTimestamp,
+    pub next_epoch_protocol_version: u64,
+    pub next_epoch_committee_size: usize,
+    pub next_epoch_total_stake: u64,
+    pub advance_epoch: AdvanceEpochArgs,
+    /// `None` if the epoch change fell back to safe mode, which doesn't emit any event.
+    pub system_epoch_info: Option<SystemEpochInfo>,
+    pub validator_rewards: Vec<ValidatorEpochInfo>,
+}
+
+impl EndOfEpochReport {
+    pub fn safe_mode(&self) -> bool {
+        self.system_epoch_info.is_none()
+    }
+}
+
+/// Extracts the system and per-validator epoch info events emitted by `advance_epoch`.
+pub fn decode_epoch_events(
+    events: &[Event],
+) -> anyhow::Result<(Option<SystemEpochInfo>, Vec<ValidatorEpochInfo>)> {
+    let mut system_epoch_info = None;
+    let mut validator_rewards = vec![];
+    for event in events {
+        if event.type_.address != SUI_SYSTEM_ADDRESS {
+            continue;
+        }
+        let module = event.type_.module.as_ident_str();
+        let name = event.type_.name.as_ident_str();
+        if module == SUI_SYSTEM_STATE_INNER_MODULE && name == SYSTEM_EPOCH_INFO_EVENT {
+            system_epoch_info = Some(bcs::from_bytes(&event.contents)?);
+        } else if module == VALIDATOR_SET_MODULE && name == VALIDATOR_EPOCH_INFO_EVENT {
+            validator_rewards.push(bcs::from_bytes(&event.contents)?);
+        }
+    }
+    Ok((system_epoch_info, validator_rewards))
+}
+
+/// Loads the reports of all end-of-epoch checkpoints in `[start_epoch, end_epoch]` from the
+/// database at `db_path`.
+pub fn load_end_of_epoch_reports(
+    db_path: &Path,
+    start_epoch: EpochId,
+    end_epoch: EpochId,
+) -> anyhow::Result<Vec<EndOfEpochReport>> {
+    let checkpoint_store = CheckpointStore::open_readonly(&db_path.join("checkpoints"));
+    let perpetual_tables = AuthorityPerpetualTables::open_readonly(&db_path.join("store"));
+
+    let mut reports = vec![];
+    for (_, checkpoint) in checkpoint_store.certified_checkpoints.iter() {
+        let summary = checkpoint.inner().data();
+        if summary.epoch < start_epoch {
+            continue;
+        }
+        if summary.epoch > end_epoch {
+            break;
+        }
+        let Some(end_of_epoch_data) = &summary.end_of_epoch_data else {
+            continue;
+        };
+        let contents = checkpoint_store
+            .checkpoint_content
+            .get(&summary.content_digest)?
+            .ok_or_else(|| {
+                anyhow!(
+                    "Missing contents for checkpoint {}",
+                    summary.sequence_number
+                )
+            })?;
+
+        let mut change_epoch = None;
+        for digests in contents.iter() {
+            let Some(transaction) = perpetual_tables.transactions.get(&digests.transaction)? else {
+                return Err(anyhow!("Missing transaction {:?}", digests.transaction));
+            };
+            if let TransactionKind::ChangeEpoch(args) =
+                transaction.inner().transaction_data().kind()
+            {
+                change_epoch = Some((args.clone(), digests.effects));
+            }
+        }
+        let Some((args, effects_digest)) = change_epoch else {
+            return Err(anyhow!(
+                "End of epoch checkpoint {} has no ChangeEpoch transaction",
+                summary.sequence_number
+            ));
+        };
+
+        let Some(effects) = perpetual_tables.effects.get(&effects_digest)? else {
+            return Err(anyhow!("Missing effects {:?}", effects_digest));
+        };
+        let mut events = vec![];
+        if let Some(events_digest) = effects.events_digest() {
+            for ((digest, _), event) in perpetual_tables
+                .events
+                .iter()
+                .skip_to(&(*events_digest, 0))?
+            {
+                if digest != *events_digest {
+                    break;
+                }
+                events.push(event);
+            }
+        }
+        let (system_epoch_info, validator_rewards) = decode_epoch_events(&events)?;
+
+        reports.push(EndOfEpochReport {
+            epoch: summary.epoch,
+            checkpoint: summary.sequence_number,
+            timestamp_ms: summary.timestamp_ms,
+            next_epoch_protocol_version: end_of_epoch_data.next_epoch_protocol_version.as_u64(),
+            next_epoch_committee_size: end_of_epoch_data.next_epoch_committee.len(),
+            next_epoch_total_stake: end_of_epoch_data
+                .next_epoch_committee
+                .iter()
+                .map(|(_, stake)| stake)
+                .sum(),
+            advance_epoch: AdvanceEpochArgs {
+                next_epoch: args.epoch,
+                protocol_version: args.protocol_version.as_u64(),
+                storage_charge: args.storage_charge,
+                computation_charge: args.computation_charge,
+                storage_rebate: args.storage_rebate,
+                epoch_start_timestamp_ms: args.epoch_start_timestamp_ms,
+                system_packages: args
+                    .system_packages
+                    .iter()
+                    .map(|(version, _, _)| *version)
+                    .collect(),
+            },
+            system_epoch_info,
+            validator_rewards,
+        });
+    }
+    Ok(reports)
+}
+
+impl Display for EndOfEpochReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let args = &self.advance_epoch;
+        writeln!(
+            f,
+            "Epoch {} (ended at checkpoint {}, timestamp {} ms)",
+            self.epoch, self.checkpoint, self.timestamp_ms
+        )?;
+        writeln!(
+            f,
+            "  next epoch: {}, protocol version {}, {} validators with {} total stake",
+            args.next_epoch,
+            self.next_epoch_protocol_version,
+            self.next_epoch_committee_size,
+            self.next_epoch_total_stake
+        )?;
+        writeln!(f, "  safe mode: {}", self.safe_mode())?;
+        writeln!(f, "  advance_epoch arguments:")?;
+        writeln!(
+            f,
+            "    computation charge:       {:>20}",
+            args.computation_charge
+        )?;
+        writeln!(
+            f,
+            "    storage charge:           {:>20}",
+            args.storage_charge
+        )?;
+        writeln!(
+            f,
+            "    storage rebate:           {:>20}",
+            args.storage_rebate
+        )?;
+        writeln!(
+            f,
+            "    epoch start timestamp:    {:>20}",
+            args.epoch_start_timestamp_ms
+        )?;
+        writeln!(
+            f,
+            "    system packages upgraded: {:>20}",
+            args.system_packages.len()
+        )?;
+        let Some(info) = &self.system_epoch_info else {
+            return writeln!(
+                f,
+                "  no SystemEpochInfoEvent: rewards were not distributed for this epoch"
+            );
+        };
+        writeln!(f, "  storage fund:")?;
+        writeln!(
+            f,
+            "    storage charge (in):      {:>20}",
+            info.storage_charge
+        )?;
+        writeln!(
+            f,
+            "    reinvestment (in):        {:>20}",
+            info.storage_fund_reinvestment
+        )?;
+        writeln!(
+            f,
+            "    leftover inflow (in):     {:>20}",
+            info.leftover_storage_fund_inflow
+        )?;
+        writeln!(
+            f,
+            "    storage rebate (out):     {:>20}",
+            info.storage_rebate
+        )?;
+        writeln!(
+            f,
+            "    net inflow:               {:>20}",
+            info.storage_fund_net_inflow()
+        )?;
+        writeln!(
+            f,
+            "    ending balance:           {:>20}",
+            info.storage_fund_balance
+        )?;
+        writeln!(f, "  rewards:")?;
+        writeln!(
+            f,
+            "    total gas fees:           {:>20}",
+            info.total_gas_fees
+        )?;
+        writeln!(
+            f,
+            "    stake subsidy:            {:>20}",
+            info.stake_subsidy_amount
+        )?;
+        writeln!(
+            f,
+            "    distributed to stakers:   {:>20}",
+            info.total_stake_rewards_distributed
+        )?;
+        writeln!(
+            f,
+            "    {:<66} {:>16} {:>8} {:>16} {:>16}",
+            "validator", "stake", "comm.bps", "pool reward", "fund reward"
+        )?;
+        for validator in &self.validator_rewards {
+            writeln!(
+                f,
+                "    {:<66} {:>16} {:>8} {:>16} {:>16}",
+                validator.validator_address,
+                validator.stake,
+                validator.commission_rate,
+                validator.pool_staking_reward,
+                validator.storage_fund_staking_reward
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct EpochCsvRow {
+    epoch: EpochId,
+    checkpoint: CheckpointSequenceNumber,
+    timestamp_ms: CheckpointTimestamp,
+    safe_mode: bool,
+    next_epoch_protocol_version: u64,
+    computation_charge: u64,
+    storage_charge: u64,
+    storage_rebate: u64,
+    reference_gas_price: Option<u64>,
+    total_stake: Option<u64>,
+    total_gas_fees: Option<u64>,
+    stake_subsidy_amount: Option<u64>,
+    total_stake_rewards_distributed: Option<u64>,
+    storage_fund_reinvestment: Option<u64>,
+    leftover_storage_fund_inflow: Option<u64>,
+    storage_fund_net_inflow: Option<i64>,
+    storage_fund_balance: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct ValidatorRewardCsvRow {
+    epoch: EpochId,
+    validator_address: SuiAddress,
+    stake: u64,
+    commission_rate: u64,
+    pool_staking_reward: u64,
+    storage_fund_staking_reward: u64,
+    total_reward: u64,
+    pool_token_exchange_rate: f64,
+    tallying_rule_global_score: u64,
+    tallying_rule_reporters: usize,
+}
+
+/// Writes one row per epoch to `epochs`, and one row per validator and epoch to
+/// `validator_rewards`. Fields that are only known from events are empty for safe mode epochs.
+pub fn write_csv<W: std::io::Write>(
+    reports: &[EndOfEpochReport],
+    epochs: W,
+    validator_rewards: W,
+) -> anyhow::Result<()> {
+    let mut epochs = csv::Writer::from_writer(epochs);
+    let mut validator_rewards = csv::Writer::from_writer(validator_rewards);
+    for report in reports {
+        let info = report.system_epoch_info.as_ref();
+        epochs.serialize(EpochCsvRow {
+            epoch: report.epoch,
+            checkpoint: report.checkpoint,
+            timestamp_ms: report.timestamp_ms,
+            safe_mode: report.safe_mode(),
+            next_epoch_protocol_version: report.next_epoch_protocol_version,
+            computation_charge: report.advance_epoch.computation_charge,
+            storage_charge: report.advance_epoch.storage_charge,
+            storage_rebate: report.advance_epoch.storage_rebate,
+            reference_gas_price: info.map(|i| i.reference_gas_price),
+            total_stake: info.map(|i| i.total_stake),
+            total_gas_fees: info.map(|i| i.total_gas_fees),
+            stake_subsidy_amount: info.map(|i| i.stake_subsidy_amount),
+            total_stake_rewards_distributed: info.map(|i| i.total_stake_rewards_distributed),
+            storage_fund_reinvestment: info.map(|i| i.storage_fund_reinvestment),
+            leftover_storage_fund_inflow: info.map(|i| i.leftover_storage_fund_inflow),
+            storage_fund_net_inflow: info.map(|i| i.storage_fund_net_inflow()),
+            storage_fund_balance: info.map(|i| i.storage_fund_balance),
+        })?;
+        for validator in &report.validator_rewards {
+            validator_rewards.serialize(ValidatorRewardCsvRow {
+                epoch: report.epoch,
+                validator_address: validator.validator_address,
+                stake: validator.stake,
+                commission_rate: validator.commission_rate,
+                pool_staking_reward: validator.pool_staking_reward,
+                storage_fund_staking_reward: validator.storage_fund_staking_reward,
+                total_reward: validator.total_reward(),
+                pool_token_exchange_rate: validator.pool_token_exchange_rate.rate(),
+                tallying_rule_global_score: validator.tallying_rule_global_score,
+                tallying_rule_reporters: validator.tallying_rule_reporters.len(),
+            })?;
+        }
+    }
+    epochs.flush()?;
+    validator_rewards.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::language_storage::StructTag;
+
+    fn event(module: &IdentStr, name: &IdentStr, contents: Vec<u8>) -> Event {
+        Event::new(
+            &SUI_SYSTEM_ADDRESS,
+            module,
+            SuiAddress::default(),
+            StructTag {
+                address: SUI_SYSTEM_ADDRESS,
+                module: module.to_owned(),
+                name: name.to_owned(),
+                type_params: vec![],
+            },
+            contents,
+        )
+    }
+
+    fn report(system_epoch_info: Option<SystemEpochInfo>) -> EndOfEpochReport {
+        EndOfEpochReport {
+            epoch: 7,
+            checkpoint: 1000,
+            timestamp_ms: 1_700_000_000_000,
+            next_epoch_protocol_version: 2,
+            next_epoch_committee_size: 1,
+            next_epoch_total_stake: 10_000,
+            advance_epoch: AdvanceEpochArgs {
+                next_epoch: 8,
+                protocol_version: 2,
+                storage_charge: 500,
+                computation_charge: 1000,
+                storage_rebate: 200,
+                epoch_start_timestamp_ms: 1_700_000_000_000,
+                system_packages: vec![],
+            },
+            system_epoch_info,
+            validator_rewards: vec![],
+        }
+    }
+
+    #[test]
+    fn decode_advance_epoch_events() {
+        let system_info = SystemEpochInfo {
+            epoch: 7,
+            storage_charge: 500,
+            storage_rebate: 200,
+            storage_fund_reinvestment: 10,
+            leftover_storage_fund_inflow: 1,
+            total_stake_rewards_distributed: 1000,
+            ..Default::default()
+        };
+        let validator_info = ValidatorEpochInfo {
+            epoch: 7,
+            validator_address: SuiAddress::random_for_testing_only(),
+            reference_gas_survey_quote: 1000,
+            stake: 10_000,
+            commission_rate: 200,
+            pool_staking_reward: 900,
+            storage_fund_staking_reward: 100,
+            pool_token_exchange_rate: PoolTokenExchangeRate::default(),
+            tallying_rule_reporters: vec![],
+            tallying_rule_global_score: 1,
+        };
+        let events = vec![
+            event(
+                VALIDATOR_SET_MODULE,
+                VALIDATOR_EPOCH_INFO_EVENT,
+                bcs::to_bytes(&validator_info).unwrap(),
+            ),
+            event(
+                SUI_SYSTEM_STATE_INNER_MODULE,
+                SYSTEM_EPOCH_INFO_EVENT,
+                bcs::to_bytes(&system_info).unwrap(),
+            ),
+        ];
+
+        let (decoded_system_info, decoded_validators) = decode_epoch_events(&events).unwrap();
+        assert_eq!(decoded_system_info, Some(system_info.clone()));
+        assert_eq!(decoded_validators, vec![validator_info.clone()]);
+        assert_eq!(system_info.storage_fund_net_inflow(), 311);
+        assert_eq!(validator_info.total_reward(), 1000);
+
+        // Epochs that fell back to safe mode emit no events.
+        assert_eq!(decode_epoch_events(&[]).unwrap(), (None, vec![]));
+    }
+
+    #[test]
+    fn csv_leaves_event_fields_empty_in_safe_mode() {
+        let reports = vec![report(Some(SystemEpochInfo::default())), report(None)];
+        let mut epochs = vec![];
+        let mut validator_rewards = vec![];
+        write_csv(&reports, &mut epochs, &mut validator_rewards).unwrap();
+
+        let epochs = String::from_utf8(epochs).unwrap();
+        let lines: Vec<_> = epochs.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("epoch,checkpoint,timestamp_ms,safe_mode"));
+        assert!(lines[1].contains(",false,"));
+        assert!(lines[2].contains(",true,"));
+        assert!(lines[2].ends_with(",,,,,,,,,"));
+        assert!(validator_rewards.is_empty());
+    }
+}
@@ -0,0 +1,472 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline gas price market simulation.
+//!
+//! Replays the user transactions of historical epochs, as recorded in a node's database, under
+//! alternative reference gas price (RGP) update rules and tip mechanisms, and reports the fee
+//! revenue and the inclusion latency distribution each combination would have produced.
+//!
+//! The model is intentionally simple: every checkpoint is a block with a fixed computation
+//! capacity, transactions bidding below the simulated RGP are dropped, and the remaining ones are
+//! included in order of their tip (bid above RGP), oldest first among equal tips.
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_types::base_types::EpochId;
+use sui_types::messages::{TransactionDataAPI, TransactionEffectsAPI};
+use sui_types::messages_checkpoint::CheckpointTimestamp;
+use typed_store::traits::Map;
+
+/// How the reference gas price of the next epoch is derived from the current one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RgpUpdateRule {
+    /// Use the RGP observed on chain for every epoch.
+    Historical,
+    /// Keep the RGP of the first simulated epoch for the whole run.
+    Fixed,
+    /// Adjust the RGP towards a target utilization of the checkpoint capacity, similar to
+    /// EIP-1559: `rgp' = rgp * (1 + max_change * (utilization - target) / target)`.
+    TargetUtilization,
+    /// Set the RGP to the median bid of the transactions included in the previous epoch.
+    MedianBid,
+}
+
+/// How much of its bid an included transaction pays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TipMechanism {
+    /// Transactions pay their full bid, as they do today.
+    FirstPrice,
+    /// Transactions pay the RGP only; the tip is used for ordering but never charged.
+    ReferencePrice,
+    /// Transactions pay their full bid, but the tip portion is burnt and not counted as revenue.
+    BurnTip,
+}
+
+#[derive(Clone, Debug)]
+pub struct GasMarketSimConfig {
+    pub rgp_rule: RgpUpdateRule,
+    pub tip_mechanism: TipMechanism,
+    /// Computation units that fit into a single checkpoint.
+    pub checkpoint_capacity: u64,
+    /// Target fraction of `checkpoint_capacity` for `RgpUpdateRule::TargetUtilization`.
+    pub target_utilization: f64,
+    /// Maximum relative RGP change per epoch for `RgpUpdateRule::TargetUtilization`.
+    pub max_rgp_change: f64,
+    /// Overrides the RGP of the first simulated epoch, otherwise the historical one is used.
+    pub initial_rgp: Option<u64>,
+}
+
+impl Default for GasMarketSimConfig {
+    fn default() -> Self {
+        Self {
+            rgp_rule: RgpUpdateRule::Historical,
+            tip_mechanism: TipMechanism::FirstPrice,
+            checkpoint_capacity: 10_000_000,
+            target_utilization: 0.5,
+            max_rgp_change: 0.125,
+            initial_rgp: None,
+        }
+    }
+}
+
+/// A single user transaction as recorded on chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoricalTransaction {
+    pub gas_price: u64,
+    /// Computation cost divided by the gas price the transaction was charged at.
+    pub computation_units: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct HistoricalCheckpoint {
+    pub timestamp_ms: CheckpointTimestamp,
+    pub transactions: Vec<HistoricalTransaction>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HistoricalEpoch {
+    pub epoch: EpochId,
+    /// The RGP in effect during the epoch. Inferred as the lowest user bid seen, since a
+    /// transaction bidding below the RGP can never be certified.
+    pub reference_gas_price: u64,
+    pub checkpoints: Vec<HistoricalCheckpoint>,
+}
+
+/// Loads the given (inclusive) range of epochs from the database of a full node or validator.
+pub fn load_epoch_history(
+    db_path: &Path,
+    start_epoch: EpochId,
+    end_epoch: EpochId,
+) -> anyhow::Result<Vec<HistoricalEpoch>> {
+    let checkpoint_store = CheckpointStore::open_readonly(&db_path.join("checkpoints"));
+    let perpetual_tables = AuthorityPerpetualTables::open_readonly(&db_path.join("store"));
+
+    let mut epochs = BTreeMap::<EpochId, Vec<HistoricalCheckpoint>>::new();
+    for (_, checkpoint) in checkpoint_store.certified_checkpoints.iter() {
+        let summary = checkpoint.inner().data();
+        if summary.epoch < start_epoch {
+            continue;
+        }
+        if summary.epoch > end_epoch {
+            break;
+        }
+        let contents = checkpoint_store
+            .checkpoint_content
+            .get(&summary.content_digest)?
+            .ok_or_else(|| {
+                anyhow!(
+                    "Missing contents for checkpoint {}",
+                    summary.sequence_number
+                )
+            })?;
+
+        let mut transactions = vec![];
+        for digests in contents.iter() {
+            let Some(transaction) = perpetual_tables.transactions.get(&digests.transaction)? else {
+                return Err(anyhow!("Missing transaction {:?}", digests.transaction));
+            };
+            let data = transaction.inner().transaction_data();
+            if data.is_system_tx() {
+                continue;
+            }
+            let Some(effects) = perpetual_tables.effects.get(&digests.effects)? else {
+                return Err(anyhow!("Missing effects {:?}", digests.effects));
+            };
+            let gas_price = data.gas_price();
+            transactions.push(HistoricalTransaction {
+                gas_price,
+                computation_units: effects.gas_cost_summary().computation_cost / gas_price.max(1),
+            });
+        }
+        epochs
+            .entry(summary.epoch)
+            .or_default()
+            .push(HistoricalCheckpoint {
+                timestamp_ms: summary.timestamp_ms,
+                transactions,
+            });
+    }
+
+    Ok(epochs
+        .into_iter()
+        .map(|(epoch, checkpoints)| {
+            let reference_gas_price = checkpoints
+                .iter()
+                .flat_map(|c| c.transactions.iter().map(|t| t.gas_price))
+                .min()
+                .unwrap_or(1);
+            HistoricalEpoch {
+                epoch,
+                reference_gas_price,
+                checkpoints,
+            }
+        })
+        .collect())
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EpochSimResult {
+    pub epoch: EpochId,
+    pub reference_gas_price: u64,
+    pub included: u64,
+    /// Transactions whose bid was below the simulated RGP.
+    pub excluded: u64,
+    /// Transactions still waiting for capacity when the epoch ended.
+    pub pending_at_epoch_end: u64,
+    pub computation_revenue: u128,
+    pub tips_paid: u128,
+    /// Milliseconds between original and simulated inclusion, one entry per included transaction.
+    pub inclusion_latencies_ms: Vec<u64>,
+    pub utilization: f64,
+}
+
+impl EpochSimResult {
+    pub fn latency_percentile(&self, percentile: u8) -> Option<u64> {
+        percentile_of(&self.inclusion_latencies_ms, percentile)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GasMarketSimReport {
+    pub epochs: Vec<EpochSimResult>,
+}
+
+impl GasMarketSimReport {
+    pub fn total_revenue(&self) -> u128 {
+        self.epochs.iter().map(|e| e.computation_revenue).sum()
+    }
+
+    pub fn latency_percentile(&self, percentile: u8) -> Option<u64> {
+        let mut all: Vec<_> = self
+            .epochs
+            .iter()
+            .flat_map(|e| e.inclusion_latencies_ms.iter().copied())
+            .collect();
+        all.sort_unstable();
+        percentile_of(&all, percentile)
+    }
+}
+
+fn percentile_of(sorted: &[u64], percentile: u8) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let idx = (sorted.len() - 1) * percentile.min(100) as usize / 100;
+    Some(sorted[idx])
+}
+
+impl Display for GasMarketSimReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:>6} {:>8} {:>10} {:>10} {:>10} {:>20} {:>16} {:>8} {:>8} {:>8} {:>6}",
+            "epoch",
+            "rgp",
+            "included",
+            "excluded",
+            "pending",
+            "revenue",
+            "tips",
+            "p50_ms",
+            "p90_ms",
+            "p99_ms",
+            "util"
+        )?;
+        for e in &self.epochs {
+            writeln!(
+                f,
+                "{:>6} {:>8} {:>10} {:>10} {:>10} {:>20} {:>16} {:>8} {:>8} {:>8} {:>6.2}",
+                e.epoch,
+                e.reference_gas_price,
+                e.included,
+                e.excluded,
+                e.pending_at_epoch_end,
+                e.computation_revenue,
+                e.tips_paid,
+                e.latency_percentile(50).unwrap_or_default(),
+                e.latency_percentile(90).unwrap_or_default(),
+                e.latency_percentile(99).unwrap_or_default(),
+                e.utilization,
+            )?;
+        }
+        write!(
+            f,
+            "Total revenue = {}, latency p50 = {}ms, p90 = {}ms, p99 = {}ms",
+            self.total_revenue(),
+            self.latency_percentile(50).unwrap_or_default(),
+            self.latency_percentile(90).unwrap_or_default(),
+            self.latency_percentile(99).unwrap_or_default(),
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct QueuedTransaction {
+    tip: u64,
+    // Reversed so that among equal tips the oldest transaction is popped first.
+    arrival: std::cmp::Reverse<(CheckpointTimestamp, usize)>,
+    bid: u64,
+    computation_units: u64,
+}
+
+/// Replays `history` under `config`. Epochs are expected in ascending order.
+pub fn simulate(history: &[HistoricalEpoch], config: &GasMarketSimConfig) -> GasMarketSimReport {
+    let mut report = GasMarketSimReport::default();
+    let mut rgp = history
+        .first()
+        .map(|e| config.initial_rgp.unwrap_or(e.reference_gas_price))
+        .unwrap_or(1)
+        .max(1);
+
+    for epoch in history {
+        if config.rgp_rule == RgpUpdateRule::Historical {
+            rgp = epoch.reference_gas_price.max(1);
+        }
+        let mut result = EpochSimResult {
+            epoch: epoch.epoch,
+            reference_gas_price: rgp,
+            ..Default::default()
+        };
+        let mut queue = BinaryHeap::new();
+        let mut included_bids = vec![];
+        let mut used_units = 0u128;
+        let mut seq = 0usize;
+
+        for checkpoint in &epoch.checkpoints {
+            for tx in &checkpoint.transactions {
+                if tx.gas_price < rgp {
+                    result.excluded += 1;
+                    continue;
+                }
+                queue.push(QueuedTransaction {
+                    tip: tx.gas_price - rgp,
+                    arrival: std::cmp::Reverse((checkpoint.timestamp_ms, seq)),
+                    bid: tx.gas_price,
+                    computation_units: tx.computation_units,
+                });
+                seq += 1;
+            }
+
+            let mut remaining = config.checkpoint_capacity;
+            while let Some(next) = queue.peek() {
+                // A transaction larger than a whole checkpoint still gets a checkpoint to itself.
+                if next.computation_units > remaining && remaining < config.checkpoint_capacity {
+                    break;
+                }
+                let next = queue.pop().unwrap();
+                remaining = remaining.saturating_sub(next.computation_units);
+                used_units += next.computation_units as u128;
+
+                let units = next.computation_units as u128;
+                let (charged_price, revenue_price) = match config.tip_mechanism {
+                    TipMechanism::FirstPrice => (next.bid, next.bid),
+                    TipMechanism::ReferencePrice => (rgp, rgp),
+                    TipMechanism::BurnTip => (next.bid, rgp),
+                };
+                result.computation_revenue += units * revenue_price as u128;
+                result.tips_paid += units * (charged_price - rgp) as u128;
+                result.included += 1;
+                result
+                    .inclusion_latencies_ms
+                    .push(checkpoint.timestamp_ms - next.arrival.0 .0);
+                included_bids.push(next.bid);
+            }
+        }
+        result.pending_at_epoch_end = queue.len() as u64;
+        result.inclusion_latencies_ms.sort_unstable();
+        let capacity = config.checkpoint_capacity as u128 * epoch.checkpoints.len() as u128;
+        result.utilization = if capacity == 0 {
+            0.0
+        } else {
+            used_units as f64 / capacity as f64
+        };
+
+        rgp = next_rgp(rgp, &result, &mut included_bids, config);
+        report.epochs.push(result);
+    }
+    report
+}
+
+fn next_rgp(
+    rgp: u64,
+    result: &EpochSimResult,
+    included_bids: &mut [u64],
+    config: &GasMarketSimConfig,
+) -> u64 {
+    match config.rgp_rule {
+        RgpUpdateRule::Historical | RgpUpdateRule::Fixed => rgp,
+        RgpUpdateRule::TargetUtilization => {
+            let target = config.target_utilization.max(f64::EPSILON);
+            let delta =
+                ((result.utilization - target) / target).clamp(-1.0, 1.0) * config.max_rgp_change;
+            ((rgp as f64 * (1.0 + delta)).round() as u64).max(1)
+        }
+        RgpUpdateRule::MedianBid => {
+            if included_bids.is_empty() {
+                return rgp;
+            }
+            included_bids.sort_unstable();
+            included_bids[included_bids.len() / 2]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(gas_price: u64, computation_units: u64) -> HistoricalTransaction {
+        HistoricalTransaction {
+            gas_price,
+            computation_units,
+        }
+    }
+
+    fn epoch(
+        epoch: EpochId,
+        rgp: u64,
+        checkpoints: Vec<Vec<HistoricalTransaction>>,
+    ) -> HistoricalEpoch {
+        HistoricalEpoch {
+            epoch,
+            reference_gas_price: rgp,
+            checkpoints: checkpoints
+                .into_iter()
+                .enumerate()
+                .map(|(i, transactions)| HistoricalCheckpoint {
+                    timestamp_ms: i as u64 * 100,
+                    transactions,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_historical_replay_matches_chain() {
+        let history = vec![epoch(
+            0,
+            10,
+            vec![vec![tx(10, 5), tx(12, 5)], vec![tx(10, 5)]],
+        )];
+        let report = simulate(&history, &GasMarketSimConfig::default());
+        let result = &report.epochs[0];
+        assert_eq!(result.included, 3);
+        assert_eq!(result.excluded, 0);
+        assert_eq!(result.computation_revenue, 10 * 5 + 12 * 5 + 10 * 5);
+        assert_eq!(result.tips_paid, 2 * 5);
+        assert_eq!(report.latency_percentile(99), Some(0));
+    }
+
+    #[test]
+    fn test_capacity_delays_low_tips() {
+        let history = vec![epoch(
+            0,
+            10,
+            vec![vec![tx(10, 6), tx(11, 6)], vec![], vec![]],
+        )];
+        let config = GasMarketSimConfig {
+            checkpoint_capacity: 10,
+            tip_mechanism: TipMechanism::ReferencePrice,
+            ..Default::default()
+        };
+        let result = &simulate(&history, &config).epochs[0];
+        assert_eq!(result.included, 2);
+        // The higher tip goes first, the other one waits a checkpoint.
+        assert_eq!(result.inclusion_latencies_ms, vec![0, 100]);
+        assert_eq!(result.computation_revenue, 10 * 12);
+        assert_eq!(result.tips_paid, 0);
+    }
+
+    #[test]
+    fn test_rgp_rules() {
+        let history = vec![
+            epoch(0, 10, vec![vec![tx(10, 10), tx(20, 10)]]),
+            epoch(1, 10, vec![vec![tx(10, 10), tx(20, 10)]]),
+        ];
+
+        let config = GasMarketSimConfig {
+            rgp_rule: RgpUpdateRule::MedianBid,
+            ..Default::default()
+        };
+        let report = simulate(&history, &config);
+        assert_eq!(report.epochs[1].reference_gas_price, 20);
+        assert_eq!(report.epochs[1].excluded, 1);
+
+        let config = GasMarketSimConfig {
+            rgp_rule: RgpUpdateRule::TargetUtilization,
+            checkpoint_capacity: 20,
+            target_utilization: 0.5,
+            max_rgp_change: 0.5,
+            ..Default::default()
+        };
+        let report = simulate(&history, &config);
+        // Fully utilized at a 50% target: the RGP goes up by the maximum change.
+        assert_eq!(report.epochs[1].reference_gas_price, 15);
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+
+use anyhow::anyhow;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::checkpoints::CheckpointStore;
+use sui_core::epoch::committee_store::CommitteeStore;
+use sui_types::base_types::TransactionDigest;
+use sui_types::inclusion_proof::TransactionInclusionProof;
+use typed_store::traits::Map;
+
+/// Builds the inclusion proof of the transaction `digest` from the node db at `db_path`, i.e. the
+/// `db-path` of the node config. The proof is verified against the committee of its epoch before
+/// being returned, so that a corrupted db does not produce an invalid proof.
+pub fn transaction_inclusion_proof_from_db(
+    db_path: &Path,
+    digest: TransactionDigest,
+) -> anyhow::Result<TransactionInclusionProof> {
+    let tables = AuthorityPerpetualTables::open_readonly(&db_path.join("store"));
+    let checkpoint_store = CheckpointStore::open_readonly(&db_path.join("checkpoints"));
+    let committee_store = CommitteeStore::open_readonly(&db_path.join("epochs"));
+
+    let (epoch, sequence_number) = tables
+        .executed_transactions_to_checkpoint
+        .get(&digest)?
+        .ok_or_else(|| anyhow!("Transaction {digest} is not checkpointed"))?;
+    let certified_checkpoint = |sequence_number| -> anyhow::Result<_> {
+        Ok(checkpoint_store
+            .certified_checkpoints
+            .get(&sequence_number)?
+            .ok_or_else(|| anyhow!("Checkpoint {sequence_number} not found"))?
+            .into_inner())
+    };
+    let checkpoint = certified_checkpoint(sequence_number)?;
+    let contents = checkpoint_store
+        .checkpoint_content
+        .get(&checkpoint.content_digest)?
+        .ok_or_else(|| anyhow!("Contents of checkpoint {sequence_number} not found"))?;
+    let transaction = tables
+        .transactions
+        .get(&digest)?
+        .ok_or_else(|| anyhow!("Transaction {digest} not found"))?;
+    let effects_digest = tables
+        .executed_effects
+        .get(&digest)?
+        .ok_or_else(|| anyhow!("Effects of transaction {digest} not found"))?;
+    let effects = tables
+        .effects
+        .get(&effects_digest)?
+        .ok_or_else(|| anyhow!("Effects {effects_digest} not found"))?;
+    let committee = committee_store
+        .committee_map
+        .get(&epoch)?
+        .ok_or_else(|| anyhow!("Committee of epoch {epoch} not found"))?;
+    let previous_epoch_checkpoint = match epoch.checked_sub(1) {
+        Some(previous_epoch) => {
+            let sequence_number = checkpoint_store
+                .epoch_last_checkpoint_map
+                .get(&previous_epoch)?
+                .ok_or_else(|| anyhow!("Last checkpoint of epoch {previous_epoch} not found"))?;
+            Some(certified_checkpoint(sequence_number)?)
+        }
+        None => None,
+    };
+
+    let proof = TransactionInclusionProof {
+        transaction: transaction.into_inner().into_data(),
+        effects,
+        checkpoint,
+        contents,
+        committee: committee.members().cloned().collect(),
+        previous_epoch_checkpoint,
+    };
+    proof.verify(&committee)?;
+    Ok(proof)
+}
@@ -21,6 +21,13 @@ use anyhow::anyhow;
 
 pub mod commands;
 pub mod db_tool;
+pub mod db_verify;
+pub mod epoch_report;
+pub mod gas_market_sim;
+pub mod inclusion_proof;
+pub mod package_dump;
+pub mod protocol_config;
+pub mod replay;
 
 fn make_clients(
     genesis: PathBuf,
@@ -0,0 +1,283 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export of the Move packages published up to a checkpoint, for source verification pipelines.
+//!
+//! Packages are read from the objects of a node db, or from the checkpoints of an archive. Every
+//! version of a package is written to `<output>/<original package id>/<version>/`, so that the
+//! versions of an upgraded package are next to each other:
+//! - `package.json`: the ids, version and publishing transaction and checkpoint of the package,
+//!   its modules, the packages it depends on (its linkage table) and its type origins,
+//! - `<module>.mv`: the bytecode of each module,
+//! - `<module>.asm`: the disassembled bytecode of each module.
+//!
+//! `<output>/index.json` lists the versions of every package, oldest first.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use anyhow::anyhow;
+use serde::Serialize;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_storage::archive::ArchiveReader;
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::messages::TransactionEffectsAPI;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::move_package::{MovePackage, TypeOrigin};
+use sui_types::object::{Object, Owner};
+use typed_store::traits::Map;
+
+#[derive(Debug, Serialize)]
+pub struct PackageMetadata {
+    pub id: ObjectID,
+    /// Id of the first version of the package, which its modules refer to themselves by.
+    pub original_id: ObjectID,
+    pub version: SequenceNumber,
+    pub previous_transaction: TransactionDigest,
+    /// Checkpoint of `previous_transaction`, if known.
+    pub checkpoint: Option<CheckpointSequenceNumber>,
+    pub modules: Vec<String>,
+    pub dependencies: Vec<Dependency>,
+    pub type_origins: Vec<TypeOrigin>,
+}
+
+/// The version of a package that a dependent package links against.
+#[derive(Debug, Serialize)]
+pub struct Dependency {
+    pub original_id: ObjectID,
+    pub id: ObjectID,
+    pub version: SequenceNumber,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackageVersion {
+    pub id: ObjectID,
+    pub version: SequenceNumber,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PackageIndex {
+    /// Checkpoint the packages were dumped at, or `None` for every checkpointed package.
+    pub checkpoint: Option<CheckpointSequenceNumber>,
+    /// Versions of every package by original id, oldest first.
+    pub lineages: BTreeMap<ObjectID, Vec<PackageVersion>>,
+    /// Packages published after the checkpoint, or whose checkpoint is unknown, which were not
+    /// dumped.
+    #[serde(skip)]
+    pub skipped: u64,
+}
+
+impl PackageIndex {
+    fn add(&mut self, metadata: &PackageMetadata) {
+        let versions = self.lineages.entry(metadata.original_id).or_default();
+        versions.push(PackageVersion {
+            id: metadata.id,
+            version: metadata.version,
+        });
+        versions.sort_by_key(|version| version.version);
+    }
+
+    fn write(&self, output: &Path) -> anyhow::Result<()> {
+        fs::write(
+            output.join("index.json"),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
+
+impl Display for PackageIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let versions: usize = self.lineages.values().map(Vec::len).sum();
+        write!(
+            f,
+            "Dumped {versions} versions of {} packages",
+            self.lineages.len()
+        )?;
+        if let Some(checkpoint) = self.checkpoint {
+            write!(f, " at checkpoint {checkpoint}")?;
+        }
+        writeln!(
+            f,
+            ", skipped {} packages published later or not checkpointed",
+            self.skipped
+        )
+    }
+}
+
+/// Dumps the packages of the node db at `db_path`, i.e. the `db-path` of the node config, that
+/// were published up to `checkpoint`, or all the checkpointed ones.
+pub fn dump_packages_from_db(
+    db_path: &Path,
+    checkpoint: Option<CheckpointSequenceNumber>,
+    output: &Path,
+) -> anyhow::Result<PackageIndex> {
+    let tables = AuthorityPerpetualTables::open_readonly(&db_path.join("store"));
+    fs::create_dir_all(output)?;
+    let mut index = PackageIndex {
+        checkpoint,
+        ..Default::default()
+    };
+    for (_, store_object) in tables.objects.iter() {
+        let Some(object) = tables.object(store_object)? else {
+            continue;
+        };
+        if !object.is_package() {
+            continue;
+        }
+        let published_at = tables
+            .executed_transactions_to_checkpoint
+            .get(&object.previous_transaction)?
+            .map(|(_, sequence_number)| sequence_number);
+        match (published_at, checkpoint) {
+            (Some(published_at), Some(checkpoint)) if published_at > checkpoint => {
+                index.skipped += 1;
+                continue;
+            }
+            (None, _) => {
+                index.skipped += 1;
+                continue;
+            }
+            _ => {}
+        }
+        index.add(&write_package(output, &object, published_at)?);
+    }
+    index.write(output)?;
+    Ok(index)
+}
+
+/// Dumps the packages published up to `checkpoint` from the archive in the local directory
+/// `archive_dir`. Every checkpoint up to `checkpoint` must be in the archive.
+pub async fn dump_packages_from_archive(
+    archive_dir: &Path,
+    checkpoint: CheckpointSequenceNumber,
+    output: &Path,
+) -> anyhow::Result<PackageIndex> {
+    let archive = ArchiveReader::new(&sui_storage::object_store::ObjectStoreConfig {
+        object_store: Some(sui_storage::object_store::ObjectStoreType::File),
+        directory: Some(archive_dir.to_path_buf()),
+        ..Default::default()
+    })?;
+    fs::create_dir_all(output)?;
+    let mut index = PackageIndex {
+        checkpoint: Some(checkpoint),
+        ..Default::default()
+    };
+    for sequence_number in 0..=checkpoint {
+        let contents = archive
+            .get_checkpoint_contents(sequence_number)
+            .await?
+            .ok_or_else(|| anyhow!("Checkpoint {sequence_number} is not in the archive"))?;
+        for data in contents.iter() {
+            // Packages are immutable, and published or upgraded (in the case of system packages)
+            // by the transactions creating or mutating them.
+            let written = data
+                .effects
+                .created()
+                .iter()
+                .chain(data.effects.mutated())
+                .filter(|(_, owner)| *owner == Owner::Immutable);
+            for (object_ref, _) in written {
+                let object = archive
+                    .get_object(&object_ref.0, object_ref.1)
+                    .await?
+                    .ok_or_else(|| anyhow!("Object {object_ref:?} is not in the archive"))?;
+                // The archive is not trusted, objects must match the effects that wrote them.
+                if object.compute_object_reference() != *object_ref {
+                    return Err(anyhow!(
+                        "Object {object_ref:?} in the archive does not match its effects"
+                    ));
+                }
+                if object.is_package() {
+                    index.add(&write_package(output, &object, Some(sequence_number))?);
+                }
+            }
+        }
+    }
+    index.write(output)?;
+    Ok(index)
+}
+
+fn write_package(
+    output: &Path,
+    object: &Object,
+    checkpoint: Option<CheckpointSequenceNumber>,
+) -> anyhow::Result<PackageMetadata> {
+    let package: &MovePackage = object
+        .data
+        .try_as_package()
+        .ok_or_else(|| anyhow!("Object {} is not a package", object.id()))?;
+    let metadata = PackageMetadata {
+        id: package.id(),
+        original_id: package.original_package_id(),
+        version: package.version(),
+        previous_transaction: object.previous_transaction,
+        checkpoint,
+        modules: package.serialized_module_map().keys().cloned().collect(),
+        dependencies: package
+            .linkage_table()
+            .iter()
+            .map(|(original_id, upgrade_info)| Dependency {
+                original_id: *original_id,
+                id: upgrade_info.upgraded_id,
+                version: upgrade_info.upgraded_version,
+            })
+            .collect(),
+        type_origins: package.type_origin_table().clone(),
+    };
+
+    let dir = output
+        .join(metadata.original_id.to_string())
+        .join(metadata.version.value().to_string());
+    fs::create_dir_all(&dir)?;
+    for (name, bytecode) in package.serialized_module_map() {
+        fs::write(dir.join(format!("{name}.mv")), bytecode)?;
+    }
+    for (name, disassembled) in package.disassemble()? {
+        if let serde_json::Value::String(disassembled) = disassembled {
+            fs::write(dir.join(format!("{name}.asm")), disassembled)?;
+        }
+    }
+    fs::write(
+        dir.join("package.json"),
+        serde_json::to_string_pretty(&metadata)?,
+    )?;
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_framework::{MoveStdlib, SuiFramework, SystemPackage};
+
+    use super::*;
+
+    #[test]
+    fn test_write_package() {
+        let tmp = tempfile::tempdir().unwrap();
+        let object = Object::new_package_from_data(
+            sui_types::object::Data::Package(MovePackage::new_system(
+                SequenceNumber::from_u64(1),
+                SuiFramework::as_modules(),
+                [MoveStdlib::ID],
+            )),
+            TransactionDigest::genesis(),
+        );
+
+        let metadata = write_package(tmp.path(), &object, Some(0)).unwrap();
+        let mut index = PackageIndex::default();
+        index.add(&metadata);
+
+        assert_eq!(metadata.original_id, SuiFramework::ID);
+        assert_eq!(metadata.dependencies.len(), 1);
+        assert_eq!(metadata.dependencies[0].original_id, MoveStdlib::ID);
+        let dir = tmp.path().join(SuiFramework::ID.to_string()).join("1");
+        assert!(dir.join("package.json").exists());
+        for module in &metadata.modules {
+            assert!(dir.join(format!("{module}.mv")).exists());
+            assert!(dir.join(format!("{module}.asm")).exists());
+        }
+        assert_eq!(index.lineages[&SuiFramework::ID].len(), 1);
+    }
+}
@@ -0,0 +1,335 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inspection of the protocol configs built into this binary, for operators and developers
+//! preparing protocol upgrades.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use clap::Parser;
+use sui_config::{Config, NodeConfig};
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::authority::epoch_start_configuration::EpochStartConfigTrait;
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion, SupportedProtocolVersions};
+use sui_types::committee::EpochId;
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
+use typed_store::traits::Map;
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum ProtocolConfigCommand {
+    /// Print every constant and feature flag of a protocol version
+    Show {
+        /// Defaults to the latest version supported by this binary
+        #[clap(long = "version")]
+        version: Option<u64>,
+    },
+    /// Print the constants and feature flags that differ between two protocol versions
+    Diff {
+        #[clap(long = "from")]
+        from: u64,
+        #[clap(long = "to")]
+        to: u64,
+    },
+    /// Check the protocol versions a node supports, as set in its config, against the current
+    /// protocol version of the chain in its db, and print what changes from the current version
+    /// to the latest version the node supports
+    Validate {
+        #[clap(long = "config-path")]
+        config_path: PathBuf,
+    },
+}
+
+pub fn execute_protocol_config_command(cmd: ProtocolConfigCommand) -> anyhow::Result<()> {
+    match cmd {
+        ProtocolConfigCommand::Show { version } => {
+            let version = match version {
+                Some(version) => protocol_version(version)?,
+                None => ProtocolVersion::MAX,
+            };
+            println!("Protocol version {}", version.as_u64());
+            for (name, value) in protocol_config_entries(version)? {
+                println!("  {name}: {}", value.as_deref().unwrap_or(UNSET));
+            }
+        }
+        ProtocolConfigCommand::Diff { from, to } => {
+            print!(
+                "{}",
+                diff_protocol_configs(protocol_version(from)?, protocol_version(to)?)?
+            );
+        }
+        ProtocolConfigCommand::Validate { config_path } => {
+            let config = NodeConfig::load(&config_path)?;
+            let check = check_node_protocol_versions(&config)?;
+            print!("{check}");
+            if !check.errors.is_empty() {
+                return Err(anyhow!(
+                    "The node can't run the current protocol version of the chain"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+const UNSET: &str = "<unset>";
+
+/// A protocol version supported by this binary.
+fn protocol_version(version: u64) -> anyhow::Result<ProtocolVersion> {
+    if version < ProtocolVersion::MIN.as_u64() || version > ProtocolVersion::MAX.as_u64() {
+        return Err(anyhow!(
+            "Protocol version {version} is not supported by this binary, which supports versions \
+            {} to {}",
+            ProtocolVersion::MIN.as_u64(),
+            ProtocolVersion::MAX.as_u64()
+        ));
+    }
+    Ok(ProtocolVersion::new(version))
+}
+
+/// Every constant and feature flag of `version` by name, feature flags prefixed with
+/// `feature_flags.`. Constants which are not defined in `version` have no value.
+pub fn protocol_config_entries(
+    version: ProtocolVersion,
+) -> anyhow::Result<BTreeMap<String, Option<String>>> {
+    let config = serde_json::to_value(ProtocolConfig::get_for_version(version))?;
+    let serde_json::Value::Object(fields) = config else {
+        return Err(anyhow!("Protocol config is not serialized as a map"));
+    };
+    let mut entries = BTreeMap::new();
+    for (name, value) in fields {
+        match value {
+            serde_json::Value::Object(flags) => {
+                for (flag, value) in flags {
+                    entries.insert(format!("{name}.{flag}"), Some(value.to_string()));
+                }
+            }
+            value => {
+                entries.insert(name, Some(value.to_string()));
+            }
+        }
+    }
+    entries.remove("version");
+
+    // Constants that are not defined are not serialized, list them as well so that a diff shows
+    // where they are introduced.
+    let latest = serde_json::to_value(ProtocolConfig::get_for_version(ProtocolVersion::MAX))?;
+    if let serde_json::Value::Object(fields) = latest {
+        for (name, value) in fields {
+            if name != "version" && !value.is_object() {
+                entries.entry(name).or_insert(None);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+pub struct ProtocolConfigChange {
+    pub name: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+pub struct ProtocolConfigDiff {
+    pub from: ProtocolVersion,
+    pub to: ProtocolVersion,
+    pub changes: Vec<ProtocolConfigChange>,
+}
+
+impl Display for ProtocolConfigDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} changes from protocol version {} to {}",
+            self.changes.len(),
+            self.from.as_u64(),
+            self.to.as_u64()
+        )?;
+        for change in &self.changes {
+            writeln!(
+                f,
+                "  {}: {} -> {}",
+                change.name,
+                change.from.as_deref().unwrap_or(UNSET),
+                change.to.as_deref().unwrap_or(UNSET)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub fn diff_protocol_configs(
+    from: ProtocolVersion,
+    to: ProtocolVersion,
+) -> anyhow::Result<ProtocolConfigDiff> {
+    let mut from_entries = protocol_config_entries(from)?;
+    let to_entries = protocol_config_entries(to)?;
+    let mut changes = vec![];
+    for (name, to_value) in to_entries {
+        let from_value = from_entries.remove(&name).flatten();
+        if from_value != to_value {
+            changes.push(ProtocolConfigChange {
+                name,
+                from: from_value,
+                to: to_value,
+            });
+        }
+    }
+    // Entries of `from` that no longer exist in `to`.
+    for (name, from_value) in from_entries {
+        if from_value.is_some() {
+            changes.push(ProtocolConfigChange {
+                name,
+                from: from_value,
+                to: None,
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(ProtocolConfigDiff { from, to, changes })
+}
+
+pub struct ProtocolVersionCheck {
+    /// Versions supported by the node, as set in its config or the defaults of this binary.
+    pub supported: SupportedProtocolVersions,
+    /// Whether the supported versions are set in the config of the node.
+    pub overridden: bool,
+    pub epoch: EpochId,
+    /// Protocol version of the current epoch of the chain, in the db of the node.
+    pub current: ProtocolVersion,
+    pub errors: Vec<String>,
+    /// Changes from the current version to the latest version supported by the node, which it
+    /// votes for.
+    pub upgrade: Option<ProtocolConfigDiff>,
+}
+
+impl Display for ProtocolVersionCheck {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Supported protocol versions: {} to {}{}",
+            self.supported.min.as_u64(),
+            self.supported.max.as_u64(),
+            if self.overridden {
+                " (set in the node config)"
+            } else {
+                ""
+            }
+        )?;
+        writeln!(
+            f,
+            "Protocol version of epoch {}: {}",
+            self.epoch,
+            self.current.as_u64()
+        )?;
+        for error in &self.errors {
+            writeln!(f, "Error: {error}")?;
+        }
+        if let Some(upgrade) = &self.upgrade {
+            write!(f, "{upgrade}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the protocol versions supported by the node of `config` against the current protocol
+/// version of the chain, as recorded at the start of the current epoch in the db of the node.
+pub fn check_node_protocol_versions(config: &NodeConfig) -> anyhow::Result<ProtocolVersionCheck> {
+    let tables = AuthorityPerpetualTables::open_readonly(&config.db_path().join("store"));
+    let epoch_start_configuration = tables
+        .epoch_start_configuration
+        .get(&())?
+        .ok_or_else(|| anyhow!("The node db has not started any epoch yet"))?;
+    let epoch_start_state = epoch_start_configuration.epoch_start_state();
+
+    let supported = config
+        .supported_protocol_versions
+        .unwrap_or(SupportedProtocolVersions::SYSTEM_DEFAULT);
+    let current = epoch_start_state.protocol_version();
+    let errors = supported_versions_errors(supported, current);
+    let upgrade = if errors.is_empty() && supported.max.as_u64() > current.as_u64() {
+        Some(diff_protocol_configs(current, supported.max)?)
+    } else {
+        None
+    };
+    Ok(ProtocolVersionCheck {
+        supported,
+        overridden: config.supported_protocol_versions.is_some(),
+        epoch: epoch_start_state.epoch(),
+        current,
+        errors,
+        upgrade,
+    })
+}
+
+fn supported_versions_errors(
+    supported: SupportedProtocolVersions,
+    current: ProtocolVersion,
+) -> Vec<String> {
+    let (min, max) = (supported.min.as_u64(), supported.max.as_u64());
+    let mut errors = vec![];
+    if min > max {
+        errors.push(format!(
+            "minimum version {min} is above maximum version {max}"
+        ));
+    }
+    if min < ProtocolVersion::MIN.as_u64() || max > ProtocolVersion::MAX.as_u64() {
+        errors.push(format!(
+            "versions {min} to {max} are not all supported by this binary, which supports \
+            versions {} to {}",
+            ProtocolVersion::MIN.as_u64(),
+            ProtocolVersion::MAX.as_u64()
+        ));
+    }
+    if !supported.is_version_supported(current) {
+        errors.push(format!(
+            "current version {} of the chain is not supported",
+            current.as_u64()
+        ));
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_config_entries() {
+        let entries = protocol_config_entries(ProtocolVersion::MAX).unwrap();
+        assert!(entries.contains_key("feature_flags.package_upgrades"));
+        assert!(!entries.contains_key("version"));
+
+        let diff = diff_protocol_configs(ProtocolVersion::MAX, ProtocolVersion::MAX).unwrap();
+        assert!(diff.changes.is_empty());
+
+        assert!(protocol_version(ProtocolVersion::MAX.as_u64() + 1).is_err());
+    }
+
+    #[test]
+    fn test_supported_versions_errors() {
+        let current = ProtocolVersion::MAX;
+        assert!(
+            supported_versions_errors(SupportedProtocolVersions::SYSTEM_DEFAULT, current)
+                .is_empty()
+        );
+
+        // The chain moved past the versions the node supports.
+        let outdated = SupportedProtocolVersions::new_for_testing(
+            ProtocolVersion::MIN.as_u64(),
+            ProtocolVersion::MIN.as_u64(),
+        );
+        let next = ProtocolVersion::new(ProtocolVersion::MIN.as_u64() + 1);
+        assert_eq!(supported_versions_errors(outdated, next).len(), 1);
+
+        // The node claims versions this binary does not know.
+        let unknown = SupportedProtocolVersions::new_for_testing(
+            ProtocolVersion::MIN.as_u64(),
+            ProtocolVersion::MAX.as_u64() + 1,
+        );
+        assert_eq!(supported_versions_errors(unknown, current).len(), 1);
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic replay of historical transactions.
+//!
+//! A transaction is re-executed locally, in the Move VM, against the state it originally executed
+//! on, read from the database of a fullnode (or an archival node, for transactions whose input
+//! object versions were pruned elsewhere):
+//! - input objects are read at the versions recorded in the transaction and in its effects,
+//! - child objects and packages are read at their latest version before the transaction's lamport
+//!   version, which is the state the transaction observed when it executed,
+//! - the protocol version and epoch start timestamp come from the system state at that point.
+//!
+//! The resulting effects are then compared with the effects stored on chain. A mismatch means
+//! either a non-deterministic execution bug or, when replaying under a different protocol version,
+//! a behavior or gas change introduced by that version.
+
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::ModuleId;
+use sui_adapter::{adapter, execution_engine, execution_mode};
+use sui_core::authority::authority_store_tables::{
+    AuthorityPerpetualTables, AuthorityPerpetualTablesReadOnly,
+};
+use sui_core::checkpoints::CheckpointStore;
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, TransactionDigest};
+use sui_types::epoch_data::EpochData;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::gas::{self, SuiCostTable, SuiGasStatus};
+use sui_types::message_envelope::Message;
+use sui_types::messages::{
+    InputObjectKind, InputObjects, TransactionDataAPI, TransactionEffects, TransactionEffectsAPI,
+};
+use sui_types::object::{Object, Owner};
+use sui_types::storage::{
+    get_module_by_id, BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync,
+};
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
+use sui_types::temporary_store::TemporaryStore;
+use typed_store::traits::Map;
+
+/// A view of the objects of a node database as they were right before a transaction executed,
+/// i.e. the latest version of every object below the transaction's lamport version.
+#[derive(Clone, Copy)]
+struct HistoricalStore<'a> {
+    tables: &'a AuthorityPerpetualTablesReadOnly,
+    max_version: SequenceNumber,
+}
+
+impl HistoricalStore<'_> {
+    fn get_entry(&self, object_id: &ObjectID) -> SuiResult<Option<(ObjectRef, Option<Object>)>> {
+        self.tables
+            .get_latest_entry_lt_or_eq_version(object_id, self.max_version)
+    }
+}
+
+impl BackingPackageStore for HistoricalStore<'_> {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.get_object(package_id)
+    }
+}
+
+impl ChildObjectResolver for HistoricalStore<'_> {
+    fn read_child_object(&self, parent: &ObjectID, child: &ObjectID) -> SuiResult<Option<Object>> {
+        let Some(child_object) = self.get_object(child)? else {
+            return Ok(None);
+        };
+        if child_object.owner != Owner::ObjectOwner((*parent).into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: *parent,
+                actual_owner: child_object.owner,
+            });
+        }
+        Ok(Some(child_object))
+    }
+}
+
+impl ParentSync for HistoricalStore<'_> {
+    fn get_latest_parent_entry_ref(&self, object_id: ObjectID) -> SuiResult<Option<ObjectRef>> {
+        Ok(self
+            .get_entry(&object_id)?
+            .map(|(object_ref, _)| object_ref))
+    }
+}
+
+impl ObjectStore for HistoricalStore<'_> {
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        Ok(self.get_entry(object_id)?.and_then(|(_, object)| object))
+    }
+}
+
+impl GetModule for HistoricalStore<'_> {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        get_module_by_id(self, id)
+    }
+}
+
+/// The outcome of replaying a transaction.
+pub struct ReplayResult {
+    pub digest: TransactionDigest,
+    pub epoch: u64,
+    pub protocol_version: ProtocolVersion,
+    /// Whether the protocol version differs from the one the transaction originally executed with.
+    pub protocol_version_overridden: bool,
+    pub expected_effects: TransactionEffects,
+    pub replayed_effects: TransactionEffects,
+    /// The error the execution failed with, if any, with more detail than the effects status.
+    pub execution_error: Option<String>,
+}
+
+impl ReplayResult {
+    pub fn effects_match(&self) -> bool {
+        self.expected_effects.digest() == self.replayed_effects.digest()
+    }
+
+    /// The fields of the effects that differ, with their expected and replayed values.
+    pub fn effects_diff(&self) -> Vec<(&'static str, String, String)> {
+        let (expected, replayed) = (&self.expected_effects, &self.replayed_effects);
+        let mut diff = vec![];
+        let mut compare = |field: &'static str, expected: &dyn Debug, replayed: &dyn Debug| {
+            let (expected, replayed) = (format!("{expected:?}"), format!("{replayed:?}"));
+            if expected != replayed {
+                diff.push((field, expected, replayed));
+            }
+        };
+        compare("status", expected.status(), replayed.status());
+        compare(
+            "gas_cost_summary",
+            expected.gas_cost_summary(),
+            replayed.gas_cost_summary(),
+        );
+        compare(
+            "modified_at_versions",
+            &as_set(expected.modified_at_versions()),
+            &as_set(replayed.modified_at_versions()),
+        );
+        compare(
+            "shared_objects",
+            &as_set(expected.shared_objects()),
+            &as_set(replayed.shared_objects()),
+        );
+        compare(
+            "created",
+            &as_set(expected.created()),
+            &as_set(replayed.created()),
+        );
+        compare(
+            "mutated",
+            &as_set(expected.mutated()),
+            &as_set(replayed.mutated()),
+        );
+        compare(
+            "unwrapped",
+            &as_set(expected.unwrapped()),
+            &as_set(replayed.unwrapped()),
+        );
+        compare(
+            "deleted",
+            &as_set(expected.deleted()),
+            &as_set(replayed.deleted()),
+        );
+        compare(
+            "unwrapped_then_deleted",
+            &as_set(expected.unwrapped_then_deleted()),
+            &as_set(replayed.unwrapped_then_deleted()),
+        );
+        compare(
+            "wrapped",
+            &as_set(expected.wrapped()),
+            &as_set(replayed.wrapped()),
+        );
+        compare("gas_object", expected.gas_object(), replayed.gas_object());
+        compare(
+            "events_digest",
+            &expected.events_digest(),
+            &replayed.events_digest(),
+        );
+        compare(
+            "dependencies",
+            &as_set(expected.dependencies()),
+            &as_set(replayed.dependencies()),
+        );
+        diff
+    }
+}
+
+/// Compares lists regardless of their order.
+fn as_set<T: Ord>(items: &[T]) -> BTreeSet<&T> {
+    items.iter().collect()
+}
+
+impl Display for ReplayResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Transaction: {}", self.digest)?;
+        writeln!(
+            f,
+            "Epoch: {}, protocol version: {}{}",
+            self.epoch,
+            self.protocol_version.as_u64(),
+            if self.protocol_version_overridden {
+                " (overridden)"
+            } else {
+                ""
+            }
+        )?;
+        if let Some(error) = &self.execution_error {
+            writeln!(f, "Execution error: {error}")?;
+        }
+        let (expected, replayed) = (
+            self.expected_effects.gas_cost_summary(),
+            self.replayed_effects.gas_cost_summary(),
+        );
+        writeln!(f, "{: <26} {: >20} {: >20}", "Gas", "On chain", "Replayed")?;
+        for (name, expected, replayed) in [
+            (
+                "computation cost",
+                expected.computation_cost,
+                replayed.computation_cost,
+            ),
+            ("storage cost", expected.storage_cost, replayed.storage_cost),
+            (
+                "storage rebate",
+                expected.storage_rebate,
+                replayed.storage_rebate,
+            ),
+            (
+                "non-refundable storage fee",
+                expected.non_refundable_storage_fee,
+                replayed.non_refundable_storage_fee,
+            ),
+        ] {
+            writeln!(f, "{name: <26} {expected: >20} {replayed: >20}")?;
+        }
+
+        if self.effects_match() {
+            write!(f, "Replayed effects match the on-chain effects")
+        } else {
+            writeln!(f, "Replayed effects differ from the on-chain effects:")?;
+            for (field, expected, replayed) in self.effects_diff() {
+                writeln!(f, "  {field}:")?;
+                writeln!(f, "    on chain: {expected}")?;
+                writeln!(f, "    replayed: {replayed}")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Re-executes the transaction `digest` from the node database at `db_path`, optionally under a
+/// different protocol version than the one it originally executed with.
+pub fn replay_transaction(
+    db_path: &Path,
+    digest: TransactionDigest,
+    protocol_version: Option<ProtocolVersion>,
+) -> anyhow::Result<ReplayResult> {
+    let tables = AuthorityPerpetualTables::open_readonly(&db_path.join("store"));
+    let checkpoint_store = CheckpointStore::open_readonly(&db_path.join("checkpoints"));
+
+    let transaction = tables
+        .transactions
+        .get(&digest)?
+        .ok_or_else(|| anyhow!("Transaction {digest} not found"))?;
+    let effects_digest = tables
+        .executed_effects
+        .get(&digest)?
+        .ok_or_else(|| anyhow!("Transaction {digest} was not executed by this node"))?;
+    let expected_effects = tables
+        .effects
+        .get(&effects_digest)?
+        .ok_or_else(|| anyhow!("Effects {effects_digest:?} of transaction {digest} not found"))?;
+    let transaction_data = transaction.inner().transaction_data();
+
+    // Every object written or deleted by a transaction ends up at its lamport version, and
+    // everything it read was below it.
+    let lamport_version = expected_effects
+        .all_changed_objects()
+        .into_iter()
+        .map(|(object_ref, _, _)| object_ref.1)
+        .chain(
+            expected_effects
+                .all_deleted()
+                .into_iter()
+                .map(|(object_ref, _)| object_ref.1),
+        )
+        .max()
+        .ok_or_else(|| anyhow!("Transaction {digest} did not change any object"))?;
+    let store = HistoricalStore {
+        tables: &tables,
+        max_version: SequenceNumber::from_u64(lamport_version.value() - 1),
+    };
+
+    let mut input_objects = vec![];
+    for kind in transaction_data.input_objects()? {
+        let object = match &kind {
+            InputObjectKind::MovePackage(id) => store.get_package_object(id)?,
+            InputObjectKind::ImmOrOwnedMoveObject((id, version, _)) => {
+                tables.get_object_by_key(id, *version)?
+            }
+            InputObjectKind::SharedMoveObject { id, .. } => {
+                let (_, version, _) = expected_effects
+                    .shared_objects()
+                    .iter()
+                    .find(|(shared_id, _, _)| shared_id == id)
+                    .ok_or_else(|| anyhow!("Version of shared object {id} not found in effects"))?;
+                tables.get_object_by_key(id, *version)?
+            }
+        };
+        let object = object.ok_or_else(|| {
+            anyhow!(
+                "Input object {:?} not found, it may have been pruned from this database",
+                kind.object_id()
+            )
+        })?;
+        input_objects.push((kind, object));
+    }
+    let input_objects = InputObjects::new(input_objects);
+
+    let system_state = get_sui_system_state(&store)?;
+    let epoch = system_state.epoch();
+    if epoch != expected_effects.executed_epoch() {
+        return Err(anyhow!(
+            "System state is at epoch {epoch}, but transaction {digest} executed in epoch {}",
+            expected_effects.executed_epoch()
+        ));
+    }
+    let original_protocol_version = ProtocolVersion::new(system_state.protocol_version());
+    let protocol_version = protocol_version.unwrap_or(original_protocol_version);
+    if protocol_version > ProtocolVersion::MAX {
+        return Err(anyhow!(
+            "Protocol version {} is not supported by this binary, which supports up to {}",
+            protocol_version.as_u64(),
+            ProtocolVersion::MAX.as_u64()
+        ));
+    }
+    let protocol_config = ProtocolConfig::get_for_version(protocol_version);
+
+    // The epoch digest is the digest of the last checkpoint of the previous epoch.
+    let epoch_digest = match epoch.checked_sub(1) {
+        Some(previous_epoch) => {
+            let sequence_number = checkpoint_store
+                .epoch_last_checkpoint_map
+                .get(&previous_epoch)?
+                .ok_or_else(|| anyhow!("Last checkpoint of epoch {previous_epoch} not found"))?;
+            checkpoint_store
+                .certified_checkpoints
+                .get(&sequence_number)?
+                .ok_or_else(|| anyhow!("Checkpoint {sequence_number} not found"))?
+                .inner()
+                .digest()
+        }
+        None => Default::default(),
+    };
+    let epoch_data = EpochData::new(epoch, system_state.epoch_start_timestamp_ms(), epoch_digest);
+
+    let gas_status = if transaction_data.kind().is_system_tx() {
+        SuiGasStatus::new_unmetered()
+    } else {
+        gas::start_gas_metering(
+            transaction_data.gas_budget(),
+            transaction_data.gas_price(),
+            protocol_config.storage_gas_price(),
+            SuiCostTable::new(&protocol_config),
+        )?
+    };
+
+    let move_vm = Arc::new(adapter::new_move_vm(
+        sui_framework::natives::all_natives(),
+        &protocol_config,
+    )?);
+    let shared_object_refs = input_objects.filter_shared_objects();
+    let transaction_dependencies = input_objects.transaction_dependencies();
+    let temporary_store = TemporaryStore::new(store, input_objects, digest, &protocol_config);
+    let (kind, signer, gas) = transaction_data.execution_parts();
+    let (_, replayed_effects, execution_result) =
+        execution_engine::execute_transaction_to_effects::<execution_mode::Normal, _>(
+            shared_object_refs,
+            temporary_store,
+            kind,
+            signer,
+            &gas,
+            digest,
+            transaction_dependencies,
+            &move_vm,
+            gas_status,
+            &epoch_data,
+            &protocol_config,
+        );
+
+    Ok(ReplayResult {
+        digest,
+        epoch,
+        protocol_version,
+        protocol_version_overridden: protocol_version != original_protocol_version,
+        expected_effects,
+        replayed_effects,
+        execution_error: execution_result.err().map(|e| format!("{e:?}")),
+    })
+}
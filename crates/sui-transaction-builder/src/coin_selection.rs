@@ -0,0 +1,152 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coin selection: which coins of an address pay an amount, for gas or payments.
+//!
+//! A [CoinSelector] picks coins by their balances. The transaction builder uses [LargestFirst]
+//! unless configured with another strategy:
+//! - [LargestFirst] pays with the fewest coins.
+//! - [BranchAndBound] looks for coins summing exactly to the amount, up to a tolerance, so that
+//!   no change is left, and falls back to the largest coins.
+//! - [ConsolidateDust] pays with the largest coins and adds the smallest ones below a threshold,
+//!   merging them into the payment.
+
+use std::cmp::Reverse;
+
+/// Picks coins of an address to pay an amount.
+pub trait CoinSelector: Send + Sync {
+    /// The indexes in `balances` of at most `max_coins` coins whose total balance is at least
+    /// `amount`, `None` if there are none.
+    fn select(&self, balances: &[u64], amount: u128, max_coins: usize) -> Option<Vec<usize>>;
+}
+
+/// Selects the coins with the largest balances until they cover the amount.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LargestFirst;
+
+impl CoinSelector for LargestFirst {
+    fn select(&self, balances: &[u64], amount: u128, max_coins: usize) -> Option<Vec<usize>> {
+        let mut selected = vec![];
+        let mut total = 0u128;
+        for index in largest_first(balances) {
+            if total >= amount || selected.len() == max_coins {
+                break;
+            }
+            total += balances[index] as u128;
+            selected.push(index);
+        }
+        (total >= amount).then_some(selected)
+    }
+}
+
+/// Selects coins whose total balance is between the amount and the amount plus `tolerance`,
+/// found by a depth-first search over the coins from the largest, bounded to `max_tries` steps.
+/// Falls back to [LargestFirst] when there are none.
+#[derive(Clone, Copy, Debug)]
+pub struct BranchAndBound {
+    pub tolerance: u64,
+    pub max_tries: usize,
+}
+
+impl Default for BranchAndBound {
+    fn default() -> Self {
+        Self {
+            tolerance: 0,
+            max_tries: 100_000,
+        }
+    }
+}
+
+impl CoinSelector for BranchAndBound {
+    fn select(&self, balances: &[u64], amount: u128, max_coins: usize) -> Option<Vec<usize>> {
+        let order = largest_first(balances);
+        // The total balance of the coins from every position of the search on.
+        let mut remaining = vec![0u128; order.len() + 1];
+        for (position, index) in order.iter().enumerate().rev() {
+            remaining[position] = remaining[position + 1] + balances[*index] as u128;
+        }
+        let mut search = Search {
+            balances,
+            order: &order,
+            remaining: &remaining,
+            amount,
+            upper_bound: amount + self.tolerance as u128,
+            max_coins,
+            tries_left: self.max_tries,
+            selected: vec![],
+        };
+        if search.search(0, 0) {
+            Some(search.selected)
+        } else {
+            LargestFirst.select(balances, amount, max_coins)
+        }
+    }
+}
+
+struct Search<'a> {
+    balances: &'a [u64],
+    order: &'a [usize],
+    remaining: &'a [u128],
+    amount: u128,
+    upper_bound: u128,
+    max_coins: usize,
+    tries_left: usize,
+    selected: Vec<usize>,
+}
+
+impl Search<'_> {
+    /// Whether coins from `position` on complete the selection of `total` within the bounds,
+    /// leaving them selected if so.
+    fn search(&mut self, position: usize, total: u128) -> bool {
+        if total >= self.amount {
+            return total <= self.upper_bound;
+        }
+        if position == self.order.len()
+            || self.selected.len() == self.max_coins
+            || total + self.remaining[position] < self.amount
+            || self.tries_left == 0
+        {
+            return false;
+        }
+        self.tries_left -= 1;
+
+        let index = self.order[position];
+        let with_coin = total + self.balances[index] as u128;
+        if with_coin <= self.upper_bound {
+            self.selected.push(index);
+            if self.search(position + 1, with_coin) {
+                return true;
+            }
+            self.selected.pop();
+        }
+        self.search(position + 1, total)
+    }
+}
+
+/// Selects coins with [LargestFirst], then adds up to `max_dust_coins` of the coins with a
+/// balance below `dust_threshold`, the smallest first.
+#[derive(Clone, Copy, Debug)]
+pub struct ConsolidateDust {
+    pub dust_threshold: u64,
+    pub max_dust_coins: usize,
+}
+
+impl CoinSelector for ConsolidateDust {
+    fn select(&self, balances: &[u64], amount: u128, max_coins: usize) -> Option<Vec<usize>> {
+        let mut selected = LargestFirst.select(balances, amount, max_coins)?;
+        let mut dust: Vec<_> = (0..balances.len())
+            .filter(|index| balances[*index] < self.dust_threshold && !selected.contains(index))
+            .collect();
+        dust.sort_by_key(|index| balances[*index]);
+        let room = max_coins.saturating_sub(selected.len());
+        selected.extend(dust.into_iter().take(room.min(self.max_dust_coins)));
+        Some(selected)
+    }
+}
+
+/// The indexes of `balances` from the largest balance to the smallest.
+fn largest_first(balances: &[u64]) -> Vec<usize> {
+    let mut order: Vec<_> = (0..balances.len()).collect();
+    order.sort_by_key(|index| Reverse(balances[*index]));
+    order
+}
@@ -16,7 +16,7 @@ use move_core_types::language_storage::TypeTag;
 use std::result::Result;
 use sui_adapter::adapter::{resolve_and_type_check, CheckCallArg};
 use sui_adapter::execution_mode::ExecutionMode;
-use sui_json::{resolve_move_function_args, ResolvedCallArg, SuiJsonValue};
+use sui_json::{resolve_partial_move_function_args, ResolvedCallArg, SuiJsonValue};
 use sui_json_rpc_types::{
     CheckpointId, ObjectsPage, RPCTransactionRequestParams, SuiData, SuiObjectDataOptions,
     SuiObjectResponse, SuiObjectResponseQuery, SuiRawData, SuiTypeTag,
@@ -27,7 +27,8 @@ use sui_types::error::UserInputError;
 use sui_types::gas_coin::GasCoin;
 use sui_types::governance::{ADD_STAKE_MUL_COIN_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages::{
-    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionKind,
+    Argument, CallArg, Command, GasData, InputObjectKind, ObjectArg, ProgrammableTransaction,
+    TransactionData, TransactionKind,
 };
 use sui_types::move_package::MovePackage;
 use sui_types::object::{Object, Owner};
@@ -38,6 +39,10 @@ use sui_types::{
     SUI_SYSTEM_STATE_OBJECT_SHARED_VERSION,
 };
 
+use crate::coin_selection::{CoinSelector, LargestFirst};
+
+pub mod coin_selection;
+
 #[async_trait]
 pub trait DataReader {
     async fn get_owned_objects(
@@ -58,17 +63,33 @@ pub trait DataReader {
     async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error>;
 }
 
+/// An argument of a Move call in a programmable transaction: a JSON value, resolved and checked
+/// against the signature of the function, or the result of an earlier command of the transaction.
+pub enum MoveCallArg {
+    Json(SuiJsonValue),
+    Argument(Argument),
+}
+
 #[derive(Clone)]
 pub struct TransactionBuilder<Mode: ExecutionMode>(
     Arc<dyn DataReader + Sync + Send>,
+    Arc<dyn CoinSelector>,
     PhantomData<Mode>,
 );
 
 impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
     pub fn new(data_reader: Arc<dyn DataReader + Sync + Send>) -> Self {
-        Self(data_reader, PhantomData)
+        Self(data_reader, Arc::new(LargestFirst), PhantomData)
     }
 
+    /// Uses `coin_selector` instead of [LargestFirst] to select the gas coins of transactions.
+    pub fn with_coin_selector(mut self, coin_selector: Arc<dyn CoinSelector>) -> Self {
+        self.1 = coin_selector;
+        self
+    }
+
+    /// The gas coins of a transaction: `input_gas` if set, otherwise coins of `signer` other than
+    /// `input_objects` covering the budget, picked by the coin selector of the builder.
     async fn select_gas(
         &self,
         signer: SuiAddress,
@@ -76,40 +97,35 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         budget: u64,
         input_objects: Vec<ObjectID>,
         gas_price: u64,
-    ) -> Result<ObjectRef, anyhow::Error> {
+    ) -> Result<Vec<ObjectRef>, anyhow::Error> {
         if let Some(gas) = input_gas {
-            self.get_object_ref(gas).await
-        } else {
-            let objs = self
+            return Ok(vec![self.get_object_ref(gas).await?]);
+        }
+        let gas_coin_type = GasCoin::type_().to_string();
+        let mut coins = vec![];
+        let mut cursor = None;
+        loop {
+            let page = self
                 .0
                 .get_owned_objects(
                     signer,
                     Some(SuiObjectResponseQuery::new_with_options(
-                        SuiObjectDataOptions::full_content(),
+                        SuiObjectDataOptions::new().with_type().with_bcs(),
                     )),
-                    None,
+                    cursor,
                     None,
                     None,
                 )
                 .await?;
-            let gas_objs = objs.data.into_iter().filter(|obj| {
-                let obj_data = obj.clone().into_object();
-                match obj_data {
-                    Result::Ok(o) => o.type_.unwrap().to_string() == GasCoin::type_().to_string(),
-                    _ => false,
+            for obj in page.data {
+                let Result::Ok(obj) = obj.into_object() else {
+                    continue;
+                };
+                let is_gas_coin =
+                    matches!(&obj.type_, Some(type_) if type_.to_string() == gas_coin_type);
+                if !is_gas_coin || input_objects.contains(&obj.object_id) {
+                    continue;
                 }
-            });
-            let required_gas_amount = (budget as u128) * (gas_price as u128);
-
-            for obj in gas_objs {
-                let response = self
-                    .0
-                    .get_object_with_options(
-                        obj.clone().into_object()?.object_id,
-                        SuiObjectDataOptions::new().with_bcs(),
-                    )
-                    .await?;
-                let obj = response.object()?;
                 let gas: GasCoin = bcs::from_bytes(
                     &obj.bcs
                         .as_ref()
@@ -118,14 +134,30 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
                         .ok_or_else(|| anyhow!("Cannot parse move object to gas object"))?
                         .bcs_bytes,
                 )?;
-                if !input_objects.contains(&obj.object_id)
-                    && (gas.value() as u128) >= required_gas_amount
-                {
-                    return Ok(obj.object_ref());
-                }
+                coins.push((obj.object_ref(), gas.value()));
+            }
+            match page.next_cursor {
+                Some(next_cursor) if page.has_next_page => cursor = Some(next_cursor),
+                _ => break,
             }
-            Err(anyhow!("Cannot find gas coin for signer address [{signer}] with amount sufficient for the required gas amount [{required_gas_amount}]."))
         }
+
+        let required_gas_amount = (budget as u128) * (gas_price as u128);
+        let max_coins = ProtocolConfig::get_for_min_version().max_gas_payment_objects() as usize;
+        let balances: Vec<_> = coins.iter().map(|(_, balance)| *balance).collect();
+        let selected = self
+            .1
+            .select(&balances, required_gas_amount, max_coins)
+            .ok_or_else(|| {
+                let total: u128 = balances.iter().map(|balance| *balance as u128).sum();
+                anyhow!(
+                    "Cannot find gas coins for signer address [{signer}] with amount sufficient \
+                     for the required gas amount [{required_gas_amount}]: {} gas coins with a \
+                     total balance of [{total}], at most {max_coins} can pay for gas.",
+                    balances.len()
+                )
+            })?;
+        Ok(selected.into_iter().map(|index| coins[index].0).collect())
     }
 
     pub async fn transfer_object(
@@ -144,7 +176,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             .select_gas(signer, gas, gas_budget, vec![object_id], gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(TransactionData::new_with_gas_coins(
             TransactionKind::programmable(builder.finish()),
             signer,
             gas,
@@ -206,7 +238,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             .select_gas(signer, gas, gas_budget, input_coins, gas_price)
             .await?;
 
-        TransactionData::new_pay(
+        TransactionData::new_pay_with_gas_coins(
             signer, coin_refs, recipients, amounts, gas, gas_budget, gas_price,
         )
     }
@@ -301,7 +333,19 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             call_args,
         )
         .await?;
-        let pt = builder.finish();
+        self.programmable_transaction(signer, builder.finish(), gas, gas_budget)
+            .await
+    }
+
+    /// Transaction data for the programmable transaction `pt`, paid for with `gas`, or else with a
+    /// coin of the signer that covers the budget and is not an input of the transaction.
+    pub async fn programmable_transaction(
+        &self,
+        signer: SuiAddress,
+        pt: ProgrammableTransaction,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
         let input_objects = pt
             .input_objects()?
             .iter()
@@ -315,7 +359,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             .select_gas(signer, gas, gas_budget, input_objects, gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(TransactionData::new_with_gas_coins(
             TransactionKind::programmable(pt),
             signer,
             gas,
@@ -324,6 +368,41 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         ))
     }
 
+    /// A programmable transaction of `signer` whose gas is paid by `sponsor`, from the `gas` coin
+    /// of the sponsor if provided.
+    pub async fn sponsored_programmable_transaction(
+        &self,
+        signer: SuiAddress,
+        sponsor: SuiAddress,
+        pt: ProgrammableTransaction,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionData> {
+        let input_objects = pt
+            .input_objects()?
+            .iter()
+            .flat_map(|obj| match obj {
+                InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        let gas_price = self.0.get_reference_gas_price().await?;
+        let gas = self
+            .select_gas(sponsor, gas, gas_budget, input_objects, gas_price)
+            .await?;
+
+        Ok(TransactionData::new_with_gas_data(
+            TransactionKind::programmable(pt),
+            signer,
+            GasData {
+                payment: gas,
+                owner: sponsor,
+                price: gas_price,
+                budget: gas_budget,
+            },
+        ))
+    }
+
     pub async fn single_move_call(
         &self,
         builder: &mut ProgrammableTransactionBuilder,
@@ -333,6 +412,23 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         type_args: Vec<SuiTypeTag>,
         call_args: Vec<SuiJsonValue>,
     ) -> anyhow::Result<()> {
+        let call_args = call_args.into_iter().map(MoveCallArg::Json).collect();
+        self.programmable_move_call(builder, package, module, function, type_args, call_args)
+            .await?;
+        Ok(())
+    }
+
+    /// Adds a Move call to `builder`, returning its result. Arguments that are results of earlier
+    /// commands are only type checked when the transaction is executed.
+    pub async fn programmable_move_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        package: ObjectID,
+        module: &str,
+        function: &str,
+        type_args: Vec<SuiTypeTag>,
+        call_args: Vec<MoveCallArg>,
+    ) -> anyhow::Result<Argument> {
         let module = Identifier::from_str(module)?;
         let function = Identifier::from_str(function)?;
 
@@ -347,10 +443,9 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             )
             .await?;
 
-        builder.command(Command::move_call(
+        Ok(builder.command(Command::move_call(
             package, module, function, type_args, call_args,
-        ));
-        Ok(())
+        )))
     }
 
     async fn get_object_arg(
@@ -389,7 +484,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         module: &Identifier,
         function: &Identifier,
         type_args: &[TypeTag],
-        json_args: Vec<SuiJsonValue>,
+        call_args: Vec<MoveCallArg>,
     ) -> Result<Vec<Argument>, anyhow::Error> {
         let object = self
             .0
@@ -408,7 +503,19 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             package.linkage_table,
         )?;
 
-        let json_args_and_tokens = resolve_move_function_args(
+        let mut json_args = Vec::with_capacity(call_args.len());
+        let mut results = Vec::new();
+        for arg in call_args {
+            match arg {
+                MoveCallArg::Json(value) => json_args.push(Some(value)),
+                MoveCallArg::Argument(argument) => {
+                    json_args.push(None);
+                    results.push(argument);
+                }
+            }
+        }
+
+        let json_args_and_tokens = resolve_partial_move_function_args(
             &package,
             module.clone(),
             function.clone(),
@@ -420,11 +527,12 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         let mut objects = BTreeMap::new();
         for (arg, expected_type) in json_args_and_tokens {
             check_args.push(match arg {
-                ResolvedCallArg::Object(id) => CheckCallArg::Object(
+                None => None,
+                Some(ResolvedCallArg::Object(id)) => Some(CheckCallArg::Object(
                     self.get_object_arg(id, &mut objects, expected_type).await?,
-                ),
-                ResolvedCallArg::Pure(p) => CheckCallArg::Pure(p),
-                ResolvedCallArg::ObjVec(v) => {
+                )),
+                Some(ResolvedCallArg::Pure(p)) => Some(CheckCallArg::Pure(p)),
+                Some(ResolvedCallArg::ObjVec(v)) => {
                     let mut object_ids = vec![];
                     for id in v {
                         object_ids.push(
@@ -432,27 +540,36 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
                                 .await?,
                         );
                     }
-                    CheckCallArg::ObjVec(object_ids)
+                    Some(CheckCallArg::ObjVec(object_ids))
                 }
             })
         }
-        let compiled_module = package.deserialize_module(module, VERSION_MAX)?;
 
-        // TODO set the Mode from outside?
-        resolve_and_type_check::<Mode>(
-            &objects,
-            &compiled_module,
-            function,
-            type_args,
-            check_args.clone(),
-            false,
-        )?;
+        // The types of the results of earlier commands are not known until execution, so the call
+        // can only be checked up front when all of its arguments are inputs.
+        if let Some(check_args) = check_args.iter().cloned().collect::<Option<Vec<_>>>() {
+            let compiled_module = package.deserialize_module(module, VERSION_MAX)?;
+
+            // TODO set the Mode from outside?
+            resolve_and_type_check::<Mode>(
+                &objects,
+                &compiled_module,
+                function,
+                type_args,
+                check_args,
+                false,
+            )?;
+        }
+        let mut results = results.into_iter();
         let args = check_args
             .into_iter()
             .map(|check_arg| match check_arg {
-                CheckCallArg::Pure(bytes) => builder.input(CallArg::Pure(bytes)),
-                CheckCallArg::Object(obj) => builder.input(CallArg::Object(obj)),
-                CheckCallArg::ObjVec(objs) => builder.make_obj_vec(objs),
+                Some(CheckCallArg::Pure(bytes)) => builder.input(CallArg::Pure(bytes)),
+                Some(CheckCallArg::Object(obj)) => builder.input(CallArg::Object(obj)),
+                Some(CheckCallArg::ObjVec(objs)) => builder.make_obj_vec(objs),
+                None => Ok(results
+                    .next()
+                    .expect("one result per argument that is not JSON")),
             })
             .collect::<Result<_, _>>()?;
         Ok(args)
@@ -470,7 +587,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         let gas = self
             .select_gas(sender, gas, gas_budget, vec![], gas_price)
             .await?;
-        Ok(TransactionData::new_module(
+        Ok(TransactionData::new_module_with_gas_coins(
             sender,
             gas,
             compiled_modules,
@@ -504,7 +621,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         let cap_owner = upgrade_cap
             .owner
             .ok_or_else(|| anyhow!("Unable to determine ownership of upgrade capability"))?;
-        TransactionData::new_upgrade(
+        TransactionData::new_upgrade_with_gas_coins(
             sender,
             gas,
             package_id,
@@ -540,7 +657,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             .select_gas(signer, gas, gas_budget, vec![coin_object_id], gas_price)
             .await?;
 
-        TransactionData::new_move_call(
+        TransactionData::new_move_call_with_gas_coins(
             signer,
             SUI_FRAMEWORK_OBJECT_ID,
             coin::PAY_MODULE_NAME.to_owned(),
@@ -578,7 +695,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             .select_gas(signer, gas, gas_budget, vec![coin_object_id], gas_price)
             .await?;
 
-        TransactionData::new_move_call(
+        TransactionData::new_move_call_with_gas_coins(
             signer,
             SUI_FRAMEWORK_OBJECT_ID,
             coin::PAY_MODULE_NAME.to_owned(),
@@ -623,7 +740,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             )
             .await?;
 
-        TransactionData::new_move_call(
+        TransactionData::new_move_call_with_gas_coins(
             signer,
             SUI_FRAMEWORK_OBJECT_ID,
             coin::PAY_MODULE_NAME.to_owned(),
@@ -687,7 +804,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             .select_gas(signer, gas, gas_budget, inputs, gas_price)
             .await?;
 
-        Ok(TransactionData::new(
+        Ok(TransactionData::new_with_gas_coins(
             TransactionKind::programmable(pt),
             signer,
             gas,
@@ -762,11 +879,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
             builder.finish()
         };
         Ok(TransactionData::new_programmable(
-            signer,
-            vec![gas],
-            pt,
-            gas_budget,
-            gas_price,
+            signer, gas, pt, gas_budget, gas_price,
         ))
     }
 
@@ -782,7 +895,7 @@ impl<Mode: ExecutionMode> TransactionBuilder<Mode> {
         let gas = self
             .select_gas(signer, gas, gas_budget, vec![], gas_price)
             .await?;
-        TransactionData::new_move_call(
+        TransactionData::new_move_call_with_gas_coins(
             signer,
             SUI_SYSTEM_PACKAGE_ID,
             SUI_SYSTEM_MODULE_NAME.to_owned(),
@@ -193,6 +193,28 @@ impl Committee {
             .collect()
     }
 
+    /// Like `shuffle_by_stake`, but every authority is equally likely to come first regardless of
+    /// its stake.
+    pub fn shuffle_uniformly(
+        &self,
+        // try these authorities first
+        preferences: Option<&BTreeSet<AuthorityName>>,
+        // only attempt from these authorities.
+        restrict_to: Option<&BTreeSet<AuthorityName>>,
+    ) -> Vec<AuthorityName> {
+        let (mut preferred, mut rest): (Vec<_>, Vec<_>) = self
+            .voting_rights
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| restrict_to.map_or(true, |restrict_to| restrict_to.contains(name)))
+            .partition(|name| preferences.map_or(false, |preferences| preferences.contains(name)));
+        let mut rng = ThreadRng::default();
+        preferred.shuffle(&mut rng);
+        rest.shuffle(&mut rng);
+        preferred.extend(rest);
+        preferred
+    }
+
     pub fn weight(&self, author: &AuthorityName) -> StakeUnit {
         match self.voting_rights.binary_search_by_key(author, |(a, _)| *a) {
             Err(_) => 0,
@@ -423,6 +445,40 @@ mod test {
         assert_eq!(0, res.len());
     }
 
+    #[test]
+    fn test_shuffle_uniformly() {
+        let (_, sec1): (_, AuthorityKeyPair) = get_key_pair();
+        let (_, sec2): (_, AuthorityKeyPair) = get_key_pair();
+        let (_, sec3): (_, AuthorityKeyPair) = get_key_pair();
+        let a1: AuthorityName = sec1.public().into();
+        let a2: AuthorityName = sec2.public().into();
+        let a3: AuthorityName = sec3.public().into();
+
+        let mut authorities = BTreeMap::new();
+        authorities.insert(a1, 1);
+        authorities.insert(a2, 1000);
+        authorities.insert(a3, 1);
+
+        let committee = Committee::new(0, authorities);
+
+        assert_eq!(committee.shuffle_uniformly(None, None).len(), 3);
+
+        // Authorities with a small stake come first about as often as the one with a large stake.
+        let a1_first = (0..1000)
+            .filter(|_| committee.shuffle_uniformly(None, None)[0] == a1)
+            .count();
+        assert!(a1_first > 200);
+
+        // preference always comes first
+        let pref = BTreeSet::from([a3]);
+        for _ in 0..100 {
+            assert_eq!(a3, committee.shuffle_uniformly(Some(&pref), None)[0]);
+        }
+
+        let restrict = BTreeSet::from([a1]);
+        assert_eq!(vec![a1], committee.shuffle_uniformly(None, Some(&restrict)));
+    }
+
     #[test]
     fn test_robust_value() {
         let (_, sec1): (_, AuthorityKeyPair) = get_key_pair();
@@ -183,6 +183,9 @@ pub enum UserInputError {
 
     #[error("Feature is not yet supported: {0}")]
     Unsupported(String),
+
+    #[error("Transaction is denied by this node: {}", error)]
+    TransactionDenied { error: String },
 }
 
 #[derive(
@@ -484,6 +487,18 @@ pub enum SuiError {
 
     #[error("Failed to perform file operation: {0}")]
     FileIOError(String),
+
+    #[error("Local {kind} execution exceeded the time limit of {limit_ms}ms")]
+    LocalExecutionTimeLimitExceeded { kind: String, limit_ms: u64 },
+
+    #[error("Invalid outputs for transaction {digest:?}: {error}")]
+    InvalidTransactionOutputs {
+        digest: TransactionDigest,
+        error: String,
+    },
+
+    #[error("Invalid data in archive: {error}")]
+    InvalidArchiveData { error: String },
 }
 
 #[repr(u64)]
@@ -635,6 +650,7 @@ impl SuiError {
             // Overload errors
             SuiError::TooManyTransactionsPendingExecution { .. } => (false, true),
             SuiError::TooManyTransactionsPendingOnObject { .. } => (false, true),
+            SuiError::LocalExecutionTimeLimitExceeded { .. } => (false, true),
             _ => (false, false),
         }
     }
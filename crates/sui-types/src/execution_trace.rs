@@ -0,0 +1,119 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured trace of the execution of a programmable transaction, returned by dry runs and
+//! dev-inspect executions on request.
+//!
+//! Gas is reported in computation gas units, before the gas price is applied. The Move VM does not
+//! expose the frames of the Move functions it interprets, so the gas of Move-to-Move calls is
+//! attributed to the command that made the outermost call. Native function calls are recorded
+//! individually under the command that made them.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::{ObjectID, SequenceNumber};
+use crate::storage::{DeleteKind, WriteKind};
+use crate::temporary_store::InnerTemporaryStore;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionTrace {
+    /// Gas charged for reading the input objects, before the first command.
+    pub object_read_gas: u64,
+    pub commands: Vec<CommandTrace>,
+    /// Gas charged after the commands for the storage of the written objects.
+    pub storage_gas: u64,
+    /// Objects written or deleted by the transaction, including the gas coin.
+    pub object_changes: Vec<ObjectChangeTrace>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTrace {
+    pub index: usize,
+    /// Name of the command, e.g. `MoveCall` or `SplitCoins`.
+    pub kind: String,
+    /// The function called, for `MoveCall` commands.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call: Option<MoveCallTrace>,
+    /// Gas used by the command, native function calls included.
+    pub gas_used: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub native_calls: Vec<NativeCallTrace>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveCallTrace {
+    pub package: ObjectID,
+    pub module: String,
+    pub function: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub type_arguments: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeCallTrace {
+    /// `address::module::function` of the native function.
+    pub function: String,
+    pub gas: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectChangeTrace {
+    pub object_id: ObjectID,
+    /// Version of the object after the transaction, or before it for deleted objects.
+    pub version: SequenceNumber,
+    pub kind: ObjectChangeKind,
+    /// Type of the object, `None` for packages and deleted objects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ObjectChangeKind {
+    Created,
+    Mutated,
+    Unwrapped,
+    Deleted,
+    Wrapped,
+    UnwrappedThenDeleted,
+}
+
+impl ExecutionTrace {
+    /// Records the objects written and deleted by the transaction, once it has been executed.
+    pub fn record_object_changes(&mut self, store: &InnerTemporaryStore) {
+        self.object_changes = store
+            .written
+            .iter()
+            .map(|(id, ((_, version, _), object, kind))| ObjectChangeTrace {
+                object_id: *id,
+                version: *version,
+                kind: match kind {
+                    WriteKind::Create => ObjectChangeKind::Created,
+                    WriteKind::Mutate => ObjectChangeKind::Mutated,
+                    WriteKind::Unwrap => ObjectChangeKind::Unwrapped,
+                },
+                object_type: object.type_().map(|type_| type_.to_string()),
+            })
+            .chain(
+                store
+                    .deleted
+                    .iter()
+                    .map(|(id, (version, kind))| ObjectChangeTrace {
+                        object_id: *id,
+                        version: *version,
+                        kind: match kind {
+                            DeleteKind::Normal => ObjectChangeKind::Deleted,
+                            DeleteKind::Wrap => ObjectChangeKind::Wrapped,
+                            DeleteKind::UnwrapThenDelete => ObjectChangeKind::UnwrappedThenDeleted,
+                        },
+                        object_type: None,
+                    }),
+            )
+            .collect();
+    }
+}
@@ -0,0 +1,326 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates test vectors of user signatures, for SDKs implemented outside of this repository to
+//! check that they derive keys and addresses, and sign and verify messages, exactly like Sui.
+//!
+//! There is one vector per signature scheme and intent scope. Each vector has the keys of the
+//! signers, the intent and BCS bytes of the signed message, the digest that is signed and the
+//! signature as serialized in transactions. Keys are derived from fixed seeds and all supported
+//! schemes sign deterministically, so the vectors are the same across runs.
+//!
+//! zkLogin signatures are not supported by this version of Sui, so they have no vectors.
+
+use clap::*;
+use fastcrypto::encoding::{Base64, Encoding, Hex};
+use fastcrypto::hash::HashFunction;
+use fastcrypto::traits::ToFromBytes;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Serialize, Serializer};
+use shared_crypto::intent::{AppId, Intent, IntentMessage, IntentScope, PersonalMessage};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+use sui_types::crypto::{
+    get_key_pair_from_rng, DefaultHash, EncodeDecodeBase64, PublicKey, Signature, SignatureScheme,
+    SuiKeyPair,
+};
+use sui_types::messages::TransactionData;
+use sui_types::multisig::{MultiSig, MultiSigPublicKey};
+use sui_types::signature::{AuthenticatorTrait, GenericSignature};
+
+const FILE_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/staged/signature_vectors.json",
+);
+
+const INTENT_SCOPES: [IntentScope; 7] = [
+    IntentScope::TransactionData,
+    IntentScope::TransactionEffects,
+    IntentScope::CheckpointSummary,
+    IntentScope::PersonalMessage,
+    IntentScope::SenderSignedTransaction,
+    IntentScope::ProofOfPossession,
+    IntentScope::HeaderDigest,
+];
+
+/// A message that is already BCS serialized, so that signing it does not serialize it again.
+struct SerializedMessage<'a>(&'a [u8]);
+
+impl Serialize for SerializedMessage<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // BCS serializes tuples without a length prefix.
+        let mut tuple = serializer.serialize_tuple(self.0.len())?;
+        for byte in self.0 {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureVector {
+    /// `ed25519`, `secp256k1`, `secp256r1` or `multisig`.
+    scheme: String,
+    intent_scope: String,
+    /// Keys of the signers, as Base64 `flag || private key`. A multisig is signed by all of them.
+    private_keys: Vec<String>,
+    /// Base64 `flag || public key`, or the Base64 BCS bytes of the multisig public key.
+    public_key: String,
+    address: SuiAddress,
+    /// Base64 BCS bytes of the intent.
+    intent: String,
+    /// Base64 BCS bytes of the signed message.
+    message: String,
+    /// Hex Blake2b-256 digest of the intent followed by the message, which is what is signed.
+    digest: String,
+    /// Base64 signature, serialized as in transactions.
+    signature: String,
+}
+
+fn key_pairs() -> Vec<SuiKeyPair> {
+    let mut rng = StdRng::from_seed([0; 32]);
+    vec![
+        SuiKeyPair::Ed25519(get_key_pair_from_rng(&mut rng).1),
+        SuiKeyPair::Secp256k1(get_key_pair_from_rng(&mut rng).1),
+        SuiKeyPair::Secp256r1(get_key_pair_from_rng(&mut rng).1),
+    ]
+}
+
+fn intent(scope: IntentScope) -> Intent {
+    let intent = Intent::default().with_scope(scope);
+    match scope {
+        IntentScope::HeaderDigest => intent.with_app_id(AppId::Narwhal),
+        _ => intent,
+    }
+}
+
+/// The message signed by `signer` with the given intent scope: a transfer of SUI sent by the
+/// signer for transaction data, and a fixed message otherwise.
+fn message(scope: IntentScope, signer: SuiAddress) -> Vec<u8> {
+    let text = b"Sui signature test vector".to_vec();
+    match scope {
+        IntentScope::TransactionData => bcs::to_bytes(&TransactionData::new_transfer_sui(
+            SuiAddress::ZERO,
+            signer,
+            Some(1_000),
+            (
+                ObjectID::ZERO,
+                SequenceNumber::from_u64(1),
+                ObjectDigest::new([0; 32]),
+            ),
+            10_000,
+            1_000,
+        )),
+        IntentScope::PersonalMessage => bcs::to_bytes(&PersonalMessage { message: text }),
+        _ => bcs::to_bytes(&text),
+    }
+    .unwrap()
+}
+
+fn digest(intent: &Intent, message: &[u8]) -> [u8; 32] {
+    let mut hasher = DefaultHash::default();
+    hasher.update(bcs::to_bytes(intent).unwrap());
+    hasher.update(message);
+    hasher.finalize().digest
+}
+
+fn multisig_public_key(key_pairs: &[SuiKeyPair]) -> MultiSigPublicKey {
+    let public_keys = key_pairs.iter().map(|key_pair| key_pair.public()).collect();
+    MultiSigPublicKey::new(public_keys, vec![1; key_pairs.len()], 2).unwrap()
+}
+
+fn sign(key_pairs: &[SuiKeyPair], multisig: bool, intent: &Intent, message: &[u8]) -> Vec<u8> {
+    let intent_message = IntentMessage::new(intent.clone(), SerializedMessage(message));
+    let signatures: Vec<_> = key_pairs
+        .iter()
+        .map(|key_pair| Signature::new_secure(&intent_message, key_pair))
+        .collect();
+    let signature = if multisig {
+        // Signed by the first two signers, which meets the threshold.
+        let signatures = signatures.into_iter().take(2).collect();
+        GenericSignature::MultiSig(
+            MultiSig::combine(signatures, multisig_public_key(key_pairs)).unwrap(),
+        )
+    } else {
+        GenericSignature::Signature(signatures.into_iter().next().unwrap())
+    };
+    signature.as_ref().to_vec()
+}
+
+fn vector(key_pairs: &[SuiKeyPair], multisig: bool, scope: IntentScope) -> SignatureVector {
+    let (scheme, public_key, address) = if multisig {
+        let public_key = multisig_public_key(key_pairs);
+        (
+            SignatureScheme::MultiSig.to_string(),
+            Base64::encode(bcs::to_bytes(&public_key).unwrap()),
+            SuiAddress::from(public_key),
+        )
+    } else {
+        let public_key = key_pairs[0].public();
+        (
+            public_key.scheme().to_string(),
+            public_key.encode_base64(),
+            SuiAddress::from(&public_key),
+        )
+    };
+    let intent = intent(scope);
+    let message = message(scope, address);
+    SignatureVector {
+        scheme,
+        intent_scope: format!("{scope:?}"),
+        private_keys: key_pairs
+            .iter()
+            .map(|key_pair| key_pair.encode_base64())
+            .collect(),
+        public_key,
+        address,
+        intent: Base64::encode(bcs::to_bytes(&intent).unwrap()),
+        digest: Hex::encode(digest(&intent, &message)),
+        signature: Base64::encode(sign(key_pairs, multisig, &intent, &message)),
+        message: Base64::encode(message),
+    }
+}
+
+fn generate() -> Vec<SignatureVector> {
+    let key_pairs = key_pairs();
+    let mut vectors = vec![];
+    for key_pair in &key_pairs {
+        for scope in INTENT_SCOPES {
+            vectors.push(vector(std::slice::from_ref(key_pair), false, scope));
+        }
+    }
+    for scope in INTENT_SCOPES {
+        vectors.push(vector(&key_pairs, true, scope));
+    }
+    vectors
+}
+
+/// Checks the vector against this crate: the keys derive the public key and address, the digest
+/// is that of the intent message, the signature verifies, and signing again gives the same
+/// signature.
+fn verify(vector: &SignatureVector) -> anyhow::Result<()> {
+    let key_pairs = vector
+        .private_keys
+        .iter()
+        .map(|key| SuiKeyPair::decode_base64(key).map_err(|e| anyhow::anyhow!(e)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let multisig = vector.scheme == SignatureScheme::MultiSig.to_string();
+    if multisig {
+        let public_key = multisig_public_key(&key_pairs);
+        anyhow::ensure!(
+            vector.public_key == Base64::encode(bcs::to_bytes(&public_key)?),
+            "multisig public key does not match the private keys"
+        );
+        anyhow::ensure!(
+            vector.address == SuiAddress::from(public_key),
+            "wrong address"
+        );
+    } else {
+        anyhow::ensure!(key_pairs.len() == 1, "expected a single private key");
+        let public_key =
+            PublicKey::decode_base64(&vector.public_key).map_err(|e| anyhow::anyhow!(e))?;
+        anyhow::ensure!(
+            public_key == key_pairs[0].public(),
+            "public key does not match the private key"
+        );
+        anyhow::ensure!(
+            vector.scheme == public_key.scheme().to_string(),
+            "wrong scheme"
+        );
+        anyhow::ensure!(
+            vector.address == SuiAddress::from(&public_key),
+            "wrong address"
+        );
+    }
+
+    let intent: Intent = bcs::from_bytes(&Base64::decode(&vector.intent)?)?;
+    anyhow::ensure!(
+        vector.intent_scope == format!("{:?}", intent.scope),
+        "wrong intent scope"
+    );
+    let message = Base64::decode(&vector.message)?;
+    anyhow::ensure!(
+        vector.digest == Hex::encode(digest(&intent, &message)),
+        "wrong digest"
+    );
+
+    let signature_bytes = Base64::decode(&vector.signature)?;
+    let signature = GenericSignature::from_bytes(&signature_bytes)?;
+    signature.verify_secure_generic(
+        &IntentMessage::new(intent.clone(), SerializedMessage(&message)),
+        vector.address,
+    )?;
+    anyhow::ensure!(
+        signature_bytes == sign(&key_pairs, multisig, &intent, &message),
+        "signing again gives a different signature"
+    );
+    Ok(())
+}
+
+fn verify_all(vectors: &[SignatureVector]) {
+    for vector in vectors {
+        if let Err(e) = verify(vector) {
+            panic!(
+                "Vector for {} over {} failed to verify: {e}",
+                vector.scheme, vector.intent_scope
+            );
+        }
+    }
+}
+
+#[derive(Debug, Parser, Clone, Copy, ArgEnum)]
+enum Action {
+    Print,
+    Test,
+    Record,
+    Verify,
+}
+
+#[derive(Debug, Parser)]
+#[clap(
+    name = "Sui signature vectors generator",
+    about = "Generate test vectors of user signatures, or verify vectors against this crate"
+)]
+struct Options {
+    #[clap(arg_enum, default_value = "Print", ignore_case = true)]
+    action: Action,
+    /// Vectors to verify, the recorded ones if not set.
+    #[clap(long)]
+    file: Option<PathBuf>,
+}
+
+fn main() {
+    let options = Options::parse();
+    match options.action {
+        Action::Print => {
+            let content = serde_json::to_string_pretty(&generate()).unwrap();
+            println!("{content}");
+        }
+        Action::Record => {
+            let content = serde_json::to_string_pretty(&generate()).unwrap();
+            let mut f = File::create(FILE_PATH).unwrap();
+            writeln!(f, "{content}").unwrap();
+        }
+        Action::Test => {
+            let vectors = generate();
+            assert_eq!(vectors, generate(), "vectors are not deterministic");
+            verify_all(&vectors);
+            // Once recorded, the vectors must not change.
+            if let Ok(reference) = std::fs::read_to_string(FILE_PATH) {
+                let reference: Vec<SignatureVector> = serde_json::from_str(&reference).unwrap();
+                assert_eq!(reference, vectors);
+            }
+        }
+        Action::Verify => {
+            let path = options.file.unwrap_or_else(|| PathBuf::from(FILE_PATH));
+            let vectors: Vec<SignatureVector> =
+                serde_json::from_reader(File::open(path).unwrap()).unwrap();
+            verify_all(&vectors);
+            println!("{} vectors verified", vectors.len());
+        }
+    }
+}
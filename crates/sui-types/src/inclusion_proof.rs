@@ -0,0 +1,258 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proofs that a transaction was executed and finalized, for light clients such as bridges and
+//! auditors, which can't trust the fullnode serving them.
+//!
+//! A checkpoint summary commits to its contents by their digest rather than by a Merkle root, so
+//! the path from a certified summary to a transaction is the whole contents of the checkpoint: a
+//! [`TransactionInclusionProof`] carries them, and they must hash to the digest in the summary
+//! and list the digests of the transaction and of its effects. The committee that certified the
+//! checkpoint is checked against a committee the verifier already trusts, either the committee of
+//! the same epoch, or the committee of the previous epoch, which certified the new committee in
+//! the last checkpoint of its epoch.
+//!
+//! Verifying a proof only needs the types of this crate, so that light clients can depend on it
+//! alone.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::{AuthorityName, TransactionDigest};
+use crate::committee::{Committee, EpochId, StakeUnit};
+use crate::error::{SuiError, SuiResult};
+use crate::message_envelope::Message;
+use crate::messages::{SenderSignedData, TransactionEffects, TransactionEffectsAPI};
+use crate::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointContents};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionInclusionProof {
+    pub transaction: SenderSignedData,
+    pub effects: TransactionEffects,
+    /// Checkpoint that includes the transaction.
+    pub checkpoint: CertifiedCheckpointSummary,
+    pub contents: CheckpointContents,
+    /// Voting rights of the committee of the epoch of the checkpoint.
+    pub committee: BTreeMap<AuthorityName, StakeUnit>,
+    /// Last checkpoint of the previous epoch, which carries `committee`. `None` in the first
+    /// epoch.
+    pub previous_epoch_checkpoint: Option<CertifiedCheckpointSummary>,
+}
+
+impl TransactionInclusionProof {
+    pub fn epoch(&self) -> EpochId {
+        self.checkpoint.epoch()
+    }
+
+    pub fn transaction_digest(&self) -> TransactionDigest {
+        self.transaction.digest()
+    }
+
+    /// Verifies the proof against `trusted_committee`, which is either the committee of the epoch
+    /// of the proof, or the committee of the previous epoch. Returns the committee of the epoch
+    /// of the proof, which can be trusted from then on.
+    pub fn verify(&self, trusted_committee: &Committee) -> SuiResult<Committee> {
+        let committee = self.verify_committee(trusted_committee)?;
+        self.checkpoint
+            .verify_with_contents(&committee, Some(&self.contents))?;
+
+        let transaction_digest = self.transaction_digest();
+        let effects_digest = self.effects.digest();
+        fp_ensure!(
+            *self.effects.transaction_digest() == transaction_digest,
+            invalid_proof(format!(
+                "effects {effects_digest:?} are not the effects of transaction \
+                {transaction_digest:?}"
+            ))
+        );
+        fp_ensure!(
+            self.contents.iter().any(|digests| {
+                digests.transaction == transaction_digest && digests.effects == effects_digest
+            }),
+            invalid_proof(format!(
+                "transaction {transaction_digest:?} is not included in checkpoint {}",
+                self.checkpoint.sequence_number
+            ))
+        );
+        Ok(committee)
+    }
+
+    fn verify_committee(&self, trusted_committee: &Committee) -> SuiResult<Committee> {
+        let epoch = self.epoch();
+        if trusted_committee.epoch == epoch {
+            fp_ensure!(
+                trusted_committee
+                    .members()
+                    .cloned()
+                    .collect::<BTreeMap<_, _>>()
+                    == self.committee,
+                invalid_proof(format!("committee of epoch {epoch} is not the trusted one"))
+            );
+            return Ok(trusted_committee.clone());
+        }
+
+        fp_ensure!(
+            trusted_committee.epoch + 1 == epoch,
+            invalid_proof(format!(
+                "committee of epoch {} can't verify a proof of epoch {epoch}",
+                trusted_committee.epoch
+            ))
+        );
+        let previous_epoch_checkpoint =
+            self.previous_epoch_checkpoint.as_ref().ok_or_else(|| {
+                invalid_proof(format!(
+                    "missing the last checkpoint of epoch {}",
+                    epoch - 1
+                ))
+            })?;
+        previous_epoch_checkpoint.verify(trusted_committee)?;
+        let next_epoch_committee = previous_epoch_checkpoint
+            .next_epoch_committee()
+            .ok_or_else(|| {
+                invalid_proof(format!(
+                    "checkpoint {} is not the last checkpoint of epoch {}",
+                    previous_epoch_checkpoint.sequence_number,
+                    epoch - 1
+                ))
+            })?;
+        fp_ensure!(
+            next_epoch_committee
+                .iter()
+                .cloned()
+                .collect::<BTreeMap<_, _>>()
+                == self.committee,
+            invalid_proof(format!(
+                "committee of epoch {epoch} is not the one elected in epoch {}",
+                epoch - 1
+            ))
+        );
+        // The committee was certified by the trusted committee, its keys are valid.
+        Ok(Committee::new(epoch, self.committee.clone()))
+    }
+}
+
+fn invalid_proof(error: String) -> SuiError {
+    SuiError::GenericAuthorityError {
+        error: format!("Invalid transaction inclusion proof: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fastcrypto::traits::KeyPair;
+    use rand::prelude::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::base_types::ExecutionDigests;
+    use crate::committee::ProtocolVersion;
+    use crate::crypto::AuthorityKeyPair;
+    use crate::gas::GasCostSummary;
+    use crate::messages_checkpoint::{CheckpointSummary, EndOfEpochData, SignedCheckpointSummary};
+    use crate::utils::{create_fake_transaction, make_committee_key};
+
+    fn certify(
+        keys: &[AuthorityKeyPair],
+        committee: &Committee,
+        summary: CheckpointSummary,
+    ) -> CertifiedCheckpointSummary {
+        let sign_infos: Vec<_> = keys
+            .iter()
+            .map(|k| SignedCheckpointSummary::sign(committee.epoch, &summary, k, k.public().into()))
+            .collect();
+        CertifiedCheckpointSummary::new(summary, sign_infos, committee).unwrap()
+    }
+
+    fn make_proof(
+        keys: &[AuthorityKeyPair],
+        committee: &Committee,
+        previous_epoch_checkpoint: Option<CertifiedCheckpointSummary>,
+    ) -> TransactionInclusionProof {
+        let transaction = create_fake_transaction().into_inner();
+        let effects = TransactionEffects::new_with_tx(&transaction);
+        let contents = CheckpointContents::new_with_causally_ordered_transactions(
+            [
+                ExecutionDigests::random(),
+                ExecutionDigests::new(*transaction.digest(), effects.digest()),
+            ]
+            .into_iter(),
+        );
+        let summary = CheckpointSummary::new(
+            committee.epoch,
+            10,
+            0,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            0,
+        );
+        TransactionInclusionProof {
+            transaction: transaction.into_data(),
+            effects,
+            checkpoint: certify(keys, committee, summary),
+            contents,
+            committee: committee.members().cloned().collect(),
+            previous_epoch_checkpoint,
+        }
+    }
+
+    #[test]
+    fn test_verify_inclusion_proof() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let (keys, committee) = make_committee_key(&mut rng);
+
+        let proof = make_proof(&keys, &committee, None);
+        proof.verify(&committee).unwrap();
+
+        // The effects must be those of the transaction, and be in the checkpoint.
+        let mut other = proof.clone();
+        other.effects = TransactionEffects::new_with_tx(&create_fake_transaction().into_inner());
+        other.verify(&committee).unwrap_err();
+
+        // The committee must be the trusted one.
+        let (_, other_committee) = make_committee_key(&mut rng);
+        proof.verify(&other_committee).unwrap_err();
+    }
+
+    #[test]
+    fn test_verify_inclusion_proof_of_next_epoch() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let (keys, committee) = make_committee_key(&mut rng);
+        let (next_keys, next_committee) = make_committee_key(&mut rng);
+        let next_committee = Committee::new(1, next_committee.members().cloned().collect());
+
+        let mut last_summary = CheckpointSummary::new(
+            0,
+            9,
+            0,
+            &CheckpointContents::new_with_causally_ordered_transactions(
+                [ExecutionDigests::random()].into_iter(),
+            ),
+            None,
+            GasCostSummary::default(),
+            None,
+            0,
+        );
+        last_summary.end_of_epoch_data = Some(EndOfEpochData {
+            next_epoch_committee: next_committee.voting_rights.clone(),
+            next_epoch_protocol_version: ProtocolVersion::MIN,
+            epoch_commitments: vec![],
+        });
+        let last_checkpoint = certify(&keys, &committee, last_summary);
+
+        let proof = make_proof(&next_keys, &next_committee, Some(last_checkpoint));
+        assert_eq!(proof.verify(&committee).unwrap(), next_committee);
+        proof.verify(&next_committee).unwrap();
+
+        // The committee of the proof must be the one elected by the previous epoch.
+        let mut other = proof.clone();
+        other.committee = committee.members().cloned().collect();
+        other.verify(&committee).unwrap_err();
+
+        let mut other = proof;
+        other.previous_epoch_checkpoint = None;
+        other.verify(&committee).unwrap_err();
+    }
+}
@@ -33,11 +33,13 @@ pub mod digests;
 pub mod display;
 pub mod dynamic_field;
 pub mod event;
+pub mod execution_trace;
 pub mod gas;
 pub mod gas_coin;
 pub mod governance;
 pub mod id;
 pub mod in_memory_storage;
+pub mod inclusion_proof;
 pub mod message_envelope;
 pub mod messages;
 pub mod messages_checkpoint;
@@ -48,6 +50,7 @@ pub mod programmable_transaction_builder;
 pub mod query;
 pub mod quorum_driver_types;
 pub mod signature;
+pub mod state_proof;
 pub mod storage;
 pub mod sui_serde;
 pub mod sui_system_state;
@@ -129,12 +132,42 @@ pub trait MoveTypeTagTrait {
     fn get_type_tag() -> TypeTag;
 }
 
+impl MoveTypeTagTrait for bool {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::Bool
+    }
+}
+
+impl MoveTypeTagTrait for u8 {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::U8
+    }
+}
+
+impl MoveTypeTagTrait for u16 {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::U16
+    }
+}
+
+impl MoveTypeTagTrait for u32 {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::U32
+    }
+}
+
 impl MoveTypeTagTrait for u64 {
     fn get_type_tag() -> TypeTag {
         TypeTag::U64
     }
 }
 
+impl MoveTypeTagTrait for u128 {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::U128
+    }
+}
+
 impl MoveTypeTagTrait for ObjectID {
     fn get_type_tag() -> TypeTag {
         TypeTag::Address
@@ -146,3 +179,33 @@ impl MoveTypeTagTrait for SuiAddress {
         TypeTag::Address
     }
 }
+
+/// `0x1::string::String`, the UTF-8 strings of Move.
+impl MoveTypeTagTrait for String {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::Struct(Box::new(StructTag {
+            address: MOVE_STDLIB_ADDRESS,
+            module: base_types::STD_UTF8_MODULE_NAME.to_owned(),
+            name: base_types::STD_UTF8_STRUCT_NAME.to_owned(),
+            type_params: vec![],
+        }))
+    }
+}
+
+impl<T: MoveTypeTagTrait> MoveTypeTagTrait for Vec<T> {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::Vector(Box::new(T::get_type_tag()))
+    }
+}
+
+/// `0x1::option::Option<T>`, which has the same BCS encoding as an `Option`.
+impl<T: MoveTypeTagTrait> MoveTypeTagTrait for Option<T> {
+    fn get_type_tag() -> TypeTag {
+        TypeTag::Struct(Box::new(StructTag {
+            address: MOVE_STDLIB_ADDRESS,
+            module: base_types::STD_OPTION_MODULE_NAME.to_owned(),
+            name: base_types::STD_OPTION_STRUCT_NAME.to_owned(),
+            type_params: vec![T::get_type_tag()],
+        }))
+    }
+}
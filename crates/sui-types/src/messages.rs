@@ -10,7 +10,7 @@ use crate::crypto::{
     DefaultHash, Ed25519SuiSignature, EmptySignInfo, Signature, Signer, SuiSignatureInner,
     ToFromBytes,
 };
-use crate::digests::{CertificateDigest, SenderSignedDataDigest, TransactionEventsDigest};
+use crate::digests::{CertificateDigest, Digest, SenderSignedDataDigest, TransactionEventsDigest};
 use crate::gas::GasCostSummary;
 use crate::message_envelope::{Envelope, Message, TrustedEnvelope, VerifiedEnvelope};
 use crate::messages_checkpoint::{
@@ -1253,6 +1253,26 @@ impl TransactionData {
         gas_payment: ObjectRef,
         gas_budget: u64,
         gas_price: u64,
+    ) -> anyhow::Result<Self> {
+        Self::new_pay_with_gas_coins(
+            sender,
+            coins,
+            recipients,
+            amounts,
+            vec![gas_payment],
+            gas_budget,
+            gas_price,
+        )
+    }
+
+    pub fn new_pay_with_gas_coins(
+        sender: SuiAddress,
+        coins: Vec<ObjectRef>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas_payment: Vec<ObjectRef>,
+        gas_budget: u64,
+        gas_price: u64,
     ) -> anyhow::Result<Self> {
         let pt = {
             let mut builder = ProgrammableTransactionBuilder::new();
@@ -1261,7 +1281,7 @@ impl TransactionData {
         };
         Ok(Self::new_programmable(
             sender,
-            vec![gas_payment],
+            gas_payment,
             pt,
             gas_budget,
             gas_price,
@@ -1348,6 +1368,24 @@ impl TransactionData {
         dep_ids: Vec<ObjectID>,
         gas_budget: u64,
         gas_price: u64,
+    ) -> Self {
+        Self::new_module_with_gas_coins(
+            sender,
+            vec![gas_payment],
+            modules,
+            dep_ids,
+            gas_budget,
+            gas_price,
+        )
+    }
+
+    pub fn new_module_with_gas_coins(
+        sender: SuiAddress,
+        gas_payment: Vec<ObjectRef>,
+        modules: Vec<Vec<u8>>,
+        dep_ids: Vec<ObjectID>,
+        gas_budget: u64,
+        gas_price: u64,
     ) -> Self {
         let pt = {
             let mut builder = ProgrammableTransactionBuilder::new();
@@ -1355,7 +1393,7 @@ impl TransactionData {
             builder.transfer_arg(sender, upgrade_cap);
             builder.finish()
         };
-        Self::new_programmable(sender, vec![gas_payment], pt, gas_budget, gas_price)
+        Self::new_programmable(sender, gas_payment, pt, gas_budget, gas_price)
     }
 
     pub fn new_upgrade(
@@ -1364,6 +1402,32 @@ impl TransactionData {
         package_id: ObjectID,
         modules: Vec<Vec<u8>>,
         dep_ids: Vec<ObjectID>,
+        upgrade_capability: (ObjectRef, Owner),
+        upgrade_policy: u8,
+        digest: Vec<u8>,
+        gas_budget: u64,
+        gas_price: u64,
+    ) -> anyhow::Result<Self> {
+        Self::new_upgrade_with_gas_coins(
+            sender,
+            vec![gas_payment],
+            package_id,
+            modules,
+            dep_ids,
+            upgrade_capability,
+            upgrade_policy,
+            digest,
+            gas_budget,
+            gas_price,
+        )
+    }
+
+    pub fn new_upgrade_with_gas_coins(
+        sender: SuiAddress,
+        gas_payment: Vec<ObjectRef>,
+        package_id: ObjectID,
+        modules: Vec<Vec<u8>>,
+        dep_ids: Vec<ObjectID>,
         (upgrade_capability, capability_owner): (ObjectRef, Owner),
         upgrade_policy: u8,
         digest: Vec<u8>,
@@ -1416,7 +1480,7 @@ impl TransactionData {
         };
         Ok(Self::new_programmable(
             sender,
-            vec![gas_payment],
+            gas_payment,
             pt,
             gas_budget,
             gas_price,
@@ -1717,6 +1781,44 @@ impl SenderSignedData {
         Ok(mapping)
     }
 
+    /// Checks that the transaction is signed once by each of its signers, and returns the
+    /// signature of each signer, which remain to be verified.
+    pub fn signatures_to_verify(&self) -> SuiResult<Vec<(SuiAddress, &GenericSignature)>> {
+        fp_ensure!(
+            self.0.len() == 1,
+            SuiError::UserInputError {
+                error: UserInputError::Unsupported(
+                    "SenderSignedData must contain exactly one transaction".to_string()
+                )
+            }
+        );
+        if self.intent_message().value.is_system_tx() {
+            return Ok(vec![]);
+        }
+
+        // Verify signatures. Steps are ordered in asc complexity order to minimize abuse.
+        let signers = self.intent_message().value.signers();
+        // Signature number needs to match
+        fp_ensure!(
+            self.inner().tx_signatures.len() == signers.len(),
+            SuiError::SignerSignatureNumberMismatch {
+                actual: self.inner().tx_signatures.len(),
+                expected: signers.len()
+            }
+        );
+        // All required signers need to be sign.
+        let present_sigs = self.get_signer_sig_mapping()?;
+        for s in signers {
+            if !present_sigs.contains_key(&s) {
+                return Err(SuiError::SignerSignatureAbsent {
+                    signer: s.to_string(),
+                });
+            }
+        }
+
+        Ok(present_sigs.into_iter().collect())
+    }
+
     pub fn transaction_data(&self) -> &TransactionData {
         &self.intent_message().value
     }
@@ -1781,40 +1883,7 @@ impl Message for SenderSignedData {
     }
 
     fn verify(&self, _sig_epoch: Option<EpochId>) -> SuiResult {
-        fp_ensure!(
-            self.0.len() == 1,
-            SuiError::UserInputError {
-                error: UserInputError::Unsupported(
-                    "SenderSignedData must contain exactly one transaction".to_string()
-                )
-            }
-        );
-        if self.intent_message().value.is_system_tx() {
-            return Ok(());
-        }
-
-        // Verify signatures. Steps are ordered in asc complexity order to minimize abuse.
-        let signers = self.intent_message().value.signers();
-        // Signature number needs to match
-        fp_ensure!(
-            self.inner().tx_signatures.len() == signers.len(),
-            SuiError::SignerSignatureNumberMismatch {
-                actual: self.inner().tx_signatures.len(),
-                expected: signers.len()
-            }
-        );
-        // All required signers need to be sign.
-        let present_sigs = self.get_signer_sig_mapping()?;
-        for s in signers {
-            if !present_sigs.contains_key(&s) {
-                return Err(SuiError::SignerSignatureAbsent {
-                    signer: s.to_string(),
-                });
-            }
-        }
-
-        // Verify all present signatures.
-        for (signer, signature) in present_sigs {
+        for (signer, signature) in self.signatures_to_verify()? {
             signature.verify_secure_generic(self.intent_message(), signer)?;
         }
         Ok(())
@@ -2199,6 +2268,19 @@ pub struct HandleCertificateResponse {
     pub events: TransactionEvents,
 }
 
+/// A soft bundle: independently signed and certified transactions that are submitted to consensus
+/// together, so that they are sequenced contiguously and in the given order.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandleCertificateBundleRequest {
+    pub certificates: Vec<CertifiedTransaction>,
+}
+
+/// Responses to the certificates of a bundle, in the order of the request.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandleCertificateBundleResponse {
+    pub responses: Vec<HandleCertificateResponse>,
+}
+
 #[derive(Clone, Debug)]
 pub struct VerifiedHandleCertificateResponse {
     pub signed_effects: VerifiedSignedTransactionEffects,
@@ -2360,6 +2442,13 @@ pub enum ExecutionFailureStatus {
 
     #[error("Invalid package upgrade. {upgrade_error}")]
     PackageUpgradeError { upgrade_error: PackageUpgradeError },
+
+    // Indicates the events emitted by the transaction are too large
+    #[error(
+        "Events of size {current_size} bytes too large. \
+    Limit is {max_size} bytes"
+    )]
+    EventsTooLarge { current_size: u64, max_size: u64 },
     // NOTE: if you want to add a new enum,
     // please add it at the end for Rust SDK backward compatibility.
 }
@@ -3175,6 +3264,7 @@ pub enum ConsensusTransactionKey {
     CheckpointSignature(AuthorityName, CheckpointSequenceNumber),
     EndOfPublish(AuthorityName),
     CapabilityNotification(AuthorityName, u64 /* generation */),
+    CertificateBundle(Digest),
 }
 
 impl Debug for ConsensusTransactionKey {
@@ -3191,6 +3281,7 @@ impl Debug for ConsensusTransactionKey {
                 name.concise(),
                 generation
             ),
+            Self::CertificateBundle(digest) => write!(f, "CertificateBundle({:?})", digest),
         }
     }
 }
@@ -3257,6 +3348,8 @@ pub enum ConsensusTransactionKind {
     CheckpointSignature(Box<CheckpointSignatureMessage>),
     EndOfPublish(AuthorityName),
     CapabilityNotification(AuthorityCapabilities),
+    /// User certificates to be sequenced contiguously, in this order.
+    UserTransactionBundle(Vec<CertifiedTransaction>),
 }
 
 impl ConsensusTransaction {
@@ -3275,6 +3368,22 @@ impl ConsensusTransaction {
         }
     }
 
+    pub fn new_certificate_bundle_message(
+        authority: &AuthorityName,
+        certificates: Vec<CertifiedTransaction>,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        for certificate in &certificates {
+            certificate.digest().hash(&mut hasher);
+        }
+        authority.hash(&mut hasher);
+        let tracking_id = hasher.finish().to_le_bytes();
+        Self {
+            tracking_id,
+            kind: ConsensusTransactionKind::UserTransactionBundle(certificates),
+        }
+    }
+
     pub fn new_checkpoint_signature_message(data: CheckpointSignatureMessage) -> Self {
         let mut hasher = DefaultHasher::new();
         data.summary.auth_sig().signature.hash(&mut hasher);
@@ -3316,6 +3425,9 @@ impl ConsensusTransaction {
             ConsensusTransactionKind::UserTransaction(certificate) => {
                 certificate.verify_signature(committee)
             }
+            ConsensusTransactionKind::UserTransactionBundle(certificates) => certificates
+                .iter()
+                .try_for_each(|certificate| certificate.verify_signature(committee)),
             ConsensusTransactionKind::CheckpointSignature(data) => data.verify(committee),
             // EndOfPublish and CapabilityNotification are authenticated in
             // AuthorityPerEpochStore::verify_consensus_transaction
@@ -3329,6 +3441,9 @@ impl ConsensusTransaction {
             ConsensusTransactionKind::UserTransaction(cert) => {
                 ConsensusTransactionKey::Certificate(*cert.digest())
             }
+            ConsensusTransactionKind::UserTransactionBundle(certificates) => {
+                ConsensusTransactionKey::CertificateBundle(certificate_bundle_digest(certificates))
+            }
             ConsensusTransactionKind::CheckpointSignature(data) => {
                 ConsensusTransactionKey::CheckpointSignature(
                     data.summary.auth_sig().authority,
@@ -3344,8 +3459,24 @@ impl ConsensusTransaction {
         }
     }
 
+    /// True for user certificates, alone or in a bundle.
     pub fn is_user_certificate(&self) -> bool {
-        matches!(self.kind, ConsensusTransactionKind::UserTransaction(_))
+        matches!(
+            self.kind,
+            ConsensusTransactionKind::UserTransaction(_)
+                | ConsensusTransactionKind::UserTransactionBundle(_)
+        )
+    }
+
+    /// The user certificates carried by this transaction, in sequencing order.
+    pub fn user_certificates(&self) -> &[CertifiedTransaction] {
+        match &self.kind {
+            ConsensusTransactionKind::UserTransaction(certificate) => {
+                std::slice::from_ref(certificate.as_ref())
+            }
+            ConsensusTransactionKind::UserTransactionBundle(certificates) => certificates,
+            _ => &[],
+        }
     }
 
     pub fn is_end_of_publish(&self) -> bool {
@@ -3353,6 +3484,15 @@ impl ConsensusTransaction {
     }
 }
 
+/// Identifies a bundle by the digests of its certificates, in order.
+pub fn certificate_bundle_digest(certificates: &[CertifiedTransaction]) -> Digest {
+    let mut digest = DefaultHash::default();
+    for certificate in certificates {
+        digest.update(certificate.digest().inner());
+    }
+    Digest::new(digest.finalize().into())
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, schemars::JsonSchema)]
 pub enum ExecuteTransactionRequestType {
     WaitForEffectsCert,
@@ -14,7 +14,8 @@ use crate::crypto::{default_hash, AuthoritySignInfo, AuthorityStrongQuorumSignIn
 use crate::error::SuiResult;
 use crate::gas::GasCostSummary;
 use crate::message_envelope::{Envelope, Message, TrustedEnvelope, VerifiedEnvelope};
-use crate::messages::TransactionEffectsAPI;
+use crate::messages::{Transaction, TransactionEffects, TransactionEffectsAPI, TransactionEvents};
+use crate::object::Object;
 use crate::signature::GenericSignature;
 use crate::storage::ReadStore;
 use crate::{base_types::AuthorityName, committee::Committee, error::SuiError};
@@ -46,6 +47,36 @@ pub struct CheckpointResponse {
     pub contents: Option<CheckpointContents>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckpointStreamRequest {
+    /// The sequence number of the first checkpoint to stream.
+    pub start_sequence_number: CheckpointSequenceNumber,
+    /// The number of checkpoints the server may read ahead of the client. Capped by the server,
+    /// which uses its own limit when this is None.
+    pub max_buffered_checkpoints: Option<u32>,
+}
+
+/// A checkpoint together with everything needed to process it without reading from a node:
+/// its transactions and their effects, events, and the objects they read and wrote.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckpointData {
+    pub checkpoint_summary: CertifiedCheckpointSummary,
+    pub checkpoint_contents: CheckpointContents,
+    /// The transactions of the checkpoint, in the order of its contents.
+    pub transactions: Vec<CheckpointTransaction>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CheckpointTransaction {
+    pub transaction: Transaction,
+    pub effects: TransactionEffects,
+    pub events: Option<TransactionEvents>,
+    /// The objects at the versions the transaction read them, including the shared objects.
+    pub input_objects: Vec<Object>,
+    /// The objects created, mutated or unwrapped by the transaction, at their new versions.
+    pub output_objects: Vec<Object>,
+}
+
 // The constituent parts of checkpoints, signed and certified
 
 /// The Sha256 digest of an EllipticCurveMultisetHash committing to the live object set.
@@ -25,6 +25,8 @@ pub enum QuorumDriverError {
     QuorumDriverInternalError(SuiError),
     #[error("Invalid user signature: {0:?}.")]
     InvalidUserSignature(SuiError),
+    #[error("Transaction is denied by this node: {0:?}.")]
+    TransactionDenied(SuiError),
     #[error(
         "Failed to sign transaction by a quorum of validators because of locked objects: {:?}, retried a conflicting transaction {:?}, success: {:?}",
         conflicting_txes,
@@ -44,4 +46,6 @@ pub enum QuorumDriverError {
     NonRecoverableTransactionError {
         errors: Vec<(SuiError, Vec<AuthorityName>, StakeUnit)>,
     },
+    #[error("Invalid soft bundle: {0}.")]
+    InvalidSoftBundle(String),
 }
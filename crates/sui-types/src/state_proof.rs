@@ -0,0 +1,276 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proofs that an object version is part of the state committed to by an epoch's state root.
+//!
+//! The state root of an epoch, committed to in the last checkpoint of the epoch, is the ECMH
+//! multiset hash of the live object set computed by the state accumulator. A multiset hash does not
+//! support succinct membership proofs: anyone can remove any element from it, so "the root without
+//! this object" proves nothing. An [`ObjectStateProof`] instead links the object version to the
+//! effects of the transaction that wrote it, those effects to the certified checkpoint that
+//! includes them, and that checkpoint to the certified end of epoch checkpoint carrying the state
+//! root. It proves that the object version was accumulated into the state of the epoch, not that it
+//! is still live at the end of the epoch.
+
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::ObjectRef;
+use crate::committee::{Committee, EpochId};
+use crate::error::{SuiError, SuiResult};
+use crate::message_envelope::Message;
+use crate::messages::{TransactionEffects, TransactionEffectsAPI};
+use crate::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointCommitment, CheckpointContents, ECMHLiveObjectSetDigest,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ObjectStateProof {
+    pub object_ref: ObjectRef,
+    /// Effects of the transaction that wrote the object version.
+    pub effects: TransactionEffects,
+    /// Checkpoint that includes the transaction.
+    pub checkpoint: CertifiedCheckpointSummary,
+    pub contents: CheckpointContents,
+    /// Last checkpoint of the epoch, carrying the state root. `None` until the epoch has ended.
+    pub end_of_epoch_checkpoint: Option<CertifiedCheckpointSummary>,
+}
+
+impl ObjectStateProof {
+    pub fn epoch(&self) -> EpochId {
+        self.checkpoint.epoch()
+    }
+
+    /// State root of the epoch, if the epoch has ended.
+    pub fn state_root(&self) -> Option<&ECMHLiveObjectSetDigest> {
+        let end_of_epoch_data = self
+            .end_of_epoch_checkpoint
+            .as_ref()?
+            .end_of_epoch_data
+            .as_ref()?;
+        end_of_epoch_data
+            .epoch_commitments
+            .iter()
+            .map(|commitment| match commitment {
+                CheckpointCommitment::ECMHLiveObjectSetDigest(digest) => digest,
+            })
+            .next()
+    }
+
+    /// Verifies the proof against the committee of its epoch.
+    pub fn verify(&self, committee: &Committee) -> SuiResult {
+        self.checkpoint
+            .verify_with_contents(committee, Some(&self.contents))?;
+
+        let effects_digest = self.effects.digest();
+        fp_ensure!(
+            self.contents
+                .iter()
+                .any(|digests| digests.effects == effects_digest),
+            invalid_proof(format!(
+                "effects {effects_digest:?} are not included in checkpoint {}",
+                self.checkpoint.sequence_number
+            ))
+        );
+        fp_ensure!(
+            self.effects
+                .all_changed_objects()
+                .iter()
+                .any(|(object_ref, _, _)| **object_ref == self.object_ref),
+            invalid_proof(format!(
+                "object {:?} is not written by transaction {:?}",
+                self.object_ref,
+                self.effects.transaction_digest()
+            ))
+        );
+
+        if let Some(end_of_epoch_checkpoint) = &self.end_of_epoch_checkpoint {
+            end_of_epoch_checkpoint.verify(committee)?;
+            fp_ensure!(
+                end_of_epoch_checkpoint.sequence_number >= self.checkpoint.sequence_number,
+                invalid_proof(format!(
+                    "end of epoch checkpoint {} precedes checkpoint {}",
+                    end_of_epoch_checkpoint.sequence_number, self.checkpoint.sequence_number
+                ))
+            );
+            fp_ensure!(
+                self.state_root().is_some(),
+                invalid_proof(format!(
+                    "checkpoint {} does not carry a state root",
+                    end_of_epoch_checkpoint.sequence_number
+                ))
+            );
+        }
+        Ok(())
+    }
+}
+
+fn invalid_proof(error: String) -> SuiError {
+    SuiError::GenericAuthorityError {
+        error: format!("Invalid object state proof: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fastcrypto::traits::KeyPair;
+    use rand::prelude::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::base_types::{random_object_ref, ExecutionDigests, TransactionDigest};
+    use crate::committee::ProtocolVersion;
+    use crate::crypto::AuthorityKeyPair;
+    use crate::gas::GasCostSummary;
+    use crate::messages::TransactionEffectsV1;
+    use crate::messages_checkpoint::{CheckpointSummary, EndOfEpochData, SignedCheckpointSummary};
+    use crate::object::Owner;
+    use crate::utils::make_committee_key;
+
+    fn certify(
+        keys: &[AuthorityKeyPair],
+        committee: &Committee,
+        summary: CheckpointSummary,
+    ) -> CertifiedCheckpointSummary {
+        let sign_infos: Vec<_> = keys
+            .iter()
+            .map(|k| SignedCheckpointSummary::sign(committee.epoch, &summary, k, k.public().into()))
+            .collect();
+        CertifiedCheckpointSummary::new(summary, sign_infos, committee).unwrap()
+    }
+
+    fn summary(
+        committee: &Committee,
+        sequence_number: u64,
+        contents: &CheckpointContents,
+    ) -> CheckpointSummary {
+        CheckpointSummary::new(
+            committee.epoch,
+            sequence_number,
+            0,
+            contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            0,
+        )
+    }
+
+    fn effects_creating(object_ref: ObjectRef) -> TransactionEffects {
+        TransactionEffects::V1(TransactionEffectsV1 {
+            transaction_digest: TransactionDigest::random(),
+            created: vec![(object_ref, Owner::Immutable)],
+            ..Default::default()
+        })
+    }
+
+    fn end_of_epoch_checkpoint(
+        keys: &[AuthorityKeyPair],
+        committee: &Committee,
+        sequence_number: u64,
+        epoch_commitments: Vec<CheckpointCommitment>,
+    ) -> CertifiedCheckpointSummary {
+        let contents = CheckpointContents::new_with_causally_ordered_transactions(
+            [ExecutionDigests::random()].into_iter(),
+        );
+        let mut summary = summary(committee, sequence_number, &contents);
+        summary.end_of_epoch_data = Some(EndOfEpochData {
+            next_epoch_committee: committee.voting_rights.clone(),
+            next_epoch_protocol_version: ProtocolVersion::MIN,
+            epoch_commitments,
+        });
+        certify(keys, committee, summary)
+    }
+
+    fn make_proof(keys: &[AuthorityKeyPair], committee: &Committee) -> ObjectStateProof {
+        let object_ref = random_object_ref();
+        let effects = effects_creating(object_ref);
+        let contents = CheckpointContents::new_with_causally_ordered_transactions(
+            [
+                ExecutionDigests::random(),
+                ExecutionDigests::new(*effects.transaction_digest(), effects.digest()),
+            ]
+            .into_iter(),
+        );
+        let checkpoint = certify(keys, committee, summary(committee, 10, &contents));
+        ObjectStateProof {
+            object_ref,
+            effects,
+            checkpoint,
+            contents,
+            end_of_epoch_checkpoint: Some(end_of_epoch_checkpoint(
+                keys,
+                committee,
+                12,
+                vec![ECMHLiveObjectSetDigest::default().into()],
+            )),
+        }
+    }
+
+    #[test]
+    fn test_verify_object_state_proof() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let (keys, committee) = make_committee_key(&mut rng);
+
+        let proof = make_proof(&keys, &committee);
+        proof.verify(&committee).unwrap();
+        assert_eq!(
+            proof.state_root(),
+            Some(&ECMHLiveObjectSetDigest::default())
+        );
+
+        // Until the epoch ends, the proof stops at the checkpoint of the transaction.
+        let mut ongoing = proof.clone();
+        ongoing.end_of_epoch_checkpoint = None;
+        ongoing.verify(&committee).unwrap();
+        assert_eq!(ongoing.state_root(), None);
+    }
+
+    #[test]
+    fn test_reject_tampered_object_state_proof() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let (keys, committee) = make_committee_key(&mut rng);
+        let proof = make_proof(&keys, &committee);
+
+        // Effects writing the object, but not included in the checkpoint.
+        let mut other = proof.clone();
+        other.effects = effects_creating(proof.object_ref);
+        other.verify(&committee).unwrap_err();
+
+        // An object version not written by the effects.
+        let mut other = proof.clone();
+        other.object_ref = random_object_ref();
+        other.verify(&committee).unwrap_err();
+        let mut other = proof.clone();
+        other.object_ref.1.increment();
+        other.verify(&committee).unwrap_err();
+
+        // Checkpoints certified by another committee.
+        let (other_keys, other_committee) = make_committee_key(&mut rng);
+        proof.verify(&other_committee).unwrap_err();
+        let mut other = proof.clone();
+        other.end_of_epoch_checkpoint = Some(end_of_epoch_checkpoint(
+            &other_keys,
+            &other_committee,
+            12,
+            vec![ECMHLiveObjectSetDigest::default().into()],
+        ));
+        other.verify(&committee).unwrap_err();
+
+        // An end of epoch checkpoint preceding the checkpoint of the transaction.
+        let mut other = proof.clone();
+        other.end_of_epoch_checkpoint = Some(end_of_epoch_checkpoint(
+            &keys,
+            &committee,
+            9,
+            vec![ECMHLiveObjectSetDigest::default().into()],
+        ));
+        other.verify(&committee).unwrap_err();
+
+        // An end of epoch checkpoint without a state root.
+        let mut other = proof;
+        other.end_of_epoch_checkpoint =
+            Some(end_of_epoch_checkpoint(&keys, &committee, 12, vec![]));
+        assert_eq!(other.state_root(), None);
+        other.verify(&committee).unwrap_err();
+    }
+}
@@ -249,6 +249,12 @@ pub trait ReadStore {
         &self,
         digest: &TransactionEventsDigest,
     ) -> Result<Option<TransactionEvents>, Self::Error>;
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, Self::Error>;
 }
 
 impl<T: ReadStore> ReadStore for &T {
@@ -307,6 +313,14 @@ impl<T: ReadStore> ReadStore for &T {
     ) -> Result<Option<TransactionEvents>, Self::Error> {
         ReadStore::get_transaction_events(*self, digest)
     }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, Self::Error> {
+        ReadStore::get_object_by_key(*self, object_id, version)
+    }
 }
 
 pub trait WriteStore: ReadStore {
@@ -357,6 +371,7 @@ pub struct InMemoryStore {
     transactions: HashMap<TransactionDigest, VerifiedTransaction>,
     effects: HashMap<TransactionEffectsDigest, TransactionEffects>,
     events: HashMap<TransactionEventsDigest, TransactionEvents>,
+    objects: HashMap<(ObjectID, VersionNumber), Object>,
 
     epoch_to_committee: Vec<Committee>,
 }
@@ -499,6 +514,18 @@ impl InMemoryStore {
     ) -> Option<&TransactionEvents> {
         self.events.get(digest)
     }
+
+    pub fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Option<&Object> {
+        self.objects.get(&(*object_id, version))
+    }
+
+    pub fn insert_object(&mut self, object: Object) {
+        self.objects.insert((object.id(), object.version()), object);
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -600,6 +627,17 @@ impl ReadStore for SharedInMemoryStore {
             .cloned()
             .pipe(Ok)
     }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, Self::Error> {
+        self.inner()
+            .get_object_by_key(object_id, version)
+            .cloned()
+            .pipe(Ok)
+    }
 }
 
 impl WriteStore for SharedInMemoryStore {
@@ -544,6 +544,14 @@ impl<S> TemporaryStore<S> {
             self.input_objects.len(),
         )
     }
+
+    /// Total size of the serialized events emitted so far.
+    pub fn events_size(&self) -> usize {
+        self.events
+            .iter()
+            .map(|event| bcs::serialized_size(event).expect("Serializing an event cannot fail"))
+            .sum()
+    }
 }
 
 impl<S: ObjectStore> TemporaryStore<S> {
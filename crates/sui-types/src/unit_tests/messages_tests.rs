@@ -1344,3 +1344,186 @@ fn check_approx_effects_components_size() {
         "Update APPROX_SIZE_OF_EXECUTION_STATUS constant"
     );
 }
+
+#[test]
+fn test_certificate_bundle_consensus_transaction() {
+    let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+    let authority = AuthorityPublicKeyBytes::from(sec.public());
+    let committee = Committee::new(0, BTreeMap::from([(authority, 1)]));
+    let (sender, sender_sec): (_, AccountKeyPair) = get_key_pair();
+
+    let certificates: Vec<_> = (0..2)
+        .map(|_| {
+            let transaction = Transaction::from_data_and_signer(
+                TransactionData::new_transfer_with_dummy_gas_price(
+                    sender,
+                    random_object_ref(),
+                    sender,
+                    random_object_ref(),
+                    10000,
+                ),
+                Intent::default(),
+                vec![&sender_sec],
+            )
+            .verify()
+            .unwrap();
+            let signed = SignedTransaction::new(
+                committee.epoch(),
+                transaction.clone().into_message(),
+                &sec,
+                authority,
+            );
+            CertifiedTransaction::new(
+                transaction.into_message(),
+                vec![signed.auth_sig().clone()],
+                &committee,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let bundle =
+        ConsensusTransaction::new_certificate_bundle_message(&authority, certificates.clone());
+    assert!(bundle.is_user_certificate());
+    assert!(bundle.verify(&committee).is_ok());
+    let digests: Vec<_> = bundle
+        .user_certificates()
+        .iter()
+        .map(|cert| *cert.digest())
+        .collect();
+    assert_eq!(
+        digests,
+        certificates
+            .iter()
+            .map(|cert| *cert.digest())
+            .collect::<Vec<_>>()
+    );
+
+    // The order of the certificates is part of the identity of the bundle.
+    let reversed = ConsensusTransaction::new_certificate_bundle_message(
+        &authority,
+        certificates.iter().rev().cloned().collect(),
+    );
+    assert_ne!(bundle.key(), reversed.key());
+    assert!(matches!(
+        bundle.key(),
+        ConsensusTransactionKey::CertificateBundle(_)
+    ));
+}
+
+#[test]
+fn test_consensus_transaction_bcs_variant_indices() {
+    // Consensus transactions and their keys are persisted and exchanged between validators, so
+    // new variants must be appended and the index of the existing ones must never change.
+    let name = AuthorityName::ZERO;
+    let keys = [
+        (
+            ConsensusTransactionKey::Certificate(TransactionDigest::ZERO),
+            0,
+        ),
+        (ConsensusTransactionKey::CheckpointSignature(name, 7), 1),
+        (ConsensusTransactionKey::EndOfPublish(name), 2),
+        (ConsensusTransactionKey::CapabilityNotification(name, 7), 3),
+        (ConsensusTransactionKey::CertificateBundle(Digest::ZERO), 4),
+    ];
+    for (key, index) in keys {
+        let bytes = bcs::to_bytes(&key).unwrap();
+        assert_eq!(bytes[0], index, "{key:?}");
+        assert_eq!(
+            bcs::from_bytes::<ConsensusTransactionKey>(&bytes).unwrap(),
+            key
+        );
+    }
+
+    let (_, sec): (_, AuthorityKeyPair) = get_key_pair();
+    let authority = AuthorityPublicKeyBytes::from(sec.public());
+    let committee = Committee::new(0, BTreeMap::from([(authority, 1)]));
+    let (sender, sender_sec): (_, AccountKeyPair) = get_key_pair();
+    let transaction = Transaction::from_data_and_signer(
+        TransactionData::new_transfer_with_dummy_gas_price(
+            sender,
+            random_object_ref(),
+            sender,
+            random_object_ref(),
+            10000,
+        ),
+        Intent::default(),
+        vec![&sender_sec],
+    )
+    .verify()
+    .unwrap();
+    let signed = SignedTransaction::new(
+        committee.epoch(),
+        transaction.clone().into_message(),
+        &sec,
+        authority,
+    );
+    let certificate = CertifiedTransaction::new(
+        transaction.into_message(),
+        vec![signed.auth_sig().clone()],
+        &committee,
+    )
+    .unwrap();
+
+    let transactions = [
+        (
+            ConsensusTransaction::new_certificate_message(&authority, certificate.clone()),
+            0,
+        ),
+        (ConsensusTransaction::new_end_of_publish(authority), 2),
+        (
+            ConsensusTransaction::new_capability_notification(AuthorityCapabilities::new(
+                authority,
+                SupportedProtocolVersions::SYSTEM_DEFAULT,
+                vec![],
+            )),
+            3,
+        ),
+        (
+            ConsensusTransaction::new_certificate_bundle_message(&authority, vec![certificate]),
+            4,
+        ),
+    ];
+    for (transaction, index) in transactions {
+        let bytes = bcs::to_bytes(&transaction).unwrap();
+        // The variant index of the kind follows the 8 bytes of the tracking id.
+        assert_eq!(bytes[8], index, "{:?}", transaction.key());
+        let decoded = bcs::from_bytes::<ConsensusTransaction>(&bytes).unwrap();
+        assert_eq!(decoded.key(), transaction.key());
+        assert_eq!(bcs::to_bytes(&decoded).unwrap(), bytes);
+    }
+}
+
+#[test]
+fn test_sui_error_bcs_variant_indices() {
+    // Errors are sent BCS encoded in the details of gRPC statuses, between nodes that may run
+    // different versions, so new variants must be appended.
+    let errors = [
+        (SuiError::FileIOError(String::new()), 78),
+        (
+            SuiError::LocalExecutionTimeLimitExceeded {
+                kind: "dry run".to_string(),
+                limit_ms: 7,
+            },
+            79,
+        ),
+        (
+            SuiError::InvalidTransactionOutputs {
+                digest: TransactionDigest::ZERO,
+                error: String::new(),
+            },
+            80,
+        ),
+        (
+            SuiError::InvalidArchiveData {
+                error: String::new(),
+            },
+            81,
+        ),
+    ];
+    for (error, index) in errors {
+        let bytes = bcs::to_bytes(&error).unwrap();
+        assert_eq!(bytes[0], index, "{error:?}");
+        assert_eq!(bcs::from_bytes::<SuiError>(&bytes).unwrap(), error);
+    }
+}
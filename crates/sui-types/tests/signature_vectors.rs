@@ -0,0 +1,24 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#[test]
+#[cfg_attr(msim, ignore)]
+fn test_signature_vectors() {
+    // If this test breaks and you intended to change how signatures are computed, you need to
+    // record the fresh vectors, and let SDK maintainers know that they changed:
+    // # cargo -q run --example generate-signature-vectors -- record
+
+    let status = std::process::Command::new("cargo")
+        .args(["run", "--example", "generate-signature-vectors", "--"])
+        .arg("test")
+        .status()
+        .expect("failed to execute process");
+    assert!(
+        status.success(),
+        "\n\
+If this test breaks and you intended to change how signatures are computed, you need to record\n\
+the fresh vectors:\n\
+cargo -q run --example generate-signature-vectors -- record\n\
+        "
+    );
+}
@@ -0,0 +1,266 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Aggregation for `sui client summary`.
+//!
+//! The summary is built from the transactions sent by an address and the transactions that sent
+//! objects to it, as returned by the fullnode (or indexer) transaction query API, restricted to a
+//! time range. It is meant for quick operational triage, not accounting: transactions that are not
+//! yet part of a checkpoint have no timestamp and are only counted when the range is open-ended.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter, Write};
+
+use anyhow::anyhow;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+use sui_json_rpc_types::{
+    SuiCommand, SuiTransactionDataAPI, SuiTransactionEffectsAPI, SuiTransactionKind,
+    SuiTransactionResponse,
+};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::object::Owner;
+
+/// Number of packages listed in the summary.
+const TOP_PACKAGES: usize = 10;
+
+/// Activity of an address over `[start_ms, end_ms)`.
+#[derive(Serialize, Clone, Debug)]
+pub struct AddressSummary {
+    pub address: SuiAddress,
+    pub start_ms: Option<u64>,
+    pub end_ms: Option<u64>,
+    /// Transactions sent by the address.
+    pub sent: u64,
+    /// Transactions sent by the address that failed to execute.
+    pub failed: u64,
+    /// Transactions sent by other addresses that touched objects of the address.
+    pub received: u64,
+    /// Gas paid by the address, net of storage rebates. Negative when rebates exceed costs.
+    pub gas_spent: i128,
+    /// Net change of the address's balance, per coin type.
+    pub balance_changes: BTreeMap<String, i128>,
+    /// Number of Move calls made by the address, per package.
+    pub package_calls: BTreeMap<ObjectID, u64>,
+    /// Whether the scan stopped at the transaction limit before covering the whole range.
+    pub truncated: bool,
+    #[serde(skip)]
+    seen: HashSet<TransactionDigest>,
+}
+
+impl AddressSummary {
+    pub fn new(address: SuiAddress, start_ms: Option<u64>, end_ms: Option<u64>) -> Self {
+        Self {
+            address,
+            start_ms,
+            end_ms,
+            sent: 0,
+            failed: 0,
+            received: 0,
+            gas_spent: 0,
+            balance_changes: BTreeMap::new(),
+            package_calls: BTreeMap::new(),
+            truncated: false,
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn transactions(&self) -> u64 {
+        self.sent + self.received
+    }
+
+    /// Whether a transaction executed at `timestamp_ms` is older than the range. Transactions are
+    /// queried newest first, so scanning can stop at the first such transaction.
+    pub fn is_before_range(&self, timestamp_ms: Option<u64>) -> bool {
+        matches!((self.start_ms, timestamp_ms), (Some(start), Some(ts)) if ts < start)
+    }
+
+    fn is_in_range(&self, timestamp_ms: Option<u64>) -> bool {
+        match timestamp_ms {
+            Some(ts) => {
+                self.start_ms.map_or(true, |start| ts >= start)
+                    && self.end_ms.map_or(true, |end| ts < end)
+            }
+            None => self.end_ms.is_none(),
+        }
+    }
+
+    /// Adds a transaction, queried with its input, effects and balance changes. Transactions
+    /// outside of the range and transactions that were already recorded are ignored. Returns
+    /// whether the transaction was recorded.
+    pub fn record(&mut self, response: &SuiTransactionResponse) -> Result<bool, anyhow::Error> {
+        if !self.is_in_range(response.timestamp_ms) || !self.seen.insert(response.digest) {
+            return Ok(false);
+        }
+        let transaction = response
+            .transaction
+            .as_ref()
+            .ok_or_else(|| anyhow!("Transaction input missing for {}", response.digest))?;
+        let effects = response
+            .effects
+            .as_ref()
+            .ok_or_else(|| anyhow!("Transaction effects missing for {}", response.digest))?;
+        let data = &transaction.data;
+
+        if *data.sender() == self.address {
+            self.sent += 1;
+            if !effects.status().is_ok() {
+                self.failed += 1;
+            }
+            if let SuiTransactionKind::ProgrammableTransaction(pt) = data.transaction() {
+                for command in &pt.commands {
+                    if let SuiCommand::MoveCall(call) = command {
+                        *self.package_calls.entry(call.package).or_default() += 1;
+                    }
+                }
+            }
+        } else {
+            self.received += 1;
+        }
+
+        if data.gas_data().owner == self.address {
+            let gas = effects.gas_cost_summary();
+            self.gas_spent += gas.computation_cost as i128 + gas.storage_cost as i128
+                - gas.storage_rebate as i128;
+        }
+
+        for change in response.balance_changes.iter().flatten() {
+            if change.owner == Owner::AddressOwner(self.address) {
+                *self
+                    .balance_changes
+                    .entry(change.coin_type.to_string())
+                    .or_default() += change.amount;
+            }
+        }
+        Ok(true)
+    }
+
+    /// The most called packages, most calls first.
+    pub fn top_packages(&self, n: usize) -> Vec<(ObjectID, u64)> {
+        let mut packages: Vec<_> = self
+            .package_calls
+            .iter()
+            .map(|(package, calls)| (*package, *calls))
+            .collect();
+        packages.sort_by(|(a_id, a), (b_id, b)| b.cmp(a).then(a_id.cmp(b_id)));
+        packages.truncate(n);
+        packages
+    }
+}
+
+/// Parses a range bound given either as an RFC 3339 timestamp, or as a `YYYY-MM-DD` date, taken
+/// as midnight UTC. Returns milliseconds since the Unix epoch.
+pub fn parse_timestamp_ms(value: &str) -> Result<u64, anyhow::Error> {
+    let datetime = match DateTime::parse_from_rfc3339(value) {
+        Ok(datetime) => datetime.with_timezone(&Utc),
+        Err(_) => {
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+                anyhow!("Invalid date {value}, expected YYYY-MM-DD or an RFC 3339 timestamp")
+            })?;
+            Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        }
+    };
+    u64::try_from(datetime.timestamp_millis()).map_err(|_| anyhow!("Date {value} is too early"))
+}
+
+fn format_timestamp_ms(timestamp_ms: u64) -> String {
+    Utc.timestamp_millis_opt(timestamp_ms as i64)
+        .single()
+        .map_or_else(|| timestamp_ms.to_string(), |t| t.to_rfc3339())
+}
+
+impl Display for AddressSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "Address: {}", self.address)?;
+        writeln!(
+            writer,
+            "Range: {} - {}",
+            self.start_ms
+                .map_or_else(|| "beginning".to_string(), format_timestamp_ms),
+            self.end_ms
+                .map_or_else(|| "now".to_string(), format_timestamp_ms)
+        )?;
+        writeln!(
+            writer,
+            "Transactions: {} ({} sent, {} failed, {} received)",
+            self.transactions(),
+            self.sent,
+            self.failed,
+            self.received
+        )?;
+        writeln!(
+            writer,
+            "Gas spent (net of storage rebates): {}",
+            self.gas_spent
+        )?;
+        if self.truncated {
+            writeln!(
+                writer,
+                "Warning: the transaction limit was reached, older transactions in the range are \
+                 not included."
+            )?;
+        }
+
+        writeln!(writer, "\nBalance changes:")?;
+        if self.balance_changes.is_empty() {
+            writeln!(writer, " None")?;
+        } else {
+            writeln!(writer, " {0: ^66} | {1: ^24}", "Coin Type", "Net Change")?;
+            writeln!(writer, "{}", ["-"; 95].join(""))?;
+            for (coin_type, amount) in &self.balance_changes {
+                writeln!(writer, " {0: <66} | {1: >24}", coin_type, amount)?;
+            }
+        }
+
+        writeln!(writer, "\nMost called packages:")?;
+        let packages = self.top_packages(TOP_PACKAGES);
+        if packages.is_empty() {
+            writeln!(writer, " None")?;
+        } else {
+            writeln!(writer, " {0: ^66} | {1: ^9}", "Package", "Calls")?;
+            writeln!(writer, "{}", ["-"; 80].join(""))?;
+            for (package, calls) in packages {
+                writeln!(writer, " {0: ^66} | {1: >9}", package, calls)?;
+            }
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp_ms() {
+        assert_eq!(parse_timestamp_ms("1970-01-02").unwrap(), 86_400_000);
+        assert_eq!(
+            parse_timestamp_ms("1970-01-02T00:00:01.5+00:00").unwrap(),
+            86_401_500
+        );
+        assert_eq!(
+            parse_timestamp_ms("1970-01-02T01:00:00+01:00").unwrap(),
+            86_400_000
+        );
+        assert!(parse_timestamp_ms("02/01/1970").is_err());
+        assert!(parse_timestamp_ms("1969-12-31").is_err());
+    }
+
+    #[test]
+    fn test_range() {
+        let summary =
+            AddressSummary::new(SuiAddress::random_for_testing_only(), Some(10), Some(20));
+        assert!(summary.is_before_range(Some(9)));
+        assert!(!summary.is_before_range(Some(10)));
+        assert!(!summary.is_before_range(None));
+        assert!(summary.is_in_range(Some(10)));
+        assert!(!summary.is_in_range(Some(20)));
+        // Transactions without a checkpoint yet are newer than any closed range.
+        assert!(!summary.is_in_range(None));
+
+        let summary = AddressSummary::new(SuiAddress::random_for_testing_only(), Some(10), None);
+        assert!(summary.is_in_range(None));
+        assert!(summary.is_in_range(Some(u64::MAX)));
+    }
+}
@@ -4,13 +4,20 @@
 use core::fmt;
 use std::sync::Arc;
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet, HashSet},
     fmt::{Debug, Display, Formatter, Write},
     path::{Path, PathBuf},
     time::Instant,
 };
 
+use crate::address_summary::{parse_timestamp_ms, AddressSummary};
+use crate::coin_cleanup::{CoinCleanupPlan, MergeTarget};
 use crate::config::{Config, PersistedConfig, SuiClientConfig, SuiEnv};
+use crate::gas_coins::{GasCoinSelection, SmashGasPlan, SplitGasPlan};
+use crate::keytool::read_transaction_file;
+use crate::ptb::{build_programmable_transaction, PtbCommand};
+use crate::sponsor::{sign_sponsored_transaction, sponsor_transaction, GasSponsor};
+use crate::watch::{watch, ws_url, WatchFilter};
 use anyhow::{anyhow, ensure};
 use bip32::DerivationPath;
 use clap::*;
@@ -37,22 +44,26 @@ use sui_framework_build::compiled_package::{
 };
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiRawData, SuiTransactionEffectsAPI, SuiTransactionResponse, SuiTransactionResponseOptions,
+    Coin, DryRunTransactionResponse, DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiRawData, SuiTransactionEffectsAPI, SuiTransactionResponse,
+    SuiTransactionResponseOptions, SuiTransactionResponseQuery,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
 use sui_keys::keystore::AccountKeystore;
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
 use sui_sdk::SuiClient;
-use sui_types::crypto::SignatureScheme;
+use sui_types::crypto::{default_hash, SignatureScheme};
 use sui_types::dynamic_field::DynamicFieldType;
 use sui_types::move_package::UpgradeCap;
 use sui_types::signature::GenericSignature;
 use sui_types::{
-    base_types::{ObjectID, ObjectRef, SuiAddress},
-    gas_coin::GasCoin,
-    messages::{Transaction, VerifiedTransaction},
+    base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest},
+    gas_coin::{GasCoin, GAS},
+    messages::{ProgrammableTransaction, Transaction, TransactionData, VerifiedTransaction},
     object::Owner,
-    parse_sui_type_tag, SUI_FRAMEWORK_ADDRESS,
+    parse_sui_type_tag,
+    query::TransactionFilter,
+    SUI_FRAMEWORK_ADDRESS,
 };
 use tokio::sync::RwLock;
 use tracing::{info, warn};
@@ -383,6 +394,27 @@ pub enum SuiClientCommands {
         address: Option<SuiAddress>,
     },
 
+    /// Summarize the activity of an address over a time range: transaction counts, gas spent,
+    /// net balance changes per coin type and most called packages
+    #[clap(name = "summary")]
+    Summary {
+        /// Address to summarize
+        /// Summarizes `sui client active-address` if no argument is passed
+        #[clap(name = "address")]
+        address: Option<SuiAddress>,
+        /// Start of the range (inclusive), as YYYY-MM-DD (midnight UTC) or an RFC 3339 timestamp.
+        /// Starts from the first transaction of the address if not provided
+        #[clap(long)]
+        start: Option<String>,
+        /// End of the range (exclusive), as YYYY-MM-DD (midnight UTC) or an RFC 3339 timestamp.
+        /// Ends with the latest transaction of the address if not provided
+        #[clap(long)]
+        end: Option<String>,
+        /// Maximum number of transactions to scan, newest first
+        #[clap(long, default_value = "10000")]
+        max_transactions: usize,
+    },
+
     /// Query a dynamic field by its address.
     #[clap(name = "dynamic-field")]
     DynamicFieldQuery {
@@ -435,6 +467,124 @@ pub enum SuiClientCommands {
         gas_budget: u64,
     },
 
+    /// Delete the zero-balance coins of the active address and merge its dust coins into the
+    /// largest coin of their type, reclaiming their storage rebates
+    #[clap(name = "clean-coins")]
+    CleanCoins {
+        /// Only clean up coins of this type, e.g. 0x2::sui::SUI. All coin types are cleaned up if
+        /// not provided
+        #[clap(long)]
+        coin_type: Option<String>,
+        /// Coins with a balance at or below this value are merged into the largest coin of their
+        /// type (the gas object for SUI)
+        #[clap(long, default_value = "0")]
+        dust_threshold: u64,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, the SUI coin with the largest balance is used
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for each of the cleanup transactions
+        #[clap(long)]
+        gas_budget: u64,
+        /// Only preview the coins to clean up and the storage rebate to reclaim
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    /// Merge the SUI coins of the active address into its largest coin, skipping coins that may
+    /// be locked by transactions in flight
+    #[clap(name = "smash-gas")]
+    SmashGas {
+        /// Only merge coins with a balance at or below this value. All coins are merged if not
+        /// provided
+        #[clap(long)]
+        max_balance: Option<u64>,
+        /// Coins to leave alone, e.g. the gas coins of transactions being sent
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        exclude: Vec<ObjectID>,
+        /// Gas budget for each of the merge transactions
+        #[clap(long)]
+        gas_budget: u64,
+        /// Only preview the coins to merge, and dry run the merge transactions
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    /// Split coins of the same amount from a SUI coin of the active address, to use as gas coins
+    /// of transactions sent in parallel. The smallest coin covering the split and the gas budgets
+    /// is split, skipping coins that may be locked by transactions in flight
+    #[clap(name = "split-gas")]
+    SplitGas {
+        /// Number of coins to split
+        #[clap(long)]
+        count: u64,
+        /// Balance of every coin split
+        #[clap(long)]
+        amount: u64,
+        /// Coins to leave alone, e.g. the gas coins of transactions being sent
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        exclude: Vec<ObjectID>,
+        /// Gas budget for each of the split transactions
+        #[clap(long)]
+        gas_budget: u64,
+        /// Only preview the coin to split, and dry run the split transactions
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    /// Stream the events, object changes and balance changes of the active address, or of the
+    /// given filters, as they happen. Requires the websocket endpoint of the node, derived from
+    /// its RPC URL if the environment has none
+    #[clap(name = "watch")]
+    Watch {
+        /// Address to watch instead of the active address
+        #[clap(long)]
+        address: Option<SuiAddress>,
+        /// Event filter in JSON, e.g. '{"MoveModule":{"package":"0x2","module":"coin"}}'.
+        /// Events of the transactions sent by the address are watched if not provided
+        #[clap(long)]
+        event_filter: Option<String>,
+        /// Object change filter in JSON, e.g. '{"ObjectType":"0x2::coin::Coin"}'. Changes of the
+        /// objects owned by the address are watched if not provided
+        #[clap(long)]
+        object_filter: Option<String>,
+        /// Print every item as a line of JSON
+        #[clap(long)]
+        json: bool,
+        /// Stop after this number of items. Watches until interrupted if not provided
+        #[clap(long)]
+        limit: Option<usize>,
+    },
+
+    /// Build a programmable transaction from a sequence of commands, e.g.
+    /// sui client ptb "split-coins gas [1000]" "transfer-objects [result(0).0] <address>"
+    /// --gas-budget 10000
+    #[clap(name = "ptb")]
+    Ptb {
+        /// Commands of the transaction, in order, each one quoted: split-coins <coin> [<amount>,
+        /// ..], merge-coins <coin> [<coin>, ..], transfer-objects [<object>, ..] <address>,
+        /// move-call <package>::<module>::<function><<type>, ..> <arg> .., publish <package path>.
+        /// Arguments are object IDs or values, `gas` for the gas coin, and `result(i)` or
+        /// `result(i).j` for the results of earlier commands
+        #[clap(required = true)]
+        commands: Vec<String>,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this transaction
+        #[clap(long)]
+        gas_budget: u64,
+        /// Dry run the transaction and print its effects, without signing or executing it
+        #[clap(long)]
+        preview: bool,
+        /// Have the gas paid by a sponsor: an address of the keystore, whose coin --gas is then,
+        /// or the URL of a gas station. The transaction is sent by the active address, and
+        /// signed by both
+        #[clap(long)]
+        gas_sponsor: Option<GasSponsor>,
+    },
+
     /// Create an example NFT
     #[clap(name = "create-example-nft")]
     CreateExampleNFT {
@@ -480,15 +630,48 @@ pub enum SuiClientCommands {
         amount: Option<u64>,
     },
 
+    /// Serialize a programmable transaction built from commands as in `sui client ptb`, and write
+    /// it to a file, to be signed elsewhere with `sui keytool sign-transaction`.
+    #[clap(name = "serialize-unsigned-tx")]
+    SerializeUnsignedTx {
+        /// Commands of the transaction, in order, as in `sui client ptb`
+        #[clap(required = true)]
+        commands: Vec<String>,
+
+        /// Sender of the transaction. Defaults to the owner of the gas object, or the active
+        /// address
+        #[clap(long)]
+        sender: Option<SuiAddress>,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this transaction
+        #[clap(long)]
+        gas_budget: u64,
+
+        /// File to write the BCS serialized transaction data to
+        #[clap(long)]
+        output: PathBuf,
+    },
+
     /// Execute a Signed Transaction. This is useful when the user prefers to sign elsewhere and use this command to execute.
     ExecuteSignedTx {
         /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
-        #[clap(long)]
-        tx_bytes: String,
+        #[clap(long, required_unless_present = "signed_tx_file")]
+        tx_bytes: Option<String>,
 
         /// A list of Base64 encoded signatures `flag || signature || pubkey`.
         #[clap(long)]
         signatures: Vec<String>,
+
+        /// File with the BCS serialized signed transaction, as written by
+        /// `sui keytool sign-transaction --offline`, instead of the transaction bytes and
+        /// signatures.
+        #[clap(long, conflicts_with_all = &["tx_bytes", "signatures"])]
+        signed_tx_file: Option<PathBuf>,
     },
 }
 
@@ -903,6 +1086,66 @@ impl SuiClientCommands {
                 SuiClientCommandResult::Objects(address_object.data)
             }
 
+            SuiClientCommands::Summary {
+                address,
+                start,
+                end,
+                max_transactions,
+            } => {
+                let address = address.unwrap_or(context.active_address()?);
+                let start_ms = start.as_deref().map(parse_timestamp_ms).transpose()?;
+                let end_ms = end.as_deref().map(parse_timestamp_ms).transpose()?;
+                if let (Some(start_ms), Some(end_ms)) = (start_ms, end_ms) {
+                    ensure!(
+                        start_ms < end_ms,
+                        "The start of the range must be before its end"
+                    );
+                }
+
+                let client = context.get_client().await?;
+                let mut summary = AddressSummary::new(address, start_ms, end_ms);
+                let mut scanned = 0;
+                // Transactions sent by the address, then transactions that sent objects to it.
+                // Transactions in both sets are only counted once.
+                'filters: for filter in [
+                    TransactionFilter::FromAddress(address),
+                    TransactionFilter::ToAddress(address),
+                ] {
+                    let query = SuiTransactionResponseQuery::new(
+                        Some(filter),
+                        Some(
+                            SuiTransactionResponseOptions::new()
+                                .with_input()
+                                .with_effects()
+                                .with_balance_changes(),
+                        ),
+                    );
+                    let mut cursor = None;
+                    loop {
+                        let page = client
+                            .read_api()
+                            .query_transactions(query.clone(), cursor, None, true)
+                            .await?;
+                        for response in &page.data {
+                            if summary.is_before_range(response.timestamp_ms) {
+                                continue 'filters;
+                            }
+                            if scanned >= max_transactions {
+                                summary.truncated = true;
+                                break 'filters;
+                            }
+                            scanned += 1;
+                            summary.record(response)?;
+                        }
+                        if !page.has_next_page {
+                            break;
+                        }
+                        cursor = page.next_cursor;
+                    }
+                }
+                SuiClientCommandResult::Summary(summary)
+            }
+
             SuiClientCommands::NewAddress {
                 key_scheme,
                 derivation_path,
@@ -992,6 +1235,249 @@ impl SuiClientCommands {
 
                 SuiClientCommandResult::MergeCoin(response)
             }
+            SuiClientCommands::CleanCoins {
+                coin_type,
+                dust_threshold,
+                gas,
+                gas_budget,
+                dry_run,
+            } => {
+                let owner = context.active_address()?;
+                let client = context.get_client().await?;
+                let gas = match gas {
+                    Some(gas) => gas,
+                    None => context
+                        .gas_objects(owner)
+                        .await?
+                        .into_iter()
+                        .max_by_key(|(value, _)| *value)
+                        .map(|(_, object)| object.object_id)
+                        .ok_or_else(|| anyhow!("No gas object found for address {owner}"))?,
+                };
+
+                let coins = get_all_coins(&client, owner, coin_type).await?;
+                let mut coins_with_rebate = Vec::with_capacity(coins.len());
+                for chunk in coins.chunks(MULTI_GET_OBJECT_CHUNK_SIZE) {
+                    let responses = client
+                        .read_api()
+                        .multi_get_object_with_options(
+                            chunk.iter().map(|coin| coin.coin_object_id).collect(),
+                            SuiObjectDataOptions {
+                                show_storage_rebate: true,
+                                ..Default::default()
+                            },
+                        )
+                        .await?;
+                    for (coin, response) in chunk.iter().zip(responses) {
+                        let storage_rebate = response.object()?.storage_rebate.unwrap_or_default();
+                        coins_with_rebate.push((coin.clone(), storage_rebate));
+                    }
+                }
+
+                // Batch sizes follow the limits of the network's protocol version.
+                let protocol_config = latest_protocol_config(&client).await?;
+                let plan =
+                    CoinCleanupPlan::new(coins_with_rebate, gas, dust_threshold, &protocol_config)?;
+
+                let mut responses = vec![];
+                if !dry_run {
+                    let gas_price = client.read_api().get_reference_gas_price().await?;
+                    for batch in &plan.batches {
+                        // The gas coin and the coins merged into change with every transaction.
+                        let merge_target = match batch.merge_target {
+                            Some(MergeTarget::Coin(id)) => Some(context.get_object_ref(id).await?),
+                            _ => None,
+                        };
+                        let data = TransactionData::new_programmable(
+                            owner,
+                            vec![context.get_object_ref(gas).await?],
+                            batch.build(merge_target)?,
+                            gas_budget,
+                            gas_price,
+                        );
+                        let signature = context.config.keystore.sign_secure(
+                            &owner,
+                            &data,
+                            Intent::default(),
+                        )?;
+                        let response = context
+                            .execute_transaction(
+                                Transaction::from_data(data, Intent::default(), vec![signature])
+                                    .verify()?,
+                            )
+                            .await?;
+                        let effects = response.effects.as_ref().ok_or_else(|| {
+                            anyhow!("Effects from SuiTransactionResult should not be empty")
+                        })?;
+                        if matches!(effects.status(), SuiExecutionStatus::Failure { .. }) {
+                            return Err(anyhow!(
+                                "Error executing coin cleanup transaction: {:#?}",
+                                effects.status()
+                            ));
+                        }
+                        responses.push(response);
+                    }
+                }
+                SuiClientCommandResult::CleanCoins(plan, responses)
+            }
+            SuiClientCommands::SmashGas {
+                max_balance,
+                exclude,
+                gas_budget,
+                dry_run,
+            } => {
+                let owner = context.active_address()?;
+                let client = context.get_client().await?;
+                let (selection, storage_rebates) =
+                    select_gas_coins(&client, owner, &exclude).await?;
+                let protocol_config = latest_protocol_config(&client).await?;
+                let plan =
+                    SmashGasPlan::new(selection, &storage_rebates, max_balance, &protocol_config)?;
+
+                let mut transactions = vec![];
+                for batch in &plan.cleanup.batches {
+                    transactions.push(batch.build(None)?);
+                }
+                let gas = plan.target.coin_object_id;
+                let (dry_runs, responses) =
+                    run_gas_transactions(context, owner, gas, transactions, gas_budget, dry_run)
+                        .await?;
+                SuiClientCommandResult::SmashGas(plan, dry_runs, responses)
+            }
+            SuiClientCommands::SplitGas {
+                count,
+                amount,
+                exclude,
+                gas_budget,
+                dry_run,
+            } => {
+                let owner = context.active_address()?;
+                let client = context.get_client().await?;
+                let (selection, _) = select_gas_coins(&client, owner, &exclude).await?;
+                let protocol_config = latest_protocol_config(&client).await?;
+                let plan =
+                    SplitGasPlan::new(selection, count, amount, gas_budget, &protocol_config)?;
+
+                let mut transactions = vec![];
+                for count in &plan.batches {
+                    transactions.push(plan.build(owner, *count)?);
+                }
+                let gas = plan.source.coin_object_id;
+                let (dry_runs, responses) =
+                    run_gas_transactions(context, owner, gas, transactions, gas_budget, dry_run)
+                        .await?;
+                SuiClientCommandResult::SplitGas(plan, dry_runs, responses)
+            }
+            SuiClientCommands::Watch {
+                address,
+                event_filter,
+                object_filter,
+                json,
+                limit,
+            } => {
+                let address = match address {
+                    Some(address) => address,
+                    None => context.active_address()?,
+                };
+                let mut filter = WatchFilter::for_address(address);
+                if let Some(event_filter) = event_filter {
+                    filter.events = serde_json::from_str(&event_filter)
+                        .map_err(|e| anyhow!("Invalid event filter {event_filter}: {e}"))?;
+                    filter.address = None;
+                }
+                if let Some(object_filter) = object_filter {
+                    filter.object_changes = serde_json::from_str(&object_filter).map_err(|e| {
+                        anyhow!("Invalid object change filter {object_filter}: {e}")
+                    })?;
+                    filter.address = None;
+                }
+
+                let mut env = context.config.get_active_env()?.clone();
+                if env.ws.is_none() {
+                    env.ws = Some(ws_url(&env.rpc)?);
+                }
+                let client = env.create_rpc_client(context.request_timeout).await?;
+                let count = watch(&client, filter, limit, |item| {
+                    if json {
+                        match serde_json::to_string(item) {
+                            Ok(line) => println!("{line}"),
+                            Err(e) => eprintln!("Can't serialize {item}: {e}"),
+                        }
+                    } else {
+                        println!("{item}");
+                    }
+                })
+                .await?;
+                SuiClientCommandResult::Watch(count)
+            }
+            SuiClientCommands::Ptb {
+                commands,
+                gas,
+                gas_budget,
+                preview,
+                gas_sponsor,
+            } => {
+                let sender = match gas_sponsor {
+                    Some(_) => context.active_address()?,
+                    None => {
+                        let sender = context.try_get_object_owner(&gas).await?;
+                        sender.unwrap_or(context.active_address()?)
+                    }
+                };
+                let client = context.get_client().await?;
+
+                let commands = commands
+                    .iter()
+                    .map(|command| command.parse())
+                    .collect::<Result<Vec<PtbCommand>, _>>()?;
+                let pt = build_programmable_transaction(context, &client, commands).await?;
+                let (data, sponsor_signature) = match &gas_sponsor {
+                    Some(sponsor) => {
+                        let (data, signature) = sponsor_transaction(
+                            context, &client, sponsor, sender, pt, gas, gas_budget,
+                        )
+                        .await?;
+                        (data, Some(signature))
+                    }
+                    None => {
+                        let data = client
+                            .transaction_builder()
+                            .programmable_transaction(sender, pt, gas, gas_budget)
+                            .await?;
+                        (data, None)
+                    }
+                };
+
+                if preview {
+                    let response = client.read_api().dry_run_transaction(data).await?;
+                    SuiClientCommandResult::PtbPreview(response)
+                } else {
+                    let transaction = match sponsor_signature {
+                        Some(sponsor_signature) => {
+                            sign_sponsored_transaction(context, data, sponsor_signature)?
+                        }
+                        None => {
+                            let signature = context.config.keystore.sign_secure(
+                                &sender,
+                                &data,
+                                Intent::default(),
+                            )?;
+                            Transaction::from_data(data, Intent::default(), vec![signature])
+                        }
+                    };
+                    let response = context.execute_transaction(transaction.verify()?).await?;
+                    let effects = response.effects.as_ref().ok_or_else(|| {
+                        anyhow!("Effects from SuiTransactionResult should not be empty")
+                    })?;
+                    if matches!(effects.status(), SuiExecutionStatus::Failure { .. }) {
+                        return Err(anyhow!(
+                            "Error executing programmable transaction: {:#?}",
+                            effects.status()
+                        ));
+                    }
+                    SuiClientCommandResult::Ptb(response)
+                }
+            }
             SuiClientCommands::Switch { address, env } => {
                 match (address, &env) {
                     (None, Some(env)) => {
@@ -1071,31 +1557,76 @@ impl SuiClientCommands {
                 ))
             }
 
+            SuiClientCommands::SerializeUnsignedTx {
+                commands,
+                sender,
+                gas,
+                gas_budget,
+                output,
+            } => {
+                let sender = match sender {
+                    Some(sender) => sender,
+                    None => context
+                        .try_get_object_owner(&gas)
+                        .await?
+                        .unwrap_or(context.active_address()?),
+                };
+                let client = context.get_client().await?;
+
+                let commands = commands
+                    .iter()
+                    .map(|command| command.parse())
+                    .collect::<Result<Vec<PtbCommand>, _>>()?;
+                let pt = build_programmable_transaction(context, &client, commands).await?;
+                let data = client
+                    .transaction_builder()
+                    .programmable_transaction(sender, pt, gas, gas_budget)
+                    .await?;
+                std::fs::write(&output, bcs::to_bytes(&data)?)?;
+                SuiClientCommandResult::SerializeUnsignedTx(
+                    TransactionDigest::new(default_hash(&data)),
+                    output,
+                )
+            }
+
             SuiClientCommands::ExecuteSignedTx {
                 tx_bytes,
                 signatures,
+                signed_tx_file,
             } => {
-                let data = bcs::from_bytes(
-                    &Base64::try_from(tx_bytes)
-                        .map_err(|e| anyhow!(e))?
-                        .to_vec()
-                        .map_err(|e| anyhow!(e))?,
-                )?;
-
-                let mut sigs = Vec::new();
-                for sig in signatures {
-                    sigs.push(
-                        GenericSignature::from_bytes(
-                            &Base64::try_from(sig)
+                let transaction = match (tx_bytes, signed_tx_file) {
+                    (_, Some(signed_tx_file)) => {
+                        Transaction::new(read_transaction_file(&signed_tx_file, None)?)
+                    }
+                    (Some(tx_bytes), None) => {
+                        let data = bcs::from_bytes(
+                            &Base64::try_from(tx_bytes)
                                 .map_err(|e| anyhow!(e))?
                                 .to_vec()
                                 .map_err(|e| anyhow!(e))?,
-                        )
-                        .map_err(|e| anyhow!(e))?,
-                    );
-                }
-                let verified =
-                    Transaction::from_generic_sig_data(data, Intent::default(), sigs).verify()?;
+                        )?;
+
+                        let mut sigs = Vec::new();
+                        for sig in signatures {
+                            sigs.push(
+                                GenericSignature::from_bytes(
+                                    &Base64::try_from(sig)
+                                        .map_err(|e| anyhow!(e))?
+                                        .to_vec()
+                                        .map_err(|e| anyhow!(e))?,
+                                )
+                                .map_err(|e| anyhow!(e))?,
+                            );
+                        }
+                        Transaction::from_generic_sig_data(data, Intent::default(), sigs)
+                    }
+                    (None, None) => {
+                        return Err(anyhow!(
+                            "Either the transaction bytes or a signed transaction file is required"
+                        ))
+                    }
+                };
+                let verified = transaction.verify()?;
 
                 let response = context.execute_transaction(verified).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
@@ -1174,7 +1705,7 @@ impl SuiClientCommands {
     }
 }
 
-async fn compile_package(
+pub(crate) async fn compile_package(
     client: &SuiClient,
     build_config: MoveBuildConfig,
     package_path: PathBuf,
@@ -1519,6 +2050,9 @@ impl Display for SuiClientCommandResult {
                 }
                 writeln!(writer, "Showing {} results.", object_refs.len())?;
             }
+            SuiClientCommandResult::Summary(summary) => {
+                writeln!(writer, "{summary}")?;
+            }
             SuiClientCommandResult::DynamicFieldQuery(df_refs) => {
                 let mut table: Table = table!([
                     "Name",
@@ -1558,7 +2092,10 @@ impl Display for SuiClientCommandResult {
                     "Created new keypair for address with scheme {:?}: [{address}]",
                     scheme
                 );
-                println!("Secret Recovery Phrase : [{recovery_phrase}]");
+                // Keys held by a hardware wallet have no recovery phrase of their own.
+                if !recovery_phrase.is_empty() {
+                    println!("Secret Recovery Phrase : [{recovery_phrase}]");
+                }
             }
             SuiClientCommandResult::Gas(gases) => {
                 // TODO: generalize formatting of CLI
@@ -1577,6 +2114,45 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::MergeCoin(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::CleanCoins(plan, responses) => {
+                if plan.is_empty() {
+                    writeln!(writer, "No coins to clean up.")?;
+                } else {
+                    writeln!(writer, "{plan}")?;
+                }
+                for response in responses {
+                    write!(writer, "{}", write_transaction_response(response)?)?;
+                }
+            }
+            SuiClientCommandResult::SmashGas(plan, dry_runs, responses) => {
+                if plan.cleanup.is_empty() {
+                    writeln!(writer, "No coins to smash.")?;
+                } else {
+                    writeln!(writer, "{plan}")?;
+                }
+                for response in dry_runs {
+                    write!(writer, "{}", write_dry_run_response(response)?)?;
+                }
+                for response in responses {
+                    write!(writer, "{}", write_transaction_response(response)?)?;
+                }
+            }
+            SuiClientCommandResult::SplitGas(plan, dry_runs, responses) => {
+                writeln!(writer, "{plan}")?;
+                for response in dry_runs {
+                    write!(writer, "{}", write_dry_run_response(response)?)?;
+                }
+                for response in responses {
+                    write!(writer, "{}", write_transaction_response(response)?)?;
+                }
+            }
+            SuiClientCommandResult::Watch(_) => {}
+            SuiClientCommandResult::Ptb(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
+            SuiClientCommandResult::PtbPreview(response) => {
+                write!(writer, "{}", write_dry_run_response(response)?)?;
+            }
             SuiClientCommandResult::Switch(response) => {
                 write!(writer, "{}", response)?;
             }
@@ -1598,6 +2174,10 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::SerializeTransferSui(data) => {
                 writeln!(writer, "Raw tx_bytes to execute: {}", data)?;
             }
+            SuiClientCommandResult::SerializeUnsignedTx(digest, output) => {
+                writeln!(writer, "Transaction digest: {digest}")?;
+                writeln!(writer, "Unsigned transaction written to: {output:?}")?;
+            }
             SuiClientCommandResult::ActiveEnv(env) => {
                 write!(writer, "{}", env.as_deref().unwrap_or("None"))?;
             }
@@ -1621,6 +2201,161 @@ impl Display for SuiClientCommandResult {
     }
 }
 
+/// Maximum number of objects requested at once from the fullnode
+const MULTI_GET_OBJECT_CHUNK_SIZE: usize = 500;
+
+/// Selects the SUI coins of `owner` that are unlikely to be locked by a transaction in flight,
+/// see [crate::gas_coins], and returns them with the storage rebate of the coins.
+async fn select_gas_coins(
+    client: &SuiClient,
+    owner: SuiAddress,
+    exclude: &[ObjectID],
+) -> Result<(GasCoinSelection, BTreeMap<ObjectID, u64>), anyhow::Error> {
+    let coins = get_all_coins(client, owner, Some(GAS::type_().to_string())).await?;
+
+    let mut latest_versions = BTreeMap::new();
+    let mut storage_rebates = BTreeMap::new();
+    for chunk in coins.chunks(MULTI_GET_OBJECT_CHUNK_SIZE) {
+        let responses = client
+            .read_api()
+            .multi_get_object_with_options(
+                chunk.iter().map(|coin| coin.coin_object_id).collect(),
+                SuiObjectDataOptions {
+                    show_storage_rebate: true,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        for response in responses {
+            if let Ok(object) = response.object() {
+                latest_versions.insert(object.object_id, object.version);
+                storage_rebates.insert(object.object_id, object.storage_rebate.unwrap_or_default());
+            }
+        }
+    }
+
+    let digests: Vec<_> = coins
+        .iter()
+        .map(|coin| coin.previous_transaction)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let mut unfinalized = HashSet::new();
+    for chunk in digests.chunks(MULTI_GET_OBJECT_CHUNK_SIZE) {
+        let responses = client
+            .read_api()
+            .multi_get_transactions_with_options(
+                chunk.to_vec(),
+                SuiTransactionResponseOptions::new(),
+            )
+            .await?;
+        unfinalized.extend(
+            responses
+                .into_iter()
+                .filter(|response| response.checkpoint.is_none())
+                .map(|response| response.digest),
+        );
+    }
+
+    let selection = GasCoinSelection::new(coins, exclude, &latest_versions, &unfinalized);
+    Ok((selection, storage_rebates))
+}
+
+/// The protocol config of the network, or of the latest version known to this binary if the
+/// network is ahead.
+async fn latest_protocol_config(client: &SuiClient) -> Result<ProtocolConfig, anyhow::Error> {
+    let protocol_version = client
+        .governance_api()
+        .get_latest_sui_system_state()
+        .await?
+        .protocol_version
+        .min(ProtocolVersion::MAX.as_u64());
+    Ok(ProtocolConfig::get_for_version(ProtocolVersion::new(
+        protocol_version,
+    )))
+}
+
+/// Dry runs or executes `transactions` in order, all paying gas with `gas`.
+async fn run_gas_transactions(
+    context: &mut WalletContext,
+    owner: SuiAddress,
+    gas: ObjectID,
+    transactions: Vec<ProgrammableTransaction>,
+    gas_budget: u64,
+    dry_run: bool,
+) -> Result<(Vec<DryRunTransactionResponse>, Vec<SuiTransactionResponse>), anyhow::Error> {
+    let client = context.get_client().await?;
+    let gas_price = client.read_api().get_reference_gas_price().await?;
+    let mut dry_runs = vec![];
+    let mut responses = vec![];
+    for pt in transactions {
+        // The gas coin changes with every transaction.
+        let data = TransactionData::new_programmable(
+            owner,
+            vec![context.get_object_ref(gas).await?],
+            pt,
+            gas_budget,
+            gas_price,
+        );
+        if dry_run {
+            dry_runs.push(client.read_api().dry_run_transaction(data).await?);
+            continue;
+        }
+        let signature = context
+            .config
+            .keystore
+            .sign_secure(&owner, &data, Intent::default())?;
+        let response = context
+            .execute_transaction(
+                Transaction::from_data(data, Intent::default(), vec![signature]).verify()?,
+            )
+            .await?;
+        let effects = response
+            .effects
+            .as_ref()
+            .ok_or_else(|| anyhow!("Effects from SuiTransactionResult should not be empty"))?;
+        if matches!(effects.status(), SuiExecutionStatus::Failure { .. }) {
+            return Err(anyhow!(
+                "Error executing gas coin transaction: {:#?}",
+                effects.status()
+            ));
+        }
+        responses.push(response);
+    }
+    Ok((dry_runs, responses))
+}
+
+/// Fetches every coin of `coin_type` owned by `owner`, or all of its coins if no type is given.
+async fn get_all_coins(
+    client: &SuiClient,
+    owner: SuiAddress,
+    coin_type: Option<String>,
+) -> Result<Vec<Coin>, anyhow::Error> {
+    let mut coins = vec![];
+    let mut cursor = None;
+    loop {
+        let page = match &coin_type {
+            Some(coin_type) => {
+                client
+                    .coin_read_api()
+                    .get_coins(owner, Some(coin_type.clone()), cursor, None)
+                    .await?
+            }
+            None => {
+                client
+                    .coin_read_api()
+                    .get_all_coins(owner, cursor, None)
+                    .await?
+            }
+        };
+        coins.extend(page.data);
+        if !page.has_next_page {
+            return Ok(coins);
+        }
+        cursor = page.next_cursor;
+    }
+}
+
 pub async fn call_move(
     package: ObjectID,
     module: &str,
@@ -1726,6 +2461,25 @@ pub fn write_transaction_response(response: &SuiTransactionResponse) -> Result<S
     Ok(writer)
 }
 
+pub fn write_dry_run_response(response: &DryRunTransactionResponse) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(
+        writer,
+        "{}",
+        "----- Transaction Effects (dry run) ----".bold()
+    )?;
+    writeln!(writer, "{}", response.effects)?;
+
+    writeln!(writer, "{}", "----- Events ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.events))?;
+
+    if let Some(gas_breakdown) = &response.gas_breakdown {
+        writeln!(writer, "{}", "----- Gas breakdown ----".bold())?;
+        writeln!(writer, "{:#?}", json!(gas_breakdown))?;
+    }
+    Ok(writer)
+}
+
 impl Debug for SuiClientCommandResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let s = unwrap_err_to_string(|| match self {
@@ -1786,18 +2540,35 @@ pub enum SuiClientCommandResult {
     PayAllSui(SuiTransactionResponse),
     Addresses(Vec<SuiAddress>, Option<SuiAddress>),
     Objects(Vec<SuiObjectResponse>),
+    Summary(AddressSummary),
     DynamicFieldQuery(DynamicFieldPage),
     SyncClientState,
     NewAddress((SuiAddress, String, SignatureScheme)),
     Gas(Vec<GasCoin>),
     SplitCoin(SuiTransactionResponse),
     MergeCoin(SuiTransactionResponse),
+    CleanCoins(CoinCleanupPlan, Vec<SuiTransactionResponse>),
+    SmashGas(
+        SmashGasPlan,
+        Vec<DryRunTransactionResponse>,
+        Vec<SuiTransactionResponse>,
+    ),
+    SplitGas(
+        SplitGasPlan,
+        Vec<DryRunTransactionResponse>,
+        Vec<SuiTransactionResponse>,
+    ),
+    /// Number of items streamed, which were printed as they arrived.
+    Watch(usize),
+    Ptb(SuiTransactionResponse),
+    PtbPreview(DryRunTransactionResponse),
     Switch(SwitchResponse),
     ActiveAddress(Option<SuiAddress>),
     ActiveEnv(Option<String>),
     Envs(Vec<SuiEnv>, Option<String>),
     CreateExampleNFT(SuiObjectResponse),
     SerializeTransferSui(String),
+    SerializeUnsignedTx(TransactionDigest, PathBuf),
     ExecuteSignedTx(SuiTransactionResponse),
     NewEnv(SuiEnv),
 }
@@ -0,0 +1,307 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Planning for `sui client clean-coins`.
+//!
+//! Every coin object carries a storage rebate that is paid back when the object is deleted.
+//! Wallets that receive many small payments accumulate coins whose balance is worth less than
+//! their rebate. Cleaning them up deletes zero-balance coins with `coin::destroy_zero`, and merges
+//! coins at or below a dust threshold into the largest coin of the same type (the gas coin for
+//! SUI), so no balance is lost.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Write};
+
+use anyhow::anyhow;
+use move_core_types::identifier::Identifier;
+use serde::Serialize;
+use sui_json_rpc_types::Coin;
+use sui_protocol_config::ProtocolConfig;
+use sui_types::base_types::{ObjectID, ObjectRef};
+use sui_types::gas_coin::GAS;
+use sui_types::messages::{Argument, Command, ObjectArg, ProgrammableTransaction};
+use sui_types::parse_sui_type_tag;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::SUI_FRAMEWORK_OBJECT_ID;
+
+/// A coin scheduled for deletion, either directly or by merging it into another coin.
+#[derive(Serialize, Clone, Debug)]
+pub struct CleanupCoin {
+    pub object_ref: ObjectRef,
+    pub balance: u64,
+    pub storage_rebate: u64,
+}
+
+/// The coin that dust of a given type is merged into.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeTarget {
+    /// The gas coin of the transaction, used for SUI.
+    GasCoin,
+    Coin(ObjectID),
+}
+
+/// Coins of a single type that are cleaned up by one transaction.
+#[derive(Serialize, Clone, Debug)]
+pub struct CleanupBatch {
+    pub coin_type: String,
+    /// Zero-balance coins deleted with `coin::destroy_zero`.
+    pub destroy: Vec<CleanupCoin>,
+    /// Dust coins merged into `merge_target`.
+    pub merge: Vec<CleanupCoin>,
+    pub merge_target: Option<MergeTarget>,
+}
+
+impl CleanupBatch {
+    pub fn coins(&self) -> impl Iterator<Item = &CleanupCoin> {
+        self.destroy.iter().chain(self.merge.iter())
+    }
+
+    pub fn storage_rebate(&self) -> u64 {
+        self.coins().map(|coin| coin.storage_rebate).sum()
+    }
+
+    /// Builds the transaction for this batch. `merge_target` is the current reference of the coin
+    /// dust is merged into, required when it is not the gas coin.
+    pub fn build(
+        &self,
+        merge_target: Option<ObjectRef>,
+    ) -> Result<ProgrammableTransaction, anyhow::Error> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        if !self.destroy.is_empty() {
+            let type_arg = parse_sui_type_tag(&self.coin_type)?;
+            for coin in &self.destroy {
+                let arg = builder.obj(ObjectArg::ImmOrOwnedObject(coin.object_ref))?;
+                builder.programmable_move_call(
+                    SUI_FRAMEWORK_OBJECT_ID,
+                    Identifier::new("coin")?,
+                    Identifier::new("destroy_zero")?,
+                    vec![type_arg.clone()],
+                    vec![arg],
+                );
+            }
+        }
+        if !self.merge.is_empty() {
+            let target = match (self.merge_target, merge_target) {
+                (Some(MergeTarget::GasCoin), _) => Argument::GasCoin,
+                (Some(MergeTarget::Coin(_)), Some(target)) => {
+                    builder.obj(ObjectArg::ImmOrOwnedObject(target))?
+                }
+                _ => return Err(anyhow!("Missing coin to merge {} into", self.coin_type)),
+            };
+            let coins = self
+                .merge
+                .iter()
+                .map(|coin| builder.obj(ObjectArg::ImmOrOwnedObject(coin.object_ref)))
+                .collect::<Result<_, _>>()?;
+            builder.command(Command::MergeCoins(target, coins));
+        }
+        Ok(builder.finish())
+    }
+}
+
+/// The cleanup of all coins of an address, split into transactions that stay under the protocol
+/// limits.
+#[derive(Serialize, Clone, Debug)]
+pub struct CoinCleanupPlan {
+    pub dust_threshold: u64,
+    pub batches: Vec<CleanupBatch>,
+    /// Share of the storage rebate paid back to the sender, in basis points.
+    pub storage_rebate_rate: u64,
+}
+
+impl CoinCleanupPlan {
+    /// Plans the cleanup of `coins`, given with their storage rebate. The gas coin and coins with
+    /// a lock are never touched.
+    pub fn new(
+        coins: Vec<(Coin, u64)>,
+        gas: ObjectID,
+        dust_threshold: u64,
+        protocol_config: &ProtocolConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let max_coins_per_batch = max_coins_per_transaction(protocol_config);
+
+        let mut coins_by_type: BTreeMap<String, Vec<(Coin, u64)>> = BTreeMap::new();
+        for (coin, storage_rebate) in coins {
+            if coin.locked_until_epoch.is_some() || coin.coin_object_id == gas {
+                continue;
+            }
+            coins_by_type
+                .entry(coin.coin_type.clone())
+                .or_default()
+                .push((coin, storage_rebate));
+        }
+
+        let mut batches = vec![];
+        for (coin_type, mut coins) in coins_by_type {
+            // Largest coin first. Except for SUI, which goes into the gas coin, dust is merged
+            // into the largest coin of its type.
+            coins.sort_by(|(a, _), (b, _)| {
+                (b.balance, b.coin_object_id).cmp(&(a.balance, a.coin_object_id))
+            });
+            let merge_target = if GAS::is_gas_type(&parse_sui_type_tag(&coin_type)?) {
+                MergeTarget::GasCoin
+            } else {
+                let primary = coins.remove(0).0;
+                MergeTarget::Coin(primary.coin_object_id)
+            };
+
+            let (destroy, merge): (Vec<_>, Vec<_>) = coins
+                .into_iter()
+                .filter(|(coin, _)| coin.balance <= dust_threshold)
+                .map(|(coin, storage_rebate)| CleanupCoin {
+                    object_ref: coin.object_ref(),
+                    balance: coin.balance,
+                    storage_rebate,
+                })
+                .partition(|coin| coin.balance == 0);
+
+            for chunk in destroy.chunks(max_coins_per_batch) {
+                batches.push(CleanupBatch {
+                    coin_type: coin_type.clone(),
+                    destroy: chunk.to_vec(),
+                    merge: vec![],
+                    merge_target: None,
+                });
+            }
+            for chunk in merge.chunks(max_coins_per_batch) {
+                batches.push(CleanupBatch {
+                    coin_type: coin_type.clone(),
+                    destroy: vec![],
+                    merge: chunk.to_vec(),
+                    merge_target: Some(merge_target),
+                });
+            }
+        }
+
+        Ok(Self {
+            dust_threshold,
+            batches,
+            storage_rebate_rate: protocol_config.storage_rebate_rate(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    pub fn storage_rebate(&self) -> u64 {
+        self.batches.iter().map(CleanupBatch::storage_rebate).sum()
+    }
+
+    /// The part of the storage rebate that is paid back to the sender.
+    pub fn reclaimable_storage_rebate(&self) -> u64 {
+        (self.storage_rebate() as u128 * self.storage_rebate_rate as u128 / 10_000) as u64
+    }
+}
+
+/// Maximum number of coins handled by one transaction. Each transaction deletes coins of a single
+/// type, either with one `destroy_zero` call per coin or with a single `MergeCoins` command, and
+/// also takes the gas coin and possibly the coin merged into as inputs.
+fn max_coins_per_transaction(protocol_config: &ProtocolConfig) -> usize {
+    let max_inputs = protocol_config.max_input_objects() as usize - 2;
+    let max_arguments = protocol_config.max_arguments() as usize - 1;
+    let max_commands = protocol_config.max_programmable_tx_commands() as usize - 1;
+    max_inputs.min(max_arguments).min(max_commands).max(1)
+}
+
+impl Display for CoinCleanupPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut totals: BTreeMap<&str, (usize, usize, u64, u64)> = BTreeMap::new();
+        for batch in &self.batches {
+            let entry = totals.entry(&batch.coin_type).or_default();
+            entry.0 += batch.destroy.len();
+            entry.1 += batch.merge.len();
+            entry.2 += batch.merge.iter().map(|coin| coin.balance).sum::<u64>();
+            entry.3 += batch.storage_rebate();
+        }
+
+        let mut writer = String::new();
+        writeln!(
+            writer,
+            " {0: ^60} | {1: ^9} | {2: ^9} | {3: ^14} | {4: ^14}",
+            "Coin Type", "Deleted", "Merged", "Merged Value", "Storage Rebate"
+        )?;
+        writeln!(writer, "{}", ["-"; 118].join(""))?;
+        for (coin_type, (destroyed, merged, merged_value, storage_rebate)) in totals {
+            writeln!(
+                writer,
+                " {0: ^60} | {1: ^9} | {2: ^9} | {3: ^14} | {4: ^14}",
+                coin_type, destroyed, merged, merged_value, storage_rebate
+            )?;
+        }
+        writeln!(
+            writer,
+            "Transactions: {}, total storage rebate: {}, reclaimable after the {}bps rebate rate: {}",
+            self.batches.len(),
+            self.storage_rebate(),
+            self.storage_rebate_rate,
+            self.reclaimable_storage_rebate()
+        )?;
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::{random_object_ref, TransactionDigest};
+
+    fn coin(coin_type: &str, balance: u64) -> (Coin, u64) {
+        let (coin_object_id, version, digest) = random_object_ref();
+        let coin = Coin {
+            coin_type: coin_type.to_string(),
+            coin_object_id,
+            version,
+            digest,
+            balance,
+            locked_until_epoch: None,
+            previous_transaction: TransactionDigest::random(),
+        };
+        (coin, 100)
+    }
+
+    #[test]
+    fn test_plan_cleanup() {
+        let config = ProtocolConfig::get_for_max_version();
+        let batch_size = max_coins_per_transaction(&config);
+        let sui = "0x2::sui::SUI";
+        let usdc = "0x42::usdc::USDC";
+
+        let gas = coin(sui, 1_000_000);
+        let gas_id = gas.0.coin_object_id;
+        let primary = coin(usdc, 1);
+        let primary_id = primary.0.coin_object_id;
+
+        let mut coins = vec![gas, primary, coin(sui, 5_000), coin(usdc, 0)];
+        coins.extend((0..batch_size + 1).map(|_| coin(sui, 1)));
+        coins.extend((0..3).map(|_| coin(sui, 0)));
+
+        let plan = CoinCleanupPlan::new(coins, gas_id, 10, &config).unwrap();
+        let summary: Vec<_> = plan
+            .batches
+            .iter()
+            .map(|b| (b.coin_type.as_str(), b.destroy.len(), b.merge.len()))
+            .collect();
+        // Coins above the threshold and the coins merged into are left alone.
+        assert_eq!(
+            summary,
+            vec![(sui, 3, 0), (sui, 0, batch_size), (sui, 0, 1), (usdc, 1, 0),]
+        );
+        assert_eq!(plan.batches[1].merge_target, Some(MergeTarget::GasCoin));
+        assert_eq!(plan.storage_rebate(), (batch_size as u64 + 5) * 100);
+        assert!(plan
+            .batches
+            .iter()
+            .flat_map(CleanupBatch::coins)
+            .all(|coin| coin.object_ref.0 != gas_id && coin.object_ref.0 != primary_id));
+
+        let tx = plan.batches[0].build(None).unwrap();
+        assert_eq!(tx.commands.len(), 3);
+        let tx = plan.batches[1].build(None).unwrap();
+        assert_eq!(tx.inputs.len(), batch_size);
+        assert!(matches!(
+            &tx.commands[..],
+            [Command::MergeCoins(Argument::GasCoin, coins)] if coins.len() == batch_size
+        ));
+    }
+}
@@ -0,0 +1,394 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Planning for `sui client smash-gas` and `sui client split-gas`.
+//!
+//! Smashing merges the SUI coins of an address into its largest coin, so that dust coins stop
+//! being picked as gas. Splitting does the opposite, and pre-splits a coin into coins of a given
+//! amount, so that transactions can be submitted in parallel, each with its own gas coin.
+//!
+//! Neither ever touches a coin that may be locked by a transaction in flight: a coin owned by an
+//! address is locked by the first transaction signed with it until that transaction is finalized,
+//! and a second transaction using it fails. The fullnode does not know about the locks held by
+//! validators, so coins are skipped on the following heuristics:
+//! - coins whose version indexed by the fullnode is not the latest version of the object, as
+//!   another transaction just used them,
+//! - coins created or changed by a transaction that is not yet part of a checkpoint, as they are
+//!   likely to be picked by the other transactions the address is sending at the same time,
+//! - time-locked coins, and coins excluded explicitly.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter, Write};
+
+use anyhow::{anyhow, ensure};
+use serde::Serialize;
+use sui_json_rpc_types::Coin;
+use sui_protocol_config::ProtocolConfig;
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::messages::{Argument, Command, ProgrammableTransaction};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+use crate::coin_cleanup::CoinCleanupPlan;
+
+/// Why a coin was left out of the coins to smash or split.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    Excluded,
+    TimeLocked,
+    /// The object has a newer version than the coin indexed by the fullnode.
+    Stale,
+    /// The coin was created or changed by a transaction that is not yet finalized.
+    Unfinalized,
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            SkipReason::Excluded => "excluded",
+            SkipReason::TimeLocked => "time-locked",
+            SkipReason::Stale => "used by a recent transaction",
+            SkipReason::Unfinalized => "changed by a transaction not yet finalized",
+        };
+        write!(f, "{reason}")
+    }
+}
+
+/// The SUI coins of an address that can be safely used, and those that were skipped.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct GasCoinSelection {
+    /// Largest first.
+    pub available: Vec<Coin>,
+    pub skipped: Vec<(ObjectID, SkipReason)>,
+}
+
+impl GasCoinSelection {
+    /// Selects among `coins`, given the latest versions of their objects and the transactions
+    /// that are not yet finalized.
+    pub fn new(
+        coins: Vec<Coin>,
+        exclude: &[ObjectID],
+        latest_versions: &BTreeMap<ObjectID, SequenceNumber>,
+        unfinalized: &HashSet<TransactionDigest>,
+    ) -> Self {
+        let mut selection = Self::default();
+        for coin in coins {
+            let reason = if exclude.contains(&coin.coin_object_id) {
+                Some(SkipReason::Excluded)
+            } else if coin.locked_until_epoch.is_some() {
+                Some(SkipReason::TimeLocked)
+            } else if latest_versions.get(&coin.coin_object_id) != Some(&coin.version) {
+                Some(SkipReason::Stale)
+            } else if unfinalized.contains(&coin.previous_transaction) {
+                Some(SkipReason::Unfinalized)
+            } else {
+                None
+            };
+            match reason {
+                Some(reason) => selection.skipped.push((coin.coin_object_id, reason)),
+                None => selection.available.push(coin),
+            }
+        }
+        selection
+            .available
+            .sort_by(|a, b| (b.balance, b.coin_object_id).cmp(&(a.balance, a.coin_object_id)));
+        selection
+    }
+}
+
+/// Merge of the SUI coins of an address into its largest coin, which pays for the gas of every
+/// transaction and receives the coins merged. The transactions are those of `sui client
+/// clean-coins` for SUI, with the largest coin as gas coin and `max_balance` as dust threshold.
+#[derive(Serialize, Clone, Debug)]
+pub struct SmashGasPlan {
+    pub target: Coin,
+    pub cleanup: CoinCleanupPlan,
+    pub skipped: Vec<(ObjectID, SkipReason)>,
+}
+
+impl SmashGasPlan {
+    /// Plans the merge of the coins of `selection` at or below `max_balance`, or of all of them.
+    /// `storage_rebates` holds the storage rebate of the coins.
+    pub fn new(
+        selection: GasCoinSelection,
+        storage_rebates: &BTreeMap<ObjectID, u64>,
+        max_balance: Option<u64>,
+        protocol_config: &ProtocolConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let GasCoinSelection {
+            mut available,
+            skipped,
+        } = selection;
+        ensure!(!available.is_empty(), "No SUI coin available to smash into");
+        let target = available.remove(0);
+        let coins = available
+            .into_iter()
+            .map(|coin| {
+                let storage_rebate = storage_rebates
+                    .get(&coin.coin_object_id)
+                    .copied()
+                    .unwrap_or_default();
+                (coin, storage_rebate)
+            })
+            .collect();
+        let cleanup = CoinCleanupPlan::new(
+            coins,
+            target.coin_object_id,
+            max_balance.unwrap_or(u64::MAX),
+            protocol_config,
+        )?;
+        Ok(Self {
+            target,
+            cleanup,
+            skipped,
+        })
+    }
+
+    pub fn merged(&self) -> usize {
+        self.cleanup
+            .batches
+            .iter()
+            .map(|batch| batch.coins().count())
+            .sum()
+    }
+
+    pub fn merged_balance(&self) -> u64 {
+        self.cleanup
+            .batches
+            .iter()
+            .flat_map(|batch| batch.coins())
+            .map(|coin| coin.balance)
+            .sum()
+    }
+}
+
+/// Split of a coin into `count` coins of `amount`, sent back to its owner. The coin split pays for
+/// the gas of every transaction.
+#[derive(Serialize, Clone, Debug)]
+pub struct SplitGasPlan {
+    pub source: Coin,
+    pub amount: u64,
+    /// Number of coins split by every transaction.
+    pub batches: Vec<u64>,
+    pub skipped: Vec<(ObjectID, SkipReason)>,
+}
+
+impl SplitGasPlan {
+    /// Plans the split from the smallest coin of `selection` that covers both the coins to split
+    /// and the gas budget of every transaction, leaving larger coins free for other transactions.
+    pub fn new(
+        selection: GasCoinSelection,
+        count: u64,
+        amount: u64,
+        gas_budget: u64,
+        protocol_config: &ProtocolConfig,
+    ) -> Result<Self, anyhow::Error> {
+        ensure!(count > 0, "Coin split count must be greater than 0");
+        ensure!(amount > 0, "Coin split amount must be greater than 0");
+        let batch_size = (protocol_config.max_arguments() as u64).max(1);
+        let mut batches = vec![batch_size; (count / batch_size) as usize];
+        if count % batch_size != 0 {
+            batches.push(count % batch_size);
+        }
+        let required = (count as u128 * amount as u128)
+            .checked_add(batches.len() as u128 * gas_budget as u128)
+            .ok_or_else(|| anyhow!("Coin split amount overflows"))?;
+
+        let GasCoinSelection { available, skipped } = selection;
+        let source = available
+            .into_iter()
+            .rev()
+            .find(|coin| coin.balance as u128 >= required)
+            .ok_or_else(|| {
+                anyhow!(
+                    "No available SUI coin has a balance of at least {required} to split {count} \
+                     coins of {amount} and pay for {} transactions",
+                    batches.len()
+                )
+            })?;
+        Ok(Self {
+            source,
+            amount,
+            batches,
+            skipped,
+        })
+    }
+
+    pub fn count(&self) -> u64 {
+        self.batches.iter().sum()
+    }
+
+    /// Builds the transaction splitting `count` coins from the gas coin and sending them to
+    /// `owner`.
+    pub fn build(
+        &self,
+        owner: SuiAddress,
+        count: u64,
+    ) -> Result<ProgrammableTransaction, anyhow::Error> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let amount = builder.pure(self.amount)?;
+        let recipient = builder.pure(owner)?;
+        let split = builder.command(Command::SplitCoins(
+            Argument::GasCoin,
+            vec![amount; count as usize],
+        ));
+        let Argument::Result(split) = split else {
+            unreachable!("commands return results");
+        };
+        let coins = (0..count as u16)
+            .map(|i| Argument::NestedResult(split, i))
+            .collect();
+        builder.command(Command::TransferObjects(coins, recipient));
+        Ok(builder.finish())
+    }
+}
+
+fn write_skipped(writer: &mut String, skipped: &[(ObjectID, SkipReason)]) -> std::fmt::Result {
+    for (id, reason) in skipped {
+        writeln!(writer, "Skipped coin {id}: {reason}")?;
+    }
+    Ok(())
+}
+
+impl Display for SmashGasPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        write_skipped(&mut writer, &self.skipped)?;
+        writeln!(
+            writer,
+            "Smashing {} coins with a total balance of {} into coin {} (balance {}), in {} \
+             transactions",
+            self.merged(),
+            self.merged_balance(),
+            self.target.coin_object_id,
+            self.target.balance,
+            self.cleanup.batches.len()
+        )?;
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+impl Display for SplitGasPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        write_skipped(&mut writer, &self.skipped)?;
+        writeln!(
+            writer,
+            "Splitting {} coins of {} from coin {} (balance {}), in {} transactions",
+            self.count(),
+            self.amount,
+            self.source.coin_object_id,
+            self.source.balance,
+            self.batches.len()
+        )?;
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::random_object_ref;
+
+    fn coin(balance: u64) -> Coin {
+        let (coin_object_id, version, digest) = random_object_ref();
+        Coin {
+            coin_type: "0x2::sui::SUI".to_string(),
+            coin_object_id,
+            version,
+            digest,
+            balance,
+            locked_until_epoch: None,
+            previous_transaction: TransactionDigest::random(),
+        }
+    }
+
+    #[test]
+    fn test_select_and_smash() {
+        let config = ProtocolConfig::get_for_max_version();
+        let coins: Vec<_> = [10, 1_000, 5, 7, 20, 100_000]
+            .into_iter()
+            .map(coin)
+            .collect();
+        let mut latest_versions: BTreeMap<_, _> = coins
+            .iter()
+            .map(|coin| (coin.coin_object_id, coin.version))
+            .collect();
+        // Used by a transaction the coin index does not know about yet.
+        latest_versions.insert(coins[2].coin_object_id, SequenceNumber::from_u64(100));
+        let unfinalized = HashSet::from([coins[3].previous_transaction]);
+
+        let selection = GasCoinSelection::new(
+            coins.clone(),
+            &[coins[4].coin_object_id],
+            &latest_versions,
+            &unfinalized,
+        );
+        assert_eq!(
+            selection.skipped,
+            vec![
+                (coins[2].coin_object_id, SkipReason::Stale),
+                (coins[3].coin_object_id, SkipReason::Unfinalized),
+                (coins[4].coin_object_id, SkipReason::Excluded),
+            ]
+        );
+        let balances: Vec<_> = selection.available.iter().map(|c| c.balance).collect();
+        assert_eq!(balances, vec![100_000, 1_000, 10]);
+
+        let storage_rebates = BTreeMap::from([(coins[0].coin_object_id, 100)]);
+        let plan =
+            SmashGasPlan::new(selection.clone(), &storage_rebates, Some(100), &config).unwrap();
+        assert_eq!(plan.target.balance, 100_000);
+        assert_eq!(plan.merged(), 1);
+        assert_eq!(plan.merged_balance(), 10);
+        assert_eq!(plan.cleanup.storage_rebate(), 100);
+        let tx = plan.cleanup.batches[0].build(None).unwrap();
+        assert!(matches!(
+            &tx.commands[..],
+            [Command::MergeCoins(Argument::GasCoin, coins)] if coins.len() == 1
+        ));
+
+        let plan = SmashGasPlan::new(selection, &storage_rebates, None, &config).unwrap();
+        assert_eq!(plan.merged(), 2);
+        assert!(
+            SmashGasPlan::new(GasCoinSelection::default(), &storage_rebates, None, &config)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let config = ProtocolConfig::get_for_max_version();
+        let batch_size = config.max_arguments() as u64;
+        let coins = vec![coin(1_000_000_000), coin(3 * batch_size * 10), coin(50)];
+        let selection = GasCoinSelection::new(
+            coins.clone(),
+            &[],
+            &coins
+                .iter()
+                .map(|c| (c.coin_object_id, c.version))
+                .collect(),
+            &HashSet::new(),
+        );
+
+        // The smallest coin covering the split and the gas budgets is split.
+        let plan = SplitGasPlan::new(selection.clone(), batch_size + 1, 10, 100, &config).unwrap();
+        assert_eq!(plan.source.coin_object_id, coins[1].coin_object_id);
+        assert_eq!(plan.batches, vec![batch_size, 1]);
+        assert_eq!(plan.count(), batch_size + 1);
+
+        let tx = plan.build(SuiAddress::ZERO, 3).unwrap();
+        assert_eq!(tx.inputs.len(), 2);
+        assert!(matches!(
+            &tx.commands[..],
+            [
+                Command::SplitCoins(Argument::GasCoin, amounts),
+                Command::TransferObjects(coins, _)
+            ] if amounts.len() == 3 && coins.len() == 3
+        ));
+
+        let plan = SplitGasPlan::new(selection.clone(), 10, 10_000, 100, &config).unwrap();
+        assert_eq!(plan.source.coin_object_id, coins[0].coin_object_id);
+        assert!(SplitGasPlan::new(selection.clone(), 2, u64::MAX, 100, &config).is_err());
+        assert!(SplitGasPlan::new(selection, 0, 10, 100, &config).is_err());
+    }
+}
@@ -1,13 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, ensure, Result};
 use camino::Utf8PathBuf;
 use clap::Parser;
 use fastcrypto::encoding::{Encoding, Hex};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::{Intent, IntentMessage, IntentScope};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 use sui_config::{
-    genesis::{Builder, UnsignedGenesis},
+    genesis::{Builder, GenesisValidatorInfo, UnsignedGenesis},
     SUI_GENESIS_FILENAME,
 };
 use sui_types::multiaddr::Multiaddr;
@@ -15,7 +19,8 @@ use sui_types::{
     base_types::{ObjectID, SuiAddress},
     committee::ProtocolVersion,
     crypto::{
-        generate_proof_of_possession, AuthorityKeyPair, KeypairTraits, NetworkKeyPair, SuiKeyPair,
+        generate_proof_of_possession, AuthorityKeyPair, AuthorityPublicKeyBytes, AuthoritySignInfo,
+        KeypairTraits, NetworkKeyPair, Signature, SuiKeyPair, SuiSignature,
     },
     message_envelope::Message,
     object::Object,
@@ -76,6 +81,10 @@ pub enum CeremonyCommand {
         image_url: String,
         #[clap(long)]
         project_url: String,
+        /// Write the validator to this file, signed with its account key, for the coordinator of
+        /// the ceremony to import, instead of adding it to the ceremony directory.
+        #[clap(long)]
+        contribution_file: Option<PathBuf>,
     },
 
     AddGasObject {
@@ -94,11 +103,172 @@ pub enum CeremonyCommand {
     VerifyAndSign {
         #[clap(long)]
         key_file: PathBuf,
+        /// Write the signature to this file, for the coordinator of the ceremony to import,
+        /// instead of adding it to the ceremony directory, e.g. a copy of the coordinator's.
+        #[clap(long)]
+        contribution_file: Option<PathBuf>,
+    },
+
+    /// Verify a contribution file, against the ceremony directory for signatures, without
+    /// importing it.
+    VerifyContribution {
+        #[clap(long)]
+        contribution_file: PathBuf,
+        /// Verify a validator on its own, without a ceremony directory.
+        #[clap(long)]
+        standalone: bool,
     },
 
+    /// Verify a contribution file and add it to the ceremony directory.
+    ImportContribution {
+        #[clap(long)]
+        contribution_file: PathBuf,
+    },
+
+    /// Verify that all the contributions to the ceremony are consistent, and that all the
+    /// validators signed the unsigned genesis checkpoint, before finalizing it.
+    VerifyCeremony,
+
     Finalize,
 }
 
+/// A contribution to a ceremony exchanged as a file, so that participants don't need to share
+/// the ceremony directory with its coordinator.
+#[derive(Serialize, Deserialize)]
+pub enum Contribution {
+    /// A validator to add, signed with its account key to authenticate its participant.
+    Validator {
+        info: GenesisValidatorInfo,
+        signature: Signature,
+    },
+    /// The signature of a validator over the unsigned genesis checkpoint.
+    CheckpointSignature(AuthoritySignInfo),
+}
+
+impl Contribution {
+    pub fn validator(info: GenesisValidatorInfo, account_keypair: &SuiKeyPair) -> Self {
+        let signature = Signature::new_secure(&validator_intent_message(&info), account_keypair);
+        Self::Validator { info, signature }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)
+            .map_err(|e| anyhow!("Unable to read contribution file {}: {e}", path.display()))?;
+        bcs::from_bytes(&bytes)
+            .map_err(|e| anyhow!("Invalid contribution file {}: {e}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, bcs::to_bytes(self)?)?;
+        Ok(())
+    }
+
+    /// Verifies the contribution against the ceremony built by `builder`. Validators are also
+    /// verified on their own without a ceremony, signatures need the unsigned checkpoint.
+    pub fn verify(&self, builder: Option<&Builder>) -> Result<()> {
+        match self {
+            Contribution::Validator { info, signature } => {
+                verify_validator(info)?;
+                signature
+                    .verify_secure(&validator_intent_message(info), info.info.sui_address())
+                    .map_err(|e| {
+                        anyhow!(
+                            "Validator {} is not signed by its account {}: {e}",
+                            info.info.name(),
+                            info.info.sui_address()
+                        )
+                    })?;
+                if let Some(builder) = builder {
+                    ensure!(
+                        builder.unsigned_genesis_checkpoint().is_none(),
+                        "The genesis checkpoint is already built, validators can't be added"
+                    );
+                    check_unique_validators(
+                        builder
+                            .validators()
+                            .values()
+                            .filter(|v| v.info.protocol_key() != info.info.protocol_key())
+                            .chain([info]),
+                    )?;
+                }
+                Ok(())
+            }
+            Contribution::CheckpointSignature(signature) => {
+                let Some(builder) = builder else {
+                    bail!("Signatures can only be verified against the ceremony directory");
+                };
+                builder.verify_validator_signature(signature)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Contribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Contribution::Validator { info, .. } => {
+                let info = &info.info;
+                writeln!(f, "Validator {}", info.name())?;
+                writeln!(f, "  account address:  {}", info.sui_address())?;
+                writeln!(f, "  protocol key:     {}", info.protocol_key())?;
+                writeln!(f, "  network address:  {}", info.network_address())?;
+                writeln!(f, "  p2p address:      {}", info.p2p_address())?;
+                writeln!(f, "  primary address:  {}", info.narwhal_primary_address)?;
+                write!(f, "  worker address:   {}", info.narwhal_worker_address)
+            }
+            Contribution::CheckpointSignature(signature) => write!(
+                f,
+                "Signature of the genesis checkpoint by validator {}",
+                signature.authority
+            ),
+        }
+    }
+}
+
+fn validator_intent_message(info: &GenesisValidatorInfo) -> IntentMessage<&GenesisValidatorInfo> {
+    IntentMessage::new(
+        Intent::default().with_scope(IntentScope::PersonalMessage),
+        info,
+    )
+}
+
+/// Checks the metadata of a validator, and that its proof of possession binds its protocol key to
+/// its account.
+fn verify_validator(info: &GenesisValidatorInfo) -> Result<()> {
+    info.validate()
+        .map_err(|e| anyhow!("Invalid validator {}: {e}", info.info.name()))
+}
+
+/// Checks that no two validators share a name, an account, a key or an address.
+fn check_unique_validators<'a>(
+    validators: impl IntoIterator<Item = &'a GenesisValidatorInfo>,
+) -> Result<()> {
+    let mut seen = BTreeSet::new();
+    for validator in validators {
+        let info = &validator.info;
+        let fields = [
+            ("name", info.name().to_string()),
+            ("account address", info.sui_address().to_string()),
+            ("protocol key", info.protocol_key().to_string()),
+            ("network key", Hex::encode(info.network_key().as_ref())),
+            ("worker key", Hex::encode(info.worker_key().as_ref())),
+            ("network address", info.network_address().to_string()),
+            ("p2p address", info.p2p_address().to_string()),
+            ("primary address", info.narwhal_primary_address.to_string()),
+            ("worker address", info.narwhal_worker_address.to_string()),
+        ];
+        for (field, value) in fields {
+            if !seen.insert((field, value.clone())) {
+                bail!(
+                    "Validator {} has the same {field} as another validator: {value}",
+                    info.name()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn run(cmd: Ceremony) -> Result<()> {
     let dir = if let Some(path) = cmd.path {
         path
@@ -136,33 +306,46 @@ pub fn run(cmd: Ceremony) -> Result<()> {
             description,
             image_url,
             project_url,
+            contribution_file,
         } => {
-            let mut builder = Builder::load(&dir)?;
             let keypair: AuthorityKeyPair = read_authority_keypair_from_file(validator_key_file)?;
             let account_keypair: SuiKeyPair = read_keypair_from_file(account_key_file)?;
             let worker_keypair: NetworkKeyPair = read_network_keypair_from_file(worker_key_file)?;
             let network_keypair: NetworkKeyPair = read_network_keypair_from_file(network_key_file)?;
             let pop = generate_proof_of_possession(&keypair, (&account_keypair.public()).into());
-            builder = builder.add_validator(
-                sui_config::ValidatorInfo {
-                    name,
-                    protocol_key: keypair.public().into(),
-                    worker_key: worker_keypair.public().clone(),
-                    account_address: SuiAddress::from(&account_keypair.public()),
-                    network_key: network_keypair.public().clone(),
-                    gas_price: 1,
-                    commission_rate: 0,
-                    network_address,
-                    p2p_address,
-                    narwhal_primary_address,
-                    narwhal_worker_address,
-                    description,
-                    image_url,
-                    project_url,
-                },
-                pop,
-            );
-            builder.save(dir)?;
+            let info = sui_config::ValidatorInfo {
+                name,
+                protocol_key: keypair.public().into(),
+                worker_key: worker_keypair.public().clone(),
+                account_address: SuiAddress::from(&account_keypair.public()),
+                network_key: network_keypair.public().clone(),
+                gas_price: 1,
+                commission_rate: 0,
+                network_address,
+                p2p_address,
+                narwhal_primary_address,
+                narwhal_worker_address,
+                description,
+                image_url,
+                project_url,
+            };
+            if let Some(contribution_file) = contribution_file {
+                let info = GenesisValidatorInfo {
+                    info,
+                    proof_of_possession: pop,
+                };
+                let contribution = Contribution::validator(info, &account_keypair);
+                contribution.verify(None)?;
+                contribution.save(&contribution_file)?;
+                println!(
+                    "Successfully wrote validator contribution to {}",
+                    contribution_file.display()
+                );
+            } else {
+                let mut builder = Builder::load(&dir)?;
+                builder = builder.add_validator(info, pop);
+                builder.save(dir)?;
+            }
         }
 
         CeremonyCommand::AddGasObject {
@@ -202,7 +385,10 @@ pub fn run(cmd: Ceremony) -> Result<()> {
             examine_genesis_checkpoint(unsigned_genesis);
         }
 
-        CeremonyCommand::VerifyAndSign { key_file } => {
+        CeremonyCommand::VerifyAndSign {
+            key_file,
+            contribution_file,
+        } => {
             let keypair: AuthorityKeyPair = read_authority_keypair_from_file(key_file)?;
 
             let mut builder = Builder::load(&dir)?;
@@ -218,7 +404,17 @@ pub fn run(cmd: Ceremony) -> Result<()> {
 
             builder = builder.add_validator_signature(&keypair);
             let UnsignedGenesis { checkpoint, .. } = builder.unsigned_genesis_checkpoint().unwrap();
-            builder.save(dir)?;
+            if let Some(contribution_file) = contribution_file {
+                let name: AuthorityPublicKeyBytes = keypair.public().into();
+                let signature = builder.signatures()[&name].clone();
+                Contribution::CheckpointSignature(signature).save(&contribution_file)?;
+                println!(
+                    "Successfully wrote signature contribution to {}",
+                    contribution_file.display()
+                );
+            } else {
+                builder.save(dir)?;
+            }
 
             println!(
                 "Successfully verified and signed genesis checkpoint: {}",
@@ -226,6 +422,74 @@ pub fn run(cmd: Ceremony) -> Result<()> {
             );
         }
 
+        CeremonyCommand::VerifyContribution {
+            contribution_file,
+            standalone,
+        } => {
+            let contribution = Contribution::load(&contribution_file)?;
+            let builder = if standalone {
+                None
+            } else {
+                Some(Builder::load(&dir)?)
+            };
+            contribution.verify(builder.as_ref())?;
+            println!("{contribution}");
+            println!("Contribution is valid");
+        }
+
+        CeremonyCommand::ImportContribution { contribution_file } => {
+            let contribution = Contribution::load(&contribution_file)?;
+            let mut builder = Builder::load(&dir)?;
+            contribution.verify(Some(&builder))?;
+            builder = match &contribution {
+                Contribution::Validator { info, .. } => {
+                    builder.add_validator(info.info.clone(), info.proof_of_possession.clone())
+                }
+                Contribution::CheckpointSignature(signature) => {
+                    builder.add_validator_signature_info(signature.clone())?
+                }
+            };
+            builder.save(dir)?;
+            println!("{contribution}");
+            println!("Successfully imported contribution");
+        }
+
+        CeremonyCommand::VerifyCeremony => {
+            let builder = Builder::load(&dir)?;
+            check_protocol_version(&builder, protocol_version)?;
+            builder.validate()?;
+            let Some(UnsignedGenesis { checkpoint, .. }) = builder.unsigned_genesis_checkpoint()
+            else {
+                bail!("Unable to verify ceremony; the genesis checkpoint hasn't been built yet");
+            };
+
+            for validator in builder.validators().values() {
+                verify_validator(validator)?;
+            }
+            check_unique_validators(builder.validators().values())?;
+
+            let missing: Vec<_> = builder
+                .validators()
+                .iter()
+                .filter(|(name, _)| !builder.signatures().contains_key(name))
+                .map(|(_, validator)| validator.info.name())
+                .collect();
+            ensure!(
+                missing.is_empty(),
+                "Validators yet to sign the genesis checkpoint: {}",
+                missing.join(", ")
+            );
+            for signature in builder.signatures().values() {
+                builder.verify_validator_signature(signature)?;
+            }
+
+            println!(
+                "Ceremony is consistent: {} validators signed genesis checkpoint {}",
+                builder.validators().len(),
+                checkpoint.digest()
+            );
+        }
+
         CeremonyCommand::Finalize => {
             let builder = Builder::load(&dir)?;
             check_protocol_version(&builder, protocol_version)?;
@@ -265,12 +529,11 @@ mod test {
     use sui_keys::keypair_file::{write_authority_keypair_to_file, write_keypair_to_file};
     use sui_types::crypto::{get_key_pair_from_rng, AccountKeyPair, AuthorityKeyPair, SuiKeyPair};
 
-    #[test]
-    #[cfg_attr(msim, ignore)]
-    fn ceremony() -> Result<()> {
-        let dir = tempfile::TempDir::new().unwrap();
+    type ValidatorFiles = (PathBuf, PathBuf, PathBuf, PathBuf, ValidatorInfo);
 
-        let validators = (0..10)
+    /// Validators with their protocol, worker, network and account key files written to `dir`.
+    fn make_validators(dir: &Path, count: usize) -> Vec<ValidatorFiles> {
+        (0..count)
             .map(|i| {
                 let keypair: AuthorityKeyPair = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
                 let worker_keypair: NetworkKeyPair =
@@ -295,18 +558,18 @@ mod test {
                     image_url: String::new(),
                     project_url: String::new(),
                 };
-                let key_file = dir.path().join(format!("{}-0.key", info.name));
+                let key_file = dir.join(format!("{}-0.key", info.name));
                 write_authority_keypair_to_file(&keypair, &key_file).unwrap();
 
-                let worker_key_file = dir.path().join(format!("{}.key", info.name));
+                let worker_key_file = dir.join(format!("{}.key", info.name));
                 write_keypair_to_file(&SuiKeyPair::Ed25519(worker_keypair), &worker_key_file)
                     .unwrap();
 
-                let network_key_file = dir.path().join(format!("{}-1.key", info.name));
+                let network_key_file = dir.join(format!("{}-1.key", info.name));
                 write_keypair_to_file(&SuiKeyPair::Ed25519(network_keypair), &network_key_file)
                     .unwrap();
 
-                let account_key_file = dir.path().join(format!("{}-2.key", info.name));
+                let account_key_file = dir.join(format!("{}-2.key", info.name));
                 write_keypair_to_file(&SuiKeyPair::Ed25519(account_keypair), &account_key_file)
                     .unwrap();
 
@@ -318,7 +581,35 @@ mod test {
                     info,
                 )
             })
-            .collect::<Vec<_>>();
+            .collect()
+    }
+
+    fn add_validator_command(
+        (key_file, worker_key_file, network_key_file, account_key_file, validator): &ValidatorFiles,
+        contribution_file: Option<PathBuf>,
+    ) -> CeremonyCommand {
+        CeremonyCommand::AddValidator {
+            name: validator.name().to_owned(),
+            validator_key_file: key_file.into(),
+            worker_key_file: worker_key_file.into(),
+            network_key_file: network_key_file.into(),
+            account_key_file: account_key_file.into(),
+            network_address: validator.network_address().to_owned(),
+            p2p_address: validator.p2p_address().to_owned(),
+            narwhal_primary_address: validator.narwhal_primary_address.clone(),
+            narwhal_worker_address: validator.narwhal_worker_address.clone(),
+            description: String::new(),
+            image_url: String::new(),
+            project_url: String::new(),
+            contribution_file,
+        }
+    }
+
+    #[test]
+    #[cfg_attr(msim, ignore)]
+    fn ceremony() -> Result<()> {
+        let dir = tempfile::TempDir::new().unwrap();
+        let validators = make_validators(dir.path(), 10);
 
         // Initialize
         let command = Ceremony {
@@ -329,26 +620,11 @@ mod test {
         command.run()?;
 
         // Add the validators
-        for (key_file, worker_key_file, network_key_file, account_key_file, validator) in
-            &validators
-        {
+        for validator in &validators {
             let command = Ceremony {
                 path: Some(dir.path().into()),
                 protocol_version: None,
-                command: CeremonyCommand::AddValidator {
-                    name: validator.name().to_owned(),
-                    validator_key_file: key_file.into(),
-                    worker_key_file: worker_key_file.into(),
-                    network_key_file: network_key_file.into(),
-                    account_key_file: account_key_file.into(),
-                    network_address: validator.network_address().to_owned(),
-                    p2p_address: validator.p2p_address().to_owned(),
-                    narwhal_primary_address: validator.narwhal_primary_address.clone(),
-                    narwhal_worker_address: validator.narwhal_worker_address.clone(),
-                    description: String::new(),
-                    image_url: String::new(),
-                    project_url: String::new(),
-                },
+                command: add_validator_command(validator, None),
             };
             command.run()?;
 
@@ -375,6 +651,7 @@ mod test {
                 protocol_version: None,
                 command: CeremonyCommand::VerifyAndSign {
                     key_file: key.into(),
+                    contribution_file: None,
                 },
             };
             command.run()?;
@@ -397,4 +674,81 @@ mod test {
 
         Ok(())
     }
+    #[test]
+    #[cfg_attr(msim, ignore)]
+    fn remote_ceremony() -> Result<()> {
+        let coordinator_dir = tempfile::TempDir::new().unwrap();
+        let participant_dir = tempfile::TempDir::new().unwrap();
+        let validators = make_validators(participant_dir.path(), 4);
+        let run = |dir: &Path, command| {
+            Ceremony {
+                path: Some(dir.into()),
+                protocol_version: None,
+                command,
+            }
+            .run()
+        };
+        run(coordinator_dir.path(), CeremonyCommand::Init)?;
+
+        // Participants send their validators as contribution files to the coordinator.
+        for (i, validator) in validators.iter().enumerate() {
+            let contribution_file = participant_dir.path().join(format!("validator-{i}"));
+            let command = add_validator_command(validator, Some(contribution_file.clone()));
+            run(participant_dir.path(), command)?;
+            run(
+                participant_dir.path(),
+                CeremonyCommand::VerifyContribution {
+                    contribution_file: contribution_file.clone(),
+                    standalone: true,
+                },
+            )?;
+            run(
+                coordinator_dir.path(),
+                CeremonyCommand::ImportContribution { contribution_file },
+            )?;
+        }
+        // Tampered contributions are rejected.
+        let contribution_file = participant_dir.path().join("validator-0");
+        let contribution = Contribution::load(&contribution_file)?;
+        let Contribution::Validator { mut info, signature } = contribution else {
+            panic!("Expected a validator contribution");
+        };
+        info.info.name = "impostor".to_string();
+        let tampered_file = participant_dir.path().join("tampered");
+        Contribution::Validator { info, signature }.save(&tampered_file)?;
+        assert!(run(
+            coordinator_dir.path(),
+            CeremonyCommand::ImportContribution {
+                contribution_file: tampered_file
+            }
+        )
+        .is_err());
+        run(
+            coordinator_dir.path(),
+            CeremonyCommand::BuildUnsignedCheckpoint,
+        )?;
+        assert!(run(coordinator_dir.path(), CeremonyCommand::VerifyCeremony).is_err());
+
+        // Participants sign copies of the ceremony directory, and send their signatures.
+        for (i, (key_file, ..)) in validators.iter().enumerate() {
+            let copy_dir = tempfile::TempDir::new().unwrap();
+            Builder::load(coordinator_dir.path())?.save(copy_dir.path())?;
+            let contribution_file = participant_dir.path().join(format!("signature-{i}"));
+            run(
+                copy_dir.path(),
+                CeremonyCommand::VerifyAndSign {
+                    key_file: key_file.into(),
+                    contribution_file: Some(contribution_file.clone()),
+                },
+            )?;
+            run(
+                coordinator_dir.path(),
+                CeremonyCommand::ImportContribution { contribution_file },
+            )?;
+        }
+
+        run(coordinator_dir.path(), CeremonyCommand::VerifyCeremony)?;
+        run(coordinator_dir.path(), CeremonyCommand::Finalize)?;
+        Ok(())
+    }
 }
@@ -1,12 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
-use anyhow::anyhow;
+use anyhow::{anyhow, ensure};
 use bip32::DerivationPath;
 use clap::*;
 use fastcrypto::encoding::{decode_bytes_hex, Base64, Encoding};
 use fastcrypto::hash::HashFunction;
 use fastcrypto::traits::KeyPair;
+use serde::{Deserialize, Serialize};
 use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use sui_keys::key_derive::generate_new_key;
@@ -18,9 +20,10 @@ use sui_keys::keystore::{AccountKeystore, Keystore};
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{get_authority_key_pair, EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
 use sui_types::crypto::{DefaultHash, PublicKey, Signature};
-use sui_types::messages::TransactionData;
+use sui_types::message_envelope::Message;
+use sui_types::messages::{SenderSignedData, TransactionData, TransactionDataAPI};
 use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
-use sui_types::signature::GenericSignature;
+use sui_types::signature::{AuthenticatorTrait, GenericSignature};
 use tracing::info;
 #[cfg(test)]
 #[path = "unit_tests/keytool_tests.rs"]
@@ -66,6 +69,28 @@ pub enum KeyToolCommand {
         #[clap(long)]
         intent: Option<Intent>,
     },
+    /// Sign a transaction read from a file, as written by `sui client serialize-unsigned-tx`, or
+    /// one already signed by other participants. With `--offline`, the transaction and all of its
+    /// signatures are written to the `--output` file, to be executed later with
+    /// `sui client execute-signed-tx --signed-tx-file`. Otherwise the transaction bytes and the
+    /// signature are printed in Base64.
+    SignTransaction {
+        #[clap(long, parse(try_from_str = decode_bytes_hex))]
+        address: SuiAddress,
+        /// File with the BCS serialized transaction
+        #[clap(long)]
+        tx_file: PathBuf,
+        /// Intent of the signature, for a transaction that is not signed yet. If absent, default
+        /// will be used
+        #[clap(long)]
+        intent: Option<Intent>,
+        /// Write the signed transaction to the output file instead of printing the signature
+        #[clap(long, requires = "output")]
+        offline: bool,
+        /// File to write the BCS serialized signed transaction to
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
     /// Add a new key to sui.key based on the input mnemonic phrase, the key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// and an optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or m/54'/784'/0'/0/0 for secp256k1
     /// or m/74'/784'/0'/0/0 for secp256r1.
@@ -81,7 +106,8 @@ pub enum KeyToolCommand {
     LoadKeypair { file: PathBuf },
 
     /// To MultiSig Sui Address. Pass in a list of all public keys `flag || pk` in Base64.
-    /// See `keytool list` for example public keys.
+    /// See `keytool list` for example public keys. The threshold must be reachable by the total
+    /// weight of the public keys, and a public key can only be given once.
     MultiSigAddress {
         #[clap(long)]
         threshold: ThresholdUnit,
@@ -89,11 +115,28 @@ pub enum KeyToolCommand {
         pks: Vec<PublicKey>,
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         weights: Vec<WeightUnit>,
+        /// Write the multisig address, public keys, weights and threshold to this file as JSON,
+        /// to share with the participants.
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Produce the partial signature of a participant of a multisig, for a transaction sent by the
+    /// multisig address, read from a file as written by `sui client serialize-unsigned-tx`. The
+    /// participants are read from the file written by `multi-sig-address --output`.
+    MultiSigSign {
+        #[clap(long, parse(try_from_str = decode_bytes_hex))]
+        address: SuiAddress,
+        #[clap(long)]
+        multisig_file: PathBuf,
+        #[clap(long)]
+        tx_file: PathBuf,
     },
 
     /// Provides a list of signatures (`flag || sig || pk` encoded in Base64), threshold, a list of public keys.
     /// Returns a valid MultiSig and its sender address. The result can be used as signature field for `sui client execute-signed-tx`.
-    /// The number of sigs must be greater than the threshold. The number of sigs must be smaller than the number of pks.
+    /// The signatures must be from distinct public keys, whose total weight reaches the threshold.
+    /// The public keys, weights and threshold can be read from the file written by `multi-sig-address --output` instead.
     MultiSigCombinePartialSig {
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         sigs: Vec<Signature>,
@@ -101,8 +144,18 @@ pub enum KeyToolCommand {
         pks: Vec<PublicKey>,
         #[clap(long, multiple_occurrences = false, multiple_values = true)]
         weights: Vec<WeightUnit>,
+        #[clap(long, required_unless_present = "multisig_file")]
+        threshold: Option<ThresholdUnit>,
+        #[clap(long, conflicts_with_all = &["pks", "weights", "threshold"])]
+        multisig_file: Option<PathBuf>,
+        /// Check the MultiSig against the transaction in this file, as written by
+        /// `sui client serialize-unsigned-tx`
         #[clap(long)]
-        threshold: ThresholdUnit,
+        tx_file: Option<PathBuf>,
+        /// Write the transaction signed with the MultiSig to this file, to be executed with
+        /// `sui client execute-signed-tx --signed-tx-file`
+        #[clap(long, requires = "tx_file")]
+        output: Option<PathBuf>,
     },
 }
 
@@ -202,6 +255,41 @@ impl KeyToolCommand {
                     sui_signature.encode_base64()
                 );
             }
+            KeyToolCommand::SignTransaction {
+                address,
+                tx_file,
+                intent,
+                offline,
+                output,
+            } => {
+                let mut signed = read_transaction_file(&tx_file, intent)?;
+                let intent_msg = signed.intent_message();
+                println!("Signer address: {}", address);
+                println!("Transaction digest: {}", signed.digest());
+                println!("Intent: {:?}", intent_msg.intent);
+                let sui_signature =
+                    keystore.sign_secure(&address, &intent_msg.value, intent_msg.intent.clone())?;
+                if offline {
+                    let output = output
+                        .ok_or_else(|| anyhow!("An output file is required with --offline"))?;
+                    signed.add_signature(sui_signature);
+                    fs::write(&output, bcs::to_bytes(&signed)?)?;
+                    println!(
+                        "Signed transaction ({} signatures) written to: {:?}",
+                        signed.tx_signatures().len(),
+                        output
+                    );
+                } else {
+                    println!(
+                        "Raw tx_bytes to execute: {}",
+                        Base64::encode(bcs::to_bytes(&intent_msg.value)?)
+                    );
+                    println!(
+                        "Serialized signature (`flag || sig || pk` in Base64): {:?}",
+                        sui_signature.encode_base64()
+                    );
+                }
+            }
             KeyToolCommand::Import {
                 mnemonic_phrase,
                 key_scheme,
@@ -238,10 +326,15 @@ impl KeyToolCommand {
                 threshold,
                 pks,
                 weights,
+                output,
             } => {
-                let multisig_pk = MultiSigPublicKey::new(pks.clone(), weights.clone(), threshold)?;
-                let address: SuiAddress = multisig_pk.into();
+                let multisig_pk = multisig_public_key(pks.clone(), weights.clone(), threshold)?;
+                let address: SuiAddress = multisig_pk.clone().into();
                 println!("MultiSig address: {address}");
+                if let Some(output) = output {
+                    MultiSigFile::new(&multisig_pk).write(&output)?;
+                    println!("MultiSig file written to: {:?}", output);
+                }
 
                 println!("Participating parties:");
                 println!(
@@ -258,19 +351,78 @@ impl KeyToolCommand {
                     );
                 }
             }
+            KeyToolCommand::MultiSigSign {
+                address,
+                multisig_file,
+                tx_file,
+            } => {
+                let multisig = MultiSigFile::read(&multisig_file)?;
+                let multisig_pk = multisig.multisig_public_key()?;
+                let participant = multisig
+                    .participants
+                    .iter()
+                    .find(|participant| participant.address == address)
+                    .ok_or_else(|| anyhow!("{address} is not a participant of the multisig"))?;
+                let signed = read_transaction_file(&tx_file, None)?;
+                let intent_msg = signed.intent_message();
+                ensure!(
+                    intent_msg.value.sender() == multisig.address,
+                    "The transaction is sent by {}, not by the multisig address {}",
+                    intent_msg.value.sender(),
+                    multisig.address
+                );
+
+                let sui_signature =
+                    keystore.sign_secure(&address, &intent_msg.value, intent_msg.intent.clone())?;
+                println!("Signer address: {}", address);
+                println!("Transaction digest: {}", signed.digest());
+                println!(
+                    "Weight: {} (threshold {})",
+                    participant.weight,
+                    multisig_pk.threshold()
+                );
+                println!(
+                    "Partial signature (`flag || sig || pk` in Base64): {:?}",
+                    sui_signature.encode_base64()
+                );
+            }
             KeyToolCommand::MultiSigCombinePartialSig {
                 sigs,
                 pks,
                 weights,
                 threshold,
+                multisig_file,
+                tx_file,
+                output,
             } => {
-                let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
+                let multisig_pk = match (multisig_file, threshold) {
+                    (Some(multisig_file), _) => {
+                        MultiSigFile::read(&multisig_file)?.multisig_public_key()?
+                    }
+                    (None, Some(threshold)) => multisig_public_key(pks, weights, threshold)?,
+                    (None, None) => {
+                        return Err(anyhow!("A threshold or a multisig file is required"))
+                    }
+                };
                 let address: SuiAddress = multisig_pk.clone().into();
-                let multisig = MultiSig::combine(sigs, multisig_pk)?;
+                let multisig = combine_partial_sigs(sigs, multisig_pk)?;
                 let generic_sig: GenericSignature = multisig.into();
                 println!("MultiSig address: {address}");
                 println!("MultiSig parsed: {:?}", generic_sig);
                 println!("MultiSig serialized: {:?}", generic_sig.encode_base64());
+
+                if let Some(tx_file) = tx_file {
+                    let mut signed = read_transaction_file(&tx_file, None)?;
+                    generic_sig.verify_secure_generic(signed.intent_message(), address)?;
+                    println!("MultiSig verified for transaction: {}", signed.digest());
+                    if let Some(output) = output {
+                        // Signatures of other participants of the transaction, e.g. the sponsor
+                        // of its gas, are kept.
+                        signed.inner_mut().tx_signatures.push(generic_sig);
+                        fs::write(&output, bcs::to_bytes(&signed)?)?;
+                        println!("Signed transaction written to: {:?}", output);
+                    }
+                }
             }
         }
 
@@ -278,6 +430,146 @@ impl KeyToolCommand {
     }
 }
 
+/// A multisig address, with the public keys, weights and threshold it is derived from, as shared
+/// with its participants in a JSON file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigFile {
+    pub address: SuiAddress,
+    pub threshold: ThresholdUnit,
+    pub participants: Vec<MultiSigParticipant>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigParticipant {
+    pub address: SuiAddress,
+    pub public_key: PublicKey,
+    pub weight: WeightUnit,
+}
+
+impl MultiSigFile {
+    pub fn new(multisig_pk: &MultiSigPublicKey) -> Self {
+        Self {
+            address: multisig_pk.clone().into(),
+            threshold: *multisig_pk.threshold(),
+            participants: multisig_pk
+                .pubkeys()
+                .iter()
+                .map(|(pk, weight)| MultiSigParticipant {
+                    address: pk.into(),
+                    public_key: pk.clone(),
+                    weight: *weight,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let file =
+            fs::read(path).map_err(|e| anyhow!("Cannot read multisig file {:?}: {e}", path))?;
+        Ok(serde_json::from_slice(&file)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The multisig public key of the file, checked to derive the address of the file.
+    pub fn multisig_public_key(&self) -> Result<MultiSigPublicKey, anyhow::Error> {
+        let (pks, weights) = self
+            .participants
+            .iter()
+            .map(|participant| (participant.public_key.clone(), participant.weight))
+            .unzip();
+        let multisig_pk = multisig_public_key(pks, weights, self.threshold)?;
+        let address: SuiAddress = multisig_pk.clone().into();
+        ensure!(
+            address == self.address,
+            "The participants of the multisig file derive address {address}, not {}",
+            self.address
+        );
+        Ok(multisig_pk)
+    }
+}
+
+/// A multisig public key. Unlike `MultiSigPublicKey::new`, this rejects thresholds that the total
+/// weight of the public keys cannot reach, and public keys given more than once.
+pub fn multisig_public_key(
+    pks: Vec<PublicKey>,
+    weights: Vec<WeightUnit>,
+    threshold: ThresholdUnit,
+) -> Result<MultiSigPublicKey, anyhow::Error> {
+    ensure!(
+        pks.len() == weights.len(),
+        "Got {} public keys but {} weights",
+        pks.len(),
+        weights.len()
+    );
+    let total_weight: ThresholdUnit = weights.iter().map(|weight| *weight as ThresholdUnit).sum();
+    ensure!(
+        threshold <= total_weight,
+        "Threshold {threshold} is above the total weight {total_weight} of the public keys"
+    );
+    for (i, pk) in pks.iter().enumerate() {
+        ensure!(
+            !pks[..i].contains(pk),
+            "Public key {} is given more than once",
+            pk.encode_base64()
+        );
+    }
+    Ok(MultiSigPublicKey::new(pks, weights, threshold)?)
+}
+
+/// Combines partial signatures into a MultiSig, checking that they are from distinct participants
+/// whose total weight reaches the threshold.
+pub fn combine_partial_sigs(
+    sigs: Vec<Signature>,
+    multisig_pk: MultiSigPublicKey,
+) -> Result<MultiSig, anyhow::Error> {
+    let mut signers = BTreeSet::new();
+    let mut weight: ThresholdUnit = 0;
+    for sig in &sigs {
+        let pk = sig.to_public_key()?;
+        let signer = SuiAddress::from(&pk);
+        let index = multisig_pk
+            .get_index(pk)
+            .ok_or_else(|| anyhow!("{signer} is not a participant of the multisig"))?;
+        ensure!(signers.insert(index), "{signer} signed more than once");
+        weight += multisig_pk.pubkeys()[index as usize].1 as ThresholdUnit;
+    }
+    ensure!(
+        weight >= *multisig_pk.threshold(),
+        "The signatures weigh {weight}, below the threshold {}",
+        multisig_pk.threshold()
+    );
+    Ok(MultiSig::combine(sigs, multisig_pk)?)
+}
+
+/// Reads a transaction file, either a signed transaction written by `sign-transaction --offline`,
+/// or the transaction data written by `sui client serialize-unsigned-tx`, which is not signed yet
+/// and is signed with `intent`.
+pub fn read_transaction_file(
+    path: &Path,
+    intent: Option<Intent>,
+) -> Result<SenderSignedData, anyhow::Error> {
+    let bytes =
+        fs::read(path).map_err(|e| anyhow!("Cannot read transaction file {:?}: {e}", path))?;
+    // The encodings cannot be confused: transaction data starts with its version, 0, which as a
+    // signed transaction would be an empty list followed by trailing bytes.
+    if let Ok(signed) = bcs::from_bytes::<SenderSignedData>(&bytes) {
+        return Ok(signed);
+    }
+    let data: TransactionData = bcs::from_bytes(&bytes)
+        .map_err(|e| anyhow!("Cannot deserialize {:?} as a transaction: {e}", path))?;
+    Ok(SenderSignedData::new(
+        data,
+        intent.unwrap_or_default(),
+        vec![],
+    ))
+}
+
 fn store_and_print_keypair(address: SuiAddress, keypair: SuiKeyPair) {
     let path_str = format!("{}.key", address).to_lowercase();
     let path = Path::new(&path_str);
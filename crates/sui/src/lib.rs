@@ -2,14 +2,21 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address_summary;
 pub mod client_commands;
+pub mod coin_cleanup;
 pub mod config;
 pub mod console;
 pub mod fire_drill;
+pub mod gas_coins;
 pub mod keytool;
+pub mod local_network;
+pub mod ptb;
 pub mod shell;
+pub mod sponsor;
 pub mod sui_commands;
 pub mod validator_commands;
+pub mod watch;
 
 pub mod genesis_ceremony;
 pub mod genesis_inspector;
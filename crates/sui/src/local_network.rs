@@ -0,0 +1,68 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Control endpoint of the local network run by `sui start`, for `sui advance-epoch`.
+//!
+//! The endpoint only listens on localhost. A POST request to `/advance-epoch` has every validator
+//! close the current epoch, and responds with the new epoch once they all reached it.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure};
+use axum::{extract::State, http::StatusCode, routing::post, Router};
+use sui_swarm::memory::Swarm;
+use sui_types::committee::EpochId;
+use tracing::{error, info};
+
+/// Port of the control endpoint, unless another one is set with `--control-port`.
+pub const DEFAULT_CONTROL_PORT: u16 = 9123;
+
+const ADVANCE_EPOCH_ROUTE: &str = "/advance-epoch";
+
+/// Serves the control endpoint of `swarm` on `port`, in the background.
+pub fn serve_control_endpoint(swarm: Arc<Swarm>, port: u16) -> Result<(), anyhow::Error> {
+    let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+    let app = Router::new()
+        .route(ADVANCE_EPOCH_ROUTE, post(advance_epoch))
+        .with_state(swarm);
+    let server = axum::Server::try_bind(&address)
+        .map_err(|e| anyhow!("Can't serve the control endpoint on {address}: {e}"))?
+        .serve(app.into_make_service());
+    info!("Control endpoint of the local network listening on {address}");
+    tokio::spawn(async move {
+        if let Err(e) = server.await {
+            error!("Control endpoint of the local network failed: {e}");
+        }
+    });
+    Ok(())
+}
+
+async fn advance_epoch(State(swarm): State<Arc<Swarm>>) -> (StatusCode, String) {
+    match swarm.advance_epoch().await {
+        Ok(epoch) => (StatusCode::OK, epoch.to_string()),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+/// Has the local network with its control endpoint on `port` advance to the next epoch, and
+/// returns that epoch.
+pub async fn request_advance_epoch(port: u16) -> Result<EpochId, anyhow::Error> {
+    let url = format!("http://127.0.0.1:{port}{ADVANCE_EPOCH_ROUTE}");
+    let response = reqwest::Client::new()
+        .post(&url)
+        .send()
+        .await
+        .map_err(|e| {
+            anyhow!("Can't reach the local network at {url}, is `sui start` running? {e}")
+        })?;
+    let status = response.status();
+    let body = response.text().await?;
+    ensure!(
+        status.is_success(),
+        "The local network failed to advance the epoch ({status}): {body}"
+    );
+    body.trim()
+        .parse()
+        .map_err(|e| anyhow!("Invalid epoch {body} from the local network: {e}"))
+}
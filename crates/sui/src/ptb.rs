@@ -0,0 +1,382 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing and building for `sui client ptb`.
+//!
+//! A programmable transaction is given as a sequence of commands, each one a quoted string:
+//!
+//!   split-coins <coin> [<amount>, ..]
+//!   merge-coins <coin> [<coin>, ..]
+//!   transfer-objects [<object>, ..] <address>
+//!   move-call <package>::<module>::<function><<type>, ..> <arg> ..
+//!   publish <package path>
+//!
+//! Arguments are object IDs or values, `gas` for the gas coin, and `result(i)` or `result(i).j`
+//! for the result of the i-th command (counting from 0), or the j-th of its results. Move call
+//! arguments are resolved and type checked against the signature of the function on chain, except
+//! for results of earlier commands, which are only checked when the transaction executes. Publish
+//! returns the upgrade capability of the package, which has to be transferred.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, ensure};
+use move_core_types::language_storage::TypeTag;
+use move_core_types::value::MoveTypeLayout;
+use move_package::BuildConfig as MoveBuildConfig;
+use serde_json::Value;
+use sui_json::SuiJsonValue;
+use sui_sdk::{MoveCallArg, SuiClient};
+use sui_types::base_types::ObjectID;
+use sui_types::messages::{Argument, Command, ObjectArg, ProgrammableTransaction};
+use sui_types::parse_sui_struct_tag;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+use crate::client_commands::{compile_package, WalletContext};
+
+/// An argument of a command: the gas coin or the result of an earlier command, or a value given
+/// on the command line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PtbArg {
+    Argument(Argument),
+    Value(SuiJsonValue),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PtbCommand {
+    SplitCoins {
+        coin: PtbArg,
+        amounts: Vec<PtbArg>,
+    },
+    MergeCoins {
+        coin: PtbArg,
+        coins: Vec<PtbArg>,
+    },
+    TransferObjects {
+        objects: Vec<PtbArg>,
+        recipient: PtbArg,
+    },
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_args: Vec<TypeTag>,
+        args: Vec<PtbArg>,
+    },
+    Publish {
+        package_path: PathBuf,
+    },
+}
+
+impl FromStr for PtbArg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "gas" {
+            return Ok(PtbArg::Argument(Argument::GasCoin));
+        }
+        if let Some(rest) = s.strip_prefix("result(") {
+            let (index, nested) = rest
+                .split_once(')')
+                .ok_or_else(|| anyhow!("Invalid result reference: {s}"))?;
+            let index = index.trim().parse()?;
+            return Ok(PtbArg::Argument(match nested {
+                "" => Argument::Result(index),
+                nested => {
+                    let nested = nested
+                        .strip_prefix('.')
+                        .ok_or_else(|| anyhow!("Invalid result reference: {s}"))?;
+                    Argument::NestedResult(index, nested.parse()?)
+                }
+            }));
+        }
+        Ok(PtbArg::Value(SuiJsonValue::from_str(s)?))
+    }
+}
+
+impl FromStr for PtbCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = split_top_level(s, char::is_whitespace)?;
+        let Some((name, args)) = tokens.split_first() else {
+            bail!("Empty command");
+        };
+        Ok(match (*name, args) {
+            ("split-coins", [coin, amounts]) => PtbCommand::SplitCoins {
+                coin: coin.parse()?,
+                amounts: parse_list(amounts)?,
+            },
+            ("merge-coins", [coin, coins]) => PtbCommand::MergeCoins {
+                coin: coin.parse()?,
+                coins: parse_list(coins)?,
+            },
+            ("transfer-objects", [objects, recipient]) => PtbCommand::TransferObjects {
+                objects: parse_list(objects)?,
+                recipient: recipient.parse()?,
+            },
+            ("move-call", [target, args @ ..]) => {
+                // The target has the syntax of a struct tag, with the function as the struct name.
+                let target = parse_sui_struct_tag(target)
+                    .map_err(|e| anyhow!("Invalid move call target {target}: {e}"))?;
+                PtbCommand::MoveCall {
+                    package: target.address.into(),
+                    module: target.module.to_string(),
+                    function: target.name.to_string(),
+                    type_args: target.type_params,
+                    args: args
+                        .iter()
+                        .map(|arg| arg.parse())
+                        .collect::<Result<_, _>>()?,
+                }
+            }
+            ("publish", [package_path]) => PtbCommand::Publish {
+                package_path: PathBuf::from(*package_path),
+            },
+            ("split-coins" | "merge-coins" | "transfer-objects" | "move-call" | "publish", _) => {
+                bail!("Wrong number of arguments for {name}: {s}")
+            }
+            _ => bail!("Unknown command: {name}"),
+        })
+    }
+}
+
+/// Builds the programmable transaction made of `commands`, in order.
+pub async fn build_programmable_transaction(
+    context: &WalletContext,
+    client: &SuiClient,
+    commands: Vec<PtbCommand>,
+) -> Result<ProgrammableTransaction, anyhow::Error> {
+    let mut builder = ProgrammableTransactionBuilder::new();
+    for command in commands {
+        match command {
+            PtbCommand::SplitCoins { coin, amounts } => {
+                let coin = object_arg(context, &mut builder, coin).await?;
+                let amounts = amounts
+                    .into_iter()
+                    .map(|amount| pure_arg(&mut builder, amount, &MoveTypeLayout::U64))
+                    .collect::<Result<_, _>>()?;
+                builder.command(Command::SplitCoins(coin, amounts));
+            }
+            PtbCommand::MergeCoins { coin, coins } => {
+                let coin = object_arg(context, &mut builder, coin).await?;
+                let mut args = Vec::with_capacity(coins.len());
+                for coin in coins {
+                    args.push(object_arg(context, &mut builder, coin).await?);
+                }
+                builder.command(Command::MergeCoins(coin, args));
+            }
+            PtbCommand::TransferObjects { objects, recipient } => {
+                let mut args = Vec::with_capacity(objects.len());
+                for object in objects {
+                    args.push(object_arg(context, &mut builder, object).await?);
+                }
+                let recipient = pure_arg(&mut builder, recipient, &MoveTypeLayout::Address)?;
+                builder.command(Command::TransferObjects(args, recipient));
+            }
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_args,
+                args,
+            } => {
+                let args = args
+                    .into_iter()
+                    .map(|arg| match arg {
+                        PtbArg::Argument(argument) => MoveCallArg::Argument(argument),
+                        PtbArg::Value(value) => MoveCallArg::Json(value),
+                    })
+                    .collect();
+                client
+                    .transaction_builder()
+                    .programmable_move_call(
+                        &mut builder,
+                        package,
+                        &module,
+                        &function,
+                        type_args.into_iter().map(Into::into).collect(),
+                        args,
+                    )
+                    .await?;
+            }
+            PtbCommand::Publish { package_path } => {
+                let (dependencies, compiled_modules, _, _) = compile_package(
+                    client,
+                    MoveBuildConfig::default(),
+                    package_path,
+                    false,
+                    false,
+                )
+                .await?;
+                builder.publish_upgradeable(
+                    compiled_modules,
+                    dependencies.published.into_values().collect(),
+                );
+            }
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// An object argument, taken by reference to its current version when given by ID.
+async fn object_arg(
+    context: &WalletContext,
+    builder: &mut ProgrammableTransactionBuilder,
+    arg: PtbArg,
+) -> Result<Argument, anyhow::Error> {
+    match arg {
+        PtbArg::Argument(argument) => Ok(argument),
+        PtbArg::Value(value) => {
+            let Value::String(id) = value.to_json_value() else {
+                bail!("Expected an object ID, found: {value:?}");
+            };
+            let id = ObjectID::from_str(&id)?;
+            builder.obj(ObjectArg::ImmOrOwnedObject(
+                context.get_object_ref(id).await?,
+            ))
+        }
+    }
+}
+
+fn pure_arg(
+    builder: &mut ProgrammableTransactionBuilder,
+    arg: PtbArg,
+    layout: &MoveTypeLayout,
+) -> Result<Argument, anyhow::Error> {
+    match arg {
+        PtbArg::Argument(argument) => Ok(argument),
+        PtbArg::Value(value) => Ok(builder.pure_bytes(value.to_bcs_bytes(layout)?, false)),
+    }
+}
+
+fn parse_list(s: &str) -> Result<Vec<PtbArg>, anyhow::Error> {
+    let inner = s
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| anyhow!("Expected a list in brackets, found: {s}"))?;
+    split_top_level(inner, |c| c == ',')?
+        .into_iter()
+        .map(str::parse)
+        .collect()
+}
+
+/// Splits `s` at the separators that are not quoted or nested in brackets, dropping empty parts.
+fn split_top_level(
+    s: &str,
+    is_separator: impl Fn(char) -> bool,
+) -> Result<Vec<&str>, anyhow::Error> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            '[' | '(' | '<' => depth += 1,
+            ']' | ')' | '>' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| anyhow!("Unbalanced '{c}' in: {s}"))?
+            }
+            c if depth == 0 && is_separator(c) => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    ensure!(
+        depth == 0 && !quoted,
+        "Unbalanced brackets or quotes in: {s}"
+    );
+    parts.push(&s[start..]);
+    Ok(parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::parse_sui_type_tag;
+
+    fn value(s: &str) -> PtbArg {
+        PtbArg::Value(SuiJsonValue::from_str(s).unwrap())
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            "gas".parse::<PtbArg>().unwrap(),
+            PtbArg::Argument(Argument::GasCoin)
+        );
+        assert_eq!(
+            "result(3)".parse::<PtbArg>().unwrap(),
+            PtbArg::Argument(Argument::Result(3))
+        );
+        assert_eq!(
+            "result(3).1".parse::<PtbArg>().unwrap(),
+            PtbArg::Argument(Argument::NestedResult(3, 1))
+        );
+        assert!("result(3)1".parse::<PtbArg>().is_err());
+        assert!("result(x)".parse::<PtbArg>().is_err());
+        assert_eq!("0x5".parse::<PtbArg>().unwrap(), value("\"0x5\""));
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            "split-coins gas [1000, result(0).1]"
+                .parse::<PtbCommand>()
+                .unwrap(),
+            PtbCommand::SplitCoins {
+                coin: PtbArg::Argument(Argument::GasCoin),
+                amounts: vec![
+                    value("1000"),
+                    PtbArg::Argument(Argument::NestedResult(0, 1))
+                ],
+            }
+        );
+        assert_eq!(
+            "transfer-objects [result(0), 0x6] 0x7"
+                .parse::<PtbCommand>()
+                .unwrap(),
+            PtbCommand::TransferObjects {
+                objects: vec![PtbArg::Argument(Argument::Result(0)), value("\"0x6\"")],
+                recipient: value("\"0x7\""),
+            }
+        );
+        assert_eq!(
+            r#"move-call 0x2::pay::split_vec<0x2::sui::SUI> result(1) [1, 2] "a b""#
+                .parse::<PtbCommand>()
+                .unwrap(),
+            PtbCommand::MoveCall {
+                package: ObjectID::from_str("0x2").unwrap(),
+                module: "pay".to_string(),
+                function: "split_vec".to_string(),
+                type_args: vec![parse_sui_type_tag("0x2::sui::SUI").unwrap()],
+                args: vec![
+                    PtbArg::Argument(Argument::Result(1)),
+                    value("[1, 2]"),
+                    value("\"a b\""),
+                ],
+            }
+        );
+        assert_eq!(
+            "publish ./move/example".parse::<PtbCommand>().unwrap(),
+            PtbCommand::Publish {
+                package_path: PathBuf::from("./move/example"),
+            }
+        );
+
+        assert!("split-coins gas".parse::<PtbCommand>().is_err());
+        assert!("split-coins gas 1000".parse::<PtbCommand>().is_err());
+        assert!("merge-coins [0x5".parse::<PtbCommand>().is_err());
+        assert!("burn-coins gas".parse::<PtbCommand>().is_err());
+        assert!("".parse::<PtbCommand>().is_err());
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sponsored transactions, whose gas is paid by another address than their sender.
+//!
+//! The sponsor is either an address of the keystore, which selects and signs the gas payment
+//! locally, or a gas station: an HTTP endpoint receiving the transaction kind and returning the
+//! transaction it pays the gas of, signed by it. A gas station is sent a JSON request
+//! `{"sender", "gasBudget", "transactionKindBytes"}`, with the transaction kind in base64 BCS, and
+//! responds with `{"txBytes", "sponsorSignature"}`, the transaction data in base64 BCS and the
+//! base64 signature of the sponsor.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{anyhow, ensure};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::traits::ToFromBytes;
+use serde::{Deserialize, Serialize};
+use shared_crypto::intent::Intent;
+use sui_keys::keystore::AccountKeystore;
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::messages::{
+    ProgrammableTransaction, Transaction, TransactionData, TransactionDataAPI, TransactionKind,
+};
+use sui_types::signature::GenericSignature;
+
+use crate::client_commands::WalletContext;
+
+/// Payer of the gas of a sponsored transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GasSponsor {
+    /// An address of the keystore.
+    Address(SuiAddress),
+    /// The URL of a gas station.
+    Station(String),
+}
+
+impl FromStr for GasSponsor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("http://") || s.starts_with("https://") {
+            Ok(Self::Station(s.to_string()))
+        } else {
+            SuiAddress::from_str(s).map(Self::Address).map_err(|_| {
+                anyhow!("Invalid gas sponsor {s}, expected an address or a gas station URL")
+            })
+        }
+    }
+}
+
+impl Display for GasSponsor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GasSponsor::Address(address) => write!(f, "{address}"),
+            GasSponsor::Station(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GasStationRequest {
+    pub sender: SuiAddress,
+    pub gas_budget: u64,
+    pub transaction_kind_bytes: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GasStationResponse {
+    pub tx_bytes: String,
+    pub sponsor_signature: String,
+}
+
+/// Builds the transaction of `pt` sent by `sender` with its gas paid by `sponsor`, and collects
+/// the signature of the sponsor. `gas` is the gas coin of a sponsor of the keystore, gas stations
+/// select their own.
+pub async fn sponsor_transaction(
+    context: &WalletContext,
+    client: &SuiClient,
+    sponsor: &GasSponsor,
+    sender: SuiAddress,
+    pt: ProgrammableTransaction,
+    gas: Option<ObjectID>,
+    gas_budget: u64,
+) -> Result<(TransactionData, GenericSignature), anyhow::Error> {
+    match sponsor {
+        GasSponsor::Address(sponsor) => {
+            ensure!(
+                *sponsor != sender,
+                "The gas sponsor must be another address than the sender"
+            );
+            let data = client
+                .transaction_builder()
+                .sponsored_programmable_transaction(sender, *sponsor, pt, gas, gas_budget)
+                .await?;
+            let signature =
+                context
+                    .config
+                    .keystore
+                    .sign_secure(sponsor, &data, Intent::default())?;
+            Ok((data, signature.into()))
+        }
+        GasSponsor::Station(url) => {
+            ensure!(
+                gas.is_none(),
+                "Gas stations select the gas coin, --gas can't be used with them"
+            );
+            let kind = TransactionKind::programmable(pt);
+            let request = GasStationRequest {
+                sender,
+                gas_budget,
+                transaction_kind_bytes: Base64::encode(bcs::to_bytes(&kind)?),
+            };
+            let response = reqwest::Client::new()
+                .post(url)
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| anyhow!("Can't reach gas station {url}: {e}"))?;
+            let status = response.status();
+            let body = response.bytes().await?;
+            ensure!(
+                status.is_success(),
+                "Gas station {url} refused to sponsor the transaction ({status}): {}",
+                String::from_utf8_lossy(&body)
+            );
+            let response: GasStationResponse = serde_json::from_slice(&body)
+                .map_err(|e| anyhow!("Invalid response from gas station {url}: {e}"))?;
+            let data: TransactionData = bcs::from_bytes(&decode_base64(&response.tx_bytes)?)?;
+            let signature =
+                GenericSignature::from_bytes(&decode_base64(&response.sponsor_signature)?)
+                    .map_err(|e| {
+                        anyhow!("Invalid sponsor signature from gas station {url}: {e}")
+                    })?;
+            check_sponsored_transaction(&data, &kind, sender, gas_budget)?;
+            Ok((data, signature))
+        }
+    }
+}
+
+/// Checks that the transaction returned by a gas station is the one requested, so that the
+/// sender does not sign anything else.
+pub fn check_sponsored_transaction(
+    data: &TransactionData,
+    kind: &TransactionKind,
+    sender: SuiAddress,
+    gas_budget: u64,
+) -> Result<(), anyhow::Error> {
+    ensure!(
+        data.kind() == kind,
+        "The sponsored transaction does not make the requested calls"
+    );
+    ensure!(
+        data.sender() == sender,
+        "The sponsored transaction is sent by {} instead of {sender}",
+        data.sender()
+    );
+    ensure!(
+        data.gas_owner() != sender,
+        "The sponsored transaction pays its gas from the sender"
+    );
+    ensure!(
+        data.gas_budget() <= gas_budget,
+        "The gas budget of the sponsored transaction {} is above the requested {gas_budget}",
+        data.gas_budget()
+    );
+    Ok(())
+}
+
+/// Signs the sponsored transaction `data` as its sender, and combines the signature with the one
+/// of the sponsor.
+pub fn sign_sponsored_transaction(
+    context: &WalletContext,
+    data: TransactionData,
+    sponsor_signature: GenericSignature,
+) -> Result<Transaction, anyhow::Error> {
+    let signature =
+        context
+            .config
+            .keystore
+            .sign_secure(&data.sender(), &data, Intent::default())?;
+    Ok(Transaction::from_generic_sig_data(
+        data,
+        Intent::default(),
+        vec![signature.into(), sponsor_signature],
+    ))
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, anyhow::Error> {
+    Base64::decode(value).map_err(|e| anyhow!("Invalid base64 {value}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::random_object_ref;
+    use sui_types::messages::GasData;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+    fn sponsored(kind: TransactionKind, sender: SuiAddress, owner: SuiAddress) -> TransactionData {
+        TransactionData::new_with_gas_data(
+            kind,
+            sender,
+            GasData {
+                payment: vec![random_object_ref()],
+                owner,
+                price: 1,
+                budget: 1000,
+            },
+        )
+    }
+
+    #[test]
+    fn parse_gas_sponsor() {
+        let address = SuiAddress::random_for_testing_only();
+        assert_eq!(
+            address.to_string().parse::<GasSponsor>().unwrap(),
+            GasSponsor::Address(address)
+        );
+        assert_eq!(
+            "https://gas.example.com/sponsor"
+                .parse::<GasSponsor>()
+                .unwrap(),
+            GasSponsor::Station("https://gas.example.com/sponsor".to_string())
+        );
+        assert!("gas.example.com".parse::<GasSponsor>().is_err());
+    }
+
+    #[test]
+    fn check_gas_station_transaction() {
+        let sender = SuiAddress::random_for_testing_only();
+        let sponsor = SuiAddress::random_for_testing_only();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.pay_sui(vec![sponsor], vec![1]).unwrap();
+        let kind = TransactionKind::programmable(builder.finish());
+
+        let data = sponsored(kind.clone(), sender, sponsor);
+        check_sponsored_transaction(&data, &kind, sender, 1000).unwrap();
+        // The budget requested caps the budget of the transaction.
+        assert!(check_sponsored_transaction(&data, &kind, sender, 999).is_err());
+        // The gas must not be paid by the sender.
+        let data = sponsored(kind.clone(), sender, sender);
+        assert!(check_sponsored_transaction(&data, &kind, sender, 1000).is_err());
+        // The transaction must be the one requested.
+        let data = sponsored(
+            TransactionKind::programmable(ProgrammableTransactionBuilder::new().finish()),
+            sender,
+            sponsor,
+        );
+        assert!(check_sponsored_transaction(&data, &kind, sender, 1000).is_err());
+        let data = sponsored(kind.clone(), SuiAddress::random_for_testing_only(), sponsor);
+        assert!(check_sponsored_transaction(&data, &kind, sender, 1000).is_err());
+    }
+}
@@ -4,9 +4,10 @@
 use std::io::{stderr, stdout, Write};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::{fs, io};
 
-use anyhow::{anyhow, bail};
+use anyhow::{anyhow, bail, ensure};
 use clap::*;
 use fastcrypto::traits::KeyPair;
 use move_package::BuildConfig;
@@ -23,6 +24,7 @@ use sui_config::{
     SUI_FULLNODE_CONFIG, SUI_NETWORK_CONFIG,
 };
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_protocol_config::ProtocolVersion;
 use sui_swarm::memory::Swarm;
 use sui_types::crypto::{SignatureScheme, SuiKeyPair};
 
@@ -32,6 +34,7 @@ use crate::console::start_console;
 use crate::fire_drill::{run_fire_drill, FireDrill};
 use crate::genesis_ceremony::{run, Ceremony};
 use crate::keytool::KeyToolCommand;
+use crate::local_network::{request_advance_epoch, serve_control_endpoint, DEFAULT_CONTROL_PORT};
 use crate::validator_commands::SuiValidatorCommand;
 use sui_move::{self, execute_move_command};
 
@@ -46,6 +49,25 @@ pub enum SuiCommand {
         config: Option<PathBuf>,
         #[clap(long = "no-full-node")]
         no_full_node: bool,
+        /// Genesis config file of the network, when generating it.
+        #[clap(long)]
+        genesis_config: Option<PathBuf>,
+        #[clap(flatten)]
+        genesis_parameters: GenesisParameters,
+        /// Generate a new network even if one was already generated, removing its state.
+        #[clap(long)]
+        force_regenesis: bool,
+        /// Port of the control endpoint of the network, used by `sui advance-epoch`.
+        #[clap(long, default_value_t = DEFAULT_CONTROL_PORT)]
+        control_port: u16,
+    },
+    /// Close the current epoch of the local network run by `sui start`, without waiting for the
+    /// end of its duration.
+    #[clap(name = "advance-epoch")]
+    AdvanceEpoch {
+        /// Port of the control endpoint of the network.
+        #[clap(long, default_value_t = DEFAULT_CONTROL_PORT)]
+        control_port: u16,
     },
     #[clap(name = "network")]
     Network {
@@ -68,8 +90,8 @@ pub enum SuiCommand {
         working_dir: Option<PathBuf>,
         #[clap(short, long, help = "Forces overwriting existing configuration")]
         force: bool,
-        #[clap(long = "epoch-duration-ms")]
-        epoch_duration_ms: Option<u64>,
+        #[clap(flatten)]
+        genesis_parameters: GenesisParameters,
         #[clap(
             long,
             value_name = "ADDR",
@@ -147,6 +169,55 @@ pub enum SuiCommand {
     },
 }
 
+/// Parameters of the genesis of a network, overriding the ones of its genesis config.
+#[derive(Args, Default)]
+#[clap(rename_all = "kebab-case")]
+pub struct GenesisParameters {
+    /// Duration of an epoch, in milliseconds.
+    #[clap(long)]
+    pub epoch_duration_ms: Option<u64>,
+    /// Protocol version the network starts at.
+    #[clap(long)]
+    pub protocol_version: Option<u64>,
+    /// Number of validators of the network.
+    #[clap(long)]
+    pub committee_size: Option<usize>,
+}
+
+impl GenesisParameters {
+    fn is_empty(&self) -> bool {
+        self.epoch_duration_ms.is_none()
+            && self.protocol_version.is_none()
+            && self.committee_size.is_none()
+    }
+
+    fn apply(self, genesis_conf: &mut GenesisConfig) -> Result<(), anyhow::Error> {
+        if let Some(epoch_duration_ms) = self.epoch_duration_ms {
+            ensure!(epoch_duration_ms > 0, "The epoch duration must be positive");
+            genesis_conf.parameters.epoch_duration_ms = epoch_duration_ms;
+        }
+        if let Some(protocol_version) = self.protocol_version {
+            ensure!(
+                (ProtocolVersion::MIN.as_u64()..=ProtocolVersion::MAX.as_u64())
+                    .contains(&protocol_version),
+                "Protocol version {protocol_version} is not supported, versions {} to {} are",
+                ProtocolVersion::MIN.as_u64(),
+                ProtocolVersion::MAX.as_u64()
+            );
+            genesis_conf.parameters.protocol_version = ProtocolVersion::new(protocol_version);
+        }
+        if let Some(committee_size) = self.committee_size {
+            ensure!(
+                genesis_conf.validator_config_info.is_none(),
+                "The committee size can't be set when the genesis config lists its validators"
+            );
+            ensure!(committee_size > 0, "The committee size must be positive");
+            genesis_conf.committee_size = committee_size;
+        }
+        Ok(())
+    }
+}
+
 impl SuiCommand {
     pub async fn execute(self) -> Result<(), anyhow::Error> {
         move_package::package_hooks::register_package_hooks(Box::new(SuiPackageHooks {}));
@@ -154,10 +225,35 @@ impl SuiCommand {
             SuiCommand::Start {
                 config,
                 no_full_node,
+                genesis_config,
+                genesis_parameters,
+                force_regenesis,
+                control_port,
             } => {
-                // Auto genesis if path is none and sui directory doesn't exists.
-                if config.is_none() && !sui_config_dir()?.join(SUI_NETWORK_CONFIG).exists() {
-                    genesis(None, None, None, false, None, None).await?;
+                let network_exists = sui_config_dir()?.join(SUI_NETWORK_CONFIG).exists();
+                if genesis_config.is_some() || !genesis_parameters.is_empty() || force_regenesis {
+                    ensure!(
+                        config.is_none(),
+                        "--network.config can't be used when generating the network"
+                    );
+                    ensure!(
+                        !network_exists || force_regenesis,
+                        "A network was already generated in {:?}, use --force-regenesis to \
+                         replace it",
+                        sui_config_dir()?
+                    );
+                    genesis(
+                        genesis_config,
+                        None,
+                        None,
+                        force_regenesis,
+                        genesis_parameters,
+                        None,
+                    )
+                    .await?;
+                } else if config.is_none() && !network_exists {
+                    // Auto genesis if path is none and sui directory doesn't exists.
+                    genesis(None, None, None, false, GenesisParameters::default(), None).await?;
                 }
 
                 // Load the config of the Sui authority.
@@ -182,6 +278,8 @@ impl SuiCommand {
                 .from_network_config(sui_config_dir()?, network_config);
 
                 swarm.launch().await?;
+                let swarm = Arc::new(swarm);
+                serve_control_endpoint(swarm.clone(), control_port)?;
 
                 let mut interval = tokio::time::interval(std::time::Duration::from_secs(3));
                 let mut unhealthy_cnt = 0;
@@ -204,6 +302,11 @@ impl SuiCommand {
                     interval.tick().await;
                 }
             }
+            SuiCommand::AdvanceEpoch { control_port } => {
+                let epoch = request_advance_epoch(control_port).await?;
+                println!("The local network advanced to epoch {epoch}");
+                Ok(())
+            }
             SuiCommand::Network {
                 config,
                 dump_addresses,
@@ -232,7 +335,7 @@ impl SuiCommand {
                 force,
                 from_config,
                 write_config,
-                epoch_duration_ms,
+                genesis_parameters,
                 benchmark_ips,
             } => {
                 genesis(
@@ -240,7 +343,7 @@ impl SuiCommand {
                     write_config,
                     working_dir,
                     force,
-                    epoch_duration_ms,
+                    genesis_parameters,
                     benchmark_ips,
                 )
                 .await
@@ -311,7 +414,7 @@ async fn genesis(
     write_config: Option<PathBuf>,
     working_dir: Option<PathBuf>,
     force: bool,
-    epoch_duration_ms: Option<u64>,
+    genesis_parameters: GenesisParameters,
     benchmark_ips: Option<Vec<String>>,
 ) -> Result<(), anyhow::Error> {
     let sui_config_dir = &match working_dir {
@@ -381,6 +484,10 @@ async fn genesis(
         Some(path) => PersistedConfig::read(&path)?,
         None => {
             if let Some(ips) = benchmark_ips {
+                ensure!(
+                    genesis_parameters.committee_size.is_none(),
+                    "The committee size of a benchmark genesis is the number of ip addresses"
+                );
                 // Make a keystore containing the key for the genesis gas object.
                 let path = sui_config_dir.join(SUI_BENCHMARK_GENESIS_GAS_KEYSTORE_FILENAME);
                 let mut keystore = FileBasedKeystore::new(&path)?;
@@ -399,6 +506,8 @@ async fn genesis(
         }
     };
 
+    genesis_parameters.apply(&mut genesis_conf)?;
+
     if let Some(path) = write_config {
         let persisted = genesis_conf.persisted(&path);
         persisted.save()?;
@@ -406,10 +515,8 @@ async fn genesis(
     }
 
     let validator_info = genesis_conf.validator_config_info.take();
-    let builder = ConfigBuilder::new(sui_config_dir);
-    if let Some(epoch_duration_ms) = epoch_duration_ms {
-        genesis_conf.parameters.epoch_duration_ms = epoch_duration_ms;
-    }
+    let builder = ConfigBuilder::new(sui_config_dir)
+        .with_protocol_version(genesis_conf.parameters.protocol_version);
     let mut network_config = if let Some(validators) = validator_info {
         builder
             .initial_accounts_config(genesis_conf)
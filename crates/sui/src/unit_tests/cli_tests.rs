@@ -15,7 +15,11 @@ use sui::client_commands::SwitchResponse;
 use sui::{
     client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext},
     config::SuiClientConfig,
-    sui_commands::SuiCommand,
+    gas_coins::SkipReason,
+    local_network::DEFAULT_CONTROL_PORT,
+    sponsor::GasSponsor,
+    sui_commands::{GenesisParameters, SuiCommand},
+    watch::{watch, WatchFilter, WatchItem},
 };
 use sui_config::{
     genesis_config::{AccountConfig, GenesisConfig, ObjectConfig},
@@ -28,17 +32,23 @@ use sui_config::{
 use sui_framework_build::compiled_package::{BuildConfig, SuiPackageHooks};
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    OwnedObjectRef, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
-    SuiObjectResponseQuery, SuiTransactionEffects, SuiTransactionEffectsAPI,
+    EventFilter, OwnedObjectRef, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionEffects, SuiTransactionEffectsAPI,
 };
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
 use sui_macros::sim_test;
+use sui_protocol_config::ProtocolVersion;
 use sui_types::base_types::{ObjectType, SuiAddress};
 use sui_types::crypto::{
     Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
-use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
+use sui_types::sui_system_state::SuiSystemStateTrait;
+use sui_types::{
+    base_types::ObjectID,
+    crypto::get_key_pair,
+    gas_coin::{GasCoin, GAS},
+};
 use sui_types::{sui_framework_address_concat_string, SUI_FRAMEWORK_ADDRESS};
 use test_utils::messages::make_transactions_with_wallet_context;
 use test_utils::network::TestClusterBuilder;
@@ -55,6 +65,10 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
     let start = SuiCommand::Start {
         config: Some(config),
         no_full_node: false,
+        genesis_config: None,
+        genesis_parameters: GenesisParameters::default(),
+        force_regenesis: false,
+        control_port: DEFAULT_CONTROL_PORT,
     }
     .execute()
     .await;
@@ -65,7 +79,7 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
         write_config: None,
         force: false,
         from_config: None,
-        epoch_duration_ms: None,
+        genesis_parameters: GenesisParameters::default(),
         benchmark_ips: None,
     }
     .execute()
@@ -103,13 +117,63 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
         write_config: None,
         force: false,
         from_config: None,
-        epoch_duration_ms: None,
+        genesis_parameters: GenesisParameters::default(),
+        benchmark_ips: None,
+    }
+    .execute()
+    .await;
+    assert!(matches!(result, Err(..)));
+
+    temp_dir.close()?;
+    Ok(())
+}
+
+#[sim_test]
+async fn test_genesis_parameters() -> Result<(), anyhow::Error> {
+    let temp_dir = tempfile::tempdir()?;
+    let working_dir = temp_dir.path();
+
+    // Protocol versions the binary doesn't support are refused.
+    let result = SuiCommand::Genesis {
+        working_dir: Some(working_dir.to_path_buf()),
+        write_config: None,
+        force: false,
+        from_config: None,
+        genesis_parameters: GenesisParameters {
+            protocol_version: Some(ProtocolVersion::MAX.as_u64() + 1),
+            ..Default::default()
+        },
         benchmark_ips: None,
     }
     .execute()
     .await;
     assert!(matches!(result, Err(..)));
 
+    SuiCommand::Genesis {
+        working_dir: Some(working_dir.to_path_buf()),
+        write_config: None,
+        force: false,
+        from_config: None,
+        genesis_parameters: GenesisParameters {
+            epoch_duration_ms: Some(10_000),
+            protocol_version: Some(ProtocolVersion::MIN.as_u64()),
+            committee_size: Some(2),
+        },
+        benchmark_ips: None,
+    }
+    .execute()
+    .await?;
+
+    let network_conf =
+        PersistedConfig::<NetworkConfig>::read(&working_dir.join(SUI_NETWORK_CONFIG))?;
+    assert_eq!(2, network_conf.validator_configs().len());
+    let system_state = network_conf.genesis.sui_system_object();
+    assert_eq!(10_000, system_state.epoch_duration_ms());
+    assert_eq!(
+        ProtocolVersion::MIN.as_u64(),
+        system_state.protocol_version()
+    );
+
     temp_dir.close()?;
     Ok(())
 }
@@ -135,7 +199,7 @@ async fn test_genesis_for_benchmarks() -> Result<(), anyhow::Error> {
         write_config: None,
         force: false,
         from_config: None,
-        epoch_duration_ms: None,
+        genesis_parameters: GenesisParameters::default(),
         benchmark_ips: Some(benchmark_ips.clone()),
     }
     .execute()
@@ -1611,6 +1675,287 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_clean_coins() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+
+    let gas_objects = context.gas_objects(address).await?;
+    assert!(gas_objects.len() > 1);
+    let (gas_value, gas) = gas_objects
+        .iter()
+        .max_by_key(|(value, _)| *value)
+        .map(|(value, object)| (*value, object.object_id))
+        .unwrap();
+
+    // The preview does not execute anything.
+    let resp = SuiClientCommands::CleanCoins {
+        coin_type: None,
+        dust_threshold: u64::MAX,
+        gas: Some(gas),
+        gas_budget: 20_000,
+        dry_run: true,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::CleanCoins(plan, responses) = resp else {
+        panic!("Command failed")
+    };
+    assert!(responses.is_empty());
+    assert_eq!(plan.batches.len(), 1);
+    assert_eq!(plan.batches[0].merge.len(), gas_objects.len() - 1);
+    assert!(plan.storage_rebate() > 0);
+    assert_eq!(context.gas_objects(address).await?.len(), gas_objects.len());
+
+    // All coins are merged into the gas coin.
+    let resp = SuiClientCommands::CleanCoins {
+        coin_type: None,
+        dust_threshold: u64::MAX,
+        gas: Some(gas),
+        gas_budget: 20_000,
+        dry_run: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::CleanCoins(_, responses) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(responses.len(), 1);
+    let gas_objects = context.gas_objects(address).await?;
+    assert_eq!(gas_objects.len(), 1);
+    assert_eq!(gas_objects[0].1.object_id, gas);
+    assert!(gas_objects[0].0 > gas_value);
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_split_and_smash_gas() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+    let gas_objects = context.gas_objects(address).await?;
+
+    let resp = SuiClientCommands::SplitGas {
+        count: 3,
+        amount: 1000,
+        exclude: vec![],
+        gas_budget: 20_000,
+        dry_run: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::SplitGas(plan, dry_runs, responses) = resp else {
+        panic!("Command failed")
+    };
+    assert!(dry_runs.is_empty());
+    assert_eq!(responses.len(), 1);
+    assert_eq!(plan.count(), 3);
+    let split_coins: Vec<_> = context
+        .gas_objects(address)
+        .await?
+        .into_iter()
+        .filter(|(value, _)| *value == 1000)
+        .collect();
+    assert_eq!(split_coins.len(), 3);
+
+    // The preview does not execute anything, and leaves the excluded coins alone.
+    let excluded = split_coins[0].1.object_id;
+    let resp = SuiClientCommands::SmashGas {
+        max_balance: Some(1000),
+        exclude: vec![excluded],
+        gas_budget: 20_000,
+        dry_run: true,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::SmashGas(plan, dry_runs, responses) = resp else {
+        panic!("Command failed")
+    };
+    assert!(responses.is_empty());
+    assert_eq!(dry_runs.len(), plan.cleanup.batches.len());
+    assert!(plan
+        .cleanup
+        .batches
+        .iter()
+        .flat_map(|batch| batch.coins())
+        .all(|coin| coin.object_ref.0 != excluded && coin.balance <= 1000));
+    assert!(plan.skipped.contains(&(excluded, SkipReason::Excluded)));
+    assert_eq!(
+        context.gas_objects(address).await?.len(),
+        gas_objects.len() + 3
+    );
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_address_summary() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    // A fresh address, so that it did not receive anything at genesis.
+    let recipient = SuiAddress::random_for_testing_only();
+    let context = &mut test_cluster.wallet;
+
+    let gas = context.gas_objects(address).await?[0].1.object_id;
+    SuiClientCommands::TransferSui {
+        to: recipient,
+        sui_coin_object_id: gas,
+        gas_budget: 20_000,
+        amount: Some(1000),
+    }
+    .execute(context)
+    .await?;
+
+    let resp = SuiClientCommands::Summary {
+        address: None,
+        start: None,
+        end: None,
+        max_transactions: 100,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Summary(summary) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(summary.address, address);
+    assert_eq!(summary.sent, 1);
+    assert_eq!(summary.failed, 0);
+    assert!(summary.gas_spent > 0);
+    assert!(summary.package_calls.is_empty());
+
+    let resp = SuiClientCommands::Summary {
+        address: Some(recipient),
+        start: None,
+        end: None,
+        max_transactions: 100,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Summary(summary) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(summary.sent, 0);
+    assert_eq!(summary.received, 1);
+    assert_eq!(summary.gas_spent, 0);
+    assert_eq!(
+        summary.balance_changes.get(&GAS::type_tag().to_string()),
+        Some(&1000)
+    );
+
+    // Nothing was sent before the second day of the unix epoch.
+    let resp = SuiClientCommands::Summary {
+        address: None,
+        start: None,
+        end: Some("1970-01-02".to_string()),
+        max_transactions: 100,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Summary(summary) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(summary.sent, 0);
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_watch() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let address = test_cluster.get_address_0();
+    let recipient = SuiAddress::random_for_testing_only();
+    let client = test_cluster.fullnode_handle.sui_client.clone();
+
+    // Watch the object and balance changes of the address only. The recipient sends nothing,
+    // so that no event comes between them.
+    let filter = WatchFilter {
+        events: EventFilter::Sender(recipient),
+        ..WatchFilter::for_address(address)
+    };
+    let handle = tokio::spawn(async move {
+        let mut items = vec![];
+        watch(&client, filter, Some(2), |item| items.push(item.clone())).await?;
+        Ok::<_, anyhow::Error>(items)
+    });
+    sleep(Duration::from_secs(1)).await;
+
+    let context = &mut test_cluster.wallet;
+    let gas = context.gas_objects(address).await?[0].1.object_id;
+    SuiClientCommands::TransferSui {
+        to: recipient,
+        sui_coin_object_id: gas,
+        gas_budget: 20_000,
+        amount: Some(1000),
+    }
+    .execute(context)
+    .await?;
+
+    let items = handle.await??;
+    let WatchItem::ObjectChange(notification) = &items[0] else {
+        panic!("Expected an object change, got {}", items[0])
+    };
+    assert_eq!(notification.object_change.object_id(), gas);
+    // The balance changes of a transaction follow its first object change.
+    let WatchItem::BalanceChange {
+        transaction_digest,
+        balance_change,
+    } = &items[1]
+    else {
+        panic!("Expected a balance change, got {}", items[1])
+    };
+    assert_eq!(*transaction_digest, notification.transaction_digest);
+    assert_eq!(balance_change.owner, Owner::AddressOwner(address));
+    assert!(balance_change.amount < -1000);
+    Ok(())
+}
+
+#[sim_test]
+async fn test_ptb_with_gas_sponsor() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let sponsor = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let sender = context.active_address()?;
+    assert_ne!(sender, sponsor);
+
+    // The sender sends one of its coins to the sponsor, which pays for the gas.
+    let coin = context.gas_objects(sender).await?[0].1.object_id;
+    let sponsor_gas = context.gas_objects(sponsor).await?[0].1.object_id;
+    let resp = SuiClientCommands::Ptb {
+        commands: vec![format!("transfer-objects [{coin}] {sponsor}")],
+        gas: Some(sponsor_gas),
+        gas_budget: 20_000,
+        preview: false,
+        gas_sponsor: Some(GasSponsor::Address(sponsor)),
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Ptb(response) = resp else {
+        panic!("Command failed")
+    };
+    let effects = response.effects.unwrap();
+    assert_eq!(effects.gas_object().reference.object_id, sponsor_gas);
+    assert_eq!(effects.gas_object().owner, Owner::AddressOwner(sponsor));
+    assert_eq!(
+        context.try_get_object_owner(&Some(coin)).await?,
+        Some(sponsor)
+    );
+
+    // The sponsor can't be the sender.
+    let result = SuiClientCommands::Ptb {
+        commands: vec![format!("transfer-objects [{coin}] {sender}")],
+        gas: None,
+        gas_budget: 20_000,
+        preview: false,
+        gas_sponsor: Some(GasSponsor::Address(sender)),
+    }
+    .execute(context)
+    .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_split_coin() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await?;
@@ -1844,8 +2189,9 @@ async fn test_execute_signed_tx() -> Result<(), anyhow::Error> {
 
     let (tx_data, signatures) = txn.to_tx_bytes_and_signatures();
     SuiClientCommands::ExecuteSignedTx {
-        tx_bytes: tx_data.encoded(),
+        tx_bytes: Some(tx_data.encoded()),
         signatures: signatures.into_iter().map(|s| s.encoded()).collect(),
+        signed_tx_file: None,
     }
     .execute(context)
     .await?;
@@ -5,6 +5,8 @@ use std::str::FromStr;
 
 use crate::keytool::read_authority_keypair_from_file;
 use crate::keytool::read_keypair_from_file;
+use crate::keytool::read_transaction_file;
+use crate::keytool::{combine_partial_sigs, multisig_public_key, MultiSigFile};
 
 use super::write_keypair_to_file;
 use super::KeyToolCommand;
@@ -30,6 +32,7 @@ use sui_types::crypto::Signature;
 use sui_types::crypto::SignatureScheme;
 use sui_types::crypto::SuiKeyPair;
 use sui_types::crypto::SuiSignatureInner;
+use sui_types::messages::Transaction;
 use sui_types::messages::TransactionData;
 use tempfile::TempDir;
 
@@ -380,3 +383,153 @@ fn test_sign_command() -> Result<(), anyhow::Error> {
     .execute(&mut keystore)?;
     Ok(())
 }
+
+#[test]
+fn test_sign_transaction_command() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new(1));
+    let sender = *keystore.addresses().first().unwrap();
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let tx_data = TransactionData::new_pay_sui_with_dummy_gas_price(
+        sender,
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        1000,
+    )
+    .unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let tx_file = temp_dir.path().join("tx.bcs");
+    let signed_tx_file = temp_dir.path().join("tx.signed.bcs");
+    std::fs::write(&tx_file, bcs::to_bytes(&tx_data)?)?;
+
+    // Without --offline, the signature is printed and nothing is written.
+    KeyToolCommand::SignTransaction {
+        address: sender,
+        tx_file: tx_file.clone(),
+        intent: None,
+        offline: false,
+        output: None,
+    }
+    .execute(&mut keystore)?;
+    assert!(!signed_tx_file.exists());
+
+    KeyToolCommand::SignTransaction {
+        address: sender,
+        tx_file,
+        intent: None,
+        offline: true,
+        output: Some(signed_tx_file.clone()),
+    }
+    .execute(&mut keystore)?;
+
+    // The signed transaction is read back as is, and its signature is valid.
+    let signed = read_transaction_file(&signed_tx_file, None)?;
+    assert_eq!(signed.transaction_data(), &tx_data);
+    assert_eq!(signed.tx_signatures().len(), 1);
+    Transaction::new(signed).verify()?;
+    Ok(())
+}
+
+#[test]
+fn test_multisig_lifecycle() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new(3));
+    let pks = keystore.keys();
+    let addresses: Vec<SuiAddress> = pks.iter().map(|pk| pk.into()).collect();
+    let weights = vec![1, 2, 3];
+
+    // The threshold must be reachable, and public keys distinct.
+    assert!(multisig_public_key(pks.clone(), weights.clone(), 7).is_err());
+    assert!(multisig_public_key(
+        vec![pks[0].clone(), pks[0].clone(), pks[1].clone()],
+        weights.clone(),
+        3
+    )
+    .is_err());
+    let multisig_pk = multisig_public_key(pks.clone(), weights.clone(), 3)?;
+    let multisig_address: SuiAddress = multisig_pk.clone().into();
+
+    let temp_dir = TempDir::new().unwrap();
+    let multisig_file = temp_dir.path().join("multisig.json");
+    KeyToolCommand::MultiSigAddress {
+        threshold: 3,
+        pks: pks.clone(),
+        weights,
+        output: Some(multisig_file.clone()),
+    }
+    .execute(&mut keystore)?;
+    let multisig = MultiSigFile::read(&multisig_file)?;
+    assert_eq!(multisig.address, multisig_address);
+    assert_eq!(multisig.multisig_public_key()?, multisig_pk);
+
+    let gas = (
+        ObjectID::random(),
+        SequenceNumber::new(),
+        ObjectDigest::random(),
+    );
+    let tx_data = TransactionData::new_pay_sui_with_dummy_gas_price(
+        multisig_address,
+        vec![gas],
+        vec![SuiAddress::random_for_testing_only()],
+        vec![10000],
+        gas,
+        1000,
+    )
+    .unwrap();
+    let tx_file = temp_dir.path().join("tx.bcs");
+    std::fs::write(&tx_file, bcs::to_bytes(&tx_data)?)?;
+
+    KeyToolCommand::MultiSigSign {
+        address: addresses[0],
+        multisig_file: multisig_file.clone(),
+        tx_file: tx_file.clone(),
+    }
+    .execute(&mut keystore)?;
+    // Only participants can sign.
+    keystore.add_key(SuiKeyPair::Ed25519(get_key_pair().1))?;
+    let outsider = *keystore
+        .addresses()
+        .iter()
+        .find(|address| !addresses.contains(address))
+        .unwrap();
+    assert!(KeyToolCommand::MultiSigSign {
+        address: outsider,
+        multisig_file: multisig_file.clone(),
+        tx_file: tx_file.clone(),
+    }
+    .execute(&mut keystore)
+    .is_err());
+
+    let sigs: Vec<Signature> = addresses
+        .iter()
+        .map(|address| keystore.sign_secure(address, &tx_data, Intent::default()))
+        .collect::<Result<_, _>>()?;
+
+    // The signatures must reach the threshold, each participant signing once.
+    assert!(combine_partial_sigs(vec![sigs[0].clone()], multisig_pk.clone()).is_err());
+    assert!(
+        combine_partial_sigs(vec![sigs[1].clone(), sigs[1].clone()], multisig_pk.clone()).is_err()
+    );
+    combine_partial_sigs(vec![sigs[0].clone(), sigs[1].clone()], multisig_pk)?;
+
+    let signed_tx_file = temp_dir.path().join("tx.signed.bcs");
+    KeyToolCommand::MultiSigCombinePartialSig {
+        sigs: vec![sigs[0].clone(), sigs[1].clone()],
+        pks: vec![],
+        weights: vec![],
+        threshold: None,
+        multisig_file: Some(multisig_file),
+        tx_file: Some(tx_file),
+        output: Some(signed_tx_file.clone()),
+    }
+    .execute(&mut keystore)?;
+    let signed = read_transaction_file(&signed_tx_file, None)?;
+    assert_eq!(signed.transaction_data(), &tx_data);
+    Transaction::new(signed).verify()?;
+    Ok(())
+}
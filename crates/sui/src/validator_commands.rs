@@ -7,7 +7,8 @@ use std::{
     collections::{BTreeMap, HashSet},
     fmt::{self, Debug, Display, Formatter, Write},
     fs,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    time::Duration,
 };
 use sui_config::genesis::GenesisValidatorInfo;
 use sui_framework::{SuiSystem, SystemPackage};
@@ -56,6 +57,9 @@ use sui_types::{
 
 // TODO adjust this to a reasonable number after the gas fix is in
 const DEFAULT_GAS_BUDGET: u64 = 15_000_000;
+/// Commission rates are in basis points, 100% at most.
+const MAX_COMMISSION_RATE: u64 = 10_000;
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
@@ -102,6 +106,29 @@ pub enum SuiValidatorCommand {
         /// Gas budget for this transaction.
         #[clap(name = "gas-budget", long)]
         gas_budget: Option<u64>,
+        /// Submit new addresses without checking that they are reachable.
+        #[clap(name = "skip-reachability-check", long)]
+        skip_reachability_check: bool,
+    },
+    /// Generate new network, worker and protocol keys and submit them for the next epoch. The
+    /// keys are written to new files, for the node config to use from the next epoch.
+    #[clap(name = "rotate-keys")]
+    RotateKeys {
+        /// Rotate the network key.
+        #[clap(long)]
+        network: bool,
+        /// Rotate the worker key.
+        #[clap(long)]
+        worker: bool,
+        /// Rotate the protocol key.
+        #[clap(long)]
+        protocol: bool,
+        /// Directory of the new key files, the current directory if not provided.
+        #[clap(name = "key-dir", long)]
+        key_dir: Option<PathBuf>,
+        /// Gas budget for each of the transactions.
+        #[clap(name = "gas-budget", long)]
+        gas_budget: Option<u64>,
     },
     /// Update gas price that is used to calculate Reference Gas Price
     #[clap(name = "update-gas-price")]
@@ -146,6 +173,7 @@ pub enum SuiValidatorCommandResponse {
     JoinCommittee(SuiTransactionResponse),
     LeaveCommittee(SuiTransactionResponse),
     UpdateMetadata(SuiTransactionResponse),
+    RotateKeys(Vec<SuiTransactionResponse>),
     UpdateGasPrice(SuiTransactionResponse),
     ReportValidator(SuiTransactionResponse),
 }
@@ -341,12 +369,52 @@ impl SuiValidatorCommand {
             SuiValidatorCommand::UpdateMetadata {
                 metadata,
                 gas_budget,
+                skip_reachability_check,
             } => {
                 let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                if !skip_reachability_check {
+                    check_metadata_reachable(&metadata).await?;
+                }
                 let resp = update_metadata(context, metadata, gas_budget).await?;
                 SuiValidatorCommandResponse::UpdateMetadata(resp)
             }
 
+            SuiValidatorCommand::RotateKeys {
+                network,
+                worker,
+                protocol,
+                key_dir,
+                gas_budget,
+            } => {
+                if !(network || worker || protocol) {
+                    bail!("Nothing to rotate, pass --network, --worker or --protocol.");
+                }
+                let gas_budget = gas_budget.unwrap_or(DEFAULT_GAS_BUDGET);
+                let dir = match key_dir {
+                    Some(dir) => dir,
+                    None => std::env::current_dir()?,
+                };
+                // Generate all the keys first, so that no key is submitted if one can't be written.
+                let mut updates = vec![];
+                if network {
+                    let file = make_next_key_file(&dir, "network", false)?;
+                    updates.push(MetadataUpdate::NetworkPubKey { file });
+                }
+                if worker {
+                    let file = make_next_key_file(&dir, "worker", false)?;
+                    updates.push(MetadataUpdate::WorkerPubKey { file });
+                }
+                if protocol {
+                    let file = make_next_key_file(&dir, "protocol", true)?;
+                    updates.push(MetadataUpdate::ProtocolPubKey { file });
+                }
+                let mut responses = vec![];
+                for update in updates {
+                    responses.push(update_metadata(context, update, gas_budget).await?);
+                }
+                SuiValidatorCommandResponse::RotateKeys(responses)
+            }
+
             SuiValidatorCommand::UpdateGasPrice {
                 operation_cap_id,
                 gas_price,
@@ -579,6 +647,11 @@ impl Display for SuiValidatorCommandResponse {
             SuiValidatorCommandResponse::UpdateMetadata(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiValidatorCommandResponse::RotateKeys(responses) => {
+                for response in responses {
+                    write!(writer, "{}", write_transaction_response(response)?)?;
+                }
+            }
             SuiValidatorCommandResponse::UpdateGasPrice(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
@@ -768,6 +841,8 @@ pub enum MetadataUpdate {
     WorkerAddress { worker_address: Multiaddr },
     /// Update P2P Address. Effectuate from next epoch.
     P2pAddress { p2p_address: Multiaddr },
+    /// Update commission rate, in basis points. Effectuate from next epoch.
+    CommissionRate { commission_rate: u64 },
     /// Update Network Public Key. Effectuate from next epoch.
     NetworkPubKey {
         #[clap(name = "network-key-path")]
@@ -859,6 +934,16 @@ async fn update_metadata(
             )
             .await
         }
+        MetadataUpdate::CommissionRate { commission_rate } => {
+            if commission_rate > MAX_COMMISSION_RATE {
+                bail!(
+                    "Commission rate {commission_rate} is above {MAX_COMMISSION_RATE} basis points."
+                );
+            }
+            let _status = check_status(context, HashSet::from([Pending, Active])).await?;
+            let args = vec![CallArg::Pure(bcs::to_bytes(&commission_rate).unwrap())];
+            call_0x5(context, "request_set_commission_rate", args, gas_budget).await
+        }
         MetadataUpdate::NetworkPubKey { file } => {
             let _status = check_status(context, HashSet::from([Pending, Active])).await?;
             let network_pub_key: NetworkPublicKey =
@@ -931,3 +1016,116 @@ async fn check_status(
     }
     bail!("Validator {validator_address} is {:?}, this operation is not supported in this tool or prohibited.", status)
 }
+
+/// Writes a new key to `<dir>/<name>-next.key`, refusing to overwrite an existing file so that
+/// the keys of a rotation in progress are never lost.
+fn make_next_key_file(dir: &Path, name: &str, is_protocol_key: bool) -> Result<PathBuf> {
+    let file_name = dir.join(format!("{name}-next.key"));
+    if file_name.exists() {
+        bail!(
+            "Key file {file_name:?} already exists, move it away to rotate the {name} key again."
+        );
+    }
+    make_key_files(file_name.clone(), is_protocol_key, None)?;
+    Ok(file_name)
+}
+
+/// Host, transport and port of an address of the form `/[ip4,ip6,dns]/{}/[tcp,udp]/{port}[/..]`.
+fn address_host_port(address: &Multiaddr) -> Result<(String, &'static str, u16)> {
+    let address_str = address.to_string();
+    let parts: Vec<_> = address_str.split('/').skip(1).collect();
+    let [host_protocol, host, transport, port, ..] = parts[..] else {
+        bail!("Unsupported address {address}, expected /<ip4|ip6|dns>/<host>/<tcp|udp>/<port>.");
+    };
+    if !matches!(host_protocol, "ip4" | "ip6" | "dns" | "dns4" | "dns6") {
+        bail!("Unsupported host protocol {host_protocol} in address {address}.");
+    }
+    let transport = match transport {
+        "tcp" => "tcp",
+        "udp" => "udp",
+        _ => bail!("Unsupported transport {transport} in address {address}."),
+    };
+    let port = port
+        .parse()
+        .map_err(|e| anyhow!("Invalid port {port} in address {address}: {e}"))?;
+    Ok((host.to_string(), transport, port))
+}
+
+/// Checks that the validator can be reached at `address` before it is submitted. TCP addresses
+/// must accept connections. UDP is connectionless, so UDP addresses must only resolve.
+async fn check_address_reachable(address: &Multiaddr) -> Result<()> {
+    let (host, transport, port) = address_host_port(address)?;
+    let resolved: Vec<_> = tokio::time::timeout(
+        REACHABILITY_TIMEOUT,
+        tokio::net::lookup_host((host.as_str(), port)),
+    )
+    .await
+    .map_err(|_| anyhow!("Timed out resolving {host} of address {address}."))?
+    .map_err(|e| anyhow!("Can't resolve {host} of address {address}: {e}"))?
+    .collect();
+    if resolved.is_empty() {
+        bail!("{host} of address {address} resolves to no IP address.");
+    }
+    if transport == "tcp" {
+        tokio::time::timeout(
+            REACHABILITY_TIMEOUT,
+            tokio::net::TcpStream::connect(&resolved[..]),
+        )
+        .await
+        .map_err(|_| anyhow!("Timed out connecting to address {address}."))?
+        .map_err(|e| anyhow!("Can't connect to address {address}: {e}"))?;
+    }
+    Ok(())
+}
+
+async fn check_metadata_reachable(metadata: &MetadataUpdate) -> Result<()> {
+    match metadata {
+        MetadataUpdate::NetworkAddress {
+            network_address: address,
+        }
+        | MetadataUpdate::PrimaryAddress {
+            primary_address: address,
+        }
+        | MetadataUpdate::WorkerAddress {
+            worker_address: address,
+        }
+        | MetadataUpdate::P2pAddress {
+            p2p_address: address,
+        } => check_address_reachable(address)
+            .await
+            .map_err(|e| anyhow!("{e} Pass --skip-reachability-check to submit it anyway.")),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_port_of_addresses() {
+        let address = Multiaddr::try_from("/dns/validator.example.com/tcp/8080/http").unwrap();
+        assert_eq!(
+            address_host_port(&address).unwrap(),
+            ("validator.example.com".to_string(), "tcp", 8080)
+        );
+        let address = Multiaddr::try_from("/ip4/127.0.0.1/udp/8084").unwrap();
+        assert_eq!(
+            address_host_port(&address).unwrap(),
+            ("127.0.0.1".to_string(), "udp", 8084)
+        );
+        let address = Multiaddr::try_from("/ip4/127.0.0.1").unwrap();
+        assert!(address_host_port(&address).is_err());
+    }
+
+    #[tokio::test]
+    async fn tcp_address_reachability() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let address = Multiaddr::try_from(format!("/ip4/127.0.0.1/tcp/{port}/http")).unwrap();
+        check_address_reachable(&address).await.unwrap();
+
+        drop(listener);
+        assert!(check_address_reachable(&address).await.is_err());
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streaming for `sui client watch`.
+//!
+//! Events and object changes are streamed from the websocket subscriptions of the node. The node
+//! has no subscription for balance changes, they are read from every new transaction seen in the
+//! object change stream instead.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+
+use anyhow::anyhow;
+use futures::{stream, StreamExt};
+use serde::Serialize;
+use sui_json_rpc_types::{
+    BalanceChange, EventFilter, ObjectChange, ObjectChangeFilter, ObjectChangeNotification,
+    SuiEvent, SuiTransactionResponseOptions,
+};
+use sui_sdk::SuiClient;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+
+/// Number of recent transactions remembered, so that the balance changes of a transaction are
+/// only read once however many of its object changes are streamed.
+const RECENT_TRANSACTIONS: usize = 1000;
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum WatchItem {
+    Event(SuiEvent),
+    ObjectChange(ObjectChangeNotification),
+    #[serde(rename_all = "camelCase")]
+    BalanceChange {
+        transaction_digest: TransactionDigest,
+        balance_change: BalanceChange,
+    },
+}
+
+impl Display for WatchItem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchItem::Event(event) => write!(
+                f,
+                "[event] {} from {} in tx {}: {}",
+                event.type_, event.sender, event.id.tx_digest, event.parsed_json
+            ),
+            WatchItem::ObjectChange(ObjectChangeNotification {
+                transaction_digest,
+                object_change,
+            }) => {
+                let change = match object_change {
+                    ObjectChange::Published { .. } => "published",
+                    ObjectChange::Transferred { .. } => "transferred",
+                    ObjectChange::Mutated { .. } => "mutated",
+                    ObjectChange::Deleted { .. } => "deleted",
+                    ObjectChange::Wrapped { .. } => "wrapped",
+                    ObjectChange::Created { .. } => "created",
+                };
+                write!(f, "[object] {change} {}", object_change.object_id())?;
+                if let Some(object_type) = object_change.object_type() {
+                    write!(f, " of type {object_type}")?;
+                }
+                if let Some(owner) = object_change.owner() {
+                    write!(f, ", owned by {owner}")?;
+                }
+                write!(f, " in tx {transaction_digest}")
+            }
+            WatchItem::BalanceChange {
+                transaction_digest,
+                balance_change,
+            } => write!(
+                f,
+                "[balance] {:+} {} for {} in tx {transaction_digest}",
+                balance_change.amount, balance_change.coin_type, balance_change.owner
+            ),
+        }
+    }
+}
+
+/// The websocket URL of the node serving JSON-RPC at `rpc`, on the same host and port.
+pub fn ws_url(rpc: &str) -> Result<String, anyhow::Error> {
+    if let Some(rest) = rpc.strip_prefix("https://") {
+        Ok(format!("wss://{rest}"))
+    } else if let Some(rest) = rpc.strip_prefix("http://") {
+        Ok(format!("ws://{rest}"))
+    } else {
+        Err(anyhow!(
+            "Can't derive a websocket URL from RPC URL {rpc}, add an environment with one"
+        ))
+    }
+}
+
+/// What to watch: events and object changes matching the filters, and balance changes of the
+/// transactions changing objects, of `address` only if provided.
+pub struct WatchFilter {
+    pub events: EventFilter,
+    pub object_changes: ObjectChangeFilter,
+    pub address: Option<SuiAddress>,
+}
+
+impl WatchFilter {
+    /// Events of the transactions sent by `address`, changes of the objects it owns, and changes
+    /// of its balances.
+    pub fn for_address(address: SuiAddress) -> Self {
+        Self {
+            events: EventFilter::Sender(address),
+            object_changes: ObjectChangeFilter::Owner(address),
+            address: Some(address),
+        }
+    }
+}
+
+/// Streams the items matching `filter` from the node of `client`, which must have a websocket
+/// connection, calling `on_item` with every item until `limit` items were streamed, if provided.
+/// Returns the number of items streamed.
+pub async fn watch(
+    client: &SuiClient,
+    filter: WatchFilter,
+    limit: Option<usize>,
+    mut on_item: impl FnMut(&WatchItem),
+) -> Result<usize, anyhow::Error> {
+    let events = client
+        .event_api()
+        .subscribe_event(filter.events)
+        .await?
+        .map(|item| item.map(WatchItem::Event));
+    let object_changes = client
+        .event_api()
+        .subscribe_object_changes(filter.object_changes)
+        .await?
+        .map(|item| item.map(WatchItem::ObjectChange));
+    let mut items = stream::select(events, object_changes);
+
+    let mut count = 0;
+    let mut recent_transactions = VecDeque::new();
+    let mut seen_transactions = HashSet::new();
+    while limit.map_or(true, |limit| count < limit) {
+        let Some(item) = items.next().await else {
+            break;
+        };
+        let item = item?;
+
+        let mut new_transaction = None;
+        if let WatchItem::ObjectChange(notification) = &item {
+            let digest = notification.transaction_digest;
+            if seen_transactions.insert(digest) {
+                recent_transactions.push_back(digest);
+                if recent_transactions.len() > RECENT_TRANSACTIONS {
+                    let oldest = recent_transactions.pop_front().unwrap();
+                    seen_transactions.remove(&oldest);
+                }
+                new_transaction = Some(digest);
+            }
+        }
+        on_item(&item);
+        count += 1;
+
+        if let Some(digest) = new_transaction {
+            for item in balance_changes(client, digest, filter.address).await? {
+                if limit.map_or(false, |limit| count >= limit) {
+                    break;
+                }
+                on_item(&item);
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// The balance changes made by the transaction `digest`, of `address` only if provided.
+async fn balance_changes(
+    client: &SuiClient,
+    digest: TransactionDigest,
+    address: Option<SuiAddress>,
+) -> Result<Vec<WatchItem>, anyhow::Error> {
+    let response = client
+        .read_api()
+        .get_transaction_with_options(
+            digest,
+            SuiTransactionResponseOptions::new().with_balance_changes(),
+        )
+        .await?;
+    Ok(response
+        .balance_changes
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|change| {
+            address.map_or(true, |address| {
+                change.owner.get_owner_address().ok() == Some(address)
+            })
+        })
+        .map(|balance_change| WatchItem::BalanceChange {
+            transaction_digest: digest,
+            balance_change,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ws_url_from_rpc_url() {
+        assert_eq!(
+            ws_url("https://fullnode.devnet.sui.io:443").unwrap(),
+            "wss://fullnode.devnet.sui.io:443"
+        );
+        assert_eq!(
+            ws_url("http://127.0.0.1:9000").unwrap(),
+            "ws://127.0.0.1:9000"
+        );
+        assert!(ws_url("127.0.0.1:9000").is_err());
+    }
+}
@@ -13,6 +13,7 @@ use mysten_metrics::RegistryService;
 use prometheus::Registry;
 use serde_json::json;
 use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext};
+use sui_core::checkpoint_stream_server::get_checkpoint_data;
 use sui_json_rpc_types::EventFilter;
 use sui_json_rpc_types::{
     type_and_fields_from_move_struct, SuiEvent, SuiExecutionStatus, SuiTransactionEffectsAPI,
@@ -28,7 +29,7 @@ use sui_types::event::{Event, EventID};
 use sui_types::message_envelope::Message;
 use sui_types::messages::{
     ExecuteTransactionRequest, ExecuteTransactionRequestType, ExecuteTransactionResponse, GasData,
-    QuorumDriverResponse, TransactionData, TransactionKind,
+    QuorumDriverResponse, TransactionData, TransactionEffectsAPI, TransactionKind,
 };
 use sui_types::object::{Object, ObjectRead, Owner, PastObjectRead};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
@@ -83,6 +84,62 @@ async fn test_full_node_follows_txes() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_full_node_checkpoint_data() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await?;
+    let node = test_cluster.start_fullnode().await?.sui_node;
+
+    let context = &mut test_cluster.wallet;
+    let (transferred_object, _, receiver, digest, _, _) = transfer_coin(context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    // Wait for the checkpoint of the transfer to be executed.
+    let checkpoint = timeout(Duration::from_secs(60), async {
+        loop {
+            let state = node.state();
+            if let Some((_, checkpoint)) = state.get_transaction_checkpoint_sequence(&digest)? {
+                if state.get_latest_checkpoint_sequence_number()? >= checkpoint {
+                    return Ok::<_, anyhow::Error>(checkpoint);
+                }
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await??;
+
+    let data = get_checkpoint_data(&node.state(), checkpoint)?;
+    assert_eq!(data.checkpoint_summary.sequence_number, checkpoint);
+    assert_eq!(
+        data.transactions.len(),
+        data.checkpoint_contents.iter().count()
+    );
+    let transfer = data
+        .transactions
+        .iter()
+        .find(|transaction| *transaction.transaction.digest() == digest)
+        .unwrap();
+    assert_eq!(
+        transfer.events.is_some(),
+        transfer.effects.events_digest().is_some()
+    );
+
+    // The transferred coin is read at its previous version and written at its new version.
+    let input = transfer
+        .input_objects
+        .iter()
+        .find(|object| object.id() == transferred_object)
+        .unwrap();
+    let output = transfer
+        .output_objects
+        .iter()
+        .find(|object| object.id() == transferred_object)
+        .unwrap();
+    assert!(input.version() < output.version());
+    assert_eq!(output.owner.get_owner_address().unwrap(), receiver);
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_full_node_shared_objects() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await?;
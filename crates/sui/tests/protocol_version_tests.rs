@@ -51,6 +51,21 @@ fn test_protocol_overrides_2() {
     );
 }
 
+#[test]
+fn test_feature_flag_overrides() {
+    telemetry_subscribers::init_for_testing();
+
+    let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+        config.set_feature_flag_for_testing("soft_bundle", true);
+        config.set_feature_flag_for_testing("package_upgrades", false);
+        config
+    });
+
+    let config = ProtocolConfig::get_for_max_version();
+    assert!(config.check_soft_bundle_supported().is_ok());
+    assert!(config.check_package_upgrades_supported().is_err());
+}
+
 #[cfg(msim)]
 mod sim_only_tests {
 
@@ -101,6 +116,49 @@ mod sim_only_tests {
         expect_upgrade_succeeded(&test_cluster).await;
     }
 
+    #[sim_test]
+    async fn test_cluster_protocol_config_overrides() {
+        let test_cluster = TestClusterBuilder::new()
+            .with_protocol_config_override(|_, config| {
+                config.set_max_function_definitions_for_testing(42)
+            })
+            .with_feature_flag("soft_bundle", true)
+            .build()
+            .await
+            .unwrap();
+
+        for v in test_cluster.swarm.validators() {
+            let node_handle = v.get_node_handle().expect("node should be running");
+            node_handle.with(|node| {
+                let epoch_store = node.state().epoch_store_for_testing();
+                let config = epoch_store.protocol_config();
+                assert_eq!(config.max_function_definitions(), 42);
+                assert!(config.check_soft_bundle_supported().is_ok());
+            });
+        }
+    }
+
+    #[sim_test]
+    async fn test_protocol_version_upgrade_mixed_version_committee() {
+        let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
+            config.set_buffer_stake_for_protocol_upgrade_bps_for_testing(0);
+            config
+        });
+
+        ProtocolConfig::poison_get_for_min_version();
+
+        let mut versions = vec![SupportedProtocolVersions::new_for_testing(START, FINISH); 4];
+        versions[0] = SupportedProtocolVersions::new_for_testing(START, START);
+        let test_cluster = TestClusterBuilder::new()
+            .with_epoch_duration_ms(20000)
+            .with_validator_protocol_versions(versions)
+            .build()
+            .await
+            .unwrap();
+
+        expect_upgrade_succeeded(&test_cluster).await;
+    }
+
     #[sim_test]
     async fn test_protocol_version_upgrade_with_shutdown_validator() {
         let _guard = ProtocolConfig::apply_overrides_for_testing(|_, mut config| {
@@ -396,6 +454,8 @@ mod sim_only_tests {
                 Base64::from_bytes(&bcs::to_bytes(&txn).unwrap()),
                 /* gas_price */ None,
                 /* epoch_id */ None,
+                /* show_trace */ None,
+                /* object_overrides */ None,
             )
             .await
             .unwrap();
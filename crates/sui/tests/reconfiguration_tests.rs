@@ -340,6 +340,49 @@ async fn test_create_advance_epoch_tx_race() {
     reconfig_delay_tx.send(()).unwrap();
 }
 
+#[cfg(msim)]
+#[sim_test]
+async fn test_validator_crash_after_checkpoint_signature_recovers() {
+    use test_utils::fault_injection::{CrashPoint, RestartPolicy};
+
+    telemetry_subscribers::init_for_testing();
+    sui_protocol_config::ProtocolConfig::poison_get_for_min_version();
+
+    let test_cluster = Arc::new(
+        TestClusterBuilder::new()
+            .with_epoch_duration_ms(5000)
+            .build()
+            .await
+            .unwrap(),
+    );
+    let validators = test_cluster.get_validator_addresses();
+
+    // Crash one validator between signing a checkpoint and reconfiguring, and bring it back
+    // without the stores of the epoch it crashed in.
+    let crash = test_cluster.crash_validator_at(
+        CrashPoint::CheckpointSignatureSubmitted,
+        validators[0],
+        RestartPolicy::default().wipe_epoch_stores(),
+    );
+    // And another one right before it reconfigures.
+    let reconfig_crash = test_cluster.crash_validator_at(
+        CrashPoint::BeforeReconfiguration,
+        validators[1],
+        RestartPolicy::default().with_delay(Duration::from_secs(3)),
+    );
+
+    test_cluster.wait_for_epoch(Some(2)).await;
+    assert!(crash.has_crashed());
+    assert!(reconfig_crash.has_crashed());
+    crash.wait_for_recovery(Duration::from_secs(60)).await;
+    reconfig_crash
+        .wait_for_recovery(Duration::from_secs(60))
+        .await;
+
+    // The network keeps making progress with the restarted validators.
+    test_cluster.wait_for_epoch(Some(3)).await;
+}
+
 #[sim_test]
 #[ignore]
 async fn test_reconfig_with_failing_validator() {
@@ -0,0 +1,277 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Time-bounded captures of spans and events in the
+//! [Chrome trace event format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+//! which can be loaded into `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+//!
+//! The `ChromeTraceLayer` is installed when the subscriber is initialized but only records while a
+//! capture started from its `ChromeTraceHandle` is running, so it costs one atomic load per span
+//! entry otherwise.
+
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::BoxError;
+
+/// Events recorded beyond this many are dropped, to bound the memory used by a capture.
+pub const MAX_CAPTURED_EVENTS: usize = 1_000_000;
+
+#[derive(Default)]
+struct Capture {
+    active: AtomicBool,
+    state: Mutex<CaptureState>,
+}
+
+struct CaptureState {
+    start: Instant,
+    events: Vec<Value>,
+    dropped: u64,
+}
+
+impl Default for CaptureState {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            events: Vec::new(),
+            dropped: 0,
+        }
+    }
+}
+
+impl Capture {
+    fn record(&self, phase: &str, name: &str, target: &str, args: Option<Map<String, Value>>) {
+        let mut state = self.state.lock().unwrap();
+        if state.events.len() >= MAX_CAPTURED_EVENTS {
+            state.dropped += 1;
+            return;
+        }
+        let mut event = json!({
+            "name": name,
+            "cat": target,
+            "ph": phase,
+            "ts": state.start.elapsed().as_micros() as u64,
+            "pid": std::process::id(),
+            "tid": thread_id(),
+        });
+        if phase == "i" {
+            // Instant events are scoped to their thread.
+            event["s"] = json!("t");
+        }
+        if let Some(args) = args {
+            event["args"] = Value::Object(args);
+        }
+        state.events.push(event);
+    }
+}
+
+/// Small sequential ids for the threads, as the trace viewers expect numeric thread ids.
+fn thread_id() -> u64 {
+    static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+    thread_local! {
+        static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+    }
+    THREAD_ID.with(|id| *id)
+}
+
+/// Fields of a span, recorded when it is created so they can be attached to its entries.
+struct SpanFields(Map<String, Value>);
+
+#[derive(Default)]
+struct FieldVisitor(Map<String, Value>);
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+}
+
+pub struct ChromeTraceLayer {
+    capture: Arc<Capture>,
+}
+
+impl ChromeTraceLayer {
+    pub fn new() -> (Self, ChromeTraceHandle) {
+        let capture = Arc::new(Capture::default());
+        let handle = ChromeTraceHandle {
+            capture: capture.clone(),
+        };
+        (Self { capture }, handle)
+    }
+}
+
+impl<S> Layer<S> for ChromeTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        // Spans created before the capture started are recorded without their fields.
+        if !self.capture.active.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            let mut visitor = FieldVisitor::default();
+            attrs.record(&mut visitor);
+            span.extensions_mut().insert(SpanFields(visitor.0));
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.capture.active.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            let args = span
+                .extensions()
+                .get::<SpanFields>()
+                .map(|fields| fields.0.clone());
+            let metadata = span.metadata();
+            self.capture
+                .record("B", metadata.name(), metadata.target(), args);
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.capture.active.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            let metadata = span.metadata();
+            self.capture
+                .record("E", metadata.name(), metadata.target(), None);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if !self.capture.active.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let name = visitor
+            .0
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or_else(|| metadata.name())
+            .to_string();
+        self.capture
+            .record("i", &name, metadata.target(), Some(visitor.0));
+    }
+}
+
+#[derive(Clone)]
+pub struct ChromeTraceHandle {
+    capture: Arc<Capture>,
+}
+
+impl fmt::Debug for ChromeTraceHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChromeTraceHandle")
+            .field("active", &self.capture.active.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl ChromeTraceHandle {
+    /// Starts a capture, which records until the returned `ChromeTraceCapture` is finished or
+    /// dropped. Only one capture can run at a time.
+    pub fn start(&self) -> Result<ChromeTraceCapture, BoxError> {
+        let mut state = self.capture.state.lock().unwrap();
+        if self.capture.active.load(Ordering::Relaxed) {
+            return Err("a chrome trace capture is already running".into());
+        }
+        *state = CaptureState::default();
+        self.capture.active.store(true, Ordering::Relaxed);
+        Ok(ChromeTraceCapture {
+            capture: self.capture.clone(),
+        })
+    }
+}
+
+#[must_use]
+pub struct ChromeTraceCapture {
+    capture: Arc<Capture>,
+}
+
+impl ChromeTraceCapture {
+    /// Stops the capture and returns the recorded trace as JSON.
+    pub fn finish(self) -> String {
+        self.capture.active.store(false, Ordering::Relaxed);
+        let state = std::mem::take(&mut *self.capture.state.lock().unwrap());
+        json!({
+            "traceEvents": state.events,
+            "displayTimeUnit": "ms",
+            "otherData": { "dropped_events": state.dropped },
+        })
+        .to_string()
+    }
+}
+
+impl Drop for ChromeTraceCapture {
+    fn drop(&mut self) {
+        self.capture.active.store(false, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::{info, info_span};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_chrome_trace_capture() {
+        let (layer, handle) = ChromeTraceLayer::new();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            info_span!("before capture").in_scope(|| info!("not captured"));
+
+            let capture = handle.start().unwrap();
+            assert!(handle.start().is_err());
+            info_span!("captured span", a = 1).in_scope(|| info!(b = 2, "captured event"));
+            let trace: Value = serde_json::from_str(&capture.finish()).unwrap();
+
+            info_span!("after capture").in_scope(|| info!("not captured"));
+
+            let events = trace["traceEvents"].as_array().unwrap();
+            assert_eq!(events.len(), 3);
+            assert_eq!(events[0]["ph"], "B");
+            assert_eq!(events[0]["name"], "captured span");
+            assert_eq!(events[0]["args"]["a"], 1);
+            assert_eq!(events[1]["ph"], "i");
+            assert_eq!(events[1]["name"], "captured event");
+            assert_eq!(events[1]["args"]["b"], 2);
+            assert_eq!(events[2]["ph"], "E");
+
+            // A new capture can start once the previous one is finished.
+            drop(handle.start().unwrap());
+        });
+    }
+}
@@ -34,6 +34,7 @@
 //! ## Features
 //! - `json` - Bunyan formatter - JSON log output, optional
 //! - `tokio-console` - [Tokio-console](https://github.com/tokio-rs/console) subscriber, optional
+//! - `otlp` - export spans to an [OpenTelemetry](https://opentelemetry.io) collector, optional
 //!
 //! ### Stdout vs file output
 //!
@@ -42,6 +43,8 @@
 //!
 //! By setting `log_file` in the config, one can write log output to a daily-rotated file.
 //!
+//! The `log_sampling` rules keep only a fraction of the log events of noisy targets, e.g. one in ten.
+//!
 //! ### Tracing and span output
 //!
 //! Detailed span start and end logs can be generated by defining the `json_log_output` config variable.  Note that this causes all output to be in JSON format, which is not as human-readable, so it is not enabled by default.
@@ -64,13 +67,28 @@
 //! separate from `RUST_LOG`, so that you can separately control the logging verbosity from the level of
 //! spans that are to be recorded and traced.
 //!
+//! ### Distributed tracing with OpenTelemetry
+//!
+//! With the `otlp` feature enabled, spans (and the events recorded inside them) up to `span_level` are exported
+//! over OTLP/gRPC to the collector at `otlp_endpoint`, or `OTEL_EXPORTER_OTLP_ENDPOINT` when using `with_env()`,
+//! e.g. `http://localhost:4317`.  The service name reported to the collector is set with `service_name`, or
+//! `OTEL_SERVICE_NAME`.  Spans are exported in batches, so the exporter must be initialized from within a Tokio runtime.
+//!
+//! ### Runtime control
+//!
+//! `init()` returns a `TracingHandle`, which changes the log filter (`update_log`) and the filter of the spans and
+//! events exported over OTLP (`update_trace`) without a restart, e.g. to `info,sui_core=debug`.  It can also start a
+//! time-bounded capture of the spans and events passing the trace filter in the Chrome trace event format
+//! (`start_chrome_trace`), to load into `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+//!
 //! ### Live async inspection / Tokio Console
 //!
 //! [Tokio-console](https://github.com/tokio-rs/console) is an awesome CLI tool designed to analyze and help debug Rust apps using Tokio, in real time!  It relies on a special subscriber.
 //!
 //! 1. Build your app using a special flag: `RUSTFLAGS="--cfg tokio_unstable" cargo build`
 //! 2. Enable the `tokio-console` feature for this crate.
-//! 2. Set the `tokio_console` config setting when running your app (or set TOKIO_CONSOLE env var if using config `with_env()` method)
+//! 2. Set the `tokio_console` config setting when running your app (or set TOKIO_CONSOLE env var if using config `with_env()` method),
+//!    or enable it at runtime with `TracingHandle::set_tokio_console`
 //! 3. Clone the console repo and `cargo run` to launch the console
 //!
 //! NOTE: setting tokio TRACE logs is NOT necessary.  It says that in the docs but there's no need to change Tokio logging levels at all.  The console subscriber has a special filter enabled taking care of that.
@@ -85,6 +103,9 @@
 //!
 //! To exit the process on panic, set the `CRASH_ON_PANIC` environment variable.
 
+use chrome_trace::{ChromeTraceCapture, ChromeTraceHandle, ChromeTraceLayer};
+pub use sampling::LogSamplingRule;
+use sampling::{SamplingFilter, SamplingHandle};
 use span_latency_prom::PrometheusSpanLatencyLayer;
 use std::{
     env,
@@ -95,11 +116,18 @@ use tracing::metadata::LevelFilter;
 use tracing::Level;
 use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
 use tracing_subscriber::{
-    filter, fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+    filter::{self, FilterExt},
+    fmt,
+    layer::SubscriberExt,
+    reload,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
 };
 
 use crossterm::tty::IsTty;
 
+pub mod chrome_trace;
+pub mod sampling;
 pub mod span_latency_prom;
 
 /// Alias for a type-erased error type.
@@ -120,6 +148,8 @@ pub struct TelemetryConfig {
     pub log_file: Option<String>,
     /// Log level to set, defaults to info
     pub log_string: Option<String>,
+    /// Sampling of the log events of noisy targets
+    pub log_sampling: Vec<LogSamplingRule>,
     /// Span level - what level of spans should be created.  Note this is not same as logging level
     /// If set to None, then defaults to INFO
     pub span_level: Option<Level>,
@@ -129,17 +159,137 @@ pub struct TelemetryConfig {
     pub crash_on_panic: bool,
     /// Optional Prometheus registry - if present, all enabled span latencies are measured
     pub prom_registry: Option<prometheus::Registry>,
+    /// If defined, export spans over OTLP to the collector at this endpoint (requires the `otlp` feature)
+    pub otlp_endpoint: Option<String>,
+    /// Name of the service reported with the exported spans
+    pub service_name: Option<String>,
 }
 
 #[must_use]
 #[allow(dead_code)]
 pub struct TelemetryGuards {
     worker_guard: WorkerGuard,
+    #[cfg(feature = "otlp")]
+    otlp_enabled: bool,
+}
+
+#[cfg(feature = "otlp")]
+impl Drop for TelemetryGuards {
+    fn drop(&mut self) {
+        if self.otlp_enabled {
+            // Flush the spans that are still batched.
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct FilterHandle(reload::Handle<EnvFilter, Registry>);
 
+/// Runtime control over what the subscriber records, returned by `TelemetryConfig::init`.
+#[derive(Clone, Debug)]
+pub struct TracingHandle {
+    log: FilterHandle,
+    sampling: SamplingHandle,
+    trace: FilterHandle,
+    chrome_trace: ChromeTraceHandle,
+    #[cfg(feature = "tokio-console")]
+    console: ConsoleHandle,
+}
+
+impl TracingHandle {
+    /// Replaces the filter of the log output, e.g. `info,sui_core=debug`.
+    pub fn update_log<S: AsRef<str>>(&self, directives: S) -> Result<(), BoxError> {
+        self.log.update(directives)
+    }
+
+    pub fn get_log(&self) -> Result<String, BoxError> {
+        self.log.get()
+    }
+
+    /// Replaces the sampling rules of the log output, see [`sampling`].
+    pub fn update_log_sampling(&self, rules: Vec<LogSamplingRule>) -> Result<(), BoxError> {
+        self.sampling.update(rules)
+    }
+
+    pub fn get_log_sampling(&self) -> Vec<LogSamplingRule> {
+        self.sampling.get()
+    }
+
+    /// Replaces the filter of the spans and events exported over OTLP and recorded by chrome
+    /// trace captures. It is separate from the log filter, so that more detailed traces can be
+    /// recorded without flooding the logs.
+    pub fn update_trace<S: AsRef<str>>(&self, directives: S) -> Result<(), BoxError> {
+        self.trace.update(directives)
+    }
+
+    pub fn get_trace(&self) -> Result<String, BoxError> {
+        self.trace.get()
+    }
+
+    /// Starts a chrome trace capture, see [`chrome_trace`].
+    pub fn start_chrome_trace(&self) -> Result<ChromeTraceCapture, BoxError> {
+        self.chrome_trace.start()
+    }
+
+    /// Starts or stops recording the data of the tokio-console subscriber. The console server is
+    /// started the first time recording is enabled.
+    #[cfg(feature = "tokio-console")]
+    pub fn set_tokio_console(&self, enabled: bool) -> Result<(), BoxError> {
+        self.console.set_enabled(enabled)
+    }
+
+    #[cfg(not(feature = "tokio-console"))]
+    pub fn set_tokio_console(&self, _enabled: bool) -> Result<(), BoxError> {
+        Err("built without the tokio-console feature".into())
+    }
+}
+
+#[cfg(feature = "tokio-console")]
+const CONSOLE_FILTER: &str = "tokio=trace,runtime=trace";
+
+#[cfg(feature = "tokio-console")]
+#[derive(Clone)]
+struct ConsoleHandle {
+    filter: FilterHandle,
+    server: std::sync::Arc<std::sync::Mutex<Option<console_subscriber::Server>>>,
+}
+
+#[cfg(feature = "tokio-console")]
+impl std::fmt::Debug for ConsoleHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsoleHandle")
+            .field("filter", &self.filter)
+            .finish()
+    }
+}
+
+#[cfg(feature = "tokio-console")]
+impl ConsoleHandle {
+    fn set_enabled(&self, enabled: bool) -> Result<(), BoxError> {
+        if !enabled {
+            return self.filter.update("off");
+        }
+        if let Some(server) = self.server.lock().unwrap().take() {
+            // Same as `console_subscriber::spawn`, the server runs on its own runtime so that it
+            // does not show up in the console.
+            std::thread::Builder::new()
+                .name("console_subscriber".into())
+                .spawn(move || {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_io()
+                        .enable_time()
+                        .build()
+                        .expect("console subscriber runtime initialization failed");
+                    runtime
+                        .block_on(server.serve())
+                        .expect("console subscriber server failed")
+                })?;
+        }
+        self.filter.update(CONSOLE_FILTER)
+    }
+}
+
 impl FilterHandle {
     pub fn update<S: AsRef<str>>(&self, directives: S) -> Result<(), BoxError> {
         let filter = EnvFilter::try_new(directives)?;
@@ -210,10 +360,13 @@ impl TelemetryConfig {
             json_log_output: false,
             log_file: None,
             log_string: None,
+            log_sampling: vec![],
             span_level: None,
             panic_hook: true,
             crash_on_panic: false,
             prom_registry: None,
+            otlp_endpoint: None,
+            service_name: None,
         }
     }
 
@@ -227,6 +380,11 @@ impl TelemetryConfig {
         self
     }
 
+    pub fn with_log_sampling(mut self, rules: Vec<LogSamplingRule>) -> Self {
+        self.log_sampling = rules;
+        self
+    }
+
     pub fn with_span_level(mut self, span_level: Level) -> Self {
         self.span_level = Some(span_level);
         self
@@ -242,6 +400,16 @@ impl TelemetryConfig {
         self
     }
 
+    pub fn with_otlp_endpoint(mut self, endpoint: &str) -> Self {
+        self.otlp_endpoint = Some(endpoint.to_owned());
+        self
+    }
+
+    pub fn with_service_name(mut self, service_name: &str) -> Self {
+        self.service_name = Some(service_name.to_owned());
+        self
+    }
+
     pub fn with_env(mut self) -> Self {
         if env::var("CRASH_ON_PANIC").is_ok() {
             self.crash_on_panic = true
@@ -264,10 +432,18 @@ impl TelemetryConfig {
             self.log_file = Some(filepath);
         }
 
+        if let Ok(endpoint) = env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            self.otlp_endpoint = Some(endpoint);
+        }
+
+        if let Ok(service_name) = env::var("OTEL_SERVICE_NAME") {
+            self.service_name = Some(service_name);
+        }
+
         self
     }
 
-    pub fn init(self) -> (TelemetryGuards, FilterHandle) {
+    pub fn init(self) -> (TelemetryGuards, TracingHandle) {
         let config = self;
 
         // Setup an EnvFilter for filtering logging output layers.
@@ -278,7 +454,9 @@ impl TelemetryConfig {
         let env_filter =
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(log_level));
         let (log_filter, reload_handle) = reload::Layer::new(env_filter);
-        let filter_handle = FilterHandle(reload_handle);
+        let log_filter_handle = FilterHandle(reload_handle);
+        let (sampling_filter, sampling_handle) =
+            SamplingFilter::new(config.log_sampling).expect("Invalid log sampling rules");
 
         // Separate span level filter.
         // This is a dumb filter for now - allows all spans that are below a given level.
@@ -288,15 +466,35 @@ impl TelemetryConfig {
             metadata.is_span() && *metadata.level() <= span_level
         });
 
+        // Filter of the layers tracing to external tools, which can be changed at runtime.
+        let (trace_filter, reload_handle) =
+            reload::Layer::new(EnvFilter::new(span_level.to_string()));
+        let trace_filter_handle = FilterHandle(reload_handle);
+
         let mut layers = Vec::new();
 
-        // tokio-console layer
+        // tokio-console layer. It is always installed, so that it can be enabled at runtime, but
+        // its server is only started once it is.
         // Please see https://docs.rs/console-subscriber/latest/console_subscriber/struct.Builder.html#configuration
         // for environment vars/config options
         #[cfg(feature = "tokio-console")]
-        if config.tokio_console {
-            layers.push(console_subscriber::spawn().boxed());
-        }
+        let console_handle = {
+            let (console_layer, server) = console_subscriber::ConsoleLayer::builder()
+                .with_default_env()
+                .build();
+            let (console_filter, reload_handle) = reload::Layer::new(EnvFilter::new("off"));
+            layers.push(console_layer.with_filter(console_filter).boxed());
+            let console_handle = ConsoleHandle {
+                filter: FilterHandle(reload_handle),
+                server: std::sync::Arc::new(std::sync::Mutex::new(Some(server))),
+            };
+            if config.tokio_console {
+                console_handle
+                    .set_enabled(true)
+                    .expect("Could not start the tokio-console server");
+            }
+            console_handle
+        };
 
         if let Some(registry) = config.prom_registry {
             let span_lat_layer = PrometheusSpanLatencyLayer::try_new(&registry, 15)
@@ -304,6 +502,42 @@ impl TelemetryConfig {
             layers.push(span_lat_layer.with_filter(span_filter.clone()).boxed());
         }
 
+        // Layers under the trace filter. Unlike the span latency layer, they also take the events
+        // recorded within the spans.
+        let mut trace_layers = Vec::new();
+        let (chrome_trace_layer, chrome_trace_handle) = ChromeTraceLayer::new();
+        trace_layers.push(chrome_trace_layer.boxed());
+
+        // OpenTelemetry layer. The events recorded within the spans are exported as span events.
+        #[cfg(feature = "otlp")]
+        let otlp_enabled = config.otlp_endpoint.is_some();
+        #[cfg(feature = "otlp")]
+        if let Some(endpoint) = &config.otlp_endpoint {
+            use opentelemetry::sdk::{trace, Resource};
+            use opentelemetry_otlp::WithExportConfig;
+
+            let mut trace_config = trace::config();
+            if let Some(service_name) = &config.service_name {
+                trace_config =
+                    trace_config.with_resource(Resource::new(vec![opentelemetry::KeyValue::new(
+                        "service.name",
+                        service_name.clone(),
+                    )]));
+            }
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_trace_config(trace_config)
+                .install_batch(opentelemetry::runtime::Tokio)
+                .expect("Could not initialize the OTLP exporter");
+            trace_layers.push(tracing_opentelemetry::layer().with_tracer(tracer).boxed());
+        }
+        layers.push(trace_layers.with_filter(trace_filter).boxed());
+
         let (nb_output, worker_guard) = get_output(config.log_file.clone());
         if config.json_log_output {
             // Output to file or to stderr in a newline-delimited JSON format
@@ -312,7 +546,7 @@ impl TelemetryConfig {
                 .with_line_number(true)
                 .json()
                 .with_writer(nb_output)
-                .with_filter(log_filter)
+                .with_filter(log_filter.and(sampling_filter))
                 .boxed();
             layers.push(json_layer);
         } else {
@@ -320,7 +554,7 @@ impl TelemetryConfig {
             let fmt_layer = fmt::layer()
                 .with_ansi(config.log_file.is_none() && stderr().is_tty())
                 .with_writer(nb_output)
-                .with_filter(log_filter)
+                .with_filter(log_filter.and(sampling_filter))
                 .boxed();
             layers.push(fmt_layer);
         }
@@ -333,9 +567,22 @@ impl TelemetryConfig {
 
         // The guard must be returned and kept in the main fn of the app, as when it's dropped then the output
         // gets flushed and closed. If this is dropped too early then no output will appear!
-        let guards = TelemetryGuards { worker_guard };
-
-        (guards, filter_handle)
+        let guards = TelemetryGuards {
+            worker_guard,
+            #[cfg(feature = "otlp")]
+            otlp_enabled,
+        };
+
+        let tracing_handle = TracingHandle {
+            log: log_filter_handle,
+            sampling: sampling_handle,
+            trace: trace_filter_handle,
+            chrome_trace: chrome_trace_handle,
+            #[cfg(feature = "tokio-console")]
+            console: console_handle,
+        };
+
+        (guards, tracing_handle)
     }
 }
 
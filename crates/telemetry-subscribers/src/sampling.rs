@@ -0,0 +1,168 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sampling of the log events of noisy targets.
+//!
+//! A rule keeps a fraction of the events of the targets starting with its prefix, e.g. one event
+//! in ten with a rate of `0.1`, after the log filter has enabled them. Sampling is deterministic:
+//! the events kept are evenly spread over the events of the rule. Events matching no rule are all
+//! kept, and the first matching rule applies when several do. Spans are never sampled.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tracing::subscriber::Interest;
+use tracing::Metadata;
+use tracing_subscriber::layer::{Context, Filter};
+
+use crate::BoxError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogSamplingRule {
+    /// Prefix of the targets the rule applies to, e.g. `sui_core::authority_server`
+    pub target: String,
+    /// Fraction of the events to keep, between 0 and 1
+    pub rate: f64,
+}
+
+struct Sampler {
+    rule: LogSamplingRule,
+    count: AtomicU64,
+}
+
+impl Sampler {
+    fn sample(&self) -> bool {
+        let count = self.count.fetch_add(1, Ordering::Relaxed);
+        // Keep the event when the number of events to keep so far goes up.
+        ((count + 1) as f64 * self.rule.rate).floor() > (count as f64 * self.rule.rate).floor()
+    }
+}
+
+impl std::fmt::Debug for Sampler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.rule.fmt(f)
+    }
+}
+
+type Samplers = Arc<RwLock<Vec<Sampler>>>;
+
+fn samplers(rules: Vec<LogSamplingRule>) -> Result<Vec<Sampler>, BoxError> {
+    rules
+        .into_iter()
+        .map(|rule| {
+            if !(0.0..=1.0).contains(&rule.rate) {
+                return Err(format!(
+                    "sampling rate of {} must be between 0 and 1, got {}",
+                    rule.target, rule.rate
+                )
+                .into());
+            }
+            Ok(Sampler {
+                rule,
+                count: AtomicU64::new(0),
+            })
+        })
+        .collect()
+}
+
+pub struct SamplingFilter {
+    samplers: Samplers,
+}
+
+impl SamplingFilter {
+    pub fn new(rules: Vec<LogSamplingRule>) -> Result<(Self, SamplingHandle), BoxError> {
+        let samplers = Arc::new(RwLock::new(samplers(rules)?));
+        let handle = SamplingHandle {
+            samplers: samplers.clone(),
+        };
+        Ok((Self { samplers }, handle))
+    }
+
+    fn matches(&self, metadata: &Metadata<'_>) -> bool {
+        self.samplers
+            .read()
+            .unwrap()
+            .iter()
+            .any(|sampler| metadata.target().starts_with(&sampler.rule.target))
+    }
+}
+
+impl<S> Filter<S> for SamplingFilter {
+    fn enabled(&self, metadata: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        if !metadata.is_event() {
+            return true;
+        }
+        self.samplers
+            .read()
+            .unwrap()
+            .iter()
+            .find(|sampler| metadata.target().starts_with(&sampler.rule.target))
+            .map_or(true, Sampler::sample)
+    }
+
+    fn callsite_enabled(&self, metadata: &'static Metadata<'static>) -> Interest {
+        // Only the callsites of sampled targets need to be evaluated for every event.
+        if metadata.is_event() && self.matches(metadata) {
+            Interest::sometimes()
+        } else {
+            Interest::always()
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SamplingHandle {
+    samplers: Samplers,
+}
+
+impl SamplingHandle {
+    pub fn update(&self, rules: Vec<LogSamplingRule>) -> Result<(), BoxError> {
+        *self.samplers.write().unwrap() = samplers(rules)?;
+        // The interest of the callsites depends on the rules.
+        tracing::callsite::rebuild_interest_cache();
+        Ok(())
+    }
+
+    pub fn get(&self) -> Vec<LogSamplingRule> {
+        self.samplers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|sampler| sampler.rule.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_rate() {
+        let sampler = |rate| Sampler {
+            rule: LogSamplingRule {
+                target: "noisy".to_string(),
+                rate,
+            },
+            count: AtomicU64::new(0),
+        };
+
+        let tenth = sampler(0.1);
+        let kept: Vec<_> = (0..30).filter(|_| tenth.sample()).collect();
+        assert_eq!(kept.len(), 3);
+
+        let none = sampler(0.0);
+        assert!((0..100).all(|_| !none.sample()));
+
+        let all = sampler(1.0);
+        assert!((0..100).all(|_| all.sample()));
+    }
+
+    #[test]
+    fn test_invalid_sampling_rate() {
+        let rule = LogSamplingRule {
+            target: "noisy".to_string(),
+            rate: 1.5,
+        };
+        assert!(SamplingFilter::new(vec![rule]).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Crash validators of a [`TestCluster`] at precise points of their execution and restart them,
+//! optionally without their per-epoch stores, to exercise crash recovery in simulation tests.
+//!
+//! ```ignore
+//! let crash = test_cluster.crash_validator_at(
+//!     CrashPoint::CheckpointSignatureSubmitted,
+//!     validator,
+//!     RestartPolicy::default().wipe_epoch_stores(),
+//! );
+//! crash.wait_for_recovery(Duration::from_secs(60)).await;
+//! ```
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use sui_core::authority::authority_per_epoch_store::EPOCH_DB_PREFIX;
+use sui_macros::{register_fail_point, register_fail_point_async};
+use sui_types::base_types::AuthorityName;
+use tokio::sync::{mpsc, watch};
+use tokio::time::{sleep, timeout};
+use tracing::info;
+
+use crate::network::TestCluster;
+
+/// Where to crash a validator. Each crash point can only be used by one crash at a time.
+#[derive(Clone, Copy, Debug)]
+pub enum CrashPoint {
+    /// Right after the validator sent its signature of a checkpoint to consensus.
+    CheckpointSignatureSubmitted,
+    /// After the validator executed the last checkpoint of the epoch, before it reconfigures.
+    BeforeReconfiguration,
+    /// During reconfiguration, before the store of the new epoch is opened.
+    BeforeOpenNewEpochStore,
+    /// Any other synchronous fail point.
+    FailPoint(&'static str),
+    /// Any other asynchronous fail point.
+    AsyncFailPoint(&'static str),
+}
+
+impl CrashPoint {
+    fn fail_point(&self) -> (&'static str, bool) {
+        match self {
+            Self::CheckpointSignatureSubmitted => ("checkpoint-signature-submitted", true),
+            Self::BeforeReconfiguration => ("reconfig_delay", true),
+            Self::BeforeOpenNewEpochStore => ("before-open-new-epoch-store", false),
+            Self::FailPoint(name) => (*name, false),
+            Self::AsyncFailPoint(name) => (*name, true),
+        }
+    }
+}
+
+/// How a crashed validator is brought back.
+#[derive(Clone, Copy, Debug)]
+pub struct RestartPolicy {
+    /// How long the validator stays down.
+    pub delay: Duration,
+    /// Delete the per-epoch stores of the validator before restarting it, so it has to recover
+    /// the state of the current epoch from its peers.
+    pub wipe_epoch_stores: bool,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_secs(1),
+            wipe_epoch_stores: false,
+        }
+    }
+}
+
+impl RestartPolicy {
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn wipe_epoch_stores(mut self) -> Self {
+        self.wipe_epoch_stores = true;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CrashState {
+    Armed,
+    Crashed,
+    Restarted,
+}
+
+/// A crash scheduled with [`TestCluster::crash_validator_at`].
+pub struct ValidatorCrash {
+    test_cluster: Arc<TestCluster>,
+    validator: AuthorityName,
+    state: watch::Receiver<CrashState>,
+}
+
+impl ValidatorCrash {
+    /// Whether the validator reached the crash point.
+    pub fn has_crashed(&self) -> bool {
+        *self.state.borrow() != CrashState::Armed
+    }
+
+    /// Wait until the validator crashed, was restarted, and caught up with the fullnode on the
+    /// epoch and executed checkpoints it was at when the validator came back.
+    pub async fn wait_for_recovery(&self, limit: Duration) {
+        timeout(limit, async {
+            let mut state = self.state.clone();
+            while *state.borrow() != CrashState::Restarted {
+                state.changed().await.expect("Crash handler stopped");
+            }
+
+            let fullnode_state = self.test_cluster.fullnode_handle.sui_node.state();
+            let epoch = fullnode_state.epoch_store_for_testing().epoch();
+            let checkpoint = fullnode_state
+                .get_latest_checkpoint_sequence_number()
+                .unwrap_or(0);
+            info!(
+                validator =? self.validator.concise(),
+                "Validator restarted, waiting for it to reach epoch {epoch} checkpoint {checkpoint}"
+            );
+            loop {
+                let caught_up = self
+                    .test_cluster
+                    .swarm
+                    .validator(self.validator)
+                    .and_then(|v| v.get_node_handle())
+                    .map_or(false, |handle| {
+                        handle.with(|node| {
+                            let state = node.state();
+                            state.epoch_store_for_testing().epoch() >= epoch
+                                && state
+                                    .get_latest_checkpoint_sequence_number()
+                                    .map_or(false, |seq| seq >= checkpoint)
+                        })
+                    });
+                if caught_up {
+                    break;
+                }
+                sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for the crashed validator to recover")
+    }
+}
+
+impl TestCluster {
+    /// Crash `validator` the first time it reaches `crash_point`, then restart it according to
+    /// `policy`. Other validators reaching the crash point are not affected.
+    pub fn crash_validator_at(
+        self: &Arc<Self>,
+        crash_point: CrashPoint,
+        validator: AuthorityName,
+        policy: RestartPolicy,
+    ) -> ValidatorCrash {
+        let node_id = self
+            .swarm
+            .validator(validator)
+            .and_then(|v| v.sim_node_id())
+            .expect("Validator must be running to schedule a crash");
+        let (crashed_tx, mut crashed_rx) = mpsc::unbounded_channel();
+        let armed = Arc::new(AtomicBool::new(true));
+        let crash = move || {
+            if sui_simulator::current_simnode_id() != node_id
+                || !armed.swap(false, Ordering::SeqCst)
+            {
+                return;
+            }
+            info!(validator =? validator.concise(), ?crash_point, "Crashing validator");
+            crashed_tx.send(()).ok();
+            sui_simulator::task::kill_current_node(None);
+        };
+        match crash_point.fail_point() {
+            (name, false) => register_fail_point(name, crash),
+            (name, true) => {
+                let crash = Arc::new(crash);
+                register_fail_point_async(name, move || {
+                    let crash = crash.clone();
+                    async move { crash() }
+                })
+            }
+        }
+
+        let (state_tx, state) = watch::channel(CrashState::Armed);
+        let test_cluster = self.clone();
+        tokio::spawn(async move {
+            if crashed_rx.recv().await.is_none() {
+                return;
+            }
+            state_tx.send_replace(CrashState::Crashed);
+            test_cluster.stop_validator(validator);
+            sleep(policy.delay).await;
+            if policy.wipe_epoch_stores {
+                let config = &test_cluster.swarm.validator(validator).unwrap().config;
+                wipe_dirs(&config.db_path().join("store"), |name| {
+                    name.starts_with(EPOCH_DB_PREFIX)
+                });
+                if let Some(consensus_config) = config.consensus_config() {
+                    // consensus keeps one store per epoch, named after the epoch
+                    wipe_dirs(consensus_config.db_path(), |name| {
+                        name.parse::<u64>().is_ok()
+                    });
+                }
+            }
+            info!(validator =? validator.concise(), "Restarting crashed validator");
+            test_cluster.start_validator(validator).await;
+            state_tx.send_replace(CrashState::Restarted);
+        });
+
+        ValidatorCrash {
+            test_cluster: self.clone(),
+            validator,
+            state,
+        }
+    }
+}
+
+/// Delete the directories under `parent` whose name matches `is_epoch_store`.
+fn wipe_dirs(parent: &Path, is_epoch_store: impl Fn(&str) -> bool) {
+    let Ok(entries) = std::fs::read_dir(parent) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_str().map_or(false, &is_epoch_store) {
+            info!("Wiping per-epoch store {:?}", entry.path());
+            std::fs::remove_dir_all(entry.path()).expect("Failed to wipe per-epoch store");
+        }
+    }
+}
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod authority;
+#[cfg(msim)]
+pub mod fault_injection;
 pub mod messages;
 pub mod network;
 pub mod transaction;
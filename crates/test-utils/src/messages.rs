@@ -374,6 +374,40 @@ pub fn make_counter_increment_transaction(
     to_sender_signed_transaction(data, keypair)
 }
 
+/// Reads a shared counter by immutable reference through `counter::assert_value`, which aborts
+/// unless the counter holds `expected_value`.
+pub fn make_counter_read_transaction(
+    gas_object: ObjectRef,
+    package_id: ObjectID,
+    counter_id: ObjectID,
+    counter_initial_shared_version: SequenceNumber,
+    expected_value: u64,
+    sender: SuiAddress,
+    keypair: &AccountKeyPair,
+    gas_price: Option<u64>,
+) -> VerifiedTransaction {
+    let data = TransactionData::new_move_call(
+        sender,
+        package_id,
+        "counter".parse().unwrap(),
+        "assert_value".parse().unwrap(),
+        Vec::new(),
+        gas_object,
+        vec![
+            CallArg::Object(ObjectArg::SharedObject {
+                id: counter_id,
+                initial_shared_version: counter_initial_shared_version,
+                mutable: false,
+            }),
+            CallArg::Pure(bcs::to_bytes(&expected_value).unwrap()),
+        ],
+        MAX_GAS,
+        gas_price.unwrap_or(1),
+    )
+    .unwrap();
+    to_sender_signed_transaction(data, keypair)
+}
+
 pub fn make_staking_transaction(
     gas_object: ObjectRef,
     coin: ObjectRef,
@@ -27,7 +27,9 @@ use sui_config::{FullnodeConfigBuilder, NodeConfig, PersistedConfig, SUI_KEYSTOR
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_node::SuiNode;
 use sui_node::SuiNodeHandle;
-use sui_protocol_config::{ProtocolVersion, SupportedProtocolVersions};
+use sui_protocol_config::{
+    OverrideGuard, ProtocolConfig, ProtocolVersion, SupportedProtocolVersions,
+};
 use sui_sdk::{SuiClient, SuiClientBuilder};
 use sui_swarm::memory::{Swarm, SwarmBuilder};
 use sui_types::base_types::{AuthorityName, SuiAddress};
@@ -55,6 +57,8 @@ pub struct TestCluster {
     pub accounts: Vec<SuiAddress>,
     pub wallet: WalletContext,
     pub fullnode_handle: FullNodeHandle,
+    /// Keeps the protocol config overrides of the builder in effect for the life of the cluster.
+    protocol_config_override: Option<OverrideGuard>,
 }
 
 impl TestCluster {
@@ -231,6 +235,8 @@ impl RandomNodeRestarter {
     }
 }
 
+type ProtocolConfigOverride = Box<dyn Fn(ProtocolVersion, &mut ProtocolConfig) + Send>;
+
 pub struct TestClusterBuilder {
     genesis_config: Option<GenesisConfig>,
     additional_objects: Vec<Object>,
@@ -241,6 +247,7 @@ pub struct TestClusterBuilder {
     supported_protocol_versions_config: ProtocolVersionsConfig,
     db_checkpoint_config_validators: DBCheckpointConfig,
     db_checkpoint_config_fullnodes: DBCheckpointConfig,
+    protocol_config_overrides: Vec<ProtocolConfigOverride>,
 }
 
 impl TestClusterBuilder {
@@ -255,6 +262,7 @@ impl TestClusterBuilder {
             supported_protocol_versions_config: ProtocolVersionsConfig::Default,
             db_checkpoint_config_validators: DBCheckpointConfig::default(),
             db_checkpoint_config_fullnodes: DBCheckpointConfig::default(),
+            protocol_config_overrides: vec![],
         }
     }
 
@@ -288,6 +296,7 @@ impl TestClusterBuilder {
             perform_db_checkpoints_at_epoch_end: true,
             checkpoint_path: None,
             object_store_config: None,
+            restore_object_store_config: None,
         };
         self
     }
@@ -297,6 +306,7 @@ impl TestClusterBuilder {
             perform_db_checkpoints_at_epoch_end: true,
             checkpoint_path: None,
             object_store_config: None,
+            restore_object_store_config: None,
         };
         self
     }
@@ -328,8 +338,55 @@ impl TestClusterBuilder {
         self
     }
 
-    pub async fn build(self) -> anyhow::Result<TestCluster> {
-        let cluster = self.start_test_network_with_customized_ports().await?;
+    /// Run a committee in which validator `i` supports `versions[i]`, e.g. to test upgrades with
+    /// some validators lagging behind. Fullnodes support the union of all the ranges.
+    pub fn with_validator_protocol_versions(
+        self,
+        versions: Vec<SupportedProtocolVersions>,
+    ) -> Self {
+        assert!(!versions.is_empty(), "at least one validator is required");
+        let fullnode = SupportedProtocolVersions::new_for_testing(
+            versions.iter().map(|v| v.min.as_u64()).min().unwrap(),
+            versions.iter().map(|v| v.max.as_u64()).max().unwrap(),
+        );
+        self.with_num_validators(versions.len())
+            .with_supported_protocol_version_callback(Arc::new(move |idx, name| match name {
+                Some(_) => versions[idx],
+                None => fullnode,
+            }))
+    }
+
+    /// Modify the protocol config of every protocol version for the nodes of this cluster.
+    /// Overrides are applied in the order they were added, and stay in effect until the
+    /// `TestCluster` is dropped. As with `ProtocolConfig::apply_overrides_for_testing`, the
+    /// overrides are thread local and the test must not install overrides of its own.
+    pub fn with_protocol_config_override(
+        mut self,
+        f: impl Fn(ProtocolVersion, &mut ProtocolConfig) + Send + 'static,
+    ) -> Self {
+        self.protocol_config_overrides.push(Box::new(f));
+        self
+    }
+
+    /// Turn the feature flag `name` (as named in the `FeatureFlags` of `sui-protocol-config`)
+    /// on or off at every protocol version.
+    pub fn with_feature_flag(self, name: &'static str, enabled: bool) -> Self {
+        self.with_protocol_config_override(move |_, config| {
+            config.set_feature_flag_for_testing(name, enabled)
+        })
+    }
+
+    pub async fn build(mut self) -> anyhow::Result<TestCluster> {
+        // Must be installed before the nodes start, as they cache their protocol config.
+        let protocol_config_override = (!self.protocol_config_overrides.is_empty()).then(|| {
+            let overrides = std::mem::take(&mut self.protocol_config_overrides);
+            ProtocolConfig::apply_overrides_for_testing(move |version, mut config| {
+                overrides.iter().for_each(|f| f(version, &mut config));
+                config
+            })
+        });
+        let mut cluster = self.start_test_network_with_customized_ports().await?;
+        cluster.protocol_config_override = protocol_config_override;
         Ok(cluster)
     }
 
@@ -377,6 +434,7 @@ impl TestClusterBuilder {
             accounts,
             wallet,
             fullnode_handle,
+            protocol_config_override: None,
         })
     }
 
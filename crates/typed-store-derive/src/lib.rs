@@ -351,11 +351,11 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
         }
 
         impl #config_struct_name {
-            /// Initialize to defaults
+            /// Initialize to the defaults of the tables
             pub fn init() -> Self {
                 Self {
                     #(
-                        #field_names : typed_store::rocks::default_db_options(),
+                        #field_names : #default_options_override_fn_names(),
                     )*
                 }
             }
@@ -86,6 +86,12 @@ pub struct ColumnFamilyMetrics {
     pub rocksdb_estimate_oldest_key_time: IntGaugeVec,
     pub rocskdb_background_errors: IntGaugeVec,
     pub rocksdb_estimated_num_keys: IntGaugeVec,
+    pub rocksdb_estimate_pending_compaction_bytes: IntGaugeVec,
+    pub rocksdb_cur_size_active_mem_table: IntGaugeVec,
+    pub rocksdb_num_immutable_mem_table: IntGaugeVec,
+    pub rocksdb_level_num_files: IntGaugeVec,
+    pub rocksdb_level_size_bytes: IntGaugeVec,
+    pub rocksdb_write_stall_count: IntGaugeVec,
 }
 
 impl ColumnFamilyMetrics {
@@ -220,6 +226,48 @@ impl ColumnFamilyMetrics {
                 registry,
             )
             .unwrap(),
+            rocksdb_estimate_pending_compaction_bytes: register_int_gauge_vec_with_registry!(
+                "rocksdb_estimate_pending_compaction_bytes",
+                "Estimated bytes compaction must rewrite to bring all levels under target size.",
+                &["cf_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_cur_size_active_mem_table: register_int_gauge_vec_with_registry!(
+                "rocksdb_cur_size_active_mem_table",
+                "The approximate size of the active memtable of the column family, in bytes.",
+                &["cf_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_num_immutable_mem_table: register_int_gauge_vec_with_registry!(
+                "rocksdb_num_immutable_mem_table",
+                "The number of unflushed immutable memtables of the column family.",
+                &["cf_name"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_level_num_files: register_int_gauge_vec_with_registry!(
+                "rocksdb_level_num_files",
+                "The number of SST files at each level of the column family.",
+                &["cf_name", "level"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_level_size_bytes: register_int_gauge_vec_with_registry!(
+                "rocksdb_level_size_bytes",
+                "The size of the SST files at each level, in bytes with a MiB granularity.",
+                &["cf_name", "level"],
+                registry,
+            )
+            .unwrap(),
+            rocksdb_write_stall_count: register_int_gauge_vec_with_registry!(
+                "rocksdb_write_stall_count",
+                "Write stalls of the column family since the DB was opened, by stall reason.",
+                &["cf_name", "reason"],
+                registry,
+            )
+            .unwrap(),
 
         }
     }
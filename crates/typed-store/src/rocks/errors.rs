@@ -23,6 +23,8 @@ pub enum TypedStoreError {
     MetricsReporting,
     #[error("Transaction should be retried")]
     RetryableTransactionError,
+    #[error("schema version error: {0}")]
+    SchemaVersionError(String),
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Debug, Error)]
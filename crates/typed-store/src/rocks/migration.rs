@@ -0,0 +1,421 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioned store schema migrations.
+//!
+//! A store records its schema version in its `default` column family under
+//! `__store_schema_version`. Changes to the set of tables of a store, to the format of their keys,
+//! or to the format of their values are made by [`SchemaMigrations`]: the ordered
+//! [`MigrationStep`]s adding, dropping or rewriting tables up to the latest version are run when
+//! the store is opened, before any reader or writer can observe it. A store is checkpointed before
+//! its pending steps are run, and restored from the checkpoint if one fails, so a migration
+//! interrupted by a crash is run again from the start at the next open.
+//!
+//! When the on-disk format of a table's values changes between releases, the value type is
+//! expected to be a versioned enum (see `StoreObjectWrapper` in sui-core for the pattern), so
+//! that values in any older format still deserialize. Such types implement [`VersionedValue`],
+//! and [`MigrationStep::migrate_values`] rewrites the stale values of their table.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bincode::Options;
+use rocksdb::{IteratorMode, MultiThreaded, ReadOptions, WriteBatch};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{debug, error, info};
+
+use super::{
+    default_db_options, open_cf_opts, DBMapTableConfigMap, MetricConf, RocksDB, RocksDBBatch,
+    TypedStoreError,
+};
+
+/// A value type whose serialized format has gone through several versions.
+pub trait VersionedValue: Sized {
+    /// The version that the current code writes.
+    const LATEST_VERSION: u32;
+
+    /// The format version this value was written in.
+    fn version(&self) -> u32;
+
+    /// Convert this value to `LATEST_VERSION`. Must be a no-op for values that are already
+    /// current.
+    fn migrate(self) -> Self;
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationStats {
+    /// Number of entries visited by the step.
+    pub scanned: u64,
+    /// Number of entries rewritten or deleted by the step.
+    pub rewritten: u64,
+}
+
+/// The schema version of stores written before store schema versions existed.
+pub const INITIAL_STORE_SCHEMA_VERSION: u32 = 1;
+
+const STORE_SCHEMA_VERSION_KEY: &str = "__store_schema_version";
+const REWRITE_CHUNK_SIZE: usize = 1000;
+
+type RewriteFn = Box<dyn Fn(&[u8], &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError>>;
+
+enum MigrationAction {
+    AddTable(String),
+    DropTable(String),
+    RewriteTable { table: String, rewrite: RewriteFn },
+}
+
+/// A change to the tables of a store, bringing it to schema `version`.
+pub struct MigrationStep {
+    version: u32,
+    description: String,
+    action: MigrationAction,
+}
+
+impl MigrationStep {
+    /// Creates an empty table, e.g. one to be filled by a later rewrite step.
+    pub fn add_table(version: u32, description: &str, table: &str) -> Self {
+        Self::new(
+            version,
+            description,
+            MigrationAction::AddTable(table.to_string()),
+        )
+    }
+
+    /// Drops a table and all of its entries.
+    pub fn drop_table(version: u32, description: &str, table: &str) -> Self {
+        Self::new(
+            version,
+            description,
+            MigrationAction::DropTable(table.to_string()),
+        )
+    }
+
+    /// Rewrites the values of a table from `V` to `W`, deleting the entries `rewrite` returns
+    /// `None` for. Fails, and so rolls the store back, on an entry which doesn't deserialize.
+    pub fn rewrite_table<K, V, W, F>(
+        version: u32,
+        description: &str,
+        table: &str,
+        rewrite: F,
+    ) -> Self
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+        W: Serialize,
+        F: Fn(K, V) -> Option<W> + 'static,
+    {
+        let rewrite = move |raw_key: &[u8], raw_value: &[u8]| -> Result<_, TypedStoreError> {
+            let key = bincode::DefaultOptions::new()
+                .with_big_endian()
+                .with_fixint_encoding()
+                .deserialize(raw_key)?;
+            let value = bcs::from_bytes(raw_value)?;
+            rewrite(key, value)
+                .map(|value| bcs::to_bytes(&value))
+                .transpose()
+                .map_err(TypedStoreError::from)
+        };
+        Self::new(
+            version,
+            description,
+            MigrationAction::RewriteTable {
+                table: table.to_string(),
+                rewrite: Box::new(rewrite),
+            },
+        )
+    }
+
+    /// Rewrites the values of a table which are older than `V::LATEST_VERSION` to the latest
+    /// format, leaving the others untouched.
+    pub fn migrate_values<V>(version: u32, description: &str, table: &str) -> Self
+    where
+        V: Serialize + DeserializeOwned + VersionedValue,
+    {
+        let rewrite = move |_: &[u8], raw_value: &[u8]| -> Result<_, TypedStoreError> {
+            let value: V = bcs::from_bytes(raw_value)?;
+            if value.version() >= V::LATEST_VERSION {
+                return Ok(Some(raw_value.to_vec()));
+            }
+            Ok(Some(bcs::to_bytes(&value.migrate())?))
+        };
+        Self::new(
+            version,
+            description,
+            MigrationAction::RewriteTable {
+                table: table.to_string(),
+                rewrite: Box::new(rewrite),
+            },
+        )
+    }
+
+    fn new(version: u32, description: &str, action: MigrationAction) -> Self {
+        Self {
+            version,
+            description: description.to_string(),
+            action,
+        }
+    }
+
+    fn apply(
+        &self,
+        db: &RocksDB,
+        table_options: impl Fn(&str) -> rocksdb::Options,
+    ) -> Result<MigrationStats, TypedStoreError> {
+        let mut stats = MigrationStats::default();
+        match &self.action {
+            MigrationAction::AddTable(table) => {
+                if db.cf_handle(table).is_none() {
+                    db.create_cf(table, &table_options(table))?;
+                }
+            }
+            MigrationAction::DropTable(table) => {
+                if db.cf_handle(table).is_some() {
+                    db.drop_cf(table)?;
+                }
+            }
+            MigrationAction::RewriteTable { table, rewrite } => {
+                let cf = db
+                    .cf_handle(table)
+                    .ok_or_else(|| TypedStoreError::UnregisteredColumn(table.clone()))?;
+                let mut batch = WriteBatch::default();
+                for entry in db.iterator_cf(&cf, ReadOptions::default(), IteratorMode::Start) {
+                    let (key, value) = entry?;
+                    stats.scanned += 1;
+                    match rewrite(&key, &value)? {
+                        Some(rewritten) if rewritten == *value => {}
+                        Some(rewritten) => {
+                            batch.put_cf(&cf, &key, rewritten);
+                            stats.rewritten += 1;
+                        }
+                        None => {
+                            batch.delete_cf(&cf, &key);
+                            stats.rewritten += 1;
+                        }
+                    }
+                    if stats.scanned as usize % REWRITE_CHUNK_SIZE == 0 {
+                        db.write(RocksDBBatch::Regular(std::mem::take(&mut batch)))?;
+                        debug!(
+                            "Table {table} rewrite progress: {} scanned, {} rewritten",
+                            stats.scanned, stats.rewritten
+                        );
+                    }
+                }
+                db.write(RocksDBBatch::Regular(batch))?;
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// What [`SchemaMigrations::run`] did to a store.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Schema version of the store before the run.
+    pub from_version: u32,
+    /// Schema version of the store after the run.
+    pub to_version: u32,
+    /// The steps run, by the version they brought the store to.
+    pub steps: Vec<(u32, MigrationStats)>,
+}
+
+/// The ordered migration steps of a store, from [`INITIAL_STORE_SCHEMA_VERSION`] to its latest
+/// schema version.
+#[derive(Default)]
+pub struct SchemaMigrations {
+    steps: Vec<MigrationStep>,
+}
+
+impl SchemaMigrations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a step, which must bring the store to a later version than the steps added before.
+    pub fn register(mut self, step: MigrationStep) -> Self {
+        assert!(
+            step.version > self.latest_version(),
+            "migration step to version {} registered after version {}",
+            step.version,
+            self.latest_version()
+        );
+        self.steps.push(step);
+        self
+    }
+
+    /// The schema version which the current code reads and writes.
+    pub fn latest_version(&self) -> u32 {
+        self.steps
+            .last()
+            .map_or(INITIAL_STORE_SCHEMA_VERSION, |step| step.version)
+    }
+
+    /// Brings the store at `path` to the latest schema version, and returns what was done. A
+    /// store which doesn't exist yet is created at the latest version. Must be called before the
+    /// store is opened; `db_options` and `tables_options` are the options it is opened with.
+    pub fn run(
+        &self,
+        path: &Path,
+        db_options: Option<rocksdb::Options>,
+        tables_options: Option<&DBMapTableConfigMap>,
+    ) -> Result<MigrationReport, TypedStoreError> {
+        let latest_version = self.latest_version();
+        let mut report = MigrationReport {
+            from_version: latest_version,
+            to_version: latest_version,
+            steps: vec![],
+        };
+        let db_options = db_options.unwrap_or_else(|| default_db_options().options);
+        let table_options = |table: &str| {
+            tables_options
+                .and_then(|tables| tables.0.get(table))
+                .map_or_else(|| default_db_options().options, |o| o.options.clone())
+        };
+        let Ok(tables) = rocksdb::DBWithThreadMode::<MultiThreaded>::list_cf(&db_options, path)
+        else {
+            let db = open_cf_opts(path, Some(db_options), MetricConf::default(), &[])?;
+            set_store_schema_version(&db, latest_version)?;
+            return Ok(report);
+        };
+        // The version is read without opening the tables, so that the store is only opened a
+        // second time when it is migrated.
+        let version = read_store_schema_version(path, &db_options)?;
+        report.from_version = version;
+        report.to_version = version;
+        if version > latest_version {
+            return Err(TypedStoreError::SchemaVersionError(format!(
+                "store {} has schema version {version}, but this binary supports at most \
+                 {latest_version}",
+                path.display()
+            )));
+        }
+        if version == latest_version {
+            return Ok(report);
+        }
+
+        let tables: Vec<_> = tables
+            .iter()
+            .map(|table| (table.as_str(), table_options(table.as_str())))
+            .collect();
+        let tables: Vec<_> = tables.iter().map(|(table, o)| (*table, o)).collect();
+        let db = open_cf_opts(path, Some(db_options), MetricConf::default(), &tables)?;
+        let backup_path = backup_path(path);
+        if backup_path.exists() {
+            fs::remove_dir_all(&backup_path).map_err(|e| migration_error(&backup_path, e))?;
+        }
+        db.checkpoint(&backup_path)?;
+        info!(
+            "Migrating store {} from schema version {version} to {latest_version}",
+            path.display()
+        );
+        let pending: Vec<_> = self
+            .steps
+            .iter()
+            .filter(|step| step.version > version)
+            .collect();
+        let mut result = Ok(());
+        for (i, step) in pending.iter().enumerate() {
+            info!(
+                "Running migration step {} of {} of store {}, to schema version {}: {}",
+                i + 1,
+                pending.len(),
+                path.display(),
+                step.version,
+                step.description
+            );
+            result = step.apply(&db, &table_options).and_then(|stats| {
+                set_store_schema_version(&db, step.version)?;
+                report.to_version = step.version;
+                report.steps.push((step.version, stats));
+                Ok(())
+            });
+            if let Err(e) = &result {
+                error!(
+                    "Migration of store {} to schema version {} failed, rolling back to version \
+                     {version}: {e}",
+                    path.display(),
+                    step.version
+                );
+                break;
+            }
+        }
+        // The store is closed before its directory is swapped with the checkpoint.
+        drop(db);
+
+        match result {
+            Ok(()) => {
+                fs::remove_dir_all(&backup_path).map_err(|e| migration_error(&backup_path, e))?;
+                info!(
+                    "Migrated store {} to schema version {latest_version}",
+                    path.display()
+                );
+                Ok(report)
+            }
+            Err(e) => {
+                fs::remove_dir_all(path).map_err(|e| migration_error(path, e))?;
+                fs::rename(&backup_path, path).map_err(|e| migration_error(path, e))?;
+                Err(TypedStoreError::SchemaVersionError(format!(
+                    "migration of store {} failed and was rolled back to schema version \
+                     {version}: {e}",
+                    path.display()
+                )))
+            }
+        }
+    }
+}
+
+/// Returns the schema version recorded by a store, or `INITIAL_STORE_SCHEMA_VERSION` if it has
+/// never been migrated.
+pub fn store_schema_version(db: &RocksDB) -> Result<u32, TypedStoreError> {
+    let cf = metadata_cf(db)?;
+    match db.get_pinned_cf(&cf, STORE_SCHEMA_VERSION_KEY, &ReadOptions::default())? {
+        Some(data) => Ok(bcs::from_bytes(&data)?),
+        None => Ok(INITIAL_STORE_SCHEMA_VERSION),
+    }
+}
+
+fn read_store_schema_version(
+    path: &Path,
+    db_options: &rocksdb::Options,
+) -> Result<u32, TypedStoreError> {
+    let db = rocksdb::DBWithThreadMode::<MultiThreaded>::open_cf_for_read_only(
+        db_options,
+        path,
+        [rocksdb::DEFAULT_COLUMN_FAMILY_NAME],
+        false,
+    )?;
+    match db.get(STORE_SCHEMA_VERSION_KEY)? {
+        Some(data) => Ok(bcs::from_bytes(&data)?),
+        None => Ok(INITIAL_STORE_SCHEMA_VERSION),
+    }
+}
+
+/// Records the schema version of a store. Only meant for tests, the version being otherwise
+/// maintained by [`SchemaMigrations::run`].
+pub fn set_store_schema_version(db: &RocksDB, version: u32) -> Result<(), TypedStoreError> {
+    let cf = metadata_cf(db)?;
+    db.put_cf(
+        &cf,
+        STORE_SCHEMA_VERSION_KEY,
+        bcs::to_bytes(&version)?,
+        &rocksdb::WriteOptions::default(),
+    )?;
+    Ok(())
+}
+
+fn metadata_cf(db: &RocksDB) -> Result<Arc<rocksdb::BoundColumnFamily<'_>>, TypedStoreError> {
+    db.cf_handle(rocksdb::DEFAULT_COLUMN_FAMILY_NAME)
+        .ok_or_else(|| {
+            TypedStoreError::UnregisteredColumn(rocksdb::DEFAULT_COLUMN_FAMILY_NAME.to_string())
+        })
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".pre-migration");
+    path.with_file_name(name)
+}
+
+fn migration_error(path: &Path, e: std::io::Error) -> TypedStoreError {
+    TypedStoreError::SchemaVersionError(format!("migration of {}: {e}", path.display()))
+}
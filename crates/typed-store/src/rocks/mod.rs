@@ -3,6 +3,7 @@
 pub mod errors;
 pub(crate) mod iter;
 pub(crate) mod keys;
+pub mod migration;
 pub mod util;
 pub(crate) mod values;
 
@@ -14,25 +15,31 @@ use bincode::Options;
 use collectable::TryExtend;
 use rocksdb::{checkpoint::Checkpoint, BlockBasedOptions, Cache};
 use rocksdb::{
-    properties, AsColumnFamilyRef, CStrLike, ColumnFamilyDescriptor, DBWithThreadMode, Error,
-    ErrorKind, IteratorMode, MultiThreaded, OptimisticTransactionOptions, ReadOptions, Transaction,
-    WriteBatch, WriteBatchWithTransaction, WriteOptions,
+    properties, AsColumnFamilyRef, CStrLike, ColumnFamilyDescriptor, CompactionDecision,
+    DBWithThreadMode, Error, ErrorKind, IteratorMode, MultiThreaded, OptimisticTransactionOptions,
+    ReadOptions, Transaction, WriteBatch, WriteBatchWithTransaction, WriteOptions,
 };
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     borrow::Borrow,
     collections::BTreeMap,
     env,
     marker::PhantomData,
+    ops::{Bound, RangeBounds},
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tap::TapFallible;
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, instrument};
 
-use self::{iter::Iter, keys::Keys, values::Values};
+use self::{
+    iter::{Iter, RevIter},
+    keys::Keys,
+    util::{parse_level_stats, parse_write_stalls},
+    values::Values,
+};
 pub use errors::TypedStoreError;
 use sui_macros::{fail_point, nondeterministic};
 
@@ -217,6 +224,14 @@ impl RocksDB {
         delegate_call!(self.multi_get_cf_opt(keys, readopts))
     }
 
+    pub fn property_value_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        name: impl CStrLike,
+    ) -> Result<Option<String>, rocksdb::Error> {
+        delegate_call!(self.property_value_cf(cf, name))
+    }
+
     pub fn property_int_value_cf(
         &self,
         cf: &impl AsColumnFamilyRef,
@@ -425,6 +440,13 @@ impl RocksDB {
         }
     }
 
+    pub fn cf_stats_report_interval(&self) -> Duration {
+        match self {
+            Self::DBWithThreadMode(d) => d.metric_conf.cf_stats_report_interval,
+            Self::OptimisticTransactionDB(d) => d.metric_conf.cf_stats_report_interval,
+        }
+    }
+
     pub fn iter_bytes_sampling_interval(&self) -> SamplingInterval {
         match self {
             Self::DBWithThreadMode(d) => d.metric_conf.iter_bytes_sample_interval.clone(),
@@ -513,36 +535,48 @@ impl RocksDBBatch {
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct MetricConf {
     pub db_name_override: Option<String>,
     pub read_sample_interval: SamplingInterval,
     pub write_sample_interval: SamplingInterval,
     pub iter_latency_sample_interval: SamplingInterval,
     pub iter_bytes_sample_interval: SamplingInterval,
+    // How often to report the column family stats that rocksdb only exposes as text (per level
+    // file counts and sizes, write stall reasons). Parsing them is more expensive than reading
+    // integer properties, so they are reported less often. Zero disables them.
+    pub cf_stats_report_interval: Duration,
 }
 
-impl MetricConf {
-    pub fn with_db_name(db_name: &str) -> Self {
+impl Default for MetricConf {
+    fn default() -> Self {
         Self {
-            db_name_override: Some(db_name.to_string()),
+            db_name_override: None,
             read_sample_interval: SamplingInterval::default(),
             write_sample_interval: SamplingInterval::default(),
             iter_latency_sample_interval: SamplingInterval::default(),
             iter_bytes_sample_interval: SamplingInterval::default(),
+            cf_stats_report_interval: Duration::from_secs(CF_STATS_REPORT_PERIOD_SECS),
+        }
+    }
+}
+
+impl MetricConf {
+    pub fn with_db_name(db_name: &str) -> Self {
+        Self {
+            db_name_override: Some(db_name.to_string()),
+            ..Default::default()
         }
     }
     pub fn with_sampling(read_interval: SamplingInterval) -> Self {
         Self {
-            db_name_override: None,
             read_sample_interval: read_interval,
-            write_sample_interval: SamplingInterval::default(),
-            iter_latency_sample_interval: SamplingInterval::default(),
-            iter_bytes_sample_interval: SamplingInterval::default(),
+            ..Default::default()
         }
     }
 }
 const CF_METRICS_REPORT_PERIOD_MILLIS: u64 = 1000;
+const CF_STATS_REPORT_PERIOD_SECS: u64 = 15;
 const METRICS_ERROR: i64 = -1;
 
 /// An interface to a rocksDB database, keyed by a columnfamily
@@ -569,18 +603,29 @@ impl<K, V> DBMap<K, V> {
         let db_metrics = DBMetrics::get();
         let db_metrics_cloned = db_metrics.clone();
         let cf = opt_cf.to_string();
+        let cf_stats_report_interval = db.cf_stats_report_interval();
         let (sender, mut recv) = tokio::sync::oneshot::channel();
         tokio::task::spawn(async move {
             let mut interval =
                 tokio::time::interval(Duration::from_millis(CF_METRICS_REPORT_PERIOD_MILLIS));
+            let mut last_cf_stats_report: Option<Instant> = None;
             loop {
                 tokio::select! {
                     _ = interval.tick() => {
                         let db = db_cloned.clone();
                         let cf = cf.clone();
                         let db_metrics = db_metrics.clone();
+                        let report_cf_stats = !cf_stats_report_interval.is_zero()
+                            && last_cf_stats_report
+                                .map_or(true, |last| last.elapsed() >= cf_stats_report_interval);
+                        if report_cf_stats {
+                            last_cf_stats_report = Some(Instant::now());
+                        }
                         if let Err(e) = tokio::task::spawn_blocking(move || {
                             Self::report_metrics(&db, &cf, &db_metrics);
+                            if report_cf_stats {
+                                Self::report_cf_stats(&db, &cf, &db_metrics);
+                            }
                         }).await {
                             error!("Failed to log metrics with error: {}", e);
                         }
@@ -692,6 +737,155 @@ impl<K, V> DBMap<K, V> {
             .iterator_cf(&self.cf(), self.opts.readopts(), IteratorMode::Start)
     }
 
+    /// Iterates over all the entries of the table. Unlike `Map::iter`, which ends at the first
+    /// entry it fails to read or deserialize, this returns such entries as errors and carries on
+    /// past them, which is what tools checking the integrity of a database need.
+    pub fn checked_iter(&self) -> impl Iterator<Item = Result<(K, V), TypedStoreError>> + '_
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let config = bincode::DefaultOptions::new()
+            .with_big_endian()
+            .with_fixint_encoding();
+        self.iterator_cf().map(move |entry| {
+            let (raw_key, raw_value) = entry?;
+            let hex_key = || {
+                raw_key
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect::<String>()
+            };
+            let key = config.deserialize(&raw_key).map_err(|e| {
+                TypedStoreError::SerializationError(format!("key 0x{}: {e}", hex_key()))
+            })?;
+            let value = bcs::from_bytes(&raw_value).map_err(|e| {
+                TypedStoreError::SerializationError(format!("value of key 0x{}: {e}", hex_key()))
+            })?;
+            Ok((key, value))
+        })
+    }
+
+    /// Deletes the entries with keys between `from` (inclusive) and `to` (non-inclusive) with a
+    /// single range tombstone, rather than a tombstone per key. The entries are hidden from reads
+    /// at once, unless the table is read with `ignore_range_deletions`, and their space is
+    /// reclaimed when the range is compacted. Not supported by transactional tables.
+    pub fn schedule_delete_range(&self, from: &K, to: &K) -> Result<(), TypedStoreError>
+    where
+        K: Serialize,
+    {
+        self.batch().delete_range(self, from, to)?.write()
+    }
+
+    /// Iterates over the entries with keys between `lower_bound` (inclusive) and `upper_bound`
+    /// (non-inclusive), either of which may be left open. Seeking the returned iterator, or
+    /// reversing it from its last entry, stays within the bounds.
+    pub fn iter_with_bounds(
+        &self,
+        lower_bound: Option<K>,
+        upper_bound: Option<K>,
+    ) -> Result<Iter<'_, K, V>, TypedStoreError>
+    where
+        K: Serialize + DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let lower_bound = lower_bound.map(|k| be_fix_int_ser(&k)).transpose()?;
+        let upper_bound = upper_bound.map(|k| be_fix_int_ser(&k)).transpose()?;
+        Ok(self.bounded_iter(lower_bound, upper_bound))
+    }
+
+    /// Iterates over the entries with keys in `range`.
+    pub fn range_iter(&self, range: impl RangeBounds<K>) -> Result<Iter<'_, K, V>, TypedStoreError>
+    where
+        K: Serialize + DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        // Appending a zero byte to a key gives the smallest key greater than it.
+        let successor = |key: &K| {
+            let mut key = be_fix_int_ser(key)?;
+            key.push(0);
+            Ok::<_, TypedStoreError>(key)
+        };
+        let lower_bound = match range.start_bound() {
+            Bound::Included(key) => Some(be_fix_int_ser(key)?),
+            Bound::Excluded(key) => Some(successor(key)?),
+            Bound::Unbounded => None,
+        };
+        let upper_bound = match range.end_bound() {
+            Bound::Included(key) => Some(successor(key)?),
+            Bound::Excluded(key) => Some(be_fix_int_ser(key)?),
+            Bound::Unbounded => None,
+        };
+        Ok(self.bounded_iter(lower_bound, upper_bound))
+    }
+
+    /// Iterates over the entries with keys in `range`, from the last one.
+    pub fn reversed_range_iter(
+        &self,
+        range: impl RangeBounds<K>,
+    ) -> Result<RevIter<'_, K, V>, TypedStoreError>
+    where
+        K: Serialize + DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        Ok(self.range_iter(range)?.skip_to_last().reverse())
+    }
+
+    /// Iterates over the entries whose keys start with `prefix`, e.g. the first elements of a
+    /// tuple key, as keys serialize to the concatenation of their elements.
+    pub fn prefix_iter<P: Serialize>(&self, prefix: &P) -> Result<Iter<'_, K, V>, TypedStoreError>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let prefix = be_fix_int_ser(prefix)?;
+        let upper_bound = prefix_upper_bound(&prefix);
+        Ok(self.bounded_iter(Some(prefix), upper_bound))
+    }
+
+    fn bounded_iter(
+        &self,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> Iter<'_, K, V>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let report_metrics = if self.iter_latency_sample_interval.sample() {
+            let timer = self
+                .db_metrics
+                .op_metrics
+                .rocksdb_iter_latency_seconds
+                .with_label_values(&[&self.cf])
+                .start_timer();
+            Some((timer, RocksDBPerfContext::default()))
+        } else {
+            None
+        };
+        let mut readopts = self.opts.readopts();
+        if let Some(lower_bound) = lower_bound {
+            readopts.set_iterate_lower_bound(lower_bound);
+        }
+        if let Some(upper_bound) = upper_bound {
+            readopts.set_iterate_upper_bound(upper_bound);
+        }
+        let mut db_iter = self.rocksdb.raw_iterator_cf(&self.cf(), readopts);
+        db_iter.seek_to_first();
+        if let Some((timer, _perf_ctx)) = report_metrics {
+            timer.stop_and_record();
+            self.db_metrics
+                .read_perf_ctx_metrics
+                .report_metrics(&self.cf);
+        }
+        Iter::new(
+            db_iter,
+            self.cf.clone(),
+            &self.db_metrics,
+            &self.iter_bytes_sample_interval,
+        )
+    }
+
     pub fn flush(&self) -> Result<(), TypedStoreError> {
         self.rocksdb
             .flush_cf(&self.cf())
@@ -852,6 +1046,30 @@ impl<K, V> DBMap<K, V> {
                 Self::get_int_property(rocksdb, &cf, properties::BACKGROUND_ERRORS)
                     .unwrap_or(METRICS_ERROR),
             );
+        db_metrics
+            .cf_metrics
+            .rocksdb_estimate_pending_compaction_bytes
+            .with_label_values(&[cf_name])
+            .set(
+                Self::get_int_property(rocksdb, &cf, properties::ESTIMATE_PENDING_COMPACTION_BYTES)
+                    .unwrap_or(METRICS_ERROR),
+            );
+        db_metrics
+            .cf_metrics
+            .rocksdb_cur_size_active_mem_table
+            .with_label_values(&[cf_name])
+            .set(
+                Self::get_int_property(rocksdb, &cf, properties::CUR_SIZE_ACTIVE_MEM_TABLE)
+                    .unwrap_or(METRICS_ERROR),
+            );
+        db_metrics
+            .cf_metrics
+            .rocksdb_num_immutable_mem_table
+            .with_label_values(&[cf_name])
+            .set(
+                Self::get_int_property(rocksdb, &cf, properties::NUM_IMMUTABLE_MEM_TABLE)
+                    .unwrap_or(METRICS_ERROR),
+            );
         let db_name = rocksdb.db_name();
         if let RocksDB::DBWithThreadMode(ref rocksdb) = **rocksdb {
             let mem_usage_stats =
@@ -886,14 +1104,52 @@ impl<K, V> DBMap<K, V> {
         }
     }
 
+    fn report_cf_stats(rocksdb: &Arc<RocksDB>, cf_name: &str, db_metrics: &Arc<DBMetrics>) {
+        let cf = rocksdb.cf_handle(cf_name).expect("Failed to get cf");
+        if let Ok(Some(level_stats)) = rocksdb.property_value_cf(&cf, properties::LEVELSTATS) {
+            for (level, num_files, size_mb) in parse_level_stats(&level_stats) {
+                let level = level.to_string();
+                db_metrics
+                    .cf_metrics
+                    .rocksdb_level_num_files
+                    .with_label_values(&[cf_name, &level])
+                    .set(num_files);
+                db_metrics
+                    .cf_metrics
+                    .rocksdb_level_size_bytes
+                    .with_label_values(&[cf_name, &level])
+                    .set(size_mb * 1024 * 1024);
+            }
+        }
+        if let Ok(Some(cf_stats)) =
+            rocksdb.property_value_cf(&cf, properties::CFSTATS_NO_FILE_HISTOGRAM)
+        {
+            for (reason, count) in parse_write_stalls(&cf_stats) {
+                db_metrics
+                    .cf_metrics
+                    .rocksdb_write_stall_count
+                    .with_label_values(&[cf_name, &reason])
+                    .set(count);
+            }
+        }
+    }
+
+    #[deprecated(note = "use `write_transaction`, which checks the values read with \
+        `get_for_update` instead of every key written since the transaction started")]
     pub fn transaction(&self) -> Result<DBTransaction<'_>, TypedStoreError> {
         DBTransaction::new(&self.rocksdb)
     }
 
+    #[deprecated(note = "use `write_transaction`, which checks the values read with \
+        `get_for_update` instead of every key written")]
     pub fn transaction_without_snapshot(&self) -> Result<DBTransaction<'_>, TypedStoreError> {
         DBTransaction::new_without_snapshot(&self.rocksdb)
     }
 
+    pub fn write_transaction(&self) -> DBWriteTransaction {
+        DBWriteTransaction::new(&self.rocksdb, &self.db_metrics, &self.write_sample_interval)
+    }
+
     pub fn checkpoint_db(&self, path: &Path) -> Result<(), TypedStoreError> {
         self.rocksdb.checkpoint(path)
     }
@@ -1189,6 +1445,8 @@ impl DBBatch {
     }
 }
 
+/// A transaction of an optimistic transaction database, borrowing the database and committed
+/// synchronously. Superseded by [`DBWriteTransaction`].
 pub struct DBTransaction<'a> {
     rocksdb: Arc<RocksDB>,
     transaction: Transaction<'a, rocksdb::OptimisticTransactionDB>,
@@ -1356,16 +1614,262 @@ impl<'a> DBTransaction<'a> {
 
     pub fn commit(self) -> Result<(), TypedStoreError> {
         fail_point!("transaction-commit");
-        self.transaction.commit().map_err(|e| match e.kind() {
-            // empirically, this is what you get when there is a write conflict. it is not
-            // documented whether this is the only time you can get this error.
-            ErrorKind::Busy | ErrorKind::TryAgain => TypedStoreError::RetryableTransactionError,
-            _ => e.into(),
-        })?;
+        self.transaction
+            .commit()
+            .map_err(transaction_commit_error)?;
         Ok(())
     }
 }
 
+fn transaction_commit_error(e: Error) -> TypedStoreError {
+    match e.kind() {
+        // empirically, this is what you get when there is a write conflict. it is not
+        // documented whether this is the only time you can get this error.
+        ErrorKind::Busy | ErrorKind::TryAgain => TypedStoreError::RetryableTransactionError,
+        _ => e.into(),
+    }
+}
+
+/// A write transaction across the column families of a database, which, unlike `DBTransaction`,
+/// doesn't borrow the database and is committed asynchronously, off the async runtime threads.
+///
+/// The values read with `get_for_update` are checked when committing: if any of them changed in
+/// the meantime, nothing is written and the commit fails with
+/// `TypedStoreError::RetryableTransactionError`, e.g. to be retried with `retry_transaction!`.
+/// Checking values requires an optimistic transaction database. On other databases, a transaction
+/// without reads is written as a batch, and a transaction with reads fails to commit.
+///
+/// Blind writes, which read nothing to check, are written with a `DBBatch` as before.
+///
+/// ```
+/// use typed_store::rocks::*;
+/// use tempfile::tempdir;
+/// use typed_store::Map;
+/// use core::fmt::Error;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Error> {
+/// let options = rocksdb::Options::default();
+/// let rocks = open_cf_opts_transactional(
+///     tempdir().unwrap(),
+///     None,
+///     MetricConf::default(),
+///     &[("balances", &options), ("transfers", &options)],
+/// )
+/// .unwrap();
+/// let rw_options = ReadWriteOptions::default();
+/// let balances = DBMap::<String, u64>::reopen(&rocks, Some("balances"), &rw_options)
+///     .expect("Failed to open storage");
+/// let transfers = DBMap::<u64, u64>::reopen(&rocks, Some("transfers"), &rw_options)
+///     .expect("Failed to open storage");
+/// balances.insert(&"alice".to_string(), &100).expect("Failed to insert");
+///
+/// let mut transaction = balances.write_transaction();
+/// let balance = transaction
+///     .get_for_update(&balances, &"alice".to_string())
+///     .expect("Failed to read")
+///     .unwrap_or_default();
+/// transaction
+///     .insert_batch(&balances, [("alice".to_string(), balance - 10)])
+///     .expect("Failed to insert");
+/// transaction
+///     .insert_batch(&transfers, [(1, 10)])
+///     .expect("Failed to insert");
+/// transaction.commit().await.expect("Failed to commit");
+/// assert_eq!(balances.get(&"alice".to_string()).unwrap(), Some(90));
+/// Ok(())
+/// }
+/// ```
+pub struct DBWriteTransaction {
+    rocksdb: Arc<RocksDB>,
+    /// The keys read, by column family, with the value read, to be checked when committing.
+    reads: Vec<(String, Vec<u8>, Option<Vec<u8>>)>,
+    /// The keys written, by column family, with the value written, `None` to delete the key.
+    writes: Vec<(String, Vec<u8>, Option<Vec<u8>>)>,
+    db_metrics: Arc<DBMetrics>,
+    write_sample_interval: SamplingInterval,
+}
+
+impl DBWriteTransaction {
+    fn new(
+        dbref: &Arc<RocksDB>,
+        db_metrics: &Arc<DBMetrics>,
+        write_sample_interval: &SamplingInterval,
+    ) -> Self {
+        Self {
+            rocksdb: dbref.clone(),
+            reads: vec![],
+            writes: vec![],
+            db_metrics: db_metrics.clone(),
+            write_sample_interval: write_sample_interval.clone(),
+        }
+    }
+
+    /// Inserts a range of (key, value) pairs given as an iterator
+    pub fn insert_batch<J: Borrow<K>, K: Serialize, U: Borrow<V>, V: Serialize>(
+        &mut self,
+        db: &DBMap<K, V>,
+        new_vals: impl IntoIterator<Item = (J, U)>,
+    ) -> Result<(), TypedStoreError> {
+        if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        for (k, v) in new_vals {
+            let k_buf = be_fix_int_ser(k.borrow())?;
+            let v_buf = bcs::to_bytes(v.borrow())?;
+            self.writes.push((db.cf.clone(), k_buf, Some(v_buf)));
+        }
+        Ok(())
+    }
+
+    /// Deletes a set of keys given as an iterator
+    pub fn delete_batch<J: Borrow<K>, K: Serialize, V>(
+        &mut self,
+        db: &DBMap<K, V>,
+        purged_vals: impl IntoIterator<Item = J>,
+    ) -> Result<(), TypedStoreError> {
+        if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        for k in purged_vals {
+            let k_buf = be_fix_int_ser(k.borrow())?;
+            self.writes.push((db.cf.clone(), k_buf, None));
+        }
+        Ok(())
+    }
+
+    /// Reads the value of `key`, the one written by this transaction if any. Otherwise the value
+    /// read is checked to be unchanged when committing.
+    pub fn get_for_update<K: Serialize, V: DeserializeOwned>(
+        &mut self,
+        db: &DBMap<K, V>,
+        key: &K,
+    ) -> Result<Option<V>, TypedStoreError> {
+        if !Arc::ptr_eq(&db.rocksdb, &self.rocksdb) {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        let k_buf = be_fix_int_ser(key)?;
+        let written = self
+            .writes
+            .iter()
+            .rev()
+            .find(|(cf, k, _)| cf == &db.cf && k == &k_buf);
+        let value = if let Some((_, _, value)) = written {
+            value.clone()
+        } else {
+            let value = self
+                .rocksdb
+                .get_pinned_cf(&db.cf(), &k_buf, &db.opts.readopts())?
+                .map(|value| value.to_vec());
+            self.reads.push((db.cf.clone(), k_buf, value.clone()));
+            value
+        };
+        match value {
+            Some(data) => Ok(Some(bcs::from_bytes(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the values of `keys` as `get_for_update` does.
+    pub fn multi_get_for_update<J: Borrow<K>, K: Serialize, V: DeserializeOwned>(
+        &mut self,
+        db: &DBMap<K, V>,
+        keys: impl IntoIterator<Item = J>,
+    ) -> Result<Vec<Option<V>>, TypedStoreError> {
+        keys.into_iter()
+            .map(|key| self.get_for_update(db, key.borrow()))
+            .collect()
+    }
+
+    /// Consumes the transaction and writes it to the database if the values it read didn't
+    /// change.
+    pub async fn commit(self) -> Result<(), TypedStoreError> {
+        tokio::task::spawn_blocking(move || self.commit_blocking())
+            .await
+            .map_err(|e| TypedStoreError::RocksDBError(e.to_string()))?
+    }
+
+    #[instrument(level = "trace", skip_all, err)]
+    fn commit_blocking(self) -> Result<(), TypedStoreError> {
+        fail_point!("transaction-commit");
+        let report_metrics = if self.write_sample_interval.sample() {
+            let db_name = self.rocksdb.db_name();
+            let timer = self
+                .db_metrics
+                .op_metrics
+                .rocksdb_batch_commit_latency_seconds
+                .with_label_values(&[&db_name])
+                .start_timer();
+            let size: usize = self
+                .writes
+                .iter()
+                .map(|(_, key, value)| key.len() + value.as_ref().map_or(0, Vec::len))
+                .sum();
+            Some((db_name, size, timer, RocksDBPerfContext::default()))
+        } else {
+            None
+        };
+        match &*self.rocksdb {
+            RocksDB::OptimisticTransactionDB(_) => {
+                let transaction = self.rocksdb.transaction()?;
+                for (cf, key, read) in &self.reads {
+                    let cf = self.cf_handle(cf)?;
+                    let value = transaction
+                        .get_for_update_cf_opt(&cf, key, true, &ReadOptions::default())
+                        .map_err(transaction_commit_error)?
+                        .map(|value| value.to_vec());
+                    if &value != read {
+                        return Err(TypedStoreError::RetryableTransactionError);
+                    }
+                }
+                for (cf, key, value) in &self.writes {
+                    let cf = self.cf_handle(cf)?;
+                    let result = match value {
+                        Some(value) => transaction.put_cf(&cf, key, value),
+                        None => transaction.delete_cf(&cf, key),
+                    };
+                    result.map_err(transaction_commit_error)?;
+                }
+                transaction.commit().map_err(transaction_commit_error)?;
+            }
+            RocksDB::DBWithThreadMode(_) => {
+                if !self.reads.is_empty() {
+                    return Err(TypedStoreError::RocksDBError(
+                        "checking the values read requires an optimistic transaction database"
+                            .to_string(),
+                    ));
+                }
+                let mut batch = WriteBatch::default();
+                for (cf, key, value) in &self.writes {
+                    let cf = self.cf_handle(cf)?;
+                    match value {
+                        Some(value) => batch.put_cf(&cf, key, value),
+                        None => batch.delete_cf(&cf, key),
+                    }
+                }
+                self.rocksdb.write(RocksDBBatch::Regular(batch))?;
+            }
+        }
+        if let Some((db_name, batch_size, _timer, _perf_ctx)) = report_metrics {
+            self.db_metrics
+                .op_metrics
+                .rocksdb_batch_commit_bytes
+                .with_label_values(&[&db_name])
+                .observe(batch_size as f64);
+            self.db_metrics
+                .write_perf_ctx_metrics
+                .report_metrics(&db_name);
+        }
+        Ok(())
+    }
+
+    fn cf_handle(&self, cf: &str) -> Result<Arc<rocksdb::BoundColumnFamily<'_>>, TypedStoreError> {
+        self.rocksdb
+            .cf_handle(cf)
+            .ok_or_else(|| TypedStoreError::UnregisteredColumn(cf.to_string()))
+    }
+}
+
 macro_rules! delegate_iter_call {
     ($self:ident.$method:ident($($args:ident),*)) => {
         match $self {
@@ -1599,33 +2103,7 @@ where
     }
 
     fn iter(&'a self) -> Self::Iterator {
-        let report_metrics = if self.iter_latency_sample_interval.sample() {
-            let timer = self
-                .db_metrics
-                .op_metrics
-                .rocksdb_iter_latency_seconds
-                .with_label_values(&[&self.cf])
-                .start_timer();
-            Some((timer, RocksDBPerfContext::default()))
-        } else {
-            None
-        };
-        let mut db_iter = self
-            .rocksdb
-            .raw_iterator_cf(&self.cf(), self.opts.readopts());
-        db_iter.seek_to_first();
-        if let Some((timer, _perf_ctx)) = report_metrics {
-            timer.stop_and_record();
-            self.db_metrics
-                .read_perf_ctx_metrics
-                .report_metrics(&self.cf);
-        }
-        Iter::new(
-            db_iter,
-            self.cf.clone(),
-            &self.db_metrics,
-            &self.iter_bytes_sample_interval,
-        )
+        self.bounded_iter(None, None)
     }
 
     fn keys(&'a self) -> Self::Keys {
@@ -1791,6 +2269,105 @@ pub struct DBOptions {
     pub rw_options: ReadWriteOptions,
 }
 
+impl DBOptions {
+    /// Sets a compaction filter on the table, dropping the entries `filter` returns true for when
+    /// they are compacted, e.g. to expire entries without the write amplification of deleting
+    /// them. Entries which don't deserialize are kept.
+    pub fn set_compaction_filter<K, V, F>(&mut self, name: &str, mut filter: F)
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+        F: FnMut(K, V) -> bool + Send + 'static,
+    {
+        self.options
+            .set_compaction_filter(name, move |_level: u32, key: &[u8], value: &[u8]| {
+                let key = bincode::DefaultOptions::new()
+                    .with_big_endian()
+                    .with_fixint_encoding()
+                    .deserialize(key);
+                match (key, bcs::from_bytes(value)) {
+                    (Ok(key), Ok(value)) if filter(key, value) => CompactionDecision::Remove,
+                    _ => CompactionDecision::Keep,
+                }
+            });
+    }
+
+    /// Overrides the options of the table set in `tuning`. Setting the block cache size or the
+    /// bloom filter replaces the block-based table options of the table with the default ones
+    /// and these settings.
+    pub fn apply_tuning(&mut self, tuning: &DBTableTuning) {
+        if let Some(compression) = tuning.compression {
+            self.options.set_compression_type(compression.into());
+        }
+        if let Some(level) = tuning.compression_level {
+            // The window bits and strategy are the defaults of RocksDB.
+            self.options.set_compression_options(-14, level, 0, 0);
+        }
+        if tuning.block_cache_size_mb.is_some() || tuning.bloom_filter_bits.is_some() {
+            self.options
+                .set_block_based_table_factory(&block_based_options(
+                    tuning
+                        .block_cache_size_mb
+                        .unwrap_or(DEFAULT_BLOCK_CACHE_SIZE_MB),
+                    tuning
+                        .bloom_filter_bits
+                        .unwrap_or(DEFAULT_BLOOM_FILTER_BITS),
+                ));
+        }
+        if let Some(write_buffer_size_mb) = tuning.write_buffer_size_mb {
+            self.options
+                .set_write_buffer_size(write_buffer_size_mb * 1024 * 1024);
+        }
+    }
+}
+
+/// Compression algorithms of RocksDB.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DBCompression {
+    None,
+    Snappy,
+    Zlib,
+    Lz4,
+    Lz4hc,
+    Zstd,
+}
+
+impl From<DBCompression> for rocksdb::DBCompressionType {
+    fn from(compression: DBCompression) -> Self {
+        match compression {
+            DBCompression::None => rocksdb::DBCompressionType::None,
+            DBCompression::Snappy => rocksdb::DBCompressionType::Snappy,
+            DBCompression::Zlib => rocksdb::DBCompressionType::Zlib,
+            DBCompression::Lz4 => rocksdb::DBCompressionType::Lz4,
+            DBCompression::Lz4hc => rocksdb::DBCompressionType::Lz4hc,
+            DBCompression::Zstd => rocksdb::DBCompressionType::Zstd,
+        }
+    }
+}
+
+/// Overrides of the RocksDB options of a table, e.g. set by the operator of a node to tune its
+/// busiest tables. The options left unset keep the defaults of the table.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct DBTableTuning {
+    /// Compression of the SST files of the table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<DBCompression>,
+    /// Level of the compression, e.g. from 1 to 22 for zstd.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_level: Option<i32>,
+    /// Size of the block cache of the table, in MiB. Every table has a block cache of its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_cache_size_mb: Option<usize>,
+    /// Bits per key of the bloom filters of the table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bloom_filter_bits: Option<f64>,
+    /// Size of the memtable of the table, in MiB, which is flushed to an SST file once full.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_buffer_size_mb: Option<usize>,
+}
+
 /// Base options to be used across all rocksdb instances.
 pub fn base_db_options() -> DBOptions {
     let mut opt = rocksdb::Options::default();
@@ -1846,22 +2423,32 @@ pub fn default_db_options() -> DBOptions {
     // except non-default binary and hash index, to hopefully reduce lookup latencies
     // without causing any regression for scanning, with slightly more memory usages.
     // https://github.com/facebook/rocksdb/blob/11cb6af6e5009c51794641905ca40ce5beec7fee/options/options.cc#L611-L621
-    let mut block_options = BlockBasedOptions::default();
-    // Configure a 64MiB block cache.
-    block_options.set_block_cache(&Cache::new_lru_cache(64 << 20).unwrap());
-    // Set a bloomfilter with 1% false positive rate.
-    block_options.set_bloom_filter(10.0, false);
-
-    // From https://github.com/EighteenZi/rocksdb_wiki/blob/master/Block-Cache.md#caching-index-and-filter-blocks
-    block_options.set_pin_l0_filter_and_index_blocks_in_cache(true);
-
-    opt.options.set_block_based_table_factory(&block_options);
+    opt.options
+        .set_block_based_table_factory(&block_based_options(
+            DEFAULT_BLOCK_CACHE_SIZE_MB,
+            DEFAULT_BLOOM_FILTER_BITS,
+        ));
     // Set memtable bloomfilter.
     opt.options.set_memtable_prefix_bloom_ratio(0.02);
 
     opt
 }
 
+// A 64MiB block cache.
+const DEFAULT_BLOCK_CACHE_SIZE_MB: usize = 64;
+// A bloomfilter with 1% false positive rate.
+const DEFAULT_BLOOM_FILTER_BITS: f64 = 10.0;
+
+fn block_based_options(block_cache_size_mb: usize, bloom_filter_bits: f64) -> BlockBasedOptions {
+    let mut block_options = BlockBasedOptions::default();
+    block_options.set_block_cache(&Cache::new_lru_cache(block_cache_size_mb << 20).unwrap());
+    block_options.set_bloom_filter(bloom_filter_bits, false);
+
+    // From https://github.com/EighteenZi/rocksdb_wiki/blob/master/Block-Cache.md#caching-index-and-filter-blocks
+    block_options.set_pin_l0_filter_and_index_blocks_in_cache(true);
+    block_options
+}
+
 /// Creates a default RocksDB option, optimized for point lookup.
 pub fn point_lookup_db_options() -> DBOptions {
     let mut db_options = default_db_options();
@@ -2042,6 +2629,69 @@ pub fn open_cf_opts_secondary<P: AsRef<Path>>(
     })
 }
 
+/// Opens every column family of the database at `primary_path` as a secondary instance, which
+/// reads the files of a primary instance that may be running in another process, e.g. the stores
+/// of a live node, and follows its writes on `try_catch_up_with_primary`. The secondary instance
+/// keeps its own info logs in `secondary_path`, or in a new temporary directory if `None`.
+pub fn open_cf_secondary(
+    primary_path: &Path,
+    secondary_path: Option<PathBuf>,
+    metric_conf: MetricConf,
+) -> Result<Arc<RocksDB>, TypedStoreError> {
+    let secondary_path = secondary_path.unwrap_or_else(|| {
+        tempfile::tempdir()
+            .expect("Failed to open temporary directory")
+            .into_path()
+    });
+    open_cf_opts_secondary(
+        primary_path,
+        Some(secondary_path.as_path()),
+        None,
+        metric_conf,
+        &[],
+    )
+}
+
+/// Reads the integer RocksDB properties of the column family `cf_name` that are reported in the
+/// metrics of its `DBMap`, by property name.
+pub fn int_properties(
+    rocksdb: &RocksDB,
+    cf_name: &str,
+) -> Result<BTreeMap<String, i64>, TypedStoreError> {
+    let cf = rocksdb
+        .cf_handle(cf_name)
+        .ok_or_else(|| TypedStoreError::UnregisteredColumn(cf_name.to_owned()))?;
+    let int_properties: [&'static std::ffi::CStr; 20] = [
+        properties::TOTAL_SST_FILES_SIZE,
+        properties::SIZE_ALL_MEM_TABLES,
+        properties::NUM_SNAPSHOTS,
+        properties::OLDEST_SNAPSHOT_TIME,
+        properties::ACTUAL_DELAYED_WRITE_RATE,
+        properties::IS_WRITE_STOPPED,
+        properties::BLOCK_CACHE_CAPACITY,
+        properties::BLOCK_CACHE_USAGE,
+        properties::BLOCK_CACHE_PINNED_USAGE,
+        properties::ESTIMATE_TABLE_READERS_MEM,
+        properties::ESTIMATE_NUM_KEYS,
+        properties::MEM_TABLE_FLUSH_PENDING,
+        properties::COMPACTION_PENDING,
+        properties::NUM_RUNNING_COMPACTIONS,
+        properties::NUM_RUNNING_FLUSHES,
+        properties::ESTIMATE_OLDEST_KEY_TIME,
+        properties::BACKGROUND_ERRORS,
+        properties::ESTIMATE_PENDING_COMPACTION_BYTES,
+        properties::CUR_SIZE_ACTIVE_MEM_TABLE,
+        properties::NUM_IMMUTABLE_MEM_TABLE,
+    ];
+    int_properties
+        .into_iter()
+        .map(|property| {
+            let value = DBMap::<(), ()>::get_int_property(rocksdb, &cf, property)?;
+            Ok((property.to_string_lossy().into_owned(), value))
+        })
+        .collect()
+}
+
 pub fn list_tables(path: std::path::PathBuf) -> eyre::Result<Vec<String>> {
     const DB_DEFAULT_CF_NAME: &str = "default";
 
@@ -2075,6 +2725,14 @@ where
         .map_err(|e| e.into())
 }
 
+/// The smallest key greater than all the keys starting with `prefix`, if any.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let last = prefix.iter().rposition(|b| *b != u8::MAX)?;
+    let mut upper_bound = prefix[..=last].to_vec();
+    upper_bound[last] += 1;
+    Some(upper_bound)
+}
+
 #[derive(Clone)]
 pub struct DBMapTableConfigMap(BTreeMap<String, DBOptions>);
 impl DBMapTableConfigMap {
@@ -2085,6 +2743,21 @@ impl DBMapTableConfigMap {
     pub fn to_map(&self) -> BTreeMap<String, DBOptions> {
         self.0.clone()
     }
+
+    /// Applies the tuning of `tuning` to the tables, by table name. Fails if a tuned table is not
+    /// in the map, e.g. because its name is misspelled in a config.
+    pub fn apply_tuning(
+        &mut self,
+        tuning: &BTreeMap<String, DBTableTuning>,
+    ) -> Result<(), TypedStoreError> {
+        for (table, table_tuning) in tuning {
+            self.0
+                .get_mut(table)
+                .ok_or_else(|| TypedStoreError::UnregisteredColumn(table.clone()))?
+                .apply_tuning(table_tuning);
+        }
+        Ok(())
+    }
 }
 
 pub enum RocksDBAccessType {
@@ -275,6 +275,58 @@ async fn test_iter(#[values(true, false)] is_transactional: bool) {
     assert_eq!(None, iter.next());
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_checked_iter(#[values(true, false)] is_transactional: bool) {
+    let db = open_map(temp_dir(), None, is_transactional);
+    db.insert(&1, &"1".to_string()).expect("Failed to insert");
+    db.insert(&3, &"3".to_string()).expect("Failed to insert");
+    // A value that is not the BCS encoding of a string.
+    db.rocksdb
+        .put_cf(
+            &db.cf(),
+            be_fix_int_ser(&2).unwrap(),
+            [0xff],
+            &db.opts.writeopts(),
+        )
+        .unwrap();
+
+    // `iter` stops at the entry it can't deserialize.
+    assert_eq!(db.iter().collect::<Vec<_>>(), vec![(1, "1".to_string())]);
+
+    let entries = db.checked_iter().collect::<Vec<_>>();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0], Ok((1, "1".to_string())));
+    assert!(matches!(
+        &entries[1],
+        Err(TypedStoreError::SerializationError(e)) if e.starts_with("value of key 0x00000002")
+    ));
+    assert_eq!(entries[2], Ok((3, "3".to_string())));
+}
+
+#[tokio::test]
+async fn test_open_cf_secondary() {
+    let path = temp_dir();
+    let primary = open_map::<_, u32, String>(&path, Some("table"), false);
+    primary.insert(&1, &"1".to_string()).unwrap();
+
+    let rocksdb = open_cf_secondary(&path, None, MetricConf::default()).unwrap();
+    let secondary =
+        DBMap::<u32, String>::reopen(&rocksdb, Some("table"), &ReadWriteOptions::default())
+            .unwrap();
+    assert_eq!(secondary.get(&1).unwrap(), Some("1".to_string()));
+
+    // The secondary only sees the writes of the primary once it catches up with them.
+    primary.insert(&2, &"2".to_string()).unwrap();
+    assert_eq!(secondary.get(&2).unwrap(), None);
+    secondary.try_catch_up_with_primary().unwrap();
+    assert_eq!(secondary.get(&2).unwrap(), Some("2".to_string()));
+
+    let properties = int_properties(&rocksdb, "table").unwrap();
+    assert!(properties.contains_key("rocksdb.estimate-num-keys"));
+    assert!(int_properties(&rocksdb, "missing").is_err());
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_iter_reverse(#[values(true, false)] is_transactional: bool) {
@@ -487,6 +539,79 @@ async fn test_delete_range() {
     assert!(db.contains_key(&100).expect("Failed to query legal key"));
 }
 
+#[tokio::test]
+async fn test_schedule_delete_range() {
+    let db = open_map(temp_dir(), None, false);
+    db.multi_insert((0u32..10).map(|i| (i, i.to_string())))
+        .expect("Failed to insert");
+
+    db.schedule_delete_range(&2, &8)
+        .expect("Failed to delete range");
+    assert_eq!(
+        db.keys().collect::<Vec<_>>(),
+        vec![0, 1, 8, 9],
+        "range deletion is not inclusive of to"
+    );
+
+    let db = open_map::<_, u32, String>(temp_dir(), None, true);
+    assert!(db.schedule_delete_range(&2, &8).is_err());
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_range_iter(#[values(true, false)] is_transactional: bool) {
+    let db = open_map(temp_dir(), None, is_transactional);
+    db.multi_insert((0u32..10).map(|i| (i, i.to_string())))
+        .expect("Failed to insert");
+    let keys = |iter: Iter<'_, u32, String>| iter.map(|(k, _)| k).collect::<Vec<_>>();
+
+    assert_eq!(keys(db.range_iter(2..5).unwrap()), vec![2, 3, 4]);
+    assert_eq!(keys(db.range_iter(2..=5).unwrap()), vec![2, 3, 4, 5]);
+    assert_eq!(keys(db.range_iter(..2).unwrap()), vec![0, 1]);
+    assert_eq!(keys(db.range_iter(8..).unwrap()), vec![8, 9]);
+    assert_eq!(
+        keys(
+            db.range_iter((Bound::Excluded(7), Bound::Unbounded))
+                .unwrap()
+        ),
+        vec![8, 9]
+    );
+    assert_eq!(
+        keys(db.iter_with_bounds(Some(3), Some(5)).unwrap()),
+        vec![3, 4]
+    );
+    assert_eq!(keys(db.iter_with_bounds(None, Some(1)).unwrap()), vec![0]);
+
+    // Seeking stays within the bounds.
+    let iter = db.range_iter(2..5).unwrap();
+    assert_eq!(keys(iter.skip_to(&0).unwrap()), vec![2, 3, 4]);
+
+    let reversed = db.reversed_range_iter(2..5).unwrap();
+    assert_eq!(reversed.map(|(k, _)| k).collect::<Vec<_>>(), vec![4, 3, 2]);
+    let reversed = db.reversed_range_iter(..=7).unwrap();
+    assert_eq!(reversed.map(|(k, _)| k).next(), Some(7));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_prefix_iter(#[values(true, false)] is_transactional: bool) {
+    let db = open_map(temp_dir(), None, is_transactional);
+    let keys = [(1u32, 1u64), (1, 2), (2, 1), (u32::MAX, 0), (u32::MAX, 1)];
+    db.multi_insert(keys.iter().map(|k| (*k, "value".to_string())))
+        .expect("Failed to insert");
+    let prefixed = |prefix: u32| {
+        db.prefix_iter(&prefix)
+            .unwrap()
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(prefixed(1), vec![(1, 1), (1, 2)]);
+    assert_eq!(prefixed(2), vec![(2, 1)]);
+    assert_eq!(prefixed(3), vec![]);
+    assert_eq!(prefixed(u32::MAX), vec![(u32::MAX, 0), (u32::MAX, 1)]);
+}
+
 #[tokio::test]
 async fn test_clear() {
     let db = DBMap::<i32, String>::open(
@@ -642,6 +767,7 @@ async fn test_multi_remove(#[values(true, false)] is_transactional: bool) {
 }
 
 #[tokio::test]
+#[allow(deprecated)] // covers `DBTransaction` until it is removed
 async fn test_transactional() {
     let key = "key";
     let path = temp_dir();
@@ -668,6 +794,7 @@ async fn test_transactional() {
 }
 
 #[tokio::test]
+#[allow(deprecated)] // covers `DBTransaction` until it is removed
 async fn test_transaction_snapshot() {
     let key = "key".to_string();
     let path = temp_dir();
@@ -762,6 +889,7 @@ async fn test_transaction_snapshot() {
 }
 
 #[tokio::test]
+#[allow(deprecated)] // covers `DBTransaction` until it is removed
 async fn test_retry_transaction() {
     let key = "key".to_string();
     let path = temp_dir();
@@ -824,6 +952,7 @@ async fn test_retry_transaction() {
 }
 
 #[tokio::test]
+#[allow(deprecated)] // covers `DBTransaction` until it is removed
 async fn test_transaction_read_your_write() {
     let key1 = "key1";
     let key2 = "key2";
@@ -870,6 +999,117 @@ async fn test_transaction_read_your_write() {
     assert!(tx.commit().is_ok());
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_write_transaction_across_cf(#[values(true, false)] is_transactional: bool) {
+    let rocks = open_rocksdb(temp_dir(), &["First_CF", "Second_CF"], is_transactional);
+    let db_cf_1 =
+        DBMap::<i32, String>::reopen(&rocks, Some("First_CF"), &ReadWriteOptions::default())
+            .expect("Failed to open storage");
+    db_cf_1
+        .multi_insert((1..10).map(|i| (i, i.to_string())))
+        .unwrap();
+    let db_cf_2 =
+        DBMap::<i32, String>::reopen(&rocks, Some("Second_CF"), &ReadWriteOptions::default())
+            .expect("Failed to open storage");
+
+    let mut tx = db_cf_1.write_transaction();
+    tx.insert_batch(&db_cf_2, (1..10).map(|i| (i, i.to_string())))
+        .unwrap();
+    tx.delete_batch(&db_cf_1, 1..5).unwrap();
+    // Nothing is written before the commit.
+    assert!(db_cf_2.is_empty());
+    tx.commit().await.expect("Failed to commit");
+
+    assert_eq!(
+        db_cf_1.keys().collect::<Vec<_>>(),
+        (5..10).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        db_cf_2.keys().collect::<Vec<_>>(),
+        (1..10).collect::<Vec<_>>()
+    );
+
+    // Reads are checked by optimistic transaction databases only.
+    let mut tx = db_cf_1.write_transaction();
+    assert_eq!(
+        tx.get_for_update(&db_cf_1, &5).unwrap(),
+        Some("5".to_string())
+    );
+    tx.insert_batch(&db_cf_1, [(5, "55".to_string())]).unwrap();
+    assert_eq!(tx.commit().await.is_ok(), is_transactional);
+}
+
+#[tokio::test]
+async fn test_write_transaction_conflict() {
+    let key = "key".to_string();
+    let rocks = open_rocksdb(temp_dir(), &["cf"], true);
+    let db = DBMap::<String, String>::reopen(&rocks, Some("cf"), &ReadWriteOptions::default())
+        .expect("Failed to open storage");
+    db.insert(&key, &"1".to_string()).unwrap();
+
+    // A value read changing before the commit fails the transaction.
+    let mut tx = db.write_transaction();
+    assert_eq!(tx.get_for_update(&db, &key).unwrap(), Some("1".to_string()));
+    tx.insert_batch(&db, [(key.clone(), "2".to_string())])
+        .unwrap();
+    db.insert(&key, &"3".to_string()).unwrap();
+    assert!(matches!(
+        tx.commit().await,
+        Err(TypedStoreError::RetryableTransactionError)
+    ));
+    assert_eq!(db.get(&key).unwrap(), Some("3".to_string()));
+
+    // The transaction reads its own writes, which are not checked.
+    let mut tx = db.write_transaction();
+    tx.insert_batch(&db, [(key.clone(), "4".to_string())])
+        .unwrap();
+    assert_eq!(tx.get_for_update(&db, &key).unwrap(), Some("4".to_string()));
+    db.insert(&key, &"5".to_string()).unwrap();
+    tx.commit().await.unwrap();
+    assert_eq!(db.get(&key).unwrap(), Some("4".to_string()));
+
+    // Retried, the transaction reads the latest value.
+    let mut conflicts = 0;
+    retry_transaction!({
+        let mut tx = db.write_transaction();
+        let value = tx.get_for_update(&db, &key).unwrap().unwrap();
+        tx.insert_batch(&db, [(key.clone(), format!("{value}0"))])
+            .unwrap();
+        if conflicts < 3 {
+            db.insert(&key, &conflicts.to_string()).unwrap();
+        }
+        conflicts += 1;
+        tx.commit().await
+    })
+    .unwrap();
+    assert_eq!(db.get(&key).unwrap(), Some("20".to_string()));
+}
+
+#[tokio::test]
+async fn test_compaction_filter() {
+    let mut options = default_db_options();
+    options.set_compaction_filter("odd values", |_key: u32, value: String| {
+        value.parse::<u32>().unwrap() % 2 == 1
+    });
+    let rocks = open_cf_opts(
+        temp_dir(),
+        None,
+        MetricConf::default(),
+        &[("cf", &options.options)],
+    )
+    .unwrap();
+    let db = DBMap::<u32, String>::reopen(&rocks, Some("cf"), &options.rw_options)
+        .expect("Failed to open storage");
+    db.multi_insert((0..10).map(|i| (i, i.to_string())))
+        .unwrap();
+
+    // Entries are only filtered out when compacted.
+    assert_eq!(db.keys().count(), 10);
+    db.compact_range(&0, &10).unwrap();
+    assert_eq!(db.keys().collect::<Vec<_>>(), vec![0, 2, 4, 6, 8]);
+}
+
 #[tokio::test]
 async fn open_as_secondary_test() {
     let primary_path = temp_dir();
@@ -1085,3 +1325,244 @@ fn open_rocksdb<P: AsRef<Path>>(path: P, opt_cfs: &[&str], is_transactional: boo
         open_cf(path, None, MetricConf::default(), opt_cfs).expect("failed to open rocksdb")
     }
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+enum VersionedRecord {
+    V1(u64),
+    V2 { value: u64, doubled: u64 },
+}
+
+impl migration::VersionedValue for VersionedRecord {
+    const LATEST_VERSION: u32 = 2;
+
+    fn version(&self) -> u32 {
+        match self {
+            VersionedRecord::V1(_) => 1,
+            VersionedRecord::V2 { .. } => 2,
+        }
+    }
+
+    fn migrate(self) -> Self {
+        match self {
+            VersionedRecord::V1(value) => VersionedRecord::V2 {
+                value,
+                doubled: value * 2,
+            },
+            v2 => v2,
+        }
+    }
+}
+
+#[test]
+fn test_migrate_values() {
+    use migration::{MigrationStats, MigrationStep, SchemaMigrations};
+
+    let path = temp_dir();
+    let db = open_cf(&path, None, MetricConf::default(), &["versioned"]).unwrap();
+    let cf = db.cf_handle("versioned").unwrap();
+    let records = (0..100u32)
+        .map(|i| (i, VersionedRecord::V1(i as u64)))
+        .chain([(
+            100,
+            VersionedRecord::V2 {
+                value: 100,
+                doubled: 200,
+            },
+        )]);
+    for (key, value) in records {
+        let (key, value) = (
+            be_fix_int_ser(&key).unwrap(),
+            bcs::to_bytes(&value).unwrap(),
+        );
+        db.put_cf(&cf, key, value, &WriteOptions::default())
+            .unwrap();
+    }
+    drop(cf);
+    drop(db);
+
+    let migrations = || {
+        SchemaMigrations::new().register(MigrationStep::migrate_values::<VersionedRecord>(
+            2,
+            "double",
+            "versioned",
+        ))
+    };
+    let report = migrations().run(&path, None, None).unwrap();
+    let migrated = MigrationStats {
+        scanned: 101,
+        rewritten: 100,
+    };
+    assert_eq!(report.steps, vec![(2, migrated)]);
+
+    // Every value is in the latest format once the store is opened.
+    let db = open_cf(&path, None, MetricConf::default(), &["versioned"]).unwrap();
+    let cf = db.cf_handle("versioned").unwrap();
+    let mut count = 0;
+    for entry in db.iterator_cf(&cf, ReadOptions::default(), IteratorMode::Start) {
+        let (key, value) = entry.unwrap();
+        let key: u32 = bincode::DefaultOptions::new()
+            .with_big_endian()
+            .with_fixint_encoding()
+            .deserialize(&key)
+            .unwrap();
+        assert_eq!(
+            bcs::from_bytes::<VersionedRecord>(&value).unwrap(),
+            VersionedRecord::V2 {
+                value: key as u64,
+                doubled: key as u64 * 2
+            }
+        );
+        count += 1;
+    }
+    assert_eq!(count, 101);
+    drop(cf);
+    drop(db);
+
+    // Migrated stores are not migrated again.
+    let report = migrations().run(&path, None, None).unwrap();
+    assert!(report.steps.is_empty());
+}
+
+#[test]
+fn test_parse_cf_stats() {
+    let level_stats = "Level Files Size(MB)\n--------------------\n  0        4       12\n  1        2       40\n";
+    assert_eq!(
+        util::parse_level_stats(level_stats),
+        vec![(0, 4, 12), (1, 2, 40)]
+    );
+
+    let cf_stats = "\n** Compaction Stats [default] **\nLevel    Files   Size\n\
+        Stalls(count): 3 level0_slowdown, 0 level0_numfiles, 2 stop for pending_compaction_bytes, interval 5 total count\n";
+    assert_eq!(
+        util::parse_write_stalls(cf_stats),
+        vec![
+            ("level0_slowdown".to_string(), 3),
+            ("level0_numfiles".to_string(), 0),
+            ("stop_for_pending_compaction_bytes".to_string(), 2),
+        ]
+    );
+    assert!(util::parse_write_stalls("no stalls here").is_empty());
+}
+
+#[test]
+fn test_apply_tuning() {
+    let mut config = DBMapTableConfigMap::new(BTreeMap::from([(
+        "table".to_string(),
+        default_db_options(),
+    )]));
+    let tuning = BTreeMap::from([(
+        "table".to_string(),
+        DBTableTuning {
+            compression: Some(DBCompression::Zstd),
+            compression_level: Some(3),
+            block_cache_size_mb: Some(16),
+            bloom_filter_bits: Some(5.0),
+            write_buffer_size_mb: Some(32),
+        },
+    )]);
+    config.apply_tuning(&tuning).unwrap();
+
+    let missing = BTreeMap::from([("missing".to_string(), DBTableTuning::default())]);
+    assert!(matches!(
+        config.apply_tuning(&missing),
+        Err(TypedStoreError::UnregisteredColumn(table)) if table == "missing"
+    ));
+}
+
+#[test]
+fn test_schema_migrations() {
+    use migration::{MigrationStats, MigrationStep, SchemaMigrations};
+
+    let tables = |path: &Path| {
+        let mut tables = rocksdb::DB::list_cf(&rocksdb::Options::default(), path).unwrap();
+        tables.sort();
+        tables
+    };
+    let migrations = || {
+        SchemaMigrations::new()
+            .register(MigrationStep::drop_table(2, "drop legacy", "legacy"))
+            .register(MigrationStep::add_table(3, "add new", "new"))
+            .register(MigrationStep::rewrite_table(
+                4,
+                "lengths",
+                "table",
+                |key: u32, value: String| (key != 0).then_some(value.len() as u64),
+            ))
+    };
+
+    // A new store starts at the latest version.
+    let path = temp_dir().join("new");
+    let report = migrations().run(&path, None, None).unwrap();
+    assert_eq!((report.from_version, report.to_version), (4, 4));
+    assert_eq!(tables(&path), vec!["default"]);
+
+    let path = temp_dir();
+    let db = open_cf(&path, None, MetricConf::default(), &["table", "legacy"]).unwrap();
+    let cf = db.cf_handle("table").unwrap();
+    for (key, value) in [(0u32, "a"), (1, "bb"), (2, "ccc")] {
+        let (key, value) = (be_fix_int_ser(&key).unwrap(), bcs::to_bytes(value).unwrap());
+        db.put_cf(&cf, key, value, &WriteOptions::default())
+            .unwrap();
+    }
+    drop(cf);
+    drop(db);
+
+    let report = migrations().run(&path, None, None).unwrap();
+    assert_eq!((report.from_version, report.to_version), (1, 4));
+    let rewritten = MigrationStats {
+        scanned: 3,
+        rewritten: 3,
+    };
+    assert_eq!(
+        report.steps,
+        vec![
+            (2, MigrationStats::default()),
+            (3, MigrationStats::default()),
+            (4, rewritten)
+        ]
+    );
+    assert_eq!(tables(&path), vec!["default", "new", "table"]);
+    let report = migrations().run(&path, None, None).unwrap();
+    assert_eq!((report.from_version, report.to_version), (4, 4));
+    assert!(report.steps.is_empty());
+
+    // A failing step rolls the store back to the version it was at.
+    let failing = migrations()
+        .register(MigrationStep::drop_table(5, "drop new", "new"))
+        .register(MigrationStep::rewrite_table(
+            6,
+            "missing",
+            "missing",
+            |_: u32, value: u64| Some(value),
+        ));
+    assert!(matches!(
+        failing.run(&path, None, None),
+        Err(TypedStoreError::SchemaVersionError(_))
+    ));
+    assert_eq!(tables(&path), vec!["default", "new", "table"]);
+
+    let db = open_cf(&path, None, MetricConf::default(), &["new", "table"]).unwrap();
+    assert_eq!(migration::store_schema_version(&db).unwrap(), 4);
+    let cf = db.cf_handle("table").unwrap();
+    let entries: Vec<_> = db
+        .iterator_cf(&cf, ReadOptions::default(), IteratorMode::Start)
+        .map(|entry| {
+            let (key, value) = entry.unwrap();
+            (
+                bincode::DefaultOptions::new()
+                    .with_big_endian()
+                    .with_fixint_encoding()
+                    .deserialize::<u32>(&key)
+                    .unwrap(),
+                bcs::from_bytes::<u64>(&value).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(entries, vec![(1, 2), (2, 3)]);
+    drop(cf);
+    drop(db);
+
+    // Stores written by a newer binary are not opened.
+    let older = SchemaMigrations::new().register(MigrationStep::add_table(2, "add", "new"));
+    assert!(older.run(&path, None, None).is_err());
+}
@@ -46,3 +46,52 @@ fn deserialize_ref_count_value(bytes: &[u8]) -> (Option<&[u8]>, i64) {
     let ref_count = i64::from_le_bytes(rc_bytes.try_into().unwrap());
     (if value.is_empty() { None } else { Some(value) }, ref_count)
 }
+
+/// Parses the output of the `rocksdb.levelstats` property, which looks like
+///
+/// ```text
+/// Level Files Size(MB)
+/// --------------------
+///   0        4       12
+///   1        2       40
+/// ```
+///
+/// into `(level, number of files, size in MB)` tuples.
+pub(crate) fn parse_level_stats(level_stats: &str) -> Vec<(u32, i64, i64)> {
+    level_stats
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let level = columns.next()?.parse().ok()?;
+            let num_files = columns.next()?.parse().ok()?;
+            let size_mb = columns.next()?.parse::<f64>().ok()?;
+            Some((level, num_files, size_mb as i64))
+        })
+        .collect()
+}
+
+/// Extracts the cumulative write stall counts from the output of the
+/// `rocksdb.cfstats-no-file-histogram` property, from the line that looks like
+///
+/// ```text
+/// Stalls(count): 0 level0_slowdown, 0 level0_numfiles, 2 stop for pending_compaction_bytes, ...
+/// ```
+///
+/// Reasons are normalized to snake case, e.g. `stop_for_pending_compaction_bytes`.
+pub(crate) fn parse_write_stalls(cf_stats: &str) -> Vec<(String, i64)> {
+    let Some(stalls) = cf_stats
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Stalls(count):"))
+    else {
+        return vec![];
+    };
+    stalls
+        .split(',')
+        .filter_map(|entry| {
+            let (count, reason) = entry.trim().split_once(' ')?;
+            let count = count.parse().ok()?;
+            let reason = reason.split_whitespace().collect::<Vec<_>>().join("_");
+            Some((reason, count))
+        })
+        .collect()
+}
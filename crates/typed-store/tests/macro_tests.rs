@@ -340,6 +340,31 @@ async fn macro_test_configure() {
     assert_eq!(TABLE2_OPTIONS_SET_FLAG.lock().unwrap().len(), 6);
 }
 
+#[derive(DBMapUtils)]
+struct TablesConfiguredOptions {
+    #[default_options_override_fn = "flipped_range_deletions_options"]
+    table1: DBMap<i32, String>,
+    table2: DBMap<i32, String>,
+}
+
+fn flipped_range_deletions_options() -> typed_store::rocks::DBOptions {
+    let mut options = typed_store::rocks::default_db_options();
+    options.rw_options.ignore_range_deletions = !options.rw_options.ignore_range_deletions;
+    options
+}
+
+#[tokio::test]
+async fn macro_test_configurator_defaults() {
+    // The configurator starts from the options of each table, so that tweaking one option of a
+    // table keeps the others
+    let config = TablesConfiguredOptions::configurator();
+    let default = typed_store::rocks::default_db_options()
+        .rw_options
+        .ignore_range_deletions;
+    assert_eq!(config.table1.rw_options.ignore_range_deletions, !default);
+    assert_eq!(config.table2.rw_options.ignore_range_deletions, default);
+}
+
 /// We show that custom functions can be applied
 #[derive(DBMapUtils)]
 struct TablesMemUsage {
@@ -97,14 +97,16 @@ class CommandMaker:
                 f'--parameters {parameters} worker --id {id}')
 
     @staticmethod
-    def run_client(address, size, rate, nodes):
+    def run_client(address, size, rate, nodes, shape='constant'):
         assert isinstance(address, str)
         assert isinstance(size, int) and size > 0
         assert isinstance(rate, int) and rate >= 0
         assert isinstance(nodes, list)
         assert all(isinstance(x, str) for x in nodes)
+        assert isinstance(shape, str)
         nodes = f'--nodes {" ".join(nodes)}' if nodes else ''
-        return f'./narwhal-benchmark-client {address} --size {size} --rate {rate} {nodes}'
+        return (f'./narwhal-benchmark-client {address} --size {size} --rate {rate} '
+                f'--shape {shape} {nodes}')
 
     @staticmethod
     def alias_demo_binaries(origin):
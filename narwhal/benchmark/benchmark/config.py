@@ -280,6 +280,9 @@ class BenchParameters:
 
             self.tx_size = int(json['tx_size'])
 
+            # See the help of the benchmark client for the supported shapes.
+            self.traffic_shape = str(json.get('traffic_shape', 'constant'))
+
             self.duration = int(json['duration'])
 
             if 'failpoints' in json:
@@ -288,6 +291,13 @@ class BenchParameters:
                 self.failpoints = False
 
             self.runs = int(json['runs']) if 'runs' in json else 1
+
+            # Format of the machine-readable results exported for each run.
+            self.results_format = str(json.get('results_format', 'json'))
+            if self.results_format not in ('json', 'csv', 'none'):
+                raise ConfigError(
+                    f'Unknown results format {self.results_format}'
+                )
         except KeyError as e:
             raise ConfigError(f'Malformed bench parameters: missing key {e}')
 
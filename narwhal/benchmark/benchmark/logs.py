@@ -1,10 +1,13 @@
 # Copyright(C) Facebook, Inc. and its affiliates.
 # Copyright (c) Mysten Labs, Inc.
 # SPDX-License-Identifier: Apache-2.0
+from csv import writer
 from datetime import datetime, timezone
 from itertools import chain
 from dateutil import parser
 from glob import glob
+from json import dump
+from math import ceil
 from logging import exception
 from multiprocessing import Pool
 from os.path import join
@@ -20,6 +23,9 @@ class ParseError(Exception):
 
 
 class LogParser:
+    # Bumped whenever a field of the exported results is renamed or removed.
+    RESULTS_SCHEMA_VERSION = 1
+
     def __init__(self, clients, primaries, workers, faults=0):
         inputs = [clients, primaries, workers]
         assert all(isinstance(x, list) for x in inputs)
@@ -52,8 +58,9 @@ class LogParser:
         except (ValueError, IndexError, AttributeError) as e:
             exception(e)
             raise ParseError(f'Failed to parse nodes\' logs: {e}')
-        proposals, commits, self.configs, primary_ips, batch_to_header_latencies, header_creation_latencies, header_to_cert_latencies, cert_commit_latencies, request_vote_outbound_latencies = zip(
+        proposals, commits, self.configs, primary_ips, batch_to_header_latencies, header_creation_latencies, header_to_cert_latencies, cert_commit_latencies, request_vote_outbound_latencies, self.primary_errors = zip(
             *results)
+        self.proposals_per_primary = [len(x) for x in proposals]
         self.proposals = self._merge_results([x.items() for x in proposals])
         self.commits = self._merge_results([x.items() for x in commits])
         self.batch_to_header_latencies = {
@@ -78,8 +85,11 @@ class LogParser:
         except (ValueError, IndexError, AttributeError) as e:
             exception(e)
             raise ParseError(f'Failed to parse workers\' logs: {e}')
-        sizes, self.received_samples, workers_ips, batch_creation_latencies = zip(
+        sizes, self.received_samples, workers_ips, batch_creation_latencies, self.worker_errors = zip(
             *results)
+        self.batches_per_worker = [
+            (len(x), sum(x.values())) for x in sizes
+        ]
         self.sizes = {
             k: v for x in sizes for k, v in x.items() if k in self.commits
         }
@@ -186,7 +196,9 @@ class LogParser:
 
         ip = search(r'booted on (/ip4/\d+.\d+.\d+.\d+)', log).group(1)
 
-        return proposals, commits, configs, ip, batch_to_header_latencies, header_creation_latencies, header_to_cert_latencies, cert_commit_latencies, request_vote_outbound_latencies
+        errors = len(findall(r' ERROR ', log))
+
+        return proposals, commits, configs, ip, batch_to_header_latencies, header_creation_latencies, header_to_cert_latencies, cert_commit_latencies, request_vote_outbound_latencies, errors
 
     def _parse_workers(self, log):
         if search(r'(?:panicked)', log) is not None:
@@ -204,7 +216,9 @@ class LogParser:
 
         ip = search(r'booted on (/ip4/\d+.\d+.\d+.\d+)', log).group(1)
 
-        return sizes, samples, ip, batch_creation_latencies
+        errors = len(findall(r' ERROR ', log))
+
+        return sizes, samples, ip, batch_creation_latencies, errors
 
     def _to_posix(self, string):
         x = parser.parse(string[:24], ignoretz=True)
@@ -221,8 +235,11 @@ class LogParser:
         tps = bps / self.size[0]
         return tps, bps, duration
 
+    def _consensus_latencies(self):
+        return [c - self.proposals[d] for d, c in self.commits.items()]
+
     def _consensus_latency(self):
-        latency = [c - self.proposals[d] for d, c in self.commits.items()]
+        latency = self._consensus_latencies()
         return mean(latency) if latency else 0
 
     def _end_to_end_throughput(self):
@@ -235,17 +252,62 @@ class LogParser:
         tps = bps / self.size[0]
         return tps, bps, duration
 
-    def _end_to_end_latency(self):
-        latency = []
+    def _end_to_end_samples(self):
+        # The commit time and end-to-end latency of every sample transaction.
+        samples = []
         for sent, received in zip(self.sent_samples, self.received_samples):
             for tx_id, batch_id in received.items():
                 if batch_id in self.commits:
                     assert tx_id in sent  # We receive txs that we sent.
                     start = sent[tx_id]
                     end = self.commits[batch_id]
-                    latency += [end-start]
+                    samples += [(end, end-start)]
+        return samples
+
+    def _end_to_end_latency(self):
+        latency = [x for _, x in self._end_to_end_samples()]
         return mean(latency) if latency else 0
 
+    @staticmethod
+    def _percentiles(values):
+        # Nearest-rank percentiles, in ms.
+        values = sorted(values)
+
+        def at(q):
+            if not values:
+                return 0
+            return round(values[max(ceil(q * len(values)) - 1, 0)] * 1_000)
+
+        return {
+            'p50': at(0.5),
+            'p95': at(0.95),
+            'p99': at(0.99),
+            'p999': at(0.999),
+            'mean': round(mean(values) * 1_000) if values else 0,
+        }
+
+    def _timeline(self):
+        # End-to-end throughput and latency of every second of the benchmark.
+        if not self.commits:
+            return []
+        start = min(self.start)
+        committed_bytes, latencies = {}, {}
+        for digest, end in self.commits.items():
+            second = int(end - start)
+            committed_bytes[second] = committed_bytes.get(
+                second, 0) + self.sizes.get(digest, 0)
+        for end, latency in self._end_to_end_samples():
+            latencies.setdefault(int(end - start), []).append(latency)
+        return [
+            {
+                'elapsed_secs': second + 1,
+                'tps': committed_bytes.get(second, 0) / self.size[0],
+                'bps': committed_bytes.get(second, 0),
+                'latency_ms': self._percentiles(latencies.get(second, [])),
+            }
+            for second in range(max(committed_bytes) + 1)
+        ]
+
     def result(self):
         header_num_of_batches_threshold = self.configs[0]['header_num_of_batches_threshold']
         max_header_num_of_batches = self.configs[0]['max_header_num_of_batches']
@@ -316,11 +378,82 @@ class LogParser:
             '-----------------------------------------\n'
         )
 
+    def results(self):
+        ''' The results of the benchmark as a dict with a stable schema. '''
+        consensus_tps, consensus_bps, _ = self._consensus_throughput()
+        end_to_end_tps, end_to_end_bps, duration = self._end_to_end_throughput()
+        return {
+            'schema_version': self.RESULTS_SCHEMA_VERSION,
+            'config': {
+                'faults': self.faults,
+                'committee_size': self.committee_size,
+                'workers_per_node': self.workers,
+                'collocate': self.collocate,
+                'input_rate': sum(self.rate),
+                'tx_size': self.size[0],
+                **self.configs[0],
+            },
+            'duration_secs': duration,
+            'consensus': {
+                'tps': consensus_tps,
+                'bps': consensus_bps,
+                'latency_ms': self._percentiles(self._consensus_latencies()),
+            },
+            'end_to_end': {
+                'tps': end_to_end_tps,
+                'bps': end_to_end_bps,
+                'latency_ms': self._percentiles(
+                    [x for _, x in self._end_to_end_samples()]
+                ),
+            },
+            'errors': {
+                'client_rate_misses': self.misses,
+                'primary_errors': sum(self.primary_errors),
+                'worker_errors': sum(self.worker_errors),
+            },
+            'primaries': [
+                {'headers_proposed': proposals, 'errors': errors}
+                for proposals, errors in zip(self.proposals_per_primary, self.primary_errors)
+            ],
+            'workers': [
+                {'batches_created': batches, 'bytes': size, 'errors': errors}
+                for (batches, size), errors in zip(self.batches_per_worker, self.worker_errors)
+            ],
+            'timeline': self._timeline(),
+        }
+
     def print(self, filename):
         assert isinstance(filename, str)
         with open(filename, 'a') as f:
             f.write(self.result())
 
+    def export(self, filename):
+        ''' Write the results as CSV if the file name ends with .csv, or as JSON. '''
+        assert isinstance(filename, str)
+        results = self.results()
+        with open(filename, 'w', newline='') as f:
+            if not filename.endswith('.csv'):
+                dump(results, f, indent=2)
+                return
+
+            # One row per second of the benchmark followed by the totals.
+            columns = ['elapsed_secs', 'tps', 'bps']
+            percentiles = ['p50', 'p95', 'p99', 'p999']
+            csv = writer(f)
+            csv.writerow(
+                ['scope'] + columns + [f'latency_{x}_ms' for x in percentiles]
+            )
+            for second in results['timeline']:
+                csv.writerow(
+                    ['interval'] + [second[x] for x in columns]
+                    + [second['latency_ms'][x] for x in percentiles]
+                )
+            total = results['end_to_end']
+            csv.writerow(
+                ['total', results['duration_secs'], total['tps'], total['bps']]
+                + [total['latency_ms'][x] for x in percentiles]
+            )
+
     @classmethod
     def process(cls, directory, faults=0):
         assert isinstance(directory, str)
@@ -283,7 +283,8 @@ class Bench:
                     address,
                     bench_parameters.tx_size,
                     rate_share,
-                    [x for y in workers_addresses for _, x in y]
+                    [x for y in workers_addresses for _, x in y],
+                    bench_parameters.traffic_shape
                 )
                 log_file = PathMaker.client_log_file(i, id)
                 self._background_run(host, cmd, log_file)
@@ -429,6 +430,17 @@ class Bench:
                             r,
                             bench_parameters.tx_size,
                         ))
+                        if bench_parameters.results_format != 'none':
+                            logger.export(PathMaker.result_export_file(
+                                faults,
+                                n,
+                                bench_parameters.workers,
+                                bench_parameters.collocate,
+                                r,
+                                bench_parameters.tx_size,
+                                i,
+                                bench_parameters.results_format,
+                            ))
                     except (subprocess.SubprocessError, GroupException, ParseError) as e:
                         self.kill(hosts=selected_hosts)
                         if isinstance(e, GroupException):
@@ -103,6 +103,13 @@ class PathMaker:
             f'bench-{faults}-{nodes}-{workers}-{collocate}-{rate}-{tx_size}.txt'
         )
 
+    @staticmethod
+    def result_export_file(faults, nodes, workers, collocate, rate, tx_size, run, format):
+        return join(
+            PathMaker.results_path(),
+            f'bench-{faults}-{nodes}-{workers}-{collocate}-{rate}-{tx_size}-{run}.{format}'
+        )
+
     @staticmethod
     def plots_path():
         return 'plots'
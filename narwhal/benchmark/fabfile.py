@@ -14,7 +14,7 @@ from benchmark.remote import Bench, BenchError
 
 
 @task
-def local(ctx, debug=True):
+def local(ctx, debug=True, export=''):
     ''' Run benchmarks on localhost '''
     bench_params = {
         'faults': 0,
@@ -58,6 +58,8 @@ def local(ctx, debug=True):
     try:
         ret = LocalBench(bench_params, node_params).run(debug)
         print(ret.result())
+        if export:
+            ret.export(export)
     except BenchError as e:
         Print.error(e)
 
@@ -361,9 +363,12 @@ def kill(ctx):
 
 
 @task
-def logs(ctx):
+def logs(ctx, export=''):
     ''' Print a summary of the logs '''
     try:
-        print(LogParser.process('./logs', faults='?').result())
+        ret = LogParser.process('./logs', faults='?')
+        print(ret.result())
+        if export:
+            ret.export(export)
     except ParseError as e:
         Print.error(BenchError('Failed to parse logs', e))
@@ -175,6 +175,10 @@ pub struct Parameters {
     /// Anemo network settings.
     #[serde(default = "AnemoParameters::default")]
     pub anemo: AnemoParameters,
+    /// Per-client rate limit for transactions submitted to the workers' gRPC endpoint. If
+    /// unspecified, submissions are not rate limited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_submission_rate_limit: Option<TxSubmissionRateLimitParameters>,
 }
 
 impl Parameters {
@@ -265,6 +269,16 @@ pub struct AnemoParameters {
     /// If unspecified, this will default to 8 MiB.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub excessive_message_size: Option<usize>,
+
+    /// QUIC transport settings for the primary network. If unspecified, the built-in defaults
+    /// are used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_quic: Option<QuicParameters>,
+
+    /// QUIC transport settings for the worker network. If unspecified, the built-in defaults
+    /// are used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker_quic: Option<QuicParameters>,
 }
 
 impl AnemoParameters {
@@ -276,6 +290,52 @@ impl AnemoParameters {
     }
 }
 
+/// Per-network QUIC transport settings. Unset values fall back to the defaults of the network.
+///
+/// Only the settings of the pinned anemo `QuicConfig` are exposed. It has no setting for the
+/// congestion controller, ECN or connection migration, so ECN-aware congestion control and
+/// connection migration can't be configured here and those keep the transport defaults.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct QuicParameters {
+    /// Maximum time a connection may stay idle before it is closed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_idle_timeout_ms: Option<u64>,
+    /// Interval between keep-alive packets on otherwise idle connections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive_interval_ms: Option<u64>,
+    /// Per-stream flow control window in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_receive_window: Option<u64>,
+    /// Per-connection flow control window in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receive_window: Option<u64>,
+    /// Per-connection send window in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_window: Option<u64>,
+}
+
+impl QuicParameters {
+    /// Applies these settings on top of the given QUIC config.
+    pub fn apply(&self, quic_config: &mut anemo::QuicConfig) {
+        if let Some(max_idle_timeout_ms) = self.max_idle_timeout_ms {
+            quic_config.max_idle_timeout_ms = Some(max_idle_timeout_ms);
+        }
+        if let Some(keep_alive_interval_ms) = self.keep_alive_interval_ms {
+            quic_config.keep_alive_interval_ms = Some(keep_alive_interval_ms);
+        }
+        if let Some(stream_receive_window) = self.stream_receive_window {
+            quic_config.stream_receive_window = Some(stream_receive_window);
+        }
+        if let Some(receive_window) = self.receive_window {
+            quic_config.receive_window = Some(receive_window);
+        }
+        if let Some(send_window) = self.send_window {
+            quic_config.send_window = Some(send_window);
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PrometheusMetricsParameters {
     /// Socket address the server should be listening to.
@@ -302,6 +362,24 @@ impl PrometheusMetricsParameters {
     }
 }
 
+/// Token bucket settings applied to each client (identified by its source IP address) of the
+/// workers' transactions endpoint.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct TxSubmissionRateLimitParameters {
+    /// Sustained number of transactions per second accepted from a single client.
+    pub transactions_per_second: NonZeroU32,
+    /// Number of transactions a client can submit in a burst. Defaults to
+    /// `transactions_per_second`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst_size: Option<NonZeroU32>,
+}
+
+impl TxSubmissionRateLimitParameters {
+    pub fn burst_size(&self) -> NonZeroU32 {
+        self.burst_size.unwrap_or(self.transactions_per_second)
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ConsensusAPIGrpcParameters {
     /// Socket address the server should be listening to.
@@ -431,6 +509,7 @@ impl Default for Parameters {
             prometheus_metrics: PrometheusMetricsParameters::default(),
             network_admin_server: NetworkAdminServerParameters::default(),
             anemo: AnemoParameters::default(),
+            tx_submission_rate_limit: None,
         }
     }
 }
@@ -531,6 +610,19 @@ impl Parameters {
             self.network_admin_server
                 .worker_network_admin_server_base_port
         );
+        if let Some(quic) = &self.anemo.primary_quic {
+            info!("Primary network QUIC parameters set to {:?}", quic);
+        }
+        if let Some(quic) = &self.anemo.worker_quic {
+            info!("Worker network QUIC parameters set to {:?}", quic);
+        }
+        if let Some(rate_limit) = &self.tx_submission_rate_limit {
+            info!(
+                "Transaction submission rate limit set to {} tx/s per client (burst {})",
+                rate_limit.transactions_per_second,
+                rate_limit.burst_size()
+            );
+        }
     }
 }
 
@@ -20,7 +20,7 @@
 
 use config::{
     ConsensusAPIGrpcParameters, Import, NetworkAdminServerParameters, Parameters,
-    PrometheusMetricsParameters, Stake,
+    PrometheusMetricsParameters, QuicParameters, Stake,
 };
 use crypto::PublicKey;
 use insta::assert_json_snapshot;
@@ -234,6 +234,36 @@ fn parameters_import_snapshot_matches() {
     assert_json_snapshot!("parameters_import", params)
 }
 
+#[test]
+fn parameters_import_quic_per_network() {
+    let input = r#"{
+         "anemo": {
+             "worker_quic": {
+                 "max_idle_timeout_ms": 60000,
+                 "keep_alive_interval_ms": 1000
+             }
+         }
+      }"#;
+
+    let dir = tempdir().expect("Couldn't create tempdir");
+    let file_path = dir.path().join("temp-properties.json");
+    let mut file = File::create(file_path.clone()).expect("Couldn't create temp file");
+    writeln!(file, "{input}").expect("Couldn't write to file");
+
+    let params = Parameters::import(file_path.to_str().unwrap())
+        .expect("Failed to import given Parameters json");
+
+    assert_eq!(params.anemo.primary_quic, None);
+    assert_eq!(
+        params.anemo.worker_quic,
+        Some(QuicParameters {
+            max_idle_timeout_ms: Some(60_000),
+            keep_alive_interval_ms: Some(1000),
+            ..QuicParameters::default()
+        })
+    );
+}
+
 #[test]
 fn commmittee_snapshot_matches() {
     // The shape of this configuration is load-bearing in the NW benchmarks,
@@ -65,7 +65,7 @@ pub fn spawn_subscriber<State: ExecutionState + Send + Sync + 'static>(
     // Some cleanup is needed
 
     let (tx_notifier, rx_notifier) =
-        metered_channel::channel(primary::CHANNEL_CAPACITY, &metrics.tx_notifier);
+        metered_channel::monitored_channel("tx_notifier", primary::CHANNEL_CAPACITY);
 
     let rx_shutdown_notify = shutdown_receivers
         .pop()
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::metrics::NetworkConnectionMetrics;
+use crate::peer_quality::PeerQuality;
 use anemo::PeerId;
 use dashmap::DashMap;
 use mysten_metrics::spawn_logged_monitored_task;
@@ -19,6 +20,7 @@ pub struct ConnectionMonitor {
     network: anemo::NetworkRef,
     connection_metrics: NetworkConnectionMetrics,
     peer_id_types: HashMap<PeerId, String>,
+    peer_quality: Option<Arc<PeerQuality>>,
     connection_statuses: Arc<DashMap<PeerId, ConnectionStatus>>,
 }
 
@@ -28,6 +30,7 @@ impl ConnectionMonitor {
         network: anemo::NetworkRef,
         connection_metrics: NetworkConnectionMetrics,
         peer_id_types: HashMap<PeerId, String>,
+        peer_quality: Option<Arc<PeerQuality>>,
     ) -> (JoinHandle<()>, Arc<DashMap<PeerId, ConnectionStatus>>) {
         let connection_statuses_outer = Arc::new(DashMap::new());
         let connection_statuses = connection_statuses_outer.clone();
@@ -37,6 +40,7 @@ impl ConnectionMonitor {
                     network,
                     connection_metrics,
                     peer_id_types,
+                    peer_quality,
                     connection_statuses,
                 }
                 .run(),
@@ -113,6 +117,10 @@ impl ConnectionMonitor {
                 .set(int_status);
         }
 
+        if let Some(peer_quality) = &self.peer_quality {
+            peer_quality.record_connection_change(peer_id, &connection_status);
+        }
+
         self.connection_statuses.insert(peer_id, connection_status);
     }
 }
@@ -145,7 +153,7 @@ mod tests {
 
         // WHEN bring up the monitor
         let (_h, statuses) =
-            ConnectionMonitor::spawn(network_1.downgrade(), metrics.clone(), HashMap::new());
+            ConnectionMonitor::spawn(network_1.downgrade(), metrics.clone(), HashMap::new(), None);
 
         // THEN peer 2 should be already connected
         assert_network_peers(metrics.clone(), 1).await;
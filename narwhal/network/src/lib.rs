@@ -16,6 +16,7 @@ pub mod epoch_filter;
 pub mod failpoints;
 pub mod metrics;
 mod p2p;
+pub mod peer_quality;
 mod retry;
 mod traits;
 
@@ -0,0 +1,418 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-peer network quality.
+//!
+//! `PeerQuality` tracks, for every peer of an anemo network, the round-trip time of the requests
+//! sent to it, the rate of failed requests in both directions, the bytes exchanged and how often
+//! its connection goes up and down. These are exported as metrics labeled by peer, and combined
+//! into a quality score between 0 and 1 that callers picking peers to fetch from use to try the
+//! best ones first. Requests are recorded by the `PeerQualityMakeCallbackHandler` installed in the
+//! inbound and outbound layers of the network, and connection changes by the `ConnectionMonitor`.
+
+use crate::connectivity::ConnectionStatus;
+use anemo::PeerId;
+use anemo_tower::callback::{MakeCallbackHandler, ResponseHandler};
+use dashmap::DashMap;
+use mysten_metrics::overrides::metrics_overrides;
+use prometheus::{
+    register_gauge_vec_with_registry, register_int_counter_vec_with_registry, GaugeVec,
+    IntCounterVec, Registry,
+};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Weight of the latest sample in the moving averages of the RTT and of the error rate.
+const EWMA_WEIGHT: f64 = 0.1;
+/// RTT at which the latency factor of the score is halved.
+const REFERENCE_RTT: Duration = Duration::from_millis(200);
+/// Period after which a connection change counts half as much in the churn factor of the score.
+const CHURN_HALF_LIFE: Duration = Duration::from_secs(300);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl Direction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Inbound => "inbound",
+            Direction::Outbound => "outbound",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PeerQualityMetrics {
+    /// Moving average of the round-trip time of the requests sent to a peer
+    peer_rtt: GaugeVec,
+    /// Requests exchanged with a peer, by direction
+    peer_requests: IntCounterVec,
+    /// Failed requests exchanged with a peer, by direction
+    peer_request_errors: IntCounterVec,
+    /// Bytes sent to a peer, by direction of the request
+    peer_bytes_sent: IntCounterVec,
+    /// Bytes received from a peer, by direction of the request
+    peer_bytes_received: IntCounterVec,
+    /// Connections and disconnections of a peer
+    peer_connection_changes: IntCounterVec,
+    /// Quality score of a peer, between 0 and 1
+    peer_quality_score: GaugeVec,
+}
+
+impl PeerQualityMetrics {
+    /// The metrics are labeled by peer, and can be disabled with the metrics overrides.
+    pub fn new(node: &'static str, registry: &Registry) -> Self {
+        let overrides = metrics_overrides();
+        Self {
+            peer_rtt: register_gauge_vec_with_registry!(
+                format!("{node}_peer_rtt_seconds"),
+                "Moving average of the round-trip time of the requests sent to a peer",
+                &["peer_id"],
+                overrides.registry(&format!("{node}_peer_rtt_seconds"), registry)
+            )
+            .unwrap(),
+            peer_requests: register_int_counter_vec_with_registry!(
+                format!("{node}_peer_requests"),
+                "The number of requests exchanged with a peer",
+                &["peer_id", "direction"],
+                overrides.registry(&format!("{node}_peer_requests"), registry)
+            )
+            .unwrap(),
+            peer_request_errors: register_int_counter_vec_with_registry!(
+                format!("{node}_peer_request_errors"),
+                "The number of failed requests exchanged with a peer",
+                &["peer_id", "direction"],
+                overrides.registry(&format!("{node}_peer_request_errors"), registry)
+            )
+            .unwrap(),
+            peer_bytes_sent: register_int_counter_vec_with_registry!(
+                format!("{node}_peer_bytes_sent"),
+                "The number of bytes sent to a peer",
+                &["peer_id", "direction"],
+                overrides.registry(&format!("{node}_peer_bytes_sent"), registry)
+            )
+            .unwrap(),
+            peer_bytes_received: register_int_counter_vec_with_registry!(
+                format!("{node}_peer_bytes_received"),
+                "The number of bytes received from a peer",
+                &["peer_id", "direction"],
+                overrides.registry(&format!("{node}_peer_bytes_received"), registry)
+            )
+            .unwrap(),
+            peer_connection_changes: register_int_counter_vec_with_registry!(
+                format!("{node}_peer_connection_changes"),
+                "The number of connections and disconnections of a peer",
+                &["peer_id", "event"],
+                overrides.registry(&format!("{node}_peer_connection_changes"), registry)
+            )
+            .unwrap(),
+            peer_quality_score: register_gauge_vec_with_registry!(
+                format!("{node}_peer_quality_score"),
+                "Quality score of a peer, between 0 (worst) and 1 (best)",
+                &["peer_id"],
+                overrides.registry(&format!("{node}_peer_quality_score"), registry)
+            )
+            .unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct PeerStats {
+    rtt: Option<Duration>,
+    error_rate: f64,
+    churn: f64,
+    churn_updated: Instant,
+}
+
+impl PeerStats {
+    fn new(now: Instant) -> Self {
+        Self {
+            rtt: None,
+            error_rate: 0.0,
+            churn: 0.0,
+            churn_updated: now,
+        }
+    }
+
+    fn record_rtt(&mut self, rtt: Duration) {
+        self.rtt = Some(match self.rtt {
+            Some(average) => average.mul_f64(1.0 - EWMA_WEIGHT) + rtt.mul_f64(EWMA_WEIGHT),
+            None => rtt,
+        });
+    }
+
+    fn record_outcome(&mut self, success: bool) {
+        let sample = if success { 0.0 } else { 1.0 };
+        self.error_rate = self.error_rate * (1.0 - EWMA_WEIGHT) + sample * EWMA_WEIGHT;
+    }
+
+    fn decayed_churn(&self, now: Instant) -> f64 {
+        let half_lives =
+            now.duration_since(self.churn_updated).as_secs_f64() / CHURN_HALF_LIFE.as_secs_f64();
+        self.churn * 0.5f64.powf(half_lives)
+    }
+
+    fn record_connection_change(&mut self, now: Instant) {
+        self.churn = self.decayed_churn(now) + 1.0;
+        self.churn_updated = now;
+    }
+
+    /// Product of the latency, error and churn factors, each between 0 and 1. Unknown RTTs count
+    /// as good, so that new peers get tried.
+    fn score(&self, now: Instant) -> f64 {
+        let latency = self.rtt.map_or(1.0, |rtt| {
+            1.0 / (1.0 + rtt.as_secs_f64() / REFERENCE_RTT.as_secs_f64())
+        });
+        // The first connection of a peer is not churn.
+        let churn = (self.decayed_churn(now) - 1.0).max(0.0);
+        latency * (1.0 - self.error_rate) / (1.0 + churn)
+    }
+}
+
+pub struct PeerQuality {
+    metrics: PeerQualityMetrics,
+    peers: DashMap<PeerId, PeerStats>,
+}
+
+impl PeerQuality {
+    pub fn new(metrics: PeerQualityMetrics) -> Self {
+        Self {
+            metrics,
+            peers: DashMap::new(),
+        }
+    }
+
+    fn update(&self, peer: PeerId, now: Instant, f: impl FnOnce(&mut PeerStats)) {
+        let mut stats = self
+            .peers
+            .entry(peer)
+            .or_insert_with(|| PeerStats::new(now));
+        f(&mut stats);
+        self.metrics
+            .peer_quality_score
+            .with_label_values(&[&peer.to_string()])
+            .set(stats.score(now));
+    }
+
+    /// Records a request exchanged with a peer. The RTT is only measured on outbound requests, as
+    /// the latency of inbound requests is our own processing time.
+    pub fn record_request(
+        &self,
+        peer: PeerId,
+        direction: Direction,
+        latency: Duration,
+        bytes_sent: usize,
+        bytes_received: usize,
+        success: bool,
+    ) {
+        let peer_label = peer.to_string();
+        let labels = [peer_label.as_str(), direction.as_str()];
+        self.metrics.peer_requests.with_label_values(&labels).inc();
+        if !success {
+            self.metrics
+                .peer_request_errors
+                .with_label_values(&labels)
+                .inc();
+        }
+        self.metrics
+            .peer_bytes_sent
+            .with_label_values(&labels)
+            .inc_by(bytes_sent as u64);
+        self.metrics
+            .peer_bytes_received
+            .with_label_values(&labels)
+            .inc_by(bytes_received as u64);
+
+        self.update(peer, Instant::now(), |stats| {
+            stats.record_outcome(success);
+            if direction == Direction::Outbound && success {
+                stats.record_rtt(latency);
+                self.metrics
+                    .peer_rtt
+                    .with_label_values(&[&peer_label])
+                    .set(stats.rtt.unwrap_or_default().as_secs_f64());
+            }
+        });
+    }
+
+    pub fn record_connection_change(&self, peer: PeerId, status: &ConnectionStatus) {
+        let event = match status {
+            ConnectionStatus::Connected => "connected",
+            ConnectionStatus::Disconnected => "disconnected",
+        };
+        self.metrics
+            .peer_connection_changes
+            .with_label_values(&[&peer.to_string(), event])
+            .inc();
+        let now = Instant::now();
+        self.update(peer, now, |stats| stats.record_connection_change(now));
+    }
+
+    /// Quality score of a peer, between 0 and 1. Peers without any history get the best score.
+    pub fn score(&self, peer: &PeerId) -> f64 {
+        self.peers
+            .get(peer)
+            .map_or(1.0, |stats| stats.score(Instant::now()))
+    }
+
+    /// Sorts peers from the best to the worst score. The sort is stable, so peers with the same
+    /// score keep their order, e.g. a random one.
+    pub fn sort_by_score<T>(&self, peers: &mut [T], peer_id: impl Fn(&T) -> PeerId) {
+        let now = Instant::now();
+        let score = |peer: &T| {
+            self.peers
+                .get(&peer_id(peer))
+                .map_or(1.0, |stats| stats.score(now))
+        };
+        peers.sort_by(|a, b| score(b).total_cmp(&score(a)));
+    }
+}
+
+#[derive(Clone)]
+pub struct PeerQualityMakeCallbackHandler {
+    quality: Arc<PeerQuality>,
+    direction: Direction,
+}
+
+impl PeerQualityMakeCallbackHandler {
+    pub fn new(quality: Arc<PeerQuality>, direction: Direction) -> Self {
+        Self { quality, direction }
+    }
+}
+
+impl MakeCallbackHandler for PeerQualityMakeCallbackHandler {
+    type Handler = PeerQualityResponseHandler;
+
+    fn make_handler(&self, request: &anemo::Request<bytes::Bytes>) -> Self::Handler {
+        PeerQualityResponseHandler {
+            quality: self.quality.clone(),
+            direction: self.direction,
+            peer: request.peer_id().copied(),
+            request_size: request.body().len(),
+            start: Instant::now(),
+        }
+    }
+}
+
+pub struct PeerQualityResponseHandler {
+    quality: Arc<PeerQuality>,
+    direction: Direction,
+    peer: Option<PeerId>,
+    request_size: usize,
+    start: Instant,
+}
+
+impl ResponseHandler for PeerQualityResponseHandler {
+    fn on_response(self, response: &anemo::Response<bytes::Bytes>) {
+        let Some(peer) = self.peer else {
+            return;
+        };
+        let response_size = response.body().len();
+        let (bytes_sent, bytes_received) = match self.direction {
+            Direction::Outbound => (self.request_size, response_size),
+            Direction::Inbound => (response_size, self.request_size),
+        };
+        self.quality.record_request(
+            peer,
+            self.direction,
+            self.start.elapsed(),
+            bytes_sent,
+            bytes_received,
+            response.status().is_success(),
+        );
+    }
+
+    fn on_error<E>(self, _error: &E) {
+        let Some(peer) = self.peer else {
+            return;
+        };
+        let (bytes_sent, bytes_received) = match self.direction {
+            Direction::Outbound => (self.request_size, 0),
+            Direction::Inbound => (0, self.request_size),
+        };
+        self.quality.record_request(
+            peer,
+            self.direction,
+            self.start.elapsed(),
+            bytes_sent,
+            bytes_received,
+            false,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_quality() -> PeerQuality {
+        PeerQuality::new(PeerQualityMetrics::new("test", &Registry::new()))
+    }
+
+    #[test]
+    fn test_peer_score() {
+        let quality = peer_quality();
+        let fast = PeerId([1; 32]);
+        let slow = PeerId([2; 32]);
+        let failing = PeerId([3; 32]);
+        let unknown = PeerId([4; 32]);
+
+        for _ in 0..10 {
+            let rtt = Duration::from_millis(10);
+            quality.record_request(fast, Direction::Outbound, rtt, 10, 10, true);
+            let rtt = Duration::from_millis(500);
+            quality.record_request(slow, Direction::Outbound, rtt, 10, 10, true);
+            let rtt = Duration::from_millis(10);
+            quality.record_request(failing, Direction::Outbound, rtt, 10, 0, false);
+        }
+
+        assert_eq!(quality.score(&unknown), 1.0);
+        assert!(quality.score(&fast) > quality.score(&slow));
+        assert!(quality.score(&fast) > quality.score(&failing));
+
+        let mut peers = vec![failing, unknown, slow, fast];
+        quality.sort_by_score(&mut peers, |peer| *peer);
+        assert_eq!(peers[0], unknown);
+        assert_eq!(peers[1], fast);
+    }
+
+    #[test]
+    fn test_inbound_requests_do_not_measure_rtt() {
+        let quality = peer_quality();
+        let peer = PeerId([1; 32]);
+        quality.record_request(
+            peer,
+            Direction::Inbound,
+            Duration::from_secs(10),
+            10,
+            10,
+            true,
+        );
+        assert_eq!(quality.score(&peer), 1.0);
+    }
+
+    #[test]
+    fn test_connection_churn() {
+        let quality = peer_quality();
+        let stable = PeerId([1; 32]);
+        let flapping = PeerId([2; 32]);
+
+        quality.record_connection_change(stable, &ConnectionStatus::Connected);
+        assert_eq!(quality.score(&stable), 1.0);
+
+        for _ in 0..5 {
+            quality.record_connection_change(flapping, &ConnectionStatus::Connected);
+            quality.record_connection_change(flapping, &ConnectionStatus::Disconnected);
+        }
+        assert!(quality.score(&flapping) < 0.2);
+
+        // Churn decays over time.
+        let stats = quality.peers.get(&flapping).unwrap().clone();
+        let later = Instant::now() + CHURN_HALF_LIFE * 10;
+        assert!(stats.score(later) > 0.99);
+    }
+}
@@ -6,6 +6,7 @@ use clap::{crate_name, crate_version, App, AppSettings};
 use eyre::Context;
 use futures::{future::join_all, StreamExt};
 use rand::Rng;
+use std::fmt;
 use tokio::{
     net::TcpStream,
     time::{interval, sleep, Duration, Instant},
@@ -25,12 +26,20 @@ async fn main() -> Result<(), eyre::Report> {
         * the worker address <ADDR> to send the transactions to. A url format is expected ex http://127.0.0.1:7000\n\
         * the rate of sending transactions via the --rate parameter\n\
         \n\
+        Optionally the --shape parameter changes how the transactions are spread over time:\n\
+        * constant (default): a constant rate\n\
+        * bursts:<ON_MS>:<OFF_MS>: send at the rate for ON_MS, then pause for OFF_MS\n\
+        * sine:<PERIOD_S>:<AMPLITUDE_PERCENT>: a rate oscillating around the given rate with the given period\n\
+        * replay:<PATH>: replay the arrival timestamps (in ms, one per line) recorded in PATH, sped up or slowed down\n\
+          so their mean rate matches the given rate, and looped when exhausted\n\
+        \n\
         Optionally the --nodes parameter can be passed where a list (comma separated string) of worker addresses\n\
         should be passed. The benchmarking client will first try to connect to all of those nodes before start sending\n\
         any transactions. That confirms the system is up and running and ready to start processing the transactions.")
         .args_from_usage("<ADDR> 'The network address of the node where to send txs. A url format is expected ex http://127.0.0.1:7000'")
         .args_from_usage("--size=<INT> 'The size of each transaction in bytes'")
         .args_from_usage("--rate=<INT> 'The rate (txs/s) at which to send the transactions'")
+        .args_from_usage("--shape=[SHAPE] 'How the transactions are spread over time, see the long help'")
         .args_from_usage("--nodes=[ADDR]... 'Network addresses, comma separated, that must be reachable before starting the benchmark.'")
         .setting(AppSettings::ArgRequiredElseHelp)
         .get_matches();
@@ -67,6 +76,7 @@ async fn main() -> Result<(), eyre::Report> {
         .unwrap()
         .parse::<u64>()
         .context("The rate of transactions must be a non-negative integer")?;
+    let shape = TrafficShape::parse(matches.value_of("shape").unwrap_or("constant"), rate)?;
     let nodes = matches
         .values_of("nodes")
         .unwrap_or_default()
@@ -81,12 +91,15 @@ async fn main() -> Result<(), eyre::Report> {
     info!("Transactions size: {size} B");
 
     // NOTE: This log entry is used to compute performance.
-    info!("Transactions rate: {rate} tx/s");
+    info!("Transactions rate: {} tx/s", shape.mean_rate(rate));
+
+    info!("Traffic shape: {shape}");
 
     let client = Client {
         target,
         size,
         rate,
+        shape,
         nodes,
     };
 
@@ -97,10 +110,186 @@ async fn main() -> Result<(), eyre::Report> {
     client.send().await.context("Failed to submit transactions")
 }
 
+/// How the transactions of the client are spread over time.
+#[derive(Debug)]
+enum TrafficShape {
+    /// Send at the rate, all the time.
+    Constant,
+    /// Alternate between sending at the rate for `on` and not sending for `off`.
+    Bursts { on: Duration, off: Duration },
+    /// Oscillate around the rate by up to `amplitude` times the rate, e.g. to mimic diurnal load.
+    Sine { period: Duration, amplitude: f64 },
+    /// Send a transaction at every arrival offset, starting over every `length`.
+    Replay {
+        arrivals: Vec<Duration>,
+        length: Duration,
+    },
+}
+
+impl TrafficShape {
+    fn parse(shape: &str, rate: u64) -> Result<Self, eyre::Report> {
+        let parts: Vec<_> = shape.split(':').collect();
+        let shape = match parts.as_slice() {
+            ["constant"] => Self::Constant,
+            ["bursts", on_ms, off_ms] => Self::Bursts {
+                on: Duration::from_millis(on_ms.parse().context("Invalid burst duration")?),
+                off: Duration::from_millis(off_ms.parse().context("Invalid pause duration")?),
+            },
+            ["sine", period_secs, amplitude_percent] => {
+                let period = Duration::from_secs(period_secs.parse().context("Invalid period")?);
+                let amplitude: u64 = amplitude_percent.parse().context("Invalid amplitude")?;
+                if period.is_zero() || amplitude > 100 {
+                    return Err(eyre::Report::msg(
+                        "The period must be positive and the amplitude at most 100%",
+                    ));
+                }
+                Self::Sine {
+                    period,
+                    amplitude: amplitude as f64 / 100.0,
+                }
+            }
+            ["replay", path] => Self::replay(path, rate)?,
+            _ => return Err(eyre::Report::msg(format!("Unknown traffic shape {shape}"))),
+        };
+        if let Self::Bursts { on, .. } = shape {
+            if on.is_zero() {
+                return Err(eyre::Report::msg("The burst duration must be positive"));
+            }
+        }
+        Ok(shape)
+    }
+
+    /// Reads the arrival timestamps recorded at `path` and rescales them to `rate`.
+    fn replay(path: &str, rate: u64) -> Result<Self, eyre::Report> {
+        let recording = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the arrivals recorded in {path}"))?;
+        let mut timestamps = recording
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .context("Arrival timestamps must be non-negative integers")?;
+        timestamps.sort_unstable();
+        let (Some(first), Some(last)) = (timestamps.first().copied(), timestamps.last().copied())
+        else {
+            return Err(eyre::Report::msg("The recording has no arrivals"));
+        };
+        if timestamps.len() < 2 || last == first {
+            return Err(eyre::Report::msg(
+                "The recording must span more than one millisecond",
+            ));
+        }
+
+        // The recording loops, so it lasts an extra average gap after its last arrival.
+        let span = (last - first) as f64;
+        let recorded_length_ms = span + span / (timestamps.len() - 1) as f64;
+        let recorded_rate = timestamps.len() as f64 * 1000.0 / recorded_length_ms;
+        let speedup = recorded_rate / rate as f64;
+        let scale = |ms: f64| Duration::from_secs_f64(ms * speedup / 1000.0);
+        Ok(Self::Replay {
+            arrivals: timestamps
+                .into_iter()
+                .map(|timestamp| scale((timestamp - first) as f64))
+                .collect(),
+            length: scale(recorded_length_ms),
+        })
+    }
+
+    /// The average rate of the shape when the target rate is `rate`.
+    fn mean_rate(&self, rate: u64) -> u64 {
+        match self {
+            Self::Bursts { on, off } => {
+                (rate as u128 * on.as_millis() / (*on + *off).as_millis()) as u64
+            }
+            Self::Constant | Self::Sine { .. } | Self::Replay { .. } => rate,
+        }
+    }
+}
+
+impl fmt::Display for TrafficShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Constant => write!(f, "constant"),
+            Self::Bursts { on, off } => write!(f, "bursts ({on:?} on, {off:?} off)"),
+            Self::Sine { period, amplitude } => {
+                write!(
+                    f,
+                    "sine (period {period:?}, amplitude {}%)",
+                    amplitude * 100.0
+                )
+            }
+            Self::Replay { arrivals, length } => {
+                write!(f, "replay ({} arrivals every {length:?})", arrivals.len())
+            }
+        }
+    }
+}
+
+/// Turns a traffic shape into the number of transactions to send in each bucket.
+struct Schedule<'a> {
+    shape: &'a TrafficShape,
+    // transactions sent per bucket at the target rate
+    burst: u64,
+    // fraction of a transaction carried over to the next bucket
+    carry: f64,
+    // next arrival to replay and the start of the current replay loop
+    replay_cursor: usize,
+    replay_start: Duration,
+}
+
+impl<'a> Schedule<'a> {
+    fn new(shape: &'a TrafficShape, burst: u64) -> Self {
+        Self {
+            shape,
+            burst,
+            carry: 0.0,
+            replay_cursor: 0,
+            replay_start: Duration::ZERO,
+        }
+    }
+
+    /// The number of transactions to send in the bucket between `start` and `end`, measured from
+    /// the start of the benchmark.
+    fn burst_size(&mut self, start: Duration, end: Duration) -> u64 {
+        match self.shape {
+            TrafficShape::Constant => self.burst,
+            TrafficShape::Bursts { on, off } => {
+                let cycle = (*on + *off).as_nanos();
+                if start.as_nanos() % cycle < on.as_nanos() {
+                    self.burst
+                } else {
+                    0
+                }
+            }
+            TrafficShape::Sine { period, amplitude } => {
+                let phase = 2.0 * std::f64::consts::PI * start.as_secs_f64() / period.as_secs_f64();
+                let expected = self.burst as f64 * (1.0 + amplitude * phase.sin()) + self.carry;
+                let size = expected.floor().max(0.0);
+                self.carry = expected - size;
+                size as u64
+            }
+            TrafficShape::Replay { arrivals, length } => {
+                let mut size = 0;
+                while self.replay_start + arrivals[self.replay_cursor] < end {
+                    size += 1;
+                    self.replay_cursor += 1;
+                    if self.replay_cursor == arrivals.len() {
+                        self.replay_cursor = 0;
+                        self.replay_start += *length;
+                    }
+                }
+                size
+            }
+        }
+    }
+}
+
 struct Client {
     target: Url,
     size: usize,
     rate: u64,
+    shape: TrafficShape,
     nodes: Vec<Url>,
 }
 
@@ -140,6 +329,7 @@ impl Client {
         // Submit all transactions.
         let mut counter = 0;
         let mut r = rand::thread_rng().gen();
+        let mut schedule = Schedule::new(&self.shape, burst);
         let interval = interval(Duration::from_millis(BURST_DURATION));
         tokio::pin!(interval);
 
@@ -150,6 +340,16 @@ impl Client {
             interval.as_mut().tick().await;
             let now = Instant::now();
 
+            let bucket_start = Duration::from_millis(counter * BURST_DURATION);
+            let burst = schedule.burst_size(
+                bucket_start,
+                bucket_start + Duration::from_millis(BURST_DURATION),
+            );
+            if burst == 0 {
+                counter += 1;
+                continue;
+            }
+
             let mut tx = BytesMut::with_capacity(self.size);
             let size = self.size;
             let stream = tokio_stream::iter(0..burst).map(move |x| {
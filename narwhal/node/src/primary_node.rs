@@ -46,10 +46,6 @@ struct PrimaryNodeInner {
 impl PrimaryNodeInner {
     /// The default channel capacity.
     pub const CHANNEL_CAPACITY: usize = 1_000;
-    /// The window where the schedule change takes place in consensus. It represents number
-    /// of committed sub dags.
-    /// TODO: move this to node properties
-    const CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS: u64 = 300;
 
     // Starts the primary node with the provided info. If the node is already running then this
     // method will return an error instead.
@@ -336,7 +332,7 @@ impl PrimaryNodeInner {
             committee.clone(),
             store.consensus_store.clone(),
             consensus_metrics.clone(),
-            Self::CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS,
+            PrimaryNode::CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS,
         );
         let consensus_handles = Consensus::spawn(
             committee.clone(),
@@ -379,6 +375,11 @@ pub struct PrimaryNode {
 }
 
 impl PrimaryNode {
+    /// The window where the schedule change takes place in consensus. It represents number
+    /// of committed sub dags.
+    /// TODO: move this to node properties
+    pub const CONSENSUS_SCHEDULE_CHANGE_SUB_DAGS: u64 = 300;
+
     pub fn new(
         parameters: Parameters,
         internal_consensus: bool,
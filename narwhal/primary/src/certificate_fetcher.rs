@@ -9,7 +9,7 @@ use crypto::NetworkPublicKey;
 use futures::{stream::FuturesUnordered, StreamExt};
 use itertools::Itertools;
 use mysten_metrics::{monitored_future, monitored_scope, spawn_logged_monitored_task};
-use network::PrimaryToPrimaryRpc;
+use network::{peer_quality::PeerQuality, PrimaryToPrimaryRpc};
 use rand::{rngs::ThreadRng, seq::SliceRandom};
 use std::{
     collections::{BTreeMap, BTreeSet},
@@ -87,6 +87,8 @@ struct CertificateFetcherState {
     synchronizer: Arc<Synchronizer>,
     /// The metrics handler
     metrics: Arc<PrimaryMetrics>,
+    /// Quality of the other primaries, to fetch from the best ones first.
+    peer_quality: Option<Arc<PeerQuality>>,
 }
 
 impl CertificateFetcher {
@@ -101,12 +103,14 @@ impl CertificateFetcher {
         rx_certificate_fetcher: Receiver<Certificate>,
         synchronizer: Arc<Synchronizer>,
         metrics: Arc<PrimaryMetrics>,
+        peer_quality: Option<Arc<PeerQuality>>,
     ) -> JoinHandle<()> {
         let state = Arc::new(CertificateFetcherState {
             authority_id,
             network,
             synchronizer,
             metrics,
+            peer_quality,
         });
 
         spawn_logged_monitored_task!(
@@ -295,10 +299,16 @@ async fn run_fetch_task(
     let request = FetchCertificatesRequest::default()
         .set_bounds(gc_round, written_rounds)
         .set_max_items(MAX_CERTIFICATES_TO_FETCH);
-    let Some(response) =
-        fetch_certificates_helper(state.authority_id, &state.network, &committee, request).await else {
-            return Err(DagError::NoCertificateFetched);
-        };
+    let Some(response) = fetch_certificates_helper(
+        state.authority_id,
+        &state.network,
+        state.peer_quality.as_deref(),
+        &committee,
+        request,
+    )
+    .await else {
+        return Err(DagError::NoCertificateFetched);
+    };
 
     // Process and store fetched certificates.
     let num_certs_fetched = response.certificates.len();
@@ -318,6 +328,7 @@ async fn run_fetch_task(
 async fn fetch_certificates_helper(
     name: AuthorityIdentifier,
     network: &anemo::Network,
+    peer_quality: Option<&PeerQuality>,
     committee: &Committee,
     request: FetchCertificatesRequest,
 ) -> Option<FetchCertificatesResponse> {
@@ -331,6 +342,11 @@ async fn fetch_certificates_helper(
         .map(|(_, _, network_key)| network_key)
         .collect();
     peers.shuffle(&mut ThreadRng::default());
+    if let Some(peer_quality) = peer_quality {
+        // Peers are popped from the end, so the best ones go last.
+        peer_quality.sort_by_score(&mut peers, |peer| anemo::PeerId(peer.0.to_bytes()));
+        peers.reverse();
+    }
     let fetch_timeout = PARALLEL_FETCH_REQUEST_INTERVAL_SECS * peers.len().try_into().unwrap()
         + PARALLEL_FETCH_REQUEST_ADDITIONAL_TIMEOUT;
     let fetch_callback = async move {
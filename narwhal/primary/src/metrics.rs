@@ -3,6 +3,7 @@
 use crate::EndpointMetrics;
 use mysten_network::metrics::MetricsCallbackProvider;
 use network::metrics::{NetworkConnectionMetrics, NetworkMetrics};
+use network::peer_quality::PeerQualityMetrics;
 use prometheus::{
     core::{AtomicI64, GenericGauge},
     default_registry, linear_buckets, register_histogram_vec_with_registry,
@@ -28,6 +29,7 @@ pub(crate) struct Metrics {
     pub(crate) primary_channel_metrics: Option<PrimaryChannelMetrics>,
     pub(crate) node_metrics: Option<PrimaryMetrics>,
     pub(crate) network_connection_metrics: Option<NetworkConnectionMetrics>,
+    pub(crate) peer_quality_metrics: Option<PeerQualityMetrics>,
 }
 
 /// Initialises the metrics
@@ -48,6 +50,9 @@ pub(crate) fn initialise_metrics(metrics_registry: &Registry) -> Metrics {
     // Network metrics for the primary connection
     let network_connection_metrics = NetworkConnectionMetrics::new("primary", metrics_registry);
 
+    // Network quality metrics of the peers of the primary
+    let peer_quality_metrics = PeerQualityMetrics::new("primary", metrics_registry);
+
     Metrics {
         node_metrics: Some(node_metrics),
         endpoint_metrics: Some(endpoint_metrics),
@@ -55,6 +60,7 @@ pub(crate) fn initialise_metrics(metrics_registry: &Registry) -> Metrics {
         inbound_network_metrics: Some(inbound_network_metrics),
         outbound_network_metrics: Some(outbound_network_metrics),
         network_connection_metrics: Some(network_connection_metrics),
+        peer_quality_metrics: Some(peer_quality_metrics),
     }
 }
 
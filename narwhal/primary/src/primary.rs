@@ -42,7 +42,11 @@ use futures::{stream::FuturesUnordered, StreamExt};
 use mysten_metrics::spawn_monitored_task;
 use mysten_network::{multiaddr::Protocol, Multiaddr};
 use network::epoch_filter::{AllowedEpoch, EPOCH_HEADER_KEY};
-use network::{failpoints::FailpointsMakeCallbackHandler, metrics::MetricsMakeCallbackHandler};
+use network::{
+    failpoints::FailpointsMakeCallbackHandler,
+    metrics::MetricsMakeCallbackHandler,
+    peer_quality::{Direction, PeerQuality, PeerQualityMakeCallbackHandler},
+};
 use prometheus::Registry;
 use std::collections::HashMap;
 use std::{
@@ -139,6 +143,7 @@ impl Primary {
         let outbound_network_metrics = Arc::new(metrics.outbound_network_metrics.unwrap());
         let node_metrics = Arc::new(metrics.node_metrics.unwrap());
         let network_connection_metrics = metrics.network_connection_metrics.unwrap();
+        let peer_quality = Arc::new(PeerQuality::new(metrics.peer_quality_metrics.unwrap()));
 
         let (tx_our_digests, rx_our_digests) = channel_with_total(
             CHANNEL_CAPACITY,
@@ -308,6 +313,10 @@ impl Primary {
                 inbound_network_metrics,
                 parameters.anemo.excessive_message_size(),
             )))
+            .layer(CallbackLayer::new(PeerQualityMakeCallbackHandler::new(
+                peer_quality.clone(),
+                Direction::Inbound,
+            )))
             .layer(CallbackLayer::new(FailpointsMakeCallbackHandler::new()))
             .layer(SetResponseHeaderLayer::overriding(
                 EPOCH_HEADER_KEY.parse().unwrap(),
@@ -325,6 +334,10 @@ impl Primary {
                 outbound_network_metrics,
                 parameters.anemo.excessive_message_size(),
             )))
+            .layer(CallbackLayer::new(PeerQualityMakeCallbackHandler::new(
+                peer_quality.clone(),
+                Direction::Outbound,
+            )))
             .layer(CallbackLayer::new(FailpointsMakeCallbackHandler::new()))
             .layer(SetRequestHeaderLayer::overriding(
                 EPOCH_HEADER_KEY.parse().unwrap(),
@@ -345,6 +358,9 @@ impl Primary {
             quic_config.crypto_buffer_size = Some(1 << 20);
             // Enable keep alives every 5s
             quic_config.keep_alive_interval_ms = Some(5_000);
+            if let Some(quic_parameters) = &parameters.anemo.primary_quic {
+                quic_parameters.apply(&mut quic_config);
+            }
             let mut config = anemo::Config::default();
             config.quic = Some(quic_config);
             // Set the max_frame_size to be 2 GB to work around the issue of there being too many
@@ -438,6 +454,7 @@ impl Primary {
             network.downgrade(),
             network_connection_metrics,
             peer_types,
+            Some(peer_quality.clone()),
         );
 
         info!(
@@ -487,6 +504,7 @@ impl Primary {
             rx_certificate_fetcher,
             synchronizer.clone(),
             node_metrics.clone(),
+            Some(peer_quality),
         );
 
         // When the `Synchronizer` collects enough parent certificates, the `Proposer` generates
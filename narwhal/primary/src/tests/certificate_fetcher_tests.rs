@@ -212,6 +212,7 @@ async fn fetch_certificates_basic() {
         rx_certificate_fetcher,
         synchronizer.clone(),
         metrics.clone(),
+        None,
     );
 
     // Generate headers and certificates in successive rounds
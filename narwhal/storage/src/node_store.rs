@@ -33,16 +33,16 @@ pub struct NodeStorage {
 
 impl NodeStorage {
     /// The datastore column family names.
-    pub(crate) const LAST_PROPOSED_CF: &'static str = "last_proposed";
-    pub(crate) const VOTES_CF: &'static str = "votes";
-    pub(crate) const HEADERS_CF: &'static str = "headers";
-    pub(crate) const CERTIFICATES_CF: &'static str = "certificates";
-    pub(crate) const CERTIFICATE_DIGEST_BY_ROUND_CF: &'static str = "certificate_digest_by_round";
-    pub(crate) const CERTIFICATE_DIGEST_BY_ORIGIN_CF: &'static str = "certificate_digest_by_origin";
-    pub(crate) const PAYLOAD_CF: &'static str = "payload";
-    pub(crate) const BATCHES_CF: &'static str = "batches";
-    pub(crate) const LAST_COMMITTED_CF: &'static str = "last_committed";
-    pub(crate) const SUB_DAG_INDEX_CF: &'static str = "sub_dag";
+    pub const LAST_PROPOSED_CF: &'static str = "last_proposed";
+    pub const VOTES_CF: &'static str = "votes";
+    pub const HEADERS_CF: &'static str = "headers";
+    pub const CERTIFICATES_CF: &'static str = "certificates";
+    pub const CERTIFICATE_DIGEST_BY_ROUND_CF: &'static str = "certificate_digest_by_round";
+    pub const CERTIFICATE_DIGEST_BY_ORIGIN_CF: &'static str = "certificate_digest_by_origin";
+    pub const PAYLOAD_CF: &'static str = "payload";
+    pub const BATCHES_CF: &'static str = "batches";
+    pub const LAST_COMMITTED_CF: &'static str = "last_committed";
+    pub const SUB_DAG_INDEX_CF: &'static str = "sub_dag";
 
     /// Open or reopen all the storage of the node.
     pub fn reopen<Path: AsRef<std::path::Path> + Send>(store_path: Path) -> Self {
@@ -21,7 +21,7 @@ pub use worker::*;
 
 mod serde;
 
-pub mod metered_channel;
+pub use mysten_metrics::metered_channel;
 
 mod pre_subscribed_broadcast;
 pub use pre_subscribed_broadcast::*;
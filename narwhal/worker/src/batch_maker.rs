@@ -2,6 +2,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::metrics::WorkerMetrics;
+use crate::TransactionPriority;
 #[cfg(feature = "trace_transaction")]
 use byteorder::{BigEndian, ReadBytesExt};
 use fastcrypto::hash::Hash;
@@ -32,6 +33,64 @@ use types::{
 // The number of batches to store / transmit in parallel.
 pub const MAX_PARALLEL_BATCH: usize = 100;
 
+/// One value per `TransactionPriority` class, e.g. the channels feeding the `BatchMaker`.
+#[derive(Clone, Debug)]
+pub struct PriorityLanes<T> {
+    system: T,
+    high: T,
+    normal: T,
+}
+
+impl<T> PriorityLanes<T> {
+    pub fn new(mut f: impl FnMut(TransactionPriority) -> T) -> Self {
+        Self {
+            system: f(TransactionPriority::System),
+            high: f(TransactionPriority::High),
+            normal: f(TransactionPriority::Normal),
+        }
+    }
+
+    pub fn get(&self, priority: TransactionPriority) -> &T {
+        match priority {
+            TransactionPriority::System => &self.system,
+            TransactionPriority::High => &self.high,
+            TransactionPriority::Normal => &self.normal,
+        }
+    }
+}
+
+impl<A, B> PriorityLanes<(A, B)> {
+    /// Splits lanes of pairs, e.g. of (sender, receiver), into a pair of lanes.
+    pub fn unzip(self) -> (PriorityLanes<A>, PriorityLanes<B>) {
+        (
+            PriorityLanes {
+                system: self.system.0,
+                high: self.high.0,
+                normal: self.normal.0,
+            },
+            PriorityLanes {
+                system: self.system.1,
+                high: self.high.1,
+                normal: self.normal.1,
+            },
+        )
+    }
+}
+
+impl<T> PriorityLanes<Receiver<T>> {
+    /// Receives the next message, always draining higher priority lanes first. Returns `None`
+    /// once all lanes are closed.
+    async fn recv(&mut self) -> Option<(TransactionPriority, T)> {
+        tokio::select! {
+            biased;
+            Some(message) = self.system.recv() => Some((TransactionPriority::System, message)),
+            Some(message) = self.high.recv() => Some((TransactionPriority::High, message)),
+            Some(message) = self.normal.recv() => Some((TransactionPriority::Normal, message)),
+            else => None,
+        }
+    }
+}
+
 #[cfg(test)]
 #[path = "tests/batch_maker_tests.rs"]
 pub mod batch_maker_tests;
@@ -46,8 +105,8 @@ pub struct BatchMaker {
     max_batch_delay: Duration,
     /// Receiver for shutdown.
     rx_shutdown: ConditionalBroadcastReceiver,
-    /// Channel to receive transactions from the network.
-    rx_batch_maker: Receiver<(Transaction, TxResponse)>,
+    /// Channels to receive transactions from the network, one lane per priority class.
+    rx_batch_maker: PriorityLanes<Receiver<(Transaction, TxResponse)>>,
     /// Output channel to deliver sealed batches to the `QuorumWaiter`.
     tx_quorum_waiter: Sender<(Batch, tokio::sync::oneshot::Sender<()>)>,
     /// Metrics handler
@@ -68,7 +127,7 @@ impl BatchMaker {
         batch_size_limit: usize,
         max_batch_delay: Duration,
         rx_shutdown: ConditionalBroadcastReceiver,
-        rx_batch_maker: Receiver<(Transaction, TxResponse)>,
+        rx_batch_maker: PriorityLanes<Receiver<(Transaction, TxResponse)>>,
         tx_quorum_waiter: Sender<(Batch, tokio::sync::oneshot::Sender<()>)>,
         node_metrics: Arc<WorkerMetrics>,
         store: DBMap<BatchDigest, Batch>,
@@ -112,7 +171,12 @@ impl BatchMaker {
                 // Note that transactions are only consumed when the number of batches
                 // 'in-flight' are below a certain number (MAX_PARALLEL_BATCH). This
                 // condition will be met eventually if the store and network are functioning.
-                Some((transaction, response_sender)) = self.rx_batch_maker.recv(), if batch_pipeline.len() < MAX_PARALLEL_BATCH => {
+                // Higher priority lanes are always drained before lower priority ones.
+                Some((priority, (transaction, response_sender))) = self.rx_batch_maker.recv(), if batch_pipeline.len() < MAX_PARALLEL_BATCH => {
+                    self.node_metrics
+                        .batched_transactions_by_priority
+                        .with_label_values(&[priority.as_str()])
+                        .inc();
                     current_batch_size += transaction.len();
                     current_batch.transactions.push(transaction);
                     current_responses.push(response_sender);
@@ -12,6 +12,8 @@ use mysten_network::{multiaddr::Protocol, Multiaddr};
 use thiserror::Error;
 use types::{metered_channel::Sender, Transaction, TxResponse};
 
+use crate::{batch_maker::PriorityLanes, TransactionPriority};
+
 /// Uses a map to allow running multiple Narwhal instances in the same process.
 /// TODO: after Rust 1.66, use BTreeMap::new() instead of wrapping it in an Option.
 static LOCAL_NARWHAL_CLIENTS: Mutex<Option<BTreeMap<Multiaddr, Arc<ArcSwap<LocalNarwhalClient>>>>> =
@@ -40,11 +42,11 @@ pub enum NarwhalError {
 #[derive(Clone)]
 pub struct LocalNarwhalClient {
     /// TODO: maybe use tx_batch_maker for load schedding.
-    tx_batch_maker: Sender<(Transaction, TxResponse)>,
+    tx_batch_maker: PriorityLanes<Sender<(Transaction, TxResponse)>>,
 }
 
 impl LocalNarwhalClient {
-    pub fn new(tx_batch_maker: Sender<(Transaction, TxResponse)>) -> Arc<Self> {
+    pub fn new(tx_batch_maker: PriorityLanes<Sender<(Transaction, TxResponse)>>) -> Arc<Self> {
         Arc::new(Self { tx_batch_maker })
     }
 
@@ -76,6 +78,16 @@ impl LocalNarwhalClient {
 
     /// Submits a transaction to the local Narwhal worker.
     pub async fn submit_transaction(&self, transaction: Transaction) -> Result<(), NarwhalError> {
+        self.submit_transaction_with_priority(transaction, TransactionPriority::default())
+            .await
+    }
+
+    /// Submits a transaction to the lane of the given priority of the local Narwhal worker.
+    pub async fn submit_transaction_with_priority(
+        &self,
+        transaction: Transaction,
+        priority: TransactionPriority,
+    ) -> Result<(), NarwhalError> {
         if transaction.len() > MAX_ALLOWED_TRANSACTION_SIZE {
             return Err(NarwhalError::TransactionTooLarge(
                 transaction.len(),
@@ -85,6 +97,7 @@ impl LocalNarwhalClient {
         // Send the transaction to the batch maker.
         let (notifier, when_done) = tokio::sync::oneshot::channel();
         self.tx_batch_maker
+            .get(priority)
             .send((transaction, notifier))
             .await
             .map_err(|_| NarwhalError::ShuttingDown)?;
@@ -9,7 +9,7 @@ use fastcrypto::hash::Hash;
 use futures::{stream::FuturesUnordered, StreamExt};
 use itertools::Itertools;
 use rand::seq::SliceRandom;
-use std::{collections::HashSet, time::Duration};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use store::{rocks::DBMap, Map};
 use tokio::time::sleep;
 use tracing::{debug, info, trace, warn};
@@ -21,6 +21,7 @@ use types::{
 };
 
 use mysten_metrics::monitored_future;
+use network::peer_quality::PeerQuality;
 
 use crate::TransactionValidator;
 
@@ -134,6 +135,8 @@ pub struct PrimaryReceiverHandler<V> {
     pub request_batch_timeout: Duration,
     // Number of random nodes to query when retrying batch requests.
     pub request_batch_retry_nodes: usize,
+    // Quality of the other workers, to retry with the best ones.
+    pub peer_quality: Option<Arc<PeerQuality>>,
     // Validate incoming batches
     pub validator: V,
 }
@@ -230,7 +233,7 @@ impl<V: TransactionValidator> PrimaryToWorker for PrimaryReceiverHandler<V> {
                 }
             } else {
                 // If first request timed out or was missing batches, try broadcasting to some others.
-                let names: Vec<_> = self
+                let mut names: Vec<_> = self
                     .worker_cache
                     .others_workers_by_id(
                         self.committee
@@ -242,9 +245,14 @@ impl<V: TransactionValidator> PrimaryToWorker for PrimaryReceiverHandler<V> {
                     .into_iter()
                     .map(|(_, info)| info.name)
                     .collect();
+                names.shuffle(&mut rand::thread_rng());
+                if let Some(peer_quality) = &self.peer_quality {
+                    peer_quality.sort_by_score(&mut names, |name| anemo::PeerId(name.0.to_bytes()));
+                }
                 handles.extend(
                     names
-                        .choose_multiple(&mut rand::thread_rng(), self.request_batch_retry_nodes)
+                        .iter()
+                        .take(self.request_batch_retry_nodes)
                         .filter_map(|name| network.peer(anemo::PeerId(name.0.to_bytes())))
                         .flat_map(|peer| {
                             batch_requests.iter().cloned().map(move |request| {
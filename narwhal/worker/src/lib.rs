@@ -19,8 +19,11 @@ mod worker;
 
 pub mod metrics;
 
+pub use crate::batch_maker::PriorityLanes;
 pub use crate::client::LocalNarwhalClient;
-pub use crate::tx_validator::{TransactionValidator, TrivialTransactionValidator};
+pub use crate::tx_validator::{
+    TransactionPriority, TransactionValidator, TrivialTransactionValidator,
+};
 pub use crate::worker::Worker;
 
 /// The number of shutdown receivers to create on startup. We need one per component loop.
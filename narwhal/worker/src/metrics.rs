@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 use mysten_network::metrics::MetricsCallbackProvider;
 use network::metrics::{NetworkConnectionMetrics, NetworkMetrics};
+use network::peer_quality::PeerQualityMetrics;
 use prometheus::{
     default_registry, register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
-    register_int_counter_with_registry, register_int_gauge_with_registry, HistogramVec, IntCounter,
-    IntCounterVec, IntGauge, Registry,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry,
+    register_int_gauge_with_registry, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Registry,
 };
 use std::time::Duration;
 use tonic::Code;
@@ -24,6 +26,7 @@ pub struct Metrics {
     pub inbound_network_metrics: Option<NetworkMetrics>,
     pub outbound_network_metrics: Option<NetworkMetrics>,
     pub network_connection_metrics: Option<NetworkConnectionMetrics>,
+    pub peer_quality_metrics: Option<PeerQualityMetrics>,
 }
 
 /// Initialises the metrics
@@ -44,6 +47,9 @@ pub fn initialise_metrics(metrics_registry: &Registry) -> Metrics {
     // Network metrics for the worker connection
     let network_connection_metrics = NetworkConnectionMetrics::new("worker", metrics_registry);
 
+    // Network quality metrics of the peers of the worker
+    let peer_quality_metrics = PeerQualityMetrics::new("worker", metrics_registry);
+
     Metrics {
         worker_metrics: Some(node_metrics),
         channel_metrics: Some(channel_metrics),
@@ -51,6 +57,7 @@ pub fn initialise_metrics(metrics_registry: &Registry) -> Metrics {
         inbound_network_metrics: Some(inbound_network_metrics),
         outbound_network_metrics: Some(outbound_network_metrics),
         network_connection_metrics: Some(network_connection_metrics),
+        peer_quality_metrics: Some(peer_quality_metrics),
     }
 }
 
@@ -62,6 +69,8 @@ pub struct WorkerMetrics {
     pub created_batch_latency: HistogramVec,
     /// The number of parallel worker batches currently processed by the worker
     pub parallel_worker_batches: IntGauge,
+    /// Number of transactions added to batches, by priority lane
+    pub batched_transactions_by_priority: IntCounterVec,
 }
 
 impl WorkerMetrics {
@@ -103,6 +112,13 @@ impl WorkerMetrics {
                 registry
             )
             .unwrap(),
+            batched_transactions_by_priority: register_int_counter_vec_with_registry!(
+                "batched_transactions_by_priority",
+                "Number of transactions added to batches, by priority lane",
+                &["priority"],
+                registry
+            )
+            .unwrap(),
         }
     }
 }
@@ -119,8 +135,9 @@ pub struct WorkerChannelMetrics {
     pub tx_our_batch: IntGauge,
     /// occupancy of the channel from various handlers to the `worker::PrimaryConnector`
     pub tx_others_batch: IntGauge,
-    /// occupancy of the channel from the `worker::TxReceiverhandler` to the `worker::BatchMaker`
-    pub tx_batch_maker: IntGauge,
+    /// occupancy of the channels from the `worker::TxReceiverhandler` to the `worker::BatchMaker`,
+    /// one per priority lane
+    pub tx_batch_maker: IntGaugeVec,
     /// occupancy of the channel from the `worker::BatchMaker` to the `worker::QuorumWaiter`
     pub tx_quorum_waiter: IntGauge,
 
@@ -148,9 +165,10 @@ impl WorkerChannelMetrics {
                 "occupancy of the channel from various handlers to the `worker::PrimaryConnector`",
                 registry
             ).unwrap(),
-            tx_batch_maker: register_int_gauge_with_registry!(
+            tx_batch_maker: register_int_gauge_vec_with_registry!(
                 "tx_batch_maker",
-                "occupancy of the channel from the `worker::TxReceiverhandler` to the `worker::BatchMaker`",
+                "occupancy of the channels from the `worker::TxReceiverhandler` to the `worker::BatchMaker`, by priority lane",
+                &["priority"],
                 registry
             ).unwrap(),
             tx_quorum_waiter: register_int_gauge_with_registry!(
@@ -191,6 +209,8 @@ pub struct WorkerEndpointMetrics {
     requests_by_route: IntCounterVec,
     /// Request latency, route is a label
     req_latency_by_route: HistogramVec,
+    /// Number of transactions rejected because their client exceeded its submission rate limit
+    pub throttled_transactions: IntCounter,
 }
 
 impl WorkerEndpointMetrics {
@@ -210,6 +230,12 @@ impl WorkerEndpointMetrics {
                 registry
             )
             .unwrap(),
+            throttled_transactions: register_int_counter_with_registry!(
+                "worker_throttled_transactions",
+                "Number of transactions rejected because their client exceeded its submission rate limit",
+                registry
+            )
+            .unwrap(),
         }
     }
 }
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
 
-use crate::NUM_SHUTDOWN_RECEIVERS;
+use crate::{TransactionPriority, NUM_SHUTDOWN_RECEIVERS};
 use prometheus::Registry;
 use store::rocks;
 use store::rocks::MetricConf;
@@ -26,7 +26,8 @@ fn create_batches_store() -> DBMap<BatchDigest, Batch> {
 async fn make_batch() {
     let store = create_batches_store();
     let mut tx_shutdown = PreSubscribedBroadcastSender::new(NUM_SHUTDOWN_RECEIVERS);
-    let (tx_batch_maker, rx_batch_maker) = test_utils::test_channel!(1);
+    let (tx_batch_maker, rx_batch_maker) =
+        PriorityLanes::new(|_| test_utils::test_channel!(1)).unzip();
     let (tx_quorum_waiter, mut rx_quorum_waiter) = test_utils::test_channel!(1);
     let (tx_our_batch, mut rx_our_batch) = test_utils::test_channel!(1);
     let node_metrics = WorkerMetrics::new(&Registry::new());
@@ -50,6 +51,7 @@ async fn make_batch() {
     let tx = transaction();
     let (s0, r0) = tokio::sync::oneshot::channel();
     let (s1, r1) = tokio::sync::oneshot::channel();
+    let tx_batch_maker = tx_batch_maker.get(TransactionPriority::Normal);
     tx_batch_maker.send((tx.clone(), s0)).await.unwrap();
     tx_batch_maker.send((tx.clone(), s1)).await.unwrap();
 
@@ -77,7 +79,8 @@ async fn make_batch() {
 async fn batch_timeout() {
     let store = create_batches_store();
     let mut tx_shutdown = PreSubscribedBroadcastSender::new(NUM_SHUTDOWN_RECEIVERS);
-    let (tx_batch_maker, rx_batch_maker) = test_utils::test_channel!(1);
+    let (tx_batch_maker, rx_batch_maker) =
+        PriorityLanes::new(|_| test_utils::test_channel!(1)).unzip();
     let (tx_quorum_waiter, mut rx_quorum_waiter) = test_utils::test_channel!(1);
     let node_metrics = WorkerMetrics::new(&Registry::new());
     let (tx_our_batch, mut rx_our_batch) = test_utils::test_channel!(1);
@@ -100,6 +103,7 @@ async fn batch_timeout() {
     // Do not send enough transactions to seal a batch.
     let tx = transaction();
     let (s0, r0) = tokio::sync::oneshot::channel();
+    let tx_batch_maker = tx_batch_maker.get(TransactionPriority::Normal);
     tx_batch_maker.send((tx.clone(), s0)).await.unwrap();
 
     // Ensure the batch is as expected.
@@ -119,3 +123,47 @@ async fn batch_timeout() {
     // Ensure the batch is stored
     assert!(store.get(&batch.digest()).unwrap().is_some());
 }
+
+#[tokio::test]
+async fn higher_priority_lanes_drained_first() {
+    let store = create_batches_store();
+    let mut tx_shutdown = PreSubscribedBroadcastSender::new(NUM_SHUTDOWN_RECEIVERS);
+    let (tx_batch_maker, rx_batch_maker) =
+        PriorityLanes::new(|_| test_utils::test_channel!(2)).unzip();
+    let (tx_quorum_waiter, mut rx_quorum_waiter) = test_utils::test_channel!(1);
+    let (tx_our_batch, _rx_our_batch) = test_utils::test_channel!(1);
+    let node_metrics = WorkerMetrics::new(&Registry::new());
+
+    // Fill the lanes before the `BatchMaker` starts, lowest priority first.
+    let normal_tx = transaction();
+    let system_tx = transaction();
+    let (s0, _r0) = tokio::sync::oneshot::channel();
+    let (s1, _r1) = tokio::sync::oneshot::channel();
+    tx_batch_maker
+        .get(TransactionPriority::Normal)
+        .send((normal_tx.clone(), s0))
+        .await
+        .unwrap();
+    tx_batch_maker
+        .get(TransactionPriority::System)
+        .send((system_tx.clone(), s1))
+        .await
+        .unwrap();
+
+    let _batch_maker_handle = BatchMaker::spawn(
+        0,
+        /* max_batch_size */ 200,
+        /* max_batch_delay */
+        Duration::from_millis(1_000_000), // Ensure the timer is not triggered.
+        tx_shutdown.subscribe(),
+        rx_batch_maker,
+        tx_quorum_waiter,
+        Arc::new(node_metrics),
+        store,
+        tx_our_batch,
+    );
+
+    // The system transaction is batched ahead of the normal one.
+    let (batch, _resp) = rx_quorum_waiter.recv().await.unwrap();
+    assert_eq!(batch.transactions, vec![system_tx, normal_tx]);
+}
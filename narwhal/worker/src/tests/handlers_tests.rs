@@ -29,6 +29,7 @@ async fn synchronize() {
         store: store.clone(),
         request_batch_timeout: Duration::from_secs(999),
         request_batch_retry_nodes: 3, // Not used in this test.
+        peer_quality: None,
         validator: TrivialTransactionValidator,
     };
 
@@ -104,6 +105,7 @@ async fn synchronize_when_batch_exists() {
         store: store.clone(),
         request_batch_timeout: Duration::from_secs(999),
         request_batch_retry_nodes: 3, // Not used in this test.
+        peer_quality: None,
         validator: TrivialTransactionValidator,
     };
 
@@ -154,6 +156,7 @@ async fn delete_batches() {
         store: store.clone(),
         request_batch_timeout: Duration::from_secs(999),
         request_batch_retry_nodes: 3, // Not used in this test.
+        peer_quality: None,
         validator: TrivialTransactionValidator,
     };
     let message = WorkerDeleteBatchesMessage {
@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 use super::*;
 use crate::LocalNarwhalClient;
-use crate::{metrics::initialise_metrics, TrivialTransactionValidator};
+use crate::{metrics::initialise_metrics, TransactionPriority, TrivialTransactionValidator};
 use async_trait::async_trait;
 use bytes::Bytes;
+use config::TxSubmissionRateLimitParameters;
 use consensus::consensus::ConsensusRound;
 use consensus::{dag::Dag, metrics::ConsensusMetrics};
 use fastcrypto::{
@@ -16,6 +17,7 @@ use futures::stream::FuturesOrdered;
 use futures::StreamExt;
 use primary::{NetworkModel, Primary, CHANNEL_CAPACITY, NUM_SHUTDOWN_RECEIVERS};
 use prometheus::Registry;
+use std::num::NonZeroU32;
 use std::time::Duration;
 use storage::NodeStorage;
 use store::rocks;
@@ -35,7 +37,7 @@ struct NilTxValidator;
 impl TransactionValidator for NilTxValidator {
     type Error = eyre::Report;
 
-    fn validate(&self, _tx: &[u8]) -> Result<(), Self::Error> {
+    async fn validate(&self, _tx: &[u8]) -> Result<TransactionPriority, Self::Error> {
         eyre::bail!("Invalid transaction");
     }
     async fn validate_batch(&self, _txs: &Batch) -> Result<(), Self::Error> {
@@ -135,6 +137,79 @@ async fn reject_invalid_clients_transactions() {
     assert!(res.is_err());
 }
 
+#[tokio::test]
+async fn throttle_clients_exceeding_rate_limit() {
+    let fixture = CommitteeFixture::builder().randomize_ports(true).build();
+    let committee = fixture.committee();
+    let worker_cache = fixture.worker_cache();
+
+    let worker_id = 0;
+    let my_primary = fixture.authorities().next().unwrap();
+    let myself = my_primary.worker(worker_id);
+    let public_key = my_primary.public_key();
+
+    let parameters = Parameters {
+        tx_submission_rate_limit: Some(TxSubmissionRateLimitParameters {
+            transactions_per_second: NonZeroU32::new(1).unwrap(),
+            burst_size: None,
+        }),
+        ..Parameters::default()
+    };
+
+    // Create a new test store.
+    let batch_store = rocks::DBMap::<BatchDigest, Batch>::open(
+        temp_dir(),
+        MetricConf::default(),
+        None,
+        Some("batches"),
+        &ReadWriteOptions::default(),
+    )
+    .unwrap();
+
+    let registry = Registry::new();
+    let metrics = initialise_metrics(&registry);
+    let endpoint_metrics = metrics.endpoint_metrics.clone().unwrap();
+
+    let mut tx_shutdown = PreSubscribedBroadcastSender::new(NUM_SHUTDOWN_RECEIVERS);
+
+    // Spawn a `Worker` instance with a reject-all validator, so that admitted transactions are
+    // answered right away.
+    Worker::spawn(
+        my_primary.authority().clone(),
+        myself.keypair(),
+        worker_id,
+        committee.clone(),
+        worker_cache.clone(),
+        parameters,
+        NilTxValidator,
+        batch_store,
+        metrics,
+        &mut tx_shutdown,
+    );
+
+    // Wait till other services have been able to start up
+    tokio::task::yield_now().await;
+    let address = worker_cache
+        .worker(&public_key, &worker_id)
+        .unwrap()
+        .transactions;
+    let config = mysten_network::config::Config::new();
+    let channel = config.connect_lazy(&address).unwrap();
+    let mut client = TransactionsClient::new(channel);
+    let txn = TransactionProto {
+        transaction: Bytes::from(transaction()),
+    };
+
+    // The first transaction gets through the rate limiter and is rejected by the validator.
+    let status = client.submit_transaction(txn.clone()).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    // The client has used up its quota, so the next one is throttled.
+    let status = client.submit_transaction(txn).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+    assert_eq!(endpoint_metrics.throttled_transactions.get(), 1);
+}
+
 /// TODO: test both RemoteNarwhalClient and LocalNarwhalClient in the same test case.
 #[tokio::test]
 async fn handle_remote_clients_transactions() {
@@ -1,19 +1,23 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::batch_maker::PriorityLanes;
 use crate::client::LocalNarwhalClient;
 use crate::metrics::WorkerEndpointMetrics;
 use crate::TransactionValidator;
 use async_trait::async_trait;
+use config::TxSubmissionRateLimitParameters;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
 use mysten_metrics::spawn_logged_monitored_task;
 use mysten_network::server::Server;
 use mysten_network::Multiaddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::task::JoinHandle;
-use tokio::time::{sleep, timeout};
+use tokio::time::{interval, sleep, timeout};
 use tonic::{Request, Response, Status};
 use tracing::{error, info, warn};
 use types::metered_channel::Sender;
@@ -26,8 +30,9 @@ pub struct TxServer<V: TransactionValidator> {
     address: Multiaddr,
     rx_shutdown: ConditionalBroadcastReceiver,
     endpoint_metrics: WorkerEndpointMetrics,
-    tx_batch_maker: Sender<(Transaction, TxResponse)>,
+    tx_batch_maker: PriorityLanes<Sender<(Transaction, TxResponse)>>,
     validator: V,
+    rate_limit: Option<TxSubmissionRateLimitParameters>,
 }
 
 impl<V: TransactionValidator> TxServer<V> {
@@ -36,8 +41,9 @@ impl<V: TransactionValidator> TxServer<V> {
         address: Multiaddr,
         rx_shutdown: ConditionalBroadcastReceiver,
         endpoint_metrics: WorkerEndpointMetrics,
-        tx_batch_maker: Sender<(Transaction, TxResponse)>,
+        tx_batch_maker: PriorityLanes<Sender<(Transaction, TxResponse)>>,
         validator: V,
+        rate_limit: Option<TxSubmissionRateLimitParameters>,
     ) -> JoinHandle<()> {
         spawn_logged_monitored_task!(
             Self {
@@ -45,7 +51,8 @@ impl<V: TransactionValidator> TxServer<V> {
                 tx_batch_maker,
                 endpoint_metrics,
                 validator,
-                rx_shutdown
+                rx_shutdown,
+                rate_limit,
             }
             .run(),
             "TxServer"
@@ -56,15 +63,24 @@ impl<V: TransactionValidator> TxServer<V> {
         const MAX_RETRIES: usize = 10;
         const RETRY_BACKOFF: Duration = Duration::from_millis(1_000);
         const GRACEFUL_SHUTDOWN_DURATION: Duration = Duration::from_millis(2_000);
+        const RATE_LIMITER_GC_INTERVAL: Duration = Duration::from_secs(60);
 
         // create and initialize local Narwhal client
         let local_client = LocalNarwhalClient::new(self.tx_batch_maker.clone());
         LocalNarwhalClient::set_global(self.address.clone(), local_client.clone());
 
         // create the handler
+        let rate_limiter = self.rate_limit.as_ref().map(|rate_limit| {
+            Arc::new(RateLimiter::keyed(
+                Quota::per_second(rate_limit.transactions_per_second)
+                    .allow_burst(rate_limit.burst_size()),
+            ))
+        });
         let tx_handler = TxReceiverHandler {
             local_client,
             validator: self.validator,
+            rate_limiter: rate_limiter.clone(),
+            endpoint_metrics: self.endpoint_metrics.clone(),
         };
 
         // now create the server
@@ -107,8 +123,20 @@ impl<V: TransactionValidator> TxServer<V> {
 
         let server_handle = spawn_logged_monitored_task!(server.serve());
 
-        // wait to receive a shutdown signal
-        let _ = self.rx_shutdown.receiver.recv().await;
+        // wait to receive a shutdown signal, while periodically forgetting about clients whose
+        // buckets are full again so that the rate limiter state doesn't grow unbounded.
+        let mut rate_limiter_gc = interval(RATE_LIMITER_GC_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = self.rx_shutdown.receiver.recv() => break,
+                _ = rate_limiter_gc.tick() => {
+                    if let Some(rate_limiter) = &rate_limiter {
+                        rate_limiter.retain_recent();
+                        rate_limiter.shrink_to_fit();
+                    }
+                }
+            }
+        }
 
         // once do just gracefully shutdown the node
         shutdown_handle.send(()).unwrap();
@@ -133,6 +161,25 @@ impl<V: TransactionValidator> TxServer<V> {
 pub(crate) struct TxReceiverHandler<V> {
     pub(crate) local_client: Arc<LocalNarwhalClient>,
     pub(crate) validator: V,
+    pub(crate) rate_limiter: Option<Arc<DefaultKeyedRateLimiter<IpAddr>>>,
+    pub(crate) endpoint_metrics: WorkerEndpointMetrics,
+}
+
+impl<V> TxReceiverHandler<V> {
+    /// Takes a token from the bucket of `client`. Clients are identified by their source address
+    /// without the port, since a single client can open many connections.
+    fn check_rate_limit(&self, client: Option<SocketAddr>) -> Result<(), Status> {
+        let (Some(rate_limiter), Some(client)) = (&self.rate_limiter, client) else {
+            return Ok(());
+        };
+        rate_limiter.check_key(&client.ip()).map_err(|_| {
+            self.endpoint_metrics.throttled_transactions.inc();
+            Status::resource_exhausted(format!(
+                "Transaction submission rate limit exceeded for {}",
+                client.ip()
+            ))
+        })
+    }
 }
 
 #[async_trait]
@@ -141,13 +188,14 @@ impl<V: TransactionValidator> Transactions for TxReceiverHandler<V> {
         &self,
         request: Request<TransactionProto>,
     ) -> Result<Response<Empty>, Status> {
+        self.check_rate_limit(request.remote_addr())?;
         let transaction = request.into_inner().transaction;
-        if self.validator.validate(transaction.as_ref()).is_err() {
+        let Ok(priority) = self.validator.validate(transaction.as_ref()).await else {
             return Err(Status::invalid_argument("Invalid transaction"));
-        }
+        };
         // Send the transaction to Narwhal via the local client.
         self.local_client
-            .submit_transaction(transaction.to_vec())
+            .submit_transaction_with_priority(transaction.to_vec(), priority)
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
         Ok(Response::new(Empty {}))
@@ -157,23 +205,29 @@ impl<V: TransactionValidator> Transactions for TxReceiverHandler<V> {
         &self,
         request: Request<tonic::Streaming<types::TransactionProto>>,
     ) -> Result<Response<types::Empty>, Status> {
+        let client = request.remote_addr();
         let mut transactions = request.into_inner();
         let mut reqeusts = FuturesUnordered::new();
 
         while let Some(Ok(txn)) = transactions.next().await {
-            if let Err(err) = self.validator.validate(txn.transaction.as_ref()) {
-                // If the transaction is invalid (often cryptographically), better to drop the client
-                return Err(Status::invalid_argument(format!(
-                    "Stream contains an invalid transaction {err}"
-                )));
-            }
+            // Every transaction of the stream counts against the client's quota.
+            self.check_rate_limit(client)?;
+            let priority = match self.validator.validate(txn.transaction.as_ref()).await {
+                Ok(priority) => priority,
+                Err(err) => {
+                    // If the transaction is invalid (often cryptographically), better to drop the client
+                    return Err(Status::invalid_argument(format!(
+                        "Stream contains an invalid transaction {err}"
+                    )));
+                }
+            };
             // Send the transaction to Narwhal via the local client.
             // Note that here we do not wait for a response because this would
             // mean that we process only a single message from this stream at a
             // time. Instead we gather them and resolve them once the stream is over.
             reqeusts.push(
                 self.local_client
-                    .submit_transaction(txn.transaction.to_vec()),
+                    .submit_transaction_with_priority(txn.transaction.to_vec(), priority),
             );
         }
 
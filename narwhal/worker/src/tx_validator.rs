@@ -5,14 +5,44 @@ use std::fmt::{Debug, Display};
 use async_trait::async_trait;
 use types::Batch;
 
+/// The priority class of a transaction. Each class has its own lane in front of the
+/// `BatchMaker`, and higher priority lanes are always drained first, so that e.g. system
+/// transactions are not stuck behind a flood of user transactions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TransactionPriority {
+    /// Transactions the system needs to make progress, e.g. checkpoint signatures.
+    System,
+    High,
+    #[default]
+    Normal,
+}
+
+impl TransactionPriority {
+    /// All priority classes, from highest to lowest.
+    pub const ALL: [TransactionPriority; 3] = [
+        TransactionPriority::System,
+        TransactionPriority::High,
+        TransactionPriority::Normal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionPriority::System => "system",
+            TransactionPriority::High => "high",
+            TransactionPriority::Normal => "normal",
+        }
+    }
+}
+
 /// Defines the validation procedure for receiving either a new single transaction (from a client)
 /// of a batch of transactions (from another validator). Invalid transactions will not receive
 /// further processing.
 #[async_trait]
 pub trait TransactionValidator: Clone + Send + Sync + 'static {
     type Error: Display + Debug + Send + Sync + 'static;
-    /// Determines if a transaction valid for the worker to consider putting in a batch
-    fn validate(&self, t: &[u8]) -> Result<(), Self::Error>;
+    /// Determines if a transaction valid for the worker to consider putting in a batch, and if
+    /// so, the priority class it should be batched with.
+    async fn validate(&self, t: &[u8]) -> Result<TransactionPriority, Self::Error>;
     /// Determines if this batch can be voted on
     async fn validate_batch(&self, b: &Batch) -> Result<(), Self::Error>;
 }
@@ -24,8 +54,8 @@ pub struct TrivialTransactionValidator;
 impl TransactionValidator for TrivialTransactionValidator {
     type Error = eyre::Report;
 
-    fn validate(&self, _t: &[u8]) -> Result<(), Self::Error> {
-        Ok(())
+    async fn validate(&self, _t: &[u8]) -> Result<TransactionPriority, Self::Error> {
+        Ok(TransactionPriority::Normal)
     }
 
     async fn validate_batch(&self, _b: &Batch) -> Result<(), Self::Error> {
@@ -2,7 +2,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    batch_maker::BatchMaker,
+    batch_maker::{BatchMaker, PriorityLanes},
     handlers::{PrimaryReceiverHandler, WorkerReceiverHandler},
     metrics::WorkerChannelMetrics,
     primary_connector::PrimaryConnector,
@@ -25,6 +25,7 @@ use mysten_network::{multiaddr::Protocol, Multiaddr};
 use network::epoch_filter::{AllowedEpoch, EPOCH_HEADER_KEY};
 use network::failpoints::FailpointsMakeCallbackHandler;
 use network::metrics::MetricsMakeCallbackHandler;
+use network::peer_quality::{Direction, PeerQuality, PeerQualityMakeCallbackHandler};
 use std::collections::HashMap;
 use std::time::Duration;
 use std::{net::Ipv4Addr, sync::Arc, thread::sleep};
@@ -102,6 +103,7 @@ impl Worker {
         let inbound_network_metrics = Arc::new(metrics.inbound_network_metrics.unwrap());
         let outbound_network_metrics = Arc::new(metrics.outbound_network_metrics.unwrap());
         let network_connection_metrics = metrics.network_connection_metrics.unwrap();
+        let peer_quality = Arc::new(PeerQuality::new(metrics.peer_quality_metrics.unwrap()));
 
         // Spawn all worker tasks.
         let (tx_our_batch, rx_our_batch) = channel_with_total(
@@ -149,6 +151,7 @@ impl Worker {
             store: worker.store.clone(),
             request_batch_timeout: worker.parameters.sync_retry_delay,
             request_batch_retry_nodes: worker.parameters.sync_retry_nodes,
+            peer_quality: Some(peer_quality.clone()),
             validator: validator.clone(),
         });
 
@@ -194,6 +197,10 @@ impl Worker {
                 inbound_network_metrics,
                 parameters.anemo.excessive_message_size(),
             )))
+            .layer(CallbackLayer::new(PeerQualityMakeCallbackHandler::new(
+                peer_quality.clone(),
+                Direction::Inbound,
+            )))
             .layer(CallbackLayer::new(FailpointsMakeCallbackHandler::new()))
             .layer(SetResponseHeaderLayer::overriding(
                 EPOCH_HEADER_KEY.parse().unwrap(),
@@ -211,6 +218,10 @@ impl Worker {
                 outbound_network_metrics,
                 parameters.anemo.excessive_message_size(),
             )))
+            .layer(CallbackLayer::new(PeerQualityMakeCallbackHandler::new(
+                peer_quality.clone(),
+                Direction::Outbound,
+            )))
             .layer(CallbackLayer::new(FailpointsMakeCallbackHandler::new()))
             .layer(SetRequestHeaderLayer::overriding(
                 EPOCH_HEADER_KEY.parse().unwrap(),
@@ -231,6 +242,9 @@ impl Worker {
             quic_config.crypto_buffer_size = Some(1 << 20);
             // Enable keep alives every 5s
             quic_config.keep_alive_interval_ms = Some(5_000);
+            if let Some(quic_parameters) = &parameters.anemo.worker_quic {
+                quic_parameters.apply(&mut quic_config);
+            }
             let mut config = anemo::Config::default();
             config.quic = Some(quic_config);
             // Set the max_frame_size to be 2 GB to work around the issue of there being too many
@@ -323,6 +337,7 @@ impl Worker {
             network.downgrade(),
             network_connection_metrics,
             peer_types,
+            Some(peer_quality),
         );
 
         let network_admin_server_base_port = parameters
@@ -439,11 +454,16 @@ impl Worker {
         validator: impl TransactionValidator,
         network: anemo::Network,
     ) -> Vec<JoinHandle<()>> {
-        let (tx_batch_maker, rx_batch_maker) = channel_with_total(
-            CHANNEL_CAPACITY,
-            &channel_metrics.tx_batch_maker,
-            &channel_metrics.tx_batch_maker_total,
-        );
+        let (tx_batch_maker, rx_batch_maker) = PriorityLanes::new(|priority| {
+            channel_with_total(
+                CHANNEL_CAPACITY,
+                &channel_metrics
+                    .tx_batch_maker
+                    .with_label_values(&[priority.as_str()]),
+                &channel_metrics.tx_batch_maker_total,
+            )
+        })
+        .unzip();
         let (tx_quorum_waiter, rx_quorum_waiter) = channel_with_total(
             CHANNEL_CAPACITY,
             &channel_metrics.tx_quorum_waiter,
@@ -466,6 +486,7 @@ impl Worker {
             endpoint_metrics,
             tx_batch_maker,
             validator,
+            self.parameters.tx_submission_rate_limit.clone(),
         );
 
         // The transactions are sent to the `BatchMaker` that assembles them into batches. It then broadcasts
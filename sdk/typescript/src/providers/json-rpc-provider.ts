@@ -16,14 +16,17 @@ import {
   SuiMoveNormalizedModules,
   SuiMoveNormalizedStruct,
   SuiTransactionResponse,
+  TransactionCursor,
   TransactionDigest,
   SuiTransactionResponseQuery,
   RpcApiVersion,
+  RpcCapabilities,
   parseVersionFromString,
   PaginatedEvents,
   FaucetResponse,
   Order,
   DevInspectResults,
+  ObjectOverride,
   CoinMetadata,
   isValidTransactionDigest,
   isValidSuiAddress,
@@ -38,7 +41,9 @@ import {
   CoinSupply,
   CheckpointDigest,
   Checkpoint,
+  AvailableHistory,
   CommitteeInfo,
+  GasPriceEstimates,
   DryRunTransactionResponse,
   SuiObjectDataOptions,
   SuiSystemStateSummary,
@@ -54,7 +59,7 @@ import {
   WebsocketClientOptions,
 } from '../rpc/websocket-client';
 import { requestSuiFromFaucet } from '../rpc/faucet-client';
-import { any, is, array, string } from 'superstruct';
+import { any, is, array, nullable, string } from 'superstruct';
 import { toB64 } from '@mysten/bcs';
 import { SerializedSignature } from '../cryptography/signature';
 import { Connection, devnetConnection } from '../rpc/connection';
@@ -64,9 +69,9 @@ import { RPCError } from '../utils/errors';
 
 export const TARGETED_RPC_VERSION = '0.27.0';
 
-export interface PaginationArguments {
+export interface PaginationArguments<Cursor = ObjectId> {
   /** Optional paging cursor */
-  cursor?: ObjectId | null;
+  cursor?: Cursor | null;
   /** Maximum item returned per page */
   limit?: number | null;
 }
@@ -121,6 +126,7 @@ export class JsonRpcProvider {
   protected wsClient: WebsocketClient;
   private rpcApiVersion: RpcApiVersion | undefined;
   private cacheExpiry: number | undefined;
+  private rpcCapabilities: Promise<RpcCapabilities | undefined> | undefined;
   /**
    * Establish a connection to a Sui RPC endpoint
    *
@@ -174,6 +180,39 @@ export class JsonRpcProvider {
     return undefined;
   }
 
+  /**
+   * Get the capabilities of the RPC server: the methods it supports, the deprecated ones, and
+   * warnings for the API version targeted by this SDK, e.g. when the server requires a newer SDK.
+   * The warnings are logged the first time the capabilities are fetched. Returns undefined if the
+   * server does not support capability negotiation.
+   */
+  async getRpcCapabilities(): Promise<RpcCapabilities | undefined> {
+    if (!this.rpcCapabilities) {
+      this.rpcCapabilities = this.fetchRpcCapabilities();
+    }
+    return this.rpcCapabilities;
+  }
+
+  private async fetchRpcCapabilities(): Promise<RpcCapabilities | undefined> {
+    try {
+      const capabilities = await this.client.requestWithType(
+        'rpc.capabilities',
+        [TARGETED_RPC_VERSION],
+        RpcCapabilities,
+        this.options.skipDataValidation,
+      );
+      capabilities.clientWarnings.forEach((warning) =>
+        console.warn(`[${warning.kind}] ${warning.message}`),
+      );
+      return capabilities;
+    } catch (err) {
+      console.warn('Error fetching capabilities of the RPC server', err);
+      // Fetch the capabilities again on the next call.
+      this.rpcCapabilities = undefined;
+    }
+    return undefined;
+  }
+
   async requestSuiFromFaucet(
     recipient: SuiAddress,
     httpHeaders?: HttpHeaders,
@@ -457,7 +496,9 @@ export class JsonRpcProvider {
    * Get transactions for a given query criteria
    */
   async queryTransactions(
-    input: SuiTransactionResponseQuery & PaginationArguments & OrderArguments,
+    input: SuiTransactionResponseQuery &
+      PaginationArguments<TransactionCursor> &
+      OrderArguments,
   ): Promise<PaginatedTransactionResponse> {
     return await this.client.requestWithType(
       'sui_queryTransactions',
@@ -620,6 +661,19 @@ export class JsonRpcProvider {
     return BigInt(resp);
   }
 
+  /**
+   * Return gas prices to pay for a transaction, estimated from the gas prices paid by the
+   * transactions of the most recent checkpoints, along with the reference gas price
+   */
+  async getGasPriceEstimates(): Promise<GasPriceEstimates> {
+    return await this.client.requestWithType(
+      'sui_getGasPriceEstimates',
+      [],
+      GasPriceEstimates,
+      this.options.skipDataValidation,
+    );
+  }
+
   /**
    * Return the delegated stakes for an address
    */
@@ -704,6 +758,8 @@ export class JsonRpcProvider {
     gasPrice?: bigint | number | null;
     /** optional. Default to use the current epoch number stored in the Sui System State object */
     epoch?: number | null;
+    /** optional. Contents and versions to give objects during the execution, in place of the ones they have on chain */
+    objectOverrides?: ObjectOverride[] | null;
   }): Promise<DevInspectResults> {
     let devInspectTxBytes;
     if (Transaction.is(input.transaction)) {
@@ -724,7 +780,15 @@ export class JsonRpcProvider {
 
     return await this.client.requestWithType(
       'sui_devInspectTransaction',
-      [input.sender, devInspectTxBytes, input.gasPrice, input.epoch],
+      [
+        input.sender,
+        devInspectTxBytes,
+        input.gasPrice,
+        input.epoch,
+        // showTrace
+        null,
+        input.objectOverrides,
+      ],
       DevInspectResults,
       this.options.skipDataValidation,
     );
@@ -801,6 +865,19 @@ export class JsonRpcProvider {
     return String(resp);
   }
 
+  /**
+   * Get the lowest checkpoint from which each kind of history is available, the history of
+   * earlier checkpoints having been pruned by the node
+   */
+  async getAvailableHistory(): Promise<AvailableHistory> {
+    return await this.client.requestWithType(
+      'sui_getAvailableHistory',
+      [],
+      AvailableHistory,
+      this.options.skipDataValidation,
+    );
+  }
+
   /**
    * Returns information about a given checkpoint
    */
@@ -852,4 +929,40 @@ export class JsonRpcProvider {
       CommitteeInfo,
     );
   }
+
+  /**
+   * Return the address a name registered with the name service points to, or null if the name
+   * is not registered, has expired, or does not point to an address
+   */
+  async resolveNameServiceAddress(input: {
+    /** The name to resolve, e.g. `example.sui` */
+    name: string;
+  }): Promise<SuiAddress | null> {
+    return await this.client.requestWithType(
+      'sui_resolveNameServiceAddress',
+      [input.name],
+      nullable(SuiAddress),
+      this.options.skipDataValidation,
+    );
+  }
+
+  /**
+   * Return the primary name of an address in the name service, or null if the address has none
+   */
+  async resolveNameServiceName(input: {
+    address: SuiAddress;
+  }): Promise<string | null> {
+    if (
+      !input.address ||
+      !isValidSuiAddress(normalizeSuiAddress(input.address))
+    ) {
+      throw new Error('Invalid Sui address');
+    }
+    return await this.client.requestWithType(
+      'sui_resolveNameServiceName',
+      [input.address],
+      nullable(string()),
+      this.options.skipDataValidation,
+    );
+  }
 }
@@ -71,3 +71,12 @@ export const CheckpointPage = object({
   hasNextPage: boolean(),
 });
 export type CheckpointPage = Infer<typeof CheckpointPage>;
+
+export const AvailableHistory = object({
+  checkpointContents: string(),
+  transactions: string(),
+  effects: string(),
+  events: string(),
+  indexes: string(),
+});
+export type AvailableHistory = Infer<typeof AvailableHistory>;
@@ -68,6 +68,7 @@ export type SuiEventFilter =
   | { Package: ObjectId }
   | { MoveModule: { package: ObjectId; module: string } }
   | { MoveEventType: string }
+  | { MoveEventTypePattern: string }
   | { MoveEventField: MoveEventField }
   | { Transaction: TransactionDigest }
   | {
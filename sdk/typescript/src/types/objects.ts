@@ -170,6 +170,8 @@ export const SuiObjectDataOptions = object({
   showStorageRebate: optional(boolean()),
   /* Whether to fetch the display metadata, default to be false */
   showDisplay: optional(boolean()),
+  /* Only fetch these fields (e.g. `owner`, `content.balance`), ignoring the other options */
+  fields: optional(array(string())),
 });
 export type SuiObjectDataOptions = Infer<typeof SuiObjectDataOptions>;
 
@@ -249,12 +249,56 @@ export type TransactionEffects = Infer<typeof TransactionEffects>;
 export const TransactionEvents = array(SuiEvent);
 export type TransactionEvents = Infer<typeof TransactionEvents>;
 
+export const CommandGas = object({
+  index: number(),
+  kind: string(),
+  computationCost: number(),
+  /** Only reported when the execution is traced */
+  functions: optional(
+    array(
+      object({
+        function: string(),
+        calls: number(),
+        computationCost: number(),
+      }),
+    ),
+  ),
+});
+export type CommandGas = Infer<typeof CommandGas>;
+
+export const ObjectGas = object({
+  objectId: ObjectId,
+  kind: string(),
+  storageCost: number(),
+  storageRebate: number(),
+});
+export type ObjectGas = Infer<typeof ObjectGas>;
+
+/** Breakdown of the gas of a transaction, in MIST */
+export const GasBreakdown = object({
+  objectReadCost: number(),
+  commands: array(CommandGas),
+  objects: array(ObjectGas),
+});
+export type GasBreakdown = Infer<typeof GasBreakdown>;
+
 export const DryRunTransactionResponse = object({
   effects: TransactionEffects,
   events: TransactionEvents,
+  gasBreakdown: optional(GasBreakdown),
 });
 export type DryRunTransactionResponse = Infer<typeof DryRunTransactionResponse>;
 
+/** Contents and version given to an object by a dev-inspect execution */
+export const ObjectOverride = object({
+  objectId: ObjectId,
+  /** Defaults to the version of the object on chain */
+  version: optional(SequenceNumber),
+  /** BCS encoded contents of the Move object, defaults to its contents on chain */
+  bcsBytes: optional(string()),
+});
+export type ObjectOverride = Infer<typeof ObjectOverride>;
+
 const ReturnValueType = tuple([array(number()), string()]);
 const MutableReferenceOutputType = tuple([
   SuiArgument,
@@ -411,15 +455,27 @@ export const SuiTransactionResponseOptions = object({
   showObjectChanges: optional(boolean()),
   /* Whether to show coin balance changes. Default to be false. */
   showBalanceChanges: optional(boolean()),
+  /* Only show these fields (e.g. `effects`, `checkpoint`), ignoring the other options. */
+  fields: optional(array(string())),
 });
 
 export type SuiTransactionResponseOptions = Infer<
   typeof SuiTransactionResponseOptions
 >;
 
+/**
+ * Position of a transaction in the checkpoint that includes it. Cursors are the same on every
+ * fullnode and indexer, so that paging can resume from any of them.
+ */
+export const TransactionCursor = object({
+  checkpoint: number(),
+  index: number(),
+});
+export type TransactionCursor = Infer<typeof TransactionCursor>;
+
 export const PaginatedTransactionResponse = object({
   data: array(SuiTransactionResponse),
-  nextCursor: union([TransactionDigest, literal(null)]),
+  nextCursor: union([TransactionCursor, literal(null)]),
   hasNextPage: boolean(),
 });
 export type PaginatedTransactionResponse = Infer<
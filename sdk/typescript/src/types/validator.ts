@@ -21,6 +21,7 @@ import { AuthorityName, EpochId } from './transactions';
 
 export type DelegatedStake = Infer<typeof DelegatedStake>;
 export type CommitteeInfo = Infer<typeof CommitteeInfo>;
+export type GasPriceEstimates = Infer<typeof GasPriceEstimates>;
 export type StakeObject = Infer<typeof StakeObject>;
 
 // Staking
@@ -105,6 +106,17 @@ export const CommitteeInfo = object({
   validators: optional(array(tuple([AuthorityName, number()]))),
 });
 
+/** Gas prices estimated from the transactions of the most recent checkpoints */
+export const GasPriceEstimates = object({
+  referenceGasPrice: string(),
+  p50: string(),
+  p90: string(),
+  p99: string(),
+  firstCheckpoint: nullable(string()),
+  lastCheckpoint: nullable(string()),
+  transactions: string(),
+});
+
 export const SuiValidatorSummary = object({
   suiAddress: SuiAddress,
   protocolPubkeyBytes: string(),
@@ -2,6 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 import { parse } from '@suchipi/femver';
+import {
+  array,
+  Infer,
+  nullable,
+  number,
+  object,
+  string,
+  union,
+  literal,
+} from 'superstruct';
 
 export type RpcApiVersion = {
   major: number;
@@ -19,3 +29,29 @@ export function versionToString(version: RpcApiVersion): string {
   const { major, minor, patch } = version;
   return `${major}.${minor}.${patch}`;
 }
+
+export const ClientWarning = object({
+  kind: union([
+    literal('StaleClient'),
+    literal('StaleServer'),
+    literal('InvalidClientApiVersion'),
+  ]),
+  message: string(),
+});
+export type ClientWarning = Infer<typeof ClientWarning>;
+
+export const DeprecatedMethod = object({
+  method: string(),
+  description: nullable(string()),
+});
+export type DeprecatedMethod = Infer<typeof DeprecatedMethod>;
+
+export const RpcCapabilities = object({
+  apiVersion: string(),
+  minClientApiVersion: nullable(string()),
+  maxProtocolVersion: number(),
+  methods: array(string()),
+  deprecatedMethods: array(DeprecatedMethod),
+  clientWarnings: array(ClientWarning),
+});
+export type RpcCapabilities = Infer<typeof RpcCapabilities>;